@@ -109,6 +109,12 @@ impl ClientApiConnection {
             "ValueChange" => {
                 comproc.update_value_change(&update);
             }
+            "IdentityChange" => {
+                comproc.update_identity_change(&update);
+            }
+            "StorageEviction" => {
+                comproc.update_storage_eviction(&update);
+            }
             _ => {
                 comproc.log_message(Level::Error, &format!("unknown update kind: {}", update));
             }
@@ -395,6 +401,29 @@ impl ClientApiConnection {
         Ok(())
     }
 
+    pub async fn server_change_log_component_level(
+        &self,
+        layer: String,
+        component: String,
+        log_level: String,
+    ) -> Result<(), String> {
+        trace!("ClientApiConnection::change_log_component_level");
+        let mut req = json::JsonValue::new_object();
+        req["op"] = "Control".into();
+        req["args"] = json::JsonValue::new_array();
+        req["args"].push("ChangeLogComponentLevel").unwrap();
+        req["args"].push(layer).unwrap();
+        req["args"].push(component).unwrap();
+        req["args"].push(log_level).unwrap();
+        let Some(resp) = self.perform_request(req).await else {
+            return Err("Cancelled".to_owned());
+        };
+        if resp.has_key("error") {
+            return Err(resp["error"].to_string());
+        }
+        Ok(())
+    }
+
     pub async fn server_change_log_ignore(
         &self,
         layer: String,
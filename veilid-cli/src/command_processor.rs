@@ -136,6 +136,12 @@ impl CommandProcessor {
                                             all, terminal, system, api, file, otlp
                                         levels include:
                                             error, warn, info, debug, trace
+    change_log_component_level <layer> <component> <level>
+                                        change the log level of one component for a tracing layer,
+                                        overriding the layer's level for that component
+                                        components include:
+                                            rtab, net, rpc, stor
+                                        use level 'default' to remove the override
     change_log_ignore <layer> <changes> change the log target ignore list for a tracing layer
                                         targets to add to the ignore list can be separated by a comma.
                                         to remove a target from the ignore list, prepend it with a minus.
@@ -244,6 +250,57 @@ Server Debug Commands:
         Ok(())
     }
 
+    pub fn cmd_change_log_component_level(
+        &self,
+        rest: Option<String>,
+        callback: UICallback,
+    ) -> Result<(), String> {
+        trace!("CommandProcessor::cmd_change_log_component_level");
+        let capi = self.capi();
+        let ui = self.ui_sender();
+        spawn_detached_local(async move {
+            let (layer, rest) = Self::word_split(&rest.unwrap_or_default());
+            let (component, rest) = Self::word_split(&rest.unwrap_or_default());
+            let rest = rest.unwrap_or_default();
+            let log_level = if rest == "default" {
+                rest
+            } else {
+                match convert_loglevel(&rest) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        ui.add_node_event(
+                            Level::Error,
+                            &format!("Failed to change log component level: {}", e),
+                        );
+                        ui.send_callback(callback);
+                        return;
+                    }
+                }
+            };
+
+            match capi
+                .server_change_log_component_level(layer, component.clone(), log_level.clone())
+                .await
+            {
+                Ok(()) => {
+                    ui.display_string_dialog(
+                        "Log component level changed",
+                        &format!("Log level for '{}' set to '{}'", component, log_level),
+                        callback,
+                    );
+                }
+                Err(e) => {
+                    ui.display_string_dialog(
+                        "Server command 'change_log_component_level' failed",
+                        &e,
+                        callback,
+                    );
+                }
+            }
+        });
+        Ok(())
+    }
+
     pub fn cmd_change_log_ignore(
         &self,
         rest: Option<String>,
@@ -333,6 +390,7 @@ Server Debug Commands:
             "disconnect" => self.cmd_disconnect(callback),
             "shutdown" => self.cmd_shutdown(callback),
             "change_log_level" => self.cmd_change_log_level(rest, callback),
+            "change_log_component_level" => self.cmd_change_log_component_level(rest, callback),
             "change_log_ignore" => self.cmd_change_log_ignore(rest, callback),
             "enable" => self.cmd_enable(rest, callback),
             "disable" => self.cmd_disable(rest, callback),
@@ -520,6 +578,35 @@ Server Debug Commands:
         );
         self.inner().ui_sender.add_node_event(Level::Info, &out);
     }
+    pub fn update_identity_change(&self, identity_change: &json::JsonValue) {
+        let mut out = String::new();
+        if !identity_change["new_node_ids"].is_empty() {
+            out.push_str(&format!(
+                "New node ids: {}",
+                identity_change["new_node_ids"].dump()
+            ));
+        }
+        if identity_change["new_device_encryption_key"]
+            .as_bool()
+            .unwrap_or_default()
+        {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str("New device encryption key");
+        }
+        if !out.is_empty() {
+            self.inner().ui_sender.add_node_event(Level::Warn, &out);
+        }
+    }
+    pub fn update_storage_eviction(&self, storage_eviction: &json::JsonValue) {
+        let out = format!(
+            "Remote record storage reached its high watermark, evicted {} records ({} bytes)",
+            storage_eviction["evicted_record_count"].dump(),
+            json_str_u64(&storage_eviction["evicted_space"]),
+        );
+        self.inner().ui_sender.add_node_event(Level::Warn, &out);
+    }
 
     pub fn update_log(&self, log: &json::JsonValue) {
         let log_level =
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use veilid_core::fuzz::decode_any;
+
+// The first byte picks the decoder, decoding errors are expected but panics are not
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_any(data);
+});
@@ -0,0 +1,22 @@
+//! Write the seed corpus for the rpc_decode fuzz target
+//!
+//! Usage: rpc_corpus [output directory]
+use std::path::PathBuf;
+use veilid_core::fuzz::{fuzz_decoder_index, generate_corpus};
+
+fn main() -> std::io::Result<()> {
+    let out_dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("corpus/rpc_decode"));
+    std::fs::create_dir_all(&out_dir)?;
+
+    for (n, (name, data)) in generate_corpus().into_iter().enumerate() {
+        let index = fuzz_decoder_index(name).expect("corpus entry should name an entry point");
+        let mut input = vec![index];
+        input.extend_from_slice(&data);
+        std::fs::write(out_dir.join(format!("{}-{}", name, n)), input)?;
+    }
+
+    Ok(())
+}
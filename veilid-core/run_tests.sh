@@ -61,5 +61,6 @@ elif [[ "$1" == "android" ]]; then
 else
     cargo test
     cargo test --no-default-features --features=default-async-std
+    cargo test --features=sim-network
 fi
 popd 2>/dev/null
\ No newline at end of file
@@ -4,17 +4,25 @@ use network_manager::*;
 use routing_table::*;
 use storage_manager::*;
 
+/// How long to wait between ticks while on a network that costs us to use
+const REDUCED_TICK_INTERVAL_MS: u32 = 5000;
+
 struct AttachmentManagerInner {
     last_attachment_state: AttachmentState,
     last_routing_table_health: Option<RoutingTableHealth>,
+    last_storage_status: StorageManagerStatus,
     maintain_peers: bool,
     attach_ts: Option<Timestamp>,
     update_callback: Option<UpdateCallback>,
     attachment_maintainer_jh: Option<MustJoinHandle<()>>,
+    network_cost: NetworkCost,
+    /// If we detached because of the network cost policy, and should attach again once it allows
+    suspended_attach: bool,
 }
 
 struct AttachmentManagerUnlockedInner {
     config: VeilidConfig,
+    storage_manager: StorageManager,
     network_manager: NetworkManager,
 }
 
@@ -34,6 +42,7 @@ impl AttachmentManager {
     ) -> AttachmentManagerUnlockedInner {
         AttachmentManagerUnlockedInner {
             config: config.clone(),
+            storage_manager: storage_manager.clone(),
             network_manager: NetworkManager::new(
                 config,
                 storage_manager,
@@ -48,10 +57,13 @@ impl AttachmentManager {
         AttachmentManagerInner {
             last_attachment_state: AttachmentState::Detached,
             last_routing_table_health: None,
+            last_storage_status: StorageManagerStatus::default(),
             maintain_peers: false,
             attach_ts: None,
             update_callback: None,
             attachment_maintainer_jh: None,
+            network_cost: NetworkCost::default(),
+            suspended_attach: false,
         }
     }
     pub fn new(
@@ -78,6 +90,10 @@ impl AttachmentManager {
         self.unlocked_inner.config.clone()
     }
 
+    pub fn storage_manager(&self) -> StorageManager {
+        self.unlocked_inner.storage_manager.clone()
+    }
+
     pub fn network_manager(&self) -> NetworkManager {
         self.unlocked_inner.network_manager.clone()
     }
@@ -119,21 +135,57 @@ impl AttachmentManager {
         AttachmentState::Attaching
     }
 
-    /// Update attachment and network readiness state
+    /// Crypto kinds that have just become too sparse to perform lookups with, while we are otherwise attached
+    fn newly_unviable_crypto_kinds(
+        previous: Option<(&RoutingTableHealth, AttachmentState)>,
+        health: &RoutingTableHealth,
+        state: AttachmentState,
+    ) -> Vec<CryptoKind> {
+        let is_attached = |s: AttachmentState| {
+            !matches!(
+                s,
+                AttachmentState::Detached | AttachmentState::Attaching | AttachmentState::Detaching
+            )
+        };
+        if !is_attached(state) {
+            return vec![];
+        }
+        health
+            .crypto_kinds
+            .iter()
+            .filter(|ckh| !ckh.viable)
+            .filter(|ckh| match previous {
+                Some((previous_health, previous_state)) if is_attached(previous_state) => {
+                    previous_health
+                        .crypto_kinds
+                        .iter()
+                        .any(|pckh| pckh.kind == ckh.kind && pckh.viable)
+                }
+                _ => true,
+            })
+            .map(|ckh| ckh.kind)
+            .collect()
+    }
+
+    /// Update attachment, network readiness and storage state
     /// and possibly send a VeilidUpdate::Attachment
-    fn update_attachment(&self) {
+    fn update_attachment(&self, storage_status: StorageManagerStatus) {
         // update the routing table health
         let routing_table = self.network_manager().routing_table();
         let health = routing_table.get_routing_table_health();
         let opt_update = {
             let mut inner = self.inner.lock();
 
+            // Swap in the new storage status
+            let storage_changed = inner.last_storage_status != storage_status;
+            inner.last_storage_status = storage_status;
+
             // Check if the routing table health is different
-            if let Some(last_routing_table_health) = &inner.last_routing_table_health {
-                // If things are the same, just return
-                if last_routing_table_health == &health {
-                    return;
-                }
+            let health_changed = inner.last_routing_table_health.as_ref() != Some(&health);
+
+            // If things are the same, just return
+            if !health_changed && !storage_changed {
+                return;
             }
 
             // Swap in new health numbers
@@ -147,6 +199,15 @@ impl AttachmentManager {
             inner.last_attachment_state =
                 AttachmentManager::translate_routing_table_health(&health, routing_table_config);
 
+            // Find crypto kinds we can no longer look anything up with
+            let unviable_crypto_kinds = AttachmentManager::newly_unviable_crypto_kinds(
+                opt_previous_health
+                    .as_ref()
+                    .map(|x| (x, previous_attachment_state)),
+                &health,
+                inner.last_attachment_state,
+            );
+
             // If we don't have an update callback yet for some reason, just return now
             let Some(update_callback) = inner.update_callback.clone() else {
                 return;
@@ -155,16 +216,28 @@ impl AttachmentManager {
             // Send update if one of:
             // * the attachment state has changed
             // * routing domain readiness has changed
+            // * the storage status has changed
+            // * the viability of a crypto kind has changed
             // * this is our first routing table health check
             let send_update = previous_attachment_state != inner.last_attachment_state
+                || storage_changed
                 || opt_previous_health
                     .map(|x| {
                         x.public_internet_ready != health.public_internet_ready
                             || x.local_network_ready != health.local_network_ready
+                            || !x
+                                .crypto_kinds
+                                .iter()
+                                .map(|ckh| ckh.viable)
+                                .eq(health.crypto_kinds.iter().map(|ckh| ckh.viable))
                     })
                     .unwrap_or(true);
             if send_update {
-                Some((update_callback, Self::get_veilid_state_inner(&inner)))
+                Some((
+                    update_callback,
+                    Self::get_veilid_state_inner(&inner),
+                    unviable_crypto_kinds,
+                ))
             } else {
                 None
             }
@@ -172,12 +245,18 @@ impl AttachmentManager {
 
         // Send the update outside of the lock
         if let Some(update) = opt_update {
+            for ck in &update.2 {
+                warn!(
+                    "Too few live peers with {} node ids to perform lookups with that crypto kind",
+                    ck
+                );
+            }
             (update.0)(VeilidUpdate::Attachment(update.1));
         }
     }
 
     fn update_attaching_detaching_state(&self, state: AttachmentState) {
-        let update_callback = {
+        let (update_callback, storage) = {
             let mut inner = self.inner.lock();
 
             // Clear routing table health so when we start measuring it we start from scratch
@@ -198,7 +277,10 @@ impl AttachmentManager {
             }
 
             // Get callback
-            inner.update_callback.clone()
+            (
+                inner.update_callback.clone(),
+                inner.last_storage_status.clone(),
+            )
         };
 
         // Send update
@@ -207,6 +289,8 @@ impl AttachmentManager {
                 state,
                 public_internet_ready: false,
                 local_network_ready: false,
+                storage,
+                crypto_kinds: vec![],
             })))
         }
     }
@@ -217,6 +301,7 @@ impl AttachmentManager {
         self.update_attaching_detaching_state(AttachmentState::Attaching);
 
         let netman = self.network_manager();
+        let storage_manager = self.storage_manager();
 
         let mut restart;
         loop {
@@ -237,6 +322,13 @@ impl AttachmentManager {
                     break;
                 }
 
+                // In low power mode every tick is a wake, so send what was batched up since the last one
+                if netman.is_low_power() {
+                    if let Err(err) = storage_manager.low_power_wake().await {
+                        log_stor!(debug "low power wake failed: {}", err);
+                    }
+                }
+
                 // see if we need to restart the network
                 if netman.needs_restart() {
                     info!("Restarting network");
@@ -244,12 +336,26 @@ impl AttachmentManager {
                     break;
                 }
 
-                // Update attachment and network readiness state
+                // Update attachment, network readiness and storage state
                 // and possibly send a VeilidUpdate::Attachment
-                self.update_attachment();
+                let storage_status = match storage_manager.status().await {
+                    Ok(v) => v,
+                    Err(e) => {
+                        log_net!(debug "could not get storage status: {}", e);
+                        self.inner.lock().last_storage_status.clone()
+                    }
+                };
+                self.update_attachment(storage_status);
 
                 // sleep should be at the end in case maintain_peers changes state
-                sleep(1000).await;
+                let tick_interval_ms = if netman.is_low_power() {
+                    self.config().get().network.low_power_wake_interval_ms
+                } else if netman.network_cost_policy().reduces_tick_rate() {
+                    REDUCED_TICK_INTERVAL_MS
+                } else {
+                    1000
+                };
+                netman.wait_for_next_tick(tick_interval_ms).await;
             }
             log_net!(debug "stopped maintaining peers");
 
@@ -301,6 +407,15 @@ impl AttachmentManager {
         if inner.attachment_maintainer_jh.is_some() {
             return false;
         }
+
+        // Wait until we are on a network we are allowed to use
+        if self.network_cost_policy_inner(&inner).is_suspend() {
+            log_net!(debug "attach suspended by network cost policy");
+            let was_suspended = inner.suspended_attach;
+            inner.suspended_attach = true;
+            return !was_suspended;
+        }
+
         inner.maintain_peers = true;
         inner.attachment_maintainer_jh = Some(spawn(self.clone().attachment_maintainer()));
 
@@ -309,6 +424,11 @@ impl AttachmentManager {
 
     #[instrument(level = "trace", skip(self))]
     pub async fn detach(&self) -> bool {
+        let was_suspended = core::mem::take(&mut self.inner.lock().suspended_attach);
+        self.detach_inner().await || was_suspended
+    }
+
+    async fn detach_inner(&self) -> bool {
         let attachment_maintainer_jh = {
             let mut inner = self.inner.lock();
             let attachment_maintainer_jh = inner.attachment_maintainer_jh.take();
@@ -326,6 +446,50 @@ impl AttachmentManager {
         }
     }
 
+    fn network_cost_policy_inner(
+        &self,
+        inner: &AttachmentManagerInner,
+    ) -> VeilidConfigNetworkCostPolicy {
+        let config = self.config();
+        let c = config.get();
+        inner
+            .network_cost
+            .effective_policy(c.network.metered_policy, c.network.roaming_policy)
+    }
+
+    /// Apply what the platform reports about the cost of the network we are on
+    ///
+    /// Suspending detaches, and we attach again by ourselves once the network no longer
+    /// costs enough to be suspended. The other policies are applied by the network manager.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn set_network_cost(&self, network_cost: NetworkCost) {
+        let (policy, detach, reattach) = {
+            let mut inner = self.inner.lock();
+            inner.network_cost = network_cost;
+            let policy = self.network_cost_policy_inner(&inner);
+
+            let detach = policy.is_suspend() && inner.attachment_maintainer_jh.is_some();
+            if detach {
+                inner.suspended_attach = true;
+            }
+            let reattach = !policy.is_suspend() && inner.suspended_attach;
+            if reattach {
+                inner.suspended_attach = false;
+            }
+            (policy, detach, reattach)
+        };
+
+        self.network_manager().set_network_cost_policy(policy);
+
+        if detach {
+            info!("Suspending attachment while on a costly network");
+            self.detach_inner().await;
+        } else if reattach {
+            info!("Resuming attachment now that the network is no longer costly");
+            self.attach().await;
+        }
+    }
+
     // pub fn get_attachment_state(&self) -> AttachmentState {
     //     self.inner.lock().last_attachment_state
     // }
@@ -343,6 +507,12 @@ impl AttachmentManager {
                 .as_ref()
                 .map(|x| x.local_network_ready)
                 .unwrap_or(false),
+            storage: inner.last_storage_status.clone(),
+            crypto_kinds: inner
+                .last_routing_table_health
+                .as_ref()
+                .map(|x| x.crypto_kinds.clone())
+                .unwrap_or_default(),
         })
     }
 
@@ -67,7 +67,11 @@ impl ServicesContext {
         info!("Veilid API starting up");
 
         info!("init api tracing");
-        ApiTracingLayer::init(self.update_callback.clone()).await;
+        ApiTracingLayer::init(
+            VeilidInstanceKey::from_config(&self.config),
+            self.update_callback.clone(),
+        )
+        .await;
 
         // Set up protected store
         let protected_store = ProtectedStore::new(self.config.clone());
@@ -104,7 +108,7 @@ impl ServicesContext {
         // Set up block store
         #[cfg(feature = "unstable-blockstore")]
         {
-            let block_store = BlockStore::new(self.config.clone());
+            let block_store = BlockStore::new(table_store.clone(), crypto.clone());
             if let Err(e) = block_store.init().await {
                 error!("failed to init block store: {}", e);
                 self.shutdown().await;
@@ -178,7 +182,7 @@ impl ServicesContext {
         info!("Veilid API shutdown complete");
 
         // api logger terminate is idempotent
-        ApiTracingLayer::terminate().await;
+        ApiTracingLayer::terminate(VeilidInstanceKey::from_config(&self.config)).await;
 
         // send final shutdown update
         (self.update_callback)(VeilidUpdate::Shutdown);
@@ -190,6 +194,7 @@ impl ServicesContext {
 pub(crate) struct VeilidCoreContext {
     pub config: VeilidConfig,
     pub update_callback: UpdateCallback,
+    pub debug_cache: Arc<Mutex<DebugCache>>,
     // Services
     pub storage_manager: StorageManager,
     pub protected_store: ProtectedStore,
@@ -228,17 +233,33 @@ impl VeilidCoreContext {
     async fn new_with_config(
         update_callback: UpdateCallback,
         config_inner: VeilidConfigInner,
+        hardware_keystore: Option<Arc<dyn HardwareKeystore>>,
     ) -> VeilidAPIResult<VeilidCoreContext> {
         // Set up config from json
         let mut config = VeilidConfig::new();
         config.setup_from_config(config_inner, update_callback.clone())?;
+        config.set_hardware_keystore(hardware_keystore);
+        Self::new_common(update_callback, config).await
+    }
+
+    #[cfg(all(any(test, feature = "sim-network"), not(target_arch = "wasm32")))]
+    #[instrument(err, skip_all)]
+    async fn new_on_sim_network(
+        update_callback: UpdateCallback,
+        config_callback: ConfigCallback,
+        sim_network: crate::network_manager::SimNetwork,
+    ) -> VeilidAPIResult<VeilidCoreContext> {
+        // Set up config from callback
+        let mut config = VeilidConfig::new();
+        config.setup(config_callback, update_callback.clone())?;
+        config.set_sim_network(Some(sim_network));
         Self::new_common(update_callback, config).await
     }
 
     #[instrument(err, skip_all)]
     async fn new_common(
         update_callback: UpdateCallback,
-        config: VeilidConfig,
+        mut config: VeilidConfig,
     ) -> VeilidAPIResult<VeilidCoreContext> {
         cfg_if! {
             if #[cfg(target_os = "android")] {
@@ -248,12 +269,26 @@ impl VeilidCoreContext {
             }
         }
 
+        // Nodes that would share storage can not run at the same time
+        // The key is reserved up front so other nodes can start up while this one does
+        let instance_key = VeilidInstanceKey::from_config(&config);
+        if !INITIALIZED.lock().insert(instance_key.clone()) {
+            apibail_already_initialized!();
+        }
+
+        // Everything the node does happens in its span, so its logs go to its own update callback
+        config.set_instance_span(instance_key.span());
+
         let mut sc = ServicesContext::new_empty(config.clone(), update_callback);
-        sc.startup().await.map_err(VeilidAPIError::generic)?;
+        if let Err(e) = sc.startup().instrument(config.instance_span()).await {
+            INITIALIZED.lock().remove(&instance_key);
+            return Err(VeilidAPIError::generic(e));
+        }
 
         Ok(VeilidCoreContext {
             config: sc.config,
             update_callback: sc.update_callback,
+            debug_cache: Arc::new(Mutex::new(DebugCache::default())),
             storage_manager: sc.storage_manager.unwrap(),
             protected_store: sc.protected_store.unwrap(),
             table_store: sc.table_store.unwrap(),
@@ -266,6 +301,7 @@ impl VeilidCoreContext {
 
     #[instrument(skip_all)]
     async fn shutdown(self) {
+        let instance_span = self.config.instance_span();
         let mut sc = ServicesContext::new_full(
             self.config.clone(),
             self.update_callback.clone(),
@@ -277,19 +313,54 @@ impl VeilidCoreContext {
             self.attachment_manager,
             self.storage_manager,
         );
-        sc.shutdown().await;
+        sc.shutdown().instrument(instance_span).await;
     }
 }
 
 /////////////////////////////////////////////////////////////////////////////
 
-lazy_static::lazy_static! {
-    static ref INITIALIZED: AsyncMutex<bool> = AsyncMutex::new(false);
+/// Identifies a running node in this process
+///
+/// Nodes are told apart by their program name and namespace, which also keep their storage apart,
+/// so any number of nodes with different namespaces can run in one process at the same time.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VeilidInstanceKey {
+    pub program_name: String,
+    pub namespace: String,
+}
+
+impl VeilidInstanceKey {
+    pub(crate) fn from_config(config: &VeilidConfig) -> Self {
+        let c = config.get();
+        Self {
+            program_name: c.program_name.clone(),
+            namespace: c.namespace.clone(),
+        }
+    }
+
+    /// Make the root span for everything this node does
+    ///
+    /// The span is at the error level so that log filters never hide it, as the api tracing layer
+    /// looks for it to know which node's update callback a log event belongs to.
+    pub(crate) fn span(&self) -> Span {
+        // The field name is VEILID_INSTANCE_FIELD
+        error_span!(parent: None, "veilid_instance", veilid_instance = %self)
+    }
 }
 
+impl fmt::Display for VeilidInstanceKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.program_name, self.namespace)
+    }
+}
+
+/// The nodes running in this process, which can not be started again until they shut down
+static INITIALIZED: Mutex<BTreeSet<VeilidInstanceKey>> = Mutex::new(BTreeSet::new());
+
 /// Initialize a Veilid node.
 ///
-/// Must be called only once at the start of an application
+/// Can be called again to run more nodes in the same process, as long as each one has its own namespace.
+/// Starting a node with the same program name and namespace as a running one fails with [VeilidAPIError::AlreadyInitialized].
 ///
 /// * `update_callback` - called when internal state of the Veilid node changes, for example, when app-level messages are received, when private routes die and need to be reallocated, or when routing table states change
 /// * `config_callback` - called at startup to supply a configuration object directly to Veilid
@@ -300,12 +371,6 @@ pub async fn api_startup(
     update_callback: UpdateCallback,
     config_callback: ConfigCallback,
 ) -> VeilidAPIResult<VeilidAPI> {
-    // See if we have an API started up already
-    let mut initialized_lock = INITIALIZED.lock().await;
-    if *initialized_lock {
-        apibail_already_initialized!();
-    }
-
     // Create core context
     let context =
         VeilidCoreContext::new_with_config_callback(update_callback, config_callback).await?;
@@ -313,14 +378,12 @@ pub async fn api_startup(
     // Return an API object around our context
     let veilid_api = VeilidAPI::new(context);
 
-    *initialized_lock = true;
-
     Ok(veilid_api)
 }
 
 /// Initialize a Veilid node, with the configuration in JSON format
 ///
-/// Must be called only once at the start of an application
+/// Otherwise the same as [api_startup].
 ///
 /// * `update_callback` - called when internal state of the Veilid node changes, for example, when app-level messages are received, when private routes die and need to be reallocated, or when routing table states change
 /// * `config_json` - called at startup to supply a JSON configuration object
@@ -331,26 +394,18 @@ pub async fn api_startup_json(
     update_callback: UpdateCallback,
     config_json: String,
 ) -> VeilidAPIResult<VeilidAPI> {
-    // See if we have an API started up already
-    let mut initialized_lock = INITIALIZED.lock().await;
-    if *initialized_lock {
-        apibail_already_initialized!();
-    }
-
     // Create core context
     let context = VeilidCoreContext::new_with_config_json(update_callback, config_json).await?;
 
     // Return an API object around our context
     let veilid_api = VeilidAPI::new(context);
 
-    *initialized_lock = true;
-
     Ok(veilid_api)
 }
 
 /// Initialize a Veilid node, with the configuration object
 ///
-/// Must be called only once at the start of an application
+/// Otherwise the same as [api_startup].
 ///
 /// * `update_callback` - called when internal state of the Veilid node changes, for example, when app-level messages are received, when private routes die and need to be reallocated, or when routing table states change
 /// * `config` - called at startup to supply a configuration object
@@ -361,26 +416,59 @@ pub async fn api_startup_config(
     update_callback: UpdateCallback,
     config: VeilidConfigInner,
 ) -> VeilidAPIResult<VeilidAPI> {
-    // See if we have an API started up already
-    let mut initialized_lock = INITIALIZED.lock().await;
-    if *initialized_lock {
-        apibail_already_initialized!();
-    }
+    // Create core context
+    let context = VeilidCoreContext::new_with_config(update_callback, config, None).await?;
+
+    // Return an API object around our context
+    let veilid_api = VeilidAPI::new(context);
 
+    Ok(veilid_api)
+}
+
+/// Initialize a Veilid node, with the configuration object and the platform hardware keystore to keep its node ids in
+///
+/// The keystore is only used by this node, and only if `protected_store.hardware_node_id` is enabled.
+///
+/// Returns a [VeilidAPI] object that can be used to operate the node
+#[instrument(err, skip_all)]
+pub async fn api_startup_with_hardware_keystore(
+    update_callback: UpdateCallback,
+    config: VeilidConfigInner,
+    hardware_keystore: Arc<dyn HardwareKeystore>,
+) -> VeilidAPIResult<VeilidAPI> {
     // Create core context
-    let context = VeilidCoreContext::new_with_config(update_callback, config).await?;
+    let context =
+        VeilidCoreContext::new_with_config(update_callback, config, Some(hardware_keystore))
+            .await?;
 
     // Return an API object around our context
     let veilid_api = VeilidAPI::new(context);
 
-    *initialized_lock = true;
+    Ok(veilid_api)
+}
+
+/// Initialize a Veilid node on a simulated network instead of real sockets
+#[cfg(all(any(test, feature = "sim-network"), not(target_arch = "wasm32")))]
+#[instrument(err, skip_all)]
+pub(crate) async fn api_startup_on_sim_network(
+    update_callback: UpdateCallback,
+    config_callback: ConfigCallback,
+    sim_network: crate::network_manager::SimNetwork,
+) -> VeilidAPIResult<VeilidAPI> {
+    // Create core context
+    let context =
+        VeilidCoreContext::new_on_sim_network(update_callback, config_callback, sim_network)
+            .await?;
+
+    // Return an API object around our context
+    let veilid_api = VeilidAPI::new(context);
 
     Ok(veilid_api)
 }
 
 #[instrument(skip_all)]
 pub(crate) async fn api_shutdown(context: VeilidCoreContext) {
-    let mut initialized_lock = INITIALIZED.lock().await;
+    let instance_key = VeilidInstanceKey::from_config(&context.config);
     context.shutdown().await;
-    *initialized_lock = false;
+    INITIALIZED.lock().remove(&instance_key);
 }
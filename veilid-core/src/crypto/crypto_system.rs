@@ -41,12 +41,50 @@ pub trait CryptoSystem {
         hash: &HashDigest,
     ) -> VeilidAPIResult<bool>;
 
+    // Key Encapsulation
+    fn kem_ciphertext_length(&self) -> usize {
+        0
+    }
+    fn kem_public_key(&self, _secret: &SecretKey) -> VeilidAPIResult<Option<KemPublicKey>> {
+        Ok(None)
+    }
+    fn encapsulate(
+        &self,
+        _kem_public_key: &KemPublicKey,
+    ) -> VeilidAPIResult<(Vec<u8>, SharedSecret)> {
+        apibail_generic!("key encapsulation not supported by this crypto kind");
+    }
+    fn decapsulate(
+        &self,
+        _ciphertext: &[u8],
+        _secret: &SecretKey,
+    ) -> VeilidAPIResult<SharedSecret> {
+        apibail_generic!("key encapsulation not supported by this crypto kind");
+    }
+    fn combine_shared_secrets(
+        &self,
+        dh_secret: &SharedSecret,
+        _kem_secret: &SharedSecret,
+    ) -> SharedSecret {
+        *dh_secret
+    }
+
     // Distance Metric
     fn distance(&self, key1: &CryptoKey, key2: &CryptoKey) -> CryptoKeyDistance;
 
     // Authentication
     fn sign(&self, key: &PublicKey, secret: &SecretKey, data: &[u8]) -> VeilidAPIResult<Signature>;
     fn verify(&self, key: &PublicKey, data: &[u8], signature: &Signature) -> VeilidAPIResult<()>;
+    /// Verify many signatures at once, failing if any of them do not verify
+    /// Does not say which signature failed, callers that need to know should fall back to verify()
+    /// Implementations that are faster than verifying one at a time must still accept and reject exactly
+    /// what verify() does, or nodes could disagree about which signed data is valid
+    fn verify_batch(&self, batch: &[(&PublicKey, &[u8], &Signature)]) -> VeilidAPIResult<()> {
+        for (key, data, signature) in batch {
+            self.verify(key, data, signature)?;
+        }
+        Ok(())
+    }
 
     // AEAD Encrypt/Decrypt
     fn aead_overhead(&self) -> usize;
@@ -19,21 +19,51 @@ use core::convert::TryInto;
 /// struct EnvelopeV0 {
 ///     // Size is 106 bytes without signature and 170 with signature
 ///     magic: [u8; 3],              // 0x00: 0x56 0x4C 0x44 ("VLD")
-///     version: u8,                 // 0x03: 0 = EnvelopeV0
+///     version: u8,                 // 0x03: 0 = EnvelopeV0, high bit set if the body starts with a compression algorithm id, next bit set if the body is a message fragment
 ///     crypto_kind: [u8; 4],        // 0x04: CryptoSystemVersion FOURCC code (CryptoKind)
 ///     size: u16,                   // 0x08: Total size of the envelope including the encrypted operations message. Maximum size is 65,507 bytes, which is the data size limit for a single UDP message on IPv4.
 ///     timestamp: u64,              // 0x0A: Duration since UNIX_EPOCH in microseconds when this message is sent. Messages older than 10 seconds are dropped.
 ///     nonce: [u8; 24],             // 0x12: Random nonce for replay protection and for dh
 ///     sender_id: [u8; 32],         // 0x2A: Node ID of the message source, which is the public key of the sender (must be verified with find_node if this is a new node_id/address combination)
 ///     recipient_id: [u8; 32],      // 0x4A: Node ID of the intended recipient, which is the public key of the recipient (must be the receiving node, or a relay lease holder)
-///                                  // 0x6A: message is appended (operations)
+///     kem_ciphertext: [u8; N],     // 0x6A: Key encapsulation ciphertext for hybrid crypto kinds (N = 0 for crypto kinds without key encapsulation)
+///                                  // 0x6A+N: message is appended (operations)
 ///     signature: [u8; 64],         // 0x?? (end-0x40): Signature of the entire envelope including header is appended to the packet
 ///                                  // entire header needs to be included in message digest, relays are not allowed to modify the envelope without invalidating the signature.
 /// }
 
 pub const MAX_ENVELOPE_SIZE: usize = 65507;
 pub const MIN_ENVELOPE_SIZE: usize = 0x6A + 0x40; // Header + Signature
+pub const ENVELOPE_HEADER_SIZE: usize = 0x6A;
 pub const ENVELOPE_MAGIC: &[u8; 3] = b"VLD";
+/// Set in the version byte when the body starts with an [EnvelopeCompressionAlgorithm] id.
+/// Only sent to nodes with the envelope compression capability, other nodes always get an lz4 compressed body.
+pub const ENVELOPE_FLAG_COMPRESSION: u8 = 0x80;
+/// Set in the version byte when the body is one fragment of a message that was split to fit the recipient's maximum envelope size.
+/// Only sent to nodes that advertise a maximum envelope size.
+pub const ENVELOPE_FLAG_FRAGMENT: u8 = 0x40;
+pub const ENVELOPE_VERSION_MASK: u8 = 0x3F;
+/// Bodies smaller than this are not worth compressing
+pub const MIN_ENVELOPE_COMPRESSION_SIZE: usize = 128;
+
+/// How an envelope body with a compression algorithm id is compressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum EnvelopeCompressionAlgorithm {
+    None = 0,
+    Lz4 = 1,
+}
+
+impl TryFrom<u8> for EnvelopeCompressionAlgorithm {
+    type Error = VeilidAPIError;
+    fn try_from(b: u8) -> Result<Self, Self::Error> {
+        match b {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Lz4),
+            _ => apibail_parse_error!("unsupported envelope compression algorithm", b),
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Envelope {
@@ -43,6 +73,8 @@ pub struct Envelope {
     nonce: Nonce,
     sender_id: PublicKey,
     recipient_id: PublicKey,
+    negotiated_compression: bool,
+    fragment: bool,
 }
 
 impl Envelope {
@@ -63,9 +95,25 @@ impl Envelope {
             nonce,
             sender_id,
             recipient_id,
+            negotiated_compression: false,
+            fragment: false,
         }
     }
 
+    /// Start the body with a compression algorithm id, and only compress it when that makes it smaller
+    /// The recipient must have the envelope compression capability
+    pub fn with_negotiated_compression(mut self, negotiated_compression: bool) -> Self {
+        self.negotiated_compression = negotiated_compression;
+        self
+    }
+
+    /// Mark the body as one fragment of a larger message
+    /// The recipient must advertise a maximum envelope size
+    pub fn with_fragment(mut self, fragment: bool) -> Self {
+        self.fragment = fragment;
+        self
+    }
+
     pub fn from_signed_data(
         crypto: Crypto,
         data: &[u8],
@@ -86,7 +134,9 @@ impl Envelope {
         }
 
         // Check envelope version
-        let version = data[0x03];
+        let negotiated_compression = (data[0x03] & ENVELOPE_FLAG_COMPRESSION) != 0;
+        let fragment = (data[0x03] & ENVELOPE_FLAG_FRAGMENT) != 0;
+        let version = data[0x03] & ENVELOPE_VERSION_MASK;
         if !VALID_ENVELOPE_VERSIONS.contains(&version) {
             apibail_parse_error!("unsupported envelope version", version);
         }
@@ -101,6 +151,11 @@ impl Envelope {
             apibail_parse_error!("unsupported crypto kind", crypto_kind);
         };
 
+        // Ensure we are large enough to hold any key encapsulation for this crypto kind
+        if data.len() < MIN_ENVELOPE_SIZE + vcrypto.kem_ciphertext_length() {
+            apibail_generic!("envelope data too small for crypto kind");
+        }
+
         // Get size and ensure it matches the size of the envelope and is less than the maximum message size
         let size: u16 = u16::from_le_bytes(
             data[0x08..0x0A]
@@ -184,6 +239,8 @@ impl Envelope {
             nonce,
             sender_id,
             recipient_id,
+            negotiated_compression,
+            fragment,
         })
     }
 
@@ -191,14 +248,22 @@ impl Envelope {
         &self,
         crypto: Crypto,
         data: &[u8],
-        node_id_secret: &SecretKey,
+        node_id_signer: &dyn NodeIdSigner,
         network_key: &Option<SharedSecret>,
     ) -> VeilidAPIResult<Vec<u8>> {
         // Get DH secret
         let vcrypto = crypto
             .get(self.crypto_kind)
             .expect("need to ensure only valid crypto kinds here");
-        let mut dh_secret = vcrypto.cached_dh(&self.sender_id, node_id_secret)?;
+        let mut dh_secret = node_id_signer.cached_dh(&self.sender_id)?;
+
+        // Combine with key encapsulation if this crypto kind is hybrid
+        let kem_ciphertext_length = vcrypto.kem_ciphertext_length();
+        let body_start = ENVELOPE_HEADER_SIZE + kem_ciphertext_length;
+        if kem_ciphertext_length != 0 {
+            let kem_secret = node_id_signer.decapsulate(&data[ENVELOPE_HEADER_SIZE..body_start])?;
+            dh_secret = vcrypto.combine_shared_secrets(&dh_secret, &kem_secret);
+        }
 
         // Apply network key
         if let Some(nk) = network_key.as_ref() {
@@ -208,26 +273,59 @@ impl Envelope {
         }
         // Decrypt message without authentication
         let body = vcrypto.crypt_no_auth_aligned_8(
-            &data[0x6A..data.len() - 64],
+            &data[body_start..data.len() - 64],
             &self.nonce.bytes,
             &dh_secret,
         );
 
         // Decompress body
-        let body = decompress_size_prepended(&body, Some(MAX_ENVELOPE_SIZE))?;
+        if !self.negotiated_compression {
+            return decompress_size_prepended(&body, Some(MAX_ENVELOPE_SIZE));
+        }
+        let Some((algorithm, body)) = body.split_first() else {
+            apibail_generic!("envelope body missing compression algorithm");
+        };
+        match EnvelopeCompressionAlgorithm::try_from(*algorithm)? {
+            EnvelopeCompressionAlgorithm::None => Ok(body.to_vec()),
+            EnvelopeCompressionAlgorithm::Lz4 => {
+                decompress_size_prepended(body, Some(MAX_ENVELOPE_SIZE))
+            }
+        }
+    }
 
-        Ok(body)
+    /// Compress a body if it is large enough and compresses well, prefixed with the algorithm used
+    fn compress_negotiated(body: &[u8]) -> Vec<u8> {
+        if body.len() >= MIN_ENVELOPE_COMPRESSION_SIZE {
+            let compressed = compress_prepend_size(body);
+            if compressed.len() < body.len() {
+                let mut out = Vec::with_capacity(compressed.len() + 1);
+                out.push(EnvelopeCompressionAlgorithm::Lz4 as u8);
+                out.extend_from_slice(&compressed);
+                return out;
+            }
+        }
+        let mut out = Vec::with_capacity(body.len() + 1);
+        out.push(EnvelopeCompressionAlgorithm::None as u8);
+        out.extend_from_slice(body);
+        out
     }
 
     pub fn to_encrypted_data(
         &self,
         crypto: Crypto,
         body: &[u8],
-        node_id_secret: &SecretKey,
+        node_id_signer: &dyn NodeIdSigner,
+        recipient_kem_public_key: Option<&KemPublicKey>,
         network_key: &Option<SharedSecret>,
     ) -> VeilidAPIResult<Vec<u8>> {
+        let vcrypto = crypto
+            .get(self.crypto_kind)
+            .expect("need to ensure only valid crypto kinds here");
+        let kem_ciphertext_length = vcrypto.kem_ciphertext_length();
+        let body_start = ENVELOPE_HEADER_SIZE + kem_ciphertext_length;
+
         // Ensure body isn't too long
-        let uncompressed_body_size: usize = body.len() + MIN_ENVELOPE_SIZE;
+        let uncompressed_body_size: usize = body.len() + MIN_ENVELOPE_SIZE + kem_ciphertext_length;
         if uncompressed_body_size > MAX_ENVELOPE_SIZE {
             apibail_parse_error!(
                 "envelope size before compression is too large",
@@ -236,10 +334,14 @@ impl Envelope {
         }
 
         // Compress body
-        let body = compress_prepend_size(body);
+        let body = if self.negotiated_compression {
+            Self::compress_negotiated(body)
+        } else {
+            compress_prepend_size(body)
+        };
 
         // Ensure body isn't too long
-        let envelope_size: usize = body.len() + MIN_ENVELOPE_SIZE;
+        let envelope_size: usize = body.len() + MIN_ENVELOPE_SIZE + kem_ciphertext_length;
         if envelope_size > MAX_ENVELOPE_SIZE {
             apibail_parse_error!(
                 "envelope size after compression is too large",
@@ -247,18 +349,31 @@ impl Envelope {
             );
         }
         // Generate dh secret
-        let vcrypto = crypto
-            .get(self.crypto_kind)
-            .expect("need to ensure only valid crypto kinds here");
-        let mut dh_secret = vcrypto.cached_dh(&self.recipient_id, node_id_secret)?;
+        let mut dh_secret = node_id_signer.cached_dh(&self.recipient_id)?;
 
         // Write envelope body
         let mut data = vec![0u8; envelope_size];
 
+        // Combine with key encapsulation if this crypto kind is hybrid
+        if kem_ciphertext_length != 0 {
+            let Some(recipient_kem_public_key) = recipient_kem_public_key else {
+                apibail_generic!("recipient kem public key required for crypto kind");
+            };
+            let (kem_ciphertext, kem_secret) = vcrypto.encapsulate(recipient_kem_public_key)?;
+            data[ENVELOPE_HEADER_SIZE..body_start].copy_from_slice(&kem_ciphertext);
+            dh_secret = vcrypto.combine_shared_secrets(&dh_secret, &kem_secret);
+        }
+
         // Write magic
         data[0x00..0x03].copy_from_slice(ENVELOPE_MAGIC);
         // Write version
         data[0x03] = self.version;
+        if self.negotiated_compression {
+            data[0x03] |= ENVELOPE_FLAG_COMPRESSION;
+        }
+        if self.fragment {
+            data[0x03] |= ENVELOPE_FLAG_FRAGMENT;
+        }
         // Write crypto kind
         data[0x04..0x08].copy_from_slice(&self.crypto_kind.0);
         // Write size
@@ -293,15 +408,11 @@ impl Envelope {
 
         // Write body
         if !encrypted_body.is_empty() {
-            data[0x6A..envelope_size - 64].copy_from_slice(encrypted_body.as_slice());
+            data[body_start..envelope_size - 64].copy_from_slice(encrypted_body.as_slice());
         }
 
         // Sign the envelope
-        let signature = vcrypto.sign(
-            &self.sender_id,
-            node_id_secret,
-            &data[0..(envelope_size - 64)],
-        )?;
+        let signature = node_id_signer.sign(&data[0..(envelope_size - 64)])?;
 
         // Append the signature
         data[(envelope_size - 64)..].copy_from_slice(&signature.bytes);
@@ -313,6 +424,14 @@ impl Envelope {
         self.version
     }
 
+    pub fn has_negotiated_compression(&self) -> bool {
+        self.negotiated_compression
+    }
+
+    pub fn is_fragment(&self) -> bool {
+        self.fragment
+    }
+
     pub fn get_crypto_kind(&self) -> CryptoKind {
         self.crypto_kind
     }
@@ -1,6 +1,7 @@
 mod blake3digest512;
 mod dh_cache;
 mod envelope;
+mod node_id_signer;
 mod receipt;
 mod types;
 
@@ -11,11 +12,14 @@ pub mod none;
 pub mod tests;
 #[cfg(feature = "enable-crypto-vld0")]
 pub mod vld0;
+#[cfg(feature = "enable-crypto-vld1")]
+pub mod vld1;
 
 pub use blake3digest512::*;
 
 pub use crypto_system::*;
 pub use envelope::*;
+pub use node_id_signer::*;
 pub use receipt::*;
 pub use types::*;
 
@@ -23,6 +27,8 @@ pub use types::*;
 pub use none::*;
 #[cfg(feature = "enable-crypto-vld0")]
 pub use vld0::*;
+#[cfg(feature = "enable-crypto-vld1")]
+pub use vld1::*;
 
 use super::*;
 use core::convert::TryInto;
@@ -33,23 +39,26 @@ use hashlink::LruCache;
 /// Handle to a particular cryptosystem
 pub type CryptoSystemVersion = Arc<dyn CryptoSystem + Send + Sync>;
 
-cfg_if! {
-    if #[cfg(all(feature = "enable-crypto-none", feature = "enable-crypto-vld0"))] {
-        /// Crypto kinds in order of preference, best cryptosystem is the first one, worst is the last one
-        pub const VALID_CRYPTO_KINDS: [CryptoKind; 2] = [CRYPTO_KIND_VLD0, CRYPTO_KIND_NONE];
-    }
-    else if #[cfg(feature = "enable-crypto-none")] {
-        /// Crypto kinds in order of preference, best cryptosystem is the first one, worst is the last one
-        pub const VALID_CRYPTO_KINDS: [CryptoKind; 1] = [CRYPTO_KIND_NONE];
-    }
-    else if #[cfg(feature = "enable-crypto-vld0")] {
-        /// Crypto kinds in order of preference, best cryptosystem is the first one, worst is the last one
-        pub const VALID_CRYPTO_KINDS: [CryptoKind; 1] = [CRYPTO_KIND_VLD0];
-    }
-    else {
-        compile_error!("No crypto kinds enabled, specify an enable-crypto- feature");
-    }
-}
+#[cfg(not(any(
+    feature = "enable-crypto-none",
+    feature = "enable-crypto-vld0",
+    feature = "enable-crypto-vld1"
+)))]
+compile_error!("No crypto kinds enabled, specify an enable-crypto- feature");
+
+/// Number of crypto kinds enabled in this build
+pub const VALID_CRYPTO_KINDS_COUNT: usize = cfg!(feature = "enable-crypto-vld1") as usize
+    + cfg!(feature = "enable-crypto-vld0") as usize
+    + cfg!(feature = "enable-crypto-none") as usize;
+/// Crypto kinds in order of preference, best cryptosystem is the first one, worst is the last one
+pub const VALID_CRYPTO_KINDS: [CryptoKind; VALID_CRYPTO_KINDS_COUNT] = [
+    #[cfg(feature = "enable-crypto-vld1")]
+    CRYPTO_KIND_VLD1,
+    #[cfg(feature = "enable-crypto-vld0")]
+    CRYPTO_KIND_VLD0,
+    #[cfg(feature = "enable-crypto-none")]
+    CRYPTO_KIND_NONE,
+];
 /// Number of cryptosystem signatures to keep on structures if many are present beyond the ones we consider valid
 pub const MAX_CRYPTO_KINDS: usize = 3;
 /// Return the best cryptosystem kind we support
@@ -72,8 +81,11 @@ pub fn best_envelope_version() -> EnvelopeVersion {
 struct CryptoInner {
     dh_cache: DHCache,
     flush_future: Option<SendPinBoxFuture<()>>,
+    hardware_node_id_signers: BTreeMap<CryptoKind, Arc<dyn NodeIdSigner>>,
     #[cfg(feature = "enable-crypto-vld0")]
     crypto_vld0: Option<Arc<dyn CryptoSystem + Send + Sync>>,
+    #[cfg(feature = "enable-crypto-vld1")]
+    crypto_vld1: Option<Arc<dyn CryptoSystem + Send + Sync>>,
     #[cfg(feature = "enable-crypto-none")]
     crypto_none: Option<Arc<dyn CryptoSystem + Send + Sync>>,
 }
@@ -95,8 +107,11 @@ impl Crypto {
         CryptoInner {
             dh_cache: DHCache::new(DH_CACHE_SIZE),
             flush_future: None,
+            hardware_node_id_signers: BTreeMap::new(),
             #[cfg(feature = "enable-crypto-vld0")]
             crypto_vld0: None,
+            #[cfg(feature = "enable-crypto-vld1")]
+            crypto_vld1: None,
             #[cfg(feature = "enable-crypto-none")]
             crypto_none: None,
         }
@@ -116,6 +131,11 @@ impl Crypto {
             out.inner.lock().crypto_vld0 = Some(Arc::new(vld0::CryptoSystemVLD0::new(out.clone())));
         }
 
+        #[cfg(feature = "enable-crypto-vld1")]
+        {
+            out.inner.lock().crypto_vld1 = Some(Arc::new(vld1::CryptoSystemVLD1::new(out.clone())));
+        }
+
         #[cfg(feature = "enable-crypto-none")]
         {
             out.inner.lock().crypto_none = Some(Arc::new(none::CryptoSystemNONE::new(out.clone())));
@@ -128,18 +148,34 @@ impl Crypto {
         self.unlocked_inner.config.clone()
     }
 
+    /// Get the signer for one of our node ids if it is kept in the hardware keystore
+    pub fn hardware_node_id_signer(&self, kind: CryptoKind) -> Option<Arc<dyn NodeIdSigner>> {
+        self.inner
+            .lock()
+            .hardware_node_id_signers
+            .get(&kind)
+            .cloned()
+    }
+
     #[instrument(skip_all, err)]
     pub async fn init(&self) -> EyreResult<()> {
         let table_store = self.unlocked_inner.table_store.clone();
         // Init node id from config
-        if let Err(e) = self
+        let hardware_node_id_signers = match self
             .unlocked_inner
             .config
             .init_node_ids(self.clone(), table_store.clone())
             .await
         {
-            return Err(e).wrap_err("init node id failed");
-        }
+            Ok(v) => v,
+            Err(e) => {
+                return Err(e).wrap_err("init node id failed");
+            }
+        };
+        self.inner.lock().hardware_node_id_signers = hardware_node_id_signers
+            .into_iter()
+            .map(|s| (s.node_id().kind, s))
+            .collect();
 
         // make local copy of node id for easy access
         let mut cache_validity_key: Vec<u8> = Vec::new();
@@ -218,6 +254,7 @@ impl Crypto {
                 error!("failed termination flush: {}", e);
             }
         };
+        self.inner.lock().hardware_node_id_signers.clear();
     }
 
     /// Factory method to get a specific crypto version
@@ -226,6 +263,8 @@ impl Crypto {
         match kind {
             #[cfg(feature = "enable-crypto-vld0")]
             CRYPTO_KIND_VLD0 => Some(inner.crypto_vld0.clone().unwrap()),
+            #[cfg(feature = "enable-crypto-vld1")]
+            CRYPTO_KIND_VLD1 => Some(inner.crypto_vld1.clone().unwrap()),
             #[cfg(feature = "enable-crypto-none")]
             CRYPTO_KIND_NONE => Some(inner.crypto_none.clone().unwrap()),
             _ => None,
@@ -261,6 +300,40 @@ impl Crypto {
         Ok(out)
     }
 
+    /// Signature set batch verification
+    /// Verifies the signature sets of many pieces of signed data at once, grouping the signatures by cryptokind
+    /// Fails if any signature of a supported cryptokind does not validate, or if any of the signed data
+    /// has no signature that can be validated at all
+    pub fn verify_signatures_batch(
+        &self,
+        batch: &[(&[TypedKey], &[u8], &[TypedSignature])],
+    ) -> VeilidAPIResult<()> {
+        let mut kind_batches = BTreeMap::<CryptoKind, Vec<(&PublicKey, &[u8], &Signature)>>::new();
+        for (node_ids, data, typed_signatures) in batch {
+            let mut has_supported_signature = false;
+            for sig in typed_signatures.iter() {
+                for nid in node_ids.iter() {
+                    if nid.kind == sig.kind && self.get(sig.kind).is_some() {
+                        kind_batches
+                            .entry(sig.kind)
+                            .or_default()
+                            .push((&nid.value, *data, &sig.value));
+                        has_supported_signature = true;
+                    }
+                }
+            }
+            if !has_supported_signature {
+                apibail_generic!("no supported signatures to verify");
+            }
+        }
+        for (kind, kind_batch) in kind_batches {
+            if let Some(vcrypto) = self.get(kind) {
+                vcrypto.verify_batch(&kind_batch)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Signature set generation
     /// Generates the set of signatures that are supported
     /// Any cryptokinds that are not supported are silently dropped
@@ -291,6 +364,11 @@ impl Crypto {
             let kp = vld0_generate_keypair();
             return Ok(TypedKeyPair::new(crypto_kind, kp));
         }
+        #[cfg(feature = "enable-crypto-vld1")]
+        if crypto_kind == CRYPTO_KIND_VLD1 {
+            let kp = vld1_generate_keypair();
+            return Ok(TypedKeyPair::new(crypto_kind, kp));
+        }
         #[cfg(feature = "enable-crypto-none")]
         if crypto_kind == CRYPTO_KIND_NONE {
             let kp = none_generate_keypair();
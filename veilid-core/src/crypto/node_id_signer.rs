@@ -0,0 +1,78 @@
+use super::*;
+
+/// Handle to the secret key of one of our node ids
+///
+/// Everything this node signs or decrypts as itself goes through a signer, so the secret key
+/// does not have to be held in memory. Node ids kept in software use [SecretKeyNodeIdSigner],
+/// node ids kept in a platform hardware keystore get their signer from the [HardwareKeystore].
+///
+/// Envelopes and private routes are encrypted to the node id, so besides signing a signer
+/// must also perform the key agreement for it.
+pub trait NodeIdSigner: Send + Sync {
+    /// The node id this signer holds the secret key for
+    fn node_id(&self) -> TypedKey;
+    /// Sign data with the node id secret key
+    fn sign(&self, data: &[u8]) -> VeilidAPIResult<Signature>;
+    /// Diffie-Hellman key agreement between the node id secret key and another public key
+    fn cached_dh(&self, key: &PublicKey) -> VeilidAPIResult<SharedSecret>;
+    /// The key encapsulation public key for the node id, if its crypto kind is hybrid
+    fn kem_public_key(&self) -> VeilidAPIResult<Option<KemPublicKey>> {
+        Ok(None)
+    }
+    /// Recover a shared secret that was encapsulated to the node id
+    fn decapsulate(&self, _ciphertext: &[u8]) -> VeilidAPIResult<SharedSecret> {
+        apibail_generic!("key encapsulation not supported by this node id signer");
+    }
+}
+
+impl fmt::Debug for dyn NodeIdSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NodeIdSigner")
+            .field("node_id", &self.node_id())
+            .finish()
+    }
+}
+
+/// Sign data with each of the node id signers
+pub fn generate_node_id_signatures(
+    signers: &[Arc<dyn NodeIdSigner>],
+    data: &[u8],
+) -> VeilidAPIResult<Vec<TypedSignature>> {
+    let mut out = Vec::<TypedSignature>::with_capacity(signers.len());
+    for signer in signers {
+        let sig = signer.sign(data)?;
+        out.push(TypedSignature::new(signer.node_id().kind, sig));
+    }
+    Ok(out)
+}
+
+/// Node id signer for a secret key held in software
+pub struct SecretKeyNodeIdSigner {
+    vcrypto: CryptoSystemVersion,
+    keypair: KeyPair,
+}
+
+impl SecretKeyNodeIdSigner {
+    pub fn new(vcrypto: CryptoSystemVersion, keypair: KeyPair) -> Self {
+        Self { vcrypto, keypair }
+    }
+}
+
+impl NodeIdSigner for SecretKeyNodeIdSigner {
+    fn node_id(&self) -> TypedKey {
+        TypedKey::new(self.vcrypto.kind(), self.keypair.key)
+    }
+    fn sign(&self, data: &[u8]) -> VeilidAPIResult<Signature> {
+        self.vcrypto
+            .sign(&self.keypair.key, &self.keypair.secret, data)
+    }
+    fn cached_dh(&self, key: &PublicKey) -> VeilidAPIResult<SharedSecret> {
+        self.vcrypto.cached_dh(key, &self.keypair.secret)
+    }
+    fn kem_public_key(&self) -> VeilidAPIResult<Option<KemPublicKey>> {
+        self.vcrypto.kem_public_key(&self.keypair.secret)
+    }
+    fn decapsulate(&self, ciphertext: &[u8]) -> VeilidAPIResult<SharedSecret> {
+        self.vcrypto.decapsulate(ciphertext, &self.keypair.secret)
+    }
+}
@@ -153,16 +153,12 @@ impl Receipt {
         })
     }
 
-    pub fn to_signed_data(&self, crypto: Crypto, secret: &SecretKey) -> VeilidAPIResult<Vec<u8>> {
+    pub fn to_signed_data(&self, node_id_signer: &dyn NodeIdSigner) -> VeilidAPIResult<Vec<u8>> {
         // Ensure extra data isn't too long
         let receipt_size: usize = self.extra_data.len() + MIN_RECEIPT_SIZE;
         if receipt_size > MAX_RECEIPT_SIZE {
             apibail_parse_error!("receipt too large", receipt_size);
         }
-        // Get crypto version
-        let vcrypto = crypto
-            .get(self.crypto_kind)
-            .expect("need to ensure only valid crypto kinds here");
 
         let mut data: Vec<u8> = vec![0u8; receipt_size];
 
@@ -183,8 +179,8 @@ impl Receipt {
             data[0x42..(receipt_size - 64)].copy_from_slice(self.extra_data.as_slice());
         }
         // Sign the receipt
-        let signature = vcrypto
-            .sign(&self.sender_id, secret, &data[0..(receipt_size - 64)])
+        let signature = node_id_signer
+            .sign(&data[0..(receipt_size - 64)])
             .map_err(VeilidAPIError::generic)?;
         // Append the signature
         data[(receipt_size - 64)..].copy_from_slice(&signature.bytes);
@@ -164,6 +164,58 @@ pub async fn test_dh(vcrypto: CryptoSystemVersion) {
     trace!("cached_dh: {:?}", r5);
 }
 
+pub async fn test_kem(vcrypto: CryptoSystemVersion) {
+    trace!("test_kem");
+    let (_dht_key, dht_key_secret) = vcrypto.generate_keypair().into_split();
+    let Some(kem_public_key) = vcrypto.kem_public_key(&dht_key_secret).unwrap() else {
+        assert_eq!(vcrypto.kem_ciphertext_length(), 0);
+        return;
+    };
+
+    // Derivation must be deterministic
+    let kem_public_key2 = vcrypto.kem_public_key(&dht_key_secret).unwrap().unwrap();
+    assert_eq!(kem_public_key, kem_public_key2);
+
+    let (ciphertext, ss1) = vcrypto.encapsulate(&kem_public_key).unwrap();
+    assert_eq!(ciphertext.len(), vcrypto.kem_ciphertext_length());
+    let ss2 = vcrypto.decapsulate(&ciphertext, &dht_key_secret).unwrap();
+    assert_eq!(ss1, ss2);
+
+    // Decapsulating with the wrong secret must not produce the same secret
+    let (_dht_key3, dht_key_secret3) = vcrypto.generate_keypair().into_split();
+    let ss3 = vcrypto.decapsulate(&ciphertext, &dht_key_secret3).unwrap();
+    assert_ne!(ss1, ss3);
+
+    vcrypto
+        .decapsulate(&ciphertext[1..], &dht_key_secret)
+        .expect_err("should reject short ciphertext");
+}
+
+pub async fn test_node_id_signer(vcrypto: CryptoSystemVersion) {
+    trace!("test_node_id_signer");
+    let keypair = vcrypto.generate_keypair();
+    let (dht_key2, dht_key_secret2) = vcrypto.generate_keypair().into_split();
+    let signer: Arc<dyn NodeIdSigner> =
+        Arc::new(SecretKeyNodeIdSigner::new(vcrypto.clone(), keypair));
+    let node_id = TypedKey::new(vcrypto.kind(), keypair.key);
+    assert_eq!(signer.node_id(), node_id);
+
+    // Signatures made through the signer verify against the node id
+    let data = b"signed by a node id signer";
+    let sigs = generate_node_id_signatures(&[signer.clone()], data).unwrap();
+    assert_eq!(sigs.len(), 1);
+    let validated = vcrypto
+        .crypto()
+        .verify_signatures(&[node_id], data, &sigs)
+        .unwrap();
+    assert!(validated.contains(&node_id));
+
+    // Key agreement through the signer matches key agreement with the secret
+    let r1 = signer.cached_dh(&dht_key2).unwrap();
+    let r2 = vcrypto.compute_dh(&keypair.key, &dht_key_secret2).unwrap();
+    assert_eq!(r1, r2);
+}
+
 pub async fn test_generation(vcrypto: CryptoSystemVersion) {
     let b1 = vcrypto.random_bytes(32);
     let b2 = vcrypto.random_bytes(32);
@@ -234,6 +286,8 @@ pub async fn test_all() {
         test_aead(vcrypto.clone()).await;
         test_no_auth(vcrypto.clone()).await;
         test_dh(vcrypto.clone()).await;
+        test_kem(vcrypto.clone()).await;
+        test_node_id_signer(vcrypto.clone()).await;
         test_generation(vcrypto).await;
     }
 
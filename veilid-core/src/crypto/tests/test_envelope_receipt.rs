@@ -17,8 +17,15 @@ pub async fn test_envelope_round_trip(
     // Create envelope
     let ts = Timestamp::from(0x12345678ABCDEF69u64);
     let nonce = vcrypto.random_nonce();
-    let (sender_id, sender_secret) = vcrypto.generate_keypair().into_split();
-    let (recipient_id, recipient_secret) = vcrypto.generate_keypair().into_split();
+    let sender_keypair = vcrypto.generate_keypair();
+    let recipient_keypair = vcrypto.generate_keypair();
+    let sender_id = sender_keypair.key;
+    let recipient_id = recipient_keypair.key;
+    let sender_signer = SecretKeyNodeIdSigner::new(vcrypto.clone(), sender_keypair);
+    let recipient_signer = SecretKeyNodeIdSigner::new(vcrypto.clone(), recipient_keypair);
+    let recipient_kem_public_key = recipient_signer
+        .kem_public_key()
+        .expect("failed to get kem public key");
     let envelope = Envelope::new(
         envelope_version,
         vcrypto.kind(),
@@ -33,7 +40,13 @@ pub async fn test_envelope_round_trip(
 
     // Serialize to bytes
     let enc_data = envelope
-        .to_encrypted_data(vcrypto.crypto(), body, &sender_secret, &network_key)
+        .to_encrypted_data(
+            vcrypto.crypto(),
+            body,
+            &sender_signer,
+            recipient_kem_public_key.as_ref(),
+            &network_key,
+        )
         .expect("failed to encrypt data");
 
     // Deserialize from bytes
@@ -41,7 +54,7 @@ pub async fn test_envelope_round_trip(
         .expect("failed to deserialize envelope from data");
 
     let body2 = envelope2
-        .decrypt_body(vcrypto.crypto(), &enc_data, &recipient_secret, &network_key)
+        .decrypt_body(vcrypto.crypto(), &enc_data, &recipient_signer, &network_key)
         .expect("failed to decrypt envelope body");
 
     // Compare envelope and body
@@ -64,6 +77,121 @@ pub async fn test_envelope_round_trip(
     );
 }
 
+pub async fn test_envelope_negotiated_compression(
+    envelope_version: EnvelopeVersion,
+    vcrypto: CryptoSystemVersion,
+) {
+    info!(
+        "--- test envelope negotiated compression {} ---",
+        vcrypto.kind()
+    );
+
+    let sender_keypair = vcrypto.generate_keypair();
+    let recipient_keypair = vcrypto.generate_keypair();
+    let sender_id = sender_keypair.key;
+    let recipient_id = recipient_keypair.key;
+    let sender_signer = SecretKeyNodeIdSigner::new(vcrypto.clone(), sender_keypair);
+    let recipient_signer = SecretKeyNodeIdSigner::new(vcrypto.clone(), recipient_keypair);
+    let recipient_kem_public_key = recipient_signer
+        .kem_public_key()
+        .expect("failed to get kem public key");
+
+    // Small bodies are sent uncompressed, large compressible ones are compressed
+    let small_body = b"This is an arbitrary body".to_vec();
+    let large_body = b"This is an arbitrary body".repeat(100);
+    for body in [small_body, large_body] {
+        let envelope = Envelope::new(
+            envelope_version,
+            vcrypto.kind(),
+            Timestamp::from(0x12345678ABCDEF69u64),
+            vcrypto.random_nonce(),
+            sender_id,
+            recipient_id,
+        )
+        .with_negotiated_compression(true);
+
+        let enc_data = envelope
+            .to_encrypted_data(
+                vcrypto.crypto(),
+                &body,
+                &sender_signer,
+                recipient_kem_public_key.as_ref(),
+                &None,
+            )
+            .expect("failed to encrypt data");
+        assert_eq!(enc_data[0x03] & ENVELOPE_VERSION_MASK, envelope_version);
+        assert_ne!(enc_data[0x03] & ENVELOPE_FLAG_COMPRESSION, 0);
+        if body.len() >= MIN_ENVELOPE_COMPRESSION_SIZE {
+            assert!(enc_data.len() < body.len());
+        }
+
+        let envelope2 = Envelope::from_signed_data(vcrypto.crypto(), &enc_data, &None)
+            .expect("failed to deserialize envelope from data");
+        assert!(envelope2.has_negotiated_compression());
+
+        let body2 = envelope2
+            .decrypt_body(vcrypto.crypto(), &enc_data, &recipient_signer, &None)
+            .expect("failed to decrypt envelope body");
+
+        assert_eq!(envelope, envelope2);
+        assert_eq!(body, body2);
+    }
+}
+
+pub async fn test_envelope_fragment(
+    envelope_version: EnvelopeVersion,
+    vcrypto: CryptoSystemVersion,
+) {
+    info!("--- test envelope fragment {} ---", vcrypto.kind());
+
+    let sender_keypair = vcrypto.generate_keypair();
+    let recipient_keypair = vcrypto.generate_keypair();
+    let sender_id = sender_keypair.key;
+    let recipient_id = recipient_keypair.key;
+    let sender_signer = SecretKeyNodeIdSigner::new(vcrypto.clone(), sender_keypair);
+    let recipient_signer = SecretKeyNodeIdSigner::new(vcrypto.clone(), recipient_keypair);
+    let recipient_kem_public_key = recipient_signer
+        .kem_public_key()
+        .expect("failed to get kem public key");
+
+    // The fragment flag does not change the version and survives the round trip
+    let body = b"This is one fragment of an arbitrary body".to_vec();
+    let envelope = Envelope::new(
+        envelope_version,
+        vcrypto.kind(),
+        Timestamp::from(0x12345678ABCDEF69u64),
+        vcrypto.random_nonce(),
+        sender_id,
+        recipient_id,
+    )
+    .with_negotiated_compression(true)
+    .with_fragment(true);
+
+    let enc_data = envelope
+        .to_encrypted_data(
+            vcrypto.crypto(),
+            &body,
+            &sender_signer,
+            recipient_kem_public_key.as_ref(),
+            &None,
+        )
+        .expect("failed to encrypt data");
+    assert_eq!(enc_data[0x03] & ENVELOPE_VERSION_MASK, envelope_version);
+    assert_ne!(enc_data[0x03] & ENVELOPE_FLAG_FRAGMENT, 0);
+
+    let envelope2 = Envelope::from_signed_data(vcrypto.crypto(), &enc_data, &None)
+        .expect("failed to deserialize envelope from data");
+    assert!(envelope2.is_fragment());
+    assert!(envelope2.has_negotiated_compression());
+
+    let body2 = envelope2
+        .decrypt_body(vcrypto.crypto(), &enc_data, &recipient_signer, &None)
+        .expect("failed to decrypt envelope body");
+
+    assert_eq!(envelope, envelope2);
+    assert_eq!(body, body2);
+}
+
 pub async fn test_receipt_round_trip(
     envelope_version: EnvelopeVersion,
     vcrypto: CryptoSystemVersion,
@@ -74,13 +202,15 @@ pub async fn test_receipt_round_trip(
 
     // Create receipt
     let nonce = vcrypto.random_nonce();
-    let (sender_id, sender_secret) = vcrypto.generate_keypair().into_split();
+    let sender_keypair = vcrypto.generate_keypair();
+    let sender_id = sender_keypair.key;
+    let sender_signer = SecretKeyNodeIdSigner::new(vcrypto.clone(), sender_keypair);
     let receipt = Receipt::try_new(envelope_version, vcrypto.kind(), nonce, sender_id, body)
         .expect("should not fail");
 
     // Serialize to bytes
     let mut enc_data = receipt
-        .to_signed_data(vcrypto.crypto(), &sender_secret)
+        .to_signed_data(&sender_signer)
         .expect("failed to make signed data");
 
     // Deserialize from bytes
@@ -108,6 +238,8 @@ pub async fn test_all() {
             test_envelope_round_trip(ev, vcrypto.clone(), None).await;
             test_envelope_round_trip(ev, vcrypto.clone(), Some(vcrypto.random_shared_secret()))
                 .await;
+            test_envelope_negotiated_compression(ev, vcrypto.clone()).await;
+            test_envelope_fragment(ev, vcrypto.clone()).await;
             test_receipt_round_trip(ev, vcrypto).await;
         }
     }
@@ -109,6 +109,155 @@ pub async fn test_sign_and_verify(vcrypto: CryptoSystemVersion) {
         .is_err());
 }
 
+fn make_signed_batch(
+    vcrypto: &CryptoSystemVersion,
+    count: usize,
+) -> Vec<(PublicKey, Vec<u8>, Signature)> {
+    (0..count)
+        .map(|n| {
+            let (key, secret) = vcrypto.generate_keypair().into_split();
+            let data = format!("{}{}", LOREM_IPSUM, n).into_bytes();
+            let signature = vcrypto.sign(&key, &secret, &data).unwrap();
+            (key, data, signature)
+        })
+        .collect()
+}
+
+fn as_batch(signed: &[(PublicKey, Vec<u8>, Signature)]) -> Vec<(&PublicKey, &[u8], &Signature)> {
+    signed
+        .iter()
+        .map(|(key, data, signature)| (key, data.as_slice(), signature))
+        .collect()
+}
+
+pub async fn test_verify_batch(vcrypto: CryptoSystemVersion) {
+    // Empty and small batches
+    assert_eq!(vcrypto.verify_batch(&[]), Ok(()));
+    let signed = make_signed_batch(&vcrypto, 2);
+    assert_eq!(vcrypto.verify_batch(&as_batch(&signed)), Ok(()));
+
+    // A batch of good signatures verifies
+    let mut signed = make_signed_batch(&vcrypto, 32);
+    assert_eq!(vcrypto.verify_batch(&as_batch(&signed)), Ok(()));
+
+    // Any bad signature fails the whole batch
+    let good_signature = signed[7].2;
+    signed[7].2 = signed[8].2;
+    assert!(vcrypto.verify_batch(&as_batch(&signed)).is_err());
+    signed[7].2 = good_signature;
+
+    // Any tampered data fails the whole batch
+    signed[20].1[0] ^= 1;
+    assert!(vcrypto.verify_batch(&as_batch(&signed)).is_err());
+    signed[20].1[0] ^= 1;
+
+    // Any wrong key fails the whole batch
+    let good_key = signed[31].0;
+    signed[31].0 = signed[0].0;
+    assert!(vcrypto.verify_batch(&as_batch(&signed)).is_err());
+    signed[31].0 = good_key;
+
+    assert_eq!(vcrypto.verify_batch(&as_batch(&signed)), Ok(()));
+}
+
+pub async fn test_verify_batch_parity(vcrypto: CryptoSystemVersion) {
+    let mut signed = make_signed_batch(&vcrypto, 16);
+    signed[3].2 = signed[4].2;
+    signed[9].1[0] ^= 1;
+    signed[12].0 = signed[13].0;
+
+    // Every batch is accepted exactly when each of its signatures verifies on its own
+    for start in 0..signed.len() {
+        for end in start..=signed.len() {
+            let batch = as_batch(&signed[start..end]);
+            let expected = batch
+                .iter()
+                .all(|(key, data, signature)| vcrypto.verify(key, data, signature).is_ok());
+            assert_eq!(vcrypto.verify_batch(&batch).is_ok(), expected);
+        }
+    }
+}
+
+/// Make an Ed25519ph signature the way vld0 does, optionally adding a small order component to R
+/// The torsion only shows up in the cofactored verification equation, which must not be used
+#[cfg(feature = "enable-crypto-vld0")]
+fn make_ed25519ph_signature(
+    key: &PublicKey,
+    secret: &SecretKey,
+    data: &[u8],
+    with_torsion: bool,
+) -> Signature {
+    use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+    use curve25519_dalek::digest::Digest;
+    use curve25519_dalek::edwards::CompressedEdwardsY;
+    use curve25519_dalek::scalar::Scalar;
+    use ed25519_dalek::Sha512;
+
+    const CONTEXT: &[u8] = b"VLD0_SIGN";
+
+    // Expanded secret scalar
+    let h: [u8; 64] = Sha512::default()
+        .chain_update(secret.bytes)
+        .finalize()
+        .into();
+    let mut a_bytes = [0u8; 32];
+    a_bytes.copy_from_slice(&h[..32]);
+    a_bytes[0] &= 248;
+    a_bytes[31] &= 127;
+    a_bytes[31] |= 64;
+    let a = Scalar::from_bytes_mod_order(a_bytes);
+
+    let mut r_bytes = [0u8; 64];
+    random_bytes(&mut r_bytes);
+    let r = Scalar::from_bytes_mod_order_wide(&r_bytes);
+    let mut big_r = ED25519_BASEPOINT_POINT * r;
+    if with_torsion {
+        // The point of order 2, (0, -1)
+        let mut t_bytes = [0xffu8; 32];
+        t_bytes[0] = 0xec;
+        t_bytes[31] = 0x7f;
+        big_r += CompressedEdwardsY(t_bytes).decompress().unwrap();
+    }
+    let big_r = big_r.compress();
+
+    // k = H(dom2(1, context) || R || A || PH(M))
+    let prehash: [u8; 64] = Sha512::default().chain_update(data).finalize().into();
+    let k_hash: [u8; 64] = Sha512::default()
+        .chain_update(b"SigEd25519 no Ed25519 collisions")
+        .chain_update([1u8, CONTEXT.len() as u8])
+        .chain_update(CONTEXT)
+        .chain_update(big_r.as_bytes())
+        .chain_update(key.bytes)
+        .chain_update(prehash)
+        .finalize()
+        .into();
+    let k = Scalar::from_bytes_mod_order_wide(&k_hash);
+    let s = r + k * a;
+
+    let mut signature = [0u8; SIGNATURE_LENGTH];
+    signature[..32].copy_from_slice(big_r.as_bytes());
+    signature[32..].copy_from_slice(s.as_bytes());
+    Signature::new(signature)
+}
+
+#[cfg(feature = "enable-crypto-vld0")]
+pub async fn test_verify_batch_torsion(vcrypto: CryptoSystemVersion) {
+    let mut signed = make_signed_batch(&vcrypto, 16);
+    let (key, secret) = vcrypto.generate_keypair().into_split();
+    let data = LOREM_IPSUM.as_bytes().to_vec();
+
+    let signature = make_ed25519ph_signature(&key, &secret, &data, false);
+    assert_eq!(vcrypto.verify(&key, &data, &signature), Ok(()));
+    signed.push((key, data.clone(), signature));
+    assert_eq!(vcrypto.verify_batch(&as_batch(&signed)), Ok(()));
+
+    // A signer can make a signature that only a cofactored check would accept
+    let signature = make_ed25519ph_signature(&key, &secret, &data, true);
+    assert!(vcrypto.verify(&key, &data, &signature).is_err());
+    signed.last_mut().unwrap().2 = signature;
+    assert!(vcrypto.verify_batch(&as_batch(&signed)).is_err());
+}
+
 pub async fn test_key_conversions(vcrypto: CryptoSystemVersion) {
     // Test default key
     let (dht_key, dht_key_secret) = (PublicKey::default(), SecretKey::default());
@@ -391,6 +540,12 @@ pub async fn test_all() {
 
         test_generate_secret(vcrypto.clone()).await;
         test_sign_and_verify(vcrypto.clone()).await;
+        test_verify_batch(vcrypto.clone()).await;
+        test_verify_batch_parity(vcrypto.clone()).await;
+        #[cfg(feature = "enable-crypto-vld0")]
+        if v == CRYPTO_KIND_VLD0 {
+            test_verify_batch_torsion(vcrypto.clone()).await;
+        }
         test_key_conversions(vcrypto.clone()).await;
         test_encode_decode(vcrypto.clone()).await;
         test_typed_convert(vcrypto.clone()).await;
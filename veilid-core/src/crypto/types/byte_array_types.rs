@@ -57,6 +57,12 @@ pub const HASH_DIGEST_LENGTH: usize = CRYPTO_KEY_LENGTH;
 /// Length of a hash digest in bytes after encoding to base64url
 #[allow(dead_code)]
 pub const HASH_DIGEST_LENGTH_ENCODED: usize = CRYPTO_KEY_LENGTH_ENCODED;
+/// Length of a key encapsulation mechanism public key in bytes
+#[allow(dead_code)]
+pub const KEM_PUBLIC_KEY_LENGTH: usize = 1184;
+/// Length of a key encapsulation mechanism public key in bytes after encoding to base64url
+#[allow(dead_code)]
+pub const KEM_PUBLIC_KEY_LENGTH_ENCODED: usize = 1579;
 
 //////////////////////////////////////////////////////////////////////
 
@@ -295,3 +301,8 @@ pub type CryptoKeyDistance = CryptoKey;
 
 byte_array_type!(Signature, SIGNATURE_LENGTH, SIGNATURE_LENGTH_ENCODED);
 byte_array_type!(Nonce, NONCE_LENGTH, NONCE_LENGTH_ENCODED);
+byte_array_type!(
+    KemPublicKey,
+    KEM_PUBLIC_KEY_LENGTH,
+    KEM_PUBLIC_KEY_LENGTH_ENCODED
+);
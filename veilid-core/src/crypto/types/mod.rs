@@ -62,6 +62,8 @@ pub type TypedKeyPair = CryptoTyped<KeyPair>;
 pub type TypedSignature = CryptoTyped<Signature>;
 #[cfg_attr(target_arch = "wasm32", declare)]
 pub type TypedSharedSecret = CryptoTyped<SharedSecret>;
+#[cfg_attr(target_arch = "wasm32", declare)]
+pub type TypedKemPublicKey = CryptoTyped<KemPublicKey>;
 
 #[cfg_attr(target_arch = "wasm32", declare)]
 pub type TypedKeyGroup = CryptoTypedGroup<PublicKey>;
@@ -73,3 +75,5 @@ pub type TypedKeyPairGroup = CryptoTypedGroup<KeyPair>;
 pub type TypedSignatureGroup = CryptoTypedGroup<Signature>;
 #[cfg_attr(target_arch = "wasm32", declare)]
 pub type TypedSharedSecretGroup = CryptoTypedGroup<SharedSecret>;
+#[cfg_attr(target_arch = "wasm32", declare)]
+pub type TypedKemPublicKeyGroup = CryptoTypedGroup<KemPublicKey>;
@@ -0,0 +1,293 @@
+use super::*;
+
+const VEILID_DOMAIN_CRYPT: &[u8] = b"VLD1_CRYPT";
+const VEILID_DOMAIN_KEM: &[u8] = b"VLD1_KEM";
+const VEILID_DOMAIN_HYBRID: &[u8] = b"VLD1_HYBRID";
+
+pub const CRYPTO_KIND_VLD1: CryptoKind = FourCC(*b"VLD1");
+
+/// Length of a Kyber768 ciphertext carried in each VLD1 envelope
+pub const KEM_CIPHERTEXT_LENGTH: usize = pqc_kyber::KYBER_CIPHERTEXTBYTES;
+
+// Ensure our published key size matches the Kyber parameter set in use
+static_assertions::const_assert_eq!(KEM_PUBLIC_KEY_LENGTH, pqc_kyber::KYBER_PUBLICKEYBYTES);
+
+pub fn vld1_generate_keypair() -> KeyPair {
+    // Signing keys are plain Ed25519, the Kyber keypair is derived from the secret
+    vld0_generate_keypair()
+}
+
+/// Kyber768 public and secret key bytes
+type KemKeypair = (pqc_kyber::PublicKey, pqc_kyber::SecretKey);
+
+/// Hybrid X25519+Kyber768 CryptoSystem
+/// Signatures, hashing and AEAD are identical to VLD0
+/// Envelope shared secrets combine the X25519 DH with a Kyber768 encapsulation
+///
+/// The Kyber768 keypair is derived from the node secret and published in the node info, so it is static
+/// and lives as long as the node id does. This keeps traffic recorded today safe from a future quantum
+/// adversary that can break X25519, but it is not forward secrecy: anyone who later learns the node secret
+/// can decapsulate every envelope that was ever sent to the node.
+#[derive(Clone)]
+pub struct CryptoSystemVLD1 {
+    crypto: Crypto,
+    vld0: CryptoSystemVLD0,
+    kem_keypair_cache: Arc<Mutex<Option<(SecretKey, KemKeypair)>>>,
+}
+
+impl CryptoSystemVLD1 {
+    pub fn new(crypto: Crypto) -> Self {
+        Self {
+            vld0: CryptoSystemVLD0::new(crypto.clone()),
+            crypto,
+            kem_keypair_cache: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Deterministically derive the static Kyber keypair that belongs to an Ed25519 secret
+    fn kem_keypair(&self, secret: &SecretKey) -> VeilidAPIResult<KemKeypair> {
+        let mut cache = self.kem_keypair_cache.lock();
+        if let Some((cached_secret, keypair)) = cache.as_ref() {
+            if cached_secret == secret {
+                return Ok(*keypair);
+            }
+        }
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(VEILID_DOMAIN_KEM);
+        hasher.update(&secret.bytes);
+        let mut seed = [0u8; 64];
+        hasher.finalize_xof().fill(&mut seed);
+
+        let keypair = pqc_kyber::derive(&seed)
+            .map(|kp| (kp.public, kp.secret))
+            .map_err(|e| VeilidAPIError::internal(format!("{:?}", e)))?;
+        *cache = Some((*secret, keypair));
+        Ok(keypair)
+    }
+}
+
+impl CryptoSystem for CryptoSystemVLD1 {
+    // Accessors
+    fn kind(&self) -> CryptoKind {
+        CRYPTO_KIND_VLD1
+    }
+
+    fn crypto(&self) -> Crypto {
+        self.crypto.clone()
+    }
+
+    // Cached Operations
+    fn cached_dh(&self, key: &PublicKey, secret: &SecretKey) -> VeilidAPIResult<SharedSecret> {
+        self.crypto
+            .cached_dh_internal::<CryptoSystemVLD1>(self, key, secret)
+    }
+
+    // Generation
+    fn random_bytes(&self, len: u32) -> Vec<u8> {
+        self.vld0.random_bytes(len)
+    }
+    fn default_salt_length(&self) -> u32 {
+        self.vld0.default_salt_length()
+    }
+    fn hash_password(&self, password: &[u8], salt: &[u8]) -> VeilidAPIResult<String> {
+        self.vld0.hash_password(password, salt)
+    }
+    fn verify_password(&self, password: &[u8], password_hash: &str) -> VeilidAPIResult<bool> {
+        self.vld0.verify_password(password, password_hash)
+    }
+    fn derive_shared_secret(&self, password: &[u8], salt: &[u8]) -> VeilidAPIResult<SharedSecret> {
+        self.vld0.derive_shared_secret(password, salt)
+    }
+    fn random_nonce(&self) -> Nonce {
+        self.vld0.random_nonce()
+    }
+    fn random_shared_secret(&self) -> SharedSecret {
+        self.vld0.random_shared_secret()
+    }
+    fn compute_dh(&self, key: &PublicKey, secret: &SecretKey) -> VeilidAPIResult<SharedSecret> {
+        // Domain-separate from VLD0 so the same keys never produce the same secret
+        let dh = self.vld0.compute_dh(key, secret)?;
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(VEILID_DOMAIN_CRYPT);
+        hasher.update(&dh.bytes);
+        let output = hasher.finalize();
+
+        Ok(SharedSecret::new(*output.as_bytes()))
+    }
+    fn generate_keypair(&self) -> KeyPair {
+        vld1_generate_keypair()
+    }
+    fn generate_hash(&self, data: &[u8]) -> PublicKey {
+        self.vld0.generate_hash(data)
+    }
+    fn generate_hash_reader(&self, reader: &mut dyn std::io::Read) -> VeilidAPIResult<PublicKey> {
+        self.vld0.generate_hash_reader(reader)
+    }
+
+    // Validation
+    fn validate_keypair(&self, dht_key: &PublicKey, dht_key_secret: &SecretKey) -> bool {
+        self.vld0.validate_keypair(dht_key, dht_key_secret)
+    }
+    fn validate_hash(&self, data: &[u8], dht_key: &PublicKey) -> bool {
+        self.vld0.validate_hash(data, dht_key)
+    }
+    fn validate_hash_reader(
+        &self,
+        reader: &mut dyn std::io::Read,
+        dht_key: &PublicKey,
+    ) -> VeilidAPIResult<bool> {
+        self.vld0.validate_hash_reader(reader, dht_key)
+    }
+
+    // Key Encapsulation
+    fn kem_ciphertext_length(&self) -> usize {
+        KEM_CIPHERTEXT_LENGTH
+    }
+    fn kem_public_key(&self, secret: &SecretKey) -> VeilidAPIResult<Option<KemPublicKey>> {
+        let (kem_public, _) = self.kem_keypair(secret)?;
+        Ok(Some(KemPublicKey::new(kem_public)))
+    }
+    fn encapsulate(
+        &self,
+        kem_public_key: &KemPublicKey,
+    ) -> VeilidAPIResult<(Vec<u8>, SharedSecret)> {
+        let mut csprng = VeilidRng {};
+        let (ciphertext, kem_secret) = pqc_kyber::encapsulate(&kem_public_key.bytes, &mut csprng)
+            .map_err(|e| VeilidAPIError::generic(format!("{:?}", e)))?;
+        Ok((ciphertext.to_vec(), SharedSecret::new(kem_secret)))
+    }
+    fn decapsulate(&self, ciphertext: &[u8], secret: &SecretKey) -> VeilidAPIResult<SharedSecret> {
+        if ciphertext.len() != KEM_CIPHERTEXT_LENGTH {
+            apibail_parse_error!("invalid kem ciphertext length", ciphertext.len());
+        }
+        let (_, kem_secret_key) = self.kem_keypair(secret)?;
+        let kem_secret = pqc_kyber::decapsulate(ciphertext, &kem_secret_key)
+            .map_err(|e| VeilidAPIError::generic(format!("{:?}", e)))?;
+        Ok(SharedSecret::new(kem_secret))
+    }
+    fn combine_shared_secrets(
+        &self,
+        dh_secret: &SharedSecret,
+        kem_secret: &SharedSecret,
+    ) -> SharedSecret {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(VEILID_DOMAIN_HYBRID);
+        hasher.update(&dh_secret.bytes);
+        hasher.update(&kem_secret.bytes);
+        SharedSecret::new(*hasher.finalize().as_bytes())
+    }
+
+    // Distance Metric
+    fn distance(&self, key1: &PublicKey, key2: &PublicKey) -> CryptoKeyDistance {
+        self.vld0.distance(key1, key2)
+    }
+
+    // Authentication
+    fn sign(
+        &self,
+        dht_key: &PublicKey,
+        dht_key_secret: &SecretKey,
+        data: &[u8],
+    ) -> VeilidAPIResult<Signature> {
+        self.vld0.sign(dht_key, dht_key_secret, data)
+    }
+    fn verify(
+        &self,
+        dht_key: &PublicKey,
+        data: &[u8],
+        signature: &Signature,
+    ) -> VeilidAPIResult<()> {
+        self.vld0.verify(dht_key, data, signature)
+    }
+
+    // AEAD Encrypt/Decrypt
+    fn aead_overhead(&self) -> usize {
+        self.vld0.aead_overhead()
+    }
+    fn decrypt_in_place_aead(
+        &self,
+        body: &mut Vec<u8>,
+        nonce: &Nonce,
+        shared_secret: &SharedSecret,
+        associated_data: Option<&[u8]>,
+    ) -> VeilidAPIResult<()> {
+        self.vld0
+            .decrypt_in_place_aead(body, nonce, shared_secret, associated_data)
+    }
+
+    fn decrypt_aead(
+        &self,
+        body: &[u8],
+        nonce: &Nonce,
+        shared_secret: &SharedSecret,
+        associated_data: Option<&[u8]>,
+    ) -> VeilidAPIResult<Vec<u8>> {
+        self.vld0
+            .decrypt_aead(body, nonce, shared_secret, associated_data)
+    }
+
+    fn encrypt_in_place_aead(
+        &self,
+        body: &mut Vec<u8>,
+        nonce: &Nonce,
+        shared_secret: &SharedSecret,
+        associated_data: Option<&[u8]>,
+    ) -> VeilidAPIResult<()> {
+        self.vld0
+            .encrypt_in_place_aead(body, nonce, shared_secret, associated_data)
+    }
+
+    fn encrypt_aead(
+        &self,
+        body: &[u8],
+        nonce: &Nonce,
+        shared_secret: &SharedSecret,
+        associated_data: Option<&[u8]>,
+    ) -> VeilidAPIResult<Vec<u8>> {
+        self.vld0
+            .encrypt_aead(body, nonce, shared_secret, associated_data)
+    }
+
+    // NoAuth Encrypt/Decrypt
+    fn crypt_in_place_no_auth(
+        &self,
+        body: &mut [u8],
+        nonce: &[u8; NONCE_LENGTH],
+        shared_secret: &SharedSecret,
+    ) {
+        self.vld0.crypt_in_place_no_auth(body, nonce, shared_secret)
+    }
+
+    fn crypt_b2b_no_auth(
+        &self,
+        in_buf: &[u8],
+        out_buf: &mut [u8],
+        nonce: &[u8; NONCE_LENGTH],
+        shared_secret: &SharedSecret,
+    ) {
+        self.vld0
+            .crypt_b2b_no_auth(in_buf, out_buf, nonce, shared_secret)
+    }
+
+    fn crypt_no_auth_aligned_8(
+        &self,
+        in_buf: &[u8],
+        nonce: &[u8; NONCE_LENGTH],
+        shared_secret: &SharedSecret,
+    ) -> Vec<u8> {
+        self.vld0
+            .crypt_no_auth_aligned_8(in_buf, nonce, shared_secret)
+    }
+
+    fn crypt_no_auth_unaligned(
+        &self,
+        in_buf: &[u8],
+        nonce: &[u8; NONCE_LENGTH],
+        shared_secret: &SharedSecret,
+    ) -> Vec<u8> {
+        self.vld0
+            .crypt_no_auth_unaligned(in_buf, nonce, shared_secret)
+    }
+}
@@ -0,0 +1,236 @@
+use super::*;
+use futures_util::{ready, AsyncRead, AsyncWrite};
+use std::io;
+use std::task::{Context, Poll};
+
+/// The most blocks one blob can be split into
+pub const MAX_BLOB_BLOCKS: usize = 1024;
+
+/// The list of blocks a blob was split into, stored as a block of its own
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobManifest {
+    /// The total length of the blob in bytes
+    pub length: u64,
+    /// The ids of the blocks holding the blob's content, in order
+    pub blocks: Vec<TypedKey>,
+}
+
+impl BlobManifest {
+    /// Check that the manifest describes a blob that could have been written
+    pub fn validate(&self) -> VeilidAPIResult<()> {
+        if self.blocks.len() > MAX_BLOB_BLOCKS {
+            apibail_parse_error!("too many blocks in blob manifest", self.blocks.len());
+        }
+        let max_length = (self.blocks.len() * MAX_BLOCK_SIZE) as u64;
+        let min_length = (self.blocks.len().saturating_sub(1) * MAX_BLOCK_SIZE) as u64;
+        if self.length > max_length || (self.length <= min_length && !self.blocks.is_empty()) {
+            apibail_parse_error!("blob manifest length does not match blocks", self.length);
+        }
+        Ok(())
+    }
+}
+
+fn to_io_error(e: VeilidAPIError) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, e)
+}
+
+/// Writes a blob into the block store
+///
+/// Every block but the last is filled completely, so flushing does not store a
+/// partial block. The blob is only complete once the writer has been closed.
+pub struct BlobWriter {
+    block_store: BlockStore,
+    buffer: Vec<u8>,
+    length: u64,
+    blocks: Vec<TypedKey>,
+    pending: Option<SendPinBoxFuture<VeilidAPIResult<TypedKey>>>,
+    closing: bool,
+    blob_id: Option<TypedKey>,
+}
+
+impl BlobWriter {
+    pub(super) fn new(block_store: BlockStore) -> Self {
+        Self {
+            block_store,
+            buffer: Vec::with_capacity(MAX_BLOCK_SIZE),
+            length: 0,
+            blocks: vec![],
+            pending: None,
+            closing: false,
+            blob_id: None,
+        }
+    }
+
+    /// The id of the blob, available once the writer has been closed
+    pub fn blob_id(&self) -> Option<TypedKey> {
+        self.blob_id
+    }
+
+    fn store(&mut self, data: Vec<u8>) {
+        let block_store = self.block_store.clone();
+        self.pending = Some(Box::pin(async move { block_store.put_block(&data).await }));
+    }
+
+    fn store_buffer(&mut self) -> io::Result<()> {
+        if self.blocks.len() >= MAX_BLOB_BLOCKS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "blob is too large",
+            ));
+        }
+        let data = std::mem::replace(&mut self.buffer, Vec::with_capacity(MAX_BLOCK_SIZE));
+        self.store(data);
+        Ok(())
+    }
+
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if let Some(pending) = &mut self.pending {
+            let res = ready!(pending.as_mut().poll(cx));
+            self.pending = None;
+            let block_id = res.map_err(to_io_error)?;
+            if self.closing {
+                self.blob_id = Some(block_id);
+            } else {
+                self.blocks.push(block_id);
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for BlobWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_pending(cx))?;
+        if this.closing {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "blob writer is closed",
+            )));
+        }
+
+        let count = buf.len().min(MAX_BLOCK_SIZE - this.buffer.len());
+        this.buffer.extend_from_slice(&buf[..count]);
+        this.length += count as u64;
+        if this.buffer.len() == MAX_BLOCK_SIZE {
+            this.store_buffer()?;
+        }
+
+        Poll::Ready(Ok(count))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_pending(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            ready!(this.poll_pending(cx))?;
+            if this.blob_id.is_some() {
+                return Poll::Ready(Ok(()));
+            }
+            if !this.buffer.is_empty() {
+                this.store_buffer()?;
+                continue;
+            }
+
+            // All the content is stored, so store the manifest to finish the blob
+            let manifest = BlobManifest {
+                length: this.length,
+                blocks: this.blocks.clone(),
+            };
+            this.closing = true;
+            this.store(serialize_json_bytes(manifest));
+        }
+    }
+}
+
+/// Reads a blob back out of the block store
+///
+/// Blocks are fetched one at a time as the content is read.
+pub struct BlobReader {
+    block_store: BlockStore,
+    manifest: BlobManifest,
+    next_block: usize,
+    current: Vec<u8>,
+    offset: usize,
+    position: u64,
+    pending: Option<SendPinBoxFuture<VeilidAPIResult<Option<Vec<u8>>>>>,
+}
+
+impl BlobReader {
+    pub(super) fn new(block_store: BlockStore, manifest: BlobManifest) -> Self {
+        Self {
+            block_store,
+            manifest,
+            next_block: 0,
+            current: vec![],
+            offset: 0,
+            position: 0,
+            pending: None,
+        }
+    }
+
+    /// The manifest of the blob being read
+    pub fn manifest(&self) -> &BlobManifest {
+        &self.manifest
+    }
+}
+
+impl AsyncRead for BlobReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(pending) = &mut this.pending {
+                let res = ready!(pending.as_mut().poll(cx));
+                this.pending = None;
+                let Some(data) = res.map_err(to_io_error)? else {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        "blob block is not stored locally",
+                    )));
+                };
+                this.current = data;
+                this.offset = 0;
+            }
+
+            if this.offset < this.current.len() || buf.is_empty() {
+                let count = buf.len().min(this.current.len() - this.offset);
+                buf[..count].copy_from_slice(&this.current[this.offset..this.offset + count]);
+                this.offset += count;
+                this.position += count as u64;
+                if this.position > this.manifest.length {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "blob is longer than its manifest",
+                    )));
+                }
+                return Poll::Ready(Ok(count));
+            }
+
+            let Some(block_id) = this.manifest.blocks.get(this.next_block).copied() else {
+                if this.position != this.manifest.length {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "blob is shorter than its manifest",
+                    )));
+                }
+                return Poll::Ready(Ok(0));
+            };
+            this.next_block += 1;
+            let block_store = this.block_store.clone();
+            this.pending = Some(Box::pin(
+                async move { block_store.get_block(block_id).await },
+            ));
+        }
+    }
+}
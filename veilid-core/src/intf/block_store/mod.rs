@@ -0,0 +1,122 @@
+use crate::*;
+
+mod blob;
+
+pub use blob::*;
+
+/// The largest block that can be stored, blobs are split into blocks of this size
+pub const MAX_BLOCK_SIZE: usize = 65536;
+
+const BLOCK_STORE_TABLE: &str = "__veilid_block_store";
+
+struct BlockStoreInner {
+    table: Option<TableDB>,
+}
+
+/// Local content-addressed storage for blocks and the blobs built from them
+///
+/// Each block is stored under the hash of its contents, so the block id of some
+/// data is always the same and a block can be validated when it is read back.
+#[derive(Clone)]
+pub struct BlockStore {
+    table_store: TableStore,
+    crypto: Crypto,
+    inner: Arc<Mutex<BlockStoreInner>>,
+}
+
+impl BlockStore {
+    fn new_inner() -> BlockStoreInner {
+        BlockStoreInner { table: None }
+    }
+    pub fn new(table_store: TableStore, crypto: Crypto) -> Self {
+        Self {
+            table_store,
+            crypto,
+            inner: Arc::new(Mutex::new(Self::new_inner())),
+        }
+    }
+
+    pub async fn init(&self) -> EyreResult<()> {
+        let table = self.table_store.open(BLOCK_STORE_TABLE, 1).await?;
+        self.inner.lock().table = Some(table);
+        Ok(())
+    }
+
+    pub async fn terminate(&self) {
+        self.inner.lock().table = None;
+    }
+
+    fn table(&self) -> VeilidAPIResult<TableDB> {
+        let Some(table) = self.inner.lock().table.clone() else {
+            apibail_not_initialized!();
+        };
+        Ok(table)
+    }
+
+    fn table_key(block_id: &TypedKey) -> Vec<u8> {
+        let mut key = block_id.kind.0.to_vec();
+        key.extend_from_slice(&block_id.value.bytes);
+        key
+    }
+
+    /// Store a block, returning the block id it can be retrieved with
+    pub async fn put_block(&self, data: &[u8]) -> VeilidAPIResult<TypedKey> {
+        if data.len() > MAX_BLOCK_SIZE {
+            apibail_invalid_argument!("block too large", "data.len", data.len());
+        }
+        let table = self.table()?;
+
+        let vcrypto = self.crypto.best();
+        let block_id = TypedKey::new(vcrypto.kind(), vcrypto.generate_hash(data));
+        table.store(0, &Self::table_key(&block_id), data).await?;
+
+        Ok(block_id)
+    }
+
+    /// Retrieve a block, returns None if the block is not stored locally
+    pub async fn get_block(&self, block_id: TypedKey) -> VeilidAPIResult<Option<Vec<u8>>> {
+        let table = self.table()?;
+        let Some(vcrypto) = self.crypto.get(block_id.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+
+        let Some(data) = table.load(0, &Self::table_key(&block_id)).await? else {
+            return Ok(None);
+        };
+        if !vcrypto.validate_hash(&data, &block_id.value) {
+            apibail_internal!(format!("block failed hash validation: {}", block_id));
+        }
+
+        Ok(Some(data))
+    }
+
+    /// Remove a block, returns true if it was stored locally
+    pub async fn delete_block(&self, block_id: TypedKey) -> VeilidAPIResult<bool> {
+        let table = self.table()?;
+        Ok(table
+            .delete(0, &Self::table_key(&block_id))
+            .await?
+            .is_some())
+    }
+
+    /// Start writing a new blob
+    ///
+    /// Content written is split into blocks, and closing the writer stores the
+    /// blob manifest, whose block id is the id of the blob.
+    pub fn open_blob_writer(&self) -> BlobWriter {
+        BlobWriter::new(self.clone())
+    }
+
+    /// Read back a blob by the block id of its manifest
+    ///
+    /// Returns None if the manifest is not stored locally.
+    pub async fn open_blob_reader(&self, blob_id: TypedKey) -> VeilidAPIResult<Option<BlobReader>> {
+        let Some(data) = self.get_block(blob_id).await? else {
+            return Ok(None);
+        };
+        let manifest: BlobManifest = deserialize_json_bytes(&data)?;
+        manifest.validate()?;
+
+        Ok(Some(BlobReader::new(self.clone(), manifest)))
+    }
+}
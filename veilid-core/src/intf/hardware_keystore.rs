@@ -0,0 +1,16 @@
+use crate::*;
+
+/// A platform hardware keystore such as a TPM, the Secure Enclave, or the Android Keystore
+///
+/// When `protected_store.hardware_node_id` is enabled, node ids are generated inside the keystore
+/// and only ever used through the [NodeIdSigner] handles it returns, so the node id secret keys
+/// are never exported. The host application passes its keystore to each node it starts with
+/// [api_startup_with_hardware_keystore].
+pub trait HardwareKeystore: Send + Sync {
+    /// Open the node id key with this name, generating it in the keystore if it does not exist yet
+    fn open_node_id_signer(
+        &self,
+        kind: CryptoKind,
+        name: &str,
+    ) -> VeilidAPIResult<Arc<dyn NodeIdSigner>>;
+}
@@ -9,4 +9,14 @@ mod native;
 #[cfg(not(target_arch = "wasm32"))]
 pub use native::*;
 
+#[cfg(feature = "unstable-blockstore")]
+mod block_store;
+mod hardware_keystore;
+mod protected_store_changes;
+
+#[cfg(feature = "unstable-blockstore")]
+pub use block_store::*;
+pub use hardware_keystore::*;
+pub use protected_store_changes::*;
+
 pub static KNOWN_PROTECTED_STORE_KEYS: [&str; 2] = ["device_encryption_key", "_test_key"];
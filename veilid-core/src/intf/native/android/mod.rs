@@ -17,8 +17,9 @@ impl Drop for AndroidGlobals {
     }
 }
 
+// The Java VM and application context belong to the whole process, so they are shared by every node in it
 lazy_static! {
-    pub static ref ANDROID_GLOBALS: Arc<Mutex<Option<AndroidGlobals>>> = Arc::new(Mutex::new(None));
+    static ref ANDROID_GLOBALS: Arc<Mutex<Option<AndroidGlobals>>> = Arc::new(Mutex::new(None));
 }
 
 pub fn veilid_core_setup_android(env: JNIEnv, ctx: JObject) {
@@ -11,6 +11,7 @@ pub struct ProtectedStoreInner {
 pub struct ProtectedStore {
     config: VeilidConfig,
     inner: Arc<Mutex<ProtectedStoreInner>>,
+    subscriptions: Arc<Mutex<ProtectedStoreSubscriptions>>,
 }
 
 impl ProtectedStore {
@@ -24,6 +25,34 @@ impl ProtectedStore {
         Self {
             config,
             inner: Arc::new(Mutex::new(Self::new_inner())),
+            subscriptions: Arc::new(Mutex::new(ProtectedStoreSubscriptions::default())),
+        }
+    }
+
+    /// Subscribe to changes made to the protected store
+    ///
+    /// The callback is called after each key is saved or removed, with the key but not the secret.
+    /// Returns an id to unsubscribe with.
+    pub fn subscribe(
+        &self,
+        callback: ProtectedStoreChangeCallback,
+    ) -> ProtectedStoreSubscriptionId {
+        self.subscriptions.lock().subscribe(callback)
+    }
+
+    /// Stop receiving changes made to the protected store
+    /// Returns false if there was no such subscription
+    pub fn unsubscribe(&self, id: ProtectedStoreSubscriptionId) -> bool {
+        self.subscriptions.lock().unsubscribe(id)
+    }
+
+    fn notify_change(&self, key: &str, kind: ProtectedStoreChangeKind) {
+        let callbacks = self.subscriptions.lock().callbacks();
+        for callback in callbacks {
+            callback(ProtectedStoreChange {
+                key: key.to_owned(),
+                kind,
+            });
         }
     }
 
@@ -119,17 +148,21 @@ impl ProtectedStore {
         key: K,
         value: V,
     ) -> EyreResult<bool> {
-        let inner = self.inner.lock();
-        inner
-            .keyring_manager
-            .as_ref()
-            .ok_or_else(|| eyre!("Protected store not initialized"))?
-            .with_keyring(&self.service_name(), key.as_ref(), |kr| {
-                let existed = kr.get_value().is_ok();
-                kr.set_value(value.as_ref())?;
-                Ok(existed)
-            })
-            .wrap_err("failed to save user secret")
+        let existed = {
+            let inner = self.inner.lock();
+            inner
+                .keyring_manager
+                .as_ref()
+                .ok_or_else(|| eyre!("Protected store not initialized"))?
+                .with_keyring(&self.service_name(), key.as_ref(), |kr| {
+                    let existed = kr.get_value().is_ok();
+                    kr.set_value(value.as_ref())?;
+                    Ok(existed)
+                })
+                .wrap_err("failed to save user secret")?
+        };
+        self.notify_change(key.as_ref(), ProtectedStoreChangeKind::Saved);
+        Ok(existed)
     }
 
     #[instrument(level = "trace", skip(self), err)]
@@ -226,14 +259,19 @@ impl ProtectedStore {
 
     #[instrument(level = "trace", skip(self), ret, err)]
     pub async fn remove_user_secret<K: AsRef<str> + fmt::Debug>(&self, key: K) -> EyreResult<bool> {
-        let inner = self.inner.lock();
-        match inner
-            .keyring_manager
-            .as_ref()
-            .ok_or_else(|| eyre!("Protected store not initialized"))?
-            .with_keyring(&self.service_name(), key.as_ref(), |kr| kr.delete_value())
-        {
-            Ok(_) => Ok(true),
+        let res = {
+            let inner = self.inner.lock();
+            inner
+                .keyring_manager
+                .as_ref()
+                .ok_or_else(|| eyre!("Protected store not initialized"))?
+                .with_keyring(&self.service_name(), key.as_ref(), |kr| kr.delete_value())
+        };
+        match res {
+            Ok(_) => {
+                self.notify_change(key.as_ref(), ProtectedStoreChangeKind::Removed);
+                Ok(true)
+            }
             Err(KeyringError::NoPasswordFound) => Ok(false),
             Err(e) => Err(eyre!("Failed to remove user secret: {}", e)),
         }
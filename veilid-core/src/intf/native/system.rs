@@ -198,3 +198,34 @@ pub fn env_variable_is_defined<S: AsRef<str>>(s: S) -> bool {
         Err(_) => false,
     }
 }
+
+lazy_static::lazy_static! {
+    static ref HOST_ID: Option<u64> = load_host_id();
+}
+
+#[cfg(unix)]
+fn load_host_id() -> Option<u64> {
+    for path in ["/etc/machine-id", "/var/lib/dbus/machine-id", "/etc/hostid"] {
+        if let Ok(id) = std::fs::read_to_string(path) {
+            let id = id.trim();
+            if !id.is_empty() {
+                let hash = blake3::hash(id.as_bytes());
+                return Some(u64::from_le_bytes(
+                    hash.as_bytes()[0..8].try_into().unwrap(),
+                ));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(not(unix))]
+fn load_host_id() -> Option<u64> {
+    None
+}
+
+/// A stable identifier for the machine we are running on, shared by every process on it
+/// Used to tell whether IPC dial info belongs to a process on this host
+pub fn host_id() -> Option<u64> {
+    *HOST_ID
+}
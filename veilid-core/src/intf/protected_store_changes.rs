@@ -0,0 +1,53 @@
+use super::*;
+
+/// What happened to a protected store key
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ProtectedStoreChangeKind {
+    /// The key was saved, either for the first time or replacing a previous value
+    Saved,
+    /// The key was removed
+    Removed,
+}
+
+/// A change to one of the keys in the protected store
+/// The secret itself is not included, it can be loaded from the protected store if needed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtectedStoreChange {
+    pub key: String,
+    pub kind: ProtectedStoreChangeKind,
+}
+
+/// Called for each change made to the protected store
+pub type ProtectedStoreChangeCallback = Arc<dyn Fn(ProtectedStoreChange) + Send + Sync>;
+
+/// Identifies a subscription to protected store changes, to unsubscribe with
+pub type ProtectedStoreSubscriptionId = u64;
+
+/// The callbacks subscribed to protected store changes
+#[derive(Default)]
+pub(super) struct ProtectedStoreSubscriptions {
+    next_id: ProtectedStoreSubscriptionId,
+    callbacks: BTreeMap<ProtectedStoreSubscriptionId, ProtectedStoreChangeCallback>,
+}
+
+impl ProtectedStoreSubscriptions {
+    pub fn subscribe(
+        &mut self,
+        callback: ProtectedStoreChangeCallback,
+    ) -> ProtectedStoreSubscriptionId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.callbacks.insert(id, callback);
+        id
+    }
+
+    pub fn unsubscribe(&mut self, id: ProtectedStoreSubscriptionId) -> bool {
+        self.callbacks.remove(&id).is_some()
+    }
+
+    /// Get the callbacks to notify of a change
+    /// They are called without holding the subscription lock, so they can subscribe or unsubscribe
+    pub fn callbacks(&self) -> Vec<ProtectedStoreChangeCallback> {
+        self.callbacks.values().cloned().collect()
+    }
+}
@@ -1,12 +1,6 @@
-#[cfg(feature = "unstable-blockstore")]
-mod block_store;
-
 mod protected_store;
 mod system;
 
-#[cfg(feature = "unstable-blockstore")]
-pub use block_store::*;
-
 pub use protected_store::*;
 pub use system::*;
 
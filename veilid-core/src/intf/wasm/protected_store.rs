@@ -6,11 +6,42 @@ use web_sys::*;
 #[derive(Clone)]
 pub struct ProtectedStore {
     config: VeilidConfig,
+    subscriptions: Arc<Mutex<ProtectedStoreSubscriptions>>,
 }
 
 impl ProtectedStore {
     pub fn new(config: VeilidConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            subscriptions: Arc::new(Mutex::new(ProtectedStoreSubscriptions::default())),
+        }
+    }
+
+    /// Subscribe to changes made to the protected store
+    ///
+    /// The callback is called after each key is saved or removed, with the key but not the secret.
+    /// Returns an id to unsubscribe with.
+    pub fn subscribe(
+        &self,
+        callback: ProtectedStoreChangeCallback,
+    ) -> ProtectedStoreSubscriptionId {
+        self.subscriptions.lock().subscribe(callback)
+    }
+
+    /// Stop receiving changes made to the protected store
+    /// Returns false if there was no such subscription
+    pub fn unsubscribe(&self, id: ProtectedStoreSubscriptionId) -> bool {
+        self.subscriptions.lock().unsubscribe(id)
+    }
+
+    fn notify_change(&self, key: &str, kind: ProtectedStoreChangeKind) {
+        let callbacks = self.subscriptions.lock().callbacks();
+        for callback in callbacks {
+            callback(ProtectedStoreChange {
+                key: key.to_owned(),
+                kind,
+            });
+        }
     }
 
     #[instrument(level = "trace", skip(self), err)]
@@ -79,6 +110,7 @@ impl ProtectedStore {
                 .map_err(map_jsvalue_error)
                 .wrap_err("exception_thrown")?;
 
+            self.notify_change(key.as_ref(), ProtectedStoreChangeKind::Saved);
             Ok(prev)
         } else {
             unimplemented!()
@@ -225,6 +257,7 @@ impl ProtectedStore {
                     ls.delete(&vkey)
                         .map_err(map_jsvalue_error)
                         .wrap_err("exception_thrown")?;
+                    self.notify_change(key.as_ref(), ProtectedStoreChangeKind::Removed);
                     Ok(true)
                 }
                 None => Ok(false),
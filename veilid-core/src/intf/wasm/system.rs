@@ -43,3 +43,7 @@ pub async fn ptr_lookup(_ip_addr: IpAddr) -> EyreResult<String> {
 pub fn env_variable_is_defined<S: AsRef<str>>(_s: S) -> bool {
     false
 }
+
+pub fn host_id() -> Option<u64> {
+    None
+}
@@ -56,15 +56,20 @@ mod storage_manager;
 mod table_store;
 mod veilid_api;
 mod veilid_config;
+mod veilid_config_schema;
 mod wasm_helpers;
 
-pub use self::core_context::{api_startup, api_startup_config, api_startup_json, UpdateCallback};
+pub use self::core_context::{
+    api_startup, api_startup_config, api_startup_json, api_startup_with_hardware_keystore,
+    UpdateCallback, VeilidInstanceKey,
+};
 pub use self::logging::{
     ApiTracingLayer, VeilidLayerFilter, DEFAULT_LOG_FACILITIES_ENABLED_LIST,
-    DEFAULT_LOG_FACILITIES_IGNORE_LIST, DURATION_LOG_FACILITIES,
+    DEFAULT_LOG_FACILITIES_IGNORE_LIST, DURATION_LOG_FACILITIES, LOG_COMPONENTS,
 };
 pub use self::veilid_api::*;
 pub use self::veilid_config::*;
+pub use self::veilid_config_schema::*;
 pub use veilid_tools as tools;
 
 /// The on-the-wire serialization format for Veilid RPC
@@ -75,6 +80,11 @@ pub mod veilid_capnp {
 #[doc(hidden)]
 pub mod tests;
 
+/// Entry points for fuzzing the RPC decoders
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub use self::rpc_processor::fuzz;
+
 /// Return the cargo package version of veilid-core in string format
 pub fn veilid_version_string() -> String {
     env!("CARGO_PKG_VERSION").to_owned()
@@ -2,51 +2,77 @@ use crate::core_context::*;
 use crate::veilid_api::*;
 use crate::*;
 use core::fmt::Write;
-use once_cell::sync::OnceCell;
 use tracing_subscriber::*;
 
+/// The span field that holds the instance key of the node a span belongs to
+pub(crate) const VEILID_INSTANCE_FIELD: &str = "veilid_instance";
+
+#[derive(Default)]
 struct ApiTracingLayerInner {
-    update_callback: UpdateCallback,
+    /// The update callbacks of the nodes logging to this layer, by node instance key
+    update_callbacks: BTreeMap<String, UpdateCallback>,
 }
 
-#[derive(Clone)]
+/// The tracing layer that turns log events into VeilidUpdate::Log updates
+///
+/// Install it in the tracing subscriber before starting any nodes, and each node sends its logs
+/// to it as it starts up. Every node does its work in a span holding its instance key, and events
+/// in that span only go to that node's update callback. Events that happen outside of any node,
+/// such as those from other libraries, go to every node.
+#[derive(Clone, Default)]
 pub struct ApiTracingLayer {
-    inner: Arc<Mutex<Option<ApiTracingLayerInner>>>,
+    inner: Arc<Mutex<ApiTracingLayerInner>>,
 }
 
-static API_LOGGER: OnceCell<ApiTracingLayer> = OnceCell::new();
-
 impl ApiTracingLayer {
-    fn new_inner(update_callback: UpdateCallback) -> ApiTracingLayerInner {
-        ApiTracingLayerInner { update_callback }
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Find the layer installed in the current tracing subscriber, if there is one
+    fn installed() -> Option<ApiTracingLayer> {
+        tracing::dispatcher::get_default(|dispatch| dispatch.downcast_ref::<Self>().cloned())
     }
 
     #[instrument(level = "debug", skip(update_callback))]
-    pub async fn init(update_callback: UpdateCallback) {
-        let api_logger = API_LOGGER.get_or_init(|| ApiTracingLayer {
-            inner: Arc::new(Mutex::new(None)),
-        });
-        let apilogger_inner = Some(Self::new_inner(update_callback));
-        *api_logger.inner.lock() = apilogger_inner;
+    pub async fn init(instance_key: VeilidInstanceKey, update_callback: UpdateCallback) {
+        if let Some(api_logger) = Self::installed() {
+            api_logger
+                .inner
+                .lock()
+                .update_callbacks
+                .insert(instance_key.to_string(), update_callback);
+        }
     }
 
     #[instrument(level = "debug")]
-    pub async fn terminate() {
-        if let Some(api_logger) = API_LOGGER.get() {
+    pub async fn terminate(instance_key: VeilidInstanceKey) {
+        if let Some(api_logger) = Self::installed() {
             let mut inner = api_logger.inner.lock();
-            *inner = None;
+            inner.update_callbacks.remove(&instance_key.to_string());
         }
     }
 
-    pub fn get() -> ApiTracingLayer {
-        API_LOGGER
-            .get_or_init(|| ApiTracingLayer {
-                inner: Arc::new(Mutex::new(None)),
-            })
-            .clone()
+    /// Find the node a span belongs to from the closest of its ancestors that names one
+    fn instance_of<'a, S>(scope: Option<registry::Scope<'a, S>>) -> Option<String>
+    where
+        S: registry::LookupSpan<'a>,
+    {
+        scope?.find_map(|span_ref| {
+            span_ref
+                .extensions()
+                .get::<InstanceTag>()
+                .map(|tag| tag.0.clone())
+        })
     }
 
-    fn emit_log(&self, inner: &mut ApiTracingLayerInner, meta: &Metadata<'_>, message: String) {
+    fn emit_log(
+        &self,
+        inner: &ApiTracingLayerInner,
+        instance: Option<String>,
+        meta: &Metadata<'_>,
+        message: String,
+    ) {
         let level = *meta.level();
         let target = meta.target();
         let log_level = VeilidLogLevel::from_tracing_level(level);
@@ -88,14 +114,29 @@ impl ApiTracingLayer {
             None
         };
 
-        (inner.update_callback)(VeilidUpdate::Log(Box::new(VeilidLog {
+        let log = VeilidLog {
             log_level,
             message,
             backtrace,
-        })))
+        };
+        match instance {
+            Some(instance) => {
+                if let Some(update_callback) = inner.update_callbacks.get(&instance) {
+                    (update_callback)(VeilidUpdate::Log(Box::new(log)))
+                }
+            }
+            None => {
+                for update_callback in inner.update_callbacks.values() {
+                    (update_callback)(VeilidUpdate::Log(Box::new(log.clone())))
+                }
+            }
+        }
     }
 }
 
+/// The instance key of the node a span belongs to
+struct InstanceTag(String);
+
 pub struct SpanDuration {
     start: Timestamp,
     end: Timestamp,
@@ -121,7 +162,16 @@ impl<S: Subscriber + for<'a> registry::LookupSpan<'a>> Layer<S> for ApiTracingLa
         id: &tracing::Id,
         ctx: layer::Context<'_, S>,
     ) {
-        if let Some(_inner) = &mut *self.inner.lock() {
+        // Spans that start a node's work name the node, whether or not anything is logged yet
+        let mut instance_recorder = InstanceRecorder::default();
+        attrs.record(&mut instance_recorder);
+        if let Some(instance) = instance_recorder.instance {
+            if let Some(span_ref) = ctx.span(id) {
+                span_ref.extensions_mut().insert(InstanceTag(instance));
+            }
+        }
+
+        if !self.inner.lock().update_callbacks.is_empty() {
             let mut new_debug_record = StringRecorder::new();
             attrs.record(&mut new_debug_record);
 
@@ -142,14 +192,17 @@ impl<S: Subscriber + for<'a> registry::LookupSpan<'a>> Layer<S> for ApiTracingLa
     }
 
     fn on_close(&self, id: span::Id, ctx: layer::Context<'_, S>) {
-        if let Some(inner) = &mut *self.inner.lock() {
+        let inner = self.inner.lock();
+        if !inner.update_callbacks.is_empty() {
             if let Some(span_ref) = ctx.span(&id) {
+                let instance = Self::instance_of(Some(span_ref.scope()));
                 if let Some(span_duration) = span_ref.extensions_mut().get_mut::<SpanDuration>() {
                     span_duration.end = get_aligned_timestamp();
                     let duration = span_duration.end.saturating_sub(span_duration.start);
                     let meta = span_ref.metadata();
                     self.emit_log(
-                        inner,
+                        &inner,
+                        instance,
                         meta,
                         format!(
                             " {}{}: duration={}",
@@ -172,7 +225,7 @@ impl<S: Subscriber + for<'a> registry::LookupSpan<'a>> Layer<S> for ApiTracingLa
         values: &tracing::span::Record<'_>,
         ctx: layer::Context<'_, S>,
     ) {
-        if let Some(_inner) = &mut *self.inner.lock() {
+        if !self.inner.lock().update_callbacks.is_empty() {
             if let Some(span_ref) = ctx.span(id) {
                 if let Some(debug_record) = span_ref.extensions_mut().get_mut::<StringRecorder>() {
                     values.record(debug_record);
@@ -181,12 +234,34 @@ impl<S: Subscriber + for<'a> registry::LookupSpan<'a>> Layer<S> for ApiTracingLa
         }
     }
 
-    fn on_event(&self, event: &tracing::Event<'_>, _ctx: layer::Context<'_, S>) {
-        if let Some(inner) = &mut *self.inner.lock() {
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: layer::Context<'_, S>) {
+        let inner = self.inner.lock();
+        if !inner.update_callbacks.is_empty() {
             let mut recorder = StringRecorder::new();
             event.record(&mut recorder);
             let meta = event.metadata();
-            self.emit_log(inner, meta, recorder.to_string());
+            let instance = Self::instance_of(ctx.event_scope(event));
+            self.emit_log(&inner, instance, meta, recorder.to_string());
+        }
+    }
+}
+
+/// Picks the node instance key out of a span's fields
+#[derive(Default)]
+struct InstanceRecorder {
+    instance: Option<String>,
+}
+
+impl tracing::field::Visit for InstanceRecorder {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        if field.name() == VEILID_INSTANCE_FIELD {
+            self.instance = Some(value.to_owned());
+        }
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn core::fmt::Debug) {
+        if field.name() == VEILID_INSTANCE_FIELD {
+            self.instance = Some(format!("{:?}", value));
         }
     }
 }
@@ -28,6 +28,9 @@ pub static DEFAULT_LOG_FACILITIES_IGNORE_LIST: [&str; 27] = [
     "fanout",
 ];
 
+/// Log targets whose level can be set separately from the rest of a tracing layer
+pub static LOG_COMPONENTS: [&str; 4] = ["rtab", "net", "rpc", "stor"];
+
 pub static DEFAULT_LOG_FACILITIES_ENABLED_LIST: [&str; 8] = [
     "net",
     "rpc",
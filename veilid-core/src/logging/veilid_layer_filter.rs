@@ -6,6 +6,7 @@ use tracing_subscriber::layer;
 struct VeilidLayerFilterInner {
     max_level: LevelFilter,
     ignore_list: Vec<String>,
+    component_levels: BTreeMap<String, LevelFilter>,
 }
 
 #[derive(Clone)]
@@ -26,6 +27,7 @@ impl VeilidLayerFilter {
             inner: Arc::new(RwLock::new(VeilidLayerFilterInner {
                 max_level: max_level.to_tracing_level_filter(),
                 ignore_list,
+                component_levels: BTreeMap::new(),
             })),
         }
     }
@@ -40,6 +42,21 @@ impl VeilidLayerFilter {
         inner.ignore_list.clone()
     }
 
+    /// The level overrides for each log component that has one
+    pub fn component_levels(&self) -> BTreeMap<String, VeilidConfigLogLevel> {
+        let inner = self.inner.read();
+        inner
+            .component_levels
+            .iter()
+            .map(|(k, v)| {
+                (
+                    k.clone(),
+                    VeilidConfigLogLevel::from_tracing_level_filter(*v),
+                )
+            })
+            .collect()
+    }
+
     pub fn set_max_level(&self, level: VeilidConfigLogLevel) {
         {
             let mut inner = self.inner.write();
@@ -60,10 +77,77 @@ impl VeilidLayerFilter {
         callsite::rebuild_interest_cache();
     }
 
+    /// Override the log level for one of the LOG_COMPONENTS, or pass None to follow the layer's max level again
+    pub fn set_component_level(
+        &self,
+        component: &str,
+        level: Option<VeilidConfigLogLevel>,
+    ) -> VeilidAPIResult<()> {
+        if !LOG_COMPONENTS.contains(&component) {
+            apibail_invalid_argument!("unknown log component", "component", component);
+        }
+        {
+            let mut inner = self.inner.write();
+            match level {
+                Some(level) => {
+                    inner
+                        .component_levels
+                        .insert(component.to_owned(), level.to_tracing_level_filter());
+                }
+                None => {
+                    inner.component_levels.remove(component);
+                }
+            }
+        }
+        callsite::rebuild_interest_cache();
+        Ok(())
+    }
+
+    /// Replace all component level overrides at once, as when applying a layer's configuration
+    pub fn set_component_levels(
+        &self,
+        component_levels: &BTreeMap<String, VeilidConfigLogLevel>,
+    ) -> VeilidAPIResult<()> {
+        for component in component_levels.keys() {
+            if !LOG_COMPONENTS.contains(&component.as_str()) {
+                apibail_invalid_argument!("unknown log component", "component", component);
+            }
+        }
+        {
+            let mut inner = self.inner.write();
+            inner.component_levels = component_levels
+                .iter()
+                .map(|(k, v)| (k.clone(), v.to_tracing_level_filter()))
+                .collect();
+        }
+        callsite::rebuild_interest_cache();
+        Ok(())
+    }
+
     fn interesting(&self, metadata: &tracing::Metadata<'_>) -> bool {
+        // The spans that tell nodes apart are always needed to route their logs
+        if metadata.is_span() && metadata.fields().field(VEILID_INSTANCE_FIELD).is_some() {
+            return true;
+        }
+
         let inner = self.inner.read();
 
-        if *metadata.level() > inner.max_level {
+        // Events from a component with an override are held to that level instead of the layer's
+        let target = metadata.target();
+        let level = inner
+            .component_levels
+            .iter()
+            .find(|(c, _)| {
+                target == c.as_str()
+                    || target
+                        .strip_prefix(c.as_str())
+                        .map(|rest| rest.starts_with("::"))
+                        .unwrap_or_default()
+            })
+            .map(|(_, l)| *l)
+            .unwrap_or(inner.max_level);
+
+        if *metadata.level() > level {
             return false;
         }
         let skip = inner
@@ -133,6 +217,63 @@ impl<S: tracing::Subscriber> layer::Filter<S> for VeilidLayerFilter {
 
     fn max_level_hint(&self) -> Option<LevelFilter> {
         let inner = self.inner.read();
-        Some(inner.max_level)
+        // A component may be allowed more verbose events than the rest of the layer
+        Some(
+            inner
+                .component_levels
+                .values()
+                .copied()
+                .fold(inner.max_level, core::cmp::max),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn component_levels_override_layer_level() {
+        let filter = VeilidLayerFilter::new(VeilidConfigLogLevel::Info, &[]);
+        let max_level_hint = |f: &VeilidLayerFilter| {
+            layer::Filter::<tracing_subscriber::Registry>::max_level_hint(f)
+        };
+        assert_eq!(max_level_hint(&filter), Some(LevelFilter::INFO));
+
+        // Only known components can be overridden
+        assert!(filter
+            .set_component_level("dht", Some(VeilidConfigLogLevel::Trace))
+            .is_err());
+        assert!(filter.component_levels().is_empty());
+
+        // A more verbose component raises the hint, a quieter one does not
+        filter
+            .set_component_level("net", Some(VeilidConfigLogLevel::Trace))
+            .unwrap();
+        filter
+            .set_component_level("rtab", Some(VeilidConfigLogLevel::Warn))
+            .unwrap();
+        assert_eq!(max_level_hint(&filter), Some(LevelFilter::TRACE));
+        assert_eq!(
+            filter.component_levels().get("net"),
+            Some(&VeilidConfigLogLevel::Trace)
+        );
+
+        // Removing the override follows the layer level again
+        filter.set_component_level("net", None).unwrap();
+        assert_eq!(max_level_hint(&filter), Some(LevelFilter::INFO));
+
+        // Replacing all overrides at once is validated the same way
+        let mut levels = BTreeMap::new();
+        levels.insert("bogus".to_owned(), VeilidConfigLogLevel::Debug);
+        assert!(filter.set_component_levels(&levels).is_err());
+        assert_eq!(filter.component_levels().len(), 1);
+        levels.clear();
+        levels.insert("rpc".to_owned(), VeilidConfigLogLevel::Debug);
+        filter.set_component_levels(&levels).unwrap();
+        assert_eq!(
+            filter.component_levels(),
+            BTreeMap::from([("rpc".to_owned(), VeilidConfigLogLevel::Debug)])
+        );
     }
 }
@@ -6,6 +6,12 @@ const PUNISHMENT_DURATION_MIN: usize = 60;
 const MAX_PUNISHMENTS_BY_NODE_ID: usize = 65536;
 const DIAL_INFO_FAILURE_DURATION_MIN: usize = 10;
 const MAX_DIAL_INFO_FAILURES: usize = 65536;
+const MAX_SPOOFED_SOURCES_PER_MIN: usize = 16;
+
+/// Table store table holding persisted punishments
+const ADDRESS_FILTER_TABLE: &str = "address_filter";
+/// Key for the serialized punishment list in the address filter table
+const PUNISHMENTS_KEY: &[u8] = b"punishments";
 
 #[derive(ThisError, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AddressFilterError {
@@ -21,6 +27,17 @@ pub enum AddressFilterError {
 #[error("Address not in table")]
 pub struct AddressNotInTableError {}
 
+/// Exportable snapshot of the address filter punishment list
+///
+/// Each entry carries the timestamp at which the punishment was applied, and expires
+/// once the punishment duration has passed since that timestamp.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressFilterPunishments {
+    pub ip4: Vec<(Ipv4Addr, Timestamp)>,
+    pub ip6_prefix: Vec<(Ipv6Addr, Timestamp)>,
+    pub node_ids: Vec<(TypedKey, Timestamp)>,
+}
+
 #[derive(Debug)]
 struct AddressFilterInner {
     conn_count_by_ip4: BTreeMap<Ipv4Addr, usize>,
@@ -31,6 +48,10 @@ struct AddressFilterInner {
     punishments_by_ip6_prefix: BTreeMap<Ipv6Addr, Timestamp>,
     punishments_by_node_id: BTreeMap<TypedKey, Timestamp>,
     dial_info_failures: BTreeMap<DialInfo, Timestamp>,
+    spoofed_source_timestamps_by_ip4: BTreeMap<Ipv4Addr, Vec<Timestamp>>,
+    spoofed_source_timestamps_by_ip6_prefix: BTreeMap<Ipv6Addr, Vec<Timestamp>>,
+    spoofed_source_count: u64,
+    punishments_dirty: bool,
 }
 
 struct AddressFilterUnlockedInner {
@@ -40,6 +61,7 @@ struct AddressFilterUnlockedInner {
     max_connection_frequency_per_min: usize,
     punishment_duration_min: usize,
     dial_info_failure_duration_min: usize,
+    max_spoofed_sources_per_min: usize,
     routing_table: RoutingTable,
 }
 
@@ -64,6 +86,10 @@ impl fmt::Debug for AddressFilterUnlockedInner {
                 "dial_info_failure_duration_min",
                 &self.dial_info_failure_duration_min,
             )
+            .field(
+                "max_spoofed_sources_per_min",
+                &self.max_spoofed_sources_per_min,
+            )
             .finish()
     }
 }
@@ -87,6 +113,7 @@ impl AddressFilter {
                     as usize,
                 punishment_duration_min: PUNISHMENT_DURATION_MIN,
                 dial_info_failure_duration_min: DIAL_INFO_FAILURE_DURATION_MIN,
+                max_spoofed_sources_per_min: MAX_SPOOFED_SOURCES_PER_MIN,
                 routing_table,
             }),
             inner: Arc::new(Mutex::new(AddressFilterInner {
@@ -98,6 +125,10 @@ impl AddressFilter {
                 punishments_by_ip6_prefix: BTreeMap::new(),
                 punishments_by_node_id: BTreeMap::new(),
                 dial_info_failures: BTreeMap::new(),
+                spoofed_source_timestamps_by_ip4: BTreeMap::new(),
+                spoofed_source_timestamps_by_ip6_prefix: BTreeMap::new(),
+                spoofed_source_count: 0,
+                punishments_dirty: false,
             })),
         }
     }
@@ -141,6 +172,17 @@ impl AddressFilter {
                 inner.conn_timestamps_by_ip6_prefix.remove(&key);
             }
         }
+        // spoofed sources
+        inner.spoofed_source_timestamps_by_ip4.retain(|_, value| {
+            value.retain(|v| cur_ts.saturating_sub(*v) < TimestampDuration::new(60_000_000u64));
+            !value.is_empty()
+        });
+        inner
+            .spoofed_source_timestamps_by_ip6_prefix
+            .retain(|_, value| {
+                value.retain(|v| cur_ts.saturating_sub(*v) < TimestampDuration::new(60_000_000u64));
+                !value.is_empty()
+            });
     }
 
     fn purge_old_punishments(&self, inner: &mut AddressFilterInner, cur_ts: Timestamp) {
@@ -158,6 +200,7 @@ impl AddressFilter {
             for key in dead_keys {
                 log_net!(debug ">>> FORGIVING: {}", key);
                 inner.punishments_by_ip4.remove(&key);
+                inner.punishments_dirty = true;
             }
         }
         // v6
@@ -174,6 +217,7 @@ impl AddressFilter {
             for key in dead_keys {
                 log_net!(debug ">>> FORGIVING: {}", key);
                 inner.punishments_by_ip6_prefix.remove(&key);
+                inner.punishments_dirty = true;
             }
         }
         // node id
@@ -190,6 +234,7 @@ impl AddressFilter {
             for key in dead_keys {
                 log_net!(debug ">>> FORGIVING: {}", key);
                 inner.punishments_by_node_id.remove(&key);
+                inner.punishments_dirty = true;
                 // make the entry alive again if it's still here
                 if let Ok(Some(nr)) = self.unlocked_inner.routing_table.lookup_node_ref(key) {
                     nr.operate_mut(|_rti, e| e.set_punished(false));
@@ -272,7 +317,148 @@ impl AddressFilter {
         let mut inner = self.inner.lock();
         inner.punishments_by_ip4.clear();
         inner.punishments_by_ip6_prefix.clear();
-        inner.punishments_by_node_id.clear();
+        let node_ids = core::mem::take(&mut inner.punishments_by_node_id);
+        inner.punishments_dirty = true;
+        drop(inner);
+
+        // make the entries alive again if they are still here
+        for node_id in node_ids.into_keys() {
+            if let Ok(Some(nr)) = self.unlocked_inner.routing_table.lookup_node_ref(node_id) {
+                nr.operate_mut(|_rti, e| e.set_punished(false));
+            }
+        }
+    }
+
+    /// Get a snapshot of all current punishments
+    pub fn export_punishments(&self) -> AddressFilterPunishments {
+        let inner = self.inner.lock();
+        AddressFilterPunishments {
+            ip4: inner
+                .punishments_by_ip4
+                .iter()
+                .map(|(k, v)| (*k, *v))
+                .collect(),
+            ip6_prefix: inner
+                .punishments_by_ip6_prefix
+                .iter()
+                .map(|(k, v)| (*k, *v))
+                .collect(),
+            node_ids: inner
+                .punishments_by_node_id
+                .iter()
+                .map(|(k, v)| (*k, *v))
+                .collect(),
+        }
+    }
+
+    /// Merge a punishment list into the current punishments
+    ///
+    /// Expired punishments are dropped, and the most recent timestamp wins when a punishment
+    /// is already present. Returns the number of punishments that were applied.
+    pub fn import_punishments(&self, punishments: AddressFilterPunishments) -> usize {
+        let cur_ts = get_aligned_timestamp();
+        let max_age = self.unlocked_inner.punishment_duration_min as u64 * 60_000_000u64;
+        let is_live = |ts: Timestamp| cur_ts.as_u64().saturating_sub(ts.as_u64()) <= max_age;
+
+        let mut applied = 0usize;
+        let mut punished_node_ids = Vec::new();
+        {
+            let mut inner = self.inner.lock();
+            for (v4, ts) in punishments.ip4 {
+                if !is_live(ts) {
+                    continue;
+                }
+                let e = inner.punishments_by_ip4.entry(v4).or_insert(ts);
+                *e = (*e).max(ts);
+                applied += 1;
+            }
+            for (v6, ts) in punishments.ip6_prefix {
+                if !is_live(ts) {
+                    continue;
+                }
+                // Normalize to the configured prefix size in case it changed
+                let IpAddr::V6(v6) = ip_to_ipblock(
+                    self.unlocked_inner.max_connections_per_ip6_prefix_size,
+                    IpAddr::V6(v6),
+                ) else {
+                    continue;
+                };
+                let e = inner.punishments_by_ip6_prefix.entry(v6).or_insert(ts);
+                *e = (*e).max(ts);
+                applied += 1;
+            }
+            for (node_id, ts) in punishments.node_ids {
+                if !is_live(ts) {
+                    continue;
+                }
+                if !inner.punishments_by_node_id.contains_key(&node_id)
+                    && inner.punishments_by_node_id.len() >= MAX_PUNISHMENTS_BY_NODE_ID
+                {
+                    log_net!(debug ">>> PUNISHMENT TABLE FULL: {}", node_id);
+                    break;
+                }
+                let e = inner.punishments_by_node_id.entry(node_id).or_insert(ts);
+                *e = (*e).max(ts);
+                punished_node_ids.push(node_id);
+                applied += 1;
+            }
+            if applied > 0 {
+                inner.punishments_dirty = true;
+            }
+        }
+
+        // make the entries dead if they are punished
+        for node_id in punished_node_ids {
+            if let Ok(Some(nr)) = self.unlocked_inner.routing_table.lookup_node_ref(node_id) {
+                nr.operate_mut(|_rti, e| e.set_punished(true));
+            }
+        }
+
+        applied
+    }
+
+    /// Load persisted punishments from the table store
+    pub async fn load_punishments(&self) -> EyreResult<()> {
+        let table_store = self
+            .unlocked_inner
+            .routing_table
+            .network_manager()
+            .table_store();
+        let db = table_store.open(ADDRESS_FILTER_TABLE, 1).await?;
+        let punishments: AddressFilterPunishments =
+            db.load_json(0, PUNISHMENTS_KEY).await?.unwrap_or_default();
+
+        let count = self.import_punishments(punishments);
+        log_net!(debug "loaded {} address filter punishments", count);
+
+        // Nothing changed relative to what is stored other than expirations
+        self.inner.lock().punishments_dirty = false;
+        Ok(())
+    }
+
+    /// Save the current punishments to the table store if they have changed
+    pub async fn save_punishments(&self) -> EyreResult<()> {
+        let punishments = {
+            let mut inner = self.inner.lock();
+            if !inner.punishments_dirty {
+                return Ok(());
+            }
+            inner.punishments_dirty = false;
+            drop(inner);
+            self.export_punishments()
+        };
+
+        let table_store = self
+            .unlocked_inner
+            .routing_table
+            .network_manager()
+            .table_store();
+        let db = table_store.open(ADDRESS_FILTER_TABLE, 1).await?;
+        if let Err(e) = db.store_json(0, PUNISHMENTS_KEY, &punishments).await {
+            self.inner.lock().punishments_dirty = true;
+            return Err(e.into());
+        }
+        Ok(())
     }
 
     pub fn punish_ip_addr(&self, addr: IpAddr) {
@@ -297,6 +483,7 @@ impl AddressFilter {
                 .and_modify(|v| *v = ts)
                 .or_insert(ts),
         };
+        inner.punishments_dirty = true;
     }
 
     fn is_node_id_punished_inner(&self, inner: &AddressFilterInner, node_id: TypedKey) -> bool {
@@ -330,6 +517,49 @@ impl AddressFilter {
             .entry(node_id)
             .and_modify(|v| *v = ts)
             .or_insert(ts);
+        inner.punishments_dirty = true;
+    }
+
+    /// Count a packet that claimed to come from somewhere it should not have, such as
+    /// an answer arriving from an address its question was never sent to.
+    /// The address is punished once it sends too many of these within a minute.
+    /// Returns true if the address was punished.
+    pub fn report_spoofed_source(&self, addr: IpAddr) -> bool {
+        let ipblock = ip_to_ipblock(
+            self.unlocked_inner.max_connections_per_ip6_prefix_size,
+            addr,
+        );
+        let ts = get_aligned_timestamp();
+
+        let punish = {
+            let inner = &mut *self.inner.lock();
+            inner.spoofed_source_count += 1;
+            self.purge_old_timestamps(inner, ts);
+
+            let tstamps = match ipblock {
+                IpAddr::V4(v4) => inner
+                    .spoofed_source_timestamps_by_ip4
+                    .entry(v4)
+                    .or_default(),
+                IpAddr::V6(v6) => inner
+                    .spoofed_source_timestamps_by_ip6_prefix
+                    .entry(v6)
+                    .or_default(),
+            };
+            tstamps.push(ts);
+            tstamps.len() >= self.unlocked_inner.max_spoofed_sources_per_min
+        };
+
+        log_net!(debug ">>> SPOOFED SOURCE: {}", addr);
+        if punish {
+            self.punish_ip_addr(addr);
+        }
+        punish
+    }
+
+    /// Total number of packets reported with spoofed-looking sources
+    pub fn spoofed_source_count(&self) -> u64 {
+        self.inner.lock().spoofed_source_count
     }
 
     pub async fn address_filter_task_routine(
@@ -338,10 +568,14 @@ impl AddressFilter {
         _last_ts: Timestamp,
         cur_ts: Timestamp,
     ) -> EyreResult<()> {
-        //
-        let mut inner = self.inner.lock();
-        self.purge_old_timestamps(&mut inner, cur_ts);
-        self.purge_old_punishments(&mut inner, cur_ts);
+        {
+            let mut inner = self.inner.lock();
+            self.purge_old_timestamps(&mut inner, cur_ts);
+            self.purge_old_punishments(&mut inner, cur_ts);
+        }
+
+        // Persist any punishment changes
+        self.save_punishments().await?;
 
         Ok(())
     }
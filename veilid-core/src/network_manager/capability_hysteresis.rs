@@ -0,0 +1,137 @@
+use super::*;
+
+/// How long we must be reachable and keeping up with our load before we start offering to relay
+pub const RELAY_ADVERTISE_DELAY_US: TimestampDuration = TimestampDuration::new(300_000_000u64); // 5 minutes
+/// How long we must be keeping up with our load before we offer to route again after withdrawing
+pub const ROUTE_ADVERTISE_DELAY_US: TimestampDuration = TimestampDuration::new(300_000_000u64); // 5 minutes
+/// How long we must be overloaded before we stop offering to relay or route
+pub const CAPABILITY_WITHDRAW_DELAY_US: TimestampDuration = TimestampDuration::new(120_000_000u64); // 2 minutes
+
+/// Decides whether a capability that puts load on us should be advertised
+///
+/// Other nodes pick their relays and route hops from the capabilities we advertise, so
+/// flapping them in and out makes everyone that depends on us churn. A capability is only
+/// offered once we have been able to serve it for a while, and only withdrawn once we have
+/// been unable to keep up for a while. Losing reachability withdraws it immediately, since
+/// nobody could use it anyway.
+#[derive(Debug, Clone)]
+pub(super) struct CapabilityHysteresis {
+    advertised: bool,
+    advertise_delay: TimestampDuration,
+    withdraw_delay: TimestampDuration,
+    eligible_since: Option<Timestamp>,
+    overloaded_since: Option<Timestamp>,
+}
+
+impl CapabilityHysteresis {
+    pub fn new(
+        advertised: bool,
+        advertise_delay: TimestampDuration,
+        withdraw_delay: TimestampDuration,
+    ) -> Self {
+        Self {
+            advertised,
+            advertise_delay,
+            withdraw_delay,
+            eligible_since: None,
+            overloaded_since: None,
+        }
+    }
+
+    pub fn advertised(&self) -> bool {
+        self.advertised
+    }
+
+    /// Feed in our current state, returns true if the capability should now be
+    /// advertised or withdrawn
+    pub fn update(&mut self, cur_ts: Timestamp, reachable: bool, overloaded: bool) -> bool {
+        let was_advertised = self.advertised;
+
+        if !reachable {
+            self.eligible_since = None;
+            self.overloaded_since = None;
+            self.advertised = false;
+        } else if overloaded {
+            self.eligible_since = None;
+            let overloaded_since = *self.overloaded_since.get_or_insert(cur_ts);
+            if cur_ts.saturating_sub(overloaded_since) >= self.withdraw_delay {
+                self.advertised = false;
+            }
+        } else {
+            self.overloaded_since = None;
+            let eligible_since = *self.eligible_since.get_or_insert(cur_ts);
+            if cur_ts.saturating_sub(eligible_since) >= self.advertise_delay {
+                self.advertised = true;
+            }
+        }
+
+        self.advertised != was_advertised
+    }
+}
+
+/// Hysteresis state for each capability we only advertise while we can serve it
+pub(super) struct CapabilityAdvertisement {
+    pub relay: CapabilityHysteresis,
+    pub route: CapabilityHysteresis,
+}
+
+impl CapabilityAdvertisement {
+    pub fn new() -> Self {
+        Self {
+            // Relaying is earned, nobody should pick us as a relay the moment we start up
+            relay: CapabilityHysteresis::new(
+                false,
+                RELAY_ADVERTISE_DELAY_US,
+                CAPABILITY_WITHDRAW_DELAY_US,
+            ),
+            // Routes are built constantly, so we route from the start and only back off under load
+            route: CapabilityHysteresis::new(
+                true,
+                ROUTE_ADVERTISE_DELAY_US,
+                CAPABILITY_WITHDRAW_DELAY_US,
+            ),
+        }
+    }
+
+    /// The capabilities we are currently holding back
+    pub fn withheld_capabilities(&self) -> Vec<Capability> {
+        let mut out = Vec::new();
+        if !self.relay.advertised() {
+            out.push(CAP_RELAY);
+        }
+        if !self.route.advertised() {
+            out.push(CAP_ROUTE);
+        }
+        out
+    }
+}
+
+impl NetworkManager {
+    /// Remove the capabilities we are holding back from a set of configured capabilities
+    pub(super) fn without_withheld_capabilities(
+        &self,
+        routing_domain: RoutingDomain,
+        capabilities: Vec<Capability>,
+    ) -> Vec<Capability> {
+        let mut withheld = Vec::new();
+
+        // We carry nobody else's traffic on a network that costs us to use, or in low power mode
+        if self.is_relay_only() {
+            withheld.extend_from_slice(NETWORK_COST_WITHHELD_CAPABILITIES);
+        }
+
+        // Hysteresis only applies to what we advertise on the public internet
+        if routing_domain == RoutingDomain::PublicInternet {
+            withheld.extend(
+                self.inner
+                    .lock()
+                    .capability_advertisement
+                    .withheld_capabilities(),
+            );
+        }
+        capabilities
+            .into_iter()
+            .filter(|cap| !withheld.contains(cap))
+            .collect()
+    }
+}
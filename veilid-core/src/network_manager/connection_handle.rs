@@ -4,7 +4,7 @@ use super::*;
 pub struct ConnectionHandle {
     connection_id: NetworkConnectionId,
     flow: Flow,
-    channel: flume::Sender<(Option<Id>, Vec<u8>)>,
+    channel: SendQueueSender,
 }
 
 #[derive(Debug)]
@@ -17,7 +17,7 @@ impl ConnectionHandle {
     pub(super) fn new(
         connection_id: NetworkConnectionId,
         flow: Flow,
-        channel: flume::Sender<(Option<Id>, Vec<u8>)>,
+        channel: SendQueueSender,
     ) -> Self {
         Self {
             connection_id,
@@ -31,7 +31,6 @@ impl ConnectionHandle {
         self.connection_id
     }
 
-    #[allow(dead_code)]
     pub fn flow(&self) -> Flow {
         self.flow
     }
@@ -52,14 +51,18 @@ impl ConnectionHandle {
     // }
 
     #[cfg_attr(feature="verbose-tracing", instrument(level="trace", skip(self, message), fields(message.len = message.len())))]
-    pub async fn send_async(&self, message: Vec<u8>) -> ConnectionHandleSendResult {
+    pub async fn send_async(
+        &self,
+        message: Vec<u8>,
+        priority: SendPriority,
+    ) -> ConnectionHandleSendResult {
         match self
             .channel
-            .send_async((Span::current().id(), message))
+            .send_async((Span::current().id(), priority, message))
             .await
         {
             Ok(()) => ConnectionHandleSendResult::Sent,
-            Err(e) => ConnectionHandleSendResult::NotSent(e.0 .1),
+            Err(e) => ConnectionHandleSendResult::NotSent(e.2),
         }
     }
 }
@@ -1,9 +1,64 @@
 use super::*;
 pub(crate) use connection_table::ConnectionRefKind;
 use connection_table::*;
+use futures_util::StreamExt;
 use network_connection::*;
 use stop_token::future::FutureExt;
 
+/// Most dial infos to race connections to for a single node
+pub const CONNECTION_RACE_MAX_CANDIDATES: usize = 3;
+/// Delay between starting each racing connection attempt
+pub const CONNECTION_RACE_STAGGER_MS: u32 = 250;
+/// Number of nodes to remember the winning dial info for
+const CONNECTION_RACE_WINNER_CACHE_SIZE: usize = 1024;
+
+/// Start an attempt for each candidate, `stagger_ms` apart in order, and return the first candidate
+/// whose attempt succeeds along with its result
+/// Attempts still in progress when one succeeds are dropped, which cancels them
+/// If every attempt fails, the last failure is returned
+pub(crate) async fn race_staggered<C, R, F, Fut>(
+    candidates: Vec<C>,
+    stagger_ms: u32,
+    attempt: F,
+) -> NetworkErrorResult<NetworkResult<(C, R)>>
+where
+    C: fmt::Debug,
+    F: Fn(&C) -> Fut,
+    Fut: Future<Output = NetworkErrorResult<NetworkResult<R>>>,
+{
+    let mut unord = FuturesUnordered::new();
+    for (n, candidate) in candidates.into_iter().enumerate() {
+        let fut = attempt(&candidate);
+        unord.push(async move {
+            if n > 0 {
+                sleep(n as u32 * stagger_ms).await;
+            }
+            (candidate, fut.await)
+        });
+    }
+
+    let mut last_failure = None;
+    while let Some((candidate, res)) = unord.next().await {
+        match res {
+            Ok(NetworkResult::Value(v)) => {
+                // Dropping the remaining attempts cancels them
+                return Ok(NetworkResult::value((candidate, v)));
+            }
+            Ok(nres) => {
+                log_net!(debug "race attempt to {:?} failed: {}", candidate, nres);
+                // Values were returned above, so only failures are mapped here
+                last_failure = Some(Ok(nres.map(|_| unreachable!())));
+            }
+            Err(e) => {
+                log_net!(debug "race attempt to {:?} failed: {}", candidate, e);
+                last_failure = Some(Err(e));
+            }
+        }
+    }
+
+    last_failure.unwrap_or_else(|| Ok(NetworkResult::no_connection_other("nothing to race")))
+}
+
 ///////////////////////////////////////////////////////////
 // Connection manager
 
@@ -50,8 +105,11 @@ struct ConnectionManagerArc {
     network_manager: NetworkManager,
     connection_initial_timeout_ms: u32,
     connection_inactivity_timeout_ms: u32,
+    tor_socks_address: Option<SocketAddr>,
     connection_table: ConnectionTable,
     address_lock_table: AsyncTagLockTable<SocketAddr>,
+    /// Dial info that won the last connection race to each node
+    race_winners: Mutex<LruCache<TypedKey, DialInfo>>,
     inner: Mutex<Option<ConnectionManagerInner>>,
 }
 impl core::fmt::Debug for ConnectionManagerArc {
@@ -82,11 +140,16 @@ impl ConnectionManager {
     }
     fn new_arc(network_manager: NetworkManager) -> ConnectionManagerArc {
         let config = network_manager.config();
-        let (connection_initial_timeout_ms, connection_inactivity_timeout_ms) = {
+        let (connection_initial_timeout_ms, connection_inactivity_timeout_ms, tor_socks_address) = {
             let c = config.get();
             (
                 c.network.connection_initial_timeout_ms,
                 c.network.connection_inactivity_timeout_ms,
+                c.network
+                    .tor
+                    .enabled
+                    .then(|| SocketAddr::from_str(&c.network.tor.socks_address).ok())
+                    .flatten(),
             )
         };
         let address_filter = network_manager.address_filter();
@@ -95,8 +158,10 @@ impl ConnectionManager {
             network_manager,
             connection_initial_timeout_ms,
             connection_inactivity_timeout_ms,
+            tor_socks_address,
             connection_table: ConnectionTable::new(config, address_filter),
             address_lock_table: AsyncTagLockTable::new(),
+            race_winners: Mutex::new(LruCache::new(CONNECTION_RACE_WINNER_CACHE_SIZE)),
             inner: Mutex::new(None),
         }
     }
@@ -191,7 +256,8 @@ impl ConnectionManager {
         &self,
         inner: &mut ConnectionManagerInner,
         prot_conn: ProtocolNetworkConnection,
-    ) -> EyreResult<NetworkResult<ConnectionHandle>> {
+        dial_info: Option<DialInfo>,
+    ) -> NetworkErrorResult<NetworkResult<ConnectionHandle>> {
         // Get next connection id to use
         let id = inner.next_id;
         inner.next_id += 1u64;
@@ -204,10 +270,11 @@ impl ConnectionManager {
         // Wrap with NetworkConnection object to start the connection processing loop
         let stop_token = match &inner.stop_source {
             Some(ss) => ss.token(),
-            None => bail!("not creating connection because we are stopping"),
+            None => return NetworkError::ShuttingDown.into_network_result(),
         };
 
-        let mut conn = NetworkConnection::from_protocol(self.clone(), stop_token, prot_conn, id);
+        let mut conn =
+            NetworkConnection::from_protocol(self.clone(), stop_token, prot_conn, id, dial_info);
         let handle = conn.get_handle();
 
         // See if this should be a protected connection
@@ -278,6 +345,52 @@ impl ConnectionManager {
         ConnectionRefScope::try_new(self.clone(), id)
     }
 
+    /// Race connections to several dial infos of the same node and return the dial info of the first one to connect
+    ///
+    /// Attempts are started CONNECTION_RACE_STAGGER_MS apart, in order, except that the dial info that won
+    /// the last race to this node goes first. Attempts still in progress when one succeeds are cancelled.
+    #[instrument(level = "trace", skip(self), err)]
+    pub async fn race_connections(
+        &self,
+        node_id: TypedKey,
+        mut dial_infos: Vec<DialInfo>,
+    ) -> NetworkErrorResult<NetworkResult<DialInfo>> {
+        // Try what worked last time first
+        if let Some(winner) = self.arc.race_winners.lock().get(&node_id).cloned() {
+            if let Some(pos) = dial_infos.iter().position(|di| *di == winner) {
+                let winner = dial_infos.remove(pos);
+                dial_infos.insert(0, winner);
+            }
+        }
+
+        let res = race_staggered(dial_infos, CONNECTION_RACE_STAGGER_MS, |dial_info| {
+            let this = self.clone();
+            let dial_info = dial_info.clone();
+            async move { this.get_or_create_connection(dial_info).await }
+        })
+        .await;
+
+        match res {
+            Ok(NetworkResult::Value((dial_info, _))) => {
+                log_net!("== connection race to {} won by {:?}", node_id, dial_info);
+                self.arc
+                    .race_winners
+                    .lock()
+                    .insert(node_id, dial_info.clone());
+                Ok(NetworkResult::value(dial_info))
+            }
+            Ok(nres) => {
+                // Everything failed, forget the last winner
+                self.arc.race_winners.lock().remove(&node_id);
+                Ok(nres.map(|(dial_info, _)| dial_info))
+            }
+            Err(e) => {
+                self.arc.race_winners.lock().remove(&node_id);
+                Err(e)
+            }
+        }
+    }
+
     /// Called when we want to create a new connection or get the current one that already exists
     /// This will kill off any connections that are in conflict with the new connection to be made
     /// in order to make room for the new connection in the system's connection table
@@ -286,7 +399,7 @@ impl ConnectionManager {
     pub async fn get_or_create_connection(
         &self,
         dial_info: DialInfo,
-    ) -> EyreResult<NetworkResult<ConnectionHandle>> {
+    ) -> NetworkErrorResult<NetworkResult<ConnectionHandle>> {
         let peer_address = dial_info.peer_address();
         let remote_addr = peer_address.socket_addr();
         let mut preferred_local_address = self
@@ -329,27 +442,26 @@ impl ConnectionManager {
         // Attempt new connection
         let mut retry_count = 1;
 
-        let prot_conn = network_result_try!(loop {
-            let result_net_res = ProtocolNetworkConnection::connect(
+        let prot_conn = loop {
+            let result = ProtocolNetworkConnection::connect(
                 preferred_local_address,
                 &dial_info,
                 self.arc.connection_initial_timeout_ms,
                 self.network_manager().address_filter(),
+                self.arc.tor_socks_address,
+                #[cfg(all(any(test, feature = "sim-network"), not(target_arch = "wasm32")))]
+                self.network_manager().config().sim_network(),
             )
             .await;
-            match result_net_res {
-                Ok(net_res) => {
-                    if net_res.is_value() || retry_count == 0 {
-                        // Successful new connection, return it
-                        break net_res;
-                    }
+            match result {
+                Ok(prot_conn) => {
+                    // Successful new connection, return it
+                    break prot_conn;
                 }
                 Err(e) => {
-                    if retry_count == 0 {
-                        return Err(e).wrap_err(format!(
-                            "failed to connect: {:?} -> {:?}",
-                            preferred_local_address, dial_info
-                        ));
+                    if retry_count == 0 || !e.is_retryable() {
+                        log_net!(debug "failed to connect: {:?} -> {:?}: {}", preferred_local_address, dial_info, e);
+                        return e.into_network_result();
                     }
                 }
             };
@@ -359,18 +471,18 @@ impl ConnectionManager {
             // Release the preferred local address if things can't connect due to a low-level collision we dont have a record of
             preferred_local_address = None;
             sleep(500).await;
-        });
+        };
 
         // Add to the connection table
         let mut inner = self.arc.inner.lock();
         let inner = match &mut *inner {
             Some(v) => v,
             None => {
-                bail!("shutting down");
+                return NetworkError::ShuttingDown.into_network_result();
             }
         };
 
-        self.on_new_protocol_network_connection(inner, prot_conn)
+        self.on_new_protocol_network_connection(inner, prot_conn, Some(dial_info))
     }
 
     ///////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -400,7 +512,7 @@ impl ConnectionManager {
                         // We don't care if this fails, since nobody here asked for the inbound connection.
                         // If it does, we just drop the connection
 
-                        let _ = self.on_new_protocol_network_connection(inner, prot_conn);
+                        let _ = self.on_new_protocol_network_connection(inner, prot_conn, None);
                     }
                     None => {
                         // If this somehow happens, we're shutting down
@@ -464,9 +576,13 @@ impl ConnectionManager {
     }
 
     // Callback from network connection receive loop when it exits
-    // cleans up the entry in the connection table
+    // cleans up the entry in the connection table and accounts for why it closed on the node it was with
     #[instrument(level = "trace", skip(self))]
-    pub(super) async fn report_connection_finished(&self, connection_id: NetworkConnectionId) {
+    pub(super) async fn report_connection_finished(
+        &self,
+        connection_id: NetworkConnectionId,
+        close_reason: ConnectionCloseReason,
+    ) {
         // Get channel sender
         let sender = {
             let mut inner = self.arc.inner.lock();
@@ -488,15 +604,81 @@ impl ConnectionManager {
 
         // Inform the processor of the event
         if let Some(conn) = conn {
-            // If the connection closed while it was protected, report it on the node the connection was established on
+            // Report the close on the node the connection was established with
+            // If the connection closed while it was protected, that counts against the node
             // In-use connections will already get reported because they will cause a 'question_lost' stat on the remote node
             if let Some(protect_nr) = conn.protected_node_ref() {
-                protect_nr.report_protected_connection_dropped();
+                protect_nr.report_connection_closed(close_reason, true);
+            } else if let Some(nr) = self
+                .network_manager()
+                .routing_table()
+                .lookup_recent_peer_by_flow(conn.flow())
+            {
+                nr.report_connection_closed(close_reason, false);
             }
             let _ = sender.send_async(ConnectionManagerEvent::Dead(conn)).await;
         }
     }
 
+    // Callback from network connection receive loop when it exits with messages still queued
+    // Sends them over another connection to the same remote, dialing a new one if we made the
+    // original connection ourselves. Inbound connections can't be redialed, so those messages are dropped.
+    pub(super) fn reroute_pending_messages(
+        &self,
+        flow: Flow,
+        dial_info: Option<DialInfo>,
+        pending: Vec<QueuedMessage>,
+    ) {
+        if pending.is_empty() {
+            return;
+        }
+        // If we are shutting down, just drop these
+        if self.arc.inner.lock().is_none() {
+            return;
+        }
+
+        // Done in the background because the dead connection's processor gets joined while
+        // its remote address is locked, and making a new connection takes that same lock
+        let this = self.clone();
+        spawn_detached(
+            async move {
+                let handle = match this
+                    .arc
+                    .connection_table
+                    .get_best_connection_by_remote(None, flow.remote())
+                {
+                    Some(handle) => handle,
+                    None => {
+                        let Some(dial_info) = dial_info else {
+                            log_net!(debug "dropping {} pending messages for closed inbound connection {:?}", pending.len(), flow);
+                            return;
+                        };
+                        match this.get_or_create_connection(dial_info).await {
+                            Ok(NetworkResult::Value(handle)) => handle,
+                            Ok(v) => {
+                                log_net!(debug "dropping {} pending messages for {:?}: {}", pending.len(), flow, v);
+                                return;
+                            }
+                            Err(e) => {
+                                log_net!(debug "dropping {} pending messages for {:?}: {}", pending.len(), flow, e);
+                                return;
+                            }
+                        }
+                    }
+                };
+
+                log_net!(debug "rerouting {} pending messages from {:?} to {:?}", pending.len(), flow, handle.flow());
+                for (_span_id, priority, message) in pending {
+                    if let ConnectionHandleSendResult::NotSent(_) = handle.send_async(message, priority).await {
+                        log_net!(debug "rerouted connection {:?} closed with messages pending", handle.flow());
+                        return;
+                    }
+                }
+            }
+            .instrument(Span::current()),
+        );
+    }
+
     pub async fn debug_print(&self) -> String {
         //let inner = self.arc.inner.lock();
         format!(
@@ -176,17 +176,20 @@ impl ConnectionTable {
         }
 
         // Filter by ip for connection limits
+        // IPC connections come from processes on this host and have no real ip address to limit
         let ip_addr = flow.remote_address().ip_addr();
-        match inner.address_filter.add_connection(ip_addr) {
-            Ok(()) => {}
-            Err(e) => {
-                // Return the connection in the error to be disposed of
-                return Err(ConnectionTableAddError::address_filter(
-                    network_connection,
-                    e,
-                ));
-            }
-        };
+        if !flow.remote_address().address().is_ipc_mapped() {
+            match inner.address_filter.add_connection(ip_addr) {
+                Ok(()) => {}
+                Err(e) => {
+                    // Return the connection in the error to be disposed of
+                    return Err(ConnectionTableAddError::address_filter(
+                        network_connection,
+                        e,
+                    ));
+                }
+            };
+        }
 
         // if we have reached the maximum number of connections per protocol type
         // then drop the least recently used connection that is not protected or referenced
@@ -365,11 +368,13 @@ impl ConnectionTable {
             }
         }
         // address_filter
-        let ip_addr = remote.socket_addr().ip();
-        inner
-            .address_filter
-            .remove_connection(ip_addr)
-            .expect("Inconsistency in connection table");
+        if !remote.socket_address().address().is_ipc_mapped() {
+            let ip_addr = remote.socket_addr().ip();
+            inner
+                .address_filter
+                .remove_connection(ip_addr)
+                .expect("Inconsistency in connection table");
+        }
         conn
     }
 
@@ -2,24 +2,48 @@ use super::*;
 
 impl NetworkManager {
     // Direct bootstrap request handler (separate fallback mechanism from cheaper TXT bootstrap mechanism)
-    #[instrument(level = "trace", skip(self), ret, err)]
-    pub(crate) async fn handle_boot_request(&self, flow: Flow) -> EyreResult<NetworkResult<()>> {
+    #[instrument(level = "trace", skip(self, data), ret, err)]
+    pub(crate) async fn handle_boot_request(
+        &self,
+        flow: Flow,
+        data: &[u8],
+    ) -> EyreResult<NetworkResult<()>> {
+        // On keyed networks only nodes that have our network key may crawl us
+        // Requests without a fresh proof get no reply at all, so scanners can't tell keyed nodes apart
+        if let Some(network_key) = &self.unlocked_inner.network_key {
+            if let Err(e) = self.open_keyed_boot_request(network_key, &data[BOOT_MAGIC.len()..]) {
+                return Ok(NetworkResult::invalid_message(format!(
+                    "bootstrap request from {:?} without a valid network key proof: {}",
+                    flow, e
+                )));
+            }
+        }
+
         let routing_table = self.routing_table();
 
         // Get a bunch of nodes with the various
-        let bootstrap_nodes = routing_table.find_bootstrap_nodes_filtered(2);
+        // Bootstrap nodes hand out more of them since that is all they are for
+        let max_per_type = if self.with_config(|c| c.network.role.is_bootstrap()) {
+            BOOTSTRAP_ROLE_BOOT_NODES_PER_TYPE
+        } else {
+            2
+        };
+        let bootstrap_nodes = routing_table.find_bootstrap_nodes_filtered(max_per_type);
 
         // Serialize out peer info
         let bootstrap_peerinfo: Vec<PeerInfo> = bootstrap_nodes
             .iter()
             .filter_map(|nr| nr.make_peer_info(RoutingDomain::PublicInternet))
             .collect();
-        let json_bytes = serialize_json(bootstrap_peerinfo).as_bytes().to_vec();
+        let mut reply = serialize_json(bootstrap_peerinfo).as_bytes().to_vec();
+        if let Some(network_key) = &self.unlocked_inner.network_key {
+            reply = self.seal_with_network_key(network_key, &reply)?;
+        }
 
         // Reply with a chunk of signed routing table
         match self
             .net()
-            .send_data_to_existing_flow(flow, json_bytes)
+            .send_data_to_existing_flow(flow, reply, SendPriority::Control)
             .await?
         {
             SendDataToExistingFlowResult::Sent(_) => {
@@ -37,7 +61,16 @@ impl NetworkManager {
     pub async fn boot_request(&self, dial_info: DialInfo) -> EyreResult<Vec<PeerInfo>> {
         let timeout_ms = self.with_config(|c| c.network.rpc.timeout_ms);
         // Send boot magic to requested peer address
-        let data = BOOT_MAGIC.to_vec();
+        // On keyed networks, prove we have the network key with it
+        let mut data = BOOT_MAGIC.to_vec();
+        if let Some(network_key) = &self.unlocked_inner.network_key {
+            data.extend_from_slice(
+                &self.seal_with_network_key(
+                    network_key,
+                    &make_boot_proof(get_aligned_timestamp()),
+                )?,
+            );
+        }
 
         let out_data: Vec<u8> = network_result_value_or_log!(self
             .net()
@@ -47,10 +80,96 @@ impl NetworkManager {
             return Ok(Vec::new());
         });
 
+        // Tell apart network key mismatches from other failures
+        // Keyed nodes don't answer requests without their network key, so those just time out
+        let out_data = match &self.unlocked_inner.network_key {
+            Some(network_key) => match self.open_with_network_key(network_key, &out_data) {
+                Ok(v) => v,
+                Err(_) if out_data.first() == Some(&b'[') => {
+                    bail!(
+                        "bootstrap node {} does not use a network key, check 'network.network_key_password'",
+                        dial_info
+                    );
+                }
+                Err(_) => {
+                    bail!(
+                        "bootstrap node {} uses a different network key, check 'network.network_key_password'",
+                        dial_info
+                    );
+                }
+            },
+            None => out_data,
+        };
+
         let bootstrap_peerinfo: Vec<PeerInfo> =
             deserialize_json(std::str::from_utf8(&out_data).wrap_err("bad utf8 in boot peerinfo")?)
                 .wrap_err("failed to deserialize boot peerinfo")?;
 
         Ok(bootstrap_peerinfo)
     }
+
+    /// Check that a direct bootstrap request proves it has our network key
+    /// The proof must be recent and is only accepted once, so a captured request can't be replayed
+    fn open_keyed_boot_request(&self, network_key: &SharedSecret, proof: &[u8]) -> EyreResult<()> {
+        let opened = self.open_with_network_key(network_key, proof)?;
+        check_boot_proof(&opened, get_aligned_timestamp())?;
+
+        // The nonce was authenticated along with the proof, so it identifies this request
+        let nonce = Nonce::try_from(&proof[0..NONCE_LENGTH]).wrap_err("invalid nonce")?;
+        let mut inner = self.inner.lock();
+        if inner.boot_proof_nonces.insert(nonce, ()).is_some() {
+            bail!("bootstrap request proof was replayed");
+        }
+        Ok(())
+    }
+
+    /// Encrypt data with the network key, prefixed with the nonce used
+    fn seal_with_network_key(
+        &self,
+        network_key: &SharedSecret,
+        data: &[u8],
+    ) -> EyreResult<Vec<u8>> {
+        let vcrypto = self.crypto().best();
+        let nonce = vcrypto.random_nonce();
+        let mut out = nonce.bytes.to_vec();
+        out.extend_from_slice(&vcrypto.encrypt_aead(data, &nonce, network_key, None)?);
+        Ok(out)
+    }
+
+    /// Decrypt data that was encrypted with the network key by [NetworkManager::seal_with_network_key]
+    fn open_with_network_key(
+        &self,
+        network_key: &SharedSecret,
+        data: &[u8],
+    ) -> EyreResult<Vec<u8>> {
+        if data.len() < NONCE_LENGTH {
+            bail!("network key sealed data too short");
+        }
+        let vcrypto = self.crypto().best();
+        let nonce = Nonce::try_from(&data[0..NONCE_LENGTH]).wrap_err("invalid nonce")?;
+        Ok(vcrypto.decrypt_aead(&data[NONCE_LENGTH..], &nonce, network_key, None)?)
+    }
+}
+
+/// Make the plaintext of a keyed bootstrap request proof
+pub(crate) fn make_boot_proof(ts: Timestamp) -> Vec<u8> {
+    let mut proof = BOOT_MAGIC.to_vec();
+    proof.extend_from_slice(&ts.as_u64().to_le_bytes());
+    proof
+}
+
+/// Check the plaintext of a keyed bootstrap request proof was made close to 'cur_ts'
+pub(crate) fn check_boot_proof(proof: &[u8], cur_ts: Timestamp) -> EyreResult<()> {
+    if proof.len() != BOOT_MAGIC.len() + 8 || proof[0..BOOT_MAGIC.len()] != *BOOT_MAGIC {
+        bail!("bootstrap request proof does not match");
+    }
+    let ts = u64::from_le_bytes(
+        proof[BOOT_MAGIC.len()..]
+            .try_into()
+            .wrap_err("invalid timestamp")?,
+    );
+    if ts.abs_diff(cur_ts.as_u64()) > BOOT_PROOF_MAX_CLOCK_DIFFERENCE_US {
+        bail!("bootstrap request proof is not recent");
+    }
+    Ok(())
 }
@@ -0,0 +1,136 @@
+use super::*;
+
+/// Header before each fragment body: message id (u32), fragment index (u8), fragment count (u8)
+pub const ENVELOPE_FRAGMENT_HEADER_SIZE: usize = 6;
+/// Fragments of a message share an index byte
+pub const MAX_ENVELOPE_FRAGMENTS: usize = 255;
+/// How many messages we reassemble at once, across all senders
+pub const ENVELOPE_FRAGMENT_REASSEMBLY_TABLE_SIZE: usize = 64;
+/// How long to wait for the rest of a message's fragments
+pub const ENVELOPE_FRAGMENT_REASSEMBLY_TIMEOUT_US: TimestampDuration =
+    TimestampDuration::new(10_000_000u64); // 10 seconds
+
+/// A message that was split into fragments to fit a peer's maximum envelope size
+/// and has not arrived completely yet
+struct PartialMessage {
+    first_ts: Timestamp,
+    fragments: Vec<Option<Vec<u8>>>,
+    remaining: usize,
+    size: usize,
+}
+
+/// Reassembles RPC messages that were split across several envelopes
+///
+/// Some transports, such as websockets through certain proxies, can not carry large frames,
+/// so nodes advertise the largest envelope they want to receive and senders split messages
+/// that would not fit. The fragments of a message travel as ordinary envelopes and may
+/// arrive in any order.
+pub(super) struct EnvelopeFragmentReassembly {
+    partial_messages: LruCache<(TypedKey, u32), PartialMessage>,
+}
+
+impl EnvelopeFragmentReassembly {
+    pub fn new() -> Self {
+        Self {
+            partial_messages: LruCache::new(ENVELOPE_FRAGMENT_REASSEMBLY_TABLE_SIZE),
+        }
+    }
+
+    /// Add a fragment received from a sender
+    /// Returns the whole message once all of its fragments have arrived
+    pub fn insert_fragment(
+        &mut self,
+        sender_id: TypedKey,
+        fragment: &[u8],
+        cur_ts: Timestamp,
+    ) -> EyreResult<Option<Vec<u8>>> {
+        if fragment.len() < ENVELOPE_FRAGMENT_HEADER_SIZE {
+            bail!("envelope fragment too small");
+        }
+        let message_id = u32::from_le_bytes(fragment[0..4].try_into().wrap_err("bad message id")?);
+        let index = fragment[4] as usize;
+        let count = fragment[5] as usize;
+        let data = &fragment[ENVELOPE_FRAGMENT_HEADER_SIZE..];
+        if index >= count {
+            bail!("invalid envelope fragment index {}/{}", index, count);
+        }
+
+        // Drop messages whose fragments took too long to arrive
+        while self
+            .partial_messages
+            .peek_lru()
+            .map(|v| cur_ts.saturating_sub(v.1.first_ts) >= ENVELOPE_FRAGMENT_REASSEMBLY_TIMEOUT_US)
+            .unwrap_or_default()
+        {
+            self.partial_messages.remove_lru();
+        }
+
+        let key = (sender_id, message_id);
+        if !self.partial_messages.contains_key(&key) {
+            self.partial_messages.insert(
+                key,
+                PartialMessage {
+                    first_ts: cur_ts,
+                    fragments: vec![None; count],
+                    remaining: count,
+                    size: 0,
+                },
+            );
+        }
+        let partial_message = self.partial_messages.get_mut(&key).unwrap();
+        if partial_message.fragments.len() != count {
+            self.partial_messages.remove(&key);
+            bail!("envelope fragment count changed");
+        }
+        if partial_message.fragments[index].is_some() {
+            // Duplicate fragment
+            return Ok(None);
+        }
+        partial_message.size += data.len();
+        if partial_message.size > MAX_MESSAGE_SIZE {
+            self.partial_messages.remove(&key);
+            bail!("reassembled message too large");
+        }
+        partial_message.fragments[index] = Some(data.to_vec());
+        partial_message.remaining -= 1;
+        if partial_message.remaining != 0 {
+            return Ok(None);
+        }
+
+        let partial_message = self.partial_messages.remove(&key).unwrap();
+        let mut message = Vec::with_capacity(partial_message.size);
+        for data in partial_message.fragments.into_iter().flatten() {
+            message.extend_from_slice(&data);
+        }
+        Ok(Some(message))
+    }
+}
+
+/// Split a message into fragment bodies of at most `max_fragment_size` bytes including their header
+pub fn split_envelope_fragments(
+    message: &[u8],
+    max_fragment_size: usize,
+) -> EyreResult<Vec<Vec<u8>>> {
+    if max_fragment_size <= ENVELOPE_FRAGMENT_HEADER_SIZE {
+        bail!("envelope fragment size too small");
+    }
+    let max_data_size = max_fragment_size - ENVELOPE_FRAGMENT_HEADER_SIZE;
+    let count = (message.len() + max_data_size - 1) / max_data_size;
+    if count > MAX_ENVELOPE_FRAGMENTS {
+        bail!("message needs too many envelope fragments: {}", count);
+    }
+
+    let message_id = get_random_u32().to_le_bytes();
+    Ok(message
+        .chunks(max_data_size)
+        .enumerate()
+        .map(|(index, data)| {
+            let mut fragment = Vec::with_capacity(ENVELOPE_FRAGMENT_HEADER_SIZE + data.len());
+            fragment.extend_from_slice(&message_id);
+            fragment.push(index as u8);
+            fragment.push(count as u8);
+            fragment.extend_from_slice(data);
+            fragment
+        })
+        .collect())
+}
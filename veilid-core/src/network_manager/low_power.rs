@@ -0,0 +1,39 @@
+use super::*;
+
+impl NetworkManager {
+    /// Whether the app has put us in low power mode
+    pub fn is_low_power(&self) -> bool {
+        *self.unlocked_inner.low_power.lock()
+    }
+
+    /// Whether we only make outbound connections, accept none, and carry no traffic for other nodes
+    /// Either the network we are on costs us to use, or we are in low power mode
+    pub fn is_relay_only(&self) -> bool {
+        self.is_low_power() || self.network_cost_policy().is_relay_only()
+    }
+
+    /// Enter or leave low power mode
+    ///
+    /// In low power mode we are relay-only, send no pings or relay keepalives, and only tick
+    /// once per `network.low_power_wake_interval_ms`, when batched up DHT writes are sent.
+    /// Going in or out of relay-only mode restarts the network if it is running.
+    pub fn set_low_power(&self, low_power: bool) {
+        let was_relay_only = self.is_relay_only();
+        let old_low_power =
+            core::mem::replace(&mut *self.unlocked_inner.low_power.lock(), low_power);
+        if old_low_power == low_power {
+            return;
+        }
+        log_net!(debug "low power mode {}", if low_power { "entered" } else { "left" });
+
+        if was_relay_only != self.is_relay_only() && self.unlocked_inner.components.read().is_some()
+        {
+            self.restart_network();
+        }
+
+        // Catch up on maintenance right away rather than at the next wake
+        if !low_power {
+            self.request_tick();
+        }
+    }
+}
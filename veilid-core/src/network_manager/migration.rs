@@ -0,0 +1,87 @@
+use super::*;
+
+use futures_util::StreamExt;
+
+/// Maximum number of recently active peers that get told we moved when our addresses change
+const MAX_MIGRATION_NOTIFY_PEERS: usize = 16;
+
+impl NetworkManager {
+    /// Migrate our connections after the local network interfaces changed underneath us,
+    /// such as a mobile device roaming between Wi-Fi and cellular networks.
+    ///
+    /// Rather than waiting for ping timeouts to mark every peer unreliable, this forgets the
+    /// flows that were bound to our old addresses, re-establishes the relay connection right away,
+    /// and sends a status ping to recently active peers so they learn our new flow immediately.
+    /// Public dial info re-detection is requested by the low-level network separately.
+    /// Only the native low-level network watches its interfaces, so this is not built for wasm.
+    #[instrument(level = "debug", skip(self))]
+    pub(crate) async fn migrate_network(&self) {
+        log_net!(debug "network interfaces changed, migrating connections");
+
+        let routing_table = self.routing_table();
+        let rpc = self.rpc_processor();
+
+        // Forget flows that were established from our old addresses
+        let relay_nr = routing_table.relay_node(RoutingDomain::PublicInternet);
+        let mut migrate_nrs = Vec::new();
+        if let Some(relay_nr) = relay_nr.clone() {
+            migrate_nrs.push(relay_nr);
+        }
+        for (node_id, _) in routing_table
+            .get_recent_peers()
+            .into_iter()
+            .take(MAX_MIGRATION_NOTIFY_PEERS)
+        {
+            let Ok(Some(nr)) = routing_table.lookup_node_ref(node_id) else {
+                continue;
+            };
+            if relay_nr
+                .as_ref()
+                .map(|r| r.same_entry(&nr))
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            migrate_nrs.push(nr);
+        }
+        for nr in &migrate_nrs {
+            nr.clear_last_connections();
+        }
+
+        // Make the relay keepalive happen again on the next ping validator pass too,
+        // so the NAT mappings for our new addresses get established
+        if relay_nr.is_some() {
+            routing_table
+                .edit_routing_domain(RoutingDomain::PublicInternet)
+                .set_relay_node_keepalive(None)
+                .commit(false)
+                .await;
+        }
+
+        // Ping the relay and recently active peers over fresh connections
+        let mut unord = FuturesUnordered::new();
+        for nr in migrate_nrs {
+            let rpc = rpc.clone();
+            unord.push(
+                async move {
+                    let res = rpc.rpc_call_status(Destination::direct(nr.clone())).await;
+                    (nr, res)
+                }
+                .instrument(Span::current()),
+            );
+        }
+        while let Some((nr, res)) = unord.next().await {
+            match res {
+                Ok(NetworkResult::Value(_)) => {
+                    log_net!(debug "migrated connection to {}", nr);
+                }
+                Ok(v) => {
+                    log_net!(debug "failed to migrate connection to {}: {}", nr, v);
+                }
+                Err(e) => {
+                    log_net!(debug "error migrating connection to {}: {}", nr, e);
+                }
+            }
+        }
+    }
+}
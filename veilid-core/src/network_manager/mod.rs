@@ -6,13 +6,23 @@ mod native;
 mod wasm;
 
 mod address_filter;
+mod capability_hysteresis;
 mod connection_handle;
 mod connection_manager;
 mod connection_table;
 mod direct_boot;
+mod envelope_fragments;
+mod low_power;
+#[cfg(not(target_arch = "wasm32"))]
+mod migration;
 mod network_connection;
+mod network_cost;
+mod network_error;
 mod receipt_manager;
+mod relay_dead_letters;
+mod relay_scheduler;
 mod send_data;
+mod send_queue;
 mod stats;
 mod tasks;
 mod types;
@@ -24,24 +34,34 @@ pub mod tests;
 
 pub(crate) use connection_manager::*;
 pub(crate) use network_connection::*;
+pub(crate) use network_error::*;
 pub(crate) use receipt_manager::*;
+pub(crate) use send_queue::SendPriority;
 pub(crate) use stats::*;
 
 pub use types::*;
 
 ////////////////////////////////////////////////////////////////////////////////////////
 use address_filter::*;
+use capability_hysteresis::*;
 use connection_handle::*;
 use crypto::*;
+use envelope_fragments::*;
 use futures_util::stream::FuturesUnordered;
 use hashlink::LruCache;
 #[cfg(not(target_arch = "wasm32"))]
 use native::*;
+#[cfg(all(any(test, feature = "sim-network"), not(target_arch = "wasm32")))]
+pub use native::{SimLink, SimNetwork, SIM_UNBOUND_ADDRESS};
 #[cfg(not(target_arch = "wasm32"))]
 pub use native::{MAX_CAPABILITIES, PUBLIC_INTERNET_CAPABILITIES};
+use network_cost::*;
+use relay_scheduler::*;
 use routing_table::*;
 use rpc_processor::*;
+use send_queue::*;
 use storage_manager::*;
+use tasks::clock_skew::*;
 #[cfg(target_arch = "wasm32")]
 use wasm::*;
 #[cfg(target_arch = "wasm32")]
@@ -50,11 +70,15 @@ pub use wasm::{/* LOCAL_NETWORK_CAPABILITIES, */ MAX_CAPABILITIES, PUBLIC_INTERN
 ////////////////////////////////////////////////////////////////////////////////////////
 
 pub const MAX_MESSAGE_SIZE: usize = MAX_ENVELOPE_SIZE;
+/// The smallest maximum envelope size a node may advertise, the minimum IPv6 MTU
+pub const MIN_MAX_ENVELOPE_SIZE: usize = 1280;
 pub const IPADDR_TABLE_SIZE: usize = 1024;
 pub const IPADDR_MAX_INACTIVE_DURATION_US: TimestampDuration =
     TimestampDuration::new(300_000_000u64); // 5 minutes
 pub const NODE_CONTACT_METHOD_CACHE_SIZE: usize = 1024;
 pub const PUBLIC_ADDRESS_CHANGE_DETECTION_COUNT: usize = 5;
+pub const PUBLIC_ADDRESS_OBSERVATION_HISTORY_SIZE: usize = 64;
+pub const RELAY_DEAD_LETTER_TABLE_SIZE: usize = 256;
 pub const PUBLIC_ADDRESS_CHECK_CACHE_SIZE: usize = 10;
 pub const PUBLIC_ADDRESS_CHECK_TASK_INTERVAL_SECS: u32 = 60;
 pub const PUBLIC_ADDRESS_INCONSISTENCY_TIMEOUT_US: TimestampDuration =
@@ -62,7 +86,40 @@ pub const PUBLIC_ADDRESS_INCONSISTENCY_TIMEOUT_US: TimestampDuration =
 pub const PUBLIC_ADDRESS_INCONSISTENCY_PUNISHMENT_TIMEOUT_US: TimestampDuration =
     TimestampDuration::new(3_600_000_000_u64); // 60 minutes
 pub const ADDRESS_FILTER_TASK_INTERVAL_SECS: u32 = 60;
+pub const CLOCK_SKEW_TASK_INTERVAL_SECS: u32 = 10;
+/// How many senders' envelope timestamps to keep for estimating our clock skew
+pub const CLOCK_SKEW_ENVELOPE_SAMPLE_COUNT: usize = 32;
+/// How many different senders' envelope timestamps are needed before they are trusted for a clock skew estimate
+pub const CLOCK_SKEW_MIN_ENVELOPE_SENDERS: usize = 3;
+pub const CAPABILITY_ADVERTISEMENT_TASK_INTERVAL_SECS: u32 = 10;
 pub const BOOT_MAGIC: &[u8; 4] = b"BOOT";
+/// How far the timestamp in a keyed direct bootstrap request may be from our clock
+pub const BOOT_PROOF_MAX_CLOCK_DIFFERENCE_US: u64 = 60_000_000;
+/// How many keyed direct bootstrap requests to remember so they can't be replayed
+pub const BOOT_PROOF_REPLAY_CACHE_SIZE: usize = 1024;
+pub const BOOTSTRAP_ROLE_BOOT_NODES_PER_TYPE: usize = 4;
+
+/// Capabilities that are never offered when running in the bootstrap role
+pub const BOOTSTRAP_ROLE_DISABLED_CAPABILITIES: &[Capability] = &[
+    CAP_ROUTE,
+    #[cfg(feature = "unstable-tunnels")]
+    CAP_TUNNEL,
+    CAP_RELAY,
+    CAP_DHT,
+    CAP_DHT_WATCH,
+    CAP_DHT_COMPRESSION,
+    CAP_DHT_BATCH,
+    CAP_DHT_DIGEST,
+    CAP_APPMESSAGE,
+    #[cfg(feature = "unstable-blockstore")]
+    CAP_BLOCKSTORE,
+];
+
+/// Check if a capability is disabled, either explicitly in the config or by our role
+pub(crate) fn is_capability_disabled(c: &VeilidConfigInner, cap: &Capability) -> bool {
+    c.capabilities.disable.contains(cap)
+        || (c.network.role.is_bootstrap() && BOOTSTRAP_ROLE_DISABLED_CAPABILITIES.contains(cap))
+}
 
 #[derive(Clone, Debug, Default)]
 pub struct ProtocolConfig {
@@ -117,6 +174,22 @@ pub(crate) enum NodeContactMethod {
     /// Must use outbound relay to reach the node
     OutboundRelay(NodeRef),
 }
+
+impl NodeContactMethod {
+    /// How the node is reached as reported through the API, None if it can not be reached
+    pub fn to_contact_method(&self) -> Option<ContactMethod> {
+        match self {
+            NodeContactMethod::Unreachable => None,
+            NodeContactMethod::Existing => Some(ContactMethod::Existing),
+            NodeContactMethod::Direct(_) => Some(ContactMethod::Direct),
+            NodeContactMethod::SignalReverse(_, _) => Some(ContactMethod::ReverseConnection),
+            NodeContactMethod::SignalHolePunch(_, _) => Some(ContactMethod::HolePunch),
+            NodeContactMethod::InboundRelay(_) | NodeContactMethod::OutboundRelay(_) => {
+                Some(ContactMethod::Relay)
+            }
+        }
+    }
+}
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
 struct NodeContactMethodCacheKey {
     own_node_info_ts: Timestamp,
@@ -142,6 +215,26 @@ struct NetworkManagerInner {
         BTreeMap<PublicAddressCheckCacheKey, LruCache<IpAddr, SocketAddress>>,
     public_address_inconsistencies_table:
         BTreeMap<PublicAddressCheckCacheKey, HashMap<IpAddr, Timestamp>>,
+    /// Most recent public address reports, newest last
+    public_address_observations: VecDeque<PublicAddressObservation>,
+    /// Destinations we failed to relay messages to, least recently failing first
+    relay_dead_letters: LruCache<TypedKey, RelayDeadLetter>,
+    /// Envelopes waiting to be relayed, and the accounting for the clients that sent them
+    relay_scheduler: RelayScheduler<RelayedEnvelope>,
+    /// If the background worker forwarding relayed envelopes is running
+    relay_worker_running: bool,
+    /// Messages split to fit our maximum envelope size that have not completely arrived yet
+    envelope_fragment_reassembly: EnvelopeFragmentReassembly,
+    /// Microseconds the network's clocks are estimated to be ahead of ours
+    clock_skew: Option<i64>,
+    /// If our clock is too far off from the network's
+    clock_skew_warning: bool,
+    /// How far ahead of our clock the timestamps of the last envelopes from each sender were
+    envelope_clock_skews: LruCache<TypedKey, i64>,
+    /// Whether we are currently offering to relay and route for others
+    capability_advertisement: CapabilityAdvertisement,
+    /// Nonces of recent keyed direct bootstrap requests
+    boot_proof_nonces: LruCache<Nonce, ()>,
 }
 
 struct NetworkManagerUnlockedInner {
@@ -157,12 +250,28 @@ struct NetworkManagerUnlockedInner {
     address_filter: RwLock<Option<AddressFilter>>,
     components: RwLock<Option<NetworkComponents>>,
     update_callback: RwLock<Option<UpdateCallback>>,
+    // Listener statuses survive a failed startup so the failure can be reported
+    listener_statuses: Mutex<Vec<ListenerStatus>>,
+    // The network cost policy is set by the attachment manager and survives network restarts
+    network_cost_policy: Mutex<VeilidConfigNetworkCostPolicy>,
+    // Low power mode is set by the app and survives network restarts
+    low_power: Mutex<bool>,
     // Background processes
     rolling_transfers_task: TickTask<EyreReport>,
     public_address_check_task: TickTask<EyreReport>,
     address_filter_task: TickTask<EyreReport>,
+    clock_skew_task: TickTask<EyreReport>,
+    capability_advertisement_task: TickTask<EyreReport>,
     // Network Key
     network_key: Option<SharedSecret>,
+    // Background worker that keeps us ticking when the browser throttles our timers
+    #[cfg(target_arch = "wasm32")]
+    keepalive_worker: KeepaliveWorker,
+    // Requests to tick early, from platform background execution hooks
+    #[cfg(not(target_arch = "wasm32"))]
+    tick_request_sender: flume::Sender<()>,
+    #[cfg(not(target_arch = "wasm32"))]
+    tick_request_receiver: flume::Receiver<()>,
 }
 
 #[derive(Clone)]
@@ -179,6 +288,16 @@ impl NetworkManager {
             node_contact_method_cache: LruCache::new(NODE_CONTACT_METHOD_CACHE_SIZE),
             public_address_check_cache: BTreeMap::new(),
             public_address_inconsistencies_table: BTreeMap::new(),
+            public_address_observations: VecDeque::new(),
+            relay_dead_letters: LruCache::new(RELAY_DEAD_LETTER_TABLE_SIZE),
+            relay_scheduler: RelayScheduler::new(),
+            relay_worker_running: false,
+            envelope_fragment_reassembly: EnvelopeFragmentReassembly::new(),
+            clock_skew: None,
+            clock_skew_warning: false,
+            envelope_clock_skews: LruCache::new(CLOCK_SKEW_ENVELOPE_SAMPLE_COUNT),
+            capability_advertisement: CapabilityAdvertisement::new(),
+            boot_proof_nonces: LruCache::new(BOOT_PROOF_REPLAY_CACHE_SIZE),
         }
     }
     fn new_unlocked_inner(
@@ -189,6 +308,8 @@ impl NetworkManager {
         crypto: Crypto,
         network_key: Option<SharedSecret>,
     ) -> NetworkManagerUnlockedInner {
+        #[cfg(not(target_arch = "wasm32"))]
+        let (tick_request_sender, tick_request_receiver) = flume::bounded(1);
         NetworkManagerUnlockedInner {
             config: config.clone(),
             storage_manager,
@@ -200,10 +321,23 @@ impl NetworkManager {
             routing_table: RwLock::new(None),
             components: RwLock::new(None),
             update_callback: RwLock::new(None),
+            listener_statuses: Mutex::new(Vec::new()),
+            network_cost_policy: Mutex::new(VeilidConfigNetworkCostPolicy::Normal),
+            low_power: Mutex::new(false),
             rolling_transfers_task: TickTask::new(ROLLING_TRANSFERS_INTERVAL_SECS),
             public_address_check_task: TickTask::new(PUBLIC_ADDRESS_CHECK_TASK_INTERVAL_SECS),
             address_filter_task: TickTask::new(ADDRESS_FILTER_TASK_INTERVAL_SECS),
+            clock_skew_task: TickTask::new(CLOCK_SKEW_TASK_INTERVAL_SECS),
+            capability_advertisement_task: TickTask::new(
+                CAPABILITY_ADVERTISEMENT_TASK_INTERVAL_SECS,
+            ),
             network_key,
+            #[cfg(target_arch = "wasm32")]
+            keepalive_worker: KeepaliveWorker::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            tick_request_sender,
+            #[cfg(not(target_arch = "wasm32"))]
+            tick_request_receiver,
         }
     }
 
@@ -332,6 +466,10 @@ impl NetworkManager {
             .connection_manager
             .clone()
     }
+    #[cfg(target_arch = "wasm32")]
+    pub fn keepalive_worker(&self) -> KeepaliveWorker {
+        self.unlocked_inner.keepalive_worker.clone()
+    }
     pub fn update_callback(&self) -> UpdateCallback {
         self.unlocked_inner
             .update_callback
@@ -347,13 +485,24 @@ impl NetworkManager {
         routing_table.init().await?;
         let address_filter = AddressFilter::new(self.config(), routing_table.clone());
         *self.unlocked_inner.routing_table.write() = Some(routing_table.clone());
-        *self.unlocked_inner.address_filter.write() = Some(address_filter);
+        *self.unlocked_inner.address_filter.write() = Some(address_filter.clone());
         *self.unlocked_inner.update_callback.write() = Some(update_callback);
+
+        // Restore punishments so restarting the node does not forgive peers
+        if let Err(e) = address_filter.load_punishments().await {
+            log_net!(debug "failed to load address filter punishments: {}", e);
+        }
         Ok(())
     }
 
     #[instrument(level = "debug", skip_all)]
     pub async fn terminate(&self) {
+        let address_filter = self.unlocked_inner.address_filter.read().clone();
+        if let Some(address_filter) = address_filter {
+            if let Err(e) = address_filter.save_punishments().await {
+                log_net!(debug "failed to save address filter punishments: {}", e);
+            }
+        }
         let routing_table = self.unlocked_inner.routing_table.write().take();
         if let Some(routing_table) = routing_table {
             routing_table.terminate().await;
@@ -445,6 +594,11 @@ impl NetworkManager {
             *self.inner.lock() = NetworkManager::new_inner();
         }
 
+        // listeners are no longer bound, but keep any errors around
+        for listener_status in self.unlocked_inner.listener_statuses.lock().iter_mut() {
+            listener_status.bound_addresses.clear();
+        }
+
         // send update
         log_net!(debug "sending network state update to api clients");
         self.send_network_update();
@@ -501,8 +655,39 @@ impl NetworkManager {
         net.needs_restart()
     }
 
+    /// Wait until it is time for the next tick
+    /// In the browser a keepalive worker may tick us early, as our own timers get throttled in background tabs
+    pub async fn wait_for_next_tick(&self, timeout_ms: u32) {
+        cfg_if! {
+            if #[cfg(target_arch = "wasm32")] {
+                self.keepalive_worker().wait_for_tick(timeout_ms).await;
+            } else {
+                let _ = timeout(
+                    timeout_ms,
+                    self.unlocked_inner.tick_request_receiver.recv_async(),
+                )
+                .await;
+            }
+        }
+    }
+
+    /// Tick as soon as possible instead of waiting out the rest of the tick interval
+    /// Used when the platform only gives us brief moments of execution time, such as a mobile app in the background
+    pub fn request_tick(&self) {
+        cfg_if! {
+            if #[cfg(target_arch = "wasm32")] {
+                self.keepalive_worker().tick();
+            } else {
+                // If a tick is already pending, this one can be dropped
+                let _ = self.unlocked_inner.tick_request_sender.try_send(());
+            }
+        }
+    }
+
     pub fn generate_node_status(&self, _routing_domain: RoutingDomain) -> NodeStatus {
-        NodeStatus {}
+        NodeStatus {
+            route_pow_difficulty: self.rpc_processor().required_route_pow_difficulty(),
+        }
     }
 
     /// Generates a multi-shot/normal receipt
@@ -522,7 +707,7 @@ impl NetworkManager {
 
         let nonce = vcrypto.random_nonce();
         let node_id = routing_table.node_id(vcrypto.kind());
-        let node_id_secret = routing_table.node_id_secret_key(vcrypto.kind());
+        let node_id_signer = routing_table.node_id_signer(vcrypto.kind());
 
         let receipt = Receipt::try_new(
             best_envelope_version(),
@@ -532,7 +717,7 @@ impl NetworkManager {
             extra_data,
         )?;
         let out = receipt
-            .to_signed_data(self.crypto(), &node_id_secret)
+            .to_signed_data(node_id_signer.as_ref())
             .wrap_err("failed to generate signed receipt")?;
 
         // Record the receipt for later
@@ -557,7 +742,7 @@ impl NetworkManager {
 
         let nonce = vcrypto.random_nonce();
         let node_id = routing_table.node_id(vcrypto.kind());
-        let node_id_secret = routing_table.node_id_secret_key(vcrypto.kind());
+        let node_id_signer = routing_table.node_id_signer(vcrypto.kind());
 
         let receipt = Receipt::try_new(
             best_envelope_version(),
@@ -567,7 +752,7 @@ impl NetworkManager {
             extra_data,
         )?;
         let out = receipt
-            .to_signed_data(self.crypto(), &node_id_secret)
+            .to_signed_data(node_id_signer.as_ref())
             .wrap_err("failed to generate signed receipt")?;
 
         // Record the receipt for later
@@ -599,6 +784,13 @@ impl NetworkManager {
             .await
     }
 
+    /// Process a receipt that an application got back through its own means
+    pub async fn handle_app_receipt(&self, receipt: Receipt) -> NetworkResult<()> {
+        self.receipt_manager()
+            .handle_receipt(receipt, ReceiptReturned::OutOfBand)
+            .await
+    }
+
     /// Process a received in-band receipt
     #[instrument(level = "trace", skip(self, receipt_data), ret)]
     pub async fn handle_in_band_receipt<R: AsRef<[u8]>>(
@@ -739,6 +931,7 @@ impl NetworkManager {
                         .send_data_to_dial_info(
                             hole_punch_dial_info_detail.dial_info.clone(),
                             Vec::new(),
+                            SendPriority::Control,
                         )
                         .await?
                 );
@@ -764,7 +957,10 @@ impl NetworkManager {
     fn build_envelope<B: AsRef<[u8]>>(
         &self,
         dest_node_id: TypedKey,
+        dest_kem_public_key: Option<KemPublicKey>,
         version: u8,
+        negotiated_compression: bool,
+        fragment: bool,
         body: B,
     ) -> EyreResult<Vec<u8>> {
         // DH to get encryption key
@@ -774,7 +970,7 @@ impl NetworkManager {
         };
 
         let node_id = routing_table.node_id(vcrypto.kind());
-        let node_id_secret = routing_table.node_id_secret_key(vcrypto.kind());
+        let node_id_signer = routing_table.node_id_signer(vcrypto.kind());
 
         // Get timestamp, nonce
         let ts = get_aligned_timestamp();
@@ -788,12 +984,15 @@ impl NetworkManager {
             nonce,
             node_id.value,
             dest_node_id.value,
-        );
+        )
+        .with_negotiated_compression(negotiated_compression)
+        .with_fragment(fragment);
         envelope
             .to_encrypted_data(
                 self.crypto(),
                 body.as_ref(),
-                &node_id_secret,
+                node_id_signer.as_ref(),
+                dest_kem_public_key.as_ref(),
                 &self.unlocked_inner.network_key,
             )
             .wrap_err("envelope failed to encode")
@@ -803,6 +1002,7 @@ impl NetworkManager {
     /// node_ref is the direct destination to which the envelope will be sent
     /// If 'destination_node_ref' is specified, it can be different than the node_ref being sent to
     /// which will cause the envelope to be relayed
+    /// 'priority' decides whether the envelope may be sent ahead of others already queued on the connection
     #[cfg_attr(
         feature = "verbose-tracing",
         instrument(level = "trace", skip(self, body), ret, err)
@@ -812,9 +1012,20 @@ impl NetworkManager {
         node_ref: NodeRef,
         destination_node_ref: Option<NodeRef>,
         body: B,
+        priority: SendPriority,
     ) -> EyreResult<NetworkResult<SendDataMethod>> {
         let destination_node_ref = destination_node_ref.as_ref().unwrap_or(&node_ref).clone();
-        let best_node_id = destination_node_ref.best_node_id();
+
+        // Hybrid crypto kinds need the destination's key encapsulation public key,
+        // so fall back to the next best crypto kind until we have learned it
+        let Some((best_node_id, best_kem_public_key)) =
+            destination_node_ref.best_envelope_node_id()
+        else {
+            bail!(
+                "can't talk to this node {} because we have no usable node id for it",
+                node_ref
+            );
+        };
 
         // Get node's envelope versions and see if we can send to it
         // and if so, get the max version we can use
@@ -825,8 +1036,43 @@ impl NetworkManager {
             );
         };
 
+        // Only compress selectively if the destination can tell from the envelope whether we did
+        let negotiated_compression =
+            destination_node_ref.has_capabilities(&[CAP_ENVELOPE_COMPRESSION]);
+
         // Build the envelope to send
-        let out = self.build_envelope(best_node_id, envelope_version, body)?;
+        let out = self.build_envelope(
+            best_node_id,
+            best_kem_public_key,
+            envelope_version,
+            negotiated_compression,
+            false,
+            body.as_ref(),
+        )?;
+
+        // Split the message if the envelope is larger than the destination, or the node we send it through, wants
+        // Only destinations that advertise a maximum envelope size can reassemble the fragments
+        if let Some(mut envelope_size_limit) = destination_node_ref.envelope_size_limit() {
+            if !node_ref.same_entry(&destination_node_ref) {
+                if let Some(relay_envelope_size_limit) = node_ref.envelope_size_limit() {
+                    envelope_size_limit = envelope_size_limit.min(relay_envelope_size_limit);
+                }
+            }
+            if out.len() > envelope_size_limit {
+                return self
+                    .send_envelope_fragments(
+                        node_ref,
+                        destination_node_ref,
+                        best_node_id,
+                        best_kem_public_key,
+                        envelope_version,
+                        envelope_size_limit,
+                        body.as_ref(),
+                        priority,
+                    )
+                    .await;
+            }
+        }
 
         if !node_ref.same_entry(&destination_node_ref) {
             log_net!(
@@ -840,7 +1086,57 @@ impl NetworkManager {
         }
 
         // Send the envelope via whatever means necessary
-        self.send_data(node_ref, out).await
+        self.send_data(node_ref, out, priority).await
+    }
+
+    /// Send a message that does not fit in the envelope size limit as several envelopes
+    #[allow(clippy::too_many_arguments)]
+    async fn send_envelope_fragments(
+        &self,
+        node_ref: NodeRef,
+        destination_node_ref: NodeRef,
+        best_node_id: TypedKey,
+        best_kem_public_key: Option<KemPublicKey>,
+        envelope_version: u8,
+        envelope_size_limit: usize,
+        body: &[u8],
+        priority: SendPriority,
+    ) -> EyreResult<NetworkResult<SendDataMethod>> {
+        let Some(vcrypto) = self.crypto().get(best_node_id.kind) else {
+            bail!("should not have a destination with incompatible crypto here");
+        };
+
+        // Fragments always start with a compression algorithm id, every node that reassembles understands it
+        let envelope_overhead = MIN_ENVELOPE_SIZE + vcrypto.kem_ciphertext_length() + 1;
+        let fragments =
+            split_envelope_fragments(body, envelope_size_limit.saturating_sub(envelope_overhead))?;
+
+        log_net!(
+            "sending message to {:?} as {} envelope fragments, len={}, limit={}",
+            destination_node_ref,
+            fragments.len(),
+            body.len(),
+            envelope_size_limit
+        );
+
+        let mut res = None;
+        for fragment in fragments {
+            let out = self.build_envelope(
+                best_node_id,
+                best_kem_public_key,
+                envelope_version,
+                true,
+                true,
+                fragment,
+            )?;
+            let send_data_method =
+                network_result_try!(self.send_data(node_ref.clone(), out, priority).await?);
+            res = Some(send_data_method);
+        }
+        let Some(send_data_method) = res else {
+            bail!("message had no envelope fragments");
+        };
+        Ok(NetworkResult::value(send_data_method))
     }
 
     /// Called by the RPC handler when we want to issue an direct receipt
@@ -874,7 +1170,7 @@ impl NetworkManager {
     async fn on_recv_envelope(&self, data: &mut [u8], flow: Flow) -> EyreResult<bool> {
         #[cfg(feature = "verbose-tracing")]
         let root = span!(
-            parent: None,
+            parent: &self.config().instance_span(),
             Level::TRACE,
             "on_recv_envelope",
             "data.len" = data.len(),
@@ -887,7 +1183,11 @@ impl NetworkManager {
         let remote_addr = flow.remote_address().ip_addr();
 
         // Network accounting
-        self.stats_packet_rcvd(remote_addr, ByteCount::new(data.len() as u64));
+        self.stats_packet_rcvd(
+            remote_addr,
+            flow.protocol_type(),
+            ByteCount::new(data.len() as u64),
+        );
 
         // If this is a zero length packet, just drop it, because these are used for hole punching
         // and possibly other low-level network connectivity tasks and will never require
@@ -917,7 +1217,7 @@ impl NetworkManager {
 
         // Is this a direct bootstrap request instead of an envelope?
         if data[0..4] == *BOOT_MAGIC {
-            network_result_value_or_log!(self.handle_boot_request(flow).await? => [ format!(": flow={:?}", flow) ] {});
+            network_result_value_or_log!(self.handle_boot_request(flow, data).await? => [ format!(": flow={:?}", flow) ] {});
             return Ok(true);
         }
 
@@ -928,17 +1228,23 @@ impl NetworkManager {
         }
 
         // Decode envelope header (may fail signature validation)
-        let envelope =
-            match Envelope::from_signed_data(self.crypto(), data, &self.unlocked_inner.network_key)
-            {
-                Ok(v) => v,
-                Err(e) => {
+        let envelope = match Envelope::from_signed_data(
+            self.crypto(),
+            data,
+            &self.unlocked_inner.network_key,
+        ) {
+            Ok(v) => v,
+            Err(e) => {
+                if self.unlocked_inner.network_key.is_some() {
+                    log_net!(debug "envelope from {:?} failed to decode, it may not have been sent with our network key: {}", flow, e);
+                } else {
                     log_net!(debug "envelope failed to decode: {}", e);
-                    // safe to punish here because relays also check here to ensure they arent forwarding things that don't decode
-                    self.address_filter().punish_ip_addr(remote_addr);
-                    return Ok(false);
                 }
-            };
+                // safe to punish here because relays also check here to ensure they arent forwarding things that don't decode
+                self.address_filter().punish_ip_addr(remote_addr);
+                return Ok(false);
+            }
+        };
 
         // Get timestamp range
         let (tsbehind, tsahead) = self.with_config(|c| {
@@ -956,9 +1262,17 @@ impl NetworkManager {
             )
         });
 
-        // Validate timestamp isn't too old
-        let ts = get_aligned_timestamp();
+        // Note how far off the sender's clock is before the timestamp is checked,
+        // otherwise a node whose own clock is off would never hear enough to notice
+        let local_ts = get_aligned_timestamp();
         let ets = envelope.get_timestamp();
+        self.record_envelope_clock_skew(envelope.get_sender_typed_id(), ets, local_ts);
+
+        // Validate against where the network's clocks are rather than ours,
+        // so we don't drop all traffic when our own clock is off
+        let max_clock_skew_us = self.with_config(|c| ms_to_us(c.network.rpc.max_clock_skew_ms));
+        let clock_skew = self.inner.lock().clock_skew;
+        let ts = compensate_timestamp(local_ts, clock_skew, max_clock_skew_us);
         if let Some(tsbehind) = tsbehind {
             if tsbehind.as_u64() != 0 && (ts > ets && ts.saturating_sub(ets) > tsbehind) {
                 log_net!(debug
@@ -1006,6 +1320,10 @@ impl NetworkManager {
                     Ok(v) => v,
                     Err(e) => {
                         log_net!(debug "failed to resolve recipient node for relay, dropping outbound relayed packet: {}" ,e);
+                        self.record_relay_failure(
+                            recipient_id,
+                            format!("failed to resolve node: {}", e),
+                        );
                         return Ok(false);
                     }
                 }
@@ -1021,6 +1339,10 @@ impl NetworkManager {
                     Ok(v) => v,
                     Err(e) => {
                         log_net!(debug "failed to look up recipient node for relay, dropping outbound relayed packet: {}" ,e);
+                        self.record_relay_failure(
+                            recipient_id,
+                            format!("failed to look up node: {}", e),
+                        );
                         return Ok(false);
                     }
                 }
@@ -1033,33 +1355,33 @@ impl NetworkManager {
                     relay_nr.set_sequencing(Sequencing::EnsureOrdered);
                 };
 
-                // Relay the packet to the desired destination
-                log_net!("relaying {} bytes to {}", data.len(), relay_nr);
-
-                network_result_value_or_log!(match self.send_data(relay_nr, data.to_vec())
-                    .await {
-                        Ok(v) => v,
-                        Err(e) => {
-                            log_net!(debug "failed to forward envelope: {}" ,e);
-                            return Ok(false);
-                        }
-                    } => [ format!(": relay_nr={}, data.len={}", relay_nr, data.len()) ] {
-                        return Ok(false);
-                    }
-                );
+                // Queue the packet to be relayed, taking turns with other clients of ours
+                if let Err(e) = self.enqueue_relayed_envelope(
+                    sender_id,
+                    RelayedEnvelope {
+                        recipient_id,
+                        relay_nr,
+                        data: data.to_vec(),
+                    },
+                ) {
+                    // Counted against the client in its relay accounting
+                    log_net!(debug "dropping relayed envelope from {}: {}", sender_id, e);
+                }
+            } else {
+                self.record_relay_failure(recipient_id, "node not in routing table");
             }
             // Inform caller that we dealt with the envelope, but did not process it locally
             return Ok(false);
         }
 
         // DH to get decryption key (cached)
-        let node_id_secret = routing_table.node_id_secret_key(envelope.get_crypto_kind());
+        let node_id_signer = routing_table.node_id_signer(envelope.get_crypto_kind());
 
         // Decrypt the envelope body
         let body = match envelope.decrypt_body(
             self.crypto(),
             data,
-            &node_id_secret,
+            node_id_signer.as_ref(),
             &self.unlocked_inner.network_key,
         ) {
             Ok(v) => v,
@@ -1088,6 +1410,28 @@ impl NetworkManager {
         };
         source_noderef.add_envelope_version(envelope.get_version());
 
+        // Reassemble messages that were split to fit our maximum envelope size
+        let body = if envelope.is_fragment() {
+            let res = self
+                .inner
+                .lock()
+                .envelope_fragment_reassembly
+                .insert_fragment(envelope.get_sender_typed_id(), &body, ts);
+            match res {
+                Ok(Some(v)) => v,
+                Ok(None) => {
+                    // Wait for the rest of the message
+                    return Ok(true);
+                }
+                Err(e) => {
+                    log_net!(debug "dropping envelope fragment: {}", e);
+                    return Ok(false);
+                }
+            }
+        } else {
+            body
+        };
+
         // Pass message to RPC system
         rpc.enqueue_direct_message(envelope, source_noderef, flow, routing_domain, body)?;
 
@@ -1098,4 +1442,10 @@ impl NetworkManager {
     pub fn restart_network(&self) {
         self.net().restart_network();
     }
+
+    /// Reload the TLS certificate used by inbound TLS listeners without restarting the network
+    /// Returns false if there are no TLS listeners
+    pub fn reload_tls_certificates(&self) -> EyreResult<bool> {
+        self.net().reload_tls_certificates()
+    }
 }
@@ -10,6 +10,9 @@ const PORT_MAP_TRY_COUNT: usize = 3;
 // Detection result of dial info detection futures
 #[derive(Clone, Debug)]
 pub enum DetectedDialInfo {
+    // Peers saw us at different external addresses, so we can only connect outbound
+    InconsistentAddress,
+    // Peers saw us at the same external address but on different ports, so there is no port to publish
     SymmetricNAT,
     Detected(DialInfoDetail),
 }
@@ -429,6 +432,7 @@ impl DiscoveryContext {
                     ddi: DetectedDialInfo::Detected(DialInfoDetail {
                         dial_info: external_1.dial_info.clone(),
                         class: DialInfoClass::Direct,
+                        preference: 0,
                     }),
                     external_address_types: AddressTypeSet::only(external_1.address.address_type()),
                 })
@@ -438,6 +442,7 @@ impl DiscoveryContext {
                     ddi: DetectedDialInfo::Detected(DialInfoDetail {
                         dial_info: external_1.dial_info.clone(),
                         class: DialInfoClass::Blocked,
+                        preference: 0,
                     }),
                     external_address_types: AddressTypeSet::only(external_1.address.address_type()),
                 })
@@ -461,12 +466,12 @@ impl DiscoveryContext {
             )
         };
 
-        // If we have two different external addresses, then this is a symmetric NAT
+        // If we have two different external addresses, then nothing can reliably reach us
         if external_2.address.address() != external_1.address.address() {
-            let do_symmetric_nat_fut: SendPinBoxFuture<Option<DetectionResult>> =
+            let do_inconsistent_address_fut: SendPinBoxFuture<Option<DetectionResult>> =
                 Box::pin(async move {
                     Some(DetectionResult {
-                        ddi: DetectedDialInfo::SymmetricNAT,
+                        ddi: DetectedDialInfo::InconsistentAddress,
                         external_address_types: AddressTypeSet::only(
                             external_1.address.address_type(),
                         ) | AddressTypeSet::only(
@@ -474,13 +479,14 @@ impl DiscoveryContext {
                         ),
                     })
                 });
-            unord.push(do_symmetric_nat_fut);
+            unord.push(do_inconsistent_address_fut);
             return;
         }
 
         // Manual Mapping Detection
         ///////////
         let this = self.clone();
+        let mut opt_do_manual_map_fut: Option<SendPinBoxFuture<Option<DetectionResult>>> = None;
         if let Some(local_port) = self
             .unlocked_inner
             .net
@@ -509,6 +515,7 @@ impl DiscoveryContext {
                                 ddi: DetectedDialInfo::Detected(DialInfoDetail {
                                     dial_info: external_1_dial_info_with_local_port,
                                     class: DialInfoClass::Direct,
+                                    preference: 0,
                                 }),
                                 external_address_types: AddressTypeSet::only(
                                     c_external_1.address.address_type(),
@@ -518,13 +525,41 @@ impl DiscoveryContext {
 
                         None
                     });
-                unord.push(do_manual_map_fut);
+                opt_do_manual_map_fut = Some(do_manual_map_fut);
             }
         }
 
         // NAT Detection
         ///////////
 
+        // Symmetric NAT Detection
+        ///////////
+        // The same external address with a different port for each peer means the NAT
+        // maps every destination separately. The port each peer sees is only good for
+        // that peer, so there is no dial info to publish and we can only connect outbound.
+        if external_2.address.port() != external_1.address.port() {
+            let do_symmetric_nat_fut: SendPinBoxFuture<Option<DetectionResult>> =
+                Box::pin(async move {
+                    // A manual port mapping still lets peers reach us
+                    if let Some(do_manual_map_fut) = opt_do_manual_map_fut {
+                        if let Some(dr) = do_manual_map_fut.await {
+                            return Some(dr);
+                        }
+                    }
+                    Some(DetectionResult {
+                        ddi: DetectedDialInfo::SymmetricNAT,
+                        external_address_types: AddressTypeSet::only(
+                            external_1.address.address_type(),
+                        ),
+                    })
+                });
+            unord.push(do_symmetric_nat_fut);
+            return;
+        }
+        if let Some(do_manual_map_fut) = opt_do_manual_map_fut {
+            unord.push(do_manual_map_fut);
+        }
+
         // Full Cone NAT Detection
         ///////////
         let this = self.clone();
@@ -559,6 +594,7 @@ impl DiscoveryContext {
                                 ddi: DetectedDialInfo::Detected(DialInfoDetail {
                                     dial_info: c_external_1.dial_info,
                                     class: DialInfoClass::FullConeNAT,
+                                    preference: 0,
                                 }),
                                 external_address_types: AddressTypeSet::only(
                                     c_external_1.address.address_type(),
@@ -593,6 +629,7 @@ impl DiscoveryContext {
                                 ddi: DetectedDialInfo::Detected(DialInfoDetail {
                                     dial_info: c_external_1.dial_info.clone(),
                                     class: DialInfoClass::AddressRestrictedNAT,
+                                    preference: 0,
                                 }),
                                 external_address_types: AddressTypeSet::only(
                                     c_external_1.address.address_type(),
@@ -604,6 +641,7 @@ impl DiscoveryContext {
                             ddi: DetectedDialInfo::Detected(DialInfoDetail {
                                 dial_info: c_external_1.dial_info.clone(),
                                 class: DialInfoClass::PortRestrictedNAT,
+                                preference: 0,
                             }),
                             external_address_types: AddressTypeSet::only(
                                 c_external_1.address.address_type(),
@@ -695,6 +733,7 @@ impl DiscoveryContext {
                         ddi: DetectedDialInfo::Detected(DialInfoDetail {
                             dial_info: external_mapped_dial_info.clone(),
                             class: DialInfoClass::Mapped,
+                            preference: 0,
                         }),
                         external_address_types: AddressTypeSet::only(
                             external_mapped_dial_info.address_type(),
@@ -1,20 +1,33 @@
 mod discovery_context;
 mod igd_manager;
 mod network_class_discovery;
+mod network_ipc;
+#[cfg(any(test, feature = "sim-network"))]
+mod network_sim;
 mod network_tcp;
 mod network_udp;
 mod protocol;
+mod public_address_resolve;
 mod start_protocols;
+mod tls_certificate;
 
 use super::*;
 use crate::routing_table::*;
 use connection_manager::*;
 use discovery_context::*;
 use network_tcp::*;
+pub(in crate::network_manager) use protocol::http::{
+    HttpApplicationRequest, HttpApplicationResource, HttpProtocolHandler,
+};
+use protocol::ipc::IpcProtocolHandler;
+#[cfg(any(test, feature = "sim-network"))]
+pub use protocol::sim::{SimLink, SimNetwork, SIM_UNBOUND_ADDRESS};
 use protocol::tcp::RawTcpProtocolHandler;
-use protocol::udp::RawUdpProtocolHandler;
+pub(in crate::network_manager) use protocol::udp::RawUdpProtocolHandler;
 use protocol::ws::WebsocketProtocolHandler;
 pub(in crate::network_manager) use protocol::*;
+use public_address_resolve::*;
+pub(in crate::network_manager) use tls_certificate::*;
 
 use async_tls::TlsAcceptor;
 use futures_util::StreamExt;
@@ -24,18 +37,23 @@ use std::fs::File;
 use std::io;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 /////////////////////////////////////////////////////////////////
 
 pub const PEEK_DETECT_LEN: usize = 64;
+/// How often to check if the TLS certificate files have been replaced
+const TLS_CERTIFICATE_CHECK_INTERVAL_SECS: u32 = 60;
+/// How often to resolve public addresses configured as hostnames again, to follow dynamic DNS
+const PUBLIC_ADDRESS_RESOLVE_INTERVAL_SECS: u32 = 300;
 
 cfg_if! {
     if #[cfg(all(feature = "unstable-blockstore", feature="unstable-tunnels"))] {
-        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 9;
+        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 14;
     } else if #[cfg(any(feature = "unstable-blockstore", feature="unstable-tunnels"))] {
-        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 8;
+        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 13;
     } else  {
-        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 7;
+        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 12;
     }
 }
 pub const PUBLIC_INTERNET_CAPABILITIES: [Capability; PUBLIC_INTERNET_CAPABILITIES_LEN] = [
@@ -47,21 +65,31 @@ pub const PUBLIC_INTERNET_CAPABILITIES: [Capability; PUBLIC_INTERNET_CAPABILITIE
     CAP_VALIDATE_DIAL_INFO,
     CAP_DHT,
     CAP_DHT_WATCH,
+    CAP_DHT_COMPRESSION,
+    CAP_DHT_BATCH,
+    CAP_DHT_DIGEST,
     CAP_APPMESSAGE,
+    CAP_ENVELOPE_COMPRESSION,
+    CAP_BUSY_ANSWER,
     #[cfg(feature = "unstable-blockstore")]
     CAP_BLOCKSTORE,
 ];
 
 #[cfg(feature = "unstable-blockstore")]
-const LOCAL_NETWORK_CAPABILITIES_LEN: usize = 5;
+const LOCAL_NETWORK_CAPABILITIES_LEN: usize = 10;
 #[cfg(not(feature = "unstable-blockstore"))]
-const LOCAL_NETWORK_CAPABILITIES_LEN: usize = 4;
+const LOCAL_NETWORK_CAPABILITIES_LEN: usize = 9;
 
 pub const LOCAL_NETWORK_CAPABILITIES: [Capability; LOCAL_NETWORK_CAPABILITIES_LEN] = [
     CAP_RELAY,
     CAP_DHT,
     CAP_DHT_WATCH,
+    CAP_DHT_COMPRESSION,
+    CAP_DHT_BATCH,
+    CAP_DHT_DIGEST,
     CAP_APPMESSAGE,
+    CAP_ENVELOPE_COMPRESSION,
+    CAP_BUSY_ANSWER,
     #[cfg(feature = "unstable-blockstore")]
     CAP_BLOCKSTORE,
 ];
@@ -80,6 +108,8 @@ struct NetworkInner {
     protocol_config: ProtocolConfig,
     /// set of statically configured protocols with public dialinfo
     static_public_dialinfo: ProtocolTypeSet,
+    /// statically configured public addresses that are hostnames, and what they resolved to
+    resolved_public_addresses: Vec<ResolvedPublicAddress>,
     /// join handles for all the low level network background tasks
     join_handles: Vec<MustJoinHandle<()>>,
     /// stop source for shutting down the low level network background tasks
@@ -109,17 +139,22 @@ struct NetworkInner {
     bound_first_udp: BTreeMap<u16, (Option<socket2::Socket>, Option<socket2::Socket>)>,
     /// mapping of protocol handlers to accept messages from a set of bound socket addresses
     inbound_udp_protocol_handlers: BTreeMap<SocketAddr, RawUdpProtocolHandler>,
-    /// outbound udp protocol handler for udpv4
-    outbound_udpv4_protocol_handler: Option<RawUdpProtocolHandler>,
-    /// outbound udp protocol handler for udpv6
-    outbound_udpv6_protocol_handler: Option<RawUdpProtocolHandler>,
+    /// pool of outbound udp protocol handlers for udpv4, all bound to the same port
+    outbound_udpv4_protocol_handlers: Vec<RawUdpProtocolHandler>,
+    /// pool of outbound udp protocol handlers for udpv6, all bound to the same port
+    outbound_udpv6_protocol_handlers: Vec<RawUdpProtocolHandler>,
     /// tcp socket record for bound-first sockets, which are used to guarantee a port is available before
     /// creating a 'reuseport' socket there. we don't want to pick ports that other programs are using
     bound_first_tcp: BTreeMap<u16, (Option<socket2::Socket>, Option<socket2::Socket>)>,
     /// TLS handling socket controller
     tls_acceptor: Option<TlsAcceptor>,
+    /// modification times of the TLS certificate and private key files the tls acceptor was loaded from
+    tls_certificate_modified: Option<(SystemTime, SystemTime)>,
     /// Multiplexer record for protocols on low level TCP sockets
     listener_states: BTreeMap<SocketAddr, Arc<RwLock<ListenerState>>>,
+    /// address we listen at on the simulated network, if the node runs on one
+    #[cfg(any(test, feature = "sim-network"))]
+    sim_address: Option<SocketAddr>,
 }
 
 struct NetworkUnlockedInner {
@@ -129,10 +164,15 @@ struct NetworkUnlockedInner {
     connection_manager: ConnectionManager,
     // Network
     interfaces: NetworkInterfaces,
+    /// reassembly state and outbound sequence numbers shared by every udp socket, since with
+    /// SO_REUSEPORT the kernel may deliver the fragments of one message to any socket of the pool
+    udp_assembly_buffer: AssemblyBuffer,
     // Background processes
     update_network_class_task: TickTask<EyreReport>,
     network_interfaces_task: TickTask<EyreReport>,
     upnp_task: TickTask<EyreReport>,
+    tls_certificate_task: TickTask<EyreReport>,
+    public_address_resolve_task: TickTask<EyreReport>,
 
     // Managers
     igd_manager: igd_manager::IGDManager,
@@ -155,6 +195,7 @@ impl Network {
             public_dial_info_check_punishment: None,
             protocol_config: Default::default(),
             static_public_dialinfo: ProtocolTypeSet::empty(),
+            resolved_public_addresses: Vec::new(),
             join_handles: Vec::new(),
             stop_source: None,
             udp_port: 0u16,
@@ -166,11 +207,14 @@ impl Network {
             enable_ipv6_local: false,
             bound_first_udp: BTreeMap::new(),
             inbound_udp_protocol_handlers: BTreeMap::new(),
-            outbound_udpv4_protocol_handler: None,
-            outbound_udpv6_protocol_handler: None,
+            outbound_udpv4_protocol_handlers: Vec::new(),
+            outbound_udpv6_protocol_handlers: Vec::new(),
             bound_first_tcp: BTreeMap::new(),
             tls_acceptor: None,
+            tls_certificate_modified: None,
             listener_states: BTreeMap::new(),
+            #[cfg(any(test, feature = "sim-network"))]
+            sim_address: None,
         }
     }
 
@@ -185,9 +229,12 @@ impl Network {
             routing_table,
             connection_manager,
             interfaces: NetworkInterfaces::new(),
+            udp_assembly_buffer: AssemblyBuffer::new(),
             update_network_class_task: TickTask::new(1),
             network_interfaces_task: TickTask::new(5),
             upnp_task: TickTask::new(1),
+            tls_certificate_task: TickTask::new(TLS_CERTIFICATE_CHECK_INTERVAL_SECS),
+            public_address_resolve_task: TickTask::new(PUBLIC_ADDRESS_RESOLVE_INTERVAL_SECS),
             igd_manager: igd_manager::IGDManager::new(config.clone()),
         }
     }
@@ -232,6 +279,24 @@ impl Network {
                 .upnp_task
                 .set_routine(move |s, l, t| Box::pin(this2.clone().upnp_task_routine(s, l, t)));
         }
+        // Set tls certificate tick task
+        {
+            let this2 = this.clone();
+            this.unlocked_inner
+                .tls_certificate_task
+                .set_routine(move |s, l, t| {
+                    Box::pin(this2.clone().tls_certificate_task_routine(s, l, t))
+                });
+        }
+        // Set public address resolve tick task
+        {
+            let this2 = this.clone();
+            this.unlocked_inner
+                .public_address_resolve_task
+                .set_routine(move |s, l, t| {
+                    Box::pin(this2.clone().public_address_resolve_task_routine(s, l, t))
+                });
+        }
 
         this
     }
@@ -276,6 +341,32 @@ impl Network {
         ))
     }
 
+    /// Get when the configured TLS certificate expires, if it can be read
+    fn load_certificate_expiration(&self) -> Option<Timestamp> {
+        let certificate_path = self.config.get().network.tls.certificate_path.clone();
+        let certs = Self::load_certs(&PathBuf::from(certificate_path)).ok()?;
+        certificate_expiration(&certs.first()?.0)
+    }
+
+    /// Check if the configured TLS certificate has expired
+    fn is_certificate_expired(&self) -> bool {
+        self.load_certificate_expiration()
+            .map(|x| x <= get_aligned_timestamp())
+            .unwrap_or_default()
+    }
+
+    /// Get when the configured TLS certificate and private key files were last modified
+    fn load_certificate_modified(&self) -> Option<(SystemTime, SystemTime)> {
+        let c = self.config.get();
+        let certificate_modified = std::fs::metadata(&c.network.tls.certificate_path)
+            .and_then(|m| m.modified())
+            .ok()?;
+        let private_key_modified = std::fs::metadata(&c.network.tls.private_key_path)
+            .and_then(|m| m.modified())
+            .ok()?;
+        Some((certificate_modified, private_key_modified))
+    }
+
     fn load_server_config(&self) -> io::Result<ServerConfig> {
         let c = self.config.get();
         //
@@ -332,6 +423,15 @@ impl Network {
         }
     }
 
+    /// The Tor SOCKS proxy to dial outbound connections through, if Tor is enabled
+    pub fn tor_socks_address(&self) -> Option<SocketAddr> {
+        let c = self.config.get();
+        if !c.network.tor.enabled {
+            return None;
+        }
+        SocketAddr::from_str(&c.network.tor.socks_address).ok()
+    }
+
     pub fn get_local_port(&self, protocol_type: ProtocolType) -> Option<u16> {
         let inner = self.inner.lock();
         let local_port = match protocol_type {
@@ -346,6 +446,17 @@ impl Network {
     pub fn get_preferred_local_address(&self, dial_info: &DialInfo) -> Option<SocketAddr> {
         let inner = self.inner.lock();
 
+        // Simulated connections come from the address we listen at on the simulated network
+        #[cfg(any(test, feature = "sim-network"))]
+        if let Some(sim_address) = inner.sim_address {
+            if matches!(
+                dial_info.protocol_type(),
+                ProtocolType::TCP | ProtocolType::UDP
+            ) {
+                return Some(sim_address);
+            }
+        }
+
         let local_port = match dial_info.protocol_type() {
             ProtocolType::UDP => inner.udp_port,
             ProtocolType::TCP => inner.tcp_port,
@@ -388,19 +499,31 @@ impl Network {
             return Ok(false);
         }
 
+        // Re-detect our dial info right away rather than waiting for it to be found invalid
         self.inner.lock().needs_public_dial_info_check = true;
 
+        // Move our relay and recently active peers over to the new addresses.
+        // Done in the background because the status pings can take as long as their timeouts,
+        // and the network interfaces tick should not wait on them
+        let network_manager = self.network_manager();
+        spawn_detached(
+            async move { network_manager.migrate_network().await }.instrument(Span::current()),
+        );
+
         Ok(true)
     }
 
     ////////////////////////////////////////////////////////////
 
     // Record DialInfo failures
-    pub async fn record_dial_info_failure<T, F: Future<Output = EyreResult<NetworkResult<T>>>>(
+    pub async fn record_dial_info_failure<
+        T,
+        F: Future<Output = NetworkErrorResult<NetworkResult<T>>>,
+    >(
         &self,
         dial_info: DialInfo,
         fut: F,
-    ) -> EyreResult<NetworkResult<T>> {
+    ) -> NetworkErrorResult<NetworkResult<T>> {
         let network_result = fut.await?;
         if matches!(network_result, NetworkResult::NoConnection(_)) {
             self.network_manager()
@@ -419,7 +542,7 @@ impl Network {
         &self,
         dial_info: DialInfo,
         data: Vec<u8>,
-    ) -> EyreResult<NetworkResult<()>> {
+    ) -> NetworkErrorResult<NetworkResult<()>> {
         self.record_dial_info_failure(dial_info.clone(), async move {
             let data_len = data.len();
             let connect_timeout_ms = {
@@ -427,51 +550,85 @@ impl Network {
                 c.network.connection_initial_timeout_ms
             };
 
+            let tor_socks_address = self.tor_socks_address();
+
             if self
                 .network_manager()
                 .address_filter()
                 .is_ip_addr_punished(dial_info.address().ip_addr())
             {
-                return Ok(NetworkResult::no_connection_other("punished"));
+                return NetworkError::filtered_by_policy("punished").into_network_result();
+            }
+            if tor_socks_address.is_some() && dial_info.protocol_type() == ProtocolType::UDP {
+                return NetworkError::filtered_by_policy("udp is disabled with tor")
+                    .into_network_result();
+            }
+            if tor_socks_address.is_none() && dial_info.is_onion() {
+                return NetworkError::filtered_by_policy("onion dial info requires tor")
+                    .into_network_result();
             }
 
             match dial_info.protocol_type() {
                 ProtocolType::UDP => {
                     let peer_socket_addr = dial_info.to_socket_addr();
-                    let h = RawUdpProtocolHandler::new_unspecified_bound_handler(&peer_socket_addr)
-                        .await
-                        .wrap_err("create socket failure")?;
+                    let h = RawUdpProtocolHandler::new_unspecified_bound_handler(
+                        &peer_socket_addr,
+                        #[cfg(any(test, feature = "sim-network"))]
+                        self.config.sim_network(),
+                    )
+                    .await
+                    .map_err(NetworkError::from_io_error)?;
                     let _ = network_result_try!(h
                         .send_message(data, peer_socket_addr)
                         .await
                         .map(NetworkResult::Value)
-                        .wrap_err("send message failure")?);
+                        .classified()?);
                 }
                 ProtocolType::TCP => {
-                    let peer_socket_addr = dial_info.to_socket_addr();
-                    let pnc = network_result_try!(RawTcpProtocolHandler::connect(
-                        None,
-                        peer_socket_addr,
-                        connect_timeout_ms
-                    )
-                    .await
-                    .wrap_err("connect failure")?);
-                    network_result_try!(pnc.send(data).await.wrap_err("send failure")?);
+                    let pnc = network_result_try!(match (dial_info.is_ipc(), tor_socks_address) {
+                        (true, _) => IpcProtocolHandler::connect(&dial_info, connect_timeout_ms)
+                            .await
+                            .classified()?,
+                        (false, Some(proxy)) => RawTcpProtocolHandler::connect_via_socks(
+                            proxy,
+                            &dial_info,
+                            connect_timeout_ms
+                        )
+                        .await
+                        .classified()?,
+                        (false, None) => {
+                            let peer_socket_addr = dial_info.to_socket_addr();
+                            RawTcpProtocolHandler::connect(
+                                None,
+                                peer_socket_addr,
+                                connect_timeout_ms,
+                                #[cfg(any(test, feature = "sim-network"))]
+                                self.config.sim_network(),
+                            )
+                            .await
+                            .classified()?
+                        }
+                    });
+                    network_result_try!(pnc.send(data).await.classified()?);
                 }
                 ProtocolType::WS | ProtocolType::WSS => {
                     let pnc = network_result_try!(WebsocketProtocolHandler::connect(
                         None,
                         &dial_info,
-                        connect_timeout_ms
+                        connect_timeout_ms,
+                        tor_socks_address,
                     )
                     .await
-                    .wrap_err("connect failure")?);
-                    network_result_try!(pnc.send(data).await.wrap_err("send failure")?);
+                    .classified()?);
+                    network_result_try!(pnc.send(data).await.classified()?);
                 }
             }
             // Network accounting
-            self.network_manager()
-                .stats_packet_sent(dial_info.ip_addr(), ByteCount::new(data_len as u64));
+            self.network_manager().stats_packet_sent(
+                dial_info.ip_addr(),
+                dial_info.protocol_type(),
+                ByteCount::new(data_len as u64),
+            );
 
             Ok(NetworkResult::Value(()))
         })
@@ -489,7 +646,7 @@ impl Network {
         dial_info: DialInfo,
         data: Vec<u8>,
         timeout_ms: u32,
-    ) -> EyreResult<NetworkResult<Vec<u8>>> {
+    ) -> NetworkErrorResult<NetworkResult<Vec<u8>>> {
         self.record_dial_info_failure(dial_info.clone(), async move {
             let data_len = data.len();
             let connect_timeout_ms = {
@@ -497,44 +654,66 @@ impl Network {
                 c.network.connection_initial_timeout_ms
             };
 
+            let tor_socks_address = self.tor_socks_address();
+
             if self
                 .network_manager()
                 .address_filter()
                 .is_ip_addr_punished(dial_info.address().ip_addr())
             {
-                return Ok(NetworkResult::no_connection_other("punished"));
+                return NetworkError::filtered_by_policy("punished").into_network_result();
+            }
+            if tor_socks_address.is_some() && dial_info.protocol_type() == ProtocolType::UDP {
+                return NetworkError::filtered_by_policy("udp is disabled with tor")
+                    .into_network_result();
+            }
+            if tor_socks_address.is_none() && dial_info.is_onion() {
+                return NetworkError::filtered_by_policy("onion dial info requires tor")
+                    .into_network_result();
             }
 
             match dial_info.protocol_type() {
                 ProtocolType::UDP => {
                     let peer_socket_addr = dial_info.to_socket_addr();
-                    let h = RawUdpProtocolHandler::new_unspecified_bound_handler(&peer_socket_addr)
-                        .await
-                        .wrap_err("create socket failure")?;
+                    let h = RawUdpProtocolHandler::new_unspecified_bound_handler(
+                        &peer_socket_addr,
+                        #[cfg(any(test, feature = "sim-network"))]
+                        self.config.sim_network(),
+                    )
+                    .await
+                    .map_err(NetworkError::from_io_error)?;
                     network_result_try!(h
                         .send_message(data, peer_socket_addr)
                         .await
-                        .wrap_err("send message failure")?);
-                    self.network_manager()
-                        .stats_packet_sent(dial_info.ip_addr(), ByteCount::new(data_len as u64));
+                        .classified()?);
+                    self.network_manager().stats_packet_sent(
+                        dial_info.ip_addr(),
+                        dial_info.protocol_type(),
+                        ByteCount::new(data_len as u64),
+                    );
 
                     // receive single response
                     let mut out = vec![0u8; MAX_MESSAGE_SIZE];
-                    let (recv_len, recv_addr) = network_result_try!(timeout(
+                    let (recv_len, recv_addr) = network_result_try!(network_result_try!(timeout(
                         timeout_ms,
                         h.recv_message(&mut out).instrument(Span::current())
                     )
                     .await
                     .into_network_result())
-                    .wrap_err("recv_message failure")?;
+                    .map(NetworkResult::Value)
+                    .classified()?);
 
                     let recv_socket_addr = recv_addr.remote_address().socket_addr();
-                    self.network_manager()
-                        .stats_packet_rcvd(recv_socket_addr.ip(), ByteCount::new(recv_len as u64));
+                    self.network_manager().stats_packet_rcvd(
+                        recv_socket_addr.ip(),
+                        recv_addr.protocol_type(),
+                        ByteCount::new(recv_len as u64),
+                    );
 
                     // if the from address is not the same as the one we sent to, then drop this
                     if recv_socket_addr != peer_socket_addr {
-                        bail!("wrong address");
+                        return NetworkError::invalid_message("wrong address")
+                            .into_network_result();
                     }
                     out.resize(recv_len, 0u8);
                     Ok(NetworkResult::Value(out))
@@ -542,35 +721,62 @@ impl Network {
                 ProtocolType::TCP | ProtocolType::WS | ProtocolType::WSS => {
                     let pnc = network_result_try!(match dial_info.protocol_type() {
                         ProtocolType::UDP => unreachable!(),
-                        ProtocolType::TCP => {
-                            let peer_socket_addr = dial_info.to_socket_addr();
-                            RawTcpProtocolHandler::connect(
-                                None,
-                                peer_socket_addr,
+                        ProtocolType::TCP => match (dial_info.is_ipc(), tor_socks_address) {
+                            (true, _) => {
+                                IpcProtocolHandler::connect(&dial_info, connect_timeout_ms)
+                                    .await
+                                    .classified()?
+                            }
+                            (false, Some(proxy)) => RawTcpProtocolHandler::connect_via_socks(
+                                proxy,
+                                &dial_info,
                                 connect_timeout_ms,
                             )
                             .await
-                            .wrap_err("connect failure")?
-                        }
-                        ProtocolType::WS | ProtocolType::WSS => {
-                            WebsocketProtocolHandler::connect(None, &dial_info, connect_timeout_ms)
+                            .classified()?,
+                            (false, None) => {
+                                let peer_socket_addr = dial_info.to_socket_addr();
+                                RawTcpProtocolHandler::connect(
+                                    None,
+                                    peer_socket_addr,
+                                    connect_timeout_ms,
+                                    #[cfg(any(test, feature = "sim-network"))]
+                                    self.config.sim_network(),
+                                )
                                 .await
-                                .wrap_err("connect failure")?
+                                .classified()?
+                            }
+                        },
+                        ProtocolType::WS | ProtocolType::WSS => {
+                            WebsocketProtocolHandler::connect(
+                                None,
+                                &dial_info,
+                                connect_timeout_ms,
+                                tor_socks_address,
+                            )
+                            .await
+                            .classified()?
                         }
                     });
 
-                    network_result_try!(pnc.send(data).await.wrap_err("send failure")?);
-                    self.network_manager()
-                        .stats_packet_sent(dial_info.ip_addr(), ByteCount::new(data_len as u64));
+                    network_result_try!(pnc.send(data).await.classified()?);
+                    self.network_manager().stats_packet_sent(
+                        dial_info.ip_addr(),
+                        dial_info.protocol_type(),
+                        ByteCount::new(data_len as u64),
+                    );
 
                     let out =
                         network_result_try!(network_result_try!(timeout(timeout_ms, pnc.recv())
                             .await
                             .into_network_result())
-                        .wrap_err("recv failure")?);
+                        .classified()?);
 
-                    self.network_manager()
-                        .stats_packet_rcvd(dial_info.ip_addr(), ByteCount::new(out.len() as u64));
+                    self.network_manager().stats_packet_rcvd(
+                        dial_info.ip_addr(),
+                        dial_info.protocol_type(),
+                        ByteCount::new(out.len() as u64),
+                    );
 
                     Ok(NetworkResult::Value(out))
                 }
@@ -584,7 +790,8 @@ impl Network {
         &self,
         flow: Flow,
         data: Vec<u8>,
-    ) -> EyreResult<SendDataToExistingFlowResult> {
+        priority: SendPriority,
+    ) -> NetworkErrorResult<SendDataToExistingFlowResult> {
         let data_len = data.len();
 
         // Handle connectionless protocol
@@ -598,12 +805,15 @@ impl Network {
                 network_result_value_or_log!(ph.clone()
                     .send_message(data.clone(), peer_socket_addr)
                     .await
-                    .wrap_err("sending data to existing connection")? => [ format!(": data.len={}, flow={:?}", data.len(), flow) ] 
+                    .classified()? => [ format!(": data.len={}, flow={:?}", data.len(), flow) ] 
                     { return Ok(SendDataToExistingFlowResult::NotSent(data)); } );
 
                 // Network accounting
-                self.network_manager()
-                    .stats_packet_sent(peer_socket_addr.ip(), ByteCount::new(data_len as u64));
+                self.network_manager().stats_packet_sent(
+                    peer_socket_addr.ip(),
+                    flow.protocol_type(),
+                    ByteCount::new(data_len as u64),
+                );
 
                 // Data was consumed
                 let unique_flow = UniqueFlow {
@@ -619,11 +829,12 @@ impl Network {
         // Try to send to the exact existing connection if one exists
         if let Some(conn) = self.connection_manager().get_connection(flow) {
             // connection exists, send over it
-            match conn.send_async(data).await {
+            match conn.send_async(data, priority).await {
                 ConnectionHandleSendResult::Sent => {
                     // Network accounting
                     self.network_manager().stats_packet_sent(
                         flow.remote().socket_addr().ip(),
+                        flow.protocol_type(),
                         ByteCount::new(data_len as u64),
                     );
 
@@ -649,7 +860,8 @@ impl Network {
         &self,
         dial_info: DialInfo,
         data: Vec<u8>,
-    ) -> EyreResult<NetworkResult<UniqueFlow>> {
+        priority: SendPriority,
+    ) -> NetworkErrorResult<NetworkResult<UniqueFlow>> {
         self.record_dial_info_failure(dial_info.clone(), async move {
             let data_len = data.len();
             let unique_flow;
@@ -659,15 +871,16 @@ impl Network {
                 let ph = match self.find_best_udp_protocol_handler(&peer_socket_addr, &None) {
                     Some(ph) => ph,
                     None => {
-                        return Ok(NetworkResult::no_connection_other(
+                        return NetworkError::unreachable(
                             "no appropriate UDP protocol handler for dial_info",
-                        ));
+                        )
+                        .into_network_result();
                     }
                 };
                 let flow = network_result_try!(ph
                     .send_message(data, peer_socket_addr)
                     .await
-                    .wrap_err("failed to send data to dial info")?);
+                    .classified()?);
                 unique_flow = UniqueFlow {
                     flow,
                     connection_id: None,
@@ -680,7 +893,9 @@ impl Network {
                         .await?
                 );
 
-                if let ConnectionHandleSendResult::NotSent(_) = conn.send_async(data).await {
+                if let ConnectionHandleSendResult::NotSent(_) =
+                    conn.send_async(data, priority).await
+                {
                     return Ok(NetworkResult::NoConnection(io::Error::new(
                         io::ErrorKind::ConnectionReset,
                         "failed to send",
@@ -690,8 +905,11 @@ impl Network {
             }
 
             // Network accounting
-            self.network_manager()
-                .stats_packet_sent(dial_info.ip_addr(), ByteCount::new(data_len as u64));
+            self.network_manager().stats_packet_sent(
+                dial_info.ip_addr(),
+                dial_info.protocol_type(),
+                ByteCount::new(data_len as u64),
+            );
 
             Ok(NetworkResult::value(unique_flow))
         })
@@ -729,6 +947,7 @@ impl Network {
         self.unlocked_inner
             .routing_table
             .configure_local_network_routing_domain(local_networks);
+        self.network_manager().reset_stats_routing_domains();
 
         // determine if we have ipv4/ipv6 addresses
         {
@@ -752,6 +971,7 @@ impl Network {
         }
 
         // Build our protocol config to share it with other nodes
+        let relay_only = self.network_manager().is_relay_only();
         let protocol_config = {
             let mut inner = self.inner.lock();
 
@@ -803,20 +1023,39 @@ impl Network {
                     family_local.insert(AddressType::IPV6);
                 }
 
+                // When using Tor, everything goes through the proxy so any address family can be
+                // reached, UDP can not be used, and we only accept connections through our onion service
+                if c.network.tor.enabled {
+                    outbound.remove(ProtocolType::UDP);
+                    inbound.clear();
+                    if c.network.protocol.tcp.listen && c.network.tor.onion_address.is_some() {
+                        inbound.insert(ProtocolType::TCP);
+                    }
+                    family_global = AddressTypeSet::all();
+                    family_local.clear();
+                }
+
+                // On a network that costs us to use, or in low power mode, nobody else gets to connect to us
+                // and everything we send goes out through our relay
+                if relay_only {
+                    outbound.remove(ProtocolType::UDP);
+                    inbound.clear();
+                }
+
                 // set up the routing table's network config
                 // if we have static public dialinfo, upgrade our network class
                 let public_internet_capabilities = {
                     PUBLIC_INTERNET_CAPABILITIES
                         .iter()
                         .copied()
-                        .filter(|cap| !c.capabilities.disable.contains(cap))
+                        .filter(|cap| !is_capability_disabled(&c, cap))
                         .collect::<Vec<Capability>>()
                 };
                 let local_network_capabilities = {
                     LOCAL_NETWORK_CAPABILITIES
                         .iter()
                         .copied()
-                        .filter(|cap| !c.capabilities.disable.contains(cap))
+                        .filter(|cap| !is_capability_disabled(&c, cap))
                         .collect::<Vec<Capability>>()
                 };
 
@@ -844,22 +1083,102 @@ impl Network {
             .routing_table
             .edit_routing_domain(RoutingDomain::LocalNetwork);
 
-        // start listeners
-        if protocol_config.inbound.contains(ProtocolType::UDP) {
-            self.start_udp_listeners(&mut editor_public_internet, &mut editor_local_network)
-                .await?;
-        }
-        if protocol_config.inbound.contains(ProtocolType::WS) {
-            self.start_ws_listeners(&mut editor_public_internet, &mut editor_local_network)
-                .await?;
+        // Serve the application landing page and health checks, unless we are hiding behind Tor
+        // or on a network that costs us to use. These go first so their handlers get to look at
+        // requests before the raw TCP handler claims every connection on a shared port.
+        let (http_enabled, https_enabled, tor_enabled) = {
+            let c = self.config.get();
+            (
+                c.network.application.http.enabled,
+                c.network.application.https.enabled,
+                c.network.tor.enabled,
+            )
+        };
+        if !tor_enabled && !relay_only {
+            if http_enabled {
+                self.start_http_listeners().await?;
+            }
+            if https_enabled {
+                self.start_https_listeners().await?;
+            }
         }
-        if protocol_config.inbound.contains(ProtocolType::WSS) {
-            self.start_wss_listeners(&mut editor_public_internet, &mut editor_local_network)
-                .await?;
+
+        // start listeners, keeping track of how each one went so it can be reported
+        let mut listener_statuses = Vec::new();
+        let mut listeners_res = Ok(());
+        for protocol_type in [
+            ProtocolType::UDP,
+            ProtocolType::WS,
+            ProtocolType::WSS,
+            ProtocolType::TCP,
+        ] {
+            if !protocol_config.inbound.contains(protocol_type) {
+                continue;
+            }
+            let certificate_expiration = if protocol_type == ProtocolType::WSS {
+                self.load_certificate_expiration()
+            } else {
+                None
+            };
+
+            // Browsers will refuse to connect if our certificate has expired, so don't pretend to be listening
+            // on WSS, but keep the other protocols going
+            if protocol_type == ProtocolType::WSS && self.is_certificate_expired() {
+                warn!("TLS certificate expired, not starting wss listeners");
+                listener_statuses.push(ListenerStatus {
+                    protocol_type,
+                    bound_addresses: Vec::new(),
+                    last_error: Some("TLS certificate expired".to_owned()),
+                    certificate_expiration,
+                });
+                continue;
+            }
+
+            let res = match protocol_type {
+                ProtocolType::UDP => {
+                    self.start_udp_listeners(&mut editor_public_internet, &mut editor_local_network)
+                        .await
+                }
+                ProtocolType::WS => {
+                    self.start_ws_listeners(&mut editor_public_internet, &mut editor_local_network)
+                        .await
+                }
+                ProtocolType::WSS => {
+                    self.start_wss_listeners(&mut editor_public_internet, &mut editor_local_network)
+                        .await
+                }
+                ProtocolType::TCP => {
+                    self.start_tcp_listeners(&mut editor_public_internet, &mut editor_local_network)
+                        .await
+                }
+            };
+            match res {
+                Ok(socket_addresses) => listener_statuses.push(ListenerStatus {
+                    protocol_type,
+                    bound_addresses: socket_addresses.iter().map(|sa| sa.to_string()).collect(),
+                    last_error: None,
+                    certificate_expiration,
+                }),
+                Err(e) => {
+                    listener_statuses.push(ListenerStatus {
+                        protocol_type,
+                        bound_addresses: Vec::new(),
+                        last_error: Some(e.to_string()),
+                        certificate_expiration,
+                    });
+                    listeners_res = Err(e);
+                    break;
+                }
+            }
         }
-        if protocol_config.inbound.contains(ProtocolType::TCP) {
-            self.start_tcp_listeners(&mut editor_public_internet, &mut editor_local_network)
-                .await?;
+        self.network_manager()
+            .set_listener_statuses(listener_statuses);
+        listeners_res?;
+
+        // Processes on this host can reach us over IPC, unless we are hiding behind Tor
+        let ipc_listen = self.config.get().network.protocol.ipc.listen;
+        if ipc_listen && !tor_enabled && !relay_only {
+            self.start_ipc_listener(&mut editor_local_network).await?;
         }
 
         // release caches of available listener ports
@@ -871,19 +1190,36 @@ impl Network {
             protocol_config.outbound,
             protocol_config.inbound,
             protocol_config.family_global,
-            protocol_config.public_internet_capabilities,
+            self.network_manager().without_withheld_capabilities(
+                RoutingDomain::PublicInternet,
+                protocol_config.public_internet_capabilities,
+            ),
         );
         editor_local_network.setup_network(
             protocol_config.outbound,
             protocol_config.inbound,
             protocol_config.family_local,
-            protocol_config.local_network_capabilities,
+            self.network_manager().without_withheld_capabilities(
+                RoutingDomain::LocalNetwork,
+                protocol_config.local_network_capabilities,
+            ),
         );
-        let detect_address_changes = {
+        let (detect_address_changes, tor_enabled) = {
             let c = self.config.get();
-            c.network.detect_address_changes
+            (c.network.detect_address_changes, c.network.tor.enabled)
         };
-        if !detect_address_changes {
+        if relay_only {
+            // Our network class is fixed while relay-only, as we accept no connections
+            editor_public_internet.set_network_class(Some(NetworkClass::OutboundOnly));
+        } else if tor_enabled {
+            // Our network class is fixed when using Tor, as we never detect our public addresses
+            let inner = self.inner.lock();
+            if inner.static_public_dialinfo.is_empty() {
+                editor_public_internet.set_network_class(Some(NetworkClass::OutboundOnly));
+            } else {
+                editor_public_internet.set_network_class(Some(NetworkClass::InboundCapable));
+            }
+        } else if !detect_address_changes {
             let inner = self.inner.lock();
             if !inner.static_public_dialinfo.is_empty() {
                 editor_public_internet.set_network_class(Some(NetworkClass::InboundCapable));
@@ -993,6 +1329,38 @@ impl Network {
         Ok(())
     }
 
+    #[instrument(level = "trace", skip(self), err)]
+    pub async fn tls_certificate_task_routine(
+        self,
+        _stop_token: StopToken,
+        _l: u64,
+        _t: u64,
+    ) -> EyreResult<()> {
+        // Only check the files if we have loaded them for a tls listener
+        let Some(loaded_modified) = self.inner.lock().tls_certificate_modified else {
+            return Ok(());
+        };
+        let Some(modified) = self.load_certificate_modified() else {
+            return Ok(());
+        };
+        if modified != loaded_modified {
+            info!("TLS certificate files changed, reloading");
+            self.reload_tls_certificates()?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self), err)]
+    pub async fn public_address_resolve_task_routine(
+        self,
+        _stop_token: StopToken,
+        _l: u64,
+        _t: u64,
+    ) -> EyreResult<()> {
+        self.check_resolved_public_addresses().await
+    }
+
     #[instrument(level = "trace", skip(self), err)]
     pub async fn upnp_task_routine(
         self,
@@ -1010,10 +1378,16 @@ impl Network {
     }
 
     pub async fn tick(&self) -> EyreResult<()> {
+        let relay_only = self.network_manager().is_relay_only();
         let (detect_address_changes, upnp) = {
             let config = self.network_manager().config();
             let c = config.get();
-            (c.network.detect_address_changes, c.network.upnp)
+            // Public address detection and port mapping would reveal our addresses when using Tor,
+            // and are pointless while relay-only as we accept no connections
+            (
+                c.network.detect_address_changes && !c.network.tor.enabled && !relay_only,
+                c.network.upnp && !c.network.tor.enabled && !relay_only,
+            )
         };
 
         // If we need to figure out our network class, tick the task for it
@@ -1060,6 +1434,19 @@ impl Network {
             self.unlocked_inner.upnp_task.tick().await?;
         }
 
+        // Pick up renewed TLS certificates
+        if !self.needs_restart() {
+            self.unlocked_inner.tls_certificate_task.tick().await?;
+        }
+
+        // Follow public addresses configured as hostnames when their addresses change
+        if !self.needs_restart() {
+            self.unlocked_inner
+                .public_address_resolve_task
+                .tick()
+                .await?;
+        }
+
         Ok(())
     }
 }
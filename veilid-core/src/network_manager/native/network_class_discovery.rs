@@ -12,9 +12,9 @@ impl Network {
             .unwrap_or_default();
 
         match ddi {
-            DetectedDialInfo::SymmetricNAT => {
-                // If we get any symmetric nat dialinfo, this whole network class is outbound only,
-                // and all dial info should be treated as invalid
+            DetectedDialInfo::InconsistentAddress | DetectedDialInfo::SymmetricNAT => {
+                // If our external address is not consistent or we are behind a symmetric NAT,
+                // this whole network class is outbound only, and all dial info should be treated as invalid
                 if !matches!(existing_network_class, NetworkClass::OutboundOnly) {
                     let mut editor = self
                         .routing_table()
@@ -39,7 +39,7 @@ impl Network {
                         did,
                     );
                 }
-                // We got a dial info, upgrade everything unless we are fixed to outbound only due to a symmetric nat
+                // We got a dial info, upgrade everything unless we are fixed to outbound only due to an inconsistent address or symmetric nat
                 if !matches!(existing_network_class, NetworkClass::OutboundOnly) {
                     // Get existing dial info for protocol/address type combination
                     let pt = did.dial_info.protocol_type();
@@ -283,6 +283,7 @@ impl Network {
                                     ProtocolType::WS,
                                 ),
                                 class: did.class,
+                                preference: 0,
                             });
                             // Add additional WS dialinfo
                             self.update_with_detected_dial_info(ws_ddi).await?;
@@ -0,0 +1,103 @@
+use super::*;
+
+cfg_if! {
+    if #[cfg(unix)] {
+        use super::protocol::ipc::*;
+        use super::protocol::sockets::*;
+        use super::protocol::tcp::RawTcpNetworkConnection;
+        use stop_token::future::FutureExt;
+
+        impl Network {
+            /// Listen for connections from other Veilid processes on this host over a unix domain socket
+            ///
+            /// The socket path is published as local network dial info. Peers only ever dial it when
+            /// they are running on the same host, so their messages never touch the network stack.
+            pub(super) async fn start_ipc_listener(
+                &self,
+                editor_local_network: &mut RoutingDomainEditor,
+            ) -> EyreResult<()> {
+                let listen_path = self.config.get().network.protocol.ipc.listen_path.clone();
+                let Some(host_id) = intf::host_id() else {
+                    bail!("IPC requires a host id, and none is available on this system");
+                };
+
+                // A socket file left behind by a process that exited can be replaced,
+                // but one that is still accepting connections belongs to someone else
+                if Path::new(&listen_path).exists() {
+                    if UnixStream::connect(&listen_path).await.is_ok() {
+                        bail!("IPC socket is already in use: {}", listen_path);
+                    }
+                    std::fs::remove_file(&listen_path)
+                        .wrap_err("failed to remove stale IPC socket")?;
+                }
+
+                #[cfg(feature = "rt-async-std")]
+                let listener = UnixListener::bind(&listen_path)
+                    .await
+                    .wrap_err("failed to bind IPC socket")?;
+                #[cfg(feature = "rt-tokio")]
+                let listener =
+                    UnixListener::bind(&listen_path).wrap_err("failed to bind IPC socket")?;
+                info!("IPC: starting listener at {}", listen_path);
+
+                // Hand accepted connections to the connection manager until we stop
+                let stop_token = self.inner.lock().stop_source.as_ref().unwrap().token();
+                let connection_manager = self.connection_manager();
+                let local_address = ipc_local_address(host_id);
+                let path = listen_path.clone();
+                let jh = spawn(async move {
+                    let mut next_port = 0u16;
+                    while let Ok(res) = listener.accept().timeout_at(stop_token.clone()).await {
+                        let us = match res {
+                            Ok((us, _)) => us,
+                            Err(e) => {
+                                log_net!(debug "IPC accept error: {}", e);
+                                continue;
+                            }
+                        };
+                        #[cfg(feature = "rt-tokio")]
+                        let us = us.compat();
+
+                        // Unix domain socket clients have no address,
+                        // so each connection gets its own port on our synthetic address
+                        next_port = next_port.wrapping_add(1).max(1);
+                        let peer_addr = PeerAddress::new(
+                            SocketAddress::new(local_address.address(), next_port),
+                            ProtocolType::TCP,
+                        );
+                        let conn = ProtocolNetworkConnection::RawTcp(RawTcpNetworkConnection::new(
+                            Flow::new(peer_addr, local_address),
+                            AsyncPeekStream::new(us),
+                        ));
+                        log_net!("IPC connection accepted on {}", path);
+                        if let Err(e) = connection_manager
+                            .on_accepted_protocol_network_connection(conn)
+                            .await
+                        {
+                            log_net!(error "failed to register new connection: {}", e);
+                        }
+                    }
+
+                    let _ = std::fs::remove_file(&path);
+                    log_net!(debug "exited IPC listener for {}", path);
+                });
+                self.add_to_join_handles(jh);
+
+                let di =
+                    DialInfo::try_ipc(host_id, listen_path).wrap_err("invalid IPC dial info")?;
+                editor_local_network.register_dial_info(di, DialInfoClass::Direct)?;
+
+                Ok(())
+            }
+        }
+    } else {
+        impl Network {
+            pub(super) async fn start_ipc_listener(
+                &self,
+                _editor_local_network: &mut RoutingDomainEditor,
+            ) -> EyreResult<()> {
+                bail!("IPC is only supported on unix platforms");
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use super::protocol::sim::SimNetwork;
+use super::*;
+use stop_token::future::FutureExt;
+
+/// Simulated nodes listen at exactly the address they are configured with
+fn sim_listen_address(listen_address: &str) -> EyreResult<SocketAddr> {
+    let sockaddrs = listen_address_to_socket_addrs(listen_address).map_err(|e| eyre!("{}", e))?;
+    let Some(addr) = sockaddrs.first().copied() else {
+        bail!("No valid listen address: {}", listen_address);
+    };
+    if addr.ip().is_unspecified() || addr.port() == 0 {
+        bail!(
+            "Simulated network needs a full listen address: {}",
+            listen_address
+        );
+    }
+    Ok(addr)
+}
+
+impl Network {
+    /// Listen for TCP connections on the simulated network instead of a real socket
+    ///
+    /// The listen address is used as-is and published as static public dial info,
+    /// since there are no interfaces or NATs to discover on the simulated network.
+    pub(super) async fn start_sim_tcp_listener(
+        &self,
+        sim_network: SimNetwork,
+        listen_address: String,
+        editor_public_internet: &mut RoutingDomainEditor,
+    ) -> EyreResult<Vec<SocketAddress>> {
+        let addr = sim_listen_address(&listen_address)?;
+
+        let receiver = sim_network
+            .listen(addr)
+            .wrap_err("failed to listen on simulated network")?;
+        {
+            let mut inner = self.inner.lock();
+            inner.tcp_port = addr.port();
+            inner.sim_address = Some(addr);
+        }
+        info!("TCP: starting simulated listener at {}", addr);
+
+        // Hand accepted connections to the connection manager until we stop
+        let this = self.clone();
+        let stop_token = self.inner.lock().stop_source.as_ref().unwrap().token();
+        let connection_manager = self.connection_manager();
+        let jh = spawn(async move {
+            while let Ok(Ok(conn)) = receiver.recv_async().timeout_at(stop_token.clone()).await {
+                let peer_addr = conn.flow().remote_address().socket_addr();
+                if this
+                    .network_manager()
+                    .address_filter()
+                    .is_ip_addr_punished(peer_addr.ip())
+                {
+                    continue;
+                }
+                log_net!("simulated TCP connection from: {}", peer_addr);
+                if let Err(e) = connection_manager
+                    .on_accepted_protocol_network_connection(ProtocolNetworkConnection::Sim(conn))
+                    .await
+                {
+                    log_net!(error "failed to register new connection: {}", e);
+                }
+            }
+
+            sim_network.unlisten(addr);
+            this.inner.lock().sim_address = None;
+            log_net!(debug "exited simulated listener for {}", addr);
+        });
+        self.add_to_join_handles(jh);
+
+        let socket_address = SocketAddress::from_socket_addr(addr);
+        editor_public_internet
+            .register_dial_info(DialInfo::tcp(socket_address), DialInfoClass::Direct)?;
+        self.inner
+            .lock()
+            .static_public_dialinfo
+            .insert(ProtocolType::TCP);
+
+        Ok(vec![socket_address])
+    }
+
+    /// Listen for UDP datagrams on the simulated network instead of a real socket
+    ///
+    /// The socket at the listen address is the whole outbound pool too, so peers see
+    /// datagrams from us come from the address they send theirs to.
+    pub(super) async fn start_sim_udp_listener(
+        &self,
+        sim_network: SimNetwork,
+        listen_address: String,
+        editor_public_internet: &mut RoutingDomainEditor,
+    ) -> EyreResult<Vec<SocketAddress>> {
+        let addr = sim_listen_address(&listen_address)?;
+
+        let socket = sim_network
+            .bind_udp(Some(addr))
+            .wrap_err("failed to bind on simulated network")?;
+        let protocol_handler = RawUdpProtocolHandler::new_sim(
+            socket,
+            self.unlocked_inner.udp_assembly_buffer.clone(),
+            Some(self.network_manager().address_filter()),
+        );
+        {
+            let mut inner = self.inner.lock();
+            inner.udp_port = addr.port();
+            inner.sim_address = Some(addr);
+            match addr {
+                SocketAddr::V4(_) => {
+                    inner.outbound_udpv4_protocol_handlers = vec![protocol_handler]
+                }
+                SocketAddr::V6(_) => {
+                    inner.outbound_udpv6_protocol_handlers = vec![protocol_handler]
+                }
+            }
+        }
+        info!("UDP: starting simulated listener at {}", addr);
+
+        self.create_udp_listener_tasks().await?;
+
+        let socket_address = SocketAddress::from_socket_addr(addr);
+        editor_public_internet
+            .register_dial_info(DialInfo::udp(socket_address), DialInfoClass::Direct)?;
+        self.inner
+            .lock()
+            .static_public_dialinfo
+            .insert(ProtocolType::UDP);
+
+        Ok(vec![socket_address])
+    }
+}
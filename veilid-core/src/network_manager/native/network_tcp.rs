@@ -30,14 +30,59 @@ impl Network {
             return Ok(ts.clone());
         }
 
+        let certificate_modified = self.load_certificate_modified();
         let server_config = self
             .load_server_config()
             .wrap_err("Couldn't create TLS configuration")?;
         let acceptor = TlsAcceptor::from(server_config);
-        self.inner.lock().tls_acceptor = Some(acceptor.clone());
+        {
+            let mut inner = self.inner.lock();
+            inner.tls_acceptor = Some(acceptor.clone());
+            inner.tls_certificate_modified = certificate_modified;
+        }
         Ok(acceptor)
     }
 
+    /// Load the TLS certificate and private key again and use them for new TLS connections
+    ///
+    /// Connections that have already been accepted keep the certificate they were accepted with.
+    /// Returns false if there are no TLS listeners to reload the certificate for.
+    pub fn reload_tls_certificates(&self) -> EyreResult<bool> {
+        let listener_states: Vec<_> = {
+            let inner = self.inner.lock();
+            if inner.tls_acceptor.is_none() {
+                return Ok(false);
+            }
+            inner.listener_states.values().cloned().collect()
+        };
+
+        // Get the modification times first so a change made while loading is picked up next time
+        let certificate_modified = self.load_certificate_modified();
+        let server_config = self
+            .load_server_config()
+            .wrap_err("Couldn't create TLS configuration")?;
+        let acceptor = TlsAcceptor::from(server_config);
+        {
+            let mut inner = self.inner.lock();
+            inner.tls_acceptor = Some(acceptor.clone());
+            inner.tls_certificate_modified = certificate_modified;
+        }
+        for ls in listener_states {
+            let mut ls = ls.write();
+            if ls.tls_acceptor.is_some() {
+                ls.tls_acceptor = Some(acceptor.clone());
+            }
+        }
+
+        self.network_manager().set_listener_certificate_expiration(
+            ProtocolType::WSS,
+            self.load_certificate_expiration(),
+        );
+
+        log_net!(debug "reloaded TLS certificate");
+        Ok(true)
+    }
+
     async fn try_tls_handlers(
         &self,
         tls_acceptor: &TlsAcceptor,
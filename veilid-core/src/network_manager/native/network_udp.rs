@@ -1,22 +1,30 @@
 use super::*;
 use sockets::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use stop_token::future::FutureExt;
 
 impl Network {
-    pub(super) async fn create_udp_listener_tasks(&self) -> EyreResult<()> {
-        // Spawn socket tasks
-        let mut task_count = {
+    /// Number of outbound udp sockets per address type, and of tasks receiving on them
+    fn udp_socket_pool_size(&self) -> usize {
+        let mut pool_size = {
             let c = self.config.get();
             c.network.protocol.udp.socket_pool_size
         };
-        if task_count == 0 {
-            task_count = get_concurrency() / 2;
-            if task_count == 0 {
-                task_count = 1;
+        if pool_size == 0 {
+            pool_size = get_concurrency() / 2;
+            if pool_size == 0 {
+                pool_size = 1;
             }
         }
+        pool_size as usize
+    }
+
+    pub(super) async fn create_udp_listener_tasks(&self) -> EyreResult<()> {
+        // Spawn socket tasks, one for each socket in the outbound pool
+        let task_count = self.udp_socket_pool_size();
         log_net!("task_count: {}", task_count);
-        for _ in 0..task_count {
+        for task_index in 0..task_count {
             log_net!("Spawning UDP listener task");
 
             ////////////////////////////////////////////////////////////
@@ -26,7 +34,7 @@ impl Network {
             let jh = spawn(async move {
                 log_net!("UDP listener task spawned");
 
-                // Collect all our protocol handlers into a vector
+                // Collect the inbound protocol handlers and this task's outbound pool sockets into a vector
                 let mut protocol_handlers: Vec<RawUdpProtocolHandler> = this
                     .inner
                     .lock()
@@ -34,10 +42,22 @@ impl Network {
                     .values()
                     .cloned()
                     .collect();
-                if let Some(ph) = this.inner.lock().outbound_udpv4_protocol_handler.clone() {
+                if let Some(ph) = this
+                    .inner
+                    .lock()
+                    .outbound_udpv4_protocol_handlers
+                    .get(task_index)
+                    .cloned()
+                {
                     protocol_handlers.push(ph);
                 }
-                if let Some(ph) = this.inner.lock().outbound_udpv6_protocol_handler.clone() {
+                if let Some(ph) = this
+                    .inner
+                    .lock()
+                    .outbound_udpv6_protocol_handlers
+                    .get(task_index)
+                    .cloned()
+                {
                     protocol_handlers.push(ph);
                 }
 
@@ -69,6 +89,7 @@ impl Network {
                                     // Network accounting
                                     network_manager.stats_packet_rcvd(
                                         flow.remote_address().ip_addr(),
+                                        flow.protocol_type(),
                                         ByteCount::new(size as u64),
                                     );
 
@@ -115,71 +136,92 @@ impl Network {
     }
 
     pub(super) async fn create_udp_outbound_sockets(&self) -> EyreResult<()> {
-        let mut inner = self.inner.lock();
-        let mut port = inner.udp_port;
-        // v4
-        let socket_addr_v4 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), port);
-        if let Ok(socket) = new_bound_shared_udp_socket(socket_addr_v4) {
-            // Pull the port if we randomly bound, so v6 can be on the same port
-            port = socket
-                .local_addr()
-                .wrap_err("failed to get local address")?
-                .as_socket_ipv4()
-                .ok_or_else(|| eyre!("expected ipv4 address type"))?
-                .port();
-
-            // Make an async UdpSocket from the socket2 socket
-            let std_udp_socket: std::net::UdpSocket = socket.into();
-            cfg_if! {
-                if #[cfg(feature="rt-async-std")] {
-                    let udp_socket = UdpSocket::from(std_udp_socket);
-                } else if #[cfg(feature="rt-tokio")] {
-                    std_udp_socket.set_nonblocking(true).expect("failed to set nonblocking");
-                    let udp_socket = UdpSocket::from_std(std_udp_socket).wrap_err("failed to make outbound v4 tokio udpsocket")?;
-                } else {
-                    compile_error!("needs executor implementation")
-                }
-            }
-            let socket_arc = Arc::new(udp_socket);
+        let pool_size = self.udp_socket_pool_size();
+        let mut port = self.inner.lock().udp_port;
 
-            // Create protocol handler
-            let udpv4_handler = RawUdpProtocolHandler::new(
-                socket_arc,
-                Some(self.network_manager().address_filter()),
-            );
+        // v4
+        let mut udpv4_handlers = Vec::with_capacity(pool_size);
+        for _ in 0..pool_size {
+            let socket_addr_v4 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), port);
+            let Some((udpv4_handler, local_port)) =
+                self.create_udp_outbound_socket(socket_addr_v4)?
+            else {
+                break;
+            };
+            // Pull the port if we randomly bound, so the rest of the pool and v6 can be on the same port
+            port = local_port;
+            udpv4_handlers.push(udpv4_handler);
+        }
 
-            inner.outbound_udpv4_protocol_handler = Some(udpv4_handler);
+        // v6
+        let mut udpv6_handlers = Vec::with_capacity(pool_size);
+        for _ in 0..pool_size {
+            let socket_addr_v6 =
+                SocketAddr::new(IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0)), port);
+            let Some((udpv6_handler, local_port)) =
+                self.create_udp_outbound_socket(socket_addr_v6)?
+            else {
+                break;
+            };
+            // In case there was no v4 to pick the port
+            port = local_port;
+            udpv6_handlers.push(udpv6_handler);
         }
-        //v6
-        let socket_addr_v6 =
-            SocketAddr::new(IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0)), port);
-        if let Ok(socket) = new_bound_shared_udp_socket(socket_addr_v6) {
-            // Make an async UdpSocket from the socket2 socket
-            let std_udp_socket: std::net::UdpSocket = socket.into();
-            cfg_if! {
-                if #[cfg(feature="rt-async-std")] {
-                    let udp_socket = UdpSocket::from(std_udp_socket);
-                } else if #[cfg(feature="rt-tokio")] {
-                    std_udp_socket.set_nonblocking(true).expect("failed to set nonblocking");
-                    let udp_socket = UdpSocket::from_std(std_udp_socket).wrap_err("failed to make outbound v6 tokio udpsocket")?;
-                } else {
-                    compile_error!("needs executor implementation")
-                }
-            }
-            let socket_arc = Arc::new(udp_socket);
 
-            // Create protocol handler
-            let udpv6_handler = RawUdpProtocolHandler::new(
-                socket_arc,
-                Some(self.network_manager().address_filter()),
-            );
+        log_net!(
+            "created {} udpv4 and {} udpv6 outbound sockets on port {}",
+            udpv4_handlers.len(),
+            udpv6_handlers.len(),
+            port
+        );
 
-            inner.outbound_udpv6_protocol_handler = Some(udpv6_handler);
-        }
+        let mut inner = self.inner.lock();
+        inner.outbound_udpv4_protocol_handlers = udpv4_handlers;
+        inner.outbound_udpv6_protocol_handlers = udpv6_handlers;
 
         Ok(())
     }
 
+    /// Create one outbound socket for the pool, returning its protocol handler and the port it is bound to
+    /// Returns None if the address type is not available on this machine
+    fn create_udp_outbound_socket(
+        &self,
+        addr: SocketAddr,
+    ) -> EyreResult<Option<(RawUdpProtocolHandler, u16)>> {
+        let Ok(socket) = new_bound_shared_udp_socket(addr) else {
+            return Ok(None);
+        };
+        let port = socket
+            .local_addr()
+            .wrap_err("failed to get local address")?
+            .as_socket()
+            .ok_or_else(|| eyre!("expected ip address type"))?
+            .port();
+
+        // Make an async UdpSocket from the socket2 socket
+        let std_udp_socket: std::net::UdpSocket = socket.into();
+        cfg_if! {
+            if #[cfg(feature="rt-async-std")] {
+                let udp_socket = UdpSocket::from(std_udp_socket);
+            } else if #[cfg(feature="rt-tokio")] {
+                std_udp_socket.set_nonblocking(true).expect("failed to set nonblocking");
+                let udp_socket = UdpSocket::from_std(std_udp_socket).wrap_err("failed to make outbound tokio udpsocket")?;
+            } else {
+                compile_error!("needs executor implementation")
+            }
+        }
+        let socket_arc = Arc::new(udp_socket);
+
+        // Create protocol handler
+        let protocol_handler = RawUdpProtocolHandler::new(
+            socket_arc,
+            self.unlocked_inner.udp_assembly_buffer.clone(),
+            Some(self.network_manager().address_filter()),
+        );
+
+        Ok(Some((protocol_handler, port)))
+    }
+
     async fn create_udp_inbound_socket(&self, addr: SocketAddr) -> EyreResult<()> {
         log_net!("create_udp_inbound_socket on {:?}", &addr);
 
@@ -201,8 +243,11 @@ impl Network {
         let socket_arc = Arc::new(udp_socket);
 
         // Create protocol handler
-        let protocol_handler =
-            RawUdpProtocolHandler::new(socket_arc, Some(self.network_manager().address_filter()));
+        let protocol_handler = RawUdpProtocolHandler::new(
+            socket_arc,
+            self.unlocked_inner.udp_assembly_buffer.clone(),
+            Some(self.network_manager().address_filter()),
+        );
 
         // Create message_handler records
         self.inner
@@ -258,11 +303,21 @@ impl Network {
             }
         }
 
-        // otherwise find the outbound udp protocol handler that matches the ip protocol version of the peer addr
+        // otherwise find the outbound udp protocol handlers that match the ip protocol version of the peer addr
         let inner = self.inner.lock();
-        match peer_socket_addr {
-            SocketAddr::V4(_) => inner.outbound_udpv4_protocol_handler.clone(),
-            SocketAddr::V6(_) => inner.outbound_udpv6_protocol_handler.clone(),
+        let pool = match peer_socket_addr {
+            SocketAddr::V4(_) => &inner.outbound_udpv4_protocol_handlers,
+            SocketAddr::V6(_) => &inner.outbound_udpv6_protocol_handlers,
+        };
+        if pool.is_empty() {
+            return None;
         }
+
+        // and spread destinations over the pool, always sending to the same destination from the same socket.
+        // replies may still arrive on any socket of the pool, which is why they all share one assembly buffer
+        let mut hasher = DefaultHasher::new();
+        peer_socket_addr.hash(&mut hasher);
+        let index = (hasher.finish() % pool.len() as u64) as usize;
+        Some(pool[index].clone())
     }
 }
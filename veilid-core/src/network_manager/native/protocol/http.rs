@@ -0,0 +1,327 @@
+use super::*;
+use futures_util::{AsyncReadExt, AsyncWriteExt};
+
+// Maximum number of http request headers to permit
+const MAX_HTTP_HEADERS: usize = 24;
+// Maximum size of any one specific http header
+const MAX_HTTP_HEADER_LENGTH: usize = 512;
+// Maximum total size of headers and request including newlines
+const MAX_HTTP_BEFORE_BODY: usize = 2048;
+// Largest static file we are willing to serve
+const MAX_HTTP_FILE_SIZE: u64 = 16 * 1024 * 1024;
+// Name of the health endpoint under the application path
+const HTTP_HEALTH_ENDPOINT: &str = "health";
+// File served when a directory is requested
+const HTTP_INDEX_FILE: &str = "index.html";
+
+/// What a request under the application path is asking for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(in crate::network_manager) enum HttpApplicationResource {
+    /// The node's health and status as JSON
+    Health,
+    /// A static file, relative to the application directory
+    File(String),
+}
+
+/// A request for the application path that we are willing to answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(in crate::network_manager) struct HttpApplicationRequest {
+    pub resource: HttpApplicationResource,
+    /// HEAD requests only get the response headers
+    pub head: bool,
+    /// Length of the request line and headers, including the blank line ending them
+    pub header_len: usize,
+}
+
+/// Health and status of the node, as served to health checks
+#[derive(Debug, Clone, Serialize)]
+struct HttpApplicationHealth {
+    version: String,
+    started: bool,
+    peer_count: usize,
+    listeners: Vec<ListenerStatus>,
+}
+
+///////////////////////////////////////////////////////////
+///
+struct HttpProtocolHandlerArc {
+    tls: bool,
+    request_path: String,
+    directory: Option<PathBuf>,
+    connection_initial_timeout_ms: u32,
+    network_manager: NetworkManager,
+}
+
+/// Serves the application landing page and health checks on the listeners shared with WS and WSS
+#[derive(Clone)]
+pub(in crate::network_manager) struct HttpProtocolHandler
+where
+    Self: ProtocolAcceptHandler,
+{
+    arc: Arc<HttpProtocolHandlerArc>,
+}
+
+impl HttpProtocolHandler {
+    pub fn new(config: VeilidConfig, tls: bool, network_manager: NetworkManager) -> Self {
+        let c = config.get();
+        let (path, directory) = if tls {
+            (
+                c.network.application.https.path.clone(),
+                c.network.application.https.directory.clone(),
+            )
+        } else {
+            (
+                c.network.application.http.path.clone(),
+                c.network.application.http.directory.clone(),
+            )
+        };
+        let connection_initial_timeout_ms = if tls {
+            c.network.tls.connection_initial_timeout_ms
+        } else {
+            c.network.connection_initial_timeout_ms
+        };
+
+        Self {
+            arc: Arc::new(HttpProtocolHandlerArc {
+                tls,
+                request_path: format!("/{}", path.trim_matches('/')),
+                directory: directory.map(PathBuf::from),
+                connection_initial_timeout_ms,
+                network_manager,
+            }),
+        }
+    }
+
+    /// Parse a request, returning None if it is not a GET or HEAD for something under the application path
+    ///
+    /// Like the websocket handler, only CRLF-conforming requests whose headers fit within
+    /// MAX_HTTP_BEFORE_BODY are accepted, to keep the attack surface small.
+    pub fn parse_request(buf: &[u8], request_path: &str) -> Option<HttpApplicationRequest> {
+        let header_end = buf.windows(4).position(|w| w == b"\r\n\r\n")?;
+        if header_end + 4 > MAX_HTTP_BEFORE_BODY {
+            return None;
+        }
+        let header = std::str::from_utf8(&buf[0..header_end]).ok()?;
+        let mut lines = header.split("\r\n");
+
+        // Request line
+        let mut parts = lines.next()?.split(' ');
+        let (method, target, version) = (parts.next()?, parts.next()?, parts.next()?);
+        if parts.next().is_some() || !version.starts_with("HTTP/1.") {
+            return None;
+        }
+        let head = match method {
+            "GET" => false,
+            "HEAD" => true,
+            _ => return None,
+        };
+
+        // Cap the number of headers total and limit the size of all headers
+        let mut header_count = 0usize;
+        for line in lines {
+            header_count += 1;
+            if header_count > MAX_HTTP_HEADERS || line.len() > MAX_HTTP_HEADER_LENGTH {
+                return None;
+            }
+        }
+
+        // Ignore any query string
+        let target = target.split(['?', '#']).next().unwrap_or_default();
+        let rest = target.strip_prefix(request_path.trim_end_matches('/'))?;
+        if !rest.is_empty() && !rest.starts_with('/') {
+            return None;
+        }
+        let rest = rest.trim_start_matches('/');
+
+        let resource = if rest == HTTP_HEALTH_ENDPOINT {
+            HttpApplicationResource::Health
+        } else {
+            HttpApplicationResource::File(rest.to_owned())
+        };
+
+        Some(HttpApplicationRequest {
+            resource,
+            head,
+            header_len: header_end + 4,
+        })
+    }
+
+    /// Map a requested file onto the application directory
+    ///
+    /// Returns None for anything that could escape the directory. Requests for a directory get its index file.
+    pub fn resolve_static_path(directory: &Path, file: &str) -> Option<PathBuf> {
+        let mut out = directory.to_path_buf();
+        let file = if file.is_empty() || file.ends_with('/') {
+            format!("{}{}", file, HTTP_INDEX_FILE)
+        } else {
+            file.to_owned()
+        };
+        for segment in file.split('/') {
+            if segment.is_empty()
+                || segment == "."
+                || segment == ".."
+                || segment.contains(['\\', ':', '\0'])
+            {
+                return None;
+            }
+            out.push(segment);
+        }
+        Some(out)
+    }
+
+    fn content_type(path: &Path) -> &'static str {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "html" | "htm" => "text/html; charset=utf-8",
+            "css" => "text/css; charset=utf-8",
+            "js" | "mjs" => "text/javascript; charset=utf-8",
+            "json" => "application/json",
+            "webmanifest" => "application/manifest+json",
+            "wasm" => "application/wasm",
+            "txt" => "text/plain; charset=utf-8",
+            "svg" => "image/svg+xml",
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "webp" => "image/webp",
+            "ico" => "image/x-icon",
+            "woff2" => "font/woff2",
+            _ => "application/octet-stream",
+        }
+    }
+
+    fn response(status: &str, content_type: &str, body: &[u8], head: bool) -> Vec<u8> {
+        let mut out = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nX-Content-Type-Options: nosniff\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+            status,
+            content_type,
+            body.len()
+        )
+        .into_bytes();
+        if !head {
+            out.extend_from_slice(body);
+        }
+        out
+    }
+
+    fn not_found(head: bool) -> Vec<u8> {
+        Self::response(
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            b"Not Found",
+            head,
+        )
+    }
+
+    fn health_response(&self, head: bool) -> Vec<u8> {
+        let state = self.arc.network_manager.get_veilid_state();
+        let health = HttpApplicationHealth {
+            version: veilid_version_string(),
+            started: state.started,
+            peer_count: state.peers.len(),
+            listeners: state.listeners,
+        };
+        let body = serialize_json(&health);
+
+        // Health checks only need the status code to know if the node is up
+        let status = if health.started {
+            "200 OK"
+        } else {
+            "503 Service Unavailable"
+        };
+        Self::response(status, "application/json", body.as_bytes(), head)
+    }
+
+    fn file_response(&self, file: &str, head: bool) -> Vec<u8> {
+        let Some(directory) = self.arc.directory.as_ref() else {
+            return Self::not_found(head);
+        };
+        let Some(mut path) = Self::resolve_static_path(directory, file) else {
+            return Self::not_found(head);
+        };
+        if path.is_dir() {
+            path.push(HTTP_INDEX_FILE);
+        }
+
+        // Symlinks must not lead out of the application directory either
+        let (Ok(directory), Ok(path)) = (directory.canonicalize(), path.canonicalize()) else {
+            return Self::not_found(head);
+        };
+        if !path.starts_with(&directory) {
+            return Self::not_found(head);
+        }
+        match std::fs::metadata(&path) {
+            Ok(md) if md.is_file() && md.len() <= MAX_HTTP_FILE_SIZE => {}
+            _ => return Self::not_found(head),
+        }
+        match std::fs::read(&path) {
+            Ok(body) => Self::response("200 OK", Self::content_type(&path), &body, head),
+            Err(_) => Self::not_found(head),
+        }
+    }
+
+    #[instrument(level = "trace", skip(self, ps))]
+    pub async fn on_accept_async(
+        self,
+        ps: AsyncPeekStream,
+        socket_addr: SocketAddr,
+    ) -> io::Result<Option<ProtocolNetworkConnection>> {
+        log_net!("HTTP: on_accept_async: enter");
+
+        let mut peek_buf = [0u8; MAX_HTTP_BEFORE_BODY];
+        let peek_len = match timeout(
+            self.arc.connection_initial_timeout_ms,
+            ps.peek(&mut peek_buf),
+        )
+        .await
+        {
+            Err(_) | Ok(Err(_)) => {
+                // Timeout or peek error
+                return Ok(None);
+            }
+            Ok(Ok(v)) => v,
+        };
+
+        // Pass on anything that isn't for the application path
+        let Some(request) = Self::parse_request(&peek_buf[0..peek_len], &self.arc.request_path)
+        else {
+            return Ok(None);
+        };
+
+        let response = match &request.resource {
+            HttpApplicationResource::Health => self.health_response(request.head),
+            HttpApplicationResource::File(file) => self.file_response(file, request.head),
+        };
+
+        // Consume the request before answering so closing the stream doesn't reset it
+        let mut ps = ps;
+        let mut request_buf = vec![0u8; request.header_len];
+        ps.read_exact(&mut request_buf).await?;
+        ps.write_all(&response).await?;
+        ps.flush().await?;
+        let _ = ps.close().await;
+
+        log_net!(debug "Application request served to: {} ({})", socket_addr, if self.arc.tls { "HTTPS" } else { "HTTP" });
+
+        // The connection is finished with, so stop any other handler from trying to claim it
+        Err(io::Error::new(
+            io::ErrorKind::ConnectionAborted,
+            "application request served",
+        ))
+    }
+}
+
+impl ProtocolAcceptHandler for HttpProtocolHandler {
+    fn on_accept(
+        &self,
+        stream: AsyncPeekStream,
+        peer_addr: SocketAddr,
+        _local_addr: SocketAddr,
+    ) -> SendPinBoxFuture<io::Result<Option<ProtocolNetworkConnection>>> {
+        Box::pin(self.clone().on_accept_async(stream, peer_addr))
+    }
+}
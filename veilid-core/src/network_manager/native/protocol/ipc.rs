@@ -0,0 +1,64 @@
+use super::*;
+use sockets::*;
+
+cfg_if! {
+    if #[cfg(all(unix, feature="rt-async-std"))] {
+        pub use async_std::os::unix::net::{UnixListener, UnixStream};
+    } else if #[cfg(all(unix, feature="rt-tokio"))] {
+        pub use tokio::net::{UnixListener, UnixStream};
+    }
+}
+
+/// Local address for the flows of IPC connections, which have no address of their own
+pub fn ipc_local_address(host_id: u64) -> SocketAddress {
+    SocketAddress::new(Address::from_ipc_path(host_id, ""), 0)
+}
+
+#[derive(Clone)]
+pub(in crate::network_manager) struct IpcProtocolHandler {}
+
+impl IpcProtocolHandler {
+    /// Connect to the IPC socket of another Veilid process on this host
+    ///
+    /// The connection carries the same framing as raw TCP once it is established,
+    /// so it is handled as a raw TCP connection from then on.
+    #[instrument(level = "trace", ret, err)]
+    pub async fn connect(
+        dial_info: &DialInfo,
+        timeout_ms: u32,
+    ) -> io::Result<NetworkResult<ProtocolNetworkConnection>> {
+        let DialInfo::IPC(di) = dial_info else {
+            panic!("invalid dialinfo for IPC protocol");
+        };
+        if !di.is_on_this_host() {
+            return Ok(NetworkResult::no_connection_other(
+                "ipc dial info is for another host",
+            ));
+        }
+
+        #[cfg(unix)]
+        {
+            let us = match timeout(timeout_ms, UnixStream::connect(&di.path)).await {
+                Ok(v) => v?,
+                Err(_) => return Ok(NetworkResult::timeout()),
+            };
+            #[cfg(feature = "rt-tokio")]
+            let us = us.compat();
+            let ps = AsyncPeekStream::new(us);
+
+            let conn = ProtocolNetworkConnection::RawTcp(tcp::RawTcpNetworkConnection::new(
+                Flow::new(dial_info.peer_address(), ipc_local_address(di.host_id)),
+                ps,
+            ));
+
+            Ok(NetworkResult::Value(conn))
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = timeout_ms;
+            Ok(NetworkResult::no_connection_other(
+                "ipc is not supported on this platform",
+            ))
+        }
+    }
+}
@@ -1,4 +1,9 @@
+pub mod http;
+pub mod ipc;
+#[cfg(any(test, feature = "sim-network"))]
+pub mod sim;
 pub mod sockets;
+pub mod socks;
 pub mod tcp;
 pub mod udp;
 pub mod wrtc;
@@ -14,6 +19,8 @@ pub(in crate::network_manager) enum ProtocolNetworkConnection {
     WsAccepted(ws::WebSocketNetworkConnectionAccepted),
     Ws(ws::WebsocketNetworkConnectionWS),
     Wss(ws::WebsocketNetworkConnectionWSS),
+    #[cfg(any(test, feature = "sim-network"))]
+    Sim(sim::SimNetworkConnection),
     //WebRTC(wrtc::WebRTCNetworkConnection),
 }
 
@@ -23,26 +30,51 @@ impl ProtocolNetworkConnection {
         dial_info: &DialInfo,
         timeout_ms: u32,
         address_filter: AddressFilter,
-    ) -> io::Result<NetworkResult<ProtocolNetworkConnection>> {
+        tor_socks_address: Option<SocketAddr>,
+        #[cfg(any(test, feature = "sim-network"))] sim_network: Option<sim::SimNetwork>,
+    ) -> NetworkErrorResult<ProtocolNetworkConnection> {
         if address_filter.is_ip_addr_punished(dial_info.address().ip_addr()) {
-            return Ok(NetworkResult::no_connection_other("punished"));
+            return Err(NetworkError::filtered_by_policy("punished"));
         }
-        match dial_info.protocol_type() {
+        if dial_info.is_onion() && tor_socks_address.is_none() {
+            return Err(NetworkError::filtered_by_policy(
+                "onion dial info requires tor",
+            ));
+        }
+        let res = match dial_info.protocol_type() {
             ProtocolType::UDP => {
                 panic!("Should not connect to UDP dialinfo");
             }
-            ProtocolType::TCP => {
-                tcp::RawTcpProtocolHandler::connect(
+            ProtocolType::TCP if dial_info.is_ipc() => {
+                ipc::IpcProtocolHandler::connect(dial_info, timeout_ms).await
+            }
+            ProtocolType::TCP => match tor_socks_address {
+                Some(proxy) => {
+                    tcp::RawTcpProtocolHandler::connect_via_socks(proxy, dial_info, timeout_ms)
+                        .await
+                }
+                None => {
+                    tcp::RawTcpProtocolHandler::connect(
+                        local_address,
+                        dial_info.to_socket_addr(),
+                        timeout_ms,
+                        #[cfg(any(test, feature = "sim-network"))]
+                        sim_network,
+                    )
+                    .await
+                }
+            },
+            ProtocolType::WS | ProtocolType::WSS => {
+                ws::WebsocketProtocolHandler::connect(
                     local_address,
-                    dial_info.to_socket_addr(),
+                    dial_info,
                     timeout_ms,
+                    tor_socks_address,
                 )
                 .await
             }
-            ProtocolType::WS | ProtocolType::WSS => {
-                ws::WebsocketProtocolHandler::connect(local_address, dial_info, timeout_ms).await
-            }
-        }
+        };
+        NetworkError::from_io_network_result(res)
     }
 
     pub fn flow(&self) -> Flow {
@@ -52,6 +84,8 @@ impl ProtocolNetworkConnection {
             Self::WsAccepted(w) => w.flow(),
             Self::Ws(w) => w.flow(),
             Self::Wss(w) => w.flow(),
+            #[cfg(any(test, feature = "sim-network"))]
+            Self::Sim(s) => s.flow(),
         }
     }
 
@@ -62,6 +96,8 @@ impl ProtocolNetworkConnection {
             Self::WsAccepted(w) => w.close().await,
             Self::Ws(w) => w.close().await,
             Self::Wss(w) => w.close().await,
+            #[cfg(any(test, feature = "sim-network"))]
+            Self::Sim(s) => s.close().await,
         }
     }
 
@@ -72,6 +108,8 @@ impl ProtocolNetworkConnection {
             Self::WsAccepted(w) => w.send(message).await,
             Self::Ws(w) => w.send(message).await,
             Self::Wss(w) => w.send(message).await,
+            #[cfg(any(test, feature = "sim-network"))]
+            Self::Sim(s) => s.send(message).await,
         }
     }
     pub async fn recv(&self) -> io::Result<NetworkResult<Vec<u8>>> {
@@ -81,6 +119,8 @@ impl ProtocolNetworkConnection {
             Self::WsAccepted(w) => w.recv().await,
             Self::Ws(w) => w.recv().await,
             Self::Wss(w) => w.recv().await,
+            #[cfg(any(test, feature = "sim-network"))]
+            Self::Sim(s) => s.recv().await,
         }
     }
 }
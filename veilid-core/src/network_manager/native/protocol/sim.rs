@@ -0,0 +1,429 @@
+use super::*;
+
+/// Address used as the source of simulated connections that are not bound to a node's listener,
+/// such as the short-lived connections used for bootstrap requests and receipts
+pub const SIM_UNBOUND_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::new(11, 255, 255, 254));
+/// First port handed out to the local end of simulated connections
+const SIM_EPHEMERAL_PORT_START: u16 = 49152;
+
+/// How messages travel between two simulated addresses
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimLink {
+    /// One-way delay added to every message
+    pub latency_ms: u32,
+    /// Chance that a message is dropped, from 0.0 (never) to 1.0 (always)
+    pub loss: f64,
+}
+
+impl SimLink {
+    pub const fn new(latency_ms: u32, loss: f64) -> Self {
+        Self { latency_ms, loss }
+    }
+    /// A link that drops everything, for partitioning nodes from each other
+    pub const fn partitioned() -> Self {
+        Self {
+            latency_ms: 0,
+            loss: 1.0,
+        }
+    }
+}
+
+impl Default for SimLink {
+    fn default() -> Self {
+        Self {
+            latency_ms: 0,
+            loss: 0.0,
+        }
+    }
+}
+
+struct SimMessage {
+    deliver_ts: u64,
+    data: Option<Vec<u8>>,
+}
+
+struct SimDatagram {
+    deliver_ts: u64,
+    from: SocketAddr,
+    data: Vec<u8>,
+}
+
+struct SimNetworkInner {
+    listeners: HashMap<SocketAddr, flume::Sender<SimNetworkConnection>>,
+    udp_sockets: HashMap<SocketAddr, flume::Sender<SimDatagram>>,
+    default_link: SimLink,
+    links: HashMap<(IpAddr, IpAddr), SimLink>,
+    rng_state: u64,
+    next_port: u16,
+}
+
+impl SimNetworkInner {
+    fn allocate_port(&mut self) -> u16 {
+        let port = self.next_port;
+        self.next_port = self
+            .next_port
+            .checked_add(1)
+            .unwrap_or(SIM_EPHEMERAL_PORT_START);
+        port
+    }
+}
+
+/// An in-process network that carries connections between veilid-core instances
+/// running in the same process
+///
+/// Nodes started on a simulated network make all of their raw TCP connections and send all of their
+/// UDP datagrams over it instead of real sockets, and listen on it at their configured listen addresses. Each node is given the
+/// network it runs on, so several simulated networks can be used in one process. Latency and loss are
+/// scripted per direction between two addresses, and the loss decisions are drawn from a seeded
+/// generator so a test sees the same drops every time it runs.
+#[derive(Clone)]
+pub struct SimNetwork {
+    inner: Arc<Mutex<SimNetworkInner>>,
+}
+
+impl fmt::Debug for SimNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimNetwork").finish()
+    }
+}
+
+impl SimNetwork {
+    pub fn new(seed: u64, default_link: SimLink) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SimNetworkInner {
+                listeners: HashMap::new(),
+                udp_sockets: HashMap::new(),
+                default_link,
+                links: HashMap::new(),
+                rng_state: seed,
+                next_port: SIM_EPHEMERAL_PORT_START,
+            })),
+        }
+    }
+
+    /// Change how messages from `from` to `to` travel. Only affects that direction.
+    pub fn set_link(&self, from: IpAddr, to: IpAddr, link: SimLink) {
+        self.inner.lock().links.insert((from, to), link);
+    }
+
+    /// Change how messages travel between two addresses in both directions
+    pub fn set_links(&self, a: IpAddr, b: IpAddr, link: SimLink) {
+        let mut inner = self.inner.lock();
+        inner.links.insert((a, b), link);
+        inner.links.insert((b, a), link);
+    }
+
+    /// Drop everything sent between two addresses
+    pub fn partition(&self, a: IpAddr, b: IpAddr) {
+        self.set_links(a, b, SimLink::partitioned());
+    }
+
+    /// Go back to the default link between two addresses
+    pub fn heal(&self, a: IpAddr, b: IpAddr) {
+        let mut inner = self.inner.lock();
+        inner.links.remove(&(a, b));
+        inner.links.remove(&(b, a));
+    }
+
+    fn link(&self, from: IpAddr, to: IpAddr) -> SimLink {
+        let inner = self.inner.lock();
+        inner
+            .links
+            .get(&(from, to))
+            .copied()
+            .unwrap_or(inner.default_link)
+    }
+
+    /// Decide when a message from one address to another arrives, or None if it is lost
+    fn deliver_ts(&self, from: IpAddr, to: IpAddr) -> Option<u64> {
+        let link = self.link(from, to);
+        if self.roll_loss(link.loss) {
+            return None;
+        }
+        Some(get_timestamp() + (link.latency_ms as u64) * 1000)
+    }
+
+    /// Decide if a message on a link is lost
+    fn roll_loss(&self, loss: f64) -> bool {
+        if loss <= 0.0 {
+            return false;
+        }
+        if loss >= 1.0 {
+            return true;
+        }
+        // splitmix64
+        let mut inner = self.inner.lock();
+        inner.rng_state = inner.rng_state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = inner.rng_state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^= z >> 31;
+        ((z >> 11) as f64 / (1u64 << 53) as f64) < loss
+    }
+
+    /// Start accepting connections at an address
+    pub(in crate::network_manager) fn listen(
+        &self,
+        addr: SocketAddr,
+    ) -> io::Result<flume::Receiver<SimNetworkConnection>> {
+        let mut inner = self.inner.lock();
+        if inner.listeners.contains_key(&addr) {
+            return Err(io::Error::from(io::ErrorKind::AddrInUse));
+        }
+        let (sender, receiver) = flume::unbounded();
+        inner.listeners.insert(addr, sender);
+        Ok(receiver)
+    }
+
+    /// Stop accepting connections at an address
+    pub(in crate::network_manager) fn unlisten(&self, addr: SocketAddr) {
+        self.inner.lock().listeners.remove(&addr);
+    }
+
+    /// Connect to a listener on the simulated network
+    pub(in crate::network_manager) fn connect(
+        &self,
+        local_address: Option<SocketAddr>,
+        remote_address: SocketAddr,
+    ) -> io::Result<NetworkResult<ProtocolNetworkConnection>> {
+        let local_ip = local_address
+            .map(|a| a.ip())
+            .filter(|ip| !ip.is_unspecified())
+            .unwrap_or(SIM_UNBOUND_ADDRESS);
+
+        // Connection attempts are lost like any other message
+        if self.roll_loss(self.link(local_ip, remote_address.ip()).loss) {
+            return Ok(NetworkResult::Timeout);
+        }
+
+        let (listener, local_port) = {
+            let mut inner = self.inner.lock();
+            let Some(listener) = inner.listeners.get(&remote_address).cloned() else {
+                return Ok(NetworkResult::no_connection(io::Error::from(
+                    io::ErrorKind::ConnectionRefused,
+                )));
+            };
+            (listener, inner.allocate_port())
+        };
+        let local_address = SocketAddr::new(local_ip, local_port);
+
+        let (out_sender, out_receiver) = flume::unbounded();
+        let (in_sender, in_receiver) = flume::unbounded();
+        let ours = SimNetworkConnection {
+            flow: Flow::new(
+                PeerAddress::new(
+                    SocketAddress::from_socket_addr(remote_address),
+                    ProtocolType::TCP,
+                ),
+                SocketAddress::from_socket_addr(local_address),
+            ),
+            sim_network: self.clone(),
+            sender: out_sender,
+            receiver: in_receiver,
+        };
+        let theirs = SimNetworkConnection {
+            flow: Flow::new(
+                PeerAddress::new(
+                    SocketAddress::from_socket_addr(local_address),
+                    ProtocolType::TCP,
+                ),
+                SocketAddress::from_socket_addr(remote_address),
+            ),
+            sim_network: self.clone(),
+            sender: in_sender,
+            receiver: out_receiver,
+        };
+        if listener.send(theirs).is_err() {
+            return Ok(NetworkResult::no_connection(io::Error::from(
+                io::ErrorKind::ConnectionRefused,
+            )));
+        }
+
+        log_net!("connected to {} over simulated network", remote_address);
+        Ok(NetworkResult::Value(ProtocolNetworkConnection::Sim(ours)))
+    }
+
+    /// Bind a datagram socket on the simulated network
+    /// Sockets without a local address get a port of their own at the unbound address
+    pub(in crate::network_manager) fn bind_udp(
+        &self,
+        local_address: Option<SocketAddr>,
+    ) -> io::Result<SimUdpSocket> {
+        let mut inner = self.inner.lock();
+        let local_address = match local_address {
+            Some(a) => a,
+            None => SocketAddr::new(SIM_UNBOUND_ADDRESS, inner.allocate_port()),
+        };
+        if inner.udp_sockets.contains_key(&local_address) {
+            return Err(io::Error::from(io::ErrorKind::AddrInUse));
+        }
+        let (sender, receiver) = flume::unbounded();
+        inner.udp_sockets.insert(local_address, sender);
+        Ok(SimUdpSocket {
+            local_address,
+            sim_network: self.clone(),
+            receiver,
+        })
+    }
+}
+
+/// A datagram socket on the simulated network, unbound when dropped
+pub struct SimUdpSocket {
+    local_address: SocketAddr,
+    sim_network: SimNetwork,
+    receiver: flume::Receiver<SimDatagram>,
+}
+
+impl fmt::Debug for SimUdpSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimUdpSocket")
+            .field("local_address", &self.local_address)
+            .finish()
+    }
+}
+
+impl Drop for SimUdpSocket {
+    fn drop(&mut self) {
+        self.sim_network
+            .inner
+            .lock()
+            .udp_sockets
+            .remove(&self.local_address);
+    }
+}
+
+impl SimUdpSocket {
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        Ok(self.local_address)
+    }
+
+    pub async fn send_to(&self, buf: &[u8], remote_address: SocketAddr) -> io::Result<usize> {
+        // Like a real datagram, it is sent whether or not anything arrives at the other end
+        let Some(deliver_ts) = self
+            .sim_network
+            .deliver_ts(self.local_address.ip(), remote_address.ip())
+        else {
+            log_net!("simulated network dropped datagram to {}", remote_address);
+            return Ok(buf.len());
+        };
+        let opt_socket = self
+            .sim_network
+            .inner
+            .lock()
+            .udp_sockets
+            .get(&remote_address)
+            .cloned();
+        if let Some(socket) = opt_socket {
+            let _ = socket.send(SimDatagram {
+                deliver_ts,
+                from: self.local_address,
+                data: buf.to_vec(),
+            });
+        }
+        Ok(buf.len())
+    }
+
+    pub async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        let Ok(datagram) = self.receiver.recv_async().await else {
+            return Err(io::Error::from(io::ErrorKind::NotConnected));
+        };
+
+        // Hold the datagram back until its latency has passed
+        let cur_ts = get_timestamp();
+        if datagram.deliver_ts > cur_ts {
+            sleep(((datagram.deliver_ts - cur_ts + 999) / 1000) as u32).await;
+        }
+
+        // Datagrams larger than the buffer are truncated, like with a real socket
+        let len = datagram.data.len().min(buf.len());
+        buf[..len].copy_from_slice(&datagram.data[..len]);
+        Ok((len, datagram.from))
+    }
+}
+
+/// One end of a connection on the simulated network
+pub struct SimNetworkConnection {
+    flow: Flow,
+    sim_network: SimNetwork,
+    sender: flume::Sender<SimMessage>,
+    receiver: flume::Receiver<SimMessage>,
+}
+
+impl fmt::Debug for SimNetworkConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimNetworkConnection")
+            .field("flow", &self.flow)
+            .finish()
+    }
+}
+
+impl SimNetworkConnection {
+    pub fn flow(&self) -> Flow {
+        self.flow
+    }
+
+    fn deliver_ts(&self) -> Option<u64> {
+        self.sim_network.deliver_ts(
+            self.flow.local().unwrap().ip_addr(),
+            self.flow.remote_address().ip_addr(),
+        )
+    }
+
+    pub async fn close(&self) -> io::Result<NetworkResult<()>> {
+        // The close travels like a message so it is seen after the data sent before it,
+        // but it is never lost
+        let deliver_ts = self.deliver_ts().unwrap_or_else(get_timestamp);
+        let _ = self.sender.send(SimMessage {
+            deliver_ts,
+            data: None,
+        });
+        Ok(NetworkResult::value(()))
+    }
+
+    pub async fn send(&self, message: Vec<u8>) -> io::Result<NetworkResult<()>> {
+        if message.len() > MAX_MESSAGE_SIZE {
+            bail_io_error_other!("sending too large simulated message");
+        }
+        let Some(deliver_ts) = self.deliver_ts() else {
+            log_net!(
+                "simulated network dropped message to {}",
+                self.flow.remote()
+            );
+            return Ok(NetworkResult::value(()));
+        };
+        if self
+            .sender
+            .send(SimMessage {
+                deliver_ts,
+                data: Some(message),
+            })
+            .is_err()
+        {
+            return Ok(NetworkResult::no_connection(io::Error::from(
+                io::ErrorKind::ConnectionReset,
+            )));
+        }
+        Ok(NetworkResult::value(()))
+    }
+
+    pub async fn recv(&self) -> io::Result<NetworkResult<Vec<u8>>> {
+        let Ok(message) = self.receiver.recv_async().await else {
+            return Ok(NetworkResult::no_connection(io::Error::from(
+                io::ErrorKind::ConnectionReset,
+            )));
+        };
+
+        // Hold the message back until its latency has passed
+        let cur_ts = get_timestamp();
+        if message.deliver_ts > cur_ts {
+            sleep(((message.deliver_ts - cur_ts + 999) / 1000) as u32).await;
+        }
+
+        match message.data {
+            Some(data) => Ok(NetworkResult::Value(data)),
+            None => Ok(NetworkResult::no_connection(io::Error::from(
+                io::ErrorKind::ConnectionAborted,
+            ))),
+        }
+    }
+}
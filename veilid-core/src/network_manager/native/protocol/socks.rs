@@ -0,0 +1,117 @@
+use super::*;
+use futures_util::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use sockets::*;
+
+// SOCKS5 protocol constants (RFC 1928)
+const SOCKS5_VERSION: u8 = 5;
+const SOCKS5_AUTH_NONE: u8 = 0;
+const SOCKS5_CMD_CONNECT: u8 = 1;
+const SOCKS5_ATYP_IPV4: u8 = 1;
+const SOCKS5_ATYP_DOMAIN: u8 = 3;
+const SOCKS5_ATYP_IPV6: u8 = 4;
+const SOCKS5_REPLY_SUCCEEDED: u8 = 0;
+
+cfg_if! {
+    if #[cfg(feature="rt-async-std")] {
+        pub type SocksStream = TcpStream;
+    } else if #[cfg(feature="rt-tokio")] {
+        pub type SocksStream = Compat<TcpStream>;
+    } else {
+        compile_error!("needs executor implementation")
+    }
+}
+
+/// Connect to a remote host and port through a SOCKS5 proxy such as the Tor SOCKS port
+///
+/// Hostnames are passed to the proxy unresolved, so no DNS queries leave this machine.
+/// Returns the proxied stream along with the local address of the connection to the proxy.
+pub async fn socks5_connect(
+    proxy: SocketAddr,
+    host: &str,
+    port: u16,
+    timeout_ms: u32,
+) -> io::Result<TimeoutOr<(SocksStream, SocketAddr)>> {
+    // Connect to the proxy itself
+    let socket = new_unbound_tcp_socket(socket2::Domain::for_address(proxy))?;
+    let ts = timeout_or_try!(nonblocking_connect(socket, proxy, timeout_ms).await?);
+    let actual_local_address = ts.local_addr()?;
+    #[cfg(feature = "rt-tokio")]
+    let ts = ts.compat();
+    let mut ts = ts;
+
+    // Ask the proxy to connect onward
+    timeout_or_try!(timeout(timeout_ms, socks5_handshake(&mut ts, host, port))
+        .await
+        .into_timeout_or()
+        .into_result()?);
+
+    Ok(TimeoutOr::value((ts, actual_local_address)))
+}
+
+/// Run the SOCKS5 no-authentication CONNECT handshake on a stream already connected to the proxy
+pub async fn socks5_handshake<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    host: &str,
+    port: u16,
+) -> io::Result<()> {
+    // Negotiate no authentication, Tor does not require any for its SOCKS port
+    stream
+        .write_all(&[SOCKS5_VERSION, 1, SOCKS5_AUTH_NONE])
+        .await?;
+    let mut method = [0u8; 2];
+    stream.read_exact(&mut method).await?;
+    if method[0] != SOCKS5_VERSION || method[1] != SOCKS5_AUTH_NONE {
+        bail_io_error_other!("socks proxy refused authentication method");
+    }
+
+    // Send connect request
+    let mut request = vec![SOCKS5_VERSION, SOCKS5_CMD_CONNECT, 0];
+    match IpAddr::from_str(host) {
+        Ok(IpAddr::V4(v4)) => {
+            request.push(SOCKS5_ATYP_IPV4);
+            request.extend_from_slice(&v4.octets());
+        }
+        Ok(IpAddr::V6(v6)) => {
+            request.push(SOCKS5_ATYP_IPV6);
+            request.extend_from_slice(&v6.octets());
+        }
+        Err(_) => {
+            let Ok(host_len) = u8::try_from(host.len()) else {
+                bail_io_error_other!("socks hostname too long");
+            };
+            request.push(SOCKS5_ATYP_DOMAIN);
+            request.push(host_len);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    // Read reply header
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != SOCKS5_VERSION {
+        bail_io_error_other!("invalid socks proxy reply");
+    }
+    if reply[1] != SOCKS5_REPLY_SUCCEEDED {
+        bail_io_error_other!(format!("socks proxy connect failed: {}", reply[1]));
+    }
+
+    // Skip the bound address, we don't use it
+    let bound_len = match reply[3] {
+        SOCKS5_ATYP_IPV4 => 4,
+        SOCKS5_ATYP_IPV6 => 16,
+        SOCKS5_ATYP_DOMAIN => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len).await?;
+            len[0] as usize
+        }
+        _ => {
+            bail_io_error_other!("invalid socks bound address type");
+        }
+    };
+    let mut bound = vec![0u8; bound_len + 2];
+    stream.read_exact(&mut bound).await?;
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use super::*;
 use futures_util::{AsyncReadExt, AsyncWriteExt};
 use sockets::*;
+use socks::*;
 
 pub struct RawTcpNetworkConnection {
     flow: Flow,
@@ -166,7 +167,14 @@ impl RawTcpProtocolHandler {
         local_address: Option<SocketAddr>,
         socket_addr: SocketAddr,
         timeout_ms: u32,
+        #[cfg(any(test, feature = "sim-network"))] sim_network: Option<sim::SimNetwork>,
     ) -> io::Result<NetworkResult<ProtocolNetworkConnection>> {
+        // Connect over the simulated network instead when the node runs on one
+        #[cfg(any(test, feature = "sim-network"))]
+        if let Some(sim_network) = sim_network {
+            return sim_network.connect(local_address, socket_addr);
+        }
+
         // Make a shared socket
         let socket = match local_address {
             Some(a) => new_bound_shared_tcp_socket(a)?,
@@ -198,6 +206,38 @@ impl RawTcpProtocolHandler {
 
         Ok(NetworkResult::Value(conn))
     }
+
+    #[instrument(level = "trace", ret, err)]
+    pub async fn connect_via_socks(
+        proxy: SocketAddr,
+        dial_info: &DialInfo,
+        timeout_ms: u32,
+    ) -> io::Result<NetworkResult<ProtocolNetworkConnection>> {
+        // Onion services are addressed by name, everything else by ip address
+        let host = match dial_info {
+            DialInfo::Onion(di) => di.host.clone(),
+            _ => dial_info.ip_addr().to_string(),
+        };
+
+        // Connect through the proxy
+        let (ts, actual_local_address) =
+            network_result_try!(socks5_connect(proxy, &host, dial_info.port(), timeout_ms)
+                .await
+                .folded()?);
+        let ps = AsyncPeekStream::new(ts);
+
+        // Wrap the stream in a network connection and return it
+        // The flow is keyed by the dial info rather than the proxy address
+        let conn = ProtocolNetworkConnection::RawTcp(RawTcpNetworkConnection::new(
+            Flow::new(
+                dial_info.peer_address(),
+                SocketAddress::from_socket_addr(actual_local_address),
+            ),
+            ps,
+        ));
+
+        Ok(NetworkResult::Value(conn))
+    }
 }
 
 impl ProtocolAcceptHandler for RawTcpProtocolHandler {
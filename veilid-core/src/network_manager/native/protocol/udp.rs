@@ -1,18 +1,69 @@
 use super::*;
 use sockets::*;
 
+/// The socket a udp protocol handler sends and receives datagrams on
+#[derive(Clone)]
+enum RawUdpSocket {
+    Real(Arc<UdpSocket>),
+    #[cfg(any(test, feature = "sim-network"))]
+    Sim(Arc<sim::SimUdpSocket>),
+}
+
+impl RawUdpSocket {
+    async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        match self {
+            Self::Real(s) => s.recv_from(buf).await,
+            #[cfg(any(test, feature = "sim-network"))]
+            Self::Sim(s) => s.recv_from(buf).await,
+        }
+    }
+
+    async fn send_to(&self, buf: &[u8], remote_addr: SocketAddr) -> io::Result<usize> {
+        match self {
+            Self::Real(s) => s.send_to(buf, remote_addr).await,
+            #[cfg(any(test, feature = "sim-network"))]
+            Self::Sim(s) => s.send_to(buf, remote_addr).await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        match self {
+            Self::Real(s) => s.local_addr(),
+            #[cfg(any(test, feature = "sim-network"))]
+            Self::Sim(s) => s.local_addr(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(in crate::network_manager) struct RawUdpProtocolHandler {
-    socket: Arc<UdpSocket>,
+    socket: RawUdpSocket,
     assembly_buffer: AssemblyBuffer,
     address_filter: Option<AddressFilter>,
 }
 
 impl RawUdpProtocolHandler {
-    pub fn new(socket: Arc<UdpSocket>, address_filter: Option<AddressFilter>) -> Self {
+    pub fn new(
+        socket: Arc<UdpSocket>,
+        assembly_buffer: AssemblyBuffer,
+        address_filter: Option<AddressFilter>,
+    ) -> Self {
+        Self {
+            socket: RawUdpSocket::Real(socket),
+            assembly_buffer,
+            address_filter,
+        }
+    }
+
+    #[cfg(any(test, feature = "sim-network"))]
+    pub fn new_sim(
+        socket: sim::SimUdpSocket,
+        assembly_buffer: AssemblyBuffer,
+        address_filter: Option<AddressFilter>,
+    ) -> Self {
         Self {
-            socket,
-            assembly_buffer: AssemblyBuffer::new(),
+            socket: RawUdpSocket::Sim(Arc::new(socket)),
+            assembly_buffer,
             address_filter,
         }
     }
@@ -136,10 +187,25 @@ impl RawUdpProtocolHandler {
     #[instrument(level = "trace", err)]
     pub async fn new_unspecified_bound_handler(
         socket_addr: &SocketAddr,
+        #[cfg(any(test, feature = "sim-network"))] sim_network: Option<sim::SimNetwork>,
     ) -> io::Result<RawUdpProtocolHandler> {
+        // Bind on the simulated network instead when the node runs on one
+        #[cfg(any(test, feature = "sim-network"))]
+        if let Some(sim_network) = sim_network {
+            return Ok(RawUdpProtocolHandler::new_sim(
+                sim_network.bind_udp(None)?,
+                AssemblyBuffer::new(),
+                None,
+            ));
+        }
+
         // get local wildcard address for bind
         let local_socket_addr = compatible_unspecified_socket_addr(socket_addr);
         let socket = UdpSocket::bind(local_socket_addr).await?;
-        Ok(RawUdpProtocolHandler::new(Arc::new(socket), None))
+        Ok(RawUdpProtocolHandler::new(
+            Arc::new(socket),
+            AssemblyBuffer::new(),
+            None,
+        ))
     }
 }
@@ -11,6 +11,7 @@ use async_tungstenite::tungstenite::Error;
 use async_tungstenite::{accept_hdr_async, client_async, WebSocketStream};
 use futures_util::{AsyncRead, AsyncWrite, SinkExt};
 use sockets::*;
+use socks::*;
 
 // Maximum number of websocket request headers to permit
 const MAX_WS_HEADERS: usize = 24;
@@ -300,6 +301,7 @@ impl WebsocketProtocolHandler {
         local_address: Option<SocketAddr>,
         dial_info: &DialInfo,
         timeout_ms: u32,
+        tor_socks_address: Option<SocketAddr>,
     ) -> io::Result<NetworkResult<ProtocolNetworkConnection>> {
         // Split dial info up
         let (tls, scheme) = match dial_info {
@@ -314,26 +316,39 @@ impl WebsocketProtocolHandler {
         }
         let domain = split_url.host.clone();
 
-        // Resolve remote address
-        let remote_socket_addr = dial_info.to_socket_addr();
+        let (tcp_stream, actual_local_addr) = if let Some(proxy) = tor_socks_address {
+            // Let the proxy resolve the url host so no dns queries leave this machine
+            let host = match &domain {
+                SplitUrlHost::Hostname(h) => h.clone(),
+                SplitUrlHost::IpAddr(a) => a.to_string(),
+            };
+            network_result_try!(socks5_connect(proxy, &host, dial_info.port(), timeout_ms)
+                .await
+                .folded()?)
+        } else {
+            // Resolve remote address
+            let remote_socket_addr = dial_info.to_socket_addr();
 
-        // Make a shared socket
-        let socket = match local_address {
-            Some(a) => new_bound_shared_tcp_socket(a)?,
-            None => new_unbound_tcp_socket(socket2::Domain::for_address(remote_socket_addr))?,
-        };
+            // Make a shared socket
+            let socket = match local_address {
+                Some(a) => new_bound_shared_tcp_socket(a)?,
+                None => new_unbound_tcp_socket(socket2::Domain::for_address(remote_socket_addr))?,
+            };
 
-        // Non-blocking connect to remote address
-        let tcp_stream =
-            network_result_try!(nonblocking_connect(socket, remote_socket_addr, timeout_ms)
-                .await
-                .folded()?);
+            // Non-blocking connect to remote address
+            let tcp_stream =
+                network_result_try!(nonblocking_connect(socket, remote_socket_addr, timeout_ms)
+                    .await
+                    .folded()?);
 
-        // See what local address we ended up with
-        let actual_local_addr = tcp_stream.local_addr()?;
+            // See what local address we ended up with
+            let actual_local_addr = tcp_stream.local_addr()?;
 
-        #[cfg(feature = "rt-tokio")]
-        let tcp_stream = tcp_stream.compat();
+            #[cfg(feature = "rt-tokio")]
+            let tcp_stream = tcp_stream.compat();
+
+            (tcp_stream, actual_local_addr)
+        };
 
         // Make our flow
         let flow = Flow::new(
@@ -344,7 +359,10 @@ impl WebsocketProtocolHandler {
         // Negotiate TLS if this is WSS
         if tls {
             let connector = TlsConnector::default();
-            let tls_stream = connector.connect(domain.to_string(), tcp_stream).await?;
+            let tls_stream = connector
+                .connect(domain.to_string(), tcp_stream)
+                .await
+                .map_err(NetworkError::tls_error)?;
             let (ws_stream, _response) = client_async(request, tls_stream)
                 .await
                 .map_err(to_io_error_other)?;
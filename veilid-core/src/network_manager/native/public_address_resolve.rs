@@ -0,0 +1,70 @@
+use super::*;
+
+/// A statically configured public address given as a hostname, such as one kept up to date by
+/// dynamic DNS, and the socket addresses it resolved to when our dial info was registered
+#[derive(Debug, Clone)]
+pub(super) struct ResolvedPublicAddress {
+    pub host_port: String,
+    pub socket_addrs: BTreeSet<SocketAddr>,
+}
+
+/// Resolve a 'host:port' address without blocking the executor
+async fn resolve_host_port(host_port: String) -> io::Result<BTreeSet<SocketAddr>> {
+    blocking_wrapper(
+        move || {
+            host_port
+                .to_socket_addrs()
+                .map(|addrs| addrs.collect::<BTreeSet<_>>())
+        },
+        Err(io::Error::new(io::ErrorKind::Other, "resolver task failed")),
+    )
+    .await
+}
+
+impl Network {
+    /// Remember what a configured public address resolved to, if it is a hostname that could
+    /// resolve to something else later on
+    pub(super) fn record_resolved_public_address<I>(&self, host_port: String, socket_addrs: I)
+    where
+        I: IntoIterator<Item = SocketAddr>,
+    {
+        if host_port.parse::<SocketAddr>().is_ok() {
+            return;
+        }
+        self.inner
+            .lock()
+            .resolved_public_addresses
+            .push(ResolvedPublicAddress {
+                host_port,
+                socket_addrs: socket_addrs.into_iter().collect(),
+            });
+    }
+
+    /// Resolve public address hostnames again, and restart the network if any of them changed
+    /// so that our dial info is registered again and our node info is re-signed
+    pub(super) async fn check_resolved_public_addresses(&self) -> EyreResult<()> {
+        let resolved_public_addresses = self.inner.lock().resolved_public_addresses.clone();
+        for rpa in resolved_public_addresses {
+            let socket_addrs = match resolve_host_port(rpa.host_port.clone()).await {
+                Ok(v) if !v.is_empty() => v,
+                Ok(_) => {
+                    log_net!(debug "public address {} resolved to nothing", rpa.host_port);
+                    continue;
+                }
+                Err(e) => {
+                    log_net!(debug "failed to resolve public address {}: {}", rpa.host_port, e);
+                    continue;
+                }
+            };
+            if socket_addrs != rpa.socket_addrs {
+                info!(
+                    "public address {} changed from {:?} to {:?}, restarting network",
+                    rpa.host_port, rpa.socket_addrs, socket_addrs
+                );
+                self.inner.lock().network_needs_restart = true;
+                break;
+            }
+        }
+        Ok(())
+    }
+}
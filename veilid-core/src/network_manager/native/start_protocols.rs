@@ -279,7 +279,7 @@ impl Network {
         &self,
         editor_public_internet: &mut RoutingDomainEditor,
         editor_local_network: &mut RoutingDomainEditor,
-    ) -> EyreResult<()> {
+    ) -> EyreResult<Vec<SocketAddress>> {
         log_net!("starting udp listeners");
         let routing_table = self.routing_table();
         let (listen_address, public_address, detect_address_changes) = {
@@ -291,6 +291,14 @@ impl Network {
             )
         };
 
+        // Tests can run nodes on a simulated network, where nothing is bound or discovered
+        #[cfg(any(test, feature = "sim-network"))]
+        if let Some(sim_network) = self.config.sim_network() {
+            return self
+                .start_sim_udp_listener(sim_network, listen_address, editor_public_internet)
+                .await;
+        }
+
         // Pick out UDP port we're going to use everywhere
         // Keep sockets around until the end of this function
         // to keep anyone else from binding in front of us
@@ -333,12 +341,19 @@ impl Network {
         // Add static public dialinfo if it's configured
         if let Some(public_address) = public_address.as_ref() {
             // Resolve statically configured public dialinfo
-            let mut public_sockaddrs = public_address
+            let public_sockaddrs: Vec<SocketAddr> = public_address
                 .to_socket_addrs()
-                .wrap_err(format!("Unable to resolve address: {}", public_address))?;
+                .wrap_err(format!("Unable to resolve address: {}", public_address))?
+                .collect();
+            if !detect_address_changes {
+                self.record_resolved_public_address(
+                    public_address.clone(),
+                    public_sockaddrs.iter().copied(),
+                );
+            }
 
             // Add all resolved addresses as public dialinfo
-            for pdi_addr in &mut public_sockaddrs {
+            for pdi_addr in public_sockaddrs {
                 let pdi = DialInfo::udp_from_socketaddr(pdi_addr);
 
                 // Register the public address
@@ -375,14 +390,19 @@ impl Network {
         }
 
         // Now create tasks for udp listeners
-        self.create_udp_listener_tasks().await
+        self.create_udp_listener_tasks().await?;
+
+        Ok(local_dial_info_list
+            .iter()
+            .map(|di| di.socket_address())
+            .collect())
     }
 
     pub(super) async fn start_ws_listeners(
         &self,
         editor_public_internet: &mut RoutingDomainEditor,
         editor_local_network: &mut RoutingDomainEditor,
-    ) -> EyreResult<()> {
+    ) -> EyreResult<Vec<SocketAddress>> {
         log_net!("starting ws listeners");
         let routing_table = self.routing_table();
         let (listen_address, url, path, detect_address_changes) = {
@@ -429,10 +449,17 @@ impl Network {
             split_url.scheme = "ws".to_owned();
 
             // Resolve static public hostnames
-            let global_socket_addrs = split_url
-                .host_port(80)
+            let host_port = split_url.host_port(80);
+            let global_socket_addrs: Vec<SocketAddr> = host_port
                 .to_socket_addrs()
-                .wrap_err("failed to resolve ws url")?;
+                .wrap_err("failed to resolve ws url")?
+                .collect();
+            if !detect_address_changes {
+                self.record_resolved_public_address(
+                    host_port.clone(),
+                    global_socket_addrs.iter().copied(),
+                );
+            }
 
             for gsa in global_socket_addrs {
                 let pdi = DialInfo::try_ws(SocketAddress::from_socket_addr(gsa), url.clone())
@@ -455,7 +482,7 @@ impl Network {
             }
         }
 
-        for socket_address in socket_addresses {
+        for socket_address in socket_addresses.iter().copied() {
             // Skip addresses we already did
             if registered_addresses.contains(&socket_address.ip_addr()) {
                 continue;
@@ -485,14 +512,14 @@ impl Network {
                 .insert(ProtocolType::WS);
         }
 
-        Ok(())
+        Ok(socket_addresses)
     }
 
     pub(super) async fn start_wss_listeners(
         &self,
         editor_public_internet: &mut RoutingDomainEditor,
         editor_local_network: &mut RoutingDomainEditor,
-    ) -> EyreResult<()> {
+    ) -> EyreResult<Vec<SocketAddress>> {
         log_net!("starting wss listeners");
 
         let (listen_address, url, detect_address_changes) = {
@@ -544,10 +571,17 @@ impl Network {
             split_url.scheme = "wss".to_owned();
 
             // Resolve static public hostnames
-            let global_socket_addrs = split_url
-                .host_port(443)
+            let host_port = split_url.host_port(443);
+            let global_socket_addrs: Vec<SocketAddr> = host_port
                 .to_socket_addrs()
-                .wrap_err("failed to resolve wss url")?;
+                .wrap_err("failed to resolve wss url")?
+                .collect();
+            if !detect_address_changes {
+                self.record_resolved_public_address(
+                    host_port.clone(),
+                    global_socket_addrs.iter().copied(),
+                );
+            }
             for gsa in global_socket_addrs {
                 let pdi = DialInfo::try_wss(SocketAddress::from_socket_addr(gsa), url.clone())
                     .wrap_err("try_wss failed")?;
@@ -578,26 +612,113 @@ impl Network {
                 .insert(ProtocolType::WSS);
         }
 
-        Ok(())
+        Ok(socket_addresses)
+    }
+
+    pub(super) async fn start_http_listeners(&self) -> EyreResult<Vec<SocketAddress>> {
+        log_net!("starting http listeners");
+        let listen_address = self
+            .config
+            .get()
+            .network
+            .application
+            .http
+            .listen_address
+            .clone();
+
+        // Pick out TCP port we're going to use everywhere
+        // An empty listen address shares the first port picked for our other TCP listeners
+        let (http_port, ip_addrs) = self.allocate_tcp_port(listen_address.clone()).await?;
+
+        info!(
+            "HTTP: starting listener on port {} at {:?}",
+            http_port, ip_addrs
+        );
+        let network_manager = self.network_manager();
+        let socket_addresses = self
+            .start_tcp_listener(
+                ip_addrs,
+                http_port,
+                false,
+                Box::new(move |c, t| {
+                    Box::new(HttpProtocolHandler::new(c, t, network_manager.clone()))
+                }),
+            )
+            .await?;
+        log_net!("HTTP: listener started on {:#?}", socket_addresses);
+
+        Ok(socket_addresses)
+    }
+
+    pub(super) async fn start_https_listeners(&self) -> EyreResult<Vec<SocketAddress>> {
+        log_net!("starting https listeners");
+
+        // Browsers will refuse to connect if our certificate has expired, so don't pretend to be listening
+        if self.is_certificate_expired() {
+            warn!("TLS certificate expired, not starting https listeners");
+            return Ok(Vec::new());
+        }
+
+        let listen_address = self
+            .config
+            .get()
+            .network
+            .application
+            .https
+            .listen_address
+            .clone();
+
+        // Pick out TCP port we're going to use everywhere
+        // An empty listen address shares the first port picked for our other TCP listeners
+        let (https_port, ip_addrs) = self.allocate_tcp_port(listen_address.clone()).await?;
+
+        info!(
+            "HTTPS: starting listener on port {} at {:?}",
+            https_port, ip_addrs
+        );
+        let network_manager = self.network_manager();
+        let socket_addresses = self
+            .start_tcp_listener(
+                ip_addrs,
+                https_port,
+                true,
+                Box::new(move |c, t| {
+                    Box::new(HttpProtocolHandler::new(c, t, network_manager.clone()))
+                }),
+            )
+            .await?;
+        log_net!("HTTPS: listener started on {:#?}", socket_addresses);
+
+        Ok(socket_addresses)
     }
 
     pub(super) async fn start_tcp_listeners(
         &self,
         editor_public_internet: &mut RoutingDomainEditor,
         editor_local_network: &mut RoutingDomainEditor,
-    ) -> EyreResult<()> {
+    ) -> EyreResult<Vec<SocketAddress>> {
         log_net!("starting tcp listeners");
 
         let routing_table = self.routing_table();
-        let (listen_address, public_address, detect_address_changes) = {
+        let (listen_address, public_address, detect_address_changes, tor_enabled, onion_address) = {
             let c = self.config.get();
             (
                 c.network.protocol.tcp.listen_address.clone(),
                 c.network.protocol.tcp.public_address.clone(),
                 c.network.detect_address_changes,
+                c.network.tor.enabled,
+                c.network.tor.onion_address.clone(),
             )
         };
 
+        // Tests can run nodes on a simulated network, where nothing is bound or discovered
+        #[cfg(any(test, feature = "sim-network"))]
+        if let Some(sim_network) = self.config.sim_network() {
+            return self
+                .start_sim_tcp_listener(sim_network, listen_address, editor_public_internet)
+                .await;
+        }
+
         // Pick out TCP port we're going to use everywhere
         // Keep sockets around until the end of this function
         // to keep anyone else from binding in front of us
@@ -621,9 +742,27 @@ impl Network {
         log_net!("TCP: listener started on {:#?}", socket_addresses);
 
         let mut static_public = false;
+
+        // When using Tor, only our onion service is published and our addresses are never revealed
+        if tor_enabled {
+            if let Some(onion_address) = onion_address {
+                let odi = DialInfo::try_onion_address(&onion_address)
+                    .wrap_err("invalid onion address")?;
+                editor_public_internet.register_dial_info(odi, DialInfoClass::Direct)?;
+                static_public = true;
+            }
+            if static_public {
+                self.inner
+                    .lock()
+                    .static_public_dialinfo
+                    .insert(ProtocolType::TCP);
+            }
+            return Ok(socket_addresses);
+        }
+
         let mut registered_addresses: HashSet<IpAddr> = HashSet::new();
 
-        for socket_address in socket_addresses {
+        for socket_address in socket_addresses.iter().copied() {
             let di = DialInfo::tcp(socket_address);
 
             // Register global dial info if no public address is specified
@@ -642,12 +781,19 @@ impl Network {
         // Add static public dialinfo if it's configured
         if let Some(public_address) = public_address.as_ref() {
             // Resolve statically configured public dialinfo
-            let mut public_sockaddrs = public_address
+            let public_sockaddrs: Vec<SocketAddr> = public_address
                 .to_socket_addrs()
-                .wrap_err("failed to resolve tcp address")?;
+                .wrap_err("failed to resolve tcp address")?
+                .collect();
+            if !detect_address_changes {
+                self.record_resolved_public_address(
+                    public_address.clone(),
+                    public_sockaddrs.iter().copied(),
+                );
+            }
 
             // Add all resolved addresses as public dialinfo
-            for pdi_addr in &mut public_sockaddrs {
+            for pdi_addr in public_sockaddrs {
                 // Skip addresses we already did
                 if registered_addresses.contains(&pdi_addr.ip()) {
                     continue;
@@ -674,6 +820,6 @@ impl Network {
                 .insert(ProtocolType::TCP);
         }
 
-        Ok(())
+        Ok(socket_addresses)
     }
 }
@@ -0,0 +1,45 @@
+use super::*;
+use der::asn1::{AnyRef, GeneralizedTime, UtcTime};
+use der::{Decode, Reader, SliceReader, Tag, TagNumber};
+
+/// Read the notAfter time of an X.509 certificate in DER format, as a duration since the unix epoch
+fn certificate_not_after(der: &[u8]) -> der::Result<Duration> {
+    // Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signatureValue }
+    let cert = AnyRef::from_der(der)?;
+    cert.tag().assert_eq(Tag::Sequence)?;
+    let mut cert = SliceReader::new(cert.value())?;
+    let tbs_cert = cert.decode::<AnyRef>()?;
+    tbs_cert.tag().assert_eq(Tag::Sequence)?;
+    let mut tbs_cert = SliceReader::new(tbs_cert.value())?;
+
+    // Skip the optional version, then the serial number, signature algorithm and issuer
+    let version_tag = Tag::ContextSpecific {
+        constructed: true,
+        number: TagNumber::N0,
+    };
+    if tbs_cert.peek_tag()? == version_tag {
+        tbs_cert.decode::<AnyRef>()?;
+    }
+    for _ in 0..3 {
+        tbs_cert.decode::<AnyRef>()?;
+    }
+
+    // Validity ::= SEQUENCE { notBefore Time, notAfter Time }
+    let validity = tbs_cert.decode::<AnyRef>()?;
+    validity.tag().assert_eq(Tag::Sequence)?;
+    let mut validity = SliceReader::new(validity.value())?;
+    validity.decode::<AnyRef>()?;
+    match validity.peek_tag()? {
+        Tag::UtcTime => Ok(validity.decode::<UtcTime>()?.to_unix_duration()),
+        Tag::GeneralizedTime => Ok(validity.decode::<GeneralizedTime>()?.to_unix_duration()),
+        tag => Err(tag.unexpected_error(Some(Tag::UtcTime))),
+    }
+}
+
+/// Get when an X.509 certificate in DER format stops being valid
+///
+/// Only walks as far into the certificate as the validity period, nothing is verified.
+pub(in crate::network_manager) fn certificate_expiration(der: &[u8]) -> Option<Timestamp> {
+    let not_after = certificate_not_after(der).ok()?;
+    Some(Timestamp::new(u64::try_from(not_after.as_micros()).ok()?))
+}
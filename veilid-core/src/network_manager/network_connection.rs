@@ -68,12 +68,33 @@ cfg_if::cfg_if! {
 ///////////////////////////////////////////////////////////
 // Top-level protocol independent network connection object
 
+/// Why a connection's processor loop exited
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ConnectionCloseReason {
+    /// Nothing was received for the inactivity timeout
+    Timeout,
+    /// The remote end closed the connection, or it broke while sending or receiving
+    RemoteClose,
+    /// The remote end sent data that could not be framed or processed
+    ProtocolError,
+    /// The remote address is punished
+    Filtered,
+    /// We closed the connection ourselves
+    Local,
+}
+
+impl ConnectionCloseReason {
+    /// If the close says something about the behavior of the peer rather than the network
+    pub fn is_misbehavior(&self) -> bool {
+        matches!(self, Self::ProtocolError | Self::Filtered)
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 enum RecvLoopAction {
     Send,
     Recv,
-    Finish,
-    Timeout,
+    Finish(ConnectionCloseReason),
 }
 
 #[derive(Debug, Clone)]
@@ -90,7 +111,7 @@ pub(in crate::network_manager) struct NetworkConnection {
     processor: Option<MustJoinHandle<()>>,
     established_time: Timestamp,
     stats: Arc<Mutex<NetworkConnectionStats>>,
-    sender: flume::Sender<(Option<Id>, Vec<u8>)>,
+    sender: SendQueueSender,
     stop_source: Option<StopSource>,
     protected_nr: Option<NodeRef>,
     ref_count: usize,
@@ -108,7 +129,7 @@ impl Drop for NetworkConnection {
 impl NetworkConnection {
     pub(super) fn dummy(id: NetworkConnectionId, flow: Flow) -> Self {
         // Create handle for sending (dummy is immediately disconnected)
-        let (sender, _receiver) = flume::bounded(get_concurrency() as usize);
+        let (sender, _receiver) = send_queue(get_concurrency() as usize);
 
         Self {
             connection_id: id,
@@ -131,12 +152,13 @@ impl NetworkConnection {
         manager_stop_token: StopToken,
         protocol_connection: ProtocolNetworkConnection,
         connection_id: NetworkConnectionId,
+        dial_info: Option<DialInfo>,
     ) -> Self {
         // Get flow
         let flow = protocol_connection.flow();
 
-        // Create handle for sending
-        let (sender, receiver) = flume::bounded(get_concurrency() as usize);
+        // Create prioritized queues for sending
+        let (sender, receiver) = send_queue(get_concurrency() as usize);
 
         // Create stats
         let stats = Arc::new(Mutex::new(NetworkConnectionStats {
@@ -154,6 +176,7 @@ impl NetworkConnection {
             manager_stop_token,
             connection_id,
             flow,
+            dial_info,
             receiver,
             protocol_connection,
             stats.clone(),
@@ -271,7 +294,8 @@ impl NetworkConnection {
         manager_stop_token: StopToken,
         connection_id: NetworkConnectionId,
         flow: Flow,
-        receiver: flume::Receiver<(Option<Id>, Vec<u8>)>,
+        dial_info: Option<DialInfo>,
+        receiver: SendQueueReceiver,
         protocol_connection: ProtocolNetworkConnection,
         stats: Arc<Mutex<NetworkConnectionStats>>,
     ) -> SendPinBoxFuture<()> {
@@ -293,20 +317,20 @@ impl NetworkConnection {
                 sleep(connection_manager.connection_inactivity_timeout_ms()).then(|_| async {
                     // timeout
                     log_net!("== Connection timeout on {:?}", flow);
-                    RecvLoopAction::Timeout
+                    RecvLoopAction::Finish(ConnectionCloseReason::Timeout)
                 })
             };
             let timer = MutableFuture::new(new_timer());
 
             unord.push(system_boxed(timer.clone().instrument(Span::current())));
 
-            loop {
+            let close_reason = loop {
                 // Add another message sender future if necessary
                 if need_sender {
                     need_sender = false;
                     let sender_fut = receiver.recv_async().then(|res| async {
                         match res {
-                            Ok((_span_id, message)) => {
+                            Ok((_span_id, _priority, message)) => {
 
                                 let recv_span = span!(Level::TRACE, "process_connection recv");
                                 // xxx: causes crash (Missing otel data span extensions)
@@ -325,7 +349,7 @@ impl NetworkConnection {
                                 {
                                     // Sending the packet along can fail, if so, this connection is dead
                                     log_net!(debug e);
-                                    RecvLoopAction::Finish
+                                    RecvLoopAction::Finish(ConnectionCloseReason::RemoteClose)
                                 } else {
                                     RecvLoopAction::Send
                                 }
@@ -333,7 +357,7 @@ impl NetworkConnection {
                             Err(e) => {
                                 // All senders gone, shouldn't happen since we store one alongside the join handle
                                 log_net!(warn e);
-                                RecvLoopAction::Finish
+                                RecvLoopAction::Finish(ConnectionCloseReason::Local)
                             }
                         }
                     });
@@ -351,24 +375,29 @@ impl NetworkConnection {
 
                                     // Check to see if it is punished
                                     if address_filter.is_ip_addr_punished(peer_address.socket_addr().ip()) {
-                                        return RecvLoopAction::Finish;
+                                        return RecvLoopAction::Finish(ConnectionCloseReason::Filtered);
                                     }
 
                                     // Check for connection close
                                     if v.is_no_connection() {
                                         log_net!(debug "Connection closed from: {} ({})", peer_address.socket_addr(), peer_address.protocol_type());
-                                        return RecvLoopAction::Finish;
+                                        return RecvLoopAction::Finish(ConnectionCloseReason::RemoteClose);
                                     }
 
                                     // Punish invalid framing (tcp framing or websocket framing)
                                     if v.is_invalid_message() {
                                         address_filter.punish_ip_addr(peer_address.socket_addr().ip());
-                                        return RecvLoopAction::Finish;
+                                        return RecvLoopAction::Finish(ConnectionCloseReason::ProtocolError);
                                     }
 
                                     // Log other network results
+                                    let other_reason = if v.is_timeout() {
+                                        ConnectionCloseReason::Timeout
+                                    } else {
+                                        ConnectionCloseReason::RemoteClose
+                                    };
                                     let mut message = network_result_value_or_log!(v => [ format!(": protocol_connection={:?}", protocol_connection) ] {
-                                        return RecvLoopAction::Finish;
+                                        return RecvLoopAction::Finish(other_reason);
                                     });
 
                                     // Pass received messages up to the network manager for processing
@@ -377,7 +406,7 @@ impl NetworkConnection {
                                         .await
                                     {
                                         log_net!(debug "failed to process received envelope: {}", e);
-                                        RecvLoopAction::Finish
+                                        RecvLoopAction::Finish(ConnectionCloseReason::ProtocolError)
                                     } else {
                                         
                                         // Touch the LRU for this connection
@@ -389,7 +418,7 @@ impl NetworkConnection {
                                 Err(e) => {
                                     // Connection unable to receive, closed
                                     log_net!(error "connection unable to receive: {}", e);
-                                    RecvLoopAction::Finish
+                                    RecvLoopAction::Finish(ConnectionCloseReason::RemoteClose)
                                 }
                             }
                         });
@@ -415,8 +444,8 @@ impl NetworkConnection {
 
                         need_receiver = true;
                     }
-                    Ok(Some(RecvLoopAction::Finish) | Some(RecvLoopAction::Timeout)) => {
-                        break;
+                    Ok(Some(RecvLoopAction::Finish(close_reason))) => {
+                        break close_reason;
                     }
                     Ok(None) => {
                         // Should not happen
@@ -424,21 +453,25 @@ impl NetworkConnection {
                     }
                     Err(_) => {
                         // Either one of the stop tokens
-                        break;
+                        break ConnectionCloseReason::Local;
                     }
                 }
-            }
+            };
 
             log_net!(
-                "== Connection loop finished flow={:?}",
-                flow
+                "== Connection loop finished flow={:?} reason={:?}",
+                flow,
+                close_reason
             );
 
             // Let the connection manager know the receive loop exited
             connection_manager
-                .report_connection_finished(connection_id)
+                .report_connection_finished(connection_id, close_reason)
                 .await;
 
+            // Anything still queued goes out over another connection to the same remote
+            connection_manager.reroute_pending_messages(flow, dial_info, receiver.drain());
+
             // Close the low level socket
             if let Err(e) = protocol_connection.close().await {
                 log_net!(debug "Protocol connection close error: {}", e);
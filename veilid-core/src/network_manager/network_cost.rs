@@ -0,0 +1,39 @@
+use super::*;
+
+/// Capabilities that carry traffic for other nodes, which we stop offering while relay-only
+pub const NETWORK_COST_WITHHELD_CAPABILITIES: &[Capability] = &[
+    CAP_ROUTE,
+    CAP_SIGNAL,
+    CAP_RELAY,
+    CAP_VALIDATE_DIAL_INFO,
+    CAP_DHT,
+    CAP_DHT_WATCH,
+];
+
+impl NetworkManager {
+    /// The policy currently applied for the cost of the network we are on
+    pub fn network_cost_policy(&self) -> VeilidConfigNetworkCostPolicy {
+        *self.unlocked_inner.network_cost_policy.lock()
+    }
+
+    /// Apply a new policy for the cost of the network we are on
+    ///
+    /// Going in or out of relay-only mode changes what we listen on and what we
+    /// advertise, so that restarts the network if it is running, unless low power
+    /// mode already keeps us relay-only.
+    pub fn set_network_cost_policy(&self, policy: VeilidConfigNetworkCostPolicy) {
+        let old_policy =
+            core::mem::replace(&mut *self.unlocked_inner.network_cost_policy.lock(), policy);
+        if old_policy == policy {
+            return;
+        }
+        log_net!(debug "network cost policy changed from {} to {}", old_policy, policy);
+
+        if old_policy.is_relay_only() != policy.is_relay_only()
+            && !self.is_low_power()
+            && self.unlocked_inner.components.read().is_some()
+        {
+            self.restart_network();
+        }
+    }
+}
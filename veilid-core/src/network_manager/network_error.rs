@@ -0,0 +1,133 @@
+use super::*;
+use std::io;
+
+/// Why establishing a connection or sending data failed
+///
+/// Protocol handlers work in terms of io errors, which do not say whether trying again
+/// could help. Classifying them once here lets callers decide whether to retry, and
+/// turns them into the same NetworkResult everywhere.
+#[derive(ThisError, Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub enum NetworkError {
+    #[error("[NetworkError: Timeout]")]
+    Timeout,
+    #[error("[NetworkError: Unreachable({0})]")]
+    Unreachable(String),
+    #[error("[NetworkError: AddrInUse({0})]")]
+    AddrInUse(String),
+    #[error("[NetworkError: TlsError({0})]")]
+    TlsError(String),
+    #[error("[NetworkError: FilteredByPolicy({0})]")]
+    FilteredByPolicy(String),
+    #[error("[NetworkError: InvalidMessage({0})]")]
+    InvalidMessage(String),
+    #[error("[NetworkError: ShuttingDown]")]
+    ShuttingDown,
+    #[error("[NetworkError: Io({0})]")]
+    Io(String),
+}
+
+pub type NetworkErrorResult<T> = Result<T, NetworkError>;
+
+impl NetworkError {
+    pub fn unreachable<X: ToString>(x: X) -> Self {
+        Self::Unreachable(x.to_string())
+    }
+    pub fn addr_in_use<X: ToString>(x: X) -> Self {
+        Self::AddrInUse(x.to_string())
+    }
+    pub fn tls_error<X: ToString>(x: X) -> Self {
+        Self::TlsError(x.to_string())
+    }
+    pub fn filtered_by_policy<X: ToString>(x: X) -> Self {
+        Self::FilteredByPolicy(x.to_string())
+    }
+    pub fn invalid_message<X: ToString>(x: X) -> Self {
+        Self::InvalidMessage(x.to_string())
+    }
+
+    /// Classify an io error from a protocol handler
+    pub fn from_io_error(e: io::Error) -> Self {
+        // Protocol handlers pass classified errors through io errors
+        if let Some(ne) = e.get_ref().and_then(|x| x.downcast_ref::<NetworkError>()) {
+            return ne.clone();
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(os_err) = e.raw_os_error() {
+            if os_err == libc::EHOSTUNREACH || os_err == libc::ENETUNREACH {
+                return Self::Unreachable(e.to_string());
+            }
+        }
+        match e.kind() {
+            io::ErrorKind::TimedOut => Self::Timeout,
+            io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset => Self::Unreachable(e.to_string()),
+            io::ErrorKind::AddrInUse | io::ErrorKind::AddrNotAvailable => {
+                Self::AddrInUse(e.to_string())
+            }
+            io::ErrorKind::InvalidData => Self::InvalidMessage(e.to_string()),
+            _ => Self::Io(e.to_string()),
+        }
+    }
+
+    /// Classify the result of a protocol handler operation
+    pub fn from_io_network_result<T>(r: io::Result<NetworkResult<T>>) -> NetworkErrorResult<T> {
+        match r {
+            Ok(NetworkResult::Value(v)) => Ok(v),
+            Ok(NetworkResult::Timeout) => Err(Self::Timeout),
+            Ok(NetworkResult::ServiceUnavailable(s)) => Err(Self::Unreachable(s)),
+            Ok(NetworkResult::NoConnection(e)) => Err(Self::Unreachable(e.to_string())),
+            Ok(NetworkResult::AlreadyExists(e)) => Err(Self::AddrInUse(e.to_string())),
+            Ok(NetworkResult::InvalidMessage(s)) => Err(Self::InvalidMessage(s)),
+            Err(e) => Err(Self::from_io_error(e)),
+        }
+    }
+
+    /// If the same operation could succeed when tried again
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Timeout | Self::Unreachable(_) | Self::AddrInUse(_) | Self::Io(_) => true,
+            Self::TlsError(_)
+            | Self::FilteredByPolicy(_)
+            | Self::InvalidMessage(_)
+            | Self::ShuttingDown => false,
+        }
+    }
+
+    /// Convert to the NetworkResult used by the send paths
+    /// Failures that only concern the remote end become a NetworkResult, the rest stay errors
+    pub fn into_network_result<T>(self) -> NetworkErrorResult<NetworkResult<T>> {
+        match self {
+            Self::Timeout => Ok(NetworkResult::Timeout),
+            Self::Unreachable(s) | Self::FilteredByPolicy(s) => {
+                Ok(NetworkResult::no_connection_other(s))
+            }
+            Self::AddrInUse(s) => Ok(NetworkResult::already_exists(io_error_other!(s))),
+            Self::InvalidMessage(s) => Ok(NetworkResult::InvalidMessage(s)),
+            Self::TlsError(_) | Self::ShuttingDown | Self::Io(_) => Err(self),
+        }
+    }
+}
+
+pub(crate) trait ClassifiedNetworkResultExt<T> {
+    fn classified(self) -> NetworkErrorResult<NetworkResult<T>>;
+}
+
+impl<T> ClassifiedNetworkResultExt<T> for io::Result<NetworkResult<T>> {
+    /// Classify the result of a protocol handler operation and convert it to the NetworkResult used by the send paths
+    fn classified(self) -> NetworkErrorResult<NetworkResult<T>> {
+        match NetworkError::from_io_network_result(self) {
+            Ok(v) => Ok(NetworkResult::Value(v)),
+            Err(e) => e.into_network_result(),
+        }
+    }
+}
+
+impl From<NetworkError> for io::Error {
+    fn from(e: NetworkError) -> Self {
+        to_io_error_other(e)
+    }
+}
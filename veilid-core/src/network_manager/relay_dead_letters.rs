@@ -0,0 +1,38 @@
+use super::*;
+
+impl NetworkManager {
+    /// Account for a message we were supposed to pass along to another node but could not deliver
+    ///
+    /// Relayed envelopes and routed operations are opaque to us, so the only thing we can do
+    /// with them when the next hop is unreachable is drop them. Counting the failures per
+    /// destination lets the relay operator see which nodes they are failing to serve.
+    pub fn record_relay_failure<S: ToString>(&self, destination: TypedKey, reason: S) {
+        let cur_ts = get_aligned_timestamp();
+        let mut inner = self.inner.lock();
+        let mut dead_letter =
+            inner
+                .relay_dead_letters
+                .remove(&destination)
+                .unwrap_or(RelayDeadLetter {
+                    destination,
+                    failure_count: 0,
+                    first_failure_ts: cur_ts,
+                    last_failure_ts: cur_ts,
+                    last_error: String::new(),
+                });
+        dead_letter.failure_count = dead_letter.failure_count.saturating_add(1);
+        dead_letter.last_failure_ts = cur_ts;
+        dead_letter.last_error = reason.to_string();
+        inner.relay_dead_letters.insert(destination, dead_letter);
+    }
+
+    /// Get the destinations we most recently failed to relay messages to, most recent failure last
+    pub fn relay_dead_letters(&self) -> Vec<RelayDeadLetter> {
+        self.inner
+            .lock()
+            .relay_dead_letters
+            .iter()
+            .map(|(_k, v)| v.clone())
+            .collect()
+    }
+}
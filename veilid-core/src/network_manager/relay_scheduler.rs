@@ -0,0 +1,283 @@
+use super::*;
+
+/// Bytes each relay client may have forwarded per round of the scheduler
+/// At least the size of the largest envelope, so every round forwards something for each client
+pub const RELAY_SCHEDULER_QUANTUM: usize = MAX_ENVELOPE_SIZE;
+/// How many relay clients we keep accounting for
+pub const RELAY_CLIENT_TABLE_SIZE: usize = 256;
+
+/// Per-client limits on relayed traffic
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct RelayClientLimits {
+    /// Bytes per second a client may have relayed, zero for no limit
+    pub bytes_per_sec: u32,
+    /// Bytes a client may have waiting to be relayed at once
+    pub max_queued_bytes: u32,
+}
+
+/// Why a relayed envelope was not queued
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum RelayRejection {
+    /// The client is sending faster than its bandwidth limit
+    OverBandwidth,
+    /// The client already has too much waiting to be relayed
+    QueueFull,
+}
+
+impl fmt::Display for RelayRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelayRejection::OverBandwidth => write!(f, "relay client over bandwidth limit"),
+            RelayRejection::QueueFull => write!(f, "relay client queue full"),
+        }
+    }
+}
+
+/// Accounting for one relay client, kept while the client is idle
+struct RelayClientAccounting {
+    relayed_bytes: ByteCount,
+    dropped_bytes: ByteCount,
+    allowance: u64,
+    allowance_ts: Timestamp,
+}
+
+/// Envelopes waiting to be relayed for one client
+struct RelayClientQueue<T> {
+    items: VecDeque<(T, usize)>,
+    queued_bytes: usize,
+    deficit: usize,
+}
+
+/// Fair scheduler over relayed traffic, using deficit round robin between the clients
+/// that sent the envelopes
+///
+/// Every client with envelopes waiting gets the same number of bytes forwarded per round,
+/// so one noisy client can only fill its own queue and not monopolize our upstream.
+/// On top of that, each client is held to a bandwidth limit and a queue size limit,
+/// and envelopes over either limit are dropped as they arrive.
+pub(super) struct RelayScheduler<T> {
+    accounting: LruCache<TypedKey, RelayClientAccounting>,
+    queues: HashMap<TypedKey, RelayClientQueue<T>>,
+    active: VecDeque<TypedKey>,
+}
+
+impl<T> RelayScheduler<T> {
+    pub fn new() -> Self {
+        Self {
+            accounting: LruCache::new(RELAY_CLIENT_TABLE_SIZE),
+            queues: HashMap::new(),
+            active: VecDeque::new(),
+        }
+    }
+
+    /// Queue an envelope of `size` bytes sent by `client`
+    pub fn enqueue(
+        &mut self,
+        client: TypedKey,
+        item: T,
+        size: usize,
+        limits: RelayClientLimits,
+        cur_ts: Timestamp,
+    ) -> Result<(), RelayRejection> {
+        let queued_bytes = self
+            .queues
+            .get(&client)
+            .map(|q| q.queued_bytes)
+            .unwrap_or(0);
+        if !self.accounting.contains_key(&client) {
+            self.accounting.insert(
+                client,
+                RelayClientAccounting {
+                    relayed_bytes: ByteCount::new(0),
+                    dropped_bytes: ByteCount::new(0),
+                    allowance: limits.bytes_per_sec as u64,
+                    allowance_ts: cur_ts,
+                },
+            );
+        }
+        let accounting = self.accounting.get_mut(&client).unwrap();
+
+        // Refill the bandwidth allowance, allowing bursts of up to one second's worth
+        if limits.bytes_per_sec != 0 {
+            let elapsed_us = cur_ts.saturating_sub(accounting.allowance_ts).as_u64();
+            let refill = (elapsed_us as u128 * limits.bytes_per_sec as u128 / 1_000_000u128)
+                .min(u64::MAX as u128) as u64;
+            accounting.allowance = accounting
+                .allowance
+                .saturating_add(refill)
+                .min(limits.bytes_per_sec as u64);
+            accounting.allowance_ts = cur_ts;
+
+            if accounting.allowance < size as u64 {
+                accounting.dropped_bytes += ByteCount::new(size as u64);
+                return Err(RelayRejection::OverBandwidth);
+            }
+        }
+        if queued_bytes + size > limits.max_queued_bytes as usize {
+            accounting.dropped_bytes += ByteCount::new(size as u64);
+            return Err(RelayRejection::QueueFull);
+        }
+        if limits.bytes_per_sec != 0 {
+            accounting.allowance -= size as u64;
+        }
+
+        let queue = self
+            .queues
+            .entry(client)
+            .or_insert_with(|| RelayClientQueue {
+                items: VecDeque::new(),
+                queued_bytes: 0,
+                deficit: 0,
+            });
+        if queue.items.is_empty() {
+            // Newly active clients start with one round's worth
+            queue.deficit = RELAY_SCHEDULER_QUANTUM;
+            self.active.push_back(client);
+        }
+        queue.items.push_back((item, size));
+        queue.queued_bytes += size;
+
+        Ok(())
+    }
+
+    /// Take the next envelope to relay, along with the client that sent it
+    pub fn next(&mut self) -> Option<(TypedKey, T)> {
+        loop {
+            let client = *self.active.front()?;
+            let queue = self.queues.get_mut(&client).unwrap();
+            let size = queue.items.front().unwrap().1;
+
+            if queue.deficit < size {
+                // This client has used up its round, so it goes to the back with another quantum
+                queue.deficit += RELAY_SCHEDULER_QUANTUM;
+                self.active.rotate_left(1);
+                continue;
+            }
+
+            queue.deficit -= size;
+            queue.queued_bytes -= size;
+            let (item, _) = queue.items.pop_front().unwrap();
+            if queue.items.is_empty() {
+                self.queues.remove(&client);
+                self.active.pop_front();
+            }
+            if let Some(accounting) = self.accounting.peek_mut(&client) {
+                accounting.relayed_bytes += ByteCount::new(size as u64);
+            }
+
+            return Some((client, item));
+        }
+    }
+
+    /// Check if any envelopes are waiting to be relayed
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Get the accounting for each relay client, least recently active first
+    pub fn client_stats(&self) -> Vec<RelayClientStats> {
+        self.accounting
+            .iter()
+            .map(|(client, accounting)| RelayClientStats {
+                client: *client,
+                relayed_bytes: accounting.relayed_bytes,
+                dropped_bytes: accounting.dropped_bytes,
+                queued_bytes: ByteCount::new(
+                    self.queues
+                        .get(client)
+                        .map(|q| q.queued_bytes as u64)
+                        .unwrap_or(0),
+                ),
+            })
+            .collect()
+    }
+}
+
+/// An envelope we are passing along for a relay client
+pub(super) struct RelayedEnvelope {
+    pub recipient_id: TypedKey,
+    pub relay_nr: NodeRef,
+    pub data: Vec<u8>,
+}
+
+impl NetworkManager {
+    /// Queue an envelope to be relayed on behalf of the node that sent it
+    ///
+    /// Envelopes are forwarded in the background, taking turns between the clients that sent them.
+    pub(super) fn enqueue_relayed_envelope(
+        &self,
+        sender_id: TypedKey,
+        envelope: RelayedEnvelope,
+    ) -> Result<(), RelayRejection> {
+        let limits = self.with_config(|c| RelayClientLimits {
+            bytes_per_sec: c.network.max_relay_client_bytes_per_sec,
+            max_queued_bytes: c.network.max_relay_client_queued_bytes,
+        });
+        let cur_ts = get_aligned_timestamp();
+        let size = envelope.data.len();
+
+        let start_worker = {
+            let mut inner = self.inner.lock();
+            inner
+                .relay_scheduler
+                .enqueue(sender_id, envelope, size, limits, cur_ts)?;
+            !std::mem::replace(&mut inner.relay_worker_running, true)
+        };
+        if start_worker {
+            spawn_detached(self.clone().relay_worker().instrument(Span::current()));
+        }
+        Ok(())
+    }
+
+    /// Forward queued relayed envelopes until there are none left
+    async fn relay_worker(self) {
+        loop {
+            let next = {
+                let mut inner = self.inner.lock();
+                let next = inner.relay_scheduler.next();
+                if next.is_none() {
+                    inner.relay_worker_running = false;
+                }
+                next
+            };
+            let Some((_sender_id, envelope)) = next else {
+                return;
+            };
+            self.forward_relayed_envelope(envelope).await;
+        }
+    }
+
+    async fn forward_relayed_envelope(&self, envelope: RelayedEnvelope) {
+        let RelayedEnvelope {
+            recipient_id,
+            relay_nr,
+            data,
+        } = envelope;
+
+        // Relay the packet to the desired destination
+        log_net!("relaying {} bytes to {}", data.len(), relay_nr);
+        let data_len = data.len();
+        let send_result = match self
+            .send_data(relay_nr.clone(), data, SendPriority::Normal)
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                log_net!(debug "failed to forward envelope: {}" ,e);
+                self.record_relay_failure(recipient_id, format!("failed to forward: {}", e));
+                return;
+            }
+        };
+        if !send_result.is_value() {
+            self.record_relay_failure(recipient_id, &send_result);
+        }
+        network_result_value_or_log!(send_result => [ format!(": relay_nr={}, data.len={}", relay_nr, data_len) ] {
+            return;
+        });
+    }
+
+    /// Get the traffic we relayed for each relay client, least recently active first
+    pub fn relay_client_stats(&self) -> Vec<RelayClientStats> {
+        self.inner.lock().relay_scheduler.client_stats()
+    }
+}
@@ -11,16 +11,20 @@ impl NetworkManager {
     ///
     /// Sending to a node requires determining a NetworkClass compatible contact method 
     /// between the source and destination node
+    ///
+    /// Control messages may be sent ahead of data already queued on the same connection,
+    /// everything else is sent in the order it was queued.
     pub(crate) async fn send_data(
         &self,
         destination_node_ref: NodeRef,
         data: Vec<u8>,
+        priority: SendPriority,
     ) -> EyreResult<NetworkResult<SendDataMethod>> {
         // First try to send data to the last flow we've seen this peer on
         let data = if let Some(flow) = destination_node_ref.last_flow() {
             match self
                 .net()
-                .send_data_to_existing_flow(flow, data)
+                .send_data_to_existing_flow(flow, data, priority)
                 .await?
             {
                 SendDataToExistingFlowResult::Sent(unique_flow) => {
@@ -50,13 +54,14 @@ impl NetworkManager {
         // Get the best way to contact this node
         let possibly_relayed_contact_method = self.get_node_contact_method(destination_node_ref.clone())?;
 
-        self.try_possibly_relayed_contact_method(possibly_relayed_contact_method, destination_node_ref, data).await
+        self.try_possibly_relayed_contact_method(possibly_relayed_contact_method, destination_node_ref, data, priority).await
     }
 
     pub(crate) fn try_possibly_relayed_contact_method(&self, 
         possibly_relayed_contact_method: NodeContactMethod,
         destination_node_ref: NodeRef,
         data: Vec<u8>,
+        priority: SendPriority,
     ) -> SendPinBoxFuture<EyreResult<NetworkResult<SendDataMethod>>> {
         let this = self.clone();
         Box::pin(
@@ -100,42 +105,54 @@ impl NetworkManager {
                         );
                     }
                     NodeContactMethod::Direct(dial_info) => {
-                        network_result_try!(
-                            this.send_data_ncm_direct(target_node_ref, dial_info, data).await?
-                        )
+                        let nres =
+                            this.send_data_ncm_direct(target_node_ref.clone(), dial_info, data.clone(), priority)
+                                .await?;
+                        if opt_relayed_contact_method.is_none() && matches!(nres, NetworkResult::Timeout | NetworkResult::NoConnection(_)) {
+                            // Failed to reach the node directly, continue the cascade with a signal or the inbound relay
+                            let fallback_contact_method = this.get_node_fallback_contact_method(target_node_ref.clone())?;
+                            if matches!(fallback_contact_method, NodeContactMethod::SignalReverse(_, _) | NodeContactMethod::SignalHolePunch(_, _) | NodeContactMethod::InboundRelay(_)) {
+                                log_network_result!(debug "Direct contact failed to {}, falling back to {:?}", target_node_ref, fallback_contact_method);
+                                network_result_try!(this.try_possibly_relayed_contact_method(fallback_contact_method, destination_node_ref, data, priority).await?)
+                            } else {
+                                network_result_try!(nres)
+                            }
+                        } else {
+                            network_result_try!(nres)
+                        }
                     }
                     NodeContactMethod::SignalReverse(relay_nr, target_node_ref) => {
                         let nres = 
-                            this.send_data_ncm_signal_reverse(relay_nr.clone(), target_node_ref.clone(), data.clone())
+                            this.send_data_ncm_signal_reverse(relay_nr.clone(), target_node_ref.clone(), data.clone(), priority)
                                 .await?;
                         if matches!(nres, NetworkResult::Timeout) {
                             // Failed to holepunch, fallback to inbound relay
                             log_network_result!(debug "Reverse connection failed to {}, falling back to inbound relay via {}", target_node_ref, relay_nr);
-                            network_result_try!(this.try_possibly_relayed_contact_method(NodeContactMethod::InboundRelay(relay_nr), destination_node_ref, data).await?)
+                            network_result_try!(this.try_possibly_relayed_contact_method(NodeContactMethod::InboundRelay(relay_nr), destination_node_ref, data, priority).await?)
                         } else {
                             network_result_try!(nres)
                         }
                     }
                     NodeContactMethod::SignalHolePunch(relay_nr, target_node_ref) => {
                         let nres = 
-                            this.send_data_ncm_signal_hole_punch(relay_nr.clone(), target_node_ref.clone(), data.clone())
+                            this.send_data_ncm_signal_hole_punch(relay_nr.clone(), target_node_ref.clone(), data.clone(), priority)
                                 .await?;
                         if matches!(nres, NetworkResult::Timeout) {
                             // Failed to holepunch, fallback to inbound relay
                             log_network_result!(debug "Hole punch failed to {}, falling back to inbound relay via {}", target_node_ref, relay_nr);
-                            network_result_try!(this.try_possibly_relayed_contact_method(NodeContactMethod::InboundRelay(relay_nr), destination_node_ref, data).await?)
+                            network_result_try!(this.try_possibly_relayed_contact_method(NodeContactMethod::InboundRelay(relay_nr), destination_node_ref, data, priority).await?)
                         } else {
                             network_result_try!(nres)
                         }
                     }
                     NodeContactMethod::Existing => {
                         network_result_try!(
-                            this.send_data_ncm_existing(target_node_ref, data).await?
+                            this.send_data_ncm_existing(target_node_ref, data, priority).await?
                         )
                     }
                     NodeContactMethod::Unreachable => {
                         network_result_try!(
-                            this.send_data_ncm_unreachable(target_node_ref, data)
+                            this.send_data_ncm_unreachable(target_node_ref, data, priority)
                                 .await?
                         )
                     }
@@ -153,6 +170,7 @@ impl NetworkManager {
         &self,
         target_node_ref: NodeRef,
         data: Vec<u8>,
+        priority: SendPriority,
     ) -> EyreResult<NetworkResult<SendDataMethod>> {
         // First try to send data to the last connection we've seen this peer on
         let Some(flow) = target_node_ref.last_flow() else {
@@ -163,7 +181,7 @@ impl NetworkManager {
 
         let unique_flow = match self
             .net()
-            .send_data_to_existing_flow(flow, data)
+            .send_data_to_existing_flow(flow, data, priority)
             .await?
         {
             SendDataToExistingFlowResult::Sent(unique_flow) => unique_flow,
@@ -189,6 +207,7 @@ impl NetworkManager {
         &self,
         target_node_ref: NodeRef,
         data: Vec<u8>,
+        priority: SendPriority,
     ) -> EyreResult<NetworkResult<SendDataMethod>> {
         // Try to send data to the last socket we've seen this peer on
         let Some(flow) = target_node_ref.last_flow() else {
@@ -199,7 +218,7 @@ impl NetworkManager {
 
         let unique_flow = match self
             .net()
-            .send_data_to_existing_flow(flow, data)
+            .send_data_to_existing_flow(flow, data, priority)
             .await?   
         {
             SendDataToExistingFlowResult::Sent(unique_flow) => unique_flow,
@@ -226,12 +245,13 @@ impl NetworkManager {
         relay_nr: NodeRef,
         target_node_ref: NodeRef,
         data: Vec<u8>,
+        priority: SendPriority,
     ) -> EyreResult<NetworkResult<SendDataMethod>> {
         // First try to send data to the last socket we've seen this peer on
         let data = if let Some(flow) = target_node_ref.last_flow() {
             match self
                 .net()
-                .send_data_to_existing_flow(flow, data)
+                .send_data_to_existing_flow(flow, data, priority)
                 .await?
             {
                 SendDataToExistingFlowResult::Sent(unique_flow) => {
@@ -257,7 +277,7 @@ impl NetworkManager {
         };
 
         let unique_flow = network_result_try!(
-            self.do_reverse_connect(relay_nr.clone(), target_node_ref.clone(), data)
+            self.do_reverse_connect(relay_nr.clone(), target_node_ref.clone(), data, priority)
                 .await?
         );
         Ok(NetworkResult::value(SendDataMethod {
@@ -273,12 +293,13 @@ impl NetworkManager {
         relay_nr: NodeRef,
         target_node_ref: NodeRef,
         data: Vec<u8>,
+        priority: SendPriority,
     ) -> EyreResult<NetworkResult<SendDataMethod>> {
         // First try to send data to the last socket we've seen this peer on
         let data = if let Some(flow) = target_node_ref.last_flow() {
             match self
                 .net()
-                .send_data_to_existing_flow(flow, data)
+                .send_data_to_existing_flow(flow, data, priority)
                 .await?
             {
                 SendDataToExistingFlowResult::Sent(unique_flow) => {
@@ -304,7 +325,7 @@ impl NetworkManager {
         };
 
         let unique_flow =
-            network_result_try!(self.do_hole_punch(relay_nr.clone(), target_node_ref.clone(), data).await?);
+            network_result_try!(self.do_hole_punch(relay_nr.clone(), target_node_ref.clone(), data, priority).await?);
         Ok(NetworkResult::value(SendDataMethod {
             contact_method: NodeContactMethod::SignalHolePunch(relay_nr, target_node_ref),
             opt_relayed_contact_method: None,
@@ -318,7 +339,11 @@ impl NetworkManager {
         node_ref: NodeRef,
         dial_info: DialInfo,
         data: Vec<u8>,
+        priority: SendPriority,
     ) -> EyreResult<NetworkResult<SendDataMethod>> {
+        // Other dial infos we could race a new connection to, if the best one is connection-oriented
+        let race_candidates = self.get_connection_race_candidates(&node_ref, &dial_info);
+
         // Since we have the best dial info already, we can find a connection to use by protocol type
         let node_ref = node_ref.filtered_clone(NodeRefFilter::from(dial_info.make_filter()));
 
@@ -332,7 +357,7 @@ impl NetworkManager {
 
             match self
                 .net()
-                .send_data_to_existing_flow(flow, data)
+                .send_data_to_existing_flow(flow, data, priority)
                 .await?
             {
                 SendDataToExistingFlowResult::Sent(unique_flow) => {
@@ -355,9 +380,20 @@ impl NetworkManager {
             data
         };
 
+        // New direct connection was necessary, race the candidates if there is more than one
+        // The winning connection is in the connection table, so sending to its dial info will use it
+        let dial_info = if race_candidates.len() > 1 {
+            network_result_try!(self
+                .connection_manager()
+                .race_connections(node_ref.best_node_id(), race_candidates)
+                .await?)
+        } else {
+            dial_info
+        };
+
         // New direct connection was necessary for this dial info
         let unique_flow =
-            network_result_try!(self.net().send_data_to_dial_info(dial_info.clone(), data).await?);
+            network_result_try!(self.net().send_data_to_dial_info(dial_info.clone(), data, priority).await?);
 
         // If we connected to this node directly, save off the last connection so we can use it again
         node_ref.set_last_flow(unique_flow.flow, get_aligned_timestamp());
@@ -369,12 +405,81 @@ impl NetworkManager {
         }))    
     }
 
+    /// Get the dial infos to race connections to when the best dial info for a node is connection-oriented
+    /// The best dial info comes first, followed by the node's other connection-oriented dial infos that we
+    /// can reach and that have not failed recently, up to CONNECTION_RACE_MAX_CANDIDATES in total
+    fn get_connection_race_candidates(
+        &self,
+        node_ref: &NodeRef,
+        best_dial_info: &DialInfo,
+    ) -> Vec<DialInfo> {
+        let mut out = vec![best_dial_info.clone()];
+        if !best_dial_info.protocol_type().is_ordered() {
+            return out;
+        }
+
+        let Some(routing_domain) = node_ref.best_routing_domain() else {
+            return out;
+        };
+        let Some(peer_b) = node_ref.make_peer_info(routing_domain) else {
+            return out;
+        };
+        let peer_a = self.routing_table().get_own_peer_info(routing_domain);
+
+        // Only dial infos we are able to connect to
+        let dial_info_filter = node_ref.dial_info_filter().filtered(
+            &DialInfoFilter::all()
+                .with_address_type_set(peer_a.signed_node_info().node_info().address_types())
+                .with_protocol_type_set(peer_a.signed_node_info().node_info().outbound_protocols()),
+        );
+
+        let address_filter = self.address_filter();
+        for did in peer_b
+            .signed_node_info()
+            .node_info()
+            .all_filtered_dial_info_details(DialInfoDetail::NO_SORT, |did| {
+                did.matches_filter(&dial_info_filter)
+            })
+        {
+            if out.len() >= CONNECTION_RACE_MAX_CANDIDATES {
+                break;
+            }
+            if !did.dial_info.protocol_type().is_ordered()
+                || out.contains(&did.dial_info)
+                || address_filter
+                    .get_dial_info_failed_ts(&did.dial_info)
+                    .is_some()
+            {
+                continue;
+            }
+            out.push(did.dial_info);
+        }
+        out
+    }
+
     /// Figure out how to reach a node from our own node over the best routing domain and reference the nodes we want to access
     /// Uses NodeRefs to ensure nodes are referenced, this is not a part of 'RoutingTable' because RoutingTable is not
     /// allowed to use NodeRefs due to recursive locking
     pub(crate) fn get_node_contact_method(
         &self,
         target_node_ref: NodeRef,
+    ) -> EyreResult<NodeContactMethod> {
+        self.resolve_node_contact_method(target_node_ref, false)
+    }
+
+    /// Figure out how else to reach a node after contacting it directly has failed
+    /// This continues the cascade from direct contact to a hole punch and then to the node's inbound relay
+    pub(crate) fn get_node_fallback_contact_method(
+        &self,
+        target_node_ref: NodeRef,
+    ) -> EyreResult<NodeContactMethod> {
+        self.resolve_node_contact_method(target_node_ref, true)
+    }
+
+    fn resolve_node_contact_method(
+        &self,
+        target_node_ref: NodeRef,
+        fallback: bool,
     ) -> EyreResult<NodeContactMethod> {
         let routing_table = self.routing_table();
 
@@ -396,11 +501,13 @@ impl NetworkManager {
         let ncm_key = NodeContactMethodCacheKey {
             own_node_info_ts: routing_table.get_own_node_info_ts(routing_domain),
             target_node_info_ts: target_node_ref.node_info_ts(routing_domain),
-            target_node_ref_filter: target_node_ref.filter_ref().cloned(),
+            target_node_ref_filter: target_node_ref.filter(),
             target_node_ref_sequencing: target_node_ref.sequencing(),
         };
-        if let Some(ncm) = self.inner.lock().node_contact_method_cache.get(&ncm_key) {
-            return Ok(ncm.clone());
+        if !fallback {
+            if let Some(ncm) = self.inner.lock().node_contact_method_cache.get(&ncm_key) {
+                return Ok(ncm.clone());
+            }
         }
 
         // Node A is our own node
@@ -451,14 +558,25 @@ impl NetworkManager {
         };
 
         // Get the best contact method with these parameters from the routing domain
-        let cm = routing_table.get_contact_method(
-            routing_domain,
-            &peer_a,
-            &peer_b,
-            dial_info_filter,
-            sequencing,
-            dif_sort,
-        );
+        let cm = if fallback {
+            routing_table.get_fallback_contact_method(
+                routing_domain,
+                &peer_a,
+                &peer_b,
+                dial_info_filter,
+                sequencing,
+                dif_sort,
+            )
+        } else {
+            routing_table.get_contact_method(
+                routing_domain,
+                &peer_a,
+                &peer_b,
+                dial_info_filter,
+                sequencing,
+                dif_sort,
+            )
+        };
 
         // Translate the raw contact method to a referenced contact method
         let ncm = match cm {
@@ -532,11 +650,13 @@ impl NetworkManager {
             }
         };
 
-        // Cache this
-        self.inner
-            .lock()
-            .node_contact_method_cache
-            .insert(ncm_key, ncm.clone());
+        // Cache this, fallbacks are only wanted after a failure so they aren't cached
+        if !fallback {
+            self.inner
+                .lock()
+                .node_contact_method_cache
+                .insert(ncm_key, ncm.clone());
+        }
         Ok(ncm)
     }
 
@@ -552,6 +672,7 @@ impl NetworkManager {
         relay_nr: NodeRef,
         target_nr: NodeRef,
         data: Vec<u8>,
+        priority: SendPriority,
     ) -> EyreResult<NetworkResult<UniqueFlow>> {
         // Build a return receipt for the signal
         let receipt_timeout = ms_to_us(
@@ -619,7 +740,7 @@ impl NetworkManager {
         if let Some(flow) = inbound_nr.last_flow() {
             match self
                 .net()
-                .send_data_to_existing_flow(flow, data)
+                .send_data_to_existing_flow(flow, data, priority)
                 .await?
             {
                 SendDataToExistingFlowResult::Sent(unique_flow) => Ok(NetworkResult::value(unique_flow)),
@@ -644,6 +765,7 @@ impl NetworkManager {
         relay_nr: NodeRef,
         target_nr: NodeRef,
         data: Vec<u8>,
+        priority: SendPriority,
     ) -> EyreResult<NetworkResult<UniqueFlow>> {
         // Ensure we are filtered down to UDP (the only hole punch protocol supported today)
         assert!(target_nr
@@ -688,7 +810,7 @@ impl NetworkManager {
         // punch should come through and create a real 'last connection' for us if this succeeds
         network_result_try!(
             self.net()
-                .send_data_to_dial_info(hole_punch_did.dial_info, Vec::new())
+                .send_data_to_dial_info(hole_punch_did.dial_info, Vec::new(), SendPriority::Control)
                 .await?
         );
 
@@ -737,7 +859,7 @@ impl NetworkManager {
         if let Some(flow) = inbound_nr.last_flow() {
             match self
                 .net()
-                .send_data_to_existing_flow(flow, data)
+                .send_data_to_existing_flow(flow, data, priority)
                 .await?
             {
                 SendDataToExistingFlowResult::Sent(unique_flow) => Ok(NetworkResult::value(unique_flow)),
@@ -0,0 +1,81 @@
+use super::*;
+use futures_util::future::{select, Either};
+
+/// Where a message goes in a connection's send queue
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum SendPriority {
+    /// Control traffic such as status, find_node, signals and receipts, which may be
+    /// sent ahead of messages that are already queued on the same connection
+    Control,
+    /// Everything else, sent in the order it was queued
+    Normal,
+}
+
+/// A message waiting to be sent, along with the span that queued it
+pub(in crate::network_manager) type QueuedMessage = (Option<Id>, SendPriority, Vec<u8>);
+
+/// Create a prioritized send queue for a single connection
+///
+/// Each half of the queue holds up to `capacity` messages, so a backlog of
+/// application payloads never blocks control messages from being queued.
+pub(in crate::network_manager) fn send_queue(
+    capacity: usize,
+) -> (SendQueueSender, SendQueueReceiver) {
+    let (priority_sender, priority_receiver) = flume::bounded(capacity);
+    let (bulk_sender, bulk_receiver) = flume::bounded(capacity);
+    (
+        SendQueueSender {
+            priority: priority_sender,
+            bulk: bulk_sender,
+        },
+        SendQueueReceiver {
+            priority: priority_receiver,
+            bulk: bulk_receiver,
+        },
+    )
+}
+
+#[derive(Clone, Debug)]
+pub(in crate::network_manager) struct SendQueueSender {
+    priority: flume::Sender<QueuedMessage>,
+    bulk: flume::Sender<QueuedMessage>,
+}
+
+impl SendQueueSender {
+    /// Queue a message, picking the queue by its priority
+    ///
+    /// Returns the message if the connection is no longer processing sends.
+    pub async fn send_async(&self, item: QueuedMessage) -> Result<(), QueuedMessage> {
+        let queue = match item.1 {
+            SendPriority::Control => &self.priority,
+            SendPriority::Normal => &self.bulk,
+        };
+        queue.send_async(item).await.map_err(|e| e.0)
+    }
+}
+
+#[derive(Debug)]
+pub(in crate::network_manager) struct SendQueueReceiver {
+    priority: flume::Receiver<QueuedMessage>,
+    bulk: flume::Receiver<QueuedMessage>,
+}
+
+impl SendQueueReceiver {
+    /// Wait for the next message to send, always taking priority messages first
+    pub async fn recv_async(&self) -> Result<QueuedMessage, flume::RecvError> {
+        if let Ok(item) = self.priority.try_recv() {
+            return Ok(item);
+        }
+        // Neither receive future consumes a message unless it completes,
+        // so dropping the one that loses the race is safe
+        match select(self.priority.recv_async(), self.bulk.recv_async()).await {
+            Either::Left((res, _)) => res,
+            Either::Right((res, _)) => res,
+        }
+    }
+
+    /// Take everything still waiting to be sent, priority messages first
+    pub fn drain(&self) -> Vec<QueuedMessage> {
+        self.priority.drain().chain(self.bulk.drain()).collect()
+    }
+}
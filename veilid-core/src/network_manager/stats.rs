@@ -6,6 +6,8 @@ pub struct PerAddressStats {
     pub last_seen_ts: Timestamp,
     pub transfer_stats_accounting: TransferStatsAccounting,
     pub transfer_stats: TransferStatsDownUp,
+    /// routing domain the address belongs to, once it has been looked up
+    pub routing_domain: Option<Option<RoutingDomain>>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -17,56 +19,104 @@ impl Default for PerAddressStatsKey {
     }
 }
 
+// Statistics per routing domain and protocol
+#[derive(Clone, Default)]
+pub struct PerDomainStats {
+    pub transfer_stats_accounting: TransferStatsAccounting,
+    pub transfer_stats: TransferStatsDownUp,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct PerDomainStatsKey {
+    pub routing_domain: RoutingDomain,
+    pub protocol_type: ProtocolType,
+}
+
 // Statistics about the low-level network
 #[derive(Clone)]
 pub struct NetworkManagerStats {
     pub self_stats: PerAddressStats,
     pub per_address_stats: LruCache<PerAddressStatsKey, PerAddressStats>,
+    pub per_domain_stats: BTreeMap<PerDomainStatsKey, PerDomainStats>,
 }
 
 impl Default for NetworkManagerStats {
     fn default() -> Self {
         Self {
-            self_stats: PerAddressStats::default(),
+            // Only our own traffic is reported over the longer transfer windows
+            self_stats: PerAddressStats {
+                transfer_stats_accounting: TransferStatsAccounting::new_with_windows(),
+                ..Default::default()
+            },
             per_address_stats: LruCache::new(IPADDR_TABLE_SIZE),
+            per_domain_stats: BTreeMap::new(),
         }
     }
 }
 
 impl NetworkManager {
     // Callbacks from low level network for statistics gathering
-    pub fn stats_packet_sent(&self, addr: IpAddr, bytes: ByteCount) {
-        let inner = &mut *self.inner.lock();
-        inner
-            .stats
-            .self_stats
-            .transfer_stats_accounting
-            .add_up(bytes);
-        #[allow(clippy::unwrap_or_default)]
-        inner
-            .stats
-            .per_address_stats
-            .entry(PerAddressStatsKey(addr))
-            .or_insert(PerAddressStats::default())
-            .transfer_stats_accounting
-            .add_up(bytes);
+    pub fn stats_packet_sent(&self, addr: IpAddr, protocol_type: ProtocolType, bytes: ByteCount) {
+        self.stats_packet(addr, protocol_type, |tsa| tsa.add_up(bytes));
     }
 
-    pub fn stats_packet_rcvd(&self, addr: IpAddr, bytes: ByteCount) {
-        let inner = &mut *self.inner.lock();
-        inner
-            .stats
-            .self_stats
-            .transfer_stats_accounting
-            .add_down(bytes);
+    pub fn stats_packet_rcvd(&self, addr: IpAddr, protocol_type: ProtocolType, bytes: ByteCount) {
+        self.stats_packet(addr, protocol_type, |tsa| tsa.add_down(bytes));
+    }
+
+    fn stats_packet<F>(&self, addr: IpAddr, protocol_type: ProtocolType, f: F)
+    where
+        F: Fn(&mut TransferStatsAccounting),
+    {
+        let key = PerAddressStatsKey(addr);
+
+        let mut inner = self.inner.lock();
+        f(&mut inner.stats.self_stats.transfer_stats_accounting);
         #[allow(clippy::unwrap_or_default)]
-        inner
+        let per_address_stats = inner
             .stats
             .per_address_stats
-            .entry(PerAddressStatsKey(addr))
-            .or_insert(PerAddressStats::default())
-            .transfer_stats_accounting
-            .add_down(bytes);
+            .entry(key)
+            .or_insert(PerAddressStats::default());
+        f(&mut per_address_stats.transfer_stats_accounting);
+        let cached_routing_domain = per_address_stats.routing_domain;
+
+        // The routing domain of an address is cached with its stats, so the routing table
+        // only gets locked for the first packet of each address rather than for every packet
+        let routing_domain = match cached_routing_domain {
+            Some(routing_domain) => routing_domain,
+            None => {
+                drop(inner);
+                let routing_domain = self
+                    .routing_table()
+                    .routing_domain_for_address(Address::from_ip_addr(addr));
+                inner = self.inner.lock();
+                if let Some(per_address_stats) = inner.stats.per_address_stats.get_mut(&key) {
+                    per_address_stats.routing_domain = Some(routing_domain);
+                }
+                routing_domain
+            }
+        };
+
+        if let Some(routing_domain) = routing_domain {
+            f(&mut inner
+                .stats
+                .per_domain_stats
+                .entry(PerDomainStatsKey {
+                    routing_domain,
+                    protocol_type,
+                })
+                .or_default()
+                .transfer_stats_accounting);
+        }
+    }
+
+    /// Forget the cached routing domains of addresses, for when the local networks change
+    pub fn reset_stats_routing_domains(&self) {
+        let mut inner = self.inner.lock();
+        for (_, per_address_stats) in inner.stats.per_address_stats.iter_mut() {
+            per_address_stats.routing_domain = None;
+        }
     }
 
     #[allow(dead_code)]
@@ -90,15 +140,38 @@ impl NetworkManager {
                 bps_down: 0.into(),
                 bps_up: 0.into(),
                 peers: Vec::new(),
+                transfer_stats: Vec::new(),
+                clock_skew: None,
+                clock_skew_warning: false,
+                listeners: self.unlocked_inner.listener_statuses.lock().clone(),
+                rpc_load: RPCLoadStats::default(),
+                transfer_windows: TransferStatsWindows::default(),
             });
         }
         let routing_table = self.routing_table();
 
-        let (bps_down, bps_up) = {
+        let (bps_down, bps_up, transfer_stats, clock_skew, clock_skew_warning, transfer_windows) = {
             let inner = self.inner.lock();
             (
                 inner.stats.self_stats.transfer_stats.down.average,
                 inner.stats.self_stats.transfer_stats.up.average,
+                inner
+                    .stats
+                    .per_domain_stats
+                    .iter()
+                    .map(|(k, v)| DomainTransferStats {
+                        routing_domain: k.routing_domain,
+                        protocol_type: k.protocol_type,
+                        transfer_stats: v.transfer_stats.clone(),
+                    })
+                    .collect(),
+                inner.clock_skew,
+                inner.clock_skew_warning,
+                inner
+                    .stats
+                    .self_stats
+                    .transfer_stats_accounting
+                    .transfer_windows(),
             )
         };
 
@@ -121,9 +194,39 @@ impl NetworkManager {
                 }
                 out
             },
+            transfer_stats,
+            clock_skew,
+            clock_skew_warning,
+            listeners: self.unlocked_inner.listener_statuses.lock().clone(),
+            rpc_load: self.rpc_processor().get_load_stats(),
+            transfer_windows,
         })
     }
 
+    /// Record how starting the inbound listeners went and let api clients know
+    pub(crate) fn set_listener_statuses(&self, listener_statuses: Vec<ListenerStatus>) {
+        *self.unlocked_inner.listener_statuses.lock() = listener_statuses;
+        self.send_network_update();
+    }
+
+    /// Update the certificate expiration of a protocol's listeners after its certificate was reloaded
+    pub(crate) fn set_listener_certificate_expiration(
+        &self,
+        protocol_type: ProtocolType,
+        certificate_expiration: Option<Timestamp>,
+    ) {
+        for listener_status in self
+            .unlocked_inner
+            .listener_statuses
+            .lock()
+            .iter_mut()
+            .filter(|ls| ls.protocol_type == protocol_type)
+        {
+            listener_status.certificate_expiration = certificate_expiration;
+        }
+        self.send_network_update();
+    }
+
     pub(super) fn send_network_update(&self) {
         let update_cb = self.unlocked_inner.update_callback.read().clone();
         if update_cb.is_none() {
@@ -0,0 +1,54 @@
+use super::*;
+
+impl NetworkManager {
+    // Decide if we can keep offering to relay and route for others, and republish our node info if that changed
+    #[instrument(level = "trace", skip(self), err)]
+    pub(crate) async fn capability_advertisement_task_routine(
+        self,
+        _stop_token: StopToken,
+        _last_ts: Timestamp,
+        cur_ts: Timestamp,
+    ) -> EyreResult<()> {
+        let routing_table = self.routing_table();
+
+        // We can only relay for others if they can reach us directly
+        let reachable = routing_table.get_network_class(RoutingDomain::PublicInternet)
+            == Some(NetworkClass::InboundCapable)
+            && routing_table
+                .relay_node(RoutingDomain::PublicInternet)
+                .is_none();
+        let overloaded = self.rpc_processor().is_under_load();
+
+        let changed = {
+            let mut inner = self.inner.lock();
+            let relay_changed = inner
+                .capability_advertisement
+                .relay
+                .update(cur_ts, reachable, overloaded);
+            let route_changed = inner
+                .capability_advertisement
+                .route
+                .update(cur_ts, true, overloaded);
+            relay_changed || route_changed
+        };
+        if !changed {
+            return Ok(());
+        }
+
+        let capabilities = self.with_config(|c| {
+            PUBLIC_INTERNET_CAPABILITIES
+                .iter()
+                .copied()
+                .filter(|cap| !is_capability_disabled(c, cap))
+                .collect::<Vec<Capability>>()
+        });
+        let capabilities =
+            self.without_withheld_capabilities(RoutingDomain::PublicInternet, capabilities);
+
+        let mut editor = routing_table.edit_routing_domain(RoutingDomain::PublicInternet);
+        editor.set_capabilities(capabilities);
+        editor.commit(true).await;
+
+        Ok(())
+    }
+}
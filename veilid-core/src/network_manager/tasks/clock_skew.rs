@@ -0,0 +1,105 @@
+use super::*;
+
+/// Estimate how far the network's clocks are ahead of ours
+///
+/// The skew our peers report in their status answers accounts for the round trip, so it is preferred.
+/// Until we have that, the timestamps on the envelopes we receive are used instead, as long as enough
+/// different senders agree that one lying sender can not move the median far.
+pub(crate) fn estimate_clock_skew(
+    peer_clock_skew: Option<i64>,
+    envelope_clock_skews: &[i64],
+) -> Option<i64> {
+    if peer_clock_skew.is_some() {
+        return peer_clock_skew;
+    }
+    if envelope_clock_skews.len() < CLOCK_SKEW_MIN_ENVELOPE_SENDERS {
+        return None;
+    }
+    let mut clock_skews = envelope_clock_skews.to_vec();
+    clock_skews.sort_unstable();
+    Some(clock_skews[clock_skews.len() / 2])
+}
+
+/// Shift a local timestamp to where the network's clocks are
+///
+/// Bounded by max_clock_skew_us so a handful of lying peers can not open the window arbitrarily far.
+pub(crate) fn compensate_timestamp(
+    local_ts: Timestamp,
+    clock_skew: Option<i64>,
+    max_clock_skew_us: u64,
+) -> Timestamp {
+    let max_clock_skew_us = i64::try_from(max_clock_skew_us).unwrap_or(i64::MAX);
+    let compensation = clock_skew
+        .unwrap_or_default()
+        .clamp(-max_clock_skew_us, max_clock_skew_us);
+    Timestamp::new(local_ts.as_u64().saturating_add_signed(compensation))
+}
+
+impl NetworkManager {
+    // Refresh our estimate of how far our clock is off from the network
+    #[instrument(level = "trace", skip(self), err)]
+    pub(crate) async fn clock_skew_task_routine(
+        self,
+        _stop_token: StopToken,
+        _last_ts: Timestamp,
+        _cur_ts: Timestamp,
+    ) -> EyreResult<()> {
+        let peer_clock_skew = self.routing_table().get_clock_skew();
+        let clock_skew_warning_us =
+            self.with_config(|c| ms_to_us(c.network.rpc.clock_skew_warning_ms));
+
+        // Only report when the skew crosses the threshold, not on every tick
+        let changed = {
+            let mut inner = self.inner.lock();
+            let envelope_clock_skews: Vec<i64> =
+                inner.envelope_clock_skews.iter().map(|(_, s)| *s).collect();
+            let clock_skew = estimate_clock_skew(peer_clock_skew, &envelope_clock_skews);
+            inner.clock_skew = clock_skew;
+
+            let clock_skew_warning = clock_skew_warning_us != 0
+                && clock_skew
+                    .map(|s| s.unsigned_abs() > clock_skew_warning_us)
+                    .unwrap_or(false);
+            let changed = clock_skew_warning != inner.clock_skew_warning;
+            inner.clock_skew_warning = clock_skew_warning;
+            changed.then_some((clock_skew, clock_skew_warning))
+        };
+
+        if let Some((clock_skew, clock_skew_warning)) = changed {
+            if let (Some(clock_skew), true) = (clock_skew, clock_skew_warning) {
+                log_net!(warn
+                    "Local clock is {}ms {} the network, check the system time",
+                    clock_skew.unsigned_abs() / 1000,
+                    if clock_skew > 0 { "behind" } else { "ahead of" }
+                );
+            }
+            self.send_network_update();
+        }
+
+        Ok(())
+    }
+
+    /// Remember how far ahead of our clock a sender's envelope timestamp was
+    /// Only the latest envelope from each sender counts, so a chatty peer does not outweigh the others
+    pub(super) fn record_envelope_clock_skew(
+        &self,
+        sender_id: TypedKey,
+        envelope_ts: Timestamp,
+        local_ts: Timestamp,
+    ) {
+        if self.address_filter().is_node_id_punished(sender_id) {
+            return;
+        }
+        let clock_skew = if envelope_ts >= local_ts {
+            i64::try_from(envelope_ts.as_u64() - local_ts.as_u64()).unwrap_or(i64::MAX)
+        } else {
+            i64::try_from(local_ts.as_u64() - envelope_ts.as_u64())
+                .map(|s| -s)
+                .unwrap_or(i64::MIN)
+        };
+        self.inner
+            .lock()
+            .envelope_clock_skews
+            .insert(sender_id, clock_skew);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod capability_advertisement;
+pub mod clock_skew;
 pub mod public_address_check;
 pub mod rolling_transfers;
 
@@ -15,7 +17,7 @@ impl NetworkManager {
                         this.clone()
                             .rolling_transfers_task_routine(s, Timestamp::new(l), Timestamp::new(t))
                             .instrument(trace_span!(
-                                parent: None,
+                                parent: &this.config().instance_span(),
                                 "NetworkManager rolling transfers task routine"
                             )),
                     )
@@ -36,7 +38,7 @@ impl NetworkManager {
                                 Timestamp::new(t),
                             )
                             .instrument(trace_span!(
-                                parent: None,
+                                parent: &this.config().instance_span(),
                                 "public address check task routine"
                             )),
                     )
@@ -52,7 +54,42 @@ impl NetworkManager {
                     Box::pin(
                         this.address_filter()
                             .address_filter_task_routine(s, Timestamp::new(l), Timestamp::new(t))
-                            .instrument(trace_span!(parent: None, "address filter task routine")),
+                            .instrument(trace_span!(parent: &this.config().instance_span(), "address filter task routine")),
+                    )
+                });
+        }
+
+        // Set clock skew task
+        {
+            let this = self.clone();
+            self.unlocked_inner
+                .clock_skew_task
+                .set_routine(move |s, l, t| {
+                    Box::pin(
+                        this.clone()
+                            .clock_skew_task_routine(s, Timestamp::new(l), Timestamp::new(t))
+                            .instrument(trace_span!(parent: &this.config().instance_span(), "clock skew task routine")),
+                    )
+                });
+        }
+
+        // Set capability advertisement task
+        {
+            let this = self.clone();
+            self.unlocked_inner
+                .capability_advertisement_task
+                .set_routine(move |s, l, t| {
+                    Box::pin(
+                        this.clone()
+                            .capability_advertisement_task_routine(
+                                s,
+                                Timestamp::new(l),
+                                Timestamp::new(t),
+                            )
+                            .instrument(trace_span!(
+                                parent: &this.config().instance_span(),
+                                "capability advertisement task routine"
+                            )),
                     )
                 });
         }
@@ -69,6 +106,15 @@ impl NetworkManager {
         // Run the address filter task
         self.unlocked_inner.address_filter_task.tick().await?;
 
+        // Run the clock skew task
+        self.unlocked_inner.clock_skew_task.tick().await?;
+
+        // Run the capability advertisement task
+        self.unlocked_inner
+            .capability_advertisement_task
+            .tick()
+            .await?;
+
         // Run the routing table tick
         routing_table.tick().await?;
 
@@ -90,6 +136,21 @@ impl NetworkManager {
             warn!("rolling_transfers_task not stopped: {}", e);
         }
 
+        log_net!(debug "stopping clock skew task");
+        if let Err(e) = self.unlocked_inner.clock_skew_task.stop().await {
+            warn!("clock_skew_task not stopped: {}", e);
+        }
+
+        log_net!(debug "stopping capability advertisement task");
+        if let Err(e) = self
+            .unlocked_inner
+            .capability_advertisement_task
+            .stop()
+            .await
+        {
+            warn!("capability_advertisement_task not stopped: {}", e);
+        }
+
         log_net!(debug "stopping routing table tasks");
         let routing_table = self.routing_table();
         routing_table.cancel_tasks().await;
@@ -48,6 +48,9 @@ impl NetworkManager {
     ) {
         log_network_result!("report_global_socket_address\nsocket_address: {:#?}\nflow: {:#?}\nreporting_peer: {:#?}", socket_address, flow, reporting_peer);
 
+        // Keep every report, even ones we ignore, so lying peers can be spotted after the fact
+        self.record_public_address_observation(socket_address, flow, &reporting_peer);
+
         // Ignore these reports if we are currently detecting public dial info
         let net = self.net();
         if net.needs_public_dial_info_check() {
@@ -68,12 +71,14 @@ impl NetworkManager {
             return;
         }
 
-        let (detect_address_changes, ip6_prefix_size) = self.with_config(|c| {
-            (
-                c.network.detect_address_changes,
-                c.network.max_connections_per_ip6_prefix_size as usize,
-            )
-        });
+        let (detect_address_changes, distinct_subnet_count, ip6_prefix_size) =
+            self.with_config(|c| {
+                (
+                    c.network.detect_address_changes,
+                    c.network.detect_address_changes_distinct_subnets as usize,
+                    c.network.max_connections_per_ip6_prefix_size as usize,
+                )
+            });
 
         // Get the ip(block) this report is coming from
         let reporting_ipblock = ip_to_ipblock(ip6_prefix_size, flow.remote_address().ip_addr());
@@ -177,10 +182,12 @@ impl NetworkManager {
                 }
             }
 
-            // If we have enough inconsistencies to consider changing our public dial info,
-            // add them to our denylist (throttling) and go ahead and check for new
-            // public dialinfo
-            let inconsistent = if inconsistencies.len() >= PUBLIC_ADDRESS_CHANGE_DETECTION_COUNT {
+            // If we have enough inconsistencies from enough different networks to consider
+            // changing our public dial info, add them to our denylist (throttling) and go ahead
+            // and check for new public dialinfo
+            let inconsistent = if inconsistencies.len() >= PUBLIC_ADDRESS_CHANGE_DETECTION_COUNT
+                && count_distinct_reporting_subnets(&inconsistencies) >= distinct_subnet_count
+            {
                 let exp_ts = get_aligned_timestamp() + PUBLIC_ADDRESS_INCONSISTENCY_TIMEOUT_US;
                 let pait = inner
                     .public_address_inconsistencies_table
@@ -225,19 +232,25 @@ impl NetworkManager {
             let mut consistencies = 0;
             let mut consistent = false;
             let mut current_address = Option::<SocketAddress>::None;
+            let mut consistent_ipblocks = Vec::new();
 
             // Iteration goes from most recent to least recent node/address pair
-            for (_, a) in pacc {
+            for (reporting_ip_block, a) in pacc {
                 if let Some(current_address) = current_address {
                     if current_address == *a {
+                        consistent_ipblocks.push(*reporting_ip_block);
                         consistencies += 1;
-                        if consistencies >= PUBLIC_ADDRESS_CHANGE_DETECTION_COUNT {
+                        if consistencies >= PUBLIC_ADDRESS_CHANGE_DETECTION_COUNT
+                            && count_distinct_reporting_subnets(&consistent_ipblocks)
+                                >= distinct_subnet_count
+                        {
                             consistent = true;
                             break;
                         }
                     }
                 } else {
                     current_address = Some(*a);
+                    consistent_ipblocks.push(*reporting_ip_block);
                 }
             }
             consistent
@@ -268,4 +281,92 @@ impl NetworkManager {
             }
         }
     }
+
+    fn record_public_address_observation(
+        &self,
+        socket_address: SocketAddress,
+        flow: Flow,
+        reporting_peer: &NodeRef,
+    ) {
+        let observation = PublicAddressObservation {
+            reporting_node_id: reporting_peer.best_node_id(),
+            reporting_address: flow.remote_address().to_string(),
+            observed_address: socket_address.to_string(),
+            protocol_type: flow.protocol_type(),
+            timestamp: get_aligned_timestamp(),
+        };
+
+        let mut inner = self.inner.lock();
+        if inner.public_address_observations.len() >= PUBLIC_ADDRESS_OBSERVATION_HISTORY_SIZE {
+            inner.public_address_observations.pop_front();
+        }
+        inner.public_address_observations.push_back(observation);
+    }
+
+    /// Get the most recent reports of our public address, oldest first
+    pub fn public_address_observations(&self) -> Vec<PublicAddressObservation> {
+        self.inner
+            .lock()
+            .public_address_observations
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Re-run public dial info detection without restarting the network
+    ///
+    /// Used when the way we reach the internet has changed, for example after
+    /// changing router settings. Our network class and public dial info are
+    /// detected again and our signed node info is updated if they changed.
+    pub fn trigger_public_dial_info_detection(&self) -> EyreResult<()> {
+        let net = self.net();
+        if !net.is_started() {
+            bail!("network is not started");
+        }
+
+        let routing_table = self.routing_table();
+        if matches!(
+            routing_table.get_network_class(RoutingDomain::PublicInternet),
+            Some(NetworkClass::WebApp)
+        ) {
+            bail!("public dial info is not detected for web apps");
+        }
+        if !self.with_config(|c| c.network.detect_address_changes && !c.network.tor.enabled) {
+            bail!("public dial info detection is disabled by 'network.detect_address_changes'");
+        }
+        if self.is_relay_only() {
+            bail!("public dial info is not detected while relay-only");
+        }
+
+        info!("Public dial info detection requested");
+
+        // Start over with fresh reports of our public address
+        self.inner.lock().public_address_check_cache.clear();
+        net.set_needs_public_dial_info_check(None);
+
+        Ok(())
+    }
+}
+
+/// The subnet a public address report is considered to come from.
+/// IPv4 reporters are grouped by /24, IPv6 reporters are already grouped by prefix in their ip block
+pub(crate) fn reporting_subnet(reporting_ip_block: IpAddr) -> IpAddr {
+    match reporting_ip_block {
+        IpAddr::V4(v4) => {
+            let mut octets = v4.octets();
+            octets[3] = 0;
+            IpAddr::V4(Ipv4Addr::from(octets))
+        }
+        IpAddr::V6(_) => reporting_ip_block,
+    }
+}
+
+/// Count how many different subnets a set of reporting ip blocks comes from, so that a
+/// handful of peers on one network can not convince us our address has changed
+pub(crate) fn count_distinct_reporting_subnets(reporting_ip_blocks: &[IpAddr]) -> usize {
+    reporting_ip_blocks
+        .iter()
+        .map(|x| reporting_subnet(*x))
+        .collect::<BTreeSet<_>>()
+        .len()
 }
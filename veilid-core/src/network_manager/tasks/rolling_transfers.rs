@@ -20,6 +20,15 @@ impl NetworkManager {
                 .transfer_stats_accounting
                 .roll_transfers(last_ts, cur_ts, &mut inner.stats.self_stats.transfer_stats);
 
+            // Roll the transfer stats for each routing domain and protocol
+            for stats in inner.stats.per_domain_stats.values_mut() {
+                stats.transfer_stats_accounting.roll_transfers(
+                    last_ts,
+                    cur_ts,
+                    &mut stats.transfer_stats,
+                );
+            }
+
             // Roll all per-address transfers
             let mut dead_addrs: HashSet<PerAddressStatsKey> = HashSet::new();
             for (addr, stats) in &mut inner.stats.per_address_stats {
@@ -1,4 +1,24 @@
+#[cfg(not(target_arch = "wasm32"))]
+pub mod test_application_http;
+pub mod test_boot_proof;
+pub mod test_capability_hysteresis;
+pub mod test_clock_skew;
+pub mod test_connection_race;
 pub mod test_connection_table;
+pub mod test_dial_info_class;
+pub mod test_envelope_fragments;
+pub mod test_ipc_dial_info;
+pub mod test_network_error;
+pub mod test_onion_dial_info;
+pub mod test_public_address_check;
+pub mod test_relay_scheduler;
+pub mod test_send_queue;
 pub mod test_signed_node_info;
+#[cfg(all(any(test, feature = "sim-network"), not(target_arch = "wasm32")))]
+pub mod test_sim_network;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod test_socks;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod test_tls_certificate;
 
 use super::*;
@@ -0,0 +1,99 @@
+use super::*;
+use std::path::{Path, PathBuf};
+
+fn parse(request: &str) -> Option<HttpApplicationRequest> {
+    HttpProtocolHandler::parse_request(request.as_bytes(), "/app")
+}
+
+fn file(request: &str) -> Option<String> {
+    match parse(request)?.resource {
+        HttpApplicationResource::File(f) => Some(f),
+        HttpApplicationResource::Health => None,
+    }
+}
+
+pub async fn test_parse_request() {
+    let req = "GET /app/health HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    let parsed = parse(req).unwrap();
+    assert_eq!(parsed.resource, HttpApplicationResource::Health);
+    assert!(!parsed.head);
+    assert_eq!(parsed.header_len, req.len());
+
+    let parsed = parse("HEAD /app/health?verbose=1 HTTP/1.0\r\n\r\n").unwrap();
+    assert_eq!(parsed.resource, HttpApplicationResource::Health);
+    assert!(parsed.head);
+
+    assert_eq!(file("GET /app HTTP/1.1\r\n\r\n"), Some(String::new()));
+    assert_eq!(file("GET /app/ HTTP/1.1\r\n\r\n"), Some(String::new()));
+    assert_eq!(
+        file("GET /app/css/site.css HTTP/1.1\r\n\r\n"),
+        Some("css/site.css".to_owned())
+    );
+
+    // Only the application path is ours
+    assert_eq!(parse("GET /ws HTTP/1.1\r\n\r\n"), None);
+    assert_eq!(parse("GET /application HTTP/1.1\r\n\r\n"), None);
+    assert_eq!(parse("GET / HTTP/1.1\r\n\r\n"), None);
+
+    // Only complete, well formed GET and HEAD requests are answered
+    assert_eq!(parse("POST /app/health HTTP/1.1\r\n\r\n"), None);
+    assert_eq!(
+        parse("GET /app/health HTTP/1.1\r\nHost: localhost\r\n"),
+        None
+    );
+    assert_eq!(parse("GET /app/health HTTP/2\r\n\r\n"), None);
+    assert_eq!(parse("GET /app/health\r\n\r\n"), None);
+    assert_eq!(parse("GET /app/health HTTP/1.1\n\n"), None);
+
+    // Header limits are enforced
+    let many_headers = format!("GET /app HTTP/1.1\r\n{}\r\n", "X-A: b\r\n".repeat(25));
+    assert_eq!(parse(&many_headers), None);
+    let long_header = format!("GET /app HTTP/1.1\r\nX-A: {}\r\n\r\n", "b".repeat(600));
+    assert_eq!(parse(&long_header), None);
+
+    // Configured paths may have slashes around them
+    assert!(
+        HttpProtocolHandler::parse_request(b"GET /app/health HTTP/1.1\r\n\r\n", "/app/").is_some()
+    );
+}
+
+pub async fn test_resolve_static_path() {
+    let root = Path::new("/srv/www");
+
+    assert_eq!(
+        HttpProtocolHandler::resolve_static_path(root, ""),
+        Some(PathBuf::from("/srv/www/index.html"))
+    );
+    assert_eq!(
+        HttpProtocolHandler::resolve_static_path(root, "docs/"),
+        Some(PathBuf::from("/srv/www/docs/index.html"))
+    );
+    assert_eq!(
+        HttpProtocolHandler::resolve_static_path(root, "css/site.css"),
+        Some(PathBuf::from("/srv/www/css/site.css"))
+    );
+
+    // Nothing may escape the directory
+    for bad in [
+        "..",
+        "../etc/passwd",
+        "css/../../etc/passwd",
+        "./index.html",
+        "css//site.css",
+        "..\\etc\\passwd",
+        "c:/windows",
+        "index.html\0.png",
+    ] {
+        assert_eq!(
+            HttpProtocolHandler::resolve_static_path(root, bad),
+            None,
+            "{}",
+            bad
+        );
+    }
+}
+
+pub async fn test_all() {
+    test_parse_request().await;
+    test_resolve_static_path().await;
+}
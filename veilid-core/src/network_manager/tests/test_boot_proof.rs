@@ -0,0 +1,32 @@
+use super::*;
+use direct_boot::{check_boot_proof, make_boot_proof};
+
+pub async fn test_boot_proof() {
+    let ts = Timestamp::new(1_700_000_000_000_000);
+
+    // A fresh proof is accepted
+    check_boot_proof(&make_boot_proof(ts), ts).expect("should accept");
+
+    // Proofs from either side of the allowed clock difference are accepted
+    let max_diff = TimestampDuration::new(BOOT_PROOF_MAX_CLOCK_DIFFERENCE_US);
+    check_boot_proof(&make_boot_proof(ts), ts + max_diff).expect("should accept");
+    check_boot_proof(&make_boot_proof(ts + max_diff), ts).expect("should accept");
+
+    // Old proofs and proofs from the future are rejected
+    let too_far = TimestampDuration::new(BOOT_PROOF_MAX_CLOCK_DIFFERENCE_US + 1);
+    assert!(check_boot_proof(&make_boot_proof(ts), ts + too_far).is_err());
+    assert!(check_boot_proof(&make_boot_proof(ts + too_far), ts).is_err());
+
+    // The static proof of older nodes and malformed proofs are rejected
+    assert!(check_boot_proof(BOOT_MAGIC, ts).is_err());
+    let mut wrong_magic = make_boot_proof(ts);
+    wrong_magic[0] ^= 0xFF;
+    assert!(check_boot_proof(&wrong_magic, ts).is_err());
+    let mut too_long = make_boot_proof(ts);
+    too_long.push(0);
+    assert!(check_boot_proof(&too_long, ts).is_err());
+}
+
+pub async fn test_all() {
+    test_boot_proof().await;
+}
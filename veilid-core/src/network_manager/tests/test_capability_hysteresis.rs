@@ -0,0 +1,79 @@
+use super::*;
+
+const ADVERTISE_DELAY: TimestampDuration = TimestampDuration::new(300_000_000u64);
+const WITHDRAW_DELAY: TimestampDuration = TimestampDuration::new(120_000_000u64);
+
+fn secs(s: u64) -> Timestamp {
+    Timestamp::new(1_000_000_000 + s * 1_000_000)
+}
+
+pub async fn test_advertise_after_delay() {
+    let mut h = CapabilityHysteresis::new(false, ADVERTISE_DELAY, WITHDRAW_DELAY);
+
+    // Not advertised until we have been eligible for the whole delay
+    assert!(!h.update(secs(0), true, false));
+    assert!(!h.update(secs(299), true, false));
+    assert!(!h.advertised());
+    assert!(h.update(secs(300), true, false));
+    assert!(h.advertised());
+
+    // A burst of load resets the clock
+    let mut h = CapabilityHysteresis::new(false, ADVERTISE_DELAY, WITHDRAW_DELAY);
+    assert!(!h.update(secs(0), true, false));
+    assert!(!h.update(secs(200), true, true));
+    assert!(!h.update(secs(300), true, false));
+    assert!(!h.update(secs(599), true, false));
+    assert!(h.update(secs(600), true, false));
+}
+
+pub async fn test_withdraw_after_sustained_overload() {
+    let mut h = CapabilityHysteresis::new(true, ADVERTISE_DELAY, WITHDRAW_DELAY);
+
+    // Short bursts of load do not withdraw
+    assert!(!h.update(secs(0), true, true));
+    assert!(!h.update(secs(100), true, true));
+    assert!(!h.update(secs(110), true, false));
+    assert!(!h.update(secs(120), true, true));
+    assert!(!h.update(secs(200), true, true));
+    assert!(h.advertised());
+
+    // Sustained load does
+    assert!(h.update(secs(240), true, true));
+    assert!(!h.advertised());
+
+    // And it takes the full advertise delay to come back
+    assert!(!h.update(secs(250), true, false));
+    assert!(!h.update(secs(500), true, false));
+    assert!(h.update(secs(550), true, false));
+}
+
+pub async fn test_withdraw_when_unreachable() {
+    let mut h = CapabilityHysteresis::new(true, ADVERTISE_DELAY, WITHDRAW_DELAY);
+    assert!(h.update(secs(0), false, false));
+    assert!(!h.advertised());
+    assert!(!h.update(secs(10), false, false));
+
+    // Reachability has to be earned back too
+    assert!(!h.update(secs(20), true, false));
+    assert!(h.update(secs(320), true, false));
+}
+
+pub async fn test_withheld_capabilities() {
+    let mut ca = CapabilityAdvertisement::new();
+    assert_eq!(ca.withheld_capabilities(), vec![CAP_RELAY]);
+
+    ca.relay.update(secs(0), true, false);
+    ca.relay.update(secs(300), true, false);
+    assert!(ca.withheld_capabilities().is_empty());
+
+    ca.route.update(secs(400), true, true);
+    ca.route.update(secs(520), true, true);
+    assert_eq!(ca.withheld_capabilities(), vec![CAP_ROUTE]);
+}
+
+pub async fn test_all() {
+    test_advertise_after_delay().await;
+    test_withdraw_after_sustained_overload().await;
+    test_withdraw_when_unreachable().await;
+    test_withheld_capabilities().await;
+}
@@ -0,0 +1,68 @@
+use super::*;
+
+const MAX_CLOCK_SKEW_US: u64 = 60_000_000;
+
+pub async fn test_estimate_clock_skew() {
+    // Nothing to go on
+    assert_eq!(estimate_clock_skew(None, &[]), None);
+
+    // Too few envelope senders to trust
+    assert_eq!(estimate_clock_skew(None, &[5_000_000, 5_000_000]), None);
+
+    // The median of the envelope senders, so one liar does not move it
+    assert_eq!(
+        estimate_clock_skew(None, &[5_000_000, -3_600_000_000, 4_000_000, 6_000_000]),
+        Some(5_000_000)
+    );
+
+    // Skew reported by peers wins over envelope timestamps
+    assert_eq!(
+        estimate_clock_skew(Some(-250_000), &[5_000_000, 4_000_000, 6_000_000]),
+        Some(-250_000)
+    );
+}
+
+pub async fn test_compensate_timestamp() {
+    let local_ts = Timestamp::new(1_700_000_000_000_000);
+
+    // No estimate, no compensation
+    assert_eq!(
+        compensate_timestamp(local_ts, None, MAX_CLOCK_SKEW_US),
+        local_ts
+    );
+
+    // Network ahead of us and behind us
+    assert_eq!(
+        compensate_timestamp(local_ts, Some(20_000_000), MAX_CLOCK_SKEW_US),
+        Timestamp::new(1_700_000_020_000_000)
+    );
+    assert_eq!(
+        compensate_timestamp(local_ts, Some(-20_000_000), MAX_CLOCK_SKEW_US),
+        Timestamp::new(1_699_999_980_000_000)
+    );
+
+    // Bounded in both directions
+    assert_eq!(
+        compensate_timestamp(local_ts, Some(3_600_000_000), MAX_CLOCK_SKEW_US),
+        Timestamp::new(1_700_000_060_000_000)
+    );
+    assert_eq!(
+        compensate_timestamp(local_ts, Some(-3_600_000_000), MAX_CLOCK_SKEW_US),
+        Timestamp::new(1_699_999_940_000_000)
+    );
+    assert_eq!(
+        compensate_timestamp(local_ts, Some(20_000_000), 0),
+        local_ts
+    );
+
+    // Never wraps around
+    assert_eq!(
+        compensate_timestamp(Timestamp::new(10), Some(-20_000_000), MAX_CLOCK_SKEW_US),
+        Timestamp::new(0)
+    );
+}
+
+pub async fn test_all() {
+    test_estimate_clock_skew().await;
+    test_compensate_timestamp().await;
+}
@@ -0,0 +1,69 @@
+use super::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Sets its flag when dropped, to tell if a racing attempt was cancelled
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+pub async fn test_first_success_wins() {
+    let loser_dropped = Arc::new(AtomicBool::new(false));
+
+    // The first attempt never finishes, the second one connects
+    let res = race_staggered(vec![1u32, 2u32, 3u32], 10, |candidate| {
+        let candidate = *candidate;
+        let drop_flag = (candidate == 1).then(|| DropFlag(loser_dropped.clone()));
+        async move {
+            let _drop_flag = drop_flag;
+            match candidate {
+                1 => {
+                    sleep(60_000).await;
+                    Ok(NetworkResult::value(candidate * 10))
+                }
+                2 => Ok(NetworkResult::value(candidate * 10)),
+                _ => Ok(NetworkResult::no_connection_other("should not start")),
+            }
+        }
+    })
+    .await
+    .expect("should not error");
+
+    assert!(matches!(res, NetworkResult::Value((2, 20))));
+
+    // The attempt that was still connecting was cancelled when the race was won
+    assert!(loser_dropped.load(Ordering::Acquire));
+}
+
+pub async fn test_last_failure_reported() {
+    let res = race_staggered(vec![1u32, 2u32], 10, |candidate| {
+        let candidate = *candidate;
+        async move {
+            if candidate == 1 {
+                Ok(NetworkResult::<u32>::timeout())
+            } else {
+                sleep(20).await;
+                Ok(NetworkResult::no_connection_other("refused"))
+            }
+        }
+    })
+    .await
+    .expect("should not error");
+    assert!(res.is_no_connection());
+
+    // Nothing to race is not a connection
+    let res = race_staggered(Vec::<u32>::new(), 10, |_| async {
+        Ok(NetworkResult::value(0u32))
+    })
+    .await
+    .expect("should not error");
+    assert!(res.is_no_connection());
+}
+
+pub async fn test_all() {
+    test_first_success_wins().await;
+    test_last_failure_reported().await;
+}
@@ -124,6 +124,68 @@ pub async fn test_add_get_remove() {
     assert_eq!(table.connection_count(), 0);
 }
 
+pub async fn test_punishment_export_import() {
+    let config = get_config();
+    let address_filter = AddressFilter::new(config.clone(), mock_routing_table());
+
+    let ip4 = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1));
+    let ip6 = IpAddr::V6(Ipv6Addr::new(191, 0, 0, 0, 0, 0, 0, 1));
+    address_filter.punish_ip_addr(ip4);
+    address_filter.punish_ip_addr(ip6);
+
+    let mut punishments = address_filter.export_punishments();
+    assert_eq!(punishments.ip4.len(), 1);
+    assert_eq!(punishments.ip6_prefix.len(), 1);
+    assert!(punishments.node_ids.is_empty());
+
+    // Add an expired punishment that should not survive the import
+    punishments
+        .ip4
+        .push((Ipv4Addr::new(10, 0, 0, 1), Timestamp::new(0)));
+
+    let address_filter2 = AddressFilter::new(config, mock_routing_table());
+    assert_eq!(address_filter2.import_punishments(punishments), 2);
+    assert!(address_filter2.is_ip_addr_punished(ip4));
+    assert!(address_filter2.is_ip_addr_punished(ip6));
+    assert!(!address_filter2.is_ip_addr_punished(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))));
+    assert_eq!(
+        address_filter2.add_connection(ip4),
+        Err(AddressFilterError::Punished)
+    );
+
+    address_filter2.clear_punishments();
+    assert!(!address_filter2.is_ip_addr_punished(ip4));
+    assert_eq!(
+        address_filter2.export_punishments(),
+        AddressFilterPunishments::default()
+    );
+}
+
+pub async fn test_spoofed_source_punishment() {
+    let config = get_config();
+    let address_filter = AddressFilter::new(config, mock_routing_table());
+
+    let ip4 = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1));
+    let other = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 2));
+
+    // Only repeated spoofed-looking packets from the same address are punished
+    assert!(!address_filter.report_spoofed_source(other));
+    let mut punished = false;
+    for _ in 0..1024 {
+        if address_filter.report_spoofed_source(ip4) {
+            punished = true;
+            break;
+        }
+        assert!(!address_filter.is_ip_addr_punished(ip4));
+    }
+    assert!(punished);
+    assert!(address_filter.is_ip_addr_punished(ip4));
+    assert!(!address_filter.is_ip_addr_punished(other));
+    assert!(address_filter.spoofed_source_count() > 1);
+}
+
 pub async fn test_all() {
     test_add_get_remove().await;
+    test_punishment_export_import().await;
+    test_spoofed_source_punishment().await;
 }
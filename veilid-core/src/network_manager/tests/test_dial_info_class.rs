@@ -0,0 +1,209 @@
+use super::*;
+use crate::tests::common::test_veilid_config::*;
+
+pub async fn test_requires_signal() {
+    for class in [
+        DialInfoClass::Direct,
+        DialInfoClass::Mapped,
+        DialInfoClass::FullConeNAT,
+    ] {
+        assert!(!class.requires_signal());
+    }
+    for class in [
+        DialInfoClass::Blocked,
+        DialInfoClass::AddressRestrictedNAT,
+        DialInfoClass::PortRestrictedNAT,
+    ] {
+        assert!(class.requires_signal());
+        assert!(class.requires_relay());
+    }
+}
+
+fn make_node_info(
+    network_class: NetworkClass,
+    dial_info_detail_list: Vec<DialInfoDetail>,
+) -> NodeInfo {
+    NodeInfo::new(
+        network_class,
+        ProtocolTypeSet::all(),
+        AddressTypeSet::all(),
+        VALID_ENVELOPE_VERSIONS.to_vec(),
+        0,
+        VALID_CRYPTO_KINDS.to_vec(),
+        vec![],
+        PUBLIC_INTERNET_CAPABILITIES.to_vec(),
+        dial_info_detail_list,
+    )
+}
+
+fn make_udp_dial_info_detail(address: &str, class: DialInfoClass) -> DialInfoDetail {
+    DialInfoDetail {
+        class,
+        dial_info: DialInfo::udp(SocketAddress::from_str(address).unwrap()),
+        preference: 0,
+    }
+}
+
+// Contact methods are only computed here, so the node info does not need to be signed
+fn make_peer_info(
+    vcrypto: CryptoSystemVersion,
+    node_info: NodeInfo,
+    opt_relay: Option<(&PeerInfo, &SignedDirectNodeInfo)>,
+) -> PeerInfo {
+    let node_ids: TypedKeyGroup =
+        TypedKey::new(vcrypto.kind(), vcrypto.generate_keypair().key).into();
+    let signed_node_info = match opt_relay {
+        Some((relay_peer_info, relay_info)) => SignedNodeInfo::Relayed(SignedRelayedNodeInfo::new(
+            node_info,
+            relay_peer_info.node_ids().clone(),
+            relay_info.clone(),
+            Timestamp::new(0),
+            vec![],
+        )),
+        None => SignedNodeInfo::Direct(SignedDirectNodeInfo::new(
+            node_info,
+            Timestamp::new(0),
+            vec![],
+        )),
+    };
+    PeerInfo::new(node_ids, signed_node_info)
+}
+
+pub async fn test_contact_method_cascade(
+    routing_table: RoutingTable,
+    vcrypto: CryptoSystemVersion,
+) {
+    let contact_methods = |peer_a: &PeerInfo, peer_b: &PeerInfo| {
+        let cm = routing_table.get_contact_method(
+            RoutingDomain::PublicInternet,
+            peer_a,
+            peer_b,
+            DialInfoFilter::all(),
+            Sequencing::NoPreference,
+            None,
+        );
+        let fallback_cm = routing_table.get_fallback_contact_method(
+            RoutingDomain::PublicInternet,
+            peer_a,
+            peer_b,
+            DialInfoFilter::all(),
+            Sequencing::NoPreference,
+            None,
+        );
+        (cm, fallback_cm)
+    };
+
+    // An inbound relay that everyone can reach
+    let relay_info = SignedDirectNodeInfo::new(
+        make_node_info(
+            NetworkClass::InboundCapable,
+            vec![make_udp_dial_info_detail(
+                "3.3.3.3:5150",
+                DialInfoClass::Direct,
+            )],
+        ),
+        Timestamp::new(0),
+        vec![],
+    );
+    let relay = make_peer_info(vcrypto.clone(), relay_info.node_info().clone(), None);
+    let relay_id = relay.node_ids().get(vcrypto.kind()).unwrap();
+
+    // A target behind a full cone NAT that also keeps an inbound relay
+    let target = make_peer_info(
+        vcrypto.clone(),
+        make_node_info(
+            NetworkClass::InboundCapable,
+            vec![make_udp_dial_info_detail(
+                "2.2.2.2:5150",
+                DialInfoClass::FullConeNAT,
+            )],
+        ),
+        Some((&relay, &relay_info)),
+    );
+    let target_id = target.node_ids().get(vcrypto.kind()).unwrap();
+
+    // We can only be reached with a hole punch, so direct falls back to a hole punch
+    let restricted_node = make_peer_info(
+        vcrypto.clone(),
+        make_node_info(
+            NetworkClass::InboundCapable,
+            vec![make_udp_dial_info_detail(
+                "1.1.1.1:5150",
+                DialInfoClass::AddressRestrictedNAT,
+            )],
+        ),
+        None,
+    );
+    let (cm, fallback_cm) = contact_methods(&restricted_node, &target);
+    assert!(matches!(cm, ContactMethod::Direct(_)));
+    assert!(matches!(
+        fallback_cm,
+        ContactMethod::SignalHolePunch(r, t) if r == relay_id && t == target_id
+    ));
+
+    // We can be reached directly, so direct falls back to a reverse connection
+    let direct_node = make_peer_info(
+        vcrypto.clone(),
+        make_node_info(
+            NetworkClass::InboundCapable,
+            vec![make_udp_dial_info_detail(
+                "1.1.1.1:5150",
+                DialInfoClass::Direct,
+            )],
+        ),
+        None,
+    );
+    let (cm, fallback_cm) = contact_methods(&direct_node, &target);
+    assert!(matches!(cm, ContactMethod::Direct(_)));
+    assert!(matches!(
+        fallback_cm,
+        ContactMethod::SignalReverse(r, t) if r == relay_id && t == target_id
+    ));
+
+    // We can't be reached at all, so direct falls back to the inbound relay
+    let outbound_node = make_peer_info(
+        vcrypto.clone(),
+        make_node_info(NetworkClass::OutboundOnly, vec![]),
+        None,
+    );
+    let (cm, fallback_cm) = contact_methods(&outbound_node, &target);
+    assert!(matches!(cm, ContactMethod::Direct(_)));
+    assert!(matches!(fallback_cm, ContactMethod::InboundRelay(r) if r == relay_id));
+
+    // A target without an inbound relay has nothing to fall back to
+    let (cm, fallback_cm) = contact_methods(&restricted_node, &relay);
+    assert!(matches!(cm, ContactMethod::Direct(_)));
+    assert!(matches!(fallback_cm, ContactMethod::Unreachable));
+
+    // Targets that need a signal already start the cascade past direct
+    let restricted_target = make_peer_info(
+        vcrypto.clone(),
+        make_node_info(
+            NetworkClass::InboundCapable,
+            vec![make_udp_dial_info_detail(
+                "2.2.2.2:5150",
+                DialInfoClass::PortRestrictedNAT,
+            )],
+        ),
+        Some((&relay, &relay_info)),
+    );
+    let (cm, fallback_cm) = contact_methods(&restricted_node, &restricted_target);
+    assert!(matches!(cm, ContactMethod::SignalHolePunch(r, _) if r == relay_id));
+    assert!(matches!(fallback_cm, ContactMethod::Unreachable));
+}
+
+pub async fn test_all() {
+    test_requires_signal().await;
+
+    let (update_callback, config_callback) = setup_veilid_core();
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+    let routing_table = api.routing_table().unwrap();
+    let crypto = api.crypto().unwrap();
+    for ck in VALID_CRYPTO_KINDS {
+        let vcrypto = crypto.get(ck).unwrap();
+        test_contact_method_cascade(routing_table.clone(), vcrypto).await;
+    }
+    api.shutdown().await;
+}
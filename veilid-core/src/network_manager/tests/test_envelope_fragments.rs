@@ -0,0 +1,108 @@
+use super::*;
+
+fn make_sender(b: u8) -> TypedKey {
+    TypedKey::new(CRYPTO_KIND_VLD0, CryptoKey::new([b; CRYPTO_KEY_LENGTH]))
+}
+
+fn make_message(len: usize) -> Vec<u8> {
+    (0..len).map(|x| x as u8).collect()
+}
+
+pub async fn test_split_and_reassemble() {
+    let message = make_message(10_000);
+    let fragments = split_envelope_fragments(&message, 1024).expect("should split");
+    assert_eq!(fragments.len(), 10);
+    assert!(fragments.iter().all(|f| f.len() <= 1024));
+
+    // Fragments may arrive in any order and more than once
+    let mut reassembly = EnvelopeFragmentReassembly::new();
+    let sender = make_sender(1);
+    let cur_ts = Timestamp::new(1_000_000);
+    let mut out = None;
+    for fragment in fragments.iter().rev().chain(fragments.iter().take(1)) {
+        if let Some(v) = reassembly
+            .insert_fragment(sender, fragment, cur_ts)
+            .expect("should insert")
+        {
+            assert!(out.is_none());
+            out = Some(v);
+        }
+    }
+    assert_eq!(out, Some(message));
+}
+
+pub async fn test_senders_kept_apart() {
+    let message = make_message(3000);
+    let fragments = split_envelope_fragments(&message, 2000).expect("should split");
+    assert_eq!(fragments.len(), 2);
+
+    // Another sender can not complete our message
+    let mut reassembly = EnvelopeFragmentReassembly::new();
+    let cur_ts = Timestamp::new(1_000_000);
+    assert_eq!(
+        reassembly
+            .insert_fragment(make_sender(1), &fragments[0], cur_ts)
+            .expect("should insert"),
+        None
+    );
+    assert_eq!(
+        reassembly
+            .insert_fragment(make_sender(2), &fragments[1], cur_ts)
+            .expect("should insert"),
+        None
+    );
+    assert_eq!(
+        reassembly
+            .insert_fragment(make_sender(1), &fragments[1], cur_ts)
+            .expect("should insert"),
+        Some(message)
+    );
+}
+
+pub async fn test_reassembly_timeout() {
+    let message = make_message(3000);
+    let fragments = split_envelope_fragments(&message, 2000).expect("should split");
+
+    // Fragments that arrive too late start a new message
+    let mut reassembly = EnvelopeFragmentReassembly::new();
+    let sender = make_sender(1);
+    let cur_ts = Timestamp::new(1_000_000);
+    let late_ts = cur_ts + ENVELOPE_FRAGMENT_REASSEMBLY_TIMEOUT_US;
+    assert_eq!(
+        reassembly
+            .insert_fragment(sender, &fragments[0], cur_ts)
+            .expect("should insert"),
+        None
+    );
+    assert_eq!(
+        reassembly
+            .insert_fragment(sender, &fragments[1], late_ts)
+            .expect("should insert"),
+        None
+    );
+}
+
+pub async fn test_invalid_fragments() {
+    let mut reassembly = EnvelopeFragmentReassembly::new();
+    let sender = make_sender(1);
+    let cur_ts = Timestamp::new(1_000_000);
+
+    // Too short for a header
+    reassembly
+        .insert_fragment(sender, &[0, 0, 0], cur_ts)
+        .expect_err("should fail");
+    // Index out of range
+    reassembly
+        .insert_fragment(sender, &[0, 0, 0, 0, 2, 2, 7], cur_ts)
+        .expect_err("should fail");
+
+    // Messages that would need more fragments than can be numbered are not split
+    split_envelope_fragments(&make_message(MAX_MESSAGE_SIZE), 64).expect_err("should fail");
+}
+
+pub async fn test_all() {
+    test_split_and_reassemble().await;
+    test_senders_kept_apart().await;
+    test_reassembly_timeout().await;
+    test_invalid_fragments().await;
+}
@@ -0,0 +1,95 @@
+use super::*;
+
+const HOST_ID: u64 = 0x0123_4567_89ab_cdef;
+
+pub async fn test_ipc_dial_info_round_trip() {
+    let di = DialInfo::try_ipc(HOST_ID, "/run/veilid/node.sock".to_string()).unwrap();
+    assert!(di.is_ipc());
+    assert!(di.is_valid());
+    assert_eq!(di.protocol_type(), ProtocolType::TCP);
+    assert_eq!(di.request(), None);
+
+    let s = di.to_string();
+    assert_eq!(s, "ipc|0123456789abcdef|/run/veilid/node.sock");
+    assert_eq!(DialInfo::from_str(&s).unwrap(), di);
+
+    // Paths can contain the separator
+    let di = DialInfo::try_ipc(HOST_ID, "/tmp/a|b".to_string()).unwrap();
+    assert_eq!(DialInfo::from_str(&di.to_string()).unwrap(), di);
+
+    assert!(DialInfo::try_ipc(HOST_ID, String::new()).is_err());
+    assert!(DialInfo::from_str("ipc|0123456789abcdef|").is_err());
+    assert!(DialInfo::from_str("ipc|nothex|/tmp/veilid.sock").is_err());
+    assert!(DialInfo::from_str("ipc|/tmp/veilid.sock").is_err());
+}
+
+pub async fn test_ipc_synthetic_address() {
+    let di = DialInfo::try_ipc(HOST_ID, "/run/veilid/node.sock".to_string()).unwrap();
+    let address = di.address();
+    assert!(address.is_ipc_mapped());
+    assert!(!address.is_onion_mapped());
+    assert!(address.is_local());
+    assert!(!address.is_global());
+
+    // Each socket on each host gets its own address
+    let other_path = DialInfo::try_ipc(HOST_ID, "/run/veilid/other.sock".to_string()).unwrap();
+    let other_host = DialInfo::try_ipc(HOST_ID + 1, "/run/veilid/node.sock".to_string()).unwrap();
+    assert_ne!(other_path.address(), address);
+    assert_ne!(other_host.address(), address);
+
+    assert!(
+        !DialInfo::tcp(SocketAddress::from_str("[fd00::1]:5150").unwrap())
+            .address()
+            .is_ipc_mapped()
+    );
+
+    // Dial info that does not match its own synthetic address is not valid
+    let DialInfo::IPC(mut ipc) = di else {
+        panic!("should be ipc dial info");
+    };
+    ipc.path = "/run/veilid/other.sock".to_string();
+    assert!(!DialInfo::IPC(ipc).is_valid());
+}
+
+pub async fn test_ipc_short_form() {
+    let di = DialInfo::try_ipc(HOST_ID, "/run/veilid/node.sock".to_string()).unwrap();
+    let (short, hostname) = di.to_short().await;
+    assert_eq!(short, "I0123456789abcdef");
+    assert_eq!(hostname, "/run/veilid/node.sock");
+    assert_eq!(
+        DialInfo::try_vec_from_short(&short, &hostname).unwrap(),
+        vec![di]
+    );
+    assert!(DialInfo::try_vec_from_short("Inothex", &hostname).is_err());
+}
+
+pub async fn test_ipc_on_this_host() {
+    let Some(host_id) = intf::host_id() else {
+        // Nothing can be on this host if it has no id
+        let di = DialInfo::try_ipc(HOST_ID, "/run/veilid/node.sock".to_string()).unwrap();
+        let DialInfo::IPC(ipc) = di else {
+            panic!("should be ipc dial info");
+        };
+        assert!(!ipc.is_on_this_host());
+        return;
+    };
+    let DialInfo::IPC(ipc) =
+        DialInfo::try_ipc(host_id, "/run/veilid/node.sock".to_string()).unwrap()
+    else {
+        panic!("should be ipc dial info");
+    };
+    assert!(ipc.is_on_this_host());
+    let DialInfo::IPC(ipc) =
+        DialInfo::try_ipc(host_id.wrapping_add(1), "/run/veilid/node.sock".to_string()).unwrap()
+    else {
+        panic!("should be ipc dial info");
+    };
+    assert!(!ipc.is_on_this_host());
+}
+
+pub async fn test_all() {
+    test_ipc_dial_info_round_trip().await;
+    test_ipc_synthetic_address().await;
+    test_ipc_short_form().await;
+    test_ipc_on_this_host().await;
+}
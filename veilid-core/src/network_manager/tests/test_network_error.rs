@@ -0,0 +1,79 @@
+use super::*;
+use std::io;
+
+pub async fn test_classify_io_errors() {
+    assert_eq!(
+        NetworkError::from_io_error(io::Error::from(io::ErrorKind::TimedOut)),
+        NetworkError::Timeout
+    );
+    assert!(matches!(
+        NetworkError::from_io_error(io::Error::from(io::ErrorKind::ConnectionRefused)),
+        NetworkError::Unreachable(_)
+    ));
+    assert!(matches!(
+        NetworkError::from_io_error(io::Error::from(io::ErrorKind::AddrInUse)),
+        NetworkError::AddrInUse(_)
+    ));
+    assert!(matches!(
+        NetworkError::from_io_error(io::Error::from(io::ErrorKind::PermissionDenied)),
+        NetworkError::Io(_)
+    ));
+
+    // Errors classified by a protocol handler survive being passed through an io error
+    let e: io::Error = NetworkError::tls_error("bad certificate").into();
+    assert_eq!(
+        NetworkError::from_io_error(e),
+        NetworkError::TlsError("bad certificate".to_owned())
+    );
+}
+
+pub async fn test_retryable() {
+    assert!(NetworkError::Timeout.is_retryable());
+    assert!(NetworkError::unreachable("refused").is_retryable());
+    assert!(NetworkError::addr_in_use("collision").is_retryable());
+    assert!(!NetworkError::tls_error("bad certificate").is_retryable());
+    assert!(!NetworkError::filtered_by_policy("punished").is_retryable());
+    assert!(!NetworkError::ShuttingDown.is_retryable());
+}
+
+pub async fn test_into_network_result() {
+    assert!(matches!(
+        NetworkError::Timeout.into_network_result::<()>(),
+        Ok(NetworkResult::Timeout)
+    ));
+    assert!(matches!(
+        NetworkError::filtered_by_policy("punished").into_network_result::<()>(),
+        Ok(NetworkResult::NoConnection(_))
+    ));
+    assert!(matches!(
+        NetworkError::addr_in_use("collision").into_network_result::<()>(),
+        Ok(NetworkResult::AlreadyExists(_))
+    ));
+    assert!(matches!(
+        NetworkError::ShuttingDown.into_network_result::<()>(),
+        Err(NetworkError::ShuttingDown)
+    ));
+    assert!(matches!(
+        NetworkError::tls_error("bad certificate").into_network_result::<()>(),
+        Err(NetworkError::TlsError(_))
+    ));
+}
+
+pub async fn test_classified() {
+    let r: io::Result<NetworkResult<()>> = Err(io::Error::from(io::ErrorKind::ConnectionRefused));
+    assert!(matches!(r.classified(), Ok(NetworkResult::NoConnection(_))));
+
+    // Fatal errors keep their type instead of being flattened into a report
+    let r: io::Result<NetworkResult<()>> = Err(NetworkError::tls_error("bad certificate").into());
+    assert_eq!(
+        r.classified().unwrap_err(),
+        NetworkError::TlsError("bad certificate".to_owned())
+    );
+}
+
+pub async fn test_all() {
+    test_classify_io_errors().await;
+    test_retryable().await;
+    test_into_network_result().await;
+    test_classified().await;
+}
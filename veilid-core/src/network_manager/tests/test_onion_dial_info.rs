@@ -0,0 +1,113 @@
+use super::*;
+
+const PUBLIC_KEY: [u8; ONION_V3_PUBLIC_KEY_LENGTH] = [0x11; ONION_V3_PUBLIC_KEY_LENGTH];
+
+/// A v3 onion hostname for a public key, with the given version byte
+fn make_onion_host(public_key: &[u8; ONION_V3_PUBLIC_KEY_LENGTH], version: u8) -> String {
+    let mut data = public_key.to_vec();
+    // The checksum is not verified, only the proxy cares about it
+    data.extend_from_slice(&[0xab, 0xcd, version]);
+    format!(
+        "{}.onion",
+        data_encoding::BASE32_NOPAD
+            .encode(&data)
+            .to_ascii_lowercase()
+    )
+}
+
+pub async fn test_onion_dial_info_round_trip() {
+    let host = make_onion_host(&PUBLIC_KEY, 3);
+    assert_eq!(host.len(), ONION_V3_LABEL_LENGTH + ".onion".len());
+    assert_eq!(decode_onion_v3_host(&host).unwrap(), PUBLIC_KEY);
+
+    let di = DialInfo::try_onion(host.clone(), 5150).unwrap();
+    assert!(di.is_onion());
+    assert!(di.is_valid());
+    assert_eq!(di.protocol_type(), ProtocolType::TCP);
+    assert_eq!(di.port(), 5150);
+    assert_eq!(di.request(), None);
+
+    let s = di.to_string();
+    assert_eq!(s, format!("onion|{}:5150", host));
+    assert_eq!(DialInfo::from_str(&s).unwrap(), di);
+
+    // Hostnames are case insensitive
+    assert_eq!(
+        DialInfo::try_onion(host.to_ascii_uppercase(), 5150).unwrap(),
+        di
+    );
+
+    // A port is required
+    let DialInfo::Onion(mut onion) = di else {
+        panic!("should be onion dial info");
+    };
+    onion.socket_address.set_port(0);
+    assert!(!DialInfo::Onion(onion).is_valid());
+    assert!(DialInfo::from_str(&format!("onion|{}", host)).is_err());
+    assert!(DialInfo::from_str(&format!("onion|{}:notaport", host)).is_err());
+}
+
+pub async fn test_onion_host_validation() {
+    // Not an onion host
+    assert!(DialInfo::try_onion("example.com".to_string(), 5150).is_err());
+    // v2 onion hosts are too short
+    assert!(DialInfo::try_onion("expyuzz4wqqyqhjn.onion".to_string(), 5150).is_err());
+    // Wrong version byte
+    assert!(DialInfo::try_onion(make_onion_host(&PUBLIC_KEY, 2), 5150).is_err());
+    // Not base32
+    let host = format!("{}.onion", "1".repeat(ONION_V3_LABEL_LENGTH));
+    assert!(DialInfo::try_onion(host, 5150).is_err());
+}
+
+pub async fn test_onion_synthetic_address() {
+    let di = DialInfo::try_onion(make_onion_host(&PUBLIC_KEY, 3), 5150).unwrap();
+    let address = di.address();
+    assert_eq!(address, Address::from_onion_public_key(&PUBLIC_KEY));
+    assert!(address.is_onion_mapped());
+    assert!(!address.is_ipc_mapped());
+    assert_eq!(
+        di.peer_address(),
+        PeerAddress::new(di.socket_address(), ProtocolType::TCP)
+    );
+
+    // Each service gets its own address
+    let other = DialInfo::try_onion(make_onion_host(&[0x22; 32], 3), 5150).unwrap();
+    assert_ne!(other.address(), address);
+
+    assert!(
+        !DialInfo::tcp(SocketAddress::from_str("[fd00::1]:5150").unwrap())
+            .address()
+            .is_onion_mapped()
+    );
+}
+
+pub async fn test_onion_short_form() {
+    let host = make_onion_host(&PUBLIC_KEY, 3);
+    let di = DialInfo::try_onion(host.clone(), 5150).unwrap();
+    let (short, hostname) = di.to_short().await;
+    assert_eq!(short, "O5150");
+    assert_eq!(hostname, host);
+    assert_eq!(
+        DialInfo::try_vec_from_short(&short, &hostname).unwrap(),
+        vec![di]
+    );
+    assert!(DialInfo::try_vec_from_short("Onotaport", &hostname).is_err());
+}
+
+pub async fn test_onion_encode_decode() {
+    let di = DialInfo::try_onion(make_onion_host(&PUBLIC_KEY, 3), 5150).unwrap();
+
+    let mut message = capnp::message::Builder::new_default();
+    let mut builder = message.init_root::<veilid_capnp::dial_info::Builder>();
+    encode_dial_info(&di, &mut builder).unwrap();
+    let decoded = decode_dial_info(&builder.into_reader()).unwrap();
+    assert_eq!(decoded, di);
+}
+
+pub async fn test_all() {
+    test_onion_dial_info_round_trip().await;
+    test_onion_host_validation().await;
+    test_onion_synthetic_address().await;
+    test_onion_short_form().await;
+    test_onion_encode_decode().await;
+}
@@ -0,0 +1,37 @@
+use super::*;
+use crate::network_manager::tasks::public_address_check::*;
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+pub async fn test_reporting_subnet() {
+    assert_eq!(reporting_subnet(ip("203.0.113.77")), ip("203.0.113.0"));
+    assert_eq!(reporting_subnet(ip("203.0.114.77")), ip("203.0.114.0"));
+
+    // IPv6 ip blocks are already reduced to their prefix
+    let v6block = ip_to_ipblock(56, ip("2001:db8:1234:5678::1"));
+    assert_eq!(reporting_subnet(v6block), v6block);
+}
+
+pub async fn test_count_distinct_reporting_subnets() {
+    assert_eq!(count_distinct_reporting_subnets(&[]), 0);
+
+    // Many peers on one network count once
+    let same_network: Vec<IpAddr> = (1..=5).map(|n| ip(&format!("203.0.113.{}", n))).collect();
+    assert_eq!(count_distinct_reporting_subnets(&same_network), 1);
+
+    let mixed = [
+        ip("203.0.113.1"),
+        ip("203.0.113.2"),
+        ip("198.51.100.1"),
+        ip("192.0.2.1"),
+        ip("192.0.2.200"),
+    ];
+    assert_eq!(count_distinct_reporting_subnets(&mixed), 3);
+}
+
+pub async fn test_all() {
+    test_reporting_subnet().await;
+    test_count_distinct_reporting_subnets().await;
+}
@@ -0,0 +1,109 @@
+use super::*;
+
+fn make_client(b: u8) -> TypedKey {
+    TypedKey::new(CRYPTO_KIND_VLD0, CryptoKey::new([b; CRYPTO_KEY_LENGTH]))
+}
+
+const UNLIMITED: RelayClientLimits = RelayClientLimits {
+    bytes_per_sec: 0,
+    max_queued_bytes: 1_048_576,
+};
+
+pub async fn test_clients_take_turns() {
+    let mut scheduler = RelayScheduler::<u32>::new();
+    let a = make_client(1);
+    let b = make_client(2);
+    let cur_ts = Timestamp::new(1_000_000);
+
+    // A noisy client queues first, but does not get to go ahead of a quieter one
+    for n in 0..4 {
+        scheduler
+            .enqueue(a, n, 60_000, UNLIMITED, cur_ts)
+            .expect("should queue");
+    }
+    for n in 10..12 {
+        scheduler
+            .enqueue(b, n, 60_000, UNLIMITED, cur_ts)
+            .expect("should queue");
+    }
+
+    let mut order = vec![];
+    while let Some((client, item)) = scheduler.next() {
+        order.push((client, item));
+    }
+    assert_eq!(
+        order,
+        vec![(a, 0), (b, 10), (a, 1), (b, 11), (a, 2), (a, 3)]
+    );
+    assert!(scheduler.is_empty());
+}
+
+pub async fn test_queue_limit() {
+    let mut scheduler = RelayScheduler::<u32>::new();
+    let a = make_client(1);
+    let limits = RelayClientLimits {
+        bytes_per_sec: 0,
+        max_queued_bytes: 100_000,
+    };
+    let cur_ts = Timestamp::new(1_000_000);
+
+    scheduler
+        .enqueue(a, 0, 60_000, limits, cur_ts)
+        .expect("should queue");
+    assert_eq!(
+        scheduler.enqueue(a, 1, 60_000, limits, cur_ts),
+        Err(RelayRejection::QueueFull)
+    );
+
+    // Once the queue drains there is room again
+    assert_eq!(scheduler.next(), Some((a, 0)));
+    scheduler
+        .enqueue(a, 2, 60_000, limits, cur_ts)
+        .expect("should queue");
+
+    let stats = scheduler.client_stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].client, a);
+    assert_eq!(stats[0].relayed_bytes, ByteCount::new(60_000));
+    assert_eq!(stats[0].dropped_bytes, ByteCount::new(60_000));
+    assert_eq!(stats[0].queued_bytes, ByteCount::new(60_000));
+}
+
+pub async fn test_bandwidth_limit() {
+    let mut scheduler = RelayScheduler::<u32>::new();
+    let a = make_client(1);
+    let b = make_client(2);
+    let limits = RelayClientLimits {
+        bytes_per_sec: 100_000,
+        max_queued_bytes: 1_048_576,
+    };
+    let cur_ts = Timestamp::new(1_000_000);
+
+    scheduler
+        .enqueue(a, 0, 60_000, limits, cur_ts)
+        .expect("should queue");
+    assert_eq!(
+        scheduler.enqueue(a, 1, 60_000, limits, cur_ts),
+        Err(RelayRejection::OverBandwidth)
+    );
+
+    // Limits are per client
+    scheduler
+        .enqueue(b, 10, 60_000, limits, cur_ts)
+        .expect("should queue");
+
+    // The allowance refills over time
+    assert_eq!(
+        scheduler.enqueue(a, 2, 60_000, limits, Timestamp::new(1_100_000)),
+        Err(RelayRejection::OverBandwidth)
+    );
+    scheduler
+        .enqueue(a, 3, 60_000, limits, Timestamp::new(1_500_000))
+        .expect("should queue");
+}
+
+pub async fn test_all() {
+    test_clients_take_turns().await;
+    test_queue_limit().await;
+    test_bandwidth_limit().await;
+}
@@ -0,0 +1,112 @@
+use super::*;
+
+fn make_message(priority: SendPriority, len: usize, tag: u8) -> QueuedMessage {
+    (None, priority, vec![tag; len])
+}
+
+pub async fn test_priority_first() {
+    let (sender, receiver) = send_queue(4);
+
+    // Queue application payloads ahead of a control message
+    sender
+        .send_async(make_message(SendPriority::Normal, 2048, 1))
+        .await
+        .expect("should queue");
+    sender
+        .send_async(make_message(SendPriority::Normal, 8192, 2))
+        .await
+        .expect("should queue");
+    sender
+        .send_async(make_message(SendPriority::Control, 16, 3))
+        .await
+        .expect("should queue");
+
+    // The control message jumps ahead, the others keep their order
+    let order: Vec<u8> = [
+        receiver.recv_async().await.expect("should receive"),
+        receiver.recv_async().await.expect("should receive"),
+        receiver.recv_async().await.expect("should receive"),
+    ]
+    .iter()
+    .map(|x| x.2[0])
+    .collect();
+    assert_eq!(order, vec![3, 1, 2]);
+}
+
+pub async fn test_small_payloads_stay_ordered() {
+    let (sender, receiver) = send_queue(4);
+
+    // A small application payload must not overtake a larger one queued before it
+    sender
+        .send_async(make_message(SendPriority::Normal, 8192, 1))
+        .await
+        .expect("should queue");
+    sender
+        .send_async(make_message(SendPriority::Normal, 16, 2))
+        .await
+        .expect("should queue");
+
+    let order: Vec<u8> = [
+        receiver.recv_async().await.expect("should receive"),
+        receiver.recv_async().await.expect("should receive"),
+    ]
+    .iter()
+    .map(|x| x.2[0])
+    .collect();
+    assert_eq!(order, vec![1, 2]);
+}
+
+pub async fn test_bulk_full_does_not_block_priority() {
+    let (sender, receiver) = send_queue(1);
+
+    sender
+        .send_async(make_message(SendPriority::Normal, 2048, 1))
+        .await
+        .expect("should queue");
+
+    // The normal queue is full, but control messages still get queued right away
+    timeout(
+        1000,
+        sender.send_async(make_message(SendPriority::Control, 2048, 2)),
+    )
+    .await
+    .expect("should not block")
+    .expect("should queue");
+
+    assert_eq!(receiver.recv_async().await.expect("should receive").2[0], 2);
+}
+
+pub async fn test_drain() {
+    let (sender, receiver) = send_queue(4);
+
+    sender
+        .send_async(make_message(SendPriority::Normal, 2048, 1))
+        .await
+        .expect("should queue");
+    sender
+        .send_async(make_message(SendPriority::Control, 1, 2))
+        .await
+        .expect("should queue");
+
+    // Drained messages keep their priority so they can be queued again elsewhere
+    let pending: Vec<(SendPriority, u8)> = receiver.drain().iter().map(|x| (x.1, x.2[0])).collect();
+    assert_eq!(
+        pending,
+        vec![(SendPriority::Control, 2), (SendPriority::Normal, 1)]
+    );
+    assert!(receiver.drain().is_empty());
+
+    // Once the receiving side is gone, messages come back to the caller
+    drop(receiver);
+    let res = sender
+        .send_async(make_message(SendPriority::Normal, 1, 3))
+        .await;
+    assert_eq!(res.expect_err("should not queue").2, vec![3]);
+}
+
+pub async fn test_all() {
+    test_priority_first().await;
+    test_small_payloads_stay_ordered().await;
+    test_bulk_full_does_not_block_priority().await;
+    test_drain().await;
+}
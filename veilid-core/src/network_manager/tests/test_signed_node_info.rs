@@ -19,19 +19,24 @@ pub async fn test_signed_node_info() {
             ProtocolTypeSet::all(),
             AddressTypeSet::all(),
             VALID_ENVELOPE_VERSIONS.to_vec(),
+            0,
             VALID_CRYPTO_KINDS.to_vec(),
+            vec![],
             PUBLIC_INTERNET_CAPABILITIES.to_vec(),
             vec![DialInfoDetail {
                 class: DialInfoClass::Mapped,
                 dial_info: DialInfo::udp(SocketAddress::default()),
+                preference: 0,
             }],
         );
 
         // Test correct validation
         let keypair = vcrypto.generate_keypair();
         let sni = SignedDirectNodeInfo::make_signatures(
-            crypto.clone(),
-            vec![TypedKeyPair::new(ck, keypair)],
+            &[Arc::new(SecretKeyNodeIdSigner::new(
+                vcrypto.clone(),
+                keypair,
+            ))],
             node_info.clone(),
         )
         .unwrap();
@@ -75,11 +80,14 @@ pub async fn test_signed_node_info() {
             ProtocolTypeSet::all(),
             AddressTypeSet::all(),
             VALID_ENVELOPE_VERSIONS.to_vec(),
+            0,
             VALID_CRYPTO_KINDS.to_vec(),
+            vec![],
             PUBLIC_INTERNET_CAPABILITIES.to_vec(),
             vec![DialInfoDetail {
                 class: DialInfoClass::Blocked,
                 dial_info: DialInfo::udp(SocketAddress::default()),
+                preference: 0,
             }],
         );
 
@@ -89,8 +97,10 @@ pub async fn test_signed_node_info() {
         let oldtks2len = tks2.len();
 
         let sni2 = SignedRelayedNodeInfo::make_signatures(
-            crypto.clone(),
-            vec![TypedKeyPair::new(ck, keypair2)],
+            &[Arc::new(SecretKeyNodeIdSigner::new(
+                vcrypto.clone(),
+                keypair2,
+            ))],
             node_info2.clone(),
             tks.clone(),
             sni.clone(),
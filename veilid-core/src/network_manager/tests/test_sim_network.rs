@@ -0,0 +1,243 @@
+use super::*;
+
+fn sim_addr(n: u8) -> SocketAddr {
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(11, 0, 0, n)), 5150)
+}
+
+fn connect(
+    sim_network: &SimNetwork,
+    from: Option<SocketAddr>,
+    to: SocketAddr,
+) -> NetworkResult<ProtocolNetworkConnection> {
+    sim_network.connect(from, to).expect("should not fail")
+}
+
+/// Send numbered messages from one address to another and return the numbers that arrived
+async fn deliver(sim_network: &SimNetwork, count: u8) -> Vec<u8> {
+    let listener = sim_network.listen(sim_addr(2)).expect("should listen");
+    // Connection attempts can be lost too
+    let conn = loop {
+        match connect(sim_network, Some(sim_addr(1)), sim_addr(2)) {
+            NetworkResult::Value(v) => break v,
+            NetworkResult::Timeout => continue,
+            r => panic!("should connect: {}", r),
+        }
+    };
+    let accepted = listener.recv_async().await.expect("should accept");
+    assert_eq!(
+        *accepted.flow().remote().socket_address(),
+        conn.flow().local().unwrap()
+    );
+
+    for n in 0..count {
+        conn.send(vec![n]).await.expect("should send");
+    }
+    conn.close().await.expect("should close");
+
+    let mut out = Vec::new();
+    while let NetworkResult::Value(v) = accepted.recv().await.expect("should recv") {
+        out.push(v[0]);
+    }
+    sim_network.unlisten(sim_addr(2));
+    out
+}
+
+pub async fn test_loss_is_deterministic() {
+    let sim_network = SimNetwork::new(42, SimLink::new(0, 0.5));
+    let first = deliver(&sim_network, 100).await;
+    let sim_network = SimNetwork::new(42, SimLink::new(0, 0.5));
+    let second = deliver(&sim_network, 100).await;
+
+    // The same seed loses the same messages, and messages that arrive stay in order
+    assert_eq!(first, second);
+    assert!(!first.is_empty() && first.len() < 100);
+    assert!(first.windows(2).all(|w| w[0] < w[1]));
+}
+
+pub async fn test_latency() {
+    let sim_network = SimNetwork::new(0, SimLink::default());
+    sim_network.set_link(sim_addr(1).ip(), sim_addr(2).ip(), SimLink::new(100, 0.0));
+
+    let start_ts = get_timestamp();
+    assert_eq!(deliver(&sim_network, 1).await, vec![0]);
+
+    assert!(get_timestamp() - start_ts >= 100_000);
+}
+
+pub async fn test_partition_and_refused() {
+    let sim_network = SimNetwork::new(0, SimLink::default());
+
+    // Nobody listening
+    assert!(matches!(
+        connect(&sim_network, Some(sim_addr(1)), sim_addr(2)),
+        NetworkResult::NoConnection(_)
+    ));
+
+    // Partitioned nodes can not reach each other until healed
+    let _listener = sim_network.listen(sim_addr(2)).expect("should listen");
+    sim_network
+        .listen(sim_addr(2))
+        .expect_err("should already be listening");
+    sim_network.partition(sim_addr(1).ip(), sim_addr(2).ip());
+    assert!(matches!(
+        connect(&sim_network, Some(sim_addr(1)), sim_addr(2)),
+        NetworkResult::Timeout
+    ));
+    sim_network.heal(sim_addr(1).ip(), sim_addr(2).ip());
+    assert!(matches!(
+        connect(&sim_network, Some(sim_addr(1)), sim_addr(2)),
+        NetworkResult::Value(_)
+    ));
+
+    // Connections without a local address come from the unbound address
+    let NetworkResult::Value(conn) = connect(&sim_network, None, sim_addr(2)) else {
+        panic!("should connect");
+    };
+    assert_eq!(conn.flow().local().unwrap().ip_addr(), SIM_UNBOUND_ADDRESS);
+}
+
+pub async fn test_separate_networks() {
+    // Simulated networks do not share listeners, so tests can each run their own
+    let sim_network_a = SimNetwork::new(0, SimLink::default());
+    let sim_network_b = SimNetwork::new(0, SimLink::default());
+    let _listener = sim_network_a.listen(sim_addr(2)).expect("should listen");
+    assert!(matches!(
+        connect(&sim_network_b, Some(sim_addr(1)), sim_addr(2)),
+        NetworkResult::NoConnection(_)
+    ));
+    assert!(matches!(
+        connect(&sim_network_a, Some(sim_addr(1)), sim_addr(2)),
+        NetworkResult::Value(_)
+    ));
+}
+
+pub async fn test_udp_datagrams() {
+    let sim_network = SimNetwork::new(0, SimLink::default());
+    sim_network.set_link(sim_addr(1).ip(), sim_addr(2).ip(), SimLink::new(100, 0.0));
+    let a = sim_network
+        .bind_udp(Some(sim_addr(1)))
+        .expect("should bind");
+    let b = sim_network
+        .bind_udp(Some(sim_addr(2)))
+        .expect("should bind");
+    sim_network
+        .bind_udp(Some(sim_addr(2)))
+        .expect_err("should already be bound");
+
+    // Datagrams arrive after the latency of their direction, from the sender's address
+    let start_ts = get_timestamp();
+    assert_eq!(a.send_to(b"ping", sim_addr(2)).await.unwrap(), 4);
+    let mut buf = vec![0u8; 16];
+    let (len, from) = b.recv_from(&mut buf).await.expect("should recv");
+    assert_eq!(&buf[..len], b"ping");
+    assert_eq!(from, sim_addr(1));
+    assert!(get_timestamp() - start_ts >= 100_000);
+
+    b.send_to(b"pong", from).await.unwrap();
+    let (len, from) = a.recv_from(&mut buf).await.expect("should recv");
+    assert_eq!(&buf[..len], b"pong");
+    assert_eq!(from, sim_addr(2));
+
+    // Sockets without an address get their own port at the unbound address
+    let unbound = sim_network.bind_udp(None).expect("should bind");
+    assert_eq!(unbound.local_addr().unwrap().ip(), SIM_UNBOUND_ADDRESS);
+    unbound.send_to(b"hello", sim_addr(2)).await.unwrap();
+    let (_, from) = b.recv_from(&mut buf).await.expect("should recv");
+    assert_eq!(from, unbound.local_addr().unwrap());
+
+    // Partitioned datagrams are silently lost, and nobody listening is not an error
+    sim_network.partition(sim_addr(1).ip(), sim_addr(2).ip());
+    a.send_to(b"lost", sim_addr(2)).await.unwrap();
+    a.send_to(b"nobody", sim_addr(3)).await.unwrap();
+    sim_network.heal(sim_addr(1).ip(), sim_addr(2).ip());
+    a.send_to(b"found", sim_addr(2)).await.unwrap();
+    let (len, _) = b.recv_from(&mut buf).await.expect("should recv");
+    assert_eq!(&buf[..len], b"found");
+
+    // Dropping a socket unbinds its address
+    drop(b);
+    sim_network
+        .bind_udp(Some(sim_addr(2)))
+        .expect("should bind again");
+}
+
+/// Send one fragmented message with its frames alternating between two receiving sockets,
+/// the way the kernel may spread them over a SO_REUSEPORT pool
+async fn deliver_split(shared: bool) -> Option<Vec<u8>> {
+    let sim_network = SimNetwork::new(0, SimLink::default());
+    let sender = sim_network
+        .bind_udp(Some(sim_addr(1)))
+        .expect("should bind");
+    let assembly_buffer = AssemblyBuffer::new();
+    let other_assembly_buffer = if shared {
+        assembly_buffer.clone()
+    } else {
+        AssemblyBuffer::new()
+    };
+    let a = RawUdpProtocolHandler::new_sim(
+        sim_network
+            .bind_udp(Some(sim_addr(2)))
+            .expect("should bind"),
+        assembly_buffer,
+        None,
+    );
+    let b = RawUdpProtocolHandler::new_sim(
+        sim_network
+            .bind_udp(Some(sim_addr(3)))
+            .expect("should bind"),
+        other_assembly_buffer,
+        None,
+    );
+
+    let message: Vec<u8> = (0..10_000u32).map(|n| n as u8).collect();
+    let mut frames = 0usize;
+    let res = AssemblyBuffer::new()
+        .split_message(message.clone(), sim_addr(2), |frame, _| {
+            let to = if frames % 2 == 0 {
+                sim_addr(2)
+            } else {
+                sim_addr(3)
+            };
+            frames += 1;
+            let sender = &sender;
+            async move {
+                sender.send_to(&frame, to).await?;
+                Ok(NetworkResult::value(()))
+            }
+        })
+        .await
+        .expect("should split");
+    assert!(matches!(res, NetworkResult::Value(())));
+    assert!(frames > 2);
+
+    // The socket that only got some of the frames never completes the message by itself
+    let mut buf = vec![0u8; 65536];
+    assert!(timeout(200, b.recv_message(&mut buf)).await.is_err());
+
+    let (len, flow) = timeout(200, a.recv_message(&mut buf)).await.ok()?.ok()?;
+    assert_eq!(
+        flow.remote().socket_addr(),
+        sim_addr(1),
+        "should come from the sender"
+    );
+    assert_eq!(len, message.len());
+    Some(buf[..len].to_vec())
+}
+
+pub async fn test_udp_shared_assembly_buffer() {
+    // Sockets that share an assembly buffer reassemble fragments whichever of them receives them
+    let message: Vec<u8> = (0..10_000u32).map(|n| n as u8).collect();
+    assert_eq!(deliver_split(true).await, Some(message));
+
+    // Sockets with their own assembly buffers each only ever see part of the message
+    assert_eq!(deliver_split(false).await, None);
+}
+
+pub async fn test_all() {
+    test_loss_is_deterministic().await;
+    test_latency().await;
+    test_partition_and_refused().await;
+    test_separate_networks().await;
+    test_udp_datagrams().await;
+    test_udp_shared_assembly_buffer().await;
+}
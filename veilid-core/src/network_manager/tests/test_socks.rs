@@ -0,0 +1,207 @@
+use super::*;
+use futures_util::{AsyncRead, AsyncReadExt, AsyncWrite};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A proxy that replies with canned bytes and records everything written to it
+struct MockProxyStream {
+    reply: futures_util::io::Cursor<Vec<u8>>,
+    request: Vec<u8>,
+}
+
+impl MockProxyStream {
+    fn new(reply: Vec<u8>) -> Self {
+        Self {
+            reply: futures_util::io::Cursor::new(reply),
+            request: Vec::new(),
+        }
+    }
+    fn fully_read(&self) -> bool {
+        self.reply.position() == self.reply.get_ref().len() as u64
+    }
+}
+
+impl AsyncRead for MockProxyStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.get_mut().reply).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for MockProxyStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.get_mut().request.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Method selection for no authentication, then a successful reply bound to 127.0.0.1:5150
+const SUCCESS_REPLY: [u8; 12] = [5, 0, 5, 0, 0, 1, 127, 0, 0, 1, 0x14, 0x1e];
+
+pub async fn test_connect_by_hostname() {
+    let host = "example.onion";
+    let mut stream = MockProxyStream::new(SUCCESS_REPLY.to_vec());
+    socks::socks5_handshake(&mut stream, host, 5150)
+        .await
+        .expect("handshake should succeed");
+
+    // Hostnames go to the proxy unresolved
+    let mut expected = vec![5, 1, 0, 5, 1, 0, 3, host.len() as u8];
+    expected.extend_from_slice(host.as_bytes());
+    expected.extend_from_slice(&[0x14, 0x1e]);
+    assert_eq!(stream.request, expected);
+    assert!(stream.fully_read());
+}
+
+pub async fn test_connect_by_address() {
+    let mut stream = MockProxyStream::new(SUCCESS_REPLY.to_vec());
+    socks::socks5_handshake(&mut stream, "1.2.3.4", 80)
+        .await
+        .expect("handshake should succeed");
+    assert_eq!(stream.request, vec![5, 1, 0, 5, 1, 0, 1, 1, 2, 3, 4, 0, 80]);
+
+    let mut stream = MockProxyStream::new(SUCCESS_REPLY.to_vec());
+    socks::socks5_handshake(&mut stream, "::1", 80)
+        .await
+        .expect("handshake should succeed");
+    let mut expected = vec![5, 1, 0, 5, 1, 0, 4];
+    expected.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+    expected.extend_from_slice(&[0, 80]);
+    assert_eq!(stream.request, expected);
+}
+
+pub async fn test_bound_address_types() {
+    // The bound address is skipped whatever its type, leaving the stream at the proxied data
+    let mut reply = vec![5, 0, 5, 0, 0, 4];
+    reply.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+    reply.extend_from_slice(&[0x14, 0x1e]);
+    let mut stream = MockProxyStream::new(reply);
+    socks::socks5_handshake(&mut stream, "example.onion", 5150)
+        .await
+        .expect("handshake should succeed");
+    assert!(stream.fully_read());
+
+    let mut reply = vec![5, 0, 5, 0, 0, 3, 9];
+    reply.extend_from_slice(b"localhost");
+    reply.extend_from_slice(&[0x14, 0x1e]);
+    let mut stream = MockProxyStream::new(reply);
+    socks::socks5_handshake(&mut stream, "example.onion", 5150)
+        .await
+        .expect("handshake should succeed");
+    assert!(stream.fully_read());
+
+    let mut stream = MockProxyStream::new(vec![5, 0, 5, 0, 0, 9, 0, 0]);
+    assert!(socks::socks5_handshake(&mut stream, "example.onion", 5150)
+        .await
+        .is_err());
+}
+
+pub async fn test_proxy_failures() {
+    // No acceptable authentication method
+    let mut stream = MockProxyStream::new(vec![5, 0xff]);
+    assert!(socks::socks5_handshake(&mut stream, "example.onion", 5150)
+        .await
+        .is_err());
+
+    // Not a SOCKS5 proxy
+    let mut stream = MockProxyStream::new(vec![4, 0]);
+    assert!(socks::socks5_handshake(&mut stream, "example.onion", 5150)
+        .await
+        .is_err());
+
+    // Host unreachable
+    let mut stream = MockProxyStream::new(vec![5, 0, 5, 4, 0, 1, 0, 0, 0, 0, 0, 0]);
+    assert!(socks::socks5_handshake(&mut stream, "example.onion", 5150)
+        .await
+        .is_err());
+
+    // Proxy hangs up mid reply
+    let mut stream = MockProxyStream::new(vec![5, 0, 5, 0]);
+    assert!(socks::socks5_handshake(&mut stream, "example.onion", 5150)
+        .await
+        .is_err());
+
+    // Hostnames longer than a SOCKS5 length byte
+    let mut stream = MockProxyStream::new(SUCCESS_REPLY.to_vec());
+    assert!(socks::socks5_handshake(&mut stream, &"a".repeat(256), 5150)
+        .await
+        .is_err());
+}
+
+/// Run a single connection SOCKS5 proxy that greets the client once connected onward
+fn spawn_proxy(host: &'static str, port: u16) -> (SocketAddr, std::thread::JoinHandle<()>) {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let proxy_addr = listener.local_addr().unwrap();
+    let jh = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut method = [0u8; 3];
+        stream.read_exact(&mut method).unwrap();
+        assert_eq!(method, [5, 1, 0]);
+        stream.write_all(&[5, 0]).unwrap();
+
+        let mut request = vec![0u8; 5 + host.len() + 2];
+        stream.read_exact(&mut request).unwrap();
+        assert_eq!(&request[0..5], &[5, 1, 0, 3, host.len() as u8]);
+        assert_eq!(&request[5..5 + host.len()], host.as_bytes());
+        assert_eq!(&request[5 + host.len()..], &port.to_be_bytes());
+        stream.write_all(&SUCCESS_REPLY[2..]).unwrap();
+
+        stream.write_all(b"hello").unwrap();
+    });
+    (proxy_addr, jh)
+}
+
+pub async fn test_socks5_connect() {
+    let (proxy_addr, jh) = spawn_proxy("example.onion", 5150);
+
+    let (mut ts, local_address) = socks::socks5_connect(proxy_addr, "example.onion", 5150, 5000)
+        .await
+        .expect("should connect to proxy")
+        .into_option()
+        .expect("should not time out");
+    assert!(local_address.ip().is_loopback());
+
+    // Data after the handshake is from the proxied connection
+    let mut greeting = [0u8; 5];
+    ts.read_exact(&mut greeting).await.unwrap();
+    assert_eq!(&greeting, b"hello");
+
+    jh.join().unwrap();
+}
+
+pub async fn test_socks5_connect_refused() {
+    // Nothing is listening once the listener is dropped
+    let proxy_addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    assert!(
+        socks::socks5_connect(proxy_addr, "example.onion", 5150, 5000)
+            .await
+            .is_err()
+    );
+}
+
+pub async fn test_all() {
+    test_connect_by_hostname().await;
+    test_connect_by_address().await;
+    test_bound_address_types().await;
+    test_proxy_failures().await;
+    test_socks5_connect().await;
+    test_socks5_connect_refused().await;
+}
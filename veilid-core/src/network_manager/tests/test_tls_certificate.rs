@@ -0,0 +1,89 @@
+use super::*;
+
+const DER_SEQUENCE: u8 = 0x30;
+const DER_UTC_TIME: u8 = 0x17;
+const DER_GENERALIZED_TIME: u8 = 0x18;
+
+fn der(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag, contents.len() as u8];
+    out.extend_from_slice(contents);
+    out
+}
+
+/// Just enough of a certificate to get to the validity period
+fn make_cert(with_version: bool, not_after: Vec<u8>) -> Vec<u8> {
+    let mut tbs_cert = Vec::new();
+    if with_version {
+        tbs_cert.extend(der(0xa0, &der(0x02, &[0x02])));
+    }
+    tbs_cert.extend(der(0x02, &[0x01]));
+    tbs_cert.extend(der(DER_SEQUENCE, &[]));
+    tbs_cert.extend(der(DER_SEQUENCE, &[]));
+    let mut validity = der(DER_UTC_TIME, b"010101000000Z");
+    validity.extend(not_after);
+    tbs_cert.extend(der(DER_SEQUENCE, &validity));
+    der(DER_SEQUENCE, &der(DER_SEQUENCE, &tbs_cert))
+}
+
+pub async fn test_test_certificate() {
+    let pem = include_bytes!("../../tests/files/cert.pem");
+    let certs = rustls_pemfile::certs(&mut &pem[..]).unwrap();
+    assert_eq!(
+        certificate_expiration(&certs[0]),
+        Some(Timestamp::new(1_708_869_136_000_000))
+    );
+}
+
+pub async fn test_validity_times() {
+    // UTCTime, with and without a version
+    let utc = der(DER_UTC_TIME, b"240225135216Z");
+    assert_eq!(
+        certificate_expiration(&make_cert(true, utc.clone())),
+        Some(Timestamp::new(1_708_869_136_000_000))
+    );
+    assert_eq!(
+        certificate_expiration(&make_cert(false, utc)),
+        Some(Timestamp::new(1_708_869_136_000_000))
+    );
+
+    // GeneralizedTime for dates past 2049
+    let generalized = der(DER_GENERALIZED_TIME, b"20510102030405Z");
+    assert_eq!(
+        certificate_expiration(&make_cert(true, generalized)),
+        Some(Timestamp::new(2_556_241_445_000_000))
+    );
+}
+
+pub async fn test_malformed_certificates() {
+    assert_eq!(certificate_expiration(&[]), None);
+
+    // Truncated anywhere
+    let cert = make_cert(true, der(DER_UTC_TIME, b"240225135216Z"));
+    for len in 0..cert.len() {
+        assert_eq!(certificate_expiration(&cert[..len]), None);
+    }
+
+    // Not a time
+    assert_eq!(
+        certificate_expiration(&make_cert(true, der(0x02, &[0x01]))),
+        None
+    );
+    assert_eq!(
+        certificate_expiration(&make_cert(true, der(DER_UTC_TIME, b"24022513Z"))),
+        None
+    );
+
+    // Missing notAfter
+    let mut tbs_cert = der(0x02, &[0x01]);
+    tbs_cert.extend(der(DER_SEQUENCE, &[]));
+    tbs_cert.extend(der(DER_SEQUENCE, &[]));
+    tbs_cert.extend(der(DER_SEQUENCE, &der(DER_UTC_TIME, b"010101000000Z")));
+    let cert = der(DER_SEQUENCE, &der(DER_SEQUENCE, &tbs_cert));
+    assert_eq!(certificate_expiration(&cert), None);
+}
+
+pub async fn test_all() {
+    test_test_certificate().await;
+    test_validity_times().await;
+    test_malformed_certificates().await;
+}
@@ -1,5 +1,10 @@
 use super::*;
 
+/// OnionCat prefix used to map Tor onion services into the IPv6 address space
+const ONION_MAPPED_PREFIX: [u8; 6] = [0xfd, 0x87, 0xd8, 0x7e, 0xeb, 0x43];
+/// Unique local prefix used to map IPC sockets of processes on the same host into the IPv6 address space
+const IPC_MAPPED_PREFIX: [u8; 6] = [0xfd, 0x56, 0x4c, 0x49, 0x50, 0x43];
+
 // Ordering here matters, IPV6 is preferred to IPV4 in dial info sorts
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Hash, Serialize, Deserialize)]
 pub enum Address {
@@ -27,6 +32,36 @@ impl Address {
             IpAddr::V6(v6) => Address::IPV6(v6),
         }
     }
+    /// Synthetic address standing in for the Tor onion service with this public key
+    pub fn from_onion_public_key(public_key: &[u8; ONION_V3_PUBLIC_KEY_LENGTH]) -> Address {
+        let mut octets = [0u8; 16];
+        octets[0..6].copy_from_slice(&ONION_MAPPED_PREFIX);
+        octets[6..16].copy_from_slice(&public_key[0..10]);
+        Address::IPV6(Ipv6Addr::from(octets))
+    }
+    pub fn is_onion_mapped(&self) -> bool {
+        match self {
+            Address::IPV4(_) => false,
+            Address::IPV6(v6) => v6.octets()[0..6] == ONION_MAPPED_PREFIX,
+        }
+    }
+    /// Synthetic address standing in for the IPC socket at `path` on the host with this id
+    pub fn from_ipc_path(host_id: u64, path: &str) -> Address {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&host_id.to_le_bytes());
+        hasher.update(path.as_bytes());
+        let hash = hasher.finalize();
+        let mut octets = [0u8; 16];
+        octets[0..6].copy_from_slice(&IPC_MAPPED_PREFIX);
+        octets[6..16].copy_from_slice(&hash.as_bytes()[0..10]);
+        Address::IPV6(Ipv6Addr::from(octets))
+    }
+    pub fn is_ipc_mapped(&self) -> bool {
+        match self {
+            Address::IPV4(_) => false,
+            Address::IPV6(v6) => v6.octets()[0..6] == IPC_MAPPED_PREFIX,
+        }
+    }
     pub fn address_type(&self) -> AddressType {
         match self {
             Address::IPV4(_) => AddressType::IPV4,
@@ -0,0 +1,25 @@
+use super::*;
+
+/// Port used in the synthetic socket address of IPC dial info
+pub const IPC_DIAL_INFO_PORT: u16 = 1;
+
+/// Dial info for a Veilid process on the same host, reached over a unix domain socket
+/// carrying raw TCP framing
+///
+/// IPC sockets have no IP address, so the socket address is a synthetic unique local IPv6
+/// address derived from the host and socket path. It is only used to identify flows and is
+/// never connected to directly. The host id keeps us from ever dialing a socket path that
+/// belongs to some other machine.
+#[derive(Clone, Default, Debug, PartialEq, PartialOrd, Ord, Eq, Hash, Serialize, Deserialize)]
+pub struct DialInfoIPC {
+    pub socket_address: SocketAddress,
+    pub host_id: u64,
+    pub path: String,
+}
+
+impl DialInfoIPC {
+    /// Is this the IPC socket of a process on the host we are running on
+    pub fn is_on_this_host(&self) -> bool {
+        intf::host_id() == Some(self.host_id)
+    }
+}
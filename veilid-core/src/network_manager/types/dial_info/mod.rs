@@ -1,3 +1,5 @@
+mod ipc;
+mod onion;
 mod tcp;
 mod udp;
 mod ws;
@@ -5,13 +7,15 @@ mod wss;
 
 use super::*;
 
+pub use ipc::*;
+pub use onion::*;
 pub use tcp::*;
 pub use udp::*;
 pub use ws::*;
 pub use wss::*;
 
 // Keep member order appropriate for sorting < preference
-// Must match ProtocolType order
+// Must match ProtocolType order, with Onion and IPC last as they are carried over TCP
 #[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Hash, Serialize, Deserialize)]
 #[serde(tag = "kind")]
 pub enum DialInfo {
@@ -19,6 +23,8 @@ pub enum DialInfo {
     TCP(DialInfoTCP),
     WS(DialInfoWS),
     WSS(DialInfoWSS),
+    Onion(DialInfoOnion),
+    IPC(DialInfoIPC),
 }
 impl Default for DialInfo {
     fn default() -> Self {
@@ -63,6 +69,8 @@ impl fmt::Display for DialInfo {
                     }
                 }
             }
+            DialInfo::Onion(di) => write!(f, "onion|{}:{}", di.host, di.socket_address.port()),
+            DialInfo::IPC(di) => write!(f, "ipc|{:016x}|{}", di.host_id, di.path),
         }
     }
 }
@@ -138,6 +146,16 @@ impl FromStr for DialInfo {
                     }
                 }
             }
+            "onion" => DialInfo::try_onion_address(rest),
+            "ipc" => {
+                let (host_id, path) = rest.split_once('|').ok_or_else(|| {
+                    VeilidAPIError::parse_error("DialInfo::from_str missing IPC host id", s)
+                })?;
+                let host_id = u64::from_str_radix(host_id, 16).map_err(|e| {
+                    VeilidAPIError::parse_error(format!("invalid IPC host id: {}", e), s)
+                })?;
+                DialInfo::try_ipc(host_id, path.to_string())
+            }
             _ => Err(VeilidAPIError::parse_error(
                 "DialInfo::from_str has invalid scheme",
                 s,
@@ -215,14 +233,53 @@ impl DialInfo {
             request: url[6..].to_string(),
         }))
     }
+    pub fn try_onion(host: String, port: u16) -> VeilidAPIResult<Self> {
+        let host = host.to_ascii_lowercase();
+        let public_key = decode_onion_v3_host(&host)?;
+        Ok(Self::Onion(DialInfoOnion {
+            socket_address: SocketAddress::new(Address::from_onion_public_key(&public_key), port),
+            host,
+        }))
+    }
+    /// Parse an onion service address in 'host.onion:port' form
+    pub fn try_onion_address(address: &str) -> VeilidAPIResult<Self> {
+        let (host, port) = address
+            .rsplit_once(':')
+            .ok_or_else(|| VeilidAPIError::parse_error("missing onion port", address))?;
+        let port = u16::from_str(port).map_err(|e| {
+            VeilidAPIError::parse_error(format!("invalid onion port: {}", e), address)
+        })?;
+        Self::try_onion(host.to_string(), port)
+    }
+    pub fn try_ipc(host_id: u64, path: String) -> VeilidAPIResult<Self> {
+        if path.is_empty() {
+            apibail_parse_error!("IPC path must not be empty", path);
+        }
+        Ok(Self::IPC(DialInfoIPC {
+            socket_address: SocketAddress::new(
+                Address::from_ipc_path(host_id, &path),
+                IPC_DIAL_INFO_PORT,
+            ),
+            host_id,
+            path,
+        }))
+    }
     pub fn protocol_type(&self) -> ProtocolType {
         match self {
             Self::UDP(_) => ProtocolType::UDP,
             Self::TCP(_) => ProtocolType::TCP,
             Self::WS(_) => ProtocolType::WS,
             Self::WSS(_) => ProtocolType::WSS,
+            Self::Onion(_) => ProtocolType::TCP,
+            Self::IPC(_) => ProtocolType::TCP,
         }
     }
+    pub fn is_onion(&self) -> bool {
+        matches!(self, Self::Onion(_))
+    }
+    pub fn is_ipc(&self) -> bool {
+        matches!(self, Self::IPC(_))
+    }
     pub fn address_type(&self) -> AddressType {
         self.socket_address().address_type()
     }
@@ -232,6 +289,8 @@ impl DialInfo {
             Self::TCP(di) => di.socket_address.address(),
             Self::WS(di) => di.socket_address.address(),
             Self::WSS(di) => di.socket_address.address(),
+            Self::Onion(di) => di.socket_address.address(),
+            Self::IPC(di) => di.socket_address.address(),
         }
     }
     #[allow(dead_code)]
@@ -241,6 +300,8 @@ impl DialInfo {
             Self::TCP(di) => di.socket_address.set_address(address),
             Self::WS(di) => di.socket_address.set_address(address),
             Self::WSS(di) => di.socket_address.set_address(address),
+            Self::Onion(di) => di.socket_address.set_address(address),
+            Self::IPC(di) => di.socket_address.set_address(address),
         }
     }
     pub fn socket_address(&self) -> SocketAddress {
@@ -249,6 +310,8 @@ impl DialInfo {
             Self::TCP(di) => di.socket_address,
             Self::WS(di) => di.socket_address,
             Self::WSS(di) => di.socket_address,
+            Self::Onion(di) => di.socket_address,
+            Self::IPC(di) => di.socket_address,
         }
     }
     pub fn ip_addr(&self) -> IpAddr {
@@ -257,6 +320,8 @@ impl DialInfo {
             Self::TCP(di) => di.socket_address.ip_addr(),
             Self::WS(di) => di.socket_address.ip_addr(),
             Self::WSS(di) => di.socket_address.ip_addr(),
+            Self::Onion(di) => di.socket_address.ip_addr(),
+            Self::IPC(di) => di.socket_address.ip_addr(),
         }
     }
     #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
@@ -266,6 +331,8 @@ impl DialInfo {
             Self::TCP(di) => di.socket_address.port(),
             Self::WS(di) => di.socket_address.port(),
             Self::WSS(di) => di.socket_address.port(),
+            Self::Onion(di) => di.socket_address.port(),
+            Self::IPC(di) => di.socket_address.port(),
         }
     }
     #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
@@ -275,6 +342,8 @@ impl DialInfo {
             Self::TCP(di) => di.socket_address.set_port(port),
             Self::WS(di) => di.socket_address.set_port(port),
             Self::WSS(di) => di.socket_address.set_port(port),
+            Self::Onion(di) => di.socket_address.set_port(port),
+            Self::IPC(di) => di.socket_address.set_port(port),
         }
     }
     #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
@@ -284,6 +353,8 @@ impl DialInfo {
             Self::TCP(di) => di.socket_address.socket_addr(),
             Self::WS(di) => di.socket_address.socket_addr(),
             Self::WSS(di) => di.socket_address.socket_addr(),
+            Self::Onion(di) => di.socket_address.socket_addr(),
+            Self::IPC(di) => di.socket_address.socket_addr(),
         }
     }
     pub fn peer_address(&self) -> PeerAddress {
@@ -292,6 +363,8 @@ impl DialInfo {
             Self::TCP(di) => PeerAddress::new(di.socket_address, ProtocolType::TCP),
             Self::WS(di) => PeerAddress::new(di.socket_address, ProtocolType::WS),
             Self::WSS(di) => PeerAddress::new(di.socket_address, ProtocolType::WSS),
+            Self::Onion(di) => PeerAddress::new(di.socket_address, ProtocolType::TCP),
+            Self::IPC(di) => PeerAddress::new(di.socket_address, ProtocolType::TCP),
         }
     }
     pub fn request(&self) -> Option<String> {
@@ -300,9 +373,18 @@ impl DialInfo {
             Self::TCP(_) => None,
             Self::WS(di) => Some(format!("ws://{}", di.request)),
             Self::WSS(di) => Some(format!("wss://{}", di.request)),
+            Self::Onion(_) => None,
+            Self::IPC(_) => None,
         }
     }
     pub fn is_valid(&self) -> bool {
+        if let Self::Onion(di) = self {
+            return decode_onion_v3_host(&di.host).is_ok() && di.socket_address.port() > 0;
+        }
+        if let Self::IPC(di) = self {
+            return !di.path.is_empty()
+                && di.socket_address.address() == Address::from_ipc_path(di.host_id, &di.path);
+        }
         let socket_address = self.socket_address();
         let address = socket_address.address();
         let port = socket_address.port();
@@ -339,6 +421,18 @@ impl DialInfo {
             "S" => {
                 format!("wss://{}:{}", hostname, &short[1..])
             }
+            "O" => {
+                let port = u16::from_str(&short[1..]).map_err(|e| {
+                    VeilidAPIError::parse_error(format!("invalid onion port: {}", e), short)
+                })?;
+                return Ok(vec![Self::try_onion(hostname.to_string(), port)?]);
+            }
+            "I" => {
+                let host_id = u64::from_str_radix(&short[1..], 16).map_err(|e| {
+                    VeilidAPIError::parse_error(format!("invalid IPC host id: {}", e), short)
+                })?;
+                return Ok(vec![Self::try_ipc(host_id, hostname.to_string())?]);
+            }
             _ => {
                 apibail_parse_error!("invalid short url type", short);
             }
@@ -455,6 +549,8 @@ impl DialInfo {
                     split_url.host.to_string(),
                 )
             }
+            DialInfo::Onion(di) => (format!("O{}", di.socket_address.port()), di.host.clone()),
+            DialInfo::IPC(di) => (format!("I{:016x}", di.host_id), di.path.clone()),
         }
     }
     #[allow(dead_code)]
@@ -486,6 +582,8 @@ impl DialInfo {
                 }
                 split_url.to_string()
             }
+            DialInfo::Onion(di) => format!("onion://{}:{}", di.host, di.socket_address.port()),
+            DialInfo::IPC(di) => format!("ipc://{:016x}|{}", di.host_id, di.path),
         }
     }
 
@@ -499,7 +597,8 @@ impl DialInfo {
             (DialInfo::TCP(a), DialInfo::TCP(b)) => a.cmp(b),
             (DialInfo::WS(a), DialInfo::WS(b)) => a.cmp(b),
             (DialInfo::WSS(a), DialInfo::WSS(b)) => a.cmp(b),
-            _ => unreachable!(),
+            // TCP, Onion and IPC share a protocol type, fall back to the variant order
+            (a, b) => a.cmp(b),
         }
     }
 }
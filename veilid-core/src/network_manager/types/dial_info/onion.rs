@@ -0,0 +1,40 @@
+use super::*;
+
+/// Length of a v3 onion service hostname label, without the '.onion' suffix
+pub const ONION_V3_LABEL_LENGTH: usize = 56;
+/// Length of the ed25519 public key encoded in a v3 onion service hostname
+pub const ONION_V3_PUBLIC_KEY_LENGTH: usize = 32;
+/// Version byte at the end of a decoded v3 onion service hostname
+const ONION_V3_VERSION: u8 = 3;
+
+/// Dial info for a Tor v3 onion service carrying raw TCP framing
+///
+/// Onion services have no IP address, so the socket address is a synthetic
+/// OnionCat-style IPv6 address derived from the service key. It is only used
+/// to identify flows and is never connected to directly.
+#[derive(Clone, Default, Debug, PartialEq, PartialOrd, Ord, Eq, Hash, Serialize, Deserialize)]
+pub struct DialInfoOnion {
+    pub socket_address: SocketAddress,
+    pub host: String,
+}
+
+/// Validate a v3 onion service hostname and return its public key
+pub fn decode_onion_v3_host(host: &str) -> VeilidAPIResult<[u8; ONION_V3_PUBLIC_KEY_LENGTH]> {
+    let Some(label) = host.strip_suffix(".onion") else {
+        apibail_parse_error!("onion host must end in '.onion'", host);
+    };
+    if label.len() != ONION_V3_LABEL_LENGTH {
+        apibail_parse_error!("only v3 onion hosts are supported", host);
+    }
+    let decoded = data_encoding::BASE32_NOPAD
+        .decode(label.to_ascii_uppercase().as_bytes())
+        .map_err(|e| VeilidAPIError::parse_error(format!("invalid onion host: {}", e), host))?;
+    if decoded.len() != ONION_V3_PUBLIC_KEY_LENGTH + 3
+        || decoded[decoded.len() - 1] != ONION_V3_VERSION
+    {
+        apibail_parse_error!("invalid onion host version", host);
+    }
+    let mut public_key = [0u8; ONION_V3_PUBLIC_KEY_LENGTH];
+    public_key.copy_from_slice(&decoded[0..ONION_V3_PUBLIC_KEY_LENGTH]);
+    Ok(public_key)
+}
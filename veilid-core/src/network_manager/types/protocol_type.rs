@@ -4,7 +4,8 @@ use super::*;
 // Keep member order appropriate for sorting < preference
 // Must match DialInfo order
 #[allow(clippy::derived_hash_with_manual_eq)]
-#[derive(Debug, PartialOrd, Ord, Hash, EnumSetType, Serialize, Deserialize)]
+#[derive(Debug, PartialOrd, Ord, Hash, EnumSetType, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
 #[enumset(repr = "u8")]
 pub enum ProtocolType {
     UDP = 0,
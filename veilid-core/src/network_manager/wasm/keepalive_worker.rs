@@ -0,0 +1,140 @@
+use super::*;
+use send_wrapper::*;
+use wasm_bindgen::JsCast;
+
+/// How often the keepalive worker is asked to tick us while the network is started
+pub const KEEPALIVE_WORKER_TICK_INTERVAL_MS: u32 = 1000;
+
+/// Messages exchanged with a keepalive worker over its MessagePort
+///
+/// When the network starts we send `{"type": "start", "interval_ms": N}` and when it
+/// shuts down we send `{"type": "stop"}`. While started, the worker replies with
+/// `{"type": "tick"}` every `interval_ms`. Timers in a SharedWorker or Service Worker
+/// are not throttled when the tab is in the background, so its messages keep our
+/// keepalive pings and watchdogs running when our own timers stall.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum KeepaliveMessage {
+    Start { interval_ms: u32 },
+    Stop,
+    Tick,
+}
+
+struct KeepalivePort {
+    port: web_sys::MessagePort,
+    _onmessage: Closure<dyn FnMut(web_sys::MessageEvent)>,
+}
+
+impl KeepalivePort {
+    fn post(&self, message: KeepaliveMessage) {
+        let res = serde_wasm_bindgen::to_value(&message)
+            .map_err(JsValue::from)
+            .and_then(|value| self.port.post_message(&value));
+        if let Err(e) = res {
+            log_net!(debug "failed to post {:?} to keepalive worker: {:?}", message, e);
+        }
+    }
+}
+
+impl Drop for KeepalivePort {
+    fn drop(&mut self) {
+        self.port.set_onmessage(None);
+    }
+}
+
+struct KeepaliveWorkerInner {
+    port: Option<SendWrapper<KeepalivePort>>,
+    /// The tick interval we asked for, if the network is started
+    interval_ms: Option<u32>,
+}
+
+/// Delegates our tick timer to a background worker connected through a MessagePort
+#[derive(Clone)]
+pub(crate) struct KeepaliveWorker {
+    inner: Arc<Mutex<KeepaliveWorkerInner>>,
+    tick_sender: flume::Sender<()>,
+    tick_receiver: flume::Receiver<()>,
+}
+
+impl KeepaliveWorker {
+    pub fn new() -> Self {
+        let (tick_sender, tick_receiver) = flume::bounded(1);
+        Self {
+            inner: Arc::new(Mutex::new(KeepaliveWorkerInner {
+                port: None,
+                interval_ms: None,
+            })),
+            tick_sender,
+            tick_receiver,
+        }
+    }
+
+    /// Set the MessagePort of the worker that drives our ticks, or None to go back to our own timers
+    pub fn set_port(&self, port: Option<web_sys::MessagePort>) {
+        let mut inner = self.inner.lock();
+
+        // Dropping the old port detaches its message handler
+        inner.port = None;
+        let Some(port) = port else {
+            return;
+        };
+
+        let tick_sender = self.tick_sender.clone();
+        let onmessage = Closure::<dyn FnMut(web_sys::MessageEvent)>::new(
+            move |event: web_sys::MessageEvent| {
+                match serde_wasm_bindgen::from_value::<KeepaliveMessage>(event.data()) {
+                    Ok(KeepaliveMessage::Tick) => {
+                        // If a tick is already pending, this one can be dropped
+                        let _ = tick_sender.try_send(());
+                    }
+                    Ok(message) => {
+                        log_net!(debug "unexpected message from keepalive worker: {:?}", message);
+                    }
+                    Err(e) => {
+                        log_net!(debug "invalid message from keepalive worker: {}", e);
+                    }
+                }
+            },
+        );
+        port.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+        port.start();
+
+        let keepalive_port = KeepalivePort {
+            port,
+            _onmessage: onmessage,
+        };
+        if let Some(interval_ms) = inner.interval_ms {
+            keepalive_port.post(KeepaliveMessage::Start { interval_ms });
+        }
+        inner.port = Some(SendWrapper::new(keepalive_port));
+    }
+
+    /// Ask the worker to start ticking us
+    pub fn start(&self, interval_ms: u32) {
+        let mut inner = self.inner.lock();
+        inner.interval_ms = Some(interval_ms);
+        if let Some(port) = &inner.port {
+            port.post(KeepaliveMessage::Start { interval_ms });
+        }
+    }
+
+    /// Ask the worker to stop ticking us
+    pub fn stop(&self) {
+        let mut inner = self.inner.lock();
+        inner.interval_ms = None;
+        if let Some(port) = &inner.port {
+            port.post(KeepaliveMessage::Stop);
+        }
+    }
+
+    /// Tick as if the worker had, waking anyone waiting for a tick
+    pub fn tick(&self) {
+        // If a tick is already pending, this one can be dropped
+        let _ = self.tick_sender.try_send(());
+    }
+
+    /// Wait until the worker ticks us or `timeout_ms` has passed, whichever comes first
+    pub async fn wait_for_tick(&self, timeout_ms: u32) {
+        let _ = timeout(timeout_ms, self.tick_receiver.recv_async()).await;
+    }
+}
@@ -1,9 +1,11 @@
+mod keepalive_worker;
 mod protocol;
 
 use super::*;
 
 use crate::routing_table::*;
 use connection_manager::*;
+pub(crate) use keepalive_worker::*;
 use protocol::ws::WebsocketProtocolHandler;
 pub use protocol::*;
 use std::io;
@@ -12,11 +14,11 @@ use std::io;
 
 cfg_if! {
     if #[cfg(all(feature = "unstable-blockstore", feature="unstable-tunnels"))] {
-        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 7;
+        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 12;
     } else if #[cfg(any(feature = "unstable-blockstore", feature="unstable-tunnels"))] {
-        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 6;
+        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 11;
     } else  {
-        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 5;
+        const PUBLIC_INTERNET_CAPABILITIES_LEN: usize = 10;
     }
 }
 pub const PUBLIC_INTERNET_CAPABILITIES: [Capability; PUBLIC_INTERNET_CAPABILITIES_LEN] = [
@@ -28,7 +30,12 @@ pub const PUBLIC_INTERNET_CAPABILITIES: [Capability; PUBLIC_INTERNET_CAPABILITIE
     //CAP_VALIDATE_DIAL_INFO,
     CAP_DHT,
     CAP_DHT_WATCH,
+    CAP_DHT_COMPRESSION,
+    CAP_DHT_BATCH,
+    CAP_DHT_DIGEST,
     CAP_APPMESSAGE,
+    CAP_ENVELOPE_COMPRESSION,
+    CAP_BUSY_ANSWER,
     #[cfg(feature = "unstable-blockstore")]
     CAP_BLOCKSTORE,
 ];
@@ -121,11 +128,14 @@ impl Network {
     /////////////////////////////////////////////////////////////////
 
     // Record DialInfo failures
-    pub async fn record_dial_info_failure<T, F: Future<Output = EyreResult<NetworkResult<T>>>>(
+    pub async fn record_dial_info_failure<
+        T,
+        F: Future<Output = NetworkErrorResult<NetworkResult<T>>>,
+    >(
         &self,
         dial_info: DialInfo,
         fut: F,
-    ) -> EyreResult<NetworkResult<T>> {
+    ) -> NetworkErrorResult<NetworkResult<T>> {
         let network_result = fut.await?;
         if matches!(network_result, NetworkResult::NoConnection(_)) {
             self.network_manager()
@@ -140,7 +150,7 @@ impl Network {
         &self,
         dial_info: DialInfo,
         data: Vec<u8>,
-    ) -> EyreResult<NetworkResult<()>> {
+    ) -> NetworkErrorResult<NetworkResult<()>> {
         self.record_dial_info_failure(dial_info.clone(), async move {
             let data_len = data.len();
             let timeout_ms = {
@@ -153,29 +163,32 @@ impl Network {
                 .address_filter()
                 .is_ip_addr_punished(dial_info.address().ip_addr())
             {
-                return Ok(NetworkResult::no_connection_other("punished"));
+                return NetworkError::filtered_by_policy("punished").into_network_result();
             }
 
             match dial_info.protocol_type() {
                 ProtocolType::UDP => {
-                    bail!("no support for UDP protocol")
+                    return Err(NetworkError::Io("no support for UDP protocol".to_owned()));
                 }
                 ProtocolType::TCP => {
-                    bail!("no support for TCP protocol")
+                    return Err(NetworkError::Io("no support for TCP protocol".to_owned()));
                 }
                 ProtocolType::WS | ProtocolType::WSS => {
                     let pnc = network_result_try!(WebsocketProtocolHandler::connect(
                         &dial_info, timeout_ms
                     )
                     .await
-                    .wrap_err("connect failure")?);
-                    network_result_try!(pnc.send(data).await.wrap_err("send failure")?);
+                    .classified()?);
+                    network_result_try!(pnc.send(data).await.classified()?);
                 }
             };
 
             // Network accounting
-            self.network_manager()
-                .stats_packet_sent(dial_info.ip_addr(), ByteCount::new(data_len as u64));
+            self.network_manager().stats_packet_sent(
+                dial_info.ip_addr(),
+                dial_info.protocol_type(),
+                ByteCount::new(data_len as u64),
+            );
 
             Ok(NetworkResult::Value(()))
         })
@@ -193,7 +206,7 @@ impl Network {
         dial_info: DialInfo,
         data: Vec<u8>,
         timeout_ms: u32,
-    ) -> EyreResult<NetworkResult<Vec<u8>>> {
+    ) -> NetworkErrorResult<NetworkResult<Vec<u8>>> {
         self.record_dial_info_failure(dial_info.clone(), async move {
             let data_len = data.len();
             let connect_timeout_ms = {
@@ -206,15 +219,15 @@ impl Network {
                 .address_filter()
                 .is_ip_addr_punished(dial_info.address().ip_addr())
             {
-                return Ok(NetworkResult::no_connection_other("punished"));
+                return NetworkError::filtered_by_policy("punished").into_network_result();
             }
 
             match dial_info.protocol_type() {
                 ProtocolType::UDP => {
-                    bail!("no support for UDP protocol")
+                    return Err(NetworkError::Io("no support for UDP protocol".to_owned()));
                 }
                 ProtocolType::TCP => {
-                    bail!("no support for TCP protocol")
+                    return Err(NetworkError::Io("no support for TCP protocol".to_owned()));
                 }
                 ProtocolType::WS | ProtocolType::WSS => {
                     let pnc = network_result_try!(match dial_info.protocol_type() {
@@ -223,22 +236,28 @@ impl Network {
                         ProtocolType::WS | ProtocolType::WSS => {
                             WebsocketProtocolHandler::connect(&dial_info, connect_timeout_ms)
                                 .await
-                                .wrap_err("connect failure")?
+                                .classified()?
                         }
                     });
 
-                    network_result_try!(pnc.send(data).await.wrap_err("send failure")?);
-                    self.network_manager()
-                        .stats_packet_sent(dial_info.ip_addr(), ByteCount::new(data_len as u64));
+                    network_result_try!(pnc.send(data).await.classified()?);
+                    self.network_manager().stats_packet_sent(
+                        dial_info.ip_addr(),
+                        dial_info.protocol_type(),
+                        ByteCount::new(data_len as u64),
+                    );
 
                     let out =
                         network_result_try!(network_result_try!(timeout(timeout_ms, pnc.recv())
                             .await
                             .into_network_result())
-                        .wrap_err("recv failure")?);
+                        .classified()?);
 
-                    self.network_manager()
-                        .stats_packet_rcvd(dial_info.ip_addr(), ByteCount::new(out.len() as u64));
+                    self.network_manager().stats_packet_rcvd(
+                        dial_info.ip_addr(),
+                        dial_info.protocol_type(),
+                        ByteCount::new(out.len() as u64),
+                    );
 
                     Ok(NetworkResult::Value(out))
                 }
@@ -252,14 +271,15 @@ impl Network {
         &self,
         flow: Flow,
         data: Vec<u8>,
-    ) -> EyreResult<SendDataToExistingFlowResult> {
+        priority: SendPriority,
+    ) -> NetworkErrorResult<SendDataToExistingFlowResult> {
         let data_len = data.len();
         match flow.protocol_type() {
             ProtocolType::UDP => {
-                bail!("no support for UDP protocol")
+                return Err(NetworkError::Io("no support for UDP protocol".to_owned()));
             }
             ProtocolType::TCP => {
-                bail!("no support for TCP protocol")
+                return Err(NetworkError::Io("no support for TCP protocol".to_owned()));
             }
             _ => {}
         }
@@ -269,11 +289,12 @@ impl Network {
         // Try to send to the exact existing connection if one exists
         if let Some(conn) = self.connection_manager().get_connection(flow) {
             // connection exists, send over it
-            match conn.send_async(data).await {
+            match conn.send_async(data, priority).await {
                 ConnectionHandleSendResult::Sent => {
                     // Network accounting
                     self.network_manager().stats_packet_sent(
                         flow.remote().socket_addr().ip(),
+                        flow.protocol_type(),
                         ByteCount::new(data_len as u64),
                     );
 
@@ -297,14 +318,15 @@ impl Network {
         &self,
         dial_info: DialInfo,
         data: Vec<u8>,
-    ) -> EyreResult<NetworkResult<UniqueFlow>> {
+        priority: SendPriority,
+    ) -> NetworkErrorResult<NetworkResult<UniqueFlow>> {
         self.record_dial_info_failure(dial_info.clone(), async move {
             let data_len = data.len();
             if dial_info.protocol_type() == ProtocolType::UDP {
-                bail!("no support for UDP protocol");
+                return Err(NetworkError::Io("no support for UDP protocol".to_owned()));
             }
             if dial_info.protocol_type() == ProtocolType::TCP {
-                bail!("no support for TCP protocol");
+                return Err(NetworkError::Io("no support for TCP protocol".to_owned()));
             }
 
             // Handle connection-oriented protocols
@@ -314,7 +336,7 @@ impl Network {
                     .await?
             );
 
-            if let ConnectionHandleSendResult::NotSent(_) = conn.send_async(data).await {
+            if let ConnectionHandleSendResult::NotSent(_) = conn.send_async(data, priority).await {
                 return Ok(NetworkResult::NoConnection(io::Error::new(
                     io::ErrorKind::ConnectionReset,
                     "failed to send",
@@ -323,8 +345,11 @@ impl Network {
             let unique_flow = conn.unique_flow();
 
             // Network accounting
-            self.network_manager()
-                .stats_packet_sent(dial_info.ip_addr(), ByteCount::new(data_len as u64));
+            self.network_manager().stats_packet_sent(
+                dial_info.ip_addr(),
+                dial_info.protocol_type(),
+                ByteCount::new(data_len as u64),
+            );
 
             Ok(NetworkResult::value(unique_flow))
         })
@@ -361,7 +386,7 @@ impl Network {
                 PUBLIC_INTERNET_CAPABILITIES
                     .iter()
                     .copied()
-                    .filter(|cap| !c.capabilities.disable.contains(cap))
+                    .filter(|cap| !is_capability_disabled(&c, cap))
                     .collect::<Vec<Capability>>()
             };
 
@@ -389,13 +414,21 @@ impl Network {
             protocol_config.outbound,
             protocol_config.inbound,
             protocol_config.family_global,
-            protocol_config.public_internet_capabilities.clone(),
+            self.network_manager().without_withheld_capabilities(
+                RoutingDomain::PublicInternet,
+                protocol_config.public_internet_capabilities.clone(),
+            ),
         );
         editor_public_internet.set_network_class(Some(NetworkClass::WebApp));
 
         // commit routing table edits
         editor_public_internet.commit(true).await;
 
+        // Let a background worker keep us ticking if the tab gets throttled
+        self.network_manager()
+            .keepalive_worker()
+            .start(KEEPALIVE_WORKER_TICK_INTERVAL_MS);
+
         self.inner.lock().network_started = true;
         log_net!(debug "network started");
         Ok(())
@@ -413,9 +446,17 @@ impl Network {
         self.inner.lock().network_needs_restart = true;
     }
 
+    pub fn reload_tls_certificates(&self) -> EyreResult<bool> {
+        // No inbound listeners in the browser
+        Ok(false)
+    }
+
     pub async fn shutdown(&self) {
         log_net!(debug "stopping network");
 
+        // Stop background worker ticks
+        self.network_manager().keepalive_worker().stop();
+
         // Reset state
         let routing_table = self.routing_table();
 
@@ -18,11 +18,12 @@ impl ProtocolNetworkConnection {
         dial_info: &DialInfo,
         timeout_ms: u32,
         address_filter: AddressFilter,
-    ) -> io::Result<NetworkResult<ProtocolNetworkConnection>> {
+        _tor_socks_address: Option<SocketAddr>,
+    ) -> NetworkErrorResult<ProtocolNetworkConnection> {
         if address_filter.is_ip_addr_punished(dial_info.address().ip_addr()) {
-            return Ok(NetworkResult::no_connection_other("punished"));
+            return Err(NetworkError::filtered_by_policy("punished"));
         }
-        match dial_info.protocol_type() {
+        let res = match dial_info.protocol_type() {
             ProtocolType::UDP => {
                 panic!("UDP dial info is not supported on WASM targets");
             }
@@ -32,7 +33,8 @@ impl ProtocolNetworkConnection {
             ProtocolType::WS | ProtocolType::WSS => {
                 ws::WebsocketProtocolHandler::connect(dial_info, timeout_ms).await
             }
-        }
+        };
+        NetworkError::from_io_network_result(res)
     }
 
     pub fn flow(&self) -> Flow {
@@ -5,12 +5,14 @@ use core::sync::atomic::Ordering;
 /// Stores map of public keys to entries, which may be in multiple routing tables per crypto kind
 /// Keeps entries at a particular 'dht distance' from this cryptokind's node id
 /// Helps to keep managed lists at particular distances so we can evict nodes by priority
-/// where the priority comes from liveness and age of the entry (older is better)
+/// where the priority comes from liveness, uptime and latency of the entry (longer lived and faster is better)
 pub struct Bucket {
     /// Map of keys to entries for this bucket
     entries: BTreeMap<PublicKey, Arc<BucketEntry>>,
     /// The crypto kind in use for the public keys in this bucket
     kind: CryptoKind,
+    /// The last time we looked up a key in this bucket's range of the keyspace
+    last_lookup_ts: Timestamp,
 }
 pub(super) type EntriesIter<'a> =
     alloc::collections::btree_map::Iter<'a, PublicKey, Arc<BucketEntry>>;
@@ -26,19 +28,12 @@ struct SerializedBucketData {
     entries: Vec<SerializedBucketEntryData>,
 }
 
-fn state_ordering(state: BucketEntryState) -> usize {
-    match state {
-        BucketEntryState::Dead => 0,
-        BucketEntryState::Unreliable => 1,
-        BucketEntryState::Reliable => 2,
-    }
-}
-
 impl Bucket {
     pub fn new(kind: CryptoKind) -> Self {
         Self {
             entries: BTreeMap::new(),
             kind,
+            last_lookup_ts: get_aligned_timestamp(),
         }
     }
 
@@ -115,6 +110,18 @@ impl Bucket {
         self.entries.iter()
     }
 
+    pub(super) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(super) fn last_lookup_ts(&self) -> Timestamp {
+        self.last_lookup_ts
+    }
+
+    pub(super) fn touch_lookup(&mut self, cur_ts: Timestamp) {
+        self.last_lookup_ts = cur_ts;
+    }
+
     pub(super) fn kick(&mut self, bucket_depth: usize) -> Option<BTreeSet<PublicKey>> {
         // Get number of entries to attempt to purge from bucket
         let bucket_len = self.entries.len();
@@ -137,21 +144,7 @@ impl Bucket {
                 return core::cmp::Ordering::Equal;
             }
             a.1.with_inner(|ea| {
-                b.1.with_inner(|eb| {
-                    let astate = state_ordering(ea.state(cur_ts));
-                    let bstate = state_ordering(eb.state(cur_ts));
-                    // first kick dead nodes, then unreliable nodes
-                    if astate < bstate {
-                        return core::cmp::Ordering::Less;
-                    }
-                    if astate > bstate {
-                        return core::cmp::Ordering::Greater;
-                    }
-                    // then kick by time added, most recent nodes are kicked first
-                    let ata = ea.peer_stats().time_added;
-                    let bta = eb.peer_stats().time_added;
-                    bta.cmp(&ata)
-                })
+                b.1.with_inner(|eb| BucketEntryInner::cmp_kick_order(cur_ts, ea, eb))
             })
         });
 
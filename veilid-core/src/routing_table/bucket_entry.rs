@@ -1,5 +1,5 @@
 use super::*;
-use core::sync::atomic::{AtomicU32, Ordering};
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 
 
 /// Reliable pings are done with increased spacing between pings
@@ -26,9 +26,22 @@ const UNRELIABLE_PING_INTERVAL_SECS: u32 = 5;
 /// How many times do we try to ping a never-reached node before we call it dead
 const NEVER_REACHED_PING_COUNT: u32 = 3;
 
+/// Flap detection counts how often a node swings between Reliable and Dead
+///
+/// - Window is the number of seconds over which flaps are counted
+const FLAP_DETECTION_WINDOW_SECS: u32 = 30 * 60;
+/// - Threshold is the number of flaps within the window that are tolerated before dampening
+const FLAP_DAMPENING_THRESHOLD: u32 = 2;
+/// - Max exponent caps the exponential dampening of the reliability promotion span
+const FLAP_DAMPENING_MAX_EXPONENT: u32 = 5;
+
+/// When kicking overflowing buckets, uptime is compared at this granularity so that
+/// entries that have been up for a similar amount of time are kicked by latency instead
+const KICK_UPTIME_GRANULARITY_SECS: u32 = 60;
+
 // Do not change order here, it will mess up other sorts
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub(crate) enum BucketEntryState {
     Dead,
     Unreliable,
@@ -38,6 +51,18 @@ pub(crate) enum BucketEntryState {
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub(crate) struct LastFlowKey(ProtocolType, AddressType);
 
+/// How many connections with a node were closed, by why they closed
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConnectionCloseStats {
+    pub timeouts: u32,
+    pub remote_closes: u32,
+    pub protocol_errors: u32,
+    pub filtered: u32,
+    pub local_closes: u32,
+    /// The last time a connection was closed because of something the node did
+    pub last_misbehavior_ts: Option<Timestamp>,
+}
+
 /// Bucket entry information specific to the LocalNetwork RoutingDomain
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct BucketEntryPublicInternet {
@@ -87,12 +112,36 @@ pub(crate) struct BucketEntryInner {
     /// The accounting for the latency statistics
     #[serde(skip)]
     latency_stats_accounting: LatencyStatsAccounting,
+    /// The accounting for the clock skew estimate
+    #[serde(skip)]
+    clock_skew_accounting: ClockSkewAccounting,
     /// The accounting for the transfer statistics
     #[serde(skip)]
     transfer_stats_accounting: TransferStatsAccounting,
+    /// The accounting for the bandwidth estimate
+    #[serde(skip)]
+    bandwidth_estimate_accounting: BandwidthEstimateAccounting,
+    /// The accounting for the traffic with this node over the last hour, for ranking top talkers
+    #[serde(skip)]
+    talker_stats_accounting: TalkerStatsAccounting,
     /// If the entry is being punished and should be considered dead
     #[serde(skip)]
     is_punished: bool,
+    /// The last Reliable or Dead state observed for this entry, used for flap detection
+    #[serde(skip)]
+    last_extreme_state: Option<BucketEntryState>,
+    /// Timestamps of recent swings between Reliable and Dead
+    #[serde(skip)]
+    flap_timestamps: VecDeque<Timestamp>,
+    /// The last time this node sent us a question or answer directly
+    #[serde(skip)]
+    last_direct_contact_ts: Option<Timestamp>,
+    /// Counts of why connections with this node were closed
+    #[serde(skip)]
+    connection_close_stats: ConnectionCloseStats,
+    /// The routing domains in which this node has answered a status question of ours
+    #[serde(skip)]
+    verified_routing_domains: RoutingDomainSet,
     /// Tracking identifier for NodeRef debugging
     #[cfg(feature = "tracking")]
     #[serde(skip)]
@@ -118,6 +167,17 @@ impl BucketEntryInner {
         self.node_ref_tracks.remove(&track_id);
     }
 
+    #[cfg(feature = "tracking")]
+    pub fn debug_node_ref_tracks(&self) -> String {
+        let mut out = String::new();
+        for (id, bt) in &self.node_ref_tracks {
+            let mut bt = bt.clone();
+            bt.resolve();
+            out += &format!("Id: {}\n----------------\n{:#?}\n", id, bt);
+        }
+        out
+    }
+
     /// Get all node ids
     pub fn node_ids(&self) -> TypedKeyGroup {
         let mut node_ids = self.validated_node_ids.clone();
@@ -157,6 +217,31 @@ impl BucketEntryInner {
         self.validated_node_ids.best().unwrap()
     }
 
+    /// Get the best node id we can address an envelope to, along with the key encapsulation
+    /// public key its crypto kind requires, if any. Hybrid crypto kinds are skipped until
+    /// we have signed node info that publishes their key encapsulation public key.
+    pub fn best_envelope_node_id(
+        &self,
+        crypto: &Crypto,
+    ) -> Option<(TypedKey, Option<KemPublicKey>)> {
+        for node_id in self.validated_node_ids.iter() {
+            let Some(vcrypto) = crypto.get(node_id.kind) else {
+                continue;
+            };
+            if vcrypto.kem_ciphertext_length() == 0 {
+                return Some((*node_id, None));
+            }
+            let opt_kem_public_key = RoutingDomain::all().iter().find_map(|rd| {
+                self.node_info(*rd)
+                    .and_then(|ni| ni.kem_public_key(node_id.kind))
+            });
+            if let Some(kem_public_key) = opt_kem_public_key {
+                return Some((*node_id, Some(kem_public_key)));
+            }
+        }
+        None
+    }
+
     /// Get crypto kinds
     pub fn crypto_kinds(&self) -> Vec<CryptoKind> {
         self.validated_node_ids.kinds()
@@ -174,6 +259,17 @@ impl BucketEntryInner {
         ni.has_capabilities(capabilities)
     }
 
+    /// Check if this node has answered a status question of ours in a routing domain
+    /// Answers are signed by the node's id, so this proves the node holds the secret key for it,
+    /// and that the node is reachable in that routing domain
+    pub fn is_verified(&self, routing_domain: RoutingDomain) -> bool {
+        self.verified_routing_domains.contains(routing_domain)
+    }
+
+    pub fn set_verified(&mut self, routing_domain: RoutingDomain) {
+        self.verified_routing_domains.insert(routing_domain);
+    }
+
     // Less is faster
     #[allow(dead_code)]
     pub fn cmp_fastest(e1: &Self, e2: &Self) -> std::cmp::Ordering {
@@ -213,6 +309,32 @@ impl BucketEntryInner {
         }
     }
 
+    // Less is more reliable then more bandwidth, then faster
+    pub fn cmp_highest_bandwidth_reliable(
+        cur_ts: Timestamp,
+        e1: &Self,
+        e2: &Self,
+    ) -> std::cmp::Ordering {
+        // Reverse compare so most reliable is at front
+        let ret = e2.state(cur_ts).cmp(&e1.state(cur_ts));
+        if ret != std::cmp::Ordering::Equal {
+            return ret;
+        }
+
+        // Higher bandwidth to the front, no estimate yet is at the end
+        let ret = match (e1.peer_stats.bandwidth, e2.peer_stats.bandwidth) {
+            (Some(e1_bandwidth), Some(e2_bandwidth)) => e2_bandwidth.cmp(&e1_bandwidth),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        };
+        if ret != std::cmp::Ordering::Equal {
+            return ret;
+        }
+
+        Self::cmp_fastest(e1, e2)
+    }
+
     // Less is more reliable then older
     pub fn cmp_oldest_reliable(cur_ts: Timestamp, e1: &Self, e2: &Self) -> std::cmp::Ordering {
         // Reverse compare so most reliable is at front
@@ -235,6 +357,45 @@ impl BucketEntryInner {
         }
     }
 
+    // Less is kicked first
+    // Prefers keeping long-lived low-latency nodes, as in Kademlia: dead and unreliable nodes go first,
+    // then nodes with the least uptime, and among nodes with similar uptime the slowest go first
+    pub fn cmp_kick_order(cur_ts: Timestamp, e1: &Self, e2: &Self) -> std::cmp::Ordering {
+        // Least reliable to the front
+        let ret = e1.state(cur_ts).cmp(&e2.state(cur_ts));
+        if ret != std::cmp::Ordering::Equal {
+            return ret;
+        }
+
+        // Least uptime to the front
+        let granularity = KICK_UPTIME_GRANULARITY_SECS as u64 * 1000000u64;
+        let e1_uptime = e1.uptime(cur_ts).as_u64() / granularity;
+        let e2_uptime = e2.uptime(cur_ts).as_u64() / granularity;
+        let ret = e1_uptime.cmp(&e2_uptime);
+        if ret != std::cmp::Ordering::Equal {
+            return ret;
+        }
+
+        // Higher latency to the front, no latency measured yet is kicked first
+        let ret = if let Some(e1_latency) = &e1.peer_stats.latency {
+            if let Some(e2_latency) = &e2.peer_stats.latency {
+                e2_latency.average.cmp(&e1_latency.average)
+            } else {
+                std::cmp::Ordering::Greater
+            }
+        } else if e2.peer_stats.latency.is_some() {
+            std::cmp::Ordering::Less
+        } else {
+            std::cmp::Ordering::Equal
+        };
+        if ret != std::cmp::Ordering::Equal {
+            return ret;
+        }
+
+        // Most recently added to the front
+        e2.peer_stats.time_added.cmp(&e1.peer_stats.time_added)
+    }
+
     #[allow(dead_code)]
     pub fn sort_fastest_reliable_fn(cur_ts: Timestamp) -> impl FnMut(&Self, &Self) -> std::cmp::Ordering {
         move |e1, e2| Self::cmp_fastest_reliable(cur_ts, e1, e2)
@@ -357,6 +518,35 @@ impl BucketEntryInner {
         opt_current_sni.as_ref().map(|s| s.as_ref())
     }
 
+    /// How long since this entry's node info in a routing domain was known to be current
+    ///
+    /// Node info is current as of when it was signed, or when the node last talked to us directly,
+    /// because nodes resend their node info whenever they see we have an out of date copy.
+    pub fn node_info_age(
+        &self,
+        routing_domain: RoutingDomain,
+        cur_ts: Timestamp,
+    ) -> Option<TimestampDuration> {
+        let sni = self.signed_node_info(routing_domain)?;
+        let current_ts = match self.last_direct_contact_ts {
+            Some(last_direct_contact_ts) => sni.timestamp().max(last_direct_contact_ts),
+            None => sni.timestamp(),
+        };
+        Some(cur_ts.saturating_sub(current_ts))
+    }
+
+    /// Check if this entry's node info in a routing domain is too old to rely on without refreshing it
+    pub fn has_stale_node_info(
+        &self,
+        routing_domain: RoutingDomain,
+        cur_ts: Timestamp,
+        max_age: TimestampDuration,
+    ) -> bool {
+        self.node_info_age(routing_domain, cur_ts)
+            .map(|age| age > max_age)
+            .unwrap_or(false)
+    }
+
     pub fn make_peer_info(&self, routing_domain: RoutingDomain) -> Option<PeerInfo> {
         let opt_current_sni = match routing_domain {
             RoutingDomain::LocalNetwork => &self.local_network.signed_node_info,
@@ -467,6 +657,11 @@ impl BucketEntryInner {
         }
     }
 
+    // Gets the most recently used flow to this node and when it was used
+    pub(super) fn latest_flow(&self) -> Option<(Flow, Timestamp)> {
+        self.last_flows.values().max_by_key(|(_, ts)| *ts).copied()
+    }
+
     // Gets all the 'last flows' that match a particular filter, and their accompanying timestamps of last use
     pub(super) fn last_flows(
         &self,
@@ -628,11 +823,26 @@ impl BucketEntryInner {
     ///// stats methods
     // called every ROLLING_TRANSFERS_INTERVAL_SECS seconds
     pub(super) fn roll_transfers(&mut self, last_ts: Timestamp, cur_ts: Timestamp) {
+        self.peer_stats.bandwidth = self.bandwidth_estimate_accounting.record_transfer(
+            self.transfer_stats_accounting.current_transfer(),
+            cur_ts.saturating_sub(last_ts),
+        );
         self.transfer_stats_accounting.roll_transfers(
             last_ts,
             cur_ts,
             &mut self.peer_stats.transfer,
         );
+        self.peer_stats.transfer_windows = self.transfer_stats_accounting.transfer_windows();
+        self.talker_stats_accounting.roll_talkers(last_ts, cur_ts);
+    }
+
+    /// The bytes and RPC messages exchanged with this node over the last hour, or since the last reset
+    pub fn talker_count(&self) -> TalkerCount {
+        self.talker_stats_accounting.talker_count()
+    }
+
+    pub(super) fn reset_talker_stats(&mut self) {
+        self.talker_stats_accounting.reset();
     }
 
     // Called for every round trip packet we receive
@@ -640,6 +850,11 @@ impl BucketEntryInner {
         self.peer_stats.latency = Some(self.latency_stats_accounting.record_latency(latency));
     }
 
+    // Called for every status answer that lets us estimate the peer's clock offset
+    pub fn record_clock_skew(&mut self, clock_skew: i64) {
+        self.peer_stats.clock_skew = Some(self.clock_skew_accounting.record_clock_skew(clock_skew));
+    }
+
     ///// state machine handling
     pub(super) fn check_reliable(&self, cur_ts: Timestamp) -> bool {
         // If we have had any failures to send, this is not reliable
@@ -647,6 +862,14 @@ impl BucketEntryInner {
             return false;
         }
 
+        // If a connection was closed recently because of something the node did, this is not reliable
+        if let Some(ts) = self.connection_close_stats.last_misbehavior_ts {
+            let span = TimestampDuration::new(UNRELIABLE_PING_SPAN_SECS as u64 * 1000000u64);
+            if cur_ts.saturating_sub(ts) < span {
+                return false;
+            }
+        }
+
         // If we have had any lost answers recently, this is not reliable
         if self.peer_stats.rpc_stats.recent_lost_answers > 0 {
             return false;
@@ -655,10 +878,64 @@ impl BucketEntryInner {
         match self.peer_stats.rpc_stats.first_consecutive_seen_ts {
             // If we have not seen seen a node consecutively, it can't be reliable
             None => false,
-            // If we have seen the node consistently for longer than UNRELIABLE_PING_SPAN_SECS then it is reliable
+            // If we have seen the node consistently for longer than the (possibly dampened) promotion span then it is reliable
             Some(ts) => {
-                cur_ts.saturating_sub(ts) >= TimestampDuration::new(UNRELIABLE_PING_SPAN_SECS as u64 * 1000000u64)
+                cur_ts.saturating_sub(ts) >= self.reliable_promotion_span(cur_ts)
+            }
+        }
+    }
+
+    /// How long a node must be seen consecutively before it is considered reliable
+    /// Nodes that flap between Reliable and Dead have this span increased exponentially
+    fn reliable_promotion_span(&self, cur_ts: Timestamp) -> TimestampDuration {
+        let exponent = self
+            .flap_score(cur_ts)
+            .saturating_sub(FLAP_DAMPENING_THRESHOLD)
+            .min(FLAP_DAMPENING_MAX_EXPONENT);
+        TimestampDuration::new((UNRELIABLE_PING_SPAN_SECS as u64 * 1000000u64) << exponent)
+    }
+
+    /// How long this node has been seen consecutively without failing to respond
+    pub fn uptime(&self, cur_ts: Timestamp) -> TimestampDuration {
+        match self.peer_stats.rpc_stats.first_consecutive_seen_ts {
+            Some(ts) => cur_ts.saturating_sub(ts),
+            None => TimestampDuration::new(0u64),
+        }
+    }
+
+    /// Number of times this node has swung between Reliable and Dead within the flap detection window
+    pub fn flap_score(&self, cur_ts: Timestamp) -> u32 {
+        let window = TimestampDuration::new(FLAP_DETECTION_WINDOW_SECS as u64 * 1000000u64);
+        self.flap_timestamps
+            .iter()
+            .filter(|ts| cur_ts.saturating_sub(**ts) < window)
+            .count() as u32
+    }
+
+    /// Record a flap if our state has swung to the opposite extreme since it was last observed
+    fn observe_state(&mut self, cur_ts: Timestamp) {
+        // Punishment forces the node dead, which is our doing and not the node flapping
+        if self.is_punished {
+            return;
+        }
+        let state = self.state(cur_ts);
+        if state == BucketEntryState::Unreliable {
+            return;
+        }
+        if let Some(last_extreme_state) = &self.last_extreme_state {
+            if *last_extreme_state != state {
+                self.flap_timestamps.push_back(cur_ts);
+            }
+        }
+        self.last_extreme_state = Some(state);
+
+        // Expire flaps that have fallen out of the window
+        let window = TimestampDuration::new(FLAP_DETECTION_WINDOW_SECS as u64 * 1000000u64);
+        while let Some(ts) = self.flap_timestamps.front() {
+            if cur_ts.saturating_sub(*ts) < window {
+                break;
             }
+            self.flap_timestamps.pop_front();
         }
     }
     pub(super) fn check_dead(&self, cur_ts: Timestamp) -> bool {
@@ -781,10 +1058,11 @@ impl BucketEntryInner {
         };
 
         format!(
-            "state: {:?}, first_consecutive_seen_ts: {}, last_seen_ts: {}",
+            "state: {:?}, first_consecutive_seen_ts: {}, last_seen_ts: {}, flap_score: {}",
             self.state(cur_ts),
             first_consecutive_seen_ts,
-            last_seen_ts_str
+            last_seen_ts_str,
+            self.flap_score(cur_ts)
         )
     }
 
@@ -793,6 +1071,7 @@ impl BucketEntryInner {
 
     pub(super) fn question_sent(&mut self, ts: Timestamp, bytes: ByteCount, expects_answer: bool) {
         self.transfer_stats_accounting.add_up(bytes);
+        self.talker_stats_accounting.add_rpc_sent(bytes);
         self.peer_stats.rpc_stats.messages_sent += 1;
         self.peer_stats.rpc_stats.failed_to_send = 0;
         if expects_answer {
@@ -802,26 +1081,34 @@ impl BucketEntryInner {
     }
     pub(super) fn question_rcvd(&mut self, ts: Timestamp, bytes: ByteCount) {
         self.transfer_stats_accounting.add_down(bytes);
+        self.talker_stats_accounting.add_rpc_rcvd(bytes);
         self.peer_stats.rpc_stats.messages_rcvd += 1;
         self.touch_last_seen(ts);
+        self.last_direct_contact_ts = Some(ts);
+        self.observe_state(ts);
     }
     pub(super) fn answer_sent(&mut self, bytes: ByteCount) {
         self.transfer_stats_accounting.add_up(bytes);
+        self.talker_stats_accounting.add_rpc_sent(bytes);
         self.peer_stats.rpc_stats.messages_sent += 1;
         self.peer_stats.rpc_stats.failed_to_send = 0;
     }
     pub(super) fn answer_rcvd(&mut self, send_ts: Timestamp, recv_ts: Timestamp, bytes: ByteCount) {
         self.transfer_stats_accounting.add_down(bytes);
+        self.talker_stats_accounting.add_rpc_rcvd(bytes);
         self.peer_stats.rpc_stats.messages_rcvd += 1;
         self.peer_stats.rpc_stats.questions_in_flight -= 1;
         self.record_latency(recv_ts.saturating_sub(send_ts));
         self.touch_last_seen(recv_ts);
+        self.last_direct_contact_ts = Some(recv_ts);
         self.peer_stats.rpc_stats.recent_lost_answers = 0;
+        self.observe_state(recv_ts);
     }
     pub(super) fn question_lost(&mut self) {
         self.peer_stats.rpc_stats.first_consecutive_seen_ts = None;
         self.peer_stats.rpc_stats.questions_in_flight -= 1;
         self.peer_stats.rpc_stats.recent_lost_answers += 1;
+        self.observe_state(get_aligned_timestamp());
     }
     pub(super) fn failed_to_send(&mut self, ts: Timestamp, expects_answer: bool) {
         if expects_answer {
@@ -829,12 +1116,38 @@ impl BucketEntryInner {
         }
         self.peer_stats.rpc_stats.failed_to_send += 1;
         self.peer_stats.rpc_stats.first_consecutive_seen_ts = None;
+        self.observe_state(ts);
+    }
+
+    ////////////////////////////////////////////////////////////////
+    /// Called when network manager things happen
+
+    /// Account for a closed connection with this node
+    /// Protocol errors always count against the node. Other closes only count against it
+    /// if the connection was protected, since idle and dropped connections are normal otherwise
+    pub(super) fn connection_closed(&mut self, ts: Timestamp, close_reason: ConnectionCloseReason, protected: bool) {
+        let stats = &mut self.connection_close_stats;
+        match close_reason {
+            ConnectionCloseReason::Timeout => stats.timeouts += 1,
+            ConnectionCloseReason::RemoteClose => stats.remote_closes += 1,
+            ConnectionCloseReason::ProtocolError => stats.protocol_errors += 1,
+            ConnectionCloseReason::Filtered => stats.filtered += 1,
+            ConnectionCloseReason::Local => stats.local_closes += 1,
+        }
+        if close_reason.is_misbehavior() {
+            stats.last_misbehavior_ts = Some(ts);
+            self.failed_to_send(ts, false);
+        } else if protected && close_reason != ConnectionCloseReason::Local {
+            self.failed_to_send(ts, false);
+        }
     }
 }
 
 #[derive(Debug)]
 pub(crate) struct BucketEntry {
     pub(super) ref_count: AtomicU32,
+    /// When the entry last went from having no NodeRefs to having some, zero if it has none
+    pinned_since: AtomicU64,
     inner: RwLock<BucketEntryInner>,
 }
 
@@ -866,10 +1179,21 @@ impl BucketEntry {
                 rpc_stats: RPCStats::default(),
                 latency: None,
                 transfer: TransferStatsDownUp::default(),
+                transfer_windows: TransferStatsWindows::default(),
+                clock_skew: None,
+                bandwidth: None,
             },
             latency_stats_accounting: LatencyStatsAccounting::new(),
-            transfer_stats_accounting: TransferStatsAccounting::new(),
+            clock_skew_accounting: ClockSkewAccounting::new(),
+            transfer_stats_accounting: TransferStatsAccounting::new_with_windows(),
+            bandwidth_estimate_accounting: BandwidthEstimateAccounting::new(),
+            talker_stats_accounting: TalkerStatsAccounting::new(),
             is_punished: false,
+            last_extreme_state: None,
+            flap_timestamps: VecDeque::new(),
+            last_direct_contact_ts: None,
+            connection_close_stats: ConnectionCloseStats::default(),
+            verified_routing_domains: RoutingDomainSet::empty(),
             #[cfg(feature = "tracking")]
             next_track_id: 0,
             #[cfg(feature = "tracking")]
@@ -882,10 +1206,44 @@ impl BucketEntry {
     pub(super) fn new_with_inner(inner: BucketEntryInner) -> Self {
         Self {
             ref_count: AtomicU32::new(0),
+            pinned_since: AtomicU64::new(0),
             inner: RwLock::new(inner),
         }
     }
 
+    /// Count a new NodeRef to this entry
+    pub(super) fn add_ref(&self) {
+        if self.ref_count.fetch_add(1u32, Ordering::AcqRel) == 0 {
+            self.pinned_since
+                .store(get_aligned_timestamp().as_u64(), Ordering::Release);
+        }
+    }
+
+    /// Count a dropped NodeRef to this entry, returning the number of NodeRefs that remain
+    pub(super) fn remove_ref(&self) -> u32 {
+        let new_ref_count = self.ref_count.fetch_sub(1u32, Ordering::AcqRel) - 1;
+        if new_ref_count == 0 {
+            self.pinned_since.store(0, Ordering::Release);
+        }
+        new_ref_count
+    }
+
+    /// The number of NodeRefs to this entry and roughly how long it has had any, if it has any at all
+    /// This is only for diagnostics, the duration is not exact when NodeRefs are racing to be created and dropped
+    pub(super) fn pinned(&self, cur_ts: Timestamp) -> Option<(u32, TimestampDuration)> {
+        let ref_count = self.ref_count.load(Ordering::Acquire);
+        if ref_count == 0 {
+            return None;
+        }
+        let pinned_since = self.pinned_since.load(Ordering::Acquire);
+        let pinned_duration = if pinned_since == 0 {
+            TimestampDuration::new(0u64)
+        } else {
+            cur_ts.saturating_sub(Timestamp::new(pinned_since))
+        };
+        Some((ref_count, pinned_duration))
+    }
+
     // Note, that this requires -also- holding the RoutingTable read lock, as an
     // immutable reference to RoutingTableInner must be passed in to get this
     // This ensures that an operation on the routing table can not change entries
@@ -933,10 +1291,7 @@ impl Drop for BucketEntry {
             #[cfg(feature = "tracking")]
             {
                 info!("NodeRef Tracking");
-                for (id, bt) in &mut self.node_ref_tracks {
-                    bt.resolve();
-                    info!("Id: {}\n----------------\n{:#?}", id, bt);
-                }
+                info!("{}", self.inner.read().debug_node_ref_tracks());
             }
 
             panic!(
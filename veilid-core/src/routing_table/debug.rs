@@ -186,8 +186,64 @@ impl RoutingTable {
     }
 
     pub(crate) fn debug_info_entry(&self, node_ref: NodeRef) -> String {
+        let cur_ts = get_aligned_timestamp();
+
         let mut out = String::new();
         out += &node_ref.operate(|_rt, e| format!("{:#?}\n", e));
+        out += &node_ref.operate(|_rt, e| format!("Flap Score: {}\n", e.flap_score(cur_ts)));
+        out
+    }
+
+    /// Get the entries that have been held by NodeRefs for at least `min_pinned`
+    /// along with their NodeRef count and how long they have been held, longest held first
+    pub(crate) fn pinned_entries(
+        &self,
+        cur_ts: Timestamp,
+        min_pinned: TimestampDuration,
+    ) -> Vec<(Arc<BucketEntry>, u32, TimestampDuration)> {
+        let inner = self.inner.read();
+        let mut out: Vec<(Arc<BucketEntry>, u32, TimestampDuration)> = inner
+            .all_entries
+            .iter()
+            .filter_map(|entry| {
+                let (ref_count, pinned_duration) = entry.pinned(cur_ts)?;
+                (pinned_duration >= min_pinned).then_some((entry, ref_count, pinned_duration))
+            })
+            .collect();
+        out.sort_by(|a, b| b.2.cmp(&a.2));
+        out
+    }
+
+    /// Turn the NodeRef watchdog on with a threshold, or off with None
+    pub(crate) fn set_node_ref_watchdog(&self, threshold: Option<TimestampDuration>) {
+        *self.unlocked_inner.node_ref_watchdog_threshold.lock() = threshold;
+    }
+
+    pub(crate) fn debug_info_noderefs(&self, min_pinned: TimestampDuration) -> String {
+        let cur_ts = get_aligned_timestamp();
+        let pinned_entries = self.pinned_entries(cur_ts, min_pinned);
+
+        let mut out = String::new();
+        out += &format!("Pinned Entries: {}\n", pinned_entries.len());
+        for (entry, ref_count, pinned_duration) in pinned_entries {
+            out += &format!(
+                "    {} refs: {} pinned: {}s\n",
+                entry.with_inner(|e| e.best_node_id()),
+                ref_count,
+                timestamp_to_secs(pinned_duration.as_u64()) as u64
+            );
+            #[cfg(feature = "tracking")]
+            {
+                out += &entry.with_inner(|e| e.debug_node_ref_tracks());
+            }
+        }
+        out += &format!(
+            "Watchdog: {}\n",
+            match *self.unlocked_inner.node_ref_watchdog_threshold.lock() {
+                Some(threshold) => format!("{}s", timestamp_to_secs(threshold.as_u64()) as u64),
+                None => "off".to_owned(),
+            }
+        );
         out
     }
 
@@ -200,7 +256,11 @@ impl RoutingTable {
         const COLS: usize = 16;
         out += "Buckets:\n";
         for ck in &VALID_CRYPTO_KINDS {
-            out += &format!("  {}:\n", ck);
+            out += &format!(
+                "  {}: (near bucket skew: {})\n",
+                ck,
+                inner.near_bucket_skew(*ck)
+            );
             let rows = inner.buckets[ck].len() / COLS;
             let mut r = 0;
             let mut b = 0;
@@ -0,0 +1,118 @@
+use super::*;
+
+/// How many fewer leading bits than the responder a peer in a FindNode answer may share with the target.
+/// A responder that is already close to the target can only return what it knows, which may be somewhat further away.
+pub const FIND_NODE_ANSWER_DISTANCE_TOLERANCE_BITS: usize = 8;
+
+/// The peers from a FindNode answer that survived validation, and how many were dropped
+#[derive(Debug, Clone)]
+pub(crate) struct ValidatedFindNodeAnswer {
+    /// Peers that are plausible answers for the target
+    pub peers: Vec<PeerInfo>,
+    /// How many peers were dropped for missing the crypto kind or being repeated
+    pub malformed_count: usize,
+    /// How many peers were dropped for being far from the target when we already know closer nodes
+    pub distant_count: usize,
+}
+
+impl ValidatedFindNodeAnswer {
+    /// How many peers were dropped from the answer
+    pub fn rejected_count(&self) -> usize {
+        self.malformed_count + self.distant_count
+    }
+
+    /// An answer is garbage if more than half of what was returned was malformed
+    /// Distance alone never makes an answer garbage, an honest responder may just know little about the target
+    pub fn is_garbage(&self) -> bool {
+        self.malformed_count * 2 > self.peers.len() + self.rejected_count()
+    }
+}
+
+impl RoutingTable {
+    /// Vet the peers returned by `responder` in answer to a FindNode for `target`
+    /// Peers are dropped if they are missing the target's crypto kind or are repeated.
+    /// Peers further from the target than the responder by more than the distance tolerance are only dropped
+    /// if they are also further away than `known_horizon`, the distance of the furthest of the closest nodes
+    /// we already know for the target. Without a horizon we know too little to second-guess the responder.
+    /// If the responder is the target, there is nothing closer it could return, so distance is not checked.
+    pub(crate) fn validate_find_node_answer(
+        vcrypto: CryptoSystemVersion,
+        responder: TypedKey,
+        target: TypedKey,
+        known_horizon: Option<CryptoKeyDistance>,
+        peers: Vec<PeerInfo>,
+    ) -> ValidatedFindNodeAnswer {
+        let kind = vcrypto.kind();
+
+        let d_responder = vcrypto.distance(&responder.value, &target.value);
+        let check_distance = known_horizon.is_some()
+            && responder.kind == kind
+            && target.kind == kind
+            && responder != target;
+        // How many leading bits the responder shares with the target, more is closer
+        let responder_common_bits = d_responder
+            .first_nonzero_bit()
+            .unwrap_or(CRYPTO_KEY_LENGTH * 8);
+
+        let mut seen = BTreeSet::<TypedKey>::new();
+        let mut out = Vec::with_capacity(peers.len());
+        let mut malformed_count = 0usize;
+        let mut distant_count = 0usize;
+        for peer in peers {
+            let Some(key_peer) = peer.node_ids().get(kind) else {
+                malformed_count += 1;
+                continue;
+            };
+            if !seen.insert(key_peer) {
+                malformed_count += 1;
+                continue;
+            }
+            if check_distance {
+                let d_peer = vcrypto.distance(&key_peer.value, &target.value);
+                if d_peer > d_responder && Some(d_peer) > known_horizon {
+                    let peer_common_bits =
+                        d_peer.first_nonzero_bit().unwrap_or(CRYPTO_KEY_LENGTH * 8);
+                    if peer_common_bits + FIND_NODE_ANSWER_DISTANCE_TOLERANCE_BITS
+                        < responder_common_bits
+                    {
+                        distant_count += 1;
+                        continue;
+                    }
+                }
+            }
+            out.push(peer);
+        }
+
+        ValidatedFindNodeAnswer {
+            peers: out,
+            malformed_count,
+            distant_count,
+        }
+    }
+
+    /// Get the distance of the furthest of the closest nodes we know for a target
+    /// Returns None if we don't know enough nodes to fill a FindNode answer ourselves
+    pub(crate) fn find_node_known_horizon(&self, target: TypedKey) -> Option<CryptoKeyDistance> {
+        let vcrypto = self.crypto().get(target.kind)?;
+        let node_count = self.config.get().network.dht.max_find_node_count as usize;
+        let own_node_id = self.node_id(target.kind);
+        let distances = self
+            .find_preferred_closest_nodes(
+                node_count,
+                target,
+                VecDeque::new(),
+                |rti, entry: Option<Arc<BucketEntry>>| {
+                    let node_id = match entry {
+                        Some(entry) => entry.with(rti, |_rti, e| e.node_ids().get(target.kind)),
+                        None => Some(own_node_id),
+                    };
+                    node_id.map(|n| vcrypto.distance(&n.value, &target.value))
+                },
+            )
+            .ok()?;
+        if distances.len() < node_count {
+            return None;
+        }
+        distances.into_iter().flatten().max()
+    }
+}
@@ -4,6 +4,7 @@ impl RoutingTable {
     /// Utility to find the closest nodes to a particular key, preferring reliable nodes first,
     /// including possibly our own node and nodes further away from the key than our own,
     /// returning their peer info
+    /// If the PublicInternet routing domain only passes on verified peers, unverified ones are left out
     pub fn find_preferred_closest_peers(
         &self,
         key: TypedKey,
@@ -19,6 +20,10 @@ impl RoutingTable {
             return NetworkResult::invalid_message("invalid crypto kind");
         }
 
+        let verified_peers_only = self
+            .unlocked_inner
+            .verified_peers_only(RoutingDomain::PublicInternet);
+
         // find N nodes closest to the target node in our routing table
         let own_peer_info = self.get_own_peer_info(RoutingDomain::PublicInternet);
         let filter = Box::new(
@@ -34,6 +39,10 @@ impl RoutingTable {
                 // Ensure capabilities are met
                 match opt_entry {
                     Some(entry) => entry.with(rti, |_rti, e| {
+                        // Ensure unverified peers are not passed on, if required
+                        if verified_peers_only && !e.is_verified(RoutingDomain::PublicInternet) {
+                            return false;
+                        }
                         e.has_capabilities(RoutingDomain::PublicInternet, capabilities)
                     }),
                     None => own_peer_info
@@ -47,7 +56,11 @@ impl RoutingTable {
 
         let node_count = {
             let c = self.config.get();
-            c.network.dht.max_find_node_count as usize
+            if c.network.role.is_bootstrap() {
+                BOOTSTRAP_ROLE_FIND_NODE_COUNT
+            } else {
+                c.network.dht.max_find_node_count as usize
+            }
         };
 
         let own_peer_info = self.get_own_peer_info(RoutingDomain::PublicInternet);
@@ -73,6 +86,7 @@ impl RoutingTable {
     /// Utility to find nodes that are closer to a key than our own node,
     /// preferring reliable nodes first, and returning their peer info
     /// Can filter based on a particular set of capabilities
+    /// If the PublicInternet routing domain only passes on verified peers, unverified ones are left out
     pub fn find_preferred_peers_closer_to_key(
         &self,
         key: TypedKey,
@@ -89,6 +103,9 @@ impl RoutingTable {
         };
         let own_distance = vcrypto.distance(&own_node_id.value, &key.value);
         let vcrypto2 = vcrypto.clone();
+        let verified_peers_only = self
+            .unlocked_inner
+            .verified_peers_only(RoutingDomain::PublicInternet);
 
         let filter = Box::new(
             move |rti: &RoutingTableInner, opt_entry: Option<Arc<BucketEntry>>| {
@@ -101,6 +118,10 @@ impl RoutingTable {
                     if !e.has_capabilities(RoutingDomain::PublicInternet, &required_capabilities) {
                         return false;
                     }
+                    // Ensure unverified peers are not passed on, if required
+                    if verified_peers_only && !e.is_verified(RoutingDomain::PublicInternet) {
+                        return false;
+                    }
                     // Ensure only things that are valid/signed in the PublicInternet domain are returned
                     if !rti.filter_has_valid_signed_node_info(
                         RoutingDomain::PublicInternet,
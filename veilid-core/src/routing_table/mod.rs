@@ -1,13 +1,16 @@
 mod bucket;
 mod bucket_entry;
 mod debug;
+mod find_node_answer;
 mod find_peers;
 mod node_ref;
 mod node_ref_filter;
+mod peer_table_export;
 mod privacy;
 mod route_spec_store;
 mod routing_domain_editor;
 mod routing_domains;
+mod routing_table_error;
 mod routing_table_inner;
 mod stats_accounting;
 mod tasks;
@@ -25,8 +28,10 @@ use bucket::*;
 use hashlink::LruCache;
 
 pub(crate) use bucket_entry::*;
+pub(crate) use find_node_answer::*;
 pub(crate) use node_ref::*;
 pub(crate) use node_ref_filter::*;
+pub(crate) use peer_table_export::*;
 pub(crate) use privacy::*;
 pub(crate) use route_spec_store::*;
 pub(crate) use routing_domain_editor::*;
@@ -34,6 +39,7 @@ pub(crate) use routing_domains::*;
 pub(crate) use routing_table_inner::*;
 pub(crate) use stats_accounting::*;
 
+pub use routing_table_error::*;
 pub use types::*;
 
 //////////////////////////////////////////////////////////////////////////
@@ -47,6 +53,36 @@ pub const RELAY_MANAGEMENT_INTERVAL_SECS: u32 = 1;
 /// How frequently we tick the private route management routine
 pub const PRIVATE_ROUTE_MANAGEMENT_INTERVAL_SECS: u32 = 1;
 
+/// How frequently we try to validate nodes waiting to join our nearest buckets
+pub const NEAR_BUCKET_VALIDATION_INTERVAL_SECS: u32 = 5;
+
+/// How frequently we try to reconnect to the peers we were connected to before restarting
+pub const WARM_PEER_RECONNECT_INTERVAL_SECS: u32 = 1;
+
+/// How frequently we look for buckets that need refreshing
+pub const BUCKET_REFRESH_INTERVAL_SECS: u32 = 10;
+
+/// How long a bucket can go without a lookup in its range before we refresh it
+pub const BUCKET_REFRESH_STALE_SECS: u32 = 3600;
+
+/// How many stale buckets we refresh at a time
+pub const BUCKET_REFRESH_BUCKET_COUNT: usize = 2;
+
+/// How many of our closest nodes we ask to find nodes in a stale bucket
+pub const BUCKET_REFRESH_NODE_COUNT: usize = 3;
+
+/// How many of the most recently connected peers we remember across restarts
+pub const WARM_PEER_COUNT: usize = 16;
+
+/// How frequently the NodeRef watchdog checks for entries held by NodeRefs for too long, when enabled
+pub const NODE_REF_WATCHDOG_INTERVAL_SECS: u32 = 60;
+
+/// How many times deeper each bucket is allowed to grow when running in the bootstrap role
+pub const BOOTSTRAP_ROLE_BUCKET_DEPTH_MULTIPLIER: usize = 8;
+
+/// How many peers a bootstrap node returns in each FindNode answer, the most an answer can carry
+pub const BOOTSTRAP_ROLE_FIND_NODE_COUNT: usize = 20;
+
 // Connectionless protocols like UDP are dependent on a NAT translation timeout
 // We should ping them with some frequency and 30 seconds is typical timeout
 pub const CONNECTIONLESS_TIMEOUT_SECS: u32 = 29;
@@ -56,6 +92,7 @@ const ALL_ENTRY_BYTES: &[u8] = b"all_entry_bytes";
 const ROUTING_TABLE: &str = "routing_table";
 const SERIALIZED_BUCKET_MAP: &[u8] = b"serialized_bucket_map";
 const CACHE_VALIDITY_KEY: &[u8] = b"cache_validity_key";
+const WARM_PEERS: &[u8] = b"warm_peers";
 
 // Critical sections
 const LOCK_TAG_TICK: &str = "TICK";
@@ -87,6 +124,10 @@ pub(crate) struct RoutingTableHealth {
     pub public_internet_ready: bool,
     /// If LocalNetwork network class is valid yet
     pub local_network_ready: bool,
+    /// Number of entries closer to us than expected per CryptoKind, a measure of bucket occupancy skew
+    pub near_bucket_skew: BTreeMap<CryptoKind, usize>,
+    /// Entry counts and viability for each supported CryptoKind
+    pub crypto_kinds: Vec<CryptoKindHealth>,
 }
 
 pub type BucketIndex = (CryptoKind, usize);
@@ -103,8 +144,8 @@ pub(crate) struct RoutingTableUnlockedInner {
 
     /// The current node's public DHT keys
     node_id: TypedKeyGroup,
-    /// The current node's public DHT secrets
-    node_id_secret: TypedSecretGroup,
+    /// Handles to the current node's DHT secrets
+    node_id_signers: BTreeMap<CryptoKind, Arc<dyn NodeIdSigner>>,
     /// Buckets to kick on our next kick task
     kick_queue: Mutex<BTreeSet<BucketIndex>>,
     /// Background process for computing statistics
@@ -121,6 +162,22 @@ pub(crate) struct RoutingTableUnlockedInner {
     relay_management_task: TickTask<EyreReport>,
     /// Background process to keep private routes up
     private_route_management_task: TickTask<EyreReport>,
+    /// Background process to prove nodes before admitting them to our nearest buckets
+    near_bucket_validation_task: TickTask<EyreReport>,
+    /// Background process to reconnect to the peers we were connected to before restarting
+    warm_peer_reconnect_task: TickTask<EyreReport>,
+    /// Background process to look up random keys in buckets we have not done lookups in lately
+    bucket_refresh_task: TickTask<EyreReport>,
+    /// How long an entry may be held by NodeRefs before the watchdog warns about it, None if the watchdog is off
+    node_ref_watchdog_threshold: Mutex<Option<TimestampDuration>>,
+    /// Background process to warn about entries that may be leaking NodeRefs
+    node_ref_watchdog_task: TickTask<EyreReport>,
+    /// How old an entry's node info may get before it is considered stale and must be refreshed
+    node_info_max_age: TimestampDuration,
+    /// The routing domains in which we only pass on peers that have answered a status question of ours
+    verified_peers_only: RoutingDomainSet,
+    /// The largest envelope we advertise we want to receive
+    max_envelope_size: u32,
 }
 
 impl RoutingTableUnlockedInner {
@@ -147,23 +204,51 @@ impl RoutingTableUnlockedInner {
         self.node_id.get(kind).unwrap()
     }
 
-    pub fn node_id_secret_key(&self, kind: CryptoKind) -> SecretKey {
-        self.node_id_secret.get(kind).unwrap().value
+    pub fn node_id_signer(&self, kind: CryptoKind) -> Arc<dyn NodeIdSigner> {
+        self.node_id_signers.get(&kind).unwrap().clone()
+    }
+
+    pub fn node_id_signer_list(&self) -> Vec<Arc<dyn NodeIdSigner>> {
+        self.node_id_signers.values().cloned().collect()
     }
 
     pub fn node_ids(&self) -> TypedKeyGroup {
         self.node_id.clone()
     }
 
-    pub fn node_id_typed_key_pairs(&self) -> Vec<TypedKeyPair> {
-        let mut tkps = Vec::new();
-        for ck in VALID_CRYPTO_KINDS {
-            tkps.push(TypedKeyPair::new(
-                ck,
-                KeyPair::new(self.node_id(ck).value, self.node_id_secret_key(ck)),
-            ));
+    pub fn node_info_max_age(&self) -> TimestampDuration {
+        self.node_info_max_age
+    }
+
+    pub fn verified_peers_only(&self, routing_domain: RoutingDomain) -> bool {
+        self.verified_peers_only.contains(routing_domain)
+    }
+
+    pub fn max_envelope_size(&self) -> u32 {
+        self.max_envelope_size
+    }
+
+    /// Signatures by each of our node ids over all of them, empty if we only have one node id
+    pub fn node_id_cross_signatures(&self) -> VeilidAPIResult<Vec<TypedSignature>> {
+        if self.node_id.len() < 2 {
+            return Ok(Vec::new());
+        }
+        PeerInfo::make_node_id_cross_signatures(&self.node_id_signer_list(), &self.node_id)
+    }
+
+    /// Key encapsulation public keys for any of our node ids whose crypto kind is hybrid
+    pub fn node_id_kem_public_keys(&self) -> Vec<TypedKemPublicKey> {
+        let mut out = Vec::new();
+        for (&ck, signer) in &self.node_id_signers {
+            match signer.kem_public_key() {
+                Ok(Some(kem_public_key)) => out.push(TypedKemPublicKey::new(ck, kem_public_key)),
+                Ok(None) => {}
+                Err(e) => {
+                    log_rtab!(error "failed to derive kem public key for {}: {}", ck, e);
+                }
+            }
         }
-        tkps
+        out
     }
 
     pub fn matches_own_node_id(&self, node_ids: &[TypedKey]) -> bool {
@@ -213,11 +298,31 @@ impl RoutingTable {
     ) -> RoutingTableUnlockedInner {
         let c = config.get();
 
+        // Node ids with a secret in the config are signed for in software, the rest are in the hardware keystore
+        let crypto = network_manager.crypto();
+        let mut node_id_signers = BTreeMap::<CryptoKind, Arc<dyn NodeIdSigner>>::new();
+        for node_id in c.network.routing_table.node_id.iter() {
+            let Some(vcrypto) = crypto.get(node_id.kind) else {
+                continue;
+            };
+            let signer: Arc<dyn NodeIdSigner> =
+                match c.network.routing_table.node_id_secret.get(node_id.kind) {
+                    Some(node_id_secret) => Arc::new(SecretKeyNodeIdSigner::new(
+                        vcrypto,
+                        KeyPair::new(node_id.value, node_id_secret.value),
+                    )),
+                    None => crypto
+                        .hardware_node_id_signer(node_id.kind)
+                        .expect("node id should have a secret or a hardware signer"),
+                };
+            node_id_signers.insert(node_id.kind, signer);
+        }
+
         RoutingTableUnlockedInner {
             config: config.clone(),
             network_manager,
             node_id: c.network.routing_table.node_id.clone(),
-            node_id_secret: c.network.routing_table.node_id_secret.clone(),
+            node_id_signers,
             kick_queue: Mutex::new(BTreeSet::default()),
             rolling_transfers_task: TickTask::new(ROLLING_TRANSFERS_INTERVAL_SECS),
             kick_buckets_task: TickTask::new(1),
@@ -226,6 +331,22 @@ impl RoutingTable {
             ping_validator_task: TickTask::new(1),
             relay_management_task: TickTask::new(RELAY_MANAGEMENT_INTERVAL_SECS),
             private_route_management_task: TickTask::new(PRIVATE_ROUTE_MANAGEMENT_INTERVAL_SECS),
+            near_bucket_validation_task: TickTask::new(NEAR_BUCKET_VALIDATION_INTERVAL_SECS),
+            warm_peer_reconnect_task: TickTask::new(WARM_PEER_RECONNECT_INTERVAL_SECS),
+            bucket_refresh_task: TickTask::new(BUCKET_REFRESH_INTERVAL_SECS),
+            node_ref_watchdog_threshold: Mutex::new(None),
+            node_ref_watchdog_task: TickTask::new(NODE_REF_WATCHDOG_INTERVAL_SECS),
+            node_info_max_age: TimestampDuration::from(ms_to_us(
+                c.network.routing_table.node_info_max_age_ms,
+            )),
+            verified_peers_only: c
+                .network
+                .routing_table
+                .verified_peers_only
+                .iter()
+                .copied()
+                .collect(),
+            max_envelope_size: c.network.max_envelope_size,
         }
     }
     pub fn new(network_manager: NetworkManager) -> Self {
@@ -361,6 +482,7 @@ impl RoutingTable {
     /// Write the serialized routing table to the table store.
     async fn save_buckets(&self) -> EyreResult<()> {
         let (serialized_bucket_map, all_entry_bytes) = self.serialized_buckets();
+        let warm_peers = self.inner.read().collect_warm_peers(WARM_PEER_COUNT);
 
         let table_store = self.unlocked_inner.network_manager().table_store();
         let tdb = table_store.open(ROUTING_TABLE, 1).await?;
@@ -373,6 +495,10 @@ impl RoutingTable {
             dbx.rollback();
             return Err(e.into());
         }
+        if let Err(e) = dbx.store_json(0, WARM_PEERS, &warm_peers) {
+            dbx.rollback();
+            return Err(e.into());
+        }
         dbx.commit().await?;
         Ok(())
     }
@@ -433,9 +559,17 @@ impl RoutingTable {
             return Ok(());
         };
 
+        // The warm peers list is only a hint, so it is fine if it is missing or unreadable
+        let warm_peers: Vec<WarmPeer> = db
+            .load_json(0, WARM_PEERS)
+            .await
+            .unwrap_or_default()
+            .unwrap_or_default();
+
         // Reconstruct all entries
         let inner = &mut *self.inner.write();
         self.populate_routing_table(inner, serialized_bucket_map, all_entry_bytes)?;
+        inner.warm_peers = warm_peers;
 
         Ok(())
     }
@@ -563,6 +697,26 @@ impl RoutingTable {
         )
     }
 
+    /// Look up how node A can still reach node B over a specific routing domain after a direct attempt failed
+    pub fn get_fallback_contact_method(
+        &self,
+        routing_domain: RoutingDomain,
+        peer_a: &PeerInfo,
+        peer_b: &PeerInfo,
+        dial_info_filter: DialInfoFilter,
+        sequencing: Sequencing,
+        dif_sort: Option<Arc<DialInfoDetailSort>>,
+    ) -> ContactMethod {
+        self.inner.read().get_fallback_contact_method(
+            routing_domain,
+            peer_a,
+            peer_b,
+            dial_info_filter,
+            sequencing,
+            dif_sort,
+        )
+    }
+
     #[instrument(level = "debug", skip(self))]
     pub fn edit_routing_domain(&self, domain: RoutingDomain) -> RoutingDomainEditor {
         RoutingDomainEditor::new(self.clone(), domain)
@@ -601,6 +755,10 @@ impl RoutingTable {
             .read()
             .get_outbound_node_ref_filter(routing_domain)
     }
+    /// Return a live filter that follows what the domain can send to as our network changes
+    pub fn live_outbound_node_ref_filter(routing_domain: RoutingDomain) -> LiveNodeRefFilter {
+        Arc::new(move |rti: &RoutingTableInner| rti.get_outbound_node_ref_filter(routing_domain))
+    }
 
     /// Attempt to empty the routing table
     /// May not empty buckets completely if there are existing node_refs
@@ -608,6 +766,15 @@ impl RoutingTable {
         self.inner.write().purge_buckets();
     }
 
+    /// Sign our peer info again and announce it to every node in the routing table
+    pub fn republish_own_peer_info(&self) {
+        let mut inner = self.inner.write();
+        for routing_domain in RoutingDomain::all() {
+            inner.with_routing_domain(routing_domain, |rdd| rdd.common().clear_cache());
+        }
+        inner.reset_all_updated_since_last_network_change();
+    }
+
     /// Attempt to remove last_connections from entries
     pub fn purge_last_connections(&self) {
         self.inner.write().purge_last_connections();
@@ -638,14 +805,17 @@ impl RoutingTable {
     }
 
     /// Resolve an existing routing table entry using any crypto kind and return a reference to it
-    pub fn lookup_any_node_ref(&self, node_id_key: PublicKey) -> EyreResult<Option<NodeRef>> {
+    pub fn lookup_any_node_ref(
+        &self,
+        node_id_key: PublicKey,
+    ) -> RoutingTableResult<Option<NodeRef>> {
         self.inner
             .read()
             .lookup_any_node_ref(self.clone(), node_id_key)
     }
 
     /// Resolve an existing routing table entry and return a reference to it
-    pub fn lookup_node_ref(&self, node_id: TypedKey) -> EyreResult<Option<NodeRef>> {
+    pub fn lookup_node_ref(&self, node_id: TypedKey) -> RoutingTableResult<Option<NodeRef>> {
         self.inner.read().lookup_node_ref(self.clone(), node_id)
     }
 
@@ -655,7 +825,7 @@ impl RoutingTable {
         node_id: TypedKey,
         routing_domain_set: RoutingDomainSet,
         dial_info_filter: DialInfoFilter,
-    ) -> EyreResult<Option<NodeRef>> {
+    ) -> RoutingTableResult<Option<NodeRef>> {
         self.inner.read().lookup_and_filter_noderef(
             self.clone(),
             node_id,
@@ -672,7 +842,8 @@ impl RoutingTable {
         routing_domain: RoutingDomain,
         peer_info: PeerInfo,
         allow_invalid: bool,
-    ) -> EyreResult<NodeRef> {
+    ) -> RoutingTableResult<NodeRef> {
+        self.check_not_banned(peer_info.node_ids())?;
         self.inner.write().register_node_with_peer_info(
             self.clone(),
             routing_domain,
@@ -681,6 +852,21 @@ impl RoutingTable {
         )
     }
 
+    /// Add a node to our routing table that has completed a round trip with us
+    /// Only near bucket validation should call this, once the node's receipt has come back
+    pub fn register_proven_node_with_peer_info(
+        &self,
+        routing_domain: RoutingDomain,
+        peer_info: PeerInfo,
+    ) -> RoutingTableResult<NodeRef> {
+        self.check_not_banned(peer_info.node_ids())?;
+        self.inner.write().register_proven_node_with_peer_info(
+            self.clone(),
+            routing_domain,
+            peer_info,
+        )
+    }
+
     /// Shortcut function to add a node to our routing table if it doesn't exist
     /// and add the last peer address we have for it, since that's pretty common
     pub fn register_node_with_existing_connection(
@@ -688,7 +874,8 @@ impl RoutingTable {
         node_id: TypedKey,
         flow: Flow,
         timestamp: Timestamp,
-    ) -> EyreResult<NodeRef> {
+    ) -> RoutingTableResult<NodeRef> {
+        self.check_not_banned(&[node_id])?;
         self.inner.write().register_node_with_existing_connection(
             self.clone(),
             node_id,
@@ -697,6 +884,20 @@ impl RoutingTable {
         )
     }
 
+    /// Refuse to register nodes that are currently punished by the address filter
+    /// This must be checked before locking the routing table, as the address filter
+    /// may call back into the routing table while holding its own lock
+    fn check_not_banned(&self, node_ids: &[TypedKey]) -> RoutingTableResult<()> {
+        let address_filter = self.network_manager().address_filter();
+        if let Some(node_id) = node_ids
+            .iter()
+            .find(|nid| address_filter.is_node_id_punished(**nid))
+        {
+            return Err(RoutingTableError::Banned(*node_id));
+        }
+        Ok(())
+    }
+
     //////////////////////////////////////////////////////////////////////
     // Routing Table Health Metrics
 
@@ -704,6 +905,40 @@ impl RoutingTable {
         self.inner.read().get_routing_table_health()
     }
 
+    pub fn get_bucket_statistics(&self) -> Vec<BucketStatistics> {
+        self.inner
+            .read()
+            .get_bucket_statistics(get_aligned_timestamp())
+    }
+
+    pub fn get_top_talkers(&self, count: usize) -> TopTalkers {
+        self.inner
+            .read()
+            .get_top_talkers(count, get_aligned_timestamp())
+    }
+
+    pub fn reset_top_talkers(&self) {
+        self.inner
+            .write()
+            .reset_top_talkers(get_aligned_timestamp());
+    }
+
+    /// Keep the connection to a node alive with keepalives for the grace period
+    pub fn prewarm_node(&self, node_id: TypedKey, grace_period: TimestampDuration) {
+        let cur_ts = get_aligned_timestamp();
+        self.inner
+            .write()
+            .prewarm_node(node_id, cur_ts, cur_ts + grace_period);
+    }
+
+    pub fn get_clock_skew(&self) -> Option<i64> {
+        self.inner.read().get_clock_skew()
+    }
+
+    pub fn get_clock_skew_with_peer_count(&self) -> Option<(i64, usize)> {
+        self.inner.read().get_clock_skew_with_peer_count()
+    }
+
     pub fn get_recent_peers(&self) -> Vec<(TypedKey, RecentPeersEntry)> {
         let mut recent_peers = Vec::new();
         let mut dead_peers = Vec::new();
@@ -743,6 +978,18 @@ impl RoutingTable {
         out
     }
 
+    /// Find the recent peer we last communicated with over a flow
+    pub fn lookup_recent_peer_by_flow(&self, flow: Flow) -> Option<NodeRef> {
+        let node_id = self
+            .inner
+            .read()
+            .recent_peers
+            .iter()
+            .find(|(_k, v)| v.last_connection == flow)
+            .map(|(k, _v)| *k)?;
+        self.lookup_node_ref(node_id).ok().flatten()
+    }
+
     //////////////////////////////////////////////////////////////////////
     // Find Nodes
 
@@ -992,17 +1239,37 @@ impl RoutingTable {
     #[instrument(level = "trace", skip(self, peers))]
     pub fn register_find_node_answer(
         &self,
-        crypto_kind: CryptoKind,
+        responder: NodeRef,
+        target: TypedKey,
         peers: Vec<PeerInfo>,
     ) -> Vec<NodeRef> {
-        // Register nodes we'd found
-        let mut out = Vec::<NodeRef>::with_capacity(peers.len());
-        for p in peers {
-            // Ensure we're getting back nodes we asked for
-            if !p.node_ids().kinds().contains(&crypto_kind) {
-                continue;
-            }
+        let crypto_kind = target.kind;
+        let Some(vcrypto) = self.crypto().get(crypto_kind) else {
+            return vec![];
+        };
 
+        // Drop peers that could not be an honest answer, and penalize responders that send mostly malformed peers
+        // Far away peers are only dropped when we already know closer nodes, and never get the responder punished
+        let responder_id = responder
+            .node_ids()
+            .get(crypto_kind)
+            .unwrap_or_else(|| responder.best_node_id());
+        let known_horizon = self.find_node_known_horizon(target);
+        let answer =
+            Self::validate_find_node_answer(vcrypto, responder_id, target, known_horizon, peers);
+        if answer.rejected_count() != 0 {
+            log_rtab!(debug "dropped {} malformed and {} distant peers from find node answer: responder={} target={}", answer.malformed_count, answer.distant_count, responder, target);
+        }
+        if answer.is_garbage() {
+            self.network_manager()
+                .address_filter()
+                .punish_node_id(responder_id);
+            return vec![];
+        }
+
+        // Register nodes we'd found
+        let mut out = Vec::<NodeRef>::with_capacity(answer.peers.len());
+        for p in answer.peers {
             // Don't register our own node
             if self.matches_own_node_id(p.node_ids()) {
                 continue;
@@ -1019,6 +1286,17 @@ impl RoutingTable {
         out
     }
 
+    /// Record that we looked up a key, so the bucket it falls in does not need refreshing
+    pub fn touch_bucket_lookup(&self, key: TypedKey) {
+        if !VALID_CRYPTO_KINDS.contains(&key.kind) || self.matches_own_node_id(&[key]) {
+            return;
+        }
+        let bucket_index = self.unlocked_inner.calculate_bucket_index(&key);
+        self.inner
+            .write()
+            .touch_bucket_lookup(bucket_index, get_aligned_timestamp());
+    }
+
     #[instrument(level = "trace", skip(self), err)]
     pub async fn find_node(
         &self,
@@ -1030,13 +1308,14 @@ impl RoutingTable {
         let res = network_result_try!(
             rpc_processor
                 .clone()
-                .rpc_call_find_node(Destination::direct(node_ref), node_id, vec![])
+                .rpc_call_find_node(Destination::direct(node_ref.clone()), node_id, vec![])
                 .await?
         );
 
         // register nodes we'd found
+        self.touch_bucket_lookup(node_id);
         Ok(NetworkResult::value(
-            self.register_find_node_answer(node_id.kind, res.answer),
+            self.register_find_node_answer(node_ref, node_id, res.answer),
         ))
     }
 
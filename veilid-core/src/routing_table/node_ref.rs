@@ -8,6 +8,7 @@ pub(crate) struct NodeRefBaseCommon {
     routing_table: RoutingTable,
     entry: Arc<BucketEntry>,
     filter: Option<NodeRefFilter>,
+    live_filter: Option<LiveNodeRefFilter>,
     sequencing: Sequencing,
     #[cfg(feature = "tracking")]
     track_id: usize,
@@ -49,6 +50,22 @@ pub(crate) trait NodeRefBase: Sized {
         self.common_mut().filter = filter
     }
 
+    fn set_live_filter(&mut self, live_filter: Option<LiveNodeRefFilter>) {
+        self.common_mut().live_filter = live_filter
+    }
+
+    /// The filter in effect right now, the fixed filter combined with the live filter computed from current state
+    fn filter(&self) -> Option<NodeRefFilter> {
+        let Some(live_filter) = self.common().live_filter.clone() else {
+            return self.common().filter;
+        };
+        let live = self.operate(|rti, _e| live_filter(rti));
+        Some(match self.common().filter {
+            Some(filter) => filter.filtered(&live),
+            None => live,
+        })
+    }
+
     fn set_sequencing(&mut self, sequencing: Sequencing) {
         self.common_mut().sequencing = sequencing;
     }
@@ -66,7 +83,7 @@ pub(crate) trait NodeRefBase: Sized {
     }
 
     fn is_filter_dead(&self) -> bool {
-        if let Some(filter) = &self.common().filter {
+        if let Some(filter) = &self.filter() {
             filter.is_dead()
         } else {
             false
@@ -74,32 +91,20 @@ pub(crate) trait NodeRefBase: Sized {
     }
 
     fn routing_domain_set(&self) -> RoutingDomainSet {
-        self.common()
-            .filter
-            .as_ref()
+        self.filter()
             .map(|f| f.routing_domain_set)
             .unwrap_or(RoutingDomainSet::all())
     }
 
     fn dial_info_filter(&self) -> DialInfoFilter {
-        self.common()
-            .filter
-            .as_ref()
+        self.filter()
             .map(|f| f.dial_info_filter)
             .unwrap_or(DialInfoFilter::all())
     }
 
     fn best_routing_domain(&self) -> Option<RoutingDomain> {
-        self.operate(|rti, e| {
-            e.best_routing_domain(
-                rti,
-                self.common()
-                    .filter
-                    .as_ref()
-                    .map(|f| f.routing_domain_set)
-                    .unwrap_or(RoutingDomainSet::all()),
-            )
-        })
+        let routing_domain_set = self.routing_domain_set();
+        self.operate(|rti, e| e.best_routing_domain(rti, routing_domain_set))
     }
 
     // Accessors
@@ -112,11 +117,24 @@ pub(crate) trait NodeRefBase: Sized {
     fn best_node_id(&self) -> TypedKey {
         self.operate(|_rti, e| e.best_node_id())
     }
+    fn best_envelope_node_id(&self) -> Option<(TypedKey, Option<KemPublicKey>)> {
+        let crypto = self.routing_table().crypto();
+        self.operate(|_rti, e| e.best_envelope_node_id(&crypto))
+    }
     fn update_node_status(&self, routing_domain: RoutingDomain, node_status: NodeStatus) {
         self.operate_mut(|_rti, e| {
             e.update_node_status(routing_domain, node_status);
         });
     }
+    fn node_status(&self, routing_domain: RoutingDomain) -> Option<NodeStatus> {
+        self.operate(|_rti, e| e.node_status(routing_domain))
+    }
+    fn set_verified(&self, routing_domain: RoutingDomain) {
+        self.operate_mut(|_rti, e| e.set_verified(routing_domain))
+    }
+    fn record_clock_skew(&self, clock_skew: i64) {
+        self.operate_mut(|_rti, e| e.record_clock_skew(clock_skew))
+    }
     fn envelope_support(&self) -> Vec<u8> {
         self.operate(|_rti, e| e.envelope_support())
     }
@@ -129,6 +147,19 @@ pub(crate) trait NodeRefBase: Sized {
     fn best_envelope_version(&self) -> Option<u8> {
         self.operate(|_rti, e| e.best_envelope_version())
     }
+    fn has_capabilities(&self, capabilities: &[Capability]) -> bool {
+        let Some(routing_domain) = self.best_routing_domain() else {
+            return false;
+        };
+        self.operate(|_rti, e| e.has_capabilities(routing_domain, capabilities))
+    }
+    fn envelope_size_limit(&self) -> Option<usize> {
+        let routing_domain = self.best_routing_domain()?;
+        self.operate(|_rti, e| {
+            e.node_info(routing_domain)
+                .and_then(|n| n.envelope_size_limit())
+        })
+    }
     fn state(&self, cur_ts: Timestamp) -> BucketEntryState {
         self.operate(|_rti, e| e.state(cur_ts))
     }
@@ -274,11 +305,11 @@ pub(crate) trait NodeRefBase: Sized {
     /// Get the most recent 'last connection' to this node
     /// Filtered first and then sorted by ordering preference and then by most recent
     fn last_flow(&self) -> Option<Flow> {
+        // apply sequencing to filter and get sort
+        let sequencing = self.common().sequencing;
+        let filter = self.filter().unwrap_or_default();
+        let (ordered, filter) = filter.with_sequencing(sequencing);
         self.operate(|rti, e| {
-            // apply sequencing to filter and get sort
-            let sequencing = self.common().sequencing;
-            let filter = self.common().filter.unwrap_or_default();
-            let (ordered, filter) = filter.with_sequencing(sequencing);
             let mut last_connections = e.last_flows(rti, true, filter);
 
             if ordered {
@@ -321,8 +352,10 @@ pub(crate) trait NodeRefBase: Sized {
         })
     }
 
-    fn report_protected_connection_dropped(&self) {
-        self.stats_failed_to_send(get_aligned_timestamp(), false);
+    fn report_connection_closed(&self, close_reason: ConnectionCloseReason, protected: bool) {
+        self.operate_mut(|_rti, e| {
+            e.connection_closed(get_aligned_timestamp(), close_reason, protected)
+        })
     }
 
     fn report_failed_route_test(&self) {
@@ -381,16 +414,19 @@ impl NodeRef {
         entry: Arc<BucketEntry>,
         filter: Option<NodeRefFilter>,
     ) -> Self {
-        entry.ref_count.fetch_add(1u32, Ordering::AcqRel);
+        entry.add_ref();
+        #[cfg(feature = "tracking")]
+        let track_id = entry.with_mut_inner(|e| e.track());
 
         Self {
             common: NodeRefBaseCommon {
                 routing_table,
                 entry,
                 filter,
+                live_filter: None,
                 sequencing: Sequencing::NoPreference,
                 #[cfg(feature = "tracking")]
-                track_id: entry.track(),
+                track_id,
             },
         }
     }
@@ -401,6 +437,12 @@ impl NodeRef {
         out
     }
 
+    pub fn live_filtered_clone(&self, live_filter: LiveNodeRefFilter) -> Self {
+        let mut out = self.clone();
+        out.set_live_filter(Some(live_filter));
+        out
+    }
+
     pub fn locked<'a>(&self, rti: &'a RoutingTableInner) -> NodeRefLocked<'a> {
         NodeRefLocked::new(rti, self.clone())
     }
@@ -437,19 +479,17 @@ impl NodeRefBase for NodeRef {
 
 impl Clone for NodeRef {
     fn clone(&self) -> Self {
-        self.common
-            .entry
-            .ref_count
-            .fetch_add(1u32, Ordering::AcqRel);
+        self.common.entry.add_ref();
 
         Self {
             common: NodeRefBaseCommon {
                 routing_table: self.common.routing_table.clone(),
                 entry: self.common.entry.clone(),
                 filter: self.common.filter,
+                live_filter: self.common.live_filter.clone(),
                 sequencing: self.common.sequencing,
                 #[cfg(feature = "tracking")]
-                track_id: self.common.entry.write().track(),
+                track_id: self.common.entry.with_mut_inner(|e| e.track()),
             },
         }
     }
@@ -466,6 +506,7 @@ impl fmt::Debug for NodeRef {
         f.debug_struct("NodeRef")
             .field("node_ids", &self.common.entry.with_inner(|e| e.node_ids()))
             .field("filter", &self.common.filter)
+            .field("live_filter", &self.common.live_filter.is_some())
             .field("sequencing", &self.common.sequencing)
             .finish()
     }
@@ -474,15 +515,12 @@ impl fmt::Debug for NodeRef {
 impl Drop for NodeRef {
     fn drop(&mut self) {
         #[cfg(feature = "tracking")]
-        self.common.entry.write().untrack(self.track_id);
+        self.common
+            .entry
+            .with_mut_inner(|e| e.untrack(self.common.track_id));
 
         // drop the noderef and queue a bucket kick if it was the last one
-        let new_ref_count = self
-            .common
-            .entry
-            .ref_count
-            .fetch_sub(1u32, Ordering::AcqRel)
-            - 1;
+        let new_ref_count = self.common.entry.remove_ref();
         if new_ref_count == 0 {
             // get node ids with inner unlocked because nothing could be referencing this entry now
             // and we don't know when it will get dropped, possibly inside a lock
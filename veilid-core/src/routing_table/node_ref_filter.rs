@@ -1,5 +1,12 @@
 use super::*;
 
+/// A node ref filter that is computed again from the current routing table state every time it is used
+///
+/// A NodeRefFilter is fixed when it is set, so a NodeRef that is held onto for a while can end up filtered
+/// by state that is no longer true, such as the protocols and address types our network interfaces had
+/// when it was made. A live filter is combined with the fixed filter whenever the NodeRef is used.
+pub type LiveNodeRefFilter = Arc<dyn Fn(&RoutingTableInner) -> NodeRefFilter + Send + Sync>;
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct NodeRefFilter {
     pub routing_domain_set: RoutingDomainSet,
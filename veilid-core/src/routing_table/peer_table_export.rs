@@ -0,0 +1,78 @@
+use super::*;
+
+/// A routing table entry as exported for analysis outside of the node
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct PeerTableExportEntry {
+    pub node_ids: TypedKeyGroup,
+    pub state: BucketEntryState,
+    pub public_internet: Option<NodeInfo>,
+    pub local_network: Option<NodeInfo>,
+    pub peer_stats: PeerStats,
+}
+
+impl RoutingTable {
+    /// Export the routing table entries that are at least in the given state
+    pub(crate) fn export_peer_table(
+        &self,
+        min_state: BucketEntryState,
+    ) -> Vec<PeerTableExportEntry> {
+        let cur_ts = get_aligned_timestamp();
+
+        let mut out = Vec::new();
+        self.inner
+            .read()
+            .with_entries(cur_ts, min_state, |rti, entry| {
+                out.push(entry.with(rti, |_rti, e| PeerTableExportEntry {
+                    node_ids: e.node_ids(),
+                    state: e.state(cur_ts),
+                    public_internet: e.node_info(RoutingDomain::PublicInternet).cloned(),
+                    local_network: e.node_info(RoutingDomain::LocalNetwork).cloned(),
+                    peer_stats: e.peer_stats().clone(),
+                }));
+                Option::<()>::None
+            });
+        out
+    }
+
+    /// Export the signed peer info of the live entries on the public internet
+    ///
+    /// This is the same list a node hands out in reply to a direct bootstrap request, so it can
+    /// be used to seed the routing table of another node. Every peer info is signed by its own node,
+    /// so the list can be passed around without having to trust whoever passes it along.
+    pub(crate) fn export_bootstrap_peers(&self) -> Vec<PeerInfo> {
+        let cur_ts = get_aligned_timestamp();
+
+        let mut out = Vec::new();
+        self.inner
+            .read()
+            .with_entries(cur_ts, BucketEntryState::Unreliable, |rti, entry| {
+                if let Some(pi) = entry.with(rti, |_rti, e| {
+                    e.make_peer_info(RoutingDomain::PublicInternet)
+                }) {
+                    if pi.signed_node_info().has_any_signature() {
+                        out.push(pi);
+                    }
+                }
+                Option::<()>::None
+            });
+        out
+    }
+
+    /// Seed the routing table with a list of signed peer info exported by another node
+    /// Peer info that does not validate is skipped
+    /// Returns the number of peers that were registered
+    pub(crate) fn import_bootstrap_peers(&self, mut peers: Vec<PeerInfo>) -> usize {
+        PeerInfo::validate_vec(&mut peers, self.crypto());
+
+        let mut count = 0usize;
+        for pi in peers {
+            match self.register_node_with_peer_info(RoutingDomain::PublicInternet, pi, false) {
+                Ok(_) => count += 1,
+                Err(e) => {
+                    log_rtab!(debug "failed to import bootstrap peer: {}", e);
+                }
+            }
+        }
+        count
+    }
+}
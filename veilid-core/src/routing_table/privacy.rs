@@ -62,6 +62,13 @@ impl RouteNode {
         }
     }
 
+    pub fn node_id(&self, crypto_kind: CryptoKind) -> Option<TypedKey> {
+        match self {
+            RouteNode::NodeId(id) => Some(TypedKey::new(crypto_kind, *id)),
+            RouteNode::PeerInfo(pi) => pi.node_ids().get(crypto_kind),
+        }
+    }
+
     pub fn describe(&self, crypto_kind: CryptoKind) -> String {
         match self {
             RouteNode::NodeId(id) => {
@@ -0,0 +1,70 @@
+use super::*;
+
+/// Probabilistic blame for the nodes used as hops in our routes that have failed
+///
+/// We can't tell which hop of a route lost a message, so each failure is split evenly between
+/// the hops that could have caused it. Nodes shared by several failing routes collect the most
+/// blame, and blame fades over time and as routes through a node succeed.
+#[derive(Debug, Default)]
+pub(crate) struct HopBlame {
+    /// Blame for each hop node id key
+    blame: HashMap<PublicKey, f32>,
+}
+
+impl HopBlame {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Split the blame for one failure between the hops of the routes it went over
+    pub fn record_failed(&mut self, hops: &[PublicKey]) {
+        if hops.is_empty() {
+            return;
+        }
+        let share = 1.0f32 / (hops.len() as f32);
+        for h in hops {
+            *self.blame.entry(*h).or_default() += share;
+        }
+    }
+
+    /// Put the whole blame for a failure on a single hop
+    /// Used when we failed to send to the first hop of a route
+    pub fn record_first_hop_failed(&mut self, hop: &PublicKey) {
+        *self.blame.entry(*hop).or_default() += 1.0f32;
+    }
+
+    /// Reduce the blame of hops that just carried a route successfully
+    pub fn record_succeeded(&mut self, hops: &[PublicKey]) {
+        for h in hops {
+            if let std::collections::hash_map::Entry::Occupied(mut o) = self.blame.entry(*h) {
+                *o.get_mut() *= HOP_BLAME_SUCCESS_FACTOR;
+                if *o.get() < HOP_BLAME_MINIMUM {
+                    o.remove();
+                }
+            }
+        }
+    }
+
+    /// Let blame fade with time, halving every HOP_BLAME_HALF_LIFE_SECS
+    pub fn decay(&mut self, elapsed: TimestampDuration) {
+        let half_lives =
+            (elapsed.as_u64() as f32) / ((HOP_BLAME_HALF_LIFE_SECS * 1_000_000) as f32);
+        let factor = 0.5f32.powf(half_lives);
+        self.blame.retain(|_, b| {
+            *b *= factor;
+            *b >= HOP_BLAME_MINIMUM
+        });
+    }
+
+    /// Get the blame for a node, by the most blamed of its node ids
+    pub fn get(&self, node_ids: &TypedKeyGroup) -> f32 {
+        node_ids.iter().fold(0.0f32, |acc, k| {
+            acc.max(self.blame.get(&k.value).cloned().unwrap_or_default())
+        })
+    }
+
+    /// Check if a node has been blamed often enough that routes should avoid it
+    pub fn is_avoided(&self, node_ids: &TypedKeyGroup) -> bool {
+        self.get(node_ids) >= HOP_BLAME_AVOID_THRESHOLD
+    }
+}
@@ -1,11 +1,13 @@
 use super::*;
 use crate::veilid_api::*;
 
+mod hop_blame;
 mod permutation;
 mod remote_private_route_info;
 mod route_set_spec_detail;
 mod route_spec_store_cache;
 mod route_spec_store_content;
+mod route_spec_store_error;
 mod route_stats;
 
 use permutation::*;
@@ -14,7 +16,11 @@ use route_set_spec_detail::*;
 use route_spec_store_cache::*;
 use route_spec_store_content::*;
 
+pub(crate) use hop_blame::*;
+pub(crate) use route_set_spec_detail::{RouteSetSpecDetail, RouteSpecDetail};
 pub(crate) use route_spec_store_cache::CompiledRoute;
+pub(crate) use route_spec_store_content::RouteSpecStoreContent;
+pub(crate) use route_spec_store_error::*;
 pub(crate) use route_stats::*;
 
 /// The size of the remote private route cache
@@ -25,6 +31,39 @@ const REMOTE_PRIVATE_ROUTE_CACHE_EXPIRY: TimestampDuration = TimestampDuration::
 const ROUTE_MIN_IDLE_TIME_MS: u32 = 30_000;
 /// The size of the compiled route cache
 const COMPILED_ROUTE_CACHE_SIZE: usize = 256;
+/// IPv4 hops of routes that need diverse networks must be in different blocks of this size
+const ROUTE_DIVERSITY_IPV4_NETMASK: u32 = 0xFFFF_FF00;
+/// Nodes with at least this much blame for route failures are not used as hops in new routes
+const HOP_BLAME_AVOID_THRESHOLD: f32 = 3.0;
+/// Hop blame is multiplied by this when a route through the hop succeeds
+const HOP_BLAME_SUCCESS_FACTOR: f32 = 0.5;
+/// Hop blame is forgotten once it falls below this
+const HOP_BLAME_MINIMUM: f32 = 0.05;
+/// Hop blame halves over this many seconds
+const HOP_BLAME_HALF_LIFE_SECS: u64 = 600;
+/// A remote private route is known to be reachable if it has delivered to us within this many seconds
+const HOP_BLAME_REMOTE_REACHABLE_SECS: u64 = 60;
+
+/// Get the network blocks a node can be reached at, for keeping the hops of a route on different networks
+/// Relayed nodes are on the networks of their relay
+fn route_diversity_blocks(ip6_prefix_size: usize, peer_info: &PeerInfo) -> BTreeSet<IpAddr> {
+    let sni = peer_info.signed_node_info();
+    let relay_dial_info = sni
+        .relay_info()
+        .map(|ni| ni.dial_info_detail_list())
+        .unwrap_or_default();
+    sni.node_info()
+        .dial_info_detail_list()
+        .iter()
+        .chain(relay_dial_info.iter())
+        .map(|did| match did.dial_info.ip_addr() {
+            IpAddr::V4(v4) => {
+                IpAddr::V4(Ipv4Addr::from(u32::from(v4) & ROUTE_DIVERSITY_IPV4_NETMASK))
+            }
+            addr @ IpAddr::V6(_) => ip_to_ipblock(ip6_prefix_size, addr),
+        })
+        .collect()
+}
 
 #[derive(Debug)]
 struct RouteSpecStoreInner {
@@ -32,6 +71,8 @@ struct RouteSpecStoreInner {
     content: RouteSpecStoreContent,
     /// RouteSpecStore cache
     cache: RouteSpecStoreCache,
+    /// Routes whose secret keys have been saved to the protected store
+    saved_secret_keys: HashSet<PublicKey>,
 }
 
 struct RouteSpecStoreUnlockedInner {
@@ -41,6 +82,10 @@ struct RouteSpecStoreUnlockedInner {
     max_route_hop_count: usize,
     /// Default number of hops in a route
     default_route_hop_count: usize,
+    /// Maximum number of manually allocated routes
+    max_private_routes: u32,
+    /// Maximum number of manually allocated routes for each API instance
+    max_private_routes_per_api: u32,
 }
 
 impl fmt::Debug for RouteSpecStoreUnlockedInner {
@@ -48,6 +93,11 @@ impl fmt::Debug for RouteSpecStoreUnlockedInner {
         f.debug_struct("RouteSpecStoreUnlockedInner")
             .field("max_route_hop_count", &self.max_route_hop_count)
             .field("default_route_hop_count", &self.default_route_hop_count)
+            .field("max_private_routes", &self.max_private_routes)
+            .field(
+                "max_private_routes_per_api",
+                &self.max_private_routes_per_api,
+            )
             .finish()
     }
 }
@@ -68,45 +118,57 @@ impl RouteSpecStore {
             unlocked_inner: Arc::new(RouteSpecStoreUnlockedInner {
                 max_route_hop_count: c.network.rpc.max_route_hop_count.into(),
                 default_route_hop_count: c.network.rpc.default_route_hop_count.into(),
+                max_private_routes: c.network.rpc.max_private_routes,
+                max_private_routes_per_api: c.network.rpc.max_private_routes_per_api,
                 routing_table,
             }),
             inner: Arc::new(Mutex::new(RouteSpecStoreInner {
                 content: RouteSpecStoreContent::new(),
                 cache: Default::default(),
+                saved_secret_keys: HashSet::new(),
             })),
         }
     }
 
     #[instrument(level = "trace", skip(routing_table), err)]
     pub async fn load(routing_table: RoutingTable) -> EyreResult<RouteSpecStore> {
-        let (max_route_hop_count, default_route_hop_count) = {
+        let (
+            max_route_hop_count,
+            default_route_hop_count,
+            max_private_routes,
+            max_private_routes_per_api,
+        ) = {
             let config = routing_table.network_manager().config();
             let c = config.get();
             (
                 c.network.rpc.max_route_hop_count as usize,
                 c.network.rpc.default_route_hop_count as usize,
+                c.network.rpc.max_private_routes,
+                c.network.rpc.max_private_routes_per_api,
             )
         };
 
         // Get frozen blob from table store
-        let content = RouteSpecStoreContent::load(routing_table.clone()).await?;
+        let (content, saved_secret_keys) =
+            RouteSpecStoreContent::load(routing_table.clone()).await?;
 
         let mut inner = RouteSpecStoreInner {
             content,
             cache: Default::default(),
+            saved_secret_keys,
         };
 
         // Rebuild the routespecstore cache
         let rti = &*routing_table.inner.read();
-        for (_, rssd) in inner.content.iter_details() {
-            inner.cache.add_to_cache(rti, rssd);
-        }
+        inner.cache.rebuild_cache(rti, &inner.content);
 
         // Return the loaded RouteSpecStore
         let rss = RouteSpecStore {
             unlocked_inner: Arc::new(RouteSpecStoreUnlockedInner {
                 max_route_hop_count,
                 default_route_hop_count,
+                max_private_routes,
+                max_private_routes_per_api,
                 routing_table: routing_table.clone(),
             }),
             inner: Arc::new(Mutex::new(inner)),
@@ -117,34 +179,33 @@ impl RouteSpecStore {
 
     #[instrument(level = "trace", skip(self), err)]
     pub async fn save(&self) -> EyreResult<()> {
-        let content = {
+        let (content, saved_secret_keys) = {
             let inner = self.inner.lock();
-            inner.content.clone()
+            (inner.content.clone(), inner.saved_secret_keys.clone())
         };
 
         // Save our content
-        content
-            .save(self.unlocked_inner.routing_table.clone())
+        let saved_secret_keys = content
+            .save(self.unlocked_inner.routing_table.clone(), saved_secret_keys)
             .await?;
 
+        self.inner.lock().saved_secret_keys = saved_secret_keys;
+
         Ok(())
     }
 
     #[instrument(level = "trace", skip(self))]
     pub fn send_route_update(&self) {
-        let (dead_routes, dead_remote_routes) = {
+        let route_change = {
             let mut inner = self.inner.lock();
-            let Some(dr) = inner.cache.take_dead_routes() else {
+            let Some(rc) = inner.cache.take_route_changes() else {
                 // Nothing to do
                 return;
             };
-            dr
+            rc
         };
 
-        let update = VeilidUpdate::RouteChange(Box::new(VeilidRouteChange {
-            dead_routes,
-            dead_remote_routes,
-        }));
+        let update = VeilidUpdate::RouteChange(Box::new(route_change));
 
         let update_callback = self.unlocked_inner.routing_table.update_callback();
         update_callback(update);
@@ -166,8 +227,10 @@ impl RouteSpecStore {
 
     /// Create a new route
     /// Prefers nodes that are not currently in use by another route
+    /// With prefer_throughput, hops are chosen by estimated bandwidth for routes carrying bulk transfers
     /// The route is not yet tested for its reachability
-    /// Returns Err(VeilidAPIError::TryAgain) if no route could be allocated at this time
+    /// Manually allocated routes count against the route budget of the API instance allocating them
+    /// Returns Err(VeilidAPIError::RouteCompilation) if no route could be allocated at this time
     /// Returns other errors on failure
     /// Returns Ok(route id string) on success
     #[instrument(level = "trace", skip(self), ret, err(level=Level::TRACE))]
@@ -180,13 +243,19 @@ impl RouteSpecStore {
         hop_count: usize,
         directions: DirectionSet,
         avoid_nodes: &[TypedKey],
+        prefer_throughput: bool,
         automatic: bool,
+        api_instance: Option<u64>,
     ) -> VeilidAPIResult<RouteId> {
         let inner = &mut *self.inner.lock();
         let routing_table = self.unlocked_inner.routing_table.clone();
         let rti = &mut *routing_table.inner.write();
 
-        self.allocate_route_inner(
+        if !automatic {
+            self.check_route_budget(inner, api_instance)?;
+        }
+
+        let id = self.allocate_route_inner(
             inner,
             rti,
             crypto_kinds,
@@ -195,7 +264,29 @@ impl RouteSpecStore {
             hop_count,
             directions,
             avoid_nodes,
+            None,
+            false,
+            prefer_throughput,
             automatic,
+        )?;
+
+        if let Some(rssd) = inner.content.get_detail_mut(&id) {
+            rssd.set_api_instance(api_instance);
+        }
+
+        Ok(id)
+    }
+
+    /// Check that there is room for another manually allocated route
+    fn check_route_budget(
+        &self,
+        inner: &RouteSpecStoreInner,
+        api_instance: Option<u64>,
+    ) -> RouteSpecStoreResult<()> {
+        inner.content.check_route_budget(
+            api_instance,
+            self.unlocked_inner.max_private_routes,
+            self.unlocked_inner.max_private_routes_per_api,
         )
     }
 
@@ -211,6 +302,9 @@ impl RouteSpecStore {
         hop_count: usize,
         directions: DirectionSet,
         avoid_nodes: &[TypedKey],
+        first_hop: Option<TypedKey>,
+        diverse_networks: bool,
+        prefer_throughput: bool,
         automatic: bool,
     ) -> VeilidAPIResult<RouteId> {
         use core::cmp::Ordering;
@@ -231,11 +325,17 @@ impl RouteSpecStore {
             );
         }
 
+        // Bootstrap nodes never allocate routes
+        if rti
+            .unlocked_inner
+            .with_config(|c| c.network.role.is_bootstrap())
+        {
+            apibail_generic!("not allocating routes in bootstrap role");
+        }
+
         // Ensure we have a valid network class so our peer info is useful
         if !rti.has_valid_network_class(RoutingDomain::PublicInternet) {
-            apibail_try_again!(
-                "unable to allocate route until we have a valid PublicInternet network class"
-            );
+            apibail_route_compilation!(RouteConstraint::NetworkClass, hop_count, 0);
         };
 
         // Get our peer info
@@ -248,6 +348,8 @@ impl RouteSpecStore {
 
         // Get list of all nodes, and sort them for selection
         let cur_ts = get_aligned_timestamp();
+        let node_info_max_age = rti.unlocked_inner.node_info_max_age();
+        let hop_blame = inner.cache.hop_blame();
         let filter = Box::new(
             |_rti: &RoutingTableInner, entry: Option<Arc<BucketEntry>>| -> bool {
                 // Exclude our own node from routes
@@ -276,6 +378,11 @@ impl RouteSpecStore {
                         return false;
                     }
 
+                    // Exclude nodes that have been blamed for too many route failures
+                    if hop_blame.is_avoided(&e.node_ids()) {
+                        return false;
+                    }
+
                     // Exclude nodes on our local network
                     if e.node_info(RoutingDomain::LocalNetwork).is_some() {
                         return false;
@@ -286,6 +393,15 @@ impl RouteSpecStore {
                         return false;
                     };
 
+                    // Exclude nodes whose node info is stale until a ping refreshes it
+                    if e.has_stale_node_info(
+                        RoutingDomain::PublicInternet,
+                        cur_ts,
+                        node_info_max_age,
+                    ) {
+                        return false;
+                    }
+
                     // Relay check
                     let relay_ids = sni.relay_ids();
                     if !relay_ids.is_empty() {
@@ -366,14 +482,31 @@ impl RouteSpecStore {
                 }
             }
 
-            // always prioritize reliable nodes, but sort by oldest or fastest
+            // always prioritize reliable nodes, but sort by most bandwidth, oldest or fastest
 
-            entry1.with_inner(|e1| {
-                entry2.with_inner(|e2| match stability {
-                    Stability::LowLatency => BucketEntryInner::cmp_fastest_reliable(cur_ts, e1, e2),
-                    Stability::Reliable => BucketEntryInner::cmp_oldest_reliable(cur_ts, e1, e2),
+            let cmp_reliable = entry1.with_inner(|e1| {
+                entry2.with_inner(|e2| {
+                    if prefer_throughput {
+                        return BucketEntryInner::cmp_highest_bandwidth_reliable(cur_ts, e1, e2);
+                    }
+                    match stability {
+                        Stability::LowLatency => {
+                            BucketEntryInner::cmp_fastest_reliable(cur_ts, e1, e2)
+                        }
+                        Stability::Reliable => {
+                            BucketEntryInner::cmp_oldest_reliable(cur_ts, e1, e2)
+                        }
+                    }
                 })
-            })
+            });
+            if !matches!(cmp_reliable, Ordering::Equal) {
+                return cmp_reliable;
+            }
+
+            // break ties with the blame nodes have collected for route failures
+            let e1_blame = hop_blame.get(&entry1_node_ids);
+            let e2_blame = hop_blame.get(&entry2_node_ids);
+            e1_blame.total_cmp(&e2_blame)
         };
 
         let routing_table = self.unlocked_inner.routing_table.clone();
@@ -382,14 +515,30 @@ impl RouteSpecStore {
         };
 
         // Pull the whole routing table in sorted order
-        let nodes: Vec<NodeRef> =
+        let mut nodes: Vec<NodeRef> =
             rti.find_peers_with_sort_and_filter(usize::MAX, cur_ts, filters, compare, transform);
 
         // If we couldn't find enough nodes, wait until we have more nodes in the routing table
         if nodes.len() < hop_count {
-            apibail_try_again!("not enough nodes to construct route at this time");
+            apibail_route_compilation!(RouteConstraint::NodeCount, hop_count, nodes.len());
         }
 
+        // Permutations never move the node they start at, so if a first hop was requested
+        // move it to the front and only try the permutations that start with it
+        let starts = if let Some(first_hop) = first_hop {
+            let Some(pos) = nodes
+                .iter()
+                .position(|nr| nr.locked(rti).node_ids().contains(&first_hop))
+            else {
+                apibail_route_compilation!(RouteConstraint::FirstHop, hop_count, nodes.len());
+            };
+            let first_hop_nr = nodes.remove(pos);
+            nodes.insert(0, first_hop_nr);
+            0..1
+        } else {
+            0..(nodes.len() - hop_count)
+        };
+
         // Get peer info for everything
         let nodes_pi: Vec<PeerInfo> = nodes
             .iter()
@@ -400,8 +549,33 @@ impl RouteSpecStore {
             })
             .collect();
 
+        // Get the networks of everything if the hops need to be on different networks
+        let (our_blocks, nodes_blocks): (BTreeSet<IpAddr>, Vec<BTreeSet<IpAddr>>) =
+            if diverse_networks {
+                let ip6_prefix_size = rti
+                    .unlocked_inner
+                    .with_config(|c| c.network.max_connections_per_ip6_prefix_size as usize);
+                (
+                    route_diversity_blocks(ip6_prefix_size, &our_peer_info),
+                    nodes_pi
+                        .iter()
+                        .map(|pi| route_diversity_blocks(ip6_prefix_size, pi))
+                        .collect(),
+                )
+            } else {
+                (BTreeSet::new(), Vec::new())
+            };
+
+        // Count the constraint each rejected route failed, to report the most common one
+        let mut rejections = BTreeMap::<RouteConstraint, usize>::new();
+
         // Now go through nodes and try to build a route we haven't seen yet
         let mut perm_func = Box::new(|permutation: &[usize]| {
+            let mut reject = |constraint: RouteConstraint| {
+                *rejections.entry(constraint).or_default() += 1;
+                None
+            };
+
             // Get the hop cache key for a particular route permutation
             // uses the same algorithm as RouteSetSpecDetail::make_cache_key
             let route_permutation_to_hop_cache =
@@ -416,7 +590,7 @@ impl RouteSpecStore {
 
             // Skip routes we have already seen
             if inner.cache.contains_route(&cache_key) {
-                return None;
+                return reject(RouteConstraint::Uniqueness);
             }
 
             // Ensure the route doesn't contain both a node and its relay
@@ -425,20 +599,37 @@ impl RouteSpecStore {
                 let node = nodes.get(*n).unwrap();
                 if !seen_nodes.insert(node.locked(rti).best_node_id()) {
                     // Already seen this node, should not be in the route twice
-                    return None;
+                    return reject(RouteConstraint::RelayConflict);
                 }
                 let opt_relay = match node.locked_mut(rti).relay(RoutingDomain::PublicInternet) {
                     Ok(r) => r,
                     Err(_) => {
                         // Not selecting a relay through ourselves
-                        return None;
+                        return reject(RouteConstraint::RelayConflict);
                     }
                 };
                 if let Some(relay) = opt_relay {
                     let relay_id = relay.locked(rti).best_node_id();
                     if !seen_nodes.insert(relay_id) {
                         // Already seen this node, should not be in the route twice
-                        return None;
+                        return reject(RouteConstraint::RelayConflict);
+                    }
+                }
+            }
+
+            // Ensure no two hops share a network, and no hop shares our network
+            if diverse_networks {
+                let mut seen_blocks = our_blocks.clone();
+                for n in permutation {
+                    let blocks = nodes_blocks.get(*n).unwrap();
+                    if blocks.is_empty() {
+                        // Can't tell what network this node is on
+                        return reject(RouteConstraint::NetworkDiversity);
+                    }
+                    for block in blocks {
+                        if !seen_blocks.insert(*block) {
+                            return reject(RouteConstraint::NetworkDiversity);
+                        }
                     }
                 }
             }
@@ -481,7 +672,7 @@ impl RouteSpecStore {
                     previous_node = current_node;
                 }
                 if !reachable {
-                    return None;
+                    return reject(RouteConstraint::Reachability);
                 }
             }
             if directions.contains(Direction::Inbound) {
@@ -519,7 +710,7 @@ impl RouteSpecStore {
                     next_node = current_node;
                 }
                 if !reachable {
-                    return None;
+                    return reject(RouteConstraint::Reachability);
                 }
             }
             // Keep this route
@@ -530,7 +721,7 @@ impl RouteSpecStore {
         let mut route_nodes: Vec<usize> = Vec::new();
         let mut can_do_sequenced: bool = true;
 
-        for start in 0..(nodes.len() - hop_count) {
+        for start in starts {
             // Try the permutations available starting with 'start'
             if let Some((rn, cds)) = with_route_permutations(hop_count, start, &mut perm_func) {
                 route_nodes = rn;
@@ -538,12 +729,18 @@ impl RouteSpecStore {
                 break;
             }
         }
+        drop(perm_func);
+
         if route_nodes.is_empty() {
-            apibail_try_again!("unable to find unique route at this time");
+            // If no routes were rejected, there were too few nodes to try any
+            let constraint = rejections
+                .iter()
+                .max_by_key(|(_, count)| **count)
+                .map(|(constraint, _)| *constraint)
+                .unwrap_or(RouteConstraint::NodeCount);
+            apibail_route_compilation!(constraint, hop_count, nodes.len());
         }
 
-        drop(perm_func);
-
         // Got a unique route, lets build the details, register it, and return it
         let hop_node_refs: Vec<NodeRef> = route_nodes.iter().map(|k| nodes[*k].clone()).collect();
         let mut route_set = BTreeMap::<PublicKey, RouteSpecDetail>::new();
@@ -584,6 +781,8 @@ impl RouteSpecStore {
             directions,
             stability,
             can_do_sequenced,
+            diverse_networks,
+            prefer_throughput,
             automatic,
         );
 
@@ -664,15 +863,23 @@ impl RouteSpecStore {
         Some(callback(rssd, rsd))
     }
 
+    /// Test one of our own allocated routes for continuity with a status question sent to ourselves over it
+    /// Without a safety spec, the route is tested as its own safety route.
+    /// With one, the safety route is chosen so that it shares no nodes with the route being tested.
+    /// Returns the round trip latency if the test made it back
     #[cfg_attr(
         feature = "verbose-tracing",
         instrument(level = "trace", skip(self), ret, err)
     )]
-    async fn test_allocated_route(&self, private_route_id: RouteId) -> VeilidAPIResult<bool> {
+    pub async fn test_allocated_route(
+        &self,
+        private_route_id: RouteId,
+        opt_safety_spec: Option<SafetySpec>,
+    ) -> VeilidAPIResult<Option<TimestampDuration>> {
         // Make loopback route to test with
         let (dest, hops) = {
             // Get the best allocated route for this id
-            let (key, hops) = {
+            let (key, hops, hop_node_ids) = {
                 let inner = &mut *self.inner.lock();
                 let Some(rssd) = inner.content.get_detail(&private_route_id) else {
                     apibail_invalid_argument!(
@@ -687,23 +894,44 @@ impl RouteSpecStore {
                 // Get the hops so we can match the route's hop length for safety
                 // route length as well as marking nodes as unreliable if this fails
                 let hops = rssd.hops_node_refs();
-                (key, hops)
+                (key, hops, rssd.hop_node_ids())
             };
 
             // Get the private route to send to
             let private_route = self.assemble_private_route(&key, None)?;
-            // Always test routes with safety routes that are more likely to succeed
-            let stability = Stability::Reliable;
-            // Routes should test with the most likely to succeed sequencing they are capable of
-            let sequencing = Sequencing::PreferOrdered;
-            // Hop count for safety spec should match the private route spec
-            let hop_count = hops.len();
 
-            let safety_spec = SafetySpec {
-                preferred_route: Some(private_route_id),
-                hop_count,
-                stability,
-                sequencing,
+            let safety_spec = match opt_safety_spec {
+                Some(safety_spec) => {
+                    // Pick a safety route that does not go through any of the hops being tested
+                    let safety_spec = self.apply_safety_profile(safety_spec);
+                    let inner = &mut *self.inner.lock();
+                    let routing_table = self.unlocked_inner.routing_table.clone();
+                    let rti = &mut *routing_table.inner.write();
+                    let sr_pubkey = self.get_route_for_safety_spec_inner(
+                        inner,
+                        rti,
+                        private_route.crypto_kind(),
+                        &safety_spec,
+                        Direction::Outbound.into(),
+                        &hop_node_ids,
+                    )?;
+                    SafetySpec {
+                        preferred_route: inner.content.get_id_by_key(&sr_pubkey),
+                        preferred_first_hop: None,
+                        ..safety_spec
+                    }
+                }
+                None => SafetySpec {
+                    preferred_route: Some(private_route_id),
+                    // Hop count for safety spec should match the private route spec
+                    hop_count: hops.len(),
+                    // Always test routes with safety routes that are more likely to succeed
+                    stability: Stability::Reliable,
+                    // Routes should test with the most likely to succeed sequencing they are capable of
+                    sequencing: Sequencing::PreferOrdered,
+                    preferred_first_hop: None,
+                    profile: None,
+                },
             };
             let safety_selection = SafetySelection::Safe(safety_spec);
 
@@ -718,20 +946,23 @@ impl RouteSpecStore {
 
         // Test with double-round trip ping to self
         let rpc_processor = self.unlocked_inner.routing_table.rpc_processor();
-        let _res = match rpc_processor.rpc_call_status(dest).await? {
+        let res = match rpc_processor.rpc_call_status(dest).await? {
             NetworkResult::Value(v) => v,
             _ => {
-                // Did not error, but did not come back, mark the nodes as failed to send, and then return false
+                // Did not error, but did not come back, mark the nodes as failed to send, and then return None
                 // This will prevent those node from immediately being included in the next allocated route,
                 // avoiding the same route being constructed to replace this one when it is removed.
-                for hop in hops {
-                    hop.report_failed_route_test();
+                // With a separate safety route, the failure may not be the fault of this route's hops.
+                if opt_safety_spec.is_none() {
+                    for hop in hops {
+                        hop.report_failed_route_test();
+                    }
                 }
-                return Ok(false);
+                return Ok(None);
             }
         };
 
-        Ok(true)
+        Ok(Some(res.latency))
     }
 
     #[instrument(level = "trace", skip(self), ret, err)]
@@ -754,6 +985,8 @@ impl RouteSpecStore {
                 hop_count: self.unlocked_inner.default_route_hop_count,
                 stability,
                 sequencing,
+                preferred_first_hop: None,
+                profile: None,
             };
 
             let safety_selection = SafetySelection::Safe(safety_spec);
@@ -814,7 +1047,7 @@ impl RouteSpecStore {
         if is_remote {
             self.test_remote_route(id).await
         } else {
-            self.test_allocated_route(id).await
+            Ok(self.test_allocated_route(id, None).await?.is_some())
         }
     }
 
@@ -841,6 +1074,9 @@ impl RouteSpecStore {
         sequencing: Sequencing,
         directions: DirectionSet,
         avoid_nodes: &[TypedKey],
+        first_hop: Option<TypedKey>,
+        diverse_networks: bool,
+        max_route_age: Option<TimestampDuration>,
     ) -> Option<RouteId> {
         let cur_ts = get_aligned_timestamp();
 
@@ -856,6 +1092,11 @@ impl RouteSpecStore {
                 && rssd.get_route_set_keys().kinds().contains(&crypto_kind)
                 && !rssd.is_published()
                 && !rssd.contains_nodes(avoid_nodes)
+                && first_hop.map(|fh| rssd.has_first_hop(&fh)).unwrap_or(true)
+                && (!diverse_networks || rssd.has_diverse_networks())
+                && max_route_age
+                    .map(|age| cur_ts.saturating_sub(rssd.get_stats().created_ts) < age)
+                    .unwrap_or(true)
             {
                 routes.push((id, rssd));
             }
@@ -952,7 +1193,7 @@ impl RouteSpecStore {
     }
 
     /// Compiles a safety route to the private route, with caching
-    /// Returns Err(VeilidAPIError::TryAgain) if no allocation could happen at this time (not an error)
+    /// Returns Err(VeilidAPIError::RouteCompilation) if no allocation could happen at this time (not an error)
     /// Returns other Err() if the parameters are wrong
     /// Returns Ok(compiled route) on success
     pub fn compile_safety_route(
@@ -986,7 +1227,7 @@ impl RouteSpecStore {
         // See if we are using a safety route, if not, short circuit this operation
         let safety_spec = match safety_selection {
             // Safety route spec to use
-            SafetySelection::Safe(safety_spec) => safety_spec,
+            SafetySelection::Safe(safety_spec) => self.apply_safety_profile(safety_spec),
             // Safety route stub with the node's public key as the safety route key since it's the 0th hop
             SafetySelection::Unsafe(sequencing) => {
                 let Some(pr_first_hop_node) = private_route.pop_first_hop() else {
@@ -994,18 +1235,15 @@ impl RouteSpecStore {
                 };
 
                 let opt_first_hop = match pr_first_hop_node {
-                    RouteNode::NodeId(id) => rti
-                        .lookup_node_ref(routing_table.clone(), TypedKey::new(crypto_kind, id))
-                        .map_err(VeilidAPIError::internal)?,
-                    RouteNode::PeerInfo(pi) => Some(
-                        rti.register_node_with_peer_info(
-                            routing_table.clone(),
-                            RoutingDomain::PublicInternet,
-                            *pi,
-                            false,
-                        )
-                        .map_err(VeilidAPIError::internal)?,
-                    ),
+                    RouteNode::NodeId(id) => {
+                        rti.lookup_node_ref(routing_table.clone(), TypedKey::new(crypto_kind, id))?
+                    }
+                    RouteNode::PeerInfo(pi) => Some(rti.register_node_with_peer_info(
+                        routing_table.clone(),
+                        RoutingDomain::PublicInternet,
+                        *pi,
+                        false,
+                    )?),
                 };
                 if opt_first_hop.is_none() {
                     // Can't reach this private route any more
@@ -1023,7 +1261,7 @@ impl RouteSpecStore {
                         routing_table.node_id(crypto_kind),
                         private_route,
                     ),
-                    secret: routing_table.node_id_secret_key(crypto_kind),
+                    secret: None,
                     first_hop,
                 });
             }
@@ -1074,7 +1312,7 @@ impl RouteSpecStore {
         first_hop.set_sequencing(safety_spec.sequencing);
 
         // Get the safety route secret key
-        let secret = safety_rsd.secret_key;
+        let secret = Some(safety_rsd.secret_key);
 
         // See if we have a cached route we can use
         if optimize {
@@ -1212,6 +1450,21 @@ impl RouteSpecStore {
         Ok(compiled_route)
     }
 
+    /// Fill in the hop count and stability of a safety spec from its safety profile if it has one
+    /// Profile hop counts are limited to our configured maximum route hop count
+    pub(crate) fn apply_safety_profile(&self, safety_spec: SafetySpec) -> SafetySpec {
+        let Some(profile) = safety_spec.profile else {
+            return safety_spec;
+        };
+        SafetySpec {
+            hop_count: profile
+                .hop_count()
+                .min(self.unlocked_inner.max_route_hop_count),
+            stability: profile.stability(),
+            ..safety_spec
+        }
+    }
+
     /// Get an allocated route that matches a particular safety spec
     #[cfg_attr(
         feature = "verbose-tracing",
@@ -1249,15 +1502,70 @@ impl RouteSpecStore {
                 // Only use the preferred route if it has the desired crypto kind
                 if let Some(preferred_key) = preferred_rssd.get_route_set_keys().get(crypto_kind) {
                     // Only use the preferred route if it doesn't contain the avoid nodes
-                    if !preferred_rssd.contains_nodes(avoid_nodes) {
+                    // and starts at the preferred first hop if there is one
+                    if !preferred_rssd.contains_nodes(avoid_nodes)
+                        && safety_spec
+                            .preferred_first_hop
+                            .map(|fh| preferred_rssd.has_first_hop(&fh))
+                            .unwrap_or(true)
+                    {
                         return Ok(preferred_key.value);
                     }
                 }
             }
         }
 
+        // Safety profiles can require hops on different networks and newer routes
+        let diverse_networks = safety_spec
+            .profile
+            .map(|p| p.requires_diverse_networks())
+            .unwrap_or(false);
+        let max_route_age = safety_spec
+            .profile
+            .and_then(|p| p.route_rotation_interval());
+
+        // Try to use a route through the preferred first hop if there is one
+        let mut opt_sr_route_id = None;
+        if let Some(first_hop) = safety_spec.preferred_first_hop {
+            opt_sr_route_id = Self::first_available_route_inner(
+                inner,
+                crypto_kind,
+                safety_spec.hop_count,
+                safety_spec.hop_count,
+                safety_spec.stability,
+                safety_spec.sequencing,
+                direction,
+                avoid_nodes,
+                Some(first_hop),
+                diverse_networks,
+                max_route_age,
+            );
+            if opt_sr_route_id.is_none() {
+                // The first hop is only a preference, so fall back to any route if it can't be used
+                opt_sr_route_id = self
+                    .allocate_route_inner(
+                        inner,
+                        rti,
+                        &[crypto_kind],
+                        safety_spec.stability,
+                        safety_spec.sequencing,
+                        safety_spec.hop_count,
+                        direction,
+                        avoid_nodes,
+                        Some(first_hop),
+                        diverse_networks,
+                        false,
+                        true,
+                    )
+                    .ok();
+            }
+        }
+
         // Select a safety route from the pool or make one if we don't have one that matches
-        let sr_route_id = if let Some(sr_route_id) = Self::first_available_route_inner(
+        let sr_route_id = if let Some(sr_route_id) = opt_sr_route_id {
+            // Found a route through the preferred first hop
+            sr_route_id
+        } else if let Some(sr_route_id) = Self::first_available_route_inner(
             inner,
             crypto_kind,
             safety_spec.hop_count,
@@ -1266,6 +1574,9 @@ impl RouteSpecStore {
             safety_spec.sequencing,
             direction,
             avoid_nodes,
+            None,
+            diverse_networks,
+            max_route_age,
         ) {
             // Found a route to use
             sr_route_id
@@ -1280,6 +1591,9 @@ impl RouteSpecStore {
                 safety_spec.hop_count,
                 direction,
                 avoid_nodes,
+                None,
+                diverse_networks,
+                false,
                 true,
             )?
         };
@@ -1315,7 +1629,7 @@ impl RouteSpecStore {
             inner,
             rti,
             crypto_kind,
-            safety_spec,
+            &self.apply_safety_profile(*safety_spec),
             Direction::Inbound.into(),
             avoid_nodes,
         )
@@ -1682,6 +1996,62 @@ impl RouteSpecStore {
         None
     }
 
+    /// Check if a remote private route has delivered anything to us recently,
+    /// so that losing a question sent to it is more likely the fault of our own hops
+    pub fn is_remote_route_reachable(&self, cur_ts: Timestamp, key: &PublicKey) -> bool {
+        let window = TimestampDuration::new(HOP_BLAME_REMOTE_REACHABLE_SECS * 1_000_000);
+        self.with_route_stats_mut(cur_ts, key, |s| {
+            s.last_received_ts
+                .map(|ts| cur_ts.saturating_sub(ts) < window)
+                .unwrap_or(false)
+        })
+        .unwrap_or(false)
+    }
+
+    /// Get the hops of one of our allocated routes by its public key
+    fn get_allocated_route_hops(inner: &RouteSpecStoreInner, key: &PublicKey) -> Vec<PublicKey> {
+        inner
+            .content
+            .get_id_by_key(key)
+            .and_then(|rsid| inner.content.get_detail(&rsid))
+            .and_then(|rssd| rssd.get_route_by_key(key))
+            .map(|rsd| rsd.hops.clone())
+            .unwrap_or_default()
+    }
+
+    /// Record a failure over some of our allocated routes
+    /// The blame for the failure is split between all of the hops of the routes
+    pub fn record_route_hops_failed(&self, keys: &[PublicKey]) {
+        let inner = &mut *self.inner.lock();
+        let hops: Vec<PublicKey> = keys
+            .iter()
+            .flat_map(|k| Self::get_allocated_route_hops(inner, k))
+            .collect();
+        inner.cache.hop_blame_mut().record_failed(&hops);
+    }
+
+    /// Record that we could not send to the first hop of one of our allocated routes
+    pub fn record_route_first_hop_failed(&self, key: &PublicKey) {
+        let inner = &mut *self.inner.lock();
+        let hops = Self::get_allocated_route_hops(inner, key);
+        if let Some(first_hop) = hops.first() {
+            inner
+                .cache
+                .hop_blame_mut()
+                .record_first_hop_failed(first_hop);
+        }
+    }
+
+    /// Record a success over some of our allocated routes, reducing the blame on their hops
+    pub fn record_route_hops_succeeded(&self, keys: &[PublicKey]) {
+        let inner = &mut *self.inner.lock();
+        let hops: Vec<PublicKey> = keys
+            .iter()
+            .flat_map(|k| Self::get_allocated_route_hops(inner, k))
+            .collect();
+        inner.cache.hop_blame_mut().record_succeeded(&hops);
+    }
+
     /// Clear caches when local our local node info changes
     #[instrument(level = "trace", skip(self))]
     pub fn reset(&self) {
@@ -1708,6 +2078,123 @@ impl RouteSpecStore {
         Ok(())
     }
 
+    /// Set or clear the rotation schedule of one of our allocated routes
+    pub fn set_route_rotation(
+        &self,
+        id: &RouteId,
+        rotation: Option<PrivateRouteRotation>,
+    ) -> VeilidAPIResult<()> {
+        let inner = &mut *self.inner.lock();
+        let Some(rssd) = inner.content.get_detail_mut(id) else {
+            apibail_invalid_argument!("route does not exist", "id", id);
+        };
+        if rssd.get_retire_ts().is_some() {
+            apibail_invalid_argument!("route has already been rotated", "id", id);
+        }
+        rssd.set_rotation(rotation);
+        Ok(())
+    }
+
+    /// Allocate a replacement for a route that is due to be rotated
+    /// The replacement has the same parameters and rotation schedule, and avoids the hops of the old route if it can
+    #[instrument(level = "trace", skip(self), ret, err(level=Level::TRACE))]
+    pub fn allocate_replacement_route(&self, id: &RouteId) -> VeilidAPIResult<RouteId> {
+        let inner = &mut *self.inner.lock();
+        let routing_table = self.unlocked_inner.routing_table.clone();
+        let rti = &mut *routing_table.inner.write();
+
+        let Some(rssd) = inner.content.get_detail(id) else {
+            apibail_invalid_argument!("route does not exist", "id", id);
+        };
+        let crypto_kinds = rssd.get_route_set_keys().kinds();
+        let stability = rssd.get_stability();
+        let sequencing = if rssd.is_sequencing_match(Sequencing::EnsureOrdered) {
+            Sequencing::EnsureOrdered
+        } else {
+            Sequencing::NoPreference
+        };
+        let hop_count = rssd.hop_count();
+        let directions = rssd.get_directions();
+        let diverse_networks = rssd.has_diverse_networks();
+        let prefer_throughput = rssd.prefers_throughput();
+        let automatic = rssd.is_automatic();
+        let api_instance = rssd.get_api_instance();
+        let rotation = rssd.get_rotation();
+        let old_hops: Vec<TypedKey> = rssd
+            .hops_node_refs()
+            .iter()
+            .flat_map(|nr| {
+                nr.locked(rti)
+                    .node_ids()
+                    .iter()
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        // Reusing the hops of the old route would make the new one easy to link to it,
+        // but small networks may not have enough other nodes
+        let new_id = match self.allocate_route_inner(
+            inner,
+            rti,
+            &crypto_kinds,
+            stability,
+            sequencing,
+            hop_count,
+            directions,
+            &old_hops,
+            None,
+            diverse_networks,
+            prefer_throughput,
+            automatic,
+        ) {
+            Err(VeilidAPIError::RouteCompilation { error: _ }) => self.allocate_route_inner(
+                inner,
+                rti,
+                &crypto_kinds,
+                stability,
+                sequencing,
+                hop_count,
+                directions,
+                &[],
+                None,
+                diverse_networks,
+                prefer_throughput,
+                automatic,
+            )?,
+            res => res?,
+        };
+
+        let new_rssd = inner.content.get_detail_mut(&new_id).unwrap();
+        new_rssd.set_api_instance(api_instance);
+        new_rssd.set_rotation(rotation);
+
+        Ok(new_id)
+    }
+
+    /// Schedule a route that has been replaced by its rotation schedule to be released,
+    /// and report the replacement to the application
+    pub fn retire_rotated_route(
+        &self,
+        old_id: RouteId,
+        new_id: RouteId,
+        blob: Vec<u8>,
+        retire_ts: Timestamp,
+    ) -> VeilidAPIResult<()> {
+        let inner = &mut *self.inner.lock();
+        let Some(rssd) = inner.content.get_detail_mut(&old_id) else {
+            apibail_invalid_argument!("route does not exist", "id", old_id);
+        };
+        rssd.set_rotation(None);
+        rssd.set_retire_ts(Some(retire_ts));
+        inner.cache.add_rotated_route(VeilidRouteRotation {
+            old_route: old_id,
+            new_route: new_id,
+            blob,
+        });
+        Ok(())
+    }
+
     /// Process transfer statistics to get averages
     pub fn roll_transfers(&self, last_ts: Timestamp, cur_ts: Timestamp) {
         let inner = &mut *self.inner.lock();
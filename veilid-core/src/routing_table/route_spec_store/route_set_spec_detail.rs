@@ -5,6 +5,9 @@ pub(crate) struct RouteSpecDetail {
     /// Crypto kind
     pub crypto_kind: CryptoKind,
     /// Secret key
+    /// Not serialized, kept in the protected store instead. Older stores that still have it
+    /// in the serialized content are migrated the next time the route spec store is saved.
+    #[serde(default, skip_serializing)]
     pub secret_key: SecretKey,
     /// Route hops (node id keys)
     pub hops: Vec<PublicKey>,
@@ -27,10 +30,26 @@ pub(crate) struct RouteSetSpecDetail {
     stability: Stability,
     /// Sequencing capability (connection oriented protocols vs datagram)
     can_do_sequenced: bool,
+    /// Every hop is on a different network from the other hops and from us
+    #[serde(default)]
+    diverse_networks: bool,
+    /// Hops were chosen for the most bandwidth rather than by stability alone
+    #[serde(default)]
+    prefer_throughput: bool,
     /// Stats
     stats: RouteStats,
     /// Automatically allocated route vs manually allocated route
     automatic: bool,
+    /// Schedule for replacing this route with a new one
+    #[serde(default)]
+    rotation: Option<PrivateRouteRotation>,
+    /// When this route is released after being replaced by its rotation schedule
+    #[serde(default)]
+    retire_ts: Option<Timestamp>,
+    /// The API instance that allocated this route, counted against its route budget
+    /// Not serialized because API instances do not outlive a restart
+    #[serde(skip)]
+    api_instance: Option<u64>,
 }
 
 impl RouteSetSpecDetail {
@@ -41,6 +60,8 @@ impl RouteSetSpecDetail {
         directions: DirectionSet,
         stability: Stability,
         can_do_sequenced: bool,
+        diverse_networks: bool,
+        prefer_throughput: bool,
         automatic: bool,
     ) -> Self {
         Self {
@@ -50,8 +71,13 @@ impl RouteSetSpecDetail {
             directions,
             stability,
             can_do_sequenced,
+            diverse_networks,
+            prefer_throughput,
             stats: RouteStats::new(cur_ts),
             automatic,
+            rotation: None,
+            retire_ts: None,
+            api_instance: None,
         }
     }
     pub fn get_route_by_key(&self, key: &PublicKey) -> Option<&RouteSpecDetail> {
@@ -75,6 +101,11 @@ impl RouteSetSpecDetail {
     ) -> alloc::collections::btree_map::Iter<PublicKey, RouteSpecDetail> {
         self.route_set.iter()
     }
+    pub fn iter_route_set_mut(
+        &mut self,
+    ) -> alloc::collections::btree_map::IterMut<PublicKey, RouteSpecDetail> {
+        self.route_set.iter_mut()
+    }
     pub fn get_stats(&self) -> &RouteStats {
         &self.stats
     }
@@ -96,6 +127,11 @@ impl RouteSetSpecDetail {
     pub fn hop_node_ref(&self, idx: usize) -> Option<NodeRef> {
         self.hop_node_refs.get(idx).cloned()
     }
+    pub fn has_first_hop(&self, node_id: &TypedKey) -> bool {
+        self.route_set
+            .values()
+            .any(|rsd| rsd.crypto_kind == node_id.kind && rsd.hops.first() == Some(&node_id.value))
+    }
     pub fn get_stability(&self) -> Stability {
         self.stability
     }
@@ -109,6 +145,18 @@ impl RouteSetSpecDetail {
             Sequencing::EnsureOrdered => self.can_do_sequenced,
         }
     }
+    pub fn has_diverse_networks(&self) -> bool {
+        self.diverse_networks
+    }
+    pub fn prefers_throughput(&self) -> bool {
+        self.prefer_throughput
+    }
+    pub fn hop_node_ids(&self) -> Vec<TypedKey> {
+        self.route_set
+            .values()
+            .flat_map(|rsd| rsd.hops.iter().map(|h| TypedKey::new(rsd.crypto_kind, *h)))
+            .collect()
+    }
     pub fn contains_nodes(&self, nodes: &[TypedKey]) -> bool {
         for tk in nodes {
             for rsd in self.route_set.values() {
@@ -122,6 +170,24 @@ impl RouteSetSpecDetail {
     pub fn is_automatic(&self) -> bool {
         self.automatic
     }
+    pub fn get_api_instance(&self) -> Option<u64> {
+        self.api_instance
+    }
+    pub fn set_api_instance(&mut self, api_instance: Option<u64>) {
+        self.api_instance = api_instance;
+    }
+    pub fn get_rotation(&self) -> Option<PrivateRouteRotation> {
+        self.rotation
+    }
+    pub fn set_rotation(&mut self, rotation: Option<PrivateRouteRotation>) {
+        self.rotation = rotation;
+    }
+    pub fn get_retire_ts(&self) -> Option<Timestamp> {
+        self.retire_ts
+    }
+    pub fn set_retire_ts(&mut self, retire_ts: Option<Timestamp>) {
+        self.retire_ts = retire_ts;
+    }
 
     /// Generate a key for the cache that can be used to uniquely identify this route's contents
     pub fn make_cache_key(&self, rti: &RoutingTableInner) -> Vec<u8> {
@@ -12,8 +12,8 @@ struct CompiledRouteCacheKey {
 pub(crate) struct CompiledRoute {
     /// The safety route attached to the private route
     pub safety_route: SafetyRoute,
-    /// The secret used to encrypt the message payload
-    pub secret: SecretKey,
+    /// The secret used to encrypt the message payload, None if the safety route is a stub and our node id is used
+    pub secret: Option<SecretKey>,
     /// The node ref to the first hop in the compiled route
     pub first_hop: NodeRef,
 }
@@ -37,6 +37,10 @@ pub(super) struct RouteSpecStoreCache {
     dead_routes: Vec<RouteId>,
     /// List of dead remote routes
     dead_remote_routes: Vec<RouteId>,
+    /// List of allocated routes replaced by their rotation schedule
+    rotated_routes: Vec<VeilidRouteRotation>,
+    /// Blame for route failures assigned to the hops of our allocated routes
+    hop_blame: HopBlame,
 }
 
 impl RouteSpecStoreCache {
@@ -60,6 +64,16 @@ impl RouteSpecStoreCache {
         }
     }
 
+    /// rebuild the allocated route caches from scratch for all of the route sets we have allocated
+    pub fn rebuild_cache(&mut self, rti: &RoutingTableInner, content: &RouteSpecStoreContent) {
+        self.used_nodes.clear();
+        self.used_end_nodes.clear();
+        self.hop_cache.clear();
+        for (_, rssd) in content.iter_details() {
+            self.add_to_cache(rti, rssd);
+        }
+    }
+
     /// checks if an allocated route is in our cache
     pub fn contains_route(&self, cache_key: &Vec<u8>) -> bool {
         self.hop_cache.contains(cache_key)
@@ -338,15 +352,25 @@ impl RouteSpecStoreCache {
         }
     }
 
-    /// Take the dead local and remote routes so we can update clients
-    pub fn take_dead_routes(&mut self) -> Option<(Vec<RouteId>, Vec<RouteId>)> {
-        if self.dead_routes.is_empty() && self.dead_remote_routes.is_empty() {
+    /// Remember an allocated route that was replaced by its rotation schedule so we can update clients
+    pub fn add_rotated_route(&mut self, rotation: VeilidRouteRotation) {
+        self.rotated_routes.push(rotation);
+    }
+
+    /// Take the dead local and remote routes and the rotated routes so we can update clients
+    pub fn take_route_changes(&mut self) -> Option<VeilidRouteChange> {
+        if self.dead_routes.is_empty()
+            && self.dead_remote_routes.is_empty()
+            && self.rotated_routes.is_empty()
+        {
             // Nothing to do
             return None;
         }
-        let dead_routes = core::mem::take(&mut self.dead_routes);
-        let dead_remote_routes = core::mem::take(&mut self.dead_remote_routes);
-        Some((dead_routes, dead_remote_routes))
+        Some(VeilidRouteChange {
+            dead_routes: core::mem::take(&mut self.dead_routes),
+            dead_remote_routes: core::mem::take(&mut self.dead_remote_routes),
+            rotated_routes: core::mem::take(&mut self.rotated_routes),
+        })
     }
 
     /// Clean up imported remote routes
@@ -363,6 +387,17 @@ impl RouteSpecStoreCache {
         for (_k, v) in self.remote_private_route_set_cache.iter_mut() {
             v.get_stats_mut().roll_transfers(last_ts, cur_ts);
         }
+        self.hop_blame.decay(cur_ts.saturating_sub(last_ts));
+    }
+
+    /// get the blame for route failures assigned to hops
+    pub fn hop_blame(&self) -> &HopBlame {
+        &self.hop_blame
+    }
+
+    /// get the blame for route failures assigned to hops, mutably
+    pub fn hop_blame_mut(&mut self) -> &mut HopBlame {
+        &mut self.hop_blame
     }
 }
 
@@ -377,6 +412,8 @@ impl Default for RouteSpecStoreCache {
             compiled_route_cache: LruCache::new(COMPILED_ROUTE_CACHE_SIZE),
             dead_routes: Default::default(),
             dead_remote_routes: Default::default(),
+            rotated_routes: Default::default(),
+            hop_blame: HopBlame::new(),
         }
     }
 }
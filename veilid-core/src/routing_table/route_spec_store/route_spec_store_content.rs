@@ -2,7 +2,7 @@ use super::*;
 
 /// The core representation of the RouteSpecStore that can be serialized
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub(super) struct RouteSpecStoreContent {
+pub(crate) struct RouteSpecStoreContent {
     /// All of the route sets we have allocated so far indexed by key (many to one)
     id_by_key: HashMap<PublicKey, RouteId>,
     /// All of the route sets we have allocated so far
@@ -17,16 +17,51 @@ impl RouteSpecStoreContent {
         }
     }
 
-    pub async fn load(routing_table: RoutingTable) -> EyreResult<RouteSpecStoreContent> {
+    /// Load the content from the table store and the route secret keys from the protected store
+    /// Returns the content along with the set of route keys whose secrets are in the protected store
+    pub async fn load(
+        routing_table: RoutingTable,
+    ) -> EyreResult<(RouteSpecStoreContent, HashSet<PublicKey>)> {
         // Deserialize what we can
         let table_store = routing_table.network_manager().table_store();
+        let protected_store = table_store.protected_store();
         let rsstdb = table_store.open("RouteSpecStore", 1).await?;
         let mut content: RouteSpecStoreContent =
             rsstdb.load_json(0, b"content").await?.unwrap_or_default();
 
-        // Look up all route hop noderefs since we can't serialize those
+        // Look up all route secret keys since we don't store those in the table
+        let mut saved_secret_keys = HashSet::new();
         let mut dead_ids = Vec::new();
         for (rsid, rssd) in content.details.iter_mut() {
+            for (pk, rsd) in rssd.iter_route_set_mut() {
+                match protected_store
+                    .load_user_secret(Self::secret_key_name(pk))
+                    .await?
+                {
+                    Some(secret) => {
+                        let Ok(secret_key) = SecretKey::try_from(secret.as_slice()) else {
+                            dead_ids.push(*rsid);
+                            break;
+                        };
+                        rsd.secret_key = secret_key;
+                        saved_secret_keys.insert(*pk);
+                    }
+                    None => {
+                        // Older stores kept the secret key in the content, it gets moved on the next save
+                        if rsd.secret_key == SecretKey::default() {
+                            dead_ids.push(*rsid);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Look up all route hop noderefs since we can't serialize those
+        for (rsid, rssd) in content.details.iter_mut() {
+            if dead_ids.contains(rsid) {
+                continue;
+            }
             // Get best route since they all should resolve
             let Some(pk) = rssd.get_best_route_set_key() else {
                 dead_ids.push(*rsid);
@@ -42,11 +77,14 @@ impl RouteSpecStoreContent {
                 let Ok(Some(nr)) =
                     routing_table.lookup_node_ref(TypedKey::new(rsd.crypto_kind, *h))
                 else {
-                    dead_ids.push(*rsid);
                     break;
                 };
                 hop_node_refs.push(nr);
             }
+            if hop_node_refs.len() != rsd.hops.len() {
+                dead_ids.push(*rsid);
+                continue;
+            }
 
             // Apply noderefs
             rssd.set_hop_node_refs(hop_node_refs);
@@ -56,17 +94,51 @@ impl RouteSpecStoreContent {
             content.remove_detail(&id);
         }
 
-        Ok(content)
+        Ok((content, saved_secret_keys))
     }
 
-    pub async fn save(&self, routing_table: RoutingTable) -> EyreResult<()> {
-        // Save all the fields we care about to the frozen blob in table storage
-        // This skips #[with(Skip)] saving the secret keys, we save them in the protected store instead
+    /// Save the content to the table store and the route secret keys to the protected store
+    /// Only secrets not in `saved_secret_keys` are written, and secrets of routes that are gone are removed
+    /// Returns the set of route keys whose secrets are now in the protected store
+    pub async fn save(
+        &self,
+        routing_table: RoutingTable,
+        saved_secret_keys: HashSet<PublicKey>,
+    ) -> EyreResult<HashSet<PublicKey>> {
         let table_store = routing_table.network_manager().table_store();
+        let protected_store = table_store.protected_store();
+
+        // Save the secret keys first so the content never refers to a secret that is missing
+        let mut secret_keys = HashSet::new();
+        for rssd in self.details.values() {
+            for (pk, rsd) in rssd.iter_route_set() {
+                if !saved_secret_keys.contains(pk) {
+                    protected_store
+                        .save_user_secret(Self::secret_key_name(pk), &rsd.secret_key.bytes)
+                        .await?;
+                }
+                secret_keys.insert(*pk);
+            }
+        }
+
+        // Save all the fields we care about to the frozen blob in table storage
+        // This skips saving the secret keys, they are in the protected store instead
         let rsstdb = table_store.open("RouteSpecStore", 1).await?;
         rsstdb.store_json(0, b"content", self).await?;
 
-        Ok(())
+        // Remove the secret keys of routes that no longer exist
+        for pk in saved_secret_keys.difference(&secret_keys) {
+            protected_store
+                .remove_user_secret(Self::secret_key_name(pk))
+                .await?;
+        }
+
+        Ok(secret_keys)
+    }
+
+    /// Name of the protected store entry holding the secret key for a route
+    fn secret_key_name(key: &PublicKey) -> String {
+        format!("RouteSpecStore_{}", key)
     }
 
     pub fn add_detail(&mut self, id: RouteId, detail: RouteSetSpecDetail) {
@@ -104,6 +176,40 @@ impl RouteSpecStoreContent {
         self.details.iter()
     }
 
+    /// Check that there is room for another manually allocated route
+    /// Automatically allocated routes are not counted, they are managed by the routing table
+    /// Routes allocated without an API instance share a budget of their own
+    pub fn check_route_budget(
+        &self,
+        api_instance: Option<u64>,
+        max_private_routes: u32,
+        max_private_routes_per_api: u32,
+    ) -> RouteSpecStoreResult<()> {
+        let mut route_count = 0u32;
+        let mut api_route_count = 0u32;
+        for rssd in self.details.values() {
+            if rssd.is_automatic() {
+                continue;
+            }
+            route_count += 1;
+            if rssd.get_api_instance() == api_instance {
+                api_route_count += 1;
+            }
+        }
+
+        if route_count >= max_private_routes {
+            return Err(RouteSpecStoreError::RouteBudgetExhausted(
+                max_private_routes,
+            ));
+        }
+        if api_route_count >= max_private_routes_per_api {
+            return Err(RouteSpecStoreError::ApiRouteBudgetExhausted(
+                max_private_routes_per_api,
+            ));
+        }
+        Ok(())
+    }
+
     /// Clean up local allocated routes
     /// Resets publication status and statistics for when our node info changes
     /// Routes must be republished
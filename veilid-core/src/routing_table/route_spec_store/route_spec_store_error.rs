@@ -0,0 +1,25 @@
+use super::*;
+
+#[derive(ThisError, Debug, Clone, PartialOrd, PartialEq, Eq, Ord)]
+#[must_use]
+pub enum RouteSpecStoreError {
+    #[error("[RouteSpecStoreError: RouteBudgetExhausted({0})]")]
+    RouteBudgetExhausted(u32),
+    #[error("[RouteSpecStoreError: ApiRouteBudgetExhausted({0})]")]
+    ApiRouteBudgetExhausted(u32),
+}
+
+pub type RouteSpecStoreResult<T> = Result<T, RouteSpecStoreError>;
+
+impl From<RouteSpecStoreError> for VeilidAPIError {
+    fn from(e: RouteSpecStoreError) -> Self {
+        match e {
+            RouteSpecStoreError::RouteBudgetExhausted(limit) => VeilidAPIError::TryAgain {
+                message: format!("private route limit of {} reached", limit),
+            },
+            RouteSpecStoreError::ApiRouteBudgetExhausted(limit) => VeilidAPIError::TryAgain {
+                message: format!("private route limit of {} per api reached", limit),
+            },
+        }
+    }
+}
@@ -19,6 +19,9 @@ pub(crate) struct RouteStats {
     /// Timestamp of when the route was last received over
     #[serde(skip)]
     pub last_received_ts: Option<Timestamp>,
+    /// Timestamp of when the route last carried something other than a route test
+    #[serde(skip)]
+    pub last_used_ts: Option<Timestamp>,
     /// Transfers up and down
     pub transfer_stats_down_up: TransferStatsDownUp,
     /// Latency stats
@@ -65,6 +68,11 @@ impl RouteStats {
         self.failed_to_send = 0;
     }
 
+    /// Mark a route as having carried application traffic
+    pub fn record_used(&mut self, cur_ts: Timestamp) {
+        self.last_used_ts = Some(cur_ts);
+    }
+
     /// Mark a route as having been sent to
     pub fn record_latency(&mut self, latency: TimestampDuration) {
         self.latency_stats = self.latency_stats_accounting.record_latency(latency);
@@ -131,4 +139,11 @@ impl RouteStats {
 
         false
     }
+
+    /// Check if a route has gone unused for longer than the idle duration
+    /// Routes that have never been used count from when they were created
+    pub fn is_idle(&self, cur_ts: Timestamp, idle_duration: TimestampDuration) -> bool {
+        let last_used_ts = self.last_used_ts.unwrap_or(self.created_ts);
+        cur_ts.saturating_sub(last_used_ts) > idle_duration
+    }
 }
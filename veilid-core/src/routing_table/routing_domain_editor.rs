@@ -26,6 +26,9 @@ enum RoutingDomainChange {
     SetNetworkClass {
         network_class: Option<NetworkClass>,
     },
+    SetCapabilities {
+        capabilities: Vec<Capability>,
+    },
 }
 
 pub(crate) struct RoutingDomainEditor {
@@ -90,10 +93,22 @@ impl RoutingDomainEditor {
             ));
         }
 
+        // Advertise the preference configured for the protocol
+        let preference = self.routing_table.with_config(|c| {
+            let protocol = &c.network.protocol;
+            match dial_info.protocol_type() {
+                ProtocolType::UDP => protocol.udp.preference,
+                ProtocolType::TCP => protocol.tcp.preference,
+                ProtocolType::WS => protocol.ws.preference,
+                ProtocolType::WSS => protocol.wss.preference,
+            }
+        });
+
         self.changes.push(RoutingDomainChange::AddDialInfoDetail {
             dial_info_detail: DialInfoDetail {
                 dial_info: dial_info.clone(),
                 class,
+                preference,
             },
         });
 
@@ -123,6 +138,13 @@ impl RoutingDomainEditor {
         self
     }
 
+    #[instrument(level = "debug", skip(self))]
+    pub fn set_capabilities(&mut self, capabilities: Vec<Capability>) -> &mut Self {
+        self.changes
+            .push(RoutingDomainChange::SetCapabilities { capabilities });
+        self
+    }
+
     #[instrument(level = "debug", skip(self))]
     pub async fn commit(&mut self, pause_tasks: bool) {
         // No locking if we have nothing to do
@@ -247,6 +269,19 @@ impl RoutingDomainEditor {
                                 peer_info_changed = true;
                             }
                         }
+                        RoutingDomainChange::SetCapabilities { capabilities } => {
+                            let old_capabilities = detail.common().capabilities();
+
+                            let this_changed = old_capabilities != capabilities;
+                            if this_changed {
+                                info!(
+                                    "[{:?}] set capabilities: {:?}",
+                                    self.routing_domain, capabilities,
+                                );
+                                detail.common_mut().set_capabilities(capabilities);
+                                peer_info_changed = true;
+                            }
+                        }
                     }
                 }
             });
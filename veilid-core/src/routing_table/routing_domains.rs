@@ -65,6 +65,11 @@ impl RoutingDomainDetailCommon {
         self.clear_cache();
     }
 
+    pub(super) fn set_capabilities(&mut self, capabilities: Vec<Capability>) {
+        self.capabilities = capabilities;
+        self.clear_cache();
+    }
+
     pub(super) fn set_network_class(&mut self, network_class: Option<NetworkClass>) {
         self.network_class = network_class;
         self.clear_cache();
@@ -136,7 +141,9 @@ impl RoutingDomainDetailCommon {
             self.outbound_protocols,
             self.address_types,
             VALID_ENVELOPE_VERSIONS.to_vec(),
+            rti.unlocked_inner.max_envelope_size(),
             VALID_CRYPTO_KINDS.to_vec(),
+            rti.unlocked_inner.node_id_kem_public_keys(),
             self.capabilities.clone(),
             self.dial_info_details.clone()
         );
@@ -180,8 +187,7 @@ impl RoutingDomainDetailCommon {
         let signed_node_info = match relay_info {
             Some((relay_ids, relay_sdni)) => SignedNodeInfo::Relayed(
                 SignedRelayedNodeInfo::make_signatures(
-                    rti.unlocked_inner.crypto(),
-                    rti.unlocked_inner.node_id_typed_key_pairs(),
+                    &rti.unlocked_inner.node_id_signer_list(),
                     node_info,
                     relay_ids,
                     relay_sdni,                    
@@ -190,15 +196,23 @@ impl RoutingDomainDetailCommon {
             ),
             None => SignedNodeInfo::Direct(
                 SignedDirectNodeInfo::make_signatures(
-                    rti.unlocked_inner.crypto(),
-                    rti.unlocked_inner.node_id_typed_key_pairs(),
+                    &rti.unlocked_inner.node_id_signer_list(),
                     node_info,
                 )
                 .unwrap()
             ),
         };
 
+        let node_id_cross_signatures = match rti.unlocked_inner.node_id_cross_signatures() {
+            Ok(v) => v,
+            Err(e) => {
+                error!("failed to cross-sign node ids: {}", e);
+                Vec::new()
+            }
+        };
+
         PeerInfo::new(rti.unlocked_inner.node_ids(), signed_node_info)
+            .with_node_id_cross_signatures(node_id_cross_signatures)
     }
 
     pub fn with_peer_info<F, R>(&self, rti: &RoutingTableInner, f: F) -> R
@@ -253,6 +267,18 @@ pub(crate) trait RoutingDomainDetail {
         sequencing: Sequencing,
         dif_sort: Option<Arc<DialInfoDetailSort>>,
     ) -> ContactMethod;
+
+    /// Get the contact method for node A to fall back to when it could not reach node B directly
+    /// Routing table must be locked for reading to use this function
+    fn get_fallback_contact_method(
+        &self,
+        rti: &RoutingTableInner,
+        peer_a: &PeerInfo,
+        peer_b: &PeerInfo,
+        dial_info_filter: DialInfoFilter,
+        sequencing: Sequencing,
+        dif_sort: Option<Arc<DialInfoDetailSort>>,
+    ) -> ContactMethod;
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -316,10 +342,154 @@ fn first_filtered_dial_info_detail_between_nodes(
     }
 
     // Get the best match dial info for node B if we have it
-    let direct_filter = |did: &DialInfoDetail| did.matches_filter(&dial_info_filter);
+    // IPC dial info is only ever chosen by ipc_dial_info_between_nodes
+    let direct_filter = |did: &DialInfoDetail| did.matches_filter(&dial_info_filter) && !did.dial_info.is_ipc();
     to_node.first_filtered_dial_info_detail(sort, direct_filter)
 }
 
+/// Get the IPC dial info node A can use to reach node B, if they are both on this host
+///
+/// We can only tell if IPC dial info is for the host we are running on, so node A
+/// must be us, or have IPC dial info of its own on this host.
+fn ipc_dial_info_between_nodes(
+    rti: &RoutingTableInner,
+    peer_a: &PeerInfo,
+    peer_b: &PeerInfo,
+    dial_info_filter: &DialInfoFilter,
+) -> Option<DialInfo> {
+    let node_a = peer_a.signed_node_info().node_info();
+    let node_b = peer_b.signed_node_info().node_info();
+
+    if !rti.unlocked_inner.with_config(|c| c.network.protocol.ipc.connect)
+        || !dial_info_filter.protocol_type_set.contains(ProtocolType::TCP)
+        || !node_a.outbound_protocols().contains(ProtocolType::TCP)
+    {
+        return None;
+    }
+    let is_this_host_ipc = |did: &&DialInfoDetail| matches!(&did.dial_info, DialInfo::IPC(di) if di.is_on_this_host());
+    if !rti.unlocked_inner.matches_own_node_id(peer_a.node_ids())
+        && !node_a.dial_info_detail_list().iter().any(|did| is_this_host_ipc(&did))
+    {
+        return None;
+    }
+
+    node_b
+        .dial_info_detail_list()
+        .iter()
+        .find(is_this_host_ipc)
+        .map(|did| did.dial_info.clone())
+}
+
+/// Get the signaled contact method node A can use to reach node B at a dial info that needs more than a direct connection
+/// Tries a reverse connection, then a UDP hole punch, and finally falls back to node B's inbound relay
+fn signal_contact_method_between_nodes(
+    peer_a: &PeerInfo,
+    peer_b: &PeerInfo,
+    target_did: &DialInfoDetail,
+    best_ck: CryptoKind,
+    dial_info_filter: DialInfoFilter,
+    sequencing: Sequencing,
+    dif_sort: Option<Arc<DialInfoDetailSort>>,
+) -> Option<ContactMethod> {
+    // Get the nodeinfos for convenience
+    let node_a = peer_a.signed_node_info().node_info();
+    let node_b = peer_b.signed_node_info().node_info();
+    let node_b_id = peer_b.node_ids().get(best_ck).unwrap();
+
+    // Get the target's inbound relay, it must have one or it is not reachable
+    if let Some(node_b_relay) = peer_b.signed_node_info().relay_info() {
+
+        // Note that relay_peer_info could be node_a, in which case a connection already exists
+        // and we only get here if the connection had dropped, in which case node_a is unreachable until
+        // it gets a new relay connection up
+        if peer_b.signed_node_info().relay_ids().contains_any(peer_a.node_ids()) {
+            return Some(ContactMethod::Existing);
+        }
+
+        // Get best node id to contact relay with
+        let Some(node_b_relay_id) = peer_b.signed_node_info().relay_ids().get(best_ck) else {
+            // No best relay id
+            return Some(ContactMethod::Unreachable);
+        };
+
+        // Can node A reach the inbound relay directly?
+        if first_filtered_dial_info_detail_between_nodes(
+            node_a,
+            node_b_relay,
+            &dial_info_filter,
+            sequencing,
+            dif_sort.clone(),
+        )
+        .is_some()
+        {
+            // Can node A receive anything inbound ever?
+            if matches!(node_a.network_class(), NetworkClass::InboundCapable) {
+                ///////// Reverse connection
+
+                // Get the best match dial info for an reverse inbound connection from node B to node A
+                if let Some(reverse_did) = first_filtered_dial_info_detail_between_nodes(
+                    node_b,
+                    node_a,
+                    &dial_info_filter,
+                    sequencing,
+                    dif_sort.clone()
+                ) {
+                    // Ensure we aren't on the same public IP address (no hairpin nat)
+                    if reverse_did.dial_info.ip_addr()
+                        != target_did.dial_info.ip_addr()
+                    {
+                        // Can we receive a direct reverse connection?
+                        if !reverse_did.class.requires_signal() {
+                            return Some(ContactMethod::SignalReverse(
+                                node_b_relay_id,
+                                node_b_id,
+                            ));
+                        }
+                    }
+                }
+
+                ///////// UDP hole-punch
+
+                // Does node B have a direct udp dialinfo node A can reach?
+                let udp_dial_info_filter = dial_info_filter
+                    .filtered(&DialInfoFilter::all().with_protocol_type(ProtocolType::UDP));
+                if let Some(target_udp_did) = first_filtered_dial_info_detail_between_nodes(
+                    node_a,
+                    node_b,
+                    &udp_dial_info_filter,
+                    sequencing,
+                    dif_sort.clone()
+                ) {
+                    // Does node A have a direct udp dialinfo that node B can reach?
+                    if let Some(reverse_udp_did) = first_filtered_dial_info_detail_between_nodes(
+                        node_b,
+                        node_a,
+                        &udp_dial_info_filter,
+                        sequencing,
+                        dif_sort.clone(),
+                    ) {
+                        // Ensure we aren't on the same public IP address (no hairpin nat)
+                        if reverse_udp_did.dial_info.ip_addr()
+                            != target_udp_did.dial_info.ip_addr()
+                        {
+                            // The target and ourselves have a udp dialinfo that they can reach
+                            return Some(ContactMethod::SignalHolePunch(
+                                node_b_relay_id,
+                                node_b_id,
+                            ));
+                        }
+                    }
+                }
+                // Otherwise we have to inbound relay
+            }
+
+            return Some(ContactMethod::InboundRelay(node_b_relay_id));
+        }
+    }
+
+    None
+}
+
 impl RoutingDomainDetail for PublicInternetRoutingDomainDetail {
     fn common(&self) -> &RoutingDomainDetailCommon {
         &self.common
@@ -363,95 +533,17 @@ impl RoutingDomainDetail for PublicInternetRoutingDomainDetail {
                 return ContactMethod::Direct(target_did.dial_info);
             }
 
-            // Get the target's inbound relay, it must have one or it is not reachable
-            if let Some(node_b_relay) = peer_b.signed_node_info().relay_info() {
-
-                // Note that relay_peer_info could be node_a, in which case a connection already exists
-                // and we only get here if the connection had dropped, in which case node_a is unreachable until
-                // it gets a new relay connection up
-                if peer_b.signed_node_info().relay_ids().contains_any(peer_a.node_ids()) {
-                    return ContactMethod::Existing;
-                }
-
-                // Get best node id to contact relay with
-                let Some(node_b_relay_id) = peer_b.signed_node_info().relay_ids().get(best_ck) else {
-                    // No best relay id
-                    return ContactMethod::Unreachable;
-                };
-
-                // Can node A reach the inbound relay directly?
-                if first_filtered_dial_info_detail_between_nodes(
-                    node_a,
-                    node_b_relay,
-                    &dial_info_filter,
-                    sequencing,
-                    dif_sort.clone(),
-                )
-                .is_some()
-                {
-                    // Can node A receive anything inbound ever?
-                    if matches!(node_a.network_class(), NetworkClass::InboundCapable) {
-                        ///////// Reverse connection
-
-                        // Get the best match dial info for an reverse inbound connection from node B to node A
-                        if let Some(reverse_did) = first_filtered_dial_info_detail_between_nodes(
-                            node_b,
-                            node_a,
-                            &dial_info_filter,
-                            sequencing,
-                            dif_sort.clone()
-                        ) {
-                            // Ensure we aren't on the same public IP address (no hairpin nat)
-                            if reverse_did.dial_info.ip_addr()
-                                != target_did.dial_info.ip_addr()
-                            {
-                                // Can we receive a direct reverse connection?
-                                if !reverse_did.class.requires_signal() {
-                                    return ContactMethod::SignalReverse(
-                                        node_b_relay_id,
-                                        node_b_id,
-                                    );
-                                }
-                            }
-                        }
-
-                        ///////// UDP hole-punch
-
-                        // Does node B have a direct udp dialinfo node A can reach?
-                        let udp_dial_info_filter = dial_info_filter
-                            .filtered(&DialInfoFilter::all().with_protocol_type(ProtocolType::UDP));
-                        if let Some(target_udp_did) = first_filtered_dial_info_detail_between_nodes(
-                            node_a,
-                            node_b,
-                            &udp_dial_info_filter,
-                            sequencing,
-                            dif_sort.clone()
-                        ) {
-                            // Does node A have a direct udp dialinfo that node B can reach?
-                            if let Some(reverse_udp_did) = first_filtered_dial_info_detail_between_nodes(
-                                node_b,
-                                node_a,
-                                &udp_dial_info_filter,
-                                sequencing,
-                                dif_sort.clone(),
-                            ) {
-                                // Ensure we aren't on the same public IP address (no hairpin nat)
-                                if reverse_udp_did.dial_info.ip_addr()
-                                    != target_udp_did.dial_info.ip_addr()
-                                {
-                                    // The target and ourselves have a udp dialinfo that they can reach
-                                    return ContactMethod::SignalHolePunch(
-                                        node_b_relay_id,
-                                        node_b_id,
-                                    );
-                                }
-                            }
-                        }
-                        // Otherwise we have to inbound relay
-                    }
-
-                    return ContactMethod::InboundRelay(node_b_relay_id);
-                }
+            // Signal the target, falling back to its inbound relay
+            if let Some(cm) = signal_contact_method_between_nodes(
+                peer_a,
+                peer_b,
+                &target_did,
+                best_ck,
+                dial_info_filter,
+                sequencing,
+                dif_sort.clone(),
+            ) {
+                return cm;
             }
         }
         // If the node B has no direct dial info, it needs to have an inbound relay
@@ -515,6 +607,49 @@ impl RoutingDomainDetail for PublicInternetRoutingDomainDetail {
 
         ContactMethod::Unreachable
     }
+
+    fn get_fallback_contact_method(
+        &self,
+        _rti: &RoutingTableInner,
+        peer_a: &PeerInfo,
+        peer_b: &PeerInfo,
+        dial_info_filter: DialInfoFilter,
+        sequencing: Sequencing,
+        dif_sort: Option<Arc<DialInfoDetailSort>>,
+    ) -> ContactMethod {
+        // Get the nodeinfos for convenience
+        let node_a = peer_a.signed_node_info().node_info();
+        let node_b = peer_b.signed_node_info().node_info();
+
+        // Get the node ids that would be used between these peers
+        let cck = common_crypto_kinds(&peer_a.node_ids().kinds(), &peer_b.node_ids().kinds());
+        let Some(best_ck) = cck.first().copied() else {
+            // No common crypto kinds between these nodes, can't contact
+            return ContactMethod::Unreachable;
+        };
+
+        // Only direct contact needs a fallback here, signaled contact methods already fall back to the inbound relay
+        let Some(target_did) =
+            first_filtered_dial_info_detail_between_nodes(node_a, node_b, &dial_info_filter, sequencing, dif_sort.clone())
+        else {
+            return ContactMethod::Unreachable;
+        };
+        if target_did.class.requires_signal() {
+            return ContactMethod::Unreachable;
+        }
+
+        // Continue the cascade as if the dial info needed a signal
+        signal_contact_method_between_nodes(
+            peer_a,
+            peer_b,
+            &target_did,
+            best_ck,
+            dial_info_filter,
+            sequencing,
+            dif_sort,
+        )
+        .unwrap_or(ContactMethod::Unreachable)
+    }
 }
 
 /// Local Network routing domain internals
@@ -566,7 +701,7 @@ impl RoutingDomainDetail for LocalNetworkRoutingDomainDetail {
 
     fn get_contact_method(
         &self,
-        _rti: &RoutingTableInner,
+        rti: &RoutingTableInner,
         peer_a: &PeerInfo,
         peer_b: &PeerInfo,
         dial_info_filter: DialInfoFilter,
@@ -585,10 +720,28 @@ impl RoutingDomainDetail for LocalNetworkRoutingDomainDetail {
             return ContactMethod::Unreachable;
         };
 
+        // Nodes on the same host skip the network stack entirely
+        if let Some(ipc_di) = ipc_dial_info_between_nodes(rti, peer_a, peer_b, &dial_info_filter) {
+            return ContactMethod::Direct(ipc_di);
+        }
+
         if let Some(target_did) = first_filtered_dial_info_detail_between_nodes(node_a, node_b, &dial_info_filter, sequencing, dif_sort) {
             return ContactMethod::Direct(target_did.dial_info);
         }
         
         ContactMethod::Unreachable
     }
+
+    fn get_fallback_contact_method(
+        &self,
+        _rti: &RoutingTableInner,
+        _peer_a: &PeerInfo,
+        _peer_b: &PeerInfo,
+        _dial_info_filter: DialInfoFilter,
+        _sequencing: Sequencing,
+        _dif_sort: Option<Arc<DialInfoDetailSort>>,
+    ) -> ContactMethod {
+        // There are no relays or signals on the local network to fall back to
+        ContactMethod::Unreachable
+    }
 }
@@ -0,0 +1,63 @@
+use super::*;
+
+#[derive(ThisError, Debug, Clone, PartialOrd, PartialEq, Eq, Ord)]
+#[must_use]
+pub enum RoutingTableError {
+    #[error("[RoutingTableError: OwnNodeId]")]
+    OwnNodeId,
+    #[error("[RoutingTableError: InvalidCryptoKind({0})]")]
+    InvalidCryptoKind(CryptoKind),
+    #[error("[RoutingTableError: InvalidSignedNodeInfo({0})]")]
+    InvalidSignedNodeInfo(String),
+    #[error("[RoutingTableError: TableFull({0})]")]
+    TableFull(String),
+    #[error("[RoutingTableError: Banned({0})]")]
+    Banned(TypedKey),
+    #[error("[RoutingTableError: RateLimited({0})]")]
+    RateLimited(TypedKey),
+    #[error("[RoutingTableError: Internal({0})]")]
+    Internal(String),
+}
+
+pub type RoutingTableResult<T> = Result<T, RoutingTableError>;
+
+impl RoutingTableError {
+    pub fn invalid_signed_node_info<X: ToString>(x: X) -> Self {
+        Self::InvalidSignedNodeInfo(x.to_string())
+    }
+    pub fn table_full<X: ToString>(x: X) -> Self {
+        Self::TableFull(x.to_string())
+    }
+    pub fn internal<X: ToString>(x: X) -> Self {
+        Self::Internal(x.to_string())
+    }
+    pub fn map_internal<M: ToString, X: ToString>(message: M) -> impl FnOnce(X) -> Self {
+        move |x| Self::Internal(format!("{}: {}", message.to_string(), x.to_string()))
+    }
+}
+
+impl From<RoutingTableError> for VeilidAPIError {
+    fn from(e: RoutingTableError) -> Self {
+        match e {
+            RoutingTableError::OwnNodeId => VeilidAPIError::InvalidTarget {
+                message: "own node id".to_owned(),
+            },
+            RoutingTableError::InvalidCryptoKind(kind) => VeilidAPIError::InvalidArgument {
+                context: "routing table".to_owned(),
+                argument: "crypto_kind".to_owned(),
+                value: kind.to_string(),
+            },
+            RoutingTableError::InvalidSignedNodeInfo(message) => {
+                VeilidAPIError::Generic { message }
+            }
+            RoutingTableError::TableFull(message) => VeilidAPIError::TryAgain { message },
+            RoutingTableError::Banned(node_id) => VeilidAPIError::InvalidTarget {
+                message: format!("banned node id: {}", node_id),
+            },
+            RoutingTableError::RateLimited(node_id) => VeilidAPIError::TryAgain {
+                message: format!("rate limited node id: {}", node_id),
+            },
+            RoutingTableError::Internal(message) => VeilidAPIError::Internal { message },
+        }
+    }
+}
@@ -2,8 +2,28 @@ use super::*;
 use weak_table::PtrWeakHashSet;
 
 pub const RECENT_PEERS_TABLE_SIZE: usize = 64;
+/// How far back top talkers are counted
+pub const TOP_TALKERS_WINDOW_SECS: u64 = 3600;
+
+/// Bucket index at or beyond which a node id is considered to be in our immediate keyspace neighborhood
+pub const NEAR_BUCKET_MIN_INDEX: usize = 20;
+/// How many unproven near nodes may become admission candidates per admission window
+pub const NEAR_BUCKET_ADMISSION_LIMIT: usize = 4;
+/// Window over which near node admissions are rate limited
+pub const NEAR_BUCKET_ADMISSION_WINDOW_SECS: u32 = 60;
+/// How many near nodes we hold while waiting for them to prove themselves
+pub const NEAR_BUCKET_CANDIDATE_CACHE_SIZE: usize = 64;
 
 pub type EntryCounts = BTreeMap<(RoutingDomain, CryptoKind), usize>;
+
+/// A node whose connection is kept alive after it was prewarmed
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct PrewarmedNode {
+    /// When keepalives for this node stop
+    pub expiration_ts: Timestamp,
+    /// When the last keepalive was sent to this node
+    pub last_keepalive_ts: Timestamp,
+}
 //////////////////////////////////////////////////////////////////////////
 
 /// RoutingTable rwlock-internal data
@@ -28,6 +48,16 @@ pub(crate) struct RoutingTableInner {
     pub(super) self_transfer_stats: TransferStatsDownUp,
     /// Peers we have recently communicated with
     pub(super) recent_peers: LruCache<TypedKey, RecentPeersEntry>,
+    /// Times at which unproven near nodes were accepted as admission candidates
+    pub(super) near_bucket_admissions: VecDeque<Timestamp>,
+    /// Unproven near nodes waiting for a successful round trip before they can be added to our buckets
+    pub(super) near_bucket_candidates: LruCache<TypedKey, (RoutingDomain, PeerInfo)>,
+    /// Peers we were connected to before restarting that we have not tried to reconnect to yet
+    pub(super) warm_peers: Vec<WarmPeer>,
+    /// When the top talker counts were last reset
+    pub(super) top_talkers_reset_ts: Option<Timestamp>,
+    /// Nodes that get keepalives until their prewarm grace period is over
+    pub(super) prewarmed_nodes: HashMap<TypedKey, PrewarmedNode>,
     /// Storage for private/safety RouteSpecs
     pub(super) route_spec_store: Option<RouteSpecStore>,
     /// Async tagged critical sections table
@@ -48,6 +78,11 @@ impl RoutingTableInner {
             self_transfer_stats_accounting: TransferStatsAccounting::new(),
             self_transfer_stats: TransferStatsDownUp::default(),
             recent_peers: LruCache::new(RECENT_PEERS_TABLE_SIZE),
+            near_bucket_admissions: VecDeque::new(),
+            near_bucket_candidates: LruCache::new(NEAR_BUCKET_CANDIDATE_CACHE_SIZE),
+            warm_peers: Vec::new(),
+            top_talkers_reset_ts: None,
+            prewarmed_nodes: HashMap::new(),
             route_spec_store: None,
             critical_sections: AsyncTagLockTable::new(),
         }
@@ -160,8 +195,15 @@ impl RoutingTableInner {
 
     pub fn ensure_dial_info_is_valid(&self, domain: RoutingDomain, dial_info: &DialInfo) -> bool {
         let address = dial_info.socket_address().address();
-        let can_contain_address =
-            self.with_routing_domain(domain, |rd| rd.can_contain_address(address));
+        // Onion services are only reachable over the public internet, through Tor,
+        // and IPC sockets only by processes on the same host, which share a local network
+        let can_contain_address = if dial_info.is_onion() {
+            domain == RoutingDomain::PublicInternet
+        } else if dial_info.is_ipc() {
+            domain == RoutingDomain::LocalNetwork
+        } else {
+            self.with_routing_domain(domain, |rd| rd.can_contain_address(address))
+        };
 
         if !can_contain_address {
             log_rtab!(debug "can not add dial info to this routing domain");
@@ -231,6 +273,27 @@ impl RoutingTableInner {
         })
     }
 
+    pub fn get_fallback_contact_method(
+        &self,
+        routing_domain: RoutingDomain,
+        peer_a: &PeerInfo,
+        peer_b: &PeerInfo,
+        dial_info_filter: DialInfoFilter,
+        sequencing: Sequencing,
+        dif_sort: Option<Arc<DialInfoDetailSort>>,
+    ) -> ContactMethod {
+        self.with_routing_domain(routing_domain, |rdd| {
+            rdd.get_fallback_contact_method(
+                self,
+                peer_a,
+                peer_b,
+                dial_info_filter,
+                sequencing,
+                dif_sort,
+            )
+        })
+    }
+
     pub fn reset_all_updated_since_last_network_change(&mut self) {
         let cur_ts = get_aligned_timestamp();
         self.with_entries_mut(cur_ts, BucketEntryState::Dead, |rti, v| {
@@ -297,8 +360,8 @@ impl RoutingTableInner {
             .with_dial_info_filter(dif)
     }
 
-    fn bucket_depth(bucket_index: BucketIndex) -> usize {
-        match bucket_index.1 {
+    fn bucket_depth(&self, bucket_index: BucketIndex) -> usize {
+        let depth = match bucket_index.1 {
             0 => 256,
             1 => 128,
             2 => 64,
@@ -308,6 +371,15 @@ impl RoutingTableInner {
             6 => 4,
             7 => 2,
             _ => 1,
+        };
+        // Bootstrap nodes keep as much of the network as they can to hand out to joining nodes
+        if self
+            .unlocked_inner
+            .with_config(|c| c.network.role.is_bootstrap())
+        {
+            depth * BOOTSTRAP_ROLE_BUCKET_DEPTH_MULTIPLIER
+        } else {
+            depth
         }
     }
 
@@ -394,8 +466,8 @@ impl RoutingTableInner {
     /// Attempt to settle buckets and remove entries down to the desired number
     /// which may not be possible due extant NodeRefs
     pub fn kick_bucket(&mut self, bucket_index: BucketIndex) {
+        let bucket_depth = self.bucket_depth(bucket_index);
         let bucket = self.get_bucket_mut(bucket_index);
-        let bucket_depth = Self::bucket_depth(bucket_index);
 
         if let Some(_dead_node_ids) = bucket.kick(bucket_depth) {
             // Remove expired entries
@@ -513,6 +585,8 @@ impl RoutingTableInner {
         cur_ts: Timestamp,
     ) -> Vec<NodeRef> {
         let own_node_info_ts = self.get_own_node_info_ts(routing_domain);
+        let node_info_max_age = self.unlocked_inner.node_info_max_age();
+        let verified_peers_only = self.unlocked_inner.verified_peers_only(routing_domain);
 
         // Collect all entries that are 'needs_ping' and have some node info making them reachable somehow
         let mut node_refs = Vec::<NodeRef>::with_capacity(self.bucket_entry_count());
@@ -534,6 +608,16 @@ impl RoutingTableInner {
                     return true;
                 }
 
+                // If this entry's node info is stale, ping it so it sends us its current node info
+                if e.has_stale_node_info(routing_domain, cur_ts, node_info_max_age) {
+                    return true;
+                }
+
+                // If we only pass on verified peers, ping this one so it can prove itself
+                if verified_peers_only && !e.is_verified(routing_domain) {
+                    return true;
+                }
+
                 // If this entry needs need a ping by non-routing-domain-specific metrics then do it
                 if e.needs_ping(cur_ts) {
                     return true;
@@ -585,7 +669,7 @@ impl RoutingTableInner {
         &mut self,
         entry: Arc<BucketEntry>,
         node_ids: &[TypedKey],
-    ) -> EyreResult<()> {
+    ) -> RoutingTableResult<()> {
         entry.with_mut_inner(|e| {
             let existing_node_ids = e.node_ids();
             for node_id in node_ids {
@@ -596,7 +680,10 @@ impl RoutingTableInner {
 
                 // Add new node id to entry
                 let ck = node_id.kind;
-                if let Some(old_node_id) = e.add_node_id(*node_id)? {
+                if let Some(old_node_id) = e
+                    .add_node_id(*node_id)
+                    .map_err(RoutingTableError::internal)?
+                {
                     // Remove any old node id for this crypto kind
                     if VALID_CRYPTO_KINDS.contains(&ck) {
                         let bucket_index = self.unlocked_inner.calculate_bucket_index(&old_node_id);
@@ -628,13 +715,13 @@ impl RoutingTableInner {
         outer_self: RoutingTable,
         node_ids: &TypedKeyGroup,
         update_func: F,
-    ) -> EyreResult<NodeRef>
+    ) -> RoutingTableResult<NodeRef>
     where
         F: FnOnce(&mut RoutingTableInner, &mut BucketEntryInner),
     {
         // Ensure someone isn't trying register this node itself
         if self.unlocked_inner.matches_own_node_id(node_ids) {
-            bail!("can't register own node");
+            return Err(RoutingTableError::OwnNodeId);
         }
 
         // Look up all bucket entries and make sure we only have zero or one
@@ -663,9 +750,10 @@ impl RoutingTableInner {
         // If the entry does exist already, update it
         if let Some(best_entry) = best_entry {
             // Update the entry with all of the node ids
-            if let Err(e) = self.update_bucket_entries(best_entry.clone(), node_ids) {
-                bail!("Not registering new ids for existing node: {}", e);
-            }
+            self.update_bucket_entries(best_entry.clone(), node_ids)
+                .map_err(RoutingTableError::map_internal(
+                    "Not registering new ids for existing node",
+                ))?;
 
             // Make a noderef to return
             let nr = NodeRef::new(outer_self.clone(), best_entry.clone(), None);
@@ -686,9 +774,8 @@ impl RoutingTableInner {
         self.unlocked_inner.kick_queue.lock().insert(bucket_entry);
 
         // Update the other bucket entries with the remaining node ids
-        if let Err(e) = self.update_bucket_entries(new_entry.clone(), node_ids) {
-            bail!("Not registering new node: {}", e);
-        }
+        self.update_bucket_entries(new_entry.clone(), node_ids)
+            .map_err(RoutingTableError::map_internal("Not registering new node"))?;
 
         // Make node ref to return
         let nr = NodeRef::new(outer_self.clone(), new_entry.clone(), None);
@@ -707,7 +794,7 @@ impl RoutingTableInner {
         &self,
         outer_self: RoutingTable,
         node_id_key: PublicKey,
-    ) -> EyreResult<Option<NodeRef>> {
+    ) -> RoutingTableResult<Option<NodeRef>> {
         for ck in VALID_CRYPTO_KINDS {
             if let Some(nr) =
                 self.lookup_node_ref(outer_self.clone(), TypedKey::new(ck, node_id_key))?
@@ -723,12 +810,12 @@ impl RoutingTableInner {
         &self,
         outer_self: RoutingTable,
         node_id: TypedKey,
-    ) -> EyreResult<Option<NodeRef>> {
+    ) -> RoutingTableResult<Option<NodeRef>> {
         if self.unlocked_inner.matches_own_node_id(&[node_id]) {
-            bail!("can't look up own node id in routing table");
+            return Err(RoutingTableError::OwnNodeId);
         }
         if !VALID_CRYPTO_KINDS.contains(&node_id.kind) {
-            bail!("can't look up node id with invalid crypto kind");
+            return Err(RoutingTableError::InvalidCryptoKind(node_id.kind));
         }
 
         let bucket_index = self.unlocked_inner.calculate_bucket_index(&node_id);
@@ -745,7 +832,7 @@ impl RoutingTableInner {
         node_id: TypedKey,
         routing_domain_set: RoutingDomainSet,
         dial_info_filter: DialInfoFilter,
-    ) -> EyreResult<Option<NodeRef>> {
+    ) -> RoutingTableResult<Option<NodeRef>> {
         let nr = self.lookup_node_ref(outer_self, node_id)?;
         Ok(nr.map(|nr| {
             nr.filtered_clone(
@@ -783,43 +870,82 @@ impl RoutingTableInner {
         routing_domain: RoutingDomain,
         peer_info: PeerInfo,
         allow_invalid: bool,
-    ) -> EyreResult<NodeRef> {
+    ) -> RoutingTableResult<NodeRef> {
+        self.register_node_with_peer_info_inner(
+            outer_self,
+            routing_domain,
+            peer_info,
+            allow_invalid,
+            false,
+        )
+    }
+
+    /// Add a node to our routing table that has completed a round trip with us, bypassing
+    /// the proof requirement for nodes in our nearest buckets
+    pub fn register_proven_node_with_peer_info(
+        &mut self,
+        outer_self: RoutingTable,
+        routing_domain: RoutingDomain,
+        peer_info: PeerInfo,
+    ) -> RoutingTableResult<NodeRef> {
+        self.near_bucket_candidates.remove(&peer_info.node_ids()[0]);
+        self.register_node_with_peer_info_inner(outer_self, routing_domain, peer_info, false, true)
+    }
+
+    fn register_node_with_peer_info_inner(
+        &mut self,
+        outer_self: RoutingTable,
+        routing_domain: RoutingDomain,
+        peer_info: PeerInfo,
+        allow_invalid: bool,
+        proven: bool,
+    ) -> RoutingTableResult<NodeRef> {
         // if our own node is in the list, then ignore it as we don't add ourselves to our own routing table
         if self
             .unlocked_inner
             .matches_own_node_id(peer_info.node_ids())
         {
-            bail!("can't register own node id in routing table");
+            return Err(RoutingTableError::OwnNodeId);
         }
 
         // node can not be its own relay
         let rids = peer_info.signed_node_info().relay_ids();
         let nids = peer_info.node_ids();
         if nids.contains_any(&rids) {
-            bail!("node can not be its own relay");
+            return Err(RoutingTableError::invalid_signed_node_info(
+                "node can not be its own relay",
+            ));
         }
 
         if !allow_invalid {
             // verify signature
             if !peer_info.signed_node_info().has_any_signature() {
-                bail!(
+                return Err(RoutingTableError::invalid_signed_node_info(format!(
                     "signed node info for {:?} has no valid signature",
                     peer_info.node_ids()
-                );
+                )));
             }
             // verify signed node info is valid in this routing domain
             if !self.signed_node_info_is_valid_in_routing_domain(
                 routing_domain,
                 peer_info.signed_node_info(),
             ) {
-                bail!(
+                return Err(RoutingTableError::invalid_signed_node_info(format!(
                     "signed node info for {:?} not valid in the {:?} routing domain",
                     peer_info.node_ids(),
                     routing_domain
-                );
+                )));
             }
         }
 
+        // Nodes claiming to be extremely close to us must return a receipt to us before they
+        // can occupy our nearest buckets, to make eclipsing our keyspace neighborhood harder.
+        // Until then they can still be contacted through a noderef that is not in any bucket.
+        if !allow_invalid && !proven && self.is_unknown_near_node(peer_info.node_ids()) {
+            self.add_near_bucket_candidate(routing_domain, peer_info.clone())?;
+            return self.make_detached_node_ref(outer_self, routing_domain, peer_info);
+        }
+
         // Register relay info first if we have that and the relay isn't us
         if let Some(relay_peer_info) = peer_info.signed_node_info().relay_peer_info() {
             if !self
@@ -847,6 +973,340 @@ impl RoutingTableInner {
         Ok(nr)
     }
 
+    /// Returns true if none of these node ids are in our routing table and any of them
+    /// would land in one of our nearest buckets
+    fn is_unknown_near_node(&self, node_ids: &TypedKeyGroup) -> bool {
+        let mut near = false;
+        for node_id in node_ids.iter() {
+            if !VALID_CRYPTO_KINDS.contains(&node_id.kind) {
+                continue;
+            }
+            let bucket_index = self.unlocked_inner.calculate_bucket_index(node_id);
+            if self
+                .get_bucket(bucket_index)
+                .entry(&node_id.value)
+                .is_some()
+            {
+                return false;
+            }
+            if bucket_index.1 >= NEAR_BUCKET_MIN_INDEX {
+                near = true;
+            }
+        }
+        near
+    }
+
+    /// Hold an unproven near node until it can be validated, subject to an admission rate limit
+    fn add_near_bucket_candidate(
+        &mut self,
+        routing_domain: RoutingDomain,
+        peer_info: PeerInfo,
+    ) -> RoutingTableResult<()> {
+        let node_id = peer_info.node_ids()[0];
+        if self.near_bucket_candidates.contains_key(&node_id) {
+            return Ok(());
+        }
+
+        let cur_ts = get_aligned_timestamp();
+        let window =
+            TimestampDuration::new(NEAR_BUCKET_ADMISSION_WINDOW_SECS as u64 * 1_000_000u64);
+        while let Some(ts) = self.near_bucket_admissions.front() {
+            if cur_ts.saturating_sub(*ts) < window {
+                break;
+            }
+            self.near_bucket_admissions.pop_front();
+        }
+        if self.near_bucket_admissions.len() >= NEAR_BUCKET_ADMISSION_LIMIT {
+            return Err(RoutingTableError::RateLimited(node_id));
+        }
+        self.near_bucket_admissions.push_back(cur_ts);
+
+        log_rtab!(debug "Near node admission candidate: {}", node_id);
+        self.near_bucket_candidates
+            .insert(node_id, (routing_domain, peer_info));
+        Ok(())
+    }
+
+    /// Record that we looked up a key in a bucket's range of the keyspace
+    pub fn touch_bucket_lookup(&mut self, bucket_index: BucketIndex, cur_ts: Timestamp) {
+        self.get_bucket_mut(bucket_index).touch_lookup(cur_ts);
+    }
+
+    /// Get up to `count` buckets we have not looked up a key in for `max_age`, oldest first.
+    /// Buckets nearer to us than our nearest known node are skipped, as a lookup there can
+    /// only find the nodes we already have.
+    pub fn get_stale_buckets(
+        &self,
+        cur_ts: Timestamp,
+        max_age: TimestampDuration,
+        count: usize,
+    ) -> Vec<BucketIndex> {
+        let mut stale = Vec::new();
+        for (ck, buckets) in &self.buckets {
+            let Some(nearest) = buckets.iter().rposition(|b| !b.is_empty()) else {
+                continue;
+            };
+            for (index, bucket) in buckets.iter().enumerate().take(nearest + 1) {
+                let last_lookup_ts = bucket.last_lookup_ts();
+                if cur_ts.saturating_sub(last_lookup_ts) >= max_age {
+                    stale.push((last_lookup_ts, (*ck, index)));
+                }
+            }
+        }
+        stale.sort();
+        stale
+            .into_iter()
+            .take(count)
+            .map(|(_, bucket_index)| bucket_index)
+            .collect()
+    }
+
+    /// Remove up to `count` near bucket candidates for validation
+    pub fn take_near_bucket_candidates(&mut self, count: usize) -> Vec<(RoutingDomain, PeerInfo)> {
+        let mut out = Vec::with_capacity(count);
+        while out.len() < count {
+            let Some((_, v)) = self.near_bucket_candidates.remove_lru() else {
+                break;
+            };
+            out.push(v);
+        }
+        out
+    }
+
+    /// Get the peers we most recently had a flow to, along with the kind of dial info that flow used.
+    /// Peers we only have inbound flows from are skipped, as there is nothing for us to dial.
+    /// Warm peers from before our last restart that we have not reconnected to yet are kept.
+    pub fn collect_warm_peers(&self, count: usize) -> Vec<WarmPeer> {
+        let mut warm_peers = Vec::new();
+        for entry in self.all_entries.iter() {
+            let opt_warm_peer = entry.with_inner(|e| {
+                let node_id = e.node_ids().best()?;
+                let (flow, last_success_ts) = e.latest_flow()?;
+                let routing_domain =
+                    self.routing_domain_for_address(flow.remote_address().address())?;
+                let dialable = e
+                    .node_info(routing_domain)?
+                    .dial_info_detail_list()
+                    .iter()
+                    .any(|did| {
+                        did.dial_info.protocol_type() == flow.protocol_type()
+                            && did.dial_info.socket_address() == *flow.remote_address()
+                    });
+                if !dialable {
+                    return None;
+                }
+                Some(WarmPeer {
+                    node_id,
+                    protocol_type: flow.protocol_type(),
+                    address_type: flow.address_type(),
+                    last_success_ts,
+                })
+            });
+            warm_peers.extend(opt_warm_peer);
+        }
+        for warm_peer in &self.warm_peers {
+            if !warm_peers.iter().any(|wp| wp.node_id == warm_peer.node_id) {
+                warm_peers.push(warm_peer.clone());
+            }
+        }
+        warm_peers.sort_by(|a, b| b.last_success_ts.cmp(&a.last_success_ts));
+        warm_peers.truncate(count);
+        warm_peers
+    }
+
+    /// Remove the warm peers loaded at startup so they can be reconnected to
+    pub fn take_warm_peers(&mut self) -> Vec<WarmPeer> {
+        core::mem::take(&mut self.warm_peers)
+    }
+
+    /// Make a noderef to a node that is not in our routing table, so it can be contacted
+    /// without being admitted to a bucket
+    pub fn make_detached_node_ref(
+        &self,
+        outer_self: RoutingTable,
+        routing_domain: RoutingDomain,
+        peer_info: PeerInfo,
+    ) -> RoutingTableResult<NodeRef> {
+        let (node_ids, signed_node_info) = peer_info.destructure();
+        let Some(first_node_id) = node_ids
+            .iter()
+            .find(|nid| VALID_CRYPTO_KINDS.contains(&nid.kind))
+            .copied()
+        else {
+            return Err(RoutingTableError::InvalidCryptoKind(
+                node_ids
+                    .first()
+                    .map(|nid| nid.kind)
+                    .unwrap_or_else(best_crypto_kind),
+            ));
+        };
+        let entry = Arc::new(BucketEntry::new(first_node_id));
+        entry.with_mut_inner(|e| -> RoutingTableResult<()> {
+            for node_id in node_ids.iter() {
+                e.add_node_id(*node_id)
+                    .map_err(RoutingTableError::internal)?;
+            }
+            e.update_signed_node_info(routing_domain, signed_node_info);
+            Ok(())
+        })?;
+
+        Ok(NodeRef::new(
+            outer_self,
+            entry,
+            Some(NodeRefFilter::new().with_routing_domain(routing_domain)),
+        ))
+    }
+
+    /// Number of entries closer to our node id than a uniform keyspace distribution would place them.
+    /// A large value suggests that our keyspace neighborhood is being flooded.
+    pub fn near_bucket_skew(&self, kind: CryptoKind) -> usize {
+        let Some(buckets) = self.buckets.get(&kind) else {
+            return 0;
+        };
+        let total: usize = buckets.iter().map(|b| b.entries().count()).sum();
+        if total == 0 {
+            return 0;
+        }
+        // With this many uniformly distributed node ids, all buckets at or beyond
+        // this index are expected to hold about one entry between them
+        let expected_depth = (usize::BITS - total.leading_zeros()) as usize;
+        let near: usize = buckets
+            .iter()
+            .skip(expected_depth)
+            .map(|b| b.entries().count())
+            .sum();
+        near.saturating_sub(1)
+    }
+
+    /// Entry counts by state and average entry age for every bucket, per crypto kind
+    pub fn get_bucket_statistics(&self, cur_ts: Timestamp) -> Vec<BucketStatistics> {
+        let mut out = Vec::with_capacity(VALID_CRYPTO_KINDS.len());
+        for ck in VALID_CRYPTO_KINDS {
+            let Some(buckets) = self.buckets.get(&ck) else {
+                continue;
+            };
+            let buckets = buckets
+                .iter()
+                .enumerate()
+                .map(|(index, bucket)| {
+                    let mut stats = BucketStats {
+                        index: index as u16,
+                        ..Default::default()
+                    };
+                    let mut total_age = 0u64;
+                    for (_k, entry) in bucket.entries() {
+                        entry.with(self, |_rti, e| {
+                            match e.state(cur_ts) {
+                                BucketEntryState::Reliable => stats.reliable_entry_count += 1,
+                                BucketEntryState::Unreliable => stats.unreliable_entry_count += 1,
+                                BucketEntryState::Dead => stats.dead_entry_count += 1,
+                            }
+                            total_age += cur_ts.saturating_sub(e.peer_stats().time_added).as_u64();
+                        });
+                    }
+                    let entry_count = stats.reliable_entry_count
+                        + stats.unreliable_entry_count
+                        + stats.dead_entry_count;
+                    if entry_count > 0 {
+                        stats.average_entry_age =
+                            TimestampDuration::new(total_age / entry_count as u64);
+                    }
+                    stats
+                })
+                .collect();
+            out.push(BucketStatistics {
+                kind: ck,
+                near_bucket_skew: self.near_bucket_skew(ck) as u32,
+                buckets,
+            });
+        }
+        out
+    }
+
+    /// The peers we exchanged the most bytes and the most RPC messages with, at most `count` of each
+    pub fn get_top_talkers(&self, count: usize, cur_ts: Timestamp) -> TopTalkers {
+        let mut talkers = Vec::new();
+        self.with_entries(cur_ts, BucketEntryState::Dead, |_rti, entry| {
+            entry.with_inner(|e| {
+                let tc = e.talker_count();
+                if tc.rpc_messages_rcvd + tc.rpc_messages_sent != 0 {
+                    talkers.push(TopTalker {
+                        node_ids: e.node_ids().iter().copied().collect(),
+                        bytes_down: tc.bytes_down,
+                        bytes_up: tc.bytes_up,
+                        rpc_messages_rcvd: tc.rpc_messages_rcvd,
+                        rpc_messages_sent: tc.rpc_messages_sent,
+                    });
+                }
+            });
+            Option::<()>::None
+        });
+
+        let mut by_bytes = talkers.clone();
+        by_bytes.sort_by_key(|t| core::cmp::Reverse(t.bytes_down + t.bytes_up));
+        by_bytes.truncate(count);
+        let mut by_rpc_count = talkers;
+        by_rpc_count.sort_by_key(|t| core::cmp::Reverse(t.rpc_messages_rcvd + t.rpc_messages_sent));
+        by_rpc_count.truncate(count);
+
+        // Counts cover the last hour, or less if they were reset within it
+        let window_start_ts = cur_ts.saturating_sub(TimestampDuration::new(
+            TOP_TALKERS_WINDOW_SECS * 1_000_000u64,
+        ));
+        let since = match self.top_talkers_reset_ts {
+            Some(reset_ts) if reset_ts > window_start_ts => reset_ts,
+            _ => window_start_ts,
+        };
+
+        TopTalkers {
+            since,
+            by_bytes,
+            by_rpc_count,
+        }
+    }
+
+    /// Keep the connection to a node alive until the expiration time
+    /// Prewarming a node again only ever extends its grace period
+    pub fn prewarm_node(&mut self, node_id: TypedKey, cur_ts: Timestamp, expiration_ts: Timestamp) {
+        let prewarmed_node = self
+            .prewarmed_nodes
+            .entry(node_id)
+            .or_insert(PrewarmedNode {
+                expiration_ts,
+                last_keepalive_ts: cur_ts,
+            });
+        prewarmed_node.expiration_ts.max_assign(expiration_ts);
+    }
+
+    /// Forget prewarmed nodes whose grace period is over and return the ones due a keepalive
+    /// The returned nodes are counted as having had their keepalive sent
+    pub fn take_prewarm_keepalives(
+        &mut self,
+        cur_ts: Timestamp,
+        keepalive_interval: TimestampDuration,
+    ) -> Vec<TypedKey> {
+        self.prewarmed_nodes
+            .retain(|_, prewarmed_node| prewarmed_node.expiration_ts > cur_ts);
+
+        let mut out = Vec::new();
+        for (node_id, prewarmed_node) in self.prewarmed_nodes.iter_mut() {
+            if cur_ts.saturating_sub(prewarmed_node.last_keepalive_ts) >= keepalive_interval {
+                prewarmed_node.last_keepalive_ts = cur_ts;
+                out.push(*node_id);
+            }
+        }
+        out
+    }
+
+    /// Start counting top talkers again from zero
+    pub fn reset_top_talkers(&mut self, cur_ts: Timestamp) {
+        self.with_entries_mut(cur_ts, BucketEntryState::Dead, |rti, entry| {
+            entry.with_mut(rti, |_rti, e| e.reset_talker_stats());
+            Option::<()>::None
+        });
+        self.top_talkers_reset_ts = Some(cur_ts);
+    }
+
     /// Shortcut function to add a node to our routing table if it doesn't exist
     /// and add the last peer address we have for it, since that's pretty common
     pub fn register_node_with_existing_connection(
@@ -855,7 +1315,7 @@ impl RoutingTableInner {
         node_id: TypedKey,
         flow: Flow,
         timestamp: Timestamp,
-    ) -> EyreResult<NodeRef> {
+    ) -> RoutingTableResult<NodeRef> {
         let nr = self.create_node_ref(outer_self, &TypedKeyGroup::from(node_id), |_rti, e| {
             // this node is live because it literally just connected to us
             e.touch_last_seen(timestamp);
@@ -872,22 +1332,50 @@ impl RoutingTableInner {
         let mut reliable_entry_count: usize = 0;
         let mut unreliable_entry_count: usize = 0;
         let mut dead_entry_count: usize = 0;
+        let mut crypto_kinds: Vec<CryptoKindHealth> = VALID_CRYPTO_KINDS
+            .iter()
+            .map(|ck| CryptoKindHealth {
+                kind: *ck,
+                reliable_entry_count: 0,
+                unreliable_entry_count: 0,
+                dead_entry_count: 0,
+                viable: false,
+            })
+            .collect();
 
         let cur_ts = get_aligned_timestamp();
         for entry in self.all_entries.iter() {
-            match entry.with_inner(|e| e.state(cur_ts)) {
+            let (state, entry_crypto_kinds) =
+                entry.with_inner(|e| (e.state(cur_ts), e.crypto_kinds()));
+            // Count the entry for each kind it has a node id for
+            let kind_healths = crypto_kinds
+                .iter_mut()
+                .filter(|ckh| entry_crypto_kinds.contains(&ckh.kind));
+            match state {
                 BucketEntryState::Reliable => {
                     reliable_entry_count += 1;
+                    kind_healths.for_each(|ckh| ckh.reliable_entry_count += 1);
                 }
                 BucketEntryState::Unreliable => {
                     unreliable_entry_count += 1;
+                    kind_healths.for_each(|ckh| ckh.unreliable_entry_count += 1);
                 }
                 BucketEntryState::Dead => {
                     dead_entry_count += 1;
+                    kind_healths.for_each(|ckh| ckh.dead_entry_count += 1);
                 }
             }
         }
 
+        // A crypto kind needs as many live peers as a weak attachment to perform lookups with it
+        let min_viable_entry_count = self
+            .unlocked_inner
+            .with_config(|c| c.network.routing_table.limit_attached_weak);
+        for ckh in crypto_kinds.iter_mut() {
+            ckh.viable =
+                ckh.reliable_entry_count + ckh.unreliable_entry_count >= min_viable_entry_count;
+        }
+
         // Public internet routing domain is ready for app use,
         // when we have proper dialinfo/networkclass
         let public_internet_ready = !matches!(
@@ -906,6 +1394,11 @@ impl RoutingTableInner {
 
         let live_entry_counts = self.cached_entry_counts();
 
+        let near_bucket_skew = VALID_CRYPTO_KINDS
+            .iter()
+            .map(|ck| (*ck, self.near_bucket_skew(*ck)))
+            .collect();
+
         RoutingTableHealth {
             reliable_entry_count,
             unreliable_entry_count,
@@ -913,7 +1406,38 @@ impl RoutingTableInner {
             live_entry_counts,
             public_internet_ready,
             local_network_ready,
+            near_bucket_skew,
+            crypto_kinds,
+        }
+    }
+
+    /// Estimate how far our clock is behind the network, in microseconds
+    ///
+    /// Takes the median of the clock skew estimates of all live peers, so a few peers
+    /// with badly set clocks do not move the result. Returns None if no peer has an estimate yet.
+    pub fn get_clock_skew(&self) -> Option<i64> {
+        self.get_clock_skew_with_peer_count()
+            .map(|(clock_skew, _)| clock_skew)
+    }
+
+    /// Estimate how far our clock is behind the network, along with how many peers the estimate comes from
+    pub fn get_clock_skew_with_peer_count(&self) -> Option<(i64, usize)> {
+        let mut clock_skews = Vec::new();
+        self.with_entries(
+            get_aligned_timestamp(),
+            BucketEntryState::Unreliable,
+            |_rti, entry| {
+                if let Some(clock_skew) = entry.with_inner(|e| e.peer_stats().clock_skew) {
+                    clock_skews.push(clock_skew);
+                }
+                Option::<()>::None
+            },
+        );
+        if clock_skews.is_empty() {
+            return None;
         }
+        clock_skews.sort_unstable();
+        Some((clock_skews[clock_skews.len() / 2], clock_skews.len()))
     }
 
     pub fn touch_recent_peer(&mut self, node_id: TypedKey, last_connection: Flow) {
@@ -1166,6 +1690,8 @@ impl RoutingTableInner {
         ) as RoutingTableEntryFilter;
         filters.push_front(filter);
 
+        let node_info_max_age = self.unlocked_inner.node_info_max_age();
+
         // Closest sort
         // Distance is done using the node id's distance metric which may vary based on crypto system
         let sort = |_rti: &RoutingTableInner,
@@ -1197,6 +1723,25 @@ impl RoutingTableInner {
                 }
             }
 
+            // nodes with fresh node info come before nodes whose node info is stale
+            let sa = a_entry.as_ref().map_or(false, |x| {
+                x.with_inner(|x| {
+                    x.has_stale_node_info(RoutingDomain::PublicInternet, cur_ts, node_info_max_age)
+                })
+            });
+            let sb = b_entry.as_ref().map_or(false, |x| {
+                x.with_inner(|x| {
+                    x.has_stale_node_info(RoutingDomain::PublicInternet, cur_ts, node_info_max_age)
+                })
+            });
+            if sa != sb {
+                if sb {
+                    return core::cmp::Ordering::Less;
+                } else {
+                    return core::cmp::Ordering::Greater;
+                }
+            }
+
             // get keys
             let a_key = if let Some(a_entry) = a_entry {
                 a_entry.with_inner(|e| e.node_ids().get(crypto_kind).unwrap())
@@ -11,16 +11,126 @@ const ROLLING_LATENCIES_SIZE: usize = 10;
 const ROLLING_TRANSFERS_SIZE: usize = 10;
 pub const ROLLING_TRANSFERS_INTERVAL_SECS: u32 = 1;
 
+// Transfer windows summarize transfers over longer durations
+// - Size is number of intervals in the window
+// - Interval is number of seconds in each interval
+const MINUTE_TRANSFER_WINDOW_SIZE: usize = 60;
+const MINUTE_TRANSFER_WINDOW_INTERVAL_SECS: u64 = 1;
+const HOUR_TRANSFER_WINDOW_SIZE: usize = 60;
+const HOUR_TRANSFER_WINDOW_INTERVAL_SECS: u64 = 60;
+const DAY_TRANSFER_WINDOW_SIZE: usize = 24;
+const DAY_TRANSFER_WINDOW_INTERVAL_SECS: u64 = 3600;
+
+// Talker windows count the traffic with a peer over the last hour for ranking peers
+// - Size is number of intervals in the window
+// - Interval is number of seconds in each interval
+const TALKER_WINDOW_SIZE: usize = 60;
+const TALKER_WINDOW_INTERVAL_SECS: u64 = 60;
+
+// Clock skew entry is per status answer that returned timestamps
+// - Size is number of entries
+const ROLLING_CLOCK_SKEWS_SIZE: usize = 10;
+
+// Bandwidth samples are the bytes moved with a peer in each rolling transfer interval
+// - Intervals moving fewer bytes than this are too idle to say anything about the link
+// - Decay is the fraction of the gap closed when a sample is lower than the estimate
+const MIN_BANDWIDTH_SAMPLE_BYTES: u64 = 16384;
+const BANDWIDTH_ESTIMATE_DECAY: u64 = 8;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct TransferCount {
     down: ByteCount,
     up: ByteCount,
 }
 
+#[derive(Debug, Clone)]
+struct TransferWindowAccounting {
+    interval: TimestampDuration,
+    size: usize,
+    rolling_intervals: VecDeque<(TransferCount, TimestampDuration)>,
+    current_interval: TransferCount,
+    current_duration: TimestampDuration,
+}
+
+impl TransferWindowAccounting {
+    fn new(size: usize, interval_secs: u64) -> Self {
+        Self {
+            interval: TimestampDuration::new(interval_secs * 1_000_000u64),
+            size,
+            rolling_intervals: VecDeque::new(),
+            current_interval: TransferCount::default(),
+            current_duration: TimestampDuration::default(),
+        }
+    }
+
+    fn add_transfer(&mut self, transfer: TransferCount, duration: TimestampDuration) {
+        self.current_interval.down += transfer.down;
+        self.current_interval.up += transfer.up;
+        self.current_duration += duration;
+        if self.current_duration < self.interval {
+            return;
+        }
+
+        while self.rolling_intervals.len() >= self.size {
+            self.rolling_intervals.pop_front();
+        }
+        self.rolling_intervals
+            .push_back((self.current_interval, self.current_duration));
+        self.current_interval = TransferCount::default();
+        self.current_duration = TimestampDuration::default();
+    }
+
+    fn transfer_stats(&self) -> TransferStatsDownUp {
+        let mut transfer_stats = TransferStatsDownUp::default();
+        transfer_stats.down.total = self.current_interval.down;
+        transfer_stats.up.total = self.current_interval.up;
+        for (xfer, _) in &self.rolling_intervals {
+            transfer_stats.down.total += xfer.down;
+            transfer_stats.up.total += xfer.up;
+        }
+
+        // Rates come from the finished intervals, or from the current one until an interval has finished
+        let mut intervals: Vec<(TransferCount, TimestampDuration)> =
+            self.rolling_intervals.iter().copied().collect();
+        if intervals.is_empty() && self.current_duration.as_u64() != 0 {
+            intervals.push((self.current_interval, self.current_duration));
+        }
+        if intervals.is_empty() {
+            return transfer_stats;
+        }
+
+        transfer_stats.down.minimum = u64::MAX.into();
+        transfer_stats.up.minimum = u64::MAX.into();
+        for (xfer, dur) in &intervals {
+            let bpsd = xfer.down * 1_000_000u64 / *dur;
+            let bpsu = xfer.up * 1_000_000u64 / *dur;
+            transfer_stats.down.maximum.max_assign(bpsd);
+            transfer_stats.up.maximum.max_assign(bpsu);
+            transfer_stats.down.minimum.min_assign(bpsd);
+            transfer_stats.up.minimum.min_assign(bpsu);
+            transfer_stats.down.average += bpsd;
+            transfer_stats.up.average += bpsu;
+        }
+        let len = intervals.len() as u64;
+        transfer_stats.down.average /= len;
+        transfer_stats.up.average /= len;
+
+        transfer_stats
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TransferWindowsAccounting {
+    minute: TransferWindowAccounting,
+    hour: TransferWindowAccounting,
+    day: TransferWindowAccounting,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct TransferStatsAccounting {
     rolling_transfers: VecDeque<TransferCount>,
     current_transfer: TransferCount,
+    windows: Option<Box<TransferWindowsAccounting>>,
 }
 
 impl TransferStatsAccounting {
@@ -28,9 +138,51 @@ impl TransferStatsAccounting {
         Self {
             rolling_transfers: VecDeque::new(),
             current_transfer: TransferCount::default(),
+            windows: None,
+        }
+    }
+
+    /// Also keep the transfers over the last minute, hour and day
+    /// Only used where the longer windows are reported since they take more memory to keep
+    pub fn new_with_windows() -> Self {
+        Self {
+            rolling_transfers: VecDeque::new(),
+            current_transfer: TransferCount::default(),
+            windows: Some(Box::new(TransferWindowsAccounting {
+                minute: TransferWindowAccounting::new(
+                    MINUTE_TRANSFER_WINDOW_SIZE,
+                    MINUTE_TRANSFER_WINDOW_INTERVAL_SECS,
+                ),
+                hour: TransferWindowAccounting::new(
+                    HOUR_TRANSFER_WINDOW_SIZE,
+                    HOUR_TRANSFER_WINDOW_INTERVAL_SECS,
+                ),
+                day: TransferWindowAccounting::new(
+                    DAY_TRANSFER_WINDOW_SIZE,
+                    DAY_TRANSFER_WINDOW_INTERVAL_SECS,
+                ),
+            })),
         }
     }
 
+    /// Get the transfer statistics over the last minute, hour and day
+    /// All zero if this accounting does not keep the longer windows
+    pub fn transfer_windows(&self) -> TransferStatsWindows {
+        let Some(windows) = &self.windows else {
+            return TransferStatsWindows::default();
+        };
+        TransferStatsWindows {
+            minute: windows.minute.transfer_stats(),
+            hour: windows.hour.transfer_stats(),
+            day: windows.day.transfer_stats(),
+        }
+    }
+
+    /// The bytes transferred so far in the current interval, before it is rolled
+    pub fn current_transfer(&self) -> TransferCount {
+        self.current_transfer
+    }
+
     pub fn add_down(&mut self, bytes: ByteCount) {
         self.current_transfer.down += bytes;
     }
@@ -51,6 +203,13 @@ impl TransferStatsAccounting {
         }
         self.rolling_transfers.push_back(self.current_transfer);
 
+        if let Some(windows) = &mut self.windows {
+            let duration = cur_ts.saturating_sub(last_ts);
+            windows.minute.add_transfer(self.current_transfer, duration);
+            windows.hour.add_transfer(self.current_transfer, duration);
+            windows.day.add_transfer(self.current_transfer, duration);
+        }
+
         transfer_stats.down.total += self.current_transfer.down;
         transfer_stats.up.total += self.current_transfer.up;
 
@@ -78,6 +237,79 @@ impl TransferStatsAccounting {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TalkerCount {
+    pub bytes_down: ByteCount,
+    pub bytes_up: ByteCount,
+    pub rpc_messages_rcvd: u64,
+    pub rpc_messages_sent: u64,
+}
+
+impl TalkerCount {
+    fn add(&mut self, other: &TalkerCount) {
+        self.bytes_down += other.bytes_down;
+        self.bytes_up += other.bytes_up;
+        self.rpc_messages_rcvd += other.rpc_messages_rcvd;
+        self.rpc_messages_sent += other.rpc_messages_sent;
+    }
+}
+
+/// Counts the bytes and RPC messages exchanged with a peer over the last hour
+/// Kept apart from the transfer statistics so it can be reset without losing them
+#[derive(Debug, Clone, Default)]
+pub struct TalkerStatsAccounting {
+    rolling_intervals: VecDeque<TalkerCount>,
+    current_interval: TalkerCount,
+    current_duration: TimestampDuration,
+}
+
+impl TalkerStatsAccounting {
+    pub fn new() -> Self {
+        Self {
+            rolling_intervals: VecDeque::new(),
+            current_interval: TalkerCount::default(),
+            current_duration: TimestampDuration::default(),
+        }
+    }
+
+    pub fn add_rpc_rcvd(&mut self, bytes: ByteCount) {
+        self.current_interval.bytes_down += bytes;
+        self.current_interval.rpc_messages_rcvd += 1;
+    }
+
+    pub fn add_rpc_sent(&mut self, bytes: ByteCount) {
+        self.current_interval.bytes_up += bytes;
+        self.current_interval.rpc_messages_sent += 1;
+    }
+
+    pub fn roll_talkers(&mut self, last_ts: Timestamp, cur_ts: Timestamp) {
+        self.current_duration += cur_ts.saturating_sub(last_ts);
+        if self.current_duration.as_u64() < TALKER_WINDOW_INTERVAL_SECS * 1_000_000u64 {
+            return;
+        }
+        while self.rolling_intervals.len() >= TALKER_WINDOW_SIZE {
+            self.rolling_intervals.pop_front();
+        }
+        self.rolling_intervals.push_back(self.current_interval);
+        self.current_interval = TalkerCount::default();
+        self.current_duration = TimestampDuration::default();
+    }
+
+    /// The counts over the last hour, including the interval in progress
+    pub fn talker_count(&self) -> TalkerCount {
+        let mut count = self.current_interval;
+        for interval in &self.rolling_intervals {
+            count.add(interval);
+        }
+        count
+    }
+
+    /// Forget everything counted so far
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct LatencyStatsAccounting {
     rolling_latencies: VecDeque<TimestampDuration>,
@@ -112,3 +344,66 @@ impl LatencyStatsAccounting {
         ls
     }
 }
+
+#[derive(Debug, Clone, Default)]
+pub struct ClockSkewAccounting {
+    rolling_clock_skews: VecDeque<i64>,
+}
+
+impl ClockSkewAccounting {
+    pub fn new() -> Self {
+        Self {
+            rolling_clock_skews: VecDeque::new(),
+        }
+    }
+
+    /// Record a clock skew sample in microseconds and return the current estimate
+    ///
+    /// The median is used so a single round trip with lopsided network delay does not skew the estimate.
+    pub fn record_clock_skew(&mut self, clock_skew: i64) -> i64 {
+        while self.rolling_clock_skews.len() >= ROLLING_CLOCK_SKEWS_SIZE {
+            self.rolling_clock_skews.pop_front();
+        }
+        self.rolling_clock_skews.push_back(clock_skew);
+
+        let mut sorted: Vec<i64> = self.rolling_clock_skews.iter().copied().collect();
+        sorted.sort_unstable();
+        sorted[sorted.len() / 2]
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BandwidthEstimateAccounting {
+    estimate: Option<ByteCount>,
+}
+
+impl BandwidthEstimateAccounting {
+    pub fn new() -> Self {
+        Self { estimate: None }
+    }
+
+    /// Record the bytes moved in one rolling transfer interval and return the current estimate in bytes per second
+    ///
+    /// Passive traffic only shows a lower bound on what a link can carry, so a busier interval raises the
+    /// estimate at once while a quieter one only lowers it slowly.
+    pub fn record_transfer(
+        &mut self,
+        transfer: TransferCount,
+        duration: TimestampDuration,
+    ) -> Option<ByteCount> {
+        let busiest = transfer.down.max(transfer.up).as_u64();
+        if busiest < MIN_BANDWIDTH_SAMPLE_BYTES || duration.as_u64() == 0 {
+            return self.estimate;
+        }
+        let sample = busiest.saturating_mul(1_000_000) / duration.as_u64();
+
+        let estimate = match self.estimate.map(|x| x.as_u64()) {
+            Some(estimate) if estimate > sample => {
+                estimate - (estimate - sample) / BANDWIDTH_ESTIMATE_DECAY
+            }
+            _ => sample,
+        };
+        self.estimate = Some(ByteCount::new(estimate));
+        self.estimate
+    }
+}
@@ -104,6 +104,7 @@ impl RoutingTable {
                 dial_info_details.push(DialInfoDetail {
                     dial_info: di,
                     class: DialInfoClass::Direct,
+                    preference: 0,
                 });
             }
         }
@@ -406,7 +407,9 @@ impl RoutingTable {
                         ProtocolTypeSet::only(ProtocolType::UDP), // Bootstraps do not participate in relaying and will not make outbound requests, but will have UDP enabled
                         AddressTypeSet::all(), // Bootstraps are always IPV4 and IPV6 capable
                         bsrec.envelope_support, // Envelope support is as specified in the bootstrap list
+                        0,                      // Max envelope size is learned from the bootstrap's signed node info
                         crypto_support,         // Crypto support is derived from list of node ids
+                        vec![],                 // Key encapsulation keys are learned from the bootstrap's signed node info
                         vec![],                 // Bootstrap needs no capabilities
                         bsrec.dial_info_details, // Dial info is as specified in the bootstrap list
                     )));
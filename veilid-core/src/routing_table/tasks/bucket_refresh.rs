@@ -0,0 +1,103 @@
+use super::*;
+
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use stop_token::future::FutureExt as StopFutureExt;
+
+/// Make a random key that falls in the bucket with the given index relative to our own node id
+pub(crate) fn random_key_in_bucket(self_key: &PublicKey, bucket: usize) -> PublicKey {
+    let mut distance = [0u8; CRYPTO_KEY_LENGTH];
+    random_bytes(&mut distance);
+
+    // The distance to any key in the bucket has its first set bit at the bucket index
+    let byte = bucket / 8;
+    let bit = 0x80u8 >> (bucket % 8);
+    distance[..byte].fill(0);
+    distance[byte] = (distance[byte] & (bit - 1)) | bit;
+
+    let mut key = self_key.bytes;
+    for (k, d) in key.iter_mut().zip(distance.iter()) {
+        *k ^= d;
+    }
+    PublicKey::new(key)
+}
+
+impl RoutingTable {
+    // Look up a random key in each bucket we have not done a lookup in for a while, so that
+    // we keep finding nodes in every part of the keyspace and not just near ourselves.
+    // This only refreshes PublicInternet routing domain buckets.
+    #[instrument(level = "trace", skip(self), err)]
+    pub(crate) async fn bucket_refresh_task_routine(
+        self,
+        stop_token: StopToken,
+        _last_ts: Timestamp,
+        cur_ts: Timestamp,
+    ) -> EyreResult<()> {
+        let stale_buckets = self.inner.read().get_stale_buckets(
+            cur_ts,
+            TimestampDuration::new(BUCKET_REFRESH_STALE_SECS as u64 * 1_000_000u64),
+            BUCKET_REFRESH_BUCKET_COUNT,
+        );
+        if stale_buckets.is_empty() {
+            return Ok(());
+        }
+
+        let mut unord = FuturesUnordered::new();
+        for (crypto_kind, bucket) in stale_buckets {
+            let target = TypedKey::new(
+                crypto_kind,
+                random_key_in_bucket(&self.node_id(crypto_kind).value, bucket),
+            );
+
+            // Mark the bucket as looked up now so it is not retried every tick if nobody answers
+            self.inner
+                .write()
+                .touch_bucket_lookup((crypto_kind, bucket), cur_ts);
+
+            // Ask the nodes we know closest to the random key to find nodes near it
+            let filter = Box::new(
+                move |rti: &RoutingTableInner, opt_entry: Option<Arc<BucketEntry>>| {
+                    let Some(entry) = opt_entry else {
+                        return false;
+                    };
+                    entry.with(rti, |_rti, e| {
+                        e.signed_node_info(RoutingDomain::PublicInternet)
+                            .map(|sni| sni.has_any_signature())
+                            .unwrap_or_default()
+                    })
+                },
+            ) as RoutingTableEntryFilter;
+            let routing_table = self.clone();
+            let noderefs = match self.find_preferred_closest_nodes(
+                BUCKET_REFRESH_NODE_COUNT,
+                target,
+                VecDeque::from([filter]),
+                |_rti, entry: Option<Arc<BucketEntry>>| {
+                    NodeRef::new(routing_table.clone(), entry.unwrap(), None)
+                },
+            ) {
+                Ok(v) => v,
+                Err(e) => {
+                    log_rtab!(debug "Unable to find nodes to refresh bucket {}:{}: {}", crypto_kind, bucket, e);
+                    continue;
+                }
+            };
+
+            log_rtab!(debug "Refreshing bucket {}:{} with {} nodes", crypto_kind, bucket, noderefs.len());
+            for nr in noderefs {
+                let routing_table = self.clone();
+                unord.push(
+                    async move { routing_table.find_node(nr, target).await }
+                        .instrument(Span::current()),
+                );
+            }
+        }
+
+        while let Ok(Some(res)) = unord.next().timeout_at(stop_token.clone()).await {
+            if let Err(e) = res {
+                log_rtab!(debug "Bucket refresh lookup failed: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+}
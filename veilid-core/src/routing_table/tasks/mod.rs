@@ -1,10 +1,14 @@
 pub mod bootstrap;
+pub mod bucket_refresh;
 pub mod kick_buckets;
+pub mod near_bucket_validation;
+pub mod node_ref_watchdog;
 pub mod peer_minimum_refresh;
 pub mod ping_validator;
 pub mod private_route_management;
 pub mod relay_management;
 pub mod rolling_transfers;
+pub mod warm_peer_reconnect;
 
 use super::*;
 
@@ -20,7 +24,7 @@ impl RoutingTable {
                         this.clone()
                             .rolling_transfers_task_routine(s, Timestamp::new(l), Timestamp::new(t))
                             .instrument(trace_span!(
-                                parent: None,
+                                parent: &this.unlocked_inner.config.instance_span(),
                                 "RoutingTable rolling transfers task routine"
                             )),
                     )
@@ -36,7 +40,7 @@ impl RoutingTable {
                     Box::pin(
                         this.clone()
                             .kick_buckets_task_routine(s, Timestamp::new(l), Timestamp::new(t))
-                            .instrument(trace_span!(parent: None, "kick buckets task routine")),
+                            .instrument(trace_span!(parent: &this.unlocked_inner.config.instance_span(), "kick buckets task routine")),
                     )
                 });
         }
@@ -50,7 +54,7 @@ impl RoutingTable {
                     Box::pin(
                         this.clone()
                             .bootstrap_task_routine(s)
-                            .instrument(trace_span!(parent: None, "bootstrap task routine")),
+                            .instrument(trace_span!(parent: &this.unlocked_inner.config.instance_span(), "bootstrap task routine")),
                     )
                 });
         }
@@ -65,7 +69,7 @@ impl RoutingTable {
                         this.clone()
                             .peer_minimum_refresh_task_routine(s)
                             .instrument(trace_span!(
-                                parent: None,
+                                parent: &this.unlocked_inner.config.instance_span(),
                                 "peer minimum refresh task routine"
                             )),
                     )
@@ -81,7 +85,7 @@ impl RoutingTable {
                     Box::pin(
                         this.clone()
                             .ping_validator_task_routine(s, Timestamp::new(l), Timestamp::new(t))
-                            .instrument(trace_span!(parent: None, "ping validator task routine")),
+                            .instrument(trace_span!(parent: &this.unlocked_inner.config.instance_span(), "ping validator task routine")),
                     )
                 });
         }
@@ -95,7 +99,63 @@ impl RoutingTable {
                     Box::pin(
                         this.clone()
                             .relay_management_task_routine(s, Timestamp::new(l), Timestamp::new(t))
-                            .instrument(trace_span!(parent: None, "relay management task routine")),
+                            .instrument(trace_span!(parent: &this.unlocked_inner.config.instance_span(), "relay management task routine")),
+                    )
+                });
+        }
+
+        // Set near bucket validation tick task
+        {
+            let this = self.clone();
+            self.unlocked_inner
+                .near_bucket_validation_task
+                .set_routine(move |s, l, t| {
+                    Box::pin(
+                        this.clone()
+                            .near_bucket_validation_task_routine(
+                                s,
+                                Timestamp::new(l),
+                                Timestamp::new(t),
+                            )
+                            .instrument(trace_span!(
+                                parent: &this.unlocked_inner.config.instance_span(),
+                                "near bucket validation task routine"
+                            )),
+                    )
+                });
+        }
+
+        // Set warm peer reconnect tick task
+        {
+            let this = self.clone();
+            self.unlocked_inner
+                .warm_peer_reconnect_task
+                .set_routine(move |s, l, t| {
+                    Box::pin(
+                        this.clone()
+                            .warm_peer_reconnect_task_routine(
+                                s,
+                                Timestamp::new(l),
+                                Timestamp::new(t),
+                            )
+                            .instrument(trace_span!(
+                                parent: &this.unlocked_inner.config.instance_span(),
+                                "warm peer reconnect task routine"
+                            )),
+                    )
+                });
+        }
+
+        // Set bucket refresh tick task
+        {
+            let this = self.clone();
+            self.unlocked_inner
+                .bucket_refresh_task
+                .set_routine(move |s, l, t| {
+                    Box::pin(
+                        this.clone()
+                            .bucket_refresh_task_routine(s, Timestamp::new(l), Timestamp::new(t))
+                            .instrument(trace_span!(parent: &this.unlocked_inner.config.instance_span(), "bucket refresh task routine")),
                     )
                 });
         }
@@ -114,12 +174,28 @@ impl RoutingTable {
                                 Timestamp::new(t),
                             )
                             .instrument(trace_span!(
-                                parent: None,
+                                parent: &this.unlocked_inner.config.instance_span(),
                                 "private route management task routine"
                             )),
                     )
                 });
         }
+
+        // Set node ref watchdog tick task
+        {
+            let this = self.clone();
+            self.unlocked_inner
+                .node_ref_watchdog_task
+                .set_routine(move |s, l, t| {
+                    Box::pin(
+                        this.clone()
+                            .node_ref_watchdog_task_routine(s, Timestamp::new(l), Timestamp::new(t))
+                            .instrument(
+                                trace_span!(parent: &this.unlocked_inner.config.instance_span(), "node ref watchdog task routine"),
+                            ),
+                    )
+                });
+        }
     }
 
     /// Ticks about once per second
@@ -143,6 +219,12 @@ impl RoutingTable {
             self.unlocked_inner.kick_buckets_task.tick().await?;
         }
 
+        // Reconnect to the peers we had before restarting, ahead of any bootstrap
+        let has_warm_peers = !self.inner.read().warm_peers.is_empty();
+        if has_warm_peers {
+            self.unlocked_inner.warm_peer_reconnect_task.tick().await?;
+        }
+
         // Refresh entry counts
         let entry_counts = {
             let mut inner = self.inner.write();
@@ -170,12 +252,29 @@ impl RoutingTable {
             self.unlocked_inner.peer_minimum_refresh_task.tick().await?;
         }
 
+        // Refresh buckets we have not done any lookups in lately, once bootstrap is done
+        if !needs_bootstrap && !self.network_manager().is_low_power() {
+            self.unlocked_inner.bucket_refresh_task.tick().await?;
+        }
+
         // Ping validate some nodes to groom the table
-        self.unlocked_inner.ping_validator_task.tick().await?;
+        // In low power mode we send no pings or relay keepalives at all, and let connections lapse
+        if !self.network_manager().is_low_power() {
+            self.unlocked_inner.ping_validator_task.tick().await?;
+        }
 
         // Run the relay management task
         self.unlocked_inner.relay_management_task.tick().await?;
 
+        // Validate nodes that want to join our nearest buckets
+        let has_near_bucket_candidates = !self.inner.read().near_bucket_candidates.is_empty();
+        if has_near_bucket_candidates {
+            self.unlocked_inner
+                .near_bucket_validation_task
+                .tick()
+                .await?;
+        }
+
         // Run the private route management task
         // If we don't know our network class then don't do this yet
         if self.has_valid_network_class(RoutingDomain::PublicInternet) {
@@ -185,6 +284,16 @@ impl RoutingTable {
                 .await?;
         }
 
+        // Warn about possible NodeRef leaks if the watchdog is on
+        let node_ref_watchdog_enabled = self
+            .unlocked_inner
+            .node_ref_watchdog_threshold
+            .lock()
+            .is_some();
+        if node_ref_watchdog_enabled {
+            self.unlocked_inner.node_ref_watchdog_task.tick().await?;
+        }
+
         Ok(())
     }
     pub(crate) async fn pause_tasks(&self) -> AsyncTagLockGuard<&'static str> {
@@ -218,6 +327,18 @@ impl RoutingTable {
         if let Err(e) = self.unlocked_inner.relay_management_task.stop().await {
             warn!("relay_management_task not stopped: {}", e);
         }
+        log_rtab!(debug "stopping near bucket validation task");
+        if let Err(e) = self.unlocked_inner.near_bucket_validation_task.stop().await {
+            warn!("near_bucket_validation_task not stopped: {}", e);
+        }
+        log_rtab!(debug "stopping warm peer reconnect task");
+        if let Err(e) = self.unlocked_inner.warm_peer_reconnect_task.stop().await {
+            warn!("warm_peer_reconnect_task not stopped: {}", e);
+        }
+        log_rtab!(debug "stopping bucket refresh task");
+        if let Err(e) = self.unlocked_inner.bucket_refresh_task.stop().await {
+            warn!("bucket_refresh_task not stopped: {}", e);
+        }
         log_rtab!(debug "stopping private route management task");
         if let Err(e) = self
             .unlocked_inner
@@ -227,5 +348,9 @@ impl RoutingTable {
         {
             warn!("private_route_management_task not stopped: {}", e);
         }
+        log_rtab!(debug "stopping node ref watchdog task");
+        if let Err(e) = self.unlocked_inner.node_ref_watchdog_task.stop().await {
+            warn!("node_ref_watchdog_task not stopped: {}", e);
+        }
     }
 }
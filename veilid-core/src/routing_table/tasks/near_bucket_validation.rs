@@ -0,0 +1,129 @@
+use super::*;
+
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use stop_token::future::FutureExt as StopFutureExt;
+
+impl RoutingTable {
+    /// Prove a near bucket candidate holds its node id and is reachable, by signalling it to
+    /// return a receipt to us and checking the receipt comes back in-band from that node id
+    async fn prove_near_bucket_candidate(
+        &self,
+        routing_domain: RoutingDomain,
+        node_id: TypedKey,
+        nr: NodeRef,
+    ) -> EyreResult<NetworkResult<()>> {
+        if !nr.has_capabilities(&[CAP_SIGNAL]) {
+            return Ok(NetworkResult::service_unavailable(
+                "near node candidate does not support signals",
+            ));
+        }
+
+        let receipt_timeout = ms_to_us(
+            self.unlocked_inner
+                .config
+                .get()
+                .network
+                .reverse_connection_receipt_time_ms,
+        );
+        let (receipt, eventual_value) = self
+            .network_manager()
+            .generate_single_shot_receipt(receipt_timeout, [])?;
+
+        // Ask the candidate to return the receipt to us, as it would for a reverse connection
+        let peer_info = self.get_own_peer_info(routing_domain);
+        network_result_try!(self
+            .rpc_processor()
+            .rpc_call_signal(
+                Destination::direct(nr),
+                SignalInfo::ReverseConnect { receipt, peer_info },
+            )
+            .await
+            .wrap_err("failed to send signal")?);
+
+        // The receipt must come back in an envelope signed by the candidate itself
+        match eventual_value.await.take_value().unwrap() {
+            ReceiptEvent::ReturnedInBand { inbound_noderef } => {
+                if !inbound_noderef.node_ids().contains(&node_id) {
+                    return Ok(NetworkResult::invalid_message(
+                        "near node receipt returned by another node",
+                    ));
+                }
+                Ok(NetworkResult::value(()))
+            }
+            ReceiptEvent::ReturnedPrivate { private_route: _ }
+            | ReceiptEvent::ReturnedOutOfBand
+            | ReceiptEvent::ReturnedSafety => Ok(NetworkResult::invalid_message(
+                "near node receipt should be returned in-band",
+            )),
+            ReceiptEvent::Expired => Ok(NetworkResult::timeout()),
+            ReceiptEvent::Cancelled => Ok(NetworkResult::no_connection_other(
+                "near node receipt cancelled",
+            )),
+        }
+    }
+
+    // Prove near bucket candidates by completing a receipt round trip with them before admitting them
+    #[instrument(level = "trace", skip(self), err)]
+    pub(crate) async fn near_bucket_validation_task_routine(
+        self,
+        stop_token: StopToken,
+        _last_ts: Timestamp,
+        _cur_ts: Timestamp,
+    ) -> EyreResult<()> {
+        let candidates = self
+            .inner
+            .write()
+            .take_near_bucket_candidates(NEAR_BUCKET_ADMISSION_LIMIT);
+        if candidates.is_empty() {
+            return Ok(());
+        }
+
+        let mut unord = FuturesUnordered::new();
+        for (routing_domain, peer_info) in candidates {
+            let nr = match self.inner.read().make_detached_node_ref(
+                self.clone(),
+                routing_domain,
+                peer_info.clone(),
+            ) {
+                Ok(v) => v,
+                Err(e) => {
+                    log_rtab!(debug "Unable to contact near node candidate: {}", e);
+                    continue;
+                }
+            };
+            let this = self.clone();
+            unord.push(
+                async move {
+                    let node_id = peer_info.node_ids()[0];
+                    let res = this
+                        .prove_near_bucket_candidate(routing_domain, node_id, nr)
+                        .await;
+                    (routing_domain, peer_info, res)
+                }
+                .instrument(Span::current()),
+            );
+        }
+
+        while let Ok(Some((routing_domain, peer_info, res))) =
+            unord.next().timeout_at(stop_token.clone()).await
+        {
+            match res {
+                Ok(NetworkResult::Value(_)) => {
+                    if let Err(e) =
+                        self.register_proven_node_with_peer_info(routing_domain, peer_info)
+                    {
+                        log_rtab!(debug "Unable to admit proven near node: {}", e);
+                    }
+                }
+                Ok(v) => {
+                    log_rtab!(debug "Near node candidate failed validation: {}", v);
+                }
+                Err(e) => {
+                    log_rtab!(debug "Error validating near node candidate: {}", e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
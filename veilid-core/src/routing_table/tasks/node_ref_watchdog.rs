@@ -0,0 +1,28 @@
+use super::*;
+
+impl RoutingTable {
+    // Warn about routing table entries that have been held by NodeRefs for longer than the watchdog threshold
+    // Entries that stay pinned like this are often leaked NodeRefs, which otherwise only show up when the entry is dropped
+    #[instrument(level = "trace", skip(self), err)]
+    pub(crate) async fn node_ref_watchdog_task_routine(
+        self,
+        _stop_token: StopToken,
+        _last_ts: Timestamp,
+        cur_ts: Timestamp,
+    ) -> EyreResult<()> {
+        let Some(threshold) = *self.unlocked_inner.node_ref_watchdog_threshold.lock() else {
+            return Ok(());
+        };
+
+        for (entry, ref_count, pinned_duration) in self.pinned_entries(cur_ts, threshold) {
+            warn!(
+                "entry {} has been held by {} NodeRefs for {}s, possible NodeRef leak",
+                entry.with_inner(|e| e.best_node_id()),
+                ref_count,
+                timestamp_to_secs(pinned_duration.as_u64()) as u64
+            );
+        }
+
+        Ok(())
+    }
+}
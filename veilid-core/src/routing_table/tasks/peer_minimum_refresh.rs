@@ -3,6 +3,54 @@ use super::*;
 use futures_util::stream::{FuturesOrdered, StreamExt};
 use stop_token::future::FutureExt as StopFutureExt;
 
+/// One in this many peer minimum refresh slots goes to a peer we have no latency measurement for yet
+const UNMEASURED_PEER_DIVISOR: usize = 4;
+
+/// Pick up to `count` peers spread evenly over the range of measured latencies, so we keep
+/// talking to far away peers as well as nearby ones. Some slots are reserved for peers whose
+/// latency is still unknown, as asking them for peers measures it.
+pub(crate) fn select_latency_diverse_peers<T>(
+    peers: Vec<(T, Option<TimestampDuration>)>,
+    count: usize,
+) -> Vec<T> {
+    let (mut measured, unmeasured): (Vec<_>, Vec<_>) = peers
+        .into_iter()
+        .partition(|(_, latency)| latency.is_some());
+    measured.sort_by_key(|(_, latency)| *latency);
+
+    let unmeasured_count = if count == 0 {
+        0
+    } else {
+        core::cmp::max(count / UNMEASURED_PEER_DIVISOR, 1)
+    }
+    .min(unmeasured.len());
+    let measured_count = (count - unmeasured_count).min(measured.len());
+    let unmeasured_count = (count - measured_count).min(unmeasured.len());
+
+    let mut out = Vec::with_capacity(measured_count + unmeasured_count);
+    if measured_count == 1 {
+        out.push(measured.swap_remove(0).0);
+    } else if measured_count > 1 {
+        // Evenly spaced from nearest to farthest, always including both ends
+        let last = measured.len() - 1;
+        let mut measured = measured
+            .into_iter()
+            .map(|(peer, _)| Some(peer))
+            .collect::<Vec<_>>();
+        for n in 0..measured_count {
+            let idx = n * last / (measured_count - 1);
+            out.extend(measured[idx].take());
+        }
+    }
+    out.extend(
+        unmeasured
+            .into_iter()
+            .take(unmeasured_count)
+            .map(|(peer, _)| peer),
+    );
+    out
+}
+
 impl RoutingTable {
     // Ask our remaining peers to give us more peers before we go
     // back to the bootstrap servers to keep us from bothering them too much
@@ -67,13 +115,20 @@ impl RoutingTable {
             ) as RoutingTableEntryFilter;
             filters.push_front(filter);
 
-            let noderefs = routing_table.find_preferred_fastest_nodes(
-                min_peer_count,
+            // Rather than only keeping whichever peers answer first, ask a set of peers
+            // spread across the latency range so that our view of the network does not
+            // collapse to the region we happen to be in
+            let candidates = routing_table.find_preferred_fastest_nodes(
+                usize::MAX,
                 filters,
                 |_rti, entry: Option<Arc<BucketEntry>>| {
-                    NodeRef::new(routing_table.clone(), entry.unwrap().clone(), None)
+                    let entry = entry.unwrap();
+                    let latency =
+                        entry.with_inner(|e| e.peer_stats().latency.as_ref().map(|l| l.average));
+                    (NodeRef::new(routing_table.clone(), entry, None), latency)
                 },
             );
+            let noderefs = select_latency_diverse_peers(candidates, min_peer_count);
 
             for nr in noderefs {
                 let routing_table = self.clone();
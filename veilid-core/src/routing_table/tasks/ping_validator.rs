@@ -118,6 +118,37 @@ impl RoutingTable {
         }
         Ok(())
     }
+    // Keep connections to prewarmed nodes alive until their grace period is over
+    #[instrument(level = "trace", skip(self, futurequeue), err)]
+    async fn prewarm_keepalive(
+        &self,
+        cur_ts: Timestamp,
+        futurequeue: &mut VecDeque<PingValidatorFuture>,
+    ) -> EyreResult<()> {
+        let rpc = self.rpc_processor();
+
+        let node_ids = self.inner.write().take_prewarm_keepalives(
+            cur_ts,
+            TimestampDuration::new(KEEPALIVE_PING_INTERVAL_SECS as u64 * 1_000_000u64),
+        );
+        for node_id in node_ids {
+            let Ok(Some(nr)) = self.lookup_node_ref(node_id) else {
+                continue;
+            };
+            let rpc = rpc.clone();
+
+            log_rtab!("--> Prewarm keepalive ping to {:?}", nr);
+
+            futurequeue.push_back(
+                async move { rpc.rpc_call_status(Destination::direct(nr)).await }
+                    .instrument(Span::current())
+                    .boxed(),
+            );
+        }
+
+        Ok(())
+    }
+
     // Ping each node in the routing table if they need to be pinged
     // to determine their reliability
     #[instrument(level = "trace", skip(self, futurequeue), err)]
@@ -201,6 +232,9 @@ impl RoutingTable {
         self.ping_validator_local_network(cur_ts, &mut futurequeue)
             .await?;
 
+        // Prewarmed nodes
+        self.prewarm_keepalive(cur_ts, &mut futurequeue).await?;
+
         // Wait for ping futures to complete in parallel
         let mut unord = FuturesUnordered::new();
 
@@ -8,7 +8,7 @@ const BACKGROUND_SAFETY_ROUTE_COUNT: usize = 2;
 impl RoutingTable {
     fn get_background_safety_route_count(&self) -> usize {
         let c = self.config.get();
-        if c.capabilities.disable.contains(&CAP_ROUTE) {
+        if is_capability_disabled(&c, &CAP_ROUTE) {
             0
         } else {
             BACKGROUND_SAFETY_ROUTE_COUNT
@@ -41,10 +41,19 @@ impl RoutingTable {
     ///   . the rest of the allocated unpublished routes
     ///
     /// If a route doesn't 'need_testing', then we neither test nor drop it
+    ///
+    /// Manually allocated routes that have been idle for too long are always dropped
     #[instrument(level = "trace", skip(self))]
     fn get_allocated_routes_to_test(&self, cur_ts: Timestamp) -> Vec<RouteId> {
-        let default_route_hop_count =
-            self.with_config(|c| c.network.rpc.default_route_hop_count as usize);
+        let (default_route_hop_count, private_route_idle_release) = self.with_config(|c| {
+            (
+                c.network.rpc.default_route_hop_count as usize,
+                c.network
+                    .rpc
+                    .private_route_idle_release_ms
+                    .map(|ms| TimestampDuration::new(ms as u64 * 1000u64)),
+            )
+        });
 
         let rss = self.route_spec_store();
         let mut must_test_routes = Vec::<RouteId>::new();
@@ -52,6 +61,13 @@ impl RoutingTable {
         let mut expired_routes = Vec::<RouteId>::new();
         rss.list_allocated_routes(|k, v| {
             let stats = v.get_stats();
+            // Release manually allocated routes that the application has stopped using
+            if let Some(private_route_idle_release) = private_route_idle_release {
+                if !v.is_automatic() && stats.is_idle(cur_ts, private_route_idle_release) {
+                    expired_routes.push(*k);
+                    return Option::<()>::None;
+                }
+            }
             // Ignore nodes that don't need testing
             if !stats.needs_testing(cur_ts) {
                 return Option::<()>::None;
@@ -170,6 +186,85 @@ impl RoutingTable {
         Ok(())
     }
 
+    /// Replace published private routes that are due for rotation,
+    /// and release rotated routes whose overlap window is over
+    #[instrument(level = "trace", skip(self), err)]
+    async fn rotate_private_routes(&self, cur_ts: Timestamp) -> EyreResult<()> {
+        let rss = self.route_spec_store();
+        let mut retired_routes = Vec::<RouteId>::new();
+        let mut due_routes = Vec::<(RouteId, PrivateRouteRotation)>::new();
+        rss.list_allocated_routes(|k, v| {
+            if let Some(retire_ts) = v.get_retire_ts() {
+                if cur_ts >= retire_ts {
+                    retired_routes.push(*k);
+                }
+            } else if let Some(rotation) = v.get_rotation() {
+                let interval = TimestampDuration::new(ms_to_us(rotation.interval_ms));
+                if v.is_published() && cur_ts.saturating_sub(v.get_stats().created_ts) >= interval {
+                    due_routes.push((*k, rotation));
+                }
+            }
+            Option::<()>::None
+        });
+
+        // Release rotated routes that are no longer needed
+        for r in retired_routes {
+            log_rtab!(debug "Retired rotated route: {}", r);
+            rss.release_route(r);
+        }
+
+        // Replace the routes that are due
+        for (old_route, rotation) in due_routes {
+            let new_route = match rss.allocate_replacement_route(&old_route) {
+                Err(VeilidAPIError::TryAgain { message }) => {
+                    log_rtab!(debug "Route rotation unavailable: {}", message);
+                    continue;
+                }
+                Err(VeilidAPIError::RouteCompilation { error }) => {
+                    log_rtab!(debug "Route rotation unavailable: {}", error);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+                Ok(v) => v,
+            };
+            if !rss.test_route(new_route).await? {
+                log_rtab!(debug "Replacement route failed to test: {}", new_route);
+                rss.release_route(new_route);
+                continue;
+            }
+            let blob = match rss
+                .assemble_private_routes(&new_route, Some(true))
+                .and_then(|prs| RouteSpecStore::private_routes_to_blob(&prs))
+            {
+                Ok(v) => v,
+                Err(e) => {
+                    rss.release_route(new_route);
+                    return Err(e.into());
+                }
+            };
+            rss.mark_route_published(&new_route, true)?;
+
+            // Write the new route to the record that references it if there is one
+            // The application hears about the new route through the route change update either way
+            if let Some(record) = rotation.record {
+                if let Err(e) = self
+                    .network_manager()
+                    .storage_manager()
+                    .set_value(record, rotation.subkey, blob.clone(), None)
+                    .await
+                {
+                    log_rtab!(debug "Failed to publish rotated route to {} subkey {}: {}", record, rotation.subkey, e);
+                }
+            }
+
+            let retire_ts = cur_ts + ms_to_us(rotation.overlap_ms);
+            rss.retire_rotated_route(old_route, new_route, blob, retire_ts)?;
+            log_rtab!(debug "Rotated route: {} -> {}", old_route, new_route);
+        }
+
+        Ok(())
+    }
+
     /// Keep private routes assigned and accessible
     #[instrument(level = "trace", skip(self, stop_token), err)]
     pub(crate) async fn private_route_management_task_routine(
@@ -178,6 +273,9 @@ impl RoutingTable {
         _last_ts: Timestamp,
         cur_ts: Timestamp,
     ) -> EyreResult<()> {
+        // Rotate published routes that are due before testing, so replacements are tested with the rest
+        self.rotate_private_routes(cur_ts).await?;
+
         // Test locally allocated routes first
         // This may remove dead routes
         let routes_needing_testing = self.get_allocated_routes_to_test(cur_ts);
@@ -218,11 +316,16 @@ impl RoutingTable {
                     default_route_hop_count,
                     DirectionSet::all(),
                     &[],
+                    false,
                     true,
+                    None,
                 ) {
                     Err(VeilidAPIError::TryAgain { message }) => {
                         log_rtab!(debug "Route allocation unavailable: {}", message);
                     }
+                    Err(VeilidAPIError::RouteCompilation { error }) => {
+                        log_rtab!(debug "Route allocation unavailable: {}", error);
+                    }
                     Err(e) => return Err(e.into()),
                     Ok(v) => {
                         newly_allocated_routes.push(v);
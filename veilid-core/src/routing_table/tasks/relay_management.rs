@@ -106,6 +106,8 @@ impl RoutingTable {
         // Get all our outbound protocol/address types
         let outbound_dif = self.get_outbound_dial_info_filter(RoutingDomain::PublicInternet);
         let mapped_port_info = self.get_low_level_port_info();
+        let node_info_max_age = self.unlocked_inner.node_info_max_age();
+        let cur_ts = get_aligned_timestamp();
 
         move |e: &BucketEntryInner| {
             // Ensure this node is not on the local network
@@ -113,6 +115,11 @@ impl RoutingTable {
                 return false;
             }
 
+            // Don't relay through nodes whose node info is stale until a ping refreshes it
+            if e.has_stale_node_info(RoutingDomain::PublicInternet, cur_ts, node_info_max_age) {
+                return false;
+            }
+
             // Disqualify nodes that don't cover all our inbound ports for tcp and udp
             // as we need to be able to use the relay for keepalives for all nat mappings
             let mut low_level_protocol_ports = mapped_port_info.low_level_protocol_ports.clone();
@@ -0,0 +1,69 @@
+use super::*;
+
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use stop_token::future::FutureExt as StopFutureExt;
+
+impl RoutingTable {
+    // Reconnect to the peers we were connected to before restarting, all at once,
+    // so we are attached again without waiting for bootstrap or peer minimum refresh
+    #[instrument(level = "trace", skip(self), err)]
+    pub(crate) async fn warm_peer_reconnect_task_routine(
+        self,
+        stop_token: StopToken,
+        _last_ts: Timestamp,
+        _cur_ts: Timestamp,
+    ) -> EyreResult<()> {
+        let warm_peers = self.inner.write().take_warm_peers();
+        if warm_peers.is_empty() {
+            return Ok(());
+        }
+
+        let rpc = self.rpc_processor();
+        let mut unord = FuturesUnordered::new();
+        for warm_peer in warm_peers {
+            let nr = match self.lookup_node_ref(warm_peer.node_id) {
+                Ok(Some(nr)) => nr,
+                Ok(None) => {
+                    continue;
+                }
+                Err(e) => {
+                    log_rtab!(debug "Unable to reconnect to warm peer: {}", e);
+                    continue;
+                }
+            };
+
+            // Prefer the same kind of dial info that worked before the restart
+            let nr = nr.filtered_clone(
+                NodeRefFilter::new().with_dial_info_filter(
+                    DialInfoFilter::all()
+                        .with_protocol_type(warm_peer.protocol_type)
+                        .with_address_type(warm_peer.address_type),
+                ),
+            );
+            let rpc = rpc.clone();
+            unord.push(
+                async move {
+                    let res = rpc.rpc_call_status(Destination::direct(nr)).await;
+                    (warm_peer, res)
+                }
+                .instrument(Span::current()),
+            );
+        }
+
+        while let Ok(Some((warm_peer, res))) = unord.next().timeout_at(stop_token.clone()).await {
+            match res {
+                Ok(NetworkResult::Value(_)) => {
+                    log_rtab!(debug "Reconnected to warm peer: {}", warm_peer.node_id);
+                }
+                Ok(v) => {
+                    log_rtab!(debug "Warm peer {} did not reconnect: {}", warm_peer.node_id, v);
+                }
+                Err(e) => {
+                    log_rtab!(debug "Error reconnecting to warm peer {}: {}", warm_peer.node_id, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
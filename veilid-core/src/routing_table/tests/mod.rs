@@ -1,14 +1,21 @@
 use super::*;
 
+pub mod test_bucket_entry;
+pub mod test_bucket_refresh;
+pub mod test_find_node_answer;
+pub mod test_peer_info;
+pub mod test_peer_minimum_refresh;
+pub mod test_route_spec_store;
+pub mod test_routing_table_error;
 pub mod test_serialize_routing_table;
 
 pub(crate) fn mock_routing_table() -> routing_table::RoutingTable {
     let veilid_config = VeilidConfig::new();
-    #[cfg(feature = "unstable-blockstore")]
-    let block_store = BlockStore::new(veilid_config.clone());
     let protected_store = ProtectedStore::new(veilid_config.clone());
     let table_store = TableStore::new(veilid_config.clone(), protected_store.clone());
     let crypto = Crypto::new(veilid_config.clone(), table_store.clone());
+    #[cfg(feature = "unstable-blockstore")]
+    let block_store = BlockStore::new(table_store.clone(), crypto.clone());
     let storage_manager = storage_manager::StorageManager::new(
         veilid_config.clone(),
         crypto.clone(),
@@ -0,0 +1,654 @@
+use super::*;
+
+const SECS: u64 = 1_000_000u64;
+const MILLIS: u64 = 1_000u64;
+
+fn make_key(n: u8) -> PublicKey {
+    PublicKey::new([n; PUBLIC_KEY_LENGTH])
+}
+
+// Simulate a node that has answered pings from `first_seen_ts` up until `last_seen_ts` with the given latency
+fn simulate_seen(e: &mut BucketEntryInner, first_seen_ts: u64, last_seen_ts: u64, latency: u64) {
+    for send_ts in [first_seen_ts, last_seen_ts] {
+        e.question_sent(Timestamp::new(send_ts), ByteCount::new(0), true);
+        e.answer_rcvd(
+            Timestamp::new(send_ts),
+            Timestamp::new(send_ts + latency),
+            ByteCount::new(0),
+        );
+    }
+}
+
+// Fail to send to a node until it is considered dead
+fn simulate_dead(e: &mut BucketEntryInner, ts: u64) {
+    // Three failures in a row are enough, even for a node that has been seen before
+    for n in 0..3 {
+        e.failed_to_send(Timestamp::new(ts + n * MILLIS), false);
+    }
+}
+
+fn make_signed_node_info(ts: u64) -> SignedNodeInfo {
+    SignedNodeInfo::Direct(SignedDirectNodeInfo::new(
+        NodeInfo::new(
+            NetworkClass::InboundCapable,
+            ProtocolTypeSet::new(),
+            AddressTypeSet::new(),
+            vec![0],
+            0,
+            vec![best_crypto_kind()],
+            vec![],
+            PUBLIC_INTERNET_CAPABILITIES.to_vec(),
+            vec![],
+        ),
+        Timestamp::new(ts),
+        Vec::new(),
+    ))
+}
+
+fn make_entry(n: u8, first_seen_ts: u64, last_seen_ts: u64, latency: u64) -> BucketEntry {
+    let entry = BucketEntry::new(TypedKey::new(best_crypto_kind(), make_key(n)));
+    entry.with_mut_inner(|e| simulate_seen(e, first_seen_ts, last_seen_ts, latency));
+    entry
+}
+
+pub async fn test_kick_order_prefers_uptime() {
+    let cur_ts = 10_000 * SECS;
+
+    let long_lived = make_entry(1, cur_ts - 600 * SECS, cur_ts - SECS, 500 * MILLIS);
+    let short_lived = make_entry(2, cur_ts - 120 * SECS, cur_ts - SECS, 20 * MILLIS);
+
+    long_lived.with_inner(|e1| {
+        short_lived.with_inner(|e2| {
+            let cur_ts = Timestamp::new(cur_ts);
+            assert_eq!(e1.state(cur_ts), BucketEntryState::Reliable);
+            assert_eq!(e2.state(cur_ts), BucketEntryState::Reliable);
+            // Uptime wins over latency, the newer node gets kicked first
+            assert_eq!(
+                BucketEntryInner::cmp_kick_order(cur_ts, e2, e1),
+                core::cmp::Ordering::Less
+            );
+            assert_eq!(
+                BucketEntryInner::cmp_kick_order(cur_ts, e1, e2),
+                core::cmp::Ordering::Greater
+            );
+        })
+    });
+}
+
+pub async fn test_kick_order_prefers_low_latency() {
+    let cur_ts = 10_000 * SECS;
+
+    // Seen within the same uptime granularity, so latency decides
+    let fast = make_entry(1, cur_ts - 590 * SECS, cur_ts - SECS, 20 * MILLIS);
+    let slow = make_entry(2, cur_ts - 580 * SECS, cur_ts - SECS, 800 * MILLIS);
+
+    fast.with_inner(|e1| {
+        slow.with_inner(|e2| {
+            let cur_ts = Timestamp::new(cur_ts);
+            assert_eq!(
+                BucketEntryInner::cmp_kick_order(cur_ts, e2, e1),
+                core::cmp::Ordering::Less
+            );
+            assert_eq!(
+                BucketEntryInner::cmp_kick_order(cur_ts, e1, e2),
+                core::cmp::Ordering::Greater
+            );
+        })
+    });
+}
+
+pub async fn test_kick_order_prefers_live() {
+    let cur_ts = 10_000 * SECS;
+
+    // A punished node is dead regardless of how long it has been up or how fast it is
+    let dead = make_entry(1, cur_ts - 3600 * SECS, cur_ts - SECS, 5 * MILLIS);
+    dead.with_mut_inner(|e| e.set_punished(true));
+    let slow = make_entry(2, cur_ts - 120 * SECS, cur_ts - SECS, 800 * MILLIS);
+
+    dead.with_inner(|e1| {
+        slow.with_inner(|e2| {
+            let cur_ts = Timestamp::new(cur_ts);
+            assert_eq!(e1.state(cur_ts), BucketEntryState::Dead);
+            assert_eq!(
+                BucketEntryInner::cmp_kick_order(cur_ts, e1, e2),
+                core::cmp::Ordering::Less
+            );
+        })
+    });
+}
+
+pub async fn test_bucket_kick() {
+    let cur_ts = get_aligned_timestamp().as_u64();
+
+    let mut bucket = Bucket::new(best_crypto_kind());
+    let long_lived_fast = bucket.add_new_entry(make_key(1));
+    long_lived_fast
+        .with_mut_inner(|e| simulate_seen(e, cur_ts - 3580 * SECS, cur_ts - SECS, 20 * MILLIS));
+    let long_lived_slow = bucket.add_new_entry(make_key(2));
+    long_lived_slow
+        .with_mut_inner(|e| simulate_seen(e, cur_ts - 3570 * SECS, cur_ts - SECS, 900 * MILLIS));
+    let short_lived_fast = bucket.add_new_entry(make_key(3));
+    short_lived_fast
+        .with_mut_inner(|e| simulate_seen(e, cur_ts - 120 * SECS, cur_ts - SECS, 10 * MILLIS));
+    bucket.add_new_entry(make_key(4));
+
+    // Nothing to kick if the bucket is not overflowing
+    assert!(bucket.kick(4).is_none());
+
+    // Never seen and short lived entries go first
+    let kicked = bucket.kick(2).expect("should kick entries");
+    assert_eq!(kicked, BTreeSet::from([make_key(3), make_key(4)]));
+    assert!(bucket.entry(&make_key(1)).is_some());
+    assert!(bucket.entry(&make_key(2)).is_some());
+
+    // Then the slower of the long lived entries
+    let kicked = bucket.kick(1).expect("should kick entries");
+    assert_eq!(kicked, BTreeSet::from([make_key(2)]));
+    assert!(bucket.entry(&make_key(1)).is_some());
+}
+
+pub async fn test_stale_node_info() {
+    let cur_ts = 10_000 * SECS;
+    let max_age = TimestampDuration::new(3600 * SECS);
+
+    let entry = BucketEntry::new(TypedKey::new(best_crypto_kind(), make_key(1)));
+    entry.with_mut_inner(|e| {
+        // Nothing to be stale without node info
+        assert!(!e.has_stale_node_info(
+            RoutingDomain::PublicInternet,
+            Timestamp::new(cur_ts),
+            max_age
+        ));
+
+        // Node info signed long ago that we have not confirmed since is stale
+        e.update_signed_node_info(
+            RoutingDomain::PublicInternet,
+            make_signed_node_info(cur_ts - 7200 * SECS),
+        );
+        assert!(e.has_stale_node_info(
+            RoutingDomain::PublicInternet,
+            Timestamp::new(cur_ts),
+            max_age
+        ));
+        assert!(!e.has_stale_node_info(
+            RoutingDomain::LocalNetwork,
+            Timestamp::new(cur_ts),
+            max_age
+        ));
+
+        // Hearing from the node directly confirms our copy is current
+        simulate_seen(e, cur_ts - 60 * SECS, cur_ts - SECS, 20 * MILLIS);
+        assert!(!e.has_stale_node_info(
+            RoutingDomain::PublicInternet,
+            Timestamp::new(cur_ts),
+            max_age
+        ));
+
+        // Until it has been quiet for too long again
+        assert!(e.has_stale_node_info(
+            RoutingDomain::PublicInternet,
+            Timestamp::new(cur_ts + 3600 * SECS),
+            max_age
+        ));
+    });
+}
+
+pub async fn test_clock_skew() {
+    let entry = BucketEntry::new(TypedKey::new(best_crypto_kind(), make_key(1)));
+    entry.with_mut_inner(|e| {
+        assert_eq!(e.peer_stats().clock_skew, None);
+
+        // A single lopsided round trip does not move the estimate far
+        for skew in [
+            2 * SECS,
+            2 * SECS + 10 * MILLIS,
+            30 * SECS,
+            2 * SECS - 10 * MILLIS,
+        ] {
+            e.record_clock_skew(skew as i64);
+        }
+        assert_eq!(
+            e.peer_stats().clock_skew,
+            Some((2 * SECS + 10 * MILLIS) as i64)
+        );
+    });
+}
+
+pub async fn test_transfer_windows() {
+    let start_ts = 10_000 * SECS;
+
+    let entry = BucketEntry::new(TypedKey::new(best_crypto_kind(), make_key(1)));
+    entry.with_mut_inner(|e| {
+        // Send a thousand bytes a second for two minutes
+        for n in 0..120 {
+            let last_ts = Timestamp::new(start_ts + n * SECS);
+            let cur_ts = Timestamp::new(start_ts + (n + 1) * SECS);
+            e.question_sent(last_ts, ByteCount::new(1000), false);
+            e.roll_transfers(last_ts, cur_ts);
+        }
+
+        let windows = &e.peer_stats().transfer_windows;
+        assert_eq!(windows.minute.up.total, ByteCount::new(60_000));
+        assert_eq!(windows.hour.up.total, ByteCount::new(120_000));
+        assert_eq!(windows.day.up.total, ByteCount::new(120_000));
+        for window in [&windows.minute, &windows.hour, &windows.day] {
+            assert_eq!(window.up.average, ByteCount::new(1000));
+            assert_eq!(window.down.total, ByteCount::new(0));
+        }
+    });
+}
+
+pub async fn test_bandwidth_estimate() {
+    let start_ts = 10_000 * SECS;
+    let cur_ts = Timestamp::new(start_ts);
+
+    let slow = BucketEntry::new(TypedKey::new(best_crypto_kind(), make_key(1)));
+    let fast = BucketEntry::new(TypedKey::new(best_crypto_kind(), make_key(2)));
+    slow.with_mut_inner(|e| simulate_seen(e, start_ts - 3600 * SECS, start_ts, 50 * MILLIS));
+    fast.with_mut_inner(|e| simulate_seen(e, start_ts - 3600 * SECS, start_ts, 200 * MILLIS));
+
+    let roll = |e: &mut BucketEntryInner, n: u64, up: u64, down: u64| {
+        let last_ts = Timestamp::new(start_ts + n * SECS);
+        e.question_sent(last_ts, ByteCount::new(up), false);
+        e.question_rcvd(last_ts, ByteCount::new(down));
+        e.roll_transfers(last_ts, Timestamp::new(start_ts + (n + 1) * SECS));
+    };
+
+    slow.with_mut_inner(|e| {
+        // Idle traffic does not produce an estimate
+        roll(e, 0, 1000, 1000);
+        assert_eq!(e.peer_stats().bandwidth, None);
+
+        // The busiest direction counts
+        roll(e, 1, 100_000, 20_000);
+        assert_eq!(e.peer_stats().bandwidth, Some(ByteCount::new(100_000)));
+
+        // A quieter interval only lowers the estimate slowly, and idle ones not at all
+        roll(e, 2, 20_000, 0);
+        assert_eq!(e.peer_stats().bandwidth, Some(ByteCount::new(90_000)));
+        roll(e, 3, 0, 0);
+        assert_eq!(e.peer_stats().bandwidth, Some(ByteCount::new(90_000)));
+    });
+    fast.with_mut_inner(|e| {
+        roll(e, 0, 0, 1_000_000);
+        assert_eq!(e.peer_stats().bandwidth, Some(ByteCount::new(1_000_000)));
+    });
+
+    // Bandwidth ordering puts the higher bandwidth node first even though it has more latency
+    slow.with_inner(|s| {
+        fast.with_inner(|f| {
+            assert_eq!(
+                BucketEntryInner::cmp_highest_bandwidth_reliable(cur_ts, f, s),
+                std::cmp::Ordering::Less
+            );
+            assert_eq!(
+                BucketEntryInner::cmp_fastest_reliable(cur_ts, f, s),
+                std::cmp::Ordering::Greater
+            );
+        })
+    });
+}
+
+pub async fn test_talker_stats() {
+    let start_ts = 10_000 * SECS;
+
+    let entry = BucketEntry::new(TypedKey::new(best_crypto_kind(), make_key(1)));
+    entry.with_mut_inner(|e| {
+        // One question out and one answer back every second for ninety minutes
+        for n in 0..5400 {
+            let last_ts = Timestamp::new(start_ts + n * SECS);
+            let cur_ts = Timestamp::new(start_ts + (n + 1) * SECS);
+            e.question_sent(last_ts, ByteCount::new(100), true);
+            e.answer_rcvd(last_ts, cur_ts, ByteCount::new(300));
+            e.roll_transfers(last_ts, cur_ts);
+        }
+
+        // Only the last hour is counted
+        let tc = e.talker_count();
+        assert_eq!(tc.rpc_messages_sent, 3600);
+        assert_eq!(tc.rpc_messages_rcvd, 3600);
+        assert_eq!(tc.bytes_up, ByteCount::new(360_000));
+        assert_eq!(tc.bytes_down, ByteCount::new(1_080_000));
+
+        // Resetting starts from zero without touching the transfer statistics
+        e.reset_talker_stats();
+        assert_eq!(e.talker_count(), TalkerCount::default());
+        assert_eq!(e.peer_stats().rpc_stats.messages_sent, 5400);
+        assert_eq!(
+            e.peer_stats().transfer_windows.hour.up.total,
+            ByteCount::new(360_000)
+        );
+    });
+}
+
+pub async fn test_connection_closed() {
+    let cur_ts = 10_000 * SECS;
+
+    let entry = make_entry(1, cur_ts - 600 * SECS, cur_ts - SECS, 20 * MILLIS);
+    entry.with_mut_inner(|e| {
+        let cur_ts = Timestamp::new(cur_ts);
+
+        // Idle and dropped connections are normal
+        e.connection_closed(cur_ts, ConnectionCloseReason::Timeout, false);
+        e.connection_closed(cur_ts, ConnectionCloseReason::RemoteClose, false);
+        e.connection_closed(cur_ts, ConnectionCloseReason::Local, true);
+        assert_eq!(e.state(cur_ts), BucketEntryState::Reliable);
+
+        // Misbehaving on a connection is not
+        e.connection_closed(cur_ts, ConnectionCloseReason::ProtocolError, false);
+        assert_eq!(e.state(cur_ts), BucketEntryState::Unreliable);
+    });
+
+    // Losing a protected connection counts against the node
+    let entry = make_entry(2, cur_ts - 600 * SECS, cur_ts - SECS, 20 * MILLIS);
+    entry.with_mut_inner(|e| {
+        let cur_ts = Timestamp::new(cur_ts);
+        e.connection_closed(cur_ts, ConnectionCloseReason::RemoteClose, true);
+        assert_eq!(e.state(cur_ts), BucketEntryState::Unreliable);
+    });
+}
+
+pub async fn test_live_node_ref_filter() {
+    let routing_table = mock_routing_table();
+    let entry = Arc::new(make_entry(1, 0, SECS, MILLIS));
+    let nr = NodeRef::new(
+        routing_table,
+        entry,
+        Some(NodeRefFilter::new().with_routing_domain(RoutingDomain::PublicInternet)),
+    );
+    assert_eq!(nr.dial_info_filter(), DialInfoFilter::all());
+
+    let protocol_type = Arc::new(Mutex::new(ProtocolType::UDP));
+    let live_nr = nr.live_filtered_clone({
+        let protocol_type = protocol_type.clone();
+        Arc::new(move |_rti: &RoutingTableInner| NodeRefFilter::from(*protocol_type.lock()))
+    });
+
+    // The live filter is combined with the fixed filter
+    assert_eq!(
+        live_nr.routing_domain_set(),
+        RoutingDomainSet::from(RoutingDomain::PublicInternet)
+    );
+    assert_eq!(
+        live_nr.dial_info_filter().protocol_type_set,
+        ProtocolTypeSet::only(ProtocolType::UDP)
+    );
+
+    // The live filter follows state that changed after the node ref was made
+    *protocol_type.lock() = ProtocolType::TCP;
+    assert_eq!(
+        live_nr.dial_info_filter().protocol_type_set,
+        ProtocolTypeSet::only(ProtocolType::TCP)
+    );
+    assert_eq!(
+        live_nr.clone().dial_info_filter().protocol_type_set,
+        ProtocolTypeSet::only(ProtocolType::TCP)
+    );
+
+    // The node ref it was cloned from is unaffected
+    assert_eq!(nr.dial_info_filter(), DialInfoFilter::all());
+}
+
+pub async fn test_verified_per_routing_domain() {
+    // Answering pings alone does not verify a node
+    let entry = Arc::new(make_entry(1, 0, SECS, MILLIS));
+    entry.with_inner(|e| {
+        assert!(!e.is_verified(RoutingDomain::PublicInternet));
+        assert!(!e.is_verified(RoutingDomain::LocalNetwork));
+    });
+
+    // A status answer verifies the node only in the routing domain it was asked in
+    let nr = NodeRef::new(mock_routing_table(), entry.clone(), None);
+    nr.set_verified(RoutingDomain::PublicInternet);
+    entry.with_inner(|e| {
+        assert!(e.is_verified(RoutingDomain::PublicInternet));
+        assert!(!e.is_verified(RoutingDomain::LocalNetwork));
+    });
+
+    // Verification is not persisted, the node must prove itself again after a restart
+    let entry_bytes = entry.with_inner(|e| serialize_json_bytes(e));
+    let inner: BucketEntryInner = deserialize_json_bytes(&entry_bytes).unwrap();
+    assert!(!inner.is_verified(RoutingDomain::PublicInternet));
+
+    // No routing domain requires verified peers by default
+    let routing_table = mock_routing_table();
+    assert!(!routing_table
+        .unlocked_inner
+        .verified_peers_only(RoutingDomain::PublicInternet));
+    assert!(!routing_table
+        .unlocked_inner
+        .verified_peers_only(RoutingDomain::LocalNetwork));
+}
+
+fn make_dialable_entry(n: u8, dial_info: DialInfo) -> Arc<BucketEntry> {
+    let entry = Arc::new(make_entry(n, 0, SECS, MILLIS));
+    let node_info = NodeInfo::new(
+        NetworkClass::InboundCapable,
+        ProtocolTypeSet::all(),
+        AddressTypeSet::all(),
+        vec![0],
+        0,
+        vec![best_crypto_kind()],
+        vec![],
+        PUBLIC_INTERNET_CAPABILITIES.to_vec(),
+        vec![DialInfoDetail {
+            class: DialInfoClass::Direct,
+            dial_info,
+            preference: 0,
+        }],
+    );
+    entry.with_mut_inner(|e| {
+        e.update_signed_node_info(
+            RoutingDomain::PublicInternet,
+            SignedNodeInfo::Direct(SignedDirectNodeInfo::new(
+                node_info,
+                Timestamp::new(1),
+                Vec::new(),
+            )),
+        )
+    });
+    entry
+}
+
+fn make_flow(protocol_type: ProtocolType, remote: SocketAddr) -> Flow {
+    Flow::new(
+        PeerAddress::new(SocketAddress::from_socket_addr(remote), protocol_type),
+        SocketAddress::from_socket_addr("1.1.1.1:5150".parse().unwrap()),
+    )
+}
+
+pub async fn test_collect_warm_peers() {
+    let routing_table = mock_routing_table();
+    let udp_addr: SocketAddr = "1.2.3.4:5150".parse().unwrap();
+    let tcp_addr: SocketAddr = "1.2.3.5:5150".parse().unwrap();
+    let older_addr: SocketAddr = "1.2.3.6:5150".parse().unwrap();
+
+    // A peer we dialed, and one we last heard from at its dial info
+    let dialed = make_dialable_entry(1, DialInfo::udp_from_socketaddr(udp_addr));
+    dialed.with_mut_inner(|e| {
+        e.set_last_flow(make_flow(ProtocolType::UDP, udp_addr), Timestamp::new(200))
+    });
+    let older = make_dialable_entry(2, DialInfo::tcp_from_socketaddr(older_addr));
+    older.with_mut_inner(|e| {
+        e.set_last_flow(
+            make_flow(ProtocolType::TCP, older_addr),
+            Timestamp::new(100),
+        )
+    });
+
+    // A peer that only connected to us from a port we can not dial
+    let inbound = make_dialable_entry(3, DialInfo::tcp_from_socketaddr(tcp_addr));
+    inbound.with_mut_inner(|e| {
+        e.set_last_flow(
+            make_flow(ProtocolType::TCP, "1.2.3.5:40000".parse().unwrap()),
+            Timestamp::new(300),
+        )
+    });
+
+    let node_id = |entry: &Arc<BucketEntry>| entry.with_inner(|e| e.node_ids().best().unwrap());
+    let unreconnected = TypedKey::new(best_crypto_kind(), make_key(4));
+    {
+        let mut inner = routing_table.inner.write();
+        for entry in [&dialed, &older, &inbound] {
+            inner.all_entries.insert(entry.clone());
+        }
+
+        // Warm peers from before the restart stay until they are reconnected to,
+        // unless there is a newer flow to the same peer
+        inner.warm_peers = vec![
+            WarmPeer {
+                node_id: unreconnected,
+                protocol_type: ProtocolType::UDP,
+                address_type: AddressType::IPV6,
+                last_success_ts: Timestamp::new(150),
+            },
+            WarmPeer {
+                node_id: node_id(&dialed),
+                protocol_type: ProtocolType::TCP,
+                address_type: AddressType::IPV4,
+                last_success_ts: Timestamp::new(50),
+            },
+        ];
+    }
+
+    // Most recent first, with the kind of dial info the last flow used
+    let warm_peers = routing_table
+        .inner
+        .read()
+        .collect_warm_peers(WARM_PEER_COUNT);
+    let summary: Vec<_> = warm_peers
+        .iter()
+        .map(|wp| {
+            (
+                wp.node_id,
+                wp.protocol_type,
+                wp.address_type,
+                wp.last_success_ts,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                node_id(&dialed),
+                ProtocolType::UDP,
+                AddressType::IPV4,
+                Timestamp::new(200)
+            ),
+            (
+                unreconnected,
+                ProtocolType::UDP,
+                AddressType::IPV6,
+                Timestamp::new(150)
+            ),
+            (
+                node_id(&older),
+                ProtocolType::TCP,
+                AddressType::IPV4,
+                Timestamp::new(100)
+            ),
+        ]
+    );
+
+    // Only as many as asked for are kept
+    let warm_peers = routing_table.inner.read().collect_warm_peers(2);
+    assert_eq!(warm_peers.len(), 2);
+    assert_eq!(warm_peers[1].node_id, unreconnected);
+
+    // Taking the warm peers from before the restart leaves only the live ones
+    assert_eq!(routing_table.inner.write().take_warm_peers().len(), 2);
+    let warm_peers = routing_table
+        .inner
+        .read()
+        .collect_warm_peers(WARM_PEER_COUNT);
+    assert_eq!(warm_peers.len(), 2);
+    assert!(warm_peers.iter().all(|wp| wp.node_id != unreconnected));
+}
+
+pub async fn test_flap_dampening() {
+    let start_ts = 10_000 * SECS;
+
+    let entry = make_entry(1, start_ts - 120 * SECS, start_ts, 20 * MILLIS);
+    entry.with_mut_inner(|e| {
+        let ts = Timestamp::new(start_ts + SECS);
+        assert_eq!(e.state(ts), BucketEntryState::Reliable);
+        assert_eq!(e.flap_score(ts), 0);
+
+        // Dying and coming back are a flap each
+        simulate_dead(e, start_ts + 10 * SECS);
+        let ts = Timestamp::new(start_ts + 11 * SECS);
+        assert_eq!(e.state(ts), BucketEntryState::Dead);
+        assert_eq!(e.flap_score(ts), 1);
+        simulate_seen(e, start_ts + 20 * SECS, start_ts + 80 * SECS, 20 * MILLIS);
+        let ts = Timestamp::new(start_ts + 81 * SECS);
+        assert_eq!(e.state(ts), BucketEntryState::Reliable);
+        assert_eq!(e.flap_score(ts), 2);
+
+        // Past the threshold, each flap doubles how long the node must be seen to be reliable again
+        simulate_dead(e, start_ts + 90 * SECS);
+        simulate_seen(e, start_ts + 100 * SECS, start_ts + 160 * SECS, 20 * MILLIS);
+        let ts = Timestamp::new(start_ts + 161 * SECS);
+        assert_eq!(e.state(ts), BucketEntryState::Unreliable);
+        assert_eq!(e.flap_score(ts), 3);
+        simulate_seen(e, start_ts + 190 * SECS, start_ts + 220 * SECS, 20 * MILLIS);
+        let ts = Timestamp::new(start_ts + 221 * SECS);
+        assert_eq!(e.state(ts), BucketEntryState::Reliable);
+        assert_eq!(e.flap_score(ts), 4);
+
+        // Flaps are forgotten once they fall out of the detection window
+        assert_eq!(e.flap_score(Timestamp::new(start_ts + 1811 * SECS)), 3);
+        assert_eq!(e.flap_score(Timestamp::new(start_ts + 2100 * SECS)), 0);
+
+        // And the node is promoted as quickly as before
+        simulate_dead(e, start_ts + 2100 * SECS);
+        simulate_seen(
+            e,
+            start_ts + 2110 * SECS,
+            start_ts + 2170 * SECS,
+            20 * MILLIS,
+        );
+        let ts = Timestamp::new(start_ts + 2171 * SECS);
+        assert_eq!(e.state(ts), BucketEntryState::Reliable);
+        assert_eq!(e.flap_score(ts), 2);
+    });
+}
+
+pub async fn test_flap_ignores_punishment() {
+    let start_ts = 10_000 * SECS;
+
+    let entry = make_entry(1, start_ts - 120 * SECS, start_ts, 20 * MILLIS);
+    entry.with_mut_inner(|e| {
+        // Punishment makes the node dead, but that is not the node flapping
+        e.set_punished(true);
+        simulate_seen(e, start_ts + 10 * SECS, start_ts + 20 * SECS, 20 * MILLIS);
+        let ts = Timestamp::new(start_ts + 21 * SECS);
+        assert_eq!(e.state(ts), BucketEntryState::Dead);
+        assert_eq!(e.flap_score(ts), 0);
+
+        // Nor is coming back once the punishment is lifted
+        e.set_punished(false);
+        simulate_seen(e, start_ts + 30 * SECS, start_ts + 40 * SECS, 20 * MILLIS);
+        let ts = Timestamp::new(start_ts + 41 * SECS);
+        assert_eq!(e.state(ts), BucketEntryState::Reliable);
+        assert_eq!(e.flap_score(ts), 0);
+    });
+}
+
+pub async fn test_all() {
+    test_kick_order_prefers_uptime().await;
+    test_kick_order_prefers_low_latency().await;
+    test_kick_order_prefers_live().await;
+    test_bucket_kick().await;
+    test_stale_node_info().await;
+    test_clock_skew().await;
+    test_transfer_windows().await;
+    test_bandwidth_estimate().await;
+    test_talker_stats().await;
+    test_connection_closed().await;
+    test_flap_dampening().await;
+    test_flap_ignores_punishment().await;
+    test_live_node_ref_filter().await;
+    test_verified_per_routing_domain().await;
+    test_collect_warm_peers().await;
+}
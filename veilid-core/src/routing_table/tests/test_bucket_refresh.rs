@@ -0,0 +1,24 @@
+use super::*;
+use crate::routing_table::tasks::bucket_refresh::random_key_in_bucket;
+
+pub async fn test_random_key_in_bucket() {
+    let self_key = PublicKey::new([0x5au8; PUBLIC_KEY_LENGTH]);
+
+    for bucket in [0, 1, 7, 8, 9, 100, PUBLIC_KEY_LENGTH * 8 - 1] {
+        for _ in 0..16 {
+            let key = random_key_in_bucket(&self_key, bucket);
+            let mut distance = [0u8; PUBLIC_KEY_LENGTH];
+            for (n, d) in distance.iter_mut().enumerate() {
+                *d = key.bytes[n] ^ self_key.bytes[n];
+            }
+            assert_eq!(
+                CryptoKeyDistance::new(distance).first_nonzero_bit(),
+                Some(bucket)
+            );
+        }
+    }
+}
+
+pub async fn test_all() {
+    test_random_key_in_bucket().await;
+}
@@ -0,0 +1,196 @@
+use super::*;
+
+fn make_key(prefix: &[u8]) -> TypedKey {
+    let mut bytes = [0u8; PUBLIC_KEY_LENGTH];
+    bytes[..prefix.len()].copy_from_slice(prefix);
+    TypedKey::new(best_crypto_kind(), PublicKey::new(bytes))
+}
+
+fn make_peer_info(node_id: TypedKey) -> PeerInfo {
+    let mut node_ids = TypedKeyGroup::new();
+    node_ids.add(node_id);
+    PeerInfo::new(
+        node_ids,
+        SignedNodeInfo::Direct(SignedDirectNodeInfo::new(
+            NodeInfo::new(
+                NetworkClass::InboundCapable,
+                ProtocolTypeSet::new(),
+                AddressTypeSet::new(),
+                vec![0],
+                0,
+                vec![node_id.kind],
+                vec![],
+                PUBLIC_INTERNET_CAPABILITIES.to_vec(),
+                vec![],
+            ),
+            Timestamp::new(0),
+            Vec::new(),
+        )),
+    )
+}
+
+fn answer_node_ids(answer: &ValidatedFindNodeAnswer) -> Vec<TypedKey> {
+    answer
+        .peers
+        .iter()
+        .map(|p| p.node_ids().get(best_crypto_kind()).unwrap())
+        .collect()
+}
+
+pub async fn test_closer_peers_accepted() {
+    let routing_table = mock_routing_table();
+    let vcrypto = routing_table.crypto().get(best_crypto_kind()).unwrap();
+
+    let target = make_key(&[]);
+    let responder = make_key(&[0x80]);
+    let peers = vec![
+        make_key(&[0x40]),
+        make_key(&[0x00, 0x01]),
+        make_key(&[0x00, 0x00, 0x00, 0x01]),
+    ];
+
+    let answer = RoutingTable::validate_find_node_answer(
+        vcrypto,
+        responder,
+        target,
+        None,
+        peers.iter().copied().map(make_peer_info).collect(),
+    );
+    assert_eq!(answer.rejected_count(), 0);
+    assert!(!answer.is_garbage());
+    assert_eq!(answer_node_ids(&answer), peers);
+}
+
+pub async fn test_far_peers_rejected() {
+    let routing_table = mock_routing_table();
+    let vcrypto = routing_table.crypto().get(best_crypto_kind()).unwrap();
+
+    // Responder shares 23 leading bits with the target
+    let target = make_key(&[]);
+    let responder = make_key(&[0x00, 0x00, 0x01]);
+
+    // Slightly further away than the responder is fine, random far away nodes are not
+    let near = make_key(&[0x00, 0x00, 0x80]);
+    let far1 = make_key(&[0x80]);
+    let far2 = make_key(&[0x00, 0x80]);
+
+    let peers = || {
+        vec![
+            make_peer_info(far1),
+            make_peer_info(near),
+            make_peer_info(far2),
+        ]
+    };
+
+    // We already know nodes sharing 15 leading bits with the target, so both far peers are dropped
+    let horizon = vcrypto.distance(&make_key(&[0x00, 0x01]).value, &target.value);
+    let answer = RoutingTable::validate_find_node_answer(
+        vcrypto.clone(),
+        responder,
+        target,
+        Some(horizon),
+        peers(),
+    );
+    assert_eq!(answer.distant_count, 2);
+    assert_eq!(answer.malformed_count, 0);
+    // Distance alone does not get the responder punished
+    assert!(!answer.is_garbage());
+    assert_eq!(answer_node_ids(&answer), vec![near]);
+
+    // Peers closer than the closest nodes we know are kept, even if further than the responder
+    let horizon = vcrypto.distance(&make_key(&[0x01]).value, &target.value);
+    let answer = RoutingTable::validate_find_node_answer(
+        vcrypto.clone(),
+        responder,
+        target,
+        Some(horizon),
+        peers(),
+    );
+    assert_eq!(answer.distant_count, 1);
+    assert_eq!(answer_node_ids(&answer), vec![near, far2]);
+
+    // If we don't know enough nodes to tell, nothing is dropped for distance
+    let answer = RoutingTable::validate_find_node_answer(vcrypto, responder, target, None, peers());
+    assert_eq!(answer.rejected_count(), 0);
+    assert_eq!(answer_node_ids(&answer), vec![far1, near, far2]);
+}
+
+pub async fn test_repeated_and_wrong_kind_peers_rejected() {
+    let routing_table = mock_routing_table();
+    let vcrypto = routing_table.crypto().get(best_crypto_kind()).unwrap();
+
+    let target = make_key(&[]);
+    let responder = make_key(&[0x80]);
+    let peer = make_key(&[0x01]);
+    let wrong_kind = TypedKey::new(FourCC::from_str("FAKE").unwrap(), peer.value);
+
+    let answer = RoutingTable::validate_find_node_answer(
+        vcrypto,
+        responder,
+        target,
+        None,
+        vec![
+            make_peer_info(peer),
+            make_peer_info(peer),
+            make_peer_info(peer),
+            make_peer_info(wrong_kind),
+        ],
+    );
+    assert_eq!(answer.malformed_count, 3);
+    assert!(answer.is_garbage());
+    assert_eq!(answer_node_ids(&answer), vec![peer]);
+}
+
+pub async fn test_responder_is_target() {
+    let routing_table = mock_routing_table();
+    let vcrypto = routing_table.crypto().get(best_crypto_kind()).unwrap();
+
+    // Asking a node about itself can only ever return nodes further away
+    let target = make_key(&[0x00, 0x00, 0x01]);
+    let peers = vec![make_key(&[0x80]), make_key(&[0x40])];
+
+    let horizon = vcrypto.distance(&make_key(&[0x00, 0x00, 0x02]).value, &target.value);
+    let answer = RoutingTable::validate_find_node_answer(
+        vcrypto,
+        target,
+        target,
+        Some(horizon),
+        peers.iter().copied().map(make_peer_info).collect(),
+    );
+    assert_eq!(answer.rejected_count(), 0);
+    assert!(!answer.is_garbage());
+    assert_eq!(answer_node_ids(&answer), peers);
+}
+
+pub async fn test_mostly_good_answer_not_garbage() {
+    let routing_table = mock_routing_table();
+    let vcrypto = routing_table.crypto().get(best_crypto_kind()).unwrap();
+
+    let target = make_key(&[]);
+    let responder = make_key(&[0x00, 0x00, 0x01]);
+    let peers = vec![
+        make_key(&[0x00, 0x00, 0x00, 0x80]),
+        make_key(&[0x00, 0x00, 0x02]),
+        make_key(&[0x80]),
+    ];
+
+    let horizon = vcrypto.distance(&make_key(&[0x00, 0x00, 0x04]).value, &target.value);
+    let answer = RoutingTable::validate_find_node_answer(
+        vcrypto,
+        responder,
+        target,
+        Some(horizon),
+        peers.iter().copied().map(make_peer_info).collect(),
+    );
+    assert_eq!(answer.distant_count, 1);
+    assert!(!answer.is_garbage());
+    assert_eq!(answer_node_ids(&answer), peers[0..2].to_vec());
+}
+
+pub async fn test_all() {
+    test_closer_peers_accepted().await;
+    test_far_peers_rejected().await;
+    test_repeated_and_wrong_kind_peers_rejected().await;
+    test_responder_is_target().await;
+    test_mostly_good_answer_not_garbage().await;
+}
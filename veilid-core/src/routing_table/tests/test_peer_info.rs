@@ -0,0 +1,153 @@
+use super::*;
+
+fn make_node_info() -> NodeInfo {
+    NodeInfo::new(
+        NetworkClass::InboundCapable,
+        ProtocolTypeSet::new(),
+        AddressTypeSet::new(),
+        vec![0],
+        0,
+        vec![best_crypto_kind()],
+        vec![],
+        PUBLIC_INTERNET_CAPABILITIES.to_vec(),
+        vec![],
+    )
+}
+
+fn make_signer(crypto: Crypto, kp: TypedKeyPair) -> Arc<dyn NodeIdSigner> {
+    Arc::new(SecretKeyNodeIdSigner::new(
+        crypto.get(kp.kind).unwrap(),
+        kp.value,
+    ))
+}
+
+fn make_peer_info(crypto: Crypto, kp: TypedKeyPair) -> PeerInfo {
+    let mut node_ids = TypedKeyGroup::new();
+    node_ids.add(TypedKey::new(kp.kind, kp.value.key));
+    let sni = SignedDirectNodeInfo::make_signatures(&[make_signer(crypto, kp)], make_node_info())
+        .unwrap();
+    PeerInfo::new(node_ids, SignedNodeInfo::Direct(sni))
+}
+
+pub async fn test_node_id_cross_signatures() {
+    let routing_table = mock_routing_table();
+    let crypto = routing_table.crypto();
+
+    let kp = Crypto::generate_keypair(best_crypto_kind()).unwrap();
+    let pi = make_peer_info(crypto.clone(), kp);
+
+    // Peer infos from nodes that do not cross-sign are still accepted
+    assert!(pi.node_id_cross_signatures().is_empty());
+    pi.validate(crypto.clone()).expect("should validate");
+
+    let xsigs =
+        PeerInfo::make_node_id_cross_signatures(&[make_signer(crypto.clone(), kp)], pi.node_ids())
+            .unwrap();
+    let pi = pi.with_node_id_cross_signatures(xsigs.clone());
+    pi.validate(crypto.clone()).expect("should validate");
+    PeerInfo::validate_batch(&[pi.clone()], crypto.clone()).expect("should validate");
+
+    // Cross-signatures survive serialization
+    let pi2: PeerInfo = deserialize_json(&serialize_json(&pi)).expect("should deserialize");
+    assert_eq!(pi, pi2);
+
+    // Cross-signatures made over someone else's node ids do not validate
+    let kp2 = Crypto::generate_keypair(best_crypto_kind()).unwrap();
+    let pi3 = make_peer_info(crypto.clone(), kp2).with_node_id_cross_signatures(xsigs);
+    assert!(pi3.validate(crypto.clone()).is_err());
+    assert!(PeerInfo::validate_batch(&[pi, pi3], crypto).is_err());
+}
+
+pub async fn test_node_id_cross_signatures_stripped() {
+    if VALID_CRYPTO_KINDS.len() < 2 {
+        return;
+    }
+    let routing_table = mock_routing_table();
+    let crypto = routing_table.crypto();
+
+    let kps: Vec<TypedKeyPair> = VALID_CRYPTO_KINDS[0..2]
+        .iter()
+        .map(|ck| Crypto::generate_keypair(*ck).unwrap())
+        .collect();
+    let signers: Vec<_> = kps
+        .iter()
+        .map(|kp| make_signer(crypto.clone(), *kp))
+        .collect();
+    let mut node_ids = TypedKeyGroup::new();
+    for kp in &kps {
+        node_ids.add(TypedKey::new(kp.kind, kp.value.key));
+    }
+    let sni = SignedDirectNodeInfo::make_signatures(&signers, make_node_info()).unwrap();
+    let xsigs = PeerInfo::make_node_id_cross_signatures(&signers, &node_ids).unwrap();
+    let pi = PeerInfo::new(node_ids, SignedNodeInfo::Direct(sni));
+
+    // Multiple node ids with cross-signatures are accepted
+    let signed_pi = pi.clone().with_node_id_cross_signatures(xsigs);
+    signed_pi.validate(crypto.clone()).expect("should validate");
+    PeerInfo::validate_batch(&[signed_pi.clone()], crypto.clone()).expect("should validate");
+
+    // The same peer info with its cross-signatures stripped is rejected
+    assert!(pi.node_id_cross_signatures().is_empty());
+    assert!(pi.validate(crypto.clone()).is_err());
+    assert!(PeerInfo::validate_batch(&[signed_pi.clone(), pi.clone()], crypto.clone()).is_err());
+    let mut pis = vec![signed_pi.clone(), pi];
+    PeerInfo::validate_vec(&mut pis, crypto);
+    assert_eq!(pis, vec![signed_pi]);
+}
+
+pub async fn test_dial_info_preference() {
+    let udp = DialInfo::udp(SocketAddress::from_str("1.2.3.4:5150").unwrap());
+    let tcp = DialInfo::tcp(SocketAddress::from_str("1.2.3.4:5150").unwrap());
+    let wss = DialInfo::try_wss(
+        SocketAddress::from_str("1.2.3.4:443").unwrap(),
+        "wss://example.com/ws".to_owned(),
+    )
+    .unwrap();
+    let make_node_info = |wss_preference: u8| {
+        let did = |dial_info: &DialInfo, preference: u8| DialInfoDetail {
+            class: DialInfoClass::Direct,
+            dial_info: dial_info.clone(),
+            preference,
+        };
+        NodeInfo::new(
+            NetworkClass::InboundCapable,
+            ProtocolTypeSet::all(),
+            AddressTypeSet::all(),
+            vec![0],
+            0,
+            vec![best_crypto_kind()],
+            vec![],
+            PUBLIC_INTERNET_CAPABILITIES.to_vec(),
+            vec![did(&udp, 0), did(&tcp, 0), did(&wss, wss_preference)],
+        )
+    };
+    let first = |ni: &NodeInfo, ordered: bool| {
+        let sort = ordered.then_some(DialInfoDetail::ordered_sequencing_sort);
+        ni.first_filtered_dial_info_detail(sort, |_| true)
+            .unwrap()
+            .dial_info
+    };
+
+    // Without preferences the advertised order and the sequencing order are kept
+    let ni = make_node_info(0);
+    assert_eq!(first(&ni, false), udp);
+    assert_eq!(first(&ni, true), tcp);
+
+    // A preferred dial info is tried first, but never before an ordered one when ordering is required
+    let ni = make_node_info(10);
+    assert_eq!(first(&ni, false), wss);
+    assert_eq!(first(&ni, true), wss);
+    assert_eq!(
+        ni.all_filtered_dial_info_details(Some(DialInfoDetail::ordered_sequencing_sort), |_| true)
+            .into_iter()
+            .map(|did| did.dial_info)
+            .collect::<Vec<_>>(),
+        vec![wss.clone(), tcp.clone(), udp.clone()]
+    );
+}
+
+pub async fn test_all() {
+    test_node_id_cross_signatures().await;
+    test_node_id_cross_signatures_stripped().await;
+    test_dial_info_preference().await;
+}
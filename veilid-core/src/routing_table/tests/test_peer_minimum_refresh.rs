@@ -0,0 +1,46 @@
+use super::*;
+use crate::routing_table::tasks::peer_minimum_refresh::select_latency_diverse_peers;
+
+/// Peers 0..measured with latencies growing with their number, given farthest first,
+/// followed by unmeasured peers numbered from 100
+fn make_peers(measured: u32, unmeasured: u32) -> Vec<(u32, Option<TimestampDuration>)> {
+    let mut peers = (0..measured)
+        .rev()
+        .map(|n| (n, Some(TimestampDuration::new((n as u64 + 1) * 10_000))))
+        .collect::<Vec<_>>();
+    peers.extend((100..100 + unmeasured).map(|n| (n, None)));
+    peers
+}
+
+pub async fn test_select_latency_diverse_peers() {
+    // Measured peers are spread from nearest to farthest, and a quarter of the slots go to unmeasured peers
+    assert_eq!(
+        select_latency_diverse_peers(make_peers(10, 4), 8),
+        vec![0, 1, 3, 5, 7, 9, 100, 101]
+    );
+
+    // Slots nobody unmeasured can fill go to measured peers
+    assert_eq!(
+        select_latency_diverse_peers(make_peers(10, 0), 8),
+        vec![0, 1, 2, 3, 5, 6, 7, 9]
+    );
+
+    // And the other way around
+    assert_eq!(
+        select_latency_diverse_peers(make_peers(1, 5), 4),
+        vec![0, 100, 101, 102]
+    );
+
+    // Asking for more peers than there are returns all of them
+    assert_eq!(
+        select_latency_diverse_peers(make_peers(2, 1), 10),
+        vec![0, 1, 100]
+    );
+
+    assert!(select_latency_diverse_peers(make_peers(10, 4), 0).is_empty());
+    assert!(select_latency_diverse_peers(make_peers(0, 0), 8).is_empty());
+}
+
+pub async fn test_all() {
+    test_select_latency_diverse_peers().await;
+}
@@ -0,0 +1,280 @@
+use super::*;
+use crate::tests::test_veilid_config::*;
+
+const SECS: u64 = 1_000_000u64;
+
+pub async fn test_route_idle() {
+    let created_ts = 10_000 * SECS;
+    let idle_duration = TimestampDuration::new(3600 * SECS);
+
+    let mut stats = RouteStats::new(Timestamp::new(created_ts));
+
+    // Unused routes are idle once they are old enough
+    assert!(!stats.is_idle(Timestamp::new(created_ts + 60 * SECS), idle_duration));
+    assert!(stats.is_idle(Timestamp::new(created_ts + 3601 * SECS), idle_duration));
+
+    // Route tests keep a route alive for testing, but do not count as use
+    stats.record_received(
+        Timestamp::new(created_ts + 3000 * SECS),
+        ByteCount::new(100),
+    );
+    stats.record_tested(Timestamp::new(created_ts + 3000 * SECS));
+    assert!(stats.is_idle(Timestamp::new(created_ts + 3601 * SECS), idle_duration));
+
+    // Using the route pushes back when it goes idle
+    stats.record_used(Timestamp::new(created_ts + 3000 * SECS));
+    assert!(!stats.is_idle(Timestamp::new(created_ts + 3601 * SECS), idle_duration));
+    assert!(stats.is_idle(Timestamp::new(created_ts + 6601 * SECS), idle_duration));
+
+    // Resetting the stats when the network restarts does not forget the last use
+    stats.reset();
+    assert!(!stats.is_idle(Timestamp::new(created_ts + 3601 * SECS), idle_duration));
+}
+
+pub async fn test_hop_blame() {
+    let hop = |n: u8| PublicKey::new([n; PUBLIC_KEY_LENGTH]);
+    let ids = |n: u8| TypedKeyGroup::from(TypedKey::new(CRYPTO_KIND_VLD0, hop(n)));
+
+    let mut blame = HopBlame::new();
+
+    // A failure is split between the hops of the route
+    blame.record_failed(&[hop(1), hop(2)]);
+    assert_eq!(blame.get(&ids(1)), 0.5);
+    assert_eq!(blame.get(&ids(3)), 0.0);
+    assert!(!blame.is_avoided(&ids(1)));
+
+    // A single failure over a one hop route is not enough to avoid the hop
+    blame.record_failed(&[hop(5)]);
+    assert_eq!(blame.get(&ids(5)), 1.0);
+    assert!(!blame.is_avoided(&ids(5)));
+
+    // The hop shared by many failing routes collects the most blame and is avoided
+    for n in 3..8 {
+        blame.record_failed(&[hop(1), hop(n)]);
+    }
+    assert!(blame.is_avoided(&ids(1)));
+    assert!(!blame.is_avoided(&ids(2)));
+    assert!(!blame.is_avoided(&ids(3)));
+    assert!(!blame.is_avoided(&ids(5)));
+
+    // Success through a hop reduces its blame
+    blame.record_succeeded(&[hop(1), hop(8)]);
+    assert!(!blame.is_avoided(&ids(1)));
+    assert_eq!(blame.get(&ids(8)), 0.0);
+
+    // Failing to send puts the whole blame on the first hop
+    for _ in 0..3 {
+        blame.record_first_hop_failed(&hop(8));
+    }
+    assert!(blame.is_avoided(&ids(8)));
+
+    // Blame fades with time
+    blame.decay(TimestampDuration::new(600 * SECS));
+    assert_eq!(blame.get(&ids(8)), 1.5);
+    blame.decay(TimestampDuration::new(6000 * SECS));
+    assert_eq!(blame.get(&ids(1)), 0.0);
+    assert_eq!(blame.get(&ids(8)), 0.0);
+}
+
+pub async fn test_route_budget() {
+    let add_route =
+        |content: &mut RouteSpecStoreContent, n: u8, automatic: bool, api_instance: Option<u64>| {
+            let key = PublicKey::new([n; PUBLIC_KEY_LENGTH]);
+            let route_set = BTreeMap::from([(
+                key,
+                RouteSpecDetail {
+                    crypto_kind: CRYPTO_KIND_VLD0,
+                    secret_key: SecretKey::new([n; SECRET_KEY_LENGTH]),
+                    hops: vec![PublicKey::new([0xFF; PUBLIC_KEY_LENGTH])],
+                },
+            )]);
+            let mut rssd = RouteSetSpecDetail::new(
+                Timestamp::new(0),
+                route_set,
+                vec![],
+                DirectionSet::all(),
+                Stability::default(),
+                true,
+                false,
+                false,
+                automatic,
+            );
+            rssd.set_api_instance(api_instance);
+            content.add_detail(key, rssd);
+        };
+
+    let mut content = RouteSpecStoreContent::new();
+
+    // Each api instance has its own budget, routes without an instance share one
+    add_route(&mut content, 1, false, Some(1));
+    add_route(&mut content, 2, false, Some(1));
+    add_route(&mut content, 3, false, None);
+    assert_eq!(
+        content.check_route_budget(Some(1), 8, 2),
+        Err(RouteSpecStoreError::ApiRouteBudgetExhausted(2))
+    );
+    assert_eq!(content.check_route_budget(Some(2), 8, 2), Ok(()));
+    assert_eq!(content.check_route_budget(None, 8, 2), Ok(()));
+    add_route(&mut content, 4, false, None);
+    assert_eq!(
+        content.check_route_budget(None, 8, 2),
+        Err(RouteSpecStoreError::ApiRouteBudgetExhausted(2))
+    );
+
+    // Automatically allocated routes are not counted
+    add_route(&mut content, 5, true, Some(2));
+    add_route(&mut content, 6, true, Some(2));
+    assert_eq!(content.check_route_budget(Some(2), 8, 2), Ok(()));
+
+    // All manually allocated routes count against the overall budget
+    assert_eq!(content.check_route_budget(Some(2), 5, 2), Ok(()));
+    assert_eq!(
+        content.check_route_budget(Some(2), 4, 2),
+        Err(RouteSpecStoreError::RouteBudgetExhausted(4))
+    );
+}
+
+pub async fn test_error_conversion() {
+    assert!(matches!(
+        VeilidAPIError::from(RouteSpecStoreError::RouteBudgetExhausted(128)),
+        VeilidAPIError::TryAgain { .. }
+    ));
+    assert!(matches!(
+        VeilidAPIError::from(RouteSpecStoreError::ApiRouteBudgetExhausted(32)),
+        VeilidAPIError::TryAgain { .. }
+    ));
+
+    // Route compilation failures keep the failed constraint when passing through rpc
+    let err = VeilidAPIError::from(RPCError::RouteCompilation(RouteCompilationError {
+        constraint: RouteConstraint::FirstHop,
+        hop_count: 2,
+        candidates: 0,
+    }));
+    assert!(matches!(
+        err,
+        VeilidAPIError::RouteCompilation {
+            error: RouteCompilationError {
+                constraint: RouteConstraint::FirstHop,
+                hop_count: 2,
+                candidates: 0,
+            }
+        }
+    ));
+}
+
+pub async fn test_content_restart_round_trip() {
+    let (update_callback, config_callback) = setup_veilid_core();
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+    let routing_table = api.routing_table().unwrap();
+    let vcrypto = api.crypto().unwrap().best();
+    let cur_ts = get_aligned_timestamp();
+
+    // Routes only survive a restart if their hops are still in the routing table
+    let mut hops = Vec::new();
+    let mut hop_node_refs = Vec::new();
+    for n in 0..2u8 {
+        let hop = vcrypto.generate_keypair().key;
+        let flow = Flow::new_no_local(PeerAddress::new(
+            SocketAddress::new(Address::IPV4(Ipv4Addr::new(192, 0, 2, n + 1)), 5150),
+            ProtocolType::TCP,
+        ));
+        let nr = routing_table
+            .register_node_with_existing_connection(
+                TypedKey::new(vcrypto.kind(), hop),
+                flow,
+                cur_ts,
+            )
+            .unwrap();
+        hops.push(hop);
+        hop_node_refs.push(nr);
+    }
+
+    let route_keypair = vcrypto.generate_keypair();
+    let mut route_set = BTreeMap::new();
+    route_set.insert(
+        route_keypair.key,
+        RouteSpecDetail {
+            crypto_kind: vcrypto.kind(),
+            secret_key: route_keypair.secret,
+            hops,
+        },
+    );
+    let id = route_keypair.key;
+    let mut content = RouteSpecStoreContent::new();
+    content.add_detail(
+        id,
+        RouteSetSpecDetail::new(
+            cur_ts,
+            route_set,
+            hop_node_refs,
+            DirectionSet::all(),
+            Stability::default(),
+            true,
+            false,
+            false,
+            false,
+        ),
+    );
+
+    // Secret keys go to the protected store, not the table store
+    let saved_secret_keys = content
+        .save(routing_table.clone(), HashSet::new())
+        .await
+        .unwrap();
+    assert!(saved_secret_keys.contains(&route_keypair.key));
+    let rsstdb = api
+        .table_store()
+        .unwrap()
+        .open("RouteSpecStore", 1)
+        .await
+        .unwrap();
+    let stored = rsstdb.load(0, b"content").await.unwrap().unwrap();
+    let stored = String::from_utf8(stored).unwrap();
+    assert!(!stored.contains(&route_keypair.secret.to_string()));
+
+    // Loading again brings back the route along with its secret key
+    let (loaded, loaded_secret_keys) = RouteSpecStoreContent::load(routing_table.clone())
+        .await
+        .unwrap();
+    assert_eq!(loaded_secret_keys, saved_secret_keys);
+    let rssd = loaded
+        .get_detail(&id)
+        .expect("route should have been loaded");
+    let rsd = rssd.get_route_by_key(&route_keypair.key).unwrap();
+    assert_eq!(rsd.secret_key, route_keypair.secret);
+    assert_eq!(rssd.hop_count(), 2);
+
+    // Secret keys of routes that are gone are removed from the protected store
+    let saved_secret_keys = RouteSpecStoreContent::new()
+        .save(routing_table.clone(), loaded_secret_keys)
+        .await
+        .unwrap();
+    assert!(saved_secret_keys.is_empty());
+
+    // A route whose secret key is missing does not survive loading
+    content
+        .save(routing_table.clone(), HashSet::from([route_keypair.key]))
+        .await
+        .unwrap();
+    let (loaded, loaded_secret_keys) = RouteSpecStoreContent::load(routing_table.clone())
+        .await
+        .unwrap();
+    assert_eq!(loaded.get_detail_count(), 0);
+    assert!(loaded_secret_keys.is_empty());
+
+    RouteSpecStoreContent::new()
+        .save(routing_table, HashSet::new())
+        .await
+        .unwrap();
+    api.shutdown().await;
+}
+
+pub async fn test_all() {
+    test_route_idle().await;
+    test_hop_blame().await;
+    test_route_budget().await;
+    test_error_conversion().await;
+    test_content_restart_round_trip().await;
+}
@@ -0,0 +1,66 @@
+use super::*;
+
+pub async fn test_lookup_errors() {
+    let routing_table = mock_routing_table();
+    routing_table.init().await.unwrap();
+
+    let bad_kind = FourCC(*b"NONE");
+    let node_id = TypedKey::new(bad_kind, PublicKey::new([1u8; PUBLIC_KEY_LENGTH]));
+    assert_eq!(
+        routing_table.lookup_node_ref(node_id).err(),
+        Some(RoutingTableError::InvalidCryptoKind(bad_kind))
+    );
+
+    let node_id = TypedKey::new(best_crypto_kind(), PublicKey::new([1u8; PUBLIC_KEY_LENGTH]));
+    assert!(matches!(routing_table.lookup_node_ref(node_id), Ok(None)));
+
+    routing_table.terminate().await;
+}
+
+pub async fn test_error_conversion() {
+    let node_id = TypedKey::new(best_crypto_kind(), PublicKey::new([1u8; PUBLIC_KEY_LENGTH]));
+
+    assert!(matches!(
+        VeilidAPIError::from(RoutingTableError::OwnNodeId),
+        VeilidAPIError::InvalidTarget { .. }
+    ));
+    assert!(matches!(
+        VeilidAPIError::from(RoutingTableError::InvalidCryptoKind(best_crypto_kind())),
+        VeilidAPIError::InvalidArgument { .. }
+    ));
+    assert!(matches!(
+        VeilidAPIError::from(RoutingTableError::table_full("full")),
+        VeilidAPIError::TryAgain { .. }
+    ));
+    assert!(matches!(
+        VeilidAPIError::from(RoutingTableError::Banned(node_id)),
+        VeilidAPIError::InvalidTarget { .. }
+    ));
+    assert!(matches!(
+        VeilidAPIError::from(RoutingTableError::RateLimited(node_id)),
+        VeilidAPIError::TryAgain { .. }
+    ));
+
+    assert!(matches!(
+        RPCError::from(RoutingTableError::invalid_signed_node_info("bad")),
+        RPCError::Protocol(_)
+    ));
+    assert!(matches!(
+        RPCError::from(RoutingTableError::table_full("full")),
+        RPCError::TryAgain(_)
+    ));
+    assert!(matches!(
+        RPCError::from(RoutingTableError::Banned(node_id)),
+        RPCError::Ignore(_)
+    ));
+    // Rate limited nodes are not at fault, so they are neither punished nor retried
+    assert!(matches!(
+        RPCError::from(RoutingTableError::RateLimited(node_id)),
+        RPCError::Ignore(_)
+    ));
+}
+
+pub async fn test_all() {
+    test_lookup_errors().await;
+    test_error_conversion().await;
+}
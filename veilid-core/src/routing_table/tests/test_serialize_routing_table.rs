@@ -72,7 +72,9 @@ pub async fn test_round_trip_peerinfo() {
                 ProtocolTypeSet::new(),
                 AddressTypeSet::new(),
                 vec![0],
+                0,
                 vec![CRYPTO_KIND_VLD0],
+                vec![],
                 PUBLIC_INTERNET_CAPABILITIES.to_vec(),
                 vec![],
             ),
@@ -88,7 +90,35 @@ pub async fn test_round_trip_peerinfo() {
     assert_eq!(s, s2);
 }
 
+pub async fn test_peer_table_export() {
+    let routing_table = mock_routing_table();
+    routing_table.init().await.unwrap();
+
+    let node_id = TypedKey::new(CRYPTO_KIND_VLD0, CryptoKey::new([1u8; CRYPTO_KEY_LENGTH]));
+    let flow = Flow::new_no_local(PeerAddress::new(
+        SocketAddress::new(Address::IPV4(Ipv4Addr::new(192, 0, 2, 1)), 5150),
+        ProtocolType::UDP,
+    ));
+    routing_table
+        .register_node_with_existing_connection(node_id, flow, get_aligned_timestamp())
+        .unwrap();
+
+    // Every entry is exported along with its state and stats
+    let entries = routing_table.export_peer_table(BucketEntryState::Dead);
+    assert_eq!(entries.len(), 1);
+    assert!(entries[0].node_ids.contains(&node_id));
+    let s = serialize_json(&entries);
+    let entries2: Vec<PeerTableExportEntry> = deserialize_json(&s).expect("Should deserialize");
+    assert_eq!(s, serialize_json(&entries2));
+
+    // Only nodes with signed public internet node info make it into the bootstrap list
+    assert!(routing_table.export_bootstrap_peers().is_empty());
+
+    routing_table.terminate().await;
+}
+
 pub async fn test_all() {
     test_routingtable_buckets_round_trip().await;
     test_round_trip_peerinfo().await;
+    test_peer_table_export().await;
 }
@@ -5,6 +5,9 @@ use super::*;
 pub struct DialInfoDetail {
     pub class: DialInfoClass,
     pub dial_info: DialInfo,
+    /// How much the node prefers to be reached over this dial info, higher first
+    #[serde(default)]
+    pub preference: u8,
 }
 
 impl MatchesDialInfoFilter for DialInfoDetail {
@@ -17,12 +20,29 @@ pub type DialInfoDetailSort = dyn Fn(&DialInfoDetail, &DialInfoDetail) -> core::
 
 impl DialInfoDetail {
     pub fn ordered_sequencing_sort(a: &DialInfoDetail, b: &DialInfoDetail) -> core::cmp::Ordering {
+        // Ordered protocols always come first, the node's preference only orders within them
+        let c = b
+            .dial_info
+            .protocol_type()
+            .is_ordered()
+            .cmp(&a.dial_info.protocol_type().is_ordered());
+        if c != core::cmp::Ordering::Equal {
+            return c;
+        }
+        let c = Self::preference_sort(a, b);
+        if c != core::cmp::Ordering::Equal {
+            return c;
+        }
         let c = DialInfo::ordered_sequencing_sort(&a.dial_info, &b.dial_info);
         if c != core::cmp::Ordering::Equal {
             return c;
         }
         a.class.cmp(&b.class)
     }
+    /// Sort by the preference advertised by the node, higher preference first
+    pub fn preference_sort(a: &DialInfoDetail, b: &DialInfoDetail) -> core::cmp::Ordering {
+        b.preference.cmp(&a.preference)
+    }
     pub const NO_SORT: std::option::Option<
         for<'r, 's> fn(&'r DialInfoDetail, &'s DialInfoDetail) -> std::cmp::Ordering,
     > = None::<fn(&DialInfoDetail, &DialInfoDetail) -> core::cmp::Ordering>;
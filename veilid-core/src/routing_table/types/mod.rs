@@ -7,6 +7,7 @@ mod routing_domain;
 mod signed_direct_node_info;
 mod signed_node_info;
 mod signed_relayed_node_info;
+mod warm_peer;
 
 use super::*;
 
@@ -19,3 +20,4 @@ pub use routing_domain::*;
 pub use signed_direct_node_info::*;
 pub use signed_node_info::*;
 pub use signed_relayed_node_info::*;
+pub use warm_peer::*;
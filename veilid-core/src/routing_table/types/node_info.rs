@@ -9,7 +9,12 @@ pub const CAP_RELAY: Capability = FourCC(*b"RLAY");
 pub const CAP_VALIDATE_DIAL_INFO: Capability = FourCC(*b"DIAL");
 pub const CAP_DHT: Capability = FourCC(*b"DHTV");
 pub const CAP_DHT_WATCH: Capability = FourCC(*b"DHTW");
+pub const CAP_DHT_COMPRESSION: Capability = FourCC(*b"DHTC");
+pub const CAP_DHT_BATCH: Capability = FourCC(*b"DHTB");
+pub const CAP_DHT_DIGEST: Capability = FourCC(*b"DHTD");
 pub const CAP_APPMESSAGE: Capability = FourCC(*b"APPM");
+pub const CAP_ENVELOPE_COMPRESSION: Capability = FourCC(*b"ENVC");
+pub const CAP_BUSY_ANSWER: Capability = FourCC(*b"BUSY");
 #[cfg(feature = "unstable-blockstore")]
 pub const CAP_BLOCKSTORE: Capability = FourCC(*b"BLOC");
 
@@ -19,9 +24,16 @@ pub struct NodeInfo {
     outbound_protocols: ProtocolTypeSet,
     address_types: AddressTypeSet,
     envelope_support: Vec<u8>,
+    #[serde(default)]
+    max_envelope_size: u32,
     crypto_support: Vec<CryptoKind>,
+    #[serde(default)]
+    kem_public_keys: Vec<TypedKemPublicKey>,
     capabilities: Vec<Capability>,
     dial_info_detail_list: Vec<DialInfoDetail>,
+    /// Fields from a newer schema than ours, kept so they can be encoded again as they were received
+    #[serde(skip)]
+    unknown_fields: Option<UnknownFields>,
 }
 
 impl NodeInfo {
@@ -30,7 +42,9 @@ impl NodeInfo {
         outbound_protocols: ProtocolTypeSet,
         address_types: AddressTypeSet,
         envelope_support: Vec<u8>,
+        max_envelope_size: u32,
         crypto_support: Vec<CryptoKind>,
+        kem_public_keys: Vec<TypedKemPublicKey>,
         capabilities: Vec<Capability>,
         dial_info_detail_list: Vec<DialInfoDetail>,
     ) -> Self {
@@ -39,12 +53,20 @@ impl NodeInfo {
             outbound_protocols,
             address_types,
             envelope_support,
+            max_envelope_size,
             crypto_support,
+            kem_public_keys,
             capabilities,
             dial_info_detail_list,
+            unknown_fields: None,
         }
     }
 
+    pub fn with_unknown_fields(mut self, unknown_fields: Option<UnknownFields>) -> Self {
+        self.unknown_fields = unknown_fields;
+        self
+    }
+
     pub fn network_class(&self) -> NetworkClass {
         self.network_class
     }
@@ -57,15 +79,40 @@ impl NodeInfo {
     pub fn envelope_support(&self) -> &[u8] {
         &self.envelope_support
     }
+    /// The largest envelope this node advertised it wants to receive, 0 if it did not advertise one
+    pub fn max_envelope_size(&self) -> u32 {
+        self.max_envelope_size
+    }
+    /// The largest envelope we should send to this node
+    /// Nodes that advertise a maximum envelope size can also reassemble messages split to fit it,
+    /// nodes that do not advertise one take envelopes of any size
+    pub fn envelope_size_limit(&self) -> Option<usize> {
+        if self.max_envelope_size == 0 {
+            return None;
+        }
+        Some((self.max_envelope_size as usize).clamp(MIN_MAX_ENVELOPE_SIZE, MAX_ENVELOPE_SIZE))
+    }
     pub fn crypto_support(&self) -> &[CryptoKind] {
         &self.crypto_support
     }
+    pub fn kem_public_keys(&self) -> &[TypedKemPublicKey] {
+        &self.kem_public_keys
+    }
+    pub fn kem_public_key(&self, kind: CryptoKind) -> Option<KemPublicKey> {
+        self.kem_public_keys
+            .iter()
+            .find(|k| k.kind == kind)
+            .map(|k| k.value)
+    }
     pub fn capabilities(&self) -> &[Capability] {
         &self.capabilities
     }
     pub fn dial_info_detail_list(&self) -> &[DialInfoDetail] {
         &self.dial_info_detail_list
     }
+    pub fn unknown_fields(&self) -> Option<&UnknownFields> {
+        self.unknown_fields.as_ref()
+    }
 
     pub fn first_filtered_dial_info_detail<S, F>(
         &self,
@@ -76,22 +123,9 @@ impl NodeInfo {
         S: Fn(&DialInfoDetail, &DialInfoDetail) -> std::cmp::Ordering,
         F: Fn(&DialInfoDetail) -> bool,
     {
-        if let Some(sort) = sort {
-            let mut dids = self.dial_info_detail_list.clone();
-            dids.sort_by(sort);
-            for did in dids {
-                if filter(&did) {
-                    return Some(did);
-                }
-            }
-        } else {
-            for did in &self.dial_info_detail_list {
-                if filter(did) {
-                    return Some(did.clone());
-                }
-            }
-        };
-        None
+        self.sorted_dial_info_detail_list(sort)
+            .into_iter()
+            .find(|did| filter(did))
     }
 
     pub fn all_filtered_dial_info_details<S, F>(
@@ -103,24 +137,25 @@ impl NodeInfo {
         S: Fn(&DialInfoDetail, &DialInfoDetail) -> std::cmp::Ordering,
         F: Fn(&DialInfoDetail) -> bool,
     {
-        let mut dial_info_detail_list = Vec::new();
+        self.sorted_dial_info_detail_list(sort)
+            .into_iter()
+            .filter(|did| filter(did))
+            .collect()
+    }
 
+    /// Get the dial info details in the order they should be tried
+    /// Dial info details the sort considers equal are tried in the order of the node's preference
+    fn sorted_dial_info_detail_list<S>(&self, sort: Option<S>) -> Vec<DialInfoDetail>
+    where
+        S: Fn(&DialInfoDetail, &DialInfoDetail) -> std::cmp::Ordering,
+    {
+        let mut dids = self.dial_info_detail_list.clone();
         if let Some(sort) = sort {
-            let mut dids = self.dial_info_detail_list.clone();
-            dids.sort_by(sort);
-            for did in dids {
-                if filter(&did) {
-                    dial_info_detail_list.push(did);
-                }
-            }
+            dids.sort_by(|a, b| sort(a, b).then_with(|| DialInfoDetail::preference_sort(a, b)));
         } else {
-            for did in &self.dial_info_detail_list {
-                if filter(did) {
-                    dial_info_detail_list.push(did.clone());
-                }
-            }
-        };
-        dial_info_detail_list
+            dids.sort_by(DialInfoDetail::preference_sort);
+        }
+        dids
     }
 
     /// Does this node has some dial info
@@ -2,7 +2,9 @@ use super::*;
 
 /// Non-nodeinfo status for each node is returned by the StatusA call
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct NodeStatus {
-    // Reserved for expansion
+    /// Proof-of-work difficulty the node demands from route operations sent to it, zero if none
+    #[serde(default)]
+    pub route_pow_difficulty: u8,
 }
@@ -4,6 +4,10 @@ use super::*;
 pub struct PeerInfo {
     node_ids: TypedKeyGroup,
     signed_node_info: SignedNodeInfo,
+    /// Signatures by each node id over the whole set of node ids, binding the ids together
+    /// so an id of one crypto kind can not be claimed by a node holding a different kind
+    #[serde(default)]
+    node_id_cross_signatures: Vec<TypedSignature>,
 }
 
 impl PeerInfo {
@@ -12,29 +16,115 @@ impl PeerInfo {
         Self {
             node_ids,
             signed_node_info,
+            node_id_cross_signatures: Vec::new(),
         }
     }
 
+    pub fn with_node_id_cross_signatures(
+        mut self,
+        node_id_cross_signatures: Vec<TypedSignature>,
+    ) -> Self {
+        self.node_id_cross_signatures = node_id_cross_signatures;
+        self
+    }
+
     pub fn validate(&self, crypto: Crypto) -> VeilidAPIResult<()> {
-        let validated_node_ids = self.signed_node_info.validate(&self.node_ids, crypto)?;
+        let validated_node_ids = self
+            .signed_node_info
+            .validate(&self.node_ids, crypto.clone())?;
         if validated_node_ids.is_empty() {
             // Shouldn't get here because signed node info validation also checks this
             apibail_generic!("no validated node ids");
         }
+        self.validate_node_id_cross_signatures(crypto)
+    }
+
+    /// Check that every node id we can validate has signed the full set of node ids.
+    /// A peer info with a single node id needs no cross-signatures, but one that claims more than
+    /// one node id must carry them, otherwise anyone could attach their node id to someone else's node.
+    pub fn validate_node_id_cross_signatures(&self, crypto: Crypto) -> VeilidAPIResult<()> {
+        if self.node_id_cross_signatures.is_empty() {
+            if self.node_ids.len() > 1 {
+                apibail_generic!("multiple node ids are not cross-signed");
+            }
+            return Ok(());
+        }
+        let data = Self::make_node_id_cross_signature_bytes(&self.node_ids);
+        let cross_signed_node_ids =
+            crypto.verify_signatures(&self.node_ids, &data, &self.node_id_cross_signatures)?;
+        for nid in self.node_ids.iter() {
+            if crypto.get(nid.kind).is_some() && !cross_signed_node_ids.contains(nid) {
+                apibail_generic!(format!("node id is not cross-signed: {}", nid));
+            }
+        }
         Ok(())
     }
 
+    /// Sign the set of node ids with each of its keys
+    pub fn make_node_id_cross_signatures(
+        signers: &[Arc<dyn NodeIdSigner>],
+        node_ids: &TypedKeyGroup,
+    ) -> VeilidAPIResult<Vec<TypedSignature>> {
+        let data = Self::make_node_id_cross_signature_bytes(node_ids);
+        generate_node_id_signatures(signers, &data)
+    }
+
+    fn make_node_id_cross_signature_bytes(node_ids: &TypedKeyGroup) -> Vec<u8> {
+        let mut data = Vec::with_capacity(node_ids.len() * (4 + PUBLIC_KEY_LENGTH));
+        for nid in node_ids.iter() {
+            data.extend_from_slice(&nid.kind.0);
+            data.extend_from_slice(&nid.value.bytes);
+        }
+        data
+    }
+
     pub fn node_ids(&self) -> &TypedKeyGroup {
         &self.node_ids
     }
     pub fn signed_node_info(&self) -> &SignedNodeInfo {
         &self.signed_node_info
     }
+    pub fn node_id_cross_signatures(&self) -> &[TypedSignature] {
+        &self.node_id_cross_signatures
+    }
     pub fn destructure(self) -> (TypedKeyGroup, SignedNodeInfo) {
         (self.node_ids, self.signed_node_info)
     }
 
+    /// Validate all of the peer infos at once
+    /// Fails if any of them are invalid, without saying which
+    pub fn validate_batch(peer_info_vec: &[PeerInfo], crypto: Crypto) -> VeilidAPIResult<()> {
+        let signed_data = peer_info_vec
+            .iter()
+            .map(|pi| pi.signed_node_info.signed_data())
+            .collect::<VeilidAPIResult<Vec<_>>>()?;
+        let batch: Vec<_> = peer_info_vec
+            .iter()
+            .zip(signed_data.iter())
+            .map(|(pi, data)| {
+                (
+                    &pi.node_ids[..],
+                    data.as_slice(),
+                    pi.signed_node_info.signatures(),
+                )
+            })
+            .collect();
+        crypto.verify_signatures_batch(&batch)?;
+
+        // Cross-signatures are rare, so they are checked one peer info at a time
+        for pi in peer_info_vec {
+            pi.validate_node_id_cross_signatures(crypto.clone())?;
+        }
+        Ok(())
+    }
+
     pub fn validate_vec(peer_info_vec: &mut Vec<PeerInfo>, crypto: Crypto) {
+        // Usually every peer info is valid, and checking them all together is much faster
+        if Self::validate_batch(peer_info_vec, crypto.clone()).is_ok() {
+            return;
+        }
+
+        // Otherwise find and drop the invalid ones
         let mut n = 0usize;
         while n < peer_info_vec.len() {
             let pi = peer_info_vec.get(n).unwrap();
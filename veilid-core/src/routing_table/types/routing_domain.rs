@@ -4,7 +4,8 @@ use super::*;
 
 // Routing domain here is listed in order of preference, keep in order
 #[allow(clippy::derived_hash_with_manual_eq)]
-#[derive(Debug, Ord, PartialOrd, Hash, EnumSetType, Serialize, Deserialize)]
+#[derive(Debug, Ord, PartialOrd, Hash, EnumSetType, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
 #[enumset(repr = "u8")]
 pub enum RoutingDomain {
     LocalNetwork = 0,
@@ -24,7 +24,7 @@ impl SignedDirectNodeInfo {
         node_ids: &TypedKeyGroup,
         crypto: Crypto,
     ) -> VeilidAPIResult<TypedKeyGroup> {
-        let node_info_bytes = Self::make_signature_bytes(&self.node_info, self.timestamp)?;
+        let node_info_bytes = self.signed_data()?;
 
         // Verify the signatures that we can
         let validated_node_ids =
@@ -36,17 +36,18 @@ impl SignedDirectNodeInfo {
         Ok(validated_node_ids)
     }
 
+    /// Get the bytes covered by the signatures
+    pub fn signed_data(&self) -> VeilidAPIResult<Vec<u8>> {
+        Self::make_signature_bytes(&self.node_info, self.timestamp)
+    }
+
     pub fn make_signatures(
-        crypto: Crypto,
-        typed_key_pairs: Vec<TypedKeyPair>,
+        signers: &[Arc<dyn NodeIdSigner>],
         node_info: NodeInfo,
     ) -> VeilidAPIResult<Self> {
         let timestamp = get_aligned_timestamp();
         let node_info_bytes = Self::make_signature_bytes(&node_info, timestamp)?;
-        let typed_signatures =
-            crypto.generate_signatures(&node_info_bytes, &typed_key_pairs, |kp, s| {
-                TypedSignature::new(kp.kind, s)
-            })?;
+        let typed_signatures = generate_node_id_signatures(signers, &node_info_bytes)?;
         Ok(Self {
             node_info,
             timestamp,
@@ -61,9 +62,7 @@ impl SignedDirectNodeInfo {
         let mut node_info_bytes = Vec::new();
 
         // Add nodeinfo to signature
-        let mut ni_msg = ::capnp::message::Builder::new_default();
-        let mut ni_builder = ni_msg.init_root::<veilid_capnp::node_info::Builder>();
-        encode_node_info(node_info, &mut ni_builder).map_err(VeilidAPIError::internal)?;
+        let ni_msg = encode_node_info_message(node_info).map_err(VeilidAPIError::internal)?;
         node_info_bytes.append(&mut builder_to_vec(ni_msg).map_err(VeilidAPIError::internal)?);
 
         // Add timestamp to signature
@@ -18,6 +18,21 @@ impl SignedNodeInfo {
         }
     }
 
+    /// Get the bytes covered by the signatures, after checking everything else that makes the node info valid
+    pub fn signed_data(&self) -> VeilidAPIResult<Vec<u8>> {
+        match self {
+            SignedNodeInfo::Direct(d) => d.signed_data(),
+            SignedNodeInfo::Relayed(r) => r.signed_data(),
+        }
+    }
+
+    pub fn signatures(&self) -> &[TypedSignature] {
+        match self {
+            SignedNodeInfo::Direct(d) => d.signatures(),
+            SignedNodeInfo::Relayed(r) => r.signatures(),
+        }
+    }
+
     pub fn has_any_signature(&self) -> bool {
         match self {
             SignedNodeInfo::Direct(d) => d.has_any_signature(),
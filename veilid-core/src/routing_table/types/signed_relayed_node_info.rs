@@ -35,6 +35,18 @@ impl SignedRelayedNodeInfo {
         node_ids: &TypedKeyGroup,
         crypto: Crypto,
     ) -> VeilidAPIResult<TypedKeyGroup> {
+        // Verify signatures
+        let node_info_bytes = self.signed_data()?;
+        let validated_node_ids =
+            crypto.verify_signatures(node_ids, &node_info_bytes, &self.signatures)?;
+        if validated_node_ids.is_empty() {
+            apibail_generic!("no valid node ids in relayed node info");
+        }
+        Ok(validated_node_ids)
+    }
+
+    /// Get the bytes covered by the signatures, after checking everything else that makes the node info valid
+    pub fn signed_data(&self) -> VeilidAPIResult<Vec<u8>> {
         // Ensure the relay info for the node has a superset of the crypto kinds of the node it is relaying
         if common_crypto_kinds(
             self.node_info.crypto_support(),
@@ -46,24 +58,16 @@ impl SignedRelayedNodeInfo {
             apibail_generic!("relay should have superset of node crypto kinds");
         }
 
-        // Verify signatures
-        let node_info_bytes = Self::make_signature_bytes(
+        Self::make_signature_bytes(
             &self.node_info,
             &self.relay_ids,
             &self.relay_info,
             self.timestamp,
-        )?;
-        let validated_node_ids =
-            crypto.verify_signatures(node_ids, &node_info_bytes, &self.signatures)?;
-        if validated_node_ids.is_empty() {
-            apibail_generic!("no valid node ids in relayed node info");
-        }
-        Ok(validated_node_ids)
+        )
     }
 
     pub fn make_signatures(
-        crypto: Crypto,
-        typed_key_pairs: Vec<TypedKeyPair>,
+        signers: &[Arc<dyn NodeIdSigner>],
         node_info: NodeInfo,
         relay_ids: TypedKeyGroup,
         relay_info: SignedDirectNodeInfo,
@@ -71,10 +75,7 @@ impl SignedRelayedNodeInfo {
         let timestamp = get_aligned_timestamp();
         let node_info_bytes =
             Self::make_signature_bytes(&node_info, &relay_ids, &relay_info, timestamp)?;
-        let typed_signatures =
-            crypto.generate_signatures(&node_info_bytes, &typed_key_pairs, |kp, s| {
-                TypedSignature::new(kp.kind, s)
-            })?;
+        let typed_signatures = generate_node_id_signatures(signers, &node_info_bytes)?;
         Ok(Self {
             node_info,
             relay_ids,
@@ -93,9 +94,7 @@ impl SignedRelayedNodeInfo {
         let mut sig_bytes = Vec::new();
 
         // Add nodeinfo to signature
-        let mut ni_msg = ::capnp::message::Builder::new_default();
-        let mut ni_builder = ni_msg.init_root::<veilid_capnp::node_info::Builder>();
-        encode_node_info(node_info, &mut ni_builder).map_err(VeilidAPIError::internal)?;
+        let ni_msg = encode_node_info_message(node_info).map_err(VeilidAPIError::internal)?;
         sig_bytes.append(&mut builder_to_vec(ni_msg).map_err(VeilidAPIError::internal)?);
 
         // Add relay ids to signature
@@ -0,0 +1,15 @@
+use super::*;
+
+/// A peer we had a working connection to, remembered across restarts so we can
+/// reconnect to it right away instead of waiting for bootstrap
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WarmPeer {
+    /// The node id of the peer
+    pub node_id: TypedKey,
+    /// The protocol the last flow to the peer used
+    pub protocol_type: ProtocolType,
+    /// The address type the last flow to the peer used
+    pub address_type: AddressType,
+    /// When the last flow to the peer was used
+    pub last_success_ts: Timestamp,
+}
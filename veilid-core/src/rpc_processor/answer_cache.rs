@@ -0,0 +1,117 @@
+use super::*;
+use hashlink::LruCache;
+
+/// How long an answer to an idempotent question may be replayed to the same sender
+pub(super) const ANSWER_CACHE_TTL_US: u64 = 5_000_000u64;
+/// Maximum number of answers kept for replay
+pub(super) const ANSWER_CACHE_SIZE: usize = 1024;
+
+/// The idempotent questions whose answers may be replayed
+///
+/// Value operations must never be added here. Their answers depend on record state
+/// that can change between two identical questions, and the sender relies on seeing
+/// those changes right away.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(super) enum AnswerCacheQuestion {
+    FindNode {
+        node_id: TypedKey,
+        capabilities: Vec<Capability>,
+    },
+    Status,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(super) struct AnswerCacheKey {
+    /// The node that asked the question
+    sender: TypedKey,
+    /// The flow the question arrived on, answers can include the sender's address
+    flow: Flow,
+    /// The question that was asked
+    question: AnswerCacheQuestion,
+}
+
+impl AnswerCacheKey {
+    pub fn new(sender: TypedKey, flow: Flow, question: AnswerCacheQuestion) -> Self {
+        Self {
+            sender,
+            flow,
+            question,
+        }
+    }
+}
+
+struct AnswerCacheEntry {
+    answer: RPCAnswer,
+    timestamp: Timestamp,
+}
+
+/// Short-lived cache of answers to idempotent questions
+///
+/// Peers refreshing their routing tables tend to ask the same FindNodeQ and StatusQ
+/// several times within a few seconds. Replaying the previous answer saves recomputing it.
+pub(super) struct AnswerCache {
+    entries: Mutex<LruCache<AnswerCacheKey, AnswerCacheEntry>>,
+}
+
+impl AnswerCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    pub fn get(&self, key: &AnswerCacheKey, cur_ts: Timestamp) -> Option<RPCAnswer> {
+        let mut entries = self.entries.lock();
+        let entry = entries.get(key)?;
+        if cur_ts.saturating_sub(entry.timestamp).as_u64() >= ANSWER_CACHE_TTL_US {
+            entries.remove(key);
+            return None;
+        }
+        Some(entry.answer.clone())
+    }
+
+    pub fn insert(&self, key: AnswerCacheKey, answer: RPCAnswer, cur_ts: Timestamp) {
+        self.entries.lock().insert(
+            key,
+            AnswerCacheEntry {
+                answer,
+                timestamp: cur_ts,
+            },
+        );
+    }
+}
+
+impl RPCProcessor {
+    /// Get the key for caching the answer to a question
+    ///
+    /// Only questions received directly are cached. Routed questions have no sender to key on.
+    pub(super) fn answer_cache_key(
+        msg: &RPCMessage,
+        question: AnswerCacheQuestion,
+    ) -> Option<AnswerCacheKey> {
+        match &msg.header.detail {
+            RPCMessageHeaderDetail::Direct(detail) => Some(AnswerCacheKey::new(
+                detail.envelope.get_sender_typed_id(),
+                detail.flow,
+                question,
+            )),
+            RPCMessageHeaderDetail::SafetyRouted(_) | RPCMessageHeaderDetail::PrivateRouted(_) => {
+                None
+            }
+        }
+    }
+
+    /// Look up a recent answer to replay for a question
+    pub(super) fn get_cached_answer(&self, key: &AnswerCacheKey) -> Option<RPCAnswer> {
+        self.unlocked_inner
+            .answer_cache
+            .get(key, get_aligned_timestamp())
+    }
+
+    /// Remember an answer so it can be replayed if the same question is asked again soon
+    pub(super) fn cache_answer(&self, key: AnswerCacheKey, answer: RPCAnswer) {
+        self.unlocked_inner
+            .answer_cache
+            .insert(key, answer, get_aligned_timestamp());
+    }
+}
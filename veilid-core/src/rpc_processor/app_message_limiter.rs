@@ -0,0 +1,208 @@
+use super::*;
+use hashlink::LruCache;
+
+/// The largest app message that can be sent or received
+pub const MAX_APP_MESSAGE_SIZE: usize = 32768;
+/// The largest app call question or answer that can be sent or received
+pub const MAX_APP_CALL_SIZE: usize = 32768;
+
+/// How long app messages and calls are counted against a source before the count starts over
+const APP_MESSAGE_RATE_WINDOW_US: u64 = 60_000_000;
+/// How many sources to keep counts for before the least recently seen one is forgotten
+const APP_MESSAGE_LIMITER_MAX_SOURCES: usize = 1024;
+/// How many app messages and calls over its limits a node can send in one window before it is punished
+const APP_MESSAGE_VIOLATIONS_BEFORE_PUNISHMENT: u32 = 16;
+
+/// Where an app message or call came from, for counting it against the limits
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(super) enum AppMessageSource {
+    /// The sender node, when it is known
+    Node(TypedKey),
+    /// The remote safety route, when the sender is hidden behind one
+    SafetyRoute(PublicKey),
+}
+
+impl AppMessageSource {
+    fn new(header: &RPCMessageHeader, sender: Option<TypedKey>) -> Self {
+        if let Some(sender) = sender {
+            return Self::Node(sender);
+        }
+        match &header.detail {
+            RPCMessageHeaderDetail::Direct(_) => Self::Node(header.direct_sender_node_id()),
+            RPCMessageHeaderDetail::SafetyRouted(s) => Self::SafetyRoute(s.remote_safety_route),
+            RPCMessageHeaderDetail::PrivateRouted(p) => Self::SafetyRoute(p.remote_safety_route),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(super) enum AppMessageKind {
+    Message,
+    Call,
+}
+
+/// What to do with an incoming app message or call
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(super) enum AppMessageVerdict {
+    /// Pass it up to the application
+    Accept,
+    /// Drop it without passing it up
+    Drop,
+    /// Drop it and punish the sender for repeatedly going over its limits
+    Punish,
+}
+
+#[derive(Debug, Default)]
+struct AppMessageWindow {
+    start_ts: u64,
+    messages: u32,
+    calls: u32,
+    violations: u32,
+}
+
+/// Limits the size and per-source rate of app messages and calls passed to the application
+///
+/// Each source gets its own count of messages and calls per minute. Anything over the limits is dropped,
+/// and a node that keeps sending over its limits within the same minute is punished.
+pub(super) struct AppMessageLimiter {
+    max_message_size: usize,
+    max_call_size: usize,
+    max_messages_per_min: u32,
+    max_calls_per_min: u32,
+    windows: LruCache<AppMessageSource, AppMessageWindow>,
+    policies: HashMap<TypedKey, AppMessagePolicy>,
+}
+
+impl AppMessageLimiter {
+    pub fn new(
+        max_message_size: usize,
+        max_call_size: usize,
+        max_messages_per_min: u32,
+        max_calls_per_min: u32,
+    ) -> Self {
+        Self {
+            max_message_size,
+            max_call_size,
+            max_messages_per_min,
+            max_calls_per_min,
+            windows: LruCache::new(APP_MESSAGE_LIMITER_MAX_SOURCES),
+            policies: HashMap::new(),
+        }
+    }
+
+    pub fn set_policy(&mut self, node_id: TypedKey, policy: AppMessagePolicy) {
+        if policy == AppMessagePolicy::default() {
+            self.policies.remove(&node_id);
+        } else {
+            self.policies.insert(node_id, policy);
+        }
+    }
+
+    pub fn get_policy(&self, source: AppMessageSource) -> AppMessagePolicy {
+        match source {
+            AppMessageSource::Node(node_id) => {
+                self.policies.get(&node_id).copied().unwrap_or_default()
+            }
+            AppMessageSource::SafetyRoute(_) => AppMessagePolicy::default(),
+        }
+    }
+
+    /// Count an incoming app message or call against its source and decide what to do with it
+    pub fn check(
+        &mut self,
+        source: AppMessageSource,
+        kind: AppMessageKind,
+        size: usize,
+        cur_ts: Timestamp,
+    ) -> AppMessageVerdict {
+        let policy = self.get_policy(source);
+        if policy == AppMessagePolicy::Blocked {
+            return AppMessageVerdict::Drop;
+        }
+
+        let (max_size, max_per_min) = match kind {
+            AppMessageKind::Message => (self.max_message_size, self.max_messages_per_min),
+            AppMessageKind::Call => (self.max_call_size, self.max_calls_per_min),
+        };
+
+        // Start a new window for this source if its last one is over
+        let cur_ts = cur_ts.as_u64();
+        let expired = self
+            .windows
+            .get(&source)
+            .map(|w| cur_ts.saturating_sub(w.start_ts) >= APP_MESSAGE_RATE_WINDOW_US)
+            .unwrap_or(true);
+        if expired {
+            self.windows.insert(
+                source,
+                AppMessageWindow {
+                    start_ts: cur_ts,
+                    ..Default::default()
+                },
+            );
+        }
+        let window = self.windows.get_mut(&source).unwrap();
+
+        let count = match kind {
+            AppMessageKind::Message => &mut window.messages,
+            AppMessageKind::Call => &mut window.calls,
+        };
+        *count = count.saturating_add(1);
+
+        let over_rate =
+            policy == AppMessagePolicy::Limited && max_per_min != 0 && *count > max_per_min;
+        if size <= max_size && !over_rate {
+            return AppMessageVerdict::Accept;
+        }
+
+        // Punish only once per window, the punishment outlasts the window anyway
+        window.violations = window.violations.saturating_add(1);
+        if window.violations == APP_MESSAGE_VIOLATIONS_BEFORE_PUNISHMENT {
+            AppMessageVerdict::Punish
+        } else {
+            AppMessageVerdict::Drop
+        }
+    }
+}
+
+impl RPCProcessor {
+    /// Check an incoming app message or call against the limits for where it came from
+    /// Returns false if it should be dropped instead of being passed up to the application
+    pub(super) fn check_app_message_limits(
+        &self,
+        header: &RPCMessageHeader,
+        sender: Option<TypedKey>,
+        kind: AppMessageKind,
+        size: usize,
+    ) -> bool {
+        let source = AppMessageSource::new(header, sender);
+        let verdict = self.unlocked_inner.app_message_limiter.lock().check(
+            source,
+            kind,
+            size,
+            get_aligned_timestamp(),
+        );
+        match verdict {
+            AppMessageVerdict::Accept => true,
+            AppMessageVerdict::Drop => false,
+            AppMessageVerdict::Punish => {
+                // Sources hidden behind a safety route can only be dropped
+                if let AppMessageSource::Node(node_id) = source {
+                    log_rpc!(debug "Punishing node for flooding app messages: {}", node_id);
+                    self.network_manager()
+                        .address_filter()
+                        .punish_node_id(node_id);
+                }
+                false
+            }
+        }
+    }
+
+    /// Set how app messages and calls from a node are limited
+    pub fn set_app_message_policy(&self, node_id: TypedKey, policy: AppMessagePolicy) {
+        self.unlocked_inner
+            .app_message_limiter
+            .lock()
+            .set_policy(node_id, policy);
+    }
+}
@@ -59,6 +59,30 @@ pub(crate) fn decode_dial_info(
             )
             .map_err(RPCError::map_protocol("invalid WSS dial info"))
         }
+        veilid_capnp::dial_info::Which::Onion(onion) => {
+            let onion = onion.map_err(RPCError::protocol)?;
+            let host = onion
+                .get_host()
+                .map_err(RPCError::map_protocol("missing Onion host"))?;
+            DialInfo::try_onion(
+                host.to_string()
+                    .map_err(RPCError::map_protocol("invalid Onion host string"))?,
+                onion.get_port(),
+            )
+            .map_err(RPCError::map_protocol("invalid Onion dial info"))
+        }
+        veilid_capnp::dial_info::Which::Ipc(ipc) => {
+            let ipc = ipc.map_err(RPCError::protocol)?;
+            let path = ipc
+                .get_path()
+                .map_err(RPCError::map_protocol("missing IPC path"))?;
+            DialInfo::try_ipc(
+                ipc.get_host_id(),
+                path.to_string()
+                    .map_err(RPCError::map_protocol("invalid IPC path string"))?,
+            )
+            .map_err(RPCError::map_protocol("invalid IPC dial info"))
+        }
     }
 }
 
@@ -117,6 +141,29 @@ pub(crate) fn encode_dial_info(
             );
             requestb.push_str(request.as_str());
         }
+        DialInfo::Onion(onion) => {
+            let mut di_onion_builder = builder.reborrow().init_onion();
+            di_onion_builder.set_port(onion.socket_address.port());
+            let mut hostb = di_onion_builder.init_host(
+                onion
+                    .host
+                    .len()
+                    .try_into()
+                    .map_err(RPCError::map_protocol("host too long"))?,
+            );
+            hostb.push_str(onion.host.as_str());
+        }
+        DialInfo::IPC(ipc) => {
+            let mut di_ipc_builder = builder.reborrow().init_ipc();
+            di_ipc_builder.set_host_id(ipc.host_id);
+            let mut pathb = di_ipc_builder.init_path(
+                ipc.path
+                    .len()
+                    .try_into()
+                    .map_err(RPCError::map_protocol("path too long"))?,
+            );
+            pathb.push_str(ipc.path.as_str());
+        }
     };
     Ok(())
 }
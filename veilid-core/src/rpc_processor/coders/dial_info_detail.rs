@@ -8,6 +8,7 @@ pub(crate) fn encode_dial_info_detail(
     encode_dial_info(&dial_info_detail.dial_info, &mut di_builder)?;
 
     builder.set_class(encode_dial_info_class(dial_info_detail.class));
+    builder.set_preference(dial_info_detail.preference);
     Ok(())
 }
 
@@ -22,6 +23,11 @@ pub(crate) fn decode_dial_info_detail(
     )?;
 
     let class = decode_dial_info_class(reader.reborrow().get_class().map_err(RPCError::protocol)?);
+    let preference = reader.reborrow().get_preference();
 
-    Ok(DialInfoDetail { dial_info, class })
+    Ok(DialInfoDetail {
+        dial_info,
+        class,
+        preference,
+    })
 }
@@ -24,9 +24,12 @@ mod signed_value_descriptor;
 mod socket_address;
 #[cfg(feature = "unstable-tunnels")]
 mod tunnel;
+mod typed_kem_public_key;
 mod typed_key;
 mod typed_signature;
+mod unknown_fields;
 
+pub(crate) use operations::MAX_DIGEST_VALUE_Q_SUBKEY_RANGES_LEN;
 pub(crate) use operations::MAX_INSPECT_VALUE_A_SEQS_LEN;
 pub(in crate::rpc_processor) use operations::*;
 
@@ -55,8 +58,10 @@ pub use signed_value_descriptor::*;
 pub use socket_address::*;
 #[cfg(feature = "unstable-tunnels")]
 pub use tunnel::*;
+pub use typed_kem_public_key::*;
 pub use typed_key::*;
 pub use typed_signature::*;
+pub use unknown_fields::*;
 
 use super::*;
 
@@ -65,7 +70,9 @@ use super::*;
 pub(in crate::rpc_processor) enum QuestionContext {
     GetValue(ValidateGetValueContext),
     SetValue(ValidateSetValueContext),
+    SetValueBatch(ValidateSetValueBatchContext),
     InspectValue(ValidateInspectValueContext),
+    DigestValue(ValidateDigestValueContext),
 }
 
 #[derive(Clone)]
@@ -1,5 +1,27 @@
 use super::*;
 
+const MAX_DIAL_INFO_DETAIL_LIST_LEN: usize = 128;
+
+/// Encode node info as the root of its own message, including any fields it was received with
+/// that are newer than our schema
+///
+/// This is what node info signatures cover, and what gets copied into the structs that hold node info.
+pub fn encode_node_info_message(
+    node_info: &NodeInfo,
+) -> Result<capnp::message::Builder<capnp::message::HeapAllocator>, RPCError> {
+    let mut message = capnp::message::Builder::new_default();
+    match node_info.unknown_fields() {
+        Some(unknown_fields) => unknown_fields.encode_node_info(node_info, &mut message)?,
+        None => {
+            let mut builder = message.init_root::<veilid_capnp::node_info::Builder>();
+            encode_node_info(node_info, &mut builder)?;
+        }
+    }
+    Ok(message)
+}
+
+/// Fields added to NodeInfo must be encoded after all of the existing ones, in the order they appear
+/// in the schema, so that older nodes can rebuild the bytes we sign (see UnknownFields)
 pub fn encode_node_info(
     node_info: &NodeInfo,
     builder: &mut veilid_capnp::node_info::Builder,
@@ -19,6 +41,8 @@ pub fn encode_node_info(
         s.clone_from_slice(node_info.envelope_support());
     }
 
+    builder.set_max_envelope_size(node_info.max_envelope_size());
+
     let mut cs_builder = builder
         .reborrow()
         .init_crypto_support(node_info.crypto_support().len() as u32);
@@ -31,6 +55,20 @@ pub fn encode_node_info(
         s.clone_from_slice(&csvec);
     }
 
+    let mut kpk_builder = builder.reborrow().init_kem_public_keys(
+        node_info
+            .kem_public_keys()
+            .len()
+            .try_into()
+            .map_err(RPCError::map_protocol(
+                "too many kem public keys in node info",
+            ))?,
+    );
+    for (idx, kpk) in node_info.kem_public_keys().iter().enumerate() {
+        let mut kpk_builder = kpk_builder.reborrow().get(idx as u32);
+        encode_typed_kem_public_key(kpk, &mut kpk_builder);
+    }
+
     let mut cap_builder = builder
         .reborrow()
         .init_capabilities(node_info.capabilities().len() as u32);
@@ -87,12 +125,13 @@ pub fn decode_node_info(reader: &veilid_capnp::node_info::Reader) -> Result<Node
         .reborrow()
         .get_envelope_support()
         .map_err(RPCError::protocol)?;
-    let envelope_support = es_reader.as_slice().map(|s| s.to_vec()).unwrap_or_default();
+    let envelope_support: Vec<u8> = es_reader.iter().collect();
 
     // Ensure envelope versions are not duplicated
     // Unsorted is okay, some nodes may have a different envelope order preference
     // But nothing should show up more than once
     let mut eversions = envelope_support.clone();
+    eversions.sort();
     eversions.dedup();
     if eversions.len() != envelope_support.len() {
         return Err(RPCError::protocol("duplicate envelope versions"));
@@ -104,6 +143,11 @@ pub fn decode_node_info(reader: &veilid_capnp::node_info::Reader) -> Result<Node
         return Err(RPCError::protocol("no envelope versions"));
     }
 
+    let max_envelope_size = reader.reborrow().get_max_envelope_size();
+    if max_envelope_size != 0 && (max_envelope_size as usize) < MIN_MAX_ENVELOPE_SIZE {
+        return Err(RPCError::protocol("max envelope size too small"));
+    }
+
     let cs_reader = reader
         .reborrow()
         .get_crypto_support()
@@ -114,14 +158,15 @@ pub fn decode_node_info(reader: &veilid_capnp::node_info::Reader) -> Result<Node
     }
 
     let crypto_support: Vec<CryptoKind> = cs_reader
-        .as_slice()
-        .map(|s| s.iter().map(|x| FourCC::from(x.to_be_bytes())).collect())
-        .unwrap_or_default();
+        .iter()
+        .map(|x| FourCC::from(x.to_be_bytes()))
+        .collect();
 
     // Ensure crypto kinds are not duplicated
     // Unsorted is okay, some nodes may have a different crypto order preference
     // But nothing should show up more than once
     let mut ckinds = crypto_support.clone();
+    ckinds.sort();
     ckinds.dedup();
     if ckinds.len() != crypto_support.len() {
         return Err(RPCError::protocol("duplicate crypto kinds"));
@@ -133,6 +178,28 @@ pub fn decode_node_info(reader: &veilid_capnp::node_info::Reader) -> Result<Node
         return Err(RPCError::protocol("no crypto kinds"));
     }
 
+    let kpk_reader = reader
+        .reborrow()
+        .get_kem_public_keys()
+        .map_err(RPCError::protocol)?;
+    if kpk_reader.len() as usize > MAX_CRYPTO_KINDS {
+        return Err(RPCError::protocol("too many kem public keys"));
+    }
+    let mut kem_public_keys = Vec::<TypedKemPublicKey>::with_capacity(kpk_reader.len() as usize);
+    for kpk in kpk_reader.iter() {
+        let kpk = decode_typed_kem_public_key(&kpk)?;
+        // Only one key per crypto kind, and only for crypto kinds this node supports
+        if kem_public_keys.iter().any(|x| x.kind == kpk.kind) {
+            return Err(RPCError::protocol("duplicate kem public key crypto kinds"));
+        }
+        if !crypto_support.contains(&kpk.kind) {
+            return Err(RPCError::protocol(
+                "kem public key for unsupported crypto kind",
+            ));
+        }
+        kem_public_keys.push(kpk);
+    }
+
     let cap_reader = reader
         .reborrow()
         .get_capabilities()
@@ -141,14 +208,17 @@ pub fn decode_node_info(reader: &veilid_capnp::node_info::Reader) -> Result<Node
         return Err(RPCError::protocol("too many capabilities"));
     }
     let capabilities = cap_reader
-        .as_slice()
-        .map(|s| s.iter().map(|x| FourCC::from(x.to_be_bytes())).collect())
-        .unwrap_or_default();
+        .iter()
+        .map(|x| FourCC::from(x.to_be_bytes()))
+        .collect();
 
     let didl_reader = reader
         .reborrow()
         .get_dial_info_detail_list()
         .map_err(RPCError::protocol)?;
+    if didl_reader.len() as usize > MAX_DIAL_INFO_DETAIL_LIST_LEN {
+        return Err(RPCError::protocol("too many dial info details"));
+    }
     let mut dial_info_detail_list = Vec::<DialInfoDetail>::with_capacity(
         didl_reader
             .len()
@@ -159,13 +229,18 @@ pub fn decode_node_info(reader: &veilid_capnp::node_info::Reader) -> Result<Node
         dial_info_detail_list.push(decode_dial_info_detail(&did)?)
     }
 
+    let unknown_fields = UnknownFields::from_node_info_reader(reader)?;
+
     Ok(NodeInfo::new(
         network_class,
         outbound_protocols,
         address_types,
         envelope_support,
+        max_envelope_size,
         crypto_support,
+        kem_public_keys,
         capabilities,
         dial_info_detail_list,
-    ))
+    )
+    .with_unknown_fields(unknown_fields))
 }
@@ -1,14 +1,17 @@
 use super::*;
 
 pub fn encode_node_status(
-    _node_status: &NodeStatus,
-    _builder: &mut veilid_capnp::node_status::Builder,
+    node_status: &NodeStatus,
+    builder: &mut veilid_capnp::node_status::Builder,
 ) -> Result<(), RPCError> {
+    builder.set_route_pow_difficulty(node_status.route_pow_difficulty);
     Ok(())
 }
 
 pub fn decode_node_status(
-    _reader: &veilid_capnp::node_status::Reader,
+    reader: &veilid_capnp::node_status::Reader,
 ) -> Result<NodeStatus, RPCError> {
-    Ok(NodeStatus {})
+    Ok(NodeStatus {
+        route_pow_difficulty: reader.get_route_pow_difficulty(),
+    })
 }
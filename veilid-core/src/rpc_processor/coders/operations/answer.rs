@@ -16,6 +16,9 @@ impl RPCAnswer {
     pub fn desc(&self) -> &'static str {
         self.detail.desc()
     }
+    pub fn is_control(&self) -> bool {
+        self.detail.is_control()
+    }
     pub fn destructure(self) -> RPCAnswerDetail {
         self.detail
     }
@@ -39,6 +42,9 @@ pub(in crate::rpc_processor) enum RPCAnswerDetail {
     SetValueA(Box<RPCOperationSetValueA>),
     WatchValueA(Box<RPCOperationWatchValueA>),
     InspectValueA(Box<RPCOperationInspectValueA>),
+    BusyA(Box<RPCOperationBusyA>),
+    SetValueBatchA(Box<RPCOperationSetValueBatchA>),
+    DigestValueA(Box<RPCOperationDigestValueA>),
     #[cfg(feature = "unstable-blockstore")]
     SupplyBlockA(Box<RPCOperationSupplyBlockA>),
     #[cfg(feature = "unstable-blockstore")]
@@ -52,6 +58,13 @@ pub(in crate::rpc_processor) enum RPCAnswerDetail {
 }
 
 impl RPCAnswerDetail {
+    /// Control answers keep the network working and are sent ahead of application data
+    pub fn is_control(&self) -> bool {
+        matches!(
+            self,
+            RPCAnswerDetail::StatusA(_) | RPCAnswerDetail::FindNodeA(_) | RPCAnswerDetail::BusyA(_)
+        )
+    }
     #[cfg(feature = "verbose-tracing")]
     pub fn desc(&self) -> &'static str {
         match self {
@@ -62,6 +75,9 @@ impl RPCAnswerDetail {
             RPCAnswerDetail::SetValueA(_) => "SetValueA",
             RPCAnswerDetail::WatchValueA(_) => "WatchValueA",
             RPCAnswerDetail::InspectValueA(_) => "InspectValueA",
+            RPCAnswerDetail::BusyA(_) => "BusyA",
+            RPCAnswerDetail::SetValueBatchA(_) => "SetValueBatchA",
+            RPCAnswerDetail::DigestValueA(_) => "DigestValueA",
             #[cfg(feature = "unstable-blockstore")]
             RPCAnswerDetail::SupplyBlockA(_) => "SupplyBlockA",
             #[cfg(feature = "unstable-blockstore")]
@@ -83,6 +99,9 @@ impl RPCAnswerDetail {
             RPCAnswerDetail::SetValueA(r) => r.validate(validate_context),
             RPCAnswerDetail::WatchValueA(r) => r.validate(validate_context),
             RPCAnswerDetail::InspectValueA(r) => r.validate(validate_context),
+            RPCAnswerDetail::BusyA(r) => r.validate(validate_context),
+            RPCAnswerDetail::SetValueBatchA(r) => r.validate(validate_context),
+            RPCAnswerDetail::DigestValueA(r) => r.validate(validate_context),
             #[cfg(feature = "unstable-blockstore")]
             RPCAnswerDetail::SupplyBlockA(r) => r.validate(validate_context),
             #[cfg(feature = "unstable-blockstore")]
@@ -135,6 +154,21 @@ impl RPCAnswerDetail {
                 let out = RPCOperationInspectValueA::decode(&op_reader)?;
                 RPCAnswerDetail::InspectValueA(Box::new(out))
             }
+            veilid_capnp::answer::detail::BusyA(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationBusyA::decode(&op_reader)?;
+                RPCAnswerDetail::BusyA(Box::new(out))
+            }
+            veilid_capnp::answer::detail::SetValueBatchA(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationSetValueBatchA::decode(&op_reader)?;
+                RPCAnswerDetail::SetValueBatchA(Box::new(out))
+            }
+            veilid_capnp::answer::detail::DigestValueA(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationDigestValueA::decode(&op_reader)?;
+                RPCAnswerDetail::DigestValueA(Box::new(out))
+            }
             #[cfg(feature = "unstable-blockstore")]
             veilid_capnp::answer::detail::SupplyBlockA(r) => {
                 let op_reader = r.map_err(RPCError::protocol)?;
@@ -184,6 +218,13 @@ impl RPCAnswerDetail {
             RPCAnswerDetail::InspectValueA(d) => {
                 d.encode(&mut builder.reborrow().init_inspect_value_a())
             }
+            RPCAnswerDetail::BusyA(d) => d.encode(&mut builder.reborrow().init_busy_a()),
+            RPCAnswerDetail::SetValueBatchA(d) => {
+                d.encode(&mut builder.reborrow().init_set_value_batch_a())
+            }
+            RPCAnswerDetail::DigestValueA(d) => {
+                d.encode(&mut builder.reborrow().init_digest_value_a())
+            }
             #[cfg(feature = "unstable-blockstore")]
             RPCAnswerDetail::SupplyBlockA(d) => {
                 d.encode(&mut builder.reborrow().init_supply_block_a())
@@ -2,12 +2,15 @@ mod answer;
 mod operation;
 mod operation_app_call;
 mod operation_app_message;
+mod operation_busy;
+mod operation_digest_value;
 mod operation_find_node;
 mod operation_get_value;
 mod operation_inspect_value;
 mod operation_return_receipt;
 mod operation_route;
 mod operation_set_value;
+mod operation_set_value_batch;
 mod operation_signal;
 mod operation_status;
 
@@ -34,12 +37,15 @@ pub(in crate::rpc_processor) use answer::*;
 pub(in crate::rpc_processor) use operation::*;
 pub(in crate::rpc_processor) use operation_app_call::*;
 pub(in crate::rpc_processor) use operation_app_message::*;
+pub(in crate::rpc_processor) use operation_busy::*;
+pub(in crate::rpc_processor) use operation_digest_value::*;
 pub(in crate::rpc_processor) use operation_find_node::*;
 pub(in crate::rpc_processor) use operation_get_value::*;
 pub(in crate::rpc_processor) use operation_inspect_value::*;
 pub(in crate::rpc_processor) use operation_return_receipt::*;
 pub(in crate::rpc_processor) use operation_route::*;
 pub(in crate::rpc_processor) use operation_set_value::*;
+pub(in crate::rpc_processor) use operation_set_value_batch::*;
 pub(in crate::rpc_processor) use operation_signal::*;
 pub(in crate::rpc_processor) use operation_status::*;
 pub(in crate::rpc_processor) use operation_validate_dial_info::*;
@@ -63,4 +69,5 @@ pub(in crate::rpc_processor) use operation_start_tunnel::*;
 
 use super::*;
 
+pub(crate) use operation_digest_value::MAX_DIGEST_VALUE_Q_SUBKEY_RANGES_LEN;
 pub(crate) use operation_inspect_value::MAX_INSPECT_VALUE_A_SEQS_LEN;
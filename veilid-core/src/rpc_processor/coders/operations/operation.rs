@@ -17,6 +17,15 @@ impl RPCOperationKind {
         }
     }
 
+    /// Control operations may be sent ahead of application data queued on the same connection
+    pub fn is_control(&self) -> bool {
+        match self {
+            RPCOperationKind::Question(q) => q.is_control(),
+            RPCOperationKind::Statement(s) => s.is_control(),
+            RPCOperationKind::Answer(a) => a.is_control(),
+        }
+    }
+
     pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
         match self {
             RPCOperationKind::Question(r) => r.validate(validate_context),
@@ -1,7 +1,7 @@
 use super::*;
 
-const MAX_APP_CALL_Q_MESSAGE_LEN: usize = 32768;
-const MAX_APP_CALL_A_MESSAGE_LEN: usize = 32768;
+const MAX_APP_CALL_Q_MESSAGE_LEN: usize = MAX_APP_CALL_SIZE;
+const MAX_APP_CALL_A_MESSAGE_LEN: usize = MAX_APP_CALL_SIZE;
 
 #[derive(Debug, Clone)]
 pub(in crate::rpc_processor) struct RPCOperationAppCallQ {
@@ -1,6 +1,6 @@
 use super::*;
 
-const MAX_APP_MESSAGE_MESSAGE_LEN: usize = 32768;
+const MAX_APP_MESSAGE_MESSAGE_LEN: usize = MAX_APP_MESSAGE_SIZE;
 
 #[derive(Debug, Clone)]
 pub(in crate::rpc_processor) struct RPCOperationAppMessage {
@@ -0,0 +1,24 @@
+use super::*;
+
+/// Sent instead of the answer to a question when we are too overloaded to process it
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationBusyA {}
+
+impl RPCOperationBusyA {
+    pub fn new() -> Self {
+        Self {}
+    }
+    pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        Ok(())
+    }
+
+    pub fn decode(_reader: &veilid_capnp::operation_busy_a::Reader) -> Result<Self, RPCError> {
+        Ok(Self {})
+    }
+    pub fn encode(
+        &self,
+        _builder: &mut veilid_capnp::operation_busy_a::Builder,
+    ) -> Result<(), RPCError> {
+        Ok(())
+    }
+}
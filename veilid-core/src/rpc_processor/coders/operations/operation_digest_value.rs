@@ -0,0 +1,176 @@
+use super::*;
+use crate::storage_manager::SubkeyDigestRange;
+
+pub(crate) const MAX_DIGEST_VALUE_Q_SUBKEY_RANGES_LEN: usize = 64;
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct ValidateDigestValueContext {
+    pub range_count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationDigestValueQ {
+    key: TypedKey,
+    ranges: Vec<SubkeyDigestRange>,
+}
+
+impl RPCOperationDigestValueQ {
+    pub fn new(key: TypedKey, ranges: Vec<SubkeyDigestRange>) -> Result<Self, RPCError> {
+        if ranges.len() > MAX_DIGEST_VALUE_Q_SUBKEY_RANGES_LEN {
+            return Err(RPCError::protocol(
+                "encoded DigestValueQ subkey ranges length too long",
+            ));
+        }
+        Self::validate_ranges(&ranges)?;
+        Ok(Self { key, ranges })
+    }
+    pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        Ok(())
+    }
+
+    /// Ranges must be well formed, in ascending order, and not overlap
+    /// Adjacent ranges are fine, unlike in a subkey range set, as each range gets its own digest
+    fn validate_ranges(ranges: &[SubkeyDigestRange]) -> Result<(), RPCError> {
+        let mut last_end: Option<ValueSubkey> = None;
+        for (start, end) in ranges {
+            if start > end {
+                return Err(RPCError::protocol("invalid subkey range"));
+            }
+            if let Some(last_end) = last_end {
+                if last_end >= *start {
+                    return Err(RPCError::protocol(
+                        "subkey ranges out of order or overlapping",
+                    ));
+                }
+            }
+            last_end = Some(*end);
+        }
+        Ok(())
+    }
+
+    // pub fn key(&self) -> &TypedKey {
+    //     &self.key
+    // }
+    // pub fn ranges(&self) -> &[SubkeyDigestRange] {
+    //     &self.ranges
+    // }
+    pub fn destructure(self) -> (TypedKey, Vec<SubkeyDigestRange>) {
+        (self.key, self.ranges)
+    }
+
+    pub fn decode(
+        reader: &veilid_capnp::operation_digest_value_q::Reader,
+    ) -> Result<Self, RPCError> {
+        let k_reader = reader.reborrow().get_key().map_err(RPCError::protocol)?;
+        let key = decode_typed_key(&k_reader)?;
+        let sk_reader = reader.get_subkeys().map_err(RPCError::protocol)?;
+        if sk_reader.len() as usize > MAX_DIGEST_VALUE_Q_SUBKEY_RANGES_LEN {
+            return Err(RPCError::protocol("DigestValueQ too many subkey ranges"));
+        }
+        let ranges: Vec<SubkeyDigestRange> = sk_reader
+            .iter()
+            .map(|skr| (skr.get_start(), skr.get_end()))
+            .collect();
+        Self::validate_ranges(&ranges)?;
+
+        Ok(Self { key, ranges })
+    }
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_digest_value_q::Builder,
+    ) -> Result<(), RPCError> {
+        let mut k_builder = builder.reborrow().init_key();
+        encode_typed_key(&self.key, &mut k_builder);
+
+        let mut sk_builder = builder.reborrow().init_subkeys(
+            self.ranges
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("invalid subkey range list length"))?,
+        );
+        for (i, (start, end)) in self.ranges.iter().enumerate() {
+            let mut skr_builder = sk_builder.reborrow().get(i as u32);
+            skr_builder.set_start(*start);
+            skr_builder.set_end(*end);
+        }
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationDigestValueA {
+    digests: Vec<HashDigest>,
+}
+
+impl RPCOperationDigestValueA {
+    pub fn new(digests: Vec<HashDigest>) -> Result<Self, RPCError> {
+        if digests.len() > MAX_DIGEST_VALUE_Q_SUBKEY_RANGES_LEN {
+            return Err(RPCError::protocol(
+                "encoded DigestValueA digests length too long",
+            ));
+        }
+        Ok(Self { digests })
+    }
+
+    pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        // The answer may not be for the kind of question we asked
+        let Some(QuestionContext::DigestValue(digest_value_context)) =
+            &validate_context.question_context
+        else {
+            return Err(RPCError::protocol(
+                "DigestValueA does not answer our question",
+            ));
+        };
+
+        // Either every range gets a digest, or the record was not found
+        if !self.digests.is_empty() && self.digests.len() != digest_value_context.range_count {
+            return Err(RPCError::protocol(format!(
+                "DigestValue digests length does not match ranges requested: {} != {}",
+                self.digests.len(),
+                digest_value_context.range_count
+            )));
+        }
+
+        Ok(())
+    }
+
+    // pub fn digests(&self) -> &[HashDigest] {
+    //     &self.digests
+    // }
+    pub fn destructure(self) -> Vec<HashDigest> {
+        self.digests
+    }
+
+    pub fn decode(
+        reader: &veilid_capnp::operation_digest_value_a::Reader,
+    ) -> Result<Self, RPCError> {
+        let digests_reader = reader.get_digests().map_err(RPCError::protocol)?;
+        if digests_reader.len() as usize > MAX_DIGEST_VALUE_Q_SUBKEY_RANGES_LEN {
+            return Err(RPCError::protocol(
+                "decoded DigestValueA digests length too long",
+            ));
+        }
+        let digests = digests_reader.iter().map(|d| decode_key256(&d)).collect();
+
+        Ok(Self { digests })
+    }
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_digest_value_a::Builder,
+    ) -> Result<(), RPCError> {
+        let mut digests_builder = builder.reborrow().init_digests(
+            self.digests
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("invalid digests list length"))?,
+        );
+        for (i, digest) in self.digests.iter().enumerate() {
+            let mut d_builder = digests_builder.reborrow().get(i as u32);
+            encode_key256(digest, &mut d_builder);
+        }
+
+        Ok(())
+    }
+}
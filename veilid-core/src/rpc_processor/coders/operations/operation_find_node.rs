@@ -41,9 +41,9 @@ impl RPCOperationFindNodeQ {
             return Err(RPCError::protocol("too many capabilities"));
         }
         let capabilities = cap_reader
-            .as_slice()
-            .map(|s| s.iter().map(|x| FourCC::from(x.to_be_bytes())).collect())
-            .unwrap_or_default();
+            .iter()
+            .map(|x| FourCC::from(x.to_be_bytes()))
+            .collect();
 
         Ok(Self {
             node_id,
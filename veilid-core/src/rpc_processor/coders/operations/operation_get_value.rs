@@ -103,12 +103,10 @@ impl RPCOperationGetValueA {
     }
 
     pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
-        let question_context = validate_context
-            .question_context
-            .as_ref()
-            .expect("GetValueA requires question context");
-        let QuestionContext::GetValue(get_value_context) = question_context else {
-            panic!("Wrong context type for GetValueA");
+        // The answer may not be for the kind of question we asked
+        let Some(QuestionContext::GetValue(get_value_context)) = &validate_context.question_context
+        else {
+            return Err(RPCError::protocol("GetValueA does not answer our question"));
         };
 
         // Validate descriptor
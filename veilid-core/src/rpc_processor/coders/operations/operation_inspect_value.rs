@@ -146,12 +146,13 @@ impl RPCOperationInspectValueA {
     }
 
     pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
-        let question_context = validate_context
-            .question_context
-            .as_ref()
-            .expect("InspectValueA requires question context");
-        let QuestionContext::InspectValue(inspect_value_context) = question_context else {
-            panic!("Wrong context type for InspectValueA");
+        // The answer may not be for the kind of question we asked
+        let Some(QuestionContext::InspectValue(inspect_value_context)) =
+            &validate_context.question_context
+        else {
+            return Err(RPCError::protocol(
+                "InspectValueA does not answer our question",
+            ));
         };
 
         // Ensure seqs returned does not exceeed subkeys requested
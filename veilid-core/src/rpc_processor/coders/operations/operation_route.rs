@@ -1,5 +1,8 @@
 use super::*;
 
+// One signature per hop, and a route can never have more hops than fit in its hop count
+const MAX_ROUTED_OPERATION_SIGNATURES_LEN: usize = 2 * (u8::MAX as usize);
+
 #[derive(Clone)]
 pub(in crate::rpc_processor) struct RoutedOperation {
     sequencing: Sequencing,
@@ -56,6 +59,9 @@ impl RoutedOperation {
 
     pub fn decode(reader: &veilid_capnp::routed_operation::Reader) -> Result<Self, RPCError> {
         let sigs_reader = reader.get_signatures().map_err(RPCError::protocol)?;
+        if sigs_reader.len() as usize > MAX_ROUTED_OPERATION_SIGNATURES_LEN {
+            return Err(RPCError::protocol("too many signatures"));
+        }
         let mut signatures = Vec::<Signature>::with_capacity(
             sigs_reader
                 .len()
@@ -109,13 +115,15 @@ impl RoutedOperation {
 pub(in crate::rpc_processor) struct RPCOperationRoute {
     safety_route: SafetyRoute,
     operation: RoutedOperation,
+    pow_nonce: u64,
 }
 
 impl RPCOperationRoute {
-    pub fn new(safety_route: SafetyRoute, operation: RoutedOperation) -> Self {
+    pub fn new(safety_route: SafetyRoute, operation: RoutedOperation, pow_nonce: u64) -> Self {
         Self {
             safety_route,
             operation,
+            pow_nonce,
         }
     }
     pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
@@ -125,9 +133,12 @@ impl RPCOperationRoute {
     pub fn safety_route(&self) -> &SafetyRoute {
         &self.safety_route
     }
-    // pub fn operation(&self) -> &RoutedOperation {
-    //     &self.operation
-    // }
+    pub fn operation(&self) -> &RoutedOperation {
+        &self.operation
+    }
+    pub fn pow_nonce(&self) -> u64 {
+        self.pow_nonce
+    }
     pub fn destructure(self) -> (SafetyRoute, RoutedOperation) {
         (self.safety_route, self.operation)
     }
@@ -139,9 +150,12 @@ impl RPCOperationRoute {
         let o_reader = reader.get_operation().map_err(RPCError::protocol)?;
         let operation = RoutedOperation::decode(&o_reader)?;
 
+        let pow_nonce = reader.get_pow_nonce();
+
         Ok(Self {
             safety_route,
             operation,
+            pow_nonce,
         })
     }
     pub fn encode(
@@ -152,6 +166,7 @@ impl RPCOperationRoute {
         encode_safety_route(&self.safety_route, &mut sr_builder)?;
         let mut o_builder = builder.reborrow().init_operation();
         self.operation.encode(&mut o_builder)?;
+        builder.set_pow_nonce(self.pow_nonce);
         Ok(())
     }
 }
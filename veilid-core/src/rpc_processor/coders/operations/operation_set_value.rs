@@ -26,6 +26,7 @@ pub(in crate::rpc_processor) struct RPCOperationSetValueQ {
     subkey: ValueSubkey,
     value: SignedValueData,
     descriptor: Option<SignedValueDescriptor>,
+    pow_nonce: u64,
 }
 
 impl RPCOperationSetValueQ {
@@ -34,12 +35,14 @@ impl RPCOperationSetValueQ {
         subkey: ValueSubkey,
         value: SignedValueData,
         descriptor: Option<SignedValueDescriptor>,
+        pow_nonce: u64,
     ) -> Self {
         Self {
             key,
             subkey,
             value,
             descriptor,
+            pow_nonce,
         }
     }
     pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
@@ -68,8 +71,15 @@ impl RPCOperationSetValueQ {
         ValueSubkey,
         SignedValueData,
         Option<SignedValueDescriptor>,
+        u64,
     ) {
-        (self.key, self.subkey, self.value, self.descriptor)
+        (
+            self.key,
+            self.subkey,
+            self.value,
+            self.descriptor,
+            self.pow_nonce,
+        )
     }
 
     pub fn decode(reader: &veilid_capnp::operation_set_value_q::Reader) -> Result<Self, RPCError> {
@@ -85,11 +95,13 @@ impl RPCOperationSetValueQ {
         } else {
             None
         };
+        let pow_nonce = reader.get_pow_nonce();
         Ok(Self {
             key,
             subkey,
             value,
             descriptor,
+            pow_nonce,
         })
     }
     pub fn encode(
@@ -105,6 +117,7 @@ impl RPCOperationSetValueQ {
             let mut d_builder = builder.reborrow().init_descriptor();
             encode_signed_value_descriptor(descriptor, &mut d_builder)?;
         }
+        builder.set_pow_nonce(self.pow_nonce);
         Ok(())
     }
 }
@@ -116,6 +129,7 @@ pub(in crate::rpc_processor) struct RPCOperationSetValueA {
     set: bool,
     value: Option<SignedValueData>,
     peers: Vec<PeerInfo>,
+    pow_difficulty: u8,
 }
 
 impl RPCOperationSetValueA {
@@ -123,22 +137,26 @@ impl RPCOperationSetValueA {
         set: bool,
         value: Option<SignedValueData>,
         peers: Vec<PeerInfo>,
+        pow_difficulty: u8,
     ) -> Result<Self, RPCError> {
         if peers.len() > MAX_SET_VALUE_A_PEERS_LEN {
             return Err(RPCError::protocol(
                 "encoded SetValueA peers length too long",
             ));
         }
-        Ok(Self { set, value, peers })
+        Ok(Self {
+            set,
+            value,
+            peers,
+            pow_difficulty,
+        })
     }
 
     pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
-        let question_context = validate_context
-            .question_context
-            .as_ref()
-            .expect("SetValueA requires question context");
-        let QuestionContext::SetValue(set_value_context) = question_context else {
-            panic!("Wrong context type for SetValueA");
+        // The answer may not be for the kind of question we asked
+        let Some(QuestionContext::SetValue(set_value_context)) = &validate_context.question_context
+        else {
+            return Err(RPCError::protocol("SetValueA does not answer our question"));
         };
 
         // Ensure the descriptor itself validates
@@ -158,6 +176,12 @@ impl RPCOperationSetValueA {
                 .map_err(RPCError::protocol)?;
         }
 
+        if self.set && self.pow_difficulty != 0 {
+            return Err(RPCError::protocol(
+                "SetValueA can not both set and require proof of work",
+            ));
+        }
+
         PeerInfo::validate_vec(&mut self.peers, validate_context.crypto.clone());
         Ok(())
     }
@@ -171,8 +195,8 @@ impl RPCOperationSetValueA {
     // pub fn peers(&self) -> &[PeerInfo] {
     //     &self.peers
     // }
-    pub fn destructure(self) -> (bool, Option<SignedValueData>, Vec<PeerInfo>) {
-        (self.set, self.value, self.peers)
+    pub fn destructure(self) -> (bool, Option<SignedValueData>, Vec<PeerInfo>, u8) {
+        (self.set, self.value, self.peers, self.pow_difficulty)
     }
 
     pub fn decode(reader: &veilid_capnp::operation_set_value_a::Reader) -> Result<Self, RPCError> {
@@ -200,8 +224,14 @@ impl RPCOperationSetValueA {
             let peer_info = decode_peer_info(&p)?;
             peers.push(peer_info);
         }
+        let pow_difficulty = reader.get_pow_difficulty();
 
-        Ok(Self { set, value, peers })
+        Ok(Self {
+            set,
+            value,
+            peers,
+            pow_difficulty,
+        })
     }
     pub fn encode(
         &self,
@@ -224,6 +254,7 @@ impl RPCOperationSetValueA {
             let mut pi_builder = peers_builder.reborrow().get(i as u32);
             encode_peer_info(peer, &mut pi_builder)?;
         }
+        builder.set_pow_difficulty(self.pow_difficulty);
 
         Ok(())
     }
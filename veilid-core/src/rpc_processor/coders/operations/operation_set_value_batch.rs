@@ -0,0 +1,296 @@
+use super::*;
+use crate::storage_manager::{
+    BatchedValue, SignedValueBatch, SignedValueData, SignedValueDescriptor, MAX_VALUE_BATCH_LEN,
+};
+
+const MAX_SET_VALUE_BATCH_A_PEERS_LEN: usize = 20;
+
+#[derive(Clone)]
+pub(in crate::rpc_processor) struct ValidateSetValueBatchContext {
+    pub descriptor: SignedValueDescriptor,
+    pub subkeys: ValueSubkeyRangeSet,
+    pub vcrypto: CryptoSystemVersion,
+}
+
+impl fmt::Debug for ValidateSetValueBatchContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidateSetValueBatchContext")
+            .field("descriptor", &self.descriptor)
+            .field("subkeys", &self.subkeys)
+            .field("vcrypto", &self.vcrypto.kind().to_string())
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationSetValueBatchQ {
+    key: TypedKey,
+    batch: SignedValueBatch,
+    descriptor: Option<SignedValueDescriptor>,
+    pow_nonce: u64,
+}
+
+impl RPCOperationSetValueBatchQ {
+    pub fn new(
+        key: TypedKey,
+        batch: SignedValueBatch,
+        descriptor: Option<SignedValueDescriptor>,
+        pow_nonce: u64,
+    ) -> Self {
+        Self {
+            key,
+            batch,
+            descriptor,
+            pow_nonce,
+        }
+    }
+    pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        // The batch signature is checked against the record owner when the batch is stored
+        Ok(())
+    }
+
+    pub fn destructure(
+        self,
+    ) -> (
+        TypedKey,
+        SignedValueBatch,
+        Option<SignedValueDescriptor>,
+        u64,
+    ) {
+        (self.key, self.batch, self.descriptor, self.pow_nonce)
+    }
+
+    pub fn decode(
+        reader: &veilid_capnp::operation_set_value_batch_q::Reader,
+    ) -> Result<Self, RPCError> {
+        let k_reader = reader.get_key().map_err(RPCError::protocol)?;
+        let key = decode_typed_key(&k_reader)?;
+        let wr = reader.get_writer().map_err(RPCError::protocol)?;
+        let writer = decode_key256(&wr);
+        let sr = reader.get_signature().map_err(RPCError::protocol)?;
+        let signature = decode_signature512(&sr);
+
+        let values_reader = reader.get_values().map_err(RPCError::protocol)?;
+        if values_reader.len() as usize > MAX_VALUE_BATCH_LEN {
+            return Err(RPCError::protocol("decoded SetValueBatchQ too long"));
+        }
+        let mut values = Vec::with_capacity(values_reader.len() as usize);
+        for v_reader in values_reader.iter() {
+            let data = v_reader.get_data().map_err(RPCError::protocol)?.to_vec();
+            values.push(BatchedValue {
+                subkey: v_reader.get_subkey(),
+                value_data: ValueData::new_with_seq(v_reader.get_seq(), data, writer)
+                    .map_err(RPCError::protocol)?,
+                compressed: v_reader.get_compressed(),
+            });
+        }
+        let batch = SignedValueBatch::new(writer, signature, values).map_err(RPCError::protocol)?;
+
+        let descriptor = if reader.has_descriptor() {
+            let d_reader = reader.get_descriptor().map_err(RPCError::protocol)?;
+            let descriptor = decode_signed_value_descriptor(&d_reader)?;
+            Some(descriptor)
+        } else {
+            None
+        };
+        let pow_nonce = reader.get_pow_nonce();
+        Ok(Self {
+            key,
+            batch,
+            descriptor,
+            pow_nonce,
+        })
+    }
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_set_value_batch_q::Builder,
+    ) -> Result<(), RPCError> {
+        let mut k_builder = builder.reborrow().init_key();
+        encode_typed_key(&self.key, &mut k_builder);
+        let mut wb = builder.reborrow().init_writer();
+        encode_key256(self.batch.writer(), &mut wb);
+        let mut sb = builder.reborrow().init_signature();
+        encode_signature512(self.batch.signature(), &mut sb);
+
+        let mut values_builder = builder.reborrow().init_values(
+            self.batch
+                .values()
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("invalid value batch length"))?,
+        );
+        for (i, value) in self.batch.values().iter().enumerate() {
+            let mut v_builder = values_builder.reborrow().get(i as u32);
+            v_builder.set_subkey(value.subkey);
+            v_builder.set_seq(value.value_data.seq());
+            v_builder.set_data(value.value_data.data());
+            v_builder.set_compressed(value.compressed);
+        }
+
+        if let Some(descriptor) = &self.descriptor {
+            let mut d_builder = builder.reborrow().init_descriptor();
+            encode_signed_value_descriptor(descriptor, &mut d_builder)?;
+        }
+        builder.set_pow_nonce(self.pow_nonce);
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone)]
+pub(in crate::rpc_processor) struct RPCOperationSetValueBatchA {
+    set: bool,
+    values: Vec<(ValueSubkey, SignedValueData)>,
+    peers: Vec<PeerInfo>,
+    pow_difficulty: u8,
+}
+
+impl RPCOperationSetValueBatchA {
+    pub fn new(
+        set: bool,
+        values: Vec<(ValueSubkey, SignedValueData)>,
+        peers: Vec<PeerInfo>,
+        pow_difficulty: u8,
+    ) -> Result<Self, RPCError> {
+        if values.len() > MAX_VALUE_BATCH_LEN {
+            return Err(RPCError::protocol(
+                "encoded SetValueBatchA values length too long",
+            ));
+        }
+        if peers.len() > MAX_SET_VALUE_BATCH_A_PEERS_LEN {
+            return Err(RPCError::protocol(
+                "encoded SetValueBatchA peers length too long",
+            ));
+        }
+        Ok(Self {
+            set,
+            values,
+            peers,
+            pow_difficulty,
+        })
+    }
+
+    pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
+        // The answer may not be for the kind of question we asked
+        let Some(QuestionContext::SetValueBatch(set_value_batch_context)) =
+            &validate_context.question_context
+        else {
+            return Err(RPCError::protocol(
+                "SetValueBatchA does not answer our question",
+            ));
+        };
+
+        // Ensure the descriptor itself validates
+        set_value_batch_context
+            .descriptor
+            .validate(set_value_batch_context.vcrypto.clone())
+            .map_err(RPCError::protocol)?;
+
+        // Newer values may only be returned for subkeys we wrote, and must validate on their own
+        for (subkey, value) in &self.values {
+            if !set_value_batch_context.subkeys.contains(*subkey) {
+                return Err(RPCError::protocol(
+                    "SetValueBatchA returned a subkey that was not written",
+                ));
+            }
+            value
+                .validate(
+                    set_value_batch_context.descriptor.owner(),
+                    *subkey,
+                    set_value_batch_context.vcrypto.clone(),
+                )
+                .map_err(RPCError::protocol)?;
+        }
+
+        if self.set && self.pow_difficulty != 0 {
+            return Err(RPCError::protocol(
+                "SetValueBatchA can not both set and require proof of work",
+            ));
+        }
+
+        PeerInfo::validate_vec(&mut self.peers, validate_context.crypto.clone());
+        Ok(())
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn destructure(self) -> (bool, Vec<(ValueSubkey, SignedValueData)>, Vec<PeerInfo>, u8) {
+        (self.set, self.values, self.peers, self.pow_difficulty)
+    }
+
+    pub fn decode(
+        reader: &veilid_capnp::operation_set_value_batch_a::Reader,
+    ) -> Result<Self, RPCError> {
+        let set = reader.get_set();
+
+        let values_reader = reader.get_values().map_err(RPCError::protocol)?;
+        if values_reader.len() as usize > MAX_VALUE_BATCH_LEN {
+            return Err(RPCError::protocol(
+                "decoded SetValueBatchA values length too long",
+            ));
+        }
+        let mut values = Vec::with_capacity(values_reader.len() as usize);
+        for sv_reader in values_reader.iter() {
+            let v_reader = sv_reader.get_value().map_err(RPCError::protocol)?;
+            values.push((sv_reader.get_subkey(), decode_signed_value_data(&v_reader)?));
+        }
+
+        let peers_reader = reader.get_peers().map_err(RPCError::protocol)?;
+        if peers_reader.len() as usize > MAX_SET_VALUE_BATCH_A_PEERS_LEN {
+            return Err(RPCError::protocol(
+                "decoded SetValueBatchA peers length too long",
+            ));
+        }
+        let mut peers = Vec::<PeerInfo>::with_capacity(
+            peers_reader
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("too many peers"))?,
+        );
+        for p in peers_reader.iter() {
+            let peer_info = decode_peer_info(&p)?;
+            peers.push(peer_info);
+        }
+        let pow_difficulty = reader.get_pow_difficulty();
+
+        Ok(Self {
+            set,
+            values,
+            peers,
+            pow_difficulty,
+        })
+    }
+    pub fn encode(
+        &self,
+        builder: &mut veilid_capnp::operation_set_value_batch_a::Builder,
+    ) -> Result<(), RPCError> {
+        builder.set_set(self.set);
+
+        let mut values_builder = builder.reborrow().init_values(
+            self.values
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("invalid values list length"))?,
+        );
+        for (i, (subkey, value)) in self.values.iter().enumerate() {
+            let mut sv_builder = values_builder.reborrow().get(i as u32);
+            sv_builder.set_subkey(*subkey);
+            let mut v_builder = sv_builder.reborrow().init_value();
+            encode_signed_value_data(value, &mut v_builder)?;
+        }
+
+        let mut peers_builder = builder.reborrow().init_peers(
+            self.peers
+                .len()
+                .try_into()
+                .map_err(RPCError::map_internal("invalid peers list length"))?,
+        );
+        for (i, peer) in self.peers.iter().enumerate() {
+            let mut pi_builder = peers_builder.reborrow().get(i as u32);
+            encode_peer_info(peer, &mut pi_builder)?;
+        }
+        builder.set_pow_difficulty(self.pow_difficulty);
+
+        Ok(())
+    }
+}
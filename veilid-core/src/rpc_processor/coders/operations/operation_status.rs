@@ -3,11 +3,15 @@ use super::*;
 #[derive(Debug, Clone)]
 pub(in crate::rpc_processor) struct RPCOperationStatusQ {
     node_status: Option<NodeStatus>,
+    timestamp: Option<Timestamp>,
 }
 
 impl RPCOperationStatusQ {
-    pub fn new(node_status: Option<NodeStatus>) -> Self {
-        Self { node_status }
+    pub fn new(node_status: Option<NodeStatus>, timestamp: Option<Timestamp>) -> Self {
+        Self {
+            node_status,
+            timestamp,
+        }
     }
     pub fn validate(&mut self, _validate_context: &RPCValidateContext) -> Result<(), RPCError> {
         Ok(())
@@ -16,8 +20,8 @@ impl RPCOperationStatusQ {
     // pub fn node_status(&self) -> Option<&NodeStatus> {
     //     self.node_status.as_ref()
     // }
-    pub fn destructure(self) -> Option<NodeStatus> {
-        self.node_status
+    pub fn destructure(self) -> (Option<NodeStatus>, Option<Timestamp>) {
+        (self.node_status, self.timestamp)
     }
 
     pub fn decode(reader: &veilid_capnp::operation_status_q::Reader) -> Result<Self, RPCError> {
@@ -28,7 +32,14 @@ impl RPCOperationStatusQ {
         } else {
             None
         };
-        Ok(Self { node_status })
+        let timestamp = match reader.get_timestamp() {
+            0 => None,
+            ts => Some(Timestamp::new(ts)),
+        };
+        Ok(Self {
+            node_status,
+            timestamp,
+        })
     }
     pub fn encode(
         &self,
@@ -38,6 +49,9 @@ impl RPCOperationStatusQ {
             let mut ns_builder = builder.reborrow().init_node_status();
             encode_node_status(ns, &mut ns_builder)?;
         }
+        if let Some(ts) = self.timestamp {
+            builder.set_timestamp(ts.as_u64());
+        }
         Ok(())
     }
 }
@@ -48,13 +62,19 @@ impl RPCOperationStatusQ {
 pub(in crate::rpc_processor) struct RPCOperationStatusA {
     node_status: Option<NodeStatus>,
     sender_info: Option<SenderInfo>,
+    timestamps: Option<StatusTimestamps>,
 }
 
 impl RPCOperationStatusA {
-    pub fn new(node_status: Option<NodeStatus>, sender_info: Option<SenderInfo>) -> Self {
+    pub fn new(
+        node_status: Option<NodeStatus>,
+        sender_info: Option<SenderInfo>,
+        timestamps: Option<StatusTimestamps>,
+    ) -> Self {
         Self {
             node_status,
             sender_info,
+            timestamps,
         }
     }
 
@@ -68,8 +88,14 @@ impl RPCOperationStatusA {
     // pub fn sender_info(&self) -> Option<&SenderInfo> {
     //     self.sender_info.as_ref()
     // }
-    pub fn destructure(self) -> (Option<NodeStatus>, Option<SenderInfo>) {
-        (self.node_status, self.sender_info)
+    pub fn destructure(
+        self,
+    ) -> (
+        Option<NodeStatus>,
+        Option<SenderInfo>,
+        Option<StatusTimestamps>,
+    ) {
+        (self.node_status, self.sender_info, self.timestamps)
     }
 
     pub fn decode(reader: &veilid_capnp::operation_status_a::Reader) -> Result<Self, RPCError> {
@@ -89,9 +115,24 @@ impl RPCOperationStatusA {
             None
         };
 
+        // All three timestamps are needed to estimate clock skew
+        let question_ts = reader.get_question_timestamp();
+        let received_ts = reader.get_received_timestamp();
+        let answer_ts = reader.get_timestamp();
+        let timestamps = if question_ts != 0 && received_ts != 0 && answer_ts != 0 {
+            Some(StatusTimestamps {
+                question_ts: Timestamp::new(question_ts),
+                received_ts: Timestamp::new(received_ts),
+                answer_ts: Timestamp::new(answer_ts),
+            })
+        } else {
+            None
+        };
+
         Ok(Self {
             node_status,
             sender_info,
+            timestamps,
         })
     }
     pub fn encode(
@@ -106,6 +147,11 @@ impl RPCOperationStatusA {
             let mut si_builder = builder.reborrow().init_sender_info();
             encode_sender_info(si, &mut si_builder)?;
         }
+        if let Some(ts) = &self.timestamps {
+            builder.set_question_timestamp(ts.question_ts.as_u64());
+            builder.set_received_timestamp(ts.received_ts.as_u64());
+            builder.set_timestamp(ts.answer_ts.as_u64());
+        }
         Ok(())
     }
 }
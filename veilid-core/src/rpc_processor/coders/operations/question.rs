@@ -24,6 +24,9 @@ impl RPCQuestion {
     pub fn desc(&self) -> &'static str {
         self.detail.desc()
     }
+    pub fn is_control(&self) -> bool {
+        self.detail.is_control()
+    }
     pub fn destructure(self) -> (RespondTo, RPCQuestionDetail) {
         (self.respond_to, self.detail)
     }
@@ -51,6 +54,8 @@ pub(in crate::rpc_processor) enum RPCQuestionDetail {
     SetValueQ(Box<RPCOperationSetValueQ>),
     WatchValueQ(Box<RPCOperationWatchValueQ>),
     InspectValueQ(Box<RPCOperationInspectValueQ>),
+    SetValueBatchQ(Box<RPCOperationSetValueBatchQ>),
+    DigestValueQ(Box<RPCOperationDigestValueQ>),
     #[cfg(feature = "unstable-blockstore")]
     SupplyBlockQ(Box<RPCOperationSupplyBlockQ>),
     #[cfg(feature = "unstable-blockstore")]
@@ -64,6 +69,13 @@ pub(in crate::rpc_processor) enum RPCQuestionDetail {
 }
 
 impl RPCQuestionDetail {
+    /// Control questions keep the network working and are sent ahead of application data
+    pub fn is_control(&self) -> bool {
+        matches!(
+            self,
+            RPCQuestionDetail::StatusQ(_) | RPCQuestionDetail::FindNodeQ(_)
+        )
+    }
     #[cfg(feature = "verbose-tracing")]
     pub fn desc(&self) -> &'static str {
         match self {
@@ -74,6 +86,8 @@ impl RPCQuestionDetail {
             RPCQuestionDetail::SetValueQ(_) => "SetValueQ",
             RPCQuestionDetail::WatchValueQ(_) => "WatchValueQ",
             RPCQuestionDetail::InspectValueQ(_) => "InspectValueQ",
+            RPCQuestionDetail::SetValueBatchQ(_) => "SetValueBatchQ",
+            RPCQuestionDetail::DigestValueQ(_) => "DigestValueQ",
             #[cfg(feature = "unstable-blockstore")]
             RPCQuestionDetail::SupplyBlockQ(_) => "SupplyBlockQ",
             #[cfg(feature = "unstable-blockstore")]
@@ -86,6 +100,19 @@ impl RPCQuestionDetail {
             RPCQuestionDetail::CancelTunnelQ(_) => "CancelTunnelQ",
         }
     }
+    /// Questions that are turned away first when we are overloaded, because the asker
+    /// can get the same answer from other nodes
+    pub fn is_low_priority(&self) -> bool {
+        matches!(
+            self,
+            RPCQuestionDetail::GetValueQ(_)
+                | RPCQuestionDetail::SetValueQ(_)
+                | RPCQuestionDetail::WatchValueQ(_)
+                | RPCQuestionDetail::InspectValueQ(_)
+                | RPCQuestionDetail::SetValueBatchQ(_)
+                | RPCQuestionDetail::DigestValueQ(_)
+        )
+    }
     pub fn validate(&mut self, validate_context: &RPCValidateContext) -> Result<(), RPCError> {
         match self {
             RPCQuestionDetail::StatusQ(r) => r.validate(validate_context),
@@ -95,6 +122,8 @@ impl RPCQuestionDetail {
             RPCQuestionDetail::SetValueQ(r) => r.validate(validate_context),
             RPCQuestionDetail::WatchValueQ(r) => r.validate(validate_context),
             RPCQuestionDetail::InspectValueQ(r) => r.validate(validate_context),
+            RPCQuestionDetail::SetValueBatchQ(r) => r.validate(validate_context),
+            RPCQuestionDetail::DigestValueQ(r) => r.validate(validate_context),
             #[cfg(feature = "unstable-blockstore")]
             RPCQuestionDetail::SupplyBlockQ(r) => r.validate(validate_context),
             #[cfg(feature = "unstable-blockstore")]
@@ -148,6 +177,16 @@ impl RPCQuestionDetail {
                 let out = RPCOperationInspectValueQ::decode(&op_reader)?;
                 RPCQuestionDetail::InspectValueQ(Box::new(out))
             }
+            veilid_capnp::question::detail::SetValueBatchQ(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationSetValueBatchQ::decode(&op_reader)?;
+                RPCQuestionDetail::SetValueBatchQ(Box::new(out))
+            }
+            veilid_capnp::question::detail::DigestValueQ(r) => {
+                let op_reader = r.map_err(RPCError::protocol)?;
+                let out = RPCOperationDigestValueQ::decode(&op_reader)?;
+                RPCQuestionDetail::DigestValueQ(Box::new(out))
+            }
             #[cfg(feature = "unstable-blockstore")]
             veilid_capnp::question::detail::SupplyBlockQ(r) => {
                 let op_reader = r.map_err(RPCError::protocol)?;
@@ -197,6 +236,12 @@ impl RPCQuestionDetail {
             RPCQuestionDetail::InspectValueQ(d) => {
                 d.encode(&mut builder.reborrow().init_inspect_value_q())
             }
+            RPCQuestionDetail::SetValueBatchQ(d) => {
+                d.encode(&mut builder.reborrow().init_set_value_batch_q())
+            }
+            RPCQuestionDetail::DigestValueQ(d) => {
+                d.encode(&mut builder.reborrow().init_digest_value_q())
+            }
             #[cfg(feature = "unstable-blockstore")]
             RPCQuestionDetail::SupplyBlockQ(d) => {
                 d.encode(&mut builder.reborrow().init_supply_block_q())
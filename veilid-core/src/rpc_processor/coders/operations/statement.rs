@@ -19,6 +19,9 @@ impl RPCStatement {
     pub fn desc(&self) -> &'static str {
         self.detail.desc()
     }
+    pub fn is_control(&self) -> bool {
+        self.detail.is_control()
+    }
     pub fn destructure(self) -> RPCStatementDetail {
         self.detail
     }
@@ -44,6 +47,15 @@ pub(in crate::rpc_processor) enum RPCStatementDetail {
 }
 
 impl RPCStatementDetail {
+    /// Control statements keep the network working and are sent ahead of application data
+    pub fn is_control(&self) -> bool {
+        matches!(
+            self,
+            RPCStatementDetail::ValidateDialInfo(_)
+                | RPCStatementDetail::Signal(_)
+                | RPCStatementDetail::ReturnReceipt(_)
+        )
+    }
     #[cfg(feature = "verbose-tracing")]
     pub fn desc(&self) -> &'static str {
         match self {
@@ -23,6 +23,24 @@ pub fn encode_peer_info(
     }
     let mut sni_builder = builder.reborrow().init_signed_node_info();
     encode_signed_node_info(peer_info.signed_node_info(), &mut sni_builder)?;
+    if !peer_info.node_id_cross_signatures().is_empty() {
+        let mut xsigs_builder = builder.reborrow().init_node_id_cross_signatures(
+            peer_info
+                .node_id_cross_signatures()
+                .len()
+                .try_into()
+                .map_err(RPCError::map_invalid_format("out of bound error"))?,
+        );
+        for (i, typed_signature) in peer_info.node_id_cross_signatures().iter().enumerate() {
+            encode_typed_signature(
+                typed_signature,
+                &mut xsigs_builder.reborrow().get(
+                    i.try_into()
+                        .map_err(RPCError::map_invalid_format("out of bound error"))?,
+                ),
+            );
+        }
+    }
 
     Ok(())
 }
@@ -36,6 +54,9 @@ pub fn decode_peer_info(reader: &veilid_capnp::peer_info::Reader) -> Result<Peer
         .reborrow()
         .get_signed_node_info()
         .map_err(RPCError::protocol)?;
+    if nids_reader.len() as usize > MAX_CRYPTO_KINDS {
+        return Err(RPCError::protocol("too many node ids"));
+    }
     let mut node_ids = TypedKeyGroup::with_capacity(nids_reader.len() as usize);
     for nid_reader in nids_reader.iter() {
         node_ids.add(decode_typed_key(&nid_reader)?);
@@ -44,5 +65,19 @@ pub fn decode_peer_info(reader: &veilid_capnp::peer_info::Reader) -> Result<Peer
     if node_ids.is_empty() {
         return Err(RPCError::protocol("no verified node ids"));
     }
-    Ok(PeerInfo::new(node_ids, signed_node_info))
+    let mut node_id_cross_signatures = Vec::new();
+    if reader.has_node_id_cross_signatures() {
+        let xsigs_reader = reader
+            .reborrow()
+            .get_node_id_cross_signatures()
+            .map_err(RPCError::protocol)?;
+        if xsigs_reader.len() as usize > MAX_CRYPTO_KINDS {
+            return Err(RPCError::protocol("too many node id cross-signatures"));
+        }
+        for xsig_reader in xsigs_reader.iter() {
+            node_id_cross_signatures.push(decode_typed_signature(&xsig_reader)?);
+        }
+    }
+    Ok(PeerInfo::new(node_ids, signed_node_info)
+        .with_node_id_cross_signatures(node_id_cross_signatures))
 }
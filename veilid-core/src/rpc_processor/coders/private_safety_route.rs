@@ -145,6 +145,13 @@ pub(crate) fn decode_private_route(
         veilid_capnp::private_route::hops::Which::Empty(_) => PrivateRouteHops::Empty,
     };
 
+    // Only a route with no hops left can have a hop count of zero
+    if (hop_count == 0) != matches!(hops, PrivateRouteHops::Empty) {
+        return Err(RPCError::protocol(
+            "private route hop count does not match hops",
+        ));
+    }
+
     Ok(PrivateRoute {
         public_key,
         hop_count,
@@ -198,6 +205,13 @@ pub(crate) fn decode_safety_route(
         }
     };
 
+    // Only a safety route that has switched to its private route can have a hop count of zero
+    if (hop_count == 0) != matches!(hops, SafetyRouteHops::Private(_)) {
+        return Err(RPCError::protocol(
+            "safety route hop count does not match hops",
+        ));
+    }
+
     Ok(SafetyRoute {
         public_key,
         hop_count,
@@ -5,8 +5,24 @@ pub fn encode_signed_direct_node_info(
     builder: &mut veilid_capnp::signed_direct_node_info::Builder,
 ) -> Result<(), RPCError> {
     //
-    let mut ni_builder = builder.reborrow().init_node_info();
-    encode_node_info(signed_direct_node_info.node_info(), &mut ni_builder)?;
+    if signed_direct_node_info
+        .node_info()
+        .unknown_fields()
+        .is_some()
+    {
+        // Copy the whole node info in, so the fields newer than our schema come along
+        let ni_message = encode_node_info_message(signed_direct_node_info.node_info())?;
+        builder
+            .set_node_info(
+                ni_message
+                    .get_root_as_reader()
+                    .map_err(RPCError::protocol)?,
+            )
+            .map_err(RPCError::protocol)?;
+    } else {
+        let mut ni_builder = builder.reborrow().init_node_info();
+        encode_node_info(signed_direct_node_info.node_info(), &mut ni_builder)?;
+    }
 
     builder
         .reborrow()
@@ -11,6 +11,10 @@ pub fn encode_signed_value_data(
     encode_key256(signed_value_data.value_data().writer(), &mut wb);
     let mut sb = builder.reborrow().init_signature();
     encode_signature512(signed_value_data.signature(), &mut sb);
+    builder.set_compressed(signed_value_data.is_compressed());
+    if let Some(batch) = signed_value_data.batch() {
+        encode_value_batch_digest(batch, &mut builder.reborrow())?;
+    }
     Ok(())
 }
 
@@ -23,9 +27,57 @@ pub fn decode_signed_value_data(
     let writer = decode_key256(&wr);
     let sr = reader.get_signature().map_err(RPCError::protocol)?;
     let signature = decode_signature512(&sr);
+    let compressed = reader.get_compressed();
+    let value_data = ValueData::new_with_seq(seq, data, writer).map_err(RPCError::protocol)?;
 
-    Ok(SignedValueData::new(
-        ValueData::new_with_seq(seq, data, writer).map_err(RPCError::protocol)?,
-        signature,
-    ))
+    if reader.has_batch() {
+        let batch = decode_value_batch_digest(reader)?;
+        return Ok(SignedValueData::new_batched(
+            value_data, signature, compressed, batch,
+        ));
+    }
+
+    Ok(SignedValueData::new(value_data, signature, compressed))
+}
+
+fn encode_value_batch_digest(
+    batch: &ValueBatchDigest,
+    builder: &mut veilid_capnp::signed_value_data::Builder,
+) -> Result<(), RPCError> {
+    let mut entries_builder = builder.reborrow().init_batch(
+        batch
+            .entries()
+            .len()
+            .try_into()
+            .map_err(RPCError::map_internal("invalid value batch length"))?,
+    );
+    for (i, entry) in batch.entries().iter().enumerate() {
+        let mut e_builder = entries_builder.reborrow().get(i as u32);
+        e_builder.set_subkey(entry.subkey);
+        e_builder.set_seq(entry.seq);
+        e_builder.set_compressed(entry.compressed);
+        let mut h_builder = e_builder.reborrow().init_data_hash();
+        encode_key256(&entry.data_hash, &mut h_builder);
+    }
+    Ok(())
+}
+
+fn decode_value_batch_digest(
+    reader: &veilid_capnp::signed_value_data::Reader,
+) -> Result<ValueBatchDigest, RPCError> {
+    let entries_reader = reader.get_batch().map_err(RPCError::protocol)?;
+    if entries_reader.len() as usize > MAX_VALUE_BATCH_LEN {
+        return Err(RPCError::protocol("value batch too long"));
+    }
+    let mut entries = Vec::with_capacity(entries_reader.len() as usize);
+    for e_reader in entries_reader.iter() {
+        let hr = e_reader.get_data_hash().map_err(RPCError::protocol)?;
+        entries.push(ValueBatchEntry {
+            subkey: e_reader.get_subkey(),
+            seq: e_reader.get_seq(),
+            compressed: e_reader.get_compressed(),
+            data_hash: decode_key256(&hr),
+        });
+    }
+    ValueBatchDigest::new(entries).map_err(RPCError::protocol)
 }
@@ -1,5 +1,5 @@
 use super::*;
-use crate::storage_manager::SignedValueDescriptor;
+use crate::storage_manager::{SignedRecordVersionLink, SignedValueDescriptor};
 
 pub fn encode_signed_value_descriptor(
     signed_value_descriptor: &SignedValueDescriptor,
@@ -10,6 +10,17 @@ pub fn encode_signed_value_descriptor(
     builder.set_schema_data(signed_value_descriptor.schema_data());
     let mut sb = builder.reborrow().init_signature();
     encode_signature512(signed_value_descriptor.signature(), &mut sb);
+    builder.set_version(signed_value_descriptor.version());
+    builder.set_expiration(
+        signed_value_descriptor
+            .expiration()
+            .map(|e| e.as_u64())
+            .unwrap_or_default(),
+    );
+    if let Some(superseded_by) = signed_value_descriptor.superseded_by() {
+        let mut lb = builder.reborrow().init_superseded_by();
+        encode_record_version_link(superseded_by, &mut lb);
+    }
     Ok(())
 }
 
@@ -24,5 +35,37 @@ pub fn decode_signed_value_descriptor(
         .to_vec();
     let sr = reader.get_signature().map_err(RPCError::protocol)?;
     let signature = decode_signature512(&sr);
-    Ok(SignedValueDescriptor::new(owner, schema_data, signature))
+    let version = reader.get_version();
+    let expiration = Timestamp::new(reader.get_expiration());
+    let descriptor = SignedValueDescriptor::new(owner, schema_data, signature, version, expiration);
+    if reader.has_superseded_by() {
+        let lr = reader.get_superseded_by().map_err(RPCError::protocol)?;
+        return Ok(descriptor.with_superseded_by(decode_record_version_link(&lr)?));
+    }
+    Ok(descriptor)
+}
+
+fn encode_record_version_link(
+    signed_link: &SignedRecordVersionLink,
+    builder: &mut veilid_capnp::record_version_link::Builder,
+) {
+    let mut kb = builder.reborrow().init_key();
+    encode_typed_key(signed_link.link().key(), &mut kb);
+    builder.set_version(signed_link.link().version());
+    let mut sb = builder.reborrow().init_signature();
+    encode_signature512(signed_link.signature(), &mut sb);
+}
+
+fn decode_record_version_link(
+    reader: &veilid_capnp::record_version_link::Reader,
+) -> Result<SignedRecordVersionLink, RPCError> {
+    let kr = reader.get_key().map_err(RPCError::protocol)?;
+    let key = decode_typed_key(&kr)?;
+    let version = reader.get_version();
+    let sr = reader.get_signature().map_err(RPCError::protocol)?;
+    let signature = decode_signature512(&sr);
+    Ok(SignedRecordVersionLink::new(
+        DHTRecordVersionLink::new(key, version),
+        signature,
+    ))
 }
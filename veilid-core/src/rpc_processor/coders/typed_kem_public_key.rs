@@ -0,0 +1,25 @@
+use super::*;
+
+pub fn decode_typed_kem_public_key(
+    typed_kem_public_key: &veilid_capnp::typed_kem_public_key::Reader,
+) -> Result<TypedKemPublicKey, RPCError> {
+    let key_reader = typed_kem_public_key
+        .get_key()
+        .map_err(RPCError::map_invalid_format("invalid typed kem public key"))?;
+    let kind = typed_kem_public_key.get_kind();
+
+    Ok(TypedKemPublicKey::new(
+        CryptoKind::from(kind.to_be_bytes()),
+        KemPublicKey::try_from(key_reader).map_err(RPCError::map_invalid_format(
+            "invalid kem public key length",
+        ))?,
+    ))
+}
+
+pub fn encode_typed_kem_public_key(
+    typed_kem_public_key: &TypedKemPublicKey,
+    builder: &mut veilid_capnp::typed_kem_public_key::Builder,
+) {
+    builder.set_kind(u32::from_be_bytes(typed_kem_public_key.kind.0));
+    builder.set_key(&typed_kem_public_key.value.bytes);
+}
@@ -0,0 +1,118 @@
+use super::*;
+use capnp::private::layout::{PointerBuilder, StructBuilder, StructReader, StructSize};
+use capnp::traits::{FromPointerBuilder, HasStructSize, IntoInternalStructReader};
+
+/// A struct as it was encoded by a node with a newer schema than ours
+///
+/// Nodes upgrade at different times, so we can receive structs with fields that were added after
+/// our schema. Those fields are kept here so that when we encode the struct again, for example to
+/// pass a peer info along or to rebuild the bytes its signatures cover, nothing the sender encoded
+/// is lost.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownFields {
+    /// A message holding the struct exactly as it was received
+    message: Arc<Vec<u8>>,
+}
+
+/// Gets the root pointer of a message, so a struct can be created there with a size we choose
+/// rather than the size from our schema
+struct RootPointer<'a>(PointerBuilder<'a>);
+
+impl<'a> FromPointerBuilder<'a> for RootPointer<'a> {
+    fn init_pointer(builder: PointerBuilder<'a>, _size: u32) -> Self {
+        Self(builder)
+    }
+    fn get_from_pointer(
+        builder: PointerBuilder<'a>,
+        _default: Option<&'a [capnp::Word]>,
+    ) -> capnp::Result<Self> {
+        Ok(Self(builder))
+    }
+}
+
+/// Create the root struct of a message with a specific size
+pub(crate) fn init_sized_root<A: capnp::message::Allocator>(
+    message: &mut capnp::message::Builder<A>,
+    size: StructSize,
+) -> StructBuilder<'_> {
+    message.init_root::<RootPointer>().0.init_struct(size)
+}
+
+fn struct_size(reader: &StructReader<'_>) -> StructSize {
+    StructSize {
+        data: (reader.get_data_section_size() / 64) as u16,
+        pointers: reader.get_pointer_section_size(),
+    }
+}
+
+fn has_unknown_fields(reader: &StructReader<'_>, known: StructSize) -> bool {
+    let size = struct_size(reader);
+    size.data > known.data || size.pointers > known.pointers
+}
+
+impl UnknownFields {
+    /// Keep a received node info if it has fields that our schema does not
+    pub fn from_node_info_reader(
+        reader: &veilid_capnp::node_info::Reader,
+    ) -> Result<Option<Self>, RPCError> {
+        let known = <veilid_capnp::node_info::Builder as HasStructSize>::STRUCT_SIZE;
+        if !has_unknown_fields(&reader.reborrow().into_internal_struct_reader(), known) {
+            return Ok(None);
+        }
+
+        let mut message = capnp::message::Builder::new_default();
+        message.set_root(*reader).map_err(RPCError::protocol)?;
+        Ok(Some(Self {
+            message: Arc::new(builder_to_vec(message)?),
+        }))
+    }
+
+    /// Encode a node info as the root of a message, with these fields added back in
+    ///
+    /// The fields we know are encoded the same way as any other node info, and the unknown ones
+    /// are copied after them in order. As long as newer versions of encode_node_info only ever add
+    /// fields after the existing ones, this gives the same bytes the sender produced, so the
+    /// sender's signatures can still be checked.
+    pub fn encode_node_info<A: capnp::message::Allocator>(
+        &self,
+        node_info: &NodeInfo,
+        message: &mut capnp::message::Builder<A>,
+    ) -> Result<(), RPCError> {
+        let received = capnp::serialize_packed::read_message(
+            self.message.as_slice(),
+            capnp::message::ReaderOptions::new(),
+        )
+        .map_err(RPCError::protocol)?;
+        let received = received
+            .get_root::<veilid_capnp::node_info::Reader>()
+            .map_err(RPCError::protocol)?
+            .into_internal_struct_reader();
+
+        let known = <veilid_capnp::node_info::Builder as HasStructSize>::STRUCT_SIZE;
+        let received_size = struct_size(&received);
+        let size = StructSize {
+            data: received_size.data.max(known.data),
+            pointers: received_size.pointers.max(known.pointers),
+        };
+        let mut builder = init_sized_root(message, size);
+
+        // Data fields are inline, so copying the whole data section keeps the unknown ones
+        // wherever they were packed, and the known ones are written again with the same values
+        for i in 0..received_size.data as usize {
+            builder.set_data_field::<u64>(i, received.get_data_field::<u64>(i));
+        }
+        encode_node_info(
+            node_info,
+            &mut veilid_capnp::node_info::Builder::from(builder.reborrow()),
+        )?;
+        for i in known.pointers..received_size.pointers {
+            builder
+                .reborrow()
+                .get_pointer_field(i as usize)
+                .copy_from(received.get_pointer_field(i as usize), false)
+                .map_err(RPCError::protocol)?;
+        }
+
+        Ok(())
+    }
+}
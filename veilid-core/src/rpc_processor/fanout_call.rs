@@ -72,7 +72,7 @@ pub(crate) fn capability_fanout_node_info_filter(caps: Vec<Capability>) -> Fanou
 ///  * 'call_routine' - routine to call for each node that performs an operation and may add more nodes to our closest_nodes set
 /// The algorithm is parameterized by:
 ///  * 'node_count' - the number of nodes to keep in the closest_nodes set
-///  * 'fanout' - the number of concurrent calls being processed at the same time
+///  * 'fanout' - the number of concurrent calls being processed at the same time, reduced while the rpc workers are under load
 /// The algorithm returns early if 'check_done' returns some value, or if an error is found during the process.
 /// If the algorithm times out, a Timeout result is returned, however operations will still have been performed and a
 /// timeout is not necessarily indicative of an algorithmic 'failure', just that no definitive stopping condition was found
@@ -85,7 +85,6 @@ where
     D: Fn(&[NodeRef]) -> Option<R>,
 {
     routing_table: RoutingTable,
-    crypto_kind: CryptoKind,
     node_id: TypedKey,
     context: Mutex<FanoutContext<R>>,
     node_count: usize,
@@ -122,7 +121,6 @@ where
         Arc::new(Self {
             routing_table,
             node_id,
-            crypto_kind: node_id.kind,
             context,
             node_count,
             fanout,
@@ -202,9 +200,11 @@ where
 
                     // Call succeeded
                     // Register the returned nodes and add them to the fanout queue in sorted order
-                    let new_nodes = self
-                        .routing_table
-                        .register_find_node_answer(self.crypto_kind, filtered_v);
+                    let new_nodes = self.routing_table.register_find_node_answer(
+                        next_node.clone(),
+                        self.node_id,
+                        filtered_v,
+                    );
                     self.clone().add_to_fanout_queue(&new_nodes);
                 }
                 #[allow(unused_variables)]
@@ -283,6 +283,9 @@ where
             }
         };
 
+        // Looking up this key keeps the bucket it falls in fresh
+        self.routing_table.touch_bucket_lookup(self.node_id);
+
         // Initialize closest nodes list
         if init_fanout_queue.is_empty() {
             if let Err(e) = self.clone().init_closest_nodes() {
@@ -300,11 +303,19 @@ where
             }
         }
 
-        // If not, do the fanout
+        // If not, do the fanout, with fewer tasks if the rpc workers are falling behind
+        let fanout = self
+            .routing_table
+            .rpc_processor()
+            .fanout_concurrency(self.fanout);
+        if fanout < self.fanout {
+            event!(target: "fanout", Level::DEBUG,
+                "Fanout reduced under load: {} -> {}", self.fanout, fanout);
+        }
         let mut unord = FuturesUnordered::new();
         {
             // Spin up 'fanout' tasks to process the fanout
-            for _ in 0..self.fanout {
+            for _ in 0..fanout {
                 let h = self.clone().fanout_processor();
                 unord.push(h);
             }
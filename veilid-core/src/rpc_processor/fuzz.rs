@@ -0,0 +1,468 @@
+//! Structured fuzzing entry points for the RPC coders
+//!
+//! Each `decode_*` function takes a packed capnp message as it would arrive off the wire and runs
+//! the matching decoder on its root. Decoders must return an error for any malformed input,
+//! so a panic from one of these functions is always a bug. Signatures are not checked here,
+//! that happens in validation after decoding.
+//!
+//! The entry points share their names with the decoders they wrap, so the decoders are
+//! referred to through their module here.
+use super::*;
+
+/// The result of running one fuzzing entry point
+pub type FuzzResult = Result<(), String>;
+
+macro_rules! fuzz_decoders {
+    ($($name:ident => $module:ident, $decode:expr;)*) => {
+        $(
+            pub fn $name(data: &[u8]) -> FuzzResult {
+                let reader = capnp::serialize_packed::read_message(
+                    data,
+                    capnp::message::ReaderOptions::new(),
+                )
+                .map_err(|e| e.to_string())?;
+                let root = reader
+                    .get_root::<veilid_capnp::$module::Reader>()
+                    .map_err(|e| e.to_string())?;
+                ($decode)(&root).map(drop).map_err(|e: RPCError| e.to_string())
+            }
+        )*
+
+        /// All entry points by name, in a fixed order so an index into this list is stable
+        pub const FUZZ_DECODERS: &[(&str, fn(&[u8]) -> FuzzResult)] = &[
+            $((stringify!($name), $name),)*
+        ];
+    };
+}
+
+fuzz_decoders! {
+    decode_operation => operation, RPCOperation::decode;
+    decode_question => question, RPCQuestion::decode;
+    decode_statement => statement, RPCStatement::decode;
+    decode_answer => answer, RPCAnswer::decode;
+    decode_status_q => operation_status_q, RPCOperationStatusQ::decode;
+    decode_status_a => operation_status_a, RPCOperationStatusA::decode;
+    decode_find_node_q => operation_find_node_q, RPCOperationFindNodeQ::decode;
+    decode_find_node_a => operation_find_node_a, RPCOperationFindNodeA::decode;
+    decode_app_call_q => operation_app_call_q, RPCOperationAppCallQ::decode;
+    decode_app_call_a => operation_app_call_a, RPCOperationAppCallA::decode;
+    decode_app_message => operation_app_message, RPCOperationAppMessage::decode;
+    decode_get_value_q => operation_get_value_q, RPCOperationGetValueQ::decode;
+    decode_get_value_a => operation_get_value_a, RPCOperationGetValueA::decode;
+    decode_set_value_q => operation_set_value_q, RPCOperationSetValueQ::decode;
+    decode_set_value_a => operation_set_value_a, RPCOperationSetValueA::decode;
+    decode_inspect_value_q => operation_inspect_value_q, RPCOperationInspectValueQ::decode;
+    decode_inspect_value_a => operation_inspect_value_a, RPCOperationInspectValueA::decode;
+    decode_watch_value_q => operation_watch_value_q, RPCOperationWatchValueQ::decode;
+    decode_watch_value_a => operation_watch_value_a, RPCOperationWatchValueA::decode;
+    decode_value_changed => operation_value_changed, RPCOperationValueChanged::decode;
+    decode_validate_dial_info => operation_validate_dial_info, RPCOperationValidateDialInfo::decode;
+    decode_return_receipt => operation_return_receipt, RPCOperationReturnReceipt::decode;
+    decode_signal => operation_signal, RPCOperationSignal::decode;
+    decode_route => operation_route, RPCOperationRoute::decode;
+    decode_routed_operation => routed_operation, RoutedOperation::decode;
+    decode_peer_info => peer_info, super::coders::decode_peer_info;
+    decode_node_info => node_info, super::coders::decode_node_info;
+    decode_signed_node_info => signed_node_info, super::coders::decode_signed_node_info;
+    decode_signed_direct_node_info => signed_direct_node_info, super::coders::decode_signed_direct_node_info;
+    decode_signed_relayed_node_info => signed_relayed_node_info, super::coders::decode_signed_relayed_node_info;
+    decode_node_status => node_status, super::coders::decode_node_status;
+    decode_sender_info => sender_info, super::coders::decode_sender_info;
+    decode_dial_info => dial_info, super::coders::decode_dial_info;
+    decode_dial_info_detail => dial_info_detail, super::coders::decode_dial_info_detail;
+    decode_socket_address => socket_address, super::coders::decode_socket_address;
+    decode_address => address, super::coders::decode_address;
+    decode_route_hop => route_hop, super::coders::decode_route_hop;
+    decode_route_hop_data => route_hop_data, super::coders::decode_route_hop_data;
+    decode_private_route => private_route, super::coders::decode_private_route;
+    decode_safety_route => safety_route, super::coders::decode_safety_route;
+    decode_signed_value_data => signed_value_data, super::coders::decode_signed_value_data;
+    decode_signed_value_descriptor => signed_value_descriptor, super::coders::decode_signed_value_descriptor;
+    decode_typed_key => typed_key, super::coders::decode_typed_key;
+    decode_typed_signature => typed_signature, super::coders::decode_typed_signature;
+    decode_typed_kem_public_key => typed_kem_public_key, super::coders::decode_typed_kem_public_key;
+    decode_set_value_batch_q => operation_set_value_batch_q, RPCOperationSetValueBatchQ::decode;
+    decode_set_value_batch_a => operation_set_value_batch_a, RPCOperationSetValueBatchA::decode;
+    decode_digest_value_q => operation_digest_value_q, RPCOperationDigestValueQ::decode;
+    decode_digest_value_a => operation_digest_value_a, RPCOperationDigestValueA::decode;
+}
+
+/// Run the entry point picked by the first byte of the input on the rest of it
+///
+/// This lets a single fuzzing target cover every decoder.
+pub fn decode_any(data: &[u8]) -> FuzzResult {
+    let Some((selector, data)) = data.split_first() else {
+        return Err("empty input".to_owned());
+    };
+    let (_, decode) = FUZZ_DECODERS[*selector as usize % FUZZ_DECODERS.len()];
+    decode(data)
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Corpus generation
+
+macro_rules! encode_root {
+    ($module:ident, |$builder:ident| $encode:expr) => {{
+        let mut message = ::capnp::message::Builder::new_default();
+        let mut $builder = message.init_root::<veilid_capnp::$module::Builder>();
+        $encode.expect("corpus entries should encode");
+        builder_to_vec(message).expect("corpus entries should serialize")
+    }};
+}
+
+fn corpus_public_key(n: u8) -> PublicKey {
+    PublicKey::new([n; PUBLIC_KEY_LENGTH])
+}
+
+fn corpus_typed_key(n: u8) -> TypedKey {
+    TypedKey::new(best_crypto_kind(), corpus_public_key(n))
+}
+
+fn corpus_signature(n: u8) -> Signature {
+    Signature::new([n; SIGNATURE_LENGTH])
+}
+
+fn corpus_dial_info_details() -> Vec<DialInfoDetail> {
+    let did = |dial_info: DialInfo, preference: u8| DialInfoDetail {
+        class: DialInfoClass::Direct,
+        dial_info,
+        preference,
+    };
+    vec![
+        did(
+            DialInfo::udp(SocketAddress::from_str("1.2.3.4:5150").unwrap()),
+            0,
+        ),
+        did(
+            DialInfo::tcp(SocketAddress::from_str("[2001:db8::1]:5150").unwrap()),
+            1,
+        ),
+        did(
+            DialInfo::try_wss(
+                SocketAddress::from_str("1.2.3.4:443").unwrap(),
+                "wss://example.com/ws".to_owned(),
+            )
+            .unwrap(),
+            2,
+        ),
+    ]
+}
+
+fn corpus_node_info() -> NodeInfo {
+    NodeInfo::new(
+        NetworkClass::InboundCapable,
+        ProtocolTypeSet::all(),
+        AddressTypeSet::all(),
+        VALID_ENVELOPE_VERSIONS.to_vec(),
+        MIN_MAX_ENVELOPE_SIZE as u32,
+        vec![best_crypto_kind()],
+        vec![],
+        vec![CAP_ROUTE, CAP_DHT, CAP_APPMESSAGE],
+        corpus_dial_info_details(),
+    )
+}
+
+fn corpus_signed_direct_node_info() -> SignedDirectNodeInfo {
+    SignedDirectNodeInfo::new(
+        corpus_node_info(),
+        Timestamp::new(1),
+        vec![TypedSignature::new(best_crypto_kind(), corpus_signature(1))],
+    )
+}
+
+fn corpus_peer_info(n: u8) -> PeerInfo {
+    let mut node_ids = TypedKeyGroup::new();
+    node_ids.add(corpus_typed_key(n));
+    PeerInfo::new(
+        node_ids,
+        SignedNodeInfo::Direct(corpus_signed_direct_node_info()),
+    )
+}
+
+fn corpus_relayed_peer_info(n: u8) -> PeerInfo {
+    let mut node_ids = TypedKeyGroup::new();
+    node_ids.add(corpus_typed_key(n));
+    let mut relay_ids = TypedKeyGroup::new();
+    relay_ids.add(corpus_typed_key(n.wrapping_add(1)));
+    PeerInfo::new(
+        node_ids,
+        SignedNodeInfo::Relayed(SignedRelayedNodeInfo::new(
+            corpus_node_info(),
+            relay_ids,
+            corpus_signed_direct_node_info(),
+            Timestamp::new(2),
+            vec![TypedSignature::new(best_crypto_kind(), corpus_signature(2))],
+        )),
+    )
+}
+
+fn corpus_signed_value_data() -> SignedValueData {
+    SignedValueData::new(
+        ValueData::new_with_seq(3, b"corpus".to_vec(), corpus_public_key(3)).unwrap(),
+        corpus_signature(3),
+        false,
+    )
+}
+
+fn corpus_signed_value_descriptor() -> SignedValueDescriptor {
+    SignedValueDescriptor::new(
+        corpus_public_key(4),
+        DHTSchema::dflt(1).unwrap().compile(),
+        corpus_signature(4),
+        0,
+        Timestamp::default(),
+    )
+}
+
+fn corpus_signed_value_batch() -> SignedValueBatch {
+    SignedValueBatch::new(
+        corpus_public_key(13),
+        corpus_signature(13),
+        (0..2)
+            .map(|subkey| BatchedValue {
+                subkey,
+                value_data: ValueData::new_with_seq(
+                    subkey,
+                    b"batch".to_vec(),
+                    corpus_public_key(13),
+                )
+                .unwrap(),
+                compressed: false,
+            })
+            .collect(),
+    )
+    .unwrap()
+}
+
+fn corpus_value_batch_digest() -> ValueBatchDigest {
+    ValueBatchDigest::new(vec![ValueBatchEntry {
+        subkey: 0,
+        seq: 3,
+        compressed: false,
+        data_hash: HashDigest::new([14; HASH_DIGEST_LENGTH]),
+    }])
+    .unwrap()
+}
+
+fn corpus_private_route() -> PrivateRoute {
+    PrivateRoute::new_stub(
+        corpus_typed_key(5),
+        RouteNode::PeerInfo(Box::new(corpus_peer_info(6))),
+    )
+}
+
+fn corpus_safety_route() -> SafetyRoute {
+    SafetyRoute::new_stub(corpus_typed_key(7), corpus_private_route())
+}
+
+fn corpus_routed_operation() -> RoutedOperation {
+    let mut routed_operation = RoutedOperation::new(
+        Sequencing::EnsureOrdered,
+        Nonce::new([8; NONCE_LENGTH]),
+        b"routed".to_vec(),
+    );
+    routed_operation.add_signature(corpus_signature(8));
+    routed_operation
+}
+
+fn corpus_questions() -> Vec<RPCQuestionDetail> {
+    let key = corpus_typed_key(9);
+    vec![
+        RPCQuestionDetail::StatusQ(Box::new(RPCOperationStatusQ::new(
+            Some(NodeStatus {
+                route_pow_difficulty: 9,
+            }),
+            Some(Timestamp::new(9)),
+        ))),
+        RPCQuestionDetail::FindNodeQ(Box::new(RPCOperationFindNodeQ::new(key, vec![CAP_DHT]))),
+        RPCQuestionDetail::AppCallQ(Box::new(
+            RPCOperationAppCallQ::new(b"call".to_vec()).unwrap(),
+        )),
+        RPCQuestionDetail::GetValueQ(Box::new(RPCOperationGetValueQ::new(key, 0, true))),
+        RPCQuestionDetail::SetValueBatchQ(Box::new(RPCOperationSetValueBatchQ::new(
+            key,
+            corpus_signed_value_batch(),
+            Some(corpus_signed_value_descriptor()),
+            0,
+        ))),
+        RPCQuestionDetail::DigestValueQ(Box::new(
+            RPCOperationDigestValueQ::new(key, vec![(0, 15), (16, 31)]).unwrap(),
+        )),
+    ]
+}
+
+fn corpus_answers() -> Vec<RPCAnswerDetail> {
+    vec![
+        RPCAnswerDetail::FindNodeA(Box::new(
+            RPCOperationFindNodeA::new(vec![corpus_peer_info(10), corpus_relayed_peer_info(11)])
+                .unwrap(),
+        )),
+        RPCAnswerDetail::AppCallA(Box::new(
+            RPCOperationAppCallA::new(b"reply".to_vec()).unwrap(),
+        )),
+        RPCAnswerDetail::GetValueA(Box::new(
+            RPCOperationGetValueA::new(
+                Some(corpus_signed_value_data()),
+                vec![corpus_peer_info(12)],
+                Some(corpus_signed_value_descriptor()),
+            )
+            .unwrap(),
+        )),
+        RPCAnswerDetail::SetValueBatchA(Box::new(
+            RPCOperationSetValueBatchA::new(
+                true,
+                vec![(
+                    0,
+                    SignedValueData::new_batched(
+                        ValueData::new_with_seq(3, b"corpus".to_vec(), corpus_public_key(3))
+                            .unwrap(),
+                        corpus_signature(3),
+                        false,
+                        corpus_value_batch_digest(),
+                    ),
+                )],
+                vec![corpus_peer_info(12)],
+                0,
+            )
+            .unwrap(),
+        )),
+        RPCAnswerDetail::DigestValueA(Box::new(
+            RPCOperationDigestValueA::new(vec![
+                HashDigest::new([15; HASH_DIGEST_LENGTH]),
+                HashDigest::new([16; HASH_DIGEST_LENGTH]),
+            ])
+            .unwrap(),
+        )),
+    ]
+}
+
+fn corpus_statements() -> Vec<RPCStatementDetail> {
+    vec![
+        RPCStatementDetail::AppMessage(Box::new(
+            RPCOperationAppMessage::new(b"message".to_vec()).unwrap(),
+        )),
+        RPCStatementDetail::ReturnReceipt(Box::new(
+            RPCOperationReturnReceipt::new(vec![0u8; MIN_RECEIPT_SIZE]).unwrap(),
+        )),
+        RPCStatementDetail::Signal(Box::new(RPCOperationSignal::new(SignalInfo::HolePunch {
+            receipt: vec![0u8; MIN_RECEIPT_SIZE],
+            peer_info: corpus_peer_info(13),
+        }))),
+        RPCStatementDetail::Route(Box::new(RPCOperationRoute::new(
+            corpus_safety_route(),
+            corpus_routed_operation(),
+            13,
+        ))),
+    ]
+}
+
+fn corpus_operations() -> Vec<RPCOperation> {
+    let with_peer_info = || SenderPeerInfo::new(corpus_peer_info(14), Timestamp::new(14));
+    let mut operations = Vec::new();
+    for detail in corpus_questions() {
+        operations.push(RPCOperation::new_question(
+            RPCQuestion::new(RespondTo::Sender, detail),
+            with_peer_info(),
+        ));
+    }
+    let request = operations[0].clone();
+    for detail in corpus_answers() {
+        operations.push(RPCOperation::new_answer(
+            &request,
+            RPCAnswer::new(detail),
+            SenderPeerInfo::default(),
+        ));
+    }
+    for detail in corpus_statements() {
+        operations.push(RPCOperation::new_statement(
+            RPCStatement::new(detail),
+            with_peer_info(),
+        ));
+    }
+    operations.push(RPCOperation::new_question(
+        RPCQuestion::new(
+            RespondTo::PrivateRoute(corpus_private_route()),
+            RPCQuestionDetail::AppCallQ(Box::new(
+                RPCOperationAppCallQ::new(b"private".to_vec()).unwrap(),
+            )),
+        ),
+        SenderPeerInfo::default(),
+    ));
+    operations
+}
+
+/// Build a seed corpus of valid encodings
+///
+/// Each entry is the name of the entry point it is for and an input for it that decodes
+/// successfully. Prefix an input with the index of its entry point in [FUZZ_DECODERS]
+/// to use it with [decode_any].
+pub fn generate_corpus() -> Vec<(&'static str, Vec<u8>)> {
+    let mut corpus = Vec::new();
+
+    for operation in corpus_operations() {
+        corpus.push((
+            "decode_operation",
+            encode_root!(operation, |b| operation.encode(&mut b)),
+        ));
+    }
+    corpus.push((
+        "decode_peer_info",
+        encode_root!(peer_info, |b| encode_peer_info(
+            &corpus_relayed_peer_info(15),
+            &mut b
+        )),
+    ));
+    corpus.push((
+        "decode_node_info",
+        encode_root!(node_info, |b| encode_node_info(&corpus_node_info(), &mut b)),
+    ));
+    for did in corpus_dial_info_details() {
+        corpus.push((
+            "decode_dial_info",
+            encode_root!(dial_info, |b| encode_dial_info(&did.dial_info, &mut b)),
+        ));
+    }
+    corpus.push((
+        "decode_private_route",
+        encode_root!(private_route, |b| encode_private_route(
+            &corpus_private_route(),
+            &mut b
+        )),
+    ));
+    corpus.push((
+        "decode_safety_route",
+        encode_root!(safety_route, |b| encode_safety_route(
+            &corpus_safety_route(),
+            &mut b
+        )),
+    ));
+    corpus.push((
+        "decode_routed_operation",
+        encode_root!(routed_operation, |b| corpus_routed_operation()
+            .encode(&mut b)),
+    ));
+    corpus.push((
+        "decode_signed_value_data",
+        encode_root!(signed_value_data, |b| encode_signed_value_data(
+            &corpus_signed_value_data(),
+            &mut b
+        )),
+    ));
+    corpus.push((
+        "decode_signed_value_descriptor",
+        encode_root!(signed_value_descriptor, |b| encode_signed_value_descriptor(
+            &corpus_signed_value_descriptor(),
+            &mut b
+        )),
+    ));
+
+    corpus
+}
+
+/// Find the index of an entry point in [FUZZ_DECODERS] by name
+pub fn fuzz_decoder_index(name: &str) -> Option<u8> {
+    FUZZ_DECODERS
+        .iter()
+        .position(|(n, _)| *n == name)
+        .map(|i| i as u8)
+}
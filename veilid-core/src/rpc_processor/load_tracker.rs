@@ -0,0 +1,144 @@
+use super::*;
+
+/// Weight given to each new sample in the running averages, as a power of two
+const LOAD_AVERAGE_SHIFT: u32 = 3;
+/// How slowly the processing time baseline follows processing time up, as a power of two
+const BASELINE_RISE_SHIFT: u32 = 10;
+/// How often the number of rpc workers allowed to process messages at once is adjusted
+const CONCURRENCY_ADAPT_INTERVAL_US: u64 = 1_000_000;
+/// The fewest rpc workers allowed to process messages at once, as a fraction of the configured concurrency
+const MIN_CONCURRENCY_DIVISOR: usize = 4;
+/// Processing taking this many times longer than its baseline means the workers are contending with each other
+const CONCURRENCY_CONTENTION_FACTOR: u64 = 2;
+
+/// Tracks how far behind the rpc workers are and how much work has been turned away
+///
+/// Questions wait in the queue before any worker picks them up. Once the queue is mostly full,
+/// or questions spend long enough waiting that their answers are unlikely to reach the asker
+/// before it gives up, low priority questions are answered with 'busy' instead of being processed.
+///
+/// The number of workers allowed to process messages at once adapts to the load as well. It grows
+/// while messages wait in the queue, and is cut in half when processing slows down well past its
+/// baseline, because then the extra workers are only contending with each other.
+#[derive(Debug, Default)]
+pub(super) struct LoadTracker {
+    average_queue_time: u64,
+    average_processing_time: u64,
+    baseline_processing_time: u64,
+    last_concurrency_adapt_ts: Option<Timestamp>,
+    shed_questions: u32,
+    dropped_messages: u32,
+}
+
+fn running_average(average: u64, sample: u64) -> u64 {
+    if average == 0 {
+        return sample;
+    }
+    average - (average >> LOAD_AVERAGE_SHIFT) + (sample >> LOAD_AVERAGE_SHIFT)
+}
+
+impl LoadTracker {
+    pub fn record_queue_time(&mut self, queue_time: TimestampDuration) {
+        self.average_queue_time = running_average(self.average_queue_time, queue_time.as_u64());
+    }
+    pub fn record_processing_time(&mut self, processing_time: TimestampDuration) {
+        self.average_processing_time =
+            running_average(self.average_processing_time, processing_time.as_u64());
+
+        // The baseline follows processing time down right away but only rises slowly,
+        // so it stays close to how long processing takes without contention
+        self.baseline_processing_time = if self.baseline_processing_time == 0
+            || self.average_processing_time < self.baseline_processing_time
+        {
+            self.average_processing_time
+        } else {
+            self.baseline_processing_time
+                + ((self.average_processing_time - self.baseline_processing_time)
+                    >> BASELINE_RISE_SHIFT)
+        };
+    }
+    pub fn record_shed_question(&mut self) {
+        self.shed_questions = self.shed_questions.saturating_add(1);
+    }
+    pub fn record_dropped_message(&mut self) {
+        self.dropped_messages = self.dropped_messages.saturating_add(1);
+    }
+
+    /// Determine if we are too far behind to answer low priority questions in time
+    pub fn is_saturated(
+        &self,
+        queue_depth: usize,
+        queue_size: usize,
+        timeout: TimestampDuration,
+    ) -> bool {
+        (queue_size > 0 && queue_depth * 4 >= queue_size * 3)
+            || self.average_queue_time * 4 >= timeout.as_u64()
+    }
+
+    /// Determine how many calls a fanout should make at once
+    ///
+    /// Fanouts run at full concurrency until the queue is half full, then at half concurrency,
+    /// and at the minimum once we are saturated, so the answers to our own questions and the
+    /// questions from other nodes aren't stuck behind a large sync.
+    pub fn fanout_concurrency(
+        &self,
+        fanout: usize,
+        min_fanout: usize,
+        queue_depth: usize,
+        queue_size: usize,
+        timeout: TimestampDuration,
+    ) -> usize {
+        if fanout <= min_fanout {
+            return fanout;
+        }
+        if self.is_saturated(queue_depth, queue_size, timeout) {
+            return min_fanout;
+        }
+        if queue_size > 0 && queue_depth * 2 >= queue_size {
+            return (fanout / 2).max(min_fanout);
+        }
+        fanout
+    }
+
+    /// Determine how many rpc workers should be allowed to process messages at once
+    ///
+    /// Only adjusts the limit once per interval, and never below a quarter of 'max_limit'.
+    pub fn adapt_concurrency_limit(
+        &mut self,
+        cur_ts: Timestamp,
+        limit: usize,
+        max_limit: usize,
+        queue_depth: usize,
+    ) -> usize {
+        let min_limit = (max_limit / MIN_CONCURRENCY_DIVISOR).max(1).min(max_limit);
+        if let Some(last_ts) = self.last_concurrency_adapt_ts {
+            if cur_ts.saturating_sub(last_ts).as_u64() < CONCURRENCY_ADAPT_INTERVAL_US {
+                return limit;
+            }
+        }
+        self.last_concurrency_adapt_ts = Some(cur_ts);
+
+        if self.average_processing_time
+            > self.baseline_processing_time * CONCURRENCY_CONTENTION_FACTOR
+        {
+            // Processing is slowing down, more workers at once would only make it worse
+            return (limit / 2).clamp(min_limit, max_limit);
+        }
+        if queue_depth > 0 {
+            // Messages are waiting and processing is keeping up, so let another worker at them
+            return (limit + 1).clamp(min_limit, max_limit);
+        }
+        limit
+    }
+
+    pub fn get_stats(&self, queue_depth: usize, queue_size: usize) -> RPCLoadStats {
+        RPCLoadStats {
+            queue_depth: queue_depth as u32,
+            queue_size: queue_size as u32,
+            average_queue_time: TimestampDuration::new(self.average_queue_time),
+            average_processing_time: TimestampDuration::new(self.average_processing_time),
+            shed_questions: self.shed_questions,
+            dropped_messages: self.dropped_messages,
+        }
+    }
+}
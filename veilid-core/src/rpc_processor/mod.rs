@@ -1,10 +1,15 @@
+mod answer_cache;
+mod app_message_limiter;
 mod coders;
 mod destination;
 mod fanout_call;
 mod fanout_queue;
+mod load_tracker;
 mod operation_waiter;
+mod proof_of_work;
 mod rpc_app_call;
 mod rpc_app_message;
+mod rpc_digest_value;
 mod rpc_error;
 mod rpc_find_node;
 mod rpc_get_value;
@@ -12,12 +17,16 @@ mod rpc_inspect_value;
 mod rpc_return_receipt;
 mod rpc_route;
 mod rpc_set_value;
+mod rpc_set_value_batch;
 mod rpc_signal;
 mod rpc_status;
 mod rpc_validate_dial_info;
 mod rpc_value_changed;
 mod rpc_watch_value;
 
+pub mod fuzz;
+pub mod tests;
+
 #[cfg(feature = "unstable-blockstore")]
 mod rpc_find_block;
 #[cfg(feature = "unstable-blockstore")]
@@ -30,20 +39,24 @@ mod rpc_complete_tunnel;
 #[cfg(feature = "unstable-tunnels")]
 mod rpc_start_tunnel;
 
+pub(crate) use app_message_limiter::*;
 pub(crate) use coders::*;
 pub(crate) use destination::*;
 pub(crate) use operation_waiter::*;
+pub(crate) use proof_of_work::*;
 pub(crate) use rpc_error::*;
 pub(crate) use rpc_status::*;
 pub(crate) use fanout_call::*;
 
 use super::*;
 
+use answer_cache::*;
 use crypto::*;
 use futures_util::StreamExt;
 use network_manager::*;
 use routing_table::*;
 use fanout_queue::*;
+use load_tracker::*;
 use stop_token::future::FutureExt;
 use storage_manager::*;
 
@@ -180,9 +193,33 @@ where
     Ok(buffer)
 }
 
+/// Context kept with a question while it waits for its answer
+#[derive(Debug, Clone)]
+struct RPCAnswerContext {
+    /// Question-specific context used to validate the answer
+    question_context: Option<QuestionContext>,
+    /// Where a direct answer over UDP must come from, if strict UDP source validation applies
+    expected_udp_source: Option<SocketAddress>,
+}
+
+/// Check the source of a direct UDP answer against the address its question was sent to
+/// Symmetric NATs may rebind the port of a mapping, so optionally only the address has to match
+fn udp_answer_source_matches(
+    expected: SocketAddress,
+    actual: SocketAddress,
+    allow_rebind: bool,
+) -> bool {
+    let expected = expected.canonical();
+    let actual = actual.canonical();
+    if expected == actual {
+        return true;
+    }
+    allow_rebind && expected.ip_addr() == actual.ip_addr()
+}
+
 #[derive(Debug)]
 struct WaitableReply {
-    handle: OperationWaitHandle<RPCMessage, Option<QuestionContext>>,
+    handle: OperationWaitHandle<RPCMessage, RPCAnswerContext>,
     timeout_us: TimestampDuration,
     node_ref: NodeRef,
     send_ts: Timestamp,
@@ -190,6 +227,8 @@ struct WaitableReply {
     safety_route: Option<PublicKey>,
     remote_private_route: Option<PublicKey>,
     reply_private_route: Option<PublicKey>,
+    /// Whether the far end was known to be answering when the question was sent
+    remote_reachable: bool,
     _opt_connection_ref_scope: Option<ConnectionRefScope>,
 }
 
@@ -276,6 +315,10 @@ enum RPCKind {
 
 struct RPCProcessorInner {
     send_channel: Option<flume::Sender<(Option<Id>, RPCMessageEncoded)>>,
+    /// One permit per rpc worker allowed to process a message right now
+    worker_permit_channel: Option<(flume::Sender<()>, flume::Receiver<()>)>,
+    /// Permits taken out of circulation to lower the number of workers processing at once
+    withheld_worker_permits: usize,
     stop_source: Option<StopSource>,
     worker_join_handles: Vec<MustJoinHandle<()>>,
 }
@@ -284,12 +327,18 @@ struct RPCProcessorUnlockedInner {
     timeout_us: TimestampDuration,
     queue_size: u32,
     concurrency: u32,
+    min_fanout_under_load: usize,
     max_route_hop_count: usize,
     #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
     validate_dial_info_receipt_time_ms: u32,
     update_callback: UpdateCallback,
-    waiting_rpc_table: OperationWaiter<RPCMessage, Option<QuestionContext>>,
+    strict_udp_source: bool,
+    strict_udp_source_allow_rebind: bool,
+    waiting_rpc_table: OperationWaiter<RPCMessage, RPCAnswerContext>,
     waiting_app_call_table: OperationWaiter<Vec<u8>, ()>,
+    answer_cache: AnswerCache,
+    load_tracker: Mutex<LoadTracker>,
+    app_message_limiter: Mutex<AppMessageLimiter>,
 }
 
 #[derive(Clone)]
@@ -307,6 +356,8 @@ impl RPCProcessor {
     fn new_inner() -> RPCProcessorInner {
         RPCProcessorInner {
             send_channel: None,
+            worker_permit_channel: None,
+            withheld_worker_permits: 0,
             stop_source: None,
             worker_join_handles: Vec::new(),
         }
@@ -333,16 +384,28 @@ impl RPCProcessor {
             concurrency *= 16;
         }
         let validate_dial_info_receipt_time_ms = c.network.dht.validate_dial_info_receipt_time_ms;
+        let app_message_limiter = AppMessageLimiter::new(
+            c.network.rpc.max_app_message_size as usize,
+            c.network.rpc.max_app_call_size as usize,
+            c.network.rpc.max_app_message_frequency_per_min,
+            c.network.rpc.max_app_call_frequency_per_min,
+        );
 
         RPCProcessorUnlockedInner {
             timeout_us,
             queue_size,
             concurrency,
+            min_fanout_under_load: c.network.dht.min_fanout_under_load as usize,
             max_route_hop_count,
             validate_dial_info_receipt_time_ms,
             update_callback,
+            strict_udp_source: c.network.rpc.strict_udp_source,
+            strict_udp_source_allow_rebind: c.network.rpc.strict_udp_source_allow_rebind,
             waiting_rpc_table: OperationWaiter::new(),
             waiting_app_call_table: OperationWaiter::new(),
+            answer_cache: AnswerCache::new(ANSWER_CACHE_SIZE),
+            load_tracker: Mutex::new(LoadTracker::default()),
+            app_message_limiter: Mutex::new(app_message_limiter),
         }
     }
     pub fn new(network_manager: NetworkManager, update_callback: UpdateCallback) -> Self {
@@ -382,6 +445,14 @@ impl RPCProcessor {
             inner.send_channel = Some(channel.0.clone());
             inner.stop_source = Some(StopSource::new());
 
+            // every worker may process messages at once until the load says otherwise
+            let permit_channel = flume::bounded(self.unlocked_inner.concurrency as usize);
+            for _ in 0..self.unlocked_inner.concurrency {
+                permit_channel.0.try_send(())?;
+            }
+            inner.worker_permit_channel = Some(permit_channel.clone());
+            inner.withheld_worker_permits = 0;
+
             // spin up N workers
             log_rpc!(
                 "Spinning up {} RPC workers",
@@ -390,10 +461,12 @@ impl RPCProcessor {
             for _ in 0..self.unlocked_inner.concurrency {
                 let this = self.clone();
                 let receiver = channel.1.clone();
+                let permit_receiver = permit_channel.1.clone();
                 let jh = spawn(Self::rpc_worker(
                     this,
                     inner.stop_source.as_ref().unwrap().token(),
                     receiver,
+                    permit_receiver,
                 ));
                 inner.worker_join_handles.push(jh);
             }
@@ -440,6 +513,104 @@ impl RPCProcessor {
 
     //////////////////////////////////////////////////////////////////////
 
+    /// Determine if the rpc worker queue is backed up enough that expensive operations should be throttled
+    pub(crate) fn is_under_load(&self) -> bool {
+        let inner = self.inner.lock();
+        let Some(send_channel) = inner.send_channel.as_ref() else {
+            return false;
+        };
+        send_channel.len() >= (self.unlocked_inner.queue_size as usize / 2)
+    }
+
+    fn queue_depth(&self) -> usize {
+        let inner = self.inner.lock();
+        inner.send_channel.as_ref().map(|sc| sc.len()).unwrap_or_default()
+    }
+
+    /// Determine if the rpc workers are too far behind to answer low priority questions in time
+    fn is_saturated(&self) -> bool {
+        let queue_depth = self.queue_depth();
+        self.unlocked_inner.load_tracker.lock().is_saturated(
+            queue_depth,
+            self.unlocked_inner.queue_size as usize,
+            self.unlocked_inner.timeout_us,
+        )
+    }
+
+    /// Determine how many calls a fanout configured for 'fanout' calls at once should make right now
+    pub(crate) fn fanout_concurrency(&self, fanout: usize) -> usize {
+        let queue_depth = self.queue_depth();
+        self.unlocked_inner.load_tracker.lock().fanout_concurrency(
+            fanout,
+            self.unlocked_inner.min_fanout_under_load,
+            queue_depth,
+            self.unlocked_inner.queue_size as usize,
+            self.unlocked_inner.timeout_us,
+        )
+    }
+
+    fn record_processing_time(&self, dequeue_ts: Timestamp) {
+        self.unlocked_inner
+            .load_tracker
+            .lock()
+            .record_processing_time(get_aligned_timestamp().saturating_sub(dequeue_ts));
+    }
+
+    /// Finish processing a message: record how long it took, give the worker's permit back,
+    /// and adapt how many workers may process messages at once
+    fn finish_rpc_message(&self, dequeue_ts: Timestamp) {
+        self.record_processing_time(dequeue_ts);
+
+        let queue_depth = self.queue_depth();
+        let mut inner = self.inner.lock();
+        let Some((permit_sender, permit_receiver)) = inner.worker_permit_channel.clone() else {
+            return;
+        };
+
+        // There are never more permits than the channel holds, so this always succeeds
+        let _ = permit_sender.try_send(());
+
+        let max_limit = self.unlocked_inner.concurrency as usize;
+        let limit = max_limit - inner.withheld_worker_permits;
+        let new_limit = self
+            .unlocked_inner
+            .load_tracker
+            .lock()
+            .adapt_concurrency_limit(get_aligned_timestamp(), limit, max_limit, queue_depth);
+        if new_limit > limit {
+            for _ in limit..new_limit {
+                if permit_sender.try_send(()).is_ok() {
+                    inner.withheld_worker_permits -= 1;
+                }
+            }
+        } else {
+            // Only permits that aren't in use can be withheld, busy workers keep theirs until they finish
+            for _ in new_limit..limit {
+                if permit_receiver.try_recv().is_ok() {
+                    inner.withheld_worker_permits += 1;
+                }
+            }
+        }
+    }
+
+    fn record_enqueue_failure<T>(&self, e: &flume::TrySendError<T>) {
+        if matches!(e, flume::TrySendError::Full(_)) {
+            self.unlocked_inner
+                .load_tracker
+                .lock()
+                .record_dropped_message();
+        }
+    }
+
+    /// Get the queue depth, latencies, and drop counters of the rpc workers
+    pub fn get_load_stats(&self) -> RPCLoadStats {
+        let queue_depth = self.queue_depth();
+        self.unlocked_inner
+            .load_tracker
+            .lock()
+            .get_stats(queue_depth, self.unlocked_inner.queue_size as usize)
+    }
+
     /// Get waiting app call id for debugging purposes
     pub fn get_app_call_ids(&self) -> Vec<OperationId> {
         self.unlocked_inner.waiting_app_call_table.get_operation_ids()
@@ -537,10 +708,7 @@ impl RPCProcessor {
             let routing_table = this.routing_table();
 
             // First see if we have the node in our routing table already
-            if let Some(nr) = routing_table
-                .lookup_node_ref(node_id)
-                .map_err(RPCError::internal)?
-            {
+            if let Some(nr) = routing_table.lookup_node_ref(node_id)? {
                 // ensure we have some dial info for the entry already,
                 // and that the node is still alive
                 // if not, we should do the find_node anyway
@@ -577,6 +745,73 @@ impl RPCProcessor {
         })
     }
 
+    /// Search the network for the nodes closest to a key that have all of the specified capabilities
+    /// Returns up to 'count' nodes in order of distance from the key, which may be fewer if the search timed out
+    pub async fn search_for_closest_nodes(
+        &self,
+        key: TypedKey,
+        count: usize,
+        capabilities: Vec<Capability>,
+        safety_selection: SafetySelection,
+    ) -> Result<Vec<NodeRef>, RPCError> {
+        let routing_table = self.routing_table();
+
+        let (fanout, timeout_us) = {
+            let c = self.config.get();
+            (
+                c.network.dht.resolve_node_fanout as usize,
+                TimestampDuration::from(ms_to_us(c.network.dht.resolve_node_timeout_ms)),
+            )
+        };
+
+        // Routine to call to generate fanout
+        let call_routine = |next_node: NodeRef| {
+            let this = self.clone();
+            let capabilities = capabilities.clone();
+            async move {
+                let v = network_result_try!(this
+                    .clone()
+                    .rpc_call_find_node(
+                        Destination::direct(next_node).with_safety(safety_selection),
+                        key,
+                        capabilities,
+                    )
+                    .await?);
+                Ok(NetworkResult::value(v.answer))
+            }
+        };
+
+        // Keep the closest nodes seen at each step, the search only ends when it runs out of closer nodes
+        let closest_nodes = Mutex::new(Vec::<NodeRef>::new());
+        let check_done = |current_nodes: &[NodeRef]| -> Option<()> {
+            *closest_nodes.lock() = current_nodes.to_vec();
+            None
+        };
+
+        // Call the fanout
+        let fanout_call = FanoutCall::new(
+            routing_table.clone(),
+            key,
+            count,
+            fanout,
+            timeout_us,
+            capability_fanout_node_info_filter(capabilities.clone()),
+            call_routine,
+            check_done,
+        );
+
+        match fanout_call.run(vec![]).await {
+            TimeoutOr::Timeout | TimeoutOr::Value(Ok(_)) => {}
+            TimeoutOr::Value(Err(e)) => {
+                return Err(e);
+            }
+        }
+
+        let mut closest_nodes = closest_nodes.lock().clone();
+        closest_nodes.truncate(count);
+        Ok(closest_nodes)
+    }
+
     #[cfg_attr(
         feature = "verbose-tracing",
         instrument(level = "trace", skip(self, waitable_reply), err)
@@ -600,6 +835,7 @@ impl RPCProcessor {
                     waitable_reply.safety_route,
                     waitable_reply.remote_private_route,
                     waitable_reply.reply_private_route,
+                    waitable_reply.remote_reachable,
                 );
             }
             Ok(TimeoutOr::Timeout) => {
@@ -610,6 +846,7 @@ impl RPCProcessor {
                     waitable_reply.safety_route,
                     waitable_reply.remote_private_route,
                     waitable_reply.reply_private_route,
+                    waitable_reply.remote_reachable,
                 );
             }
             Ok(TimeoutOr::Value((rpcreader, _))) => {
@@ -646,7 +883,7 @@ impl RPCProcessor {
     }
 
     /// Wrap an operation with a private route inside a safety route
-    fn wrap_with_route(
+    async fn wrap_with_route(
         &self,
         safety_selection: SafetySelection,
         remote_private_route: PrivateRoute,
@@ -674,9 +911,13 @@ impl RPCProcessor {
         // Encrypt routed operation
         // Xmsg + ENC(Xmsg, DH(PKapr, SKbsr))
         let nonce = vcrypto.random_nonce();
-        let dh_secret = vcrypto
-            .cached_dh(&pr_pubkey, &compiled_route.secret)
-            .map_err(RPCError::map_internal("dh failed"))?;
+        let dh_secret = match &compiled_route.secret {
+            Some(secret) => vcrypto.cached_dh(&pr_pubkey, secret),
+            None => routing_table
+                .node_id_signer(crypto_kind)
+                .cached_dh(&pr_pubkey),
+        }
+        .map_err(RPCError::map_internal("dh failed"))?;
         let enc_msg_data = vcrypto
             .encrypt_aead(&message_data, &nonce, &dh_secret, None)
             .map_err(RPCError::map_internal("encryption failed"))?;
@@ -685,9 +926,19 @@ impl RPCProcessor {
         let operation =
             RoutedOperation::new(safety_selection.get_sequencing(), nonce, enc_msg_data);
 
+        // Solve the proof-of-work the first hop demands, if any
+        let pow_nonce = self
+            .solve_route_pow(
+                &compiled_route.first_hop,
+                &compiled_route.safety_route.public_key,
+                &operation,
+            )
+            .await;
+
         // Prepare route operation
         let sr_hop_count = compiled_route.safety_route.hop_count;
-        let route_operation = RPCOperationRoute::new(compiled_route.safety_route, operation);
+        let route_operation =
+            RPCOperationRoute::new(compiled_route.safety_route, operation, pow_nonce);
         let ssni_route =
             self.get_sender_peer_info(&Destination::direct(compiled_route.first_hop.clone()));
         let operation = RPCOperation::new_statement(
@@ -724,7 +975,7 @@ impl RPCProcessor {
         feature = "verbose-tracing",
         instrument(level = "debug", skip(self, operation), err)
     )]
-    fn render_operation(
+    async fn render_operation(
         &self,
         dest: Destination,
         operation: &RPCOperation,
@@ -828,7 +1079,7 @@ impl RPCProcessor {
                             private_route,
                             reply_private_route,
                             message,
-                        )?;
+                        ).await?;
                     }
                 };
             }
@@ -844,7 +1095,7 @@ impl RPCProcessor {
                     private_route,
                     reply_private_route,
                     message,
-                )?;
+                ).await?;
             }
         }
 
@@ -914,6 +1165,16 @@ impl RPCProcessor {
         SenderPeerInfo::new(own_peer_info, target_node_info_ts)
     }
 
+    /// Control traffic may jump ahead of application data on a connection,
+    /// which must otherwise arrive in the order it was sent
+    fn send_priority(operation: &RPCOperation) -> SendPriority {
+        if operation.kind().is_control() {
+            SendPriority::Control
+        } else {
+            SendPriority::Normal
+        }
+    }
+
     /// Record failure to send to node or route
     fn record_send_failure(
         &self,
@@ -939,6 +1200,9 @@ impl RPCProcessor {
         if let Some(sr_pubkey) = &safety_route {
             let rss = self.routing_table.route_spec_store();
             rss.with_route_stats_mut(send_ts, sr_pubkey, |s| s.record_send_failed());
+
+            // Failing to send means the first hop of the safety route is at fault
+            rss.record_route_first_hop_failed(sr_pubkey);
         } else {
             // If no safety route was in use, then it's the private route's fault if we have one
             if let Some(pr_pubkey) = &remote_private_route {
@@ -956,6 +1220,7 @@ impl RPCProcessor {
         safety_route: Option<PublicKey>,
         remote_private_route: Option<PublicKey>,
         private_route: Option<PublicKey>,
+        remote_reachable: bool,
     ) {
         // Record for node if this was not sent via a route
         if safety_route.is_none() && remote_private_route.is_none() {
@@ -988,6 +1253,42 @@ impl RPCProcessor {
                 s.record_question_lost();
             });
         }
+
+        // An unreachable far end explains the loss by itself, so our hops are only blamed
+        // when the far end was known to be answering
+        if !remote_reachable {
+            return;
+        }
+
+        // Any hop of our own routes could have lost the question, so spread the blame over all of them
+        let local_route_keys: Vec<PublicKey> =
+            safety_route.into_iter().chain(private_route).collect();
+        rss.record_route_hops_failed(&local_route_keys);
+    }
+
+    /// Check if the far end of a destination was answering us recently
+    fn is_destination_reachable(&self, dest: &Destination, cur_ts: Timestamp) -> bool {
+        match dest {
+            Destination::Direct { node, .. } | Destination::Relay { node, .. } => {
+                matches!(node.state(cur_ts), BucketEntryState::Reliable)
+            }
+            Destination::PrivateRoute { private_route, .. } => {
+                if private_route.is_stub() {
+                    // A stub route is the node itself
+                    let node_id = TypedKey::new(
+                        private_route.crypto_kind(),
+                        private_route.public_key.value,
+                    );
+                    return matches!(
+                        self.routing_table.lookup_node_ref(node_id),
+                        Ok(Some(nr)) if matches!(nr.state(cur_ts), BucketEntryState::Reliable)
+                    );
+                }
+                self.routing_table
+                    .route_spec_store()
+                    .is_remote_route_reachable(cur_ts, &private_route.public_key.value)
+            }
+        }
     }
 
     /// Record success sending to node or route
@@ -1116,6 +1417,11 @@ impl RPCProcessor {
                 });
             }
         }
+
+        // Every hop of our own routes carried the answer, so reduce their blame
+        let local_route_keys: Vec<PublicKey> =
+            safety_route.into_iter().chain(reply_private_route).collect();
+        rss.record_route_hops_succeeded(&local_route_keys);
     }
 
     /// Record question or statement received from node or route
@@ -1153,9 +1459,18 @@ impl RPCProcessor {
                     s.record_received(recv_ts, bytes);
                 });
 
+                // Route tests are status questions, anything else is the application using the route
+                let is_route_test = matches!(
+                    msg.operation.kind(),
+                    RPCOperationKind::Question(q) if matches!(q.detail(), RPCQuestionDetail::StatusQ(_))
+                );
+
                 // Record for our local private route we received over
                 rss.with_route_stats_mut(recv_ts, &d.private_route, |s| {
                     s.record_received(recv_ts, bytes);
+                    if !is_route_test {
+                        s.record_used(recv_ts);
+                    }
                 });
             }
         }
@@ -1176,6 +1491,9 @@ impl RPCProcessor {
         // Get sender peer info if we should send that
         let spi = self.get_sender_peer_info(&dest);
 
+        // Lost answers only count against our route hops if the far end is known to be answering
+        let remote_reachable = self.is_destination_reachable(&dest, get_aligned_timestamp());
+
         // Wrap question in operation
         let operation = RPCOperation::new_question(question, spi);
         let op_id = operation.op_id();
@@ -1193,7 +1511,7 @@ impl RPCProcessor {
             safety_route,
             remote_private_route,
             reply_private_route,
-        } = network_result_try!(self.render_operation(dest.clone(), &operation)?);
+        } = network_result_try!(self.render_operation(dest.clone(), &operation).await?);
 
         // Calculate answer timeout
         // Timeout is number of hops times the timeout per hop
@@ -1203,7 +1521,13 @@ impl RPCProcessor {
         let handle = self
             .unlocked_inner
             .waiting_rpc_table
-            .add_op_waiter(op_id, context);
+            .add_op_waiter(
+                op_id,
+                RPCAnswerContext {
+                    question_context: context,
+                    expected_udp_source: None,
+                },
+            );
 
         // Send question
         let bytes: ByteCount = (message.len() as u64).into();
@@ -1216,6 +1540,7 @@ impl RPCProcessor {
                 node_ref.clone(),
                 Some(destination_node_ref.clone()),
                 message,
+                Self::send_priority(&operation),
             )
             .await
             .map_err(|e| {
@@ -1246,6 +1571,21 @@ impl RPCProcessor {
             remote_private_route,
         );
 
+        // Direct answers to questions sent over UDP must come back from where we sent them
+        if self.unlocked_inner.strict_udp_source
+            && safety_route.is_none()
+            && remote_private_route.is_none()
+            && send_data_method.opt_relayed_contact_method.is_none()
+        {
+            let remote = send_data_method.unique_flow.flow.remote();
+            if remote.protocol_type() == ProtocolType::UDP {
+                self.unlocked_inner
+                    .waiting_rpc_table
+                    .update_op_context(op_id, |c| {
+                        c.expected_udp_source = Some(*remote.socket_address());
+                    });
+            }
+        }
 
         // Ref the connection so it doesn't go away until we're done with the waitable reply
         let opt_connection_ref_scope = send_data_method.unique_flow.connection_id.and_then(|id| self
@@ -1263,6 +1603,7 @@ impl RPCProcessor {
             safety_route,
             remote_private_route,
             reply_private_route,
+            remote_reachable,
             _opt_connection_ref_scope: opt_connection_ref_scope,
         }))
     }
@@ -1296,7 +1637,7 @@ impl RPCProcessor {
             safety_route,
             remote_private_route,
             reply_private_route: _,
-        } = network_result_try!(self.render_operation(dest, &operation)?);
+        } = network_result_try!(self.render_operation(dest, &operation).await?);
 
         // Send statement
         let bytes: ByteCount = (message.len() as u64).into();
@@ -1309,6 +1650,7 @@ impl RPCProcessor {
                 node_ref.clone(),
                 Some(destination_node_ref.clone()),
                 message,
+                Self::send_priority(&operation),
             )
             .await
             .map_err(|e| {
@@ -1374,7 +1716,7 @@ impl RPCProcessor {
             safety_route,
             remote_private_route,
             reply_private_route: _,
-        } = network_result_try!(self.render_operation(dest, &operation)?);
+        } = network_result_try!(self.render_operation(dest, &operation).await?);
 
         // Send the reply
         let bytes: ByteCount = (message.len() as u64).into();
@@ -1387,6 +1729,7 @@ impl RPCProcessor {
                 node_ref.clone(),
                 Some(destination_node_ref.clone()),
                 message,
+                Self::send_priority(&operation),
             )
             .await
             .map_err(|e| {
@@ -1459,6 +1802,7 @@ impl RPCProcessor {
             self.unlocked_inner
                 .waiting_rpc_table
                 .get_op_context(op_id)?
+                .question_context
         } else {
             None
         };
@@ -1474,6 +1818,25 @@ impl RPCProcessor {
         Ok(())
     }
 
+    /// Check that a direct answer came back from where its question was sent, if that
+    /// question went out over UDP with strict source validation on
+    fn check_udp_answer_source(&self, op_id: OperationId, flow: &Flow) -> bool {
+        if flow.protocol_type() != ProtocolType::UDP {
+            return true;
+        }
+        let Ok(context) = self.unlocked_inner.waiting_rpc_table.get_op_context(op_id) else {
+            return true;
+        };
+        let Some(expected) = context.expected_udp_source else {
+            return true;
+        };
+        udp_answer_source_matches(
+            expected,
+            *flow.remote_address(),
+            self.unlocked_inner.strict_udp_source_allow_rebind,
+        )
+    }
+
     //////////////////////////////////////////////////////////////////////
     #[cfg_attr(
         feature = "verbose-tracing",
@@ -1505,7 +1868,10 @@ impl RPCProcessor {
                                 address_filter.punish_node_id(sender_node_id);
                             },
                             // Ignored messages that should be dropped
-                            RPCError::Ignore(_) | RPCError::Network(_) | RPCError::TryAgain(_) => {
+                            RPCError::Ignore(_)
+                            | RPCError::Network(_)
+                            | RPCError::TryAgain(_)
+                            | RPCError::RouteCompilation(_) => {
                                 log_rpc!(debug "Dropping RPC Operation: {}", e);
                             },
                             // Internal errors that deserve louder logging
@@ -1517,6 +1883,17 @@ impl RPCProcessor {
                     },
                 };
 
+                // Drop answers that came from somewhere other than where the question went,
+                // leaving the question waiting for the real answer
+                if matches!(operation.kind(), RPCOperationKind::Answer(_))
+                    && !self.check_udp_answer_source(operation.op_id(), &detail.flow)
+                {
+                    address_filter.report_spoofed_source(detail.flow.remote_address().ip_addr());
+                    return Ok(NetworkResult::invalid_message(
+                        format!("answer from unexpected udp source: {}", detail.flow.remote_address()),
+                    ));
+                }
+
                 // Get the routing domain this message came over
                 let routing_domain = detail.routing_domain;
 
@@ -1543,12 +1920,21 @@ impl RPCProcessor {
                             format!("sender peerinfo has invalid peer scope: {:?}",sender_peer_info.signed_node_info())
                         ));
                     }
+                    // A signature only proves the sender holds its key, not that it is reachable,
+                    // so unknown near nodes still have to be validated before they get a bucket
                     opt_sender_nr = match self.routing_table().register_node_with_peer_info(
                         routing_domain,
                         sender_peer_info.clone(),
                         false,
                     ) {
                         Ok(v) => Some(v),
+                        Err(RoutingTableError::RateLimited(_)) => None,
+                        Err(RoutingTableError::Banned(_)) => {
+                            return Ok(NetworkResult::no_connection_other("sender is banned"));
+                        }
+                        Err(e @ (RoutingTableError::TableFull(_) | RoutingTableError::Internal(_))) => {
+                            return Err(e.into());
+                        }
                         Err(e) => {
                             address_filter.punish_node_id(sender_node_id);
                             return Ok(NetworkResult::invalid_message(e));
@@ -1635,6 +2021,30 @@ impl RPCProcessor {
             }
         };
 
+        // Turn away low priority questions when we are too far behind to answer them in time
+        if let RPCOperationKind::Question(q) = msg.operation.kind() {
+            if q.detail().is_low_priority() && self.is_saturated() {
+                self.unlocked_inner.load_tracker.lock().record_shed_question();
+
+                // Only nodes that advertise they can decode a busy answer get one, anyone else just times out
+                let sender_understands_busy = msg
+                    .opt_sender_nr
+                    .as_ref()
+                    .map(|nr| nr.has_capabilities(&[CAP_BUSY_ANSWER]))
+                    .unwrap_or_default();
+                if !sender_understands_busy {
+                    log_rpc!(debug "Dropping RPC question from {:?}", msg.opt_sender_nr);
+                    return Ok(NetworkResult::service_unavailable("too busy to answer question"));
+                }
+
+                log_rpc!(debug "Shedding RPC question from {:?}", msg.opt_sender_nr);
+                let busy_a = RPCOperationBusyA::new();
+                return self
+                    .answer(msg, RPCAnswer::new(RPCAnswerDetail::BusyA(Box::new(busy_a))))
+                    .await;
+            }
+        }
+
         // Process specific message kind
         match msg.operation.kind() {
             RPCOperationKind::Question(q) => match q.detail() {
@@ -1645,6 +2055,8 @@ impl RPCProcessor {
                 RPCQuestionDetail::SetValueQ(_) => self.process_set_value_q(msg).await,
                 RPCQuestionDetail::WatchValueQ(_) => self.process_watch_value_q(msg).await,
                 RPCQuestionDetail::InspectValueQ(_) => self.process_inspect_value_q(msg).await,
+                RPCQuestionDetail::SetValueBatchQ(_) => self.process_set_value_batch_q(msg).await,
+                RPCQuestionDetail::DigestValueQ(_) => self.process_digest_value_q(msg).await,
                 #[cfg(feature = "unstable-blockstore")]
                 RPCQuestionDetail::SupplyBlockQ(_) => self.process_supply_block_q(msg).await,
                 #[cfg(feature = "unstable-blockstore")]
@@ -1680,13 +2092,25 @@ impl RPCProcessor {
         self,
         stop_token: StopToken,
         receiver: flume::Receiver<(Option<Id>, RPCMessageEncoded)>,
+        permit_receiver: flume::Receiver<()>,
     ) {
-        while let Ok(Ok((_span_id, msg))) =
-            receiver.recv_async().timeout_at(stop_token.clone()).await
-        {
-            let rpc_worker_span = span!(parent: None, Level::TRACE, "rpc_worker recv");
+        // Wait until this worker is allowed to process a message, then for the message itself
+        while let Ok(Ok(())) = permit_receiver.recv_async().timeout_at(stop_token.clone()).await {
+            let Ok(Ok((_span_id, msg))) =
+                receiver.recv_async().timeout_at(stop_token.clone()).await
+            else {
+                break;
+            };
+
+            let rpc_worker_span = span!(parent: &self.config.instance_span(), Level::TRACE, "rpc_worker recv");
             // xxx: causes crash (Missing otel data span extensions)
             // rpc_worker_span.follows_from(span_id);
+
+            let dequeue_ts = get_aligned_timestamp();
+            self.unlocked_inner
+                .load_tracker
+                .lock()
+                .record_queue_time(dequeue_ts.saturating_sub(msg.header.timestamp));
                     
             network_result_value_or_log!(match self
                 .process_rpc_message(msg)
@@ -1695,6 +2119,7 @@ impl RPCProcessor {
             {
                 Err(e) => {
                     log_rpc!(error "couldn't process rpc message: {}", e);
+                    self.finish_rpc_message(dequeue_ts);
                     continue;
                 }
 
@@ -1702,6 +2127,8 @@ impl RPCProcessor {
                     v
                 }
             } => [ format!(": msg.header={:?}", msg.header) ] {});
+
+            self.finish_rpc_message(dequeue_ts);
         }
     }
 
@@ -1743,7 +2170,10 @@ impl RPCProcessor {
         let span_id = Span::current().id();
         send_channel
             .try_send((span_id, msg))
-            .map_err(|e| eyre!("failed to enqueue direct RPC message: {}", e))?;
+            .map_err(|e| {
+                self.record_enqueue_failure(&e);
+                eyre!("failed to enqueue direct RPC message: {}", e)
+            })?;
         Ok(())
     }
 
@@ -1782,7 +2212,10 @@ impl RPCProcessor {
         let span_id = Span::current().id();
         send_channel
             .try_send((span_id, msg))
-            .map_err(|e| eyre!("failed to enqueue safety routed RPC message: {}", e))?;
+            .map_err(|e| {
+                self.record_enqueue_failure(&e);
+                eyre!("failed to enqueue safety routed RPC message: {}", e)
+            })?;
         Ok(())
     }
 
@@ -1824,7 +2257,10 @@ impl RPCProcessor {
         let span_id = Span::current().id();
         send_channel
             .try_send((span_id, msg))
-            .map_err(|e| eyre!("failed to enqueue private routed RPC message: {}", e))?;
+            .map_err(|e| {
+                self.record_enqueue_failure(&e);
+                eyre!("failed to enqueue private routed RPC message: {}", e)
+            })?;
         Ok(())
     }
 }
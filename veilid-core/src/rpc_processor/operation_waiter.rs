@@ -9,6 +9,7 @@ where
     waiter: OperationWaiter<T, C>,
     op_id: OperationId,
     eventual_instance: Option<EventualValueFuture<(Option<Id>, T)>>,
+    completed: bool,
 }
 
 impl<T, C> Drop for OperationWaitHandle<T, C>
@@ -17,7 +18,9 @@ where
     C: Unpin + Clone,
 {
     fn drop(&mut self) {
-        if self.eventual_instance.is_some() {
+        // Free up the waiting op if we stopped waiting for it before it completed,
+        // either by timing out or by the wait being dropped
+        if !self.completed {
             self.waiter.cancel_op_waiter(self.op_id);
         }
     }
@@ -97,6 +100,7 @@ where
             waiter: self.clone(),
             op_id,
             eventual_instance: Some(e.instance()),
+            completed: false,
         }
     }
 
@@ -121,6 +125,14 @@ where
         Ok(waiting_op.context.clone())
     }
 
+    /// Change the context of an operation that is still waiting
+    pub fn update_op_context<F: FnOnce(&mut C)>(&self, op_id: OperationId, f: F) {
+        let mut inner = self.inner.lock();
+        if let Some(waiting_op) = inner.waiting_op_table.get_mut(&op_id) {
+            f(&mut waiting_op.context);
+        }
+    }
+
     /// Remove wait for op
     fn cancel_op_waiter(&self, op_id: OperationId) {
         let mut inner = self.inner.lock();
@@ -159,7 +171,7 @@ where
         let timeout_ms = us_to_ms(timeout_us.as_u64()).map_err(RPCError::internal)?;
 
         // Take the instance
-        // After this, we must manually cancel since the cancel on handle drop is disabled
+        // If this wait is dropped or times out, the op is cancelled when the handle is dropped
         let eventual_instance = handle.eventual_instance.take().unwrap();
 
         // wait for eventualvalue
@@ -167,20 +179,20 @@ where
         let res = timeout(timeout_ms, eventual_instance)
             .await
             .into_timeout_or();
-        Ok(res
-            .on_timeout(|| {
-                // log_rpc!(debug "op wait timed out: {}", handle.op_id);
-                // debug_print_backtrace();
-                self.cancel_op_waiter(handle.op_id);
-            })
-            .map(|res| {
-                let (_span_id, ret) = res.take_value().unwrap();
-                let end_ts = get_aligned_timestamp();
-
-                //xxx: causes crash (Missing otel data span extensions)
-                // Span::current().follows_from(span_id);
-
-                (ret, end_ts.saturating_sub(start_ts))
-            }))
+
+        // Completing the op already removed it from the table
+        if res.is_value() {
+            handle.completed = true;
+        }
+
+        Ok(res.map(|res| {
+            let (_span_id, ret) = res.take_value().unwrap();
+            let end_ts = get_aligned_timestamp();
+
+            //xxx: causes crash (Missing otel data span extensions)
+            // Span::current().follows_from(span_id);
+
+            (ret, end_ts.saturating_sub(start_ts))
+        }))
     }
 }
@@ -0,0 +1,69 @@
+use super::*;
+
+/// Largest proof-of-work difficulty we will demand or attempt to solve, in leading zero bits
+pub const MAX_POW_DIFFICULTY: u8 = 24;
+
+/// Domain separation for proof-of-work hashes
+const POW_DOMAIN: &[u8] = b"VEILID_POW";
+
+fn pow_digest(vcrypto: &CryptoSystemVersion, challenge: &[u8], nonce: u64) -> HashDigest {
+    let mut data = Vec::with_capacity(POW_DOMAIN.len() + challenge.len() + 8);
+    data.extend_from_slice(POW_DOMAIN);
+    data.extend_from_slice(challenge);
+    data.extend_from_slice(&nonce.to_le_bytes());
+    vcrypto.generate_hash(&data)
+}
+
+/// Check a client puzzle solution. The hash of the challenge and nonce must have at least
+/// `difficulty` leading zero bits. A difficulty of zero always passes.
+pub fn verify_pow(
+    vcrypto: &CryptoSystemVersion,
+    challenge: &[u8],
+    nonce: u64,
+    difficulty: u8,
+) -> bool {
+    if difficulty == 0 {
+        return true;
+    }
+    let digest = pow_digest(vcrypto, challenge, nonce);
+    let zero_bits = digest.first_nonzero_bit().unwrap_or(HASH_DIGEST_LENGTH * 8);
+    zero_bits >= difficulty as usize
+}
+
+/// Find a nonce that solves the client puzzle for a challenge at the requested difficulty
+pub fn solve_pow(vcrypto: &CryptoSystemVersion, challenge: &[u8], difficulty: u8) -> u64 {
+    let mut nonce = get_random_u64();
+    while !verify_pow(vcrypto, challenge, nonce, difficulty) {
+        nonce = nonce.wrapping_add(1);
+    }
+    nonce
+}
+
+/// Solve a client puzzle without stalling the async executor
+pub async fn solve_pow_async(
+    vcrypto: CryptoSystemVersion,
+    challenge: Vec<u8>,
+    difficulty: u8,
+) -> u64 {
+    cfg_if! {
+        if #[cfg(target_arch = "wasm32")] {
+            // Number of nonces to try before giving the event loop a turn
+            const SOLVE_BATCH_SIZE: u64 = 4096;
+
+            // There is no blocking thread pool here, so solve in batches on the event loop
+            let mut nonce = get_random_u64();
+            loop {
+                for _ in 0..SOLVE_BATCH_SIZE {
+                    if verify_pow(&vcrypto, &challenge, nonce, difficulty) {
+                        return nonce;
+                    }
+                    nonce = nonce.wrapping_add(1);
+                }
+                sleep(0).await;
+            }
+        } else {
+            // If the blocking task fails we return an unsolved nonce and the request is refused again
+            blocking_wrapper(move || solve_pow(&vcrypto, &challenge, difficulty), 0).await
+        }
+    }
+}
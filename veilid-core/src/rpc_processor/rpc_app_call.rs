@@ -37,6 +37,9 @@ impl RPCProcessor {
         let app_call_a = match kind {
             RPCOperationKind::Answer(a) => match a.destructure() {
                 RPCAnswerDetail::AppCallA(a) => a,
+                RPCAnswerDetail::BusyA(_) => {
+                    return Ok(NetworkResult::service_unavailable("server busy"))
+                }
                 _ => return Ok(NetworkResult::invalid_message("not an appcall answer")),
             },
             _ => return Ok(NetworkResult::invalid_message("not an answer")),
@@ -116,8 +119,19 @@ impl RPCProcessor {
             .waiting_app_call_table
             .add_op_waiter(op_id, ());
 
-        // Pass the call up through the update callback
         let message_q = app_call_q.destructure();
+
+        // Drop the call if its source is over the app call limits
+        if !self.check_app_message_limits(
+            &msg.header,
+            sender,
+            AppMessageKind::Call,
+            message_q.len(),
+        ) {
+            return Ok(NetworkResult::service_unavailable("app call over limits"));
+        }
+
+        // Pass the call up through the update callback
         (self.unlocked_inner.update_callback)(VeilidUpdate::AppCall(Box::new(VeilidAppCall::new(
             sender, route_id, message_q, op_id,
         ))));
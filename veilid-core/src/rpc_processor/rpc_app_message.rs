@@ -1,4 +1,5 @@
 use super::*;
+use futures_util::stream::{FuturesUnordered, StreamExt};
 
 impl RPCProcessor {
     // Sends a high level app message
@@ -19,6 +20,59 @@ impl RPCProcessor {
         self.statement(dest, statement).await
     }
 
+    // Sends a high level app message to each of the nodes closest to a key
+    // Returns the nodes the message was sent to, closest first
+    #[cfg_attr(
+        feature = "verbose-tracing",
+        instrument(level = "trace", skip(self, message), fields(message.len = message.len()), err)
+    )]
+    pub async fn rpc_call_app_message_closest(
+        self,
+        key: TypedKey,
+        count: usize,
+        message: Vec<u8>,
+        safety_selection: SafetySelection,
+    ) -> Result<Vec<NodeRef>, RPCError> {
+        // Find the nodes closest to the key that will accept app messages
+        let closest_nodes = self
+            .search_for_closest_nodes(key, count, vec![CAP_APPMESSAGE], safety_selection)
+            .await?;
+
+        // Send the message to all of them at once
+        let mut unord = FuturesUnordered::new();
+        for (n, node) in closest_nodes.into_iter().enumerate() {
+            let this = self.clone();
+            let message = message.clone();
+            unord.push(async move {
+                let res = this
+                    .rpc_call_app_message(
+                        Destination::direct(node.clone()).with_safety(safety_selection),
+                        message,
+                    )
+                    .await;
+                (n, node, res)
+            });
+        }
+
+        let mut sent_nodes = vec![];
+        while let Some((n, node, res)) = unord.next().await {
+            match res {
+                Ok(NetworkResult::Value(())) => {
+                    sent_nodes.push((n, node));
+                }
+                Ok(v) => {
+                    log_network_result!(debug "AppMessage to closest node {} failed: {}", node, v);
+                }
+                Err(e) => {
+                    return Err(e);
+                }
+            }
+        }
+
+        sent_nodes.sort_by_key(|(n, _)| *n);
+        Ok(sent_nodes.into_iter().map(|(_, node)| node).collect())
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     #[cfg_attr(feature="verbose-tracing", instrument(level = "trace", skip(self, msg), fields(msg.operation.op_id), ret, err))]
@@ -73,8 +127,20 @@ impl RPCProcessor {
             .as_ref()
             .map(|nr| nr.node_ids().get(crypto_kind).unwrap());
 
-        // Pass the message up through the update callback
+        // Drop the message if its source is over the app message limits
         let message = app_message.destructure();
+        if !self.check_app_message_limits(
+            &msg.header,
+            sender,
+            AppMessageKind::Message,
+            message.len(),
+        ) {
+            return Ok(NetworkResult::service_unavailable(
+                "app message over limits",
+            ));
+        }
+
+        // Pass the message up through the update callback
         (self.unlocked_inner.update_callback)(VeilidUpdate::AppMessage(Box::new(
             VeilidAppMessage::new(sender, route_id, message),
         )));
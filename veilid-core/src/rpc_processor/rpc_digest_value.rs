@@ -0,0 +1,158 @@
+use super::*;
+use crate::storage_manager::SubkeyDigestRange;
+
+#[derive(Clone, Debug)]
+pub struct DigestValueAnswer {
+    pub digests: Vec<HashDigest>,
+}
+
+impl RPCProcessor {
+    /// Sends a digest value request and wait for response
+    /// Can be sent via all methods including relays
+    /// Safety routes may be used, but never private routes.
+    /// Because this leaks information about the identity of the node itself,
+    /// replying to this request received over a private route will leak
+    /// the identity of the node and defeat the private route.
+    /// The number of digests returned may either be:
+    ///  * one per subkey range requested
+    ///  * zero if the record was not found
+    #[cfg_attr(
+        feature = "verbose-tracing",
+        instrument(level = "trace", skip(self, ranges), fields(ret.digests.len, ret.latency), err)
+    )]
+    pub async fn rpc_call_digest_value(
+        self,
+        dest: Destination,
+        key: TypedKey,
+        ranges: Vec<SubkeyDigestRange>,
+    ) -> RPCNetworkResult<Answer<DigestValueAnswer>> {
+        // Ensure destination never has a private route
+        if dest.node().is_none() {
+            return Err(RPCError::internal(
+                "Never send digest value requests over private routes",
+            ));
+        }
+
+        let debug_string = format!(
+            "OUT ==> DigestValueQ({} ranges={}) => {}",
+            key,
+            ranges.len(),
+            dest
+        );
+
+        // Send the digestvalue question
+        let range_count = ranges.len();
+        let digest_value_q = RPCOperationDigestValueQ::new(key, ranges)?;
+        let question = RPCQuestion::new(
+            network_result_try!(self.get_destination_respond_to(&dest)?),
+            RPCQuestionDetail::DigestValueQ(Box::new(digest_value_q)),
+        );
+
+        let question_context =
+            QuestionContext::DigestValue(ValidateDigestValueContext { range_count });
+
+        log_dht!(debug "{}", debug_string);
+
+        let waitable_reply = network_result_try!(
+            self.question(dest.clone(), question, Some(question_context))
+                .await?
+        );
+
+        // Keep the reply private route that was used to return with the answer
+        let reply_private_route = waitable_reply.reply_private_route;
+
+        // Wait for reply
+        let (msg, latency) = match self.wait_for_reply(waitable_reply, debug_string).await? {
+            TimeoutOr::Timeout => return Ok(NetworkResult::Timeout),
+            TimeoutOr::Value(v) => v,
+        };
+
+        // Get the right answer type
+        let (_, _, _, kind) = msg.operation.destructure();
+        let digest_value_a = match kind {
+            RPCOperationKind::Answer(a) => match a.destructure() {
+                RPCAnswerDetail::DigestValueA(a) => a,
+                RPCAnswerDetail::BusyA(_) => {
+                    return Ok(NetworkResult::service_unavailable("server busy"))
+                }
+                _ => return Ok(NetworkResult::invalid_message("not a digestvalue answer")),
+            },
+            _ => return Ok(NetworkResult::invalid_message("not an answer")),
+        };
+
+        let digests = digest_value_a.destructure();
+        log_dht!(debug "OUT <== DigestValueA({} digests={}) <= {}", key, digests.len(), dest);
+
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.latency", latency.as_u64());
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.digests.len", digests.len());
+
+        Ok(NetworkResult::value(Answer::new(
+            latency,
+            reply_private_route,
+            DigestValueAnswer { digests },
+        )))
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    #[cfg_attr(feature="verbose-tracing", instrument(level = "trace", skip(self, msg), fields(msg.operation.op_id), ret, err))]
+    pub(crate) async fn process_digest_value_q(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
+        // Ensure this never came over a private route, safety route is okay though
+        match &msg.header.detail {
+            RPCMessageHeaderDetail::Direct(_) | RPCMessageHeaderDetail::SafetyRouted(_) => {}
+            RPCMessageHeaderDetail::PrivateRouted(_) => {
+                return Ok(NetworkResult::invalid_message(
+                    "not processing digest value request over private route",
+                ))
+            }
+        }
+        // Ignore if disabled
+        let routing_table = self.routing_table();
+        let opi = routing_table.get_own_peer_info(msg.header.routing_domain());
+        if !opi
+            .signed_node_info()
+            .node_info()
+            .has_capabilities(&[CAP_DHT, CAP_DHT_DIGEST])
+        {
+            return Ok(NetworkResult::service_unavailable(
+                "dht digests are not available",
+            ));
+        }
+
+        // Get the question
+        let kind = msg.operation.kind().clone();
+        let digest_value_q = match kind {
+            RPCOperationKind::Question(q) => match q.destructure() {
+                (_, RPCQuestionDetail::DigestValueQ(q)) => q,
+                _ => panic!("not a digestvalue question"),
+            },
+            _ => panic!("not a question"),
+        };
+
+        // Destructure
+        let (key, ranges) = digest_value_q.destructure();
+
+        log_dht!(debug "IN <=== DigestValueQ({} ranges={}) <== {}", key, ranges.len(), msg.header.direct_sender_node_id());
+
+        // See if we have this record ourselves
+        let storage_manager = self.storage_manager();
+        let digests = network_result_try!(storage_manager
+            .inbound_digest_value(key, ranges)
+            .await
+            .map_err(RPCError::internal)?);
+
+        log_dht!(debug "IN ===> DigestValueA({} digests={}) ==> {}", key, digests.len(), msg.header.direct_sender_node_id());
+
+        // Make DigestValue answer
+        let digest_value_a = RPCOperationDigestValueA::new(digests)?;
+
+        // Send DigestValue answer
+        self.answer(
+            msg,
+            RPCAnswer::new(RPCAnswerDetail::DigestValueA(Box::new(digest_value_a))),
+        )
+        .await
+    }
+}
@@ -17,6 +17,8 @@ pub enum RPCError {
     TryAgain(String),
     #[error("[RPCError: Ignore({0})]")]
     Ignore(String),
+    #[error("[RPCError: RouteCompilation({0})]")]
+    RouteCompilation(RouteCompilationError),
 }
 
 impl RPCError {
@@ -74,6 +76,21 @@ impl From<RPCError> for VeilidAPIError {
             RPCError::Network(message) => VeilidAPIError::Generic { message },
             RPCError::TryAgain(message) => VeilidAPIError::TryAgain { message },
             RPCError::Ignore(message) => VeilidAPIError::Generic { message },
+            RPCError::RouteCompilation(error) => VeilidAPIError::RouteCompilation { error },
+        }
+    }
+}
+
+impl From<RoutingTableError> for RPCError {
+    fn from(e: RoutingTableError) -> Self {
+        match e {
+            RoutingTableError::OwnNodeId => RPCError::protocol(e),
+            RoutingTableError::InvalidCryptoKind(_) => RPCError::invalid_format(e),
+            RoutingTableError::InvalidSignedNodeInfo(_) => RPCError::protocol(e),
+            RoutingTableError::TableFull(message) => RPCError::TryAgain(message),
+            RoutingTableError::Banned(_) => RPCError::ignore(e),
+            RoutingTableError::RateLimited(_) => RPCError::ignore(e),
+            RoutingTableError::Internal(message) => RPCError::Internal(message),
         }
     }
 }
@@ -88,6 +105,9 @@ impl<T> ToRPCNetworkResult<T> for VeilidAPIResult<T> {
     fn to_rpc_network_result(self) -> RPCNetworkResult<T> {
         match self {
             Err(VeilidAPIError::TryAgain { message }) => Err(RPCError::TryAgain(message)),
+            Err(VeilidAPIError::RouteCompilation { error }) => {
+                Err(RPCError::RouteCompilation(error))
+            }
             Err(VeilidAPIError::Timeout) => Ok(NetworkResult::timeout()),
             Err(VeilidAPIError::Unimplemented { message }) => Err(RPCError::Unimplemented(message)),
             Err(e) => Err(RPCError::internal(e)),
@@ -57,6 +57,9 @@ impl RPCProcessor {
         let find_node_a = match kind {
             RPCOperationKind::Answer(a) => match a.destructure() {
                 RPCAnswerDetail::FindNodeA(a) => a,
+                RPCAnswerDetail::BusyA(_) => {
+                    return Ok(NetworkResult::service_unavailable("server busy"))
+                }
                 _ => return Ok(NetworkResult::invalid_message("not a find_node answer")),
             },
             _ => return Ok(NetworkResult::invalid_message("not an answer")),
@@ -109,6 +112,18 @@ impl RPCProcessor {
         };
         let (node_id, capabilities) = find_node_q.destructure();
 
+        // Replay a recent answer if this sender just asked the same thing
+        let cache_key = Self::answer_cache_key(
+            &msg,
+            AnswerCacheQuestion::FindNode {
+                node_id,
+                capabilities: capabilities.clone(),
+            },
+        );
+        if let Some(answer) = cache_key.as_ref().and_then(|k| self.get_cached_answer(k)) {
+            return self.answer(msg, answer).await;
+        }
+
         // Get a chunk of the routing table near the requested node id
         let routing_table = self.routing_table();
         let closest_nodes =
@@ -116,12 +131,12 @@ impl RPCProcessor {
 
         // Make FindNode answer
         let find_node_a = RPCOperationFindNodeA::new(closest_nodes)?;
+        let answer = RPCAnswer::new(RPCAnswerDetail::FindNodeA(Box::new(find_node_a)));
+        if let Some(cache_key) = cache_key {
+            self.cache_answer(cache_key, answer.clone());
+        }
 
         // Send FindNode answer
-        self.answer(
-            msg,
-            RPCAnswer::new(RPCAnswerDetail::FindNodeA(Box::new(find_node_a))),
-        )
-        .await
+        self.answer(msg, answer).await
     }
 }
@@ -15,6 +15,7 @@ impl RPCProcessor {
     /// Because this leaks information about the identity of the node itself,
     /// replying to this request received over a private route will leak
     /// the identity of the node and defeat the private route.
+    /// The descriptor is returned if asked for, and always if there is no last descriptor to validate against.
     
     #[cfg_attr(
         feature = "verbose-tracing",        
@@ -32,6 +33,7 @@ impl RPCProcessor {
         key: TypedKey,
         subkey: ValueSubkey,
         last_descriptor: Option<SignedValueDescriptor>,
+        want_descriptor: bool,
     ) ->RPCNetworkResult<Answer<GetValueAnswer>> {
         // Ensure destination never has a private route
         // and get the target noderef so we can validate the response
@@ -62,7 +64,8 @@ impl RPCProcessor {
         );
 
         // Send the getvalue question
-        let get_value_q = RPCOperationGetValueQ::new(key, subkey, last_descriptor.is_none());
+        let want_descriptor = want_descriptor || last_descriptor.is_none();
+        let get_value_q = RPCOperationGetValueQ::new(key, subkey, want_descriptor);
         let question = RPCQuestion::new(
             network_result_try!(self.get_destination_respond_to(&dest)?),
             RPCQuestionDetail::GetValueQ(Box::new(get_value_q)),
@@ -95,6 +98,9 @@ impl RPCProcessor {
         let get_value_a = match kind {
             RPCOperationKind::Answer(a) => match a.destructure() {
                 RPCAnswerDetail::GetValueA(a) => a,
+                RPCAnswerDetail::BusyA(_) => {
+                    return Ok(NetworkResult::service_unavailable("server busy"))
+                }
                 _ => return Ok(NetworkResult::invalid_message("not a getvalue answer")),
             },
             _ => return Ok(NetworkResult::invalid_message("not an answer")),
@@ -249,6 +255,25 @@ impl RPCProcessor {
             (get_result.opt_value, get_result.opt_descriptor)
         };
 
+        // Keep a record of the request for abuse investigations
+        let audit_result = if closer_to_key_peers.len() >= set_value_count {
+            DHTAuditResult::NotClose
+        } else if get_result_value.is_some() {
+            DHTAuditResult::Returned
+        } else {
+            DHTAuditResult::NoValue
+        };
+        self.storage_manager()
+            .audit_inbound_operation(
+                DHTAuditOperation::GetValue,
+                msg.header.direct_sender_node_id(),
+                key,
+                subkey,
+                audit_result,
+                get_result_value.as_ref().map(|v| v.value_data().seq()),
+            )
+            .await;
+
         if debug_target_enabled!("dht") {
             let debug_string_value = get_result_value.as_ref().map(|v| {
                 format!(" len={} seq={} writer={}",
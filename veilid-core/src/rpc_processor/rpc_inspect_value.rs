@@ -97,6 +97,9 @@ impl RPCProcessor {
         let inspect_value_a = match kind {
             RPCOperationKind::Answer(a) => match a.destructure() {
                 RPCAnswerDetail::InspectValueA(a) => a,
+                RPCAnswerDetail::BusyA(_) => {
+                    return Ok(NetworkResult::service_unavailable("server busy"))
+                }
                 _ => return Ok(NetworkResult::invalid_message("not an inspectvalue answer")),
             },
             _ => return Ok(NetworkResult::invalid_message("not an answer")),
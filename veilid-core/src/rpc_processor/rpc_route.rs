@@ -1,6 +1,86 @@
 use super::*;
 
+/// Build the proof-of-work challenge for a route operation
+///
+/// The routed operation nonce is unique to each operation and is carried unchanged to every hop,
+/// so together with the safety route key it binds the solution to this one operation.
+fn route_pow_challenge(safety_route_public_key: &TypedKey, nonce: &Nonce) -> Vec<u8> {
+    let mut challenge = Vec::with_capacity(4 + PUBLIC_KEY_LENGTH + NONCE_LENGTH);
+    challenge.extend_from_slice(&safety_route_public_key.kind.0);
+    challenge.extend_from_slice(&safety_route_public_key.value.bytes);
+    challenge.extend_from_slice(&nonce.bytes);
+    challenge
+}
+
 impl RPCProcessor {
+    /// Proof-of-work difficulty to demand from inbound route operations right now
+    pub(crate) fn required_route_pow_difficulty(&self) -> u8 {
+        let difficulty = {
+            let c = self.config.get();
+            c.network
+                .rpc
+                .route_pow_difficulty
+                .min(MAX_POW_DIFFICULTY as u32) as u8
+        };
+        if difficulty == 0 || !self.is_under_load() {
+            return 0;
+        }
+        difficulty
+    }
+
+    /// Solve the proof-of-work the next hop of a route demands, as of the last status it reported to us
+    pub(super) async fn solve_route_pow(
+        &self,
+        next_hop_nr: &NodeRef,
+        safety_route_public_key: &TypedKey,
+        routed_operation: &RoutedOperation,
+    ) -> u64 {
+        let Some(vcrypto) = self.crypto.get(safety_route_public_key.kind) else {
+            return 0;
+        };
+        let difficulty = next_hop_nr
+            .node_status(RoutingDomain::PublicInternet)
+            .map(|ns| ns.route_pow_difficulty)
+            .unwrap_or_default();
+        if difficulty == 0 {
+            return 0;
+        }
+        if difficulty > MAX_POW_DIFFICULTY {
+            log_rpc!(debug "route proof-of-work difficulty too high: {} for {}", difficulty, next_hop_nr);
+            return 0;
+        }
+        log_rpc!(debug "Solving route proof-of-work at difficulty {} for {}", difficulty, next_hop_nr);
+        solve_pow_async(
+            vcrypto,
+            route_pow_challenge(safety_route_public_key, routed_operation.nonce()),
+            difficulty,
+        )
+        .await
+    }
+
+    /// Send a route statement on to the next hop, accounting for it as a dead letter if it can not be delivered
+    async fn forward_route_statement(
+        &self,
+        next_hop_nr: NodeRef,
+        next_hop_route: RPCOperationRoute,
+    ) -> RPCNetworkResult<()> {
+        let destination = next_hop_nr.best_node_id();
+        let next_hop_route_stmt =
+            RPCStatement::new(RPCStatementDetail::Route(Box::new(next_hop_route)));
+
+        let res = self
+            .statement(Destination::direct(next_hop_nr), next_hop_route_stmt)
+            .await;
+        match &res {
+            Ok(NetworkResult::Value(())) => {}
+            Ok(nres) => self
+                .network_manager()
+                .record_relay_failure(destination, nres),
+            Err(e) => self.network_manager().record_relay_failure(destination, e),
+        }
+        res
+    }
+
     #[cfg_attr(
         feature = "verbose-tracing",
         instrument(level = "trace", skip_all, err)
@@ -33,6 +113,10 @@ impl RPCProcessor {
             .node
             .node_ref(self.routing_table.clone(), safety_route.public_key.kind)
         else {
+            if let Some(node_id) = route_hop.node.node_id(safety_route.public_key.kind) {
+                self.network_manager()
+                    .record_relay_failure(node_id, "could not get route node hop ref");
+            }
             return Err(RPCError::network(format!(
                 "could not get route node hop ref: {}",
                 route_hop.node.describe(safety_route.public_key.kind)
@@ -43,6 +127,9 @@ impl RPCProcessor {
         next_hop_nr.set_sequencing(routed_operation.sequencing());
 
         // Pass along the route
+        let pow_nonce = self
+            .solve_route_pow(&next_hop_nr, &safety_route.public_key, &routed_operation)
+            .await;
         let next_hop_route = RPCOperationRoute::new(
             SafetyRoute {
                 public_key: safety_route.public_key,
@@ -50,12 +137,11 @@ impl RPCProcessor {
                 hops: SafetyRouteHops::Data(route_hop.next_hop.unwrap()),
             },
             routed_operation,
+            pow_nonce,
         );
-        let next_hop_route_stmt =
-            RPCStatement::new(RPCStatementDetail::Route(Box::new(next_hop_route)));
 
         // Send the next route statement
-        self.statement(Destination::direct(next_hop_nr), next_hop_route_stmt)
+        self.forward_route_statement(next_hop_nr, next_hop_route)
             .await
     }
 
@@ -81,6 +167,10 @@ impl RPCProcessor {
         let Some(mut next_hop_nr) =
             next_route_node.node_ref(self.routing_table.clone(), safety_route_public_key.kind)
         else {
+            if let Some(node_id) = next_route_node.node_id(safety_route_public_key.kind) {
+                self.network_manager()
+                    .record_relay_failure(node_id, "could not get route node hop ref");
+            }
             return Err(RPCError::network(format!(
                 "could not get route node hop ref: {}",
                 next_route_node.describe(safety_route_public_key.kind)
@@ -91,6 +181,9 @@ impl RPCProcessor {
         next_hop_nr.set_sequencing(routed_operation.sequencing());
 
         // Pass along the route
+        let pow_nonce = self
+            .solve_route_pow(&next_hop_nr, &safety_route_public_key, &routed_operation)
+            .await;
         let next_hop_route = RPCOperationRoute::new(
             SafetyRoute {
                 public_key: safety_route_public_key,
@@ -98,12 +191,11 @@ impl RPCProcessor {
                 hops: SafetyRouteHops::Private(next_private_route),
             },
             routed_operation,
+            pow_nonce,
         );
-        let next_hop_route_stmt =
-            RPCStatement::new(RPCStatementDetail::Route(Box::new(next_hop_route)));
 
         // Send the next route statement
-        self.statement(Destination::direct(next_hop_nr), next_hop_route_stmt)
+        self.forward_route_statement(next_hop_nr, next_hop_route)
             .await
     }
 
@@ -125,8 +217,8 @@ impl RPCProcessor {
     ) -> RPCNetworkResult<()> {
         // Now that things are valid, decrypt the routed operation with DEC(nonce, DH(the SR's public key, the PR's (or node's) secret)
         // xxx: punish nodes that send messages that fail to decrypt eventually? How to do this for safety routes?
-        let node_id_secret = self.routing_table.node_id_secret_key(remote_sr_pubkey.kind);
-        let Ok(dh_secret) = vcrypto.cached_dh(&remote_sr_pubkey.value, &node_id_secret) else {
+        let node_id_signer = self.routing_table.node_id_signer(remote_sr_pubkey.kind);
+        let Ok(dh_secret) = node_id_signer.cached_dh(&remote_sr_pubkey.value) else {
             return Ok(NetworkResult::invalid_message(
                 "dh failed for remote safety route for safety routed operation",
             ));
@@ -195,6 +287,8 @@ impl RPCProcessor {
                         hop_count: rssd.hop_count(),
                         stability: rssd.get_stability(),
                         sequencing: routed_operation.sequencing(),
+                        preferred_first_hop: None,
+                        profile: None,
                     },
                 )
             },
@@ -352,9 +446,9 @@ impl RPCProcessor {
         };
 
         // Decrypt the blob with DEC(nonce, DH(the PR's public key, this hop's secret)
-        let node_id_secret = self.routing_table.node_id_secret_key(crypto_kind);
-        let dh_secret = vcrypto
-            .cached_dh(&pr_pubkey.value, &node_id_secret)
+        let node_id_signer = self.routing_table.node_id_signer(crypto_kind);
+        let dh_secret = node_id_signer
+            .cached_dh(&pr_pubkey.value)
             .map_err(RPCError::protocol)?;
         let dec_blob_data = match vcrypto.decrypt_aead(
             &route_hop_data.blob,
@@ -388,10 +482,9 @@ impl RPCProcessor {
         // Sign the operation if this is not our last hop
         // as the last hop is already signed by the envelope
         if route_hop.next_hop.is_some() {
-            let node_id = self.routing_table.node_id(crypto_kind);
-            let node_id_secret = self.routing_table.node_id_secret_key(crypto_kind);
-            let sig = vcrypto
-                .sign(&node_id.value, &node_id_secret, route_operation.data())
+            let node_id_signer = self.routing_table.node_id_signer(crypto_kind);
+            let sig = node_id_signer
+                .sign(route_operation.data())
                 .map_err(RPCError::internal)?;
             route_operation.add_signature(sig);
         }
@@ -445,16 +538,29 @@ impl RPCProcessor {
             ));
         };
 
+        // Demand a client puzzle solution for route operations while we are under load
+        let pow_difficulty = self.required_route_pow_difficulty();
+        if pow_difficulty != 0
+            && !verify_pow(
+                &vcrypto,
+                &route_pow_challenge(&route.safety_route().public_key, route.operation().nonce()),
+                route.pow_nonce(),
+                pow_difficulty,
+            )
+        {
+            return Ok(NetworkResult::service_unavailable(
+                "route proof-of-work required",
+            ));
+        }
+
         // See what kind of safety route we have going on here
         let (safety_route, mut routed_operation) = route.destructure();
         match safety_route.hops {
             // There is a safety route hop
             SafetyRouteHops::Data(ref route_hop_data) => {
                 // Decrypt the blob with DEC(nonce, DH(the SR's public key, this hop's secret)
-                let node_id_secret = self.routing_table.node_id_secret_key(crypto_kind);
-                let Ok(dh_secret) =
-                    vcrypto.cached_dh(&safety_route.public_key.value, &node_id_secret)
-                else {
+                let node_id_signer = self.routing_table.node_id_signer(crypto_kind);
+                let Ok(dh_secret) = node_id_signer.cached_dh(&safety_route.public_key.value) else {
                     return Ok(NetworkResult::invalid_message(
                         "dh failed for safety route hop",
                     ));
@@ -7,7 +7,32 @@ pub struct SetValueAnswer {
     pub peers: Vec<PeerInfo>,
 }
 
+/// Build the proof-of-work challenge for a SetValue request
+///
+/// The value signature serves as the operation nonce, since it is unique to each write and
+/// already covers the value data and sequence number.
+fn set_value_pow_challenge(key: &TypedKey, subkey: ValueSubkey, value: &SignedValueData) -> Vec<u8> {
+    let mut challenge = Vec::with_capacity(4 + PUBLIC_KEY_LENGTH + 4 + SIGNATURE_LENGTH);
+    challenge.extend_from_slice(&key.kind.0);
+    challenge.extend_from_slice(&key.value.bytes);
+    challenge.extend_from_slice(&subkey.to_le_bytes());
+    challenge.extend_from_slice(&value.signature().bytes);
+    challenge
+}
+
 impl RPCProcessor {
+    /// Proof-of-work difficulty to demand from inbound SetValue requests right now
+    pub(super) fn required_set_value_pow_difficulty(&self) -> u8 {
+        let difficulty = {
+            let c = self.config.get();
+            c.network.dht.set_value_pow_difficulty.min(MAX_POW_DIFFICULTY as u32) as u8
+        };
+        if difficulty == 0 || !self.is_under_load() {
+            return 0;
+        }
+        difficulty
+    }
+
     /// Sends a set value request and wait for response
     /// Can be sent via all methods including relays
     /// Safety routes may be used, but never private routes.
@@ -67,57 +92,85 @@ impl RPCProcessor {
             dest
         );
 
-        // Send the setvalue question
-        let set_value_q = RPCOperationSetValueQ::new(
-            key,
-            subkey,
-            value,
-            if send_descriptor {
-                Some(descriptor.clone())
-            } else {
-                None
-            },
-        );
-        let question = RPCQuestion::new(
-            network_result_try!(self.get_destination_respond_to(&dest)?),
-            RPCQuestionDetail::SetValueQ(Box::new(set_value_q)),
-        );
-        let question_context = QuestionContext::SetValue(ValidateSetValueContext {
-            descriptor,
-            subkey,
-            vcrypto: vcrypto.clone(),
-        });
+        // The proof-of-work challenge is bound to this specific write
+        let pow_challenge = set_value_pow_challenge(&key, subkey, &value);
+        let mut pow_nonce = 0u64;
+        let mut pow_solved = false;
 
-        if debug_target_enabled!("dht") {
-            log_dht!(debug "{}", debug_string);
-        }
+        let (set, value, peers, latency, reply_private_route) = loop {
+            // Send the setvalue question
+            let set_value_q = RPCOperationSetValueQ::new(
+                key,
+                subkey,
+                value.clone(),
+                if send_descriptor {
+                    Some(descriptor.clone())
+                } else {
+                    None
+                },
+                pow_nonce,
+            );
+            let question = RPCQuestion::new(
+                network_result_try!(self.get_destination_respond_to(&dest)?),
+                RPCQuestionDetail::SetValueQ(Box::new(set_value_q)),
+            );
+            let question_context = QuestionContext::SetValue(ValidateSetValueContext {
+                descriptor: descriptor.clone(),
+                subkey,
+                vcrypto: vcrypto.clone(),
+            });
 
-        let waitable_reply = network_result_try!(
-            self.question(dest.clone(), question, Some(question_context))
-                .await?
-        );
+            if debug_target_enabled!("dht") {
+                log_dht!(debug "{}", debug_string);
+            }
 
-        // Keep the reply private route that was used to return with the answer
-        let reply_private_route = waitable_reply.reply_private_route;
+            let waitable_reply = network_result_try!(
+                self.question(dest.clone(), question, Some(question_context))
+                    .await?
+            );
 
-        // Wait for reply
-        let (msg, latency) = match self.wait_for_reply(waitable_reply, debug_string).await? {
-            TimeoutOr::Timeout => return Ok(NetworkResult::Timeout),
-            TimeoutOr::Value(v) => v,
-        };
+            // Keep the reply private route that was used to return with the answer
+            let reply_private_route = waitable_reply.reply_private_route;
+
+            // Wait for reply
+            let (msg, latency) = match self.wait_for_reply(waitable_reply, debug_string.clone()).await? {
+                TimeoutOr::Timeout => return Ok(NetworkResult::Timeout),
+                TimeoutOr::Value(v) => v,
+            };
+
+            // Get the right answer type
+            let (_, _, _, kind) = msg.operation.destructure();
+            let set_value_a = match kind {
+                RPCOperationKind::Answer(a) => match a.destructure() {
+                    RPCAnswerDetail::SetValueA(a) => a,
+                    RPCAnswerDetail::BusyA(_) => {
+                        return Ok(NetworkResult::service_unavailable("server busy"))
+                    }
+                    _ => return Ok(NetworkResult::invalid_message("not a setvalue answer")),
+                },
+                _ => return Ok(NetworkResult::invalid_message("not an answer")),
+            };
+
+            let (set, value, peers, pow_difficulty) = set_value_a.destructure();
+
+            // Solve the client puzzle and try again once if the node demands proof of work
+            if !set && pow_difficulty != 0 && !pow_solved {
+                if pow_difficulty > MAX_POW_DIFFICULTY {
+                    return Ok(NetworkResult::invalid_message(format!(
+                        "setvalue proof-of-work difficulty too high: {}",
+                        pow_difficulty
+                    )));
+                }
+                log_dht!(debug "Solving SetValue proof-of-work at difficulty {} for {}", pow_difficulty, dest);
+                pow_nonce =
+                    solve_pow_async(vcrypto.clone(), pow_challenge.clone(), pow_difficulty).await;
+                pow_solved = true;
+                continue;
+            }
 
-        // Get the right answer type
-        let (_, _, _, kind) = msg.operation.destructure();
-        let set_value_a = match kind {
-            RPCOperationKind::Answer(a) => match a.destructure() {
-                RPCAnswerDetail::SetValueA(a) => a,
-                _ => return Ok(NetworkResult::invalid_message("not a setvalue answer")),
-            },
-            _ => return Ok(NetworkResult::invalid_message("not an answer")),
+            break (set, value, peers, latency, reply_private_route);
         };
 
-        let (set, value, peers) = set_value_a.destructure();
-
         if debug_target_enabled!("dht") {
             let debug_string_value = value.as_ref().map(|v| {
                 format!(" len={} writer={}",
@@ -224,7 +277,8 @@ impl RPCProcessor {
         };
 
         // Destructure
-        let (key, subkey, value, descriptor) = set_value_q.destructure();
+        let (key, subkey, value, descriptor, pow_nonce) = set_value_q.destructure();
+        let value_seq = value.value_data().seq();
 
         // Get target for ValueChanged notifications
         let dest = network_result_try!(self.get_respond_to_destination(&msg));
@@ -256,9 +310,26 @@ impl RPCProcessor {
             let c = self.config.get();
             c.network.dht.set_value_count as usize
         };
-        let (set, new_value) = if closer_to_key_peers.len() >= set_value_count {
+        // Demand a client puzzle solution for remote writes while we are under load
+        let pow_difficulty = self.required_set_value_pow_difficulty();
+        let pow_valid = pow_difficulty == 0 || {
+            let Some(vcrypto) = self.crypto.get(key.kind) else {
+                return Ok(NetworkResult::invalid_message("unsupported cryptosystem"));
+            };
+            verify_pow(
+                &vcrypto,
+                &set_value_pow_challenge(&key, subkey, &value),
+                pow_nonce,
+                pow_difficulty,
+            )
+        };
+
+        let (set, new_value, answer_pow_difficulty) = if closer_to_key_peers.len() >= set_value_count {
             // Not close enough
-            (false, None)
+            (false, None, 0)
+        } else if !pow_valid {
+            // Close enough, but no valid proof of work
+            (false, None, pow_difficulty)
         } else {
             // Close enough, lets set it
 
@@ -269,8 +340,34 @@ impl RPCProcessor {
                 .await
                 .map_err(RPCError::internal)?);
 
-            (true, new_value)
+            (true, new_value, 0)
+        };
+
+        // Keep a record of the request for abuse investigations
+        let audit_result = if !set {
+            if answer_pow_difficulty != 0 {
+                DHTAuditResult::PowRequired
+            } else {
+                DHTAuditResult::NotClose
+            }
+        } else if new_value.is_some() {
+            DHTAuditResult::Stale
+        } else {
+            DHTAuditResult::Stored
         };
+        self.storage_manager()
+            .audit_inbound_operation(
+                DHTAuditOperation::SetValue,
+                msg.header.direct_sender_node_id(),
+                key,
+                subkey,
+                audit_result,
+                new_value
+                    .as_ref()
+                    .map(|v| v.value_data().seq())
+                    .or(set.then_some(value_seq)),
+            )
+            .await;
 
         if debug_target_enabled!("dht") {
             let debug_string_value = new_value.as_ref().map(|v| {
@@ -299,7 +396,12 @@ impl RPCProcessor {
         }
 
         // Make SetValue answer
-        let set_value_a = RPCOperationSetValueA::new(set, new_value.map(|x| (*x).clone()), closer_to_key_peers)?;
+        let set_value_a = RPCOperationSetValueA::new(
+            set,
+            new_value.map(|x| (*x).clone()),
+            closer_to_key_peers,
+            answer_pow_difficulty,
+        )?;
 
         // Send SetValue answer
         self.answer(msg, RPCAnswer::new(RPCAnswerDetail::SetValueA(Box::new(set_value_a))))
@@ -0,0 +1,374 @@
+use super::*;
+
+#[derive(Clone, Debug)]
+pub struct SetValueBatchAnswer {
+    pub set: bool,
+    pub values: Vec<(ValueSubkey, SignedValueData)>,
+    pub peers: Vec<PeerInfo>,
+}
+
+/// Build the proof-of-work challenge for a SetValueBatch request
+///
+/// The batch signature serves as the operation nonce, since it is unique to each batch and
+/// already covers every value in it.
+fn set_value_batch_pow_challenge(key: &TypedKey, batch: &SignedValueBatch) -> Vec<u8> {
+    let mut challenge = Vec::with_capacity(4 + PUBLIC_KEY_LENGTH + SIGNATURE_LENGTH);
+    challenge.extend_from_slice(&key.kind.0);
+    challenge.extend_from_slice(&key.value.bytes);
+    challenge.extend_from_slice(&batch.signature().bytes);
+    challenge
+}
+
+impl RPCProcessor {
+    /// Sends a batch of values for several subkeys of one record and waits for the response
+    /// Can be sent via all methods including relays
+    /// Safety routes may be used, but never private routes.
+    /// Because this leaks information about the identity of the node itself,
+    /// replying to this request received over a private route will leak
+    /// the identity of the node and defeat the private route.
+    #[cfg_attr(
+        feature = "verbose-tracing",
+        instrument(level = "trace", skip(self, batch, descriptor),
+            fields(batch.len = batch.values().len(),
+                batch.writer = batch.writer().to_string(),
+                ret.set,
+                ret.values.len,
+                ret.peers.len,
+                ret.latency
+            ), err)
+    )]
+    pub async fn rpc_call_set_value_batch(
+        self,
+        dest: Destination,
+        key: TypedKey,
+        batch: SignedValueBatch,
+        descriptor: SignedValueDescriptor,
+        send_descriptor: bool,
+    ) -> RPCNetworkResult<Answer<SetValueBatchAnswer>> {
+        // Ensure destination never has a private route
+        // and get the target noderef so we can validate the response
+        let Some(target) = dest.node() else {
+            return Err(RPCError::internal(
+                "Never send set value batch requests over private routes",
+            ));
+        };
+
+        // Get the target node id
+        let Some(vcrypto) = self.crypto.get(key.kind) else {
+            return Err(RPCError::internal("unsupported cryptosystem"));
+        };
+        let Some(target_node_id) = target.node_ids().get(key.kind) else {
+            return Err(RPCError::internal("No node id for crypto kind"));
+        };
+
+        let subkeys = batch.subkeys();
+        let debug_string = format!(
+            "OUT ==> SetValueBatchQ({} #{} writer={}{}) => {}",
+            key,
+            subkeys,
+            batch.writer(),
+            if send_descriptor { " +senddesc" } else { "" },
+            dest
+        );
+
+        // The proof-of-work challenge is bound to this specific batch
+        let pow_challenge = set_value_batch_pow_challenge(&key, &batch);
+        let mut pow_nonce = 0u64;
+        let mut pow_solved = false;
+
+        let (set, values, peers, latency, reply_private_route) = loop {
+            // Send the setvaluebatch question
+            let set_value_batch_q = RPCOperationSetValueBatchQ::new(
+                key,
+                batch.clone(),
+                if send_descriptor {
+                    Some(descriptor.clone())
+                } else {
+                    None
+                },
+                pow_nonce,
+            );
+            let question = RPCQuestion::new(
+                network_result_try!(self.get_destination_respond_to(&dest)?),
+                RPCQuestionDetail::SetValueBatchQ(Box::new(set_value_batch_q)),
+            );
+            let question_context = QuestionContext::SetValueBatch(ValidateSetValueBatchContext {
+                descriptor: descriptor.clone(),
+                subkeys: subkeys.clone(),
+                vcrypto: vcrypto.clone(),
+            });
+
+            if debug_target_enabled!("dht") {
+                log_dht!(debug "{}", debug_string);
+            }
+
+            let waitable_reply = network_result_try!(
+                self.question(dest.clone(), question, Some(question_context))
+                    .await?
+            );
+
+            // Keep the reply private route that was used to return with the answer
+            let reply_private_route = waitable_reply.reply_private_route;
+
+            // Wait for reply
+            let (msg, latency) = match self
+                .wait_for_reply(waitable_reply, debug_string.clone())
+                .await?
+            {
+                TimeoutOr::Timeout => return Ok(NetworkResult::Timeout),
+                TimeoutOr::Value(v) => v,
+            };
+
+            // Get the right answer type
+            let (_, _, _, kind) = msg.operation.destructure();
+            let set_value_batch_a = match kind {
+                RPCOperationKind::Answer(a) => match a.destructure() {
+                    RPCAnswerDetail::SetValueBatchA(a) => a,
+                    RPCAnswerDetail::BusyA(_) => {
+                        return Ok(NetworkResult::service_unavailable("server busy"))
+                    }
+                    _ => return Ok(NetworkResult::invalid_message("not a setvaluebatch answer")),
+                },
+                _ => return Ok(NetworkResult::invalid_message("not an answer")),
+            };
+
+            let (set, values, peers, pow_difficulty) = set_value_batch_a.destructure();
+
+            // Solve the client puzzle and try again once if the node demands proof of work
+            if !set && pow_difficulty != 0 && !pow_solved {
+                if pow_difficulty > MAX_POW_DIFFICULTY {
+                    return Ok(NetworkResult::invalid_message(format!(
+                        "setvaluebatch proof-of-work difficulty too high: {}",
+                        pow_difficulty
+                    )));
+                }
+                log_dht!(debug "Solving SetValueBatch proof-of-work at difficulty {} for {}", pow_difficulty, dest);
+                pow_nonce =
+                    solve_pow_async(vcrypto.clone(), pow_challenge.clone(), pow_difficulty).await;
+                pow_solved = true;
+                continue;
+            }
+
+            break (set, values, peers, latency, reply_private_route);
+        };
+
+        if debug_target_enabled!("dht") {
+            let debug_string_answer = format!(
+                "OUT <== SetValueBatchA({} #{}{} newer={} peers={}) <= {}",
+                key,
+                subkeys,
+                if set { " +set" } else { "" },
+                values.len(),
+                peers.len(),
+                dest,
+            );
+
+            log_dht!(debug "{}", debug_string_answer);
+
+            let peer_ids: Vec<String> = peers
+                .iter()
+                .filter_map(|p| p.node_ids().get(key.kind).map(|k| k.to_string()))
+                .collect();
+            log_dht!(debug "Peers: {:#?}", peer_ids);
+        }
+
+        // Validate peers returned are, in fact, closer to the key than the node we sent this to
+        let valid = match RoutingTable::verify_peers_closer(vcrypto, target_node_id, key, &peers) {
+            Ok(v) => v,
+            Err(e) => {
+                return Ok(NetworkResult::invalid_message(format!(
+                    "missing cryptosystem in peers node ids: {}",
+                    e
+                )));
+            }
+        };
+        if !valid {
+            return Ok(NetworkResult::invalid_message("non-closer peers returned"));
+        }
+
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.latency", latency.as_u64());
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.set", set);
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.values.len", values.len());
+        #[cfg(feature = "verbose-tracing")]
+        tracing::Span::current().record("ret.peers.len", peers.len());
+
+        Ok(NetworkResult::value(Answer::new(
+            latency,
+            reply_private_route,
+            SetValueBatchAnswer { set, values, peers },
+        )))
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    #[cfg_attr(feature="verbose-tracing", instrument(level = "trace", skip(self, msg), fields(msg.operation.op_id), ret, err))]
+    pub(crate) async fn process_set_value_batch_q(&self, msg: RPCMessage) -> RPCNetworkResult<()> {
+        // Ignore if disabled
+        let routing_table = self.routing_table();
+        let rss = routing_table.route_spec_store();
+
+        let opi = routing_table.get_own_peer_info(msg.header.routing_domain());
+        if !opi
+            .signed_node_info()
+            .node_info()
+            .has_capabilities(&[CAP_DHT, CAP_DHT_BATCH])
+        {
+            return Ok(NetworkResult::service_unavailable(
+                "dht batches are not available",
+            ));
+        }
+
+        // Ensure this never came over a private route, safety route is okay though
+        match &msg.header.detail {
+            RPCMessageHeaderDetail::Direct(_) | RPCMessageHeaderDetail::SafetyRouted(_) => {}
+            RPCMessageHeaderDetail::PrivateRouted(_) => {
+                return Ok(NetworkResult::invalid_message(
+                    "not processing set value batch request over private route",
+                ))
+            }
+        }
+
+        // Get the question
+        let kind = msg.operation.kind().clone();
+        let set_value_batch_q = match kind {
+            RPCOperationKind::Question(q) => match q.destructure() {
+                (_, RPCQuestionDetail::SetValueBatchQ(q)) => q,
+                _ => panic!("not a setvaluebatch question"),
+            },
+            _ => panic!("not a question"),
+        };
+
+        // Destructure
+        let (key, batch, descriptor, pow_nonce) = set_value_batch_q.destructure();
+
+        // Get target for ValueChanged notifications
+        let dest = network_result_try!(self.get_respond_to_destination(&msg));
+        let target = dest.get_target(rss)?;
+
+        // Get the nodes that we know about that are closer to the the key than our own node
+        let routing_table = self.routing_table();
+        let closer_to_key_peers = network_result_try!(
+            routing_table.find_preferred_peers_closer_to_key(key, vec![CAP_DHT, CAP_DHT_BATCH])
+        );
+
+        let subkeys = batch.subkeys();
+        let batch_seqs: Vec<(ValueSubkey, ValueSeqNum)> = batch
+            .values()
+            .iter()
+            .map(|v| (v.subkey, v.value_data.seq()))
+            .collect();
+        let debug_string = format!(
+            "IN <=== SetValueBatchQ({} #{} writer={}{}) <== {}",
+            key,
+            subkeys,
+            batch.writer(),
+            if descriptor.is_some() { " +desc" } else { "" },
+            msg.header.direct_sender_node_id()
+        );
+
+        log_rpc!(debug "{}", debug_string);
+
+        // If there are less than 'set_value_count' peers that are closer, then store here too
+        let set_value_count = {
+            let c = self.config.get();
+            c.network.dht.set_value_count as usize
+        };
+        // Demand a client puzzle solution for remote writes while we are under load
+        let pow_difficulty = self.required_set_value_pow_difficulty();
+        let pow_valid = pow_difficulty == 0 || {
+            let Some(vcrypto) = self.crypto.get(key.kind) else {
+                return Ok(NetworkResult::invalid_message("unsupported cryptosystem"));
+            };
+            verify_pow(
+                &vcrypto,
+                &set_value_batch_pow_challenge(&key, &batch),
+                pow_nonce,
+                pow_difficulty,
+            )
+        };
+
+        let (set, new_values, answer_pow_difficulty) =
+            if closer_to_key_peers.len() >= set_value_count {
+                // Not close enough
+                (false, vec![], 0)
+            } else if !pow_valid {
+                // Close enough, but no valid proof of work
+                (false, vec![], pow_difficulty)
+            } else {
+                // Close enough, lets set it
+
+                // Check the batch signature and save the subkeys, creating a new record if necessary
+                let storage_manager = self.storage_manager();
+                let new_values = network_result_try!(storage_manager
+                    .inbound_set_value_batch(key, batch, descriptor.map(Arc::new), target)
+                    .await
+                    .map_err(RPCError::internal)?);
+
+                (true, new_values, 0)
+            };
+
+        // Keep a record of each subkey in the request for abuse investigations
+        for (subkey, seq) in batch_seqs {
+            let newer = new_values
+                .iter()
+                .find(|(s, _)| *s == subkey)
+                .map(|(_, v)| v.value_data().seq());
+            let audit_result = if !set {
+                if answer_pow_difficulty != 0 {
+                    DHTAuditResult::PowRequired
+                } else {
+                    DHTAuditResult::NotClose
+                }
+            } else if newer.is_some() {
+                DHTAuditResult::Stale
+            } else {
+                DHTAuditResult::Stored
+            };
+            self.storage_manager()
+                .audit_inbound_operation(
+                    DHTAuditOperation::SetValue,
+                    msg.header.direct_sender_node_id(),
+                    key,
+                    subkey,
+                    audit_result,
+                    newer.or(set.then_some(seq)),
+                )
+                .await;
+        }
+
+        if debug_target_enabled!("dht") {
+            let debug_string_answer = format!(
+                "IN ===> SetValueBatchA({} #{}{} newer={} peers={}) ==> {}",
+                key,
+                subkeys,
+                if set { " +set" } else { "" },
+                new_values.len(),
+                closer_to_key_peers.len(),
+                msg.header.direct_sender_node_id()
+            );
+
+            log_dht!(debug "{}", debug_string_answer);
+        }
+
+        // Make SetValueBatch answer
+        let set_value_batch_a = RPCOperationSetValueBatchA::new(
+            set,
+            new_values
+                .into_iter()
+                .map(|(subkey, value)| (subkey, (*value).clone()))
+                .collect(),
+            closer_to_key_peers,
+            answer_pow_difficulty,
+        )?;
+
+        // Send SetValueBatch answer
+        self.answer(
+            msg,
+            RPCAnswer::new(RPCAnswerDetail::SetValueBatchA(Box::new(set_value_batch_a))),
+        )
+        .await
+    }
+}
@@ -5,6 +5,35 @@ pub struct SenderInfo {
     pub socket_address: SocketAddress,
 }
 
+/// Timestamps echoed back in a StatusA, used to estimate the clock offset between two nodes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct StatusTimestamps {
+    /// When the StatusQ was sent, by the questioner's clock
+    pub question_ts: Timestamp,
+    /// When the StatusQ was received, by the answerer's clock
+    pub received_ts: Timestamp,
+    /// When the StatusA was sent, by the answerer's clock
+    pub answer_ts: Timestamp,
+}
+
+impl StatusTimestamps {
+    /// Estimate how many microseconds the answerer's clock is ahead of ours,
+    /// given when we received the answer
+    ///
+    /// Assumes the network delay is the same in both directions, the same way NTP does.
+    /// Returns None if the timestamps can not have come from a single round trip.
+    pub fn clock_skew(&self, answer_rcvd_ts: Timestamp) -> Option<i64> {
+        if answer_rcvd_ts < self.question_ts || self.answer_ts < self.received_ts {
+            return None;
+        }
+        let t0 = self.question_ts.as_u64() as i64;
+        let t1 = self.received_ts.as_u64() as i64;
+        let t2 = self.answer_ts.as_u64() as i64;
+        let t3 = answer_rcvd_ts.as_u64() as i64;
+        Some(((t1 - t0) + (t2 - t3)) / 2)
+    }
+}
+
 impl RPCProcessor {
     // Send StatusQ RPC request, receive StatusA answer
     // Can be sent via relays or routes, but will have less information via routes
@@ -98,7 +127,7 @@ impl RPCProcessor {
             }
         };
 
-        let status_q = RPCOperationStatusQ::new(node_status);
+        let status_q = RPCOperationStatusQ::new(node_status, Some(get_aligned_timestamp()));
         let question = RPCQuestion::new(
             network_result_try!(self.get_destination_respond_to(&dest)?),
             RPCQuestionDetail::StatusQ(Box::new(status_q)),
@@ -127,18 +156,30 @@ impl RPCProcessor {
         let status_a = match kind {
             RPCOperationKind::Answer(a) => match a.destructure() {
                 RPCAnswerDetail::StatusA(a) => a,
+                RPCAnswerDetail::BusyA(_) => {
+                    return Ok(NetworkResult::service_unavailable("server busy"))
+                }
                 _ => return Ok(NetworkResult::invalid_message("not a status answer")),
             },
             _ => return Ok(NetworkResult::invalid_message("not an answer")),
         };
-        let (a_node_status, sender_info) = status_a.destructure();
+        let (a_node_status, sender_info, timestamps) = status_a.destructure();
 
         // Ensure the returned node status is the kind for the routing domain we asked for
         if let Some(target_nr) = opt_target_nr {
+            // The target answered us in this routing domain, so it is who it says it is
+            target_nr.set_verified(routing_domain);
+
             if let Some(a_node_status) = a_node_status {
                 // Update latest node status in routing table
                 target_nr.update_node_status(routing_domain, a_node_status.clone());
             }
+
+            // Estimate how far off the target's clock is from ours
+            if let Some(clock_skew) = timestamps.and_then(|ts| ts.clock_skew(msg.header.timestamp))
+            {
+                target_nr.record_clock_skew(clock_skew);
+            }
         }
 
         // Report sender_info IP addresses to network manager
@@ -213,29 +254,52 @@ impl RPCProcessor {
             },
             _ => panic!("not a question"),
         };
-        let q_node_status = status_q.destructure();
-
-        let (node_status, sender_info) = match &msg.header.detail {
-            RPCMessageHeaderDetail::Direct(detail) => {
-                let flow = detail.flow;
-                let routing_domain = detail.routing_domain;
+        let (q_node_status, q_timestamp) = status_q.destructure();
 
-                // Ensure the node status from the question is the kind for the routing domain we received the request in
-                if let Some(q_node_status) = q_node_status {
-                    // update node status for the requesting node to our routing table
-                    if let Some(sender_nr) = msg.opt_sender_nr.clone() {
-                        // Update latest node status in routing table for the statusq sender
-                        sender_nr.update_node_status(routing_domain, q_node_status.clone());
-                    }
+        // Update latest node status in routing table for the statusq sender
+        if let RPCMessageHeaderDetail::Direct(detail) = &msg.header.detail {
+            // Ensure the node status from the question is the kind for the routing domain we received the request in
+            if let Some(q_node_status) = q_node_status {
+                if let Some(sender_nr) = msg.opt_sender_nr.clone() {
+                    sender_nr.update_node_status(detail.routing_domain, q_node_status);
                 }
+            }
+        }
 
+        // Echo the question timestamp back so the sender can estimate clock skew
+        // Timestamps are made fresh for every answer, even replayed ones
+        let received_ts = msg.header.timestamp;
+        let make_status_a = move |node_status, sender_info| {
+            let timestamps = q_timestamp.map(|question_ts| StatusTimestamps {
+                question_ts,
+                received_ts,
+                answer_ts: get_aligned_timestamp(),
+            });
+            RPCOperationStatusA::new(node_status, sender_info, timestamps)
+        };
+
+        // Replay a recent answer if this sender just asked the same thing
+        let cache_key = Self::answer_cache_key(&msg, AnswerCacheQuestion::Status);
+        if let Some(answer) = cache_key.as_ref().and_then(|k| self.get_cached_answer(k)) {
+            if let RPCAnswerDetail::StatusA(status_a) = answer.destructure() {
+                let (node_status, sender_info, _) = status_a.destructure();
+                let status_a = make_status_a(node_status, sender_info);
+                let answer = RPCAnswer::new(RPCAnswerDetail::StatusA(Box::new(status_a)));
+                return self.answer(msg, answer).await;
+            }
+        }
+
+        let (node_status, sender_info) = match &msg.header.detail {
+            RPCMessageHeaderDetail::Direct(detail) => {
                 // Get the peer address in the returned sender info
                 let sender_info = SenderInfo {
-                    socket_address: *flow.remote_address(),
+                    socket_address: *detail.flow.remote_address(),
                 };
 
                 // Make status answer
-                let node_status = self.network_manager().generate_node_status(routing_domain);
+                let node_status = self
+                    .network_manager()
+                    .generate_node_status(detail.routing_domain);
                 (Some(node_status), Some(sender_info))
             }
             RPCMessageHeaderDetail::SafetyRouted(_) => {
@@ -249,13 +313,13 @@ impl RPCProcessor {
         };
 
         // Make status answer
-        let status_a = RPCOperationStatusA::new(node_status, sender_info);
+        let status_a = make_status_a(node_status, sender_info);
+        let answer = RPCAnswer::new(RPCAnswerDetail::StatusA(Box::new(status_a)));
+        if let Some(cache_key) = cache_key {
+            self.cache_answer(cache_key, answer.clone());
+        }
 
         // Send status answer
-        self.answer(
-            msg,
-            RPCAnswer::new(RPCAnswerDetail::StatusA(Box::new(status_a))),
-        )
-        .await
+        self.answer(msg, answer).await
     }
 }
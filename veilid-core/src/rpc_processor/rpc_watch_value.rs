@@ -100,6 +100,9 @@ impl RPCProcessor {
         let watch_value_a = match kind {
             RPCOperationKind::Answer(a) => match a.destructure() {
                 RPCAnswerDetail::WatchValueA(a) => a,
+                RPCAnswerDetail::BusyA(_) => {
+                    return Ok(NetworkResult::service_unavailable("server busy"))
+                }
                 _ => return Ok(NetworkResult::invalid_message("not a watchvalue answer")),
             },
             _ => return Ok(NetworkResult::invalid_message("not an answer")),
@@ -229,6 +232,13 @@ impl RPCProcessor {
         // Get target for ValueChanged notifications
         let dest = network_result_try!(self.get_respond_to_destination(&msg));
         let target = dest.get_target(rss)?;
+        let target_route_blob = match &dest {
+            Destination::PrivateRoute { private_route, .. } => Some(
+                RouteSpecStore::private_routes_to_blob(&[private_route.clone()])
+                    .map_err(RPCError::internal)?,
+            ),
+            _ => None,
+        };
 
         if debug_target_enabled!("dht") {
             let debug_string = format!(
@@ -274,6 +284,7 @@ impl RPCProcessor {
                     count,
                     watcher,
                     target,
+                    target_route_blob,
                 };
 
                 // See if we have this record ourselves, if so, accept the watch
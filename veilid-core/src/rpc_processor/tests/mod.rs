@@ -0,0 +1,14 @@
+use super::*;
+
+pub mod test_answer_cache;
+pub mod test_app_message_limiter;
+pub mod test_coder_compat;
+pub mod test_digest_value;
+pub mod test_fuzz;
+pub mod test_load_tracker;
+pub mod test_operation_waiter;
+pub mod test_proof_of_work;
+pub mod test_record_expiration;
+pub mod test_record_versions;
+pub mod test_set_value_batch;
+pub mod test_udp_answer_source;
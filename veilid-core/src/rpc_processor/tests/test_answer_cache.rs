@@ -0,0 +1,93 @@
+use super::*;
+
+fn make_node_id(n: u8) -> TypedKey {
+    TypedKey::new(best_crypto_kind(), PublicKey::new([n; PUBLIC_KEY_LENGTH]))
+}
+
+fn make_key(n: u8, question: AnswerCacheQuestion) -> AnswerCacheKey {
+    let flow = Flow::new(
+        PeerAddress::new(
+            SocketAddress::from_socket_addr(SocketAddr::from(([1, 2, 3, n], 5150))),
+            ProtocolType::UDP,
+        ),
+        SocketAddress::from_socket_addr("1.1.1.1:5150".parse().unwrap()),
+    );
+    AnswerCacheKey::new(make_node_id(n), flow, question)
+}
+
+fn make_answer(ts: Timestamp) -> RPCAnswer {
+    RPCAnswer::new(RPCAnswerDetail::StatusA(Box::new(
+        RPCOperationStatusA::new(None, Some(ts)),
+    )))
+}
+
+/// The timestamp in a status answer, to tell which answer was replayed
+fn answer_timestamp(answer: RPCAnswer) -> Option<Timestamp> {
+    match answer.destructure() {
+        RPCAnswerDetail::StatusA(status_a) => status_a.destructure().1,
+        _ => None,
+    }
+}
+
+pub async fn test_hit() {
+    let cache = AnswerCache::new(ANSWER_CACHE_SIZE);
+    let key = make_key(1, AnswerCacheQuestion::Status);
+    let ts = Timestamp::new(1_000_000);
+
+    assert!(cache.get(&key, ts).is_none());
+    cache.insert(key.clone(), make_answer(ts), ts);
+
+    let answer = cache.get(&key, ts + TimestampDuration::new(1_000_000));
+    assert_eq!(answer.and_then(answer_timestamp), Some(ts));
+
+    // A different sender or question asked by the same sender is not a hit
+    assert!(cache
+        .get(&make_key(2, AnswerCacheQuestion::Status), ts)
+        .is_none());
+    let find_node = AnswerCacheQuestion::FindNode {
+        node_id: make_node_id(3),
+        capabilities: vec![],
+    };
+    assert!(cache.get(&make_key(1, find_node), ts).is_none());
+}
+
+pub async fn test_expiry() {
+    let cache = AnswerCache::new(ANSWER_CACHE_SIZE);
+    let key = make_key(1, AnswerCacheQuestion::Status);
+    let ts = Timestamp::new(1_000_000);
+    cache.insert(key.clone(), make_answer(ts), ts);
+
+    let ttl = TimestampDuration::new(ANSWER_CACHE_TTL_US);
+    assert!(cache
+        .get(&key, ts + ttl - TimestampDuration::new(1))
+        .is_some());
+    assert!(cache.get(&key, ts + ttl).is_none());
+
+    // The expired answer was dropped, so it is not replayed even at an earlier time
+    assert!(cache.get(&key, ts).is_none());
+}
+
+pub async fn test_eviction() {
+    let cache = AnswerCache::new(2);
+    let ts = Timestamp::new(1_000_000);
+    let key1 = make_key(1, AnswerCacheQuestion::Status);
+    let key2 = make_key(2, AnswerCacheQuestion::Status);
+    let key3 = make_key(3, AnswerCacheQuestion::Status);
+
+    cache.insert(key1.clone(), make_answer(ts), ts);
+    cache.insert(key2.clone(), make_answer(ts), ts);
+
+    // Using the first answer makes the second one the least recently used
+    assert!(cache.get(&key1, ts).is_some());
+    cache.insert(key3.clone(), make_answer(ts), ts);
+
+    assert!(cache.get(&key1, ts).is_some());
+    assert!(cache.get(&key2, ts).is_none());
+    assert!(cache.get(&key3, ts).is_some());
+}
+
+pub async fn test_all() {
+    test_hit().await;
+    test_expiry().await;
+    test_eviction().await;
+}
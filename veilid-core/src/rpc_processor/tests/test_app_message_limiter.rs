@@ -0,0 +1,152 @@
+use super::*;
+
+fn make_node_id(n: u8) -> TypedKey {
+    TypedKey::new(best_crypto_kind(), PublicKey::new([n; PUBLIC_KEY_LENGTH]))
+}
+
+fn ts_secs(secs: u64) -> Timestamp {
+    Timestamp::new(secs * 1_000_000)
+}
+
+pub async fn test_size_limits() {
+    let mut limiter = AppMessageLimiter::new(100, 200, 0, 0);
+    let source = AppMessageSource::Node(make_node_id(1));
+
+    assert_eq!(
+        limiter.check(source, AppMessageKind::Message, 100, ts_secs(0)),
+        AppMessageVerdict::Accept
+    );
+    assert_eq!(
+        limiter.check(source, AppMessageKind::Message, 101, ts_secs(0)),
+        AppMessageVerdict::Drop
+    );
+    assert_eq!(
+        limiter.check(source, AppMessageKind::Call, 200, ts_secs(0)),
+        AppMessageVerdict::Accept
+    );
+    assert_eq!(
+        limiter.check(source, AppMessageKind::Call, 201, ts_secs(0)),
+        AppMessageVerdict::Drop
+    );
+}
+
+pub async fn test_rate_limits() {
+    let mut limiter = AppMessageLimiter::new(100, 100, 4, 2);
+    let source1 = AppMessageSource::Node(make_node_id(1));
+    let source2 = AppMessageSource::SafetyRoute(PublicKey::new([2; PUBLIC_KEY_LENGTH]));
+
+    for _ in 0..4 {
+        assert_eq!(
+            limiter.check(source1, AppMessageKind::Message, 10, ts_secs(0)),
+            AppMessageVerdict::Accept
+        );
+    }
+    assert_eq!(
+        limiter.check(source1, AppMessageKind::Message, 10, ts_secs(1)),
+        AppMessageVerdict::Drop
+    );
+
+    // Calls and other sources are counted separately
+    for _ in 0..2 {
+        assert_eq!(
+            limiter.check(source1, AppMessageKind::Call, 10, ts_secs(1)),
+            AppMessageVerdict::Accept
+        );
+    }
+    assert_eq!(
+        limiter.check(source1, AppMessageKind::Call, 10, ts_secs(1)),
+        AppMessageVerdict::Drop
+    );
+    assert_eq!(
+        limiter.check(source2, AppMessageKind::Message, 10, ts_secs(1)),
+        AppMessageVerdict::Accept
+    );
+
+    // The count starts over after a minute
+    assert_eq!(
+        limiter.check(source1, AppMessageKind::Message, 10, ts_secs(60)),
+        AppMessageVerdict::Accept
+    );
+}
+
+pub async fn test_punishment_escalation() {
+    let mut limiter = AppMessageLimiter::new(100, 100, 1, 1);
+    let source = AppMessageSource::Node(make_node_id(1));
+
+    assert_eq!(
+        limiter.check(source, AppMessageKind::Message, 10, ts_secs(0)),
+        AppMessageVerdict::Accept
+    );
+
+    // Going over the limits is dropped a number of times before it is punished, and punished only once
+    let verdicts: Vec<AppMessageVerdict> = (0..32)
+        .map(|_| limiter.check(source, AppMessageKind::Message, 10, ts_secs(1)))
+        .collect();
+    let punish_count = verdicts
+        .iter()
+        .filter(|v| **v == AppMessageVerdict::Punish)
+        .count();
+    assert_eq!(punish_count, 1);
+    assert_eq!(verdicts[0], AppMessageVerdict::Drop);
+    assert!(!verdicts.contains(&AppMessageVerdict::Accept));
+}
+
+pub async fn test_policies() {
+    let mut limiter = AppMessageLimiter::new(100, 100, 1, 1);
+    let trusted = make_node_id(1);
+    let blocked = make_node_id(2);
+    limiter.set_policy(trusted, AppMessagePolicy::Unlimited);
+    limiter.set_policy(blocked, AppMessagePolicy::Blocked);
+
+    // Unlimited nodes skip the rate limits but not the size limits
+    for _ in 0..10 {
+        assert_eq!(
+            limiter.check(
+                AppMessageSource::Node(trusted),
+                AppMessageKind::Message,
+                10,
+                ts_secs(0)
+            ),
+            AppMessageVerdict::Accept
+        );
+    }
+    assert_eq!(
+        limiter.check(
+            AppMessageSource::Node(trusted),
+            AppMessageKind::Message,
+            101,
+            ts_secs(0)
+        ),
+        AppMessageVerdict::Drop
+    );
+
+    // Blocked nodes are always dropped
+    assert_eq!(
+        limiter.check(
+            AppMessageSource::Node(blocked),
+            AppMessageKind::Call,
+            10,
+            ts_secs(0)
+        ),
+        AppMessageVerdict::Drop
+    );
+
+    // Going back to the default policy applies the limits again
+    limiter.set_policy(trusted, AppMessagePolicy::Limited);
+    assert_eq!(
+        limiter.check(
+            AppMessageSource::Node(trusted),
+            AppMessageKind::Message,
+            10,
+            ts_secs(0)
+        ),
+        AppMessageVerdict::Drop
+    );
+}
+
+pub async fn test_all() {
+    test_size_limits().await;
+    test_rate_limits().await;
+    test_punishment_escalation().await;
+    test_policies().await;
+}
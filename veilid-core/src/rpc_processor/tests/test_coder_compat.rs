@@ -0,0 +1,190 @@
+use super::*;
+use capnp::private::layout::StructSize;
+use capnp::traits::{HasStructSize, IntoInternalStructReader};
+
+fn make_node_info() -> NodeInfo {
+    NodeInfo::new(
+        NetworkClass::InboundCapable,
+        ProtocolTypeSet::all(),
+        AddressTypeSet::all(),
+        VALID_ENVELOPE_VERSIONS.to_vec(),
+        0,
+        vec![best_crypto_kind()],
+        vec![],
+        vec![CAP_ROUTE, CAP_DHT],
+        vec![DialInfoDetail {
+            class: DialInfoClass::Direct,
+            dial_info: DialInfo::udp(SocketAddress::from_str("1.2.3.4:5150").unwrap()),
+            preference: 0,
+        }],
+    )
+}
+
+fn known_node_info_size() -> StructSize {
+    <veilid_capnp::node_info::Builder as HasStructSize>::STRUCT_SIZE
+}
+
+fn decode_node_info_bytes(data: &[u8]) -> NodeInfo {
+    let reader = capnp::serialize_packed::read_message(data, capnp::message::ReaderOptions::new())
+        .expect("should read message");
+    let ni_reader = reader
+        .get_root::<veilid_capnp::node_info::Reader>()
+        .expect("should get root");
+    decode_node_info(&ni_reader).expect("should decode")
+}
+
+/// Encode node info the way a node from before kem public keys were added would have
+fn encode_older_node_info(node_info: &NodeInfo) -> Vec<u8> {
+    let current = encode_node_info_message(node_info).expect("should encode");
+    let current = current
+        .get_root_as_reader::<veilid_capnp::node_info::Reader>()
+        .expect("should get root")
+        .into_internal_struct_reader();
+
+    let older_size = StructSize {
+        data: 1,
+        pointers: 6,
+    };
+    let mut message = capnp::message::Builder::new_default();
+    let mut builder = init_sized_root(&mut message, older_size);
+    builder.set_data_field::<u64>(0, current.get_data_field::<u64>(0));
+    for i in 0..older_size.pointers as usize {
+        builder
+            .reborrow()
+            .get_pointer_field(i)
+            .copy_from(current.get_pointer_field(i), false)
+            .expect("should copy field");
+    }
+    builder_to_vec(message).expect("should serialize")
+}
+
+/// Encode node info the way a node with one more data field and one more pointer field would
+fn encode_newer_node_info(node_info: &NodeInfo) -> Vec<u8> {
+    let known = known_node_info_size();
+    let mut message = capnp::message::Builder::new_default();
+    let mut builder = init_sized_root(
+        &mut message,
+        StructSize {
+            data: known.data + 1,
+            pointers: known.pointers + 1,
+        },
+    );
+    encode_node_info(
+        node_info,
+        &mut veilid_capnp::node_info::Builder::from(builder.reborrow()),
+    )
+    .expect("should encode");
+    builder.set_data_field::<u64>(known.data as usize, 0x5eed_f00d);
+    builder
+        .reborrow()
+        .get_pointer_field(known.pointers as usize)
+        .init_struct(StructSize {
+            data: 1,
+            pointers: 0,
+        })
+        .set_data_field::<u64>(0, 42);
+    builder_to_vec(message).expect("should serialize")
+}
+
+pub async fn test_decode_older_node_info() {
+    let node_info = make_node_info();
+    let decoded = decode_node_info_bytes(&encode_older_node_info(&node_info));
+
+    // Fields the older node did not know about are left at their defaults
+    assert!(decoded.unknown_fields().is_none());
+    assert!(decoded.kem_public_keys().is_empty());
+    assert_eq!(decoded.max_envelope_size(), 0);
+    assert_eq!(decoded, node_info);
+}
+
+pub async fn test_encode_known_node_info_unchanged() {
+    let node_info = make_node_info();
+
+    let mut message = capnp::message::Builder::new_default();
+    let mut builder = message.init_root::<veilid_capnp::node_info::Builder>();
+    encode_node_info(&node_info, &mut builder).expect("should encode");
+    let expected = builder_to_vec(message).expect("should serialize");
+
+    let encoded = builder_to_vec(encode_node_info_message(&node_info).expect("should encode"))
+        .expect("should serialize");
+    assert_eq!(encoded, expected);
+
+    let decoded = decode_node_info_bytes(&encoded);
+    assert!(decoded.unknown_fields().is_none());
+    assert_eq!(decoded, node_info);
+}
+
+pub async fn test_newer_node_info_signed_bytes() {
+    let node_info = make_node_info();
+    let newer = encode_newer_node_info(&node_info);
+    let decoded = decode_node_info_bytes(&newer);
+
+    assert!(decoded.unknown_fields().is_some());
+    assert_eq!(decoded.clone().with_unknown_fields(None), node_info);
+
+    // Encoding it again gives exactly what the newer node encoded, so its signatures still check out
+    let encoded = builder_to_vec(encode_node_info_message(&decoded).expect("should encode"))
+        .expect("should serialize");
+    assert_eq!(encoded, newer);
+
+    let timestamp = Timestamp::new(1);
+    let sdni = SignedDirectNodeInfo::new(decoded, timestamp, vec![]);
+    let mut signed_data = newer.clone();
+    signed_data.extend_from_slice(&timestamp.as_u64().to_le_bytes());
+    assert_eq!(
+        sdni.signed_data().expect("should get signed data"),
+        signed_data
+    );
+}
+
+pub async fn test_newer_node_info_relayed() {
+    let node_info = make_node_info();
+    let newer = encode_newer_node_info(&node_info);
+    let decoded = decode_node_info_bytes(&newer);
+
+    let mut node_ids = TypedKeyGroup::new();
+    node_ids.add(TypedKey::new(
+        best_crypto_kind(),
+        PublicKey::new([1u8; PUBLIC_KEY_LENGTH]),
+    ));
+    let peer_info = PeerInfo::new(
+        node_ids,
+        SignedNodeInfo::Direct(SignedDirectNodeInfo::new(
+            decoded,
+            Timestamp::new(1),
+            vec![],
+        )),
+    );
+
+    // Pass the peer info along as we would in an answer
+    let mut message = capnp::message::Builder::new_default();
+    let mut builder = message.init_root::<veilid_capnp::peer_info::Builder>();
+    encode_peer_info(&peer_info, &mut builder).expect("should encode");
+    let relayed = builder_to_vec(message).expect("should serialize");
+
+    let reader = capnp::serialize_packed::read_message(
+        relayed.as_slice(),
+        capnp::message::ReaderOptions::new(),
+    )
+    .expect("should read message");
+    let pi_reader = reader
+        .get_root::<veilid_capnp::peer_info::Reader>()
+        .expect("should get root");
+    let relayed_peer_info = decode_peer_info(&pi_reader).expect("should decode");
+    assert_eq!(relayed_peer_info, peer_info);
+
+    // The node info still has the fields we do not know about
+    let encoded = builder_to_vec(
+        encode_node_info_message(relayed_peer_info.signed_node_info().node_info())
+            .expect("should encode"),
+    )
+    .expect("should serialize");
+    assert_eq!(encoded, newer);
+}
+
+pub async fn test_all() {
+    test_decode_older_node_info().await;
+    test_encode_known_node_info_unchanged().await;
+    test_newer_node_info_signed_bytes().await;
+    test_newer_node_info_relayed().await;
+}
@@ -0,0 +1,126 @@
+use super::*;
+use crate::tests::common::test_veilid_config::*;
+
+pub async fn test_split_subkey_digest_range() {
+    // Parts cover the whole range without gaps and differ in length by at most one
+    let parts = split_subkey_digest_range((0, 999), 16);
+    assert_eq!(parts.len(), 16);
+    assert_eq!(parts.first().unwrap().0, 0);
+    assert_eq!(parts.last().unwrap().1, 999);
+    for pair in parts.windows(2) {
+        assert_eq!(pair[0].1 + 1, pair[1].0);
+    }
+    let lens: Vec<u32> = parts.iter().map(|(s, e)| e - s + 1).collect();
+    assert_eq!(*lens.iter().max().unwrap() - *lens.iter().min().unwrap(), 1);
+
+    // Short ranges split into single subkeys
+    assert_eq!(
+        split_subkey_digest_range((5, 7), 16),
+        vec![(5, 5), (6, 6), (7, 7)]
+    );
+
+    // The full subkey space does not overflow
+    let parts = split_subkey_digest_range((0, ValueSubkey::MAX), 2);
+    assert_eq!(
+        parts,
+        vec![(0, 0x7FFF_FFFF), (0x8000_0000, ValueSubkey::MAX)]
+    );
+}
+
+pub async fn test_subkey_seqs_digest(vcrypto: CryptoSystemVersion) {
+    let seqs = vec![0, 1, ValueSeqNum::MAX, 7];
+    assert_eq!(
+        subkey_seqs_digest(&vcrypto, &seqs),
+        subkey_seqs_digest(&vcrypto, &[0, 1, ValueSeqNum::MAX, 7])
+    );
+
+    // Any newer value changes the digest
+    let mut newer = seqs.clone();
+    newer[2] = 0;
+    assert_ne!(
+        subkey_seqs_digest(&vcrypto, &seqs),
+        subkey_seqs_digest(&vcrypto, &newer)
+    );
+
+    // Order matters
+    let mut swapped = seqs.clone();
+    swapped.swap(0, 1);
+    assert_ne!(
+        subkey_seqs_digest(&vcrypto, &seqs),
+        subkey_seqs_digest(&vcrypto, &swapped)
+    );
+}
+
+pub async fn test_digest_value_coders(vcrypto: CryptoSystemVersion) {
+    let key = TypedKey::new(vcrypto.kind(), vcrypto.generate_keypair().key);
+    let ranges = vec![(0, 15), (16, 31), (100, 100)];
+
+    let digest_value_q = RPCOperationDigestValueQ::new(key, ranges.clone()).unwrap();
+    let mut message = ::capnp::message::Builder::new_default();
+    let mut builder = message.init_root::<veilid_capnp::operation_digest_value_q::Builder>();
+    digest_value_q.encode(&mut builder).unwrap();
+    let data = builder_to_vec(message).unwrap();
+
+    let reader = ::capnp::serialize_packed::read_message(
+        data.as_slice(),
+        ::capnp::message::ReaderOptions::new(),
+    )
+    .unwrap();
+    let decoded = RPCOperationDigestValueQ::decode(
+        &reader
+            .get_root::<veilid_capnp::operation_digest_value_q::Reader>()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(decoded.destructure(), (key, ranges));
+
+    // Ranges must be ascending and must not overlap
+    assert!(RPCOperationDigestValueQ::new(key, vec![(16, 31), (0, 15)]).is_err());
+    assert!(RPCOperationDigestValueQ::new(key, vec![(0, 16), (16, 31)]).is_err());
+    assert!(RPCOperationDigestValueQ::new(key, vec![(5, 4)]).is_err());
+    let too_many = (0..=MAX_DIGEST_VALUE_Q_SUBKEY_RANGES_LEN as ValueSubkey)
+        .map(|x| (x, x))
+        .collect();
+    assert!(RPCOperationDigestValueQ::new(key, too_many).is_err());
+
+    let digests = vec![
+        vcrypto.generate_hash(b"first"),
+        vcrypto.generate_hash(b"second"),
+    ];
+    let digest_value_a = RPCOperationDigestValueA::new(digests.clone()).unwrap();
+    let mut message = ::capnp::message::Builder::new_default();
+    let mut builder = message.init_root::<veilid_capnp::operation_digest_value_a::Builder>();
+    digest_value_a.encode(&mut builder).unwrap();
+    let data = builder_to_vec(message).unwrap();
+
+    let reader = ::capnp::serialize_packed::read_message(
+        data.as_slice(),
+        ::capnp::message::ReaderOptions::new(),
+    )
+    .unwrap();
+    let decoded = RPCOperationDigestValueA::decode(
+        &reader
+            .get_root::<veilid_capnp::operation_digest_value_a::Reader>()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(decoded.destructure(), digests);
+}
+
+pub async fn test_all() {
+    let (update_callback, config_callback) = setup_veilid_core();
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+
+    test_split_subkey_digest_range().await;
+
+    let crypto = api.crypto().unwrap();
+    for ck in VALID_CRYPTO_KINDS {
+        let vcrypto = crypto.get(ck).unwrap();
+        test_subkey_seqs_digest(vcrypto.clone()).await;
+        test_digest_value_coders(vcrypto).await;
+    }
+
+    api.shutdown().await;
+}
@@ -0,0 +1,82 @@
+use super::*;
+use crate::rpc_processor::fuzz::{self, FuzzResult, FUZZ_DECODERS};
+
+fn decoder(name: &str) -> fn(&[u8]) -> FuzzResult {
+    FUZZ_DECODERS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, d)| *d)
+        .expect("corpus entry should name an entry point")
+}
+
+pub async fn test_corpus_decodes() {
+    let corpus = fuzz::generate_corpus();
+    assert!(!corpus.is_empty());
+    for (name, data) in corpus {
+        decoder(name)(&data).unwrap_or_else(|e| panic!("{} failed to decode: {}", name, e));
+
+        let mut prefixed = vec![fuzz::fuzz_decoder_index(name).unwrap()];
+        prefixed.extend_from_slice(&data);
+        fuzz::decode_any(&prefixed)
+            .unwrap_or_else(|e| panic!("{} failed to dispatch: {}", name, e));
+    }
+}
+
+pub async fn test_malformed_inputs() {
+    assert!(fuzz::decode_any(&[]).is_err());
+
+    for (name, data) in fuzz::generate_corpus() {
+        let decode = decoder(name);
+
+        // Truncated messages
+        for len in 0..data.len() {
+            let _ = decode(&data[..len]);
+        }
+
+        // Corrupted bytes
+        for i in 0..data.len() {
+            for flip in [0x01u8, 0x80, 0xFF] {
+                let mut corrupt = data.clone();
+                corrupt[i] ^= flip;
+                let _ = decode(&corrupt);
+            }
+        }
+
+        // Every other decoder on the same bytes
+        for (_, other) in FUZZ_DECODERS {
+            let _ = other(&data);
+        }
+    }
+}
+
+pub async fn test_route_hop_count_mismatch() {
+    let encode = |private_route: &PrivateRoute| {
+        let mut message = ::capnp::message::Builder::new_default();
+        let mut builder = message.init_root::<veilid_capnp::private_route::Builder>();
+        encode_private_route(private_route, &mut builder).unwrap();
+        builder_to_vec(message).unwrap()
+    };
+    let public_key = TypedKey::new(best_crypto_kind(), PublicKey::new([1; PUBLIC_KEY_LENGTH]));
+    let node = RouteNode::NodeId(PublicKey::new([2; PUBLIC_KEY_LENGTH]));
+
+    let mut private_route = PrivateRoute::new_stub(public_key, node);
+    assert!(fuzz::decode_private_route(&encode(&private_route)).is_ok());
+
+    // Hops left but no hop count
+    private_route.hop_count = 0;
+    assert!(fuzz::decode_private_route(&encode(&private_route)).is_err());
+
+    // No hops left but a hop count
+    private_route.hops = PrivateRouteHops::Empty;
+    private_route.hop_count = 1;
+    assert!(fuzz::decode_private_route(&encode(&private_route)).is_err());
+
+    private_route.hop_count = 0;
+    assert!(fuzz::decode_private_route(&encode(&private_route)).is_ok());
+}
+
+pub async fn test_all() {
+    test_corpus_decodes().await;
+    test_malformed_inputs().await;
+    test_route_hop_count_mismatch().await;
+}
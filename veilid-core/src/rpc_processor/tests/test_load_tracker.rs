@@ -0,0 +1,187 @@
+use super::*;
+
+const QUEUE_SIZE: usize = 1024;
+
+fn timeout() -> TimestampDuration {
+    TimestampDuration::new(ms_to_us(5000))
+}
+
+pub async fn test_fanout_concurrency() {
+    let tracker = LoadTracker::default();
+
+    // Full concurrency while the queue is less than half full
+    assert_eq!(
+        tracker.fanout_concurrency(8, 1, 0, QUEUE_SIZE, timeout()),
+        8
+    );
+    assert_eq!(
+        tracker.fanout_concurrency(8, 1, QUEUE_SIZE / 2 - 1, QUEUE_SIZE, timeout()),
+        8
+    );
+
+    // Half concurrency once the queue is half full
+    assert_eq!(
+        tracker.fanout_concurrency(8, 1, QUEUE_SIZE / 2, QUEUE_SIZE, timeout()),
+        4
+    );
+    assert_eq!(
+        tracker.fanout_concurrency(8, 6, QUEUE_SIZE / 2, QUEUE_SIZE, timeout()),
+        6
+    );
+
+    // Minimum concurrency once saturated
+    assert_eq!(
+        tracker.fanout_concurrency(8, 1, QUEUE_SIZE * 3 / 4, QUEUE_SIZE, timeout()),
+        1
+    );
+    assert_eq!(
+        tracker.fanout_concurrency(8, 2, QUEUE_SIZE, QUEUE_SIZE, timeout()),
+        2
+    );
+
+    // Fanouts at or below the minimum are never reduced
+    assert_eq!(
+        tracker.fanout_concurrency(1, 1, QUEUE_SIZE, QUEUE_SIZE, timeout()),
+        1
+    );
+    assert_eq!(
+        tracker.fanout_concurrency(4, 8, QUEUE_SIZE, QUEUE_SIZE, timeout()),
+        4
+    );
+}
+
+pub async fn test_fanout_concurrency_queue_time() {
+    let mut tracker = LoadTracker::default();
+
+    // Questions waiting too long saturate us even with an empty queue
+    for _ in 0..64 {
+        tracker.record_queue_time(TimestampDuration::new(ms_to_us(5000)));
+    }
+    assert_eq!(
+        tracker.fanout_concurrency(8, 1, 0, QUEUE_SIZE, timeout()),
+        1
+    );
+}
+
+pub async fn test_is_saturated() {
+    let mut tracker = LoadTracker::default();
+
+    // Saturated once the queue is three quarters full
+    assert!(!tracker.is_saturated(0, QUEUE_SIZE, timeout()));
+    assert!(!tracker.is_saturated(QUEUE_SIZE * 3 / 4 - 1, QUEUE_SIZE, timeout()));
+    assert!(tracker.is_saturated(QUEUE_SIZE * 3 / 4, QUEUE_SIZE, timeout()));
+
+    // Or once questions wait a quarter of the timeout on average
+    tracker.record_queue_time(TimestampDuration::new(ms_to_us(1000)));
+    assert!(!tracker.is_saturated(0, QUEUE_SIZE, timeout()));
+    for _ in 0..64 {
+        tracker.record_queue_time(TimestampDuration::new(ms_to_us(2000)));
+    }
+    assert!(tracker.is_saturated(0, QUEUE_SIZE, timeout()));
+}
+
+pub async fn test_get_stats() {
+    let mut tracker = LoadTracker::default();
+
+    // The first sample starts the running average
+    tracker.record_queue_time(TimestampDuration::new(800));
+    tracker.record_processing_time(TimestampDuration::new(1600));
+    let stats = tracker.get_stats(3, QUEUE_SIZE);
+    assert_eq!(stats.queue_depth, 3);
+    assert_eq!(stats.queue_size, QUEUE_SIZE as u32);
+    assert_eq!(stats.average_queue_time, TimestampDuration::new(800));
+    assert_eq!(stats.average_processing_time, TimestampDuration::new(1600));
+
+    // Later samples move it an eighth of the way
+    tracker.record_queue_time(TimestampDuration::new(1600));
+    tracker.record_processing_time(TimestampDuration::new(0));
+    let stats = tracker.get_stats(0, QUEUE_SIZE);
+    assert_eq!(stats.average_queue_time, TimestampDuration::new(900));
+    assert_eq!(stats.average_processing_time, TimestampDuration::new(1400));
+
+    // Turned away work is counted
+    assert_eq!(stats.shed_questions, 0);
+    assert_eq!(stats.dropped_messages, 0);
+    tracker.record_shed_question();
+    tracker.record_shed_question();
+    tracker.record_dropped_message();
+    let stats = tracker.get_stats(0, QUEUE_SIZE);
+    assert_eq!(stats.shed_questions, 2);
+    assert_eq!(stats.dropped_messages, 1);
+}
+
+pub async fn test_adapt_concurrency_limit() {
+    const MAX_LIMIT: usize = 16;
+    let mut tracker = LoadTracker::default();
+    let mut cur_ts = Timestamp::new(1_000_000);
+    let mut next_interval = || {
+        cur_ts += 1_000_000u64;
+        cur_ts
+    };
+
+    // Nothing waiting and nothing slowing down leaves the limit alone
+    tracker.record_processing_time(TimestampDuration::new(1000));
+    assert_eq!(
+        tracker.adapt_concurrency_limit(next_interval(), 8, MAX_LIMIT, 0),
+        8
+    );
+
+    // Messages waiting let another worker in, once per interval
+    let ts = next_interval();
+    assert_eq!(tracker.adapt_concurrency_limit(ts, 8, MAX_LIMIT, 10), 9);
+    assert_eq!(tracker.adapt_concurrency_limit(ts, 9, MAX_LIMIT, 10), 9);
+    assert_eq!(
+        tracker.adapt_concurrency_limit(ts + 999_999u64, 9, MAX_LIMIT, 10),
+        9
+    );
+    assert_eq!(
+        tracker.adapt_concurrency_limit(next_interval(), 9, MAX_LIMIT, 10),
+        10
+    );
+
+    // But never past the configured concurrency
+    assert_eq!(
+        tracker.adapt_concurrency_limit(next_interval(), MAX_LIMIT, MAX_LIMIT, 10),
+        MAX_LIMIT
+    );
+
+    // Processing slowing down well past its baseline halves the limit, even with messages waiting
+    for _ in 0..64 {
+        tracker.record_processing_time(TimestampDuration::new(4000));
+    }
+    assert_eq!(
+        tracker.adapt_concurrency_limit(next_interval(), MAX_LIMIT, MAX_LIMIT, 10),
+        8
+    );
+
+    // But never below a quarter of the configured concurrency
+    assert_eq!(
+        tracker.adapt_concurrency_limit(next_interval(), 8, MAX_LIMIT, 10),
+        4
+    );
+    assert_eq!(
+        tracker.adapt_concurrency_limit(next_interval(), 4, MAX_LIMIT, 10),
+        4
+    );
+    assert_eq!(
+        tracker.adapt_concurrency_limit(next_interval(), 1, 1, 10),
+        1
+    );
+
+    // Once processing speeds up again the limit grows back
+    for _ in 0..64 {
+        tracker.record_processing_time(TimestampDuration::new(1000));
+    }
+    assert_eq!(
+        tracker.adapt_concurrency_limit(next_interval(), 4, MAX_LIMIT, 10),
+        5
+    );
+}
+
+pub async fn test_all() {
+    test_fanout_concurrency().await;
+    test_fanout_concurrency_queue_time().await;
+    test_is_saturated().await;
+    test_get_stats().await;
+    test_adapt_concurrency_limit().await;
+}
@@ -0,0 +1,66 @@
+use super::*;
+use futures_util::FutureExt;
+
+const WAIT_TIMEOUT_US: u64 = 10_000_000;
+
+pub async fn test_complete() {
+    let waiter = OperationWaiter::<u32, ()>::new();
+    let op_id = OperationId::new(1);
+
+    let handle = waiter.add_op_waiter(op_id, ());
+    assert_eq!(waiter.get_operation_ids(), vec![op_id]);
+
+    waiter.complete_op_waiter(op_id, 5).await.unwrap();
+    let res = waiter
+        .wait_for_op(handle, TimestampDuration::new(WAIT_TIMEOUT_US))
+        .await
+        .unwrap();
+    let TimeoutOr::Value((v, _)) = res else {
+        panic!("should not have timed out");
+    };
+    assert_eq!(v, 5);
+    assert!(waiter.get_operation_ids().is_empty());
+}
+
+pub async fn test_drop_handle() {
+    let waiter = OperationWaiter::<u32, ()>::new();
+    let op_id = OperationId::new(2);
+
+    // Giving up before waiting frees the op
+    let handle = waiter.add_op_waiter(op_id, ());
+    drop(handle);
+    assert!(waiter.get_operation_ids().is_empty());
+    assert!(waiter.complete_op_waiter(op_id, 5).await.is_err());
+}
+
+pub async fn test_drop_wait() {
+    let waiter = OperationWaiter::<u32, ()>::new();
+    let op_id = OperationId::new(3);
+
+    // Dropping a wait that has started frees the op too
+    let handle = waiter.add_op_waiter(op_id, ());
+    let wait = waiter.wait_for_op(handle, TimestampDuration::new(WAIT_TIMEOUT_US));
+    assert!(wait.now_or_never().is_none());
+    assert!(waiter.get_operation_ids().is_empty());
+    assert!(waiter.complete_op_waiter(op_id, 5).await.is_err());
+}
+
+pub async fn test_timeout() {
+    let waiter = OperationWaiter::<u32, ()>::new();
+    let op_id = OperationId::new(4);
+
+    let handle = waiter.add_op_waiter(op_id, ());
+    let res = waiter
+        .wait_for_op(handle, TimestampDuration::new(1_000))
+        .await
+        .unwrap();
+    assert!(res.is_timeout());
+    assert!(waiter.get_operation_ids().is_empty());
+}
+
+pub async fn test_all() {
+    test_complete().await;
+    test_drop_handle().await;
+    test_drop_wait().await;
+    test_timeout().await;
+}
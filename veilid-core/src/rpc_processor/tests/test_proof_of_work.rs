@@ -0,0 +1,139 @@
+use super::*;
+use crate::tests::common::test_veilid_config::*;
+
+pub async fn test_verify_pow(vcrypto: CryptoSystemVersion) {
+    // A difficulty of zero always passes
+    assert!(verify_pow(&vcrypto, b"challenge", 0, 0));
+    assert!(verify_pow(&vcrypto, b"challenge", u64::MAX, 0));
+
+    // Find a nonce that does not solve the puzzle and make sure it is refused
+    let mut nonce = 0u64;
+    while verify_pow(&vcrypto, b"challenge", nonce, 8) {
+        nonce += 1;
+    }
+    assert!(!verify_pow(&vcrypto, b"challenge", nonce, 8));
+}
+
+pub async fn test_solve_pow(vcrypto: CryptoSystemVersion) {
+    for difficulty in [1u8, 4, 8, 12] {
+        let nonce = solve_pow(&vcrypto, b"challenge", difficulty);
+        assert!(verify_pow(&vcrypto, b"challenge", nonce, difficulty));
+
+        // Any solution also solves every easier puzzle
+        for easier in 0..difficulty {
+            assert!(verify_pow(&vcrypto, b"challenge", nonce, easier));
+        }
+    }
+
+    // Solutions are bound to their challenge
+    let nonce = solve_pow(&vcrypto, b"challenge", 16);
+    assert!(verify_pow(&vcrypto, b"challenge", nonce, 16));
+    assert!(!verify_pow(&vcrypto, b"other challenge", nonce, 16));
+}
+
+pub async fn test_solve_pow_async(vcrypto: CryptoSystemVersion) {
+    let nonce = solve_pow_async(vcrypto.clone(), b"challenge".to_vec(), 12).await;
+    assert!(verify_pow(&vcrypto, b"challenge", nonce, 12));
+
+    // Nothing to solve at zero difficulty
+    let nonce = solve_pow_async(vcrypto.clone(), b"challenge".to_vec(), 0).await;
+    assert!(verify_pow(&vcrypto, b"challenge", nonce, 0));
+}
+
+pub async fn test_route_pow_coders(vcrypto: CryptoSystemVersion) {
+    let public_key = TypedKey::new(vcrypto.kind(), vcrypto.generate_keypair().key);
+    let safety_route = SafetyRoute {
+        public_key,
+        hop_count: 0,
+        hops: SafetyRouteHops::Private(PrivateRoute {
+            public_key,
+            hop_count: 0,
+            hops: PrivateRouteHops::Empty,
+        }),
+    };
+    let routed_operation = RoutedOperation::new(
+        Sequencing::NoPreference,
+        vcrypto.random_nonce(),
+        b"routed".to_vec(),
+    );
+
+    let route = RPCOperationRoute::new(safety_route, routed_operation, 0x0123_4567_89ab_cdef);
+    let mut message = ::capnp::message::Builder::new_default();
+    let mut builder = message.init_root::<veilid_capnp::operation_route::Builder>();
+    route.encode(&mut builder).unwrap();
+    let data = builder_to_vec(message).unwrap();
+
+    let reader = ::capnp::serialize_packed::read_message(
+        data.as_slice(),
+        ::capnp::message::ReaderOptions::new(),
+    )
+    .unwrap();
+    let decoded = RPCOperationRoute::decode(
+        &reader
+            .get_root::<veilid_capnp::operation_route::Reader>()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(decoded.pow_nonce(), 0x0123_4567_89ab_cdef);
+    assert_eq!(decoded.safety_route().public_key, public_key);
+    assert_eq!(decoded.operation().nonce(), route.operation().nonce());
+}
+
+pub async fn test_node_status_coders() {
+    let node_status = NodeStatus {
+        route_pow_difficulty: 17,
+    };
+    let mut message = ::capnp::message::Builder::new_default();
+    let mut builder = message.init_root::<veilid_capnp::node_status::Builder>();
+    encode_node_status(&node_status, &mut builder).unwrap();
+    let data = builder_to_vec(message).unwrap();
+
+    let reader = ::capnp::serialize_packed::read_message(
+        data.as_slice(),
+        ::capnp::message::ReaderOptions::new(),
+    )
+    .unwrap();
+    let decoded = decode_node_status(
+        &reader
+            .get_root::<veilid_capnp::node_status::Reader>()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(decoded.route_pow_difficulty, 17);
+
+    // Status from nodes that do not demand proof of work decodes as zero difficulty
+    let mut message = ::capnp::message::Builder::new_default();
+    message.init_root::<veilid_capnp::node_status::Builder>();
+    let data = builder_to_vec(message).unwrap();
+    let reader = ::capnp::serialize_packed::read_message(
+        data.as_slice(),
+        ::capnp::message::ReaderOptions::new(),
+    )
+    .unwrap();
+    let decoded = decode_node_status(
+        &reader
+            .get_root::<veilid_capnp::node_status::Reader>()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(decoded.route_pow_difficulty, 0);
+}
+
+pub async fn test_all() {
+    let (update_callback, config_callback) = setup_veilid_core();
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+
+    let crypto = api.crypto().unwrap();
+    for ck in VALID_CRYPTO_KINDS {
+        let vcrypto = crypto.get(ck).unwrap();
+        test_verify_pow(vcrypto.clone()).await;
+        test_solve_pow(vcrypto.clone()).await;
+        test_solve_pow_async(vcrypto.clone()).await;
+        test_route_pow_coders(vcrypto).await;
+    }
+    test_node_status_coders().await;
+
+    api.shutdown().await;
+}
@@ -0,0 +1,101 @@
+use super::*;
+use crate::tests::common::test_veilid_config::*;
+
+fn make_descriptor(
+    vcrypto: CryptoSystemVersion,
+    owner: &KeyPair,
+    expiration: u64,
+) -> SignedValueDescriptor {
+    SignedValueDescriptor::make_signature(
+        owner.key,
+        DHTSchema::dflt(2).unwrap().compile(),
+        0,
+        Timestamp::new(expiration),
+        vcrypto,
+        owner.secret,
+    )
+    .unwrap()
+}
+
+pub async fn test_descriptor_expiration(vcrypto: CryptoSystemVersion) {
+    let owner = vcrypto.generate_keypair();
+
+    // Records that never expire sign the same data as before expiration existed
+    let forever = make_descriptor(vcrypto.clone(), &owner, 0);
+    forever.validate(vcrypto.clone()).unwrap();
+    assert_eq!(forever.expiration(), None);
+    assert!(!forever.is_expired(Timestamp::new(u64::MAX)));
+
+    let expiring = make_descriptor(vcrypto.clone(), &owner, 1_000);
+    expiring.validate(vcrypto.clone()).unwrap();
+    assert_eq!(expiring.expiration(), Some(Timestamp::new(1_000)));
+    assert!(!expiring.is_expired(Timestamp::new(999)));
+    assert!(expiring.is_expired(Timestamp::new(1_000)));
+
+    // The expiration is signed, so nobody but the owner can change it
+    let forged = SignedValueDescriptor::new(
+        *expiring.owner(),
+        expiring.schema_data().to_vec(),
+        *expiring.signature(),
+        expiring.version(),
+        Timestamp::new(2_000),
+    );
+    assert!(forged.validate(vcrypto.clone()).is_err());
+
+    // Extending keeps the identity of the record but only moves the expiration later
+    let extended = make_descriptor(vcrypto.clone(), &owner, 2_000);
+    assert_eq!(extended.cmp_no_sig(&expiring), cmp::Ordering::Equal);
+    assert!(extended.extends_expiration_of(&expiring));
+    assert!(!expiring.extends_expiration_of(&extended));
+    assert!(!extended.extends_expiration_of(&extended));
+    assert!(forever.extends_expiration_of(&expiring));
+    assert!(!expiring.extends_expiration_of(&forever));
+
+    // Descriptors for other records never extend each other
+    let other_owner = vcrypto.generate_keypair();
+    let other = make_descriptor(vcrypto.clone(), &other_owner, 3_000);
+    assert!(!other.extends_expiration_of(&expiring));
+}
+
+pub async fn test_descriptor_expiration_coders(vcrypto: CryptoSystemVersion) {
+    let owner = vcrypto.generate_keypair();
+
+    for expiration in [0, 1_234_567] {
+        let descriptor = make_descriptor(vcrypto.clone(), &owner, expiration);
+
+        let mut message = ::capnp::message::Builder::new_default();
+        let mut builder = message.init_root::<veilid_capnp::signed_value_descriptor::Builder>();
+        encode_signed_value_descriptor(&descriptor, &mut builder).unwrap();
+        let data = builder_to_vec(message).unwrap();
+
+        let reader = ::capnp::serialize_packed::read_message(
+            data.as_slice(),
+            ::capnp::message::ReaderOptions::new(),
+        )
+        .unwrap();
+        let decoded = decode_signed_value_descriptor(
+            &reader
+                .get_root::<veilid_capnp::signed_value_descriptor::Reader>()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(decoded, descriptor);
+        decoded.validate(vcrypto.clone()).unwrap();
+    }
+}
+
+pub async fn test_all() {
+    let (update_callback, config_callback) = setup_veilid_core();
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+
+    let crypto = api.crypto().unwrap();
+    for ck in VALID_CRYPTO_KINDS {
+        let vcrypto = crypto.get(ck).unwrap();
+        test_descriptor_expiration(vcrypto.clone()).await;
+        test_descriptor_expiration_coders(vcrypto).await;
+    }
+
+    api.shutdown().await;
+}
@@ -0,0 +1,160 @@
+use super::*;
+use crate::tests::common::test_veilid_config::*;
+
+fn make_descriptor(
+    vcrypto: CryptoSystemVersion,
+    owner: &KeyPair,
+    version: u32,
+    expiration: u64,
+) -> SignedValueDescriptor {
+    SignedValueDescriptor::make_signature(
+        owner.key,
+        DHTSchema::dflt(2).unwrap().compile(),
+        version,
+        Timestamp::new(expiration),
+        vcrypto,
+        owner.secret,
+    )
+    .unwrap()
+}
+
+fn make_link(
+    vcrypto: CryptoSystemVersion,
+    owner: &KeyPair,
+    descriptor: &SignedValueDescriptor,
+    version: u32,
+) -> SignedRecordVersionLink {
+    SignedRecordVersionLink::make_signature(
+        DHTRecordVersionLink::new(TypedKey::new(vcrypto.kind(), owner.key), version),
+        descriptor.owner(),
+        descriptor.schema_data(),
+        descriptor.version(),
+        vcrypto,
+        owner.secret,
+    )
+    .unwrap()
+}
+
+pub async fn test_descriptor_version_link(vcrypto: CryptoSystemVersion) {
+    let owner = vcrypto.generate_keypair();
+    let descriptor = make_descriptor(vcrypto.clone(), &owner, 0, 0);
+
+    // The link is signed separately, so adding it does not change the record or its descriptor signature
+    let linked =
+        descriptor
+            .clone()
+            .with_superseded_by(make_link(vcrypto.clone(), &owner, &descriptor, 1));
+    linked.validate(vcrypto.clone()).unwrap();
+    assert_eq!(linked.cmp_no_sig(&descriptor), cmp::Ordering::Equal);
+    assert_eq!(linked.signature(), descriptor.signature());
+
+    // Only the owner can sign a link
+    let other_owner = vcrypto.generate_keypair();
+    let forged = descriptor.clone().with_superseded_by(make_link(
+        vcrypto.clone(),
+        &other_owner,
+        &descriptor,
+        1,
+    ));
+    assert!(forged.validate(vcrypto.clone()).is_err());
+
+    // A link is bound to the record it was made for, even one by the same owner
+    let other_record = make_descriptor(vcrypto.clone(), &owner, 1, 0);
+    let moved =
+        other_record
+            .clone()
+            .with_superseded_by(make_link(vcrypto.clone(), &owner, &descriptor, 2));
+    assert!(moved.validate(vcrypto.clone()).is_err());
+
+    // Links only point forward
+    let backward = other_record.clone().with_superseded_by(make_link(
+        vcrypto.clone(),
+        &owner,
+        &other_record,
+        1,
+    ));
+    assert!(backward.validate(vcrypto.clone()).is_err());
+}
+
+pub async fn test_descriptor_updated_by(vcrypto: CryptoSystemVersion) {
+    let owner = vcrypto.generate_keypair();
+    let descriptor = make_descriptor(vcrypto.clone(), &owner, 0, 1_000);
+    let linked =
+        descriptor
+            .clone()
+            .with_superseded_by(make_link(vcrypto.clone(), &owner, &descriptor, 1));
+    let extended = make_descriptor(vcrypto.clone(), &owner, 0, 2_000);
+
+    // Nothing new
+    assert!(descriptor.updated_by(&descriptor).is_none());
+    assert!(linked.updated_by(&descriptor).is_none());
+    assert!(linked.updated_by(&linked).is_none());
+
+    // A new link is taken
+    assert_eq!(descriptor.updated_by(&linked), Some(linked.clone()));
+
+    // A later expiration keeps the link we already have
+    let updated = linked.updated_by(&extended).unwrap();
+    updated.validate(vcrypto.clone()).unwrap();
+    assert_eq!(updated.expiration(), extended.expiration());
+    assert_eq!(updated.superseded_by(), linked.superseded_by());
+
+    // A newer link replaces an older one
+    let relinked =
+        descriptor
+            .clone()
+            .with_superseded_by(make_link(vcrypto.clone(), &owner, &descriptor, 2));
+    assert_eq!(linked.updated_by(&relinked), Some(relinked.clone()));
+    assert!(relinked.updated_by(&linked).is_none());
+
+    // Descriptors for other records never update each other
+    let other = make_descriptor(vcrypto.clone(), &vcrypto.generate_keypair(), 0, 3_000);
+    assert!(descriptor.updated_by(&other).is_none());
+}
+
+pub async fn test_descriptor_version_link_coders(vcrypto: CryptoSystemVersion) {
+    let owner = vcrypto.generate_keypair();
+    let descriptor = make_descriptor(vcrypto.clone(), &owner, 0, 0);
+    let linked =
+        descriptor
+            .clone()
+            .with_superseded_by(make_link(vcrypto.clone(), &owner, &descriptor, 1));
+
+    for descriptor in [descriptor, linked] {
+        let mut message = ::capnp::message::Builder::new_default();
+        let mut builder = message.init_root::<veilid_capnp::signed_value_descriptor::Builder>();
+        encode_signed_value_descriptor(&descriptor, &mut builder).unwrap();
+        let data = builder_to_vec(message).unwrap();
+
+        let reader = ::capnp::serialize_packed::read_message(
+            data.as_slice(),
+            ::capnp::message::ReaderOptions::new(),
+        )
+        .unwrap();
+        let decoded = decode_signed_value_descriptor(
+            &reader
+                .get_root::<veilid_capnp::signed_value_descriptor::Reader>()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(decoded, descriptor);
+        decoded.validate(vcrypto.clone()).unwrap();
+    }
+}
+
+pub async fn test_all() {
+    let (update_callback, config_callback) = setup_veilid_core();
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+
+    let crypto = api.crypto().unwrap();
+    for ck in VALID_CRYPTO_KINDS {
+        let vcrypto = crypto.get(ck).unwrap();
+        test_descriptor_version_link(vcrypto.clone()).await;
+        test_descriptor_updated_by(vcrypto.clone()).await;
+        test_descriptor_version_link_coders(vcrypto).await;
+    }
+
+    api.shutdown().await;
+}
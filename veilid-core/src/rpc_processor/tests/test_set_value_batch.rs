@@ -0,0 +1,170 @@
+use super::*;
+use crate::tests::common::test_veilid_config::*;
+
+fn make_batch(
+    vcrypto: CryptoSystemVersion,
+    owner: &PublicKey,
+    writer: KeyPair,
+    subkeys: &[ValueSubkey],
+) -> SignedValueBatch {
+    let values = subkeys
+        .iter()
+        .map(|subkey| BatchedValue {
+            subkey: *subkey,
+            value_data: ValueData::new_with_seq(
+                *subkey + 1,
+                format!("value {}", subkey).into_bytes(),
+                writer.key,
+            )
+            .unwrap(),
+            compressed: false,
+        })
+        .collect();
+    SignedValueBatch::make_signature(values, owner, vcrypto, writer).unwrap()
+}
+
+pub async fn test_batch_signature(vcrypto: CryptoSystemVersion) {
+    let owner = vcrypto.generate_keypair();
+    let writer = vcrypto.generate_keypair();
+
+    let batch = make_batch(vcrypto.clone(), &owner.key, writer, &[0, 1, 5]);
+    let digest = batch.validate(&owner.key, vcrypto.clone()).unwrap();
+    assert_eq!(digest.entries().len(), 3);
+
+    // Each value split from the batch still validates on its own, but only for its own subkey
+    let signed_values = batch.clone().into_signed_values(digest);
+    assert_eq!(signed_values.len(), 3);
+    for (subkey, value) in &signed_values {
+        value
+            .validate(&owner.key, *subkey, vcrypto.clone())
+            .unwrap();
+        assert!(value.validate(&owner.key, 2, vcrypto.clone()).is_err());
+    }
+
+    // The batch is signed for one owner
+    let other = vcrypto.generate_keypair();
+    assert!(batch.validate(&other.key, vcrypto.clone()).is_err());
+
+    // Tampering with any value breaks the batch signature
+    let mut values = batch.values().to_vec();
+    values[1].value_data =
+        ValueData::new_with_seq(9, b"tampered".to_vec(), *batch.writer()).unwrap();
+    let tampered = SignedValueBatch::new(*batch.writer(), *batch.signature(), values).unwrap();
+    assert!(tampered.validate(&owner.key, vcrypto.clone()).is_err());
+
+    // A batch must be in increasing subkey order
+    let mut values = batch.values().to_vec();
+    values.swap(0, 1);
+    let reordered = SignedValueBatch::new(*batch.writer(), *batch.signature(), values).unwrap();
+    assert!(reordered.validate(&owner.key, vcrypto.clone()).is_err());
+
+    // A batch signature can not be passed off as a single value signature
+    let (subkey, value) = &signed_values[0];
+    let unbatched = SignedValueData::new(
+        value.value_data().clone(),
+        *value.signature(),
+        value.is_compressed(),
+    );
+    assert!(unbatched
+        .validate(&owner.key, *subkey, vcrypto.clone())
+        .is_err());
+}
+
+pub async fn test_batch_limits(vcrypto: CryptoSystemVersion) {
+    let writer = vcrypto.generate_keypair();
+    let owner = vcrypto.generate_keypair();
+
+    // Empty and oversized batches are refused
+    assert!(SignedValueBatch::make_signature(vec![], &owner.key, vcrypto.clone(), writer).is_err());
+    let subkeys: Vec<ValueSubkey> = (0..=MAX_VALUE_BATCH_LEN as ValueSubkey).collect();
+    let values = subkeys
+        .iter()
+        .map(|subkey| BatchedValue {
+            subkey: *subkey,
+            value_data: ValueData::new(vec![], writer.key).unwrap(),
+            compressed: false,
+        })
+        .collect();
+    assert!(SignedValueBatch::make_signature(values, &owner.key, vcrypto.clone(), writer).is_err());
+
+    // Every value must come from the batch writer
+    let other = vcrypto.generate_keypair();
+    let values = vec![BatchedValue {
+        subkey: 0,
+        value_data: ValueData::new(vec![], other.key).unwrap(),
+        compressed: false,
+    }];
+    assert!(SignedValueBatch::new(writer.key, Signature::default(), values).is_err());
+}
+
+pub async fn test_batch_coders(vcrypto: CryptoSystemVersion) {
+    let owner = vcrypto.generate_keypair();
+    let writer = vcrypto.generate_keypair();
+    let key = TypedKey::new(vcrypto.kind(), owner.key);
+    let batch = make_batch(vcrypto.clone(), &owner.key, writer, &[2, 3]);
+
+    let set_value_batch_q = RPCOperationSetValueBatchQ::new(key, batch.clone(), None, 7);
+    let mut message = ::capnp::message::Builder::new_default();
+    let mut builder = message.init_root::<veilid_capnp::operation_set_value_batch_q::Builder>();
+    set_value_batch_q.encode(&mut builder).unwrap();
+    let data = builder_to_vec(message).unwrap();
+
+    let reader = ::capnp::serialize_packed::read_message(
+        data.as_slice(),
+        ::capnp::message::ReaderOptions::new(),
+    )
+    .unwrap();
+    let decoded = RPCOperationSetValueBatchQ::decode(
+        &reader
+            .get_root::<veilid_capnp::operation_set_value_batch_q::Reader>()
+            .unwrap(),
+    )
+    .unwrap();
+    let (decoded_key, decoded_batch, decoded_descriptor, pow_nonce) = decoded.destructure();
+    assert_eq!(decoded_key, key);
+    assert_eq!(decoded_batch, batch);
+    assert!(decoded_descriptor.is_none());
+    assert_eq!(pow_nonce, 7);
+
+    // The batch digest travels with each value it signed
+    let digest = decoded_batch.validate(&owner.key, vcrypto.clone()).unwrap();
+    for (subkey, value) in decoded_batch.into_signed_values(digest) {
+        let mut message = ::capnp::message::Builder::new_default();
+        let mut builder = message.init_root::<veilid_capnp::signed_value_data::Builder>();
+        encode_signed_value_data(&value, &mut builder).unwrap();
+        let data = builder_to_vec(message).unwrap();
+
+        let reader = ::capnp::serialize_packed::read_message(
+            data.as_slice(),
+            ::capnp::message::ReaderOptions::new(),
+        )
+        .unwrap();
+        let decoded = decode_signed_value_data(
+            &reader
+                .get_root::<veilid_capnp::signed_value_data::Reader>()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(decoded, value);
+        decoded
+            .validate(&owner.key, subkey, vcrypto.clone())
+            .unwrap();
+    }
+}
+
+pub async fn test_all() {
+    let (update_callback, config_callback) = setup_veilid_core();
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+
+    let crypto = api.crypto().unwrap();
+    for ck in VALID_CRYPTO_KINDS {
+        let vcrypto = crypto.get(ck).unwrap();
+        test_batch_signature(vcrypto.clone()).await;
+        test_batch_limits(vcrypto.clone()).await;
+        test_batch_coders(vcrypto).await;
+    }
+
+    api.shutdown().await;
+}
@@ -0,0 +1,49 @@
+use super::*;
+
+fn udp_source(a: u8, port: u16) -> SocketAddress {
+    SocketAddress::new(Address::IPV4(Ipv4Addr::new(192, 168, 0, a)), port)
+}
+
+pub async fn test_exact_source() {
+    let expected = udp_source(1, 5150);
+
+    assert!(udp_answer_source_matches(expected, expected, false));
+    assert!(!udp_answer_source_matches(
+        expected,
+        udp_source(1, 5151),
+        false
+    ));
+    assert!(!udp_answer_source_matches(
+        expected,
+        udp_source(2, 5150),
+        false
+    ));
+
+    // IPv4-mapped IPv6 sources are the same address
+    let mapped = SocketAddress::new(
+        Address::IPV6(Ipv4Addr::new(192, 168, 0, 1).to_ipv6_mapped()),
+        5150,
+    );
+    assert!(udp_answer_source_matches(expected, mapped, false));
+}
+
+pub async fn test_rebind_source() {
+    let expected = udp_source(1, 5150);
+
+    // A rebound NAT mapping keeps the address but changes the port
+    assert!(udp_answer_source_matches(
+        expected,
+        udp_source(1, 40000),
+        true
+    ));
+    assert!(!udp_answer_source_matches(
+        expected,
+        udp_source(2, 5150),
+        true
+    ));
+}
+
+pub async fn test_all() {
+    test_exact_source().await;
+    test_rebind_source().await;
+}
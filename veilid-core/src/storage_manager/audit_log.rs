@@ -0,0 +1,166 @@
+use super::*;
+
+const STORAGE_MANAGER_AUDIT_LOG: &str = "storage_manager_audit_log";
+
+/// Append-only log of the remote DHT operations served by this node, kept in the table store
+///
+/// Entries are keyed by an increasing index so the oldest ones can be dropped once the log
+/// holds more than `max_entries`.
+pub(super) struct AuditLog {
+    db: TableDB,
+    max_entries: u64,
+    first_index: u64,
+    next_index: u64,
+}
+
+impl AuditLog {
+    pub async fn open(table_store: TableStore, max_entries: u32) -> EyreResult<AuditLog> {
+        let db = table_store.open(STORAGE_MANAGER_AUDIT_LOG, 1).await?;
+
+        // Find the range of entries that are already in the log
+        let mut first_index = u64::MAX;
+        let mut next_index = 0u64;
+        for k in db.get_keys(0).await? {
+            let Ok(k) = <[u8; 8]>::try_from(k.as_slice()) else {
+                continue;
+            };
+            let index = u64::from_be_bytes(k);
+            first_index = first_index.min(index);
+            next_index = next_index.max(index + 1);
+        }
+        if first_index == u64::MAX {
+            first_index = 0;
+        }
+
+        let mut audit_log = AuditLog {
+            db,
+            max_entries: max_entries as u64,
+            first_index,
+            next_index,
+        };
+        // The limit may have been lowered since the log was written
+        audit_log.trim().await?;
+
+        Ok(audit_log)
+    }
+
+    /// Add an entry to the end of the log, dropping the oldest entries if the log is full
+    pub async fn append(&mut self, entry: &DHTAuditLogEntry) -> EyreResult<()> {
+        self.db
+            .store_json(0, &self.next_index.to_be_bytes(), entry)
+            .await?;
+        self.next_index += 1;
+        self.trim().await
+    }
+
+    /// Get the entries in the log, oldest first, optionally only those for one record and
+    /// those served at or after a timestamp
+    pub async fn query(
+        &self,
+        opt_key: Option<TypedKey>,
+        since: Option<Timestamp>,
+    ) -> EyreResult<Vec<DHTAuditLogEntry>> {
+        let mut out = Vec::new();
+        for index in self.first_index..self.next_index {
+            let Some(entry) = self
+                .db
+                .load_json::<DHTAuditLogEntry>(0, &index.to_be_bytes())
+                .await?
+            else {
+                continue;
+            };
+            if opt_key.map(|k| k != entry.key).unwrap_or_default() {
+                continue;
+            }
+            if since.map(|s| entry.timestamp < s).unwrap_or_default() {
+                continue;
+            }
+            out.push(entry);
+        }
+        Ok(out)
+    }
+
+    /// Take all of the entries out of the log, oldest first, and start over with an empty log
+    pub async fn rotate(&mut self) -> EyreResult<Vec<DHTAuditLogEntry>> {
+        let entries = self.query(None, None).await?;
+
+        let tx = self.db.transact();
+        for index in self.first_index..self.next_index {
+            tx.delete(0, &index.to_be_bytes())?;
+        }
+        tx.commit().await?;
+        self.first_index = self.next_index;
+
+        Ok(entries)
+    }
+
+    async fn trim(&mut self) -> EyreResult<()> {
+        if self.next_index - self.first_index <= self.max_entries {
+            return Ok(());
+        }
+        let tx = self.db.transact();
+        while self.next_index - self.first_index > self.max_entries {
+            tx.delete(0, &self.first_index.to_be_bytes())?;
+            self.first_index += 1;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+impl StorageManager {
+    /// Record a remote DHT operation served by this node, if the audit log is enabled
+    pub async fn audit_inbound_operation(
+        &self,
+        operation: DHTAuditOperation,
+        requester_hint: TypedKey,
+        key: TypedKey,
+        subkey: ValueSubkey,
+        result: DHTAuditResult,
+        seq: Option<ValueSeqNum>,
+    ) {
+        let Ok(mut inner) = self.lock().await else {
+            return;
+        };
+        let Some(audit_log) = inner.audit_log.as_mut() else {
+            return;
+        };
+        let entry = DHTAuditLogEntry {
+            timestamp: get_aligned_timestamp(),
+            operation,
+            requester_hint,
+            key,
+            subkey,
+            result,
+            seq,
+        };
+        if let Err(e) = audit_log.append(&entry).await {
+            log_stor!(error "failed to append to dht audit log: {}", e);
+        }
+    }
+
+    /// Get the remote DHT operations served by this node from the audit log, oldest first
+    pub async fn query_audit_log(
+        &self,
+        opt_key: Option<TypedKey>,
+        since: Option<Timestamp>,
+    ) -> VeilidAPIResult<Vec<DHTAuditLogEntry>> {
+        let inner = self.lock().await?;
+        let Some(audit_log) = inner.audit_log.as_ref() else {
+            apibail_generic!("dht audit log is not enabled");
+        };
+        audit_log
+            .query(opt_key, since)
+            .await
+            .map_err(VeilidAPIError::internal)
+    }
+
+    /// Take all of the entries out of the audit log, oldest first, and start a new log
+    pub async fn rotate_audit_log(&self) -> VeilidAPIResult<Vec<DHTAuditLogEntry>> {
+        let mut inner = self.lock().await?;
+        let Some(audit_log) = inner.audit_log.as_mut() else {
+            apibail_generic!("dht audit log is not enabled");
+        };
+        audit_log.rotate().await.map_err(VeilidAPIError::internal)
+    }
+}
@@ -0,0 +1,222 @@
+use super::*;
+
+/// How many ways a differing subkey range is split for the next round of digests
+const DIGEST_SPLIT_FACTOR: usize = 16;
+/// Differing ranges at most this long are inspected directly rather than split further
+const DIGEST_LEAF_RANGE_LEN: u64 = 32;
+
+impl StorageManager {
+    /// Bring an opened record up to date with one of its value nodes, fetching only stale subkeys.
+    /// Subkey ranges are compared by digests of their sequence numbers, and ranges that differ are
+    /// split and compared again, so the differing subkeys are found in a logarithmic number of round trips.
+    /// Returns the subkeys that were refreshed from the network.
+    pub async fn sync_record(&self, key: TypedKey) -> VeilidAPIResult<ValueSubkeyRangeSet> {
+        let inner = self.lock().await?;
+
+        // Get cryptosystem
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+
+        let safety_selection = {
+            let Some(opened_record) = inner.opened_records.get(&key) else {
+                apibail_generic!("record not open");
+            };
+            opened_record.safety_selection()
+        };
+        let descriptor = inner.get_local_descriptor(key)?;
+        let schema = descriptor.schema()?;
+
+        // Only nodes that have returned values for this record and understand digests are useful
+        let digest_nodes: Vec<NodeRef> = inner
+            .get_value_nodes(key)?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|x| {
+                x.node_info(RoutingDomain::PublicInternet)
+                    .map(|ni| ni.has_capabilities(&[CAP_DHT, CAP_DHT_DIGEST]))
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        // Get rpc processor and drop mutex so we don't block while talking to the network
+        let Some(rpc_processor) = Self::online_ready_inner(&inner) else {
+            apibail_try_again!("offline, try again later");
+        };
+        drop(inner);
+
+        if digest_nodes.is_empty() {
+            apibail_try_again!("no value nodes for this record support digests");
+        }
+
+        // Find the differing subkeys with the first node that can answer
+        let mut opt_differing = None;
+        for node in digest_nodes {
+            let dest = Destination::direct(node.clone()).with_safety(safety_selection);
+            match self
+                .find_differing_subkeys(rpc_processor.clone(), dest.clone(), key, &vcrypto, &schema)
+                .await
+            {
+                Ok(Some(differing)) => {
+                    opt_differing = Some((dest, differing));
+                    break;
+                }
+                Ok(None) => {
+                    log_stor!(debug "Digest sync of {} with {} unavailable", key, node);
+                }
+                Err(e) => {
+                    log_stor!(debug "Digest sync of {} with {} failed: {}", key, node, e);
+                }
+            }
+        }
+        let Some((dest, differing)) = opt_differing else {
+            apibail_try_again!("no value node could be synced with");
+        };
+
+        // Find out which side is newer for the differing subkeys
+        let mut stale = ValueSubkeyRangeSet::new();
+        let differing_subkeys = differing.iter().collect::<Vec<_>>();
+        for chunk in differing_subkeys.chunks(MAX_INSPECT_VALUE_A_SEQS_LEN) {
+            let mut subkeys = ValueSubkeyRangeSet::new();
+            for subkey in chunk {
+                subkeys.insert(*subkey);
+            }
+            let local_seqs = {
+                let mut inner = self.lock().await?;
+                inner
+                    .handle_inspect_local_value(key, subkeys.clone(), false)
+                    .await?
+                    .seqs
+            };
+            let answer = match rpc_processor
+                .clone()
+                .rpc_call_inspect_value(
+                    dest.clone(),
+                    key,
+                    subkeys.clone(),
+                    Some((*descriptor).clone()),
+                )
+                .await
+                .map_err(VeilidAPIError::from)?
+            {
+                NetworkResult::Value(v) => v,
+                nr => {
+                    apibail_try_again!(format!("inspecting differing subkeys failed: {}", nr));
+                }
+            };
+            for ((subkey, local_seq), remote_seq) in
+                subkeys.iter().zip(local_seqs).zip(answer.answer.seqs)
+            {
+                // Unwritten subkeys are reported as ValueSeqNum::MAX
+                if remote_seq != ValueSeqNum::MAX
+                    && (local_seq == ValueSeqNum::MAX || remote_seq > local_seq)
+                {
+                    stale.insert(subkey);
+                }
+            }
+        }
+
+        // Fetch only the stale subkeys
+        for subkey in stale.iter() {
+            self.get_value(key, subkey, true).await?;
+        }
+
+        log_stor!(debug "Synced {}: {} differing, {} refreshed", key, differing.len(), stale.len());
+
+        Ok(stale)
+    }
+
+    /// Compare digests with a node, splitting differing ranges until they are small enough to inspect
+    /// Returns None if the node does not have the record
+    async fn find_differing_subkeys(
+        &self,
+        rpc_processor: RPCProcessor,
+        dest: Destination,
+        key: TypedKey,
+        vcrypto: &CryptoSystemVersion,
+        schema: &DHTSchema,
+    ) -> VeilidAPIResult<Option<ValueSubkeyRangeSet>> {
+        let mut differing = ValueSubkeyRangeSet::new();
+        let mut pending: Vec<SubkeyDigestRange> = vec![(0, schema.max_subkey())];
+
+        while !pending.is_empty() {
+            let mut next_pending = Vec::new();
+            for ranges in pending.chunks(MAX_DIGEST_VALUE_Q_SUBKEY_RANGES_LEN) {
+                let local_digests = {
+                    let mut inner = self.lock().await?;
+                    inner
+                        .handle_digest_local_value(key, ranges, vcrypto.clone())
+                        .await?
+                        .ok_or_else(|| VeilidAPIError::generic("record not found locally"))?
+                };
+
+                let answer = match rpc_processor
+                    .clone()
+                    .rpc_call_digest_value(dest.clone(), key, ranges.to_vec())
+                    .await
+                    .map_err(VeilidAPIError::from)?
+                {
+                    NetworkResult::Value(v) => v,
+                    nr => {
+                        apibail_try_again!(format!("digest value failed: {}", nr));
+                    }
+                };
+                if answer.answer.digests.is_empty() {
+                    return Ok(None);
+                }
+
+                for ((range, local), remote) in
+                    ranges.iter().zip(local_digests).zip(answer.answer.digests)
+                {
+                    if local == remote {
+                        continue;
+                    }
+                    let (start, end) = *range;
+                    if u64::from(end - start) < DIGEST_LEAF_RANGE_LEN {
+                        differing.ranges_insert(start..=end);
+                    } else {
+                        next_pending.extend(split_subkey_digest_range(*range, DIGEST_SPLIT_FACTOR));
+                    }
+                }
+            }
+            pending = next_pending;
+        }
+
+        // Only subkeys that exist in the schema can differ
+        Ok(Some(schema.truncate_subkeys(&differing, None)))
+    }
+
+    /// Handle a received 'Digest Value' query
+    pub async fn inbound_digest_value(
+        &self,
+        key: TypedKey,
+        ranges: Vec<SubkeyDigestRange>,
+    ) -> VeilidAPIResult<NetworkResult<Vec<HashDigest>>> {
+        let mut inner = self.lock().await?;
+
+        // Expired records are not served
+        if inner.is_record_expired(key, get_aligned_timestamp()) {
+            return Ok(NetworkResult::value(Vec::new()));
+        }
+
+        // Get cryptosystem
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+            return Ok(NetworkResult::invalid_message("unsupported cryptosystem"));
+        };
+
+        // See if this is a local or remote record
+        let opt_digests = match inner
+            .handle_digest_local_value(key, &ranges, vcrypto.clone())
+            .await?
+        {
+            Some(digests) => Some(digests),
+            None => {
+                inner
+                    .handle_digest_remote_value(key, &ranges, vcrypto)
+                    .await?
+            }
+        };
+
+        Ok(NetworkResult::value(opt_digests.unwrap_or_default()))
+    }
+}
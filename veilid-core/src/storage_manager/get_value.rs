@@ -43,6 +43,11 @@ impl StorageManager {
             )
         };
 
+        // Get cryptosystem
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+
         // Make do-get-value answer context
         let schema = if let Some(d) = &last_get_result.opt_descriptor {
             Some(d.schema()?)
@@ -60,6 +65,7 @@ impl StorageManager {
         let call_routine = |next_node: NodeRef| {
             let rpc_processor = rpc_processor.clone();
             let context = context.clone();
+            let vcrypto = vcrypto.clone();
             let last_descriptor = last_get_result.opt_descriptor.clone();
             async move {
                 let gva = network_result_try!(
@@ -70,6 +76,7 @@ impl StorageManager {
                             key,
                             subkey,
                             last_descriptor.map(|x| (*x).clone()),
+                            false,
                         )
                         .await?
                 );
@@ -106,9 +113,11 @@ impl StorageManager {
 
                     // Validate with schema
                     if !schema.check_subkey_value_data(
+                        vcrypto,
                         descriptor.owner(),
                         subkey,
                         value.value_data(),
+                        None,
                     ) {
                         // Validation failed, ignore this value
                         // Move to the next node
@@ -219,6 +228,11 @@ impl StorageManager {
     ) -> VeilidAPIResult<NetworkResult<GetResult>> {
         let mut inner = self.lock().await?;
 
+        // Expired records are not served
+        if inner.is_record_expired(key, get_aligned_timestamp()) {
+            return Ok(NetworkResult::value(GetResult::default()));
+        }
+
         // See if this is a remote or local value
         let (_is_local, last_get_result) = {
             // See if the subkey we are getting has a last known local value
@@ -311,6 +311,11 @@ impl StorageManager {
     ) -> VeilidAPIResult<NetworkResult<InspectResult>> {
         let mut inner = self.lock().await?;
 
+        // Expired records are not served
+        if inner.is_record_expired(key, get_aligned_timestamp()) {
+            return Ok(NetworkResult::value(InspectResult::default()));
+        }
+
         // See if this is a remote or local value
         let (_is_local, inspect_result) = {
             // See if the subkey we are getting has a last known local value
@@ -1,14 +1,23 @@
+mod audit_log;
 mod debug;
+mod digest_value;
 mod get_value;
 mod inspect_value;
+mod record_expiration;
+mod record_names;
 mod record_store;
+mod record_versions;
 mod set_value;
+mod set_value_batch;
 mod storage_manager_inner;
 mod tasks;
 mod types;
 mod watch_value;
 
+pub mod tests;
+
 use super::*;
+use audit_log::*;
 use network_manager::*;
 use record_store::*;
 use routing_table::*;
@@ -32,11 +41,16 @@ const SEND_VALUE_CHANGES_INTERVAL_SECS: u32 = 1;
 const CHECK_ACTIVE_WATCHES_INTERVAL_SECS: u32 = 1;
 /// Frequency to check for expired server-side watched records
 const CHECK_WATCHED_RECORDS_INTERVAL_SECS: u32 = 1;
+/// The most version links followed when resolving a record to its newest version
+const MAX_RECORD_VERSION_LINKS: usize = 16;
+/// Prefix hashed with a name to derive the schema member that binds the name to its record
+const RECORD_NAME_HASH_PREFIX: &[u8] = b"veilid-name:";
 
 #[derive(Debug, Clone)]
 /// A single 'value changed' message to send
 struct ValueChangedInfo {
     target: Target,
+    target_route_blob: Option<Vec<u8>>,
     key: TypedKey,
     subkeys: ValueSubkeyRangeSet,
     count: u32,
@@ -127,6 +141,13 @@ impl StorageManager {
 
     #[instrument(level = "debug", skip_all, err)]
     pub async fn init(&self, update_callback: UpdateCallback) -> EyreResult<()> {
+        // Bootstrap nodes don't store records, leave the storage manager uninitialized
+        // so that all record operations fail
+        if self.unlocked_inner.config.get().network.role.is_bootstrap() {
+            log_stor!(debug "storage manager disabled in bootstrap role");
+            return Ok(());
+        }
+
         log_stor!(debug "startup storage manager");
 
         let mut inner = self.inner.lock().await;
@@ -196,24 +217,56 @@ impl StorageManager {
         Ok(Self::online_ready_inner(&inner))
     }
 
+    /// In low power mode writes are batched up and only sent when the node wakes
+    fn low_power_inner(inner: &StorageManagerInner) -> bool {
+        inner
+            .opt_rpc_processor
+            .as_ref()
+            .map(|r| r.network_manager().is_low_power())
+            .unwrap_or_default()
+    }
+
+    async fn is_low_power(&self) -> EyreResult<bool> {
+        let inner = self.lock().await?;
+        Ok(Self::low_power_inner(&inner))
+    }
+
     async fn has_offline_subkey_writes(&self) -> EyreResult<bool> {
         let inner = self.lock().await?;
         Ok(!inner.offline_subkey_writes.is_empty())
     }
 
     /// Create a local record from scratch with a new owner key, open it, and return the opened descriptor
+    /// Records with an expiration stop being served by storage nodes once it passes, unless the owner extends it
     pub async fn create_record(
         &self,
         kind: CryptoKind,
         schema: DHTSchema,
+        expiration: Option<Timestamp>,
         safety_selection: SafetySelection,
     ) -> VeilidAPIResult<DHTRecordDescriptor> {
         let mut inner = self.lock().await?;
         schema.validate()?;
+        if let Some(expiration) = expiration {
+            if expiration <= get_aligned_timestamp() {
+                apibail_invalid_argument!(
+                    "expiration has already passed",
+                    "expiration",
+                    expiration
+                );
+            }
+        }
 
         // Create a new owned local record from scratch
         let (key, owner) = inner
-            .create_new_owned_local_record(kind, schema, safety_selection)
+            .create_new_owned_local_record(
+                kind,
+                schema,
+                None,
+                0,
+                expiration.unwrap_or_default(),
+                safety_selection,
+            )
             .await?;
 
         // Now that the record is made we should always succeed to open the existing record
@@ -355,13 +408,26 @@ impl StorageManager {
         key: TypedKey,
         subkey: ValueSubkey,
         force_refresh: bool,
+    ) -> VeilidAPIResult<Option<ValueData>> {
+        self.get_value_with_safety(key, subkey, force_refresh, None)
+            .await
+    }
+
+    /// Get the value of a subkey from an opened local record
+    /// If a safety selection is given it is used instead of the one the record was opened with
+    pub async fn get_value_with_safety(
+        &self,
+        key: TypedKey,
+        subkey: ValueSubkey,
+        force_refresh: bool,
+        opt_safety_selection: Option<SafetySelection>,
     ) -> VeilidAPIResult<Option<ValueData>> {
         let mut inner = self.lock().await?;
         let safety_selection = {
             let Some(opened_record) = inner.opened_records.get(&key) else {
                 apibail_generic!("record not open");
             };
-            opened_record.safety_selection()
+            opt_safety_selection.unwrap_or(opened_record.safety_selection())
         };
 
         // See if the requested subkey is our local record store
@@ -370,7 +436,7 @@ impl StorageManager {
         // Return the existing value if we have one unless we are forcing a refresh
         if !force_refresh {
             if let Some(last_get_result_value) = last_get_result.opt_value {
-                return Ok(Some(last_get_result_value.value_data().clone()));
+                return Ok(Some(last_get_result_value.decompressed_value_data()?));
             }
         }
 
@@ -380,7 +446,7 @@ impl StorageManager {
         let Some(rpc_processor) = Self::online_ready_inner(&inner) else {
             // Return the existing value if we have one if we aren't online
             if let Some(last_get_result_value) = last_get_result.opt_value {
-                return Ok(Some(last_get_result_value.value_data().clone()));
+                return Ok(Some(last_get_result_value.decompressed_value_data()?));
             }
             apibail_try_again!("offline, try again later");
         };
@@ -389,7 +455,7 @@ impl StorageManager {
         drop(inner);
 
         // May have last descriptor / value
-        // Use the safety selection we opened the record with
+        // Use the safety selection we opened the record with, unless one was given
         let opt_last_seq = last_get_result
             .opt_value
             .as_ref()
@@ -429,7 +495,7 @@ impl StorageManager {
                 )
                 .await?;
         }
-        Ok(Some(get_result_value.value_data().clone()))
+        Ok(Some(get_result_value.decompressed_value_data()?))
     }
 
     /// Set the value of a subkey on an opened local record
@@ -439,6 +505,20 @@ impl StorageManager {
         subkey: ValueSubkey,
         data: Vec<u8>,
         writer: Option<KeyPair>,
+    ) -> VeilidAPIResult<Option<ValueData>> {
+        self.set_value_with_safety(key, subkey, data, writer, None)
+            .await
+    }
+
+    /// Set the value of a subkey on an opened local record
+    /// If a safety selection is given it is used instead of the one the record was opened with
+    pub async fn set_value_with_safety(
+        &self,
+        key: TypedKey,
+        subkey: ValueSubkey,
+        data: Vec<u8>,
+        writer: Option<KeyPair>,
+        opt_safety_selection: Option<SafetySelection>,
     ) -> VeilidAPIResult<Option<ValueData>> {
         let mut inner = self.lock().await?;
 
@@ -452,7 +532,7 @@ impl StorageManager {
                 apibail_generic!("record not open");
             };
             (
-                opened_record.safety_selection(),
+                opt_safety_selection.unwrap_or(opened_record.safety_selection()),
                 opened_record.writer().cloned(),
             )
         };
@@ -472,13 +552,15 @@ impl StorageManager {
         let Some(descriptor) = last_get_result.opt_descriptor else {
             apibail_generic!("must have a descriptor");
         };
+        if descriptor.is_expired(get_aligned_timestamp()) {
+            apibail_generic!("record has expired");
+        }
         let schema = descriptor.schema()?;
 
         // Make new subkey data
         let value_data = if let Some(last_signed_value_data) = last_get_result.opt_value {
-            if last_signed_value_data.value_data().data() == data
-                && last_signed_value_data.value_data().writer() == &writer.key
-            {
+            let last_value_data = last_signed_value_data.decompressed_value_data()?;
+            if last_value_data.data() == data && last_value_data.writer() == &writer.key {
                 // Data and writer is the same, nothing is changing,
                 // just return that we set it, but no network activity needs to happen
                 return Ok(None);
@@ -490,14 +572,32 @@ impl StorageManager {
         };
 
         // Validate with schema
-        if !schema.check_subkey_value_data(descriptor.owner(), subkey, &value_data) {
+        let written_subkeys = inner.get_local_written_subkeys(key)?;
+        if !schema.check_subkey_value_data(
+            vcrypto.clone(),
+            descriptor.owner(),
+            subkey,
+            &value_data,
+            written_subkeys.as_ref(),
+        ) {
             // Validation failed, ignore this value
             apibail_generic!("failed schema validation");
         }
 
+        // Compress the value data if enabled, except where co-owners signed the data itself
+        let (value_data, compressed) =
+            if self.unlocked_inner.config.get().network.dht.compress_values
+                && !schema.is_cosigned_subkey(subkey)
+            {
+                SignedValueData::compress_value_data(value_data)?
+            } else {
+                (value_data, false)
+            };
+
         // Sign the new value data with the writer
         let signed_value_data = Arc::new(SignedValueData::make_signature(
             value_data,
+            compressed,
             descriptor.owner(),
             subkey,
             vcrypto,
@@ -516,7 +616,13 @@ impl StorageManager {
             .await?;
 
         // Get rpc processor and drop mutex so we don't block while getting the value from the network
-        let Some(rpc_processor) = Self::online_ready_inner(&inner) else {
+        // In low power mode the write waits with the offline writes for the next wake
+        let opt_rpc_processor = if Self::low_power_inner(&inner) {
+            None
+        } else {
+            Self::online_ready_inner(&inner)
+        };
+        let Some(rpc_processor) = opt_rpc_processor else {
             log_stor!(debug "Writing subkey offline: {}:{} len={}", key, subkey, signed_value_data.value_data().data().len() );
             // Add to offline writes to flush
             inner
@@ -537,7 +643,7 @@ impl StorageManager {
 
         log_stor!(debug "Writing subkey to the network: {}:{} len={}", key, subkey, signed_value_data.value_data().data().len() );
 
-        // Use the safety selection we opened the record with
+        // Use the safety selection we opened the record with, unless one was given
         let result = self
             .outbound_set_value(
                 rpc_processor,
@@ -569,13 +675,214 @@ impl StorageManager {
                 )
                 .await?;
 
-            return Ok(Some(result.signed_value_data.value_data().clone()));
+            return Ok(Some(result.signed_value_data.decompressed_value_data()?));
         }
 
         // If the original value was set, return None
         Ok(None)
     }
 
+    /// Set the values of several subkeys at once, signed once as a batch and sent to each node once
+    /// Returns one entry per subkey in increasing subkey order, with the newer value found if the
+    /// value passed in was not the newest
+    pub async fn set_values(
+        &self,
+        key: TypedKey,
+        mut values: Vec<(ValueSubkey, Vec<u8>)>,
+        writer: Option<KeyPair>,
+    ) -> VeilidAPIResult<Vec<(ValueSubkey, Option<ValueData>)>> {
+        let mut inner = self.lock().await?;
+
+        // Get cryptosystem
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+
+        let (safety_selection, opt_writer) = {
+            let Some(opened_record) = inner.opened_records.get(&key) else {
+                apibail_generic!("record not open");
+            };
+            (
+                opened_record.safety_selection(),
+                opened_record.writer().cloned(),
+            )
+        };
+
+        // Use the specified writer, or if not specified, the default writer when the record was opened
+        let opt_writer = writer.or(opt_writer);
+
+        // If we don't have a writer then we can't write
+        let Some(writer) = opt_writer else {
+            apibail_generic!("value is not writable");
+        };
+
+        // Batches are signed over their subkeys in increasing order
+        values.sort_by_key(|(subkey, _)| *subkey);
+        if values.windows(2).any(|w| w[0].0 == w[1].0) {
+            apibail_invalid_argument!("subkey written more than once", "values", values.len());
+        }
+        if values.len() > MAX_VALUE_BATCH_LEN {
+            apibail_invalid_argument!("too many subkeys in batch", "values", values.len());
+        }
+
+        let descriptor = inner.get_local_descriptor(key)?;
+        if descriptor.is_expired(get_aligned_timestamp()) {
+            apibail_generic!("record has expired");
+        }
+        let schema = descriptor.schema()?;
+        let compress_values = self.unlocked_inner.config.get().network.dht.compress_values;
+
+        // Make new subkey data for each subkey that is changing
+        let mut results = Vec::with_capacity(values.len());
+        let mut batched_values = Vec::with_capacity(values.len());
+        let mut written_subkeys = inner.get_local_written_subkeys(key)?.unwrap_or_default();
+        for (subkey, data) in values {
+            results.push((subkey, None));
+
+            let last_get_result = inner.handle_get_local_value(key, subkey, false).await?;
+            let value_data = if let Some(last_signed_value_data) = last_get_result.opt_value {
+                let last_value_data = last_signed_value_data.decompressed_value_data()?;
+                if last_value_data.data() == data && last_value_data.writer() == &writer.key {
+                    // Data and writer is the same, nothing is changing for this subkey
+                    continue;
+                }
+                let seq = last_signed_value_data.value_data().seq();
+                ValueData::new_with_seq(seq + 1, data, writer.key)?
+            } else {
+                ValueData::new(data, writer.key)?
+            };
+
+            // Validate with schema, counting the subkeys written earlier in this batch
+            if !schema.check_subkey_value_data(
+                vcrypto.clone(),
+                descriptor.owner(),
+                subkey,
+                &value_data,
+                Some(&written_subkeys),
+            ) {
+                // Validation failed, ignore this batch
+                apibail_generic!("failed schema validation");
+            }
+            written_subkeys.insert(subkey);
+
+            // Compress the value data if enabled, except where co-owners signed the data itself
+            let (value_data, compressed) = if compress_values && !schema.is_cosigned_subkey(subkey)
+            {
+                SignedValueData::compress_value_data(value_data)?
+            } else {
+                (value_data, false)
+            };
+
+            batched_values.push(BatchedValue {
+                subkey,
+                value_data,
+                compressed,
+            });
+        }
+
+        // Nothing is changing, no network activity needs to happen
+        if batched_values.is_empty() {
+            return Ok(results);
+        }
+
+        // Sign the whole batch once with the writer
+        let batch = SignedValueBatch::make_signature(
+            batched_values,
+            descriptor.owner(),
+            vcrypto.clone(),
+            writer,
+        )?;
+        let digest = batch.validate(descriptor.owner(), vcrypto)?;
+        let signed_values: BTreeMap<ValueSubkey, Arc<SignedValueData>> = batch
+            .clone()
+            .into_signed_values(digest)
+            .into_iter()
+            .map(|(subkey, value)| (subkey, Arc::new(value)))
+            .collect();
+        let subkeys = batch.subkeys();
+
+        // Write the values locally first
+        log_stor!(debug "Writing subkeys locally: {}:{}", key, subkeys);
+        for (subkey, signed_value_data) in &signed_values {
+            inner
+                .handle_set_local_value(
+                    key,
+                    *subkey,
+                    signed_value_data.clone(),
+                    WatchUpdateMode::NoUpdate,
+                )
+                .await?;
+        }
+
+        // Get rpc processor and drop mutex so we don't block while setting the values on the network
+        // In low power mode the writes wait with the offline writes for the next wake
+        let opt_rpc_processor = if Self::low_power_inner(&inner) {
+            None
+        } else {
+            Self::online_ready_inner(&inner)
+        };
+        let Some(rpc_processor) = opt_rpc_processor else {
+            log_stor!(debug "Writing subkeys offline: {}:{}", key, subkeys);
+            // Add to offline writes to flush
+            inner
+                .offline_subkey_writes
+                .entry(key)
+                .and_modify(|x| {
+                    x.subkeys = x.subkeys.union(&subkeys);
+                })
+                .or_insert(OfflineSubkeyWrite {
+                    safety_selection,
+                    subkeys,
+                });
+            return Ok(results);
+        };
+
+        // Drop the lock for network access
+        drop(inner);
+
+        log_stor!(debug "Writing subkeys to the network: {}:{}", key, subkeys);
+
+        // Use the safety selection we opened the record with
+        let result = self
+            .outbound_set_value_batch(
+                rpc_processor,
+                key,
+                safety_selection,
+                batch,
+                signed_values.clone(),
+                descriptor,
+            )
+            .await?;
+
+        // Keep the list of nodes that returned a value for later reference
+        let mut inner = self.lock().await?;
+        inner.process_fanout_results(
+            key,
+            signed_values
+                .keys()
+                .map(|subkey| (*subkey, &result.fanout_result)),
+            true,
+        )?;
+
+        // Record any newer values and send updates since they are different than what we just set
+        for (subkey, new_value) in result.signed_values {
+            let Some(signed_value_data) = signed_values.get(&subkey) else {
+                continue;
+            };
+            if new_value.value_data() == signed_value_data.value_data() {
+                continue;
+            }
+            inner
+                .handle_set_local_value(key, subkey, new_value.clone(), WatchUpdateMode::UpdateAll)
+                .await?;
+            if let Some(r) = results.iter_mut().find(|(s, _)| *s == subkey) {
+                r.1 = Some(new_value.decompressed_value_data()?);
+            }
+        }
+
+        Ok(results)
+    }
+
     /// Create,update or cancel an outbound watch to a DHT value
     pub async fn watch_values(
         &self,
@@ -757,6 +1064,48 @@ impl StorageManager {
         Ok(true)
     }
 
+    /// Get how much storage space the local and remote record stores are using
+    pub async fn storage_usage(&self) -> VeilidAPIResult<DHTStorageUsage> {
+        let inner = self.lock().await?;
+        let mut usage = DHTStorageUsage::default();
+        if let Some(local_record_store) = &inner.local_record_store {
+            let (record_count, used, _, _) = local_record_store.storage_usage();
+            usage.local_record_count = record_count as u32;
+            usage.local_used = ByteCount::new(used);
+        }
+        if let Some(remote_record_store) = &inner.remote_record_store {
+            let (record_count, used, high_watermark, limit) = remote_record_store.storage_usage();
+            usage.remote_record_count = record_count as u32;
+            usage.remote_used = ByteCount::new(used);
+            usage.remote_high_watermark = high_watermark.map(ByteCount::new);
+            usage.remote_limit = limit.map(ByteCount::new);
+        }
+        Ok(usage)
+    }
+
+    /// Get the pending offline writes, open records and storage space used, as reported to the application
+    pub async fn status(&self) -> VeilidAPIResult<StorageManagerStatus> {
+        let inner = self.lock().await?;
+        let mut status = StorageManagerStatus {
+            pending_offline_writes: inner
+                .offline_subkey_writes
+                .values()
+                .map(|x| x.subkeys.len() as u64)
+                .sum::<u64>() as u32,
+            open_record_count: inner.opened_records.len() as u32,
+            ..Default::default()
+        };
+        if let Some(local_record_store) = &inner.local_record_store {
+            let (_, used, _, _) = local_record_store.storage_usage();
+            status.local_used = ByteCount::new(used);
+        }
+        if let Some(remote_record_store) = &inner.remote_record_store {
+            let (_, used, _, _) = remote_record_store.storage_usage();
+            status.remote_used = ByteCount::new(used);
+        }
+        Ok(status)
+    }
+
     /// Inspect an opened DHT record for its subkey sequence numbers
     pub async fn inspect_record(
         &self,
@@ -881,6 +1230,16 @@ impl StorageManager {
             }
         };
 
+        // Watches restored after a restart can be for private routes we have since forgotten
+        if let (Target::PrivateRoute(route_id), Some(target_route_blob)) =
+            (vc.target, &vc.target_route_blob)
+        {
+            let rss = rpc_processor.routing_table().route_spec_store();
+            if rss.best_remote_private_route(&route_id).is_none() {
+                rss.import_remote_private_route_blob(target_route_blob.clone())?;
+            }
+        }
+
         let dest = rpc_processor
             .resolve_target_to_destination(
                 vc.target,
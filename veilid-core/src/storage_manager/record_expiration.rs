@@ -0,0 +1,126 @@
+use super::*;
+
+impl StorageManager {
+    /// Extend the expiration of an opened record that we own, or make it never expire with None.
+    /// The descriptor is re-signed with the new expiration, which does not change the record's key,
+    /// and is pushed to the network along with the first subkey we have a value for.
+    pub async fn extend_record_expiration(
+        &self,
+        key: TypedKey,
+        expiration: Option<Timestamp>,
+    ) -> VeilidAPIResult<()> {
+        let mut inner = self.lock().await?;
+
+        // Get cryptosystem
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+
+        let (safety_selection, opt_writer) = {
+            let Some(opened_record) = inner.opened_records.get(&key) else {
+                apibail_generic!("record not open");
+            };
+            (
+                opened_record.safety_selection(),
+                opened_record.writer().cloned(),
+            )
+        };
+
+        let old_descriptor = inner.get_local_descriptor(key)?;
+
+        // Only the owner can extend a record
+        let Some(owner) = opt_writer.filter(|w| w.key == *old_descriptor.owner()) else {
+            apibail_generic!("only the owner can extend the expiration of a record");
+        };
+
+        // Re-sign the descriptor with the new expiration, keeping any version link it has
+        let new_descriptor = SignedValueDescriptor::make_signature(
+            *old_descriptor.owner(),
+            old_descriptor.schema_data().to_vec(),
+            old_descriptor.version(),
+            expiration.unwrap_or_default(),
+            vcrypto,
+            owner.secret,
+        )?;
+        let Some(descriptor) = old_descriptor.updated_by(&new_descriptor) else {
+            apibail_generic!("record expiration can only be extended");
+        };
+        let descriptor = Arc::new(descriptor);
+        if descriptor.is_expired(get_aligned_timestamp()) {
+            apibail_generic!("new record expiration has already passed");
+        }
+        inner.update_local_descriptor(key, descriptor.clone())?;
+
+        self.push_local_descriptor(inner, key, safety_selection, descriptor)
+            .await
+    }
+
+    /// Push an updated descriptor of a local record to the network along with the first subkey we have a value for.
+    /// If nothing has been written yet, the descriptor goes out with the first write.
+    pub(super) async fn push_local_descriptor(
+        &self,
+        mut inner: AsyncMutexGuardArc<StorageManagerInner>,
+        key: TypedKey,
+        safety_selection: SafetySelection,
+        descriptor: Arc<SignedValueDescriptor>,
+    ) -> VeilidAPIResult<()> {
+        // Find a value to carry the new descriptor to the network
+        let Some(subkey) = inner
+            .get_local_written_subkeys(key)?
+            .and_then(|subkeys| subkeys.first())
+        else {
+            // Nothing has been written yet, the new descriptor goes out with the first write
+            return Ok(());
+        };
+        let Some(signed_value_data) = inner
+            .handle_get_local_value(key, subkey, false)
+            .await?
+            .opt_value
+        else {
+            return Ok(());
+        };
+
+        // Get rpc processor and drop mutex so we don't block while sending to the network
+        let Some(rpc_processor) = Self::online_ready_inner(&inner) else {
+            log_stor!(debug "Pushing record descriptor offline: {}", key);
+            // Add to offline writes to flush
+            inner
+                .offline_subkey_writes
+                .entry(key)
+                .and_modify(|x| {
+                    x.subkeys.insert(subkey);
+                })
+                .or_insert(OfflineSubkeyWrite {
+                    safety_selection,
+                    subkeys: ValueSubkeyRangeSet::single(subkey),
+                });
+            return Ok(());
+        };
+
+        // Drop the lock for network access
+        drop(inner);
+
+        log_stor!(debug "Pushing record descriptor to the network: {}", key);
+
+        let result = self
+            .outbound_set_value(
+                rpc_processor,
+                key,
+                subkey,
+                safety_selection,
+                signed_value_data,
+                descriptor,
+            )
+            .await?;
+
+        // Keep the list of nodes that returned a value for later reference
+        let mut inner = self.lock().await?;
+        inner.process_fanout_results(
+            key,
+            core::iter::once((subkey, &result.fanout_result)),
+            true,
+        )?;
+
+        Ok(())
+    }
+}
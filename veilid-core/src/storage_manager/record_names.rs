@@ -0,0 +1,121 @@
+use super::*;
+
+impl StorageManager {
+    /// Register a name for a record key under an owner, creating the name record if it does not exist yet
+    /// Registering the name again moves it to the new target
+    /// Returns the reference that resolves to the target
+    pub async fn register_name(
+        &self,
+        kind: CryptoKind,
+        name: String,
+        target: TypedKey,
+        owner: KeyPair,
+        safety_selection: SafetySelection,
+    ) -> VeilidAPIResult<DHTNameReference> {
+        let reference = DHTNameReference::new(TypedKey::new(kind, owner.key), name)?;
+        let (key, schema) = self.name_record_key(&reference)?;
+
+        // Open the name record, or create it if nobody has registered the name yet
+        let was_open = self.lock().await?.opened_records.contains_key(&key);
+        if !was_open {
+            match self.open_record(key, Some(owner), safety_selection).await {
+                Ok(_) => {}
+                Err(VeilidAPIError::KeyNotFound { .. }) => {
+                    let mut inner = self.lock().await?;
+                    let (new_key, _) = inner
+                        .create_new_owned_local_record(
+                            kind,
+                            schema,
+                            Some(owner),
+                            0,
+                            Timestamp::default(),
+                            safety_selection,
+                        )
+                        .await?;
+                    inner
+                        .open_existing_record(new_key, Some(owner), safety_selection)
+                        .await?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let res = self
+            .set_value(key, 0, compile_name_target(&target), Some(owner))
+            .await;
+
+        if !was_open {
+            self.close_record(key).await?;
+        }
+        res?;
+
+        Ok(reference)
+    }
+
+    /// Look up the record key a name reference was registered for
+    pub async fn resolve_name(
+        &self,
+        reference: DHTNameReference,
+        safety_selection: SafetySelection,
+    ) -> VeilidAPIResult<TypedKey> {
+        let (key, _) = self.name_record_key(&reference)?;
+
+        let was_open = self.lock().await?.opened_records.contains_key(&key);
+        if !was_open {
+            self.open_record(key, None, safety_selection).await?;
+        }
+
+        let res = self.get_value(key, 0, true).await;
+
+        if !was_open {
+            self.close_record(key).await?;
+        }
+
+        match res? {
+            Some(value_data) => parse_name_target(value_data.data()),
+            None => apibail_key_not_found!(key),
+        }
+    }
+
+    /// Derive the key and schema of the record holding a name
+    /// The name is bound to the record by a schema member derived from the hash of the name,
+    /// which has no subkeys of its own, so only the owner can write the single subkey holding the target
+    fn name_record_key(
+        &self,
+        reference: &DHTNameReference,
+    ) -> VeilidAPIResult<(TypedKey, DHTSchema)> {
+        let owner = reference.owner();
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(owner.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+
+        let mut name_data = RECORD_NAME_HASH_PREFIX.to_vec();
+        name_data.extend_from_slice(reference.name().as_bytes());
+        let name_member = DHTSchemaSMPLMember {
+            m_key: vcrypto.generate_hash(&name_data),
+            m_cnt: 0,
+        };
+        let schema = DHTSchema::smpl(1, vec![name_member])?;
+
+        let key = StorageManagerInner::derive_key(vcrypto, &owner.value, &schema.compile(), 0);
+        Ok((key, schema))
+    }
+}
+
+/// Encode the target of a name as stored in its name record
+fn compile_name_target(target: &TypedKey) -> Vec<u8> {
+    let mut out = Vec::<u8>::with_capacity(4 + PUBLIC_KEY_LENGTH);
+    out.extend_from_slice(&target.kind.0);
+    out.extend_from_slice(&target.value.bytes);
+    out
+}
+
+/// Decode the target of a name from its name record
+fn parse_name_target(b: &[u8]) -> VeilidAPIResult<TypedKey> {
+    if b.len() != 4 + PUBLIC_KEY_LENGTH {
+        apibail_generic!("invalid name target size");
+    }
+    let kind = FourCC::try_from(&b[0..4])?;
+    let value = PublicKey::try_from(&b[4..])?;
+    Ok(TypedKey::new(kind, value))
+}
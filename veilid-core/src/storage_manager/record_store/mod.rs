@@ -30,6 +30,11 @@ pub use watch::{WatchParameters, WatchResult};
 use super::*;
 use hashlink::LruCache;
 
+/// Key in the watch table for the list of watched records
+const WATCHED_RECORDS: &[u8] = b"watched_records";
+/// Number of least recently used records considered when choosing one to evict
+const EVICTION_CANDIDATE_COUNT: usize = 16;
+
 #[derive(Debug, Clone)]
 /// A dead record that is yet to be purged from disk and statistics
 struct DeadRecord<D>
@@ -57,6 +62,8 @@ where
     record_table: Option<TableDB>,
     /// The tabledb used for subkey data
     subkey_table: Option<TableDB>,
+    /// The tabledb used for watches so they survive restarts
+    watch_table: Option<TableDB>,
     /// The in-memory index that keeps track of what records are in the tabledb
     record_index: LruCache<RecordTableKey, Record<D>>,
     /// The in-memory cache of commonly accessed subkey data so we don't have to keep hitting the db
@@ -67,6 +74,10 @@ where
     subkey_cache_total_size: LimitedSize<usize>,
     /// Total storage space of records in the tabledb inclusive of subkey data and structures
     total_storage_space: LimitedSize<u64>,
+    /// Storage space above which records start being evicted
+    storage_high_watermark: Option<u64>,
+    /// Storage space that eviction brings the total storage space back down to
+    storage_low_watermark: Option<u64>,
     /// Records to be removed from the tabledb upon next purge
     dead_records: Vec<DeadRecord<D>>,
     /// The list of records that have changed since last flush to disk (optimization for batched writes)
@@ -75,6 +86,8 @@ where
     watched_records: HashMap<RecordTableKey, WatchList>,
     /// The list of watched records that have changed values since last notification
     changed_watched_values: HashSet<RecordTableKey>,
+    /// If the watches have changed since last flush to disk
+    changed_watches: bool,
     /// A mutex to ensure we handle this concurrently
     purge_dead_records_mutex: Arc<AsyncMutex<()>>,
 }
@@ -111,6 +124,16 @@ where
         let limit_max_storage_space = limits
             .max_storage_space_mb
             .map(|mb| mb as u64 * 1_048_576u64);
+        let watermark = |percent: Option<u8>| {
+            limit_max_storage_space
+                .zip(percent)
+                .filter(|(_, p)| *p != 0 && *p < 100)
+                .map(|(space, p)| space / 100 * p as u64)
+        };
+        let storage_high_watermark = watermark(limits.storage_high_watermark_percent);
+        let storage_low_watermark = storage_high_watermark
+            .and(watermark(limits.storage_low_watermark_percent))
+            .map(|low| low.min(storage_high_watermark.unwrap_or(low)));
 
         Self {
             table_store,
@@ -118,6 +141,7 @@ where
             limits,
             record_table: None,
             subkey_table: None,
+            watch_table: None,
             record_index: LruCache::new(limits.max_records.unwrap_or(usize::MAX)),
             subkey_cache: LruCache::new(subkey_cache_size),
             inspect_cache: InspectCache::new(subkey_cache_size),
@@ -131,11 +155,14 @@ where
                 0,
                 limit_max_storage_space,
             ),
+            storage_high_watermark,
+            storage_low_watermark,
             dead_records: Vec::new(),
             changed_records: HashSet::new(),
             watched_records: HashMap::new(),
             purge_dead_records_mutex: Arc::new(AsyncMutex::new(())),
             changed_watched_values: HashSet::new(),
+            changed_watches: false,
         }
     }
 
@@ -148,6 +175,10 @@ where
             .table_store
             .open(&format!("{}_subkeys", self.name), 1)
             .await?;
+        let watch_table = self
+            .table_store
+            .open(&format!("{}_watches", self.name), 1)
+            .await?;
 
         // Pull record index from table into a vector to ensure we sort them
         let record_table_keys = record_table.get_keys(0).await?;
@@ -203,8 +234,30 @@ where
             self.dead_records.push(dr);
         }
 
+        // Restore the watches on records we still have
+        let watched_records_saved = watch_table
+            .load_json::<Vec<(TypedKey, WatchList)>>(0, WATCHED_RECORDS)
+            .await?
+            .unwrap_or_default();
+        for (key, watch_list) in watched_records_saved {
+            let rtk = RecordTableKey { key };
+            if !self.record_index.contains_key(&rtk) {
+                self.changed_watches = true;
+                continue;
+            }
+            if watch_list.watches.iter().any(|w| !w.changed.is_empty()) {
+                self.changed_watched_values.insert(rtk);
+            }
+            self.watched_records.insert(rtk, watch_list);
+        }
+
         self.record_table = Some(record_table);
         self.subkey_table = Some(subkey_table);
+        self.watch_table = Some(watch_table);
+
+        // Drop any watches that expired while we were not running
+        self.check_watched_records();
+
         Ok(())
     }
 
@@ -361,8 +414,30 @@ where
         }
     }
 
+    async fn flush_changed_watches(&mut self) {
+        if !self.changed_watches {
+            return;
+        }
+        self.changed_watches = false;
+
+        let watch_table = self.watch_table.clone().unwrap();
+
+        let watched_records: Vec<(TypedKey, &WatchList)> = self
+            .watched_records
+            .iter()
+            .map(|(rtk, watch_list)| (rtk.key, watch_list))
+            .collect();
+        if let Err(e) = watch_table
+            .store_json(0, WATCHED_RECORDS, &watched_records)
+            .await
+        {
+            log_stor!(error "failed to save watches: {}", e);
+        }
+    }
+
     pub async fn flush(&mut self) -> EyreResult<()> {
         self.flush_changed_records().await;
+        self.flush_changed_watches().await;
         self.purge_dead_records(true).await;
         Ok(())
     }
@@ -396,6 +471,14 @@ where
         // Update storage space (won't fail due to check_limit above)
         self.total_storage_space.commit().unwrap();
 
+        // If the record index is full, make room by evicting a rarely used record
+        // rather than leaving it to plain LRU order
+        if let Some(max_records) = self.limits.max_records {
+            if self.record_index.len() >= max_records {
+                self.evict_record();
+            }
+        }
+
         // Save to record index
         let mut dead_records = Vec::new();
         if let Some(v) = self.record_index.insert_with_callback(rtk, record, |k, v| {
@@ -422,7 +505,9 @@ where
         };
 
         // Remove watches
-        self.watched_records.remove(&rtk);
+        if self.watched_records.remove(&rtk).is_some() {
+            self.changed_watches = true;
+        }
 
         // Remove watch changes
         self.changed_watched_values.remove(&rtk);
@@ -437,6 +522,112 @@ where
         Ok(())
     }
 
+    /// Pick the record to evict next
+    /// Of the least recently used records, the one read and written the least goes first
+    fn eviction_candidate(&self) -> Option<RecordTableKey> {
+        self.record_index
+            .iter()
+            .take(EVICTION_CANDIDATE_COUNT)
+            .enumerate()
+            .min_by_key(|(n, (_, r))| (r.access_stats().total_count(), *n))
+            .map(|(_, (k, _))| *k)
+    }
+
+    /// Evict a record, returning the amount of space it will free once purged
+    fn evict_record(&mut self) -> Option<usize> {
+        let rtk = self.eviction_candidate()?;
+        let record = self.record_index.remove(&rtk)?;
+        let size = mem::size_of::<RecordTableKey>() + record.total_size();
+        if self.watched_records.remove(&rtk).is_some() {
+            self.changed_watches = true;
+        }
+        self.changed_watched_values.remove(&rtk);
+        self.inspect_cache.invalidate(&rtk.key);
+        self.add_dead_record(rtk, record);
+        Some(size)
+    }
+
+    /// Delete records whose expiration has passed
+    /// Returns the number of records deleted and the space they will free
+    pub async fn delete_expired_records(&mut self, cur_ts: Timestamp) -> Option<(usize, u64)> {
+        let expired: Vec<RecordTableKey> = self
+            .record_index
+            .iter()
+            .filter(|(_, r)| r.descriptor().is_expired(cur_ts))
+            .map(|(k, _)| *k)
+            .collect();
+        if expired.is_empty() {
+            return None;
+        }
+
+        let mut deleted_space = 0u64;
+        for rtk in &expired {
+            let Some(record) = self.record_index.remove(rtk) else {
+                continue;
+            };
+            deleted_space += (mem::size_of::<RecordTableKey>() + record.total_size()) as u64;
+            if self.watched_records.remove(rtk).is_some() {
+                self.changed_watches = true;
+            }
+            self.changed_watched_values.remove(rtk);
+            self.inspect_cache.invalidate(&rtk.key);
+            self.add_dead_record(*rtk, record);
+        }
+        self.purge_dead_records(false).await;
+
+        Some((expired.len(), deleted_space))
+    }
+
+    /// Evict least recently used records once storage space passes the high watermark,
+    /// until it will be back under the low watermark when the dead records are purged
+    /// Returns the number of records evicted and the space they will free
+    pub fn evict_over_high_watermark(&mut self) -> Option<(usize, u64)> {
+        let high_watermark = self.storage_high_watermark?;
+        let low_watermark = self.storage_low_watermark.unwrap_or(high_watermark);
+
+        // Space already waiting to be purged is not ours to reclaim again
+        let pending_dead_space: u64 = self
+            .dead_records
+            .iter()
+            .filter(|dr| dr.in_total_storage)
+            .map(|dr| (mem::size_of::<RecordTableKey>() + dr.record.total_size()) as u64)
+            .sum();
+        let mut storage_space = self
+            .total_storage_space
+            .get()
+            .saturating_sub(pending_dead_space);
+        if storage_space <= high_watermark {
+            return None;
+        }
+
+        let mut evicted_count = 0usize;
+        let mut evicted_space = 0u64;
+        while storage_space > low_watermark {
+            let Some(size) = self.evict_record() else {
+                break;
+            };
+            storage_space = storage_space.saturating_sub(size as u64);
+            evicted_count += 1;
+            evicted_space += size as u64;
+        }
+        if evicted_count == 0 {
+            return None;
+        }
+        Some((evicted_count, evicted_space))
+    }
+
+    /// Get the number of records, the storage space they use, and the storage space limits
+    pub fn storage_usage(&self) -> (usize, u64, Option<u64>, Option<u64>) {
+        (
+            self.record_index.len(),
+            self.total_storage_space.get(),
+            self.storage_high_watermark,
+            self.limits
+                .max_storage_space_mb
+                .map(|mb| mb as u64 * 1_048_576u64),
+        )
+    }
+
     pub(super) fn contains_record(&mut self, key: TypedKey) -> bool {
         let rtk = RecordTableKey { key };
         self.record_index.contains_key(&rtk)
@@ -502,6 +693,21 @@ where
         out
     }
 
+    /// Note a read of a record
+    /// The record moves up the LRU and its access statistics are updated in memory only.
+    /// It is not marked as changed, so reads never cause it to be written back by themselves.
+    fn record_read(&mut self, key: TypedKey, cur_ts: Timestamp) {
+        let rtk = RecordTableKey { key };
+        if let Some(record) = self.record_index.get_mut(&rtk) {
+            record.record_get(cur_ts);
+        }
+    }
+
+    /// Get the number of records waiting to be written back to the record table
+    pub(super) fn changed_record_count(&self) -> usize {
+        self.changed_records.len()
+    }
+
     pub async fn get_subkey(
         &mut self,
         key: TypedKey,
@@ -509,7 +715,7 @@ where
         want_descriptor: bool,
     ) -> VeilidAPIResult<Option<GetResult>> {
         // Get record from index
-        let Some((subkey_count, has_subkey, opt_descriptor)) = self.with_record(key, |record| {
+        let Some((subkey_count, has_subkey, opt_descriptor)) = self.peek_record(key, |record| {
             (
                 record.subkey_count(),
                 record.stored_subkeys().contains(subkey),
@@ -523,6 +729,7 @@ where
             // Record not available
             return Ok(None);
         };
+        self.record_read(key, get_aligned_timestamp());
 
         // Check if the subkey is in range
         if subkey as usize >= subkey_count {
@@ -676,6 +883,7 @@ where
         }
         if changed {
             self.changed_watched_values.insert(rtk);
+            self.changed_watches = true;
         }
     }
 
@@ -773,6 +981,7 @@ where
         self.with_record_mut(key, |record| {
             record.store_subkey(subkey);
             record.set_record_data_size(new_record_data_size);
+            record.record_set(get_aligned_timestamp());
         })
         .expect("record should still be here");
 
@@ -792,11 +1001,6 @@ where
         subkeys: ValueSubkeyRangeSet,
         want_descriptor: bool,
     ) -> VeilidAPIResult<Option<InspectResult>> {
-        // Get subkey table
-        let Some(subkey_table) = self.subkey_table.clone() else {
-            apibail_internal!("record store not initialized");
-        };
-
         // Get record from index
         let Some((subkeys, opt_descriptor)) = self.with_record(key, |record| {
             // Get number of subkeys from schema and ensure we are getting the
@@ -822,13 +1026,52 @@ where
             apibail_invalid_argument!("subkeys set does not overlap schema", "subkeys", subkeys);
         }
 
+        let seqs = self.load_seqs(key, &subkeys).await?;
+
+        Ok(Some(InspectResult {
+            subkeys,
+            seqs,
+            opt_descriptor,
+        }))
+    }
+
+    /// Hash the sequence numbers of each of the subkey ranges of a record
+    /// Ranges are truncated to the schema, so two nodes holding the same values agree on every digest
+    pub async fn digest_record(
+        &mut self,
+        key: TypedKey,
+        ranges: &[SubkeyDigestRange],
+        vcrypto: CryptoSystemVersion,
+    ) -> VeilidAPIResult<Option<Vec<HashDigest>>> {
+        let Some(schema) = self.with_record(key, |record| record.schema()) else {
+            // Record not available
+            return Ok(None);
+        };
+
+        let mut digests = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            let subkeys =
+                schema.truncate_subkeys(&ValueSubkeyRangeSet::single_range(*start, *end), None);
+            let seqs = self.load_seqs(key, &subkeys).await?;
+            digests.push(subkey_seqs_digest(&vcrypto, &seqs));
+        }
+        Ok(Some(digests))
+    }
+
+    /// Get the sequence numbers of a set of subkeys, ValueSeqNum::MAX for those that have not been written
+    async fn load_seqs(
+        &mut self,
+        key: TypedKey,
+        subkeys: &ValueSubkeyRangeSet,
+    ) -> VeilidAPIResult<Vec<ValueSeqNum>> {
+        // Get subkey table
+        let Some(subkey_table) = self.subkey_table.clone() else {
+            apibail_internal!("record store not initialized");
+        };
+
         // See if we have this inspection cached
-        if let Some(icv) = self.inspect_cache.get(&key, &subkeys) {
-            return Ok(Some(InspectResult {
-                subkeys,
-                seqs: icv.seqs,
-                opt_descriptor,
-            }));
+        if let Some(icv) = self.inspect_cache.get(&key, subkeys) {
+            return Ok(icv.seqs);
         }
 
         // Build sequence number list to return
@@ -862,11 +1105,7 @@ where
             InspectCacheL2Value { seqs: seqs.clone() },
         );
 
-        Ok(Some(InspectResult {
-            subkeys,
-            seqs,
-            opt_descriptor,
-        }))
+        Ok(seqs)
     }
 
     pub async fn _change_existing_watch(
@@ -895,6 +1134,7 @@ where
             if w.id == watch_id && w.params.watcher == params.watcher {
                 // Updating an existing watch
                 w.params = params;
+                self.changed_watches = true;
                 return Ok(WatchResult::Changed {
                     expiration: w.params.expiration,
                 });
@@ -991,6 +1231,7 @@ where
             id,
             changed: ValueSubkeyRangeSet::new(),
         });
+        self.changed_watches = true;
         Ok(WatchResult::Created { id, expiration })
     }
 
@@ -1087,6 +1328,9 @@ where
         if is_empty {
             self.watched_records.remove(&rtk);
         }
+        if ret {
+            self.changed_watches = true;
+        }
 
         Ok(ret)
     }
@@ -1099,6 +1343,9 @@ where
     ) -> Option<(WatchList, bool)> {
         let rtk = RecordTableKey { key };
         let out = self.watched_records.remove(&rtk);
+        if out.is_some() || in_watch.is_some() {
+            self.changed_watches = true;
+        }
         if let Some(in_watch) = in_watch {
             self.watched_records.insert(rtk, in_watch.0);
             if in_watch.1 {
@@ -1112,10 +1359,15 @@ where
     /// See if any watched records have expired and clear them out
     pub fn check_watched_records(&mut self) {
         let now = get_aligned_timestamp();
+        let mut changed_watches = false;
         self.watched_records.retain(|key, watch_list| {
+            let watch_count = watch_list.watches.len();
             watch_list.watches.retain(|w| {
                 w.params.count != 0 && w.params.expiration > now && !w.params.subkeys.is_empty()
             });
+            if watch_list.watches.len() != watch_count {
+                changed_watches = true;
+            }
             if watch_list.watches.is_empty() {
                 // If we're removing the watched record, drop any changed watch values too
                 self.changed_watched_values.remove(key);
@@ -1124,12 +1376,16 @@ where
                 true
             }
         });
+        if changed_watches {
+            self.changed_watches = true;
+        }
     }
 
     pub async fn take_value_changes(&mut self, changes: &mut Vec<ValueChangedInfo>) {
         // ValueChangedInfo but without the subkey data that requires a double mutable borrow to get
         struct EarlyValueChangedInfo {
             target: Target,
+            target_route_blob: Option<Vec<u8>>,
             key: TypedKey,
             subkeys: ValueSubkeyRangeSet,
             count: u32,
@@ -1163,6 +1419,7 @@ where
 
                     evcis.push(EarlyValueChangedInfo {
                         target: w.params.target,
+                        target_route_blob: w.params.target_route_blob.clone(),
                         key: rtk.key,
                         subkeys,
                         count,
@@ -1182,6 +1439,10 @@ where
         for ewr in empty_watched_records {
             self.watched_records.remove(&ewr);
         }
+        if !evcis.is_empty() {
+            // Watch counts and changed subkeys were updated
+            self.changed_watches = true;
+        }
 
         for evci in evcis {
             // Get the first subkey data
@@ -1207,6 +1468,7 @@ where
 
             changes.push(ValueChangedInfo {
                 target: evci.target,
+                target_route_blob: evci.target_route_blob,
                 key: evci.key,
                 subkeys: evci.subkeys,
                 count: evci.count,
@@ -1216,17 +1478,16 @@ where
         }
     }
 
-    /// LRU out some records until we reclaim the amount of space requested
+    /// Evict some records until we reclaim the amount of space requested
+    /// Rarely used records among the least recently used go first
     /// This will force a garbage collection of the space immediately
     /// If zero is passed in here, a garbage collection will be performed of dead records
     /// without removing any live records
     pub async fn reclaim_space(&mut self, space: usize) -> usize {
         let mut reclaimed = 0usize;
         while reclaimed < space {
-            if let Some((k, v)) = self.record_index.remove_lru() {
-                reclaimed += mem::size_of::<RecordTableKey>();
-                reclaimed += v.total_size();
-                self.add_dead_record(k, v);
+            if let Some(size) = self.evict_record() {
+                reclaimed += size;
             } else {
                 break;
             }
@@ -1241,12 +1502,19 @@ where
 
         out += "Record Index:\n";
         for (rik, rec) in &self.record_index {
+            let access_stats = rec.access_stats();
             out += &format!(
-                "  {} age={} len={} subkeys={}\n",
+                "  {} age={} len={} subkeys={} gets={} sets={} last_access={}\n",
                 rik.key,
                 debug_duration(get_timestamp() - rec.last_touched().as_u64()),
                 rec.record_data_size(),
                 rec.stored_subkeys(),
+                access_stats.get_count,
+                access_stats.set_count,
+                access_stats
+                    .last_access_ts
+                    .map(|ts| debug_duration(get_timestamp().saturating_sub(ts.as_u64())))
+                    .unwrap_or("never".to_owned()),
             );
         }
         out += &format!("Subkey Cache Count: {}\n", self.subkey_cache.len());
@@ -30,6 +30,9 @@ pub(in crate::storage_manager) struct OpenedRecord {
 
     /// Active watch we have on this record
     active_watch: Option<ActiveWatch>,
+
+    /// The newest sequence number announced in a ValueChange for each subkey
+    value_change_seqs: HashMap<ValueSubkey, ValueSeqNum>,
 }
 
 impl OpenedRecord {
@@ -38,6 +41,7 @@ impl OpenedRecord {
             writer,
             safety_selection,
             active_watch: None,
+            value_change_seqs: HashMap::new(),
         }
     }
 
@@ -66,4 +70,53 @@ impl OpenedRecord {
     pub fn active_watch(&self) -> Option<ActiveWatch> {
         self.active_watch.clone()
     }
+
+    /// Track the sequence number of a value change for a subkey before it is announced
+    /// `opt_last_seq` is the sequence number of the value we already had for the subkey, if any.
+    /// Returns None if the value change is a duplicate or older than what was already announced,
+    /// otherwise returns true if sequence numbers were skipped since the last one we knew of.
+    pub fn track_value_change_seq(
+        &mut self,
+        subkey: ValueSubkey,
+        seq: ValueSeqNum,
+        opt_last_seq: Option<ValueSeqNum>,
+    ) -> Option<bool> {
+        let opt_known_seq = match (self.value_change_seqs.get(&subkey).copied(), opt_last_seq) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        if let Some(known_seq) = opt_known_seq {
+            if seq <= known_seq {
+                return None;
+            }
+        }
+        self.value_change_seqs.insert(subkey, seq);
+        Some(opt_known_seq.is_some_and(|known_seq| seq - known_seq > 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track_value_change_seq_order_and_gaps() {
+        let mut record = OpenedRecord::new(None, SafetySelection::default());
+
+        // First value change for a subkey we had no value for
+        assert_eq!(record.track_value_change_seq(0, 4, None), Some(false));
+        // Next in sequence
+        assert_eq!(record.track_value_change_seq(0, 5, Some(4)), Some(false));
+        // Duplicate
+        assert_eq!(record.track_value_change_seq(0, 5, Some(5)), None);
+        // Skipped sequence numbers
+        assert_eq!(record.track_value_change_seq(0, 8, Some(5)), Some(true));
+        // Arrived after a newer value
+        assert_eq!(record.track_value_change_seq(0, 7, Some(8)), None);
+        // Announced sequence numbers are remembered even if the local value is older
+        assert_eq!(record.track_value_change_seq(0, 8, Some(2)), None);
+        // Subkeys are tracked separately and compared with the local value
+        assert_eq!(record.track_value_change_seq(1, 1, Some(0)), Some(false));
+        assert_eq!(record.track_value_change_seq(2, 3, Some(1)), Some(true));
+    }
 }
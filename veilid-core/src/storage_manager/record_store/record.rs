@@ -1,5 +1,22 @@
 use super::*;
 
+/// How often a stored record has been read and written
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(in crate::storage_manager) struct RecordAccessStats {
+    /// Number of subkey reads
+    pub get_count: u64,
+    /// Number of subkey writes
+    pub set_count: u64,
+    /// Last time a subkey was read or written
+    pub last_access_ts: Option<Timestamp>,
+}
+
+impl RecordAccessStats {
+    pub fn total_count(&self) -> u64 {
+        self.get_count.saturating_add(self.set_count)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub(in crate::storage_manager) struct Record<D>
 where
@@ -10,6 +27,8 @@ where
     stored_subkeys: ValueSubkeyRangeSet,
     last_touched_ts: Timestamp,
     record_data_size: usize,
+    #[serde(default)]
+    access_stats: RecordAccessStats,
     detail: D,
 }
 
@@ -30,6 +49,7 @@ where
             stored_subkeys: ValueSubkeyRangeSet::new(),
             last_touched_ts: cur_ts,
             record_data_size: 0,
+            access_stats: RecordAccessStats::default(),
             detail,
         })
     }
@@ -37,6 +57,10 @@ where
     pub fn descriptor(&self) -> Arc<SignedValueDescriptor> {
         self.descriptor.clone()
     }
+    /// Replace the descriptor with a newer one for the same record, such as one extending its expiration
+    pub fn set_descriptor(&mut self, descriptor: Arc<SignedValueDescriptor>) {
+        self.descriptor = descriptor;
+    }
     pub fn owner(&self) -> &PublicKey {
         self.descriptor.owner()
     }
@@ -60,6 +84,20 @@ where
         self.last_touched_ts
     }
 
+    pub fn access_stats(&self) -> &RecordAccessStats {
+        &self.access_stats
+    }
+
+    pub fn record_get(&mut self, cur_ts: Timestamp) {
+        self.access_stats.get_count = self.access_stats.get_count.saturating_add(1);
+        self.access_stats.last_access_ts = Some(cur_ts);
+    }
+
+    pub fn record_set(&mut self, cur_ts: Timestamp) {
+        self.access_stats.set_count = self.access_stats.set_count.saturating_add(1);
+        self.access_stats.last_access_ts = Some(cur_ts);
+    }
+
     pub fn set_record_data_size(&mut self, size: usize) {
         self.record_data_size = size;
     }
@@ -69,7 +107,7 @@ where
     }
 
     pub fn schema(&self) -> DHTSchema {
-        // unwrap is safe here because descriptor is immutable and set in new()
+        // unwrap is safe here because the schema is validated in new() and replacement descriptors keep it
         self.descriptor.schema().unwrap()
     }
 
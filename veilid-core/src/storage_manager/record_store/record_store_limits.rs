@@ -15,6 +15,10 @@ pub struct RecordStoreLimits {
     pub max_subkey_cache_memory_mb: Option<usize>,
     /// Limit on the amount of storage space to use for subkey data and record data
     pub max_storage_space_mb: Option<usize>,
+    /// Percentage of max_storage_space_mb at which least recently used records start being evicted
+    pub storage_high_watermark_percent: Option<u8>,
+    /// Percentage of max_storage_space_mb that eviction brings storage space back down to
+    pub storage_low_watermark_percent: Option<u8>,
     /// Max number of anonymous watches
     pub public_watch_limit: usize,
     /// Max number of watches per schema member
@@ -1,7 +1,7 @@
 use super::*;
 
 /// Watch parameters used to configure a watch
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WatchParameters {
     /// The range of subkeys being watched, empty meaning full
     pub subkeys: ValueSubkeyRangeSet,
@@ -13,6 +13,10 @@ pub struct WatchParameters {
     pub watcher: PublicKey,
     /// The place where updates are sent
     pub target: Target,
+    /// The private route updates are sent over when the target is a private route,
+    /// kept so the watch can still be notified after a restart forgets the imported route
+    #[serde(default, with = "as_human_opt_base64")]
+    pub target_route_blob: Option<Vec<u8>>,
 }
 
 /// Watch result to return with answer
@@ -38,7 +42,7 @@ pub enum WatchResult {
 }
 
 /// An individual watch
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Watch {
     /// The configuration of the watch
     pub params: WatchParameters,
@@ -48,7 +52,7 @@ pub struct Watch {
     pub changed: ValueSubkeyRangeSet,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 /// A record being watched for changes
 pub struct WatchList {
     /// The list of active watches
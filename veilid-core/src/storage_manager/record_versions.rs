@@ -0,0 +1,251 @@
+use super::*;
+
+/// The context of the outbound_get_descriptor operation
+struct OutboundGetDescriptorContext {
+    /// The newest descriptor seen so far, combining the owner updates from every node
+    pub descriptor: Arc<SignedValueDescriptor>,
+    /// The number of nodes that have returned a descriptor so far
+    pub descriptor_count: usize,
+}
+
+impl StorageManager {
+    /// Create a new version of an opened record that we own, with a possibly different schema,
+    /// and add a signed link to it to the old record's descriptor.
+    /// The new record keeps the owner of the old record and is returned opened.
+    pub async fn supersede_record(
+        &self,
+        key: TypedKey,
+        schema: DHTSchema,
+    ) -> VeilidAPIResult<DHTRecordDescriptor> {
+        let mut inner = self.lock().await?;
+        schema.validate()?;
+
+        // Get cryptosystem
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+
+        let (safety_selection, opt_writer) = {
+            let Some(opened_record) = inner.opened_records.get(&key) else {
+                apibail_generic!("record not open");
+            };
+            (
+                opened_record.safety_selection(),
+                opened_record.writer().cloned(),
+            )
+        };
+
+        // Get the descriptor of the record being superseded
+        let old_descriptor = inner.get_local_descriptor(key)?;
+
+        // Only the owner can supersede a record
+        let Some(owner) = opt_writer.filter(|w| w.key == *old_descriptor.owner()) else {
+            apibail_generic!("only the owner can supersede a record");
+        };
+        let Some(version) = old_descriptor.version().checked_add(1) else {
+            apibail_generic!("record version overflow");
+        };
+
+        // Create the new version of the record with the same owner, expiring when the old one does
+        let (new_key, owner) = inner
+            .create_new_owned_local_record(
+                key.kind,
+                schema,
+                Some(owner),
+                version,
+                old_descriptor.expiration().unwrap_or_default(),
+                safety_selection,
+            )
+            .await?;
+        let Some(descriptor) = inner
+            .open_existing_record(new_key, Some(owner), safety_selection)
+            .await?
+        else {
+            apibail_internal!("superseding record was not created");
+        };
+
+        // Point the old record at the new one
+        let superseded_by = SignedRecordVersionLink::make_signature(
+            DHTRecordVersionLink::new(new_key, version),
+            old_descriptor.owner(),
+            old_descriptor.schema_data(),
+            old_descriptor.version(),
+            vcrypto,
+            owner.secret,
+        )?;
+        let linked_descriptor =
+            Arc::new((*old_descriptor).clone().with_superseded_by(superseded_by));
+        inner.update_local_descriptor(key, linked_descriptor.clone())?;
+
+        self.push_local_descriptor(inner, key, safety_selection, linked_descriptor)
+            .await?;
+
+        Ok(descriptor)
+    }
+
+    /// Follow the version links from a record to the newest version of it
+    /// Records that are not already open are opened only long enough to read their descriptor.
+    /// Every record along the way must have the same owner and the version the link promised.
+    pub async fn resolve_record(
+        &self,
+        key: TypedKey,
+        safety_selection: SafetySelection,
+    ) -> VeilidAPIResult<TypedKey> {
+        let mut cur_key = key;
+        let mut opt_expected: Option<(PublicKey, u32)> = None;
+        for _ in 0..MAX_RECORD_VERSION_LINKS {
+            let descriptor = self
+                .get_latest_descriptor(cur_key, safety_selection)
+                .await?;
+
+            // Don't trust the link alone, the record it points at must be a later version by the same owner
+            if let Some((owner, version)) = opt_expected {
+                if *descriptor.owner() != owner {
+                    apibail_generic!("record version link points to a record with another owner");
+                }
+                if descriptor.version() != version {
+                    apibail_generic!("record version link points to a record with another version");
+                }
+            }
+
+            // Descriptor validation ensures links only move forward, so they can not loop
+            let Some(superseded_by) = descriptor.superseded_by() else {
+                return Ok(cur_key);
+            };
+            opt_expected = Some((*descriptor.owner(), superseded_by.link().version()));
+            cur_key = *superseded_by.link().key();
+        }
+
+        apibail_generic!("too many record version links");
+    }
+
+    /// Get the newest descriptor of a record that the network knows about, to learn if it has been superseded
+    async fn get_latest_descriptor(
+        &self,
+        key: TypedKey,
+        safety_selection: SafetySelection,
+    ) -> VeilidAPIResult<Arc<SignedValueDescriptor>> {
+        let is_open = self.lock().await?.opened_records.contains_key(&key);
+        if !is_open {
+            self.open_record(key, None, safety_selection).await?;
+        }
+
+        let res = async {
+            let inner = self.lock().await?;
+            let descriptor = inner.get_local_descriptor(key)?;
+
+            // Without the network, what we have is the newest we know of
+            let Some(rpc_processor) = Self::online_ready_inner(&inner) else {
+                return Ok(descriptor);
+            };
+            drop(inner);
+
+            let latest_descriptor = self
+                .outbound_get_descriptor(rpc_processor, key, safety_selection, descriptor.clone())
+                .await?;
+            if latest_descriptor != descriptor {
+                self.lock()
+                    .await?
+                    .update_local_descriptor(key, latest_descriptor.clone())?;
+            }
+            Ok::<_, VeilidAPIError>(latest_descriptor)
+        }
+        .await;
+
+        if !is_open {
+            self.close_record(key).await?;
+        }
+        res
+    }
+
+    /// Ask the nodes storing a record for their descriptor of it and combine the owner updates they have
+    async fn outbound_get_descriptor(
+        &self,
+        rpc_processor: RPCProcessor,
+        key: TypedKey,
+        safety_selection: SafetySelection,
+        last_descriptor: Arc<SignedValueDescriptor>,
+    ) -> VeilidAPIResult<Arc<SignedValueDescriptor>> {
+        let routing_table = rpc_processor.routing_table();
+
+        // Get the DHT parameters for 'GetValue'
+        let (key_count, consensus_count, fanout, timeout_us) = {
+            let c = self.unlocked_inner.config.get();
+            (
+                c.network.dht.max_find_node_count as usize,
+                c.network.dht.get_value_count as usize,
+                c.network.dht.get_value_fanout as usize,
+                TimestampDuration::from(ms_to_us(c.network.dht.get_value_timeout_ms)),
+            )
+        };
+
+        let context = Arc::new(Mutex::new(OutboundGetDescriptorContext {
+            descriptor: last_descriptor.clone(),
+            descriptor_count: 0,
+        }));
+
+        // Routine to call to generate fanout
+        let call_routine = |next_node: NodeRef| {
+            let rpc_processor = rpc_processor.clone();
+            let context = context.clone();
+            let last_descriptor = last_descriptor.clone();
+            async move {
+                // Returned descriptors are validated against the last descriptor by rpc_call_get_value
+                let gva = network_result_try!(
+                    rpc_processor
+                        .clone()
+                        .rpc_call_get_value(
+                            Destination::direct(next_node).with_safety(safety_selection),
+                            key,
+                            0,
+                            Some((*last_descriptor).clone()),
+                            true,
+                        )
+                        .await?
+                );
+
+                if let Some(descriptor) = gva.answer.descriptor {
+                    let mut ctx = context.lock();
+                    if let Some(updated) = ctx.descriptor.updated_by(&descriptor) {
+                        ctx.descriptor = Arc::new(updated);
+                    }
+                    ctx.descriptor_count += 1;
+                }
+
+                Ok(NetworkResult::value(gva.answer.peers))
+            }
+        };
+
+        // Routine to call to check if we're done at each step
+        let check_done = |_closest_nodes: &[NodeRef]| {
+            let ctx = context.lock();
+            if ctx.descriptor_count >= consensus_count {
+                return Some(());
+            }
+            None
+        };
+
+        // Call the fanout
+        let fanout_call = FanoutCall::new(
+            routing_table.clone(),
+            key,
+            key_count,
+            fanout,
+            timeout_us,
+            capability_fanout_node_info_filter(vec![CAP_DHT]),
+            call_routine,
+            check_done,
+        );
+
+        match fanout_call.run(vec![]).await {
+            TimeoutOr::Timeout | TimeoutOr::Value(Ok(_)) => {}
+            TimeoutOr::Value(Err(e)) => {
+                log_dht!(debug "GetDescriptor Fanout Error: {}", e);
+                return Err(e.into());
+            }
+        }
+
+        let ctx = context.lock();
+        Ok(ctx.descriptor.clone())
+    }
+}
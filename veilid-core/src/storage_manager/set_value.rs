@@ -44,6 +44,20 @@ impl StorageManager {
             )
         };
 
+        // Compressed or batch signed values can only be stored by nodes that understand them
+        let mut capabilities = vec![CAP_DHT];
+        if value.is_compressed() {
+            capabilities.push(CAP_DHT_COMPRESSION);
+        }
+        if value.batch().is_some() {
+            capabilities.push(CAP_DHT_BATCH);
+        }
+
+        // Get cryptosystem
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+
         // Make do-set-value answer context
         let schema = descriptor.schema()?;
         let context = Arc::new(Mutex::new(OutboundSetValueContext {
@@ -57,6 +71,7 @@ impl StorageManager {
         let call_routine = |next_node: NodeRef| {
             let rpc_processor = rpc_processor.clone();
             let context = context.clone();
+            let vcrypto = vcrypto.clone();
             let descriptor = descriptor.clone();
             async move {
                 let send_descriptor = true; // xxx check if next_node needs the descriptor or not
@@ -92,9 +107,11 @@ impl StorageManager {
 
                         // Validate with schema
                         if !ctx.schema.check_subkey_value_data(
+                            vcrypto,
                             descriptor.owner(),
                             subkey,
                             value.value_data(),
+                            None,
                         ) {
                             // Validation failed, ignore this value and pretend we never saw this node
                             return Ok(NetworkResult::invalid_message("Schema validation failed"));
@@ -165,7 +182,7 @@ impl StorageManager {
             key_count,
             fanout,
             timeout_us,
-            capability_fanout_node_info_filter(vec![CAP_DHT]),
+            capability_fanout_node_info_filter(capabilities),
             call_routine,
             check_done,
         );
@@ -211,7 +228,7 @@ impl StorageManager {
         let mut inner = self.lock().await?;
 
         // See if this is a remote or local value
-        let (is_local, last_get_result) = {
+        let (is_local, mut last_get_result) = {
             // See if the subkey we are modifying has a last known local value
             let last_get_result = inner.handle_get_local_value(key, subkey, true).await?;
             // If this is local, it must have a descriptor already
@@ -224,9 +241,51 @@ impl StorageManager {
             }
         };
 
+        // The owner extends the expiration of a record, or links it to the record that supersedes it,
+        // by sending a newer descriptor for it
+        let mut updated = false;
+        if let (Some(last_descriptor), Some(descriptor)) =
+            (&last_get_result.opt_descriptor, &descriptor)
+        {
+            if let Some(updated_descriptor) = last_descriptor.updated_by(descriptor) {
+                let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+                    return Ok(NetworkResult::invalid_message("unsupported cryptosystem"));
+                };
+                if descriptor.validate(vcrypto).is_err() {
+                    return Ok(NetworkResult::invalid_message(
+                        "setvalue descriptor failed validation",
+                    ));
+                }
+                let updated_descriptor = Arc::new(updated_descriptor);
+                if is_local {
+                    inner.update_local_descriptor(key, updated_descriptor.clone())?;
+                } else {
+                    inner.update_remote_descriptor(key, updated_descriptor.clone())?;
+                }
+                last_get_result.opt_descriptor = Some(updated_descriptor);
+                updated = true;
+            }
+        }
+
+        // Expired records can not be written to until the owner extends them
+        let cur_ts = get_aligned_timestamp();
+        if last_get_result
+            .opt_descriptor
+            .as_ref()
+            .or(descriptor.as_ref())
+            .map(|d| d.is_expired(cur_ts))
+            .unwrap_or(false)
+        {
+            return Ok(NetworkResult::invalid_message("record has expired"));
+        }
+
         // Make sure this value would actually be newer
         if let Some(last_value) = &last_get_result.opt_value {
             if value.value_data().seq() <= last_value.value_data().seq() {
+                // Resending the value we have is how the owner pushes an updated descriptor, so count it as set
+                if updated && last_value.value_data() == value.value_data() {
+                    return Ok(NetworkResult::value(None));
+                }
                 // inbound value is older than or equal to the sequence number that we have, just return the one we have
                 return Ok(NetworkResult::value(Some(last_value.clone())));
             }
@@ -263,7 +322,18 @@ impl StorageManager {
         };
 
         // Validate new value with schema
-        if !schema.check_subkey_value_data(actual_descriptor.owner(), subkey, value.value_data()) {
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+            return Ok(NetworkResult::invalid_message("unsupported cryptosystem"));
+        };
+        // Write order is only enforced by the writer, since replicas may receive subkeys
+        // out of order or never see the earlier ones at all
+        if !schema.check_subkey_value_data(
+            vcrypto,
+            actual_descriptor.owner(),
+            subkey,
+            value.value_data(),
+            None,
+        ) {
             // Validation failed, ignore this value
             return Ok(NetworkResult::invalid_message("failed schema validation"));
         }
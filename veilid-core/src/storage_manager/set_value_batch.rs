@@ -0,0 +1,270 @@
+use super::*;
+
+/// The context of the outbound_set_value_batch operation
+struct OutboundSetValueBatchContext {
+    /// The latest value of each subkey, may be the values passed in
+    pub values: BTreeMap<ValueSubkey, Arc<SignedValueData>>,
+    /// The nodes that have set the whole batch so far (up to the consensus count)
+    pub value_nodes: Vec<NodeRef>,
+    /// The number of non-sets since the last set we have received
+    pub missed_since_last_set: usize,
+    /// The parsed schema from the descriptor if we have one
+    pub schema: DHTSchema,
+}
+
+/// The result of the outbound_set_value_batch operation
+pub(super) struct OutboundSetValueBatchResult {
+    /// Fanout result
+    pub fanout_result: FanoutResult,
+    /// The values that were set, or newer ones found on the network
+    pub signed_values: BTreeMap<ValueSubkey, Arc<SignedValueData>>,
+}
+
+impl StorageManager {
+    /// Perform a 'set value batch' query on the network
+    pub(super) async fn outbound_set_value_batch(
+        &self,
+        rpc_processor: RPCProcessor,
+        key: TypedKey,
+        safety_selection: SafetySelection,
+        batch: SignedValueBatch,
+        signed_values: BTreeMap<ValueSubkey, Arc<SignedValueData>>,
+        descriptor: Arc<SignedValueDescriptor>,
+    ) -> VeilidAPIResult<OutboundSetValueBatchResult> {
+        let routing_table = rpc_processor.routing_table();
+
+        // Get the DHT parameters for 'SetValue'
+        let (key_count, consensus_count, fanout, timeout_us) = {
+            let c = self.unlocked_inner.config.get();
+            (
+                c.network.dht.max_find_node_count as usize,
+                c.network.dht.set_value_count as usize,
+                c.network.dht.set_value_fanout as usize,
+                TimestampDuration::from(ms_to_us(c.network.dht.set_value_timeout_ms)),
+            )
+        };
+
+        // Batches can only be stored by nodes that understand them
+        let capabilities = if batch.is_compressed() {
+            vec![CAP_DHT, CAP_DHT_BATCH, CAP_DHT_COMPRESSION]
+        } else {
+            vec![CAP_DHT, CAP_DHT_BATCH]
+        };
+
+        // Get cryptosystem
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+            apibail_generic!("unsupported cryptosystem");
+        };
+
+        // Make do-set-value-batch answer context
+        let schema = descriptor.schema()?;
+        let context = Arc::new(Mutex::new(OutboundSetValueBatchContext {
+            values: signed_values,
+            value_nodes: vec![],
+            missed_since_last_set: 0,
+            schema,
+        }));
+
+        // Routine to call to generate fanout
+        let call_routine = |next_node: NodeRef| {
+            let rpc_processor = rpc_processor.clone();
+            let context = context.clone();
+            let vcrypto = vcrypto.clone();
+            let descriptor = descriptor.clone();
+            let batch = batch.clone();
+            async move {
+                let send_descriptor = true; // xxx check if next_node needs the descriptor or not
+
+                // send across the wire
+                let sva = network_result_try!(
+                    rpc_processor
+                        .clone()
+                        .rpc_call_set_value_batch(
+                            Destination::direct(next_node.clone()).with_safety(safety_selection),
+                            key,
+                            batch,
+                            (*descriptor).clone(),
+                            send_descriptor,
+                        )
+                        .await?
+                );
+
+                // If the node was close enough to possibly set the values
+                if sva.answer.set {
+                    let mut ctx = context.lock();
+
+                    // Keep any newer values that pass schema validation
+                    let mut newer_found = false;
+                    for (subkey, value) in sva.answer.values {
+                        log_dht!(debug "Got value back: subkey={} len={} seq={}", subkey, value.value_data().data().len(), value.value_data().seq());
+
+                        // Validate with schema
+                        if !ctx.schema.check_subkey_value_data(
+                            vcrypto.clone(),
+                            descriptor.owner(),
+                            subkey,
+                            value.value_data(),
+                            None,
+                        ) {
+                            // Validation failed, ignore this node's answer
+                            return Ok(NetworkResult::invalid_message("Schema validation failed"));
+                        }
+
+                        let Some(prior_value) = ctx.values.get(&subkey) else {
+                            return Ok(NetworkResult::invalid_message(
+                                "value returned for subkey not in batch",
+                            ));
+                        };
+
+                        // If we got a value back it should be different than the one we are setting
+                        if prior_value.value_data() == value.value_data() {
+                            continue;
+                        }
+
+                        // We have a prior value, ensure this is a newer sequence number
+                        if value.value_data().seq() < prior_value.value_data().seq() {
+                            // Skip this node and its closer list because it is misbehaving
+                            return Ok(NetworkResult::invalid_message("Sequence number is older"));
+                        }
+                        ctx.values.insert(subkey, Arc::new(value));
+                        newer_found = true;
+                    }
+
+                    if newer_found {
+                        // Nodes that already agreed were storing values that are now superseded
+                        ctx.value_nodes = vec![next_node];
+                    } else {
+                        // The whole batch was set on this node, so increase our consensus count
+                        ctx.value_nodes.push(next_node);
+                    }
+                    ctx.missed_since_last_set = 0;
+                } else {
+                    let mut ctx = context.lock();
+                    ctx.missed_since_last_set += 1;
+                }
+
+                // Return peers if we have some
+                log_network_result!(debug "SetValueBatch fanout call returned peers {}", sva.answer.peers.len());
+
+                Ok(NetworkResult::value(sva.answer.peers))
+            }
+        };
+
+        // Routine to call to check if we're done at each step
+        let check_done = |_closest_nodes: &[NodeRef]| {
+            let ctx = context.lock();
+
+            // If we have reached sufficient consensus, return done
+            if ctx.value_nodes.len() >= consensus_count {
+                return Some(());
+            }
+            // If we have missed more than our consensus count since our last set, return done
+            // This keeps the traversal from searching too many nodes when we aren't converging
+            // Only do this if we have gotten at least half our desired sets.
+            if ctx.value_nodes.len() >= ((consensus_count + 1) / 2)
+                && ctx.missed_since_last_set >= consensus_count
+            {
+                return Some(());
+            }
+            None
+        };
+
+        // Call the fanout
+        let fanout_call = FanoutCall::new(
+            routing_table.clone(),
+            key,
+            key_count,
+            fanout,
+            timeout_us,
+            capability_fanout_node_info_filter(capabilities),
+            call_routine,
+            check_done,
+        );
+
+        let kind = match fanout_call.run(vec![]).await {
+            // If we don't finish in the timeout (too much time passed checking for consensus)
+            TimeoutOr::Timeout => FanoutResultKind::Timeout,
+            // If we finished with or without consensus (enough nodes returning the same value)
+            TimeoutOr::Value(Ok(Some(()))) => FanoutResultKind::Finished,
+            // If we ran out of nodes before getting consensus)
+            TimeoutOr::Value(Ok(None)) => FanoutResultKind::Exhausted,
+            // Failed
+            TimeoutOr::Value(Err(e)) => {
+                // If we finished with an error, return that
+                log_dht!(debug "SetValueBatch Fanout Error: {}", e);
+                return Err(e.into());
+            }
+        };
+        let ctx = context.lock();
+        let fanout_result = FanoutResult {
+            kind,
+            value_nodes: ctx.value_nodes.clone(),
+        };
+        log_network_result!(debug "SetValueBatch Fanout: {:?}", fanout_result);
+
+        Ok(OutboundSetValueBatchResult {
+            fanout_result,
+            signed_values: ctx.values.clone(),
+        })
+    }
+
+    /// Handle a received 'Set Value Batch' query
+    /// The batch signature is checked once, then each value is set as if it was sent on its own
+    /// Returns the current value of each subkey that was older and kept instead
+    pub async fn inbound_set_value_batch(
+        &self,
+        key: TypedKey,
+        batch: SignedValueBatch,
+        descriptor: Option<Arc<SignedValueDescriptor>>,
+        target: Target,
+    ) -> VeilidAPIResult<NetworkResult<Vec<(ValueSubkey, Arc<SignedValueData>)>>> {
+        let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+            return Ok(NetworkResult::invalid_message("unsupported cryptosystem"));
+        };
+
+        // Find the owner the batch must have been signed for
+        let owner = {
+            let inner = self.lock().await?;
+            match inner
+                .get_local_descriptor(key)
+                .or_else(|_| inner.get_remote_descriptor(key))
+            {
+                Ok(last_descriptor) => *last_descriptor.owner(),
+                Err(_) => {
+                    let Some(descriptor) = &descriptor else {
+                        // No descriptor
+                        return Ok(NetworkResult::invalid_message(
+                            "descriptor must be provided",
+                        ));
+                    };
+                    if descriptor.validate(vcrypto.clone()).is_err() {
+                        return Ok(NetworkResult::invalid_message("invalid descriptor"));
+                    }
+                    *descriptor.owner()
+                }
+            }
+        };
+
+        // Check the batch signature once for every value in it
+        let digest = match batch.validate(&owner, vcrypto) {
+            Ok(v) => v,
+            Err(e) => {
+                return Ok(NetworkResult::invalid_message(e));
+            }
+        };
+
+        // Set each value, keeping the newer ones we already had
+        let mut newer_values = vec![];
+        for (subkey, value) in batch.into_signed_values(digest) {
+            let newer_value = network_result_try!(
+                self.inbound_set_value(key, subkey, Arc::new(value), descriptor.clone(), target)
+                    .await?
+            );
+            if let Some(newer_value) = newer_value {
+                newer_values.push((subkey, newer_value));
+            }
+        }
+
+        Ok(NetworkResult::value(newer_values))
+    }
+}
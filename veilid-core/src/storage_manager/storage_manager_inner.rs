@@ -24,6 +24,8 @@ pub(super) struct StorageManagerInner {
     pub offline_subkey_writes: HashMap<TypedKey, OfflineSubkeyWrite>,
     /// Storage manager metadata that is persistent, including copy of offline subkey writes
     pub metadata_db: Option<TableDB>,
+    /// Log of the remote operations served by this node, if enabled
+    pub audit_log: Option<AuditLog>,
     /// RPC processor if it is available
     pub opt_rpc_processor: Option<RPCProcessor>,
     /// Routing table if it is available
@@ -46,6 +48,8 @@ fn local_limits_from_config(config: VeilidConfig) -> RecordStoreLimits {
         max_records: None,
         max_subkey_cache_memory_mb: Some(c.network.dht.local_max_subkey_cache_memory_mb as usize),
         max_storage_space_mb: None,
+        storage_high_watermark_percent: None,
+        storage_low_watermark_percent: None,
         public_watch_limit: c.network.dht.public_watch_limit as usize,
         member_watch_limit: c.network.dht.member_watch_limit as usize,
         max_watch_expiration: TimestampDuration::new(ms_to_us(
@@ -64,6 +68,8 @@ fn remote_limits_from_config(config: VeilidConfig) -> RecordStoreLimits {
         max_records: Some(c.network.dht.remote_max_records as usize),
         max_subkey_cache_memory_mb: Some(c.network.dht.remote_max_subkey_cache_memory_mb as usize),
         max_storage_space_mb: Some(c.network.dht.remote_max_storage_space_mb as usize),
+        storage_high_watermark_percent: Some(c.network.dht.remote_storage_high_watermark_percent),
+        storage_low_watermark_percent: Some(c.network.dht.remote_storage_low_watermark_percent),
         public_watch_limit: c.network.dht.public_watch_limit as usize,
         member_watch_limit: c.network.dht.member_watch_limit as usize,
         max_watch_expiration: TimestampDuration::new(ms_to_us(
@@ -84,6 +90,7 @@ impl StorageManagerInner {
             remote_record_store: Default::default(),
             offline_subkey_writes: Default::default(),
             metadata_db: Default::default(),
+            audit_log: None,
             opt_rpc_processor: Default::default(),
             opt_routing_table: Default::default(),
             tick_future: Default::default(),
@@ -120,6 +127,23 @@ impl StorageManagerInner {
         );
         remote_record_store.init().await?;
 
+        let audit_log_max_entries = self
+            .unlocked_inner
+            .config
+            .get()
+            .network
+            .dht
+            .audit_log_max_entries;
+        if audit_log_max_entries != 0 {
+            self.audit_log = Some(
+                AuditLog::open(
+                    self.unlocked_inner.table_store.clone(),
+                    audit_log_max_entries,
+                )
+                .await?,
+            );
+        }
+
         self.metadata_db = Some(metadata_db);
         self.local_record_store = Some(local_record_store);
         self.remote_record_store = Some(remote_record_store);
@@ -171,6 +195,7 @@ impl StorageManagerInner {
             self.metadata_db = None;
         }
         self.offline_subkey_writes.clear();
+        self.audit_log = None;
 
         // Mark not initialized
         self.initialized = false;
@@ -205,6 +230,9 @@ impl StorageManagerInner {
         &mut self,
         kind: CryptoKind,
         schema: DHTSchema,
+        owner: Option<KeyPair>,
+        version: u32,
+        expiration: Timestamp,
         safety_selection: SafetySelection,
     ) -> VeilidAPIResult<(TypedKey, KeyPair)> {
         // Get cryptosystem
@@ -234,13 +262,15 @@ impl StorageManagerInner {
         // Compile the dht schema
         let schema_data = schema.compile();
 
-        // New values require a new owner key
-        let owner = vcrypto.generate_keypair();
+        // New records require a new owner key, superseding records keep the owner of the record they replace
+        let owner = owner.unwrap_or_else(|| vcrypto.generate_keypair());
 
         // Make a signed value descriptor for this dht value
         let signed_value_descriptor = Arc::new(SignedValueDescriptor::make_signature(
             owner.key,
             schema_data,
+            version,
+            expiration,
             vcrypto.clone(),
             owner.secret,
         )?);
@@ -261,7 +291,7 @@ impl StorageManagerInner {
         &mut self,
         key: TypedKey,
         safety_selection: SafetySelection,
-    ) -> VeilidAPIResult<Option<(PublicKey, DHTSchema)>> {
+    ) -> VeilidAPIResult<Option<(PublicKey, DHTSchema, u32, Option<Timestamp>)>> {
         // Get local record store
         let Some(local_record_store) = self.local_record_store.as_mut() else {
             apibail_not_initialized!();
@@ -314,7 +344,12 @@ impl StorageManagerInner {
         remote_record_store.delete_record(key).await?;
 
         // Return record information as transferred to local record
-        Ok(Some((*remote_record.owner(), remote_record.schema())))
+        Ok(Some((
+            *remote_record.owner(),
+            remote_record.schema(),
+            remote_record.descriptor().version(),
+            remote_record.descriptor().expiration(),
+        )))
     }
 
     pub async fn open_existing_record(
@@ -336,9 +371,15 @@ impl StorageManagerInner {
             r.detail_mut().safety_selection = safety_selection;
 
             // Return record details
-            (*r.owner(), r.schema())
+            (
+                *r.owner(),
+                r.schema(),
+                r.descriptor().version(),
+                r.descriptor().expiration(),
+            )
         };
-        let (owner, schema) = match local_record_store.with_record_mut(key, cb) {
+        let (owner, schema, version, expiration) = match local_record_store.with_record_mut(key, cb)
+        {
             Some(v) => v,
             None => {
                 // If we don't have a local record yet, check to see if we have a remote record
@@ -377,7 +418,8 @@ impl StorageManagerInner {
             .or_insert_with(|| OpenedRecord::new(writer, safety_selection));
 
         // Make DHT Record Descriptor to return
-        let descriptor = DHTRecordDescriptor::new(key, owner, owner_secret, schema);
+        let descriptor =
+            DHTRecordDescriptor::new(key, owner, owner_secret, schema, version, expiration);
         Ok(Some(descriptor))
     }
 
@@ -414,6 +456,8 @@ impl StorageManagerInner {
             None
         };
         let schema = signed_value_descriptor.schema()?;
+        let version = signed_value_descriptor.version();
+        let expiration = signed_value_descriptor.expiration();
 
         // Get local record store
         let Some(local_record_store) = self.local_record_store.as_mut() else {
@@ -441,7 +485,8 @@ impl StorageManagerInner {
             .insert(key, OpenedRecord::new(writer, safety_selection));
 
         // Make DHT Record Descriptor to return
-        let descriptor = DHTRecordDescriptor::new(key, owner, owner_secret, schema);
+        let descriptor =
+            DHTRecordDescriptor::new(key, owner, owner_secret, schema, version, expiration);
         Ok(descriptor)
     }
 
@@ -595,6 +640,93 @@ impl StorageManagerInner {
         })
     }
 
+    pub(super) fn get_local_written_subkeys(
+        &self,
+        key: TypedKey,
+    ) -> VeilidAPIResult<Option<ValueSubkeyRangeSet>> {
+        // See if it's in the local record store
+        let Some(local_record_store) = self.local_record_store.as_ref() else {
+            apibail_not_initialized!();
+        };
+        Ok(local_record_store.peek_record(key, |r| r.stored_subkeys().clone()))
+    }
+
+    pub(super) fn get_local_descriptor(
+        &self,
+        key: TypedKey,
+    ) -> VeilidAPIResult<Arc<SignedValueDescriptor>> {
+        let Some(local_record_store) = self.local_record_store.as_ref() else {
+            apibail_not_initialized!();
+        };
+        let Some(descriptor) = local_record_store.peek_record(key, |r| r.descriptor()) else {
+            apibail_key_not_found!(key);
+        };
+        Ok(descriptor)
+    }
+
+    pub(super) fn get_remote_descriptor(
+        &self,
+        key: TypedKey,
+    ) -> VeilidAPIResult<Arc<SignedValueDescriptor>> {
+        let Some(remote_record_store) = self.remote_record_store.as_ref() else {
+            apibail_not_initialized!();
+        };
+        let Some(descriptor) = remote_record_store.peek_record(key, |r| r.descriptor()) else {
+            apibail_key_not_found!(key);
+        };
+        Ok(descriptor)
+    }
+
+    /// Replace the descriptor of a local record with a newer one for the same record
+    pub(super) fn update_local_descriptor(
+        &mut self,
+        key: TypedKey,
+        descriptor: Arc<SignedValueDescriptor>,
+    ) -> VeilidAPIResult<()> {
+        let Some(local_record_store) = self.local_record_store.as_mut() else {
+            apibail_not_initialized!();
+        };
+        if local_record_store
+            .with_record_mut(key, |r| r.set_descriptor(descriptor))
+            .is_none()
+        {
+            apibail_key_not_found!(key);
+        }
+        Ok(())
+    }
+
+    /// Replace the descriptor of a remote record with a newer one for the same record
+    pub(super) fn update_remote_descriptor(
+        &mut self,
+        key: TypedKey,
+        descriptor: Arc<SignedValueDescriptor>,
+    ) -> VeilidAPIResult<()> {
+        let Some(remote_record_store) = self.remote_record_store.as_mut() else {
+            apibail_not_initialized!();
+        };
+        if remote_record_store
+            .with_record_mut(key, |r| r.set_descriptor(descriptor))
+            .is_none()
+        {
+            apibail_key_not_found!(key);
+        }
+        Ok(())
+    }
+
+    /// True if we have the record and its expiration has passed
+    pub(super) fn is_record_expired(&self, key: TypedKey, cur_ts: Timestamp) -> bool {
+        let local_descriptor = self
+            .local_record_store
+            .as_ref()
+            .and_then(|s| s.peek_record(key, |r| r.descriptor()));
+        let descriptor = local_descriptor.or_else(|| {
+            self.remote_record_store
+                .as_ref()
+                .and_then(|s| s.peek_record(key, |r| r.descriptor()))
+        });
+        descriptor.map(|d| d.is_expired(cur_ts)).unwrap_or(false)
+    }
+
     pub(super) async fn handle_get_remote_value(
         &mut self,
         key: TypedKey,
@@ -676,17 +808,62 @@ impl StorageManagerInner {
         })
     }
 
-    /// # DHT Key = Hash(ownerKeyKind) of: [ ownerKeyValue, schema ]
+    /// Digest subkey ranges of a local record, None if we don't have it
+    pub(super) async fn handle_digest_local_value(
+        &mut self,
+        key: TypedKey,
+        ranges: &[SubkeyDigestRange],
+        vcrypto: CryptoSystemVersion,
+    ) -> VeilidAPIResult<Option<Vec<HashDigest>>> {
+        let Some(local_record_store) = self.local_record_store.as_mut() else {
+            apibail_not_initialized!();
+        };
+        local_record_store.digest_record(key, ranges, vcrypto).await
+    }
+
+    /// Digest subkey ranges of a remote record, None if we don't have it
+    pub(super) async fn handle_digest_remote_value(
+        &mut self,
+        key: TypedKey,
+        ranges: &[SubkeyDigestRange],
+        vcrypto: CryptoSystemVersion,
+    ) -> VeilidAPIResult<Option<Vec<HashDigest>>> {
+        let Some(remote_record_store) = self.remote_record_store.as_mut() else {
+            apibail_not_initialized!();
+        };
+        remote_record_store
+            .digest_record(key, ranges, vcrypto)
+            .await
+    }
+
+    /// # DHT Key = Hash(ownerKeyKind) of: [ ownerKeyValue, schema, version (if nonzero) ]
     fn get_key<D>(vcrypto: CryptoSystemVersion, record: &Record<D>) -> TypedKey
     where
         D: fmt::Debug + Clone + Serialize,
     {
         let descriptor = record.descriptor();
-        let compiled = descriptor.schema_data();
-        let mut hash_data = Vec::<u8>::with_capacity(PUBLIC_KEY_LENGTH + 4 + compiled.len());
+        Self::derive_key(
+            vcrypto,
+            record.owner(),
+            descriptor.schema_data(),
+            descriptor.version(),
+        )
+    }
+
+    /// Derive the key of a record from its owner, compiled schema and version
+    pub(super) fn derive_key(
+        vcrypto: CryptoSystemVersion,
+        owner: &PublicKey,
+        schema_data: &[u8],
+        version: u32,
+    ) -> TypedKey {
+        let mut hash_data = Vec::<u8>::with_capacity(PUBLIC_KEY_LENGTH + 8 + schema_data.len());
         hash_data.extend_from_slice(&vcrypto.kind().0);
-        hash_data.extend_from_slice(&record.owner().bytes);
-        hash_data.extend_from_slice(compiled);
+        hash_data.extend_from_slice(&owner.bytes);
+        hash_data.extend_from_slice(schema_data);
+        if version != 0 {
+            hash_data.extend_from_slice(&version.to_le_bytes());
+        }
         let hash = vcrypto.generate_hash(&hash_data);
         TypedKey::new(vcrypto.kind(), hash)
     }
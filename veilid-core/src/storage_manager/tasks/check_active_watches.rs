@@ -60,6 +60,7 @@ impl StorageManager {
                             subkeys: ValueSubkeyRangeSet::new(),
                             count: 0,
                             value: None,
+                            gap: false,
                         })));
                     }
                 }
@@ -7,13 +7,37 @@ impl StorageManager {
         self,
         stop_token: StopToken,
         _last_ts: Timestamp,
-        _cur_ts: Timestamp,
+        cur_ts: Timestamp,
     ) -> EyreResult<()> {
         let mut inner = self.inner.lock().await;
         if let Some(local_record_store) = &mut inner.local_record_store {
             local_record_store.flush().await?;
         }
+        let opt_update_callback = inner.update_callback.clone();
         if let Some(remote_record_store) = &mut inner.remote_record_store {
+            // Records past their expiration are no longer served, so stop storing them too
+            if let Some((expired_count, expired_space)) =
+                remote_record_store.delete_expired_records(cur_ts).await
+            {
+                log_stor!(debug "Deleted {} expired remote records ({} bytes)", expired_count, expired_space);
+            }
+            // Make room before we run out of storage space and have to start rejecting writes
+            if let Some((evicted_count, evicted_space)) =
+                remote_record_store.evict_over_high_watermark()
+            {
+                log_stor!(debug
+                    "Remote record storage reached its high watermark, evicted {} records ({} bytes)",
+                    evicted_count, evicted_space
+                );
+                if let Some(update_callback) = opt_update_callback {
+                    update_callback(VeilidUpdate::StorageEviction(Box::new(
+                        VeilidStorageEviction {
+                            evicted_record_count: evicted_count as u32,
+                            evicted_space: ByteCount::new(evicted_space),
+                        },
+                    )));
+                }
+            }
             remote_record_store.flush().await?;
         }
         Ok(())
@@ -23,7 +23,7 @@ impl StorageManager {
                                 Timestamp::new(t),
                             )
                             .instrument(trace_span!(
-                                parent: None,
+                                parent: &this.unlocked_inner.config.instance_span(),
                                 "StorageManager flush record stores task routine"
                             )),
                     )
@@ -44,7 +44,7 @@ impl StorageManager {
                                 Timestamp::new(t),
                             )
                             .instrument(trace_span!(
-                                parent: None,
+                                parent: &this.unlocked_inner.config.instance_span(),
                                 "StorageManager offline subkey writes task routine"
                             )),
                     )
@@ -65,7 +65,7 @@ impl StorageManager {
                                 Timestamp::new(t),
                             )
                             .instrument(trace_span!(
-                                parent: None,
+                                parent: &this.unlocked_inner.config.instance_span(),
                                 "StorageManager send value changes task routine"
                             )),
                     )
@@ -86,7 +86,7 @@ impl StorageManager {
                                 Timestamp::new(t),
                             )
                             .instrument(trace_span!(
-                                parent: None,
+                                parent: &this.unlocked_inner.config.instance_span(),
                                 "StorageManager check active watches task routine"
                             )),
                     )
@@ -107,7 +107,7 @@ impl StorageManager {
                                 Timestamp::new(t),
                             )
                             .instrument(trace_span!(
-                                parent: None,
+                                parent: &this.unlocked_inner.config.instance_span(),
                                 "StorageManager check watched records task routine"
                             )),
                     )
@@ -128,8 +128,8 @@ impl StorageManager {
             .tick()
             .await?;
 
-        // Run online-only tasks
-        if self.online_writes_ready().await?.is_some() {
+        // Run online-only tasks, which wait for the next wake in low power mode
+        if self.online_writes_ready().await?.is_some() && !self.is_low_power().await? {
             // Run offline subkey writes task if there's work to be done
             if self.has_offline_subkey_writes().await? {
                 self.unlocked_inner
@@ -144,6 +144,24 @@ impl StorageManager {
         Ok(())
     }
 
+    /// Send the DHT writes and value changed notifications batched up since the last wake in low power mode
+    pub async fn low_power_wake(&self) -> EyreResult<()> {
+        if self.online_writes_ready().await?.is_none() {
+            return Ok(());
+        }
+        if self.has_offline_subkey_writes().await? {
+            self.unlocked_inner
+                .offline_subkey_writes_task
+                .try_tick_now()
+                .await?;
+        }
+        self.unlocked_inner
+            .send_value_changes_task
+            .try_tick_now()
+            .await?;
+        Ok(())
+    }
+
     pub(crate) async fn cancel_tasks(&self) {
         log_stor!(debug "stopping check watched records task");
         if let Err(e) = self.unlocked_inner.check_watched_records_task.stop().await {
@@ -0,0 +1,3 @@
+use super::*;
+
+pub mod test_record_store;
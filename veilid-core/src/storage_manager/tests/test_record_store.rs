@@ -0,0 +1,315 @@
+use super::*;
+use crate::tests::common::test_veilid_config::*;
+
+async fn startup() -> VeilidAPI {
+    let (update_callback, _) = setup_veilid_core();
+    let memory_config_callback: ConfigCallback = Arc::new(|key: String| {
+        if key == "table_store.backend" {
+            return Ok(Box::new(VeilidConfigTableStoreBackend::Memory));
+        }
+        config_callback(key)
+    });
+    api_startup(update_callback, memory_config_callback)
+        .await
+        .expect("startup failed")
+}
+
+async fn make_record_store(
+    table_store: TableStore,
+    vcrypto: CryptoSystemVersion,
+    name: &str,
+    max_records: Option<usize>,
+) -> RecordStore<RemoteRecordDetail> {
+    make_record_store_with_limits(
+        table_store,
+        vcrypto,
+        name,
+        RecordStoreLimits {
+            subkey_cache_size: 16,
+            max_subkey_size: MAX_SUBKEY_SIZE,
+            max_record_total_size: MAX_RECORD_DATA_SIZE,
+            max_records,
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+async fn make_record_store_with_limits(
+    table_store: TableStore,
+    vcrypto: CryptoSystemVersion,
+    name: &str,
+    limits: RecordStoreLimits,
+) -> RecordStore<RemoteRecordDetail> {
+    // Each crypto kind gets its own tables so they start out empty
+    let name = format!("{}_{}", name, vcrypto.kind());
+    let mut record_store = RecordStore::new(table_store, &name, limits);
+    record_store.init().await.unwrap();
+    record_store
+}
+
+async fn add_record(
+    record_store: &mut RecordStore<RemoteRecordDetail>,
+    vcrypto: CryptoSystemVersion,
+) -> TypedKey {
+    let owner = vcrypto.generate_keypair();
+    let descriptor = SignedValueDescriptor::make_signature(
+        owner.key,
+        DHTSchema::dflt(2).unwrap().compile(),
+        0,
+        Timestamp::new(0),
+        vcrypto.clone(),
+        owner.secret,
+    )
+    .unwrap();
+    let key = TypedKey::new(vcrypto.kind(), owner.key);
+    let record = Record::new(
+        get_aligned_timestamp(),
+        Arc::new(descriptor),
+        RemoteRecordDetail {},
+    )
+    .unwrap();
+    record_store.new_record(key, record).await.unwrap();
+    key
+}
+
+async fn read_record(
+    record_store: &mut RecordStore<RemoteRecordDetail>,
+    key: TypedKey,
+    count: usize,
+) {
+    for _ in 0..count {
+        let get_result = record_store.get_subkey(key, 0, false).await.unwrap();
+        assert!(get_result.unwrap().opt_value.is_none());
+    }
+}
+
+pub async fn test_get_subkey_does_not_change_record(
+    table_store: TableStore,
+    vcrypto: CryptoSystemVersion,
+) {
+    let mut record_store =
+        make_record_store(table_store, vcrypto.clone(), "test_get_subkey", None).await;
+    let key = add_record(&mut record_store, vcrypto.clone()).await;
+    record_store.flush().await.unwrap();
+    assert_eq!(record_store.changed_record_count(), 0);
+
+    // Reads are counted, but do not cause the record to be written back
+    read_record(&mut record_store, key, 3).await;
+    assert_eq!(record_store.changed_record_count(), 0);
+    let access_stats = record_store
+        .peek_record(key, |r| r.access_stats().clone())
+        .unwrap();
+    assert_eq!(access_stats.get_count, 3);
+    assert_eq!(access_stats.set_count, 0);
+    assert!(access_stats.last_access_ts.is_some());
+
+    // Missing records are not available
+    let missing = TypedKey::new(vcrypto.kind(), vcrypto.generate_keypair().key);
+    assert!(record_store
+        .get_subkey(missing, 0, false)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+pub async fn test_evict_rarely_used_record(table_store: TableStore, vcrypto: CryptoSystemVersion) {
+    let mut record_store = make_record_store(
+        table_store,
+        vcrypto.clone(),
+        "test_evict_rarely_used",
+        Some(2),
+    )
+    .await;
+    let key_a = add_record(&mut record_store, vcrypto.clone()).await;
+    let key_b = add_record(&mut record_store, vcrypto.clone()).await;
+
+    // 'a' is least recently used, but 'b' has been read less
+    read_record(&mut record_store, key_a, 3).await;
+    read_record(&mut record_store, key_b, 1).await;
+
+    // Making room for a new record evicts the rarely used one
+    let key_c = add_record(&mut record_store, vcrypto.clone()).await;
+    assert!(record_store.contains_record(key_a));
+    assert!(!record_store.contains_record(key_b));
+    assert!(record_store.contains_record(key_c));
+}
+
+pub async fn test_evict_lru_on_tie(table_store: TableStore, vcrypto: CryptoSystemVersion) {
+    let mut record_store = make_record_store(
+        table_store,
+        vcrypto.clone(),
+        "test_evict_lru_on_tie",
+        Some(2),
+    )
+    .await;
+    let key_a = add_record(&mut record_store, vcrypto.clone()).await;
+    let key_b = add_record(&mut record_store, vcrypto.clone()).await;
+
+    // Records that have been used equally go in LRU order
+    let key_c = add_record(&mut record_store, vcrypto.clone()).await;
+    assert!(!record_store.contains_record(key_a));
+    assert!(record_store.contains_record(key_b));
+    assert!(record_store.contains_record(key_c));
+}
+
+pub async fn test_reclaim_space(table_store: TableStore, vcrypto: CryptoSystemVersion) {
+    let mut record_store =
+        make_record_store(table_store, vcrypto.clone(), "test_reclaim_space", None).await;
+    let key_a = add_record(&mut record_store, vcrypto.clone()).await;
+    let key_b = add_record(&mut record_store, vcrypto.clone()).await;
+    let key_c = add_record(&mut record_store, vcrypto.clone()).await;
+    read_record(&mut record_store, key_a, 2).await;
+    read_record(&mut record_store, key_c, 1).await;
+
+    // The least used record goes first
+    assert!(record_store.reclaim_space(1).await > 0);
+    assert!(record_store.contains_record(key_a));
+    assert!(!record_store.contains_record(key_b));
+    assert!(record_store.contains_record(key_c));
+
+    // Then the next least used one
+    assert!(record_store.reclaim_space(1).await > 0);
+    assert!(record_store.contains_record(key_a));
+    assert!(!record_store.contains_record(key_c));
+
+    // Nothing is evicted when no space is asked for
+    assert_eq!(record_store.reclaim_space(0).await, 0);
+    assert!(record_store.contains_record(key_a));
+}
+
+pub async fn test_evict_over_high_watermark(table_store: TableStore, vcrypto: CryptoSystemVersion) {
+    // Watermarks at 2% and 1% of a megabyte, so a few dozen empty records go over them
+    let mut record_store = make_record_store_with_limits(
+        table_store.clone(),
+        vcrypto.clone(),
+        "test_evict_over_high_watermark",
+        RecordStoreLimits {
+            subkey_cache_size: 16,
+            max_subkey_size: MAX_SUBKEY_SIZE,
+            max_record_total_size: MAX_RECORD_DATA_SIZE,
+            max_storage_space_mb: Some(1),
+            storage_high_watermark_percent: Some(2),
+            storage_low_watermark_percent: Some(1),
+            ..Default::default()
+        },
+    )
+    .await;
+    let (_, _, high_watermark, limit) = record_store.storage_usage();
+    let high_watermark = high_watermark.expect("should have a high watermark");
+    let low_watermark = 1_048_576u64 / 100;
+    assert_eq!(high_watermark, 1_048_576u64 / 100 * 2);
+    assert_eq!(limit, Some(1_048_576u64));
+
+    // Nothing is evicted at or under the high watermark
+    let mut keys = Vec::new();
+    loop {
+        keys.push(add_record(&mut record_store, vcrypto.clone()).await);
+        if record_store.storage_usage().1 > high_watermark {
+            break;
+        }
+        assert_eq!(record_store.evict_over_high_watermark(), None);
+    }
+    let used_before = record_store.storage_usage().1;
+
+    // Past it, least recently used records go until the low watermark is reached
+    let last_key = *keys.last().unwrap();
+    read_record(&mut record_store, keys[0], 1).await;
+    let (evicted_count, evicted_space) = record_store
+        .evict_over_high_watermark()
+        .expect("should evict");
+    assert!(evicted_count > 0 && evicted_count < keys.len());
+    assert!(used_before - evicted_space <= low_watermark);
+    assert!(record_store.contains_record(keys[0]));
+    assert!(!record_store.contains_record(keys[1]));
+    assert!(record_store.contains_record(last_key));
+
+    // Space already waiting to be purged does not count towards the high watermark
+    assert_eq!(record_store.evict_over_high_watermark(), None);
+    record_store.flush().await.unwrap();
+    let (record_count, used, _, _) = record_store.storage_usage();
+    assert_eq!(record_count, keys.len() - evicted_count);
+    assert_eq!(used, used_before - evicted_space);
+    assert_eq!(record_store.evict_over_high_watermark(), None);
+
+    // Stores without a storage space limit never evict over a watermark
+    let mut record_store = make_record_store_with_limits(
+        table_store,
+        vcrypto.clone(),
+        "test_evict_no_watermark",
+        RecordStoreLimits {
+            subkey_cache_size: 16,
+            max_subkey_size: MAX_SUBKEY_SIZE,
+            max_record_total_size: MAX_RECORD_DATA_SIZE,
+            storage_high_watermark_percent: Some(2),
+            storage_low_watermark_percent: Some(1),
+            ..Default::default()
+        },
+    )
+    .await;
+    for _ in 0..keys.len() {
+        add_record(&mut record_store, vcrypto.clone()).await;
+    }
+    assert_eq!(record_store.storage_usage().2, None);
+    assert_eq!(record_store.evict_over_high_watermark(), None);
+}
+
+pub async fn test_watch_persisted(table_store: TableStore, vcrypto: CryptoSystemVersion) {
+    let limits = RecordStoreLimits {
+        subkey_cache_size: 16,
+        max_subkey_size: MAX_SUBKEY_SIZE,
+        max_record_total_size: MAX_RECORD_DATA_SIZE,
+        public_watch_limit: 8,
+        member_watch_limit: 8,
+        max_watch_expiration: TimestampDuration::new(600_000_000),
+        min_watch_expiration: TimestampDuration::new(1_000_000),
+        ..Default::default()
+    };
+    let mut record_store = make_record_store_with_limits(
+        table_store.clone(),
+        vcrypto.clone(),
+        "watch_persisted",
+        limits,
+    )
+    .await;
+    let key = add_record(&mut record_store, vcrypto.clone()).await;
+
+    let route_id = RouteId::new([1u8; CRYPTO_KEY_LENGTH]);
+    let params = WatchParameters {
+        subkeys: ValueSubkeyRangeSet::full(),
+        expiration: Timestamp::new(0),
+        count: 3,
+        watcher: vcrypto.generate_keypair().key,
+        target: Target::PrivateRoute(route_id),
+        target_route_blob: Some(vec![1, 2, 3]),
+    };
+    let watch_result = record_store.watch_record(key, params, None).await.unwrap();
+    assert!(matches!(watch_result, WatchResult::Created { .. }));
+    record_store.flush().await.unwrap();
+    drop(record_store);
+
+    // The private route the watch notifies comes back with it
+    let mut record_store =
+        make_record_store_with_limits(table_store, vcrypto, "watch_persisted", limits).await;
+    let (watch_list, _) = record_store.move_watches(key, None).unwrap();
+    assert_eq!(watch_list.watches.len(), 1);
+    let params = &watch_list.watches[0].params;
+    assert_eq!(params.target, Target::PrivateRoute(route_id));
+    assert_eq!(params.target_route_blob, Some(vec![1, 2, 3]));
+}
+
+pub async fn test_all() {
+    let api = startup().await;
+    let table_store = api.table_store().unwrap();
+    let crypto = api.crypto().unwrap();
+    for ck in VALID_CRYPTO_KINDS {
+        let vcrypto = crypto.get(ck).unwrap();
+        test_get_subkey_does_not_change_record(table_store.clone(), vcrypto.clone()).await;
+        test_evict_rarely_used_record(table_store.clone(), vcrypto.clone()).await;
+        test_evict_lru_on_tie(table_store.clone(), vcrypto.clone()).await;
+        test_reclaim_space(table_store.clone(), vcrypto.clone()).await;
+        test_evict_over_high_watermark(table_store.clone(), vcrypto.clone()).await;
+        test_watch_persisted(table_store.clone(), vcrypto).await;
+    }
+    api.shutdown().await;
+}
@@ -1,7 +1,13 @@
+mod signed_record_version_link;
+mod signed_value_batch;
 mod signed_value_data;
 mod signed_value_descriptor;
+mod subkey_digest;
 
 use super::*;
 
+pub use signed_record_version_link::*;
+pub use signed_value_batch::*;
 pub use signed_value_data::*;
 pub use signed_value_descriptor::*;
+pub use subkey_digest::*;
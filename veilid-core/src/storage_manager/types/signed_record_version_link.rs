@@ -0,0 +1,77 @@
+use super::*;
+
+/// A version link kept in the descriptor of a superseded record
+///
+/// The link is signed by the owner separately from the descriptor, so it can be added to a record
+/// after it was created without changing the descriptor signature that nodes predating links check.
+#[derive(Clone, PartialOrd, PartialEq, Eq, Ord, Serialize, Deserialize)]
+pub struct SignedRecordVersionLink {
+    link: DHTRecordVersionLink,
+    signature: Signature,
+}
+
+impl SignedRecordVersionLink {
+    pub fn new(link: DHTRecordVersionLink, signature: Signature) -> Self {
+        Self { link, signature }
+    }
+
+    pub fn link(&self) -> &DHTRecordVersionLink {
+        &self.link
+    }
+
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    /// Check the link was signed by the owner of the superseded record described by the arguments
+    pub fn validate(
+        &self,
+        owner: &PublicKey,
+        schema_data: &[u8],
+        version: u32,
+        vcrypto: CryptoSystemVersion,
+    ) -> VeilidAPIResult<()> {
+        let signed_data = Self::make_signed_data(owner, schema_data, version, &self.link);
+        vcrypto.verify(owner, &signed_data, &self.signature)
+    }
+
+    pub fn make_signature(
+        link: DHTRecordVersionLink,
+        owner: &PublicKey,
+        schema_data: &[u8],
+        version: u32,
+        vcrypto: CryptoSystemVersion,
+        owner_secret: SecretKey,
+    ) -> VeilidAPIResult<Self> {
+        let signed_data = Self::make_signed_data(owner, schema_data, version, &link);
+        let signature = vcrypto.sign(owner, &owner_secret, &signed_data)?;
+        Ok(Self { link, signature })
+    }
+
+    /// The link is bound to everything that makes up the key of the record it supersedes,
+    /// so it can not be moved to another record of the same owner
+    fn make_signed_data(
+        owner: &PublicKey,
+        schema_data: &[u8],
+        version: u32,
+        link: &DHTRecordVersionLink,
+    ) -> Vec<u8> {
+        let mut signed_data = Vec::with_capacity(
+            PUBLIC_KEY_LENGTH + schema_data.len() + 4 + DHTRecordVersionLink::FIXED_SIZE,
+        );
+        signed_data.extend_from_slice(&owner.bytes);
+        signed_data.extend_from_slice(schema_data);
+        signed_data.extend_from_slice(&version.to_le_bytes());
+        signed_data.extend_from_slice(&link.compile());
+        signed_data
+    }
+}
+
+impl fmt::Debug for SignedRecordVersionLink {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("SignedRecordVersionLink")
+            .field("link", &self.link)
+            .field("signature", &self.signature)
+            .finish()
+    }
+}
@@ -0,0 +1,203 @@
+use super::*;
+
+/// Prefixed to the signed bytes of a batch, so a batch signature can not be passed off as a single value signature
+const BATCH_SIGNATURE_DOMAIN: &[u8; 4] = b"VBAT";
+
+/// The most subkeys that may be written in one batch
+pub const MAX_VALUE_BATCH_LEN: usize = 32;
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// What a batch signature says about one of the subkeys in the batch
+#[derive(Clone, Debug, PartialOrd, PartialEq, Eq, Ord, Serialize, Deserialize)]
+pub struct ValueBatchEntry {
+    pub subkey: ValueSubkey,
+    pub seq: ValueSeqNum,
+    pub compressed: bool,
+    /// Hash of the value data as it is stored, compressed or not
+    pub data_hash: HashDigest,
+}
+
+/// The digest of a batch of subkey writes that was signed once for all of them
+///
+/// Every value written in a batch keeps a copy of this so it can still be validated
+/// on its own, when it is read back or passed along to other nodes.
+#[derive(Clone, Debug, PartialOrd, PartialEq, Eq, Ord, Serialize, Deserialize)]
+pub struct ValueBatchDigest {
+    entries: Vec<ValueBatchEntry>,
+}
+
+impl ValueBatchDigest {
+    pub fn new(entries: Vec<ValueBatchEntry>) -> VeilidAPIResult<Self> {
+        if entries.is_empty() || entries.len() > MAX_VALUE_BATCH_LEN {
+            apibail_parse_error!("invalid value batch length", entries.len());
+        }
+        // Subkeys must be strictly increasing so each subkey appears once and the digest is canonical
+        if entries.windows(2).any(|w| w[0].subkey >= w[1].subkey) {
+            apibail_parse_error!("value batch subkeys out of order", entries.len());
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn entries(&self) -> &[ValueBatchEntry] {
+        &self.entries
+    }
+
+    pub fn entry(&self, subkey: ValueSubkey) -> Option<&ValueBatchEntry> {
+        self.entries
+            .binary_search_by_key(&subkey, |e| e.subkey)
+            .ok()
+            .map(|i| &self.entries[i])
+    }
+
+    pub fn total_size(&self) -> usize {
+        mem::size_of::<Self>() + self.entries.len() * mem::size_of::<ValueBatchEntry>()
+    }
+
+    pub(super) fn make_signature_bytes(&self, owner: &PublicKey) -> Vec<u8> {
+        let mut batch_bytes = Vec::with_capacity(
+            4 + PUBLIC_KEY_LENGTH + self.entries.len() * (4 + 4 + 1 + HASH_DIGEST_LENGTH),
+        );
+
+        batch_bytes.extend_from_slice(BATCH_SIGNATURE_DOMAIN);
+        // Add owner to signature
+        batch_bytes.extend_from_slice(&owner.bytes);
+        // Add each subkey's sequence number and data hash to signature
+        for e in &self.entries {
+            batch_bytes.extend_from_slice(&e.subkey.to_le_bytes());
+            batch_bytes.extend_from_slice(&e.seq.to_le_bytes());
+            batch_bytes.push(u8::from(e.compressed));
+            batch_bytes.extend_from_slice(&e.data_hash.bytes);
+        }
+
+        batch_bytes
+    }
+}
+
+/// A value for one subkey of a batch
+#[derive(Clone, Debug, PartialOrd, PartialEq, Eq, Ord)]
+pub struct BatchedValue {
+    pub subkey: ValueSubkey,
+    pub value_data: ValueData,
+    /// The value data is lz4 compressed with the uncompressed size prepended
+    pub compressed: bool,
+}
+
+/// Values for several subkeys of one record, written by the same writer under one signature
+///
+/// Signing and sending each subkey on its own costs a signature, a verification and a fanout
+/// per subkey. A batch is signed once over the digest of all of its values, sent to each
+/// node once, and checked once by each node that stores it.
+#[derive(Clone, Debug, PartialOrd, PartialEq, Eq, Ord)]
+pub struct SignedValueBatch {
+    writer: PublicKey,
+    signature: Signature,
+    values: Vec<BatchedValue>,
+}
+
+impl SignedValueBatch {
+    pub fn new(
+        writer: PublicKey,
+        signature: Signature,
+        values: Vec<BatchedValue>,
+    ) -> VeilidAPIResult<Self> {
+        if values.iter().any(|v| v.value_data.writer() != &writer) {
+            apibail_parse_error!("value batch has more than one writer", writer);
+        }
+        Ok(Self {
+            writer,
+            signature,
+            values,
+        })
+    }
+
+    pub fn make_signature(
+        values: Vec<BatchedValue>,
+        owner: &PublicKey,
+        vcrypto: CryptoSystemVersion,
+        writer: KeyPair,
+    ) -> VeilidAPIResult<Self> {
+        let digest = Self::make_digest(&values, vcrypto.clone())?;
+
+        // create signature
+        let signature = vcrypto.sign(
+            &writer.key,
+            &writer.secret,
+            &digest.make_signature_bytes(owner),
+        )?;
+        Self::new(writer.key, signature, values)
+    }
+
+    /// Check the batch signature, once for all of the values in it
+    pub fn validate(
+        &self,
+        owner: &PublicKey,
+        vcrypto: CryptoSystemVersion,
+    ) -> VeilidAPIResult<ValueBatchDigest> {
+        let digest = Self::make_digest(&self.values, vcrypto.clone())?;
+        vcrypto.verify(
+            &self.writer,
+            &digest.make_signature_bytes(owner),
+            &self.signature,
+        )?;
+        Ok(digest)
+    }
+
+    /// Split a validated batch into values that can each be stored and validated on their own
+    pub fn into_signed_values(
+        self,
+        digest: ValueBatchDigest,
+    ) -> Vec<(ValueSubkey, SignedValueData)> {
+        self.values
+            .into_iter()
+            .map(|v| {
+                (
+                    v.subkey,
+                    SignedValueData::new_batched(
+                        v.value_data,
+                        self.signature,
+                        v.compressed,
+                        digest.clone(),
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    pub fn writer(&self) -> &PublicKey {
+        &self.writer
+    }
+
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    pub fn values(&self) -> &[BatchedValue] {
+        &self.values
+    }
+
+    pub fn subkeys(&self) -> ValueSubkeyRangeSet {
+        ValueSubkeyRangeSet::new_with_data(self.values.iter().map(|v| v.subkey).collect())
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.values.iter().any(|v| v.compressed)
+    }
+
+    fn make_digest(
+        values: &[BatchedValue],
+        vcrypto: CryptoSystemVersion,
+    ) -> VeilidAPIResult<ValueBatchDigest> {
+        ValueBatchDigest::new(
+            values
+                .iter()
+                .map(|v| ValueBatchEntry {
+                    subkey: v.subkey,
+                    seq: v.value_data.seq(),
+                    compressed: v.compressed,
+                    data_hash: vcrypto.generate_hash(v.value_data.data()),
+                })
+                .collect(),
+        )
+    }
+}
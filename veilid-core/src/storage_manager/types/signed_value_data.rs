@@ -1,5 +1,8 @@
 use super::*;
 
+/// Prefixed to the signed bytes of a compressed value, so it can not be passed off as an uncompressed one
+const COMPRESSED_SIGNATURE_DOMAIN: &[u8; 4] = b"LZ4V";
+
 /////////////////////////////////////////////////////////////////////////////////////////////////////
 ///
 
@@ -7,12 +10,34 @@ use super::*;
 pub struct SignedValueData {
     value_data: ValueData,
     signature: Signature,
+    /// The value data is lz4 compressed with the uncompressed size prepended
+    #[serde(default)]
+    compressed: bool,
+    /// Set if the value was written in a batch and the signature is over the whole batch
+    #[serde(default)]
+    batch: Option<ValueBatchDigest>,
 }
 impl SignedValueData {
-    pub fn new(value_data: ValueData, signature: Signature) -> Self {
+    pub fn new(value_data: ValueData, signature: Signature, compressed: bool) -> Self {
+        Self {
+            value_data,
+            signature,
+            compressed,
+            batch: None,
+        }
+    }
+
+    pub fn new_batched(
+        value_data: ValueData,
+        signature: Signature,
+        compressed: bool,
+        batch: ValueBatchDigest,
+    ) -> Self {
         Self {
             value_data,
             signature,
+            compressed,
+            batch: Some(batch),
         }
     }
 
@@ -22,28 +47,64 @@ impl SignedValueData {
         subkey: ValueSubkey,
         vcrypto: CryptoSystemVersion,
     ) -> VeilidAPIResult<()> {
-        let node_info_bytes = Self::make_signature_bytes(&self.value_data, owner, subkey)?;
+        if let Some(batch) = &self.batch {
+            // The batch must vouch for exactly this value at this subkey
+            let Some(entry) = batch.entry(subkey) else {
+                apibail_generic!("subkey is not part of the value batch");
+            };
+            if entry.seq != self.value_data.seq()
+                || entry.compressed != self.compressed
+                || entry.data_hash != vcrypto.generate_hash(self.value_data.data())
+            {
+                apibail_generic!("value does not match the value batch");
+            }
+            return vcrypto.verify(
+                self.value_data.writer(),
+                &batch.make_signature_bytes(owner),
+                &self.signature,
+            );
+        }
+
+        let node_info_bytes =
+            Self::make_signature_bytes(&self.value_data, self.compressed, owner, subkey)?;
         // validate signature
         vcrypto.verify(self.value_data.writer(), &node_info_bytes, &self.signature)
     }
 
     pub fn make_signature(
         value_data: ValueData,
+        compressed: bool,
         owner: &PublicKey,
         subkey: ValueSubkey,
         vcrypto: CryptoSystemVersion,
         writer_secret: SecretKey,
     ) -> VeilidAPIResult<Self> {
-        let node_info_bytes = Self::make_signature_bytes(&value_data, owner, subkey)?;
+        let node_info_bytes = Self::make_signature_bytes(&value_data, compressed, owner, subkey)?;
 
         // create signature
         let signature = vcrypto.sign(value_data.writer(), &writer_secret, &node_info_bytes)?;
         Ok(Self {
             value_data,
             signature,
+            compressed,
+            batch: None,
         })
     }
 
+    /// Compress value data for signing if that makes it smaller
+    ///
+    /// Returns the value data to sign and whether or not it was compressed.
+    pub fn compress_value_data(value_data: ValueData) -> VeilidAPIResult<(ValueData, bool)> {
+        let compressed_data = compress_prepend_size(value_data.data());
+        if compressed_data.len() >= value_data.data().len() {
+            return Ok((value_data, false));
+        }
+        Ok((
+            ValueData::new_with_seq(value_data.seq(), compressed_data, *value_data.writer())?,
+            true,
+        ))
+    }
+
     pub fn value_data(&self) -> &ValueData {
         &self.value_data
     }
@@ -52,22 +113,50 @@ impl SignedValueData {
         &self.signature
     }
 
+    pub fn is_compressed(&self) -> bool {
+        self.compressed
+    }
+
+    pub fn batch(&self) -> Option<&ValueBatchDigest> {
+        self.batch.as_ref()
+    }
+
+    /// Get the value data as the application wrote it, decompressing it if required
+    pub fn decompressed_value_data(&self) -> VeilidAPIResult<ValueData> {
+        if !self.compressed {
+            return Ok(self.value_data.clone());
+        }
+        let data = decompress_size_prepended(self.value_data.data(), Some(ValueData::MAX_LEN))?;
+        ValueData::new_with_seq(self.value_data.seq(), data, *self.value_data.writer())
+    }
+
     pub fn data_size(&self) -> usize {
         self.value_data.data_size()
     }
 
     pub fn total_size(&self) -> usize {
-        (mem::size_of::<Self>() - mem::size_of::<ValueData>()) + self.value_data.total_size()
+        (mem::size_of::<Self>() - mem::size_of::<ValueData>())
+            + self.value_data.total_size()
+            + self
+                .batch
+                .as_ref()
+                .map(|b| b.total_size())
+                .unwrap_or_default()
     }
 
     fn make_signature_bytes(
         value_data: &ValueData,
+        compressed: bool,
         owner: &PublicKey,
         subkey: ValueSubkey,
     ) -> VeilidAPIResult<Vec<u8>> {
         let mut node_info_bytes =
-            Vec::with_capacity(PUBLIC_KEY_LENGTH + 4 + 4 + value_data.data().len());
+            Vec::with_capacity(4 + PUBLIC_KEY_LENGTH + 4 + 4 + value_data.data().len());
 
+        // Mark compressed values, uncompressed values are signed the same as they always have been
+        if compressed {
+            node_info_bytes.extend_from_slice(COMPRESSED_SIGNATURE_DOMAIN);
+        }
         // Add owner to signature
         node_info_bytes.extend_from_slice(&owner.bytes);
         // Add subkey to signature
@@ -80,3 +169,52 @@ impl SignedValueData {
         Ok(node_info_bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_value_data(data: Vec<u8>) -> ValueData {
+        ValueData::new_with_seq(3, data, CryptoKey { bytes: [1; 32] }).unwrap()
+    }
+
+    #[test]
+    fn compress_value_data_round_trip() {
+        let value_data =
+            make_value_data(br#"{"name":"veilid","tags":["a","a","a","a"]}"#.repeat(100));
+        let (compressed_value_data, compressed) =
+            SignedValueData::compress_value_data(value_data.clone()).unwrap();
+        assert!(compressed);
+        assert!(compressed_value_data.data_size() < value_data.data_size());
+        assert_eq!(compressed_value_data.seq(), value_data.seq());
+
+        let signed_value_data = SignedValueData::new(
+            compressed_value_data,
+            Signature::new([0; SIGNATURE_LENGTH]),
+            true,
+        );
+        assert_eq!(
+            signed_value_data.decompressed_value_data().unwrap(),
+            value_data
+        );
+    }
+
+    #[test]
+    fn compress_value_data_skips_incompressible() {
+        let value_data = make_value_data(vec![7]);
+        let (uncompressed_value_data, compressed) =
+            SignedValueData::compress_value_data(value_data.clone()).unwrap();
+        assert!(!compressed);
+        assert_eq!(uncompressed_value_data, value_data);
+    }
+
+    #[test]
+    fn decompress_value_data_rejects_oversize() {
+        let signed_value_data = SignedValueData::new(
+            make_value_data(compress_prepend_size(&vec![0; ValueData::MAX_LEN + 1])),
+            Signature::new([0; SIGNATURE_LENGTH]),
+            true,
+        );
+        assert!(signed_value_data.decompressed_value_data().is_err());
+    }
+}
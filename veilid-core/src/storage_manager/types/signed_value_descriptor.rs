@@ -8,21 +8,49 @@ pub struct SignedValueDescriptor {
     owner: PublicKey,
     schema_data: Vec<u8>,
     signature: Signature,
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    expiration: Timestamp,
+    #[serde(default)]
+    superseded_by: Option<SignedRecordVersionLink>,
 }
 impl SignedValueDescriptor {
-    pub fn new(owner: PublicKey, schema_data: Vec<u8>, signature: Signature) -> Self {
+    pub fn new(
+        owner: PublicKey,
+        schema_data: Vec<u8>,
+        signature: Signature,
+        version: u32,
+        expiration: Timestamp,
+    ) -> Self {
         Self {
             owner,
             schema_data,
             signature,
+            version,
+            expiration,
+            superseded_by: None,
         }
     }
 
+    pub fn with_superseded_by(mut self, superseded_by: SignedRecordVersionLink) -> Self {
+        self.superseded_by = Some(superseded_by);
+        self
+    }
+
     pub fn validate(&self, vcrypto: CryptoSystemVersion) -> VeilidAPIResult<()> {
         // validate signature
-        vcrypto.verify(&self.owner, &self.schema_data, &self.signature)?;
+        let signed_data = Self::make_signed_data(&self.schema_data, self.version, self.expiration);
+        vcrypto.verify(&self.owner, &signed_data, &self.signature)?;
         // validate schema
         DHTSchema::try_from(self.schema_data.as_slice())?;
+        // validate version link
+        if let Some(superseded_by) = &self.superseded_by {
+            if superseded_by.link().version() <= self.version {
+                apibail_generic!("version link does not point to a newer version");
+            }
+            superseded_by.validate(&self.owner, &self.schema_data, self.version, vcrypto)?;
+        }
         Ok(())
     }
 
@@ -42,31 +70,121 @@ impl SignedValueDescriptor {
         &self.signature
     }
 
+    /// Which version of the record this is, records that supersede another record have a higher version
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// When the record expires and storage nodes may stop serving it, None if it never expires
+    pub fn expiration(&self) -> Option<Timestamp> {
+        if self.expiration.as_u64() == 0 {
+            None
+        } else {
+            Some(self.expiration)
+        }
+    }
+
+    /// The link to the record that supersedes this one, if the owner has published one
+    pub fn superseded_by(&self) -> Option<&SignedRecordVersionLink> {
+        self.superseded_by.as_ref()
+    }
+
+    pub fn is_expired(&self, cur_ts: Timestamp) -> bool {
+        self.expiration().map(|e| e <= cur_ts).unwrap_or(false)
+    }
+
+    /// True if this descriptor is for the same record as 'other' but lives longer
+    /// Owners extend a record's life by re-signing its descriptor with a later expiration
+    pub fn extends_expiration_of(&self, other: &Self) -> bool {
+        if self.cmp_no_sig(other) != cmp::Ordering::Equal {
+            return false;
+        }
+        match (self.expiration(), other.expiration()) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(new_exp), Some(old_exp)) => new_exp > old_exp,
+        }
+    }
+
+    /// Combine this descriptor with the owner updates found in a newer descriptor for the same record
+    /// Returns None if 'other' has neither a later expiration nor a newer version link than this one.
+    /// Both descriptors must already be validated.
+    pub fn updated_by(&self, other: &Self) -> Option<Self> {
+        if self.cmp_no_sig(other) != cmp::Ordering::Equal {
+            return None;
+        }
+        let extends = other.extends_expiration_of(self);
+        let links = match (&other.superseded_by, &self.superseded_by) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(new_link), Some(old_link)) => {
+                new_link.link().version() > old_link.link().version()
+            }
+        };
+        if !extends && !links {
+            return None;
+        }
+
+        // The expiration is covered by the descriptor signature, the link carries its own
+        let mut updated = if extends { other.clone() } else { self.clone() };
+        updated.superseded_by = if links {
+            other.superseded_by.clone()
+        } else {
+            self.superseded_by.clone()
+        };
+        Some(updated)
+    }
+
     pub fn make_signature(
         owner: PublicKey,
         schema_data: Vec<u8>,
+        version: u32,
+        expiration: Timestamp,
         vcrypto: CryptoSystemVersion,
         owner_secret: SecretKey,
     ) -> VeilidAPIResult<Self> {
         // create signature
-        let signature = vcrypto.sign(&owner, &owner_secret, &schema_data)?;
+        let signed_data = Self::make_signed_data(&schema_data, version, expiration);
+        let signature = vcrypto.sign(&owner, &owner_secret, &signed_data)?;
         Ok(Self {
             owner,
             schema_data,
             signature,
+            version,
+            expiration,
+            superseded_by: None,
         })
     }
 
+    /// The first version of a record only signs the schema data, so descriptors made before versioning still validate
+    /// Records that expire also sign the version, even if it is zero, followed by the expiration
+    fn make_signed_data(schema_data: &[u8], version: u32, expiration: Timestamp) -> Vec<u8> {
+        let mut signed_data = schema_data.to_vec();
+        if version != 0 || expiration.as_u64() != 0 {
+            signed_data.extend_from_slice(&version.to_le_bytes());
+        }
+        if expiration.as_u64() != 0 {
+            signed_data.extend_from_slice(&expiration.as_u64().to_le_bytes());
+        }
+        signed_data
+    }
+
     pub fn total_size(&self) -> usize {
         mem::size_of::<Self>() + self.schema_data.len()
     }
 
+    /// Compare the parts of the descriptor that identify the record
+    /// The expiration and version link are not compared, as the owner may update them without changing the record
     pub fn cmp_no_sig(&self, other: &Self) -> cmp::Ordering {
         let o = self.owner.cmp(&other.owner);
         if o != cmp::Ordering::Equal {
             return o;
         }
-        self.schema_data.cmp(&other.schema_data)
+        let o = self.schema_data.cmp(&other.schema_data);
+        if o != cmp::Ordering::Equal {
+            return o;
+        }
+        self.version.cmp(&other.version)
     }
 }
 
@@ -76,6 +194,9 @@ impl fmt::Debug for SignedValueDescriptor {
             .field("owner", &self.owner)
             .field("schema_data", &format!("{:?}", &self.schema_data))
             .field("signature", &self.signature)
+            .field("version", &self.version)
+            .field("expiration", &self.expiration)
+            .field("superseded_by", &self.superseded_by)
             .finish()
     }
 }
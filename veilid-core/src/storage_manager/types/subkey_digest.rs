@@ -0,0 +1,33 @@
+use super::*;
+
+/// An inclusive range of subkeys, as sent in a DigestValue question
+pub type SubkeyDigestRange = (ValueSubkey, ValueSubkey);
+
+/// Hash the sequence numbers of a run of subkeys, in ascending subkey order
+/// Subkeys that have not been written are hashed as ValueSeqNum::MAX, just as InspectValue reports them,
+/// so two nodes holding the same values for a range always produce the same digest for it
+pub fn subkey_seqs_digest(vcrypto: &CryptoSystemVersion, seqs: &[ValueSeqNum]) -> HashDigest {
+    let mut data = Vec::with_capacity(seqs.len() * mem::size_of::<ValueSeqNum>());
+    for seq in seqs {
+        data.extend_from_slice(&seq.to_le_bytes());
+    }
+    vcrypto.generate_hash(&data)
+}
+
+/// Split a subkey range into at most 'parts' contiguous ranges of nearly equal size
+pub fn split_subkey_digest_range(range: SubkeyDigestRange, parts: usize) -> Vec<SubkeyDigestRange> {
+    let (start, end) = range;
+    let len = (end - start) as u64 + 1;
+    let parts = (parts.max(1) as u64).min(len);
+
+    let mut out = Vec::with_capacity(parts as usize);
+    let mut part_start = start as u64;
+    for n in 0..parts {
+        // Spread the remainder over the first ranges
+        let part_len = len / parts + u64::from(n < len % parts);
+        let part_end = part_start + part_len - 1;
+        out.push((part_start as ValueSubkey, part_end as ValueSubkey));
+        part_start = part_end + 1;
+    }
+    out
+}
@@ -241,107 +241,125 @@ impl StorageManager {
         watch_id: u64,
     ) -> VeilidAPIResult<NetworkResult<()>> {
         // Update local record store with new value
-        let (is_value_seq_newer, opt_update_callback, value) = {
-            let mut inner = self.lock().await?;
+        // The lock is held until the update is announced, so value changes
+        // for a record are delivered to the update callback in sequence order
+        let mut inner = self.lock().await?;
 
-            // Don't process update if the record is closed
-            let Some(opened_record) = inner.opened_records.get_mut(&key) else {
-                return Ok(NetworkResult::value(()));
-            };
+        // Don't process update if the record is closed
+        let Some(opened_record) = inner.opened_records.get_mut(&key) else {
+            return Ok(NetworkResult::value(()));
+        };
 
-            // No active watch means no callback
-            let Some(mut active_watch) = opened_record.active_watch() else {
-                return Ok(NetworkResult::value(()));
-            };
+        // No active watch means no callback
+        let Some(mut active_watch) = opened_record.active_watch() else {
+            return Ok(NetworkResult::value(()));
+        };
 
-            // If the watch id doesn't match, then don't process this
-            if active_watch.id != watch_id {
-                return Ok(NetworkResult::value(()));
-            }
+        // If the watch id doesn't match, then don't process this
+        if active_watch.id != watch_id {
+            return Ok(NetworkResult::value(()));
+        }
 
-            // If the reporting node is not the same as our watch, don't process the value change
-            if !active_watch
-                .watch_node
-                .node_ids()
-                .contains(&inbound_node_id)
-            {
-                return Ok(NetworkResult::value(()));
-            }
+        // If the reporting node is not the same as our watch, don't process the value change
+        if !active_watch
+            .watch_node
+            .node_ids()
+            .contains(&inbound_node_id)
+        {
+            return Ok(NetworkResult::value(()));
+        }
 
-            if count > active_watch.count {
-                // If count is greater than our requested count then this is invalid, cancel the watch
-                log_stor!(debug "watch count went backward: {}: {}/{}", key, count, active_watch.count);
-                // Force count to zero
-                count = 0;
-                opened_record.clear_active_watch();
-            } else if count == 0 {
-                // If count is zero, we're done, cancel the watch and the app can renew it if it wants
-                log_stor!(debug "watch count finished: {}", key);
-                opened_record.clear_active_watch();
-            } else {
-                log_stor!(debug
-                    "watch count decremented: {}: {}/{}",
-                    key,
-                    count,
-                    active_watch.count
-                );
-                active_watch.count = count;
-                opened_record.set_active_watch(active_watch);
-            }
+        if count > active_watch.count {
+            // If count is greater than our requested count then this is invalid, cancel the watch
+            log_stor!(debug "watch count went backward: {}: {}/{}", key, count, active_watch.count);
+            // Force count to zero
+            count = 0;
+            opened_record.clear_active_watch();
+        } else if count == 0 {
+            // If count is zero, we're done, cancel the watch and the app can renew it if it wants
+            log_stor!(debug "watch count finished: {}", key);
+            opened_record.clear_active_watch();
+        } else {
+            log_stor!(debug
+                "watch count decremented: {}: {}/{}",
+                key,
+                count,
+                active_watch.count
+            );
+            active_watch.count = count;
+            opened_record.set_active_watch(active_watch);
+        }
 
-            // Null out default value
-            let value = value.filter(|value| *value.value_data() != ValueData::default());
+        // Null out default value
+        let value = value.filter(|value| *value.value_data() != ValueData::default());
 
-            // Set the local value
-            let mut is_value_seq_newer = false;
-            if let Some(value) = &value {
-                let Some(first_subkey) = subkeys.first() else {
-                    apibail_internal!("should not have value without first subkey");
-                };
+        // Set the local value
+        let mut is_value_seq_newer = false;
+        let mut is_gap = false;
+        if let Some(value) = &value {
+            let Some(first_subkey) = subkeys.first() else {
+                apibail_internal!("should not have value without first subkey");
+            };
 
-                let last_get_result = inner
-                    .handle_get_local_value(key, first_subkey, true)
-                    .await?;
+            let last_get_result = inner
+                .handle_get_local_value(key, first_subkey, true)
+                .await?;
 
-                let descriptor = last_get_result.opt_descriptor.unwrap();
-                let schema = descriptor.schema()?;
-
-                // Validate with schema
-                if !schema.check_subkey_value_data(
-                    descriptor.owner(),
-                    first_subkey,
-                    value.value_data(),
-                ) {
-                    // Validation failed, ignore this value
-                    // Move to the next node
-                    return Ok(NetworkResult::invalid_message(format!(
-                        "Schema validation failed on subkey {}",
-                        first_subkey
-                    )));
-                }
+            let descriptor = last_get_result.opt_descriptor.unwrap();
+            let schema = descriptor.schema()?;
+
+            // Validate with schema
+            let Some(vcrypto) = self.unlocked_inner.crypto.get(key.kind) else {
+                return Ok(NetworkResult::invalid_message("unsupported cryptosystem"));
+            };
+            if !schema.check_subkey_value_data(
+                vcrypto,
+                descriptor.owner(),
+                first_subkey,
+                value.value_data(),
+                None,
+            ) {
+                // Validation failed, ignore this value
+                // Move to the next node
+                return Ok(NetworkResult::invalid_message(format!(
+                    "Schema validation failed on subkey {}",
+                    first_subkey
+                )));
+            }
 
-                // Make sure this value would actually be newer
+            // Make sure this value would actually be newer than what we have and what we have announced
+            // Duplicates and values that arrive after a newer one are dropped here
+            let opt_last_seq = last_get_result
+                .opt_value
+                .as_ref()
+                .map(|last_value| last_value.value_data().seq());
+            let Some(opened_record) = inner.opened_records.get_mut(&key) else {
+                return Ok(NetworkResult::value(()));
+            };
+            if let Some(gap) = opened_record.track_value_change_seq(
+                first_subkey,
+                value.value_data().seq(),
+                opt_last_seq,
+            ) {
                 is_value_seq_newer = true;
-                if let Some(last_value) = &last_get_result.opt_value {
-                    if value.value_data().seq() <= last_value.value_data().seq() {
-                        // inbound value is older than or equal to the sequence number that we have, just return the one we have
-                        is_value_seq_newer = false;
-                    }
-                }
-                if is_value_seq_newer {
-                    inner
-                        .handle_set_local_value(
-                            key,
-                            first_subkey,
-                            value.clone(),
-                            WatchUpdateMode::NoUpdate,
-                        )
-                        .await?;
+                is_gap = gap;
+                if is_gap {
+                    log_stor!(debug "value change skipped sequence numbers: {}:{}", key, first_subkey);
                 }
+            } else {
+                log_stor!(debug "value change duplicate or out of order: {}:{}", key, first_subkey);
             }
-
-            (is_value_seq_newer, inner.update_callback.clone(), value)
-        };
+            if is_value_seq_newer {
+                inner
+                    .handle_set_local_value(
+                        key,
+                        first_subkey,
+                        value.clone(),
+                        WatchUpdateMode::NoUpdate,
+                    )
+                    .await?;
+            }
+        }
 
         // Announce ValueChanged VeilidUpdate
         // * if the value in the update had a newer sequence number
@@ -350,16 +368,17 @@ impl StorageManager {
 
         let do_update = is_value_seq_newer || subkeys.len() > 1 || count == 0;
         if do_update {
-            if let Some(update_callback) = opt_update_callback {
+            if let Some(update_callback) = inner.update_callback.clone() {
                 update_callback(VeilidUpdate::ValueChange(Box::new(VeilidValueChange {
                     key,
                     subkeys,
                     count,
                     value: if is_value_seq_newer {
-                        Some(value.unwrap().value_data().clone())
+                        Some(value.unwrap().decompressed_value_data()?)
                     } else {
                         None
                     },
+                    gap: is_gap,
                 })));
             }
         }
@@ -0,0 +1,104 @@
+use super::*;
+use keyvaluedb_memorydb::InMemory;
+
+/// A low level database opened by one of the table store backends
+#[derive(Clone)]
+pub(crate) enum TableStoreDatabase {
+    Persistent(Database),
+    Memory(InMemory),
+}
+
+/// Run an expression against the low level database, whichever backend opened it
+macro_rules! with_database {
+    ($database:expr, |$db:ident| $body:expr) => {
+        match $database {
+            TableStoreDatabase::Persistent($db) => $body,
+            TableStoreDatabase::Memory($db) => $body,
+        }
+    };
+}
+pub(crate) use with_database;
+
+/// Keeps tables in memory for the life of the process, nothing is written to storage
+#[derive(Clone)]
+pub(crate) struct MemoryTableStoreDriver {
+    tables: Arc<Mutex<HashMap<String, InMemory>>>,
+}
+
+impl MemoryTableStoreDriver {
+    pub fn new() -> Self {
+        Self {
+            tables: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub async fn open(&self, table_name: &str, column_count: u32) -> VeilidAPIResult<InMemory> {
+        let mut tables = self.tables.lock();
+        if let Some(db) = tables.get(table_name) {
+            let existing_col_count = db.num_columns().map_err(VeilidAPIError::from)?;
+            if column_count > existing_col_count {
+                apibail_generic!(format!(
+                    "in-memory table can not increase column count {} -> {}",
+                    existing_col_count, column_count
+                ));
+            }
+            return Ok(db.clone());
+        }
+
+        let db = keyvaluedb_memorydb::create(column_count);
+        tables.insert(table_name.to_owned(), db.clone());
+        log_tstore!(
+            "opened in-memory table store '{}' with {} columns",
+            table_name,
+            column_count
+        );
+        Ok(db)
+    }
+
+    pub async fn delete(&self, table_name: &str) -> VeilidAPIResult<bool> {
+        Ok(self.tables.lock().remove(table_name).is_some())
+    }
+}
+
+/// The table store backend selected by `table_store.backend`
+#[derive(Clone)]
+pub(crate) enum TableStoreBackendDriver {
+    Persistent(TableStoreDriver),
+    Memory(MemoryTableStoreDriver),
+}
+
+impl TableStoreBackendDriver {
+    pub fn new(config: VeilidConfig) -> Self {
+        let backend = config.get().table_store.backend;
+        match backend {
+            VeilidConfigTableStoreBackend::Persistent => {
+                Self::Persistent(TableStoreDriver::new(config))
+            }
+            VeilidConfigTableStoreBackend::Memory => Self::Memory(MemoryTableStoreDriver::new()),
+        }
+    }
+
+    pub async fn open(
+        &self,
+        table_name: &str,
+        column_count: u32,
+    ) -> VeilidAPIResult<TableStoreDatabase> {
+        match self {
+            Self::Persistent(driver) => driver
+                .open(table_name, column_count)
+                .await
+                .map(TableStoreDatabase::Persistent),
+            Self::Memory(driver) => driver
+                .open(table_name, column_count)
+                .await
+                .map(TableStoreDatabase::Memory),
+        }
+    }
+
+    pub async fn delete(&self, table_name: &str) -> VeilidAPIResult<bool> {
+        match self {
+            Self::Persistent(driver) => driver.delete(table_name).await,
+            Self::Memory(driver) => driver.delete(table_name).await,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use super::*;
 
+mod backend;
 mod table_db;
+use backend::*;
 pub use table_db::*;
 
 pub mod tests;
@@ -22,7 +24,7 @@ struct TableStoreInner {
     opened: BTreeMap<String, Weak<TableDBUnlockedInner>>,
     encryption_key: Option<TypedSharedSecret>,
     all_table_names: HashMap<String, String>,
-    all_tables_db: Option<Database>,
+    all_tables_db: Option<TableStoreDatabase>,
     crypto: Option<Crypto>,
 }
 
@@ -32,7 +34,7 @@ struct TableStoreInner {
 pub struct TableStore {
     config: VeilidConfig,
     protected_store: ProtectedStore,
-    table_store_driver: TableStoreDriver,
+    table_store_driver: TableStoreBackendDriver,
     inner: Arc<Mutex<TableStoreInner>>, // Sync mutex here because TableDB drops can happen at any time
     async_lock: Arc<AsyncMutex<()>>,    // Async mutex for operations
 }
@@ -49,7 +51,7 @@ impl TableStore {
     }
     pub(crate) fn new(config: VeilidConfig, protected_store: ProtectedStore) -> Self {
         let inner = Self::new_inner();
-        let table_store_driver = TableStoreDriver::new(config.clone());
+        let table_store_driver = TableStoreBackendDriver::new(config.clone());
 
         Self {
             config,
@@ -60,6 +62,12 @@ impl TableStore {
         }
     }
 
+    /// The protected store that holds the device encryption key and other secrets
+    /// that should not be kept in a table
+    pub(crate) fn protected_store(&self) -> ProtectedStore {
+        self.protected_store.clone()
+    }
+
     pub(crate) fn set_crypto(&self, crypto: Crypto) {
         let mut inner = self.inner.lock();
         inner.crypto = Some(crypto);
@@ -74,7 +82,7 @@ impl TableStore {
         };
         let mut dbt = DBTransaction::new();
         dbt.put(0, ALL_TABLE_NAMES, &all_table_names_value);
-        if let Err(e) = all_tables_db.write(dbt).await {
+        if let Err(e) = with_database!(&all_tables_db, |db| db.write(dbt).await) {
             error!("failed to write all tables db: {}", e);
         }
     }
@@ -374,6 +382,15 @@ impl TableStore {
             self.save_device_encryption_key(device_encryption_key)
                 .await?;
         }
+        if device_encryption_key_changed {
+            self.config
+                .send_update(VeilidUpdate::IdentityChange(Box::new(
+                    VeilidIdentityChange {
+                        new_node_ids: vec![],
+                        new_device_encryption_key: true,
+                    },
+                )));
+        }
 
         // Deserialize all table names
         let all_tables_db = self
@@ -381,7 +398,7 @@ impl TableStore {
             .open("__veilid_all_tables", 1)
             .await
             .wrap_err("failed to create all tables table")?;
-        match all_tables_db.get(0, ALL_TABLE_NAMES).await {
+        match with_database!(&all_tables_db, |db| db.get(0, ALL_TABLE_NAMES).await) {
             Ok(Some(v)) => match deserialize_json_bytes::<HashMap<String, String>>(&v) {
                 Ok(all_table_names) => {
                     let mut inner = self.inner.lock();
@@ -482,7 +499,7 @@ impl TableStore {
             }
         }
 
-        // Open table db using platform-specific driver
+        // Open table db using the configured backend
         let mut db = match self
             .table_store_driver
             .open(&table_name, column_count)
@@ -500,7 +517,8 @@ impl TableStore {
         self.flush().await;
 
         // If more columns are available, open the low level db with the max column count but restrict the tabledb object to the number requested
-        let existing_col_count = db.num_columns().map_err(VeilidAPIError::from)?;
+        let existing_col_count =
+            with_database!(&db, |db| db.num_columns()).map_err(VeilidAPIError::from)?;
         if existing_col_count > column_count {
             drop(db);
             db = match self
@@ -517,7 +535,7 @@ impl TableStore {
             };
         }
 
-        // Wrap low-level Database in TableDB object
+        // Wrap low-level database in TableDB object
         let mut inner = self.inner.lock();
         let table_db = TableDB::new(
             table_name.clone(),
@@ -561,7 +579,7 @@ impl TableStore {
             }
         }
 
-        // Delete table db using platform-specific driver
+        // Delete table db using the configured backend
         let deleted = self.table_store_driver.delete(&table_name).await?;
         if !deleted {
             // Table missing? Just remove name
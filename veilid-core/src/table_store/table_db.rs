@@ -1,3 +1,4 @@
+use super::backend::*;
 use crate::*;
 
 cfg_if! {
@@ -25,7 +26,7 @@ impl CryptInfo {
 pub struct TableDBUnlockedInner {
     table: String,
     table_store: TableStore,
-    database: Database,
+    database: TableStoreDatabase,
     // Encryption and decryption key will be the same unless configured for an in-place migration
     encrypt_info: Option<CryptInfo>,
     decrypt_info: Option<CryptInfo>,
@@ -54,7 +55,7 @@ impl TableDB {
         table: String,
         table_store: TableStore,
         crypto: Crypto,
-        database: Database,
+        database: TableStoreDatabase,
         encryption_key: Option<TypedSharedSecret>,
         decryption_key: Option<TypedSharedSecret>,
         opened_column_count: u32,
@@ -91,8 +92,8 @@ impl TableDB {
     /// Get the total number of columns in the TableDB
     /// Not the number of columns that were opened, rather the total number that could be opened
     pub fn get_column_count(&self) -> VeilidAPIResult<u32> {
-        let db = &self.unlocked_inner.database;
-        db.num_columns().map_err(VeilidAPIError::from)
+        with_database!(&self.unlocked_inner.database, |db| db.num_columns())
+            .map_err(VeilidAPIError::from)
     }
 
     /// Encrypt buffer using encrypt key and prepend nonce to output
@@ -165,19 +166,20 @@ impl TableDB {
         }
         let db = self.unlocked_inner.database.clone();
         let mut out = Vec::new();
-        db.iter_keys(col, None, |k| {
-            let key = self.maybe_decrypt(k)?;
-            out.push(key);
-            Ok(Option::<()>::None)
-        })
-        .await
+        with_database!(&db, |db| db
+            .iter_keys(col, None, |k| {
+                let key = self.maybe_decrypt(k)?;
+                out.push(key);
+                Ok(Option::<()>::None)
+            })
+            .await)
         .map_err(VeilidAPIError::from)?;
         Ok(out)
     }
 
     /// Start a TableDB write transaction. The transaction object must be committed or rolled back before dropping.
     pub fn transact(&self) -> TableDBTransaction {
-        let dbt = self.unlocked_inner.database.transaction();
+        let dbt = with_database!(&self.unlocked_inner.database, |db| db.transaction());
         TableDBTransaction::new(self.clone(), dbt)
     }
 
@@ -190,13 +192,13 @@ impl TableDB {
             ));
         }
         let db = self.unlocked_inner.database.clone();
-        let mut dbt = db.transaction();
+        let mut dbt = with_database!(&db, |db| db.transaction());
         dbt.put(
             col,
             self.maybe_encrypt(key, true),
             self.maybe_encrypt(value, false),
         );
-        db.write(dbt).await.map_err(VeilidAPIError::generic)
+        with_database!(&db, |db| db.write(dbt).await).map_err(VeilidAPIError::generic)
     }
 
     /// Store a key in json format with a value in a column in the TableDB. Performs a single transaction immediately.
@@ -218,7 +220,7 @@ impl TableDB {
         }
         let db = self.unlocked_inner.database.clone();
         let key = self.maybe_encrypt(key, true);
-        match db.get(col, &key).await.map_err(VeilidAPIError::from)? {
+        match with_database!(&db, |db| db.get(col, &key).await).map_err(VeilidAPIError::from)? {
             Some(v) => Ok(Some(self.maybe_decrypt(&v).map_err(VeilidAPIError::from)?)),
             None => Ok(None),
         }
@@ -248,7 +250,7 @@ impl TableDB {
 
         let db = self.unlocked_inner.database.clone();
 
-        match db.delete(col, &key).await.map_err(VeilidAPIError::from)? {
+        match with_database!(&db, |db| db.delete(col, &key).await).map_err(VeilidAPIError::from)? {
             Some(v) => Ok(Some(self.maybe_decrypt(&v).map_err(VeilidAPIError::from)?)),
             None => Ok(None),
         }
@@ -313,8 +315,7 @@ impl TableDBTransaction {
         };
 
         let db = self.db.unlocked_inner.database.clone();
-        db.write(dbt)
-            .await
+        with_database!(&db, |db| db.write(dbt).await)
             .map_err(|e| VeilidAPIError::generic(format!("commit failed, transaction lost: {}", e)))
     }
 
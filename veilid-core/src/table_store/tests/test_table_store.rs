@@ -9,6 +9,20 @@ async fn startup() -> VeilidAPI {
         .expect("startup failed")
 }
 
+async fn startup_memory() -> VeilidAPI {
+    trace!("test_table_store: starting with memory backend");
+    let (update_callback, _) = setup_veilid_core();
+    let memory_config_callback: ConfigCallback = Arc::new(|key: String| {
+        if key == "table_store.backend" {
+            return Ok(Box::new(VeilidConfigTableStoreBackend::Memory));
+        }
+        config_callback(key)
+    });
+    api_startup(update_callback, memory_config_callback)
+        .await
+        .expect("startup failed")
+}
+
 async fn shutdown(api: VeilidAPI) {
     trace!("test_table_store: shutting down");
     api.shutdown().await;
@@ -250,8 +264,31 @@ pub async fn test_protect_unprotect(vcrypto: CryptoSystemVersion, ts: TableStore
     }
 }
 
-pub async fn test_all() {
-    let api = startup().await;
+pub async fn test_memory_backend() {
+    trace!("test_memory_backend");
+
+    let api = startup_memory().await;
+    let ts = api.table_store().unwrap();
+
+    // Tables live on while the node is running
+    let db = ts.open("test", 1).await.expect("should have opened");
+    db.store(0, b"foo", b"1234").await.expect("should store");
+    drop(db);
+    let db = ts.open("test", 1).await.expect("should have opened");
+    assert_eq!(db.load(0, b"foo").await.unwrap(), Some(b"1234".to_vec()));
+    drop(db);
+    shutdown(api).await;
+
+    // But nothing is kept once it shuts down
+    let api = startup_memory().await;
+    let ts = api.table_store().unwrap();
+    let db = ts.open("test", 1).await.expect("should have opened");
+    assert_eq!(db.load(0, b"foo").await.unwrap(), None);
+    drop(db);
+    shutdown(api).await;
+}
+
+async fn test_table_store(api: VeilidAPI) {
     let crypto = api.crypto().unwrap();
     let ts = api.table_store().unwrap();
 
@@ -267,3 +304,9 @@ pub async fn test_all() {
 
     shutdown(api).await;
 }
+
+pub async fn test_all() {
+    test_table_store(startup().await).await;
+    test_table_store(startup_memory().await).await;
+    test_memory_backend().await;
+}
@@ -1,4 +1,10 @@
+#[cfg(all(any(test, feature = "sim-network"), not(target_arch = "wasm32")))]
+pub mod sim_network_harness;
+#[cfg(feature = "unstable-blockstore")]
+pub mod test_block_store;
 pub mod test_dht;
 pub mod test_protected_store;
+#[cfg(all(any(test, feature = "sim-network"), not(target_arch = "wasm32")))]
+pub mod test_sim_integration;
 pub mod test_veilid_config;
 pub mod test_veilid_core;
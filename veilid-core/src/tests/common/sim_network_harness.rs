@@ -0,0 +1,245 @@
+//! Harness for integration tests that run many nodes in one process on the simulated network
+use super::test_veilid_config::*;
+use crate::core_context::api_startup_on_sim_network;
+use crate::network_manager::ProtocolTypeSet;
+use crate::*;
+
+pub use crate::network_manager::{SimLink, SimNetwork};
+
+/// Port every simulated node listens on, each at its own address
+const SIM_NODE_PORT: u16 = 5150;
+/// How often to poll while waiting for nodes
+const SIM_POLL_INTERVAL_MS: u32 = 250;
+
+/// The simulated address of the n-th node
+pub fn sim_node_address(n: usize) -> SocketAddr {
+    let n = n + 1;
+    SocketAddr::new(
+        IpAddr::V4(Ipv4Addr::new(11, 0, (n >> 8) as u8, (n & 0xFF) as u8)),
+        SIM_NODE_PORT,
+    )
+}
+
+fn sim_config_callback(
+    n: usize,
+    address: SocketAddr,
+    protocols: ProtocolTypeSet,
+    role: VeilidConfigRole,
+    bootstrap: &[String],
+    key: String,
+) -> ConfigCallbackReturn {
+    let udp = protocols.contains(ProtocolType::UDP);
+    let tcp = protocols.contains(ProtocolType::TCP);
+    match key.as_str() {
+        "namespace" => Ok(Box::new(format!("sim{}", n))),
+        "protected_store.always_use_insecure_storage" => Ok(Box::new(true)),
+        "network.routing_table.bootstrap" => Ok(Box::new(bootstrap.to_vec())),
+        // Bootstrap requests and receipts all come from the unbound address
+        "network.max_connections_per_ip4" => Ok(Box::new(1024u32)),
+        "network.max_connection_frequency_per_min" => Ok(Box::new(4096u32)),
+        "network.detect_address_changes" => Ok(Box::new(false)),
+        "network.protocol.udp.enabled" => Ok(Box::new(udp)),
+        "network.protocol.udp.listen_address" => Ok(Box::new(address.to_string())),
+        "network.protocol.tcp.connect" => Ok(Box::new(tcp)),
+        "network.protocol.tcp.listen" => Ok(Box::new(tcp)),
+        "network.protocol.tcp.listen_address" => Ok(Box::new(address.to_string())),
+        "network.protocol.tcp.max_connections" => Ok(Box::new(1024u32)),
+        "network.protocol.ws.connect" => Ok(Box::new(false)),
+        "network.protocol.ws.listen" => Ok(Box::new(false)),
+        "network.protocol.wss.connect" => Ok(Box::new(false)),
+        "network.protocol.wss.listen" => Ok(Box::new(false)),
+        "network.role" => Ok(Box::new(role)),
+        _ => config_callback(key),
+    }
+}
+
+/// A node running on the simulated network
+pub struct SimNode {
+    pub address: SocketAddr,
+    pub api: VeilidAPI,
+    updates: flume::Receiver<VeilidUpdate>,
+}
+
+impl SimNode {
+    pub fn ip_addr(&self) -> IpAddr {
+        self.address.ip()
+    }
+
+    pub fn routing_context(&self) -> RoutingContext {
+        self.api
+            .routing_context()
+            .unwrap()
+            .with_safety(SafetySelection::Unsafe(Sequencing::EnsureOrdered))
+            .unwrap()
+    }
+
+    /// Wait for an update that matches, dropping the ones before it
+    pub async fn wait_for_update<F: Fn(&VeilidUpdate) -> bool>(
+        &self,
+        timeout_ms: u32,
+        f: F,
+    ) -> Option<VeilidUpdate> {
+        timeout(timeout_ms, async {
+            while let Ok(update) = self.updates.recv_async().await {
+                if f(&update) {
+                    return Some(update);
+                }
+            }
+            None
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+}
+
+/// A set of nodes on a simulated network, all bootstrapping from the first one
+///
+/// Links between nodes can be changed at any time through the simulated network,
+/// for example to add latency or to partition nodes from each other.
+pub struct SimNetworkHarness {
+    sim_network: SimNetwork,
+    nodes: Vec<SimNode>,
+}
+
+impl SimNetworkHarness {
+    /// Start nodes speaking both UDP and TCP on a new simulated network and attach them
+    pub async fn start(node_count: usize, seed: u64, default_link: SimLink) -> Self {
+        Self::start_with_protocols(
+            node_count,
+            seed,
+            default_link,
+            ProtocolType::UDP | ProtocolType::TCP,
+        )
+        .await
+    }
+
+    /// Start nodes speaking only the given protocols on a new simulated network and attach them
+    /// Only UDP and TCP are simulated
+    pub async fn start_with_protocols(
+        node_count: usize,
+        seed: u64,
+        default_link: SimLink,
+        protocols: ProtocolTypeSet,
+    ) -> Self {
+        Self::start_inner(
+            node_count,
+            seed,
+            default_link,
+            protocols,
+            VeilidConfigRole::Full,
+        )
+        .await
+    }
+
+    /// Start nodes speaking both UDP and TCP on a new simulated network and attach them,
+    /// with the first node running in the bootstrap role
+    pub async fn start_with_bootstrap_role(
+        node_count: usize,
+        seed: u64,
+        default_link: SimLink,
+    ) -> Self {
+        Self::start_inner(
+            node_count,
+            seed,
+            default_link,
+            ProtocolType::UDP | ProtocolType::TCP,
+            VeilidConfigRole::Bootstrap,
+        )
+        .await
+    }
+
+    async fn start_inner(
+        node_count: usize,
+        seed: u64,
+        default_link: SimLink,
+        protocols: ProtocolTypeSet,
+        bootstrap_role: VeilidConfigRole,
+    ) -> Self {
+        assert!(node_count > 0);
+        let sim_network = SimNetwork::new(seed, default_link);
+        let bootstrap_scheme = if protocols.contains(ProtocolType::TCP) {
+            "tcp"
+        } else {
+            "udp"
+        };
+        let bootstrap = vec![format!("{}://{}", bootstrap_scheme, sim_node_address(0))];
+
+        let mut nodes = Vec::with_capacity(node_count);
+        for n in 0..node_count {
+            let address = sim_node_address(n);
+            // The first node is the bootstrap, it learns about the others as they arrive
+            let bootstrap = if n == 0 { vec![] } else { bootstrap.clone() };
+            let role = if n == 0 {
+                bootstrap_role
+            } else {
+                VeilidConfigRole::Full
+            };
+
+            let (sender, updates) = flume::unbounded();
+            // Each node has its own namespace, so they can all run in this process
+            let api = api_startup_on_sim_network(
+                Arc::new(move |update: VeilidUpdate| {
+                    let _ = sender.send(update);
+                }),
+                Arc::new(move |key: String| {
+                    sim_config_callback(n, address, protocols, role, &bootstrap, key)
+                }),
+                sim_network.clone(),
+            )
+            .await
+            .expect("startup failed");
+            api.attach().await.expect("attach failed");
+
+            nodes.push(SimNode {
+                address,
+                api,
+                updates,
+            });
+        }
+
+        Self { sim_network, nodes }
+    }
+
+    pub fn sim_network(&self) -> &SimNetwork {
+        &self.sim_network
+    }
+
+    pub fn nodes(&self) -> &[SimNode] {
+        &self.nodes
+    }
+
+    pub fn node(&self, n: usize) -> &SimNode {
+        &self.nodes[n]
+    }
+
+    /// Wait until every node is ready to use the public internet routing domain
+    /// Returns false if they are not all ready in time
+    pub async fn wait_for_ready(&self, timeout_ms: u32) -> bool {
+        let start_ts = get_timestamp();
+        loop {
+            let mut ready = true;
+            for node in &self.nodes {
+                let state = node.api.get_state().await.expect("should get state");
+                if !state.attachment.public_internet_ready {
+                    ready = false;
+                    break;
+                }
+            }
+            if ready {
+                return true;
+            }
+            if get_timestamp() - start_ts > (timeout_ms as u64) * 1000 {
+                return false;
+            }
+            sleep(SIM_POLL_INTERVAL_MS).await;
+        }
+    }
+
+    /// Shut down all nodes
+    pub async fn shutdown(self) {
+        for node in self.nodes {
+            node.api.shutdown().await;
+        }
+    }
+}
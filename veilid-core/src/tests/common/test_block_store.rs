@@ -0,0 +1,116 @@
+use crate::tests::test_veilid_config::*;
+use crate::*;
+use futures_util::{AsyncReadExt, AsyncWriteExt};
+
+async fn startup() -> VeilidAPI {
+    trace!("test_block_store: starting");
+    let (update_callback, config_callback) = setup_veilid_core();
+    api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed")
+}
+
+async fn shutdown(api: VeilidAPI) {
+    trace!("test_block_store: shutting down");
+    api.shutdown().await;
+    trace!("test_block_store: finished");
+}
+
+async fn write_blob(bs: &BlockStore, content: &[u8]) -> TypedKey {
+    let mut writer = bs.open_blob_writer();
+    // Write in uneven pieces so writes straddle block boundaries
+    for piece in content.chunks(10000) {
+        writer.write_all(piece).await.expect("should write");
+    }
+    assert!(writer.blob_id().is_none(), "blob id before close");
+    writer.close().await.expect("should close");
+    writer.blob_id().expect("should have blob id")
+}
+
+async fn read_blob(bs: &BlockStore, blob_id: TypedKey) -> Vec<u8> {
+    let mut reader = bs
+        .open_blob_reader(blob_id)
+        .await
+        .expect("should open")
+        .expect("should exist");
+    let mut content = vec![];
+    reader.read_to_end(&mut content).await.expect("should read");
+    content
+}
+
+pub async fn test_blocks(bs: BlockStore) {
+    trace!("test_blocks");
+
+    let block_id = bs.put_block(b"some block").await.expect("should put");
+    assert_eq!(
+        bs.put_block(b"some block").await.expect("should put"),
+        block_id,
+        "same content should have the same block id"
+    );
+    assert_eq!(
+        bs.get_block(block_id).await.expect("should get"),
+        Some(b"some block".to_vec())
+    );
+
+    assert!(bs.delete_block(block_id).await.expect("should delete"));
+    assert!(!bs.delete_block(block_id).await.expect("should delete"));
+    assert_eq!(bs.get_block(block_id).await.expect("should get"), None);
+
+    assert!(
+        bs.put_block(&vec![0u8; MAX_BLOCK_SIZE + 1]).await.is_err(),
+        "oversized block should fail"
+    );
+}
+
+pub async fn test_blobs(bs: BlockStore) {
+    trace!("test_blobs");
+
+    let content: Vec<u8> = (0..(MAX_BLOCK_SIZE * 2 + 1234))
+        .map(|x| (x % 251) as u8)
+        .collect();
+    let blob_id = write_blob(&bs, &content).await;
+
+    let reader = bs
+        .open_blob_reader(blob_id)
+        .await
+        .expect("should open")
+        .expect("should exist");
+    assert_eq!(reader.manifest().length, content.len() as u64);
+    assert_eq!(reader.manifest().blocks.len(), 3);
+    assert_eq!(read_blob(&bs, blob_id).await, content);
+
+    // Blocks are shared by identical content
+    assert_eq!(write_blob(&bs, &content).await, blob_id);
+
+    // Empty and exactly block sized blobs
+    let empty_id = write_blob(&bs, &[]).await;
+    assert!(read_blob(&bs, empty_id).await.is_empty());
+    let exact: Vec<u8> = vec![7u8; MAX_BLOCK_SIZE];
+    let exact_id = write_blob(&bs, &exact).await;
+    assert_eq!(read_blob(&bs, exact_id).await, exact);
+
+    // A block id that is not a manifest is not a blob
+    let block_id = bs.put_block(b"not a manifest").await.expect("should put");
+    assert!(bs.open_blob_reader(block_id).await.is_err());
+
+    // Missing content blocks fail the read
+    let mut reader = bs
+        .open_blob_reader(blob_id)
+        .await
+        .expect("should open")
+        .expect("should exist");
+    let missing = reader.manifest().blocks[1];
+    assert!(bs.delete_block(missing).await.expect("should delete"));
+    let mut out = vec![];
+    assert!(reader.read_to_end(&mut out).await.is_err());
+}
+
+pub async fn test_all() {
+    let api = startup().await;
+    let bs = api.block_store().unwrap();
+
+    test_blocks(bs.clone()).await;
+    test_blobs(bs).await;
+
+    shutdown(api).await;
+}
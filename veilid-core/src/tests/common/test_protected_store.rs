@@ -78,10 +78,52 @@ pub async fn test_protected_store(ps: ProtectedStore) {
     let _ = ps.remove_user_secret("_test_broken").await;
 }
 
+pub async fn test_subscriptions(ps: ProtectedStore) {
+    info!("testing protected store subscriptions");
+
+    let _ = ps.remove_user_secret("_test_key").await;
+
+    let changes = Arc::new(Mutex::new(Vec::<ProtectedStoreChange>::new()));
+    let changes2 = changes.clone();
+    let id = ps.subscribe(Arc::new(move |change| changes2.lock().push(change)));
+
+    ps.save_user_secret("_test_key", &[1u8, 2u8, 3u8])
+        .await
+        .unwrap();
+    assert!(ps.remove_user_secret("_test_key").await.unwrap());
+    // Removing a key that does not exist is not a change
+    assert!(!ps.remove_user_secret("_test_key").await.unwrap());
+
+    assert_eq!(
+        *changes.lock(),
+        vec![
+            ProtectedStoreChange {
+                key: "_test_key".to_owned(),
+                kind: ProtectedStoreChangeKind::Saved,
+            },
+            ProtectedStoreChange {
+                key: "_test_key".to_owned(),
+                kind: ProtectedStoreChangeKind::Removed,
+            },
+        ]
+    );
+
+    // No more changes once unsubscribed
+    assert!(ps.unsubscribe(id));
+    assert!(!ps.unsubscribe(id));
+    ps.save_user_secret("_test_key", &[1u8, 2u8, 3u8])
+        .await
+        .unwrap();
+    assert_eq!(changes.lock().len(), 2);
+
+    let _ = ps.remove_user_secret("_test_key").await;
+}
+
 pub async fn test_all() {
     let api = startup().await;
     let ps = api.protected_store().unwrap();
     test_protected_store(ps.clone()).await;
+    test_subscriptions(ps.clone()).await;
 
     shutdown(api).await;
 }
@@ -0,0 +1,503 @@
+use super::sim_network_harness::*;
+use crate::network_manager::BOOTSTRAP_ROLE_DISABLED_CAPABILITIES;
+use crate::routing_table::RoutingDomain;
+use crate::*;
+
+const SIM_NODE_COUNT: usize = 12;
+const SIM_READY_TIMEOUT_MS: u32 = 60_000;
+const SIM_HEAL_TIMEOUT_MS: u32 = 60_000;
+
+pub async fn test_bootstrap() {
+    let harness = SimNetworkHarness::start(SIM_NODE_COUNT, 1, SimLink::new(20, 0.0)).await;
+    assert!(harness.wait_for_ready(SIM_READY_TIMEOUT_MS).await);
+
+    // Everyone found more than just the bootstrap node
+    for node in harness.nodes() {
+        let state = node.api.get_state().await.unwrap();
+        assert!(state.network.peers.len() > 1);
+    }
+
+    harness.shutdown().await;
+}
+
+pub async fn test_dht_set_get() {
+    let harness = SimNetworkHarness::start(SIM_NODE_COUNT, 2, SimLink::new(20, 0.0)).await;
+    assert!(harness.wait_for_ready(SIM_READY_TIMEOUT_MS).await);
+
+    // Write a value on one node
+    let writer_rc = harness.node(1).routing_context();
+    let rec = writer_rc
+        .create_dht_record(DHTSchema::dflt(1).unwrap(), Some(CRYPTO_KIND_VLD0))
+        .await
+        .unwrap();
+    let key = *rec.key();
+    writer_rc
+        .set_dht_value(key, 0, b"simulated".to_vec(), None)
+        .await
+        .unwrap();
+
+    // Every other node reads it back from the network
+    for node in harness.nodes().iter().skip(2) {
+        let rc = node.routing_context();
+        rc.open_dht_record(key, None).await.unwrap();
+        let value = rc
+            .get_dht_value(key, 0, true)
+            .await
+            .unwrap()
+            .expect("value should be found");
+        assert_eq!(value.data(), b"simulated");
+        rc.close_dht_record(key).await.unwrap();
+    }
+
+    writer_rc.close_dht_record(key).await.unwrap();
+    harness.shutdown().await;
+}
+
+pub async fn test_route_construction() {
+    let harness = SimNetworkHarness::start(SIM_NODE_COUNT, 3, SimLink::new(20, 0.0)).await;
+    assert!(harness.wait_for_ready(SIM_READY_TIMEOUT_MS).await);
+
+    // One node makes a private route, another one sends a message over it
+    let receiver = harness.node(2);
+    let sender = harness.node(3);
+    let (_route_id, blob) = receiver.api.new_private_route().await.unwrap();
+    let remote_route_id = sender.api.import_remote_private_route(blob).unwrap();
+    sender
+        .routing_context()
+        .app_message(Target::PrivateRoute(remote_route_id), b"hello".to_vec())
+        .await
+        .unwrap();
+
+    let update = receiver
+        .wait_for_update(10_000, |u| matches!(u, VeilidUpdate::AppMessage(_)))
+        .await;
+    let Some(VeilidUpdate::AppMessage(msg)) = update else {
+        panic!("app message should arrive over the route");
+    };
+    assert_eq!(msg.message(), b"hello");
+
+    harness.shutdown().await;
+}
+
+pub async fn test_private_route_self_test() {
+    let harness = SimNetworkHarness::start(SIM_NODE_COUNT, 8, SimLink::new(20, 0.0)).await;
+    assert!(harness.wait_for_ready(SIM_READY_TIMEOUT_MS).await);
+
+    let node = harness.node(2);
+    let (route_id, blob) = node.api.new_private_route().await.unwrap();
+
+    // Our own route tests good, with or without a safety route of our own
+    let res = node
+        .routing_context()
+        .test_private_route(route_id)
+        .await
+        .unwrap();
+    assert!(res.success);
+    assert!(res.latency.is_some());
+
+    // Asking for the route under test as the safety route still picks a different one
+    let rc = node
+        .api
+        .routing_context()
+        .unwrap()
+        .with_safety(SafetySelection::Safe(SafetySpec {
+            preferred_route: Some(route_id),
+            hop_count: 1,
+            stability: Stability::Reliable,
+            sequencing: Sequencing::PreferOrdered,
+            preferred_first_hop: None,
+            profile: None,
+        }))
+        .unwrap();
+    let res = rc.test_private_route(route_id).await.unwrap();
+    assert!(res.success);
+
+    // The loopback question is never passed up to the app
+    let update = node
+        .wait_for_update(1_000, |u| {
+            matches!(u, VeilidUpdate::AppCall(_) | VeilidUpdate::AppMessage(_))
+        })
+        .await;
+    assert!(update.is_none());
+
+    // Routes imported from other nodes can not be tested this way
+    let other = harness.node(3);
+    let remote_route_id = other.api.import_remote_private_route(blob).unwrap();
+    assert!(matches!(
+        other
+            .routing_context()
+            .test_private_route(remote_route_id)
+            .await,
+        Err(VeilidAPIError::InvalidArgument { .. })
+    ));
+
+    harness.shutdown().await;
+}
+
+pub async fn test_published_private_route() {
+    let harness = SimNetworkHarness::start(SIM_NODE_COUNT, 9, SimLink::new(20, 0.0)).await;
+    assert!(harness.wait_for_ready(SIM_READY_TIMEOUT_MS).await);
+
+    // The published route follows the safety selection of the routing context, not the defaults
+    let receiver = harness.node(2);
+    let receiver_rc = receiver
+        .api
+        .routing_context()
+        .unwrap()
+        .with_safety(SafetySelection::Safe(SafetySpec {
+            preferred_route: None,
+            hop_count: 2,
+            stability: Stability::LowLatency,
+            sequencing: Sequencing::NoPreference,
+            preferred_first_hop: None,
+            profile: None,
+        }))
+        .unwrap();
+    let rec = receiver_rc
+        .create_dht_record(DHTSchema::dflt(2).unwrap(), Some(CRYPTO_KIND_VLD0))
+        .await
+        .unwrap();
+    let key = *rec.key();
+    let route_id = receiver_rc
+        .publish_private_route(key, 0, None)
+        .await
+        .unwrap();
+
+    let rss = receiver.api.routing_table().unwrap().route_spec_store();
+    for private_route in rss.assemble_private_routes(&route_id, None).unwrap() {
+        // Two hops and ourselves at the end of the route
+        assert_eq!(private_route.hop_count, 3);
+    }
+    assert!(rss
+        .debug_route(&route_id)
+        .unwrap()
+        .contains("stability: LowLatency"));
+
+    // Another node resolves the route and sends a message over it
+    let sender = harness.node(3);
+    let sender_rc = sender.routing_context();
+    sender_rc.open_dht_record(key, None).await.unwrap();
+    let remote_route_id = sender_rc
+        .resolve_private_route(key, 0, true)
+        .await
+        .unwrap()
+        .expect("route should be published");
+    sender_rc
+        .app_message(Target::PrivateRoute(remote_route_id), b"published".to_vec())
+        .await
+        .unwrap();
+    let update = receiver
+        .wait_for_update(10_000, |u| matches!(u, VeilidUpdate::AppMessage(_)))
+        .await;
+    let Some(VeilidUpdate::AppMessage(msg)) = update else {
+        panic!("app message should arrive over the published route");
+    };
+    assert_eq!(msg.message(), b"published");
+
+    // Nothing was published to the other subkey
+    assert_eq!(
+        sender_rc.resolve_private_route(key, 1, true).await.unwrap(),
+        None
+    );
+
+    sender_rc.close_dht_record(key).await.unwrap();
+    receiver_rc.close_dht_record(key).await.unwrap();
+    harness.shutdown().await;
+}
+
+pub async fn test_three_nodes_app_messages() {
+    let harness = SimNetworkHarness::start(3, 5, SimLink::new(20, 0.0)).await;
+    assert!(harness.wait_for_ready(SIM_READY_TIMEOUT_MS).await);
+
+    // Each node sends a message directly to the next one around the ring
+    for n in 0..3 {
+        let sender = harness.node(n);
+        let receiver = harness.node((n + 1) % 3);
+        let receiver_id = receiver
+            .api
+            .routing_table()
+            .unwrap()
+            .node_id(CRYPTO_KIND_VLD0);
+        let message = format!("hello from node {}", n).into_bytes();
+        sender
+            .routing_context()
+            .app_message(Target::NodeId(receiver_id), message.clone())
+            .await
+            .unwrap();
+
+        let update = receiver
+            .wait_for_update(10_000, |u| matches!(u, VeilidUpdate::AppMessage(_)))
+            .await;
+        let Some(VeilidUpdate::AppMessage(msg)) = update else {
+            panic!("app message should arrive at node {}", (n + 1) % 3);
+        };
+        assert_eq!(msg.message(), message.as_slice());
+    }
+
+    harness.shutdown().await;
+}
+
+pub async fn test_rendezvous() {
+    let harness = SimNetworkHarness::start(SIM_NODE_COUNT, 6, SimLink::new(20, 0.0)).await;
+    assert!(harness.wait_for_ready(SIM_READY_TIMEOUT_MS).await);
+
+    let inviter = harness.node(2);
+    let accepter = harness.node(3);
+    let inviter_rc = inviter.routing_context();
+    let accepter_rc = accepter.routing_context();
+
+    // Blobs that are not invites are refused before touching the network
+    assert!(matches!(
+        accepter_rc.accept_invite(b"not an invite".to_vec()).await,
+        Err(VeilidAPIError::InvalidArgument { .. })
+    ));
+
+    // The accepter waits for the inviter to answer while the inviter waits for the app call
+    let (invite, blob) = inviter_rc.create_invite().await.unwrap();
+    let accept = spawn({
+        let accepter_rc = accepter_rc.clone();
+        async move { accepter_rc.accept_invite(blob).await }
+    });
+    let update = inviter
+        .wait_for_update(10_000, |u| matches!(u, VeilidUpdate::AppCall(_)))
+        .await;
+    let Some(VeilidUpdate::AppCall(app_call)) = update else {
+        panic!("invite acceptance should arrive over the invite route");
+    };
+    let inviter_session = inviter_rc
+        .answer_invite(&invite, &app_call)
+        .await
+        .unwrap()
+        .expect("app call should accept the invite");
+    let accepter_session = accept.await.unwrap();
+
+    // Each side's local route is the other side's remote route
+    assert_eq!(inviter_session.local_route_id(), invite.route_id());
+    assert_eq!(
+        accepter_session.remote_route_id(),
+        inviter_session.local_route_id()
+    );
+    assert_eq!(
+        inviter_session.remote_route_id(),
+        accepter_session.local_route_id()
+    );
+
+    // Messages go both ways and only open with the session secret
+    accepter_session
+        .send(&accepter_rc, b"hello inviter")
+        .await
+        .unwrap();
+    let update = inviter
+        .wait_for_update(10_000, |u| matches!(u, VeilidUpdate::AppMessage(_)))
+        .await;
+    let Some(VeilidUpdate::AppMessage(msg)) = update else {
+        panic!("session message should arrive at the inviter");
+    };
+    assert_eq!(msg.route_id(), Some(&inviter_session.local_route_id()));
+    assert_eq!(
+        inviter_session.open(&inviter.api, msg.message()).unwrap(),
+        b"hello inviter"
+    );
+    assert!(inviter_session.open(&inviter.api, b"garbage").is_err());
+
+    inviter_session
+        .send(&inviter_rc, b"hello accepter")
+        .await
+        .unwrap();
+    let update = accepter
+        .wait_for_update(10_000, |u| matches!(u, VeilidUpdate::AppMessage(_)))
+        .await;
+    let Some(VeilidUpdate::AppMessage(msg)) = update else {
+        panic!("session message should arrive at the accepter");
+    };
+    assert_eq!(
+        accepter_session.open(&accepter.api, msg.message()).unwrap(),
+        b"hello accepter"
+    );
+
+    inviter_session.close(&inviter.api).unwrap();
+    accepter_session.close(&accepter.api).unwrap();
+    harness.shutdown().await;
+}
+
+pub async fn test_partitioned_node() {
+    let harness = SimNetworkHarness::start(SIM_NODE_COUNT, 4, SimLink::new(20, 0.0)).await;
+    assert!(harness.wait_for_ready(SIM_READY_TIMEOUT_MS).await);
+
+    // Cut one node off from everyone, it can no longer find values others wrote
+    let isolated = harness.node(SIM_NODE_COUNT - 1);
+    for node in harness.nodes().iter().take(SIM_NODE_COUNT - 1) {
+        harness
+            .sim_network()
+            .partition(isolated.ip_addr(), node.ip_addr());
+    }
+
+    let writer_rc = harness.node(1).routing_context();
+    let rec = writer_rc
+        .create_dht_record(DHTSchema::dflt(1).unwrap(), Some(CRYPTO_KIND_VLD0))
+        .await
+        .unwrap();
+    let key = *rec.key();
+    writer_rc
+        .set_dht_value(key, 0, b"partitioned".to_vec(), None)
+        .await
+        .unwrap();
+
+    // Nothing it asks reaches a node that has the record
+    let rc = isolated.routing_context();
+    let res = rc.open_dht_record(key, None).await;
+    assert!(
+        matches!(
+            res,
+            Err(VeilidAPIError::KeyNotFound { .. } | VeilidAPIError::TryAgain { .. })
+        ),
+        "partitioned node should not find the record: {:?}",
+        res
+    );
+
+    // Once the partition heals, the node finds the value again
+    for node in harness.nodes().iter().take(SIM_NODE_COUNT - 1) {
+        harness
+            .sim_network()
+            .heal(isolated.ip_addr(), node.ip_addr());
+    }
+    let start_ts = get_timestamp();
+    let value = loop {
+        if rc.open_dht_record(key, None).await.is_ok() {
+            if let Ok(Some(value)) = rc.get_dht_value(key, 0, true).await {
+                break value;
+            }
+            rc.close_dht_record(key).await.unwrap();
+        }
+        assert!(
+            get_timestamp() - start_ts < (SIM_HEAL_TIMEOUT_MS as u64) * 1000,
+            "healed node should find the record"
+        );
+        sleep(1000).await;
+    };
+    assert_eq!(value.data(), b"partitioned");
+    rc.close_dht_record(key).await.unwrap();
+
+    writer_rc.close_dht_record(key).await.unwrap();
+    harness.shutdown().await;
+}
+
+pub async fn test_udp_only() {
+    let harness = SimNetworkHarness::start_with_protocols(
+        SIM_NODE_COUNT,
+        7,
+        SimLink::new(20, 0.0),
+        ProtocolType::UDP.into(),
+    )
+    .await;
+    assert!(harness.wait_for_ready(SIM_READY_TIMEOUT_MS).await);
+
+    // Nodes only know each other by their UDP dial info
+    for node in harness.nodes() {
+        let state = node.api.get_state().await.unwrap();
+        assert!(state.network.peers.len() > 1);
+        for peer in &state.network.peers {
+            assert!(peer
+                .peer_address
+                .starts_with(&format!("{}:", ProtocolType::UDP)));
+        }
+    }
+
+    // Values written on one node can be read on another
+    let writer_rc = harness.node(1).routing_context();
+    let rec = writer_rc
+        .create_dht_record(DHTSchema::dflt(1).unwrap(), Some(CRYPTO_KIND_VLD0))
+        .await
+        .unwrap();
+    let key = *rec.key();
+    writer_rc
+        .set_dht_value(key, 0, b"datagram".to_vec(), None)
+        .await
+        .unwrap();
+
+    let rc = harness.node(SIM_NODE_COUNT - 1).routing_context();
+    rc.open_dht_record(key, None).await.unwrap();
+    let value = rc
+        .get_dht_value(key, 0, true)
+        .await
+        .unwrap()
+        .expect("value should be found");
+    assert_eq!(value.data(), b"datagram");
+    rc.close_dht_record(key).await.unwrap();
+
+    writer_rc.close_dht_record(key).await.unwrap();
+    harness.shutdown().await;
+}
+
+pub async fn test_bootstrap_role() {
+    let harness =
+        SimNetworkHarness::start_with_bootstrap_role(SIM_NODE_COUNT, 10, SimLink::new(20, 0.0))
+            .await;
+    assert!(harness.wait_for_ready(SIM_READY_TIMEOUT_MS).await);
+
+    // Everyone still joins through a bootstrap node that only does bootstrapping
+    for node in harness.nodes().iter().skip(1) {
+        let state = node.api.get_state().await.unwrap();
+        assert!(state.network.peers.len() > 1);
+    }
+
+    // It does not offer the capabilities it does not serve
+    let bootstrap = harness.node(0);
+    let peer_info = bootstrap
+        .api
+        .routing_table()
+        .unwrap()
+        .get_own_peer_info(RoutingDomain::PublicInternet);
+    for cap in BOOTSTRAP_ROLE_DISABLED_CAPABILITIES {
+        assert!(!peer_info
+            .signed_node_info()
+            .node_info()
+            .has_capability(*cap));
+    }
+
+    // It neither allocates private routes nor stores records
+    assert!(bootstrap.api.new_private_route().await.is_err());
+    assert!(bootstrap
+        .routing_context()
+        .create_dht_record(DHTSchema::dflt(1).unwrap(), Some(CRYPTO_KIND_VLD0))
+        .await
+        .is_err());
+
+    // The other nodes keep storing values among themselves
+    let writer_rc = harness.node(1).routing_context();
+    let rec = writer_rc
+        .create_dht_record(DHTSchema::dflt(1).unwrap(), Some(CRYPTO_KIND_VLD0))
+        .await
+        .unwrap();
+    let key = *rec.key();
+    writer_rc
+        .set_dht_value(key, 0, b"no bootstrap".to_vec(), None)
+        .await
+        .unwrap();
+
+    let rc = harness.node(SIM_NODE_COUNT - 1).routing_context();
+    rc.open_dht_record(key, None).await.unwrap();
+    let value = rc
+        .get_dht_value(key, 0, true)
+        .await
+        .unwrap()
+        .expect("value should be found");
+    assert_eq!(value.data(), b"no bootstrap");
+    rc.close_dht_record(key).await.unwrap();
+
+    writer_rc.close_dht_record(key).await.unwrap();
+    harness.shutdown().await;
+}
+
+pub async fn test_all() {
+    test_bootstrap().await;
+    test_dht_set_get().await;
+    test_route_construction().await;
+    test_private_route_self_test().await;
+    test_published_private_route().await;
+    test_three_nodes_app_messages().await;
+    test_rendezvous().await;
+    test_partitioned_node().await;
+    test_udp_only().await;
+    test_bootstrap_role().await;
+}
@@ -169,10 +169,13 @@ pub fn config_callback(key: String) -> ConfigCallbackReturn {
         "capabilities.disable" => Ok(Box::<Vec<FourCC>>::default()),
         "table_store.directory" => Ok(Box::new(get_table_store_path())),
         "table_store.delete" => Ok(Box::new(true)),
+        "table_store.backend" => Ok(Box::new(VeilidConfigTableStoreBackend::Persistent)),
         "block_store.directory" => Ok(Box::new(get_block_store_path())),
         "block_store.delete" => Ok(Box::new(true)),
         "protected_store.allow_insecure_fallback" => Ok(Box::new(true)),
         "protected_store.always_use_insecure_storage" => Ok(Box::new(false)),
+        "protected_store.hardware_node_id" => Ok(Box::new(false)),
+        "protected_store.hardware_node_id_migrate" => Ok(Box::new(false)),
         "protected_store.directory" => Ok(Box::new(get_protected_store_path())),
         "protected_store.delete" => Ok(Box::new(true)),
         "protected_store.device_encryption_key_password" => Ok(Box::new("".to_owned())),
@@ -188,6 +191,9 @@ pub fn config_callback(key: String) -> ConfigCallbackReturn {
         "network.client_allowlist_timeout_ms" => Ok(Box::new(300_000u32)),
         "network.reverse_connection_receipt_time_ms" => Ok(Box::new(5_000u32)),
         "network.hole_punch_receipt_time_ms" => Ok(Box::new(5_000u32)),
+        "network.prewarm_grace_period_ms" => Ok(Box::new(60_000u32)),
+        "network.max_relay_client_bytes_per_sec" => Ok(Box::new(1_048_576u32)),
+        "network.max_relay_client_queued_bytes" => Ok(Box::new(262_144u32)),
         "network.network_key_password" => Ok(Box::new(Option::<String>::None)),
         "network.routing_table.node_id" => Ok(Box::new(TypedKeyGroup::new())),
         "network.routing_table.node_id_secret" => Ok(Box::new(TypedSecretGroup::new())),
@@ -203,13 +209,27 @@ pub fn config_callback(key: String) -> ConfigCallbackReturn {
         "network.routing_table.limit_attached_strong" => Ok(Box::new(16u32)),
         "network.routing_table.limit_attached_good" => Ok(Box::new(8u32)),
         "network.routing_table.limit_attached_weak" => Ok(Box::new(4u32)),
+        "network.routing_table.node_info_max_age_ms" => Ok(Box::new(3_600_000u32)),
+        "network.routing_table.verified_peers_only" => Ok(Box::new(Vec::<RoutingDomain>::new())),
         "network.rpc.concurrency" => Ok(Box::new(0u32)),
         "network.rpc.queue_size" => Ok(Box::new(1024u32)),
         "network.rpc.max_timestamp_behind_ms" => Ok(Box::new(Some(10_000u32))),
         "network.rpc.max_timestamp_ahead_ms" => Ok(Box::new(Some(10_000u32))),
+        "network.rpc.max_clock_skew_ms" => Ok(Box::new(60_000u32)),
+        "network.rpc.clock_skew_warning_ms" => Ok(Box::new(5_000u32)),
         "network.rpc.timeout_ms" => Ok(Box::new(5_000u32)),
         "network.rpc.max_route_hop_count" => Ok(Box::new(4u8)),
         "network.rpc.default_route_hop_count" => Ok(Box::new(1u8)),
+        "network.rpc.max_private_routes" => Ok(Box::new(128u32)),
+        "network.rpc.max_private_routes_per_api" => Ok(Box::new(32u32)),
+        "network.rpc.private_route_idle_release_ms" => Ok(Box::new(Option::<u32>::None)),
+        "network.rpc.max_app_message_size" => Ok(Box::new(32768u32)),
+        "network.rpc.max_app_call_size" => Ok(Box::new(32768u32)),
+        "network.rpc.max_app_message_frequency_per_min" => Ok(Box::new(1024u32)),
+        "network.rpc.max_app_call_frequency_per_min" => Ok(Box::new(256u32)),
+        "network.rpc.strict_udp_source" => Ok(Box::new(false)),
+        "network.rpc.strict_udp_source_allow_rebind" => Ok(Box::new(true)),
+        "network.rpc.route_pow_difficulty" => Ok(Box::new(0u32)),
         "network.dht.max_find_node_count" => Ok(Box::new(20u32)),
         "network.dht.resolve_node_timeout_ms" => Ok(Box::new(10_000u32)),
         "network.dht.resolve_node_count" => Ok(Box::new(1u32)),
@@ -220,6 +240,7 @@ pub fn config_callback(key: String) -> ConfigCallbackReturn {
         "network.dht.set_value_timeout_ms" => Ok(Box::new(10_000u32)),
         "network.dht.set_value_count" => Ok(Box::new(5u32)),
         "network.dht.set_value_fanout" => Ok(Box::new(4u32)),
+        "network.dht.min_fanout_under_load" => Ok(Box::new(1u32)),
         "network.dht.min_peer_count" => Ok(Box::new(20u32)),
         "network.dht.min_peer_refresh_time_ms" => Ok(Box::new(60_000u32)),
         "network.dht.validate_dial_info_receipt_time_ms" => Ok(Box::new(2_000u32)),
@@ -229,44 +250,67 @@ pub fn config_callback(key: String) -> ConfigCallbackReturn {
         "network.dht.remote_max_records" => Ok(Box::new(4096u32)),
         "network.dht.remote_max_subkey_cache_memory_mb" => Ok(Box::new(64u32)),
         "network.dht.remote_max_storage_space_mb" => Ok(Box::new(64u32)),
+        "network.dht.remote_storage_high_watermark_percent" => Ok(Box::new(90u8)),
+        "network.dht.remote_storage_low_watermark_percent" => Ok(Box::new(75u8)),
         "network.dht.public_watch_limit" => Ok(Box::new(32u32)),
         "network.dht.member_watch_limit" => Ok(Box::new(8u32)),
         "network.dht.max_watch_expiration_ms" => Ok(Box::new(600_000u32)),
+        "network.dht.set_value_pow_difficulty" => Ok(Box::new(0u32)),
+        "network.dht.compress_values" => Ok(Box::new(false)),
+        "network.dht.audit_log_max_entries" => Ok(Box::new(0u32)),
         "network.upnp" => Ok(Box::new(false)),
         "network.detect_address_changes" => Ok(Box::new(true)),
+        "network.detect_address_changes_distinct_subnets" => Ok(Box::new(3u32)),
         "network.restricted_nat_retries" => Ok(Box::new(0u32)),
+        "network.max_envelope_size" => Ok(Box::new(65507u32)),
         "network.tls.certificate_path" => Ok(Box::new(get_certfile_path())),
         "network.tls.private_key_path" => Ok(Box::new(get_keyfile_path())),
         "network.tls.connection_initial_timeout_ms" => Ok(Box::new(2_000u32)),
+        "network.tor.enabled" => Ok(Box::new(false)),
+        "network.tor.socks_address" => Ok(Box::new(String::from("127.0.0.1:9050"))),
+        "network.tor.onion_address" => Ok(Box::new(Option::<String>::None)),
         "network.application.https.enabled" => Ok(Box::new(false)),
         "network.application.https.listen_address" => Ok(Box::new("".to_owned())),
         "network.application.https.path" => Ok(Box::new(String::from("app"))),
         "network.application.https.url" => Ok(Box::new(Option::<String>::None)),
+        "network.application.https.directory" => Ok(Box::new(Option::<String>::None)),
         "network.application.http.enabled" => Ok(Box::new(false)),
         "network.application.http.listen_address" => Ok(Box::new("".to_owned())),
         "network.application.http.path" => Ok(Box::new(String::from("app"))),
         "network.application.http.url" => Ok(Box::new(Option::<String>::None)),
+        "network.application.http.directory" => Ok(Box::new(Option::<String>::None)),
         "network.protocol.udp.enabled" => Ok(Box::new(true)),
         "network.protocol.udp.socket_pool_size" => Ok(Box::new(0u32)),
         "network.protocol.udp.listen_address" => Ok(Box::new("".to_owned())),
         "network.protocol.udp.public_address" => Ok(Box::new(Option::<String>::None)),
+        "network.protocol.udp.preference" => Ok(Box::new(0u8)),
         "network.protocol.tcp.connect" => Ok(Box::new(true)),
         "network.protocol.tcp.listen" => Ok(Box::new(true)),
         "network.protocol.tcp.max_connections" => Ok(Box::new(32u32)),
         "network.protocol.tcp.listen_address" => Ok(Box::new("".to_owned())),
         "network.protocol.tcp.public_address" => Ok(Box::new(Option::<String>::None)),
+        "network.protocol.tcp.preference" => Ok(Box::new(0u8)),
         "network.protocol.ws.connect" => Ok(Box::new(true)),
         "network.protocol.ws.listen" => Ok(Box::new(true)),
         "network.protocol.ws.max_connections" => Ok(Box::new(32u32)),
         "network.protocol.ws.listen_address" => Ok(Box::new("".to_owned())),
         "network.protocol.ws.path" => Ok(Box::new(String::from("ws"))),
         "network.protocol.ws.url" => Ok(Box::new(Option::<String>::None)),
+        "network.protocol.ws.preference" => Ok(Box::new(0u8)),
         "network.protocol.wss.connect" => Ok(Box::new(true)),
         "network.protocol.wss.listen" => Ok(Box::new(false)),
         "network.protocol.wss.max_connections" => Ok(Box::new(32u32)),
         "network.protocol.wss.listen_address" => Ok(Box::new("".to_owned())),
         "network.protocol.wss.path" => Ok(Box::new(String::from("ws"))),
         "network.protocol.wss.url" => Ok(Box::new(Option::<String>::None)),
+        "network.protocol.wss.preference" => Ok(Box::new(0u8)),
+        "network.protocol.ipc.connect" => Ok(Box::new(true)),
+        "network.protocol.ipc.listen" => Ok(Box::new(false)),
+        "network.protocol.ipc.listen_path" => Ok(Box::new("".to_owned())),
+        "network.role" => Ok(Box::new(VeilidConfigRole::Full)),
+        "network.metered_policy" => Ok(Box::new(VeilidConfigNetworkCostPolicy::ReduceTickRate)),
+        "network.roaming_policy" => Ok(Box::new(VeilidConfigNetworkCostPolicy::Suspend)),
+        "network.low_power_wake_interval_ms" => Ok(Box::new(60_000u32)),
         _ => {
             let err = format!("config key '{}' doesn't exist", key);
             debug!("{}", err);
@@ -303,10 +347,16 @@ pub async fn test_config() {
     assert_eq!(inner.capabilities.disable, Vec::<FourCC>::new());
     assert_eq!(inner.table_store.directory, get_table_store_path());
     assert!(inner.table_store.delete);
+    assert_eq!(
+        inner.table_store.backend,
+        VeilidConfigTableStoreBackend::Persistent
+    );
     assert_eq!(inner.block_store.directory, get_block_store_path());
     assert!(inner.block_store.delete);
     assert!(inner.protected_store.allow_insecure_fallback);
     assert!(!inner.protected_store.always_use_insecure_storage);
+    assert!(!inner.protected_store.hardware_node_id);
+    assert!(!inner.protected_store.hardware_node_id_migrate);
     assert_eq!(inner.protected_store.directory, get_protected_store_path());
     assert!(inner.protected_store.delete);
     assert_eq!(
@@ -326,12 +376,22 @@ pub async fn test_config() {
     assert_eq!(inner.network.client_allowlist_timeout_ms, 300_000u32);
     assert_eq!(inner.network.reverse_connection_receipt_time_ms, 5_000u32);
     assert_eq!(inner.network.hole_punch_receipt_time_ms, 5_000u32);
+    assert_eq!(inner.network.prewarm_grace_period_ms, 60_000u32);
+    assert_eq!(inner.network.max_relay_client_bytes_per_sec, 1_048_576u32);
+    assert_eq!(inner.network.max_relay_client_queued_bytes, 262_144u32);
     assert_eq!(inner.network.network_key_password, Option::<String>::None);
     assert_eq!(inner.network.rpc.concurrency, 0u32);
     assert_eq!(inner.network.rpc.queue_size, 1024u32);
     assert_eq!(inner.network.rpc.timeout_ms, 5_000u32);
     assert_eq!(inner.network.rpc.max_route_hop_count, 4u8);
     assert_eq!(inner.network.rpc.default_route_hop_count, 1u8);
+    assert_eq!(inner.network.rpc.max_app_message_size, 32768u32);
+    assert_eq!(inner.network.rpc.max_app_call_size, 32768u32);
+    assert_eq!(inner.network.rpc.max_app_message_frequency_per_min, 1024u32);
+    assert_eq!(inner.network.rpc.max_app_call_frequency_per_min, 256u32);
+    assert!(!inner.network.rpc.strict_udp_source);
+    assert!(inner.network.rpc.strict_udp_source_allow_rebind);
+    assert_eq!(inner.network.rpc.route_pow_difficulty, 0u32);
     assert_eq!(inner.network.routing_table.node_id.len(), 0);
     assert_eq!(inner.network.routing_table.node_id_secret.len(), 0);
     #[cfg(not(target_arch = "wasm32"))]
@@ -349,6 +409,11 @@ pub async fn test_config() {
     assert_eq!(inner.network.routing_table.limit_attached_strong, 16u32);
     assert_eq!(inner.network.routing_table.limit_attached_good, 8u32);
     assert_eq!(inner.network.routing_table.limit_attached_weak, 4u32);
+    assert_eq!(
+        inner.network.routing_table.node_info_max_age_ms,
+        3_600_000u32
+    );
+    assert!(inner.network.routing_table.verified_peers_only.is_empty());
 
     assert_eq!(inner.network.dht.max_find_node_count, 20u32);
     assert_eq!(inner.network.dht.resolve_node_timeout_ms, 10_000u32);
@@ -360,6 +425,7 @@ pub async fn test_config() {
     assert_eq!(inner.network.dht.set_value_timeout_ms, 10_000u32);
     assert_eq!(inner.network.dht.set_value_count, 5u32);
     assert_eq!(inner.network.dht.set_value_fanout, 4u32);
+    assert_eq!(inner.network.dht.min_fanout_under_load, 1u32);
     assert_eq!(inner.network.dht.min_peer_count, 20u32);
     assert_eq!(inner.network.dht.min_peer_refresh_time_ms, 60_000u32);
     assert_eq!(
@@ -369,43 +435,290 @@ pub async fn test_config() {
 
     assert!(!inner.network.upnp);
     assert!(inner.network.detect_address_changes);
+    assert_eq!(inner.network.detect_address_changes_distinct_subnets, 3u32);
     assert_eq!(inner.network.restricted_nat_retries, 0u32);
+    assert_eq!(inner.network.max_envelope_size, 65507u32);
     assert_eq!(inner.network.tls.certificate_path, get_certfile_path());
     assert_eq!(inner.network.tls.private_key_path, get_keyfile_path());
     assert_eq!(inner.network.tls.connection_initial_timeout_ms, 2_000u32);
+    assert!(!inner.network.tor.enabled);
+    assert_eq!(inner.network.tor.socks_address, "127.0.0.1:9050");
+    assert_eq!(inner.network.tor.onion_address, None);
 
     assert!(!inner.network.application.https.enabled);
     assert_eq!(inner.network.application.https.listen_address, "");
     assert_eq!(inner.network.application.https.path, "app");
     assert_eq!(inner.network.application.https.url, None);
+    assert_eq!(inner.network.application.https.directory, None);
     assert!(!inner.network.application.http.enabled);
     assert_eq!(inner.network.application.http.listen_address, "");
     assert_eq!(inner.network.application.http.path, "app");
     assert_eq!(inner.network.application.http.url, None);
+    assert_eq!(inner.network.application.http.directory, None);
 
     assert!(inner.network.protocol.udp.enabled);
     assert_eq!(inner.network.protocol.udp.socket_pool_size, 0u32);
     assert_eq!(inner.network.protocol.udp.listen_address, "");
     assert_eq!(inner.network.protocol.udp.public_address, None);
+    assert_eq!(inner.network.protocol.udp.preference, 0u8);
     assert!(inner.network.protocol.tcp.connect);
     assert!(inner.network.protocol.tcp.listen);
     assert_eq!(inner.network.protocol.tcp.max_connections, 32u32);
     assert_eq!(inner.network.protocol.tcp.listen_address, "");
     assert_eq!(inner.network.protocol.tcp.public_address, None);
+    assert_eq!(inner.network.protocol.tcp.preference, 0u8);
     assert!(inner.network.protocol.ws.connect);
     assert!(inner.network.protocol.ws.listen);
     assert_eq!(inner.network.protocol.ws.max_connections, 32u32);
     assert_eq!(inner.network.protocol.ws.listen_address, "");
     assert_eq!(inner.network.protocol.ws.path, "ws");
     assert_eq!(inner.network.protocol.ws.url, None);
+    assert_eq!(inner.network.protocol.ws.preference, 0u8);
     assert!(inner.network.protocol.wss.connect);
     assert!(!inner.network.protocol.wss.listen);
     assert_eq!(inner.network.protocol.wss.max_connections, 32u32);
     assert_eq!(inner.network.protocol.wss.listen_address, "");
     assert_eq!(inner.network.protocol.wss.path, "ws");
     assert_eq!(inner.network.protocol.wss.url, None);
+    assert_eq!(inner.network.protocol.wss.preference, 0u8);
+    assert!(inner.network.protocol.ipc.connect);
+    assert!(!inner.network.protocol.ipc.listen);
+    assert_eq!(inner.network.protocol.ipc.listen_path, "");
+    assert_eq!(inner.network.role, VeilidConfigRole::Full);
+    assert_eq!(
+        inner.network.metered_policy,
+        VeilidConfigNetworkCostPolicy::ReduceTickRate
+    );
+    assert_eq!(
+        inner.network.roaming_policy,
+        VeilidConfigNetworkCostPolicy::Suspend
+    );
+    assert_eq!(inner.network.low_power_wake_interval_ms, 60_000u32);
+}
+
+pub async fn test_config_role() {
+    assert_eq!(
+        VeilidConfigRole::from_str("full").unwrap(),
+        VeilidConfigRole::Full
+    );
+    assert_eq!(
+        VeilidConfigRole::from_str("Bootstrap").unwrap(),
+        VeilidConfigRole::Bootstrap
+    );
+    assert!(VeilidConfigRole::from_str("relay").is_err());
+    assert_eq!(
+        VeilidConfigRole::from_str(&VeilidConfigRole::Bootstrap.to_string()).unwrap(),
+        VeilidConfigRole::Bootstrap
+    );
+}
+
+pub async fn test_config_network_cost_policy() {
+    assert_eq!(
+        VeilidConfigNetworkCostPolicy::from_str("relay_only").unwrap(),
+        VeilidConfigNetworkCostPolicy::RelayOnly
+    );
+    assert_eq!(
+        VeilidConfigNetworkCostPolicy::from_str("ReduceTickRate").unwrap(),
+        VeilidConfigNetworkCostPolicy::ReduceTickRate
+    );
+    assert!(VeilidConfigNetworkCostPolicy::from_str("detach").is_err());
+    assert_eq!(
+        VeilidConfigNetworkCostPolicy::from_str(
+            &VeilidConfigNetworkCostPolicy::Suspend.to_string()
+        )
+        .unwrap(),
+        VeilidConfigNetworkCostPolicy::Suspend
+    );
+
+    // Each policy includes the ones before it
+    let normal = VeilidConfigNetworkCostPolicy::Normal;
+    assert!(!normal.reduces_tick_rate() && !normal.is_relay_only() && !normal.is_suspend());
+    let relay_only = VeilidConfigNetworkCostPolicy::RelayOnly;
+    assert!(relay_only.reduces_tick_rate() && relay_only.is_relay_only());
+    assert!(!relay_only.is_suspend());
+    let suspend = VeilidConfigNetworkCostPolicy::Suspend;
+    assert!(suspend.reduces_tick_rate() && suspend.is_relay_only() && suspend.is_suspend());
+}
+
+pub async fn test_config_older_json() {
+    // Config json from clients that predate newer fields still deserializes, with their defaults
+    let default_config = VeilidConfigInner::default();
+    let mut json = serde_json::to_value(&default_config).unwrap();
+    let network = json["network"].as_object_mut().unwrap();
+    for key in [
+        "prewarm_grace_period_ms",
+        "max_relay_client_bytes_per_sec",
+        "max_relay_client_queued_bytes",
+        "max_envelope_size",
+        "tor",
+        "role",
+        "metered_policy",
+        "roaming_policy",
+        "low_power_wake_interval_ms",
+        "detect_address_changes_distinct_subnets",
+    ] {
+        network.remove(key).unwrap();
+    }
+    for key in [
+        "min_fanout_under_load",
+        "remote_storage_high_watermark_percent",
+        "remote_storage_low_watermark_percent",
+        "set_value_pow_difficulty",
+        "compress_values",
+        "audit_log_max_entries",
+    ] {
+        network["dht"].as_object_mut().unwrap().remove(key).unwrap();
+    }
+    for key in [
+        "max_clock_skew_ms",
+        "clock_skew_warning_ms",
+        "max_private_routes",
+        "max_private_routes_per_api",
+        "private_route_idle_release_ms",
+        "max_app_message_size",
+        "max_app_call_size",
+        "max_app_message_frequency_per_min",
+        "max_app_call_frequency_per_min",
+        "strict_udp_source",
+        "strict_udp_source_allow_rebind",
+        "route_pow_difficulty",
+    ] {
+        network["rpc"].as_object_mut().unwrap().remove(key).unwrap();
+    }
+    for key in ["node_info_max_age_ms", "verified_peers_only"] {
+        network["routing_table"]
+            .as_object_mut()
+            .unwrap()
+            .remove(key)
+            .unwrap();
+    }
+    let protocol = network["protocol"].as_object_mut().unwrap();
+    protocol.remove("ipc").unwrap();
+    for p in ["udp", "tcp", "ws", "wss"] {
+        protocol[p]
+            .as_object_mut()
+            .unwrap()
+            .remove("preference")
+            .unwrap();
+    }
+    json["table_store"]
+        .as_object_mut()
+        .unwrap()
+        .remove("backend")
+        .unwrap();
+    json["protected_store"]
+        .as_object_mut()
+        .unwrap()
+        .remove("hardware_node_id")
+        .unwrap();
+    json["protected_store"]
+        .as_object_mut()
+        .unwrap()
+        .remove("hardware_node_id_migrate")
+        .unwrap();
+
+    let config: VeilidConfigInner = serde_json::from_value(json).unwrap();
+    assert_eq!(config, default_config);
+}
+
+pub async fn test_config_schema() {
+    let schema = veilid_config_schema();
+
+    // Every leaf of the default config is described, with its default
+    let default = serde_json::to_value(VeilidConfigInner::default()).unwrap();
+    fn leaves(value: &serde_json::Value, path: String, out: &mut Vec<(String, serde_json::Value)>) {
+        match value {
+            serde_json::Value::Object(m) => {
+                for (k, v) in m {
+                    let path = if path.is_empty() {
+                        k.clone()
+                    } else {
+                        format!("{}.{}", path, k)
+                    };
+                    leaves(v, path, out);
+                }
+            }
+            _ => out.push((path, value.clone())),
+        }
+    }
+    let mut default_leaves = Vec::new();
+    leaves(&default, String::new(), &mut default_leaves);
+    assert_eq!(schema.fields.len(), default_leaves.len());
+    for (path, value) in default_leaves {
+        let field = schema
+            .field(&path)
+            .unwrap_or_else(|| panic!("missing field {}", path));
+        assert_eq!(field.default, value, "default of {}", path);
+    }
+
+    let timeout = schema.field("network.rpc.timeout_ms").unwrap();
+    assert_eq!(timeout.kind, VeilidConfigFieldKind::Integer);
+    assert_eq!(timeout.format.as_deref(), Some("uint32"));
+    assert!(!timeout.optional);
+    assert!(timeout.constraints.contains(&VeilidConfigConstraint {
+        kind: VeilidConfigConstraintKind::Minimum { value: 1000 },
+        when: vec![],
+    }));
+
+    let role = schema.field("network.role").unwrap();
+    assert_eq!(role.kind, VeilidConfigFieldKind::Choice);
+    assert_eq!(
+        role.choices,
+        vec!["Full".to_owned(), "Bootstrap".to_owned()]
+    );
+
+    let onion = schema.field("network.tor.onion_address").unwrap();
+    assert_eq!(onion.kind, VeilidConfigFieldKind::String);
+    assert!(onion.optional);
+    assert_eq!(onion.constraints.len(), 2);
+
+    let ws_path = schema.field("network.protocol.ws.path").unwrap();
+    assert!(ws_path.constraints.contains(&VeilidConfigConstraint {
+        kind: VeilidConfigConstraintKind::DiffersFromField {
+            path: "network.application.https.path".to_owned()
+        },
+        when: vec![
+            "network.protocol.ws.listen".to_owned(),
+            "network.application.https.enabled".to_owned()
+        ],
+    }));
+
+    assert_eq!(
+        schema
+            .field("network.routing_table.bootstrap")
+            .unwrap()
+            .kind,
+        VeilidConfigFieldKind::List
+    );
+    assert_eq!(
+        schema.field("network.upnp").unwrap().kind,
+        VeilidConfigFieldKind::Bool
+    );
+
+    // Secrets are marked so editors can hide them
+    assert!(
+        schema
+            .field("protected_store.device_encryption_key_password")
+            .unwrap()
+            .secret
+    );
+    assert!(
+        schema
+            .field("network.routing_table.node_id_secret")
+            .unwrap()
+            .secret
+    );
+    assert!(!schema.field("program_name").unwrap().secret);
+
+    // The schema survives a round trip for clients to consume
+    let copy: VeilidConfigSchema = deserialize_json(&serialize_json(&schema)).unwrap();
+    assert_eq!(copy, schema);
 }
 
 pub async fn test_all() {
     test_config().await;
+    test_config_role().await;
+    test_config_network_cost_policy().await;
+    test_config_older_json().await;
+    test_config_schema().await;
 }
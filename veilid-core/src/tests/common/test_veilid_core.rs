@@ -73,8 +73,142 @@ pub async fn test_attach_detach() {
     api.shutdown().await;
 }
 
+fn namespaced_config_callback(namespace: &'static str) -> ConfigCallback {
+    Arc::new(move |key: String| match key.as_str() {
+        "namespace" => Ok(Box::new(namespace.to_owned())),
+        _ => config_callback(key),
+    })
+}
+
+pub async fn test_multiple_instances() {
+    let (update_callback, config_callback) = setup_veilid_core();
+    let first = api_startup(update_callback.clone(), config_callback.clone())
+        .await
+        .expect("startup failed");
+
+    // Nodes with their own namespace run side by side
+    let second = api_startup(
+        update_callback.clone(),
+        namespaced_config_callback("second"),
+    )
+    .await
+    .expect("second startup failed");
+    let third = api_startup(update_callback.clone(), namespaced_config_callback("third"))
+        .await
+        .expect("third startup failed");
+
+    // A node that would share storage with a running one is refused
+    assert!(matches!(
+        api_startup(
+            update_callback.clone(),
+            namespaced_config_callback("second")
+        )
+        .await,
+        Err(VeilidAPIError::AlreadyInitialized)
+    ));
+
+    // Each node has its own table store
+    let ts1 = first.table_store().unwrap();
+    let ts2 = second.table_store().unwrap();
+    let db1 = ts1.open("multiple_instances", 1).await.unwrap();
+    db1.store(0, b"key", b"first").await.unwrap();
+    let db2 = ts2.open("multiple_instances", 1).await.unwrap();
+    assert_eq!(db2.load(0, b"key").await.unwrap(), None);
+    drop(db1);
+    drop(db2);
+
+    // Shutting one node down leaves the others running, and frees its namespace
+    second.shutdown().await;
+    assert!(!first.is_shutdown());
+    assert!(!third.is_shutdown());
+    let second = api_startup(update_callback, namespaced_config_callback("second"))
+        .await
+        .expect("restart of second failed");
+
+    second.shutdown().await;
+    third.shutdown().await;
+    first.shutdown().await;
+}
+
+pub async fn test_low_power() {
+    let (update_callback, config_callback) = setup_veilid_core();
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+    let network_manager = api.network_manager().unwrap();
+    assert!(!network_manager.is_low_power());
+    assert!(!network_manager.is_relay_only());
+
+    // Low power mode can be entered before attaching and survives attachment
+    api.set_low_power(true).unwrap();
+    assert!(network_manager.is_low_power());
+    assert!(network_manager.is_relay_only());
+    api.attach().await.unwrap();
+    sleep(2000).await;
+    assert!(network_manager.is_low_power());
+
+    // Leaving it goes back to accepting connections
+    api.set_low_power(false).unwrap();
+    assert!(!network_manager.is_low_power());
+    assert!(!network_manager.is_relay_only());
+    sleep(2000).await;
+    api.detach().await.unwrap();
+    api.shutdown().await;
+}
+
+pub async fn test_log_routing() {
+    use tracing_subscriber::prelude::*;
+
+    let layer = ApiTracingLayer::new();
+    let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(layer));
+
+    let logs_callback = |logs: Arc<Mutex<Vec<String>>>| -> UpdateCallback {
+        Arc::new(move |update: VeilidUpdate| {
+            if let VeilidUpdate::Log(log) = update {
+                logs.lock().push(log.message);
+            }
+        })
+    };
+    let key_a = VeilidInstanceKey {
+        program_name: "VeilidCoreTests".into(),
+        namespace: "a".into(),
+    };
+    let key_b = VeilidInstanceKey {
+        program_name: "VeilidCoreTests".into(),
+        namespace: "b".into(),
+    };
+    let logs_a = Arc::new(Mutex::new(Vec::new()));
+    let logs_b = Arc::new(Mutex::new(Vec::new()));
+    ApiTracingLayer::init(key_a.clone(), logs_callback(logs_a.clone())).await;
+    ApiTracingLayer::init(key_b.clone(), logs_callback(logs_b.clone())).await;
+
+    // Events in a node's span only go to that node, even from nested spans
+    key_a.span().in_scope(|| {
+        info_span!("nested").in_scope(|| info!("only a"));
+    });
+    key_b.span().in_scope(|| info!("only b"));
+
+    // Events outside of any node go to every node
+    info!("everyone");
+
+    ApiTracingLayer::terminate(key_a).await;
+    ApiTracingLayer::terminate(key_b).await;
+
+    assert_eq!(
+        *logs_a.lock(),
+        vec!["only a".to_owned(), "everyone".to_owned()]
+    );
+    assert_eq!(
+        *logs_b.lock(),
+        vec!["only b".to_owned(), "everyone".to_owned()]
+    );
+}
+
 pub async fn test_all() {
     test_startup_shutdown().await;
     test_startup_shutdown_from_config().await;
+    test_multiple_instances().await;
+    test_log_routing().await;
     test_attach_detach().await;
+    test_low_power().await;
 }
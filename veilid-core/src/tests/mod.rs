@@ -13,5 +13,7 @@ pub use common::*;
 pub use crypto::tests::*;
 pub use network_manager::tests::*;
 pub use routing_table::tests::*;
+pub use rpc_processor::tests::*;
+pub use storage_manager::tests::*;
 pub use table_store::tests::*;
 pub use veilid_api::tests::*;
@@ -14,22 +14,107 @@ pub async fn run_all_tests() {
     test_veilid_core::test_all().await;
     info!("TEST: test_veilid_config");
     test_veilid_config::test_all().await;
+    info!("TEST: test_application_http");
+    test_application_http::test_all().await;
+    info!("TEST: test_boot_proof");
+    test_boot_proof::test_all().await;
+    info!("TEST: test_capability_hysteresis");
+    test_capability_hysteresis::test_all().await;
+    info!("TEST: test_clock_skew");
+    test_clock_skew::test_all().await;
+    info!("TEST: test_connection_race");
+    test_connection_race::test_all().await;
     info!("TEST: test_connection_table");
     test_connection_table::test_all().await;
+    info!("TEST: test_dial_info_class");
+    test_dial_info_class::test_all().await;
+    info!("TEST: test_envelope_fragments");
+    test_envelope_fragments::test_all().await;
+    info!("TEST: test_ipc_dial_info");
+    test_ipc_dial_info::test_all().await;
+    info!("TEST: test_network_error");
+    test_network_error::test_all().await;
+    info!("TEST: test_onion_dial_info");
+    test_onion_dial_info::test_all().await;
+    info!("TEST: test_public_address_check");
+    test_public_address_check::test_all().await;
+    info!("TEST: test_relay_scheduler");
+    test_relay_scheduler::test_all().await;
+    info!("TEST: test_send_queue");
+    test_send_queue::test_all().await;
     info!("TEST: test_signed_node_info");
     test_signed_node_info::test_all().await;
+    info!("TEST: test_socks");
+    test_socks::test_all().await;
+    info!("TEST: test_tls_certificate");
+    test_tls_certificate::test_all().await;
+    #[cfg(any(test, feature = "sim-network"))]
+    {
+        info!("TEST: test_sim_network");
+        test_sim_network::test_all().await;
+    }
     info!("TEST: test_table_store");
     test_table_store::test_all().await;
     info!("TEST: test_protected_store");
     test_protected_store::test_all().await;
+    #[cfg(feature = "unstable-blockstore")]
+    {
+        info!("TEST: test_block_store");
+        test_block_store::test_all().await;
+    }
     info!("TEST: test_crypto");
     test_crypto::test_all().await;
     info!("TEST: test_envelope_receipt");
     test_envelope_receipt::test_all().await;
     info!("TEST: veilid_api::tests::test_serialize_json");
     veilid_api::tests::test_serialize_json::test_all().await;
+    info!("TEST: routing_table::test_bucket_entry");
+    routing_table::tests::test_bucket_entry::test_all().await;
+    info!("TEST: routing_table::test_bucket_refresh");
+    routing_table::tests::test_bucket_refresh::test_all().await;
     info!("TEST: routing_table::test_serialize_routing_table");
     routing_table::tests::test_serialize_routing_table::test_all().await;
+    info!("TEST: routing_table::test_routing_table_error");
+    routing_table::tests::test_routing_table_error::test_all().await;
+    info!("TEST: routing_table::test_route_spec_store");
+    routing_table::tests::test_route_spec_store::test_all().await;
+    info!("TEST: routing_table::test_peer_info");
+    routing_table::tests::test_peer_info::test_all().await;
+    info!("TEST: routing_table::test_peer_minimum_refresh");
+    routing_table::tests::test_peer_minimum_refresh::test_all().await;
+    info!("TEST: routing_table::test_find_node_answer");
+    routing_table::tests::test_find_node_answer::test_all().await;
+    info!("TEST: rpc_processor::test_answer_cache");
+    rpc_processor::tests::test_answer_cache::test_all().await;
+    info!("TEST: rpc_processor::test_app_message_limiter");
+    rpc_processor::tests::test_app_message_limiter::test_all().await;
+    info!("TEST: rpc_processor::test_coder_compat");
+    rpc_processor::tests::test_coder_compat::test_all().await;
+    info!("TEST: rpc_processor::test_digest_value");
+    rpc_processor::tests::test_digest_value::test_all().await;
+    info!("TEST: rpc_processor::test_fuzz");
+    rpc_processor::tests::test_fuzz::test_all().await;
+    info!("TEST: rpc_processor::test_load_tracker");
+    rpc_processor::tests::test_load_tracker::test_all().await;
+    info!("TEST: rpc_processor::test_operation_waiter");
+    rpc_processor::tests::test_operation_waiter::test_all().await;
+    info!("TEST: rpc_processor::test_proof_of_work");
+    rpc_processor::tests::test_proof_of_work::test_all().await;
+    info!("TEST: rpc_processor::test_record_expiration");
+    rpc_processor::tests::test_record_expiration::test_all().await;
+    info!("TEST: rpc_processor::test_record_versions");
+    rpc_processor::tests::test_record_versions::test_all().await;
+    info!("TEST: rpc_processor::test_set_value_batch");
+    rpc_processor::tests::test_set_value_batch::test_all().await;
+    info!("TEST: rpc_processor::test_udp_answer_source");
+    rpc_processor::tests::test_udp_answer_source::test_all().await;
+    info!("TEST: storage_manager::test_record_store");
+    storage_manager::tests::test_record_store::test_all().await;
+    #[cfg(any(test, feature = "sim-network"))]
+    {
+        info!("TEST: test_sim_integration");
+        test_sim_integration::test_all().await;
+    }
     // info!("TEST: test_dht");
     // test_dht::test_all().await;
 
@@ -115,22 +200,101 @@ cfg_if! {
 
         run_test!(test_veilid_config);
 
+        run_test!(test_application_http);
+
+        run_test!(test_boot_proof);
+
+        run_test!(test_capability_hysteresis);
+
+        run_test!(test_clock_skew);
+
+        run_test!(test_connection_race);
+
         run_test!(test_connection_table);
 
+        run_test!(test_dial_info_class);
+
+        run_test!(test_envelope_fragments);
+
+        run_test!(test_ipc_dial_info);
+
+        run_test!(test_network_error);
+
+        run_test!(test_onion_dial_info);
+
+        run_test!(test_public_address_check);
+
+        run_test!(test_relay_scheduler);
+
+        run_test!(test_send_queue);
+
         run_test!(test_signed_node_info);
 
+        run_test!(test_socks);
+
+        run_test!(test_tls_certificate);
+
+        #[cfg(any(test, feature = "sim-network"))]
+        run_test!(test_sim_network);
+
         run_test!(test_table_store);
 
         run_test!(test_protected_store);
 
+        #[cfg(feature = "unstable-blockstore")]
+        run_test!(test_block_store);
+
         run_test!(test_crypto);
 
         run_test!(test_envelope_receipt);
 
         run_test!(veilid_api, test_serialize_json);
 
+        run_test!(routing_table, test_bucket_entry);
+
+        run_test!(routing_table, test_bucket_refresh);
+
         run_test!(routing_table, test_serialize_routing_table);
 
+        run_test!(routing_table, test_routing_table_error);
+
+        run_test!(routing_table, test_route_spec_store);
+
+        run_test!(routing_table, test_peer_info);
+
+        run_test!(routing_table, test_peer_minimum_refresh);
+
+        run_test!(routing_table, test_find_node_answer);
+
+        run_test!(rpc_processor, test_answer_cache);
+
+        run_test!(rpc_processor, test_app_message_limiter);
+
+        run_test!(rpc_processor, test_coder_compat);
+
+        run_test!(rpc_processor, test_digest_value);
+
+        run_test!(rpc_processor, test_fuzz);
+
+        run_test!(rpc_processor, test_load_tracker);
+
+        run_test!(rpc_processor, test_operation_waiter);
+
+        run_test!(rpc_processor, test_proof_of_work);
+
+        run_test!(rpc_processor, test_record_expiration);
+
+        run_test!(rpc_processor, test_record_versions);
+
+        run_test!(rpc_processor, test_set_value_batch);
+
+        run_test!(rpc_processor, test_udp_answer_source);
+
+        run_test!(storage_manager, test_record_store);
+
+        #[cfg(any(test, feature = "sim-network"))]
+        run_test!(test_sim_integration);
+
         // run_test!(test_dht);
     }
 }
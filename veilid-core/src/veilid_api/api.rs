@@ -1,9 +1,12 @@
 use super::*;
+use futures_util::StreamExt;
+use routing_table::RoutingDomain;
 
 /////////////////////////////////////////////////////////////////////////////////////////////////////
 
 struct VeilidAPIInner {
     context: Option<VeilidCoreContext>,
+    next_instance_id: u64,
 }
 
 impl fmt::Debug for VeilidAPIInner {
@@ -36,6 +39,8 @@ impl Drop for VeilidAPIInner {
 #[derive(Clone, Debug)]
 pub struct VeilidAPI {
     inner: Arc<Mutex<VeilidAPIInner>>,
+    /// Which API instance this is, private routes are budgeted per instance
+    instance_id: u64,
 }
 
 impl VeilidAPI {
@@ -46,12 +51,32 @@ impl VeilidAPI {
         Self {
             inner: Arc::new(Mutex::new(VeilidAPIInner {
                 context: Some(context),
+                next_instance_id: 1,
             })),
+            instance_id: 0,
+        }
+    }
+
+    /// Get a new instance of the API for a separate application
+    ///
+    /// The instance shares the same Veilid node, but has its own budget of
+    /// private routes so one application can not allocate them all.
+    /// Clones of an instance share its budget.
+    pub fn new_instance(&self) -> Self {
+        let instance_id = {
+            let mut inner = self.inner.lock();
+            let instance_id = inner.next_instance_id;
+            inner.next_instance_id += 1;
+            instance_id
+        };
+        Self {
+            inner: self.inner.clone(),
+            instance_id,
         }
     }
 
     /// Shut down Veilid and terminate the API
-    #[instrument(target = "veilid_api", level = "debug", skip_all)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all)]
     pub async fn shutdown(self) {
         event!(target: "veilid_api", Level::DEBUG, 
             "VeilidAPI::shutdown()");
@@ -150,6 +175,21 @@ impl VeilidAPI {
         }
         Err(VeilidAPIError::not_initialized())
     }
+    /// The span this node does its work in, so the logs of API calls reach this node's update callback
+    pub(crate) fn instance_span(&self) -> Span {
+        let inner = self.inner.lock();
+        if let Some(context) = &inner.context {
+            return context.config.instance_span();
+        }
+        Span::none()
+    }
+    pub(crate) fn debug_cache(&self) -> VeilidAPIResult<Arc<Mutex<DebugCache>>> {
+        let inner = self.inner.lock();
+        if let Some(context) = &inner.context {
+            return Ok(context.debug_cache.clone());
+        }
+        Err(VeilidAPIError::NotInitialized)
+    }
 
     ////////////////////////////////////////////////////////////////
     // Attach/Detach
@@ -160,7 +200,8 @@ impl VeilidAPI {
         let network_manager = attachment_manager.network_manager();
         let config = self.config()?;
 
-        let attachment = attachment_manager.get_veilid_state();
+        let mut attachment = attachment_manager.get_veilid_state();
+        attachment.storage = attachment_manager.storage_manager().status().await?;
         let network = network_manager.get_veilid_state();
         let config = config.get_veilid_state();
 
@@ -171,37 +212,301 @@ impl VeilidAPI {
         })
     }
 
+    /// Get the current time as estimated from the clocks of our peers
+    ///
+    /// This is the local time shifted by the median clock skew measured in status exchanges with live peers,
+    /// so it stays consistent with the rest of the network even if the local clock is wrong.
+    /// Fails with TryAgain until at least one peer has been asked for its time.
+    pub fn get_network_time(&self) -> VeilidAPIResult<NetworkTime> {
+        let routing_table = self.routing_table()?;
+        let Some((clock_skew, peer_count)) = routing_table.get_clock_skew_with_peer_count() else {
+            apibail_try_again!("no peers have reported their time yet");
+        };
+        Ok(NetworkTime::new(
+            get_aligned_timestamp(),
+            clock_skew,
+            peer_count as u32,
+        ))
+    }
+
+    /// Get how much storage space the DHT record stores are using
+    pub async fn get_storage_usage(&self) -> VeilidAPIResult<DHTStorageUsage> {
+        let storage_manager = self.storage_manager()?;
+        storage_manager.storage_usage().await
+    }
+
+    /// Get the pending offline writes, open records and storage space used by the DHT storage manager
+    ///
+    /// This is also reported in every [VeilidUpdate::Attachment] while attached.
+    pub async fn get_storage_status(&self) -> VeilidAPIResult<StorageManagerStatus> {
+        let storage_manager = self.storage_manager()?;
+        storage_manager.status().await
+    }
+
+    /// Get the remote DHT operations this node has served from the DHT audit log, oldest first
+    ///
+    /// Optionally only the operations on one record, and only those served at or after a timestamp.
+    /// The audit log is only kept if `network.dht.audit_log_max_entries` is not zero.
+    pub async fn query_dht_audit_log(
+        &self,
+        key: Option<TypedKey>,
+        since: Option<Timestamp>,
+    ) -> VeilidAPIResult<Vec<DHTAuditLogEntry>> {
+        let storage_manager = self.storage_manager()?;
+        storage_manager.query_audit_log(key, since).await
+    }
+
+    /// Take all of the entries out of the DHT audit log, oldest first, and start a new log
+    pub async fn rotate_dht_audit_log(&self) -> VeilidAPIResult<Vec<DHTAuditLogEntry>> {
+        let storage_manager = self.storage_manager()?;
+        storage_manager.rotate_audit_log().await
+    }
+
     /// Connect to the network
-    #[instrument(target = "veilid_api", level = "debug", skip_all, ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, ret, err)]
     pub async fn attach(&self) -> VeilidAPIResult<()> {
         event!(target: "veilid_api", Level::DEBUG, 
             "VeilidAPI::attach()");
 
+        // The network tasks started here are spawned in this node's span, so their logs go to its update callback
         let attachment_manager = self.attachment_manager()?;
-        if !attachment_manager.attach().await {
+        if !attachment_manager
+            .attach()
+            .instrument(self.instance_span())
+            .await
+        {
             apibail_generic!("Already attached");
         }
         Ok(())
     }
 
     /// Disconnect from the network
-    #[instrument(target = "veilid_api", level = "debug", skip_all, ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, ret, err)]
     pub async fn detach(&self) -> VeilidAPIResult<()> {
         event!(target: "veilid_api", Level::DEBUG, 
             "VeilidAPI::detach()");
 
         let attachment_manager = self.attachment_manager()?;
-        if !attachment_manager.detach().await {
+        if !attachment_manager
+            .detach()
+            .instrument(self.instance_span())
+            .await
+        {
             apibail_generic!("Already detached");
         }
         Ok(())
     }
 
+    /// Detect our public dial info and network class again
+    ///
+    /// Call this when the way the node reaches the internet has changed, such as after changing
+    /// router or firewall settings, instead of detaching and attaching again.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, ret, err)]
+    pub fn trigger_public_dial_info_detection(&self) -> VeilidAPIResult<()> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::trigger_public_dial_info_detection()");
+
+        let network_manager = self.network_manager()?;
+        network_manager
+            .trigger_public_dial_info_detection()
+            .map_err(VeilidAPIError::try_again)
+    }
+
+    /// Load the TLS certificate and private key for the WSS listener again
+    ///
+    /// Call this after renewing the certificate to use it for new connections without restarting
+    /// the network. Existing connections are not dropped. The certificate files are also checked for
+    /// changes periodically. Returns false if there is no TLS listener to reload.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, ret, err)]
+    pub fn reload_tls_certificates(&self) -> VeilidAPIResult<bool> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::reload_tls_certificates()");
+
+        let network_manager = self.network_manager()?;
+        network_manager
+            .reload_tls_certificates()
+            .map_err(VeilidAPIError::generic)
+    }
+
+    /// Announce our node ids of every crypto kind to the network, cross-signed by each other
+    ///
+    /// Nodes that only had a node id of an older crypto kind are given node ids for the newer kinds
+    /// supported by this build when they start. Call this to re-sign our node info, check that the
+    /// new peer info cross-signs every node id, and push it to our recent peers right away with a
+    /// status question so they learn that the new node ids belong to the same node without waiting
+    /// for the next ping. Returns all of our node ids.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, ret, err)]
+    pub async fn migrate_node_ids(&self) -> VeilidAPIResult<TypedKeyGroup> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::migrate_node_ids()");
+
+        let routing_table = self.routing_table()?;
+        let rpc_processor = self.rpc_processor()?;
+        routing_table.republish_own_peer_info();
+
+        // Make sure what we are about to announce is something peers will accept
+        let own_peer_info = routing_table.get_own_peer_info(RoutingDomain::PublicInternet);
+        own_peer_info.validate(self.crypto()?)?;
+
+        // Send our new peer info to every recent peer
+        let mut unord = FuturesUnordered::new();
+        for (node_id, _) in routing_table.get_recent_peers() {
+            let Ok(Some(nr)) = routing_table.lookup_node_ref(node_id) else {
+                continue;
+            };
+            let rpc_processor = rpc_processor.clone();
+            unord.push(
+                async move {
+                    if let Err(e) = rpc_processor.rpc_call_status(Destination::direct(nr)).await {
+                        log_rtab!(debug "failed to announce migrated node ids to {}: {}", node_id, e);
+                    }
+                }
+                .instrument(self.instance_span()),
+            );
+        }
+        while unord.next().await.is_some() {}
+
+        Ok(routing_table.node_ids())
+    }
+
+    /// Get the most recent reports from peers of the address they see us at, oldest first
+    ///
+    /// Useful for finding out which peers are behind unexpected public address changes.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, ret, err)]
+    pub fn public_address_observations(&self) -> VeilidAPIResult<Vec<PublicAddressObservation>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::public_address_observations()");
+
+        let network_manager = self.network_manager()?;
+        Ok(network_manager.public_address_observations())
+    }
+
+    /// Get the destinations we most recently failed to pass messages along to while relaying or
+    /// routing for other nodes, least recent failure first
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, ret, err)]
+    pub fn relay_dead_letters(&self) -> VeilidAPIResult<Vec<RelayDeadLetter>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::relay_dead_letters()");
+
+        let network_manager = self.network_manager()?;
+        Ok(network_manager.relay_dead_letters())
+    }
+
+    /// Get the traffic we relayed for each node using us as a relay, least recently active first
+    ///
+    /// Includes what was dropped for going over the per-client limits in the network config.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, ret, err)]
+    pub fn relay_client_stats(&self) -> VeilidAPIResult<Vec<RelayClientStats>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::relay_client_stats()");
+
+        let network_manager = self.network_manager()?;
+        Ok(network_manager.relay_client_stats())
+    }
+
+    /// Get entry counts by state and average entry age for each routing table bucket, per crypto kind
+    ///
+    /// Useful for visualizing how our peers are spread over the keyspace, and for spotting
+    /// neighborhoods that fill up faster than they should.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, ret, err)]
+    pub fn get_bucket_statistics(&self) -> VeilidAPIResult<Vec<BucketStatistics>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::get_bucket_statistics()");
+
+        let routing_table = self.network_manager()?.routing_table();
+        Ok(routing_table.get_bucket_statistics())
+    }
+
+    /// Get the peers we exchanged the most bytes and the most RPC messages with, at most `count` of each
+    ///
+    /// Counts cover the last hour, or the time since [VeilidAPI::reset_top_talkers] if that was more recent.
+    /// Useful for finding out which peers use our bandwidth without capturing packets.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, ret, err)]
+    pub fn get_top_talkers(&self, count: usize) -> VeilidAPIResult<TopTalkers> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::get_top_talkers(count: {})", count);
+
+        let routing_table = self.network_manager()?.routing_table();
+        Ok(routing_table.get_top_talkers(count))
+    }
+
+    /// Start counting top talkers again from zero
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, ret, err)]
+    pub fn reset_top_talkers(&self) -> VeilidAPIResult<()> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::reset_top_talkers()");
+
+        let routing_table = self.network_manager()?.routing_table();
+        routing_table.reset_top_talkers();
+        Ok(())
+    }
+
+    /// Delegate keepalive ticks to a SharedWorker or Service Worker
+    ///
+    /// Browsers throttle timers in background tabs, which stalls our keepalive pings and lets
+    /// connections drop. Pass the MessagePort of a worker that answers `{"type": "start", "interval_ms": N}`
+    /// by posting `{"type": "tick"}` every `interval_ms` until it receives `{"type": "stop"}`.
+    /// Pass None to go back to using our own timers.
+    #[cfg(target_arch = "wasm32")]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, ret, err)]
+    pub fn set_keepalive_port(&self, port: Option<web_sys::MessagePort>) -> VeilidAPIResult<()> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::set_keepalive_port()");
+
+        let network_manager = self.network_manager()?;
+        network_manager.keepalive_worker().set_port(port);
+        Ok(())
+    }
+
+    /// Tick now instead of waiting for the next tick interval
+    ///
+    /// Runs the relay keepalive and other periodic maintenance right away. Platform integrations that only get
+    /// brief execution time while an app is in the background call this to keep the node attached through its relay.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, ret, err)]
+    pub fn keepalive_tick(&self) -> VeilidAPIResult<()> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::keepalive_tick()");
+
+        let network_manager = self.network_manager()?;
+        network_manager.request_tick();
+        Ok(())
+    }
+
+    /// Report whether the network we are using is metered or roaming
+    ///
+    /// Platform integrations call this whenever the connectivity changes. The `network.metered_policy` and
+    /// `network.roaming_policy` settings decide whether to tick less often, only talk through a relay, or detach
+    /// until the network is no longer costly, in which case the node attaches again by itself.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), ret, err)]
+    pub async fn set_network_cost(&self, network_cost: NetworkCost) -> VeilidAPIResult<()> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::set_network_cost(network_cost: {:?})", network_cost);
+
+        let attachment_manager = self.attachment_manager()?;
+        attachment_manager.set_network_cost(network_cost).await;
+        Ok(())
+    }
+
+    /// Enter or leave low power mode
+    ///
+    /// Mobile apps call this when going into and coming back from the background. In low power mode the node only makes
+    /// outbound connections, sends no pings or relay keepalives, and wakes once per `network.low_power_wake_interval_ms`
+    /// to do its maintenance and send the DHT writes batched up since the last wake. Leaving it catches up right away.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), ret, err)]
+    pub fn set_low_power(&self, low_power: bool) -> VeilidAPIResult<()> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::set_low_power(low_power: {})", low_power);
+
+        let network_manager = self.network_manager()?;
+        network_manager.set_low_power(low_power);
+        Ok(())
+    }
+
     ////////////////////////////////////////////////////////////////
     // Routing Context
 
     /// Get a new `RoutingContext` object to use to send messages over the Veilid network with default safety, sequencing, and stability parameters.
-    #[instrument(target = "veilid_api", level = "debug", skip_all, err, ret)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip_all, err, ret)]
     pub fn routing_context(&self) -> VeilidAPIResult<RoutingContext> {
         event!(target: "veilid_api", Level::DEBUG, 
             "VeilidAPI::routing_context()");
@@ -216,7 +521,7 @@ impl VeilidAPI {
     /// `VLD0:XmnGyJrjMJBRC5ayJZRPXWTBspdX36-pbLb98H3UMeE` but if the prefix is left off
     /// `XmnGyJrjMJBRC5ayJZRPXWTBspdX36-pbLb98H3UMeE` will be parsed with the 'best' cryptosystem
     /// available (at the time of this writing this is `VLD0`)
-    #[instrument(target = "veilid_api", level = "debug", skip(self), fields(s=s.to_string()), ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), fields(s=s.to_string()), ret, err)]
     pub fn parse_as_target<S: ToString>(&self, s: S) -> VeilidAPIResult<Target> {
         let s = s.to_string();
 
@@ -270,7 +575,7 @@ impl VeilidAPI {
     ///
     /// Returns a route id and 'blob' that can be published over some means (DHT or otherwise) to be
     /// imported by another Veilid node.
-    #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), ret, err)]
     pub async fn new_custom_private_route(
         &self,
         crypto_kinds: &[CryptoKind],
@@ -293,17 +598,40 @@ impl VeilidAPI {
             c.network.rpc.default_route_hop_count.into()
         };
 
+        self.allocate_published_private_route(
+            crypto_kinds,
+            stability,
+            sequencing,
+            default_route_hop_count,
+        )
+        .await
+    }
+
+    /// Allocate and test a new private route with a specific hop count, and assemble its publishable 'blob'
+    pub(crate) async fn allocate_published_private_route(
+        &self,
+        crypto_kinds: &[CryptoKind],
+        stability: Stability,
+        sequencing: Sequencing,
+        hop_count: usize,
+    ) -> VeilidAPIResult<(RouteId, Vec<u8>)> {
         let rss = self.routing_table()?.route_spec_store();
         let route_id = rss.allocate_route(
             crypto_kinds,
             stability,
             sequencing,
-            default_route_hop_count,
+            hop_count,
             DirectionSet::all(),
             &[],
             false,
+            false,
+            Some(self.instance_id),
         )?;
-        if !rss.test_route(route_id).await? {
+        if !rss
+            .test_route(route_id)
+            .instrument(self.instance_span())
+            .await?
+        {
             rss.release_route(route_id);
             apibail_generic!("allocated route failed to test");
         }
@@ -324,7 +652,7 @@ impl VeilidAPI {
     /// Import a private route blob as a remote private route.
     ///
     /// Returns a route id that can be used to send private messages to the node creating this route.
-    #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), ret, err)]
     pub fn import_remote_private_route(&self, blob: Vec<u8>) -> VeilidAPIResult<RouteId> {
         event!(target: "veilid_api", Level::DEBUG, 
             "VeilidAPI::import_remote_private_route(blob: {:?})", blob);
@@ -336,7 +664,7 @@ impl VeilidAPI {
     ///
     /// This will deactivate the route and free its resources and it can no longer be sent to
     /// or received from.
-    #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), ret, err)]
     pub fn release_private_route(&self, route_id: RouteId) -> VeilidAPIResult<()> {
         event!(target: "veilid_api", Level::DEBUG, 
             "VeilidAPI::release_private_route(route_id: {:?})", route_id);
@@ -347,6 +675,37 @@ impl VeilidAPI {
         Ok(())
     }
 
+    /// Set or clear the rotation schedule of one of our published private routes
+    ///
+    /// Long-lived published routes become linkable, so a route with a rotation schedule is replaced by a new one
+    /// every `interval_ms`. The new route blob is reported in a [VeilidUpdate::RouteChange], and is also written
+    /// to a DHT record subkey if the schedule names one. The old route keeps working for `overlap_ms` so senders
+    /// have time to pick up the new one, and is then released. The replacement route keeps the same schedule.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), ret, err)]
+    pub fn set_private_route_rotation(
+        &self,
+        route_id: RouteId,
+        rotation: Option<PrivateRouteRotation>,
+    ) -> VeilidAPIResult<()> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::set_private_route_rotation(route_id: {:?}, rotation: {:?})", route_id, rotation);
+
+        if let Some(rotation) = &rotation {
+            if rotation.interval_ms == 0 {
+                apibail_invalid_argument!(
+                    "rotation interval must be greater than zero",
+                    "interval_ms",
+                    rotation.interval_ms
+                );
+            }
+        }
+        let rss = self.routing_table()?.route_spec_store();
+        if rss.is_route_id_remote(&route_id) {
+            apibail_invalid_argument!("not one of our own private routes", "route_id", route_id);
+        }
+        rss.set_route_rotation(&route_id, rotation)
+    }
+
     ////////////////////////////////////////////////////////////////
     // App Calls
 
@@ -354,7 +713,7 @@ impl VeilidAPI {
     ///
     /// * `call_id` - specifies which call to reply to, and it comes from a [VeilidUpdate::AppCall], specifically the [VeilidAppCall::id()] value.
     /// * `message` - is an answer blob to be returned by the remote node's [RoutingContext::app_call()] function, and may be up to 32768 bytes
-    #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), ret, err)]
     pub async fn app_call_reply(
         &self,
         call_id: OperationId,
@@ -366,15 +725,101 @@ impl VeilidAPI {
         let rpc_processor = self.rpc_processor()?;
         rpc_processor
             .app_call_reply(call_id, message)
+            .instrument(self.instance_span())
             .await
             .map_err(|e| e.into())
     }
 
+    /// Set how AppMessage and AppCall from a node are limited before being passed up through the update callback.
+    ///
+    /// * `node_id` - the node the policy applies to. Messages received over a private route do not have a known sender, so they always get the default policy.
+    /// * `policy` - [AppMessagePolicy::Limited] applies the size and rate limits from the `network.rpc` config and is the default,
+    ///   [AppMessagePolicy::Unlimited] applies only the size limits, and [AppMessagePolicy::Blocked] drops everything from the node.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), ret, err)]
+    pub fn set_app_message_policy(
+        &self,
+        node_id: TypedKey,
+        policy: AppMessagePolicy,
+    ) -> VeilidAPIResult<()> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::set_app_message_policy(node_id: {:?}, policy: {:?})", node_id, policy);
+
+        let rpc_processor = self.rpc_processor()?;
+        rpc_processor.set_app_message_policy(node_id, policy);
+        Ok(())
+    }
+
+    ////////////////////////////////////////////////////////////////
+    // Receipts
+
+    /// Make a signed receipt that can be handed to someone else and returned to this node later,
+    /// for example to confirm that a peer can reach this node over some out-of-band channel.
+    ///
+    /// * `expiration` - how long the receipt can be returned for, after which it can no longer be verified
+    /// * `extra_data` - arbitrary data carried with the receipt, up to 1250 bytes. It is signed but not encrypted.
+    ///
+    /// Returns the serialized receipt. Each receipt can be verified with [VeilidAPI::verify_receipt] only once.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), ret, err)]
+    pub fn make_receipt(
+        &self,
+        expiration: TimestampDuration,
+        extra_data: Vec<u8>,
+    ) -> VeilidAPIResult<Vec<u8>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::make_receipt(expiration: {:?}, extra_data: {:?})", expiration, extra_data);
+
+        if extra_data.len() > MAX_EXTRA_DATA_SIZE {
+            apibail_invalid_argument!("extra data too large", "extra_data", extra_data.len());
+        }
+        let network_manager = self.network_manager()?;
+        let (receipt, _receipt_event) = network_manager
+            .generate_single_shot_receipt(expiration.as_u64(), extra_data)
+            .map_err(VeilidAPIError::generic)?;
+        Ok(receipt)
+    }
+
+    /// Verify a receipt returned to this node.
+    ///
+    /// * `receipt` - a serialized receipt made by [VeilidAPI::make_receipt]
+    ///
+    /// Succeeds only if the receipt was signed by this node, has not expired, and has not been verified before.
+    /// Returns the contents of the receipt.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), ret, err)]
+    pub async fn verify_receipt(&self, receipt: Vec<u8>) -> VeilidAPIResult<VeilidReceipt> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "VeilidAPI::verify_receipt(receipt: {:?})", receipt);
+
+        let network_manager = self.network_manager()?;
+        let decoded = Receipt::from_signed_data(self.crypto()?, &receipt)?;
+        let sender_id = decoded.get_sender_typed_id();
+        if !self.routing_table()?.matches_own_node_id(&[sender_id]) {
+            apibail_invalid_argument!("receipt not made by this node", "receipt", sender_id);
+        }
+        let out = VeilidReceipt::new(
+            decoded.get_nonce(),
+            sender_id,
+            decoded.get_extra_data().to_vec(),
+        );
+
+        match network_manager
+            .handle_app_receipt(decoded)
+            .instrument(self.instance_span())
+            .await
+        {
+            NetworkResult::Value(()) => Ok(out),
+            _ => Err(VeilidAPIError::invalid_argument(
+                "receipt expired or already verified",
+                "receipt",
+                out.nonce(),
+            )),
+        }
+    }
+
     ////////////////////////////////////////////////////////////////
     // Tunnel Building
 
     #[cfg(feature = "unstable-tunnels")]
-    #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), ret, err)]
     pub async fn start_tunnel(
         &self,
         _endpoint_mode: TunnelMode,
@@ -384,7 +829,7 @@ impl VeilidAPI {
     }
 
     #[cfg(feature = "unstable-tunnels")]
-    #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), ret, err)]
     pub async fn complete_tunnel(
         &self,
         _endpoint_mode: TunnelMode,
@@ -395,7 +840,7 @@ impl VeilidAPI {
     }
 
     #[cfg(feature = "unstable-tunnels")]
-    #[instrument(target = "veilid_api", level = "debug", skip(self), ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.instance_span(), skip(self), ret, err)]
     pub async fn cancel_tunnel(&self, _tunnel_id: TunnelId) -> VeilidAPIResult<bool> {
         panic!("unimplemented");
     }
@@ -5,20 +5,18 @@ use super::*;
 use data_encoding::BASE64URL_NOPAD;
 use hashlink::LinkedHashMap;
 use network_manager::*;
-use once_cell::sync::Lazy;
 use routing_table::*;
 
+/// How many top talkers to show if no count is given
+const DEFAULT_TOP_TALKERS_COUNT: usize = 10;
+
+/// State kept between debug commands, per node so that nodes sharing a process do not see each other's
 #[derive(Default)]
-struct DebugCache {
+pub(crate) struct DebugCache {
     imported_routes: Vec<RouteId>,
-    opened_record_contexts: Lazy<LinkedHashMap<TypedKey, RoutingContext>>,
+    opened_record_contexts: LinkedHashMap<TypedKey, RoutingContext>,
 }
 
-static DEBUG_CACHE: Mutex<DebugCache> = Mutex::new(DebugCache {
-    imported_routes: Vec::new(),
-    opened_record_contexts: Lazy::new(LinkedHashMap::new),
-});
-
 pub fn format_opt_ts(ts: Option<TimestampDuration>) -> String {
     let Some(ts) = ts else {
         return "---".to_owned();
@@ -172,8 +170,14 @@ fn get_safety_selection(routing_table: RoutingTable) -> impl Fn(&str) -> Option<
             let mut hop_count = default_route_hop_count;
             let mut stability = Stability::default();
             let mut sequencing = Sequencing::default();
+            let mut preferred_first_hop = None;
+            let mut profile = None;
             for x in text.split(',') {
                 let x = x.trim();
+                if let Some(fh) = x.strip_prefix('^') {
+                    preferred_first_hop = Some(get_typed_key(fh)?);
+                    continue;
+                }
                 if let Some(pr) = get_route_id(rss.clone(), true, false)(x) {
                     preferred_route = Some(pr)
                 }
@@ -186,12 +190,22 @@ fn get_safety_selection(routing_table: RoutingTable) -> impl Fn(&str) -> Option<
                 if let Some(s) = get_sequencing(x) {
                     sequencing = s;
                 }
+                if let Some(p) = get_safety_profile(x) {
+                    profile = Some(p);
+                }
+            }
+            // A profile overrides the hop count and stability
+            if let Some(p) = profile {
+                hop_count = p.hop_count();
+                stability = p.stability();
             }
             let ss = SafetySpec {
                 preferred_route,
                 hop_count,
                 stability,
                 sequencing,
+                preferred_first_hop,
+                profile,
             };
             Some(SafetySelection::Safe(ss))
         }
@@ -217,6 +231,7 @@ fn get_node_ref_modifiers(mut node_ref: NodeRef) -> impl FnOnce(&str) -> Option<
 
 fn get_destination(
     routing_table: RoutingTable,
+    debug_cache: Arc<Mutex<DebugCache>>,
 ) -> impl FnOnce(&str) -> SendPinBoxFuture<Option<Destination>> {
     move |text| {
         let text = text.to_owned();
@@ -241,7 +256,7 @@ fn get_destination(
                 {
                     rss.best_remote_private_route(&prid)?
                 } else {
-                    let mut dc = DEBUG_CACHE.lock();
+                    let mut dc = debug_cache.lock();
                     let n = get_number(text)?;
                     let prid = *dc.imported_routes.get(n)?;
                     let Some(private_route) = rss.best_remote_private_route(&prid) else {
@@ -454,6 +469,18 @@ fn get_stability(text: &str) -> Option<Stability> {
         None
     }
 }
+fn get_safety_profile(text: &str) -> Option<SafetyProfile> {
+    let sptext = text.to_ascii_lowercase();
+    if sptext == "fast" {
+        Some(SafetyProfile::Fast)
+    } else if sptext == "balanced" {
+        Some(SafetyProfile::Balanced)
+    } else if sptext == "paranoid" {
+        Some(SafetyProfile::Paranoid)
+    } else {
+        None
+    }
+}
 fn get_direction_set(text: &str) -> Option<DirectionSet> {
     let dstext = text.to_ascii_lowercase();
     if dstext == "in" {
@@ -547,12 +574,13 @@ async fn async_get_debug_argument_at<T, G: FnOnce(&str) -> SendPinBoxFuture<Opti
 }
 
 fn get_opened_dht_record_context(
+    debug_cache: &Mutex<DebugCache>,
     args: &[String],
     context: &str,
     key: &str,
     arg: usize,
 ) -> VeilidAPIResult<(TypedKey, RoutingContext)> {
-    let dc = DEBUG_CACHE.lock();
+    let dc = debug_cache.lock();
 
     let key = match get_debug_argument_at(args, arg, context, key, get_dht_key_no_safety)
         .ok()
@@ -611,6 +639,27 @@ pub fn print_data(data: &[u8], truncate_len: Option<usize>) -> String {
 impl VeilidAPI {
     async fn debug_buckets(&self, args: String) -> VeilidAPIResult<String> {
         let args: Vec<String> = args.split_whitespace().map(|s| s.to_owned()).collect();
+        if args.first().map(|a| a.as_str()) == Some("stats") {
+            // Dump per-bucket entry state counts and ages
+            let mut out = String::new();
+            for bs in self.get_bucket_statistics()? {
+                out += &format!("{}: (near bucket skew: {})\n", bs.kind, bs.near_bucket_skew);
+                for b in bs.buckets {
+                    if b.reliable_entry_count + b.unreliable_entry_count + b.dead_entry_count == 0 {
+                        continue;
+                    }
+                    out += &format!(
+                        "  {:>3}: R:{:<3} U:{:<3} D:{:<3} avg age: {}\n",
+                        b.index,
+                        b.reliable_entry_count,
+                        b.unreliable_entry_count,
+                        b.dead_entry_count,
+                        debug_duration(b.average_entry_age.as_u64())
+                    );
+                }
+            }
+            return Ok(out);
+        }
         let mut min_state = BucketEntryState::Unreliable;
         if args.len() == 1 {
             min_state = get_debug_argument(
@@ -625,7 +674,32 @@ impl VeilidAPI {
         Ok(routing_table.debug_info_buckets(min_state))
     }
 
-    async fn debug_dialinfo(&self, _args: String) -> VeilidAPIResult<String> {
+    async fn debug_dialinfo(&self, args: String) -> VeilidAPIResult<String> {
+        let args: Vec<String> = args.split_whitespace().map(|s| s.to_owned()).collect();
+        if let Some(arg) = args.first() {
+            if arg == "detect" {
+                // Re-run public dial info detection
+                self.trigger_public_dial_info_detection()?;
+                return Ok("Public dial info detection started".to_owned());
+            }
+            if arg == "observations" {
+                // Dump the public address reports from our peers
+                let mut out = String::new();
+                for o in self.public_address_observations()? {
+                    out += &format!(
+                        "{} {} from {} ({}): {}\n",
+                        debug_ts(o.timestamp.as_u64()),
+                        o.protocol_type,
+                        o.reporting_node_id,
+                        o.reporting_address,
+                        o.observed_address
+                    );
+                }
+                return Ok(out);
+            }
+            apibail_invalid_argument!("debug_dialinfo", "arg_0", arg);
+        }
+
         // Dump routing table dialinfo
         let routing_table = self.network_manager()?.routing_table();
         Ok(routing_table.debug_info_dialinfo())
@@ -713,6 +787,108 @@ impl VeilidAPI {
         Ok(routing_table.debug_info_entry(node_ref))
     }
 
+    async fn debug_noderefs(&self, args: String) -> VeilidAPIResult<String> {
+        let args: Vec<String> = args.split_whitespace().map(|s| s.to_owned()).collect();
+        let routing_table = self.network_manager()?.routing_table();
+
+        // Turn the watchdog on or off
+        if args.first().map(|x| x.as_str()) == Some("watch") {
+            let arg = get_debug_argument_at(&args, 1, "debug_noderefs", "minutes", get_string)?;
+            let threshold = if arg == "off" {
+                None
+            } else {
+                let minutes =
+                    get_debug_argument(&arg, "debug_noderefs", "minutes", get_number::<u64>)?;
+                Some(TimestampDuration::new(
+                    minutes.saturating_mul(60_000_000u64),
+                ))
+            };
+            routing_table.set_node_ref_watchdog(threshold);
+            return Ok(match threshold {
+                Some(_) => "NodeRef watchdog on\n".to_owned(),
+                None => "NodeRef watchdog off\n".to_owned(),
+            });
+        }
+
+        // Dump entries held by NodeRefs, optionally only those held for at least some minutes
+        let mut min_pinned = TimestampDuration::new(0u64);
+        if args.len() == 1 {
+            let minutes =
+                get_debug_argument(&args[0], "debug_noderefs", "minutes", get_number::<u64>)?;
+            min_pinned = TimestampDuration::new(minutes.saturating_mul(60_000_000u64));
+        }
+        Ok(routing_table.debug_info_noderefs(min_pinned))
+    }
+
+    async fn debug_deadletters(&self, _args: String) -> VeilidAPIResult<String> {
+        // Dump the destinations we failed to relay messages to
+        let mut out = String::new();
+        for d in self.relay_dead_letters()? {
+            out += &format!(
+                "{} {} dropped, first {}, last {}: {}\n",
+                d.destination,
+                d.failure_count,
+                debug_ts(d.first_failure_ts.as_u64()),
+                debug_ts(d.last_failure_ts.as_u64()),
+                d.last_error
+            );
+        }
+        Ok(out)
+    }
+
+    async fn debug_relayclients(&self, _args: String) -> VeilidAPIResult<String> {
+        // Dump the traffic we relayed for each of our relay clients
+        let mut out = String::new();
+        for c in self.relay_client_stats()? {
+            out += &format!(
+                "{} relayed {}, dropped {}, queued {}\n",
+                c.client, c.relayed_bytes, c.dropped_bytes, c.queued_bytes
+            );
+        }
+        Ok(out)
+    }
+
+    async fn debug_toptalkers(&self, args: String) -> VeilidAPIResult<String> {
+        let args: Vec<String> = args.split_whitespace().map(|s| s.to_owned()).collect();
+        if args.first().map(|a| a.as_str()) == Some("reset") {
+            self.reset_top_talkers()?;
+            return Ok("Top talkers reset\n".to_owned());
+        }
+        let count = if args.is_empty() {
+            DEFAULT_TOP_TALKERS_COUNT
+        } else {
+            get_debug_argument_at(&args, 0, "debug_toptalkers", "count", get_number::<usize>)?
+        };
+
+        // Dump the peers using the most bandwidth and sending the most messages
+        let top_talkers = self.get_top_talkers(count)?;
+        let format_talker = |t: &TopTalker| {
+            format!(
+                "  {} down: {} up: {} rcvd: {} sent: {}\n",
+                t.node_ids
+                    .first()
+                    .map(|n| n.to_string())
+                    .unwrap_or_default(),
+                t.bytes_down,
+                t.bytes_up,
+                t.rpc_messages_rcvd,
+                t.rpc_messages_sent
+            )
+        };
+        let mut out = format!(
+            "Since {}\nBy bytes:\n",
+            debug_ts(top_talkers.since.as_u64())
+        );
+        for t in &top_talkers.by_bytes {
+            out += &format_talker(t);
+        }
+        out += "By RPC count:\n";
+        for t in &top_talkers.by_rpc_count {
+            out += &format_talker(t);
+        }
+        Ok(out)
+    }
+
     async fn debug_relay(&self, args: String) -> VeilidAPIResult<String> {
         let args: Vec<String> = args.split_whitespace().map(|s| s.to_owned()).collect();
         let routing_table = self.network_manager()?.routing_table();
@@ -833,6 +1009,12 @@ impl VeilidAPI {
             netman.restart_network();
 
             Ok("Network restarted".to_owned())
+        } else if arg == "tls" {
+            if self.reload_tls_certificates()? {
+                Ok("TLS certificate reloaded".to_owned())
+            } else {
+                Ok("No TLS listeners".to_owned())
+            }
         } else {
             apibail_invalid_argument!("debug_restart", "arg_1", arg);
         }
@@ -867,7 +1049,8 @@ impl VeilidAPI {
             } else if args[0] == "routes" {
                 // Purge route spec store
                 {
-                    let mut dc = DEBUG_CACHE.lock();
+                    let debug_cache = self.debug_cache()?;
+                    let mut dc = debug_cache.lock();
                     dc.imported_routes.clear();
                 }
                 let rss = self.network_manager()?.routing_table().route_spec_store();
@@ -948,7 +1131,7 @@ impl VeilidAPI {
             0,
             "debug_resolve",
             "destination",
-            get_destination(routing_table.clone()),
+            get_destination(routing_table.clone(), self.debug_cache()?),
         )
         .await?;
 
@@ -990,7 +1173,7 @@ impl VeilidAPI {
             0,
             "debug_ping",
             "destination",
-            get_destination(routing_table),
+            get_destination(routing_table, self.debug_cache()?),
         )
         .await?;
 
@@ -1021,7 +1204,7 @@ impl VeilidAPI {
             arg,
             "debug_app_message",
             "destination",
-            get_destination(routing_table),
+            get_destination(routing_table, self.debug_cache()?),
         )
         .await?;
 
@@ -1055,7 +1238,7 @@ impl VeilidAPI {
             arg,
             "debug_app_call",
             "destination",
-            get_destination(routing_table),
+            get_destination(routing_table, self.debug_cache()?),
         )
         .await?;
 
@@ -1113,7 +1296,7 @@ impl VeilidAPI {
     }
 
     async fn debug_route_allocate(&self, args: Vec<String>) -> VeilidAPIResult<String> {
-        // [ord|*ord] [rel] [<count>] [in|out] [avoid_node_id]
+        // [ord|*ord] [rel] [<count>] [in|out] [tput]
 
         let netman = self.network_manager()?;
         let routing_table = netman.routing_table();
@@ -1129,9 +1312,12 @@ impl VeilidAPI {
         let mut stability = Stability::default();
         let mut hop_count = default_route_hop_count;
         let mut directions = DirectionSet::all();
+        let mut prefer_throughput = false;
 
         while ai < args.len() {
-            if let Ok(seq) =
+            if args[ai] == "tput" {
+                prefer_throughput = true;
+            } else if let Ok(seq) =
                 get_debug_argument_at(&args, ai, "debug_route", "sequencing", get_sequencing)
             {
                 sequencing = seq;
@@ -1161,7 +1347,9 @@ impl VeilidAPI {
             hop_count,
             directions,
             &[],
+            prefer_throughput,
             false,
+            None,
         ) {
             Ok(v) => v.to_string(),
             Err(e) => {
@@ -1189,7 +1377,8 @@ impl VeilidAPI {
         let out = match rss.release_route(route_id) {
             true => {
                 // release imported
-                let mut dc = DEBUG_CACHE.lock();
+                let debug_cache = self.debug_cache()?;
+                let mut dc = debug_cache.lock();
                 for (n, ir) in dc.imported_routes.iter().enumerate() {
                     if *ir == route_id {
                         dc.imported_routes.remove(n);
@@ -1331,7 +1520,9 @@ impl VeilidAPI {
             .import_remote_private_route_blob(blob_dec)
             .map_err(VeilidAPIError::generic)?;
 
-        let mut dc = DEBUG_CACHE.lock();
+        let debug_cache = self.debug_cache()?;
+
+        let mut dc = debug_cache.lock();
         let n = dc.imported_routes.len();
         let out = format!("Private route #{} imported: {}", n, route_id);
         dc.imported_routes.push(route_id);
@@ -1483,7 +1674,8 @@ impl VeilidAPI {
         };
 
         // Save routing context for record
-        let mut dc = DEBUG_CACHE.lock();
+        let debug_cache = self.debug_cache()?;
+        let mut dc = debug_cache.lock();
         dc.opened_record_contexts.insert(*record.key(), rc);
 
         Ok(format!(
@@ -1527,14 +1719,21 @@ impl VeilidAPI {
         };
 
         // Save routing context for record
-        let mut dc = DEBUG_CACHE.lock();
+        let debug_cache = self.debug_cache()?;
+        let mut dc = debug_cache.lock();
         dc.opened_record_contexts.insert(*record.key(), rc);
 
         Ok(format!("Opened: {} : {:?}", key, record))
     }
 
     async fn debug_record_close(&self, args: Vec<String>) -> VeilidAPIResult<String> {
-        let (key, rc) = get_opened_dht_record_context(&args, "debug_record_close", "key", 1)?;
+        let (key, rc) = get_opened_dht_record_context(
+            &self.debug_cache()?,
+            &args,
+            "debug_record_close",
+            "key",
+            1,
+        )?;
 
         // Do a record close
         if let Err(e) = rc.close_dht_record(key).await {
@@ -1550,7 +1749,13 @@ impl VeilidAPI {
         } else {
             0
         };
-        let (key, rc) = get_opened_dht_record_context(&args, "debug_record_set", "key", 1)?;
+        let (key, rc) = get_opened_dht_record_context(
+            &self.debug_cache()?,
+            &args,
+            "debug_record_set",
+            "key",
+            1,
+        )?;
         let subkey = get_debug_argument_at(
             &args,
             1 + opt_arg_add,
@@ -1594,7 +1799,13 @@ impl VeilidAPI {
             0
         };
 
-        let (key, rc) = get_opened_dht_record_context(&args, "debug_record_get", "key", 1)?;
+        let (key, rc) = get_opened_dht_record_context(
+            &self.debug_cache()?,
+            &args,
+            "debug_record_get",
+            "key",
+            1,
+        )?;
         let subkey = get_debug_argument_at(
             &args,
             1 + opt_arg_add,
@@ -1701,7 +1912,13 @@ impl VeilidAPI {
             0
         };
 
-        let (key, rc) = get_opened_dht_record_context(&args, "debug_record_watch", "key", 1)?;
+        let (key, rc) = get_opened_dht_record_context(
+            &self.debug_cache()?,
+            &args,
+            "debug_record_watch",
+            "key",
+            1,
+        )?;
 
         let mut rest_defaults = false;
         let subkeys = get_debug_argument_at(
@@ -1774,7 +1991,13 @@ impl VeilidAPI {
             0
         };
 
-        let (key, rc) = get_opened_dht_record_context(&args, "debug_record_watch", "key", 1)?;
+        let (key, rc) = get_opened_dht_record_context(
+            &self.debug_cache()?,
+            &args,
+            "debug_record_watch",
+            "key",
+            1,
+        )?;
         let subkeys = get_debug_argument_at(
             &args,
             1 + opt_arg_add,
@@ -1807,7 +2030,13 @@ impl VeilidAPI {
             0
         };
 
-        let (key, rc) = get_opened_dht_record_context(&args, "debug_record_watch", "key", 1)?;
+        let (key, rc) = get_opened_dht_record_context(
+            &self.debug_cache()?,
+            &args,
+            "debug_record_watch",
+            "key",
+            1,
+        )?;
 
         let mut rest_defaults = false;
 
@@ -1906,6 +2135,27 @@ impl VeilidAPI {
         Ok("Address Filter punishments cleared\n".to_owned())
     }
 
+    async fn debug_punish_export(&self, _args: Vec<String>) -> VeilidAPIResult<String> {
+        //
+        let network_manager = self.network_manager()?;
+        let address_filter = network_manager.address_filter();
+
+        Ok(serialize_json(address_filter.export_punishments()))
+    }
+
+    async fn debug_punish_import(&self, args: Vec<String>) -> VeilidAPIResult<String> {
+        let network_manager = self.network_manager()?;
+        let address_filter = network_manager.address_filter();
+
+        let json = args[1..].join(" ");
+        if json.is_empty() {
+            apibail_missing_argument!("debug_punish_import", "punishments");
+        }
+        let count = address_filter.import_punishments(deserialize_json(&json)?);
+
+        Ok(format!("Imported {} address filter punishments\n", count))
+    }
+
     async fn debug_punish(&self, args: String) -> VeilidAPIResult<String> {
         let args: Vec<String> =
             shell_words::split(&args).map_err(|e| VeilidAPIError::parse_error(e, args))?;
@@ -1914,6 +2164,59 @@ impl VeilidAPI {
 
         if command == "list" {
             self.debug_punish_list(args).await
+        } else if command == "clear" {
+            self.debug_punish_clear(args).await
+        } else if command == "export" {
+            self.debug_punish_export(args).await
+        } else if command == "import" {
+            self.debug_punish_import(args).await
+        } else {
+            Ok(">>> Unknown command\n".to_owned())
+        }
+    }
+
+    async fn debug_peertable_export(&self, args: Vec<String>) -> VeilidAPIResult<String> {
+        let min_state = match args.get(1) {
+            Some(arg) => match get_bucket_entry_state(arg) {
+                Some(ms) => ms,
+                None => apibail_invalid_argument!("debug_peertable_export", "min_state", arg),
+            },
+            None => BucketEntryState::Unreliable,
+        };
+
+        let routing_table = self.network_manager()?.routing_table();
+        Ok(serialize_json(routing_table.export_peer_table(min_state)))
+    }
+
+    async fn debug_peertable_bootstrap(&self, _args: Vec<String>) -> VeilidAPIResult<String> {
+        let routing_table = self.network_manager()?.routing_table();
+        Ok(serialize_json(routing_table.export_bootstrap_peers()))
+    }
+
+    async fn debug_peertable_import(&self, args: Vec<String>) -> VeilidAPIResult<String> {
+        let routing_table = self.network_manager()?.routing_table();
+
+        let json = args[1..].join(" ");
+        if json.is_empty() {
+            apibail_missing_argument!("debug_peertable_import", "peers");
+        }
+        let count = routing_table.import_bootstrap_peers(deserialize_json(&json)?);
+
+        Ok(format!("Imported {} bootstrap peers\n", count))
+    }
+
+    async fn debug_peertable(&self, args: String) -> VeilidAPIResult<String> {
+        let args: Vec<String> =
+            shell_words::split(&args).map_err(|e| VeilidAPIError::parse_error(e, args))?;
+
+        let command = get_debug_argument_at(&args, 0, "debug_peertable", "command", get_string)?;
+
+        if command == "export" {
+            self.debug_peertable_export(args).await
+        } else if command == "bootstrap" {
+            self.debug_peertable_bootstrap(args).await
+        } else if command == "import" {
+            self.debug_peertable_import(args).await
         } else {
             Ok(">>> Unknown command\n".to_owned())
         }
@@ -1921,11 +2224,13 @@ impl VeilidAPI {
 
     /// Get the help text for 'internal debug' commands
     pub async fn debug_help(&self, _args: String) -> VeilidAPIResult<String> {
-        Ok(r#"buckets [dead|reliable]
-dialinfo
+        Ok(r#"buckets [dead|reliable|stats]
+dialinfo [detect|observations]
 peerinfo [routingdomain]
 entries [dead|reliable] [<capabilities>]
 entry <node>
+noderefs [<minutes>]
+         watch <minutes>|off
 nodeinfo
 config [insecure] [configkey [new value]]
 txtrecord
@@ -1933,7 +2238,7 @@ keypair
 purge <buckets|connections|routes>
 attach
 detach
-restart network
+restart <network|tls>
 contact <node>[<modifiers>]
 resolve <destination>
 ping <destination>
@@ -1941,9 +2246,17 @@ appmessage <destination> <data>
 appcall <destination> <data>
 appreply [#id] <data>
 relay <relay> [public|local]
+deadletters
+relayclients
+toptalkers [<count>|reset]
 punish list
        clear
-route allocate [ord|*ord] [rel] [<count>] [in|out]
+       export
+       import <json>
+peertable export [dead|reliable]
+          bootstrap
+          import <json>
+route allocate [ord|*ord] [rel] [<count>] [in|out] [tput]
       release <route>
       publish <route> [full]
       unpublish <route>
@@ -1974,7 +2287,7 @@ record list <local|remote>
     * private: #<id>[+<safety>]
 <safety> is:
     * unsafe: -[ord|*ord]
-    * safe: [route][,ord|*ord][,rel][,<count>]
+    * safe: [route][,ord|*ord][,rel][,<count>][,^<node>][,fast|balanced|paranoid]
 <modifiers> is: [/<protocoltype>][/<addresstype>][/<routingdomain>]
 <protocoltype> is: udp|tcp|ws|wss
 <addresstype> is: ipv4|ipv6
@@ -1999,6 +2312,11 @@ record list <local|remote>
 
     /// Execute an 'internal debug command'
     pub async fn debug(&self, args: String) -> VeilidAPIResult<String> {
+        let instance_span = self.instance_span();
+        self.debug_command(args).instrument(instance_span).await
+    }
+
+    async fn debug_command(&self, args: String) -> VeilidAPIResult<String> {
         let res = {
             let args = args.trim_start();
             if args.is_empty() {
@@ -2024,8 +2342,16 @@ record list <local|remote>
                 self.debug_entries(rest).await
             } else if arg == "entry" {
                 self.debug_entry(rest).await
+            } else if arg == "noderefs" {
+                self.debug_noderefs(rest).await
             } else if arg == "relay" {
                 self.debug_relay(rest).await
+            } else if arg == "deadletters" {
+                self.debug_deadletters(rest).await
+            } else if arg == "relayclients" {
+                self.debug_relayclients(rest).await
+            } else if arg == "toptalkers" {
+                self.debug_toptalkers(rest).await
             } else if arg == "ping" {
                 self.debug_ping(rest).await
             } else if arg == "appmessage" {
@@ -2056,6 +2382,8 @@ record list <local|remote>
                 self.debug_record(rest).await
             } else if arg == "punish" {
                 self.debug_punish(rest).await
+            } else if arg == "peertable" {
+                self.debug_peertable(rest).await
             } else {
                 Err(VeilidAPIError::generic("Unknown server debug command"))
             }
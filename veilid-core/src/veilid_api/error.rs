@@ -96,6 +96,14 @@ macro_rules! apibail_route_not_found {
     };
 }
 
+#[allow(unused_macros)]
+#[macro_export]
+macro_rules! apibail_route_compilation {
+    ($x:expr, $y:expr, $z:expr) => {
+        return Err(VeilidAPIError::route_compilation($x, $y, $z))
+    };
+}
+
 #[allow(unused_macros)]
 #[macro_export]
 macro_rules! apibail_already_initialized {
@@ -104,6 +112,43 @@ macro_rules! apibail_already_initialized {
     };
 }
 
+/// The constraint that could not be met when allocating a route
+#[derive(
+    Copy, Clone, Debug, PartialOrd, PartialEq, Eq, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum RouteConstraint {
+    /// Our own network class is not known yet, so routes can not be built from our node
+    NetworkClass,
+    /// Fewer nodes are usable as hops than the route needs
+    NodeCount,
+    /// The requested first hop can not be used for a route
+    FirstHop,
+    /// Every route that can be built from the usable nodes is already allocated
+    Uniqueness,
+    /// Every route would contain the same node twice, or a node and its relay
+    RelayConflict,
+    /// The hops could not all be put on different networks from each other and from us
+    NetworkDiversity,
+    /// The hops could not all reach each other
+    Reachability,
+}
+
+/// Why a route could not be allocated, and how many nodes there were to build it from
+#[derive(
+    ThisError, Clone, Debug, PartialOrd, PartialEq, Eq, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+#[error("{constraint:?} constraint failed for {hop_count} hop route with {candidates} candidates")]
+pub struct RouteCompilationError {
+    /// The constraint that could not be met
+    pub constraint: RouteConstraint,
+    /// The number of hops the route needed
+    pub hop_count: u32,
+    /// The number of nodes that were usable as hops
+    pub candidates: u32,
+}
+
 #[derive(
     ThisError, Clone, Debug, PartialOrd, PartialEq, Eq, Ord, Serialize, Deserialize, JsonSchema,
 )]
@@ -145,6 +190,8 @@ pub enum VeilidAPIError {
     MissingArgument { context: String, argument: String },
     #[error("Generic: {message}")]
     Generic { message: String },
+    #[error("Route compilation: {error}")]
+    RouteCompilation { error: RouteCompilationError },
 }
 from_impl_to_jsvalue!(VeilidAPIError);
 
@@ -218,6 +265,20 @@ impl VeilidAPIError {
         }
     }
 
+    pub fn route_compilation(
+        constraint: RouteConstraint,
+        hop_count: usize,
+        candidates: usize,
+    ) -> Self {
+        Self::RouteCompilation {
+            error: RouteCompilationError {
+                constraint,
+                hop_count: hop_count as u32,
+                candidates: candidates as u32,
+            },
+        }
+    }
+
     pub(crate) fn from_network_result<T>(nr: NetworkResult<T>) -> Result<T, Self> {
         match nr {
             NetworkResult::Timeout => Err(VeilidAPIError::timeout()),
@@ -236,6 +297,12 @@ impl VeilidAPIError {
 
 pub type VeilidAPIResult<T> = Result<T, VeilidAPIError>;
 
+impl From<RouteCompilationError> for VeilidAPIError {
+    fn from(error: RouteCompilationError) -> Self {
+        VeilidAPIError::RouteCompilation { error }
+    }
+}
+
 impl From<std::io::Error> for VeilidAPIError {
     fn from(e: std::io::Error) -> Self {
         match e.kind() {
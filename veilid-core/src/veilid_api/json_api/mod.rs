@@ -46,8 +46,18 @@ pub enum RequestOp {
         args: Vec<String>,
     },
     GetState,
+    GetStorageUsage,
+    GetStorageStatus,
+    GetNetworkTime,
     Attach,
     Detach,
+    SetNetworkCost {
+        network_cost: NetworkCost,
+    },
+    SetLowPower {
+        low_power: bool,
+    },
+    TriggerPublicDialInfoDetection,
     NewPrivateRoute,
     NewCustomPrivateRoute {
         #[schemars(with = "Vec<String>")]
@@ -121,6 +131,7 @@ pub enum RequestOp {
     VeilidVersionString,
     VeilidVersion,
     DefaultVeilidConfig,
+    VeilidConfigSchema,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -143,6 +154,18 @@ pub enum ResponseOp {
         #[serde(flatten)]
         result: ApiResult<Box<VeilidState>>,
     },
+    GetStorageUsage {
+        #[serde(flatten)]
+        result: ApiResult<Box<DHTStorageUsage>>,
+    },
+    GetStorageStatus {
+        #[serde(flatten)]
+        result: ApiResult<StorageManagerStatus>,
+    },
+    GetNetworkTime {
+        #[serde(flatten)]
+        result: ApiResult<NetworkTime>,
+    },
     Attach {
         #[serde(flatten)]
         result: ApiResult<()>,
@@ -151,6 +174,19 @@ pub enum ResponseOp {
         #[serde(flatten)]
         result: ApiResult<()>,
     },
+    SetNetworkCost {
+        #[serde(flatten)]
+        result: ApiResult<()>,
+    },
+    SetLowPower {
+        #[serde(flatten)]
+        result: ApiResult<()>,
+    },
+    TriggerPublicDialInfoDetection {
+        #[serde(flatten)]
+        result: ApiResult<()>,
+    },
+
     NewPrivateRoute {
         #[serde(flatten)]
         result: ApiResult<NewPrivateRouteResult>,
@@ -234,6 +270,9 @@ pub enum ResponseOp {
     DefaultVeilidConfig {
         value: String,
     },
+    VeilidConfigSchema {
+        value: String,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -255,15 +255,36 @@ impl JsonRequestProcessor {
                     ),
                 }
             }
+            RoutingContextRequestOp::WithSafetyProfile { profile } => {
+                RoutingContextResponseOp::WithSafetyProfile {
+                    result: to_json_api_result(
+                        routing_context
+                            .clone()
+                            .with_safety_profile(profile)
+                            .map(|new_rc| self.add_routing_context(new_rc)),
+                    ),
+                }
+            }
             RoutingContextRequestOp::WithSequencing { sequencing } => {
                 RoutingContextResponseOp::WithSequencing {
                     value: self
                         .add_routing_context(routing_context.clone().with_sequencing(sequencing)),
                 }
             }
+            RoutingContextRequestOp::WithTimeout { timeout_ms } => {
+                RoutingContextResponseOp::WithTimeout {
+                    value: self
+                        .add_routing_context(routing_context.clone().with_timeout(timeout_ms)),
+                }
+            }
             RoutingContextRequestOp::Safety => RoutingContextResponseOp::Safety {
                 value: routing_context.safety(),
             },
+            RoutingContextRequestOp::TestPrivateRoute { route_id } => {
+                RoutingContextResponseOp::TestPrivateRoute {
+                    result: to_json_api_result(routing_context.test_private_route(route_id).await),
+                }
+            }
             RoutingContextRequestOp::AppCall { target, message } => {
                 RoutingContextResponseOp::AppCall {
                     result: to_json_api_result_with_vec_u8(
@@ -282,6 +303,24 @@ impl JsonRequestProcessor {
                     ),
                 }
             }
+            RoutingContextRequestOp::Prewarm { target } => RoutingContextResponseOp::Prewarm {
+                result: to_json_api_result(
+                    self.parse_target(target)
+                        .then(|tr| async { routing_context.prewarm(tr?).await })
+                        .await,
+                ),
+            },
+            RoutingContextRequestOp::AppMessageClosest {
+                key,
+                count,
+                message,
+            } => RoutingContextResponseOp::AppMessageClosest {
+                result: to_json_api_result(
+                    routing_context
+                        .app_message_closest(key, count, message)
+                        .await,
+                ),
+            },
             RoutingContextRequestOp::CreateDhtRecord { schema, kind } => {
                 RoutingContextResponseOp::CreateDhtRecord {
                     result: to_json_api_result(
@@ -292,6 +331,27 @@ impl JsonRequestProcessor {
                     ),
                 }
             }
+            RoutingContextRequestOp::CreateExpiringDhtRecord {
+                schema,
+                expiration,
+                kind,
+            } => RoutingContextResponseOp::CreateExpiringDhtRecord {
+                result: to_json_api_result(
+                    routing_context
+                        .create_expiring_dht_record(schema, expiration, kind)
+                        .await
+                        .map(Box::new),
+                ),
+            },
+            RoutingContextRequestOp::ExtendDhtRecordExpiration { key, expiration } => {
+                RoutingContextResponseOp::ExtendDhtRecordExpiration {
+                    result: to_json_api_result(
+                        routing_context
+                            .extend_dht_record_expiration(key, expiration)
+                            .await,
+                    ),
+                }
+            }
             RoutingContextRequestOp::OpenDhtRecord { key, writer } => {
                 RoutingContextResponseOp::OpenDhtRecord {
                     result: to_json_api_result(
@@ -366,6 +426,78 @@ impl JsonRequestProcessor {
                         .map(Box::new),
                 ),
             },
+            RoutingContextRequestOp::SyncDhtRecord { key } => {
+                RoutingContextResponseOp::SyncDhtRecord {
+                    result: to_json_api_result(routing_context.sync_dht_record(key).await),
+                }
+            }
+            RoutingContextRequestOp::PublishPrivateRoute {
+                key,
+                subkey,
+                rotation,
+            } => RoutingContextResponseOp::PublishPrivateRoute {
+                result: to_json_api_result_with_string(
+                    routing_context
+                        .publish_private_route(key, subkey, rotation)
+                        .await,
+                ),
+            },
+            RoutingContextRequestOp::ResolvePrivateRoute {
+                key,
+                subkey,
+                force_refresh,
+            } => RoutingContextResponseOp::ResolvePrivateRoute {
+                result: to_json_api_result_with_string(
+                    routing_context
+                        .resolve_private_route(key, subkey, force_refresh)
+                        .await,
+                ),
+            },
+            RoutingContextRequestOp::CreateInvite => RoutingContextResponseOp::CreateInvite {
+                result: to_json_api_result(
+                    routing_context
+                        .create_invite()
+                        .await
+                        .map(|(invite, blob)| Box::new(CreateInviteResult { invite, blob })),
+                ),
+            },
+            RoutingContextRequestOp::AcceptInvite { invite_blob } => {
+                RoutingContextResponseOp::AcceptInvite {
+                    result: to_json_api_result(
+                        routing_context
+                            .accept_invite(invite_blob)
+                            .await
+                            .map(Box::new),
+                    ),
+                }
+            }
+            RoutingContextRequestOp::AnswerInvite { invite, app_call } => {
+                RoutingContextResponseOp::AnswerInvite {
+                    result: to_json_api_result(
+                        routing_context
+                            .answer_invite(&invite, &app_call)
+                            .await
+                            .map(|opt_session| opt_session.map(Box::new)),
+                    ),
+                }
+            }
+            RoutingContextRequestOp::SendSessionMessage { session, message } => {
+                RoutingContextResponseOp::SendSessionMessage {
+                    result: to_json_api_result(session.send(&routing_context, &message).await),
+                }
+            }
+            RoutingContextRequestOp::OpenSessionMessage { session, message } => {
+                RoutingContextResponseOp::OpenSessionMessage {
+                    result: to_json_api_result_with_vec_u8(
+                        session.open(&routing_context.api(), &message),
+                    ),
+                }
+            }
+            RoutingContextRequestOp::CloseSession { session } => {
+                RoutingContextResponseOp::CloseSession {
+                    result: to_json_api_result(session.close(&routing_context.api())),
+                }
+            }
         };
         RoutingContextResponse {
             rc_id: rcr.rc_id,
@@ -590,12 +722,32 @@ impl JsonRequestProcessor {
             RequestOp::GetState => ResponseOp::GetState {
                 result: to_json_api_result(self.api.get_state().await.map(Box::new)),
             },
+            RequestOp::GetStorageUsage => ResponseOp::GetStorageUsage {
+                result: to_json_api_result(self.api.get_storage_usage().await.map(Box::new)),
+            },
+            RequestOp::GetStorageStatus => ResponseOp::GetStorageStatus {
+                result: to_json_api_result(self.api.get_storage_status().await),
+            },
+            RequestOp::GetNetworkTime => ResponseOp::GetNetworkTime {
+                result: to_json_api_result(self.api.get_network_time()),
+            },
             RequestOp::Attach => ResponseOp::Attach {
                 result: to_json_api_result(self.api.attach().await),
             },
             RequestOp::Detach => ResponseOp::Detach {
                 result: to_json_api_result(self.api.detach().await),
             },
+            RequestOp::SetNetworkCost { network_cost } => ResponseOp::SetNetworkCost {
+                result: to_json_api_result(self.api.set_network_cost(network_cost).await),
+            },
+            RequestOp::SetLowPower { low_power } => ResponseOp::SetLowPower {
+                result: to_json_api_result(self.api.set_low_power(low_power)),
+            },
+            RequestOp::TriggerPublicDialInfoDetection => {
+                ResponseOp::TriggerPublicDialInfoDetection {
+                    result: to_json_api_result(self.api.trigger_public_dial_info_detection()),
+                }
+            }
             RequestOp::NewPrivateRoute => ResponseOp::NewPrivateRoute {
                 result: to_json_api_result(self.api.new_private_route().await.map(|r| {
                     NewPrivateRouteResult {
@@ -817,6 +969,9 @@ impl JsonRequestProcessor {
             RequestOp::DefaultVeilidConfig => ResponseOp::DefaultVeilidConfig {
                 value: default_veilid_config(),
             },
+            RequestOp::VeilidConfigSchema => ResponseOp::VeilidConfigSchema {
+                value: serialize_json(veilid_config_schema()),
+            },
         };
 
         Response { id, op }
@@ -22,10 +22,20 @@ pub enum RoutingContextRequestOp {
     WithSafety {
         safety_selection: SafetySelection,
     },
+    WithSafetyProfile {
+        profile: SafetyProfile,
+    },
     WithSequencing {
         sequencing: Sequencing,
     },
+    WithTimeout {
+        timeout_ms: Option<u32>,
+    },
     Safety,
+    TestPrivateRoute {
+        #[schemars(with = "String")]
+        route_id: RouteId,
+    },
     AppCall {
         target: String,
         #[serde(with = "as_human_base64")]
@@ -38,11 +48,33 @@ pub enum RoutingContextRequestOp {
         #[schemars(with = "String")]
         message: Vec<u8>,
     },
+    Prewarm {
+        target: String,
+    },
+    AppMessageClosest {
+        #[schemars(with = "String")]
+        key: TypedKey,
+        count: u32,
+        #[serde(with = "as_human_base64")]
+        #[schemars(with = "String")]
+        message: Vec<u8>,
+    },
     CreateDhtRecord {
         schema: DHTSchema,
         #[schemars(with = "Option<String>")]
         kind: Option<CryptoKind>,
     },
+    CreateExpiringDhtRecord {
+        schema: DHTSchema,
+        expiration: Timestamp,
+        #[schemars(with = "Option<String>")]
+        kind: Option<CryptoKind>,
+    },
+    ExtendDhtRecordExpiration {
+        #[schemars(with = "String")]
+        key: TypedKey,
+        expiration: Option<Timestamp>,
+    },
     OpenDhtRecord {
         #[schemars(with = "String")]
         key: TypedKey,
@@ -91,6 +123,55 @@ pub enum RoutingContextRequestOp {
         subkeys: ValueSubkeyRangeSet,
         scope: DHTReportScope,
     },
+    SyncDhtRecord {
+        #[schemars(with = "String")]
+        key: TypedKey,
+    },
+    PublishPrivateRoute {
+        #[schemars(with = "String")]
+        key: TypedKey,
+        subkey: ValueSubkey,
+        rotation: Option<PrivateRouteRotation>,
+    },
+    ResolvePrivateRoute {
+        #[schemars(with = "String")]
+        key: TypedKey,
+        subkey: ValueSubkey,
+        force_refresh: bool,
+    },
+    CreateInvite,
+    AcceptInvite {
+        #[serde(with = "as_human_base64")]
+        #[schemars(with = "String")]
+        invite_blob: Vec<u8>,
+    },
+    AnswerInvite {
+        invite: RendezvousInvite,
+        app_call: VeilidAppCall,
+    },
+    SendSessionMessage {
+        session: RendezvousSession,
+        #[serde(with = "as_human_base64")]
+        #[schemars(with = "String")]
+        message: Vec<u8>,
+    },
+    OpenSessionMessage {
+        session: RendezvousSession,
+        #[serde(with = "as_human_base64")]
+        #[schemars(with = "String")]
+        message: Vec<u8>,
+    },
+    CloseSession {
+        session: RendezvousSession,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateInviteResult {
+    pub invite: RendezvousInvite,
+    #[serde(with = "as_human_base64")]
+    #[schemars(with = "String")]
+    pub blob: Vec<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -106,12 +187,23 @@ pub enum RoutingContextResponseOp {
         #[serde(flatten)]
         result: ApiResult<u32>,
     },
+    WithSafetyProfile {
+        #[serde(flatten)]
+        result: ApiResult<u32>,
+    },
     WithSequencing {
         value: u32,
     },
+    WithTimeout {
+        value: u32,
+    },
     Safety {
         value: SafetySelection,
     },
+    TestPrivateRoute {
+        #[serde(flatten)]
+        result: ApiResult<PrivateRouteTestResult>,
+    },
     AppCall {
         #[serde(flatten)]
         #[schemars(with = "ApiResult<String>")]
@@ -121,10 +213,27 @@ pub enum RoutingContextResponseOp {
         #[serde(flatten)]
         result: ApiResult<()>,
     },
+    Prewarm {
+        #[serde(flatten)]
+        result: ApiResult<ContactMethod>,
+    },
+    AppMessageClosest {
+        #[serde(flatten)]
+        #[schemars(with = "ApiResult<Vec<String>>")]
+        result: ApiResult<Vec<TypedKey>>,
+    },
     CreateDhtRecord {
         #[serde(flatten)]
         result: ApiResult<Box<DHTRecordDescriptor>>,
     },
+    CreateExpiringDhtRecord {
+        #[serde(flatten)]
+        result: ApiResult<Box<DHTRecordDescriptor>>,
+    },
+    ExtendDhtRecordExpiration {
+        #[serde(flatten)]
+        result: ApiResult<()>,
+    },
     OpenDhtRecord {
         #[serde(flatten)]
         result: ApiResult<Box<DHTRecordDescriptor>>,
@@ -157,4 +266,43 @@ pub enum RoutingContextResponseOp {
         #[serde(flatten)]
         result: ApiResult<Box<DHTRecordReport>>,
     },
+    SyncDhtRecord {
+        #[serde(flatten)]
+        result: ApiResult<ValueSubkeyRangeSet>,
+    },
+    PublishPrivateRoute {
+        #[serde(flatten)]
+        #[schemars(with = "ApiResult<String>")]
+        result: ApiResultWithString<RouteId>,
+    },
+    ResolvePrivateRoute {
+        #[serde(flatten)]
+        #[schemars(with = "ApiResult<Option<String>>")]
+        result: ApiResultWithString<Option<RouteId>>,
+    },
+    CreateInvite {
+        #[serde(flatten)]
+        result: ApiResult<Box<CreateInviteResult>>,
+    },
+    AcceptInvite {
+        #[serde(flatten)]
+        result: ApiResult<Box<RendezvousSession>>,
+    },
+    AnswerInvite {
+        #[serde(flatten)]
+        result: ApiResult<Option<Box<RendezvousSession>>>,
+    },
+    SendSessionMessage {
+        #[serde(flatten)]
+        result: ApiResult<()>,
+    },
+    OpenSessionMessage {
+        #[serde(flatten)]
+        #[schemars(with = "ApiResult<String>")]
+        result: ApiResultWithVecU8,
+    },
+    CloseSession {
+        #[serde(flatten)]
+        result: ApiResult<()>,
+    },
 }
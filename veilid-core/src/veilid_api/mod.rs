@@ -3,6 +3,7 @@
 mod api;
 mod debug;
 mod error;
+mod rendezvous;
 mod routing_context;
 mod serialize_helpers;
 mod types;
@@ -14,6 +15,7 @@ pub mod tests;
 pub use api::*;
 pub use debug::*;
 pub use error::*;
+pub use rendezvous::*;
 pub use routing_context::*;
 pub use serialize_helpers::*;
 pub use types::*;
@@ -23,7 +25,10 @@ pub use core::str::FromStr;
 pub use crypto::*;
 #[cfg(feature = "unstable-blockstore")]
 pub use intf::BlockStore;
+pub use intf::HardwareKeystore;
 pub use intf::ProtectedStore;
+pub use network_manager::ProtocolType;
+pub use routing_table::RoutingDomain;
 pub use table_store::{TableDB, TableDBTransaction, TableStore};
 
 use crate::*;
@@ -0,0 +1,328 @@
+use super::*;
+
+/// Magic prefix for an invite blob
+const INVITE_MAGIC: &[u8; 4] = b"VINV";
+/// Magic prefix for the app call sent when accepting an invite
+const INVITE_REQUEST_MAGIC: &[u8; 4] = b"VRDQ";
+/// Magic prefix for the app call reply confirming an accepted invite
+const INVITE_REPLY_MAGIC: &[u8; 4] = b"VRDA";
+/// Domain separator for the rendezvous session secret
+const RENDEZVOUS_DOMAIN: &[u8] = b"VEILID_RENDEZVOUS";
+
+/// An outstanding invite created with [RoutingContext::create_invite]
+///
+/// Keep this around until the invite has been answered with [RoutingContext::answer_invite].
+/// The private route it allocated is released once the resulting session is closed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(from_wasm_abi, into_wasm_abi)
+)]
+pub struct RendezvousInvite {
+    /// The cryptosystem used for the invite keys
+    #[schemars(with = "String")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    crypto_kind: CryptoKind,
+    /// The ephemeral keypair whose public key is embedded in the invite blob
+    #[schemars(with = "String")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    keypair: KeyPair,
+    /// The private route the inviter receives the handshake on
+    #[schemars(with = "String")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    route_id: RouteId,
+}
+from_impl_to_jsvalue!(RendezvousInvite);
+
+impl RendezvousInvite {
+    pub fn crypto_kind(&self) -> CryptoKind {
+        self.crypto_kind
+    }
+    pub fn route_id(&self) -> RouteId {
+        self.route_id
+    }
+}
+
+/// A verified bidirectional messaging session established through an invite
+///
+/// Both sides hold a shared secret derived from the ephemeral invite keys, and
+/// messages are sent as encrypted app messages to the other side's private route.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(from_wasm_abi, into_wasm_abi)
+)]
+pub struct RendezvousSession {
+    /// The cryptosystem used for session encryption
+    #[schemars(with = "String")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    crypto_kind: CryptoKind,
+    /// The secret shared by both ends of the session
+    #[schemars(with = "String")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    shared_secret: SharedSecret,
+    /// Our private route that the remote end sends to
+    #[schemars(with = "String")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    local_route_id: RouteId,
+    /// The remote private route that we send to
+    #[schemars(with = "String")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    remote_route_id: RouteId,
+}
+from_impl_to_jsvalue!(RendezvousSession);
+
+impl RendezvousSession {
+    pub fn crypto_kind(&self) -> CryptoKind {
+        self.crypto_kind
+    }
+    /// Our private route, incoming session messages arrive with this route id
+    pub fn local_route_id(&self) -> RouteId {
+        self.local_route_id
+    }
+    /// The remote private route that session messages are sent to
+    pub fn remote_route_id(&self) -> RouteId {
+        self.remote_route_id
+    }
+
+    /// Encrypt and send a message to the other end of the session
+    pub async fn send(
+        &self,
+        routing_context: &RoutingContext,
+        message: &[u8],
+    ) -> VeilidAPIResult<()> {
+        let vcrypto = get_rendezvous_crypto(&routing_context.api(), self.crypto_kind)?;
+        let nonce = vcrypto.random_nonce();
+        let body = vcrypto.encrypt_aead(message, &nonce, &self.shared_secret, None)?;
+        routing_context
+            .app_message(
+                Target::PrivateRoute(self.remote_route_id),
+                [nonce.bytes.as_slice(), &body].concat(),
+            )
+            .await
+    }
+
+    /// Decrypt a message received from the other end of the session
+    ///
+    /// Only messages arriving over [RendezvousSession::local_route_id] belong to this session.
+    pub fn open(&self, api: &VeilidAPI, message: &[u8]) -> VeilidAPIResult<Vec<u8>> {
+        let vcrypto = get_rendezvous_crypto(api, self.crypto_kind)?;
+        if message.len() < NONCE_LENGTH {
+            apibail_generic!("session message too short");
+        }
+        let nonce = Nonce::try_from(&message[0..NONCE_LENGTH])?;
+        vcrypto.decrypt_aead(&message[NONCE_LENGTH..], &nonce, &self.shared_secret, None)
+    }
+
+    /// Release the private routes used by this session
+    pub fn close(self, api: &VeilidAPI) -> VeilidAPIResult<()> {
+        let res_local = api.release_private_route(self.local_route_id);
+        let res_remote = api.release_private_route(self.remote_route_id);
+        res_local.and(res_remote)
+    }
+}
+
+fn get_rendezvous_crypto(
+    api: &VeilidAPI,
+    crypto_kind: CryptoKind,
+) -> VeilidAPIResult<CryptoSystemVersion> {
+    let crypto = api.crypto()?;
+    crypto
+        .get(crypto_kind)
+        .ok_or_else(|| VeilidAPIError::invalid_argument("rendezvous", "crypto_kind", crypto_kind))
+}
+
+impl RoutingContext {
+    /// Create an invite that another node can accept with [RoutingContext::accept_invite]
+    ///
+    /// Allocates a private route and an ephemeral keypair. Returns the outstanding invite to keep,
+    /// along with the invite blob to hand to the other party out of band.
+    #[instrument(target = "veilid_api", level = "debug", err)]
+    pub async fn create_invite(&self) -> VeilidAPIResult<(RendezvousInvite, Vec<u8>)> {
+        event!(target: "veilid_api", Level::DEBUG,
+            "RoutingContext::create_invite(self: {:?})", self);
+
+        let api = self.api();
+        let vcrypto = api.crypto()?.best();
+        let crypto_kind = vcrypto.kind();
+        let keypair = vcrypto.generate_keypair();
+        let (route_id, route_blob) = api.new_private_route().await?;
+
+        let blob = [
+            INVITE_MAGIC.as_slice(),
+            &crypto_kind.0,
+            &keypair.key.bytes,
+            &route_blob,
+        ]
+        .concat();
+
+        Ok((
+            RendezvousInvite {
+                crypto_kind,
+                keypair,
+                route_id,
+            },
+            blob,
+        ))
+    }
+
+    /// Accept an invite blob created with [RoutingContext::create_invite]
+    ///
+    /// Performs the handshake with the inviter over an app call and returns the established session
+    /// once the inviter has proven it holds the invite key.
+    #[instrument(target = "veilid_api", level = "debug", skip(invite_blob), err)]
+    pub async fn accept_invite(&self, invite_blob: Vec<u8>) -> VeilidAPIResult<RendezvousSession> {
+        event!(target: "veilid_api", Level::DEBUG,
+            "RoutingContext::accept_invite(self: {:?}, invite_blob: {:?})", self, invite_blob);
+
+        // Decode the invite
+        let header_len = 4 + 4 + PUBLIC_KEY_LENGTH;
+        if invite_blob.len() <= header_len || &invite_blob[0..4] != INVITE_MAGIC {
+            apibail_invalid_argument!("accept_invite", "invite_blob", "not an invite");
+        }
+        let crypto_kind = CryptoKind::try_from(&invite_blob[4..8])?;
+        let inviter_key = PublicKey::try_from(&invite_blob[8..header_len])?;
+        let inviter_route_blob = invite_blob[header_len..].to_vec();
+
+        let api = self.api();
+        let vcrypto = get_rendezvous_crypto(&api, crypto_kind)?;
+
+        // Set up our end of the session
+        let keypair = vcrypto.generate_keypair();
+        let shared_secret =
+            vcrypto.generate_shared_secret(&inviter_key, &keypair.secret, RENDEZVOUS_DOMAIN)?;
+        let remote_route_id = api.import_remote_private_route(inviter_route_blob)?;
+        let (local_route_id, local_route_blob) = match api.new_private_route().await {
+            Ok(v) => v,
+            Err(e) => {
+                let _ = api.release_private_route(remote_route_id);
+                return Err(e);
+            }
+        };
+        let session = RendezvousSession {
+            crypto_kind,
+            shared_secret,
+            local_route_id,
+            remote_route_id,
+        };
+
+        // Send our route encrypted to the inviter, bound to our ephemeral key
+        let nonce = vcrypto.random_nonce();
+        let body = vcrypto.encrypt_aead(
+            &local_route_blob,
+            &nonce,
+            &shared_secret,
+            Some(&keypair.key.bytes),
+        )?;
+        let request = [
+            INVITE_REQUEST_MAGIC.as_slice(),
+            &keypair.key.bytes,
+            &nonce.bytes,
+            &body,
+        ]
+        .concat();
+
+        let reply = match self
+            .app_call(Target::PrivateRoute(remote_route_id), request)
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                let _ = session.close(&api);
+                return Err(e);
+            }
+        };
+
+        // The inviter proves it holds the invite secret by encrypting the confirmation
+        let verified = reply.len() > 4 + NONCE_LENGTH
+            && &reply[0..4] == INVITE_REPLY_MAGIC
+            && Nonce::try_from(&reply[4..4 + NONCE_LENGTH])
+                .and_then(|nonce| {
+                    vcrypto.decrypt_aead(
+                        &reply[4 + NONCE_LENGTH..],
+                        &nonce,
+                        &shared_secret,
+                        Some(&inviter_key.bytes),
+                    )
+                })
+                .is_ok();
+        if !verified {
+            let _ = session.close(&api);
+            apibail_generic!("invite reply could not be verified");
+        }
+
+        Ok(session)
+    }
+
+    /// Answer an app call received on the route of an outstanding invite
+    ///
+    /// Returns `None` if the app call is not an invite acceptance for this invite, in which case the
+    /// application should handle it normally. Otherwise the handshake is completed, the app call
+    /// is replied to, and the established session is returned.
+    #[instrument(target = "veilid_api", level = "debug", skip(invite, app_call), err)]
+    pub async fn answer_invite(
+        &self,
+        invite: &RendezvousInvite,
+        app_call: &VeilidAppCall,
+    ) -> VeilidAPIResult<Option<RendezvousSession>> {
+        event!(target: "veilid_api", Level::DEBUG,
+            "RoutingContext::answer_invite(self: {:?}, route_id: {:?}, app_call: {:?})", self, invite.route_id, app_call);
+
+        // Must be a rendezvous request over the invite's route
+        if app_call.route_id() != Some(&invite.route_id) {
+            return Ok(None);
+        }
+        let message = app_call.message();
+        let header_len = 4 + PUBLIC_KEY_LENGTH + NONCE_LENGTH;
+        if message.len() <= header_len || &message[0..4] != INVITE_REQUEST_MAGIC {
+            return Ok(None);
+        }
+        let accepter_key = PublicKey::try_from(&message[4..4 + PUBLIC_KEY_LENGTH])?;
+        let nonce = Nonce::try_from(&message[4 + PUBLIC_KEY_LENGTH..header_len])?;
+
+        let api = self.api();
+        let vcrypto = get_rendezvous_crypto(&api, invite.crypto_kind)?;
+
+        // Decrypting the accepter's route proves it derived the same shared secret
+        let shared_secret = vcrypto.generate_shared_secret(
+            &accepter_key,
+            &invite.keypair.secret,
+            RENDEZVOUS_DOMAIN,
+        )?;
+        let accepter_route_blob = vcrypto.decrypt_aead(
+            &message[header_len..],
+            &nonce,
+            &shared_secret,
+            Some(&accepter_key.bytes),
+        )?;
+        let remote_route_id = api.import_remote_private_route(accepter_route_blob)?;
+
+        // Confirm to the accepter
+        let reply_nonce = vcrypto.random_nonce();
+        let reply_body = vcrypto.encrypt_aead(
+            INVITE_REPLY_MAGIC,
+            &reply_nonce,
+            &shared_secret,
+            Some(&invite.keypair.key.bytes),
+        )?;
+        let reply = [
+            INVITE_REPLY_MAGIC.as_slice(),
+            &reply_nonce.bytes,
+            &reply_body,
+        ]
+        .concat();
+        if let Err(e) = api.app_call_reply(app_call.id(), reply).await {
+            let _ = api.release_private_route(remote_route_id);
+            return Err(e);
+        }
+
+        Ok(Some(RendezvousSession {
+            crypto_kind: invite.crypto_kind,
+            shared_secret,
+            local_route_id: invite.route_id,
+            remote_route_id,
+        }))
+    }
+}
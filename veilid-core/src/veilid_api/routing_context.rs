@@ -3,7 +3,7 @@ use super::*;
 ///////////////////////////////////////////////////////////////////////////////////////
 
 /// Valid destinations for a message sent over a routing context
-#[derive(Clone, Debug, Eq, PartialEq, Hash, Copy, PartialOrd, Ord)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Copy, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Target {
     /// Node by its public key
     NodeId(TypedKey),
@@ -11,11 +11,61 @@ pub enum Target {
     PrivateRoute(RouteId),
 }
 
+/// Cancels the app-level messaging and DHT operations in progress on the routing contexts it is given to.
+///
+/// Cancelled operations stop their network work, such as DHT fanouts and questions waiting for an answer,
+/// and fail with [VeilidAPIError::Generic]. Dropping an operation's future stops its network work as well.
+/// Cancelling is permanent, so operations started after that fail straight away.
+#[derive(Clone)]
+pub struct Canceller {
+    stop_source: Arc<Mutex<Option<StopSource>>>,
+    stop_token: StopToken,
+}
+
+impl Canceller {
+    pub fn new() -> Self {
+        let stop_source = StopSource::new();
+        let stop_token = stop_source.token();
+        Self {
+            stop_source: Arc::new(Mutex::new(Some(stop_source))),
+            stop_token,
+        }
+    }
+
+    /// Cancel every operation using this canceller
+    pub fn cancel(&self) {
+        drop(self.stop_source.lock().take());
+    }
+
+    /// Check if this canceller has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.stop_source.lock().is_none()
+    }
+}
+
+impl Default for Canceller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Canceller {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Canceller")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
 pub struct RoutingContextInner {}
 
 pub struct RoutingContextUnlockedInner {
     /// Safety routing requirements
     safety_selection: SafetySelection,
+    /// Deadline applied to app-level messaging and DHT operations, if any
+    timeout_ms: Option<u32>,
+    /// Cancels app-level messaging and DHT operations, if any
+    canceller: Option<Canceller>,
 }
 
 /// Routing contexts are the way you specify the communication preferences for Veilid.
@@ -24,6 +74,9 @@ pub struct RoutingContextUnlockedInner {
 /// privacy. To disable this and send RPC operations straight from the node use [RoutingContext::with_safety()] with a [SafetySelection::Unsafe] parameter.
 /// To enable receiver privacy, you should send to a private route RouteId that you have imported, rather than directly to a NodeId.
 ///
+/// Safety, sequencing and timeout preferences are set once on the routing context and are inherited by every
+/// app-level message and DHT operation performed through it, including records opened or created with it.
+///
 #[derive(Clone)]
 pub struct RoutingContext {
     /// Veilid API handle
@@ -37,6 +90,8 @@ impl fmt::Debug for RoutingContext {
         f.debug_struct("RoutingContext")
             .field("ptr", &format!("{:p}", Arc::as_ptr(&self.unlocked_inner)))
             .field("safety_selection", &self.unlocked_inner.safety_selection)
+            .field("timeout_ms", &self.unlocked_inner.timeout_ms)
+            .field("canceller", &self.unlocked_inner.canceller)
             .finish()
     }
 }
@@ -57,7 +112,11 @@ impl RoutingContext {
                     hop_count: c.network.rpc.default_route_hop_count as usize,
                     stability: Stability::Reliable,
                     sequencing: Sequencing::EnsureOrdered,
+                    preferred_first_hop: None,
+                    profile: None,
                 }),
+                timeout_ms: None,
+                canceller: None,
             }),
         })
     }
@@ -72,7 +131,7 @@ impl RoutingContext {
     /// * Sequencing default is to prefer ordered before unordered message delivery
     ///
     /// To customize the safety selection in use, use [RoutingContext::with_safety()].
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub fn with_default_safety(self) -> VeilidAPIResult<Self> {
         event!(target: "veilid_api", Level::DEBUG, 
             "RoutingContext::with_default_safety(self: {:?})", self);
@@ -85,11 +144,26 @@ impl RoutingContext {
             hop_count: c.network.rpc.default_route_hop_count as usize,
             stability: Stability::Reliable,
             sequencing: Sequencing::EnsureOrdered,
+            preferred_first_hop: None,
+            profile: None,
         }))
     }
 
+    /// Use safety routes chosen by a [SafetyProfile], with the sequencing preference of the current safety selection
+    ///
+    /// The profile picks the hop count, stability, network diversity, and how often routes are rotated,
+    /// so applications can pick how much privacy they need without choosing hop counts themselves.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub fn with_safety_profile(self, profile: SafetyProfile) -> VeilidAPIResult<Self> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::with_safety_profile(self: {:?}, profile: {:?})", self, profile);
+
+        let sequencing = self.sequencing();
+        self.with_safety(SafetySelection::with_profile(profile, sequencing))
+    }
+
     /// Use a custom [SafetySelection]. Can be used to disable safety via [SafetySelection::Unsafe]
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub fn with_safety(self, safety_selection: SafetySelection) -> VeilidAPIResult<Self> {
         event!(target: "veilid_api", Level::DEBUG, 
             "RoutingContext::with_safety(self: {:?}, safety_selection: {:?})", self, safety_selection);
@@ -97,12 +171,16 @@ impl RoutingContext {
         Ok(Self {
             api: self.api.clone(),
             inner: Arc::new(Mutex::new(RoutingContextInner {})),
-            unlocked_inner: Arc::new(RoutingContextUnlockedInner { safety_selection }),
+            unlocked_inner: Arc::new(RoutingContextUnlockedInner {
+                safety_selection,
+                timeout_ms: self.unlocked_inner.timeout_ms,
+                canceller: self.unlocked_inner.canceller.clone(),
+            }),
         })
     }
 
     /// Use a specified [Sequencing] preference, with or without privacy
-    #[instrument(target = "veilid_api", level = "debug", ret)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret)]
     pub fn with_sequencing(self, sequencing: Sequencing) -> Self {
         event!(target: "veilid_api", Level::DEBUG, 
             "RoutingContext::with_sequencing(self: {:?}, sequencing: {:?})", self, sequencing);
@@ -118,8 +196,53 @@ impl RoutingContext {
                         hop_count: safety_spec.hop_count,
                         stability: safety_spec.stability,
                         sequencing,
+                        preferred_first_hop: safety_spec.preferred_first_hop,
+                        profile: safety_spec.profile,
                     }),
                 },
+                timeout_ms: self.unlocked_inner.timeout_ms,
+                canceller: self.unlocked_inner.canceller.clone(),
+            }),
+        }
+    }
+
+    /// Use a specified timeout in milliseconds for all app-level messaging and DHT operations
+    /// performed with this routing context. Operations that do not complete in time fail with
+    /// [VeilidAPIError::Timeout].
+    ///
+    /// Passing `None` removes the deadline, leaving only the network-level RPC timeouts in effect.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret)]
+    pub fn with_timeout(self, timeout_ms: Option<u32>) -> Self {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::with_timeout(self: {:?}, timeout_ms: {:?})", self, timeout_ms);
+
+        Self {
+            api: self.api.clone(),
+            inner: Arc::new(Mutex::new(RoutingContextInner {})),
+            unlocked_inner: Arc::new(RoutingContextUnlockedInner {
+                safety_selection: self.unlocked_inner.safety_selection,
+                timeout_ms,
+                canceller: self.unlocked_inner.canceller.clone(),
+            }),
+        }
+    }
+
+    /// Use a [Canceller] to cancel the app-level messaging and DHT operations performed with this
+    /// routing context, including records opened or created with it.
+    ///
+    /// Passing `None` removes the canceller.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret)]
+    pub fn with_canceller(self, canceller: Option<Canceller>) -> Self {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::with_canceller(self: {:?}, canceller: {:?})", self, canceller);
+
+        Self {
+            api: self.api.clone(),
+            inner: Arc::new(Mutex::new(RoutingContextInner {})),
+            unlocked_inner: Arc::new(RoutingContextUnlockedInner {
+                safety_selection: self.unlocked_inner.safety_selection,
+                timeout_ms: self.unlocked_inner.timeout_ms,
+                canceller,
             }),
         }
     }
@@ -129,6 +252,11 @@ impl RoutingContext {
         self.unlocked_inner.safety_selection
     }
 
+    /// Get the timeout in milliseconds in use on this routing context, if any
+    pub fn timeout_ms(&self) -> Option<u32> {
+        self.unlocked_inner.timeout_ms
+    }
+
     fn sequencing(&self) -> Sequencing {
         match self.unlocked_inner.safety_selection {
             SafetySelection::Unsafe(sequencing) => sequencing,
@@ -141,7 +269,7 @@ impl RoutingContext {
         self.api.clone()
     }
 
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     async fn get_destination(&self, target: Target) -> VeilidAPIResult<rpc_processor::Destination> {
         event!(target: "veilid_api", Level::DEBUG, 
             "RoutingContext::get_destination(self: {:?}, target: {:?})", self, target);
@@ -149,10 +277,55 @@ impl RoutingContext {
         let rpc_processor = self.api.rpc_processor()?;
         rpc_processor
             .resolve_target_to_destination(target, self.unlocked_inner.safety_selection)
+            .instrument(self.api.instance_span())
             .await
             .map_err(VeilidAPIError::invalid_target)
     }
 
+    /// The safety spec to use for routes of our own, with defaults filled in when the context is unsafe
+    fn route_safety_spec(&self) -> VeilidAPIResult<SafetySpec> {
+        match self.unlocked_inner.safety_selection {
+            SafetySelection::Safe(safety_spec) => Ok(safety_spec),
+            SafetySelection::Unsafe(sequencing) => {
+                let config = self.api.config()?;
+                let c = config.get();
+                Ok(SafetySpec {
+                    preferred_route: None,
+                    hop_count: c.network.rpc.default_route_hop_count as usize,
+                    stability: Stability::Reliable,
+                    sequencing,
+                    preferred_first_hop: None,
+                    profile: None,
+                })
+            }
+        }
+    }
+
+    /// Run an operation under this routing context's timeout and canceller, if they are set
+    /// Network work stops when the operation's future is dropped, so neither outlives the operation
+    /// The operation runs in this node's span, so its logs go to this node's update callback
+    async fn with_deadline<F, T>(&self, f: F) -> VeilidAPIResult<T>
+    where
+        F: Future<Output = VeilidAPIResult<T>>,
+    {
+        let f = f.instrument(self.api.instance_span());
+        let f = async {
+            match self.unlocked_inner.timeout_ms {
+                Some(timeout_ms) => timeout(timeout_ms, f)
+                    .await
+                    .map_err(|_| VeilidAPIError::timeout())?,
+                None => f.await,
+            }
+        };
+        match &self.unlocked_inner.canceller {
+            Some(canceller) => f
+                .timeout_at(canceller.stop_token.clone())
+                .await
+                .map_err(|_| VeilidAPIError::generic("operation cancelled"))?,
+            None => f.await,
+        }
+    }
+
     ////////////////////////////////////////////////////////////////
     // App-level Messaging
 
@@ -164,7 +337,7 @@ impl RoutingContext {
     /// * `message` - an arbitrary message blob of up to 32768 bytes
     ///
     /// Returns an answer blob of up to 32768 bytes
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub async fn app_call(&self, target: Target, message: Vec<u8>) -> VeilidAPIResult<Vec<u8>> {
         event!(target: "veilid_api", Level::DEBUG, 
             "RoutingContext::app_call(self: {:?}, target: {:?}, message: {:?})", self, target, message);
@@ -175,7 +348,15 @@ impl RoutingContext {
         let dest = self.get_destination(target).await?;
 
         // Send app message
-        let answer = match rpc_processor.rpc_call_app_call(dest, message).await {
+        let res = self
+            .with_deadline(async {
+                rpc_processor
+                    .rpc_call_app_call(dest, message)
+                    .await
+                    .map_err(VeilidAPIError::from)
+            })
+            .await;
+        let answer = match res {
             Ok(NetworkResult::Value(v)) => v,
             Ok(NetworkResult::Timeout) => apibail_timeout!(),
             Ok(NetworkResult::ServiceUnavailable(e)) => apibail_invalid_target!(e),
@@ -186,7 +367,7 @@ impl RoutingContext {
             Ok(NetworkResult::InvalidMessage(message)) => {
                 apibail_generic!(message);
             }
-            Err(e) => return Err(e.into()),
+            Err(e) => return Err(e),
         };
 
         Ok(answer.answer)
@@ -198,7 +379,7 @@ impl RoutingContext {
     ///
     /// * `target` - can be either a direct node id or a private route
     /// * `message` - an arbitrary message blob of up to 32768 bytes
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub async fn app_message(&self, target: Target, message: Vec<u8>) -> VeilidAPIResult<()> {
         event!(target: "veilid_api", Level::DEBUG, 
             "RoutingContext::app_message(self: {:?}, target: {:?}, message: {:?})", self, target, message);
@@ -209,7 +390,15 @@ impl RoutingContext {
         let dest = self.get_destination(target).await?;
 
         // Send app message
-        match rpc_processor.rpc_call_app_message(dest, message).await {
+        let res = self
+            .with_deadline(async {
+                rpc_processor
+                    .rpc_call_app_message(dest, message)
+                    .await
+                    .map_err(VeilidAPIError::from)
+            })
+            .await;
+        match res {
             Ok(NetworkResult::Value(())) => {}
             Ok(NetworkResult::Timeout) => apibail_timeout!(),
             Ok(NetworkResult::ServiceUnavailable(e)) => apibail_invalid_target!(e),
@@ -219,12 +408,196 @@ impl RoutingContext {
             Ok(NetworkResult::InvalidMessage(message)) => {
                 apibail_generic!(message);
             }
-            Err(e) => return Err(e.into()),
+            Err(e) => return Err(e),
         };
 
         Ok(())
     }
 
+    /// App-level unidirectional message sent to each of the nodes closest to a key.
+    ///
+    /// The nodes closest to an arbitrary key can act as a rendezvous point, so apps may use this to
+    /// build topic-based discovery or pub/sub without keeping their own lists of nodes. Closest nodes
+    /// are found with the same search used by the DHT, and only nodes that accept app messages are used.
+    ///
+    /// * `key` - the key to find the closest nodes to
+    /// * `count` - the number of closest nodes to send the message to, at most `network.dht.max_find_node_count`
+    /// * `message` - an arbitrary message blob of up to 32768 bytes
+    ///
+    /// Returns the ids of the nodes the message was sent to, in order of distance from the key
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn app_message_closest(
+        &self,
+        key: TypedKey,
+        count: u32,
+        message: Vec<u8>,
+    ) -> VeilidAPIResult<Vec<TypedKey>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::app_message_closest(self: {:?}, key: {:?}, count: {}, message: {:?})", self, key, count, message);
+
+        let rpc_processor = self.api.rpc_processor()?;
+
+        let max_find_node_count = {
+            let config = self.api.config()?;
+            let c = config.get();
+            c.network.dht.max_find_node_count
+        };
+        if count == 0 || count > max_find_node_count {
+            apibail_invalid_argument!("invalid count", "count", count);
+        }
+        if message.len() > rpc_processor::MAX_APP_MESSAGE_SIZE {
+            apibail_invalid_argument!("message too large", "message.len", message.len());
+        }
+        if self.api.crypto()?.get(key.kind).is_none() {
+            apibail_generic!("unsupported cryptosystem");
+        }
+
+        // Send app message to the closest nodes
+        let sent_nodes = self
+            .with_deadline(async {
+                rpc_processor
+                    .rpc_call_app_message_closest(
+                        key,
+                        count as usize,
+                        message,
+                        self.unlocked_inner.safety_selection,
+                    )
+                    .await
+                    .map_err(VeilidAPIError::from)
+            })
+            .await?;
+
+        Ok(sent_nodes
+            .iter()
+            .filter_map(|nr| nr.node_ids().get(key.kind))
+            .collect())
+    }
+
+    /// Establish a connection to a target ahead of a latency-sensitive [RoutingContext::app_call].
+    ///
+    /// For a node id without a safety route, the node is resolved and sent a status question, which does any
+    /// hole punching, reverse connection or relay setup needed to reach it. The connection is then kept alive
+    /// with keepalives for `network.prewarm_grace_period_ms`. Otherwise the status question only warms up
+    /// the route hops, which are kept alive by route testing rather than by this call.
+    ///
+    /// * `target` - can be either a direct node id or a private route
+    ///
+    /// Returns how the target was reached
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn prewarm(&self, target: Target) -> VeilidAPIResult<ContactMethod> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::prewarm(self: {:?}, target: {:?})", self, target);
+
+        let rpc_processor = self.api.rpc_processor()?;
+
+        // Only a direct connection to a node can be set up and kept alive
+        let direct_node_id = match (&target, &self.unlocked_inner.safety_selection) {
+            (Target::NodeId(node_id), SafetySelection::Unsafe(_)) => Some(*node_id),
+            _ => None,
+        };
+
+        // Get destination
+        let dest = self.get_destination(target).await?;
+
+        // Find out how the node is going to be reached before the status question sets it up
+        let opt_contact_method = match dest.node() {
+            Some(nr) if direct_node_id.is_some() => {
+                if nr.last_flow().is_some() {
+                    Some(ContactMethod::Existing)
+                } else {
+                    let network_manager = self.api.network_manager()?;
+                    let ncm = network_manager
+                        .get_node_contact_method(nr)
+                        .map_err(VeilidAPIError::internal)?;
+                    let Some(contact_method) = ncm.to_contact_method() else {
+                        apibail_no_connection!("target is unreachable");
+                    };
+                    Some(contact_method)
+                }
+            }
+            _ => None,
+        };
+
+        // Send status question
+        let res = self
+            .with_deadline(async {
+                rpc_processor
+                    .rpc_call_status(dest)
+                    .await
+                    .map_err(VeilidAPIError::from)
+            })
+            .await;
+        match res {
+            Ok(NetworkResult::Value(_)) => {}
+            Ok(NetworkResult::Timeout) => apibail_timeout!(),
+            Ok(NetworkResult::ServiceUnavailable(e)) => apibail_invalid_target!(e),
+            Ok(NetworkResult::NoConnection(e)) | Ok(NetworkResult::AlreadyExists(e)) => {
+                apibail_no_connection!(e);
+            }
+            Ok(NetworkResult::InvalidMessage(message)) => {
+                apibail_generic!(message);
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Keep the direct connection alive for the grace period
+        let (Some(node_id), Some(contact_method)) = (direct_node_id, opt_contact_method) else {
+            return Ok(ContactMethod::Routed);
+        };
+        let prewarm_grace_period_ms = {
+            let config = self.api.config()?;
+            let c = config.get();
+            c.network.prewarm_grace_period_ms
+        };
+        self.api.routing_table()?.prewarm_node(
+            node_id,
+            TimestampDuration::new(ms_to_us(prewarm_grace_period_ms)),
+        );
+
+        Ok(contact_method)
+    }
+
+    /// Test that one of our own private routes is reachable, so it can be validated before it is published.
+    ///
+    /// Sends a status question to ourselves over the private route, using this routing context's safety
+    /// settings with a safety route that shares no nodes with the route being tested. The question is
+    /// answered by the node itself and is never delivered to the app.
+    ///
+    /// * `route_id` - the id of a private route allocated with [VeilidAPI::new_private_route] or [VeilidAPI::new_custom_private_route]
+    ///
+    /// Returns whether the question made it back and the measured round trip latency
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn test_private_route(
+        &self,
+        route_id: RouteId,
+    ) -> VeilidAPIResult<PrivateRouteTestResult> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::test_private_route(self: {:?}, route_id: {:?})", self, route_id);
+
+        let rss = self.api.routing_table()?.route_spec_store();
+        if rss.is_route_id_remote(&route_id) {
+            apibail_invalid_argument!("not one of our own private routes", "route_id", route_id);
+        }
+
+        let safety_spec = self.route_safety_spec()?;
+
+        // Send the loopback question
+        let res = self
+            .with_deadline(async { rss.test_allocated_route(route_id, Some(safety_spec)).await })
+            .await;
+        match res {
+            Ok(Some(latency)) => Ok(PrivateRouteTestResult {
+                success: true,
+                latency: Some(latency),
+            }),
+            Ok(None) | Err(VeilidAPIError::Timeout) => Ok(PrivateRouteTestResult {
+                success: false,
+                latency: None,
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
     ///////////////////////////////////
     /// DHT Records
 
@@ -233,7 +606,7 @@ impl RoutingContext {
     /// The record is considered 'open' after the create operation succeeds.
     ///
     /// Returns the newly allocated DHT record's key if successful.    
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub async fn create_dht_record(
         &self,
         schema: DHTSchema,
@@ -246,8 +619,64 @@ impl RoutingContext {
         let kind = kind.unwrap_or(best_crypto_kind());
         Crypto::validate_crypto_kind(kind)?;
         let storage_manager = self.api.storage_manager()?;
-        storage_manager
-            .create_record(kind, schema, self.unlocked_inner.safety_selection)
+        self.with_deadline(storage_manager.create_record(
+            kind,
+            schema,
+            None,
+            self.unlocked_inner.safety_selection,
+        ))
+        .await
+    }
+
+    /// Creates a new DHT record a specified crypto kind and schema that expires at a specified time
+    ///
+    /// The expiration is signed by the owner along with the schema. Once it passes, storage nodes stop
+    /// serving the record, refuse writes to it, and eventually delete it, so ephemeral data cleans itself up.
+    /// The owner can push the expiration back with [RoutingContext::extend_dht_record_expiration].
+    ///
+    /// The record is considered 'open' after the create operation succeeds.
+    ///
+    /// Returns the newly allocated DHT record's key if successful.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn create_expiring_dht_record(
+        &self,
+        schema: DHTSchema,
+        expiration: Timestamp,
+        kind: Option<CryptoKind>,
+    ) -> VeilidAPIResult<DHTRecordDescriptor> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::create_expiring_dht_record(self: {:?}, schema: {:?}, expiration: {:?}, kind: {:?})", self, schema, expiration, kind);
+        schema.validate()?;
+
+        let kind = kind.unwrap_or(best_crypto_kind());
+        Crypto::validate_crypto_kind(kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        self.with_deadline(storage_manager.create_record(
+            kind,
+            schema,
+            Some(expiration),
+            self.unlocked_inner.safety_selection,
+        ))
+        .await
+    }
+
+    /// Extends the expiration of a DHT record, or makes it never expire if 'expiration' is None
+    ///
+    /// The record must be open with the owner as its writer. The expiration can only be moved later,
+    /// and the record keeps its key. Storage nodes learn of the new expiration when it is pushed along
+    /// with a subkey value, which happens now if any subkey has been written.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn extend_dht_record_expiration(
+        &self,
+        key: TypedKey,
+        expiration: Option<Timestamp>,
+    ) -> VeilidAPIResult<()> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::extend_dht_record_expiration(self: {:?}, key: {:?}, expiration: {:?})", self, key, expiration);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        self.with_deadline(storage_manager.extend_record_expiration(key, expiration))
             .await
     }
 
@@ -262,7 +691,7 @@ impl RoutingContext {
     /// safety selection.
     ///
     /// Returns the DHT record descriptor for the opened record if successful
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub async fn open_dht_record(
         &self,
         key: TypedKey,
@@ -273,15 +702,130 @@ impl RoutingContext {
 
         Crypto::validate_crypto_kind(key.kind)?;
         let storage_manager = self.api.storage_manager()?;
-        storage_manager
-            .open_record(key, default_writer, self.unlocked_inner.safety_selection)
+        self.with_deadline(storage_manager.open_record(
+            key,
+            default_writer,
+            self.unlocked_inner.safety_selection,
+        ))
+        .await
+    }
+
+    /// Publishes a new version of a DHT record, allowing its schema to change
+    ///
+    /// DHT record descriptors can not be changed once created, so instead the owner creates a superseding
+    /// record with the same owner and a higher version, and a link to it signed by the owner is added to the
+    /// old record's descriptor. Readers can follow the link with [RoutingContext::resolve_dht_record].
+    ///
+    /// The old record must be open with the owner as its writer. The new record is considered 'open'
+    /// after this operation succeeds. Subkey data is not copied from the old record.
+    ///
+    /// Returns the DHT record descriptor for the new version of the record if successful
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn supersede_dht_record(
+        &self,
+        key: TypedKey,
+        schema: DHTSchema,
+    ) -> VeilidAPIResult<DHTRecordDescriptor> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::supersede_dht_record(self: {:?}, key: {:?}, schema: {:?})", self, key, schema);
+        schema.validate()?;
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        self.with_deadline(storage_manager.supersede_record(key, schema))
             .await
     }
 
+    /// Resolves a DHT record key to the key of its newest version
+    ///
+    /// Follows the links published by [RoutingContext::supersede_dht_record] from record to record.
+    /// Records that are not already open are only opened long enough to check for a link.
+    /// Each record a link points at must have the same owner and the version the link promised.
+    ///
+    /// Returns the key passed in if the record has not been superseded
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn resolve_dht_record(&self, key: TypedKey) -> VeilidAPIResult<TypedKey> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::resolve_dht_record(self: {:?}, key: {:?})", self, key);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        self.with_deadline(
+            storage_manager.resolve_record(key, self.unlocked_inner.safety_selection),
+        )
+        .await
+    }
+
+    /// Opens the newest version of a DHT record
+    ///
+    /// Equivalent to [RoutingContext::resolve_dht_record] followed by [RoutingContext::open_dht_record]
+    /// on the resolved key. Check the key of the returned descriptor to see which version was opened.
+    ///
+    /// Returns the DHT record descriptor for the opened record if successful
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn open_latest_dht_record(
+        &self,
+        key: TypedKey,
+        default_writer: Option<KeyPair>,
+    ) -> VeilidAPIResult<DHTRecordDescriptor> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::open_latest_dht_record(self: {:?}, key: {:?}, default_writer: {:?})", self, key, default_writer);
+
+        let key = self.resolve_dht_record(key).await?;
+        self.open_dht_record(key, default_writer).await
+    }
+
+    /// Registers a human-readable name for a DHT record key
+    ///
+    /// The name is stored in a DHT record whose key is derived from the owner and the name, so a
+    /// `veilid://<owner>/<name>` reference can be resolved by anyone without a central registry. Names are
+    /// only unique per owner. Registering the same name again with the same owner points it at the new target.
+    ///
+    /// Returns the name reference that resolves to the target if successful
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn register_name(
+        &self,
+        name: String,
+        target: TypedKey,
+        owner: KeyPair,
+        kind: Option<CryptoKind>,
+    ) -> VeilidAPIResult<DHTNameReference> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::register_name(self: {:?}, name: {:?}, target: {:?}, owner: {:?}, kind: {:?})", self, name, target, owner, kind);
+
+        let kind = kind.unwrap_or(best_crypto_kind());
+        Crypto::validate_crypto_kind(kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        self.with_deadline(storage_manager.register_name(
+            kind,
+            name,
+            target,
+            owner,
+            self.unlocked_inner.safety_selection,
+        ))
+        .await
+    }
+
+    /// Resolves a name reference registered with [RoutingContext::register_name] to its DHT record key
+    ///
+    /// Returns the key the owner last registered the name for
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn resolve_name(&self, reference: DHTNameReference) -> VeilidAPIResult<TypedKey> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::resolve_name(self: {:?}, reference: {:?})", self, reference);
+
+        Crypto::validate_crypto_kind(reference.owner().kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        self.with_deadline(
+            storage_manager.resolve_name(reference, self.unlocked_inner.safety_selection),
+        )
+        .await
+    }
+
     /// Closes a DHT record at a specific key that was opened with create_dht_record or open_dht_record.
     ///
     /// Closing a record allows you to re-open it with a different routing context
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub async fn close_dht_record(&self, key: TypedKey) -> VeilidAPIResult<()> {
         event!(target: "veilid_api", Level::DEBUG, 
             "RoutingContext::close_dht_record(self: {:?}, key: {:?})", self, key);
@@ -296,7 +840,7 @@ impl RoutingContext {
     /// If the record is opened, it must be closed before it is deleted.
     /// Deleting a record does not delete it from the network, but will remove the storage of the record
     /// locally, and will prevent its value from being refreshed on the network by this node.
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub async fn delete_dht_record(&self, key: TypedKey) -> VeilidAPIResult<()> {
         event!(target: "veilid_api", Level::DEBUG, 
             "RoutingContext::delete_dht_record(self: {:?}, key: {:?})", self, key);
@@ -312,7 +856,7 @@ impl RoutingContext {
     ///
     /// Returns `None` if the value subkey has not yet been set
     /// Returns `Some(data)` if the value subkey has valid data
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub async fn get_dht_value(
         &self,
         key: TypedKey,
@@ -324,7 +868,8 @@ impl RoutingContext {
 
         Crypto::validate_crypto_kind(key.kind)?;
         let storage_manager = self.api.storage_manager()?;
-        storage_manager.get_value(key, subkey, force_refresh).await
+        self.with_deadline(storage_manager.get_value(key, subkey, force_refresh))
+            .await
     }
 
     /// Pushes a changed subkey value to the network
@@ -334,7 +879,7 @@ impl RoutingContext {
     ///
     /// Returns `None` if the value was successfully put
     /// Returns `Some(data)` if the value put was older than the one available on the network
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub async fn set_dht_value(
         &self,
         key: TypedKey,
@@ -347,7 +892,35 @@ impl RoutingContext {
 
         Crypto::validate_crypto_kind(key.kind)?;
         let storage_manager = self.api.storage_manager()?;
-        storage_manager.set_value(key, subkey, data, writer).await
+        self.with_deadline(storage_manager.set_value(key, subkey, data, writer))
+            .await
+    }
+
+    /// Pushes several changed subkey values to the network at once
+    /// The DHT record must first by opened via open_dht_record or create_dht_record.
+    ///
+    /// The values are signed once together and each node is sent the whole batch in a single request,
+    /// which is much cheaper than calling set_dht_value for each subkey. At most 32 subkeys may be written in a batch,
+    /// and only nodes that support batches will store them.
+    ///
+    /// The writer, if specified, will override the 'default_writer' specified when the record is opened.
+    ///
+    /// Returns one entry per subkey in increasing subkey order, with `None` if the value was successfully put
+    /// and `Some(data)` if the value put was older than the one available on the network
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn set_dht_values(
+        &self,
+        key: TypedKey,
+        values: Vec<(ValueSubkey, Vec<u8>)>,
+        writer: Option<KeyPair>,
+    ) -> VeilidAPIResult<Vec<(ValueSubkey, Option<ValueData>)>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::set_dht_values(self: {:?}, key: {:?}, values: {:?}, writer: {:?})", self, key, values, writer);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        self.with_deadline(storage_manager.set_values(key, values, writer))
+            .await
     }
 
     /// Add or update a watch to a DHT value that informs the user via an VeilidUpdate::ValueChange callback when the record has subkeys change.
@@ -370,7 +943,7 @@ impl RoutingContext {
     /// * If a member (either the owner or a SMPL schema member) has opened the key for writing (even if no writing is performed) then the watch will be signed and guaranteed network.dht.member_watch_limit per writer
     ///
     /// Members can be specified via the SMPL schema and do not need to allocate writable subkeys in order to offer a member watch capability.
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub async fn watch_dht_values(
         &self,
         key: TypedKey,
@@ -383,8 +956,7 @@ impl RoutingContext {
 
         Crypto::validate_crypto_kind(key.kind)?;
         let storage_manager = self.api.storage_manager()?;
-        storage_manager
-            .watch_values(key, subkeys, expiration, count)
+        self.with_deadline(storage_manager.watch_values(key, subkeys, expiration, count))
             .await
     }
 
@@ -396,7 +968,7 @@ impl RoutingContext {
     /// If no subkeys remain, the watch is entirely cancelled and will receive no more updates.
     /// Returns Ok(true) if there is any remaining watch for this record
     /// Returns Ok(false) if the entire watch has been cancelled
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub async fn cancel_dht_watch(
         &self,
         key: TypedKey,
@@ -407,7 +979,8 @@ impl RoutingContext {
 
         Crypto::validate_crypto_kind(key.kind)?;
         let storage_manager = self.api.storage_manager()?;
-        storage_manager.cancel_watch_values(key, subkeys).await
+        self.with_deadline(storage_manager.cancel_watch_values(key, subkeys))
+            .await
     }
 
     /// Inspects a DHT record for subkey state.
@@ -449,7 +1022,7 @@ impl RoutingContext {
     ///     Useful for determine which subkeys would change with an SetValue operation
     ///
     /// Returns a DHTRecordReport with the subkey ranges that were returned that overlapped the schema, and sequence numbers for each of the subkeys in the range.
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub async fn inspect_dht_record(
         &self,
         key: TypedKey,
@@ -461,20 +1034,162 @@ impl RoutingContext {
 
         Crypto::validate_crypto_kind(key.kind)?;
         let storage_manager = self.api.storage_manager()?;
-        storage_manager.inspect_record(key, subkeys, scope).await
+        self.with_deadline(storage_manager.inspect_record(key, subkeys, scope))
+            .await
+    }
+
+    /// Brings an opened DHT record up to date, fetching only the subkeys that are stale locally.
+    ///
+    /// Rather than getting or inspecting every subkey, ranges of subkeys are compared with a node
+    /// holding the record by digests of their sequence numbers, narrowing down to the subkeys that
+    /// differ in a logarithmic number of round trips. This makes syncing large records cheap when
+    /// little has changed.
+    ///
+    /// * `key` is the record key to sync. it must first be opened for reading or writing.
+    ///
+    /// Returns the subkeys that were refreshed from the network.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn sync_dht_record(&self, key: TypedKey) -> VeilidAPIResult<ValueSubkeyRangeSet> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::sync_dht_record(self: {:?}, key: {:?})", self, key);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        self.with_deadline(storage_manager.sync_record(key)).await
+    }
+
+    ///////////////////////////////////
+    /// Published Private Routes
+
+    /// Allocates a new private route and publishes its blob to a DHT record subkey in one step
+    /// The DHT record must first be opened with a writer via open_dht_record or create_dht_record.
+    ///
+    /// The route is allocated with the hop count, stability and sequencing of this routing context's safety selection,
+    /// and its blob is written to the subkey with that safety selection. If the blob can not be written to the subkey,
+    /// the route is released again, so a route is only left allocated once it has been published.
+    ///
+    /// With a `rotation` schedule, the route is replaced periodically and each replacement blob is written to the
+    /// same subkey. The `record` and `subkey` of the schedule are replaced with `key` and `subkey`.
+    ///
+    /// Returns the route id that messages sent to the published route arrive on.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn publish_private_route(
+        &self,
+        key: TypedKey,
+        subkey: ValueSubkey,
+        rotation: Option<PrivateRouteRotation>,
+    ) -> VeilidAPIResult<RouteId> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::publish_private_route(self: {:?}, key: {:?}, subkey: {:?}, rotation: {:?})", self, key, subkey, rotation);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        if let Some(rotation) = &rotation {
+            if rotation.interval_ms == 0 {
+                apibail_invalid_argument!(
+                    "rotation interval must be greater than zero",
+                    "interval_ms",
+                    rotation.interval_ms
+                );
+            }
+        }
+        let storage_manager = self.api.storage_manager()?;
+        let rss = self.api.routing_table()?.route_spec_store();
+
+        let safety_spec = rss.apply_safety_profile(self.route_safety_spec()?);
+        let (route_id, blob) = self
+            .api
+            .allocate_published_private_route(
+                &VALID_CRYPTO_KINDS,
+                safety_spec.stability,
+                safety_spec.sequencing,
+                safety_spec.hop_count,
+            )
+            .await?;
+
+        // Don't leave the route allocated unless it made it into the record
+        let res = match self
+            .with_deadline(storage_manager.set_value_with_safety(
+                key,
+                subkey,
+                blob,
+                None,
+                Some(self.unlocked_inner.safety_selection),
+            ))
+            .await
+        {
+            Ok(None) => match rotation {
+                Some(rotation) => rss.set_route_rotation(
+                    &route_id,
+                    Some(PrivateRouteRotation {
+                        record: Some(key),
+                        subkey,
+                        ..rotation
+                    }),
+                ),
+                None => Ok(()),
+            },
+            Ok(Some(_)) => Err(VeilidAPIError::try_again(
+                "record subkey has a newer value on the network",
+            )),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = res {
+            rss.release_route(route_id);
+            return Err(e);
+        }
+
+        Ok(route_id)
+    }
+
+    /// Reads a private route blob published to a DHT record subkey and imports it as a remote private route
+    /// The DHT record must first be opened via open_dht_record or create_dht_record.
+    ///
+    /// The subkey is read with this routing context's safety selection rather than the one the record was opened with.
+    ///
+    /// Published routes that rotate are replaced in the same subkey, so resolving again with `force_refresh`
+    /// picks up the replacement once the old route stops working.
+    ///
+    /// Returns `None` if no route has been published to the subkey.
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
+    pub async fn resolve_private_route(
+        &self,
+        key: TypedKey,
+        subkey: ValueSubkey,
+        force_refresh: bool,
+    ) -> VeilidAPIResult<Option<RouteId>> {
+        event!(target: "veilid_api", Level::DEBUG, 
+            "RoutingContext::resolve_private_route(self: {:?}, key: {:?}, subkey: {:?}, force_refresh: {:?})", self, key, subkey, force_refresh);
+
+        Crypto::validate_crypto_kind(key.kind)?;
+        let storage_manager = self.api.storage_manager()?;
+        let Some(value) = self
+            .with_deadline(storage_manager.get_value_with_safety(
+                key,
+                subkey,
+                force_refresh,
+                Some(self.unlocked_inner.safety_selection),
+            ))
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        self.api
+            .import_remote_private_route(value.data().to_vec())
+            .map(Some)
     }
 
     ///////////////////////////////////
     /// Block Store
 
     #[cfg(feature = "unstable-blockstore")]
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub async fn find_block(&self, _block_id: PublicKey) -> VeilidAPIResult<Vec<u8>> {
         panic!("unimplemented");
     }
 
     #[cfg(feature = "unstable-blockstore")]
-    #[instrument(target = "veilid_api", level = "debug", ret, err)]
+    #[instrument(target = "veilid_api", level = "debug", parent = &self.api.instance_span(), ret, err)]
     pub async fn supply_block(&self, _block_id: PublicKey) -> VeilidAPIResult<bool> {
         panic!("unimplemented");
     }
@@ -26,6 +26,14 @@ pub fn fix_transferstatsdownup() -> TransferStatsDownUp {
     }
 }
 
+pub fn fix_transferstatswindows() -> TransferStatsWindows {
+    TransferStatsWindows {
+        minute: fix_transferstatsdownup(),
+        hour: fix_transferstatsdownup(),
+        day: fix_transferstatsdownup(),
+    }
+}
+
 pub fn fix_rpcstats() -> RPCStats {
     RPCStats {
         messages_sent: 1_000_000,
@@ -39,12 +47,26 @@ pub fn fix_rpcstats() -> RPCStats {
     }
 }
 
+pub fn fix_rpcloadstats() -> RPCLoadStats {
+    RPCLoadStats {
+        queue_depth: 120,
+        queue_size: 1024,
+        average_queue_time: AlignedU64::from(25_000),
+        average_processing_time: AlignedU64::from(1_500),
+        shed_questions: 17,
+        dropped_messages: 2,
+    }
+}
+
 pub fn fix_peerstats() -> PeerStats {
     PeerStats {
         time_added: AlignedU64::from(1685569176894),
         rpc_stats: fix_rpcstats(),
         latency: Some(fix_latencystats()),
         transfer: fix_transferstatsdownup(),
+        transfer_windows: fix_transferstatswindows(),
+        clock_skew: Some(42_000),
+        bandwidth: Some(AlignedU64::from(250_000)),
     }
 }
 
@@ -71,6 +93,23 @@ pub fn fix_peertabledata() -> PeerTableData {
     }
 }
 
+pub fn fix_domaintransferstats() -> DomainTransferStats {
+    DomainTransferStats {
+        routing_domain: RoutingDomain::LocalNetwork,
+        protocol_type: ProtocolType::TCP,
+        transfer_stats: fix_transferstatsdownup(),
+    }
+}
+
+pub fn fix_listenerstatus() -> ListenerStatus {
+    ListenerStatus {
+        protocol_type: ProtocolType::WSS,
+        bound_addresses: vec!["0.0.0.0:5150".to_string()],
+        last_error: Some("address in use".to_string()),
+        certificate_expiration: Some(Timestamp::new(1_735_689_600_000_000)),
+    }
+}
+
 pub fn fix_veilidconfiginner() -> VeilidConfigInner {
     VeilidConfigInner {
         program_name: "Bob".to_string(),
@@ -81,6 +120,8 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
         protected_store: VeilidConfigProtectedStore {
             allow_insecure_fallback: true,
             always_use_insecure_storage: false,
+            hardware_node_id: false,
+            hardware_node_id_migrate: false,
             directory: "/root".to_string(),
             delete: true,
             device_encryption_key_password: "1234".to_string(),
@@ -89,6 +130,7 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
         table_store: VeilidConfigTableStore {
             directory: "Yellow Pages".to_string(),
             delete: false,
+            backend: VeilidConfigTableStoreBackend::Memory,
         },
         block_store: VeilidConfigBlockStore {
             directory: "C:\\Program Files".to_string(),
@@ -104,6 +146,9 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
             client_allowlist_timeout_ms: 7000,
             reverse_connection_receipt_time_ms: 8000,
             hole_punch_receipt_time_ms: 9000,
+            prewarm_grace_period_ms: 10000,
+            max_relay_client_bytes_per_sec: 11000,
+            max_relay_client_queued_bytes: 12000,
             network_key_password: None,
             routing_table: VeilidConfigRoutingTable {
                 node_id: TypedKeyGroup::new(),
@@ -114,15 +159,29 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
                 limit_attached_strong: 3,
                 limit_attached_good: 4,
                 limit_attached_weak: 5,
+                node_info_max_age_ms: 6,
+                verified_peers_only: vec![RoutingDomain::PublicInternet],
             },
             rpc: VeilidConfigRPC {
                 concurrency: 5,
                 queue_size: 6,
                 max_timestamp_behind_ms: Some(1000),
                 max_timestamp_ahead_ms: Some(2000),
+                max_clock_skew_ms: 2500,
+                clock_skew_warning_ms: 2700,
                 timeout_ms: 3000,
                 max_route_hop_count: 7,
                 default_route_hop_count: 8,
+                max_private_routes: 64,
+                max_private_routes_per_api: 16,
+                private_route_idle_release_ms: Some(900_000),
+                max_app_message_size: 16384,
+                max_app_call_size: 8192,
+                max_app_message_frequency_per_min: 600,
+                max_app_call_frequency_per_min: 60,
+                strict_udp_source: true,
+                strict_udp_source_allow_rebind: false,
+                route_pow_difficulty: 12,
             },
             dht: VeilidConfigDHT {
                 max_find_node_count: 1,
@@ -135,6 +194,7 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
                 set_value_timeout_ms: 8,
                 set_value_count: 9,
                 set_value_fanout: 10,
+                min_fanout_under_load: 2,
                 min_peer_count: 11,
                 min_peer_refresh_time_ms: 12,
                 validate_dial_info_receipt_time_ms: 13,
@@ -144,30 +204,44 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
                 remote_max_records: 17,
                 remote_max_subkey_cache_memory_mb: 18,
                 remote_max_storage_space_mb: 19,
+                remote_storage_high_watermark_percent: 80,
+                remote_storage_low_watermark_percent: 60,
                 public_watch_limit: 20,
                 member_watch_limit: 21,
                 max_watch_expiration_ms: 22,
+                set_value_pow_difficulty: 23,
+                compress_values: true,
+                audit_log_max_entries: 24,
             },
             upnp: true,
             detect_address_changes: false,
+            detect_address_changes_distinct_subnets: 2,
             restricted_nat_retries: 10000,
+            max_envelope_size: 8192,
             tls: VeilidConfigTLS {
                 certificate_path: "/etc/ssl/certs/cert.pem".to_string(),
                 private_key_path: "/etc/ssl/keys/key.pem".to_string(),
                 connection_initial_timeout_ms: 1000,
             },
+            tor: VeilidConfigTor {
+                enabled: true,
+                socks_address: "127.0.0.1:9150".to_string(),
+                onion_address: None,
+            },
             application: VeilidConfigApplication {
                 https: VeilidConfigHTTPS {
                     enabled: true,
                     listen_address: "10.0.0.3".to_string(),
                     path: "/https_path/".to_string(),
                     url: Some("https://veilid.com/".to_string()),
+                    directory: Some("/var/lib/veilid/https".to_string()),
                 },
                 http: VeilidConfigHTTP {
                     enabled: true,
                     listen_address: "10.0.0.4".to_string(),
                     path: "/http_path/".to_string(),
                     url: Some("http://veilid.com/".to_string()),
+                    directory: None,
                 },
             },
             protocol: VeilidConfigProtocol {
@@ -176,6 +250,7 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
                     socket_pool_size: 30,
                     listen_address: "10.0.0.2".to_string(),
                     public_address: Some("2.3.4.5".to_string()),
+                    preference: 1,
                 },
                 tcp: VeilidConfigTCP {
                     connect: true,
@@ -183,6 +258,7 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
                     max_connections: 8,
                     listen_address: "10.0.0.1".to_string(),
                     public_address: Some("1.2.3.4".to_string()),
+                    preference: 2,
                 },
                 ws: VeilidConfigWS {
                     connect: false,
@@ -191,6 +267,7 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
                     listen_address: "127.0.0.1".to_string(),
                     path: "Straight".to_string(),
                     url: Some("https://veilid.com/ws".to_string()),
+                    preference: 3,
                 },
                 wss: VeilidConfigWSS {
                     connect: true,
@@ -199,8 +276,18 @@ pub fn fix_veilidconfiginner() -> VeilidConfigInner {
                     listen_address: "::1".to_string(),
                     path: "Curved".to_string(),
                     url: Some("https://veilid.com/wss".to_string()),
+                    preference: 4,
+                },
+                ipc: VeilidConfigIPC {
+                    connect: false,
+                    listen: true,
+                    listen_path: "/tmp/veilid.sock".to_string(),
                 },
             },
+            role: VeilidConfigRole::Bootstrap,
+            metered_policy: VeilidConfigNetworkCostPolicy::RelayOnly,
+            roaming_policy: VeilidConfigNetworkCostPolicy::Normal,
+            low_power_wake_interval_ms: 30000,
         },
     }
 }
@@ -211,5 +298,6 @@ pub fn fix_veilidvaluechange() -> VeilidValueChange {
         subkeys: ValueSubkeyRangeSet::new(),
         count: 5,
         value: Some(ValueData::new_with_seq(23, b"ValueData".to_vec(), fix_cryptokey()).unwrap()),
+        gap: true,
     }
 }
@@ -1,22 +1,40 @@
 use super::test_types::*;
 use super::test_types_dht::*;
 use super::test_types_dht_schema::*;
+use crate::tests::common::test_veilid_config::*;
+use crate::*;
 
 pub async fn test_all() {
     // test_types
     test_alignedu64().await;
     test_veilidappmessage().await;
     test_veilidappcall().await;
+    test_appmessagepolicy().await;
     test_fourcc().await;
+    test_networkcost().await;
+    test_networkcost_effective_policy().await;
+    test_networktime().await;
+    test_veilidreceipt().await;
+    test_rendezvousinvite().await;
+    test_rendezvoussession().await;
     test_sequencing().await;
     test_stability().await;
     test_safetyselection().await;
     test_safetyspec().await;
+    test_safetyprofile().await;
+    test_privaterouterotation().await;
+    test_privateroutetestresult().await;
     test_latencystats().await;
     test_transferstats().await;
     test_transferstatsdownup().await;
+    test_transferstatswindows().await;
     test_rpcstats().await;
+    test_rpcloadstats().await;
     test_peerstats().await;
+    test_bucketstatistics().await;
+    test_toptalkers().await;
+    test_relaydeadletter().await;
+    test_relayclientstats().await;
     #[cfg(feature = "unstable-tunnels")]
     test_tunnelmode().await;
     #[cfg(feature = "unstable-tunnels")]
@@ -33,18 +51,38 @@ pub async fn test_all() {
     test_veilidstateattachment().await;
     test_peertabledata().await;
     test_veilidstatenetwork().await;
+    test_publicaddressobservation().await;
     test_veilidroutechange().await;
     test_veilidstateconfig().await;
     test_veilidvaluechange().await;
+    test_veilididentitychange().await;
+    test_veilidstorageeviction().await;
     test_veilidupdate().await;
     test_veilidstate().await;
     // test_types_dht
     test_dhtrecorddescriptor().await;
+    test_dhtrecordversionlink().await;
+    test_dhtauditlogentry().await;
+    test_dhtnamereference().await;
     test_valuedata().await;
     test_valuesubkeyrangeset().await;
+    test_dhtrecordreport().await;
+    test_dhtstorageusage().await;
     // test_types_dht_schema
     test_dhtschemadflt().await;
     test_dhtschema().await;
     test_dhtschemasmplmember().await;
     test_dhtschemasmpl().await;
+    test_dhtschemaalog().await;
+    test_dhtschemamult().await;
+
+    // schema checks that verify signatures need a crypto system
+    let (update_callback, config_callback) = setup_veilid_core();
+    let api = api_startup(update_callback, config_callback)
+        .await
+        .expect("startup failed");
+    let vcrypto = api.crypto().unwrap().get(CRYPTO_KIND_VLD0).unwrap();
+    test_dhtschemaalog_check_subkey_value_data(vcrypto.clone()).await;
+    test_dhtschemamult_check_subkey_value_data(vcrypto).await;
+    api.shutdown().await;
 }
@@ -35,6 +35,13 @@ pub async fn test_veilidappcall() {
     assert_eq!(orig, copy);
 }
 
+pub async fn test_appmessagepolicy() {
+    let orig = AppMessagePolicy::Unlimited;
+    let copy = deserialize_json(&serialize_json(orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
 // fourcc
 
 pub async fn test_fourcc() {
@@ -44,6 +51,115 @@ pub async fn test_fourcc() {
     assert_eq!(orig, copy);
 }
 
+// network_cost
+
+pub async fn test_networkcost() {
+    let orig = NetworkCost {
+        metered: true,
+        roaming: false,
+    };
+    let copy = deserialize_json(&serialize_json(orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
+// network_time
+
+pub async fn test_networktime() {
+    let orig = NetworkTime::new(Timestamp::new(1_000_000), -250, 5);
+    let copy = deserialize_json(&serialize_json(orig)).unwrap();
+
+    assert_eq!(orig, copy);
+
+    // The local clock is shifted by the skew in either direction, without wrapping
+    assert_eq!(orig.timestamp, Timestamp::new(999_750));
+    assert_eq!(
+        NetworkTime::new(Timestamp::new(1_000_000), 250, 5).timestamp,
+        Timestamp::new(1_000_250)
+    );
+    assert_eq!(
+        NetworkTime::new(Timestamp::new(100), -1_000, 1).timestamp,
+        Timestamp::new(0)
+    );
+}
+
+pub async fn test_networkcost_effective_policy() {
+    let metered = VeilidConfigNetworkCostPolicy::ReduceTickRate;
+    let roaming = VeilidConfigNetworkCostPolicy::Suspend;
+
+    assert_eq!(
+        NetworkCost::default().effective_policy(metered, roaming),
+        VeilidConfigNetworkCostPolicy::Normal
+    );
+    assert_eq!(
+        NetworkCost {
+            metered: true,
+            roaming: false
+        }
+        .effective_policy(metered, roaming),
+        metered
+    );
+    assert_eq!(
+        NetworkCost {
+            metered: true,
+            roaming: true
+        }
+        .effective_policy(metered, roaming),
+        roaming
+    );
+    // The stricter policy wins even when it is configured for metered networks
+    assert_eq!(
+        NetworkCost {
+            metered: true,
+            roaming: true
+        }
+        .effective_policy(roaming, metered),
+        roaming
+    );
+}
+
+// receipt
+
+pub async fn test_veilidreceipt() {
+    let orig = VeilidReceipt::new(
+        Nonce::new([7u8; NONCE_LENGTH]),
+        fix_typedkey(),
+        b"Extra data".to_vec(),
+    );
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
+// rendezvous
+
+pub async fn test_rendezvousinvite() {
+    let json = format!(
+        r#"{{"crypto_kind":"VLD0","keypair":"{}","route_id":"{}"}}"#,
+        KeyPair::new(fix_cryptokey(), fix_cryptokey()),
+        fix_cryptokey()
+    );
+    let orig: RendezvousInvite = deserialize_json(&json).unwrap();
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+    assert_eq!(orig.crypto_kind(), CRYPTO_KIND_VLD0);
+}
+
+pub async fn test_rendezvoussession() {
+    let json = format!(
+        r#"{{"crypto_kind":"VLD0","shared_secret":"{}","local_route_id":"{}","remote_route_id":"{}"}}"#,
+        fix_cryptokey(),
+        fix_cryptokey(),
+        fix_cryptokey()
+    );
+    let orig: RendezvousSession = deserialize_json(&json).unwrap();
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+    assert_ne!(orig.local_route_id(), orig.remote_route_id());
+}
+
 // safety
 
 pub async fn test_sequencing() {
@@ -73,12 +189,43 @@ pub async fn test_safetyspec() {
         hop_count: 23,
         stability: Stability::default(),
         sequencing: Sequencing::default(),
+        preferred_first_hop: Some(fix_typedkey()),
+        profile: Some(SafetyProfile::Balanced),
     };
     let copy = deserialize_json(&serialize_json(orig)).unwrap();
 
     assert_eq!(orig, copy);
 }
 
+pub async fn test_privaterouterotation() {
+    let orig = PrivateRouteRotation {
+        interval_ms: 3_600_000,
+        overlap_ms: 300_000,
+        record: Some(fix_typedkey()),
+        subkey: 2,
+    };
+    let copy = deserialize_json(&serialize_json(orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
+pub async fn test_privateroutetestresult() {
+    let orig = PrivateRouteTestResult {
+        success: true,
+        latency: Some(AlignedU64::from(250_000)),
+    };
+    let copy = deserialize_json(&serialize_json(orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
+pub async fn test_safetyprofile() {
+    let orig = SafetyProfile::Paranoid;
+    let copy = deserialize_json(&serialize_json(orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
 // stats
 
 pub async fn test_latencystats() {
@@ -102,6 +249,13 @@ pub async fn test_transferstatsdownup() {
     assert_eq!(orig, copy);
 }
 
+pub async fn test_transferstatswindows() {
+    let orig = fix_transferstatswindows();
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
 pub async fn test_rpcstats() {
     let orig = fix_rpcstats();
     let copy = deserialize_json(&serialize_json(&orig)).unwrap();
@@ -109,6 +263,13 @@ pub async fn test_rpcstats() {
     assert_eq!(orig, copy);
 }
 
+pub async fn test_rpcloadstats() {
+    let orig = fix_rpcloadstats();
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
 pub async fn test_peerstats() {
     let orig = fix_peerstats();
     let copy = deserialize_json(&serialize_json(&orig)).unwrap();
@@ -116,6 +277,66 @@ pub async fn test_peerstats() {
     assert_eq!(orig, copy);
 }
 
+pub async fn test_bucketstatistics() {
+    let orig = BucketStatistics {
+        kind: FourCC::from_str("D34D").unwrap(),
+        near_bucket_skew: 3,
+        buckets: vec![BucketStats {
+            index: 17,
+            reliable_entry_count: 5,
+            unreliable_entry_count: 2,
+            dead_entry_count: 1,
+            average_entry_age: TimestampDuration::new(90_000_000),
+        }],
+    };
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
+pub async fn test_relaydeadletter() {
+    let orig = RelayDeadLetter {
+        destination: fix_typedkey(),
+        failure_count: 12,
+        first_failure_ts: Timestamp::new(1_685_569_084_280),
+        last_failure_ts: Timestamp::new(1_685_569_101_256),
+        last_error: "NoConnection(ConnectionRefused)".to_string(),
+    };
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
+pub async fn test_relayclientstats() {
+    let orig = RelayClientStats {
+        client: fix_typedkey(),
+        relayed_bytes: ByteCount::new(1_048_576),
+        dropped_bytes: ByteCount::new(65_536),
+        queued_bytes: ByteCount::new(1024),
+    };
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
+pub async fn test_toptalkers() {
+    let talker = TopTalker {
+        node_ids: vec![fix_typedkey()],
+        bytes_down: ByteCount::new(1_000_000),
+        bytes_up: ByteCount::new(20_000),
+        rpc_messages_rcvd: 900,
+        rpc_messages_sent: 40,
+    };
+    let orig = TopTalkers {
+        since: Timestamp::new(1_700_000_000_000_000),
+        by_bytes: vec![talker.clone()],
+        by_rpc_count: vec![talker],
+    };
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
 //  tunnel
 
 #[cfg(feature = "unstable-tunnels")]
@@ -213,6 +434,19 @@ pub async fn test_veilidstateattachment() {
         state: AttachmentState::OverAttached,
         public_internet_ready: true,
         local_network_ready: false,
+        storage: StorageManagerStatus {
+            pending_offline_writes: 3,
+            open_record_count: 2,
+            local_used: ByteCount::new(4096),
+            remote_used: ByteCount::new(65_536),
+        },
+        crypto_kinds: vec![CryptoKindHealth {
+            kind: FourCC::from_str("VLD0").unwrap(),
+            reliable_entry_count: 12,
+            unreliable_entry_count: 3,
+            dead_entry_count: 1,
+            viable: true,
+        }],
     };
     let copy = deserialize_json(&serialize_json(&orig)).unwrap();
 
@@ -232,6 +466,25 @@ pub async fn test_veilidstatenetwork() {
         bps_down: AlignedU64::from(14_400),
         bps_up: AlignedU64::from(1200),
         peers: vec![fix_peertabledata()],
+        transfer_stats: vec![fix_domaintransferstats()],
+        clock_skew: Some(-1_250),
+        clock_skew_warning: true,
+        listeners: vec![fix_listenerstatus()],
+        rpc_load: fix_rpcloadstats(),
+        transfer_windows: fix_transferstatswindows(),
+    };
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
+pub async fn test_publicaddressobservation() {
+    let orig = PublicAddressObservation {
+        reporting_node_id: fix_typedkey(),
+        reporting_address: "203.0.113.7:5150".to_string(),
+        observed_address: "198.51.100.23:40123".to_string(),
+        protocol_type: ProtocolType::UDP,
+        timestamp: Timestamp::new(1_685_569_101_256),
     };
     let copy = deserialize_json(&serialize_json(&orig)).unwrap();
 
@@ -242,6 +495,11 @@ pub async fn test_veilidroutechange() {
     let orig = VeilidRouteChange {
         dead_routes: vec![fix_cryptokey()],
         dead_remote_routes: vec![fix_cryptokey()],
+        rotated_routes: vec![VeilidRouteRotation {
+            old_route: fix_cryptokey(),
+            new_route: fix_cryptokey(),
+            blob: b"route blob".to_vec(),
+        }],
     };
     let copy = deserialize_json(&serialize_json(&orig)).unwrap();
 
@@ -264,6 +522,36 @@ pub async fn test_veilidvaluechange() {
     assert_eq!(orig, copy);
 }
 
+pub async fn test_veilididentitychange() {
+    let orig = VeilidIdentityChange {
+        new_node_ids: vec![fix_typedkey()],
+        new_device_encryption_key: true,
+    };
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+
+    let orig = VeilidUpdate::IdentityChange(Box::new(orig));
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
+pub async fn test_veilidstorageeviction() {
+    let orig = VeilidStorageEviction {
+        evicted_record_count: 12,
+        evicted_space: ByteCount::new(1_048_576),
+    };
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+
+    let orig = VeilidUpdate::StorageEviction(Box::new(orig));
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
 pub async fn test_veilidupdate() {
     let orig = VeilidUpdate::ValueChange(Box::new(fix_veilidvaluechange()));
     let copy = deserialize_json(&serialize_json(&orig)).unwrap();
@@ -277,12 +565,20 @@ pub async fn test_veilidstate() {
             state: AttachmentState::OverAttached,
             public_internet_ready: true,
             local_network_ready: false,
+            storage: StorageManagerStatus::default(),
+            crypto_kinds: vec![],
         }),
         network: Box::new(VeilidStateNetwork {
             started: true,
             bps_down: AlignedU64::from(14_400),
             bps_up: AlignedU64::from(1200),
             peers: vec![fix_peertabledata()],
+            transfer_stats: vec![fix_domaintransferstats()],
+            clock_skew: None,
+            clock_skew_warning: false,
+            listeners: vec![],
+            rpc_load: RPCLoadStats::default(),
+            transfer_windows: TransferStatsWindows::default(),
         }),
         config: Box::new(VeilidStateConfig {
             config: fix_veilidconfiginner(),
@@ -10,10 +10,68 @@ pub async fn test_dhtrecorddescriptor() {
         fix_cryptokey(),
         Some(fix_cryptokey()),
         DHTSchema::dflt(4321).unwrap(),
+        2,
+        Some(Timestamp::new(1_700_000_000_000_000)),
     );
     let copy = deserialize_json(&serialize_json(&orig)).unwrap();
 
     assert_eq!(orig, copy);
+
+    // Descriptors serialized before expiration existed never expire
+    let mut value: serde_json::Value = serde_json::from_str(&serialize_json(&orig)).unwrap();
+    value.as_object_mut().unwrap().remove("expiration");
+    let copy: DHTRecordDescriptor = deserialize_json(&value.to_string()).unwrap();
+    assert_eq!(copy.expiration(), None);
+}
+
+// dht_record_version_link
+
+pub async fn test_dhtrecordversionlink() {
+    let orig = DHTRecordVersionLink::new(fix_typedkey(), 7);
+    let copy = deserialize_json(&serialize_json(orig)).unwrap();
+
+    assert_eq!(orig, copy);
+
+    let compiled = orig.compile();
+    assert_eq!(compiled.len(), DHTRecordVersionLink::FIXED_SIZE);
+    assert_eq!(
+        DHTRecordVersionLink::try_from(compiled.as_slice()).unwrap(),
+        orig
+    );
+    assert!(DHTRecordVersionLink::try_from(&compiled[1..]).is_err());
+}
+
+// dht_audit_log_entry
+
+pub async fn test_dhtauditlogentry() {
+    let orig = DHTAuditLogEntry {
+        timestamp: Timestamp::new(1_685_569_101_256),
+        operation: DHTAuditOperation::SetValue,
+        requester_hint: fix_typedkey(),
+        key: fix_typedkey(),
+        subkey: 3,
+        result: DHTAuditResult::Stale,
+        seq: Some(12),
+    };
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
+
+// dht_name_reference
+
+pub async fn test_dhtnamereference() {
+    let orig = DHTNameReference::new(fix_typedkey(), "my-app.profile".to_string()).unwrap();
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+
+    let url = orig.to_string();
+    assert!(url.starts_with(DHTNameReference::URL_SCHEME));
+    assert_eq!(DHTNameReference::from_str(&url).unwrap(), orig);
+    assert!(DHTNameReference::from_str(&url[DHTNameReference::URL_SCHEME.len()..]).is_err());
+    assert!(DHTNameReference::new(fix_typedkey(), String::new()).is_err());
+    assert!(DHTNameReference::new(fix_typedkey(), "two words".to_string()).is_err());
 }
 
 // value_data
@@ -33,3 +91,36 @@ pub async fn test_valuesubkeyrangeset() {
 
     assert_eq!(orig, copy);
 }
+
+// dht_record_report
+
+pub async fn test_dhtrecordreport() {
+    let orig = DHTRecordReport::new(
+        ValueSubkeyRangeSet::new_with_data(RangeSetBlaze::from_iter([2..=3, 7..=8])),
+        vec![1, ValueSeqNum::MAX, 5, 4],
+        vec![2, 0, 5, ValueSeqNum::MAX],
+    );
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+    assert_eq!(
+        orig.newer_network_subkeys(),
+        ValueSubkeyRangeSet::new_with_data(RangeSetBlaze::from_iter([2..=3]))
+    );
+}
+
+// dht_storage_usage
+
+pub async fn test_dhtstorageusage() {
+    let orig = DHTStorageUsage {
+        local_record_count: 3,
+        local_used: ByteCount::new(4096),
+        remote_record_count: 120,
+        remote_used: ByteCount::new(230_000_000),
+        remote_high_watermark: Some(ByteCount::new(241_591_910)),
+        remote_limit: None,
+    };
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+
+    assert_eq!(orig, copy);
+}
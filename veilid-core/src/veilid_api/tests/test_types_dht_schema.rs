@@ -65,3 +65,171 @@ pub async fn test_dhtschemasmpl() {
 
     assert_eq!(orig, copy);
 }
+
+// alog
+
+pub async fn test_dhtschemaalog() {
+    let orig = DHTSchemaALOG::new(100, vec![fix_cryptokey(), fix_cryptokey()]).unwrap();
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+    assert_eq!(orig, copy);
+
+    let compiled = DHTSchema::ALOG(orig.clone()).compile();
+    assert_eq!(
+        DHTSchema::try_from(compiled.as_slice()).unwrap(),
+        DHTSchema::ALOG(orig)
+    );
+
+    assert!(DHTSchemaALOG::new(0, vec![]).is_err());
+}
+
+pub async fn test_dhtschemaalog_check_subkey_value_data(vcrypto: CryptoSystemVersion) {
+    let owner = fix_cryptokey();
+    let writer = fix_cryptokey();
+    let stranger = fix_cryptokey();
+    let schema = DHTSchema::alog(4, vec![writer]).unwrap();
+
+    let entry = |seq: ValueSeqNum, writer: PublicKey| {
+        ValueData::new_with_seq(seq, b"entry".to_vec(), writer).unwrap()
+    };
+
+    // Owner and writers may append, nobody else may
+    assert!(schema.check_subkey_value_data(vcrypto.clone(), &owner, 0, &entry(0, owner), None));
+    assert!(schema.check_subkey_value_data(vcrypto.clone(), &owner, 0, &entry(0, writer), None));
+    assert!(!schema.check_subkey_value_data(vcrypto.clone(), &owner, 0, &entry(0, stranger), None));
+
+    // Entries past the end of the log are rejected
+    assert!(schema.check_subkey_value_data(vcrypto.clone(), &owner, 3, &entry(0, owner), None));
+    assert!(!schema.check_subkey_value_data(vcrypto.clone(), &owner, 4, &entry(0, owner), None));
+
+    // Written entries can not be replaced
+    assert!(!schema.check_subkey_value_data(vcrypto.clone(), &owner, 0, &entry(1, owner), None));
+
+    // Entries must be appended in order
+    let written = ValueSubkeyRangeSet::single_range(0, 1);
+    assert!(schema.check_subkey_value_data(
+        vcrypto.clone(),
+        &owner,
+        2,
+        &entry(0, writer),
+        Some(&written)
+    ));
+    assert!(!schema.check_subkey_value_data(
+        vcrypto.clone(),
+        &owner,
+        3,
+        &entry(0, writer),
+        Some(&written)
+    ));
+    assert!(schema.check_subkey_value_data(
+        vcrypto.clone(),
+        &owner,
+        0,
+        &entry(0, owner),
+        Some(&ValueSubkeyRangeSet::new())
+    ));
+    assert!(!schema.check_subkey_value_data(
+        vcrypto.clone(),
+        &owner,
+        1,
+        &entry(0, owner),
+        Some(&ValueSubkeyRangeSet::new())
+    ));
+}
+
+// mult
+
+pub async fn test_dhtschemamult() {
+    let orig = DHTSchemaMULT::new(
+        2,
+        vec![fix_cryptokey(), fix_cryptokey(), fix_cryptokey()],
+        4,
+    )
+    .unwrap();
+    let copy = deserialize_json(&serialize_json(&orig)).unwrap();
+    assert_eq!(orig, copy);
+
+    let compiled = DHTSchema::MULT(orig.clone()).compile();
+    assert_eq!(
+        DHTSchema::try_from(compiled.as_slice()).unwrap(),
+        DHTSchema::MULT(orig)
+    );
+
+    let key = fix_cryptokey();
+    assert!(DHTSchemaMULT::new(1, vec![], 1).is_err());
+    assert!(DHTSchemaMULT::new(0, vec![key], 1).is_err());
+    assert!(DHTSchemaMULT::new(2, vec![key], 1).is_err());
+    assert!(DHTSchemaMULT::new(1, vec![key, key], 1).is_err());
+}
+
+pub async fn test_dhtschemamult_check_subkey_value_data(vcrypto: CryptoSystemVersion) {
+    let owner = vcrypto.generate_keypair();
+    let co_owners = [
+        vcrypto.generate_keypair(),
+        vcrypto.generate_keypair(),
+        vcrypto.generate_keypair(),
+    ];
+    let stranger = vcrypto.generate_keypair();
+    let schema = DHTSchemaMULT::new(2, co_owners.iter().map(|k| k.key).collect(), 2).unwrap();
+    let payload = b"application root".to_vec();
+
+    let cosign = |seq: ValueSeqNum, co_owner: &KeyPair| {
+        schema
+            .cosign(vcrypto.clone(), &owner.key, seq, &payload, co_owner)
+            .unwrap()
+    };
+    let check = |subkey: ValueSubkey, seq: ValueSeqNum, data: Vec<u8>, writer: PublicKey| {
+        DHTSchema::MULT(schema.clone()).check_subkey_value_data(
+            vcrypto.clone(),
+            &owner.key,
+            subkey,
+            &ValueData::new_with_seq(seq, data, writer).unwrap(),
+            None,
+        )
+    };
+    let control = |cosignatures: &[DHTSchemaMULTCoSignature]| {
+        DHTSchemaMULT::encode_control_data(cosignatures, &payload).unwrap()
+    };
+
+    // Strangers can not sign for the record
+    assert!(schema
+        .cosign(vcrypto.clone(), &owner.key, 0, &payload, &stranger)
+        .is_err());
+
+    // The control subkey needs signatures from enough distinct co-owners
+    let sig0 = cosign(0, &co_owners[0]);
+    let sig1 = cosign(0, &co_owners[1]);
+    let approved = control(&[sig0.clone(), sig1.clone()]);
+    assert!(check(0, 0, approved.clone(), co_owners[2].key));
+    assert!(!check(0, 0, control(&[sig0.clone()]), co_owners[0].key));
+    let repeated = control(&[sig0.clone(), sig0.clone()]);
+    assert!(!check(0, 0, repeated, co_owners[0].key));
+    assert!(!check(0, 0, payload.clone(), co_owners[0].key));
+
+    // Only co-owners may submit the control subkey, even with enough signatures
+    assert!(!check(0, 0, approved.clone(), owner.key));
+    assert!(!check(0, 0, approved.clone(), stranger.key));
+
+    // Signatures do not carry over to another sequence number or payload
+    assert!(!check(0, 1, approved.clone(), co_owners[0].key));
+    let mut tampered = approved;
+    tampered.push(0);
+    assert!(!check(0, 0, tampered, co_owners[0].key));
+
+    // Signatures must come from the co-owner at the index they claim
+    let misplaced = DHTSchemaMULTCoSignature {
+        index: 2,
+        signature: sig1.signature,
+    };
+    assert!(!check(0, 0, control(&[sig0, misplaced]), co_owners[0].key));
+
+    // Any co-owner may write the other subkeys on their own
+    assert!(check(1, 5, b"entry".to_vec(), co_owners[1].key));
+    assert!(check(2, 0, b"entry".to_vec(), co_owners[2].key));
+    assert!(!check(2, 0, b"entry".to_vec(), stranger.key));
+    assert!(!check(3, 0, b"entry".to_vec(), co_owners[0].key));
+
+    // Only the control subkey is co-signed
+    let schema = DHTSchema::MULT(schema.clone());
+    assert!(schema.is_cosigned_subkey(0));
+    assert!(!schema.is_cosigned_subkey(1));
+}
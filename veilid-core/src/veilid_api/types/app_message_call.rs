@@ -51,7 +51,7 @@ impl VeilidAppMessage {
 
 /// Direct question blob passed to hosting application for processing to send an eventual AppReply
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
-#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify), tsify(from_wasm_abi))]
 pub struct VeilidAppCall {
     #[serde(with = "as_human_opt_string")]
     #[schemars(with = "Option<String>")]
@@ -112,3 +112,49 @@ impl VeilidAppCall {
         self.call_id
     }
 }
+
+/// How AppMessage and AppCall from a particular node are limited before being passed to the application
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(from_wasm_abi, into_wasm_abi, namespace)
+)]
+pub enum AppMessagePolicy {
+    /// Apply the configured size and rate limits
+    Limited = 0,
+    /// Apply the configured size limits but no rate limits, for trusted nodes
+    Unlimited = 1,
+    /// Drop everything from this node
+    Blocked = 2,
+}
+
+impl Default for AppMessagePolicy {
+    fn default() -> Self {
+        Self::Limited
+    }
+}
+
+/// How a target was reached when connections to it were prewarmed with [RoutingContext::prewarm]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(from_wasm_abi, into_wasm_abi, namespace)
+)]
+pub enum ContactMethod {
+    /// A connection to the node was already open
+    Existing = 0,
+    /// The node was dialed directly
+    Direct = 1,
+    /// The node was signaled to connect back to us
+    ReverseConnection = 2,
+    /// A hole punch was negotiated with the node
+    HolePunch = 3,
+    /// The node can only be reached through a relay
+    Relay = 4,
+    /// The target was reached through a safety or private route
+    Routed = 5,
+}
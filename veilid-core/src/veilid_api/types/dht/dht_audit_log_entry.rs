@@ -0,0 +1,58 @@
+use super::*;
+
+/// The kind of remote DHT operation recorded in the audit log
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify), tsify(namespace))]
+pub enum DHTAuditOperation {
+    /// Another node asked us for a subkey value
+    GetValue,
+    /// Another node asked us to store a subkey value
+    SetValue,
+}
+
+/// How a remote DHT operation recorded in the audit log was served
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify), tsify(namespace))]
+pub enum DHTAuditResult {
+    /// We are not close enough to the key to store it, only closer peers were returned
+    NotClose,
+    /// The write was not accepted because it did not come with a valid proof of work
+    PowRequired,
+    /// We have no value for the subkey
+    NoValue,
+    /// We returned our value for the subkey
+    Returned,
+    /// We stored the value that was sent
+    Stored,
+    /// We kept our own value because the value that was sent was not newer
+    Stale,
+}
+
+/// One remote DHT operation served by this node
+///
+/// The requester is the node that sent the operation to us. For operations that arrived over a
+/// safety route this is the last hop of the route rather than the originator, so it is only a hint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct DHTAuditLogEntry {
+    /// When the operation was served
+    pub timestamp: Timestamp,
+    /// The kind of operation
+    pub operation: DHTAuditOperation,
+    /// The node that sent the operation to us
+    #[schemars(with = "String")]
+    pub requester_hint: TypedKey,
+    /// The record key
+    #[schemars(with = "String")]
+    pub key: TypedKey,
+    /// The subkey that was read or written
+    pub subkey: ValueSubkey,
+    /// How the operation was served
+    pub result: DHTAuditResult,
+    /// The sequence number of the value that was returned or stored, if there was one
+    pub seq: Option<ValueSeqNum>,
+}
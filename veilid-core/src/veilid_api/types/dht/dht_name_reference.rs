@@ -0,0 +1,74 @@
+use super::*;
+
+/// DHT Name Reference
+///
+/// A human-readable reference to a DHT record in the form `veilid://<owner>/<name>`.
+/// The record holding the name is derived from the owner and the name, so anyone who has the reference
+/// can find the record the owner registered the name for, without a central registry.
+#[derive(
+    Debug, Clone, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct DHTNameReference {
+    /// The public key of the owner of the name
+    #[schemars(with = "String")]
+    owner: TypedKey,
+    /// The name
+    name: String,
+}
+
+impl DHTNameReference {
+    pub const URL_SCHEME: &'static str = "veilid://";
+    pub const MAX_NAME_LENGTH: usize = 255;
+
+    pub fn new(owner: TypedKey, name: String) -> VeilidAPIResult<Self> {
+        Self::validate_name(&name)?;
+        Ok(Self { owner, name })
+    }
+
+    pub fn owner(&self) -> &TypedKey {
+        &self.owner
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Check that a name can be registered
+    pub fn validate_name(name: &str) -> VeilidAPIResult<()> {
+        if name.is_empty() {
+            apibail_invalid_argument!("name must not be empty", "name", name);
+        }
+        if name.len() > Self::MAX_NAME_LENGTH {
+            apibail_invalid_argument!("name is too long", "name", name);
+        }
+        if name.chars().any(|c| c.is_control() || c.is_whitespace()) {
+            apibail_invalid_argument!(
+                "name must not contain whitespace or control characters",
+                "name",
+                name
+            );
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DHTNameReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}/{}", Self::URL_SCHEME, self.owner, self.name)
+    }
+}
+
+impl FromStr for DHTNameReference {
+    type Err = VeilidAPIError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(rest) = s.strip_prefix(Self::URL_SCHEME) else {
+            apibail_parse_error!("missing url scheme", s);
+        };
+        let Some((owner, name)) = rest.split_once('/') else {
+            apibail_parse_error!("missing name", s);
+        };
+        let owner = TypedKey::from_str(owner)?;
+        Self::new(owner, name.to_owned())
+    }
+}
@@ -21,6 +21,13 @@ pub struct DHTRecordDescriptor {
     owner_secret: Option<SecretKey>,
     /// The schema in use associated with the key
     schema: DHTSchema,
+    /// The version of the record, records that supersede another record have a higher version
+    #[serde(default)]
+    version: u32,
+    /// When the record expires and storage nodes stop serving it, if it expires at all
+    #[serde(default)]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    expiration: Option<Timestamp>,
 }
 from_impl_to_jsvalue!(DHTRecordDescriptor);
 
@@ -30,12 +37,16 @@ impl DHTRecordDescriptor {
         owner: PublicKey,
         owner_secret: Option<SecretKey>,
         schema: DHTSchema,
+        version: u32,
+        expiration: Option<Timestamp>,
     ) -> Self {
         Self {
             key,
             owner,
             owner_secret,
             schema,
+            version,
+            expiration,
         }
     }
 
@@ -53,4 +64,12 @@ impl DHTRecordDescriptor {
     pub fn schema(&self) -> &DHTSchema {
         &self.schema
     }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn expiration(&self) -> Option<Timestamp> {
+        self.expiration
+    }
 }
@@ -41,6 +41,28 @@ impl DHTRecordReport {
     pub fn network_seqs(&self) -> &[ValueSeqNum] {
         &self.network_seqs
     }
+
+    /// The subkeys for which the network reported a newer sequence number than the local copy
+    /// These are the stale subkeys a sync engine should fetch with GetValue
+    pub fn newer_network_subkeys(&self) -> ValueSubkeyRangeSet {
+        let mut newer = ValueSubkeyRangeSet::new();
+        for (n, (local_seq, network_seq)) in self
+            .local_seqs
+            .iter()
+            .zip(self.network_seqs.iter())
+            .enumerate()
+        {
+            if *network_seq == ValueSeqNum::MAX {
+                continue;
+            }
+            if *local_seq == ValueSeqNum::MAX || *network_seq > *local_seq {
+                if let Some(subkey) = self.subkeys.nth_subkey(n) {
+                    newer.insert(subkey);
+                }
+            }
+        }
+        newer
+    }
 }
 
 impl fmt::Debug for DHTRecordReport {
@@ -0,0 +1,65 @@
+use super::*;
+
+/// DHT Record Version Link
+///
+/// Added by the owner of a record to its descriptor to point at the record that supersedes it.
+/// The link is signed by the owner, so only the owner can move a record forward.
+#[derive(
+    Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct DHTRecordVersionLink {
+    /// The key of the superseding record
+    #[schemars(with = "String")]
+    key: TypedKey,
+    /// The descriptor version of the superseding record
+    version: u32,
+}
+
+impl DHTRecordVersionLink {
+    pub const FCC: [u8; 4] = *b"LINK";
+    pub const FIXED_SIZE: usize = 44;
+
+    pub fn new(key: TypedKey, version: u32) -> Self {
+        Self { key, version }
+    }
+
+    pub fn key(&self) -> &TypedKey {
+        &self.key
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Build the data representation of the link
+    pub fn compile(&self) -> Vec<u8> {
+        let mut out = Vec::<u8>::with_capacity(Self::FIXED_SIZE);
+        // kind
+        out.extend_from_slice(&Self::FCC);
+        // key
+        out.extend_from_slice(&self.key.kind.0);
+        out.extend_from_slice(&self.key.value.bytes);
+        // version
+        out.extend_from_slice(&self.version.to_le_bytes());
+        out
+    }
+}
+
+impl TryFrom<&[u8]> for DHTRecordVersionLink {
+    type Error = VeilidAPIError;
+    fn try_from(b: &[u8]) -> Result<Self, Self::Error> {
+        if b.len() != Self::FIXED_SIZE {
+            apibail_generic!("invalid size");
+        }
+        if b[0..4] != Self::FCC {
+            apibail_generic!("wrong fourcc");
+        }
+
+        let kind = FourCC::try_from(&b[4..8])?;
+        let value = PublicKey::try_from(&b[8..40])?;
+        let version = u32::from_le_bytes(b[40..44].try_into().map_err(VeilidAPIError::internal)?);
+
+        Ok(Self::new(TypedKey::new(kind, value), version))
+    }
+}
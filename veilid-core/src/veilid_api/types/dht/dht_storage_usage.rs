@@ -0,0 +1,24 @@
+use super::*;
+
+/// DHT Storage Usage
+///
+/// How much space the DHT record stores are using on this node
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify), tsify(into_wasm_abi))]
+pub struct DHTStorageUsage {
+    /// Number of records that were created or opened locally
+    pub local_record_count: u32,
+    /// Storage space used by records that were created or opened locally
+    pub local_used: ByteCount,
+    /// Number of records being stored on behalf of other nodes
+    pub remote_record_count: u32,
+    /// Storage space used by records being stored on behalf of other nodes
+    pub remote_used: ByteCount,
+    /// Storage space above which remote records start being evicted, least recently used first
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub remote_high_watermark: Option<ByteCount>,
+    /// Storage space beyond which new remote records and values are rejected
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub remote_limit: Option<ByteCount>,
+}
+from_impl_to_jsvalue!(DHTStorageUsage);
@@ -1,14 +1,24 @@
+mod dht_audit_log_entry;
+mod dht_name_reference;
 mod dht_record_descriptor;
 mod dht_record_report;
+mod dht_record_version_link;
+mod dht_storage_usage;
 mod schema;
+mod storage_manager_status;
 mod value_data;
 mod value_subkey_range_set;
 
 use super::*;
 
+pub use dht_audit_log_entry::*;
+pub use dht_name_reference::*;
 pub use dht_record_descriptor::*;
 pub use dht_record_report::*;
+pub use dht_record_version_link::*;
+pub use dht_storage_usage::*;
 pub use schema::*;
+pub use storage_manager_status::*;
 pub use value_data::*;
 pub use value_subkey_range_set::*;
 
@@ -0,0 +1,136 @@
+use super::*;
+
+/// Append-Only Log DHT Schema (ALOG)
+///
+/// Subkeys are log entries that must be written in order, starting at subkey 0.
+/// Once an entry has been written it can never be changed.
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify), tsify(from_wasm_abi))]
+pub struct DHTSchemaALOG {
+    /// Maximum number of log entries
+    max_len: u16,
+    /// Writers allowed to append entries in addition to the owner
+    #[schemars(with = "Vec<String>")]
+    writers: Vec<PublicKey>,
+}
+
+impl DHTSchemaALOG {
+    pub const FCC: [u8; 4] = *b"ALOG";
+    pub const FIXED_SIZE: usize = 6;
+
+    /// Make a schema
+    pub fn new(max_len: u16, writers: Vec<PublicKey>) -> VeilidAPIResult<Self> {
+        let out = Self { max_len, writers };
+        out.validate()?;
+        Ok(out)
+    }
+
+    /// Validate the data representation
+    pub fn validate(&self) -> VeilidAPIResult<()> {
+        if self.max_len == 0 {
+            apibail_invalid_argument!("must have at least one subkey", "max_len", self.max_len);
+        }
+        Ok(())
+    }
+
+    /// Get the maximum number of log entries
+    pub fn max_len(&self) -> u16 {
+        self.max_len
+    }
+
+    /// Get the writers of the schema
+    pub fn writers(&self) -> &[PublicKey] {
+        &self.writers
+    }
+
+    /// Build the data representation of the schema
+    pub fn compile(&self) -> Vec<u8> {
+        let mut out =
+            Vec::<u8>::with_capacity(Self::FIXED_SIZE + (self.writers.len() * PUBLIC_KEY_LENGTH));
+        // kind
+        out.extend_from_slice(&Self::FCC);
+        // max_len
+        out.extend_from_slice(&self.max_len.to_le_bytes());
+        // writers
+        for w in &self.writers {
+            out.extend_from_slice(&w.bytes);
+        }
+        out
+    }
+
+    /// Get the maximum subkey this schema allocates
+    pub fn max_subkey(&self) -> ValueSubkey {
+        (self.max_len - 1) as ValueSubkey
+    }
+
+    /// Get the data size of this schema beyond the size of the structure itself
+    pub fn data_size(&self) -> usize {
+        self.writers.len() * mem::size_of::<PublicKey>()
+    }
+
+    /// Check a subkey value data against the schema
+    ///
+    /// If the subkeys already written to the record are known, the subkey must also be the
+    /// first entry or directly follow an entry that has been written. Only the writer knows
+    /// this, so replicas accepting values from the network do not enforce the order.
+    pub fn check_subkey_value_data(
+        &self,
+        owner: &PublicKey,
+        subkey: ValueSubkey,
+        value_data: &ValueData,
+        written_subkeys: Option<&ValueSubkeyRangeSet>,
+    ) -> bool {
+        // Subkey out of range
+        if subkey > self.max_subkey() {
+            return false;
+        }
+
+        // Check value data has valid writer
+        if value_data.writer() != owner && !self.is_member(value_data.writer()) {
+            return false;
+        }
+
+        // Entries are written once and never updated, so no later sequence number is valid
+        if value_data.seq() != 0 {
+            return false;
+        }
+
+        // Entries must be appended in order
+        if let Some(written_subkeys) = written_subkeys {
+            if subkey > 0 && !written_subkeys.contains(subkey - 1) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Check if a key is a schema member
+    pub fn is_member(&self, key: &PublicKey) -> bool {
+        self.writers.contains(key)
+    }
+}
+
+impl TryFrom<&[u8]> for DHTSchemaALOG {
+    type Error = VeilidAPIError;
+    fn try_from(b: &[u8]) -> Result<Self, Self::Error> {
+        if b.len() < Self::FIXED_SIZE {
+            apibail_generic!("invalid size");
+        }
+        if b[0..4] != Self::FCC {
+            apibail_generic!("wrong fourcc");
+        }
+        if (b.len() - Self::FIXED_SIZE) % PUBLIC_KEY_LENGTH != 0 {
+            apibail_generic!("invalid writer length");
+        }
+
+        let max_len = u16::from_le_bytes(b[4..6].try_into().map_err(VeilidAPIError::internal)?);
+
+        let writers = b[Self::FIXED_SIZE..]
+            .chunks_exact(PUBLIC_KEY_LENGTH)
+            .map(|w| PublicKey::try_from(w).map_err(VeilidAPIError::internal))
+            .collect::<VeilidAPIResult<Vec<_>>>()?;
+
+        Self::new(max_len, writers)
+    }
+}
@@ -57,6 +57,7 @@ impl DHTSchemaDFLT {
         owner: &PublicKey,
         subkey: ValueSubkey,
         value_data: &ValueData,
+        _written_subkeys: Option<&ValueSubkeyRangeSet>,
     ) -> bool {
         let subkey = subkey as usize;
 
@@ -1,9 +1,13 @@
+mod alog;
 mod dflt;
+mod mult;
 mod smpl;
 
 use super::*;
 
+pub use alog::*;
 pub use dflt::*;
+pub use mult::*;
 pub use smpl::*;
 
 /// Enum over all the supported DHT Schemas
@@ -13,6 +17,8 @@ pub use smpl::*;
 pub enum DHTSchema {
     DFLT(DHTSchemaDFLT),
     SMPL(DHTSchemaSMPL),
+    ALOG(DHTSchemaALOG),
+    MULT(DHTSchemaMULT),
 }
 
 impl DHTSchema {
@@ -22,12 +28,22 @@ impl DHTSchema {
     pub fn smpl(o_cnt: u16, members: Vec<DHTSchemaSMPLMember>) -> VeilidAPIResult<DHTSchema> {
         Ok(DHTSchema::SMPL(DHTSchemaSMPL::new(o_cnt, members)?))
     }
+    pub fn alog(max_len: u16, writers: Vec<PublicKey>) -> VeilidAPIResult<DHTSchema> {
+        Ok(DHTSchema::ALOG(DHTSchemaALOG::new(max_len, writers)?))
+    }
+    pub fn mult(threshold: u8, owners: Vec<PublicKey>, o_cnt: u16) -> VeilidAPIResult<DHTSchema> {
+        Ok(DHTSchema::MULT(DHTSchemaMULT::new(
+            threshold, owners, o_cnt,
+        )?))
+    }
 
     /// Validate the data representation
     pub fn validate(&self) -> VeilidAPIResult<()> {
         match self {
             DHTSchema::DFLT(d) => d.validate(),
             DHTSchema::SMPL(s) => s.validate(),
+            DHTSchema::ALOG(a) => a.validate(),
+            DHTSchema::MULT(m) => m.validate(),
         }
     }
 
@@ -36,6 +52,8 @@ impl DHTSchema {
         match self {
             DHTSchema::DFLT(d) => d.compile(),
             DHTSchema::SMPL(s) => s.compile(),
+            DHTSchema::ALOG(a) => a.compile(),
+            DHTSchema::MULT(m) => m.compile(),
         }
     }
 
@@ -44,27 +62,55 @@ impl DHTSchema {
         match self {
             DHTSchema::DFLT(d) => d.max_subkey(),
             DHTSchema::SMPL(s) => s.max_subkey(),
+            DHTSchema::ALOG(a) => a.max_subkey(),
+            DHTSchema::MULT(m) => m.max_subkey(),
         }
     }
 
+    /// Check if writes to a subkey carry co-owner signatures over their data
+    ///
+    /// Values for these subkeys must not be compressed, or the signatures could not be checked.
+    pub fn is_cosigned_subkey(&self, subkey: ValueSubkey) -> bool {
+        matches!(self, DHTSchema::MULT(_)) && subkey == DHTSchemaMULT::CONTROL_SUBKEY
+    }
+
     /// Get the data size of this schema beyond the size of the structure itself
     pub fn data_size(&self) -> usize {
         match self {
             DHTSchema::DFLT(d) => d.data_size(),
             DHTSchema::SMPL(s) => s.data_size(),
+            DHTSchema::ALOG(a) => a.data_size(),
+            DHTSchema::MULT(m) => m.data_size(),
         }
     }
 
     /// Check a subkey value data against the schema
+    ///
+    /// Pass the subkeys already written to the record when writing a value locally, so that
+    /// schemas which restrict the order of writes can enforce it. Values received from the
+    /// network pass None, as replicas can not know which subkeys have been written. The record's crypto system
+    /// is used to verify co-owner signatures for schemas that require them.
     pub fn check_subkey_value_data(
         &self,
+        vcrypto: CryptoSystemVersion,
         owner: &PublicKey,
         subkey: ValueSubkey,
         value_data: &ValueData,
+        written_subkeys: Option<&ValueSubkeyRangeSet>,
     ) -> bool {
         match self {
-            DHTSchema::DFLT(d) => d.check_subkey_value_data(owner, subkey, value_data),
-            DHTSchema::SMPL(s) => s.check_subkey_value_data(owner, subkey, value_data),
+            DHTSchema::DFLT(d) => {
+                d.check_subkey_value_data(owner, subkey, value_data, written_subkeys)
+            }
+            DHTSchema::SMPL(s) => {
+                s.check_subkey_value_data(owner, subkey, value_data, written_subkeys)
+            }
+            DHTSchema::ALOG(a) => {
+                a.check_subkey_value_data(owner, subkey, value_data, written_subkeys)
+            }
+            DHTSchema::MULT(m) => {
+                m.check_subkey_value_data(vcrypto, owner, subkey, value_data, written_subkeys)
+            }
         }
     }
 
@@ -73,6 +119,8 @@ impl DHTSchema {
         match self {
             DHTSchema::DFLT(d) => d.is_member(key),
             DHTSchema::SMPL(s) => s.is_member(key),
+            DHTSchema::ALOG(a) => a.is_member(key),
+            DHTSchema::MULT(m) => m.is_member(key),
         }
     }
 
@@ -119,6 +167,8 @@ impl TryFrom<&[u8]> for DHTSchema {
         match fcc {
             DHTSchemaDFLT::FCC => Ok(DHTSchema::DFLT(DHTSchemaDFLT::try_from(b)?)),
             DHTSchemaSMPL::FCC => Ok(DHTSchema::SMPL(DHTSchemaSMPL::try_from(b)?)),
+            DHTSchemaALOG::FCC => Ok(DHTSchema::ALOG(DHTSchemaALOG::try_from(b)?)),
+            DHTSchemaMULT::FCC => Ok(DHTSchema::MULT(DHTSchemaMULT::try_from(b)?)),
             _ => {
                 apibail_generic!("unknown fourcc");
             }
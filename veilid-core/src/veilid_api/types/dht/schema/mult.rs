@@ -0,0 +1,285 @@
+use super::*;
+
+/// Co-owner approval of a control subkey value in a Multi-owner DHT Schema (MULT) record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DHTSchemaMULTCoSignature {
+    /// Index of the signing co-owner in the schema
+    pub index: u8,
+    /// Signature by the co-owner over the control signature data
+    pub signature: Signature,
+}
+
+/// Multi-owner DHT Schema (MULT)
+///
+/// The control subkey (subkey 0) can only be written with signatures from at least `threshold`
+/// of the co-owners, so no single co-owner has unilateral control over it. The remaining
+/// subkeys may be written by any co-owner.
+///
+/// The record owner keypair still signs the descriptor and the version link subkey, so it
+/// should be a throwaway keypair that is discarded once the record has been created.
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify), tsify(from_wasm_abi))]
+pub struct DHTSchemaMULT {
+    /// Number of co-owner signatures required to write the control subkey
+    threshold: u8,
+    /// Co-owner keys
+    #[schemars(with = "Vec<String>")]
+    owners: Vec<PublicKey>,
+    /// Number of subkeys after the control subkey that any co-owner may write
+    o_cnt: u16,
+}
+
+impl DHTSchemaMULT {
+    pub const FCC: [u8; 4] = *b"MULT";
+    pub const FIXED_SIZE: usize = 7;
+    pub const CONTROL_SUBKEY: ValueSubkey = 0;
+
+    /// Make a schema
+    pub fn new(threshold: u8, owners: Vec<PublicKey>, o_cnt: u16) -> VeilidAPIResult<Self> {
+        let out = Self {
+            threshold,
+            owners,
+            o_cnt,
+        };
+        out.validate()?;
+        Ok(out)
+    }
+
+    /// Validate the data representation
+    pub fn validate(&self) -> VeilidAPIResult<()> {
+        if self.owners.is_empty() {
+            apibail_invalid_argument!("must have at least one co-owner", "owners", 0);
+        }
+        if self.owners.len() > (u8::MAX as usize) {
+            apibail_invalid_argument!("too many co-owners", "owners", self.owners.len());
+        }
+        let mut sorted_owners = self.owners.clone();
+        sorted_owners.sort();
+        sorted_owners.dedup();
+        if sorted_owners.len() != self.owners.len() {
+            apibail_invalid_argument!("duplicate co-owner", "owners", self.owners.len());
+        }
+        if self.threshold == 0 || (self.threshold as usize) > self.owners.len() {
+            apibail_invalid_argument!(
+                "threshold must be between one and the number of co-owners",
+                "threshold",
+                self.threshold
+            );
+        }
+        if self.o_cnt == u16::MAX {
+            apibail_invalid_argument!("too many subkeys", "o_cnt", self.o_cnt);
+        }
+        Ok(())
+    }
+
+    /// Get the number of co-owner signatures required to write the control subkey
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Get the co-owners of the schema
+    pub fn owners(&self) -> &[PublicKey] {
+        &self.owners
+    }
+
+    /// Get the number of subkeys after the control subkey
+    pub fn o_cnt(&self) -> u16 {
+        self.o_cnt
+    }
+
+    /// Build the data representation of the schema
+    pub fn compile(&self) -> Vec<u8> {
+        let mut out =
+            Vec::<u8>::with_capacity(Self::FIXED_SIZE + (self.owners.len() * PUBLIC_KEY_LENGTH));
+        // kind
+        out.extend_from_slice(&Self::FCC);
+        // threshold
+        out.push(self.threshold);
+        // o_cnt
+        out.extend_from_slice(&self.o_cnt.to_le_bytes());
+        // owners
+        for o in &self.owners {
+            out.extend_from_slice(&o.bytes);
+        }
+        out
+    }
+
+    /// Get the maximum subkey this schema allocates
+    pub fn max_subkey(&self) -> ValueSubkey {
+        self.o_cnt as ValueSubkey
+    }
+
+    /// Get the data size of this schema beyond the size of the structure itself
+    pub fn data_size(&self) -> usize {
+        self.owners.len() * mem::size_of::<PublicKey>()
+    }
+
+    /// Get the data a co-owner signs to approve a control subkey payload
+    ///
+    /// The signature covers the record owner and schema so it can not be replayed on another
+    /// record, and the sequence number so it can not be replayed over a newer value.
+    pub fn control_signature_data(
+        &self,
+        owner: &PublicKey,
+        seq: ValueSeqNum,
+        payload: &[u8],
+    ) -> Vec<u8> {
+        let schema_data = self.compile();
+        let mut out = Vec::<u8>::with_capacity(
+            PUBLIC_KEY_LENGTH + schema_data.len() + mem::size_of::<ValueSeqNum>() + payload.len(),
+        );
+        out.extend_from_slice(&owner.bytes);
+        out.extend_from_slice(&schema_data);
+        out.extend_from_slice(&seq.to_le_bytes());
+        out.extend_from_slice(payload);
+        out
+    }
+
+    /// Sign a control subkey payload as one of the co-owners
+    pub fn cosign(
+        &self,
+        vcrypto: CryptoSystemVersion,
+        owner: &PublicKey,
+        seq: ValueSeqNum,
+        payload: &[u8],
+        co_owner: &KeyPair,
+    ) -> VeilidAPIResult<DHTSchemaMULTCoSignature> {
+        let Some(index) = self.owners.iter().position(|o| *o == co_owner.key) else {
+            apibail_invalid_argument!("not a co-owner", "co_owner", co_owner.key);
+        };
+        let signature = vcrypto.sign(
+            &co_owner.key,
+            &co_owner.secret,
+            &self.control_signature_data(owner, seq, payload),
+        )?;
+        Ok(DHTSchemaMULTCoSignature {
+            index: index as u8,
+            signature,
+        })
+    }
+
+    /// Build the control subkey data from a payload and the co-owner signatures approving it
+    pub fn encode_control_data(
+        cosignatures: &[DHTSchemaMULTCoSignature],
+        payload: &[u8],
+    ) -> VeilidAPIResult<Vec<u8>> {
+        if cosignatures.len() > (u8::MAX as usize) {
+            apibail_invalid_argument!("too many co-signatures", "cosignatures", cosignatures.len());
+        }
+        let mut out = Vec::<u8>::with_capacity(
+            1 + cosignatures.len() * (1 + SIGNATURE_LENGTH) + payload.len(),
+        );
+        out.push(cosignatures.len() as u8);
+        for cs in cosignatures {
+            out.push(cs.index);
+            out.extend_from_slice(&cs.signature.bytes);
+        }
+        out.extend_from_slice(payload);
+        Ok(out)
+    }
+
+    /// Split control subkey data into the co-owner signatures and the payload
+    pub fn decode_control_data(
+        data: &[u8],
+    ) -> VeilidAPIResult<(Vec<DHTSchemaMULTCoSignature>, &[u8])> {
+        let Some(count) = data.first() else {
+            apibail_generic!("invalid size");
+        };
+        let payload_start = 1 + (*count as usize) * (1 + SIGNATURE_LENGTH);
+        if data.len() < payload_start {
+            apibail_generic!("invalid size");
+        }
+        let mut cosignatures = Vec::with_capacity(*count as usize);
+        for n in 0..(*count as usize) {
+            let cstart = 1 + n * (1 + SIGNATURE_LENGTH);
+            let signature = Signature::try_from(&data[cstart + 1..cstart + 1 + SIGNATURE_LENGTH])
+                .map_err(VeilidAPIError::internal)?;
+            cosignatures.push(DHTSchemaMULTCoSignature {
+                index: data[cstart],
+                signature,
+            });
+        }
+        Ok((cosignatures, &data[payload_start..]))
+    }
+
+    /// Check a subkey value data against the schema
+    ///
+    /// The control subkey must carry valid signatures from at least `threshold` distinct co-owners.
+    pub fn check_subkey_value_data(
+        &self,
+        vcrypto: CryptoSystemVersion,
+        owner: &PublicKey,
+        subkey: ValueSubkey,
+        value_data: &ValueData,
+        _written_subkeys: Option<&ValueSubkeyRangeSet>,
+    ) -> bool {
+        // Subkey out of range
+        if subkey > self.max_subkey() {
+            return false;
+        }
+
+        // Check value data has valid writer
+        if !self.is_member(value_data.writer()) {
+            return false;
+        }
+
+        if subkey != Self::CONTROL_SUBKEY {
+            return true;
+        }
+
+        // Check the co-owner signatures on the control subkey
+        let Ok((cosignatures, payload)) = Self::decode_control_data(value_data.data()) else {
+            return false;
+        };
+        let signature_data = self.control_signature_data(owner, value_data.seq(), payload);
+        let mut signed = vec![false; self.owners.len()];
+        for cs in cosignatures {
+            let Some(co_owner) = self.owners.get(cs.index as usize) else {
+                return false;
+            };
+            // Each co-owner counts only once
+            if signed[cs.index as usize] {
+                return false;
+            }
+            if vcrypto
+                .verify(co_owner, &signature_data, &cs.signature)
+                .is_err()
+            {
+                return false;
+            }
+            signed[cs.index as usize] = true;
+        }
+
+        signed.iter().filter(|s| **s).count() >= (self.threshold as usize)
+    }
+
+    /// Check if a key is a schema member
+    pub fn is_member(&self, key: &PublicKey) -> bool {
+        self.owners.contains(key)
+    }
+}
+
+impl TryFrom<&[u8]> for DHTSchemaMULT {
+    type Error = VeilidAPIError;
+    fn try_from(b: &[u8]) -> Result<Self, Self::Error> {
+        if b.len() < Self::FIXED_SIZE {
+            apibail_generic!("invalid size");
+        }
+        if b[0..4] != Self::FCC {
+            apibail_generic!("wrong fourcc");
+        }
+        if (b.len() - Self::FIXED_SIZE) % PUBLIC_KEY_LENGTH != 0 {
+            apibail_generic!("invalid owner length");
+        }
+
+        let threshold = b[4];
+        let o_cnt = u16::from_le_bytes(b[5..7].try_into().map_err(VeilidAPIError::internal)?);
+
+        let owners = b[Self::FIXED_SIZE..]
+            .chunks_exact(PUBLIC_KEY_LENGTH)
+            .map(|o| PublicKey::try_from(o).map_err(VeilidAPIError::internal))
+            .collect::<VeilidAPIResult<Vec<_>>>()?;
+
+        Self::new(threshold, owners, o_cnt)
+    }
+}
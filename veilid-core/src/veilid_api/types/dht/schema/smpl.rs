@@ -97,6 +97,7 @@ impl DHTSchemaSMPL {
         owner: &PublicKey,
         subkey: ValueSubkey,
         value_data: &ValueData,
+        _written_subkeys: Option<&ValueSubkeyRangeSet>,
     ) -> bool {
         let mut cur_subkey = subkey as usize;
 
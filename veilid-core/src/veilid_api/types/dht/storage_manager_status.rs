@@ -0,0 +1,18 @@
+use super::*;
+
+/// Storage Manager Status
+///
+/// What the DHT storage manager is doing on behalf of the application, reported with attachment state updates
+/// so applications can tell when their writes have not reached the network yet
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct StorageManagerStatus {
+    /// Number of subkeys written while offline that are waiting to be sent to the network
+    pub pending_offline_writes: u32,
+    /// Number of records that are currently open
+    pub open_record_count: u32,
+    /// Storage space used by records that were created or opened locally
+    pub local_used: ByteCount,
+    /// Storage space used by records being stored on behalf of other nodes
+    pub remote_used: ByteCount,
+}
@@ -2,6 +2,9 @@ mod aligned_u64;
 mod app_message_call;
 mod dht;
 mod fourcc;
+mod network_cost;
+mod network_time;
+mod receipt;
 mod safety;
 mod stats;
 #[cfg(feature = "unstable-tunnels")]
@@ -15,6 +18,9 @@ pub use aligned_u64::*;
 pub use app_message_call::*;
 pub use dht::*;
 pub use fourcc::*;
+pub use network_cost::*;
+pub use network_time::*;
+pub use receipt::*;
 pub use safety::*;
 pub use stats::*;
 #[cfg(feature = "unstable-tunnels")]
@@ -0,0 +1,33 @@
+use super::*;
+
+/// What the platform reports about the cost of the network we are currently using
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(from_wasm_abi, into_wasm_abi)
+)]
+pub struct NetworkCost {
+    /// The network charges by the amount of data used, or has a data cap
+    pub metered: bool,
+    /// The network is provided by someone other than our home carrier
+    pub roaming: bool,
+}
+
+impl NetworkCost {
+    /// The strictest of the configured policies that apply to this network
+    pub fn effective_policy(
+        &self,
+        metered_policy: VeilidConfigNetworkCostPolicy,
+        roaming_policy: VeilidConfigNetworkCostPolicy,
+    ) -> VeilidConfigNetworkCostPolicy {
+        let mut policy = VeilidConfigNetworkCostPolicy::Normal;
+        if self.metered {
+            policy = policy.max(metered_policy);
+        }
+        if self.roaming {
+            policy = policy.max(roaming_policy);
+        }
+        policy
+    }
+}
@@ -0,0 +1,31 @@
+use super::*;
+
+/// The current time as the network sees it, estimated from the clocks of our peers
+///
+/// Applications that timestamp the data they store can use this instead of the local clock,
+/// so that devices with a badly set clock still agree with everyone else.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(from_wasm_abi, into_wasm_abi)
+)]
+pub struct NetworkTime {
+    /// The estimated current network time
+    pub timestamp: Timestamp,
+    /// Microseconds our clock is behind the network, negative if it is ahead
+    pub clock_skew: i64,
+    /// The number of peers whose clocks the estimate is the median of
+    pub peer_count: u32,
+}
+
+impl NetworkTime {
+    /// Shift a local timestamp by the median clock skew of our peers
+    pub fn new(local_ts: Timestamp, clock_skew: i64, peer_count: u32) -> Self {
+        Self {
+            timestamp: Timestamp::new(local_ts.as_u64().saturating_add_signed(clock_skew)),
+            clock_skew,
+            peer_count,
+        }
+    }
+}
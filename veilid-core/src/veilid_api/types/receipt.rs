@@ -0,0 +1,48 @@
+use super::*;
+
+/// A receipt made by this node with [VeilidAPI::make_receipt] that has been returned to it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(from_wasm_abi, into_wasm_abi)
+)]
+pub struct VeilidReceipt {
+    /// The random nonce that uniquely identifies the receipt
+    #[schemars(with = "String")]
+    nonce: Nonce,
+    /// The node id that made and signed the receipt
+    #[schemars(with = "String")]
+    sender_id: TypedKey,
+    /// The extra data the receipt was made with
+    #[cfg_attr(not(target_arch = "wasm32"), serde(with = "as_human_base64"))]
+    #[schemars(with = "String")]
+    #[cfg_attr(
+        target_arch = "wasm32",
+        serde(with = "serde_bytes"),
+        tsify(type = "Uint8Array")
+    )]
+    extra_data: Vec<u8>,
+}
+
+impl VeilidReceipt {
+    pub fn new(nonce: Nonce, sender_id: TypedKey, extra_data: Vec<u8>) -> Self {
+        Self {
+            nonce,
+            sender_id,
+            extra_data,
+        }
+    }
+
+    pub fn nonce(&self) -> &Nonce {
+        &self.nonce
+    }
+
+    pub fn sender_id(&self) -> &TypedKey {
+        &self.sender_id
+    }
+
+    pub fn extra_data(&self) -> &[u8] {
+        &self.extra_data
+    }
+}
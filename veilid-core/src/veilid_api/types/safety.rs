@@ -61,6 +61,18 @@ pub enum SafetySelection {
 }
 
 impl SafetySelection {
+    /// Use a safety route chosen by a [SafetyProfile] instead of a hand picked hop count
+    pub fn with_profile(profile: SafetyProfile, sequencing: Sequencing) -> Self {
+        Self::Safe(SafetySpec {
+            preferred_route: None,
+            hop_count: profile.hop_count(),
+            stability: profile.stability(),
+            sequencing,
+            preferred_first_hop: None,
+            profile: Some(profile),
+        })
+    }
+
     pub fn get_sequencing(&self) -> Sequencing {
         match self {
             SafetySelection::Unsafe(seq) => *seq,
@@ -91,4 +103,88 @@ pub struct SafetySpec {
     pub stability: Stability,
     /// prefer connection-oriented sequenced protocols
     pub sequencing: Sequencing,
+    /// preferred first hop node for the safety route if a route through it can be used
+    #[schemars(with = "Option<String>")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional, type = "string"))]
+    pub preferred_first_hop: Option<TypedKey>,
+    /// safety profile that picks the hop count and stability, and adds network diversity and route rotation requirements
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub profile: Option<SafetyProfile>,
+}
+
+/// Safety presets for routes, so applications do not need to choose hop counts themselves
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(from_wasm_abi, into_wasm_abi, namespace)
+)]
+pub enum SafetyProfile {
+    /// Short low latency routes that are kept as long as they work
+    Fast = 0,
+    /// Reliable routes with hops on different networks that are rotated hourly
+    Balanced = 1,
+    /// Longer reliable routes with hops on different networks that are rotated frequently
+    Paranoid = 2,
+}
+
+impl SafetyProfile {
+    /// Number of hops in the safety route, limited to the configured maximum route hop count
+    pub fn hop_count(&self) -> usize {
+        match self {
+            SafetyProfile::Fast => 1,
+            SafetyProfile::Balanced => 2,
+            SafetyProfile::Paranoid => 3,
+        }
+    }
+
+    pub fn stability(&self) -> Stability {
+        match self {
+            SafetyProfile::Fast => Stability::LowLatency,
+            SafetyProfile::Balanced | SafetyProfile::Paranoid => Stability::Reliable,
+        }
+    }
+
+    /// Whether each hop of the route must be on a different network from the other hops and from us
+    pub fn requires_diverse_networks(&self) -> bool {
+        !matches!(self, SafetyProfile::Fast)
+    }
+
+    /// How long a route can be used before a new one is chosen, if routes are rotated
+    pub fn route_rotation_interval(&self) -> Option<TimestampDuration> {
+        match self {
+            SafetyProfile::Fast => None,
+            SafetyProfile::Balanced => Some(TimestampDuration::new(3_600_000_000u64)),
+            SafetyProfile::Paranoid => Some(TimestampDuration::new(600_000_000u64)),
+        }
+    }
+}
+
+/// Schedule for replacing one of our published private routes with a new one, so it does not stay linkable for too long
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify), tsify(from_wasm_abi))]
+pub struct PrivateRouteRotation {
+    /// how long the route is used before it is replaced
+    pub interval_ms: u32,
+    /// how long the old route keeps working after its replacement is published
+    pub overlap_ms: u32,
+    /// DHT record to write the replacement route blob to, which must be open with a writer
+    #[schemars(with = "Option<String>")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional, type = "string"))]
+    pub record: Option<TypedKey>,
+    /// subkey of the DHT record to write the replacement route blob to
+    pub subkey: ValueSubkey,
+}
+
+/// Outcome of testing one of our own private routes with [RoutingContext::test_private_route]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify), tsify(into_wasm_abi))]
+pub struct PrivateRouteTestResult {
+    /// whether the loopback question made it through the route and back
+    pub success: bool,
+    /// measured round trip time of the loopback question if it succeeded
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub latency: Option<TimestampDuration>,
 }
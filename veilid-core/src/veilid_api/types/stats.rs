@@ -24,6 +24,39 @@ pub struct TransferStatsDownUp {
     pub up: TransferStats,
 }
 
+/// Transfer statistics over rolling windows of different durations
+///
+/// The total of each window is the number of bytes transferred within it and its rates are taken
+/// over the intervals the window is divided into, so windows can be compared across peers.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct TransferStatsWindows {
+    pub minute: TransferStatsDownUp, // last minute, with rates over one second intervals
+    pub hour: TransferStatsDownUp,   // last hour, with rates over one minute intervals
+    pub day: TransferStatsDownUp,    // last day, with rates over one hour intervals
+}
+
+/// Low-level network transfer statistics for one routing domain and protocol
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct DomainTransferStats {
+    pub routing_domain: RoutingDomain,
+    pub protocol_type: ProtocolType,
+    pub transfer_stats: TransferStatsDownUp,
+}
+
+/// How busy the RPC processor is and how much work it has turned away
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct RPCLoadStats {
+    pub queue_depth: u32, // number of rpcs waiting to be processed
+    pub queue_size: u32,  // number of rpcs that can wait before new ones are dropped
+    pub average_queue_time: TimestampDuration, // average time rpcs wait before they are processed
+    pub average_processing_time: TimestampDuration, // average time taken to process an rpc
+    pub shed_questions: u32, // number of questions answered with 'busy' because we were overloaded
+    pub dropped_messages: u32, // number of rpcs dropped because the queue was full
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(target_arch = "wasm32", derive(Tsify))]
 pub struct RPCStats {
@@ -44,4 +77,82 @@ pub struct PeerStats {
     pub rpc_stats: RPCStats,   // information about RPCs
     pub latency: Option<LatencyStats>, // latencies for communications with the peer
     pub transfer: TransferStatsDownUp, // Stats for communications with the peer
+    #[serde(default)]
+    pub transfer_windows: TransferStatsWindows, // Stats for communications with the peer over the last minute, hour and day
+    #[serde(default)]
+    pub clock_skew: Option<i64>, // microseconds the peer's clock is estimated to be ahead of ours
+    #[serde(default)]
+    pub bandwidth: Option<ByteCount>, // bytes per second the link with the peer has been seen to carry
+}
+
+/// Entry counts for one routing table bucket, broken down by entry state
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct BucketStats {
+    pub index: u16, // bucket index, the number of leading bits node ids in the bucket share with ours
+    pub reliable_entry_count: u32,
+    pub unreliable_entry_count: u32,
+    pub dead_entry_count: u32,
+    pub average_entry_age: TimestampDuration, // average time since the entries were added to the routing table
+}
+
+/// Bucket statistics for the routing table of one crypto kind
+///
+/// Entries pile up in the near buckets when our keyspace neighborhood is being flooded,
+/// which is summarized by `near_bucket_skew`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct BucketStatistics {
+    #[schemars(with = "String")]
+    pub kind: CryptoKind,
+    pub near_bucket_skew: u32, // entries closer to our node id than a uniform keyspace distribution would place them
+    pub buckets: Vec<BucketStats>,
+}
+
+/// Messages we failed to pass along to one destination while relaying or routing for other nodes
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct RelayDeadLetter {
+    #[schemars(with = "String")]
+    pub destination: TypedKey, // the node we could not deliver to
+    pub failure_count: u32, // number of messages dropped since the destination was first seen failing
+    pub first_failure_ts: Timestamp, // when the first message was dropped
+    pub last_failure_ts: Timestamp, // when the most recent message was dropped
+    pub last_error: String, // why the most recent message could not be delivered
+}
+
+/// Traffic we relayed for one client node, whose envelopes we pass along to other nodes
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct RelayClientStats {
+    #[schemars(with = "String")]
+    pub client: TypedKey, // the node that sent the envelopes
+    pub relayed_bytes: ByteCount, // bytes of envelopes passed along
+    pub dropped_bytes: ByteCount, // bytes of envelopes dropped for going over the client's limits
+    pub queued_bytes: ByteCount,  // bytes of envelopes waiting to be passed along
+}
+
+/// Traffic exchanged with one peer over the counting window of [TopTalkers]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct TopTalker {
+    #[schemars(with = "Vec<String>")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string[]"))]
+    pub node_ids: Vec<TypedKey>,
+    pub bytes_down: ByteCount, // bytes of RPC messages received from the peer
+    pub bytes_up: ByteCount,   // bytes of RPC messages sent to the peer
+    pub rpc_messages_rcvd: u64,
+    pub rpc_messages_sent: u64,
+}
+
+/// The peers we exchanged the most traffic with over the last hour, or since the counts were reset
+///
+/// Peers are ranked separately by bytes and by RPC message count, in both directions combined,
+/// so a peer that sends many small messages shows up even if it moves few bytes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct TopTalkers {
+    pub since: Timestamp, // start of the counting window
+    pub by_bytes: Vec<TopTalker>,
+    pub by_rpc_count: Vec<TopTalker>,
 }
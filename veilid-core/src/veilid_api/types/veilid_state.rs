@@ -58,6 +58,31 @@ pub struct VeilidStateAttachment {
     pub state: AttachmentState,
     pub public_internet_ready: bool,
     pub local_network_ready: bool,
+    /// Pending offline writes, open records and storage space used by the DHT storage manager
+    #[serde(default)]
+    pub storage: StorageManagerStatus,
+    /// Routing table health broken out for each crypto kind we support
+    #[serde(default)]
+    pub crypto_kinds: Vec<CryptoKindHealth>,
+}
+
+/// Routing table health for one crypto kind
+///
+/// Peers only count for the crypto kinds they have node ids for, so a node that supports a newer kind
+/// can be well attached while having too few peers of that kind to look anything up with it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct CryptoKindHealth {
+    #[schemars(with = "String")]
+    pub kind: CryptoKind,
+    /// Number of reliable (long-term responsive) entries with a node id of this kind
+    pub reliable_entry_count: u32,
+    /// Number of unreliable (occasionally unresponsive) entries with a node id of this kind
+    pub unreliable_entry_count: u32,
+    /// Number of dead (always unresponsive) entries with a node id of this kind
+    pub dead_entry_count: u32,
+    /// If there are enough live entries of this kind to perform lookups with it
+    pub viable: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -77,6 +102,53 @@ pub struct VeilidStateNetwork {
     pub bps_down: ByteCount,
     pub bps_up: ByteCount,
     pub peers: Vec<PeerTableData>,
+    /// Transfer totals and rates broken down by routing domain and protocol
+    pub transfer_stats: Vec<DomainTransferStats>,
+    /// Estimated microseconds the network's clocks are ahead of ours, if any peer has reported one
+    #[serde(default)]
+    pub clock_skew: Option<i64>,
+    /// Our clock is further off from the network than 'clock_skew_warning_ms', the system time should be checked
+    #[serde(default)]
+    pub clock_skew_warning: bool,
+    /// How starting the inbound listener for each protocol went
+    #[serde(default)]
+    pub listeners: Vec<ListenerStatus>,
+    /// How busy our RPC processing is and how much work it has turned away
+    #[serde(default)]
+    pub rpc_load: RPCLoadStats,
+    /// Transfer totals and rates for all of our traffic over the last minute, hour and day
+    #[serde(default)]
+    pub transfer_windows: TransferStatsWindows,
+}
+
+/// The state of the inbound listener for one protocol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct ListenerStatus {
+    pub protocol_type: ProtocolType,
+    /// Addresses the listener is bound to, empty if it is not running
+    pub bound_addresses: Vec<String>,
+    /// Why the listener failed to start, if it did
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub last_error: Option<String>,
+    /// When the TLS certificate served by the listener expires, only reported for WSS
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub certificate_expiration: Option<Timestamp>,
+}
+
+/// A report from a peer of the address it saw our traffic come from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct PublicAddressObservation {
+    /// The node that reported the address
+    #[schemars(with = "String")]
+    pub reporting_node_id: TypedKey,
+    /// The address the report came from
+    pub reporting_address: String,
+    /// The address the peer says it saw us at
+    pub observed_address: String,
+    pub protocol_type: ProtocolType,
+    pub timestamp: Timestamp,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -86,6 +158,28 @@ pub struct VeilidRouteChange {
     pub dead_routes: Vec<RouteId>,
     #[schemars(with = "Vec<String>")]
     pub dead_remote_routes: Vec<RouteId>,
+    pub rotated_routes: Vec<VeilidRouteRotation>,
+}
+
+/// A published private route that was replaced by its rotation schedule
+///
+/// The old route keeps working until the overlap window of the schedule ends and is then reported as dead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VeilidRouteRotation {
+    #[schemars(with = "String")]
+    pub old_route: RouteId,
+    #[schemars(with = "String")]
+    pub new_route: RouteId,
+    /// The private route blob of the new route, to publish in place of the old one
+    #[cfg_attr(not(target_arch = "wasm32"), serde(with = "as_human_base64"))]
+    #[schemars(with = "String")]
+    #[cfg_attr(
+        target_arch = "wasm32",
+        serde(with = "serde_bytes"),
+        tsify(type = "Uint8Array")
+    )]
+    pub blob: Vec<u8>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -102,6 +196,34 @@ pub struct VeilidValueChange {
     pub subkeys: ValueSubkeyRangeSet,
     pub count: u32,
     pub value: Option<ValueData>,
+    /// Set if sequence numbers were skipped since the last value we knew of for the subkey
+    #[serde(default)]
+    pub gap: bool,
+}
+
+/// Identity keys that were generated at startup, because they did not exist yet or could not be loaded
+///
+/// A new node id makes this node a different node to the rest of the network, and a new device
+/// encryption key makes tables encrypted with the previous one unreadable, so embedders that did not
+/// expect either may want to raise it with the user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VeilidIdentityChange {
+    /// The node ids that were generated, one per crypto kind
+    #[schemars(with = "Vec<String>")]
+    pub new_node_ids: Vec<TypedKey>,
+    /// Set if a new device encryption key was generated
+    pub new_device_encryption_key: bool,
+}
+
+/// Remote records that were evicted because remote record storage passed its high watermark
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VeilidStorageEviction {
+    /// Number of remote records that were evicted, least recently used first
+    pub evicted_record_count: u32,
+    /// Storage space the evicted records free up
+    pub evicted_space: ByteCount,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -116,6 +238,8 @@ pub enum VeilidUpdate {
     Config(Box<VeilidStateConfig>),
     RouteChange(Box<VeilidRouteChange>),
     ValueChange(Box<VeilidValueChange>),
+    IdentityChange(Box<VeilidIdentityChange>),
+    StorageEviction(Box<VeilidStorageEviction>),
     Shutdown,
 }
 from_impl_to_jsvalue!(VeilidUpdate);
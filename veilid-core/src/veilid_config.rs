@@ -28,6 +28,7 @@ pub type ConfigCallback = Arc<dyn Fn(String) -> ConfigCallbackReturn + Send + Sy
 ///     listen_address: ':5150'
 ///     path: 'app'
 ///     url: 'https://localhost:5150'
+///     directory: '/var/lib/veilid/www'
 /// ```
 ///
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -38,6 +39,10 @@ pub struct VeilidConfigHTTPS {
     pub path: String,
     #[cfg_attr(target_arch = "wasm32", tsify(optional))]
     pub url: Option<String>, // Fixed URL is not optional for TLS-based protocols and is dynamically validated
+    /// Directory of static files served under the path, if any
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    #[serde(default)]
+    pub directory: Option<String>,
 }
 
 impl Default for VeilidConfigHTTPS {
@@ -47,6 +52,7 @@ impl Default for VeilidConfigHTTPS {
             listen_address: String::from(""),
             path: String::from("app"),
             url: None,
+            directory: None,
         }
     }
 }
@@ -59,6 +65,7 @@ impl Default for VeilidConfigHTTPS {
 ///     listen_address: ':5150'
 ///     path: 'app"
 ///     url: 'https://localhost:5150'
+///     directory: '/var/lib/veilid/www'
 /// ```
 ///
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -69,6 +76,10 @@ pub struct VeilidConfigHTTP {
     pub path: String,
     #[cfg_attr(target_arch = "wasm32", tsify(optional))]
     pub url: Option<String>,
+    /// Directory of static files served under the path, if any
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    #[serde(default)]
+    pub directory: Option<String>,
 }
 
 impl Default for VeilidConfigHTTP {
@@ -78,15 +89,15 @@ impl Default for VeilidConfigHTTP {
             listen_address: String::from(""),
             path: String::from("app"),
             url: None,
+            directory: None,
         }
     }
 }
 
 /// Application configuration
 ///
-/// Configure web access to the Progressive Web App (PWA)
-///
-/// To be implemented...
+/// Serve a landing page from a directory of static files, and a health check at `<path>/health`
+/// returning the node's status as JSON, on the same listeners as WS and WSS
 ///
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(target_arch = "wasm32", derive(Tsify))]
@@ -103,6 +114,7 @@ pub struct VeilidConfigApplication {
 ///     socket_pool_size: 0
 ///     listen_address: ':5150'
 ///     public_address: ''
+///     preference: 0
 /// ```
 ///
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -113,6 +125,8 @@ pub struct VeilidConfigUDP {
     pub listen_address: String,
     #[cfg_attr(target_arch = "wasm32", tsify(optional))]
     pub public_address: Option<String>,
+    #[serde(default)]
+    pub preference: u8,
 }
 
 impl Default for VeilidConfigUDP {
@@ -129,6 +143,7 @@ impl Default for VeilidConfigUDP {
             socket_pool_size: 0,
             listen_address: String::from(""),
             public_address: None,
+            preference: 0,
         }
     }
 }
@@ -142,6 +157,7 @@ impl Default for VeilidConfigUDP {
 ///     max_connections: 32
 ///     listen_address: ':5150'
 ///     public_address: ''
+///     preference: 0
 ///
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(target_arch = "wasm32", derive(Tsify))]
@@ -152,6 +168,8 @@ pub struct VeilidConfigTCP {
     pub listen_address: String,
     #[cfg_attr(target_arch = "wasm32", tsify(optional))]
     pub public_address: Option<String>,
+    #[serde(default)]
+    pub preference: u8,
 }
 
 impl Default for VeilidConfigTCP {
@@ -171,6 +189,7 @@ impl Default for VeilidConfigTCP {
             max_connections: 32,
             listen_address: String::from(""),
             public_address: None,
+            preference: 0,
         }
     }
 }
@@ -185,6 +204,7 @@ impl Default for VeilidConfigTCP {
 ///     listen_address: ':5150'
 ///     path: 'ws'
 ///     url: 'ws://localhost:5150/ws'
+///     preference: 0
 ///
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(target_arch = "wasm32", derive(Tsify))]
@@ -197,6 +217,8 @@ pub struct VeilidConfigWS {
     pub path: String,
     #[cfg_attr(target_arch = "wasm32", tsify(optional))]
     pub url: Option<String>,
+    #[serde(default)]
+    pub preference: u8,
 }
 
 impl Default for VeilidConfigWS {
@@ -217,6 +239,7 @@ impl Default for VeilidConfigWS {
             listen_address: String::from(""),
             path: String::from("ws"),
             url: None,
+            preference: 0,
         }
     }
 }
@@ -231,6 +254,7 @@ impl Default for VeilidConfigWS {
 ///     listen_address: ':5150'
 ///     path: 'ws'
 ///     url: ''
+///     preference: 0
 ///
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(target_arch = "wasm32", derive(Tsify))]
@@ -243,6 +267,8 @@ pub struct VeilidConfigWSS {
     pub path: String,
     #[cfg_attr(target_arch = "wasm32", tsify(optional))]
     pub url: Option<String>, // Fixed URL is not optional for TLS-based protocols and is dynamically validated
+    #[serde(default)]
+    pub preference: u8,
 }
 
 impl Default for VeilidConfigWSS {
@@ -254,17 +280,53 @@ impl Default for VeilidConfigWSS {
             listen_address: String::from(""),
             path: String::from("ws"),
             url: None,
+            preference: 0,
+        }
+    }
+}
+
+/// Enable and configure IPC between Veilid processes on the same host
+///
+/// IPC uses a unix domain socket, and is only offered to and used with peers in the local
+/// network routing domain that are running on the same machine.
+///
+/// ```yaml
+/// ipc:
+///     connect: true
+///     listen: false
+///     listen_path: '/run/veilid/node.sock'
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+
+pub struct VeilidConfigIPC {
+    pub connect: bool,
+    pub listen: bool,
+    pub listen_path: String,
+}
+
+impl Default for VeilidConfigIPC {
+    fn default() -> Self {
+        Self {
+            connect: true,
+            listen: false,
+            listen_path: String::from(""),
         }
     }
 }
 
 /// Configure Network Protocols
 ///
-/// Veilid can communicate over UDP, TCP, and Web Sockets.
+/// Veilid can communicate over UDP, TCP, and Web Sockets, and over IPC with
+/// other Veilid processes on the same host.
 ///
 /// All protocols are available by default, and the Veilid node will
 /// sort out which protocol is used for each peer connection.
 ///
+/// Each protocol's `preference` is advertised along with the dial info we listen on.
+/// Peers that can reach us over more than one protocol try the one with the higher
+/// preference first, so it can be used to steer traffic to a preferred listener.
+///
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(target_arch = "wasm32", derive(Tsify))]
 
@@ -273,6 +335,8 @@ pub struct VeilidConfigProtocol {
     pub tcp: VeilidConfigTCP,
     pub ws: VeilidConfigWS,
     pub wss: VeilidConfigWSS,
+    #[serde(default)]
+    pub ipc: VeilidConfigIPC,
 }
 
 /// Configure TLS
@@ -303,6 +367,37 @@ impl Default for VeilidConfigTLS {
     }
 }
 
+/// Configure routing connections through Tor
+///
+/// When enabled, outbound TCP and WS/WSS connections are dialed through the local Tor SOCKS port,
+/// UDP is disabled, and our IP addresses are not published. An onion service that forwards to our
+/// TCP listener can be published as dial info so other Tor-enabled nodes can reach us.
+///
+/// ```yaml
+/// tor:
+///     enabled: false
+///     socks_address: '127.0.0.1:9050'
+///     onion_address: ''
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VeilidConfigTor {
+    pub enabled: bool,
+    pub socks_address: String,
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub onion_address: Option<String>,
+}
+
+impl Default for VeilidConfigTor {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            socks_address: String::from("127.0.0.1:9050"),
+            onion_address: None,
+        }
+    }
+}
+
 #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
 pub fn get_default_ssl_directory(sub_path: &str) -> String {
     cfg_if::cfg_if! {
@@ -341,6 +436,11 @@ pub struct VeilidConfigDHT {
     pub set_value_timeout_ms: u32,
     pub set_value_count: u32,
     pub set_value_fanout: u32,
+    /// Fanouts reduce their concurrency toward this as the rpc queue fills, so our own
+    /// lookups and stores don't crowd out answering other nodes. Set it as high as the
+    /// fanouts to always run them at full concurrency.
+    #[serde(default = "default_min_fanout_under_load")]
+    pub min_fanout_under_load: u32,
     pub min_peer_count: u32,
     pub min_peer_refresh_time_ms: u32,
     pub validate_dial_info_receipt_time_ms: u32,
@@ -350,9 +450,29 @@ pub struct VeilidConfigDHT {
     pub remote_max_records: u32,
     pub remote_max_subkey_cache_memory_mb: u32,
     pub remote_max_storage_space_mb: u32,
+    #[serde(default = "default_remote_storage_high_watermark_percent")]
+    pub remote_storage_high_watermark_percent: u8,
+    #[serde(default = "default_remote_storage_low_watermark_percent")]
+    pub remote_storage_low_watermark_percent: u8,
     pub public_watch_limit: u32,
     pub member_watch_limit: u32,
     pub max_watch_expiration_ms: u32,
+    #[serde(default)]
+    pub set_value_pow_difficulty: u32,
+    #[serde(default)]
+    pub compress_values: bool,
+    #[serde(default)]
+    pub audit_log_max_entries: u32,
+}
+
+fn default_min_fanout_under_load() -> u32 {
+    1
+}
+fn default_remote_storage_high_watermark_percent() -> u8 {
+    90
+}
+fn default_remote_storage_low_watermark_percent() -> u8 {
+    75
 }
 
 impl Default for VeilidConfigDHT {
@@ -394,6 +514,7 @@ impl Default for VeilidConfigDHT {
             set_value_timeout_ms: 10000,
             set_value_count: 5,
             set_value_fanout: 4,
+            min_fanout_under_load: default_min_fanout_under_load(),
             min_peer_count: 20,
             min_peer_refresh_time_ms: 60000,
             validate_dial_info_receipt_time_ms: 2000,
@@ -403,9 +524,14 @@ impl Default for VeilidConfigDHT {
             remote_max_records,
             remote_max_subkey_cache_memory_mb,
             remote_max_storage_space_mb,
+            remote_storage_high_watermark_percent: default_remote_storage_high_watermark_percent(),
+            remote_storage_low_watermark_percent: default_remote_storage_low_watermark_percent(),
             public_watch_limit: 32,
             member_watch_limit: 8,
             max_watch_expiration_ms: 600000,
+            set_value_pow_difficulty: 0,
+            compress_values: false,
+            audit_log_max_entries: 0,
         }
     }
 }
@@ -421,9 +547,62 @@ pub struct VeilidConfigRPC {
     pub max_timestamp_behind_ms: Option<u32>,
     #[cfg_attr(target_arch = "wasm32", tsify(optional))]
     pub max_timestamp_ahead_ms: Option<u32>,
+    #[serde(default = "default_max_clock_skew_ms")]
+    pub max_clock_skew_ms: u32,
+    #[serde(default = "default_clock_skew_warning_ms")]
+    pub clock_skew_warning_ms: u32,
     pub timeout_ms: u32,
     pub max_route_hop_count: u8,
     pub default_route_hop_count: u8,
+    #[serde(default = "default_max_private_routes")]
+    pub max_private_routes: u32,
+    #[serde(default = "default_max_private_routes_per_api")]
+    pub max_private_routes_per_api: u32,
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    #[serde(default)]
+    pub private_route_idle_release_ms: Option<u32>,
+    #[serde(default = "default_max_app_message_size")]
+    pub max_app_message_size: u32,
+    #[serde(default = "default_max_app_call_size")]
+    pub max_app_call_size: u32,
+    #[serde(default = "default_max_app_message_frequency_per_min")]
+    pub max_app_message_frequency_per_min: u32,
+    #[serde(default = "default_max_app_call_frequency_per_min")]
+    pub max_app_call_frequency_per_min: u32,
+    #[serde(default)]
+    pub strict_udp_source: bool,
+    #[serde(default = "default_strict_udp_source_allow_rebind")]
+    pub strict_udp_source_allow_rebind: bool,
+    #[serde(default)]
+    pub route_pow_difficulty: u32,
+}
+
+fn default_max_clock_skew_ms() -> u32 {
+    60000
+}
+fn default_clock_skew_warning_ms() -> u32 {
+    5000
+}
+fn default_max_private_routes() -> u32 {
+    128
+}
+fn default_max_private_routes_per_api() -> u32 {
+    32
+}
+fn default_max_app_message_size() -> u32 {
+    32768
+}
+fn default_max_app_call_size() -> u32 {
+    32768
+}
+fn default_max_app_message_frequency_per_min() -> u32 {
+    1024
+}
+fn default_max_app_call_frequency_per_min() -> u32 {
+    256
+}
+fn default_strict_udp_source_allow_rebind() -> bool {
+    true
 }
 
 impl Default for VeilidConfigRPC {
@@ -433,9 +612,21 @@ impl Default for VeilidConfigRPC {
             queue_size: 1024,
             max_timestamp_behind_ms: Some(10000),
             max_timestamp_ahead_ms: Some(10000),
+            max_clock_skew_ms: default_max_clock_skew_ms(),
+            clock_skew_warning_ms: default_clock_skew_warning_ms(),
             timeout_ms: 5000,
             max_route_hop_count: 4,
             default_route_hop_count: 1,
+            max_private_routes: default_max_private_routes(),
+            max_private_routes_per_api: default_max_private_routes_per_api(),
+            private_route_idle_release_ms: None,
+            max_app_message_size: default_max_app_message_size(),
+            max_app_call_size: default_max_app_call_size(),
+            max_app_message_frequency_per_min: default_max_app_message_frequency_per_min(),
+            max_app_call_frequency_per_min: default_max_app_call_frequency_per_min(),
+            strict_udp_source: false,
+            strict_udp_source_allow_rebind: default_strict_udp_source_allow_rebind(),
+            route_pow_difficulty: 0,
         }
     }
 }
@@ -455,10 +646,21 @@ pub struct VeilidConfigRoutingTable {
     pub limit_attached_strong: u32,
     pub limit_attached_good: u32,
     pub limit_attached_weak: u32,
+    #[serde(default = "default_node_info_max_age_ms")]
+    pub node_info_max_age_ms: u32,
+    /// The routing domains in which peers are only returned in our answers to find_node and DHT
+    /// questions once they have answered a status question of ours, proving they hold the secret key
+    /// for their node id. This keeps third-party node ids with fabricated dial info from spreading.
+    #[serde(default)]
+    pub verified_peers_only: Vec<RoutingDomain>,
     // xxx pub enable_public_internet: bool,
     // xxx pub enable_local_network: bool,
 }
 
+fn default_node_info_max_age_ms() -> u32 {
+    3_600_000
+}
+
 impl Default for VeilidConfigRoutingTable {
     fn default() -> Self {
         cfg_if::cfg_if! {
@@ -478,10 +680,125 @@ impl Default for VeilidConfigRoutingTable {
             limit_attached_strong: 16,
             limit_attached_good: 8,
             limit_attached_weak: 4,
+            node_info_max_age_ms: default_node_info_max_age_ms(),
+            verified_peers_only: Vec::new(),
         }
     }
 }
 
+/// The role this node plays in the network
+///
+/// A `Bootstrap` node only helps other nodes join the network. It does not store DHT records,
+/// allocate or carry private routes, relay or deliver app messages. Instead it keeps a much
+/// larger routing table and answers peer lookups with larger batches.
+///
+/// ```yaml
+/// role: 'Full'
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+#[cfg_attr(target_arch = "wasm32", tsify(namespace, from_wasm_abi))]
+pub enum VeilidConfigRole {
+    Full,
+    Bootstrap,
+}
+
+impl VeilidConfigRole {
+    pub fn is_bootstrap(&self) -> bool {
+        matches!(self, Self::Bootstrap)
+    }
+}
+impl Default for VeilidConfigRole {
+    fn default() -> Self {
+        Self::Full
+    }
+}
+impl FromStr for VeilidConfigRole {
+    type Err = VeilidAPIError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "full" => Self::Full,
+            "bootstrap" => Self::Bootstrap,
+            _ => {
+                apibail_invalid_argument!("Can't convert str", "s", s);
+            }
+        })
+    }
+}
+impl fmt::Display for VeilidConfigRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let text = match self {
+            Self::Full => "Full",
+            Self::Bootstrap => "Bootstrap",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// What to do while the platform reports that the network we are using costs money to use
+///
+/// Each policy also applies the ones before it. With `ReduceTickRate` maintenance runs less often,
+/// `RelayOnly` also stops accepting connections and sends everything through our relay without
+/// carrying traffic for other nodes, and `Suspend` detaches until the network is no longer costly.
+///
+/// ```yaml
+/// metered_policy: 'reduce_tick_rate'
+/// roaming_policy: 'suspend'
+/// ```
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema,
+)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+#[cfg_attr(target_arch = "wasm32", tsify(namespace, from_wasm_abi))]
+pub enum VeilidConfigNetworkCostPolicy {
+    Normal,
+    ReduceTickRate,
+    RelayOnly,
+    Suspend,
+}
+
+impl VeilidConfigNetworkCostPolicy {
+    pub fn reduces_tick_rate(&self) -> bool {
+        *self >= Self::ReduceTickRate
+    }
+    pub fn is_relay_only(&self) -> bool {
+        *self >= Self::RelayOnly
+    }
+    pub fn is_suspend(&self) -> bool {
+        *self >= Self::Suspend
+    }
+}
+impl Default for VeilidConfigNetworkCostPolicy {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+impl FromStr for VeilidConfigNetworkCostPolicy {
+    type Err = VeilidAPIError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "normal" => Self::Normal,
+            "reduce_tick_rate" | "reducetickrate" => Self::ReduceTickRate,
+            "relay_only" | "relayonly" => Self::RelayOnly,
+            "suspend" => Self::Suspend,
+            _ => {
+                apibail_invalid_argument!("Can't convert str", "s", s);
+            }
+        })
+    }
+}
+impl fmt::Display for VeilidConfigNetworkCostPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let text = match self {
+            Self::Normal => "Normal",
+            Self::ReduceTickRate => "ReduceTickRate",
+            Self::RelayOnly => "RelayOnly",
+            Self::Suspend => "Suspend",
+        };
+        write!(f, "{}", text)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(target_arch = "wasm32", derive(Tsify))]
 pub struct VeilidConfigNetwork {
@@ -494,6 +811,12 @@ pub struct VeilidConfigNetwork {
     pub client_allowlist_timeout_ms: u32,
     pub reverse_connection_receipt_time_ms: u32,
     pub hole_punch_receipt_time_ms: u32,
+    #[serde(default = "default_prewarm_grace_period_ms")]
+    pub prewarm_grace_period_ms: u32,
+    #[serde(default = "default_max_relay_client_bytes_per_sec")]
+    pub max_relay_client_bytes_per_sec: u32,
+    #[serde(default = "default_max_relay_client_queued_bytes")]
+    pub max_relay_client_queued_bytes: u32,
     #[cfg_attr(target_arch = "wasm32", tsify(optional))]
     pub network_key_password: Option<String>,
     pub routing_table: VeilidConfigRoutingTable,
@@ -501,10 +824,49 @@ pub struct VeilidConfigNetwork {
     pub dht: VeilidConfigDHT,
     pub upnp: bool,
     pub detect_address_changes: bool,
+    #[serde(default = "default_detect_address_changes_distinct_subnets")]
+    pub detect_address_changes_distinct_subnets: u32,
     pub restricted_nat_retries: u32,
+    #[serde(default = "default_max_envelope_size")]
+    pub max_envelope_size: u32,
     pub tls: VeilidConfigTLS,
+    #[serde(default)]
+    pub tor: VeilidConfigTor,
     pub application: VeilidConfigApplication,
     pub protocol: VeilidConfigProtocol,
+    #[serde(default)]
+    pub role: VeilidConfigRole,
+    #[serde(default = "default_metered_policy")]
+    pub metered_policy: VeilidConfigNetworkCostPolicy,
+    #[serde(default = "default_roaming_policy")]
+    pub roaming_policy: VeilidConfigNetworkCostPolicy,
+    #[serde(default = "default_low_power_wake_interval_ms")]
+    pub low_power_wake_interval_ms: u32,
+}
+
+fn default_prewarm_grace_period_ms() -> u32 {
+    60000
+}
+fn default_max_relay_client_bytes_per_sec() -> u32 {
+    1_048_576
+}
+fn default_max_relay_client_queued_bytes() -> u32 {
+    262_144
+}
+fn default_detect_address_changes_distinct_subnets() -> u32 {
+    3
+}
+fn default_max_envelope_size() -> u32 {
+    65507
+}
+fn default_metered_policy() -> VeilidConfigNetworkCostPolicy {
+    VeilidConfigNetworkCostPolicy::ReduceTickRate
+}
+fn default_roaming_policy() -> VeilidConfigNetworkCostPolicy {
+    VeilidConfigNetworkCostPolicy::Suspend
+}
+fn default_low_power_wake_interval_ms() -> u32 {
+    60000
 }
 
 impl Default for VeilidConfigNetwork {
@@ -519,25 +881,81 @@ impl Default for VeilidConfigNetwork {
             client_allowlist_timeout_ms: 300000,
             reverse_connection_receipt_time_ms: 5000,
             hole_punch_receipt_time_ms: 5000,
+            prewarm_grace_period_ms: default_prewarm_grace_period_ms(),
+            max_relay_client_bytes_per_sec: default_max_relay_client_bytes_per_sec(),
+            max_relay_client_queued_bytes: default_max_relay_client_queued_bytes(),
             network_key_password: None,
             routing_table: VeilidConfigRoutingTable::default(),
             rpc: VeilidConfigRPC::default(),
             dht: VeilidConfigDHT::default(),
             upnp: true,
             detect_address_changes: true,
+            detect_address_changes_distinct_subnets:
+                default_detect_address_changes_distinct_subnets(),
             restricted_nat_retries: 0,
+            max_envelope_size: default_max_envelope_size(),
             tls: VeilidConfigTLS::default(),
+            tor: VeilidConfigTor::default(),
             application: VeilidConfigApplication::default(),
             protocol: VeilidConfigProtocol::default(),
+            role: VeilidConfigRole::default(),
+            metered_policy: default_metered_policy(),
+            roaming_policy: default_roaming_policy(),
+            low_power_wake_interval_ms: default_low_power_wake_interval_ms(),
         }
     }
 }
 
+/// Where the table store keeps its tables
+///
+/// `Memory` tables are kept for the life of the process and nothing is written to storage,
+/// which suits tests and ephemeral nodes.
+///
+/// ```yaml
+/// backend: 'persistent'
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+#[cfg_attr(target_arch = "wasm32", tsify(namespace, from_wasm_abi))]
+pub enum VeilidConfigTableStoreBackend {
+    Persistent,
+    Memory,
+}
+
+impl Default for VeilidConfigTableStoreBackend {
+    fn default() -> Self {
+        Self::Persistent
+    }
+}
+impl FromStr for VeilidConfigTableStoreBackend {
+    type Err = VeilidAPIError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "persistent" => Self::Persistent,
+            "memory" => Self::Memory,
+            _ => {
+                apibail_invalid_argument!("Can't convert str", "s", s);
+            }
+        })
+    }
+}
+impl fmt::Display for VeilidConfigTableStoreBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let text = match self {
+            Self::Persistent => "Persistent",
+            Self::Memory => "Memory",
+        };
+        write!(f, "{}", text)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(target_arch = "wasm32", derive(Tsify))]
 pub struct VeilidConfigTableStore {
     pub directory: String,
     pub delete: bool,
+    #[serde(default)]
+    pub backend: VeilidConfigTableStoreBackend,
 }
 
 impl Default for VeilidConfigTableStore {
@@ -545,6 +963,7 @@ impl Default for VeilidConfigTableStore {
         Self {
             directory: get_default_store_path("table_store"),
             delete: false,
+            backend: VeilidConfigTableStoreBackend::default(),
         }
     }
 }
@@ -594,6 +1013,12 @@ impl Default for VeilidConfigBlockStore {
 pub struct VeilidConfigProtectedStore {
     pub allow_insecure_fallback: bool,
     pub always_use_insecure_storage: bool,
+    #[serde(default)]
+    pub hardware_node_id: bool,
+    /// Allow switching node ids that are held in software over to the hardware keystore
+    /// This permanently forgets the software secret keys, and changes the identity of the node
+    #[serde(default)]
+    pub hardware_node_id_migrate: bool,
     pub directory: String,
     pub delete: bool,
     pub device_encryption_key_password: String,
@@ -606,6 +1031,8 @@ impl Default for VeilidConfigProtectedStore {
         Self {
             allow_insecure_fallback: false,
             always_use_insecure_storage: false,
+            hardware_node_id: false,
+            hardware_node_id_migrate: false,
             directory: get_default_store_path("protected_store"),
             delete: false,
             device_encryption_key_password: "".to_owned(),
@@ -727,6 +1154,10 @@ pub struct VeilidConfigInner {
 #[derive(Clone)]
 pub struct VeilidConfig {
     update_cb: Option<UpdateCallback>,
+    hardware_keystore: Option<Arc<dyn HardwareKeystore>>,
+    #[cfg(all(any(test, feature = "sim-network"), not(target_arch = "wasm32")))]
+    sim_network: Option<crate::network_manager::SimNetwork>,
+    instance_span: Span,
     inner: Arc<RwLock<VeilidConfigInner>>,
 }
 
@@ -752,10 +1183,54 @@ impl VeilidConfig {
     pub fn new() -> Self {
         Self {
             update_cb: None,
+            hardware_keystore: None,
+            #[cfg(all(any(test, feature = "sim-network"), not(target_arch = "wasm32")))]
+            sim_network: None,
+            instance_span: Span::none(),
             inner: Arc::new(RwLock::new(Self::new_inner())),
         }
     }
 
+    /// Set the platform hardware keystore this node keeps its node ids in
+    pub(crate) fn set_hardware_keystore(
+        &mut self,
+        hardware_keystore: Option<Arc<dyn HardwareKeystore>>,
+    ) {
+        self.hardware_keystore = hardware_keystore;
+    }
+
+    /// Get the platform hardware keystore this node keeps its node ids in, if it was started with one
+    pub(crate) fn hardware_keystore(&self) -> Option<Arc<dyn HardwareKeystore>> {
+        self.hardware_keystore.clone()
+    }
+
+    /// Set the span this node does its work in
+    pub(crate) fn set_instance_span(&mut self, instance_span: Span) {
+        self.instance_span = instance_span;
+    }
+
+    /// Get the span this node does its work in
+    /// Background work that should not be nested under whatever started it is parented here instead,
+    /// so its logs still reach this node's update callback and not those of other nodes in the process
+    pub(crate) fn instance_span(&self) -> Span {
+        self.instance_span.clone()
+    }
+
+    /// Set the simulated network this node runs on instead of real sockets
+    #[cfg(all(any(test, feature = "sim-network"), not(target_arch = "wasm32")))]
+    pub(crate) fn set_sim_network(
+        &mut self,
+        sim_network: Option<crate::network_manager::SimNetwork>,
+    ) {
+        self.sim_network = sim_network;
+    }
+
+    /// Get the simulated network this node runs on, if it was started on one
+    #[cfg(all(any(test, feature = "sim-network"), not(target_arch = "wasm32")))]
+    pub(crate) fn sim_network(&self) -> Option<crate::network_manager::SimNetwork> {
+        self.sim_network.clone()
+    }
+
     pub fn setup_from_json(
         &mut self,
         config: String,
@@ -808,10 +1283,13 @@ impl VeilidConfig {
             get_config!(inner.capabilities.disable);
             get_config!(inner.table_store.directory);
             get_config!(inner.table_store.delete);
+            get_config!(inner.table_store.backend);
             get_config!(inner.block_store.directory);
             get_config!(inner.block_store.delete);
             get_config!(inner.protected_store.allow_insecure_fallback);
             get_config!(inner.protected_store.always_use_insecure_storage);
+            get_config!(inner.protected_store.hardware_node_id);
+            get_config!(inner.protected_store.hardware_node_id_migrate);
             get_config!(inner.protected_store.directory);
             get_config!(inner.protected_store.delete);
             get_config!(inner.protected_store.device_encryption_key_password);
@@ -825,6 +1303,9 @@ impl VeilidConfig {
             get_config!(inner.network.client_allowlist_timeout_ms);
             get_config!(inner.network.reverse_connection_receipt_time_ms);
             get_config!(inner.network.hole_punch_receipt_time_ms);
+            get_config!(inner.network.prewarm_grace_period_ms);
+            get_config!(inner.network.max_relay_client_bytes_per_sec);
+            get_config!(inner.network.max_relay_client_queued_bytes);
             get_config!(inner.network.network_key_password);
             get_config!(inner.network.routing_table.node_id);
             get_config!(inner.network.routing_table.node_id_secret);
@@ -834,6 +1315,8 @@ impl VeilidConfig {
             get_config!(inner.network.routing_table.limit_attached_strong);
             get_config!(inner.network.routing_table.limit_attached_good);
             get_config!(inner.network.routing_table.limit_attached_weak);
+            get_config!(inner.network.routing_table.node_info_max_age_ms);
+            get_config!(inner.network.routing_table.verified_peers_only);
             get_config!(inner.network.dht.max_find_node_count);
             get_config!(inner.network.dht.resolve_node_timeout_ms);
             get_config!(inner.network.dht.resolve_node_count);
@@ -844,6 +1327,7 @@ impl VeilidConfig {
             get_config!(inner.network.dht.set_value_timeout_ms);
             get_config!(inner.network.dht.set_value_count);
             get_config!(inner.network.dht.set_value_fanout);
+            get_config!(inner.network.dht.min_fanout_under_load);
             get_config!(inner.network.dht.min_peer_count);
             get_config!(inner.network.dht.min_peer_refresh_time_ms);
             get_config!(inner.network.dht.validate_dial_info_receipt_time_ms);
@@ -853,51 +1337,86 @@ impl VeilidConfig {
             get_config!(inner.network.dht.remote_max_records);
             get_config!(inner.network.dht.remote_max_subkey_cache_memory_mb);
             get_config!(inner.network.dht.remote_max_storage_space_mb);
+            get_config!(inner.network.dht.remote_storage_high_watermark_percent);
+            get_config!(inner.network.dht.remote_storage_low_watermark_percent);
             get_config!(inner.network.dht.public_watch_limit);
             get_config!(inner.network.dht.member_watch_limit);
             get_config!(inner.network.dht.max_watch_expiration_ms);
+            get_config!(inner.network.dht.set_value_pow_difficulty);
+            get_config!(inner.network.dht.compress_values);
+            get_config!(inner.network.dht.audit_log_max_entries);
             get_config!(inner.network.rpc.concurrency);
             get_config!(inner.network.rpc.queue_size);
             get_config!(inner.network.rpc.max_timestamp_behind_ms);
             get_config!(inner.network.rpc.max_timestamp_ahead_ms);
+            get_config!(inner.network.rpc.max_clock_skew_ms);
+            get_config!(inner.network.rpc.clock_skew_warning_ms);
             get_config!(inner.network.rpc.timeout_ms);
             get_config!(inner.network.rpc.max_route_hop_count);
             get_config!(inner.network.rpc.default_route_hop_count);
+            get_config!(inner.network.rpc.max_private_routes);
+            get_config!(inner.network.rpc.max_private_routes_per_api);
+            get_config!(inner.network.rpc.private_route_idle_release_ms);
+            get_config!(inner.network.rpc.max_app_message_size);
+            get_config!(inner.network.rpc.max_app_call_size);
+            get_config!(inner.network.rpc.max_app_message_frequency_per_min);
+            get_config!(inner.network.rpc.max_app_call_frequency_per_min);
+            get_config!(inner.network.rpc.strict_udp_source);
+            get_config!(inner.network.rpc.strict_udp_source_allow_rebind);
+            get_config!(inner.network.rpc.route_pow_difficulty);
             get_config!(inner.network.upnp);
             get_config!(inner.network.detect_address_changes);
+            get_config!(inner.network.detect_address_changes_distinct_subnets);
             get_config!(inner.network.restricted_nat_retries);
+            get_config!(inner.network.max_envelope_size);
             get_config!(inner.network.tls.certificate_path);
             get_config!(inner.network.tls.private_key_path);
             get_config!(inner.network.tls.connection_initial_timeout_ms);
+            get_config!(inner.network.tor.enabled);
+            get_config!(inner.network.tor.socks_address);
+            get_config!(inner.network.tor.onion_address);
             get_config!(inner.network.application.https.enabled);
             get_config!(inner.network.application.https.listen_address);
             get_config!(inner.network.application.https.path);
             get_config!(inner.network.application.https.url);
+            get_config!(inner.network.application.https.directory);
             get_config!(inner.network.application.http.enabled);
             get_config!(inner.network.application.http.listen_address);
             get_config!(inner.network.application.http.path);
             get_config!(inner.network.application.http.url);
+            get_config!(inner.network.application.http.directory);
             get_config!(inner.network.protocol.udp.enabled);
             get_config!(inner.network.protocol.udp.socket_pool_size);
             get_config!(inner.network.protocol.udp.listen_address);
             get_config!(inner.network.protocol.udp.public_address);
+            get_config!(inner.network.protocol.udp.preference);
             get_config!(inner.network.protocol.tcp.connect);
             get_config!(inner.network.protocol.tcp.listen);
             get_config!(inner.network.protocol.tcp.max_connections);
             get_config!(inner.network.protocol.tcp.listen_address);
             get_config!(inner.network.protocol.tcp.public_address);
+            get_config!(inner.network.protocol.tcp.preference);
             get_config!(inner.network.protocol.ws.connect);
             get_config!(inner.network.protocol.ws.listen);
             get_config!(inner.network.protocol.ws.max_connections);
             get_config!(inner.network.protocol.ws.listen_address);
             get_config!(inner.network.protocol.ws.path);
             get_config!(inner.network.protocol.ws.url);
+            get_config!(inner.network.protocol.ws.preference);
             get_config!(inner.network.protocol.wss.connect);
             get_config!(inner.network.protocol.wss.listen);
             get_config!(inner.network.protocol.wss.max_connections);
             get_config!(inner.network.protocol.wss.listen_address);
             get_config!(inner.network.protocol.wss.path);
             get_config!(inner.network.protocol.wss.url);
+            get_config!(inner.network.protocol.wss.preference);
+            get_config!(inner.network.protocol.ipc.connect);
+            get_config!(inner.network.protocol.ipc.listen);
+            get_config!(inner.network.protocol.ipc.listen_path);
+            get_config!(inner.network.role);
+            get_config!(inner.network.metered_policy);
+            get_config!(inner.network.roaming_policy);
+            get_config!(inner.network.low_power_wake_interval_ms);
             Ok(())
         })
     }
@@ -934,10 +1453,21 @@ impl VeilidConfig {
 
         VeilidConfig {
             update_cb: self.update_cb.clone(),
+            hardware_keystore: self.hardware_keystore.clone(),
+            #[cfg(all(any(test, feature = "sim-network"), not(target_arch = "wasm32")))]
+            sim_network: self.sim_network.clone(),
+            instance_span: self.instance_span.clone(),
             inner: Arc::new(RwLock::new(safe_cfg)),
         }
     }
 
+    /// Send an update to clients, if they have registered for updates
+    pub(crate) fn send_update(&self, update: VeilidUpdate) {
+        if let Some(update_cb) = &self.update_cb {
+            update_cb(update);
+        }
+    }
+
     pub fn with_mut<F, R>(&self, f: F) -> VeilidAPIResult<R>
     where
         F: FnOnce(&mut VeilidConfigInner) -> VeilidAPIResult<R>,
@@ -1039,11 +1569,28 @@ impl VeilidConfig {
         })
     }
 
+    // Any check added here must also be described in `config_constraints()` in veilid_config_schema.rs
     fn validate(inner: &VeilidConfigInner) -> VeilidAPIResult<()> {
         if inner.program_name.is_empty() {
             apibail_generic!("Program name must not be empty in 'program_name'");
         }
 
+        if inner.network.tor.enabled {
+            // Validate Tor settings
+            if SocketAddr::from_str(&inner.network.tor.socks_address).is_err() {
+                apibail_generic!("Tor SOCKS address must be an IP address and port in config key 'network.tor.socks_address'");
+            }
+        }
+        if let Some(onion_address) = &inner.network.tor.onion_address {
+            if DialInfo::try_onion_address(onion_address).is_err() {
+                apibail_generic!("Onion address must be a v3 onion host and port in config key 'network.tor.onion_address'");
+            }
+            if !inner.network.tor.enabled {
+                apibail_generic!(
+                    "Onion address requires Tor to be enabled in config key 'network.tor.enabled'"
+                );
+            }
+        }
         // if inner.network.protocol.udp.enabled {
         //     // Validate UDP settings
         // }
@@ -1098,8 +1645,26 @@ impl VeilidConfig {
                 apibail_generic!("WSS path conflicts with HTTP application path in config key 'network.protocol.ws.path'");
             }
         }
+        if inner.network.protocol.ipc.listen {
+            // Validate IPC settings
+            if inner.network.protocol.ipc.listen_path.is_empty() {
+                apibail_generic!("IPC listen path must be specified in config key 'network.protocol.ipc.listen_path'");
+            }
+        }
         if inner.network.application.https.enabled {
             // Validate HTTPS settings
+            if inner
+                .network
+                .application
+                .https
+                .path
+                .trim_matches('/')
+                .is_empty()
+            {
+                apibail_generic!(
+                    "HTTPS path must be specified in config key 'network.application.https.path'"
+                );
+            }
             if inner
                 .network
                 .application
@@ -1114,6 +1679,21 @@ impl VeilidConfig {
                 );
             }
         }
+        if inner.network.application.http.enabled {
+            // Validate HTTP settings
+            if inner
+                .network
+                .application
+                .http
+                .path
+                .trim_matches('/')
+                .is_empty()
+            {
+                apibail_generic!(
+                    "HTTP path must be specified in config key 'network.application.http.path'"
+                );
+            }
+        }
         if inner.network.rpc.max_route_hop_count == 0 {
             apibail_generic!(
                 "max route hop count must be >= 1 in 'network.rpc.max_route_hop_count'"
@@ -1134,12 +1714,73 @@ impl VeilidConfig {
                 "default route hop count must be <= max route hop count in 'network.rpc.default_route_hop_count <= network.rpc.max_route_hop_count'"
             );
         }
+        if inner.network.rpc.max_private_routes_per_api > inner.network.rpc.max_private_routes {
+            apibail_generic!(
+                "private routes per api must be <= max private routes in 'network.rpc.max_private_routes_per_api <= network.rpc.max_private_routes'"
+            );
+        }
+        if inner.network.rpc.max_app_message_size as usize > rpc_processor::MAX_APP_MESSAGE_SIZE {
+            apibail_generic!(format!(
+                "app message size must be <= {} in 'network.rpc.max_app_message_size'",
+                rpc_processor::MAX_APP_MESSAGE_SIZE
+            ));
+        }
+        if inner.network.rpc.max_app_call_size as usize > rpc_processor::MAX_APP_CALL_SIZE {
+            apibail_generic!(format!(
+                "app call size must be <= {} in 'network.rpc.max_app_call_size'",
+                rpc_processor::MAX_APP_CALL_SIZE
+            ));
+        }
+        if (inner.network.max_envelope_size as usize) < network_manager::MIN_MAX_ENVELOPE_SIZE
+            || (inner.network.max_envelope_size as usize) > crypto::MAX_ENVELOPE_SIZE
+        {
+            apibail_generic!(format!(
+                "max envelope size must be between {} and {} in 'network.max_envelope_size'",
+                network_manager::MIN_MAX_ENVELOPE_SIZE,
+                crypto::MAX_ENVELOPE_SIZE
+            ));
+        }
         if inner.network.rpc.queue_size < 256 {
             apibail_generic!("rpc queue size must be >= 256 in 'network.rpc.queue_size'");
         }
         if inner.network.rpc.timeout_ms < 1000 {
             apibail_generic!("rpc timeout must be >= 1000 in 'network.rpc.timeout_ms'");
         }
+        if inner.network.rpc.route_pow_difficulty > rpc_processor::MAX_POW_DIFFICULTY as u32 {
+            apibail_generic!(format!(
+                "route proof-of-work difficulty must be <= {} in 'network.rpc.route_pow_difficulty'",
+                rpc_processor::MAX_POW_DIFFICULTY
+            ));
+        }
+        if inner.network.dht.set_value_pow_difficulty > rpc_processor::MAX_POW_DIFFICULTY as u32 {
+            apibail_generic!(format!(
+                "set value proof-of-work difficulty must be <= {} in 'network.dht.set_value_pow_difficulty'",
+                rpc_processor::MAX_POW_DIFFICULTY
+            ));
+        }
+        if inner.network.dht.remote_storage_high_watermark_percent > 100 {
+            apibail_generic!("remote storage high watermark must be <= 100 in 'network.dht.remote_storage_high_watermark_percent'");
+        }
+        if inner.network.dht.remote_storage_low_watermark_percent
+            > inner.network.dht.remote_storage_high_watermark_percent
+        {
+            apibail_generic!("remote storage low watermark must be <= high watermark in 'network.dht.remote_storage_low_watermark_percent <= network.dht.remote_storage_high_watermark_percent'");
+        }
+        if inner.network.dht.min_fanout_under_load == 0 {
+            apibail_generic!(
+                "min fanout under load must be >= 1 in 'network.dht.min_fanout_under_load'"
+            );
+        }
+        if inner.network.low_power_wake_interval_ms < 1000 {
+            apibail_generic!(
+                "low power wake interval must be >= 1000 in 'network.low_power_wake_interval_ms'"
+            );
+        }
+        if inner.network.detect_address_changes_distinct_subnets == 0 {
+            apibail_generic!(
+                "distinct subnets must be >= 1 in 'network.detect_address_changes_distinct_subnets'"
+            );
+        }
 
         Ok(())
     }
@@ -1149,7 +1790,7 @@ impl VeilidConfig {
         &self,
         vcrypto: CryptoSystemVersion,
         table_store: TableStore,
-    ) -> VeilidAPIResult<(TypedKey, TypedSecret)> {
+    ) -> VeilidAPIResult<(TypedKey, TypedSecret, bool)> {
         let ck = vcrypto.kind();
         let mut node_id = self.inner.read().network.routing_table.node_id.get(ck);
         let mut node_id_secret = self
@@ -1194,7 +1835,7 @@ impl VeilidConfig {
         }
 
         // If we have a node id from storage, check it
-        let (node_id, node_id_secret) =
+        let (node_id, node_id_secret, generated) =
             if let (Some(node_id), Some(node_id_secret)) = (node_id, node_id_secret) {
                 // Validate node id
                 if !vcrypto.validate_keypair(&node_id.value, &node_id_secret.value) {
@@ -1203,12 +1844,16 @@ impl VeilidConfig {
                         ck, ck
                     ));
                 }
-                (node_id, node_id_secret)
+                (node_id, node_id_secret, false)
             } else {
                 // If we still don't have a valid node id, generate one
                 log_tstore!(debug "generating new node_id_{}", ck);
                 let kp = vcrypto.generate_keypair();
-                (TypedKey::new(ck, kp.key), TypedSecret::new(ck, kp.secret))
+                (
+                    TypedKey::new(ck, kp.key),
+                    TypedSecret::new(ck, kp.secret),
+                    true,
+                )
             };
         info!("Node Id: {}", node_id);
 
@@ -1220,19 +1865,124 @@ impl VeilidConfig {
             .store_json(0, table_key_node_id_secret.as_bytes(), &node_id_secret)
             .await?;
 
-        Ok((node_id, node_id_secret))
+        Ok((node_id, node_id_secret, generated))
+    }
+
+    /// Get the node id for a crypto kind from the platform hardware keystore
+    /// The secret key stays in the keystore, so only the node id is saved in storage
+    #[cfg(not(test))]
+    async fn init_hardware_node_id(
+        &self,
+        vcrypto: CryptoSystemVersion,
+        table_store: TableStore,
+        keystore: Arc<dyn HardwareKeystore>,
+    ) -> VeilidAPIResult<(Arc<dyn NodeIdSigner>, bool)> {
+        let ck = vcrypto.kind();
+        if vcrypto.kem_ciphertext_length() != 0 {
+            apibail_generic!(format!(
+                "hardware node ids are not supported for hybrid crypto kind {}",
+                ck
+            ));
+        }
+        let (configured_node_id, keystore_name, migrate) = {
+            let c = self.inner.read();
+            let keystore_name = if c.namespace.is_empty() {
+                format!("node_id_{}", ck)
+            } else {
+                format!("node_id_{}_{}", c.namespace, ck)
+            };
+            (
+                c.network.routing_table.node_id.get(ck),
+                keystore_name,
+                c.protected_store.hardware_node_id_migrate,
+            )
+        };
+
+        let config_table = table_store.open("__veilid_config", 1).await?;
+
+        let table_key_node_id = format!("node_id_{}", ck);
+        let table_key_node_id_secret = format!("node_id_secret_{}", ck);
+
+        // Switching to the hardware keystore forgets the software secret and changes our identity,
+        // so a node that already has a software node id must ask for it explicitly
+        let has_software_secret = config_table
+            .load_json::<TypedSecret>(0, table_key_node_id_secret.as_bytes())
+            .await?
+            .is_some();
+        if has_software_secret && !migrate {
+            apibail_generic!(format!(
+                "node_id_{} is held in software, set 'protected_store.hardware_node_id_migrate' to replace it with a hardware node id",
+                ck
+            ));
+        }
+
+        log_tstore!(debug "opening {} in hardware keystore", keystore_name);
+        let signer = keystore.open_node_id_signer(ck, &keystore_name)?;
+        let node_id = signer.node_id();
+        if node_id.kind != ck {
+            apibail_internal!(format!(
+                "hardware keystore returned node id of kind {} for {}",
+                node_id.kind, ck
+            ));
+        }
+        if let Some(configured_node_id) = configured_node_id {
+            if configured_node_id != node_id {
+                apibail_generic!(format!(
+                    "node_id_{} does not match the node id in the hardware keystore",
+                    ck
+                ));
+            }
+        }
+
+        // A node id that was not stored before, or was stored in software, is new
+        let generated = !matches!(
+            config_table
+                .load_json::<TypedKey>(0, table_key_node_id.as_bytes())
+                .await,
+            Ok(Some(stored_node_id)) if stored_node_id == node_id
+        );
+        info!("Node Id: {} (hardware keystore)", node_id);
+
+        // Save the node id in storage, and forget the software secret it replaced if we are migrating
+        config_table
+            .store_json(0, table_key_node_id.as_bytes(), &node_id)
+            .await?;
+        if has_software_secret {
+            info!("Replaced software node id {} with hardware node id", ck);
+            config_table
+                .delete(0, table_key_node_id_secret.as_bytes())
+                .await?;
+        }
+
+        Ok((signer, generated))
     }
 
     /// Get the node id from config if one is specified
+    /// Nodes that only have node ids of older crypto kinds get new ones generated for the kinds they are missing,
+    /// and the full set of node ids is cross-signed when our peer info is signed
+    /// If 'protected_store.hardware_node_id' is set, the node ids are kept in the platform hardware keystore
+    /// and their signers are returned, as there is no secret key to put in the config
     /// Must be done -after- protected store startup
-    #[cfg_attr(test, allow(unused_variables))]
+    #[cfg_attr(test, allow(unused_variables, unused_mut))]
     pub async fn init_node_ids(
         &self,
         crypto: Crypto,
         table_store: TableStore,
-    ) -> VeilidAPIResult<()> {
+    ) -> VeilidAPIResult<Vec<Arc<dyn NodeIdSigner>>> {
         let mut out_node_id = TypedKeyGroup::new();
         let mut out_node_id_secret = TypedSecretGroup::new();
+        let mut out_hardware_signers = Vec::<Arc<dyn NodeIdSigner>>::new();
+        let mut generated_node_ids = Vec::new();
+
+        #[cfg(not(test))]
+        let opt_keystore = if self.inner.read().protected_store.hardware_node_id {
+            let Some(keystore) = self.hardware_keystore() else {
+                apibail_generic!("'protected_store.hardware_node_id' is set but the node was not started with a hardware keystore");
+            };
+            Some(keystore)
+        } else {
+            None
+        };
 
         for ck in VALID_CRYPTO_KINDS {
             let vcrypto = crypto
@@ -1240,16 +1990,47 @@ impl VeilidConfig {
                 .expect("Valid crypto kind is not actually valid.");
 
             #[cfg(test)]
-            let (node_id, node_id_secret) = {
+            let (node_id, node_id_secret, generated) = {
                 let kp = vcrypto.generate_keypair();
-                (TypedKey::new(ck, kp.key), TypedSecret::new(ck, kp.secret))
+                (
+                    TypedKey::new(ck, kp.key),
+                    TypedSecret::new(ck, kp.secret),
+                    true,
+                )
             };
             #[cfg(not(test))]
-            let (node_id, node_id_secret) = self.init_node_id(vcrypto, table_store.clone()).await?;
+            let (node_id, node_id_secret, generated) = if let Some(keystore) = opt_keystore.clone()
+            {
+                let (signer, generated) = self
+                    .init_hardware_node_id(vcrypto, table_store.clone(), keystore)
+                    .await?;
+                let node_id = signer.node_id();
+                out_hardware_signers.push(signer);
+                (node_id, None, generated)
+            } else {
+                let (node_id, node_id_secret, generated) =
+                    self.init_node_id(vcrypto, table_store.clone()).await?;
+                (node_id, Some(node_id_secret), generated)
+            };
+            #[cfg(test)]
+            let node_id_secret = Some(node_id_secret);
 
             // Save for config
             out_node_id.add(node_id);
-            out_node_id_secret.add(node_id_secret);
+            if let Some(node_id_secret) = node_id_secret {
+                out_node_id_secret.add(node_id_secret);
+            }
+            if generated {
+                generated_node_ids.push(node_id);
+            }
+        }
+
+        // An existing identity that just got node ids for new crypto kinds has been migrated
+        if !generated_node_ids.is_empty() && generated_node_ids.len() < out_node_id.len() {
+            info!(
+                "Migrated node identity to new crypto kinds: {:?}",
+                generated_node_ids
+            );
         }
 
         // Commit back to config
@@ -1259,7 +2040,17 @@ impl VeilidConfig {
             Ok(())
         })?;
 
-        Ok(())
+        // Let clients know the identity of this node changed
+        if !generated_node_ids.is_empty() {
+            self.send_update(VeilidUpdate::IdentityChange(Box::new(
+                VeilidIdentityChange {
+                    new_node_ids: generated_node_ids,
+                    new_device_encryption_key: false,
+                },
+            )));
+        }
+
+        Ok(out_hardware_signers)
     }
 }
 
@@ -0,0 +1,381 @@
+use crate::*;
+
+/// Fields holding secrets, which a config editor should mask
+const SECRET_CONFIG_FIELDS: &[&str] = &[
+    "protected_store.device_encryption_key_password",
+    "protected_store.new_device_encryption_key_password",
+    "network.network_key_password",
+    "network.routing_table.node_id_secret",
+];
+
+/// The kind of value a config field holds
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VeilidConfigFieldKind {
+    Bool,
+    Integer,
+    Number,
+    String,
+    /// One of a fixed set of strings, listed in the field's `choices`
+    Choice,
+    /// A list of values, limited to the field's `choices` if there are any
+    List,
+}
+
+/// A check made on a config field before the config is accepted
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum VeilidConfigConstraintKind {
+    /// The value must be at least this
+    Minimum { value: u64 },
+    /// The value must be at most this
+    Maximum { value: u64 },
+    /// The value must not be an empty string
+    NotEmpty,
+    /// The value must parse as this format, either `socket_address` or `onion_address`
+    Format { format: String },
+    /// The value must be at most the value of another field
+    AtMostField { path: String },
+    /// The value must be different from the value of another field
+    DiffersFromField { path: String },
+    /// The value can only be set while another boolean field is true
+    RequiresField { path: String },
+}
+
+/// A constraint on a config field, and when it applies
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VeilidConfigConstraint {
+    #[serde(flatten)]
+    pub kind: VeilidConfigConstraintKind,
+    /// Boolean fields that must all be true for the constraint to be checked
+    pub when: Vec<String>,
+}
+
+/// Everything a config editor needs to know about one config field
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VeilidConfigSchemaField {
+    /// Dotted path of the field, as used by `VeilidConfig::get_key_json` and `VeilidConfig::set_key_json`
+    pub path: String,
+    pub kind: VeilidConfigFieldKind,
+    /// The integer format, such as `uint32`, which bounds the values the field can hold
+    pub format: Option<String>,
+    /// The field can be null
+    pub optional: bool,
+    /// The values a `Choice` field or the items of a `List` field can take, if they are limited
+    pub choices: Vec<String>,
+    /// The value used when the field is not configured
+    pub default: serde_json::Value,
+    pub description: Option<String>,
+    /// The field holds a secret that should not be shown
+    pub secret: bool,
+    pub constraints: Vec<VeilidConfigConstraint>,
+}
+
+/// A machine-readable description of every field of `VeilidConfigInner`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VeilidConfigSchema {
+    pub fields: Vec<VeilidConfigSchemaField>,
+}
+
+impl VeilidConfigSchema {
+    pub fn field(&self, path: &str) -> Option<&VeilidConfigSchemaField> {
+        self.fields.iter().find(|f| f.path == path)
+    }
+}
+
+/// Return a description of every config field, with its type, default and the checks made on it
+pub fn veilid_config_schema() -> VeilidConfigSchema {
+    let root = serde_json::to_value(schema_for!(VeilidConfigInner)).unwrap();
+    let default = serde_json::to_value(VeilidConfigInner::default()).unwrap();
+    let empty = serde_json::Map::new();
+    let definitions = root
+        .get("definitions")
+        .and_then(|d| d.as_object())
+        .unwrap_or(&empty);
+
+    let mut fields = Vec::new();
+    collect_schema_fields(&root, definitions, &default, "", &mut fields);
+
+    for (path, constraint) in config_constraints() {
+        if let Some(field) = fields.iter_mut().find(|f| f.path == path) {
+            field.constraints.push(constraint);
+        }
+    }
+
+    VeilidConfigSchema { fields }
+}
+
+/// Follow references and optional wrappers to the schema describing a value
+/// Returns the schema and whether the value can be null
+fn resolve_schema<'a>(
+    schema: &'a serde_json::Value,
+    definitions: &'a serde_json::Map<String, serde_json::Value>,
+) -> (&'a serde_json::Value, bool) {
+    if let Some(name) = schema
+        .get("$ref")
+        .and_then(|r| r.as_str())
+        .and_then(|r| r.strip_prefix("#/definitions/"))
+    {
+        if let Some(def) = definitions.get(name) {
+            return resolve_schema(def, definitions);
+        }
+    }
+    if let Some([inner]) = schema
+        .get("allOf")
+        .and_then(|a| a.as_array())
+        .map(|a| a.as_slice())
+    {
+        return resolve_schema(inner, definitions);
+    }
+    if let Some(any_of) = schema.get("anyOf").and_then(|a| a.as_array()) {
+        let is_null =
+            |s: &serde_json::Value| s.get("type").and_then(|t| t.as_str()) == Some("null");
+        if any_of.iter().any(is_null) {
+            if let Some(inner) = any_of.iter().find(|s| !is_null(s)) {
+                let (resolved, _) = resolve_schema(inner, definitions);
+                return (resolved, true);
+            }
+        }
+    }
+    (schema, false)
+}
+
+fn schema_choices(schema: &serde_json::Value) -> Vec<String> {
+    schema
+        .get("enum")
+        .and_then(|e| e.as_array())
+        .map(|e| {
+            e.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_owned()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn collect_schema_fields(
+    schema: &serde_json::Value,
+    definitions: &serde_json::Map<String, serde_json::Value>,
+    default: &serde_json::Value,
+    path: &str,
+    out: &mut Vec<VeilidConfigSchemaField>,
+) {
+    let description = schema
+        .get("description")
+        .and_then(|d| d.as_str())
+        .map(|d| d.to_owned());
+    let (schema, mut optional) = resolve_schema(schema, definitions);
+
+    // Structs are flattened into their fields
+    if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+        for (name, property) in properties {
+            let path = if path.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", path, name)
+            };
+            let default = default.get(name).unwrap_or(&serde_json::Value::Null);
+            collect_schema_fields(property, definitions, default, &path, out);
+        }
+        return;
+    }
+
+    // Optional primitives are typed as a list including null
+    let mut types = Vec::new();
+    match schema.get("type") {
+        Some(serde_json::Value::String(t)) => types.push(t.as_str()),
+        Some(serde_json::Value::Array(ts)) => types.extend(ts.iter().filter_map(|t| t.as_str())),
+        _ => {}
+    }
+    if types.contains(&"null") {
+        optional = true;
+    }
+
+    let mut choices = schema_choices(schema);
+    let kind = if !choices.is_empty() {
+        VeilidConfigFieldKind::Choice
+    } else if types.contains(&"boolean") {
+        VeilidConfigFieldKind::Bool
+    } else if types.contains(&"integer") {
+        VeilidConfigFieldKind::Integer
+    } else if types.contains(&"number") {
+        VeilidConfigFieldKind::Number
+    } else if types.contains(&"array") {
+        if let Some(items) = schema.get("items") {
+            choices = schema_choices(resolve_schema(items, definitions).0);
+        }
+        VeilidConfigFieldKind::List
+    } else {
+        VeilidConfigFieldKind::String
+    };
+
+    out.push(VeilidConfigSchemaField {
+        path: path.to_owned(),
+        kind,
+        format: schema
+            .get("format")
+            .and_then(|f| f.as_str())
+            .map(|f| f.to_owned()),
+        optional,
+        choices,
+        default: default.clone(),
+        description: description.or_else(|| {
+            schema
+                .get("description")
+                .and_then(|d| d.as_str())
+                .map(|d| d.to_owned())
+        }),
+        secret: SECRET_CONFIG_FIELDS.contains(&path),
+        constraints: Vec::new(),
+    });
+}
+
+/// The checks `VeilidConfig` makes when validating a config, by field
+/// These must be kept in sync with `VeilidConfig::validate()`
+fn config_constraints() -> Vec<(&'static str, VeilidConfigConstraint)> {
+    use VeilidConfigConstraintKind::*;
+    let c = |kind: VeilidConfigConstraintKind, when: &[&str]| VeilidConfigConstraint {
+        kind,
+        when: when.iter().map(|w| (*w).to_owned()).collect(),
+    };
+    let minimum = |value: usize| Minimum {
+        value: value as u64,
+    };
+    let maximum = |value: usize| Maximum {
+        value: value as u64,
+    };
+    let format = |format: &str| Format {
+        format: format.to_owned(),
+    };
+    let at_most = |path: &str| AtMostField {
+        path: path.to_owned(),
+    };
+    let differs_from = |path: &str| DiffersFromField {
+        path: path.to_owned(),
+    };
+
+    const TOR_ENABLED: &str = "network.tor.enabled";
+    const TCP_LISTEN: &str = "network.protocol.tcp.listen";
+    const WS_LISTEN: &str = "network.protocol.ws.listen";
+    const WSS_LISTEN: &str = "network.protocol.wss.listen";
+    const IPC_LISTEN: &str = "network.protocol.ipc.listen";
+    const HTTP_ENABLED: &str = "network.application.http.enabled";
+    const HTTPS_ENABLED: &str = "network.application.https.enabled";
+    const HTTP_PATH: &str = "network.application.http.path";
+    const HTTPS_PATH: &str = "network.application.https.path";
+
+    vec![
+        ("program_name", c(NotEmpty, &[])),
+        (
+            "network.tor.socks_address",
+            c(format("socket_address"), &[TOR_ENABLED]),
+        ),
+        ("network.tor.onion_address", c(format("onion_address"), &[])),
+        (
+            "network.tor.onion_address",
+            c(
+                RequiresField {
+                    path: TOR_ENABLED.to_owned(),
+                },
+                &[],
+            ),
+        ),
+        (
+            "network.protocol.tcp.max_connections",
+            c(minimum(1), &[TCP_LISTEN]),
+        ),
+        (
+            "network.protocol.ws.max_connections",
+            c(minimum(1), &[WS_LISTEN]),
+        ),
+        (
+            "network.protocol.ws.path",
+            c(differs_from(HTTPS_PATH), &[WS_LISTEN, HTTPS_ENABLED]),
+        ),
+        (
+            "network.protocol.ws.path",
+            c(differs_from(HTTP_PATH), &[WS_LISTEN, HTTP_ENABLED]),
+        ),
+        (
+            "network.protocol.wss.max_connections",
+            c(minimum(1), &[WSS_LISTEN]),
+        ),
+        ("network.protocol.wss.url", c(NotEmpty, &[WSS_LISTEN])),
+        (
+            "network.protocol.wss.path",
+            c(differs_from(HTTPS_PATH), &[WSS_LISTEN, HTTPS_ENABLED]),
+        ),
+        (
+            "network.protocol.wss.path",
+            c(differs_from(HTTP_PATH), &[WSS_LISTEN, HTTP_ENABLED]),
+        ),
+        (
+            "network.protocol.ipc.listen_path",
+            c(NotEmpty, &[IPC_LISTEN]),
+        ),
+        (
+            "network.application.https.path",
+            c(NotEmpty, &[HTTPS_ENABLED]),
+        ),
+        (
+            "network.application.https.url",
+            c(NotEmpty, &[HTTPS_ENABLED]),
+        ),
+        (
+            "network.application.http.path",
+            c(NotEmpty, &[HTTP_ENABLED]),
+        ),
+        ("network.rpc.max_route_hop_count", c(minimum(1), &[])),
+        ("network.rpc.max_route_hop_count", c(maximum(5), &[])),
+        ("network.rpc.default_route_hop_count", c(minimum(1), &[])),
+        (
+            "network.rpc.default_route_hop_count",
+            c(at_most("network.rpc.max_route_hop_count"), &[]),
+        ),
+        (
+            "network.rpc.max_private_routes_per_api",
+            c(at_most("network.rpc.max_private_routes"), &[]),
+        ),
+        (
+            "network.rpc.max_app_message_size",
+            c(maximum(rpc_processor::MAX_APP_MESSAGE_SIZE), &[]),
+        ),
+        (
+            "network.rpc.max_app_call_size",
+            c(maximum(rpc_processor::MAX_APP_CALL_SIZE), &[]),
+        ),
+        (
+            "network.max_envelope_size",
+            c(minimum(network_manager::MIN_MAX_ENVELOPE_SIZE), &[]),
+        ),
+        (
+            "network.max_envelope_size",
+            c(maximum(crypto::MAX_ENVELOPE_SIZE), &[]),
+        ),
+        ("network.rpc.queue_size", c(minimum(256), &[])),
+        ("network.rpc.timeout_ms", c(minimum(1000), &[])),
+        (
+            "network.rpc.route_pow_difficulty",
+            c(maximum(rpc_processor::MAX_POW_DIFFICULTY as usize), &[]),
+        ),
+        (
+            "network.dht.set_value_pow_difficulty",
+            c(maximum(rpc_processor::MAX_POW_DIFFICULTY as usize), &[]),
+        ),
+        (
+            "network.dht.remote_storage_high_watermark_percent",
+            c(maximum(100), &[]),
+        ),
+        (
+            "network.dht.remote_storage_low_watermark_percent",
+            c(
+                at_most("network.dht.remote_storage_high_watermark_percent"),
+                &[],
+            ),
+        ),
+        ("network.dht.min_fanout_under_load", c(minimum(1), &[])),
+        ("network.low_power_wake_interval_ms", c(minimum(1000), &[])),
+        (
+            "network.detect_address_changes_distinct_subnets",
+            c(minimum(1), &[]),
+        ),
+    ]
+}
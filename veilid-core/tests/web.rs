@@ -48,6 +48,34 @@ async fn wasm_test_veilid_config() {
     test_veilid_config::test_all().await;
 }
 
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_boot_proof() {
+    setup();
+    test_boot_proof::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_capability_hysteresis() {
+    setup();
+    test_capability_hysteresis::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_clock_skew() {
+    setup();
+    test_clock_skew::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_connection_race() {
+    setup();
+    test_connection_race::test_all().await;
+}
+
 #[wasm_bindgen_test]
 #[serial]
 async fn wasm_test_connection_table() {
@@ -55,6 +83,62 @@ async fn wasm_test_connection_table() {
     test_connection_table::test_all().await;
 }
 
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_dial_info_class() {
+    setup();
+    test_dial_info_class::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_envelope_fragments() {
+    setup();
+    test_envelope_fragments::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_ipc_dial_info() {
+    setup();
+    test_ipc_dial_info::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_network_error() {
+    setup();
+    test_network_error::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_onion_dial_info() {
+    setup();
+    test_onion_dial_info::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_public_address_check() {
+    setup();
+    test_public_address_check::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_relay_scheduler() {
+    setup();
+    test_relay_scheduler::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_send_queue() {
+    setup();
+    test_send_queue::test_all().await;
+}
+
 #[wasm_bindgen_test]
 #[serial]
 async fn wasm_test_signed_node_info() {
@@ -76,6 +160,14 @@ async fn wasm_test_protected_store() {
     test_protected_store::test_all().await;
 }
 
+#[cfg(feature = "unstable-blockstore")]
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_block_store() {
+    setup();
+    test_block_store::test_all().await;
+}
+
 #[wasm_bindgen_test]
 #[serial]
 async fn wasm_test_crypto() {
@@ -97,6 +189,20 @@ async fn wasm_test_serialize_json() {
     test_serialize_json::test_all().await;
 }
 
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_bucket_entry() {
+    setup();
+    test_bucket_entry::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_bucket_refresh() {
+    setup();
+    test_bucket_refresh::test_all().await;
+}
+
 #[wasm_bindgen_test]
 #[serial]
 async fn wasm_test_serialize_routing_table() {
@@ -104,6 +210,132 @@ async fn wasm_test_serialize_routing_table() {
     test_serialize_routing_table::test_all().await;
 }
 
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_routing_table_error() {
+    setup();
+    test_routing_table_error::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_route_spec_store() {
+    setup();
+    test_route_spec_store::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_peer_info() {
+    setup();
+    test_peer_info::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_peer_minimum_refresh() {
+    setup();
+    test_peer_minimum_refresh::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_find_node_answer() {
+    setup();
+    test_find_node_answer::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_answer_cache() {
+    setup();
+    test_answer_cache::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_app_message_limiter() {
+    setup();
+    test_app_message_limiter::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_coder_compat() {
+    setup();
+    test_coder_compat::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_digest_value() {
+    setup();
+    test_digest_value::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_fuzz() {
+    setup();
+    test_fuzz::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_load_tracker() {
+    setup();
+    test_load_tracker::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_operation_waiter() {
+    setup();
+    test_operation_waiter::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_proof_of_work() {
+    setup();
+    test_proof_of_work::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_record_expiration() {
+    setup();
+    test_record_expiration::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_record_versions() {
+    setup();
+    test_record_versions::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_set_value_batch() {
+    setup();
+    test_set_value_batch::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_udp_answer_source() {
+    setup();
+    test_udp_answer_source::test_all().await;
+}
+
+#[wasm_bindgen_test]
+#[serial]
+async fn wasm_test_record_store() {
+    setup();
+    test_record_store::test_all().await;
+}
+
 #[wasm_bindgen_test]
 #[serial]
 async fn wasm_test_dht() {
@@ -0,0 +1,97 @@
+package com.veilid.veilid
+
+import android.app.Notification
+import android.app.NotificationChannel
+import android.app.NotificationManager
+import android.app.Service
+import android.content.Context
+import android.content.Intent
+import android.os.Build
+import android.os.Handler
+import android.os.IBinder
+import android.os.Looper
+import android.util.Log
+
+/**
+ * Foreground service that keeps the process running while the app is in the background,
+ * so the Veilid node can keep its relay alive. It stops itself once its budget runs out.
+ */
+class VeilidKeepaliveService: Service() {
+
+  companion object {
+    private const val TAG = "VeilidKeepalive"
+    private const val CHANNEL_ID = "veilid_keepalive"
+    private const val NOTIFICATION_ID = 0x7e111d
+    private const val EXTRA_BUDGET_MS = "budget_ms"
+    private const val EXTRA_TITLE = "notification_title"
+    private const val EXTRA_TEXT = "notification_text"
+
+    private var onExpired: (() -> Unit)? = null
+
+    fun start(context: Context, budgetMs: Long, title: String, text: String, expired: () -> Unit) {
+      onExpired = expired
+      val intent = Intent(context, VeilidKeepaliveService::class.java)
+        .putExtra(EXTRA_BUDGET_MS, budgetMs)
+        .putExtra(EXTRA_TITLE, title)
+        .putExtra(EXTRA_TEXT, text)
+      if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+        context.startForegroundService(intent)
+      } else {
+        context.startService(intent)
+      }
+    }
+
+    fun stop(context: Context) {
+      onExpired = null
+      context.stopService(Intent(context, VeilidKeepaliveService::class.java))
+    }
+  }
+
+  private val handler = Handler(Looper.getMainLooper())
+  private val expire = Runnable {
+    Log.i(TAG, "background budget used up")
+    val expired = onExpired
+    onExpired = null
+    expired?.invoke()
+    stopSelf()
+  }
+
+  override fun onBind(intent: Intent?): IBinder? = null
+
+  override fun onStartCommand(intent: Intent?, flags: Int, startId: Int): Int {
+    val title = intent?.getStringExtra(EXTRA_TITLE) ?: "Veilid"
+    val text = intent?.getStringExtra(EXTRA_TEXT) ?: ""
+    startForeground(NOTIFICATION_ID, buildNotification(title, text))
+
+    handler.removeCallbacks(expire)
+    val budgetMs = intent?.getLongExtra(EXTRA_BUDGET_MS, 0L) ?: 0L
+    if (budgetMs > 0) {
+      handler.postDelayed(expire, budgetMs)
+    }
+    return START_NOT_STICKY
+  }
+
+  override fun onDestroy() {
+    handler.removeCallbacks(expire)
+    super.onDestroy()
+  }
+
+  private fun buildNotification(title: String, text: String): Notification {
+    val builder = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+      val manager = getSystemService(Context.NOTIFICATION_SERVICE) as NotificationManager
+      manager.createNotificationChannel(
+        NotificationChannel(CHANNEL_ID, title, NotificationManager.IMPORTANCE_LOW)
+      )
+      Notification.Builder(this, CHANNEL_ID)
+    } else {
+      @Suppress("DEPRECATION")
+      Notification.Builder(this)
+    }
+    return builder
+      .setContentTitle(title)
+      .setContentText(text)
+      .setSmallIcon(applicationInfo.icon)
+      .setOngoing(true)
+      .build()
+  }
+}
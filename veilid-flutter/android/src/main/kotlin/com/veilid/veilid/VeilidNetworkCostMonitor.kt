@@ -0,0 +1,68 @@
+package com.veilid.veilid
+
+import android.content.Context
+import android.net.ConnectivityManager
+import android.net.Network
+import android.net.NetworkCapabilities
+import android.os.Build
+import android.os.Handler
+import android.os.Looper
+
+/**
+ * Watches the default network and reports whether it is metered or roaming,
+ * so the Veilid node can apply its network cost policies.
+ */
+class VeilidNetworkCostMonitor(
+  context: Context,
+  private val onChanged: (metered: Boolean, roaming: Boolean) -> Unit
+) {
+  private val connectivityManager =
+    context.getSystemService(Context.CONNECTIVITY_SERVICE) as ConnectivityManager
+  private val mainHandler = Handler(Looper.getMainLooper())
+  private var callback: ConnectivityManager.NetworkCallback? = null
+  private var last: Pair<Boolean, Boolean>? = null
+
+  fun start() {
+    if (callback != null) {
+      return
+    }
+    val cb = object : ConnectivityManager.NetworkCallback() {
+      override fun onCapabilitiesChanged(network: Network, capabilities: NetworkCapabilities) {
+        report(capabilities)
+      }
+    }
+    connectivityManager.registerDefaultNetworkCallback(cb)
+    callback = cb
+
+    // Report where we are now, the callback only tells us about changes
+    connectivityManager.activeNetwork
+      ?.let { connectivityManager.getNetworkCapabilities(it) }
+      ?.let { report(it) }
+  }
+
+  fun stop() {
+    callback?.let { connectivityManager.unregisterNetworkCallback(it) }
+    callback = null
+    last = null
+  }
+
+  private fun report(capabilities: NetworkCapabilities) {
+    var metered = !capabilities.hasCapability(NetworkCapabilities.NET_CAPABILITY_NOT_METERED)
+    if (Build.VERSION.SDK_INT >= 30 &&
+      capabilities.hasCapability(NetworkCapabilities.NET_CAPABILITY_TEMPORARILY_NOT_METERED)) {
+      metered = false
+    }
+    // Roaming is only reported from Android 9 onwards
+    val roaming = Build.VERSION.SDK_INT >= Build.VERSION_CODES.P &&
+      !capabilities.hasCapability(NetworkCapabilities.NET_CAPABILITY_NOT_ROAMING)
+
+    // Network callbacks arrive on a binder thread, but channels must be used from the main thread
+    mainHandler.post {
+      val current = Pair(metered, roaming)
+      if (callback != null && current != last) {
+        last = current
+        onChanged(metered, roaming)
+      }
+    }
+  }
+}
@@ -18,16 +18,65 @@ class VeilidPlugin: FlutterPlugin, MethodCallHandler {
     }
   }
 
+  private lateinit var context: Context
+  private lateinit var backgroundChannel: MethodChannel
+  private lateinit var networkCostChannel: MethodChannel
+  private lateinit var networkCostMonitor: VeilidNetworkCostMonitor
+
   external fun init_android(ctx: Context)
 
   override fun onAttachedToEngine(@NonNull flutterPluginBinding: FlutterPlugin.FlutterPluginBinding) {
-    init_android(flutterPluginBinding.applicationContext)
+    context = flutterPluginBinding.applicationContext
+    init_android(context)
+
+    backgroundChannel = MethodChannel(flutterPluginBinding.binaryMessenger, "veilid/background")
+    backgroundChannel.setMethodCallHandler(this)
+
+    networkCostChannel = MethodChannel(flutterPluginBinding.binaryMessenger, "veilid/network_cost")
+    networkCostMonitor = VeilidNetworkCostMonitor(context) { metered, roaming ->
+      networkCostChannel.invokeMethod("changed", mapOf("metered" to metered, "roaming" to roaming))
+    }
+    networkCostChannel.setMethodCallHandler { call, result ->
+      when (call.method) {
+        "start" -> {
+          networkCostMonitor.start()
+          result.success(null)
+        }
+        "stop" -> {
+          networkCostMonitor.stop()
+          result.success(null)
+        }
+        else -> result.notImplemented()
+      }
+    }
   }
 
   override fun onMethodCall(@NonNull call: MethodCall, @NonNull result: Result) {
-    result.notImplemented()
+    when (call.method) {
+      "start" -> {
+        VeilidKeepaliveService.start(
+          context,
+          call.argument<Number>("budget_ms")?.toLong() ?: 0L,
+          call.argument<String>("notification_title") ?: "Veilid",
+          call.argument<String>("notification_text") ?: ""
+        ) {
+          // Tell the Dart side the budget ran out
+          backgroundChannel.invokeMethod("expired", null)
+        }
+        result.success(null)
+      }
+      "stop" -> {
+        VeilidKeepaliveService.stop(context)
+        result.success(null)
+      }
+      else -> result.notImplemented()
+    }
   }
 
   override fun onDetachedFromEngine(@NonNull binding: FlutterPlugin.FlutterPluginBinding) {
+    backgroundChannel.setMethodCallHandler(null)
+    VeilidKeepaliveService.stop(context)
+    networkCostChannel.setMethodCallHandler(null)
+    networkCostMonitor.stop()
   }
 }
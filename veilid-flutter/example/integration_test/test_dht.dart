@@ -170,7 +170,7 @@ Future<void> testOpenWriterDHTValue() async {
     expect(rec.owner, equals(owner));
     expect(rec.ownerSecret, equals(secret));
     expect(rec.schema, isA<DHTSchemaDFLT>());
-    expect(rec.schema.oCnt, equals(2));
+    expect((rec.schema as DHTSchemaDFLT).oCnt, equals(2));
 
     // Verify subkey 1 can be set before it is get but newer is available online
     vdtemp = await rc.setDHTValue(key, 1, vc);
@@ -200,7 +200,7 @@ Future<void> testOpenWriterDHTValue() async {
     expect(rec.owner, equals(owner));
     expect(rec.ownerSecret, isNull);
     expect(rec.schema, isA<DHTSchemaDFLT>());
-    expect(rec.schema.oCnt, equals(2));
+    expect((rec.schema as DHTSchemaDFLT).oCnt, equals(2));
 
     // Verify subkey 1 can NOT be set because we have the wrong writer
     await expectLater(() async => await rc.setDHTValue(key, 1, va),
@@ -42,6 +42,12 @@ Future<void> testRoutingContexts() async {
     rcp.close();
     rc.close();
   }
+  {
+    final rc = await Veilid.instance.routingContext();
+    final rcp = rc.withSafetyProfile(SafetyProfile.balanced);
+    rcp.close();
+    rc.close();
+  }
 }
 
 Future<void> testAppMessageLoopback(Stream<VeilidUpdate> updateStream) async {
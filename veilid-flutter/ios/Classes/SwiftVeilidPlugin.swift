@@ -1,14 +1,117 @@
 import Flutter
+import Network
 import UIKit
 
 public class SwiftVeilidPlugin: NSObject, FlutterPlugin {
+  private let backgroundChannel: FlutterMethodChannel
+  private var backgroundTask: UIBackgroundTaskIdentifier = .invalid
+  private var budgetTimer: Timer?
+  private let networkCostChannel: FlutterMethodChannel
+  private var pathMonitor: AnyObject?
+  private var lastNetworkCost: (metered: Bool, roaming: Bool)?
+
+  init(backgroundChannel: FlutterMethodChannel, networkCostChannel: FlutterMethodChannel) {
+    self.backgroundChannel = backgroundChannel
+    self.networkCostChannel = networkCostChannel
+  }
+
   public static func register(with registrar: FlutterPluginRegistrar) {
-    // No channel, FFI plugin
-    print("dummy_value");
+    // Everything else is FFI, these channels only manage background execution and network cost reports
+    let channel = FlutterMethodChannel(name: "veilid/background", binaryMessenger: registrar.messenger())
+    let networkCostChannel = FlutterMethodChannel(name: "veilid/network_cost", binaryMessenger: registrar.messenger())
+    let instance = SwiftVeilidPlugin(backgroundChannel: channel, networkCostChannel: networkCostChannel)
+    registrar.addMethodCallDelegate(instance, channel: channel)
+    networkCostChannel.setMethodCallHandler { [weak instance] call, result in
+      instance?.handleNetworkCost(call, result: result)
+    }
   }
 
   public func handle(_ call: FlutterMethodCall, result: @escaping FlutterResult) {
-    // Noop
-    result(nil)
+    switch call.method {
+    case "start":
+      let args = call.arguments as? [String: Any]
+      let budgetMs = (args?["budget_ms"] as? NSNumber)?.doubleValue ?? 0
+      startBackgroundTask(budgetMs: budgetMs)
+      result(nil)
+    case "stop":
+      endBackgroundTask()
+      result(nil)
+    default:
+      result(FlutterMethodNotImplemented)
+    }
+  }
+
+  // iOS decides how long a background task may run, the budget can only shorten it
+  private func startBackgroundTask(budgetMs: Double) {
+    endBackgroundTask()
+    backgroundTask = UIApplication.shared.beginBackgroundTask(withName: "veilid_keepalive") { [weak self] in
+      self?.expire()
+    }
+    if budgetMs > 0 {
+      budgetTimer = Timer.scheduledTimer(withTimeInterval: budgetMs / 1000.0, repeats: false) { [weak self] _ in
+        self?.expire()
+      }
+    }
+  }
+
+  private func handleNetworkCost(_ call: FlutterMethodCall, result: @escaping FlutterResult) {
+    switch call.method {
+    case "start":
+      startPathMonitor()
+      result(nil)
+    case "stop":
+      stopPathMonitor()
+      result(nil)
+    default:
+      result(FlutterMethodNotImplemented)
+    }
+  }
+
+  // iOS only tells us if a network is expensive, which covers cellular data and personal hotspots.
+  // It does not report roaming, so the roaming policy never applies here.
+  private func startPathMonitor() {
+    guard #available(iOS 12.0, *), pathMonitor == nil else {
+      return
+    }
+    let monitor = NWPathMonitor()
+    monitor.pathUpdateHandler = { [weak self] path in
+      var metered = path.isExpensive
+      if #available(iOS 13.0, *) {
+        metered = metered || path.isConstrained
+      }
+      self?.reportNetworkCost(metered: metered, roaming: false)
+    }
+    monitor.start(queue: DispatchQueue.main)
+    pathMonitor = monitor
+  }
+
+  private func stopPathMonitor() {
+    if #available(iOS 12.0, *), let monitor = pathMonitor as? NWPathMonitor {
+      monitor.cancel()
+    }
+    pathMonitor = nil
+    lastNetworkCost = nil
+  }
+
+  private func reportNetworkCost(metered: Bool, roaming: Bool) {
+    if let last = lastNetworkCost, last.metered == metered, last.roaming == roaming {
+      return
+    }
+    lastNetworkCost = (metered, roaming)
+    networkCostChannel.invokeMethod("changed", arguments: ["metered": metered, "roaming": roaming])
+  }
+
+  private func expire() {
+    endBackgroundTask()
+    backgroundChannel.invokeMethod("expired", arguments: nil)
+  }
+
+  private func endBackgroundTask() {
+    budgetTimer?.invalidate()
+    budgetTimer = nil
+    if backgroundTask != .invalid {
+      UIApplication.shared.endBackgroundTask(backgroundTask)
+      backgroundTask = .invalid
+    }
   }
 }
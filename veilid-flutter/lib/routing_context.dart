@@ -41,12 +41,51 @@ extension ValidateSMPL on DHTSchemaSMPL {
   int subkeyCount() => members.fold(oCnt, (acc, v) => acc + v.mCnt);
 }
 
+extension ValidateALOG on DHTSchemaALOG {
+  bool validate() {
+    if (maxLen > 65535) {
+      return false;
+    }
+    if (maxLen <= 0) {
+      return false;
+    }
+    return true;
+  }
+
+  int subkeyCount() => maxLen;
+}
+
+extension ValidateMULT on DHTSchemaMULT {
+  bool validate() {
+    if (owners.isEmpty || owners.length > 255) {
+      return false;
+    }
+    if (owners.toSet().length != owners.length) {
+      return false;
+    }
+    if (threshold <= 0 || threshold > owners.length) {
+      return false;
+    }
+    if (oCnt < 0 || oCnt >= 65535) {
+      return false;
+    }
+    return true;
+  }
+
+  // The control subkey comes before the co-owner subkeys
+  int subkeyCount() => oCnt + 1;
+}
+
 extension Validate on DHTSchema {
   bool validate() {
     if (this is DHTSchemaDFLT) {
       return (this as DHTSchemaDFLT).validate();
     } else if (this is DHTSchemaSMPL) {
       return (this as DHTSchemaSMPL).validate();
+    } else if (this is DHTSchemaALOG) {
+      return (this as DHTSchemaALOG).validate();
+    } else if (this is DHTSchemaMULT) {
+      return (this as DHTSchemaMULT).validate();
     }
     throw TypeError();
   }
@@ -56,6 +95,10 @@ extension Validate on DHTSchema {
       return (this as DHTSchemaDFLT).subkeyCount();
     } else if (this is DHTSchemaSMPL) {
       return (this as DHTSchemaSMPL).subkeyCount();
+    } else if (this is DHTSchemaALOG) {
+      return (this as DHTSchemaALOG).subkeyCount();
+    } else if (this is DHTSchemaMULT) {
+      return (this as DHTSchemaMULT).subkeyCount();
     }
     throw TypeError();
   }
@@ -74,6 +117,16 @@ sealed class DHTSchema with _$DHTSchema {
       {required int oCnt,
       required List<DHTSchemaMember> members}) = DHTSchemaSMPL;
 
+  @FreezedUnionValue('ALOG')
+  const factory DHTSchema.alog(
+      {required int maxLen, required List<PublicKey> writers}) = DHTSchemaALOG;
+
+  @FreezedUnionValue('MULT')
+  const factory DHTSchema.mult(
+      {required int threshold,
+      required List<PublicKey> owners,
+      required int oCnt}) = DHTSchemaMULT;
+
   factory DHTSchema.fromJson(dynamic json) =>
       _$DHTSchemaFromJson(json as Map<String, dynamic>);
 }
@@ -151,6 +204,19 @@ enum Stability {
   String toJson() => name.toPascalCase();
 }
 
+//////////////////////////////////////
+/// SafetyProfile
+
+enum SafetyProfile {
+  fast,
+  balanced,
+  paranoid;
+
+  factory SafetyProfile.fromJson(dynamic j) =>
+      SafetyProfile.values.byName((j as String).toCamelCase());
+  String toJson() => name.toPascalCase();
+}
+
 //////////////////////////////////////
 /// Sequencing
 
@@ -223,6 +289,8 @@ class SafetySpec with _$SafetySpec {
     required Stability stability,
     required Sequencing sequencing,
     String? preferredRoute,
+    TypedKey? preferredFirstHop,
+    SafetyProfile? profile,
   }) = _SafetySpec;
 
   factory SafetySpec.fromJson(dynamic json) =>
@@ -265,6 +333,177 @@ enum DHTReportScope {
   String toJson() => name.toPascalCase();
 }
 
+//////////////////////////////////////
+/// Rendezvous
+
+/// An outstanding invite created with [VeilidRoutingContext.createInvite]
+///
+/// Keep this around until the invite has been answered with
+/// [VeilidRoutingContext.answerInvite].
+@immutable
+class RendezvousInvite extends Equatable {
+  const RendezvousInvite(
+      {required this.cryptoKind, required this.keypair, required this.routeId});
+  factory RendezvousInvite.fromJson(dynamic json) => RendezvousInvite(
+      cryptoKind: cryptoKindFromString(
+          (json as Map<String, dynamic>)['crypto_kind'] as String),
+      keypair: KeyPair.fromJson(json['keypair']),
+      routeId: json['route_id'] as String);
+
+  /// The cryptosystem used for the invite keys
+  final CryptoKind cryptoKind;
+
+  /// The ephemeral keypair whose public key is embedded in the invite blob
+  final KeyPair keypair;
+
+  /// The private route the inviter receives the handshake on
+  final String routeId;
+
+  @override
+  List<Object> get props => [cryptoKind, keypair, routeId];
+
+  Map<String, dynamic> toJson() => {
+        'crypto_kind': cryptoKindToString(cryptoKind),
+        'keypair': keypair.toJson(),
+        'route_id': routeId
+      };
+}
+
+/// A new invite, with the blob to hand to the other party out of band
+@immutable
+class RendezvousInviteBlob extends Equatable {
+  const RendezvousInviteBlob({required this.invite, required this.blob});
+  factory RendezvousInviteBlob.fromJson(dynamic json) => RendezvousInviteBlob(
+      invite:
+          RendezvousInvite.fromJson((json as Map<String, dynamic>)['invite']),
+      blob: base64UrlNoPadDecode(json['blob'] as String));
+
+  final RendezvousInvite invite;
+  final Uint8List blob;
+
+  @override
+  List<Object> get props => [invite, blob];
+}
+
+/// A verified bidirectional messaging session established through an invite
+@immutable
+class RendezvousSession extends Equatable {
+  const RendezvousSession(
+      {required this.cryptoKind,
+      required this.sharedSecret,
+      required this.localRouteId,
+      required this.remoteRouteId});
+  factory RendezvousSession.fromJson(dynamic json) => RendezvousSession(
+      cryptoKind: cryptoKindFromString(
+          (json as Map<String, dynamic>)['crypto_kind'] as String),
+      sharedSecret: SharedSecret.fromJson(json['shared_secret']),
+      localRouteId: json['local_route_id'] as String,
+      remoteRouteId: json['remote_route_id'] as String);
+
+  /// The cryptosystem used for session encryption
+  final CryptoKind cryptoKind;
+
+  /// The secret shared by both ends of the session
+  final SharedSecret sharedSecret;
+
+  /// Our private route, incoming session messages arrive with this route id
+  final String localRouteId;
+
+  /// The remote private route that session messages are sent to
+  final String remoteRouteId;
+
+  @override
+  List<Object> get props =>
+      [cryptoKind, sharedSecret, localRouteId, remoteRouteId];
+
+  Map<String, dynamic> toJson() => {
+        'crypto_kind': cryptoKindToString(cryptoKind),
+        'shared_secret': sharedSecret.toJson(),
+        'local_route_id': localRouteId,
+        'remote_route_id': remoteRouteId
+      };
+}
+
+//////////////////////////////////////
+/// Private route tests
+
+/// Outcome of testing one of our own private routes with
+/// [VeilidRoutingContext.testPrivateRoute]
+@immutable
+class PrivateRouteTestResult extends Equatable {
+  const PrivateRouteTestResult({required this.success, this.latency});
+  factory PrivateRouteTestResult.fromJson(dynamic json) =>
+      PrivateRouteTestResult(
+          success: (json as Map<String, dynamic>)['success'] as bool,
+          latency: json['latency'] == null
+              ? null
+              : TimestampDuration.fromJson(json['latency']));
+
+  /// Whether the loopback question made it through the route and back
+  final bool success;
+
+  /// Measured round trip time of the loopback question if it succeeded
+  final TimestampDuration? latency;
+
+  @override
+  List<Object?> get props => [success, latency];
+}
+
+//////////////////////////////////////
+/// Prewarm
+
+/// How a target was reached when connections to it were prewarmed with
+/// [VeilidRoutingContext.prewarm]
+enum ContactMethod {
+  existing,
+  direct,
+  reverseConnection,
+  holePunch,
+  relay,
+  routed;
+
+  factory ContactMethod.fromJson(dynamic j) =>
+      ContactMethod.values.byName((j as String).toCamelCase());
+  String toJson() => name.toPascalCase();
+}
+
+/// Schedule for replacing one of our published private routes with a new one
+@immutable
+class PrivateRouteRotation extends Equatable {
+  const PrivateRouteRotation(
+      {required this.intervalMs,
+      required this.overlapMs,
+      this.record,
+      this.subkey = 0});
+  factory PrivateRouteRotation.fromJson(dynamic json) => PrivateRouteRotation(
+      intervalMs: (json as Map<String, dynamic>)['interval_ms'] as int,
+      overlapMs: json['overlap_ms'] as int,
+      record: json['record'] == null ? null : TypedKey.fromJson(json['record']),
+      subkey: json['subkey'] as int);
+
+  /// How long the route is used before it is replaced
+  final int intervalMs;
+
+  /// How long the old route keeps working after its replacement is published
+  final int overlapMs;
+
+  /// DHT record to write the replacement route blob to
+  final TypedKey? record;
+
+  /// Subkey of the DHT record to write the replacement route blob to
+  final int subkey;
+
+  @override
+  List<Object?> get props => [intervalMs, overlapMs, record, subkey];
+
+  Map<String, dynamic> toJson() => {
+        'interval_ms': intervalMs,
+        'overlap_ms': overlapMs,
+        'record': record?.toJson(),
+        'subkey': subkey
+      };
+}
+
 //////////////////////////////////////
 /// VeilidRoutingContext
 
@@ -275,17 +514,33 @@ abstract class VeilidRoutingContext {
   VeilidRoutingContext withDefaultSafety({bool closeSelf = false});
   VeilidRoutingContext withSafety(SafetySelection safetySelection,
       {bool closeSelf = false});
+  VeilidRoutingContext withSafetyProfile(SafetyProfile profile,
+      {bool closeSelf = false});
   VeilidRoutingContext withSequencing(Sequencing sequencing,
       {bool closeSelf = false});
+  VeilidRoutingContext withTimeout(int? timeoutMs, {bool closeSelf = false});
   Future<SafetySelection> safety();
 
+  // Private routes
+  Future<PrivateRouteTestResult> testPrivateRoute(String routeId);
+  Future<String> publishPrivateRoute(TypedKey key, int subkey,
+      {PrivateRouteRotation? rotation});
+  Future<String?> resolvePrivateRoute(TypedKey key, int subkey,
+      {bool forceRefresh = false});
+
   // App call/message
   Future<Uint8List> appCall(String target, Uint8List request);
   Future<void> appMessage(String target, Uint8List message);
+  Future<ContactMethod> prewarm(String target);
+  Future<List<TypedKey>> appMessageClosest(
+      TypedKey key, int count, Uint8List message);
 
   // DHT Operations
   Future<DHTRecordDescriptor> createDHTRecord(DHTSchema schema,
       {CryptoKind kind = 0});
+  Future<DHTRecordDescriptor> createExpiringDHTRecord(
+      DHTSchema schema, Timestamp expiration, {CryptoKind kind = 0});
+  Future<void> extendDHTRecordExpiration(TypedKey key, Timestamp? expiration);
   Future<DHTRecordDescriptor> openDHTRecord(TypedKey key, {KeyPair? writer});
   Future<void> closeDHTRecord(TypedKey key);
   Future<void> deleteDHTRecord(TypedKey key);
@@ -299,4 +554,16 @@ abstract class VeilidRoutingContext {
   Future<DHTRecordReport> inspectDHTRecord(TypedKey key,
       {List<ValueSubkeyRange>? subkeys,
       DHTReportScope scope = DHTReportScope.local});
+  Future<List<ValueSubkeyRange>> syncDHTRecord(TypedKey key);
+
+  // Rendezvous
+  Future<RendezvousInviteBlob> createInvite();
+  Future<RendezvousSession> acceptInvite(Uint8List inviteBlob);
+  Future<RendezvousSession?> answerInvite(
+      RendezvousInvite invite, VeilidAppCall appCall);
+  Future<void> sendSessionMessage(
+      RendezvousSession session, Uint8List message);
+  Future<Uint8List> openSessionMessage(
+      RendezvousSession session, Uint8List message);
+  Future<void> closeSession(RendezvousSession session);
 }
@@ -20,6 +20,10 @@ DHTSchema _$DHTSchemaFromJson(Map<String, dynamic> json) {
       return DHTSchemaDFLT.fromJson(json);
     case 'SMPL':
       return DHTSchemaSMPL.fromJson(json);
+    case 'ALOG':
+      return DHTSchemaALOG.fromJson(json);
+    case 'MULT':
+      return DHTSchemaMULT.fromJson(json);
 
     default:
       throw CheckedFromJsonException(
@@ -29,23 +33,35 @@ DHTSchema _$DHTSchemaFromJson(Map<String, dynamic> json) {
 
 /// @nodoc
 mixin _$DHTSchema {
-  int get oCnt => throw _privateConstructorUsedError;
   @optionalTypeArgs
   TResult when<TResult extends Object?>({
     required TResult Function(int oCnt) dflt,
     required TResult Function(int oCnt, List<DHTSchemaMember> members) smpl,
+    required TResult Function(int maxLen, List<FixedEncodedString43> writers)
+        alog,
+    required TResult Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)
+        mult,
   }) =>
       throw _privateConstructorUsedError;
   @optionalTypeArgs
   TResult? whenOrNull<TResult extends Object?>({
     TResult? Function(int oCnt)? dflt,
     TResult? Function(int oCnt, List<DHTSchemaMember> members)? smpl,
+    TResult? Function(int maxLen, List<FixedEncodedString43> writers)? alog,
+    TResult? Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)?
+        mult,
   }) =>
       throw _privateConstructorUsedError;
   @optionalTypeArgs
   TResult maybeWhen<TResult extends Object?>({
     TResult Function(int oCnt)? dflt,
     TResult Function(int oCnt, List<DHTSchemaMember> members)? smpl,
+    TResult Function(int maxLen, List<FixedEncodedString43> writers)? alog,
+    TResult Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)?
+        mult,
     required TResult orElse(),
   }) =>
       throw _privateConstructorUsedError;
@@ -53,33 +69,34 @@ mixin _$DHTSchema {
   TResult map<TResult extends Object?>({
     required TResult Function(DHTSchemaDFLT value) dflt,
     required TResult Function(DHTSchemaSMPL value) smpl,
+    required TResult Function(DHTSchemaALOG value) alog,
+    required TResult Function(DHTSchemaMULT value) mult,
   }) =>
       throw _privateConstructorUsedError;
   @optionalTypeArgs
   TResult? mapOrNull<TResult extends Object?>({
     TResult? Function(DHTSchemaDFLT value)? dflt,
     TResult? Function(DHTSchemaSMPL value)? smpl,
+    TResult? Function(DHTSchemaALOG value)? alog,
+    TResult? Function(DHTSchemaMULT value)? mult,
   }) =>
       throw _privateConstructorUsedError;
   @optionalTypeArgs
   TResult maybeMap<TResult extends Object?>({
     TResult Function(DHTSchemaDFLT value)? dflt,
     TResult Function(DHTSchemaSMPL value)? smpl,
+    TResult Function(DHTSchemaALOG value)? alog,
+    TResult Function(DHTSchemaMULT value)? mult,
     required TResult orElse(),
   }) =>
       throw _privateConstructorUsedError;
   Map<String, dynamic> toJson() => throw _privateConstructorUsedError;
-  @JsonKey(ignore: true)
-  $DHTSchemaCopyWith<DHTSchema> get copyWith =>
-      throw _privateConstructorUsedError;
 }
 
 /// @nodoc
 abstract class $DHTSchemaCopyWith<$Res> {
   factory $DHTSchemaCopyWith(DHTSchema value, $Res Function(DHTSchema) then) =
       _$DHTSchemaCopyWithImpl<$Res, DHTSchema>;
-  @useResult
-  $Res call({int oCnt});
 }
 
 /// @nodoc
@@ -91,28 +108,13 @@ class _$DHTSchemaCopyWithImpl<$Res, $Val extends DHTSchema>
   final $Val _value;
   // ignore: unused_field
   final $Res Function($Val) _then;
-
-  @pragma('vm:prefer-inline')
-  @override
-  $Res call({
-    Object? oCnt = null,
-  }) {
-    return _then(_value.copyWith(
-      oCnt: null == oCnt
-          ? _value.oCnt
-          : oCnt // ignore: cast_nullable_to_non_nullable
-              as int,
-    ) as $Val);
-  }
 }
 
 /// @nodoc
-abstract class _$$DHTSchemaDFLTImplCopyWith<$Res>
-    implements $DHTSchemaCopyWith<$Res> {
+abstract class _$$DHTSchemaDFLTImplCopyWith<$Res> {
   factory _$$DHTSchemaDFLTImplCopyWith(
           _$DHTSchemaDFLTImpl value, $Res Function(_$DHTSchemaDFLTImpl) then) =
       __$$DHTSchemaDFLTImplCopyWithImpl<$Res>;
-  @override
   @useResult
   $Res call({int oCnt});
 }
@@ -182,6 +184,11 @@ class _$DHTSchemaDFLTImpl implements DHTSchemaDFLT {
   TResult when<TResult extends Object?>({
     required TResult Function(int oCnt) dflt,
     required TResult Function(int oCnt, List<DHTSchemaMember> members) smpl,
+    required TResult Function(int maxLen, List<FixedEncodedString43> writers)
+        alog,
+    required TResult Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)
+        mult,
   }) {
     return dflt(oCnt);
   }
@@ -191,6 +198,10 @@ class _$DHTSchemaDFLTImpl implements DHTSchemaDFLT {
   TResult? whenOrNull<TResult extends Object?>({
     TResult? Function(int oCnt)? dflt,
     TResult? Function(int oCnt, List<DHTSchemaMember> members)? smpl,
+    TResult? Function(int maxLen, List<FixedEncodedString43> writers)? alog,
+    TResult? Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)?
+        mult,
   }) {
     return dflt?.call(oCnt);
   }
@@ -200,6 +211,10 @@ class _$DHTSchemaDFLTImpl implements DHTSchemaDFLT {
   TResult maybeWhen<TResult extends Object?>({
     TResult Function(int oCnt)? dflt,
     TResult Function(int oCnt, List<DHTSchemaMember> members)? smpl,
+    TResult Function(int maxLen, List<FixedEncodedString43> writers)? alog,
+    TResult Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)?
+        mult,
     required TResult orElse(),
   }) {
     if (dflt != null) {
@@ -213,6 +228,8 @@ class _$DHTSchemaDFLTImpl implements DHTSchemaDFLT {
   TResult map<TResult extends Object?>({
     required TResult Function(DHTSchemaDFLT value) dflt,
     required TResult Function(DHTSchemaSMPL value) smpl,
+    required TResult Function(DHTSchemaALOG value) alog,
+    required TResult Function(DHTSchemaMULT value) mult,
   }) {
     return dflt(this);
   }
@@ -222,6 +239,8 @@ class _$DHTSchemaDFLTImpl implements DHTSchemaDFLT {
   TResult? mapOrNull<TResult extends Object?>({
     TResult? Function(DHTSchemaDFLT value)? dflt,
     TResult? Function(DHTSchemaSMPL value)? smpl,
+    TResult? Function(DHTSchemaALOG value)? alog,
+    TResult? Function(DHTSchemaMULT value)? mult,
   }) {
     return dflt?.call(this);
   }
@@ -231,6 +250,8 @@ class _$DHTSchemaDFLTImpl implements DHTSchemaDFLT {
   TResult maybeMap<TResult extends Object?>({
     TResult Function(DHTSchemaDFLT value)? dflt,
     TResult Function(DHTSchemaSMPL value)? smpl,
+    TResult Function(DHTSchemaALOG value)? alog,
+    TResult Function(DHTSchemaMULT value)? mult,
     required TResult orElse(),
   }) {
     if (dflt != null) {
@@ -253,21 +274,17 @@ abstract class DHTSchemaDFLT implements DHTSchema {
   factory DHTSchemaDFLT.fromJson(Map<String, dynamic> json) =
       _$DHTSchemaDFLTImpl.fromJson;
 
-  @override
   int get oCnt;
-  @override
   @JsonKey(ignore: true)
   _$$DHTSchemaDFLTImplCopyWith<_$DHTSchemaDFLTImpl> get copyWith =>
       throw _privateConstructorUsedError;
 }
 
 /// @nodoc
-abstract class _$$DHTSchemaSMPLImplCopyWith<$Res>
-    implements $DHTSchemaCopyWith<$Res> {
+abstract class _$$DHTSchemaSMPLImplCopyWith<$Res> {
   factory _$$DHTSchemaSMPLImplCopyWith(
           _$DHTSchemaSMPLImpl value, $Res Function(_$DHTSchemaSMPLImpl) then) =
       __$$DHTSchemaSMPLImplCopyWithImpl<$Res>;
-  @override
   @useResult
   $Res call({int oCnt, List<DHTSchemaMember> members});
 }
@@ -355,6 +372,11 @@ class _$DHTSchemaSMPLImpl implements DHTSchemaSMPL {
   TResult when<TResult extends Object?>({
     required TResult Function(int oCnt) dflt,
     required TResult Function(int oCnt, List<DHTSchemaMember> members) smpl,
+    required TResult Function(int maxLen, List<FixedEncodedString43> writers)
+        alog,
+    required TResult Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)
+        mult,
   }) {
     return smpl(oCnt, members);
   }
@@ -364,6 +386,10 @@ class _$DHTSchemaSMPLImpl implements DHTSchemaSMPL {
   TResult? whenOrNull<TResult extends Object?>({
     TResult? Function(int oCnt)? dflt,
     TResult? Function(int oCnt, List<DHTSchemaMember> members)? smpl,
+    TResult? Function(int maxLen, List<FixedEncodedString43> writers)? alog,
+    TResult? Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)?
+        mult,
   }) {
     return smpl?.call(oCnt, members);
   }
@@ -373,6 +399,10 @@ class _$DHTSchemaSMPLImpl implements DHTSchemaSMPL {
   TResult maybeWhen<TResult extends Object?>({
     TResult Function(int oCnt)? dflt,
     TResult Function(int oCnt, List<DHTSchemaMember> members)? smpl,
+    TResult Function(int maxLen, List<FixedEncodedString43> writers)? alog,
+    TResult Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)?
+        mult,
     required TResult orElse(),
   }) {
     if (smpl != null) {
@@ -386,6 +416,8 @@ class _$DHTSchemaSMPLImpl implements DHTSchemaSMPL {
   TResult map<TResult extends Object?>({
     required TResult Function(DHTSchemaDFLT value) dflt,
     required TResult Function(DHTSchemaSMPL value) smpl,
+    required TResult Function(DHTSchemaALOG value) alog,
+    required TResult Function(DHTSchemaMULT value) mult,
   }) {
     return smpl(this);
   }
@@ -395,6 +427,8 @@ class _$DHTSchemaSMPLImpl implements DHTSchemaSMPL {
   TResult? mapOrNull<TResult extends Object?>({
     TResult? Function(DHTSchemaDFLT value)? dflt,
     TResult? Function(DHTSchemaSMPL value)? smpl,
+    TResult? Function(DHTSchemaALOG value)? alog,
+    TResult? Function(DHTSchemaMULT value)? mult,
   }) {
     return smpl?.call(this);
   }
@@ -404,6 +438,8 @@ class _$DHTSchemaSMPLImpl implements DHTSchemaSMPL {
   TResult maybeMap<TResult extends Object?>({
     TResult Function(DHTSchemaDFLT value)? dflt,
     TResult Function(DHTSchemaSMPL value)? smpl,
+    TResult Function(DHTSchemaALOG value)? alog,
+    TResult Function(DHTSchemaMULT value)? mult,
     required TResult orElse(),
   }) {
     if (smpl != null) {
@@ -428,15 +464,410 @@ abstract class DHTSchemaSMPL implements DHTSchema {
   factory DHTSchemaSMPL.fromJson(Map<String, dynamic> json) =
       _$DHTSchemaSMPLImpl.fromJson;
 
-  @override
   int get oCnt;
   List<DHTSchemaMember> get members;
-  @override
   @JsonKey(ignore: true)
   _$$DHTSchemaSMPLImplCopyWith<_$DHTSchemaSMPLImpl> get copyWith =>
       throw _privateConstructorUsedError;
 }
 
+/// @nodoc
+abstract class _$$DHTSchemaALOGImplCopyWith<$Res> {
+  factory _$$DHTSchemaALOGImplCopyWith(
+          _$DHTSchemaALOGImpl value, $Res Function(_$DHTSchemaALOGImpl) then) =
+      __$$DHTSchemaALOGImplCopyWithImpl<$Res>;
+  @useResult
+  $Res call({int maxLen, List<FixedEncodedString43> writers});
+}
+
+/// @nodoc
+class __$$DHTSchemaALOGImplCopyWithImpl<$Res>
+    extends _$DHTSchemaCopyWithImpl<$Res, _$DHTSchemaALOGImpl>
+    implements _$$DHTSchemaALOGImplCopyWith<$Res> {
+  __$$DHTSchemaALOGImplCopyWithImpl(
+      _$DHTSchemaALOGImpl _value, $Res Function(_$DHTSchemaALOGImpl) _then)
+      : super(_value, _then);
+
+  @pragma('vm:prefer-inline')
+  @override
+  $Res call({
+    Object? maxLen = null,
+    Object? writers = null,
+  }) {
+    return _then(_$DHTSchemaALOGImpl(
+      maxLen: null == maxLen
+          ? _value.maxLen
+          : maxLen // ignore: cast_nullable_to_non_nullable
+              as int,
+      writers: null == writers
+          ? _value._writers
+          : writers // ignore: cast_nullable_to_non_nullable
+              as List<FixedEncodedString43>,
+    ));
+  }
+}
+
+/// @nodoc
+@JsonSerializable()
+class _$DHTSchemaALOGImpl implements DHTSchemaALOG {
+  const _$DHTSchemaALOGImpl(
+      {required this.maxLen,
+      required final List<FixedEncodedString43> writers,
+      final String? $type})
+      : _writers = writers,
+        $type = $type ?? 'ALOG';
+
+  factory _$DHTSchemaALOGImpl.fromJson(Map<String, dynamic> json) =>
+      _$$DHTSchemaALOGImplFromJson(json);
+
+  @override
+  final int maxLen;
+  final List<FixedEncodedString43> _writers;
+  @override
+  List<FixedEncodedString43> get writers {
+    if (_writers is EqualUnmodifiableListView) return _writers;
+    // ignore: implicit_dynamic_type
+    return EqualUnmodifiableListView(_writers);
+  }
+
+  @JsonKey(name: 'kind')
+  final String $type;
+
+  @override
+  String toString() {
+    return 'DHTSchema.alog(maxLen: $maxLen, writers: $writers)';
+  }
+
+  @override
+  bool operator ==(Object other) {
+    return identical(this, other) ||
+        (other.runtimeType == runtimeType &&
+            other is _$DHTSchemaALOGImpl &&
+            (identical(other.maxLen, maxLen) || other.maxLen == maxLen) &&
+            const DeepCollectionEquality().equals(other._writers, _writers));
+  }
+
+  @JsonKey(ignore: true)
+  @override
+  int get hashCode => Object.hash(
+      runtimeType, maxLen, const DeepCollectionEquality().hash(_writers));
+
+  @JsonKey(ignore: true)
+  @override
+  @pragma('vm:prefer-inline')
+  _$$DHTSchemaALOGImplCopyWith<_$DHTSchemaALOGImpl> get copyWith =>
+      __$$DHTSchemaALOGImplCopyWithImpl<_$DHTSchemaALOGImpl>(this, _$identity);
+
+  @override
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(int oCnt) dflt,
+    required TResult Function(int oCnt, List<DHTSchemaMember> members) smpl,
+    required TResult Function(int maxLen, List<FixedEncodedString43> writers)
+        alog,
+    required TResult Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)
+        mult,
+  }) {
+    return alog(maxLen, writers);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(int oCnt)? dflt,
+    TResult? Function(int oCnt, List<DHTSchemaMember> members)? smpl,
+    TResult? Function(int maxLen, List<FixedEncodedString43> writers)? alog,
+    TResult? Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)?
+        mult,
+  }) {
+    return alog?.call(maxLen, writers);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(int oCnt)? dflt,
+    TResult Function(int oCnt, List<DHTSchemaMember> members)? smpl,
+    TResult Function(int maxLen, List<FixedEncodedString43> writers)? alog,
+    TResult Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)?
+        mult,
+    required TResult orElse(),
+  }) {
+    if (alog != null) {
+      return alog(maxLen, writers);
+    }
+    return orElse();
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(DHTSchemaDFLT value) dflt,
+    required TResult Function(DHTSchemaSMPL value) smpl,
+    required TResult Function(DHTSchemaALOG value) alog,
+    required TResult Function(DHTSchemaMULT value) mult,
+  }) {
+    return alog(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(DHTSchemaDFLT value)? dflt,
+    TResult? Function(DHTSchemaSMPL value)? smpl,
+    TResult? Function(DHTSchemaALOG value)? alog,
+    TResult? Function(DHTSchemaMULT value)? mult,
+  }) {
+    return alog?.call(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(DHTSchemaDFLT value)? dflt,
+    TResult Function(DHTSchemaSMPL value)? smpl,
+    TResult Function(DHTSchemaALOG value)? alog,
+    TResult Function(DHTSchemaMULT value)? mult,
+    required TResult orElse(),
+  }) {
+    if (alog != null) {
+      return alog(this);
+    }
+    return orElse();
+  }
+
+  @override
+  Map<String, dynamic> toJson() {
+    return _$$DHTSchemaALOGImplToJson(
+      this,
+    );
+  }
+}
+
+abstract class DHTSchemaALOG implements DHTSchema {
+  const factory DHTSchemaALOG(
+      {required final int maxLen,
+      required final List<FixedEncodedString43> writers}) = _$DHTSchemaALOGImpl;
+
+  factory DHTSchemaALOG.fromJson(Map<String, dynamic> json) =
+      _$DHTSchemaALOGImpl.fromJson;
+
+  int get maxLen;
+  List<FixedEncodedString43> get writers;
+  @JsonKey(ignore: true)
+  _$$DHTSchemaALOGImplCopyWith<_$DHTSchemaALOGImpl> get copyWith =>
+      throw _privateConstructorUsedError;
+}
+
+/// @nodoc
+abstract class _$$DHTSchemaMULTImplCopyWith<$Res> {
+  factory _$$DHTSchemaMULTImplCopyWith(
+          _$DHTSchemaMULTImpl value, $Res Function(_$DHTSchemaMULTImpl) then) =
+      __$$DHTSchemaMULTImplCopyWithImpl<$Res>;
+  @useResult
+  $Res call({int threshold, List<FixedEncodedString43> owners, int oCnt});
+}
+
+/// @nodoc
+class __$$DHTSchemaMULTImplCopyWithImpl<$Res>
+    extends _$DHTSchemaCopyWithImpl<$Res, _$DHTSchemaMULTImpl>
+    implements _$$DHTSchemaMULTImplCopyWith<$Res> {
+  __$$DHTSchemaMULTImplCopyWithImpl(
+      _$DHTSchemaMULTImpl _value, $Res Function(_$DHTSchemaMULTImpl) _then)
+      : super(_value, _then);
+
+  @pragma('vm:prefer-inline')
+  @override
+  $Res call({
+    Object? threshold = null,
+    Object? owners = null,
+    Object? oCnt = null,
+  }) {
+    return _then(_$DHTSchemaMULTImpl(
+      threshold: null == threshold
+          ? _value.threshold
+          : threshold // ignore: cast_nullable_to_non_nullable
+              as int,
+      owners: null == owners
+          ? _value._owners
+          : owners // ignore: cast_nullable_to_non_nullable
+              as List<FixedEncodedString43>,
+      oCnt: null == oCnt
+          ? _value.oCnt
+          : oCnt // ignore: cast_nullable_to_non_nullable
+              as int,
+    ));
+  }
+}
+
+/// @nodoc
+@JsonSerializable()
+class _$DHTSchemaMULTImpl implements DHTSchemaMULT {
+  const _$DHTSchemaMULTImpl(
+      {required this.threshold,
+      required final List<FixedEncodedString43> owners,
+      required this.oCnt,
+      final String? $type})
+      : _owners = owners,
+        $type = $type ?? 'MULT';
+
+  factory _$DHTSchemaMULTImpl.fromJson(Map<String, dynamic> json) =>
+      _$$DHTSchemaMULTImplFromJson(json);
+
+  @override
+  final int threshold;
+  final List<FixedEncodedString43> _owners;
+  @override
+  List<FixedEncodedString43> get owners {
+    if (_owners is EqualUnmodifiableListView) return _owners;
+    // ignore: implicit_dynamic_type
+    return EqualUnmodifiableListView(_owners);
+  }
+
+  @override
+  final int oCnt;
+
+  @JsonKey(name: 'kind')
+  final String $type;
+
+  @override
+  String toString() {
+    return 'DHTSchema.mult(threshold: $threshold, owners: $owners, oCnt: $oCnt)';
+  }
+
+  @override
+  bool operator ==(Object other) {
+    return identical(this, other) ||
+        (other.runtimeType == runtimeType &&
+            other is _$DHTSchemaMULTImpl &&
+            (identical(other.threshold, threshold) || other.threshold == threshold) &&
+            const DeepCollectionEquality().equals(other._owners, _owners) &&
+            (identical(other.oCnt, oCnt) || other.oCnt == oCnt));
+  }
+
+  @JsonKey(ignore: true)
+  @override
+  int get hashCode => Object.hash(
+      runtimeType,
+      threshold,
+      const DeepCollectionEquality().hash(_owners),
+      oCnt);
+
+  @JsonKey(ignore: true)
+  @override
+  @pragma('vm:prefer-inline')
+  _$$DHTSchemaMULTImplCopyWith<_$DHTSchemaMULTImpl> get copyWith =>
+      __$$DHTSchemaMULTImplCopyWithImpl<_$DHTSchemaMULTImpl>(this, _$identity);
+
+  @override
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(int oCnt) dflt,
+    required TResult Function(int oCnt, List<DHTSchemaMember> members) smpl,
+    required TResult Function(int maxLen, List<FixedEncodedString43> writers)
+        alog,
+    required TResult Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)
+        mult,
+  }) {
+    return mult(threshold, owners, oCnt);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(int oCnt)? dflt,
+    TResult? Function(int oCnt, List<DHTSchemaMember> members)? smpl,
+    TResult? Function(int maxLen, List<FixedEncodedString43> writers)? alog,
+    TResult? Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)?
+        mult,
+  }) {
+    return mult?.call(threshold, owners, oCnt);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(int oCnt)? dflt,
+    TResult Function(int oCnt, List<DHTSchemaMember> members)? smpl,
+    TResult Function(int maxLen, List<FixedEncodedString43> writers)? alog,
+    TResult Function(
+            int threshold, List<FixedEncodedString43> owners, int oCnt)?
+        mult,
+    required TResult orElse(),
+  }) {
+    if (mult != null) {
+      return mult(threshold, owners, oCnt);
+    }
+    return orElse();
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(DHTSchemaDFLT value) dflt,
+    required TResult Function(DHTSchemaSMPL value) smpl,
+    required TResult Function(DHTSchemaALOG value) alog,
+    required TResult Function(DHTSchemaMULT value) mult,
+  }) {
+    return mult(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(DHTSchemaDFLT value)? dflt,
+    TResult? Function(DHTSchemaSMPL value)? smpl,
+    TResult? Function(DHTSchemaALOG value)? alog,
+    TResult? Function(DHTSchemaMULT value)? mult,
+  }) {
+    return mult?.call(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(DHTSchemaDFLT value)? dflt,
+    TResult Function(DHTSchemaSMPL value)? smpl,
+    TResult Function(DHTSchemaALOG value)? alog,
+    TResult Function(DHTSchemaMULT value)? mult,
+    required TResult orElse(),
+  }) {
+    if (mult != null) {
+      return mult(this);
+    }
+    return orElse();
+  }
+
+  @override
+  Map<String, dynamic> toJson() {
+    return _$$DHTSchemaMULTImplToJson(
+      this,
+    );
+  }
+}
+
+abstract class DHTSchemaMULT implements DHTSchema {
+  const factory DHTSchemaMULT(
+      {required final int threshold,
+      required final List<FixedEncodedString43> owners,
+      required final int oCnt}) = _$DHTSchemaMULTImpl;
+
+  factory DHTSchemaMULT.fromJson(Map<String, dynamic> json) =
+      _$DHTSchemaMULTImpl.fromJson;
+
+  int get threshold;
+  List<FixedEncodedString43> get owners;
+  int get oCnt;
+  @JsonKey(ignore: true)
+  _$$DHTSchemaMULTImplCopyWith<_$DHTSchemaMULTImpl> get copyWith =>
+      throw _privateConstructorUsedError;
+}
+
 DHTSchemaMember _$DHTSchemaMemberFromJson(Map<String, dynamic> json) {
   return _DHTSchemaMember.fromJson(json);
 }
@@ -1002,6 +1433,9 @@ mixin _$SafetySpec {
   Stability get stability => throw _privateConstructorUsedError;
   Sequencing get sequencing => throw _privateConstructorUsedError;
   String? get preferredRoute => throw _privateConstructorUsedError;
+  Typed<FixedEncodedString43>? get preferredFirstHop =>
+      throw _privateConstructorUsedError;
+  SafetyProfile? get profile => throw _privateConstructorUsedError;
 
   Map<String, dynamic> toJson() => throw _privateConstructorUsedError;
   @JsonKey(ignore: true)
@@ -1019,7 +1453,9 @@ abstract class $SafetySpecCopyWith<$Res> {
       {int hopCount,
       Stability stability,
       Sequencing sequencing,
-      String? preferredRoute});
+      String? preferredRoute,
+      Typed<FixedEncodedString43>? preferredFirstHop,
+      SafetyProfile? profile});
 }
 
 /// @nodoc
@@ -1039,6 +1475,8 @@ class _$SafetySpecCopyWithImpl<$Res, $Val extends SafetySpec>
     Object? stability = null,
     Object? sequencing = null,
     Object? preferredRoute = freezed,
+    Object? preferredFirstHop = freezed,
+    Object? profile = freezed,
   }) {
     return _then(_value.copyWith(
       hopCount: null == hopCount
@@ -1057,6 +1495,14 @@ class _$SafetySpecCopyWithImpl<$Res, $Val extends SafetySpec>
           ? _value.preferredRoute
           : preferredRoute // ignore: cast_nullable_to_non_nullable
               as String?,
+      preferredFirstHop: freezed == preferredFirstHop
+          ? _value.preferredFirstHop
+          : preferredFirstHop // ignore: cast_nullable_to_non_nullable
+              as Typed<FixedEncodedString43>?,
+      profile: freezed == profile
+          ? _value.profile
+          : profile // ignore: cast_nullable_to_non_nullable
+              as SafetyProfile?,
     ) as $Val);
   }
 }
@@ -1073,7 +1519,9 @@ abstract class _$$SafetySpecImplCopyWith<$Res>
       {int hopCount,
       Stability stability,
       Sequencing sequencing,
-      String? preferredRoute});
+      String? preferredRoute,
+      Typed<FixedEncodedString43>? preferredFirstHop,
+      SafetyProfile? profile});
 }
 
 /// @nodoc
@@ -1091,6 +1539,8 @@ class __$$SafetySpecImplCopyWithImpl<$Res>
     Object? stability = null,
     Object? sequencing = null,
     Object? preferredRoute = freezed,
+    Object? preferredFirstHop = freezed,
+    Object? profile = freezed,
   }) {
     return _then(_$SafetySpecImpl(
       hopCount: null == hopCount
@@ -1109,6 +1559,14 @@ class __$$SafetySpecImplCopyWithImpl<$Res>
           ? _value.preferredRoute
           : preferredRoute // ignore: cast_nullable_to_non_nullable
               as String?,
+      preferredFirstHop: freezed == preferredFirstHop
+          ? _value.preferredFirstHop
+          : preferredFirstHop // ignore: cast_nullable_to_non_nullable
+              as Typed<FixedEncodedString43>?,
+      profile: freezed == profile
+          ? _value.profile
+          : profile // ignore: cast_nullable_to_non_nullable
+              as SafetyProfile?,
     ));
   }
 }
@@ -1120,7 +1578,9 @@ class _$SafetySpecImpl implements _SafetySpec {
       {required this.hopCount,
       required this.stability,
       required this.sequencing,
-      this.preferredRoute});
+      this.preferredRoute,
+      this.preferredFirstHop,
+      this.profile});
 
   factory _$SafetySpecImpl.fromJson(Map<String, dynamic> json) =>
       _$$SafetySpecImplFromJson(json);
@@ -1133,10 +1593,14 @@ class _$SafetySpecImpl implements _SafetySpec {
   final Sequencing sequencing;
   @override
   final String? preferredRoute;
+  @override
+  final Typed<FixedEncodedString43>? preferredFirstHop;
+  @override
+  final SafetyProfile? profile;
 
   @override
   String toString() {
-    return 'SafetySpec(hopCount: $hopCount, stability: $stability, sequencing: $sequencing, preferredRoute: $preferredRoute)';
+    return 'SafetySpec(hopCount: $hopCount, stability: $stability, sequencing: $sequencing, preferredRoute: $preferredRoute, preferredFirstHop: $preferredFirstHop, profile: $profile)';
   }
 
   @override
@@ -1151,13 +1615,17 @@ class _$SafetySpecImpl implements _SafetySpec {
             (identical(other.sequencing, sequencing) ||
                 other.sequencing == sequencing) &&
             (identical(other.preferredRoute, preferredRoute) ||
-                other.preferredRoute == preferredRoute));
+                other.preferredRoute == preferredRoute) &&
+            (identical(other.preferredFirstHop, preferredFirstHop) ||
+                other.preferredFirstHop == preferredFirstHop) &&
+            (identical(other.profile, profile) ||
+                other.profile == profile));
   }
 
   @JsonKey(ignore: true)
   @override
-  int get hashCode =>
-      Object.hash(runtimeType, hopCount, stability, sequencing, preferredRoute);
+  int get hashCode => Object.hash(runtimeType, hopCount, stability, sequencing,
+      preferredRoute, preferredFirstHop, profile);
 
   @JsonKey(ignore: true)
   @override
@@ -1178,7 +1646,9 @@ abstract class _SafetySpec implements SafetySpec {
       {required final int hopCount,
       required final Stability stability,
       required final Sequencing sequencing,
-      final String? preferredRoute}) = _$SafetySpecImpl;
+      final String? preferredRoute,
+      final Typed<FixedEncodedString43>? preferredFirstHop,
+      final SafetyProfile? profile}) = _$SafetySpecImpl;
 
   factory _SafetySpec.fromJson(Map<String, dynamic> json) =
       _$SafetySpecImpl.fromJson;
@@ -1192,6 +1662,10 @@ abstract class _SafetySpec implements SafetySpec {
   @override
   String? get preferredRoute;
   @override
+  Typed<FixedEncodedString43>? get preferredFirstHop;
+  @override
+  SafetyProfile? get profile;
+  @override
   @JsonKey(ignore: true)
   _$$SafetySpecImplCopyWith<_$SafetySpecImpl> get copyWith =>
       throw _privateConstructorUsedError;
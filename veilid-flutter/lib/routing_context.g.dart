@@ -34,6 +34,40 @@ Map<String, dynamic> _$$DHTSchemaSMPLImplToJson(_$DHTSchemaSMPLImpl instance) =>
       'kind': instance.$type,
     };
 
+_$DHTSchemaALOGImpl _$$DHTSchemaALOGImplFromJson(Map<String, dynamic> json) =>
+    _$DHTSchemaALOGImpl(
+      maxLen: json['max_len'] as int,
+      writers: (json['writers'] as List<dynamic>)
+          .map(FixedEncodedString43.fromJson)
+          .toList(),
+      $type: json['kind'] as String?,
+    );
+
+Map<String, dynamic> _$$DHTSchemaALOGImplToJson(_$DHTSchemaALOGImpl instance) =>
+    <String, dynamic>{
+      'max_len': instance.maxLen,
+      'writers': instance.writers.map((e) => e.toJson()).toList(),
+      'kind': instance.$type,
+    };
+
+_$DHTSchemaMULTImpl _$$DHTSchemaMULTImplFromJson(Map<String, dynamic> json) =>
+    _$DHTSchemaMULTImpl(
+      threshold: json['threshold'] as int,
+      owners: (json['owners'] as List<dynamic>)
+          .map(FixedEncodedString43.fromJson)
+          .toList(),
+      oCnt: json['o_cnt'] as int,
+      $type: json['kind'] as String?,
+    );
+
+Map<String, dynamic> _$$DHTSchemaMULTImplToJson(_$DHTSchemaMULTImpl instance) =>
+    <String, dynamic>{
+      'threshold': instance.threshold,
+      'owners': instance.owners.map((e) => e.toJson()).toList(),
+      'o_cnt': instance.oCnt,
+      'kind': instance.$type,
+    };
+
 _$DHTSchemaMemberImpl _$$DHTSchemaMemberImplFromJson(
         Map<String, dynamic> json) =>
     _$DHTSchemaMemberImpl(
@@ -88,6 +122,12 @@ _$SafetySpecImpl _$$SafetySpecImplFromJson(Map<String, dynamic> json) =>
       stability: Stability.fromJson(json['stability']),
       sequencing: Sequencing.fromJson(json['sequencing']),
       preferredRoute: json['preferred_route'] as String?,
+      preferredFirstHop: json['preferred_first_hop'] == null
+          ? null
+          : Typed<FixedEncodedString43>.fromJson(json['preferred_first_hop']),
+      profile: json['profile'] == null
+          ? null
+          : SafetyProfile.fromJson(json['profile']),
     );
 
 Map<String, dynamic> _$$SafetySpecImplToJson(_$SafetySpecImpl instance) =>
@@ -96,6 +136,8 @@ Map<String, dynamic> _$$SafetySpecImplToJson(_$SafetySpecImpl instance) =>
       'stability': instance.stability.toJson(),
       'sequencing': instance.sequencing.toJson(),
       'preferred_route': instance.preferredRoute,
+      'preferred_first_hop': instance.preferredFirstHop?.toJson(),
+      'profile': instance.profile?.toJson(),
     };
 
 _$RouteBlobImpl _$$RouteBlobImplFromJson(Map<String, dynamic> json) =>
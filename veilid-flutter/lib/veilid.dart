@@ -21,9 +21,11 @@ export 'routing_context.dart';
 export 'value_subkey_range.dart';
 export 'veilid.dart';
 export 'veilid_api_exception.dart';
+export 'veilid_background.dart';
 export 'veilid_config.dart';
 export 'veilid_crypto.dart';
 export 'veilid_encoding.dart';
+export 'veilid_network_cost.dart';
 export 'veilid_state.dart';
 export 'veilid_table_db.dart';
 
@@ -58,6 +60,135 @@ class VeilidVersion extends Equatable {
   List<Object> get props => [major, minor, patch];
 }
 
+//////////////////////////////////////
+/// NetworkCost
+
+/// What the platform reports about the cost of the network in use
+@immutable
+class NetworkCost extends Equatable {
+  const NetworkCost({this.metered = false, this.roaming = false});
+  factory NetworkCost.fromJson(dynamic json) => NetworkCost(
+      metered: (json as Map<String, dynamic>)['metered'] as bool,
+      roaming: json['roaming'] as bool);
+
+  /// The network charges by the amount of data used, or has a data cap
+  final bool metered;
+
+  /// The network is provided by someone other than the home carrier
+  final bool roaming;
+
+  @override
+  List<Object> get props => [metered, roaming];
+
+  Map<String, dynamic> toJson() => {'metered': metered, 'roaming': roaming};
+}
+
+//////////////////////////////////////
+/// DHTStorageUsage
+
+/// How much space the DHT record stores are using on this node
+@immutable
+class DHTStorageUsage extends Equatable {
+  const DHTStorageUsage(
+      {required this.localRecordCount,
+      required this.localUsed,
+      required this.remoteRecordCount,
+      required this.remoteUsed,
+      this.remoteHighWatermark,
+      this.remoteLimit});
+  factory DHTStorageUsage.fromJson(dynamic json) => DHTStorageUsage(
+      localRecordCount:
+          (json as Map<String, dynamic>)['local_record_count'] as int,
+      localUsed: BigInt.parse(json['local_used'] as String),
+      remoteRecordCount: json['remote_record_count'] as int,
+      remoteUsed: BigInt.parse(json['remote_used'] as String),
+      remoteHighWatermark: json['remote_high_watermark'] == null
+          ? null
+          : BigInt.parse(json['remote_high_watermark'] as String),
+      remoteLimit: json['remote_limit'] == null
+          ? null
+          : BigInt.parse(json['remote_limit'] as String));
+
+  /// Number of records that were created or opened locally
+  final int localRecordCount;
+
+  /// Storage space used by records that were created or opened locally
+  final BigInt localUsed;
+
+  /// Number of records being stored on behalf of other nodes
+  final int remoteRecordCount;
+
+  /// Storage space used by records being stored on behalf of other nodes
+  final BigInt remoteUsed;
+
+  /// Storage space above which remote records start being evicted
+  final BigInt? remoteHighWatermark;
+
+  /// Storage space beyond which new remote records and values are rejected
+  final BigInt? remoteLimit;
+
+  @override
+  List<Object?> get props => [
+        localRecordCount,
+        localUsed,
+        remoteRecordCount,
+        remoteUsed,
+        remoteHighWatermark,
+        remoteLimit
+      ];
+
+  Map<String, dynamic> toJson() => {
+        'local_record_count': localRecordCount,
+        'local_used': localUsed.toString(),
+        'remote_record_count': remoteRecordCount,
+        'remote_used': remoteUsed.toString(),
+        'remote_high_watermark': remoteHighWatermark?.toString(),
+        'remote_limit': remoteLimit?.toString()
+      };
+}
+
+//////////////////////////////////////
+/// StorageManagerStatus
+
+/// What the DHT storage manager is doing on behalf of the application
+@immutable
+class StorageManagerStatus extends Equatable {
+  const StorageManagerStatus(
+      {required this.pendingOfflineWrites,
+      required this.openRecordCount,
+      required this.localUsed,
+      required this.remoteUsed});
+  factory StorageManagerStatus.fromJson(dynamic json) => StorageManagerStatus(
+      pendingOfflineWrites:
+          (json as Map<String, dynamic>)['pending_offline_writes'] as int,
+      openRecordCount: json['open_record_count'] as int,
+      localUsed: BigInt.parse(json['local_used'] as String),
+      remoteUsed: BigInt.parse(json['remote_used'] as String));
+
+  /// Subkeys written while offline that are waiting to be sent to the network
+  final int pendingOfflineWrites;
+
+  /// Records that are currently open
+  final int openRecordCount;
+
+  /// Storage space used by records that were created or opened locally
+  final BigInt localUsed;
+
+  /// Storage space used by records being stored on behalf of other nodes
+  final BigInt remoteUsed;
+
+  @override
+  List<Object> get props =>
+      [pendingOfflineWrites, openRecordCount, localUsed, remoteUsed];
+
+  Map<String, dynamic> toJson() => {
+        'pending_offline_writes': pendingOfflineWrites,
+        'open_record_count': openRecordCount,
+        'local_used': localUsed.toString(),
+        'remote_used': remoteUsed.toString()
+      };
+}
+
 //////////////////////////////////////
 /// Timestamp
 @immutable
@@ -133,6 +264,41 @@ class TimestampDuration extends Equatable
   BigInt toMicros() => value;
 }
 
+//////////////////////////////////////
+/// NetworkTime
+
+/// The current time as estimated from the clocks of our peers
+@immutable
+class NetworkTime extends Equatable {
+  const NetworkTime(
+      {required this.timestamp,
+      required this.clockSkew,
+      required this.peerCount});
+  factory NetworkTime.fromJson(dynamic json) => NetworkTime(
+      timestamp:
+          Timestamp.fromJson((json as Map<String, dynamic>)['timestamp']),
+      clockSkew: json['clock_skew'] as int,
+      peerCount: json['peer_count'] as int);
+
+  /// The estimated current network time
+  final Timestamp timestamp;
+
+  /// Microseconds our clock is behind the network, negative if it is ahead
+  final int clockSkew;
+
+  /// The number of peers whose clocks the estimate is the median of
+  final int peerCount;
+
+  @override
+  List<Object> get props => [timestamp, clockSkew, peerCount];
+
+  Map<String, dynamic> toJson() => {
+        'timestamp': timestamp.toJson(),
+        'clock_skew': clockSkew,
+        'peer_count': peerCount
+      };
+}
+
 //////////////////////////////////////
 /// Veilid singleton factory
 
@@ -144,8 +310,14 @@ abstract class Veilid {
   void changeLogIgnore(String layer, List<String> changes);
   Future<Stream<VeilidUpdate>> startupVeilidCore(VeilidConfig config);
   Future<VeilidState> getVeilidState();
+  Future<DHTStorageUsage> getStorageUsage();
+  Future<StorageManagerStatus> getStorageStatus();
   Future<void> attach();
   Future<void> detach();
+  Future<void> keepaliveTick();
+  Future<void> setNetworkCost(NetworkCost networkCost);
+  Future<void> setLowPower(bool lowPower);
+  Future<void> triggerPublicDialInfoDetection();
   Future<void> shutdownVeilidCore();
 
   // Crypto
@@ -192,8 +364,10 @@ abstract class Veilid {
 
   // Misc
   Timestamp now();
+  Future<NetworkTime> getNetworkTime();
   String veilidVersionString();
   VeilidVersion veilidVersion();
   String defaultVeilidConfig();
+  String veilidConfigSchema();
   Future<String> debug(String command);
 }
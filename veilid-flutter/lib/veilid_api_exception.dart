@@ -68,6 +68,14 @@ abstract class VeilidAPIException implements Exception {
         {
           return VeilidAPIExceptionGeneric(json['message']! as String);
         }
+      case 'RouteCompilation':
+        {
+          final error = json['error']! as Map<String, dynamic>;
+          return VeilidAPIExceptionRouteCompilation(
+              error['constraint']! as String,
+              error['hop_count']! as int,
+              error['candidates']! as int);
+        }
       default:
         {
           throw VeilidAPIExceptionInternal(
@@ -249,3 +257,21 @@ class VeilidAPIExceptionGeneric implements VeilidAPIException {
   @override
   String toDisplayError() => message;
 }
+
+@immutable
+class VeilidAPIExceptionRouteCompilation implements VeilidAPIException {
+  //
+  const VeilidAPIExceptionRouteCompilation(
+      this.constraint, this.hopCount, this.candidates);
+  final String constraint;
+  final int hopCount;
+  final int candidates;
+
+  @override
+  String toString() => 'VeilidAPIException: RouteCompilation'
+      ' ($constraint constraint failed for $hopCount hop route'
+      ' with $candidates candidates)';
+
+  @override
+  String toDisplayError() => 'Route compilation failed: $constraint';
+}
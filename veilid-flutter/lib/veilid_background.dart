@@ -0,0 +1,151 @@
+import 'dart:async';
+
+import 'package:flutter/foundation.dart';
+import 'package:flutter/services.dart';
+import 'package:flutter/widgets.dart';
+
+import 'veilid_stub.dart'
+    if (dart.library.io) 'veilid_ffi.dart'
+    if (dart.library.js) 'veilid_js.dart';
+
+//////////////////////////////////////
+/// Background keepalive configuration
+
+@immutable
+class VeilidBackgroundConfig {
+  const VeilidBackgroundConfig({
+    this.budget = const Duration(minutes: 10),
+    this.tickInterval = const Duration(seconds: 10),
+    this.androidNotificationTitle = 'Veilid',
+    this.androidNotificationText = 'Staying connected to the network',
+  });
+
+  /// How long to keep the node attached after the app goes to the background.
+  /// iOS may end background execution sooner than this.
+  final Duration budget;
+
+  /// How often to tick the node while in the background, so its relay
+  /// keepalive keeps running
+  final Duration tickInterval;
+
+  /// Title of the notification shown while the Android foreground service runs
+  final String androidNotificationTitle;
+
+  /// Text of the notification shown while the Android foreground service runs
+  final String androidNotificationText;
+}
+
+//////////////////////////////////////
+/// Background keepalive
+
+/// Keeps a Veilid node attached for a while after the app is backgrounded.
+///
+/// When the app is paused, this asks the platform for background execution
+/// time (an Android foreground service, or an iOS background task) and ticks
+/// the node from a background isolate so it keeps its relay alive while the
+/// UI isolate is paused. Everything stops when the app is resumed, when the
+/// budget runs out, or when the platform takes the background time away.
+class VeilidBackgroundKeepalive with WidgetsBindingObserver {
+  VeilidBackgroundKeepalive._();
+
+  static final VeilidBackgroundKeepalive instance =
+      VeilidBackgroundKeepalive._();
+
+  static const MethodChannel _channel = MethodChannel('veilid/background');
+
+  VeilidBackgroundConfig? _config;
+  void Function()? _stopTicker;
+  Timer? _budgetTimer;
+  bool _platformStarted = false;
+
+  /// Start keeping the node attached whenever the app is backgrounded
+  void enable(
+      {VeilidBackgroundConfig config = const VeilidBackgroundConfig()}) {
+    if (_config == null) {
+      WidgetsBinding.instance.addObserver(this);
+      _channel.setMethodCallHandler(_handleMethodCall);
+    }
+    _config = config;
+  }
+
+  /// Stop keeping the node attached in the background
+  Future<void> disable() async {
+    if (_config == null) {
+      return;
+    }
+    WidgetsBinding.instance.removeObserver(this);
+    _channel.setMethodCallHandler(null);
+    _config = null;
+    await _stop();
+  }
+
+  /// If background execution is currently keeping the node attached
+  bool get isActive => _stopTicker != null;
+
+  @override
+  void didChangeAppLifecycleState(AppLifecycleState state) {
+    switch (state) {
+      case AppLifecycleState.paused:
+      case AppLifecycleState.hidden:
+        unawaited(_start());
+      case AppLifecycleState.resumed:
+        unawaited(_stop());
+      case AppLifecycleState.inactive:
+      case AppLifecycleState.detached:
+        break;
+    }
+  }
+
+  Future<void> _start() async {
+    final config = _config;
+    if (config == null || isActive) {
+      return;
+    }
+
+    if (_hasPlatformIntegration) {
+      try {
+        await _channel.invokeMethod<void>('start', <String, dynamic>{
+          'budget_ms': config.budget.inMilliseconds,
+          'notification_title': config.androidNotificationTitle,
+          'notification_text': config.androidNotificationText,
+        });
+        _platformStarted = true;
+      } on PlatformException catch (e) {
+        debugPrint('veilid background execution unavailable: $e');
+      }
+    }
+
+    _stopTicker = await spawnKeepaliveTicker(config.tickInterval);
+    _budgetTimer = Timer(config.budget, () => unawaited(_stop()));
+  }
+
+  Future<void> _stop() async {
+    _budgetTimer?.cancel();
+    _budgetTimer = null;
+    _stopTicker?.call();
+    _stopTicker = null;
+
+    if (_platformStarted) {
+      _platformStarted = false;
+      try {
+        await _channel.invokeMethod<void>('stop');
+      } on PlatformException catch (e) {
+        debugPrint('veilid background execution failed to stop: $e');
+      }
+    }
+  }
+
+  Future<dynamic> _handleMethodCall(MethodCall call) async {
+    switch (call.method) {
+      // The platform took our background time away
+      case 'expired':
+        _platformStarted = false;
+        await _stop();
+    }
+  }
+
+  static bool get _hasPlatformIntegration =>
+      !kIsWeb &&
+      (defaultTargetPlatform == TargetPlatform.android ||
+          defaultTargetPlatform == TargetPlatform.iOS);
+}
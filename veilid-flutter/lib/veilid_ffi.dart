@@ -38,10 +38,22 @@ typedef _ChangeLogIgnoreDart = void Function(Pointer<Utf8>, Pointer<Utf8>);
 typedef _StartupVeilidCoreDart = void Function(int, int, Pointer<Utf8>);
 // fn get_veilid_state(port: i64)
 typedef _GetVeilidStateDart = void Function(int);
+// fn get_storage_usage(port: i64)
+typedef _GetStorageUsageDart = void Function(int);
+// fn get_storage_status(port: i64)
+typedef _GetStorageStatusDart = void Function(int);
 // fn attach(port: i64)
 typedef _AttachDart = void Function(int);
 // fn detach(port: i64)
 typedef _DetachDart = void Function(int);
+// fn keepalive_tick(port: i64)
+typedef _KeepaliveTickDart = void Function(int);
+// fn set_network_cost(port: i64, network_cost: FfiStr)
+typedef _SetNetworkCostDart = void Function(int, Pointer<Utf8>);
+// fn set_low_power(port: i64, low_power: bool)
+typedef _SetLowPowerDart = void Function(int, bool);
+// fn trigger_public_dial_info_detection(port: i64)
+typedef _TriggerPublicDialInfoDetectionDart = void Function(int);
 
 // fn routing_context(port: i64)
 typedef _RoutingContextDart = void Function(int);
@@ -51,11 +63,27 @@ typedef _ReleaseRoutingContextDart = int Function(int);
 typedef _RoutingContextWithDefaultSafetyDart = int Function(int);
 // fn routing_context_with_safety(id: u32, stability: FfiStr)
 typedef _RoutingContextWithSafetyDart = int Function(int, Pointer<Utf8>);
+// fn routing_context_with_safety_profile(id: u32, profile: FfiStr)
+typedef _RoutingContextWithSafetyProfileDart = int Function(int, Pointer<Utf8>);
 // fn routing_context_with_sequencing(id: u32, sequencing: FfiStr)
 typedef _RoutingContextWithSequencingDart = int Function(int, Pointer<Utf8>);
+// fn routing_context_with_timeout(id: u32, timeout_ms: FfiStr)
+typedef _RoutingContextWithTimeoutDart = int Function(int, Pointer<Utf8>);
 // fn routing_context_safety(port: i64,
 //    id: u32)
 typedef _RoutingContextSafetyDart = void Function(int, int);
+// fn routing_context_test_private_route(port: i64,
+//    id: u32, route_id: FfiStr)
+typedef _RoutingContextTestPrivateRouteDart = void Function(
+    int, int, Pointer<Utf8>);
+// fn routing_context_publish_private_route(port: i64,
+//     id: u32, key: FfiStr, subkey: u32, rotation: FfiStr)
+typedef _RoutingContextPublishPrivateRouteDart = void Function(
+    int, int, Pointer<Utf8>, int, Pointer<Utf8>);
+// fn routing_context_resolve_private_route(port: i64,
+//     id: u32, key: FfiStr, subkey: u32, force_refresh: bool)
+typedef _RoutingContextResolvePrivateRouteDart = void Function(
+    int, int, Pointer<Utf8>, int, bool);
 // fn routing_context_app_call(port: i64,
 //    id: u32, target: FfiStr, request: FfiStr)
 typedef _RoutingContextAppCallDart = void Function(
@@ -64,10 +92,25 @@ typedef _RoutingContextAppCallDart = void Function(
 //    id: u32, target: FfiStr, request: FfiStr)
 typedef _RoutingContextAppMessageDart = void Function(
     int, int, Pointer<Utf8>, Pointer<Utf8>);
+// fn routing_context_prewarm(port: i64,
+//    id: u32, target: FfiStr)
+typedef _RoutingContextPrewarmDart = void Function(int, int, Pointer<Utf8>);
+// fn routing_context_app_message_closest(port: i64,
+//    id: u32, key: FfiStr, count: u32, message: FfiStr)
+typedef _RoutingContextAppMessageClosestDart = void Function(
+    int, int, Pointer<Utf8>, int, Pointer<Utf8>);
 // fn routing_context_create_dht_record(port: i64,
 //    id: u32, kind: u32, schema: FfiStr)
 typedef _RoutingContextCreateDHTRecordDart = void Function(
     int, int, Pointer<Utf8>, int);
+// fn routing_context_create_expiring_dht_record(port: i64,
+//    id: u32, schema: FfiStr, expiration: u64, kind: u32)
+typedef _RoutingContextCreateExpiringDHTRecordDart = void Function(
+    int, int, Pointer<Utf8>, int, int);
+// fn routing_context_extend_dht_record_expiration(port: i64,
+//    id: u32, key: FfiStr, expiration: FfiStr)
+typedef _RoutingContextExtendDHTRecordExpirationDart = void Function(
+    int, int, Pointer<Utf8>, Pointer<Utf8>);
 // fn routing_context_open_dht_record(port: i64,
 //    id: u32, key: FfiStr, writer: FfiStr)
 typedef _RoutingContextOpenDHTRecordDart = void Function(
@@ -98,6 +141,29 @@ typedef _RoutingContextCancelDHTWatchDart = void Function(
 //     id: u32, key: FfiStr, subkeys: FfiStr, scope: FfiStr)
 typedef _RoutingContextInspectDHTRecordDart = void Function(
     int, int, Pointer<Utf8>, Pointer<Utf8>, Pointer<Utf8>);
+// fn routing_context_sync_dht_record(port: i64, id: u32, key: FfiStr)
+typedef _RoutingContextSyncDHTRecordDart = void Function(
+    int, int, Pointer<Utf8>);
+// fn routing_context_create_invite(port: i64, id: u32)
+typedef _RoutingContextCreateInviteDart = void Function(int, int);
+// fn routing_context_accept_invite(port: i64, id: u32, invite_blob: FfiStr)
+typedef _RoutingContextAcceptInviteDart = void Function(
+    int, int, Pointer<Utf8>);
+// fn routing_context_answer_invite(port: i64,
+//     id: u32, invite: FfiStr, app_call: FfiStr)
+typedef _RoutingContextAnswerInviteDart = void Function(
+    int, int, Pointer<Utf8>, Pointer<Utf8>);
+// fn routing_context_send_session_message(port: i64,
+//     id: u32, session: FfiStr, message: FfiStr)
+typedef _RoutingContextSendSessionMessageDart = void Function(
+    int, int, Pointer<Utf8>, Pointer<Utf8>);
+// fn routing_context_open_session_message(port: i64,
+//     id: u32, session: FfiStr, message: FfiStr)
+typedef _RoutingContextOpenSessionMessageDart = void Function(
+    int, int, Pointer<Utf8>, Pointer<Utf8>);
+// fn routing_context_close_session(port: i64, id: u32, session: FfiStr)
+typedef _RoutingContextCloseSessionDart = void Function(
+    int, int, Pointer<Utf8>);
 
 // fn new_private_route(port: i64)
 typedef _NewPrivateRouteDart = void Function(int);
@@ -226,6 +292,8 @@ typedef _CryptoCryptNoAuthDart = void Function(
 
 // fn now() -> u64
 typedef _NowDart = int Function();
+// fn get_network_time(port: i64)
+typedef _GetNetworkTimeDart = void Function(int);
 // fn debug(port: i64, log_level: FfiStr)
 typedef _DebugDart = void Function(int, Pointer<Utf8>);
 // fn shutdown_veilid_core(port: i64)
@@ -247,6 +315,8 @@ typedef _VeilidVersionDart = VeilidVersionFFI Function();
 
 // fn default_veilid_config() -> *mut c_char
 typedef _DefaultVeilidConfigDart = Pointer<Utf8> Function();
+// fn veilid_config_schema() -> *mut c_char
+typedef _VeilidConfigSchemaDart = Pointer<Utf8> Function();
 
 // Async message types
 const int messageOk = 0;
@@ -262,6 +332,39 @@ const int messageStreamClose = 8;
 // Interface factory for high level Veilid API
 Veilid getVeilid() => VeilidFFI(_dylib);
 
+// Keepalive ticks run in their own isolate so they keep going
+// when the UI isolate is paused
+class _KeepaliveTickerArgs {
+  _KeepaliveTickerArgs(this.interval, this.sendPort);
+  final Duration interval;
+  final SendPort sendPort;
+}
+
+Future<void> _keepaliveTickerMain(_KeepaliveTickerArgs args) async {
+  final veilid = VeilidFFI(_dylib);
+  final stopPort = ReceivePort('keepalive_ticker_stop');
+  args.sendPort.send(stopPort.sendPort);
+
+  final timer = Timer.periodic(args.interval, (_) async {
+    try {
+      await veilid.keepaliveTick();
+    } on VeilidAPIException {
+      // Not started or shutting down, try again next tick
+    }
+  });
+  await stopPort.first;
+  timer.cancel();
+}
+
+Future<void Function()> spawnKeepaliveTicker(Duration interval) async {
+  final readyPort = ReceivePort('keepalive_ticker_ready');
+  await Isolate.spawn(
+      _keepaliveTickerMain, _KeepaliveTickerArgs(interval, readyPort.sendPort),
+      debugName: 'veilid_keepalive');
+  final stopPort = await readyPort.first as SendPort;
+  return () => stopPort.send(null);
+}
+
 // Uint8List marshaling
 Uint8List convertUint8ListFromJson(dynamic json) =>
     base64UrlNoPadDecode(json as String);
@@ -565,6 +668,19 @@ class VeilidRoutingContextFFI extends VeilidRoutingContext {
     return out;
   }
 
+  @override
+  VeilidRoutingContextFFI withSafetyProfile(SafetyProfile profile,
+      {bool closeSelf = false}) {
+    _ctx.ensureValid();
+    final newId = _ctx.ffi._routingContextWithSafetyProfile(
+        _ctx.id!, jsonEncode(profile).toNativeUtf8());
+    final out = VeilidRoutingContextFFI._(_Ctx(newId, _ctx.ffi));
+    if (closeSelf) {
+      close();
+    }
+    return out;
+  }
+
   @override
   VeilidRoutingContextFFI withSequencing(Sequencing sequencing,
       {bool closeSelf = false}) {
@@ -578,6 +694,19 @@ class VeilidRoutingContextFFI extends VeilidRoutingContext {
     return out;
   }
 
+  @override
+  VeilidRoutingContextFFI withTimeout(int? timeoutMs,
+      {bool closeSelf = false}) {
+    _ctx.ensureValid();
+    final newId = _ctx.ffi._routingContextWithTimeout(
+        _ctx.id!, jsonEncode(timeoutMs).toNativeUtf8());
+    final out = VeilidRoutingContextFFI._(_Ctx(newId, _ctx.ffi));
+    if (closeSelf) {
+      close();
+    }
+    return out;
+  }
+
   @override
   Future<SafetySelection> safety() async {
     _ctx.ensureValid();
@@ -589,6 +718,50 @@ class VeilidRoutingContextFFI extends VeilidRoutingContext {
     return out;
   }
 
+  @override
+  Future<PrivateRouteTestResult> testPrivateRoute(String routeId) async {
+    _ctx.ensureValid();
+    final nativeEncodedRouteId = routeId.toNativeUtf8();
+
+    final recvPort = ReceivePort('routing_context_test_private_route');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextTestPrivateRoute(
+        sendPort.nativePort, _ctx.id!, nativeEncodedRouteId);
+    final out = await processFutureJson<PrivateRouteTestResult>(
+        PrivateRouteTestResult.fromJson, recvPort.first);
+    return out;
+  }
+
+  @override
+  Future<String> publishPrivateRoute(TypedKey key, int subkey,
+      {PrivateRouteRotation? rotation}) async {
+    _ctx.ensureValid();
+    final nativeKey = jsonEncode(key).toNativeUtf8();
+    final nativeRotation =
+        rotation != null ? jsonEncode(rotation).toNativeUtf8() : nullptr;
+
+    final recvPort = ReceivePort('routing_context_publish_private_route');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextPublishPrivateRoute(
+        sendPort.nativePort, _ctx.id!, nativeKey, subkey, nativeRotation);
+    final routeId = await processFuturePlain<String>(recvPort.first);
+    return routeId;
+  }
+
+  @override
+  Future<String?> resolvePrivateRoute(TypedKey key, int subkey,
+      {bool forceRefresh = false}) async {
+    _ctx.ensureValid();
+    final nativeKey = jsonEncode(key).toNativeUtf8();
+
+    final recvPort = ReceivePort('routing_context_resolve_private_route');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextResolvePrivateRoute(
+        sendPort.nativePort, _ctx.id!, nativeKey, subkey, forceRefresh);
+    final routeId = await processFuturePlain<String?>(recvPort.first);
+    return routeId;
+  }
+
   @override
   Future<Uint8List> appCall(String target, Uint8List request) async {
     _ctx.ensureValid();
@@ -616,6 +789,33 @@ class VeilidRoutingContextFFI extends VeilidRoutingContext {
     return processFutureVoid(recvPort.first);
   }
 
+  @override
+  Future<ContactMethod> prewarm(String target) async {
+    _ctx.ensureValid();
+    final nativeEncodedTarget = target.toNativeUtf8();
+
+    final recvPort = ReceivePort('routing_context_prewarm');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextPrewarm(
+        sendPort.nativePort, _ctx.id!, nativeEncodedTarget);
+    return processFutureJson(ContactMethod.fromJson, recvPort.first);
+  }
+
+  @override
+  Future<List<TypedKey>> appMessageClosest(
+      TypedKey key, int count, Uint8List message) async {
+    _ctx.ensureValid();
+    final nativeKey = jsonEncode(key).toNativeUtf8();
+    final nativeEncodedMessage = base64UrlNoPadEncode(message).toNativeUtf8();
+
+    final recvPort = ReceivePort('routing_context_app_message_closest');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextAppMessageClosest(sendPort.nativePort, _ctx.id!,
+        nativeKey, count, nativeEncodedMessage);
+    return processFutureJson(
+        jsonListConstructor<TypedKey>(TypedKey.fromJson), recvPort.first);
+  }
+
   @override
   Future<DHTRecordDescriptor> createDHTRecord(DHTSchema schema,
       {CryptoKind kind = 0}) async {
@@ -630,6 +830,36 @@ class VeilidRoutingContextFFI extends VeilidRoutingContext {
     return dhtRecordDescriptor;
   }
 
+  @override
+  Future<DHTRecordDescriptor> createExpiringDHTRecord(
+      DHTSchema schema, Timestamp expiration, {CryptoKind kind = 0}) async {
+    _ctx.ensureValid();
+    final nativeSchema = jsonEncode(schema).toNativeUtf8();
+    final nativeExpiration = expiration.value.toInt();
+    final recvPort = ReceivePort('routing_context_create_expiring_dht_record');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextCreateExpiringDHTRecord(sendPort.nativePort,
+        _ctx.id!, nativeSchema, nativeExpiration, kind);
+    final dhtRecordDescriptor =
+        await processFutureJson(DHTRecordDescriptor.fromJson, recvPort.first);
+    return dhtRecordDescriptor;
+  }
+
+  @override
+  Future<void> extendDHTRecordExpiration(
+      TypedKey key, Timestamp? expiration) async {
+    _ctx.ensureValid();
+    final nativeKey = jsonEncode(key).toNativeUtf8();
+    final nativeExpiration =
+        expiration != null ? jsonEncode(expiration).toNativeUtf8() : nullptr;
+    final recvPort =
+        ReceivePort('routing_context_extend_dht_record_expiration');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextExtendDHTRecordExpiration(
+        sendPort.nativePort, _ctx.id!, nativeKey, nativeExpiration);
+    return processFutureVoid(recvPort.first);
+  }
+
   @override
   Future<DHTRecordDescriptor> openDHTRecord(TypedKey key,
       {KeyPair? writer}) async {
@@ -759,6 +989,102 @@ class VeilidRoutingContextFFI extends VeilidRoutingContext {
         await processFutureJson(DHTRecordReport.fromJson, recvPort.first);
     return report;
   }
+
+  @override
+  Future<List<ValueSubkeyRange>> syncDHTRecord(TypedKey key) async {
+    _ctx.ensureValid();
+    final nativeKey = jsonEncode(key).toNativeUtf8();
+    final recvPort = ReceivePort('routing_context_sync_dht_record');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextSyncDHTRecord(
+        sendPort.nativePort, _ctx.id!, nativeKey);
+    return processFutureJson(
+        jsonListConstructor<ValueSubkeyRange>(ValueSubkeyRange.fromJson),
+        recvPort.first);
+  }
+
+  @override
+  Future<RendezvousInviteBlob> createInvite() async {
+    _ctx.ensureValid();
+    final recvPort = ReceivePort('routing_context_create_invite');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextCreateInvite(sendPort.nativePort, _ctx.id!);
+    final inviteBlob = await processFutureJson(
+        RendezvousInviteBlob.fromJson, recvPort.first);
+    return inviteBlob;
+  }
+
+  @override
+  Future<RendezvousSession> acceptInvite(Uint8List inviteBlob) async {
+    _ctx.ensureValid();
+    final nativeEncodedInviteBlob =
+        base64UrlNoPadEncode(inviteBlob).toNativeUtf8();
+
+    final recvPort = ReceivePort('routing_context_accept_invite');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextAcceptInvite(
+        sendPort.nativePort, _ctx.id!, nativeEncodedInviteBlob);
+    final session =
+        await processFutureJson(RendezvousSession.fromJson, recvPort.first);
+    return session;
+  }
+
+  @override
+  Future<RendezvousSession?> answerInvite(
+      RendezvousInvite invite, VeilidAppCall appCall) async {
+    _ctx.ensureValid();
+    final nativeInvite = jsonEncode(invite).toNativeUtf8();
+    final nativeAppCall = jsonEncode(appCall).toNativeUtf8();
+
+    final recvPort = ReceivePort('routing_context_answer_invite');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextAnswerInvite(
+        sendPort.nativePort, _ctx.id!, nativeInvite, nativeAppCall);
+    final session =
+        await processFutureOptJson(RendezvousSession.fromJson, recvPort.first);
+    return session;
+  }
+
+  @override
+  Future<void> sendSessionMessage(
+      RendezvousSession session, Uint8List message) async {
+    _ctx.ensureValid();
+    final nativeSession = jsonEncode(session).toNativeUtf8();
+    final nativeEncodedMessage = base64UrlNoPadEncode(message).toNativeUtf8();
+
+    final recvPort = ReceivePort('routing_context_send_session_message');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextSendSessionMessage(
+        sendPort.nativePort, _ctx.id!, nativeSession, nativeEncodedMessage);
+    return processFutureVoid(recvPort.first);
+  }
+
+  @override
+  Future<Uint8List> openSessionMessage(
+      RendezvousSession session, Uint8List message) async {
+    _ctx.ensureValid();
+    final nativeSession = jsonEncode(session).toNativeUtf8();
+    final nativeEncodedMessage = base64UrlNoPadEncode(message).toNativeUtf8();
+
+    final recvPort = ReceivePort('routing_context_open_session_message');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextOpenSessionMessage(
+        sendPort.nativePort, _ctx.id!, nativeSession, nativeEncodedMessage);
+    final out = await processFuturePlain<String>(recvPort.first);
+    return base64UrlNoPadDecode(out);
+  }
+
+  @override
+  Future<void> closeSession(RendezvousSession session) async {
+    _ctx.ensureValid();
+    final nativeSession = jsonEncode(session).toNativeUtf8();
+
+    final recvPort = ReceivePort('routing_context_close_session');
+    final sendPort = recvPort.sendPort;
+    _ctx.ffi._routingContextCloseSession(
+        sendPort.nativePort, _ctx.id!, nativeSession);
+    return processFutureVoid(recvPort.first);
+  }
 }
 
 class _TDBT {
@@ -1252,10 +1578,28 @@ class VeilidFFI extends Veilid {
         _getVeilidState =
             dylib.lookupFunction<Void Function(Int64), _GetVeilidStateDart>(
                 'get_veilid_state'),
+        _getStorageUsage =
+            dylib.lookupFunction<Void Function(Int64), _GetStorageUsageDart>(
+                'get_storage_usage'),
+        _getStorageStatus =
+            dylib.lookupFunction<Void Function(Int64), _GetStorageStatusDart>(
+                'get_storage_status'),
         _attach =
             dylib.lookupFunction<Void Function(Int64), _AttachDart>('attach'),
         _detach =
             dylib.lookupFunction<Void Function(Int64), _DetachDart>('detach'),
+        _keepaliveTick =
+            dylib.lookupFunction<Void Function(Int64), _KeepaliveTickDart>(
+                'keepalive_tick'),
+        _setNetworkCost = dylib.lookupFunction<
+            Void Function(Int64, Pointer<Utf8>),
+            _SetNetworkCostDart>('set_network_cost'),
+        _setLowPower =
+            dylib.lookupFunction<Void Function(Int64, Bool), _SetLowPowerDart>(
+                'set_low_power'),
+        _triggerPublicDialInfoDetection = dylib.lookupFunction<
+                Void Function(Int64), _TriggerPublicDialInfoDetectionDart>(
+            'trigger_public_dial_info_detection'),
         _shutdownVeilidCore =
             dylib.lookupFunction<Void Function(Int64), _ShutdownVeilidCoreDart>(
                 'shutdown_veilid_core'),
@@ -1270,23 +1614,60 @@ class VeilidFFI extends Veilid {
         _routingContextWithSafety = dylib.lookupFunction<
             Uint32 Function(Uint32, Pointer<Utf8>),
             _RoutingContextWithSafetyDart>('routing_context_with_safety'),
+        _routingContextWithSafetyProfile = dylib.lookupFunction<
+                Uint32 Function(Uint32, Pointer<Utf8>),
+                _RoutingContextWithSafetyProfileDart>(
+            'routing_context_with_safety_profile'),
         _routingContextWithSequencing = dylib.lookupFunction<
                 Uint32 Function(Uint32, Pointer<Utf8>),
                 _RoutingContextWithSequencingDart>(
             'routing_context_with_sequencing'),
+        _routingContextWithTimeout = dylib.lookupFunction<
+                Uint32 Function(Uint32, Pointer<Utf8>),
+                _RoutingContextWithTimeoutDart>(
+            'routing_context_with_timeout'),
         _routingContextSafety = dylib.lookupFunction<
             Void Function(Int64, Uint32),
             _RoutingContextSafetyDart>('routing_context_safety'),
+        _routingContextTestPrivateRoute = dylib.lookupFunction<
+                Void Function(Int64, Uint32, Pointer<Utf8>),
+                _RoutingContextTestPrivateRouteDart>(
+            'routing_context_test_private_route'),
+        _routingContextPublishPrivateRoute = dylib.lookupFunction<
+                Void Function(
+                    Int64, Uint32, Pointer<Utf8>, Uint32, Pointer<Utf8>),
+                _RoutingContextPublishPrivateRouteDart>(
+            'routing_context_publish_private_route'),
+        _routingContextResolvePrivateRoute = dylib.lookupFunction<
+                Void Function(Int64, Uint32, Pointer<Utf8>, Uint32, Bool),
+                _RoutingContextResolvePrivateRouteDart>(
+            'routing_context_resolve_private_route'),
         _routingContextAppCall = dylib.lookupFunction<
             Void Function(Int64, Uint32, Pointer<Utf8>, Pointer<Utf8>),
             _RoutingContextAppCallDart>('routing_context_app_call'),
         _routingContextAppMessage = dylib.lookupFunction<
             Void Function(Int64, Uint32, Pointer<Utf8>, Pointer<Utf8>),
             _RoutingContextAppMessageDart>('routing_context_app_message'),
+        _routingContextPrewarm = dylib.lookupFunction<
+            Void Function(Int64, Uint32, Pointer<Utf8>),
+            _RoutingContextPrewarmDart>('routing_context_prewarm'),
+        _routingContextAppMessageClosest = dylib.lookupFunction<
+                Void Function(
+                    Int64, Uint32, Pointer<Utf8>, Uint32, Pointer<Utf8>),
+                _RoutingContextAppMessageClosestDart>(
+            'routing_context_app_message_closest'),
         _routingContextCreateDHTRecord = dylib.lookupFunction<
                 Void Function(Int64, Uint32, Pointer<Utf8>, Uint32),
                 _RoutingContextCreateDHTRecordDart>(
             'routing_context_create_dht_record'),
+        _routingContextCreateExpiringDHTRecord = dylib.lookupFunction<
+                Void Function(Int64, Uint32, Pointer<Utf8>, Uint64, Uint32),
+                _RoutingContextCreateExpiringDHTRecordDart>(
+            'routing_context_create_expiring_dht_record'),
+        _routingContextExtendDHTRecordExpiration = dylib.lookupFunction<
+                Void Function(Int64, Uint32, Pointer<Utf8>, Pointer<Utf8>),
+                _RoutingContextExtendDHTRecordExpirationDart>(
+            'routing_context_extend_dht_record_expiration'),
         _routingContextOpenDHTRecord = dylib.lookupFunction<
                 Void Function(Int64, Uint32, Pointer<Utf8>, Pointer<Utf8>),
                 _RoutingContextOpenDHTRecordDart>(
@@ -1320,6 +1701,30 @@ class VeilidFFI extends Veilid {
                     Int64, Uint32, Pointer<Utf8>, Pointer<Utf8>, Pointer<Utf8>),
                 _RoutingContextInspectDHTRecordDart>(
             'routing_context_inspect_dht_record'),
+        _routingContextSyncDHTRecord = dylib.lookupFunction<
+                Void Function(Int64, Uint32, Pointer<Utf8>),
+                _RoutingContextSyncDHTRecordDart>(
+            'routing_context_sync_dht_record'),
+        _routingContextCreateInvite = dylib.lookupFunction<
+            Void Function(Int64, Uint32),
+            _RoutingContextCreateInviteDart>('routing_context_create_invite'),
+        _routingContextAcceptInvite = dylib.lookupFunction<
+            Void Function(Int64, Uint32, Pointer<Utf8>),
+            _RoutingContextAcceptInviteDart>('routing_context_accept_invite'),
+        _routingContextAnswerInvite = dylib.lookupFunction<
+            Void Function(Int64, Uint32, Pointer<Utf8>, Pointer<Utf8>),
+            _RoutingContextAnswerInviteDart>('routing_context_answer_invite'),
+        _routingContextSendSessionMessage = dylib.lookupFunction<
+                Void Function(Int64, Uint32, Pointer<Utf8>, Pointer<Utf8>),
+                _RoutingContextSendSessionMessageDart>(
+            'routing_context_send_session_message'),
+        _routingContextOpenSessionMessage = dylib.lookupFunction<
+                Void Function(Int64, Uint32, Pointer<Utf8>, Pointer<Utf8>),
+                _RoutingContextOpenSessionMessageDart>(
+            'routing_context_open_session_message'),
+        _routingContextCloseSession = dylib.lookupFunction<
+            Void Function(Int64, Uint32, Pointer<Utf8>),
+            _RoutingContextCloseSessionDart>('routing_context_close_session'),
         _newPrivateRoute =
             dylib.lookupFunction<Void Function(Int64), _NewPrivateRouteDart>(
                 'new_private_route'),
@@ -1457,6 +1862,9 @@ class VeilidFFI extends Veilid {
                 Int64, Uint32, Pointer<Utf8>, Pointer<Utf8>, Pointer<Utf8>),
             _CryptoCryptNoAuthDart>('crypto_crypt_no_auth'),
         _now = dylib.lookupFunction<Uint64 Function(), _NowDart>('now'),
+        _getNetworkTime =
+            dylib.lookupFunction<Void Function(Int64), _GetNetworkTimeDart>(
+                'get_network_time'),
         _debug = dylib.lookupFunction<Void Function(Int64, Pointer<Utf8>),
             _DebugDart>('debug'),
         _veilidVersionString = dylib.lookupFunction<Pointer<Utf8> Function(),
@@ -1464,7 +1872,9 @@ class VeilidFFI extends Veilid {
         _veilidVersion = dylib.lookupFunction<VeilidVersionFFI Function(),
             _VeilidVersionDart>('veilid_version'),
         _defaultVeilidConfig = dylib.lookupFunction<Pointer<Utf8> Function(),
-            _DefaultVeilidConfigDart>('default_veilid_config') {
+            _DefaultVeilidConfigDart>('default_veilid_config'),
+        _veilidConfigSchema = dylib.lookupFunction<Pointer<Utf8> Function(),
+            _VeilidConfigSchemaDart>('veilid_config_schema') {
     // Get veilid_flutter initializer
     final initializeVeilidFlutter = _dylib.lookupFunction<
         Void Function(Pointer<_DartPostCObject>, Pointer<Utf8>),
@@ -1486,19 +1896,38 @@ class VeilidFFI extends Veilid {
   final _ChangeLogIgnoreDart _changeLogIgnore;
   final _StartupVeilidCoreDart _startupVeilidCore;
   final _GetVeilidStateDart _getVeilidState;
+  final _GetStorageUsageDart _getStorageUsage;
+  final _GetStorageStatusDart _getStorageStatus;
   final _AttachDart _attach;
   final _DetachDart _detach;
+  final _KeepaliveTickDart _keepaliveTick;
+  final _SetNetworkCostDart _setNetworkCost;
+  final _SetLowPowerDart _setLowPower;
+  final _TriggerPublicDialInfoDetectionDart _triggerPublicDialInfoDetection;
   final _ShutdownVeilidCoreDart _shutdownVeilidCore;
 
   final _RoutingContextDart _routingContext;
   final _ReleaseRoutingContextDart _releaseRoutingContext;
   final _RoutingContextWithDefaultSafetyDart _routingContextWithDefaultSafety;
   final _RoutingContextWithSafetyDart _routingContextWithSafety;
+  final _RoutingContextWithSafetyProfileDart _routingContextWithSafetyProfile;
   final _RoutingContextWithSequencingDart _routingContextWithSequencing;
+  final _RoutingContextWithTimeoutDart _routingContextWithTimeout;
   final _RoutingContextSafetyDart _routingContextSafety;
+  final _RoutingContextTestPrivateRouteDart _routingContextTestPrivateRoute;
+  final _RoutingContextPublishPrivateRouteDart
+      _routingContextPublishPrivateRoute;
+  final _RoutingContextResolvePrivateRouteDart
+      _routingContextResolvePrivateRoute;
   final _RoutingContextAppCallDart _routingContextAppCall;
   final _RoutingContextAppMessageDart _routingContextAppMessage;
+  final _RoutingContextPrewarmDart _routingContextPrewarm;
+  final _RoutingContextAppMessageClosestDart _routingContextAppMessageClosest;
   final _RoutingContextCreateDHTRecordDart _routingContextCreateDHTRecord;
+  final _RoutingContextCreateExpiringDHTRecordDart
+      _routingContextCreateExpiringDHTRecord;
+  final _RoutingContextExtendDHTRecordExpirationDart
+      _routingContextExtendDHTRecordExpiration;
   final _RoutingContextOpenDHTRecordDart _routingContextOpenDHTRecord;
   final _RoutingContextCloseDHTRecordDart _routingContextCloseDHTRecord;
   final _RoutingContextDeleteDHTRecordDart _routingContextDeleteDHTRecord;
@@ -1507,6 +1936,15 @@ class VeilidFFI extends Veilid {
   final _RoutingContextWatchDHTValuesDart _routingContextWatchDHTValues;
   final _RoutingContextCancelDHTWatchDart _routingContextCancelDHTWatch;
   final _RoutingContextInspectDHTRecordDart _routingContextInspectDHTRecord;
+  final _RoutingContextSyncDHTRecordDart _routingContextSyncDHTRecord;
+  final _RoutingContextCreateInviteDart _routingContextCreateInvite;
+  final _RoutingContextAcceptInviteDart _routingContextAcceptInvite;
+  final _RoutingContextAnswerInviteDart _routingContextAnswerInvite;
+  final _RoutingContextSendSessionMessageDart
+      _routingContextSendSessionMessage;
+  final _RoutingContextOpenSessionMessageDart
+      _routingContextOpenSessionMessage;
+  final _RoutingContextCloseSessionDart _routingContextCloseSession;
 
   final _NewPrivateRouteDart _newPrivateRoute;
   final _NewCustomPrivateRouteDart _newCustomPrivateRoute;
@@ -1562,10 +2000,12 @@ class VeilidFFI extends Veilid {
   final _CryptoCryptNoAuthDart _cryptoCryptNoAuth;
 
   final _NowDart _now;
+  final _GetNetworkTimeDart _getNetworkTime;
   final _DebugDart _debug;
   final _VeilidVersionStringDart _veilidVersionString;
   final _VeilidVersionDart _veilidVersion;
   final _DefaultVeilidConfigDart _defaultVeilidConfig;
+  final _VeilidConfigSchemaDart _veilidConfigSchema;
 
   @override
   void initializeVeilidCore(Map<String, dynamic> platformConfigJson) {
@@ -1619,6 +2059,22 @@ class VeilidFFI extends Veilid {
     return processFutureJson(VeilidState.fromJson, recvPort.first);
   }
 
+  @override
+  Future<DHTStorageUsage> getStorageUsage() async {
+    final recvPort = ReceivePort('get_storage_usage');
+    final sendPort = recvPort.sendPort;
+    _getStorageUsage(sendPort.nativePort);
+    return processFutureJson(DHTStorageUsage.fromJson, recvPort.first);
+  }
+
+  @override
+  Future<StorageManagerStatus> getStorageStatus() async {
+    final recvPort = ReceivePort('get_storage_status');
+    final sendPort = recvPort.sendPort;
+    _getStorageStatus(sendPort.nativePort);
+    return processFutureJson(StorageManagerStatus.fromJson, recvPort.first);
+  }
+
   @override
   Future<void> attach() async {
     final recvPort = ReceivePort('attach');
@@ -1635,6 +2091,40 @@ class VeilidFFI extends Veilid {
     return processFutureVoid(recvPort.first);
   }
 
+  @override
+  Future<void> keepaliveTick() async {
+    final recvPort = ReceivePort('keepalive_tick');
+    final sendPort = recvPort.sendPort;
+    _keepaliveTick(sendPort.nativePort);
+    return processFutureVoid(recvPort.first);
+  }
+
+  @override
+  Future<void> setNetworkCost(NetworkCost networkCost) async {
+    final nativeNetworkCost = jsonEncode(networkCost).toNativeUtf8();
+    final recvPort = ReceivePort('set_network_cost');
+    final sendPort = recvPort.sendPort;
+    _setNetworkCost(sendPort.nativePort, nativeNetworkCost);
+    malloc.free(nativeNetworkCost);
+    return processFutureVoid(recvPort.first);
+  }
+
+  @override
+  Future<void> setLowPower(bool lowPower) async {
+    final recvPort = ReceivePort('set_low_power');
+    final sendPort = recvPort.sendPort;
+    _setLowPower(sendPort.nativePort, lowPower);
+    return processFutureVoid(recvPort.first);
+  }
+
+  @override
+  Future<void> triggerPublicDialInfoDetection() async {
+    final recvPort = ReceivePort('trigger_public_dial_info_detection');
+    final sendPort = recvPort.sendPort;
+    _triggerPublicDialInfoDetection(sendPort.nativePort);
+    return processFutureVoid(recvPort.first);
+  }
+
   @override
   Future<void> shutdownVeilidCore() async {
     final recvPort = ReceivePort('shutdown_veilid_core');
@@ -1776,6 +2266,14 @@ class VeilidFFI extends Veilid {
     return Timestamp(value: BigInt.from(ts));
   }
 
+  @override
+  Future<NetworkTime> getNetworkTime() async {
+    final recvPort = ReceivePort('get_network_time');
+    final sendPort = recvPort.sendPort;
+    _getNetworkTime(sendPort.nativePort);
+    return processFutureJson(NetworkTime.fromJson, recvPort.first);
+  }
+
   @override
   Future<TypedKeyPair> generateKeyPair(CryptoKind kind) async {
     final recvPort = ReceivePort('generate_key_pair');
@@ -1818,4 +2316,12 @@ class VeilidFFI extends Veilid {
     _freeString(defaultVeilidConfig);
     return ret;
   }
+
+  @override
+  String veilidConfigSchema() {
+    final veilidConfigSchema = _veilidConfigSchema();
+    final ret = veilidConfigSchema.toDartString();
+    _freeString(veilidConfigSchema);
+    return ret;
+  }
 }
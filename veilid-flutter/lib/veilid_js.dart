@@ -12,6 +12,20 @@ import 'veilid.dart';
 
 Veilid getVeilid() => VeilidJS();
 
+// There are no isolates on the web, a SharedWorker set with
+// setKeepalivePort is what keeps a backgrounded tab ticking
+Future<void Function()> spawnKeepaliveTicker(Duration interval) async {
+  final veilid = getVeilid();
+  final timer = Timer.periodic(interval, (_) async {
+    try {
+      await veilid.keepaliveTick();
+    } on VeilidAPIException {
+      // Not started or shutting down, try again next tick
+    }
+  });
+  return timer.cancel;
+}
+
 Object wasm = js_util.getProperty(html.window, 'veilid_wasm');
 
 Uint8List convertUint8ListFromJson(dynamic json) => Uint8List.fromList(
@@ -92,6 +106,19 @@ class VeilidRoutingContextJS extends VeilidRoutingContext {
     return out;
   }
 
+  @override
+  VeilidRoutingContextJS withSafetyProfile(SafetyProfile profile,
+      {bool closeSelf = false}) {
+    final id = _ctx.requireId();
+    final newId = js_util.callMethod<int>(
+        wasm, 'routing_context_with_safety_profile', [id, jsonEncode(profile)]);
+    final out = VeilidRoutingContextJS._(_Ctx(newId, _ctx.js));
+    if (closeSelf) {
+      close();
+    }
+    return out;
+  }
+
   @override
   VeilidRoutingContextJS withSequencing(Sequencing sequencing,
       {bool closeSelf = false}) {
@@ -105,6 +132,19 @@ class VeilidRoutingContextJS extends VeilidRoutingContext {
     return out;
   }
 
+  @override
+  VeilidRoutingContextJS withTimeout(int? timeoutMs,
+      {bool closeSelf = false}) {
+    final id = _ctx.requireId();
+    final newId = js_util.callMethod<int>(
+        wasm, 'routing_context_with_timeout', [id, timeoutMs]);
+    final out = VeilidRoutingContextJS._(_Ctx(newId, _ctx.js));
+    if (closeSelf) {
+      close();
+    }
+    return out;
+  }
+
   @override
   Future<SafetySelection> safety() async {
     final id = _ctx.requireId();
@@ -114,6 +154,37 @@ class VeilidRoutingContextJS extends VeilidRoutingContext {
     ]))));
   }
 
+  @override
+  Future<PrivateRouteTestResult> testPrivateRoute(String routeId) async {
+    final id = _ctx.requireId();
+    return PrivateRouteTestResult.fromJson(jsonDecode(await _wrapApiPromise(
+        js_util.callMethod(
+            wasm, 'routing_context_test_private_route', [id, routeId]))));
+  }
+
+  @override
+  Future<String> publishPrivateRoute(TypedKey key, int subkey,
+      {PrivateRouteRotation? rotation}) async {
+    final id = _ctx.requireId();
+    return _wrapApiPromise(js_util.callMethod(
+        wasm, 'routing_context_publish_private_route', [
+      id,
+      jsonEncode(key),
+      subkey,
+      if (rotation != null) jsonEncode(rotation) else null
+    ]));
+  }
+
+  @override
+  Future<String?> resolvePrivateRoute(TypedKey key, int subkey,
+      {bool forceRefresh = false}) async {
+    final id = _ctx.requireId();
+    return jsonDecode(await _wrapApiPromise(js_util.callMethod(
+        wasm,
+        'routing_context_resolve_private_route',
+        [id, jsonEncode(key), subkey, forceRefresh]))) as String?;
+  }
+
   @override
   Future<Uint8List> appCall(String target, Uint8List request) async {
     final id = _ctx.requireId();
@@ -132,6 +203,26 @@ class VeilidRoutingContextJS extends VeilidRoutingContext {
         wasm, 'routing_context_app_message', [id, target, encodedMessage]));
   }
 
+  @override
+  Future<ContactMethod> prewarm(String target) async {
+    final id = _ctx.requireId();
+    return ContactMethod.fromJson(jsonDecode(await _wrapApiPromise(
+        js_util.callMethod(wasm, 'routing_context_prewarm', [id, target]))));
+  }
+
+  @override
+  Future<List<TypedKey>> appMessageClosest(
+      TypedKey key, int count, Uint8List message) async {
+    final id = _ctx.requireId();
+    final encodedMessage = base64UrlNoPadEncode(message);
+
+    return jsonListConstructor(TypedKey.fromJson)(jsonDecode(
+        await _wrapApiPromise(js_util.callMethod(
+            wasm,
+            'routing_context_app_message_closest',
+            [id, jsonEncode(key), count, encodedMessage]))));
+  }
+
   @override
   Future<DHTRecordDescriptor> createDHTRecord(DHTSchema schema,
       {CryptoKind kind = 0}) async {
@@ -141,6 +232,26 @@ class VeilidRoutingContextJS extends VeilidRoutingContext {
             [id, jsonEncode(schema), kind]))));
   }
 
+  @override
+  Future<DHTRecordDescriptor> createExpiringDHTRecord(
+      DHTSchema schema, Timestamp expiration, {CryptoKind kind = 0}) async {
+    final id = _ctx.requireId();
+    return DHTRecordDescriptor.fromJson(jsonDecode(await _wrapApiPromise(js_util
+        .callMethod(wasm, 'routing_context_create_expiring_dht_record',
+            [id, jsonEncode(schema), expiration.toString(), kind]))));
+  }
+
+  @override
+  Future<void> extendDHTRecordExpiration(TypedKey key, Timestamp? expiration) {
+    final id = _ctx.requireId();
+    return _wrapApiPromise(js_util.callMethod(
+        wasm, 'routing_context_extend_dht_record_expiration', [
+      id,
+      jsonEncode(key),
+      if (expiration != null) expiration.toString() else null
+    ]));
+  }
+
   @override
   Future<DHTRecordDescriptor> openDHTRecord(TypedKey key,
       {KeyPair? writer}) async {
@@ -244,6 +355,74 @@ class VeilidRoutingContextJS extends VeilidRoutingContext {
         .callMethod(wasm, 'routing_context_inspect_dht_record',
             [id, jsonEncode(key), jsonEncode(subkeys), jsonEncode(scope)]))));
   }
+
+  @override
+  Future<List<ValueSubkeyRange>> syncDHTRecord(TypedKey key) async {
+    final id = _ctx.requireId();
+    return jsonListConstructor(ValueSubkeyRange.fromJson)(jsonDecode(
+        await _wrapApiPromise(js_util.callMethod(
+            wasm, 'routing_context_sync_dht_record', [id, jsonEncode(key)]))));
+  }
+
+  @override
+  Future<RendezvousInviteBlob> createInvite() async {
+    final id = _ctx.requireId();
+    return RendezvousInviteBlob.fromJson(jsonDecode(await _wrapApiPromise(
+        js_util.callMethod(wasm, 'routing_context_create_invite', [id]))));
+  }
+
+  @override
+  Future<RendezvousSession> acceptInvite(Uint8List inviteBlob) async {
+    final id = _ctx.requireId();
+    final encodedInviteBlob = base64UrlNoPadEncode(inviteBlob);
+    return RendezvousSession.fromJson(jsonDecode(await _wrapApiPromise(
+        js_util.callMethod(
+            wasm, 'routing_context_accept_invite', [id, encodedInviteBlob]))));
+  }
+
+  @override
+  Future<RendezvousSession?> answerInvite(
+      RendezvousInvite invite, VeilidAppCall appCall) async {
+    final id = _ctx.requireId();
+    final opt = await _wrapApiPromise<String?>(js_util.callMethod(
+        wasm,
+        'routing_context_answer_invite',
+        [id, jsonEncode(invite), jsonEncode(appCall)]));
+    if (opt == null) {
+      return null;
+    }
+    final jsonOpt = jsonDecode(opt);
+    return jsonOpt == null ? null : RendezvousSession.fromJson(jsonOpt);
+  }
+
+  @override
+  Future<void> sendSessionMessage(
+      RendezvousSession session, Uint8List message) async {
+    final id = _ctx.requireId();
+    final encodedMessage = base64UrlNoPadEncode(message);
+    return _wrapApiPromise(js_util.callMethod(
+        wasm,
+        'routing_context_send_session_message',
+        [id, jsonEncode(session), encodedMessage]));
+  }
+
+  @override
+  Future<Uint8List> openSessionMessage(
+      RendezvousSession session, Uint8List message) async {
+    final id = _ctx.requireId();
+    final encodedMessage = base64UrlNoPadEncode(message);
+    return base64UrlNoPadDecode(await _wrapApiPromise(js_util.callMethod(
+        wasm,
+        'routing_context_open_session_message',
+        [id, jsonEncode(session), encodedMessage])));
+  }
+
+  @override
+  Future<void> closeSession(RendezvousSession session) async {
+    final id = _ctx.requireId();
+    return _wrapApiPromise(js_util.callMethod(
+        wasm, 'routing_context_close_session', [id, jsonEncode(session)]));
+  }
 }
 
 // JS implementation of VeilidCryptoSystem
@@ -623,6 +802,16 @@ class VeilidJS extends Veilid {
       VeilidState.fromJson(jsonDecode(await _wrapApiPromise<String>(
           js_util.callMethod(wasm, 'get_veilid_state', []))));
 
+  @override
+  Future<DHTStorageUsage> getStorageUsage() async =>
+      DHTStorageUsage.fromJson(jsonDecode(await _wrapApiPromise<String>(
+          js_util.callMethod(wasm, 'get_storage_usage', []))));
+
+  @override
+  Future<StorageManagerStatus> getStorageStatus() async =>
+      StorageManagerStatus.fromJson(jsonDecode(await _wrapApiPromise<String>(
+          js_util.callMethod(wasm, 'get_storage_status', []))));
+
   @override
   Future<void> attach() =>
       _wrapApiPromise(js_util.callMethod(wasm, 'attach', []));
@@ -631,6 +820,22 @@ class VeilidJS extends Veilid {
   Future<void> detach() =>
       _wrapApiPromise(js_util.callMethod(wasm, 'detach', []));
 
+  @override
+  Future<void> keepaliveTick() =>
+      _wrapApiPromise(js_util.callMethod(wasm, 'keepalive_tick', []));
+
+  @override
+  Future<void> setNetworkCost(NetworkCost networkCost) => _wrapApiPromise(
+      js_util.callMethod(wasm, 'set_network_cost', [jsonEncode(networkCost)]));
+
+  @override
+  Future<void> setLowPower(bool lowPower) => _wrapApiPromise(
+      js_util.callMethod(wasm, 'set_low_power', [lowPower]));
+
+  @override
+  Future<void> triggerPublicDialInfoDetection() => _wrapApiPromise(
+      js_util.callMethod(wasm, 'trigger_public_dial_info_detection', []));
+
   @override
   Future<void> shutdownVeilidCore() =>
       _wrapApiPromise(js_util.callMethod(wasm, 'shutdown_veilid_core', []));
@@ -731,6 +936,11 @@ class VeilidJS extends Veilid {
   @override
   Timestamp now() => Timestamp.fromString(js_util.callMethod(wasm, 'now', []));
 
+  @override
+  Future<NetworkTime> getNetworkTime() async =>
+      NetworkTime.fromJson(jsonDecode(await _wrapApiPromise<String>(
+          js_util.callMethod(wasm, 'get_network_time', []))));
+
   @override
   Future<String> debug(String command) async =>
       _wrapApiPromise(js_util.callMethod(wasm, 'debug', [command]));
@@ -751,4 +961,8 @@ class VeilidJS extends Veilid {
   @override
   String defaultVeilidConfig() =>
       js_util.callMethod(wasm, 'default_veilid_config', []);
+
+  @override
+  String veilidConfigSchema() =>
+      js_util.callMethod(wasm, 'veilid_config_schema', []);
 }
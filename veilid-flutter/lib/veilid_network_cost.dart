@@ -0,0 +1,87 @@
+import 'dart:async';
+
+import 'package:flutter/foundation.dart';
+import 'package:flutter/services.dart';
+
+import 'veilid.dart';
+
+//////////////////////////////////////
+/// Network cost monitor
+
+/// Reports whether the device is on a metered or roaming network to Veilid.
+///
+/// The platform watches the network and tells us whenever its cost changes,
+/// and the node applies the `network.metered_policy` and
+/// `network.roaming_policy` settings from its config. Android reports both
+/// metered and roaming networks, iOS only reports metered ones. On other
+/// platforms call [Veilid.setNetworkCost] directly.
+class VeilidNetworkCostMonitor {
+  VeilidNetworkCostMonitor._();
+
+  static final VeilidNetworkCostMonitor instance =
+      VeilidNetworkCostMonitor._();
+
+  static const MethodChannel _channel = MethodChannel('veilid/network_cost');
+
+  bool _enabled = false;
+  NetworkCost _networkCost = const NetworkCost();
+
+  /// The most recent network cost reported by the platform
+  NetworkCost get networkCost => _networkCost;
+
+  /// Start reporting the network cost to the node
+  Future<void> enable() async {
+    if (_enabled || !_hasPlatformIntegration) {
+      return;
+    }
+    _enabled = true;
+    _channel.setMethodCallHandler(_handleMethodCall);
+    try {
+      await _channel.invokeMethod<void>('start');
+    } on PlatformException catch (e) {
+      debugPrint('veilid network cost monitoring unavailable: $e');
+    }
+  }
+
+  /// Stop reporting the network cost, and let the node act normally again
+  Future<void> disable() async {
+    if (!_enabled) {
+      return;
+    }
+    _enabled = false;
+    _channel.setMethodCallHandler(null);
+    try {
+      await _channel.invokeMethod<void>('stop');
+    } on PlatformException catch (e) {
+      debugPrint('veilid network cost monitoring failed to stop: $e');
+    }
+    await _report(const NetworkCost());
+  }
+
+  Future<void> _report(NetworkCost networkCost) async {
+    if (networkCost == _networkCost) {
+      return;
+    }
+    _networkCost = networkCost;
+    try {
+      await Veilid.instance.setNetworkCost(networkCost);
+    } on VeilidAPIException catch (e) {
+      debugPrint('veilid network cost could not be set: $e');
+    }
+  }
+
+  Future<dynamic> _handleMethodCall(MethodCall call) async {
+    switch (call.method) {
+      case 'changed':
+        final args = call.arguments as Map<dynamic, dynamic>;
+        await _report(NetworkCost(
+            metered: args['metered'] as bool? ?? false,
+            roaming: args['roaming'] as bool? ?? false));
+    }
+  }
+
+  static bool get _hasPlatformIntegration =>
+      !kIsWeb &&
+      (defaultTargetPlatform == TargetPlatform.android ||
+          defaultTargetPlatform == TargetPlatform.iOS);
+}
@@ -7,3 +7,5 @@ Uint8List convertUint8ListFromJson(dynamic json) =>
     throw UnsupportedError('Cannot convertUint8ListFromJson');
 dynamic convertUint8ListToJson(Uint8List data) =>
     throw UnsupportedError('Cannot convertUint8ListToJson');
+Future<void Function()> spawnKeepaliveTicker(Duration interval) =>
+    throw UnsupportedError('Cannot spawnKeepaliveTicker');
@@ -303,7 +303,7 @@ pub extern "C" fn initialize_veilid_core(platform_config: FfiStr) {
             platform_config.logging.api.level,
             &platform_config.logging.api.ignore_log_targets,
         );
-        let layer = veilid_core::ApiTracingLayer::get().with_filter(filter.clone());
+        let layer = veilid_core::ApiTracingLayer::new().with_filter(filter.clone());
         filters.insert("api", filter);
         layers.push(layer.boxed());
     }
@@ -421,6 +421,24 @@ pub extern "C" fn get_veilid_state(port: i64) {
     });
 }
 
+#[no_mangle]
+pub extern "C" fn get_storage_usage(port: i64) {
+    DartIsolateWrapper::new(port).spawn_result_json(async move {
+        let veilid_api = get_veilid_api().await?;
+        let storage_usage = veilid_api.get_storage_usage().await?;
+        APIResult::Ok(storage_usage)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn get_storage_status(port: i64) {
+    DartIsolateWrapper::new(port).spawn_result_json(async move {
+        let veilid_api = get_veilid_api().await?;
+        let storage_status = veilid_api.get_storage_status().await?;
+        APIResult::Ok(storage_status)
+    });
+}
+
 #[no_mangle]
 pub extern "C" fn attach(port: i64) {
     DartIsolateWrapper::new(port).spawn_result(async move {
@@ -439,6 +457,44 @@ pub extern "C" fn detach(port: i64) {
     });
 }
 
+#[no_mangle]
+pub extern "C" fn keepalive_tick(port: i64) {
+    DartIsolateWrapper::new(port).spawn_result(async move {
+        let veilid_api = get_veilid_api().await?;
+        veilid_api.keepalive_tick()?;
+        APIRESULT_VOID
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn set_network_cost(port: i64, network_cost: FfiStr) {
+    let network_cost: veilid_core::NetworkCost =
+        veilid_core::deserialize_opt_json(network_cost.into_opt_string()).unwrap();
+    DartIsolateWrapper::new(port).spawn_result(async move {
+        let veilid_api = get_veilid_api().await?;
+        veilid_api.set_network_cost(network_cost).await?;
+        APIRESULT_VOID
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn set_low_power(port: i64, low_power: bool) {
+    DartIsolateWrapper::new(port).spawn_result(async move {
+        let veilid_api = get_veilid_api().await?;
+        veilid_api.set_low_power(low_power)?;
+        APIRESULT_VOID
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn trigger_public_dial_info_detection(port: i64) {
+    DartIsolateWrapper::new(port).spawn_result(async move {
+        let veilid_api = get_veilid_api().await?;
+        veilid_api.trigger_public_dial_info_detection()?;
+        APIRESULT_VOID
+    });
+}
+
 #[no_mangle]
 #[instrument]
 pub extern "C" fn shutdown_veilid_core(port: i64) {
@@ -510,6 +566,22 @@ pub extern "C" fn routing_context_with_safety(id: u32, safety_selection: FfiStr)
     add_routing_context(&mut rc, routing_context)
 }
 
+#[no_mangle]
+pub extern "C" fn routing_context_with_safety_profile(id: u32, profile: FfiStr) -> u32 {
+    let profile: veilid_core::SafetyProfile =
+        veilid_core::deserialize_opt_json(profile.into_opt_string()).unwrap();
+
+    let mut rc = ROUTING_CONTEXTS.lock();
+    let Some(routing_context) = rc.get(&id) else {
+        return 0;
+    };
+    let Ok(routing_context) = routing_context.clone().with_safety_profile(profile) else {
+        return 0;
+    };
+
+    add_routing_context(&mut rc, routing_context)
+}
+
 #[no_mangle]
 pub extern "C" fn routing_context_with_sequencing(id: u32, sequencing: FfiStr) -> u32 {
     let sequencing: veilid_core::Sequencing =
@@ -524,6 +596,20 @@ pub extern "C" fn routing_context_with_sequencing(id: u32, sequencing: FfiStr) -
     add_routing_context(&mut rc, routing_context)
 }
 
+#[no_mangle]
+pub extern "C" fn routing_context_with_timeout(id: u32, timeout_ms: FfiStr) -> u32 {
+    let timeout_ms: Option<u32> =
+        veilid_core::deserialize_opt_json(timeout_ms.into_opt_string()).unwrap();
+
+    let mut rc = ROUTING_CONTEXTS.lock();
+    let Some(routing_context) = rc.get(&id) else {
+        return 0;
+    };
+    let routing_context = routing_context.clone().with_timeout(timeout_ms);
+
+    add_routing_context(&mut rc, routing_context)
+}
+
 fn get_routing_context(id: u32, func_name: &str) -> APIResult<veilid_core::RoutingContext> {
     let rc = ROUTING_CONTEXTS.lock();
     let Some(routing_context) = rc.get(&id) else {
@@ -541,6 +627,18 @@ pub extern "C" fn routing_context_safety(port: i64, id: u32) {
         APIResult::Ok(routing_context.safety())
     });
 }
+
+#[no_mangle]
+pub extern "C" fn routing_context_test_private_route(port: i64, id: u32, route_id: FfiStr) {
+    let route_id = veilid_core::RouteId::try_decode(route_id.into_string()).unwrap();
+    DartIsolateWrapper::new(port).spawn_result_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_test_private_route")?;
+
+        let res = routing_context.test_private_route(route_id).await?;
+        APIResult::Ok(res)
+    });
+}
+
 #[no_mangle]
 pub extern "C" fn routing_context_app_call(port: i64, id: u32, target: FfiStr, request: FfiStr) {
     let target_string: String = target.into_opt_string().unwrap();
@@ -574,6 +672,42 @@ pub extern "C" fn routing_context_app_message(port: i64, id: u32, target: FfiStr
     });
 }
 
+#[no_mangle]
+pub extern "C" fn routing_context_prewarm(port: i64, id: u32, target: FfiStr) {
+    let target_string: String = target.into_opt_string().unwrap();
+    DartIsolateWrapper::new(port).spawn_result_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_prewarm")?;
+
+        let veilid_api = get_veilid_api().await?;
+        let target = veilid_api.parse_as_target(target_string)?;
+        let contact_method = routing_context.prewarm(target).await?;
+        APIResult::Ok(contact_method)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn routing_context_app_message_closest(
+    port: i64,
+    id: u32,
+    key: FfiStr,
+    count: u32,
+    message: FfiStr,
+) {
+    let key: veilid_core::TypedKey =
+        veilid_core::deserialize_opt_json(key.into_opt_string()).unwrap();
+    let message: Vec<u8> = data_encoding::BASE64URL_NOPAD
+        .decode(message.into_opt_string().unwrap().as_bytes())
+        .unwrap();
+    DartIsolateWrapper::new(port).spawn_result_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_app_message_closest")?;
+
+        let delivered = routing_context
+            .app_message_closest(key, count, message)
+            .await?;
+        APIResult::Ok(delivered)
+    });
+}
+
 #[no_mangle]
 pub extern "C" fn routing_context_create_dht_record(port: i64, id: u32, schema: FfiStr, kind: u32) {
     let crypto_kind = if kind == 0 {
@@ -594,6 +728,57 @@ pub extern "C" fn routing_context_create_dht_record(port: i64, id: u32, schema:
     });
 }
 
+#[no_mangle]
+pub extern "C" fn routing_context_create_expiring_dht_record(
+    port: i64,
+    id: u32,
+    schema: FfiStr,
+    expiration: u64,
+    kind: u32,
+) {
+    let crypto_kind = if kind == 0 {
+        None
+    } else {
+        Some(veilid_core::FourCC::from(kind))
+    };
+    let schema: veilid_core::DHTSchema =
+        veilid_core::deserialize_opt_json(schema.into_opt_string()).unwrap();
+    let expiration = veilid_core::Timestamp::from(expiration);
+
+    DartIsolateWrapper::new(port).spawn_result_json(async move {
+        let routing_context =
+            get_routing_context(id, "routing_context_create_expiring_dht_record")?;
+
+        let dht_record_descriptor = routing_context
+            .create_expiring_dht_record(schema, expiration, crypto_kind)
+            .await?;
+        APIResult::Ok(dht_record_descriptor)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn routing_context_extend_dht_record_expiration(
+    port: i64,
+    id: u32,
+    key: FfiStr,
+    expiration: FfiStr,
+) {
+    let key: veilid_core::TypedKey =
+        veilid_core::deserialize_opt_json(key.into_opt_string()).unwrap();
+    let expiration: Option<veilid_core::Timestamp> = expiration
+        .into_opt_string()
+        .map(|s| veilid_core::deserialize_json(&s).unwrap());
+    DartIsolateWrapper::new(port).spawn_result(async move {
+        let routing_context =
+            get_routing_context(id, "routing_context_extend_dht_record_expiration")?;
+
+        routing_context
+            .extend_dht_record_expiration(key, expiration)
+            .await?;
+        APIRESULT_VOID
+    });
+}
+
 #[no_mangle]
 pub extern "C" fn routing_context_open_dht_record(port: i64, id: u32, key: FfiStr, writer: FfiStr) {
     let key: veilid_core::TypedKey =
@@ -751,6 +936,157 @@ pub extern "C" fn routing_context_inspect_dht_record(
     });
 }
 
+#[no_mangle]
+pub extern "C" fn routing_context_sync_dht_record(port: i64, id: u32, key: FfiStr) {
+    let key: veilid_core::TypedKey =
+        veilid_core::deserialize_opt_json(key.into_opt_string()).unwrap();
+    DartIsolateWrapper::new(port).spawn_result_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_sync_dht_record")?;
+
+        let res = routing_context.sync_dht_record(key).await?;
+        APIResult::Ok(res)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn routing_context_publish_private_route(
+    port: i64,
+    id: u32,
+    key: FfiStr,
+    subkey: u32,
+    rotation: FfiStr,
+) {
+    let key: veilid_core::TypedKey =
+        veilid_core::deserialize_opt_json(key.into_opt_string()).unwrap();
+    let rotation: Option<veilid_core::PrivateRouteRotation> = rotation
+        .into_opt_string()
+        .map(|s| veilid_core::deserialize_json(&s).unwrap());
+
+    DartIsolateWrapper::new(port).spawn_result(async move {
+        let routing_context = get_routing_context(id, "routing_context_publish_private_route")?;
+
+        let route_id = routing_context
+            .publish_private_route(key, subkey, rotation)
+            .await?;
+        APIResult::Ok(route_id.encode())
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn routing_context_resolve_private_route(
+    port: i64,
+    id: u32,
+    key: FfiStr,
+    subkey: u32,
+    force_refresh: bool,
+) {
+    let key: veilid_core::TypedKey =
+        veilid_core::deserialize_opt_json(key.into_opt_string()).unwrap();
+    DartIsolateWrapper::new(port).spawn_result(async move {
+        let routing_context = get_routing_context(id, "routing_context_resolve_private_route")?;
+
+        let res = routing_context
+            .resolve_private_route(key, subkey, force_refresh)
+            .await?;
+        APIResult::Ok(res.map(|route_id| route_id.encode()))
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn routing_context_create_invite(port: i64, id: u32) {
+    DartIsolateWrapper::new(port).spawn_result_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_create_invite")?;
+
+        let (invite, blob) = routing_context.create_invite().await?;
+        APIResult::Ok(veilid_core::json_api::CreateInviteResult { invite, blob })
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn routing_context_accept_invite(port: i64, id: u32, invite_blob: FfiStr) {
+    let invite_blob: Vec<u8> = data_encoding::BASE64URL_NOPAD
+        .decode(invite_blob.into_opt_string().unwrap().as_bytes())
+        .unwrap();
+    DartIsolateWrapper::new(port).spawn_result_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_accept_invite")?;
+
+        let session = routing_context.accept_invite(invite_blob).await?;
+        APIResult::Ok(session)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn routing_context_answer_invite(
+    port: i64,
+    id: u32,
+    invite: FfiStr,
+    app_call: FfiStr,
+) {
+    let invite: veilid_core::RendezvousInvite =
+        veilid_core::deserialize_opt_json(invite.into_opt_string()).unwrap();
+    let app_call: veilid_core::VeilidAppCall =
+        veilid_core::deserialize_opt_json(app_call.into_opt_string()).unwrap();
+    DartIsolateWrapper::new(port).spawn_result_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_answer_invite")?;
+
+        let opt_session = routing_context.answer_invite(&invite, &app_call).await?;
+        APIResult::Ok(opt_session)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn routing_context_send_session_message(
+    port: i64,
+    id: u32,
+    session: FfiStr,
+    message: FfiStr,
+) {
+    let session: veilid_core::RendezvousSession =
+        veilid_core::deserialize_opt_json(session.into_opt_string()).unwrap();
+    let message: Vec<u8> = data_encoding::BASE64URL_NOPAD
+        .decode(message.into_opt_string().unwrap().as_bytes())
+        .unwrap();
+    DartIsolateWrapper::new(port).spawn_result(async move {
+        let routing_context = get_routing_context(id, "routing_context_send_session_message")?;
+
+        session.send(&routing_context, &message).await?;
+        APIRESULT_VOID
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn routing_context_open_session_message(
+    port: i64,
+    id: u32,
+    session: FfiStr,
+    message: FfiStr,
+) {
+    let session: veilid_core::RendezvousSession =
+        veilid_core::deserialize_opt_json(session.into_opt_string()).unwrap();
+    let message: Vec<u8> = data_encoding::BASE64URL_NOPAD
+        .decode(message.into_opt_string().unwrap().as_bytes())
+        .unwrap();
+    DartIsolateWrapper::new(port).spawn_result(async move {
+        let routing_context = get_routing_context(id, "routing_context_open_session_message")?;
+
+        let opened = session.open(&routing_context.api(), &message)?;
+        let opened = data_encoding::BASE64URL_NOPAD.encode(&opened);
+        APIResult::Ok(opened)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn routing_context_close_session(port: i64, id: u32, session: FfiStr) {
+    let session: veilid_core::RendezvousSession =
+        veilid_core::deserialize_opt_json(session.into_opt_string()).unwrap();
+    DartIsolateWrapper::new(port).spawn_result(async move {
+        let routing_context = get_routing_context(id, "routing_context_close_session")?;
+
+        session.close(&routing_context.api())?;
+        APIRESULT_VOID
+    });
+}
+
 #[no_mangle]
 pub extern "C" fn new_private_route(port: i64) {
     DartIsolateWrapper::new(port).spawn_result_json(async move {
@@ -1688,6 +2024,15 @@ pub extern "C" fn now() -> u64 {
     veilid_core::get_aligned_timestamp().as_u64()
 }
 
+#[no_mangle]
+pub extern "C" fn get_network_time(port: i64) {
+    DartIsolateWrapper::new(port).spawn_result_json(async move {
+        let veilid_api = get_veilid_api().await?;
+        let network_time = veilid_api.get_network_time()?;
+        APIResult::Ok(network_time)
+    });
+}
+
 #[no_mangle]
 pub extern "C" fn debug(port: i64, command: FfiStr) {
     let command = command.into_opt_string().unwrap_or_default();
@@ -1724,3 +2069,8 @@ pub extern "C" fn veilid_version() -> VeilidVersion {
 pub extern "C" fn default_veilid_config() -> *mut c_char {
     veilid_core::default_veilid_config().into_ffi_value()
 }
+
+#[no_mangle]
+pub extern "C" fn veilid_config_schema() -> *mut c_char {
+    veilid_core::serialize_json(veilid_core::veilid_config_schema()).into_ffi_value()
+}
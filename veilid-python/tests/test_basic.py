@@ -26,6 +26,13 @@ async def test_get_node_id(api_connection: veilid.VeilidAPI):
         assert node_id[4] == ":"
 
 
+@pytest.mark.asyncio
+async def test_get_storage_status(api_connection: veilid.VeilidAPI):
+    status = await api_connection.get_storage_status()
+    assert status.pending_offline_writes >= 0
+    assert status.open_record_count >= 0
+
+
 @pytest.mark.asyncio
 async def test_fail_connect():
     with pytest.raises(socket.gaierror) as exc:
@@ -58,3 +65,14 @@ async def test_config(api_connection: veilid.VeilidAPI):
     
     assert veilidConfigInstance == veilidConfigInstance2
 
+
+@pytest.mark.asyncio
+async def test_config_schema(api_connection: veilid.VeilidAPI):
+    schema = json.loads(await api_connection.veilid_config_schema())
+    fields = {f["path"]: f for f in schema["fields"]}
+
+    cfgjson = json.loads(await api_connection.default_veilid_config())
+    assert fields["network.rpc.timeout_ms"]["default"] == cfgjson["network"]["rpc"]["timeout_ms"]
+    assert {"kind": "Minimum", "value": 1000, "when": []} in fields["network.rpc.timeout_ms"]["constraints"]
+    assert fields["network.role"]["kind"] == "Choice"
+    assert fields["protected_store.device_encryption_key_password"]["secret"]
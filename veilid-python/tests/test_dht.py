@@ -56,6 +56,26 @@ async def test_create_delete_dht_record_simple(api_connection: veilid.VeilidAPI)
         await rc.delete_dht_record(rec.key)
 
 
+@pytest.mark.asyncio
+async def test_create_extend_expiring_dht_record(api_connection: veilid.VeilidAPI):
+    rc = await api_connection.new_routing_context()
+    async with rc:
+        now = await api_connection.now()
+        expiration = veilid.Timestamp(now + 3_600_000_000)
+        rec = await rc.create_expiring_dht_record(veilid.DHTSchema.dflt(1), expiration)
+        try:
+            assert rec.expiration == expiration
+
+            # push the expiration back, then make the record permanent
+            await rc.extend_dht_record_expiration(
+                rec.key, veilid.Timestamp(expiration + 3_600_000_000)
+            )
+            await rc.extend_dht_record_expiration(rec.key, None)
+        finally:
+            await rc.close_dht_record(rec.key)
+            await rc.delete_dht_record(rec.key)
+
+
 @pytest.mark.asyncio
 async def test_get_dht_value_nonexistent(api_connection: veilid.VeilidAPI):
     rc = await api_connection.new_routing_context()
@@ -340,4 +360,20 @@ async def test_inspect_dht_record(api_connection: veilid.VeilidAPI):
         assert rr2.network_seqs == [0, 0xFFFFFFFF]
 
         await rc.close_dht_record(rec.key)
-        await rc.delete_dht_record(rec.key)
\ No newline at end of file
+        await rc.delete_dht_record(rec.key)
+
+@pytest.mark.asyncio
+async def test_sync_dht_record(api_connection: veilid.VeilidAPI):
+    rc = await api_connection.new_routing_context()
+    async with rc:
+        rec = await rc.create_dht_record(veilid.DHTSchema.dflt(2))
+
+        vd = await rc.set_dht_value(rec.key, 0, b"BLAH BLAH BLAH")
+        assert vd == None
+
+        # everything we hold locally was just written, so nothing is stale
+        refreshed = await rc.sync_dht_record(rec.key)
+        assert refreshed == []
+
+        await rc.close_dht_record(rec.key)
+        await rc.delete_dht_record(rec.key)
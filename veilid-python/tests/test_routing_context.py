@@ -42,6 +42,73 @@ async def test_routing_contexts(api_connection: veilid.VeilidAPI):
     )
     await rc.release()
 
+    rc = await (await api_connection.new_routing_context()).with_safety_profile(
+        veilid.SafetyProfile.BALANCED
+    )
+    await rc.release()
+
+    rc = await (await api_connection.new_routing_context()).with_timeout(5000)
+    rc = await rc.with_timeout(None)
+    await rc.release()
+
+
+@pytest.mark.asyncio
+async def test_routing_context_test_private_route(api_connection: veilid.VeilidAPI):
+    # purge routes to ensure we start fresh
+    await api_connection.debug("purge routes")
+
+    rc = await api_connection.new_routing_context()
+    async with rc:
+        # make a new local private route and test it before using it
+        prl, blob = await api_connection.new_private_route()
+        try:
+            res = await rc.test_private_route(prl)
+            assert res.success
+            assert res.latency is not None
+        finally:
+            # release local private route
+            await api_connection.release_private_route(prl)
+
+
+@pytest.mark.asyncio
+async def test_routing_context_publish_private_route(api_connection: veilid.VeilidAPI):
+    # purge routes to ensure we start fresh
+    await api_connection.debug("purge routes")
+
+    rc = await api_connection.new_routing_context()
+    async with rc:
+        rec = await rc.create_dht_record(veilid.DHTSchema.dflt(2))
+        try:
+            # publish a route to the first subkey and read it back
+            prl = await rc.publish_private_route(rec.key, veilid.ValueSubkey(0))
+            try:
+                prr = await rc.resolve_private_route(rec.key, veilid.ValueSubkey(0), False)
+                assert prr is not None
+
+                # nothing was published to the second subkey
+                assert await rc.resolve_private_route(rec.key, veilid.ValueSubkey(1), False) is None
+            finally:
+                await api_connection.release_private_route(prl)
+        finally:
+            await rc.close_dht_record(rec.key)
+            await rc.delete_dht_record(rec.key)
+
+
+@pytest.mark.asyncio
+async def test_routing_context_app_message_closest(api_connection: veilid.VeilidAPI):
+    rc = await api_connection.new_routing_context()
+    async with rc:
+        rec = await rc.create_dht_record(veilid.DHTSchema.dflt(1))
+        try:
+            # every node we delivered to is reported back
+            delivered = await rc.app_message_closest(rec.key, 2, b"closest")
+            assert len(delivered) <= 2
+            for node_id in delivered:
+                assert isinstance(node_id, veilid.TypedKey)
+        finally:
+            await rc.close_dht_record(rec.key)
+            await rc.delete_dht_record(rec.key)
+
 
 @pytest.mark.asyncio
 async def test_routing_context_app_message_loopback():
@@ -84,6 +151,9 @@ async def test_routing_context_app_message_loopback():
                     assert update.detail.message == message
                     assert update.detail.route_id is not None
 
+                    # private routes are only warmed up, never connected to directly
+                    assert await rc.prewarm(prr) == veilid.ContactMethod.ROUTED
+
                 finally:
                         # release imported private route
                         await api.release_private_route(prr)
@@ -147,6 +217,51 @@ async def test_routing_context_app_call_loopback():
                 # release local private route
                 await api.release_private_route(prl)
 
+
+@pytest.mark.asyncio
+async def test_routing_context_rendezvous_loopback():
+    update_queue: asyncio.Queue = asyncio.Queue()
+
+    async def update_queue_update_callback(update: veilid.VeilidUpdate):
+        if update.kind in (veilid.VeilidUpdateKind.APP_CALL, veilid.VeilidUpdateKind.APP_MESSAGE):
+            await update_queue.put(update)
+
+    try:
+        api = await api_connector(update_queue_update_callback)
+    except VeilidTestConnectionError:
+        pytest.skip("Unable to connect to veilid-server.")
+        return
+
+    async with api:
+        # purge routes to ensure we start fresh
+        await api.debug("purge routes")
+
+        rc = await api.new_routing_context()
+        async with rc:
+            # accept our own invite, answering the handshake when it arrives
+            invite, blob = await rc.create_invite()
+            accept_task = asyncio.create_task(rc.accept_invite(blob), name="accept invite task")
+
+            update: veilid.VeilidUpdate = await asyncio.wait_for(update_queue.get(), timeout=10)
+            assert isinstance(update.detail, veilid.VeilidAppCall)
+            inviter_session = await rc.answer_invite(invite, update.detail)
+            assert inviter_session is not None
+            accepter_session = await accept_task
+            assert inviter_session.local_route_id == invite.route_id
+
+            try:
+                # a message sent on one end of the session opens on the other
+                message = b"abcd1234"
+                await rc.send_session_message(accepter_session, message)
+                update = await asyncio.wait_for(update_queue.get(), timeout=10)
+                assert isinstance(update.detail, veilid.VeilidAppMessage)
+                assert update.detail.route_id == inviter_session.local_route_id
+                assert await rc.open_session_message(inviter_session, update.detail.message) == message
+            finally:
+                # in loopback both ends use the same routes, so closing one end releases them all
+                await rc.close_session(inviter_session)
+
+
 @pytest.mark.asyncio
 async def test_routing_context_app_message_loopback_big_packets():
     app_message_queue: asyncio.Queue = asyncio.Queue()
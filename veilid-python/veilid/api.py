@@ -2,7 +2,7 @@ from abc import ABC, abstractmethod
 from typing import Optional, Self
 
 from . import types
-from .state import VeilidState
+from .state import StorageManagerStatus, VeilidAppCall, VeilidState
 
 
 class RoutingContext(ABC):
@@ -31,14 +31,26 @@ class RoutingContext(ABC):
     ) -> Self:
         pass
 
+    @abstractmethod
+    async def with_safety_profile(self, profile: types.SafetyProfile, release=True) -> Self:
+        pass
+
     @abstractmethod
     async def with_sequencing(self, sequencing: types.Sequencing, release=True) -> Self:
         pass
 
+    @abstractmethod
+    async def with_timeout(self, timeout_ms: Optional[int], release=True) -> Self:
+        pass
+
     @abstractmethod
     async def safety(self) -> types.SafetySelection:
         pass
 
+    @abstractmethod
+    async def test_private_route(self, route_id: types.RouteId) -> types.PrivateRouteTestResult:
+        pass
+
     @abstractmethod
     async def app_call(self, target: types.TypedKey | types.RouteId, request: bytes) -> bytes:
         pass
@@ -47,12 +59,37 @@ class RoutingContext(ABC):
     async def app_message(self, target: types.TypedKey | types.RouteId, message: bytes):
         pass
 
+    @abstractmethod
+    async def prewarm(self, target: types.TypedKey | types.RouteId) -> types.ContactMethod:
+        pass
+
+    @abstractmethod
+    async def app_message_closest(
+        self, key: types.TypedKey, count: int, message: bytes
+    ) -> list[types.TypedKey]:
+        pass
+
     @abstractmethod
     async def create_dht_record(
         self, schema: types.DHTSchema, kind: Optional[types.CryptoKind] = None
     ) -> types.DHTRecordDescriptor:
         pass
 
+    @abstractmethod
+    async def create_expiring_dht_record(
+        self,
+        schema: types.DHTSchema,
+        expiration: types.Timestamp,
+        kind: Optional[types.CryptoKind] = None,
+    ) -> types.DHTRecordDescriptor:
+        pass
+
+    @abstractmethod
+    async def extend_dht_record_expiration(
+        self, key: types.TypedKey, expiration: Optional[types.Timestamp]
+    ):
+        pass
+
     @abstractmethod
     async def open_dht_record(
         self, key: types.TypedKey, writer: Optional[types.KeyPair] = None
@@ -106,6 +143,55 @@ class RoutingContext(ABC):
     ) -> types.DHTRecordReport:
         pass
 
+    @abstractmethod
+    async def sync_dht_record(
+        self, key: types.TypedKey
+    ) -> list[tuple[types.ValueSubkey, types.ValueSubkey]]:
+        pass
+
+    @abstractmethod
+    async def publish_private_route(
+        self,
+        key: types.TypedKey,
+        subkey: types.ValueSubkey,
+        rotation: Optional[types.PrivateRouteRotation] = None,
+    ) -> types.RouteId:
+        pass
+
+    @abstractmethod
+    async def resolve_private_route(
+        self, key: types.TypedKey, subkey: types.ValueSubkey, force_refresh: bool
+    ) -> Optional[types.RouteId]:
+        pass
+
+    @abstractmethod
+    async def create_invite(self) -> tuple[types.RendezvousInvite, bytes]:
+        pass
+
+    @abstractmethod
+    async def accept_invite(self, invite_blob: bytes) -> types.RendezvousSession:
+        pass
+
+    @abstractmethod
+    async def answer_invite(
+        self, invite: types.RendezvousInvite, app_call: VeilidAppCall
+    ) -> Optional[types.RendezvousSession]:
+        pass
+
+    @abstractmethod
+    async def send_session_message(self, session: types.RendezvousSession, message: bytes):
+        pass
+
+    @abstractmethod
+    async def open_session_message(
+        self, session: types.RendezvousSession, message: bytes
+    ) -> bytes:
+        pass
+
+    @abstractmethod
+    async def close_session(self, session: types.RendezvousSession):
+        pass
+
 
 
 class TableDbTransaction(ABC):
@@ -325,6 +411,18 @@ class VeilidAPI(ABC):
     async def get_state(self) -> VeilidState:
         pass
 
+    @abstractmethod
+    async def get_storage_usage(self) -> types.DHTStorageUsage:
+        pass
+
+    @abstractmethod
+    async def get_storage_status(self) -> StorageManagerStatus:
+        pass
+
+    @abstractmethod
+    async def get_network_time(self) -> types.NetworkTime:
+        pass
+
     @abstractmethod
     async def attach(self):
         pass
@@ -333,6 +431,18 @@ class VeilidAPI(ABC):
     async def detach(self):
         pass
 
+    @abstractmethod
+    async def set_network_cost(self, network_cost: types.NetworkCost):
+        pass
+
+    @abstractmethod
+    async def set_low_power(self, low_power: bool):
+        pass
+
+    @abstractmethod
+    async def trigger_public_dial_info_detection(self):
+        pass
+
     @abstractmethod
     async def new_private_route(self) -> tuple[types.RouteId, bytes]:
         pass
@@ -416,3 +526,7 @@ class VeilidAPI(ABC):
     @abstractmethod
     async def default_veilid_config(self) -> str:
         pass
+
+    @abstractmethod
+    async def veilid_config_schema(self) -> str:
+        pass
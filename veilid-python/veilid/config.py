@@ -14,6 +14,23 @@ class VeilidConfigLogLevel(StrEnum):
     TRACE = "Trace"
 
 
+class VeilidConfigRole(StrEnum):
+    FULL = "Full"
+    BOOTSTRAP = "Bootstrap"
+
+
+class VeilidConfigNetworkCostPolicy(StrEnum):
+    NORMAL = "Normal"
+    REDUCE_TICK_RATE = "ReduceTickRate"
+    RELAY_ONLY = "RelayOnly"
+    SUSPEND = "Suspend"
+
+
+class VeilidConfigTableStoreBackend(StrEnum):
+    PERSISTENT = "Persistent"
+    MEMORY = "Memory"
+
+
 @dataclass
 class ConfigBase:
     @classmethod
@@ -48,6 +65,8 @@ class VeilidConfigCapabilities(ConfigBase):
 class VeilidConfigProtectedStore(ConfigBase):
     allow_insecure_fallback: bool
     always_use_insecure_storage: bool
+    hardware_node_id: bool
+    hardware_node_id_migrate: bool
     directory: str
     delete: bool
     device_encryption_key_password: str
@@ -58,6 +77,7 @@ class VeilidConfigProtectedStore(ConfigBase):
 class VeilidConfigTableStore(ConfigBase):
     directory: str
     delete: bool
+    backend: VeilidConfigTableStoreBackend
 
 
 @dataclass
@@ -76,6 +96,8 @@ class VeilidConfigRoutingTable(ConfigBase):
     limit_attached_strong: int
     limit_attached_good: int
     limit_attached_weak: int
+    node_info_max_age_ms: int
+    verified_peers_only: list[str]
 
 
 @dataclass
@@ -84,9 +106,21 @@ class VeilidConfigRPC(ConfigBase):
     queue_size: int
     max_timestamp_behind_ms: Optional[int]
     max_timestamp_ahead_ms: Optional[int]
+    max_clock_skew_ms: int
+    clock_skew_warning_ms: int
     timeout_ms: int
     max_route_hop_count: int
     default_route_hop_count: int
+    max_private_routes: int
+    max_private_routes_per_api: int
+    private_route_idle_release_ms: Optional[int]
+    max_app_message_size: int
+    max_app_call_size: int
+    max_app_message_frequency_per_min: int
+    max_app_call_frequency_per_min: int
+    strict_udp_source: bool
+    strict_udp_source_allow_rebind: bool
+    route_pow_difficulty: int
 
 
 @dataclass
@@ -101,6 +135,7 @@ class VeilidConfigDHT(ConfigBase):
     set_value_timeout_ms: int
     set_value_count: int
     set_value_fanout: int
+    min_fanout_under_load: int
     min_peer_count: int
     min_peer_refresh_time_ms: int
     validate_dial_info_receipt_time_ms: int
@@ -110,9 +145,14 @@ class VeilidConfigDHT(ConfigBase):
     remote_max_records: int
     remote_max_subkey_cache_memory_mb: int
     remote_max_storage_space_mb: int
+    remote_storage_high_watermark_percent: int
+    remote_storage_low_watermark_percent: int
     public_watch_limit: int
     member_watch_limit: int
     max_watch_expiration_ms: int
+    set_value_pow_difficulty: int
+    compress_values: bool
+    audit_log_max_entries: int
 
 @dataclass
 class VeilidConfigTLS(ConfigBase):
@@ -121,12 +161,20 @@ class VeilidConfigTLS(ConfigBase):
     connection_initial_timeout_ms: int
 
 
+@dataclass
+class VeilidConfigTor(ConfigBase):
+    enabled: bool
+    socks_address: str
+    onion_address: Optional[str]
+
+
 @dataclass
 class VeilidConfigHTTPS(ConfigBase):
     enabled: bool
     listen_address: str
     path: str
     url: Optional[str]
+    directory: Optional[str]
 
 
 @dataclass
@@ -135,6 +183,7 @@ class VeilidConfigHTTP(ConfigBase):
     listen_address: str
     path: str
     url: Optional[str]
+    directory: Optional[str]
 
 
 @dataclass
@@ -149,6 +198,7 @@ class VeilidConfigUDP(ConfigBase):
     socket_pool_size: int
     listen_address: str
     public_address: Optional[str]
+    preference: int
 
 
 @dataclass
@@ -158,6 +208,7 @@ class VeilidConfigTCP(ConfigBase):
     max_connections: int
     listen_address: str
     public_address: Optional[str]
+    preference: int
 
 
 @dataclass
@@ -168,6 +219,7 @@ class VeilidConfigWS(ConfigBase):
     listen_address: str
     path: str
     url: Optional[str]
+    preference: int
 
 
 @dataclass
@@ -178,6 +230,14 @@ class VeilidConfigWSS(ConfigBase):
     listen_address: str
     path: str
     url: Optional[str]
+    preference: int
+
+
+@dataclass
+class VeilidConfigIPC(ConfigBase):
+    connect: bool
+    listen: bool
+    listen_path: str
 
 
 @dataclass
@@ -186,6 +246,7 @@ class VeilidConfigProtocol(ConfigBase):
     tcp: VeilidConfigTCP
     ws: VeilidConfigWS
     wss: VeilidConfigWSS
+    ipc: VeilidConfigIPC
 
 
 @dataclass
@@ -199,16 +260,26 @@ class VeilidConfigNetwork(ConfigBase):
     client_allowlist_timeout_ms: int
     reverse_connection_receipt_time_ms: int
     hole_punch_receipt_time_ms: int
+    prewarm_grace_period_ms: int
+    max_relay_client_bytes_per_sec: int
+    max_relay_client_queued_bytes: int
     network_key_password: Optional[str]
     routing_table: VeilidConfigRoutingTable
     rpc: VeilidConfigRPC
     dht: VeilidConfigDHT
     upnp: bool
     detect_address_changes: bool
+    detect_address_changes_distinct_subnets: int
     restricted_nat_retries: int
+    max_envelope_size: int
     tls: VeilidConfigTLS
+    tor: VeilidConfigTor
     application: VeilidConfigApplication
     protocol: VeilidConfigProtocol
+    role: VeilidConfigRole
+    metered_policy: VeilidConfigNetworkCostPolicy
+    roaming_policy: VeilidConfigNetworkCostPolicy
+    low_power_wake_interval_ms: int
 
 
 @dataclass
@@ -141,6 +141,14 @@ class VeilidAPIErrorGeneric(VeilidAPIError):
     message: str
 
 
+@dataclass
+class VeilidAPIErrorRouteCompilation(VeilidAPIError):
+    """A route could not be allocated because one of its constraints could not be satisfied"""
+
+    label = "Route compilation"
+    error: dict
+
+
 # Build a mapping of canonicalized labels to their exception classes. Do this in-place to update
 # the object inside the closure so VeilidAPIError.from_json can access the values.
 _ERROR_REGISTRY.clear()
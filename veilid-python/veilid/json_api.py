@@ -17,22 +17,32 @@ from .operations import (
     TableDbOperation,
     TableDbTransactionOperation,
 )
-from .state import VeilidState, VeilidUpdate
+from .state import StorageManagerStatus, VeilidAppCall, VeilidState, VeilidUpdate
 from .types import (
+    ContactMethod,
+    CreateInviteResult,
     CryptoKey,
     CryptoKeyDistance,
     CryptoKind,
     DHTRecordDescriptor,
     DHTRecordReport,
+    DHTStorageUsage,
     DHTReportScope,
     DHTSchema,
     HashDigest,
     KeyPair,
+    NetworkCost,
+    NetworkTime,
     NewPrivateRouteResult,
     Nonce,
     OperationId,
+    PrivateRouteRotation,
+    PrivateRouteTestResult,
     PublicKey,
+    RendezvousInvite,
+    RendezvousSession,
     RouteId,
+    SafetyProfile,
     SafetySelection,
     SecretKey,
     Sequencing,
@@ -313,12 +323,42 @@ class _JsonVeilidAPI(VeilidAPI):
             raise_api_result(await self.send_ndjson_request(Operation.GET_STATE))
         )
 
+    async def get_storage_usage(self) -> DHTStorageUsage:
+        return DHTStorageUsage.from_json(
+            raise_api_result(await self.send_ndjson_request(Operation.GET_STORAGE_USAGE))
+        )
+
+    async def get_storage_status(self) -> StorageManagerStatus:
+        return StorageManagerStatus.from_json(
+            raise_api_result(await self.send_ndjson_request(Operation.GET_STORAGE_STATUS))
+        )
+
+    async def get_network_time(self) -> NetworkTime:
+        return NetworkTime.from_json(
+            raise_api_result(await self.send_ndjson_request(Operation.GET_NETWORK_TIME))
+        )
+
     async def attach(self):
         raise_api_result(await self.send_ndjson_request(Operation.ATTACH))
 
     async def detach(self):
         raise_api_result(await self.send_ndjson_request(Operation.DETACH))
 
+    async def set_network_cost(self, network_cost: NetworkCost):
+        raise_api_result(
+            await self.send_ndjson_request(Operation.SET_NETWORK_COST, network_cost=network_cost)
+        )
+
+    async def set_low_power(self, low_power: bool):
+        raise_api_result(
+            await self.send_ndjson_request(Operation.SET_LOW_POWER, low_power=low_power)
+        )
+
+    async def trigger_public_dial_info_detection(self):
+        raise_api_result(
+            await self.send_ndjson_request(Operation.TRIGGER_PUBLIC_DIAL_INFO_DETECTION)
+        )
+
     async def new_private_route(self) -> tuple[RouteId, bytes]:
         return NewPrivateRouteResult.from_json(
             raise_api_result(await self.send_ndjson_request(Operation.NEW_PRIVATE_ROUTE))
@@ -441,6 +481,9 @@ class _JsonVeilidAPI(VeilidAPI):
     async def default_veilid_config(self) -> str:
         return raise_api_result(await self.send_ndjson_request(Operation.DEFAULT_VEILID_CONFIG))
 
+    async def veilid_config_schema(self) -> str:
+        return raise_api_result(await self.send_ndjson_request(Operation.VEILID_CONFIG_SCHEMA))
+
 
 ######################################################
 
@@ -511,6 +554,20 @@ class _JsonRoutingContext(RoutingContext):
             await self.release()
         return self.__class__(self.api, new_rc_id)
 
+    async def with_safety_profile(self, profile: SafetyProfile, release=True) -> Self:
+        new_rc_id = raise_api_result(
+            await self.api.send_ndjson_request(
+                Operation.ROUTING_CONTEXT,
+                validate=validate_rc_op,
+                rc_id=self.rc_id,
+                rc_op=RoutingContextOperation.WITH_SAFETY_PROFILE,
+                profile=profile,
+            )
+        )
+        if release:
+            await self.release()
+        return self.__class__(self.api, new_rc_id)
+
     async def with_sequencing(self, sequencing: Sequencing, release=True) -> Self:
         new_rc_id = raise_api_result(
             await self.api.send_ndjson_request(
@@ -525,6 +582,20 @@ class _JsonRoutingContext(RoutingContext):
             await self.release()
         return self.__class__(self.api, new_rc_id)
 
+    async def with_timeout(self, timeout_ms: Optional[int], release=True) -> Self:
+        new_rc_id = raise_api_result(
+            await self.api.send_ndjson_request(
+                Operation.ROUTING_CONTEXT,
+                validate=validate_rc_op,
+                rc_id=self.rc_id,
+                rc_op=RoutingContextOperation.WITH_TIMEOUT,
+                timeout_ms=timeout_ms,
+            )
+        )
+        if release:
+            await self.release()
+        return self.__class__(self.api, new_rc_id)
+
     async def safety(
         self
     ) -> SafetySelection:
@@ -538,6 +609,20 @@ class _JsonRoutingContext(RoutingContext):
                 )
             )
         )
+
+    async def test_private_route(self, route_id: RouteId) -> PrivateRouteTestResult:
+        return PrivateRouteTestResult.from_json(
+            raise_api_result(
+                await self.api.send_ndjson_request(
+                    Operation.ROUTING_CONTEXT,
+                    validate=validate_rc_op,
+                    rc_id=self.rc_id,
+                    rc_op=RoutingContextOperation.TEST_PRIVATE_ROUTE,
+                    route_id=route_id,
+                )
+            )
+        )
+
     async def app_call(self, target: TypedKey | RouteId, message: bytes) -> bytes:
         return urlsafe_b64decode_no_pad(
             raise_api_result(
@@ -564,6 +649,39 @@ class _JsonRoutingContext(RoutingContext):
             )
         )
 
+    async def prewarm(self, target: TypedKey | RouteId) -> ContactMethod:
+        return ContactMethod(
+            raise_api_result(
+                await self.api.send_ndjson_request(
+                    Operation.ROUTING_CONTEXT,
+                    validate=validate_rc_op,
+                    rc_id=self.rc_id,
+                    rc_op=RoutingContextOperation.PREWARM,
+                    target=target,
+                )
+            )
+        )
+
+    async def app_message_closest(
+        self, key: TypedKey, count: int, message: bytes
+    ) -> list[TypedKey]:
+        return list(
+            map(
+                lambda x: TypedKey(x),
+                raise_api_result(
+                    await self.api.send_ndjson_request(
+                        Operation.ROUTING_CONTEXT,
+                        validate=validate_rc_op,
+                        rc_id=self.rc_id,
+                        rc_op=RoutingContextOperation.APP_MESSAGE_CLOSEST,
+                        key=key,
+                        count=count,
+                        message=message,
+                    )
+                ),
+            )
+        )
+
     async def create_dht_record(
         self, schema: DHTSchema, kind: Optional[CryptoKind] = None
     ) -> DHTRecordDescriptor:
@@ -580,6 +698,38 @@ class _JsonRoutingContext(RoutingContext):
             )
         )
 
+    async def create_expiring_dht_record(
+        self,
+        schema: DHTSchema,
+        expiration: Timestamp,
+        kind: Optional[CryptoKind] = None,
+    ) -> DHTRecordDescriptor:
+        return DHTRecordDescriptor.from_json(
+            raise_api_result(
+                await self.api.send_ndjson_request(
+                    Operation.ROUTING_CONTEXT,
+                    validate=validate_rc_op,
+                    rc_id=self.rc_id,
+                    rc_op=RoutingContextOperation.CREATE_EXPIRING_DHT_RECORD,
+                    kind=kind,
+                    schema=schema,
+                    expiration=str(expiration),
+                )
+            )
+        )
+
+    async def extend_dht_record_expiration(self, key: TypedKey, expiration: Optional[Timestamp]):
+        raise_api_result(
+            await self.api.send_ndjson_request(
+                Operation.ROUTING_CONTEXT,
+                validate=validate_rc_op,
+                rc_id=self.rc_id,
+                rc_op=RoutingContextOperation.EXTEND_DHT_RECORD_EXPIRATION,
+                key=key,
+                expiration=None if expiration is None else str(expiration),
+            )
+        )
+
     async def open_dht_record(
         self, key: TypedKey, writer: Optional[KeyPair] = None
     ) -> DHTRecordDescriptor:
@@ -706,6 +856,133 @@ class _JsonRoutingContext(RoutingContext):
                 )
             )
         )
+
+    async def sync_dht_record(self, key: TypedKey) -> list[tuple[ValueSubkey, ValueSubkey]]:
+        return [
+            (ValueSubkey(p[0]), ValueSubkey(p[1]))
+            for p in raise_api_result(
+                await self.api.send_ndjson_request(
+                    Operation.ROUTING_CONTEXT,
+                    validate=validate_rc_op,
+                    rc_id=self.rc_id,
+                    rc_op=RoutingContextOperation.SYNC_DHT_RECORD,
+                    key=key,
+                )
+            )
+        ]
+
+    async def publish_private_route(
+        self,
+        key: TypedKey,
+        subkey: ValueSubkey,
+        rotation: Optional[PrivateRouteRotation] = None,
+    ) -> RouteId:
+        return RouteId(
+            raise_api_result(
+                await self.api.send_ndjson_request(
+                    Operation.ROUTING_CONTEXT,
+                    validate=validate_rc_op,
+                    rc_id=self.rc_id,
+                    rc_op=RoutingContextOperation.PUBLISH_PRIVATE_ROUTE,
+                    key=key,
+                    subkey=subkey,
+                    rotation=rotation,
+                )
+            )
+        )
+
+    async def resolve_private_route(
+        self, key: TypedKey, subkey: ValueSubkey, force_refresh: bool
+    ) -> Optional[RouteId]:
+        ret = raise_api_result(
+            await self.api.send_ndjson_request(
+                Operation.ROUTING_CONTEXT,
+                validate=validate_rc_op,
+                rc_id=self.rc_id,
+                rc_op=RoutingContextOperation.RESOLVE_PRIVATE_ROUTE,
+                key=key,
+                subkey=subkey,
+                force_refresh=force_refresh,
+            )
+        )
+        return None if ret is None else RouteId(ret)
+
+    async def create_invite(self) -> tuple[RendezvousInvite, bytes]:
+        return CreateInviteResult.from_json(
+            raise_api_result(
+                await self.api.send_ndjson_request(
+                    Operation.ROUTING_CONTEXT,
+                    validate=validate_rc_op,
+                    rc_id=self.rc_id,
+                    rc_op=RoutingContextOperation.CREATE_INVITE,
+                )
+            )
+        ).to_tuple()
+
+    async def accept_invite(self, invite_blob: bytes) -> RendezvousSession:
+        return RendezvousSession.from_json(
+            raise_api_result(
+                await self.api.send_ndjson_request(
+                    Operation.ROUTING_CONTEXT,
+                    validate=validate_rc_op,
+                    rc_id=self.rc_id,
+                    rc_op=RoutingContextOperation.ACCEPT_INVITE,
+                    invite_blob=invite_blob,
+                )
+            )
+        )
+
+    async def answer_invite(
+        self, invite: RendezvousInvite, app_call: VeilidAppCall
+    ) -> Optional[RendezvousSession]:
+        ret = raise_api_result(
+            await self.api.send_ndjson_request(
+                Operation.ROUTING_CONTEXT,
+                validate=validate_rc_op,
+                rc_id=self.rc_id,
+                rc_op=RoutingContextOperation.ANSWER_INVITE,
+                invite=invite,
+                app_call=app_call,
+            )
+        )
+        return None if ret is None else RendezvousSession.from_json(ret)
+
+    async def send_session_message(self, session: RendezvousSession, message: bytes):
+        raise_api_result(
+            await self.api.send_ndjson_request(
+                Operation.ROUTING_CONTEXT,
+                validate=validate_rc_op,
+                rc_id=self.rc_id,
+                rc_op=RoutingContextOperation.SEND_SESSION_MESSAGE,
+                session=session,
+                message=message,
+            )
+        )
+
+    async def open_session_message(self, session: RendezvousSession, message: bytes) -> bytes:
+        return urlsafe_b64decode_no_pad(
+            raise_api_result(
+                await self.api.send_ndjson_request(
+                    Operation.ROUTING_CONTEXT,
+                    validate=validate_rc_op,
+                    rc_id=self.rc_id,
+                    rc_op=RoutingContextOperation.OPEN_SESSION_MESSAGE,
+                    session=session,
+                    message=message,
+                )
+            )
+        )
+
+    async def close_session(self, session: RendezvousSession):
+        raise_api_result(
+            await self.api.send_ndjson_request(
+                Operation.ROUTING_CONTEXT,
+                validate=validate_rc_op,
+                rc_id=self.rc_id,
+                rc_op=RoutingContextOperation.CLOSE_SESSION,
+                session=session,
+            )
+        )
         
 
 
@@ -5,8 +5,14 @@ from typing import Self
 class Operation(StrEnum):
     CONTROL = "Control"
     GET_STATE = "GetState"
+    GET_STORAGE_USAGE = "GetStorageUsage"
+    GET_STORAGE_STATUS = "GetStorageStatus"
+    GET_NETWORK_TIME = "GetNetworkTime"
     ATTACH = "Attach"
     DETACH = "Detach"
+    SET_NETWORK_COST = "SetNetworkCost"
+    SET_LOW_POWER = "SetLowPower"
+    TRIGGER_PUBLIC_DIAL_INFO_DETECTION = "TriggerPublicDialInfoDetection"
     NEW_PRIVATE_ROUTE = "NewPrivateRoute"
     NEW_CUSTOM_PRIVATE_ROUTE = "NewCustomPrivateRoute"
     IMPORT_REMOTE_PRIVATE_ROUTE = "ImportRemotePrivateRoute"
@@ -29,6 +35,7 @@ class Operation(StrEnum):
     VEILID_VERSION_STRING = "VeilidVersionString"
     VEILID_VERSION = "VeilidVersion"
     DEFAULT_VEILID_CONFIG = "DefaultVeilidConfig"
+    VEILID_CONFIG_SCHEMA = "VeilidConfigSchema"
 
 
 class RoutingContextOperation(StrEnum):
@@ -36,11 +43,18 @@ class RoutingContextOperation(StrEnum):
     RELEASE = "Release"
     WITH_DEFAULT_SAFETY = "WithDefaultSafety"
     WITH_SAFETY = "WithSafety"
+    WITH_SAFETY_PROFILE = "WithSafetyProfile"
     WITH_SEQUENCING = "WithSequencing"
+    WITH_TIMEOUT = "WithTimeout"
     SAFETY = "Safety"
+    TEST_PRIVATE_ROUTE = "TestPrivateRoute"
     APP_CALL = "AppCall"
     APP_MESSAGE = "AppMessage"
+    PREWARM = "Prewarm"
+    APP_MESSAGE_CLOSEST = "AppMessageClosest"
     CREATE_DHT_RECORD = "CreateDhtRecord"
+    CREATE_EXPIRING_DHT_RECORD = "CreateExpiringDhtRecord"
+    EXTEND_DHT_RECORD_EXPIRATION = "ExtendDhtRecordExpiration"
     OPEN_DHT_RECORD = "OpenDhtRecord"
     CLOSE_DHT_RECORD = "CloseDhtRecord"
     DELETE_DHT_RECORD = "DeleteDhtRecord"
@@ -49,6 +63,15 @@ class RoutingContextOperation(StrEnum):
     WATCH_DHT_VALUES = "WatchDhtValues"
     CANCEL_DHT_WATCH = "CancelDhtWatch"
     INSPECT_DHT_RECORD = "InspectDhtRecord"
+    SYNC_DHT_RECORD = "SyncDhtRecord"
+    PUBLISH_PRIVATE_ROUTE = "PublishPrivateRoute"
+    RESOLVE_PRIVATE_ROUTE = "ResolvePrivateRoute"
+    CREATE_INVITE = "CreateInvite"
+    ACCEPT_INVITE = "AcceptInvite"
+    ANSWER_INVITE = "AnswerInvite"
+    SEND_SESSION_MESSAGE = "SendSessionMessage"
+    OPEN_SESSION_MESSAGE = "OpenSessionMessage"
+    CLOSE_SESSION = "CloseSession"
 
 
 class TableDbOperation(StrEnum):
@@ -4,6 +4,7 @@ from typing import Optional, Self
 from .config import VeilidConfig
 from .types import (
     ByteCount,
+    CryptoKind,
     RouteId,
     Timestamp,
     TimestampDuration,
@@ -27,20 +28,88 @@ class AttachmentState(StrEnum):
     DETACHING = "Detaching"
 
 
+class StorageManagerStatus:
+    pending_offline_writes: int
+    open_record_count: int
+    local_used: ByteCount
+    remote_used: ByteCount
+
+    def __init__(
+        self,
+        pending_offline_writes: int,
+        open_record_count: int,
+        local_used: ByteCount,
+        remote_used: ByteCount,
+    ):
+        self.pending_offline_writes = pending_offline_writes
+        self.open_record_count = open_record_count
+        self.local_used = local_used
+        self.remote_used = remote_used
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        """JSON object hook"""
+        return cls(
+            j["pending_offline_writes"],
+            j["open_record_count"],
+            ByteCount(j["local_used"]),
+            ByteCount(j["remote_used"]),
+        )
+
+
+class CryptoKindHealth:
+    kind: CryptoKind
+    reliable_entry_count: int
+    unreliable_entry_count: int
+    dead_entry_count: int
+    viable: bool
+
+    def __init__(
+        self,
+        kind: CryptoKind,
+        reliable_entry_count: int,
+        unreliable_entry_count: int,
+        dead_entry_count: int,
+        viable: bool,
+    ):
+        self.kind = kind
+        self.reliable_entry_count = reliable_entry_count
+        self.unreliable_entry_count = unreliable_entry_count
+        self.dead_entry_count = dead_entry_count
+        self.viable = viable
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        """JSON object hook"""
+        return cls(
+            CryptoKind(j["kind"]),
+            j["reliable_entry_count"],
+            j["unreliable_entry_count"],
+            j["dead_entry_count"],
+            j["viable"],
+        )
+
+
 class VeilidStateAttachment:
     state: AttachmentState
     public_internet_ready: bool
     local_network_ready: bool
+    storage: Optional[StorageManagerStatus]
+    crypto_kinds: list[CryptoKindHealth]
 
     def __init__(
         self,
         state: AttachmentState,
         public_internet_ready: bool,
         local_network_ready: bool,
+        storage: Optional[StorageManagerStatus],
+        crypto_kinds: list[CryptoKindHealth],
     ):
         self.state = state
         self.public_internet_ready = public_internet_ready
         self.local_network_ready = local_network_ready
+        self.storage = storage
+        self.crypto_kinds = crypto_kinds
 
     @classmethod
     def from_json(cls, j: dict) -> Self:
@@ -49,6 +118,8 @@ class VeilidStateAttachment:
             AttachmentState(j["state"]),
             j["public_internet_ready"],
             j["local_network_ready"],
+            StorageManagerStatus.from_json(j["storage"]) if "storage" in j else None,
+            [CryptoKindHealth.from_json(ckh) for ckh in j.get("crypto_kinds", [])],
         )
 
 
@@ -99,6 +170,43 @@ class RPCStats:
         )
 
 
+class RPCLoadStats:
+    queue_depth: int
+    queue_size: int
+    average_queue_time: TimestampDuration
+    average_processing_time: TimestampDuration
+    shed_questions: int
+    dropped_messages: int
+
+    def __init__(
+        self,
+        queue_depth: int,
+        queue_size: int,
+        average_queue_time: TimestampDuration,
+        average_processing_time: TimestampDuration,
+        shed_questions: int,
+        dropped_messages: int,
+    ):
+        self.queue_depth = queue_depth
+        self.queue_size = queue_size
+        self.average_queue_time = average_queue_time
+        self.average_processing_time = average_processing_time
+        self.shed_questions = shed_questions
+        self.dropped_messages = dropped_messages
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        """JSON object hook"""
+        return cls(
+            j["queue_depth"],
+            j["queue_size"],
+            TimestampDuration(j["average_queue_time"]),
+            TimestampDuration(j["average_processing_time"]),
+            j["shed_questions"],
+            j["dropped_messages"],
+        )
+
+
 class LatencyStats:
     fastest: TimestampDuration
     average: TimestampDuration
@@ -167,11 +275,103 @@ class TransferStatsDownUp:
         return cls(TransferStats.from_json(j["down"]), TransferStats.from_json(j["up"]))
 
 
+class TransferStatsWindows:
+    minute: TransferStatsDownUp
+    hour: TransferStatsDownUp
+    day: TransferStatsDownUp
+
+    def __init__(
+        self, minute: TransferStatsDownUp, hour: TransferStatsDownUp, day: TransferStatsDownUp
+    ):
+        self.minute = minute
+        self.hour = hour
+        self.day = day
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        """JSON object hook"""
+        return cls(
+            TransferStatsDownUp.from_json(j["minute"]),
+            TransferStatsDownUp.from_json(j["hour"]),
+            TransferStatsDownUp.from_json(j["day"]),
+        )
+
+
+class RoutingDomain(StrEnum):
+    LOCAL_NETWORK = "LocalNetwork"
+    PUBLIC_INTERNET = "PublicInternet"
+
+
+class ProtocolType(StrEnum):
+    UDP = "UDP"
+    TCP = "TCP"
+    WS = "WS"
+    WSS = "WSS"
+
+
+class DomainTransferStats:
+    routing_domain: RoutingDomain
+    protocol_type: ProtocolType
+    transfer_stats: TransferStatsDownUp
+
+    def __init__(
+        self,
+        routing_domain: RoutingDomain,
+        protocol_type: ProtocolType,
+        transfer_stats: TransferStatsDownUp,
+    ):
+        self.routing_domain = routing_domain
+        self.protocol_type = protocol_type
+        self.transfer_stats = transfer_stats
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        """JSON object hook"""
+        return cls(
+            RoutingDomain(j["routing_domain"]),
+            ProtocolType(j["protocol_type"]),
+            TransferStatsDownUp.from_json(j["transfer_stats"]),
+        )
+
+
+class ListenerStatus:
+    protocol_type: ProtocolType
+    bound_addresses: list[str]
+    last_error: Optional[str]
+    certificate_expiration: Optional[Timestamp]
+
+    def __init__(
+        self,
+        protocol_type: ProtocolType,
+        bound_addresses: list[str],
+        last_error: Optional[str],
+        certificate_expiration: Optional[Timestamp],
+    ):
+        self.protocol_type = protocol_type
+        self.bound_addresses = bound_addresses
+        self.last_error = last_error
+        self.certificate_expiration = certificate_expiration
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        """JSON object hook"""
+        return cls(
+            ProtocolType(j["protocol_type"]),
+            j["bound_addresses"],
+            j.get("last_error"),
+            None
+            if j.get("certificate_expiration") is None
+            else Timestamp(j["certificate_expiration"]),
+        )
+
+
 class PeerStats:
     time_added: Timestamp
     rpc_stats: RPCStats
     latency: Optional[LatencyStats]
     transfer: TransferStatsDownUp
+    clock_skew: Optional[int]
+    transfer_windows: Optional[TransferStatsWindows]
 
     def __init__(
         self,
@@ -179,11 +379,15 @@ class PeerStats:
         rpc_stats: RPCStats,
         latency: Optional[LatencyStats],
         transfer: TransferStatsDownUp,
+        clock_skew: Optional[int],
+        transfer_windows: Optional[TransferStatsWindows],
     ):
         self.time_added = time_added
         self.rpc_stats = rpc_stats
         self.latency = latency
         self.transfer = transfer
+        self.clock_skew = clock_skew
+        self.transfer_windows = transfer_windows
 
     @classmethod
     def from_json(cls, j: dict) -> Self:
@@ -193,6 +397,12 @@ class PeerStats:
             RPCStats.from_json(j["rpc_stats"]),
             None if j["latency"] is None else LatencyStats.from_json(j["latency"]),
             TransferStatsDownUp.from_json(j["transfer"]),
+            j.get("clock_skew"),
+            (
+                TransferStatsWindows.from_json(j["transfer_windows"])
+                if "transfer_windows" in j
+                else None
+            ),
         )
 
 
@@ -217,6 +427,12 @@ class VeilidStateNetwork:
     bps_down: ByteCount
     bps_up: ByteCount
     peers: list[PeerTableData]
+    transfer_stats: list[DomainTransferStats]
+    clock_skew: Optional[int]
+    clock_skew_warning: bool
+    listeners: list[ListenerStatus]
+    rpc_load: Optional[RPCLoadStats]
+    transfer_windows: Optional[TransferStatsWindows]
 
     def __init__(
         self,
@@ -224,11 +440,23 @@ class VeilidStateNetwork:
         bps_down: ByteCount,
         bps_up: ByteCount,
         peers: list[PeerTableData],
+        transfer_stats: list[DomainTransferStats],
+        clock_skew: Optional[int],
+        clock_skew_warning: bool,
+        listeners: list[ListenerStatus],
+        rpc_load: Optional[RPCLoadStats],
+        transfer_windows: Optional[TransferStatsWindows],
     ):
         self.started = started
         self.bps_down = bps_down
         self.bps_up = bps_up
         self.peers = peers
+        self.transfer_stats = transfer_stats
+        self.clock_skew = clock_skew
+        self.clock_skew_warning = clock_skew_warning
+        self.listeners = listeners
+        self.rpc_load = rpc_load
+        self.transfer_windows = transfer_windows
 
     @classmethod
     def from_json(cls, j: dict) -> Self:
@@ -238,6 +466,16 @@ class VeilidStateNetwork:
             ByteCount(j["bps_down"]),
             ByteCount(j["bps_up"]),
             [PeerTableData.from_json(peer) for peer in j["peers"]],
+            [DomainTransferStats.from_json(stats) for stats in j["transfer_stats"]],
+            j.get("clock_skew"),
+            j.get("clock_skew_warning", False),
+            [ListenerStatus.from_json(listener) for listener in j.get("listeners", [])],
+            RPCLoadStats.from_json(j["rpc_load"]) if "rpc_load" in j else None,
+            (
+                TransferStatsWindows.from_json(j["transfer_windows"])
+                if "transfer_windows" in j
+                else None
+            ),
         )
 
 
@@ -336,14 +574,44 @@ class VeilidAppCall:
             OperationId(j["call_id"]),
         )
 
+    def to_json(self) -> dict:
+        return self.__dict__
+
+
+class VeilidRouteRotation:
+    old_route: RouteId
+    new_route: RouteId
+    blob: bytes
+
+    def __init__(self, old_route: RouteId, new_route: RouteId, blob: bytes):
+        self.old_route = old_route
+        self.new_route = new_route
+        self.blob = blob
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        """JSON object hook"""
+        return cls(
+            RouteId(j["old_route"]),
+            RouteId(j["new_route"]),
+            urlsafe_b64decode_no_pad(j["blob"]),
+        )
+
 
 class VeilidRouteChange:
     dead_routes: list[RouteId]
     dead_remote_routes: list[RouteId]
+    rotated_routes: list[VeilidRouteRotation]
 
-    def __init__(self, dead_routes: list[RouteId], dead_remote_routes: list[RouteId]):
+    def __init__(
+        self,
+        dead_routes: list[RouteId],
+        dead_remote_routes: list[RouteId],
+        rotated_routes: list[VeilidRouteRotation],
+    ):
         self.dead_routes = dead_routes
         self.dead_remote_routes = dead_remote_routes
+        self.rotated_routes = rotated_routes
 
     @classmethod
     def from_json(cls, j: dict) -> Self:
@@ -351,6 +619,7 @@ class VeilidRouteChange:
         return cls(
             [RouteId(route) for route in j["dead_routes"]],
             [RouteId(route) for route in j["dead_remote_routes"]],
+            [VeilidRouteRotation.from_json(r) for r in j["rotated_routes"]],
         )
 
 
@@ -359,12 +628,14 @@ class VeilidValueChange:
     subkeys: list[tuple[ValueSubkey, ValueSubkey]]
     count: int
     value: Optional[ValueData]
+    gap: bool
 
-    def __init__(self, key: TypedKey, subkeys: list[tuple[ValueSubkey, ValueSubkey]], count: int, value: Optional[ValueData]):
+    def __init__(self, key: TypedKey, subkeys: list[tuple[ValueSubkey, ValueSubkey]], count: int, value: Optional[ValueData], gap: bool = False):
         self.key = key
         self.subkeys = subkeys
         self.count = count
         self.value = value
+        self.gap = gap
 
     @classmethod
     def from_json(cls, j: dict) -> Self:
@@ -374,9 +645,41 @@ class VeilidValueChange:
             [(p[0], p[1]) for p in j["subkeys"]],
             j["count"],
             None if j["value"] is None else ValueData.from_json(j["value"]),
+            j.get("gap", False),
+        )
+
+
+class VeilidIdentityChange:
+    new_node_ids: list[TypedKey]
+    new_device_encryption_key: bool
+
+    def __init__(self, new_node_ids: list[TypedKey], new_device_encryption_key: bool):
+        self.new_node_ids = new_node_ids
+        self.new_device_encryption_key = new_device_encryption_key
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        """JSON object hook"""
+        return cls(
+            [TypedKey(node_id) for node_id in j["new_node_ids"]],
+            j["new_device_encryption_key"],
         )
 
 
+class VeilidStorageEviction:
+    evicted_record_count: int
+    evicted_space: ByteCount
+
+    def __init__(self, evicted_record_count: int, evicted_space: ByteCount):
+        self.evicted_record_count = evicted_record_count
+        self.evicted_space = evicted_space
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        """JSON object hook"""
+        return cls(j["evicted_record_count"], ByteCount(j["evicted_space"]))
+
+
 class VeilidUpdateKind(StrEnum):
     LOG = "Log"
     APP_MESSAGE = "AppMessage"
@@ -386,6 +689,8 @@ class VeilidUpdateKind(StrEnum):
     CONFIG = "Config"
     ROUTE_CHANGE = "RouteChange"
     VALUE_CHANGE = "ValueChange"
+    IDENTITY_CHANGE = "IdentityChange"
+    STORAGE_EVICTION = "StorageEviction"
     SHUTDOWN = "Shutdown"
 
 
@@ -398,6 +703,8 @@ VeilidUpdateDetailType = Optional[
     | VeilidStateConfig
     | VeilidRouteChange
     | VeilidValueChange
+    | VeilidIdentityChange
+    | VeilidStorageEviction
 ]
 
 
@@ -435,6 +742,10 @@ class VeilidUpdate:
                 detail = VeilidRouteChange.from_json(j)
             case VeilidUpdateKind.VALUE_CHANGE:
                 detail = VeilidValueChange.from_json(j)
+            case VeilidUpdateKind.IDENTITY_CHANGE:
+                detail = VeilidIdentityChange.from_json(j)
+            case VeilidUpdateKind.STORAGE_EVICTION:
+                detail = VeilidStorageEviction.from_json(j)
             case VeilidUpdateKind.SHUTDOWN:
                 detail = None
             case _:
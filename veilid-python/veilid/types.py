@@ -50,6 +50,7 @@ class VeilidLogLevel(StrEnum):
 class CryptoKind(StrEnum):
     CRYPTO_KIND_NONE = "NONE"
     CRYPTO_KIND_VLD0 = "VLD0"
+    CRYPTO_KIND_VLD1 = "VLD1"
 
 
 class Capability(StrEnum):
@@ -60,7 +61,12 @@ class Capability(StrEnum):
     CAP_VALIDATE_DIAL_INFO = "DIAL"
     CAP_DHT = "DHTV"
     CAP_DHT_WATCH = "DHTW"
+    CAP_DHT_COMPRESSION = "DHTC"
+    CAP_DHT_BATCH = "DHTB"
+    CAP_DHT_DIGEST = "DHTD"
     CAP_APPMESSAGE = "APPM"
+    CAP_ENVELOPE_COMPRESSION = "ENVC"
+    CAP_BUSY_ANSWER = "BUSY"
     CAP_BLOCKSTORE = "BLOC"
 
 
@@ -69,6 +75,21 @@ class Stability(StrEnum):
     RELIABLE = "Reliable"
 
 
+class SafetyProfile(StrEnum):
+    FAST = "Fast"
+    BALANCED = "Balanced"
+    PARANOID = "Paranoid"
+
+
+class ContactMethod(StrEnum):
+    EXISTING = "Existing"
+    DIRECT = "Direct"
+    REVERSE_CONNECTION = "ReverseConnection"
+    HOLE_PUNCH = "HolePunch"
+    RELAY = "Relay"
+    ROUTED = "Routed"
+
+
 class Sequencing(StrEnum):
     NO_PREFERENCE = "NoPreference"
     PREFER_ORDERED = "PreferOrdered"
@@ -78,6 +99,8 @@ class Sequencing(StrEnum):
 class DHTSchemaKind(StrEnum):
     DFLT = "DFLT"
     SMPL = "SMPL"
+    ALOG = "ALOG"
+    MULT = "MULT"
 
 
 class SafetySelectionKind(StrEnum):
@@ -295,6 +318,118 @@ class NewPrivateRouteResult:
         return cls(RouteId(j["route_id"]), urlsafe_b64decode_no_pad(j["blob"]))
 
 
+class PrivateRouteTestResult:
+    success: bool
+    latency: Optional[TimestampDuration]
+
+    def __init__(self, success: bool, latency: Optional[TimestampDuration]):
+        self.success = success
+        self.latency = latency
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        return cls(
+            j["success"],
+            None if j.get("latency") is None else TimestampDuration(j["latency"]),
+        )
+
+
+class PrivateRouteRotation:
+    interval_ms: int
+    overlap_ms: int
+    record: Optional[TypedKey]
+    subkey: ValueSubkey
+
+    def __init__(
+        self,
+        interval_ms: int,
+        overlap_ms: int,
+        record: Optional[TypedKey] = None,
+        subkey: ValueSubkey = ValueSubkey(0),
+    ):
+        self.interval_ms = interval_ms
+        self.overlap_ms = overlap_ms
+        self.record = record
+        self.subkey = subkey
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        return cls(
+            j["interval_ms"],
+            j["overlap_ms"],
+            None if j.get("record") is None else TypedKey(j["record"]),
+            ValueSubkey(j["subkey"]),
+        )
+
+    def to_json(self) -> dict:
+        return self.__dict__
+
+
+class RendezvousInvite:
+    crypto_kind: CryptoKind
+    keypair: KeyPair
+    route_id: RouteId
+
+    def __init__(self, crypto_kind: CryptoKind, keypair: KeyPair, route_id: RouteId):
+        self.crypto_kind = crypto_kind
+        self.keypair = keypair
+        self.route_id = route_id
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        return cls(CryptoKind(j["crypto_kind"]), KeyPair(j["keypair"]), RouteId(j["route_id"]))
+
+    def to_json(self) -> dict:
+        return self.__dict__
+
+
+class CreateInviteResult:
+    invite: RendezvousInvite
+    blob: bytes
+
+    def __init__(self, invite: RendezvousInvite, blob: bytes):
+        self.invite = invite
+        self.blob = blob
+
+    def to_tuple(self) -> Tuple[RendezvousInvite, bytes]:
+        return (self.invite, self.blob)
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        return cls(RendezvousInvite.from_json(j["invite"]), urlsafe_b64decode_no_pad(j["blob"]))
+
+
+class RendezvousSession:
+    crypto_kind: CryptoKind
+    shared_secret: SharedSecret
+    local_route_id: RouteId
+    remote_route_id: RouteId
+
+    def __init__(
+        self,
+        crypto_kind: CryptoKind,
+        shared_secret: SharedSecret,
+        local_route_id: RouteId,
+        remote_route_id: RouteId,
+    ):
+        self.crypto_kind = crypto_kind
+        self.shared_secret = shared_secret
+        self.local_route_id = local_route_id
+        self.remote_route_id = remote_route_id
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        return cls(
+            CryptoKind(j["crypto_kind"]),
+            SharedSecret(j["shared_secret"]),
+            RouteId(j["local_route_id"]),
+            RouteId(j["remote_route_id"]),
+        )
+
+    def to_json(self) -> dict:
+        return self.__dict__
+
+
 class DHTSchemaSMPLMember:
     m_key: PublicKey
     m_cnt: int
@@ -327,6 +462,14 @@ class DHTSchema:
     def smpl(cls, o_cnt: int, members: list[DHTSchemaSMPLMember]) -> Self:
         return cls(DHTSchemaKind.SMPL, o_cnt=o_cnt, members=members)
 
+    @classmethod
+    def alog(cls, max_len: int, writers: list[PublicKey]) -> Self:
+        return cls(DHTSchemaKind.ALOG, max_len=max_len, writers=writers)
+
+    @classmethod
+    def mult(cls, threshold: int, owners: list[PublicKey], o_cnt: int) -> Self:
+        return cls(DHTSchemaKind.MULT, threshold=threshold, owners=owners, o_cnt=o_cnt)
+
     @classmethod
     def from_json(cls, j: dict) -> Self:
         if DHTSchemaKind(j["kind"]) == DHTSchemaKind.DFLT:
@@ -336,6 +479,17 @@ class DHTSchema:
                 j["o_cnt"],
                 [DHTSchemaSMPLMember.from_json(member) for member in j["members"]],
             )
+        if DHTSchemaKind(j["kind"]) == DHTSchemaKind.ALOG:
+            return cls.alog(
+                j["max_len"],
+                [PublicKey(writer) for writer in j["writers"]],
+            )
+        if DHTSchemaKind(j["kind"]) == DHTSchemaKind.MULT:
+            return cls.mult(
+                j["threshold"],
+                [PublicKey(owner) for owner in j["owners"]],
+                j["o_cnt"],
+            )
         raise Exception("Unknown DHTSchema kind", j["kind"])
 
     def to_json(self) -> dict:
@@ -347,6 +501,8 @@ class DHTRecordDescriptor:
     owner: PublicKey
     owner_secret: Optional[SecretKey]
     schema: DHTSchema
+    version: int
+    expiration: Optional[Timestamp]
 
     def __init__(
         self,
@@ -354,14 +510,18 @@ class DHTRecordDescriptor:
         owner: PublicKey,
         owner_secret: Optional[SecretKey],
         schema: DHTSchema,
+        version: int = 0,
+        expiration: Optional[Timestamp] = None,
     ):
         self.key = key
         self.owner = owner
         self.owner_secret = owner_secret
         self.schema = schema
+        self.version = version
+        self.expiration = expiration
 
     def __repr__(self) -> str:
-        return f"<{self.__class__.__name__}(key={self.key!r}, owner={self.owner!r}, owner_secret={self.owner_secret!r}, schema={self.schema!r})>"
+        return f"<{self.__class__.__name__}(key={self.key!r}, owner={self.owner!r}, owner_secret={self.owner_secret!r}, schema={self.schema!r}, version={self.version!r}, expiration={self.expiration!r})>"
 
     def owner_key_pair(self) -> Optional[KeyPair]:
         return KeyPair.from_parts(self.owner, self.owner_secret)
@@ -373,6 +533,8 @@ class DHTRecordDescriptor:
             PublicKey(j["owner"]),
             None if j["owner_secret"] is None else SecretKey(j["owner_secret"]),
             DHTSchema.from_json(j["schema"]),
+            j.get("version", 0),
+            None if j.get("expiration") is None else Timestamp(j["expiration"]),
         )
 
     def to_json(self) -> dict:
@@ -410,6 +572,75 @@ class DHTRecordReport:
         return self.__dict__
 
 
+class DHTStorageUsage:
+    local_record_count: int
+    local_used: ByteCount
+    remote_record_count: int
+    remote_used: ByteCount
+    remote_high_watermark: Optional[ByteCount]
+    remote_limit: Optional[ByteCount]
+
+    def __init__(
+        self,
+        local_record_count: int,
+        local_used: ByteCount,
+        remote_record_count: int,
+        remote_used: ByteCount,
+        remote_high_watermark: Optional[ByteCount],
+        remote_limit: Optional[ByteCount],
+    ):
+        self.local_record_count = local_record_count
+        self.local_used = local_used
+        self.remote_record_count = remote_record_count
+        self.remote_used = remote_used
+        self.remote_high_watermark = remote_high_watermark
+        self.remote_limit = remote_limit
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        return cls(
+            j["local_record_count"],
+            ByteCount(j["local_used"]),
+            j["remote_record_count"],
+            ByteCount(j["remote_used"]),
+            None
+            if j.get("remote_high_watermark") is None
+            else ByteCount(j["remote_high_watermark"]),
+            None if j.get("remote_limit") is None else ByteCount(j["remote_limit"]),
+        )
+
+
+class NetworkCost:
+    metered: bool
+    roaming: bool
+
+    def __init__(self, metered: bool = False, roaming: bool = False):
+        self.metered = metered
+        self.roaming = roaming
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        return cls(j["metered"], j["roaming"])
+
+    def to_json(self) -> dict:
+        return self.__dict__
+
+
+class NetworkTime:
+    timestamp: Timestamp
+    clock_skew: int
+    peer_count: int
+
+    def __init__(self, timestamp: Timestamp, clock_skew: int, peer_count: int):
+        self.timestamp = timestamp
+        self.clock_skew = clock_skew
+        self.peer_count = peer_count
+
+    @classmethod
+    def from_json(cls, j: dict) -> Self:
+        return cls(Timestamp(j["timestamp"]), j["clock_skew"], j["peer_count"])
+
+
 @total_ordering
 class ValueData:
     seq: ValueSeqNum
@@ -467,6 +698,8 @@ class SafetySpec:
     hop_count: int
     stability: Stability
     sequencing: Sequencing
+    preferred_first_hop: Optional[TypedKey]
+    profile: Optional[SafetyProfile]
 
     def __init__(
         self,
@@ -474,11 +707,15 @@ class SafetySpec:
         hop_count: int,
         stability: Stability,
         sequencing: Sequencing,
+        preferred_first_hop: Optional[TypedKey] = None,
+        profile: Optional[SafetyProfile] = None,
     ):
         self.preferred_route = preferred_route
         self.hop_count = hop_count
         self.stability = stability
         self.sequencing = sequencing
+        self.preferred_first_hop = preferred_first_hop
+        self.profile = profile
 
     @classmethod
     def from_json(cls, j: dict) -> Self:
@@ -487,6 +724,10 @@ class SafetySpec:
             j["hop_count"],
             Stability(j["stability"]),
             Sequencing(j["sequencing"]),
+            TypedKey(j["preferred_first_hop"])
+            if j.get("preferred_first_hop") is not None
+            else None,
+            SafetyProfile(j["profile"]) if j.get("profile") is not None else None,
         )
 
     def to_json(self) -> dict:
@@ -93,6 +93,18 @@ impl ClientApi {
         veilid_logs.change_log_level(layer, log_level)
     }
 
+    fn change_log_component_level(
+        &self,
+        layer: String,
+        component: String,
+        log_level: Option<VeilidConfigLogLevel>,
+    ) -> VeilidAPIResult<()> {
+        trace!(target: "client_api", "ClientApi::change_log_component_level");
+
+        let veilid_logs = self.inner.lock().veilid_logs.clone();
+        veilid_logs.change_log_component_level(layer, component, log_level)
+    }
+
     fn change_log_ignore(&self, layer: String, log_ignore: String) -> VeilidAPIResult<()> {
         trace!(target: "client_api", "ClientApi::change_log_ignore");
 
@@ -210,6 +222,18 @@ impl ClientApi {
             let log_level = VeilidConfigLogLevel::from_str(&args[2])?;
             self.change_log_level(args[1].clone(), log_level)?;
             Ok("".to_owned())
+        } else if args[0] == "ChangeLogComponentLevel" {
+            if args.len() != 4 {
+                apibail_generic!("wrong number of arguments");
+            }
+            // 'default' removes the override so the component follows the layer's level again
+            let log_level = if args[3] == "default" {
+                None
+            } else {
+                Some(VeilidConfigLogLevel::from_str(&args[3])?)
+            };
+            self.change_log_component_level(args[1].clone(), args[2].clone(), log_level)?;
+            Ok("".to_owned())
         } else if args[0] == "ChangeLogIgnore" {
             if args.len() != 3 {
                 apibail_generic!("wrong number of arguments");
@@ -358,7 +382,8 @@ impl ClientApi {
         W: AsyncWriteExt + Unpin + Send,
     {
         // Make request processor for this connection
-        let api = self.inner.lock().veilid_api.clone();
+        // Each connection gets its own private route budget
+        let api = self.inner.lock().veilid_api.new_instance();
         let jrp = json_api::JsonRequestProcessor::new(api);
 
         // Futures to process unordered
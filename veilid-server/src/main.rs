@@ -116,6 +116,11 @@ pub struct CmdlineArgs {
     #[arg(long)]
     dump_config: bool,
 
+    /// Instead of running the server, print a description of every veilid-core configuration field to the console,
+    /// with its type, default and constraints. The fields go under 'core' in the server configuration.
+    #[arg(long)]
+    dump_config_schema: bool,
+
     /// Prints the bootstrap TXT record for this node and then quits
     #[arg(long)]
     dump_txt_record: bool,
@@ -145,6 +150,18 @@ pub struct CmdlineArgs {
     #[cfg(feature = "rt-tokio")]
     #[arg(long)]
     console: bool,
+
+    /// Run with a virtual clock and seeded non-security randomness, to reproduce a debugging session
+    ///
+    /// Makes operation ids and other identifiers predictable. Never use this on a real network.
+    #[cfg(feature = "deterministic")]
+    #[arg(long, value_name = "SEED")]
+    deterministic_seed: Option<u64>,
+
+    /// Virtual time to start the clock at in deterministic mode, in microseconds since the epoch. Defaults to the current time.
+    #[cfg(feature = "deterministic")]
+    #[arg(long, value_name = "TIMESTAMP", requires = "deterministic_seed")]
+    deterministic_start_ts: Option<u64>,
 }
 
 #[instrument(err)]
@@ -165,6 +182,19 @@ fn main() -> EyreResult<()> {
         debugger::wait_until_attached(None).expect("state() not implemented on this platform");
     }
 
+    // Switch to the virtual clock before anything reads the time
+    #[cfg(feature = "deterministic")]
+    if let Some(seed) = args.deterministic_seed {
+        let start_ts = args
+            .deterministic_start_ts
+            .unwrap_or_else(veilid_core::tools::get_timestamp);
+        println!(
+            "Enabling deterministic mode with seed {} at virtual time {}",
+            seed, start_ts
+        );
+        veilid_core::tools::enable_deterministic_mode(seed, start_ts);
+    }
+
     // Attempt to load configuration
     let settings_path: Option<OsString> = args
         .config_file
@@ -296,6 +326,15 @@ fn main() -> EyreResult<()> {
             .wrap_err("failed to write yaml");
     }
 
+    // --- Dump Config Schema ---
+    if args.dump_config_schema {
+        println!(
+            "{}",
+            veilid_core::serialize_json(veilid_core::veilid_config_schema())
+        );
+        return Ok(());
+    }
+
     // --- Generate DHT Key ---
     if let Some(ckstr) = args.generate_key_pair {
         if ckstr.is_empty() {
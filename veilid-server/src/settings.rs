@@ -47,25 +47,30 @@ logging:
         enabled: false
         level: 'info'
         ignore_log_targets: []
+        component_levels: {}
     terminal:
         enabled: true
         level: 'info'
         ignore_log_targets: []
+        component_levels: {}
     file: 
         enabled: false
         path: ''
         append: true
         level: 'info'
         ignore_log_targets: []
+        component_levels: {}
     api:
         enabled: true
         level: 'info'
         ignore_log_targets: []
+        component_levels: {}
     otlp:
         enabled: false
         level: 'trace'
         grpc_endpoint: 'localhost:4317'
         ignore_log_targets: []
+        component_levels: {}
     console:
         enabled: false
 testing:
@@ -76,6 +81,8 @@ core:
     protected_store:
         allow_insecure_fallback: true
         always_use_insecure_storage: true
+        hardware_node_id: false
+        hardware_node_id_migrate: false
         directory: '%DIRECTORY%'
         delete: false
         device_encryption_key_password: '%DEVICE_ENCRYPTION_KEY_PASSWORD%'
@@ -83,6 +90,7 @@ core:
     table_store:
         directory: '%TABLE_STORE_DIRECTORY%'
         delete: false
+        backend: 'persistent'
     block_store:
         directory: '%BLOCK_STORE_DIRECTORY%'
         delete: false
@@ -96,6 +104,9 @@ core:
         client_allowlist_timeout_ms: 300000 
         reverse_connection_receipt_time_ms: 5000 
         hole_punch_receipt_time_ms: 5000 
+        prewarm_grace_period_ms: 60000
+        max_relay_client_bytes_per_sec: 1048576
+        max_relay_client_queued_bytes: 262144
         network_key_password: null
         disable_capabilites: []
         routing_table:
@@ -107,14 +118,28 @@ core:
             limit_attached_strong: 16
             limit_attached_good: 8
             limit_attached_weak: 4
+            node_info_max_age_ms: 3600000
+            verified_peers_only: []
         rpc: 
             concurrency: 0
             queue_size: 1024
             max_timestamp_behind_ms: 10000
             max_timestamp_ahead_ms: 10000
+            max_clock_skew_ms: 60000
+            clock_skew_warning_ms: 5000
             timeout_ms: 5000
             max_route_hop_count: 4
             default_route_hop_count: 1
+            max_private_routes: 128
+            max_private_routes_per_api: 32
+            private_route_idle_release_ms: null
+            max_app_message_size: 32768
+            max_app_call_size: 32768
+            max_app_message_frequency_per_min: 1024
+            max_app_call_frequency_per_min: 256
+            strict_udp_source: false
+            strict_udp_source_allow_rebind: true
+            route_pow_difficulty: 0
         dht:
             max_find_node_count: 20
             resolve_node_timeout_ms: 10000
@@ -126,6 +151,7 @@ core:
             set_value_timeout_ms: 10000
             set_value_count: 5
             set_value_fanout: 4
+            min_fanout_under_load: 1
             min_peer_count: 20
             min_peer_refresh_time_ms: 60000
             validate_dial_info_receipt_time_ms: 2000
@@ -135,39 +161,54 @@ core:
             remote_max_records: 65536
             remote_max_subkey_cache_memory_mb: %REMOTE_MAX_SUBKEY_CACHE_MEMORY_MB%
             remote_max_storage_space_mb: 0
+            remote_storage_high_watermark_percent: 90
+            remote_storage_low_watermark_percent: 75
             public_watch_limit: 32
             member_watch_limit: 8
             max_watch_expiration_ms: 600000
+            set_value_pow_difficulty: 0
+            compress_values: false
+            audit_log_max_entries: 0
         upnp: true
         detect_address_changes: true
+        detect_address_changes_distinct_subnets: 3
         restricted_nat_retries: 0
+        max_envelope_size: 65507
         tls:
             certificate_path: '%CERTIFICATE_PATH%'
             private_key_path: '%PRIVATE_KEY_PATH%'
             connection_initial_timeout_ms: 2000
+        tor:
+            enabled: false
+            socks_address: '127.0.0.1:9050'
+            onion_address: null
         application:
             https:
                 enabled: false
                 listen_address: ':443'
                 path: 'app'
                 # url: 'https://localhost'
+                # directory: '/var/lib/veilid/www'
             http:
                 enabled: false
                 listen_address: ':80'
                 path: 'app'
                 # url: 'http://localhost'
+                # directory: '/var/lib/veilid/www'
         protocol:
             udp:
                 enabled: true
                 socket_pool_size: 0
                 listen_address: ''
                 # public_address: ''
+                preference: 0
             tcp:
                 connect: true
                 listen: true
                 max_connections: 32
                 listen_address: ''
                 #'public_address: ''
+                preference: 0
             ws:
                 connect: true
                 listen: true
@@ -175,6 +216,7 @@ core:
                 listen_address: ''
                 path: 'ws'
                 # url: 'ws://localhost:5150/ws'
+                preference: 0
             wss:
                 connect: true
                 listen: false
@@ -182,6 +224,15 @@ core:
                 listen_address: ''
                 path: 'ws'
                 # url: ''
+                preference: 0
+            ipc:
+                connect: true
+                listen: false
+                listen_path: ''
+        role: 'Full'
+        metered_policy: 'reduce_tick_rate'
+        roaming_policy: 'suspend'
+        low_power_wake_interval_ms: 60000
         "#,
     )
     .replace(
@@ -314,6 +365,15 @@ pub fn convert_loglevel(log_level: LogLevel) -> veilid_core::VeilidConfigLogLeve
     }
 }
 
+pub fn convert_component_levels(
+    component_levels: &BTreeMap<String, LogLevel>,
+) -> BTreeMap<String, veilid_core::VeilidConfigLogLevel> {
+    component_levels
+        .iter()
+        .map(|(k, v)| (k.clone(), convert_loglevel(*v)))
+        .collect()
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ParsedUrl {
     pub urlstring: String,
@@ -440,6 +500,7 @@ pub struct Terminal {
     pub enabled: bool,
     pub level: LogLevel,
     pub ignore_log_targets: Vec<String>,
+    pub component_levels: BTreeMap<String, LogLevel>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -454,6 +515,7 @@ pub struct File {
     pub append: bool,
     pub level: LogLevel,
     pub ignore_log_targets: Vec<String>,
+    pub component_levels: BTreeMap<String, LogLevel>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -461,6 +523,7 @@ pub struct System {
     pub enabled: bool,
     pub level: LogLevel,
     pub ignore_log_targets: Vec<String>,
+    pub component_levels: BTreeMap<String, LogLevel>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -468,6 +531,7 @@ pub struct Api {
     pub enabled: bool,
     pub level: LogLevel,
     pub ignore_log_targets: Vec<String>,
+    pub component_levels: BTreeMap<String, LogLevel>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -476,6 +540,7 @@ pub struct Otlp {
     pub level: LogLevel,
     pub grpc_endpoint: NamedSocketAddrs,
     pub ignore_log_targets: Vec<String>,
+    pub component_levels: BTreeMap<String, LogLevel>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -502,6 +567,7 @@ pub struct Https {
     pub listen_address: NamedSocketAddrs,
     pub path: PathBuf,
     pub url: Option<ParsedUrl>,
+    pub directory: Option<PathBuf>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -510,6 +576,7 @@ pub struct Http {
     pub listen_address: NamedSocketAddrs,
     pub path: PathBuf,
     pub url: Option<ParsedUrl>,
+    pub directory: Option<PathBuf>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -524,6 +591,7 @@ pub struct Udp {
     pub socket_pool_size: u32,
     pub listen_address: NamedSocketAddrs,
     pub public_address: Option<NamedSocketAddrs>,
+    pub preference: u8,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -533,6 +601,7 @@ pub struct Tcp {
     pub max_connections: u32,
     pub listen_address: NamedSocketAddrs,
     pub public_address: Option<NamedSocketAddrs>,
+    pub preference: u8,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -543,6 +612,7 @@ pub struct Ws {
     pub listen_address: NamedSocketAddrs,
     pub path: PathBuf,
     pub url: Option<ParsedUrl>,
+    pub preference: u8,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -553,6 +623,14 @@ pub struct Wss {
     pub listen_address: NamedSocketAddrs,
     pub path: PathBuf,
     pub url: Option<ParsedUrl>,
+    pub preference: u8,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Ipc {
+    pub connect: bool,
+    pub listen: bool,
+    pub listen_path: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -561,6 +639,7 @@ pub struct Protocol {
     pub tcp: Tcp,
     pub ws: Ws,
     pub wss: Wss,
+    pub ipc: Ipc,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -570,15 +649,34 @@ pub struct Tls {
     pub connection_initial_timeout_ms: u32,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Tor {
+    pub enabled: bool,
+    pub socks_address: String,
+    pub onion_address: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Rpc {
     pub concurrency: u32,
     pub queue_size: u32,
     pub max_timestamp_behind_ms: Option<u32>,
     pub max_timestamp_ahead_ms: Option<u32>,
+    pub max_clock_skew_ms: u32,
+    pub clock_skew_warning_ms: u32,
     pub timeout_ms: u32,
     pub max_route_hop_count: u8,
     pub default_route_hop_count: u8,
+    pub max_private_routes: u32,
+    pub max_private_routes_per_api: u32,
+    pub private_route_idle_release_ms: Option<u32>,
+    pub max_app_message_size: u32,
+    pub max_app_call_size: u32,
+    pub max_app_message_frequency_per_min: u32,
+    pub max_app_call_frequency_per_min: u32,
+    pub strict_udp_source: bool,
+    pub strict_udp_source_allow_rebind: bool,
+    pub route_pow_difficulty: u32,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -593,6 +691,7 @@ pub struct Dht {
     pub set_value_timeout_ms: u32,
     pub set_value_count: u32,
     pub set_value_fanout: u32,
+    pub min_fanout_under_load: u32,
     pub min_peer_count: u32,
     pub min_peer_refresh_time_ms: u32,
     pub validate_dial_info_receipt_time_ms: u32,
@@ -602,9 +701,14 @@ pub struct Dht {
     pub remote_max_records: u32,
     pub remote_max_subkey_cache_memory_mb: u32,
     pub remote_max_storage_space_mb: u32,
+    pub remote_storage_high_watermark_percent: u8,
+    pub remote_storage_low_watermark_percent: u8,
     pub public_watch_limit: u32,
     pub member_watch_limit: u32,
     pub max_watch_expiration_ms: u32,
+    pub set_value_pow_difficulty: u32,
+    pub compress_values: bool,
+    pub audit_log_max_entries: u32,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -617,6 +721,8 @@ pub struct RoutingTable {
     pub limit_attached_strong: u32,
     pub limit_attached_good: u32,
     pub limit_attached_weak: u32,
+    pub node_info_max_age_ms: u32,
+    pub verified_peers_only: Vec<veilid_core::RoutingDomain>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -630,16 +736,26 @@ pub struct Network {
     pub client_allowlist_timeout_ms: u32,
     pub reverse_connection_receipt_time_ms: u32,
     pub hole_punch_receipt_time_ms: u32,
+    pub prewarm_grace_period_ms: u32,
+    pub max_relay_client_bytes_per_sec: u32,
+    pub max_relay_client_queued_bytes: u32,
     pub network_key_password: Option<String>,
     pub routing_table: RoutingTable,
     pub rpc: Rpc,
     pub dht: Dht,
     pub upnp: bool,
     pub detect_address_changes: bool,
+    pub detect_address_changes_distinct_subnets: u32,
     pub restricted_nat_retries: u32,
+    pub max_envelope_size: u32,
     pub tls: Tls,
+    pub tor: Tor,
     pub application: Application,
     pub protocol: Protocol,
+    pub role: String,
+    pub metered_policy: String,
+    pub roaming_policy: String,
+    pub low_power_wake_interval_ms: u32,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -651,6 +767,7 @@ pub struct Testing {
 pub struct TableStore {
     pub directory: String,
     pub delete: bool,
+    pub backend: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -663,6 +780,8 @@ pub struct BlockStore {
 pub struct ProtectedStore {
     pub allow_insecure_fallback: bool,
     pub always_use_insecure_storage: bool,
+    pub hardware_node_id: bool,
+    pub hardware_node_id_migrate: bool,
     pub directory: String,
     pub delete: bool,
     pub device_encryption_key_password: String,
@@ -947,21 +1066,26 @@ impl Settings {
         set_config_value!(inner.logging.system.enabled, value);
         set_config_value!(inner.logging.system.level, value);
         set_config_value!(inner.logging.system.ignore_log_targets, value);
+        set_config_value!(inner.logging.system.component_levels, value);
         set_config_value!(inner.logging.terminal.enabled, value);
         set_config_value!(inner.logging.terminal.level, value);
         set_config_value!(inner.logging.terminal.ignore_log_targets, value);
+        set_config_value!(inner.logging.terminal.component_levels, value);
         set_config_value!(inner.logging.file.enabled, value);
         set_config_value!(inner.logging.file.path, value);
         set_config_value!(inner.logging.file.append, value);
         set_config_value!(inner.logging.file.level, value);
         set_config_value!(inner.logging.file.ignore_log_targets, value);
+        set_config_value!(inner.logging.file.component_levels, value);
         set_config_value!(inner.logging.api.enabled, value);
         set_config_value!(inner.logging.api.level, value);
         set_config_value!(inner.logging.api.ignore_log_targets, value);
+        set_config_value!(inner.logging.api.component_levels, value);
         set_config_value!(inner.logging.otlp.enabled, value);
         set_config_value!(inner.logging.otlp.level, value);
         set_config_value!(inner.logging.otlp.grpc_endpoint, value);
         set_config_value!(inner.logging.otlp.ignore_log_targets, value);
+        set_config_value!(inner.logging.otlp.component_levels, value);
         set_config_value!(inner.logging.console.enabled, value);
         set_config_value!(inner.testing.subnode_index, value);
         set_config_value!(inner.core.capabilities.disable, value);
@@ -970,6 +1094,8 @@ impl Settings {
             inner.core.protected_store.always_use_insecure_storage,
             value
         );
+        set_config_value!(inner.core.protected_store.hardware_node_id, value);
+        set_config_value!(inner.core.protected_store.hardware_node_id_migrate, value);
         set_config_value!(inner.core.protected_store.directory, value);
         set_config_value!(inner.core.protected_store.delete, value);
         set_config_value!(
@@ -985,6 +1111,7 @@ impl Settings {
         );
         set_config_value!(inner.core.table_store.directory, value);
         set_config_value!(inner.core.table_store.delete, value);
+        set_config_value!(inner.core.table_store.backend, value);
         set_config_value!(inner.core.block_store.directory, value);
         set_config_value!(inner.core.block_store.delete, value);
         set_config_value!(inner.core.network.connection_initial_timeout_ms, value);
@@ -999,6 +1126,9 @@ impl Settings {
         set_config_value!(inner.core.network.client_allowlist_timeout_ms, value);
         set_config_value!(inner.core.network.reverse_connection_receipt_time_ms, value);
         set_config_value!(inner.core.network.hole_punch_receipt_time_ms, value);
+        set_config_value!(inner.core.network.prewarm_grace_period_ms, value);
+        set_config_value!(inner.core.network.max_relay_client_bytes_per_sec, value);
+        set_config_value!(inner.core.network.max_relay_client_queued_bytes, value);
         set_config_value!(inner.core.network.network_key_password, value);
         set_config_value!(inner.core.network.routing_table.node_id, value);
         set_config_value!(inner.core.network.routing_table.node_id_secret, value);
@@ -1011,13 +1141,30 @@ impl Settings {
         );
         set_config_value!(inner.core.network.routing_table.limit_attached_good, value);
         set_config_value!(inner.core.network.routing_table.limit_attached_weak, value);
+        set_config_value!(inner.core.network.routing_table.node_info_max_age_ms, value);
+        set_config_value!(inner.core.network.routing_table.verified_peers_only, value);
         set_config_value!(inner.core.network.rpc.concurrency, value);
         set_config_value!(inner.core.network.rpc.queue_size, value);
         set_config_value!(inner.core.network.rpc.max_timestamp_behind_ms, value);
         set_config_value!(inner.core.network.rpc.max_timestamp_ahead_ms, value);
+        set_config_value!(inner.core.network.rpc.max_clock_skew_ms, value);
+        set_config_value!(inner.core.network.rpc.clock_skew_warning_ms, value);
         set_config_value!(inner.core.network.rpc.timeout_ms, value);
         set_config_value!(inner.core.network.rpc.max_route_hop_count, value);
         set_config_value!(inner.core.network.rpc.default_route_hop_count, value);
+        set_config_value!(inner.core.network.rpc.max_private_routes, value);
+        set_config_value!(inner.core.network.rpc.max_private_routes_per_api, value);
+        set_config_value!(inner.core.network.rpc.private_route_idle_release_ms, value);
+        set_config_value!(inner.core.network.rpc.max_app_message_size, value);
+        set_config_value!(inner.core.network.rpc.max_app_call_size, value);
+        set_config_value!(
+            inner.core.network.rpc.max_app_message_frequency_per_min,
+            value
+        );
+        set_config_value!(inner.core.network.rpc.max_app_call_frequency_per_min, value);
+        set_config_value!(inner.core.network.rpc.strict_udp_source, value);
+        set_config_value!(inner.core.network.rpc.strict_udp_source_allow_rebind, value);
+        set_config_value!(inner.core.network.rpc.route_pow_difficulty, value);
         set_config_value!(inner.core.network.dht.max_find_node_count, value);
         set_config_value!(inner.core.network.dht.resolve_node_timeout_ms, value);
         set_config_value!(inner.core.network.dht.resolve_node_count, value);
@@ -1028,6 +1175,7 @@ impl Settings {
         set_config_value!(inner.core.network.dht.set_value_timeout_ms, value);
         set_config_value!(inner.core.network.dht.set_value_count, value);
         set_config_value!(inner.core.network.dht.set_value_fanout, value);
+        set_config_value!(inner.core.network.dht.min_fanout_under_load, value);
         set_config_value!(inner.core.network.dht.min_peer_count, value);
         set_config_value!(inner.core.network.dht.min_peer_refresh_time_ms, value);
         set_config_value!(
@@ -1046,44 +1194,76 @@ impl Settings {
             value
         );
         set_config_value!(inner.core.network.dht.remote_max_storage_space_mb, value);
+        set_config_value!(
+            inner.core.network.dht.remote_storage_high_watermark_percent,
+            value
+        );
+        set_config_value!(
+            inner.core.network.dht.remote_storage_low_watermark_percent,
+            value
+        );
         set_config_value!(inner.core.network.dht.public_watch_limit, value);
         set_config_value!(inner.core.network.dht.member_watch_limit, value);
         set_config_value!(inner.core.network.dht.max_watch_expiration_ms, value);
+        set_config_value!(inner.core.network.dht.set_value_pow_difficulty, value);
+        set_config_value!(inner.core.network.dht.compress_values, value);
+        set_config_value!(inner.core.network.dht.audit_log_max_entries, value);
         set_config_value!(inner.core.network.upnp, value);
         set_config_value!(inner.core.network.detect_address_changes, value);
+        set_config_value!(
+            inner.core.network.detect_address_changes_distinct_subnets,
+            value
+        );
         set_config_value!(inner.core.network.restricted_nat_retries, value);
+        set_config_value!(inner.core.network.max_envelope_size, value);
         set_config_value!(inner.core.network.tls.certificate_path, value);
         set_config_value!(inner.core.network.tls.private_key_path, value);
         set_config_value!(inner.core.network.tls.connection_initial_timeout_ms, value);
+        set_config_value!(inner.core.network.tor.enabled, value);
+        set_config_value!(inner.core.network.tor.socks_address, value);
+        set_config_value!(inner.core.network.tor.onion_address, value);
         set_config_value!(inner.core.network.application.https.enabled, value);
         set_config_value!(inner.core.network.application.https.listen_address, value);
         set_config_value!(inner.core.network.application.https.path, value);
         set_config_value!(inner.core.network.application.https.url, value);
+        set_config_value!(inner.core.network.application.https.directory, value);
         set_config_value!(inner.core.network.application.http.enabled, value);
         set_config_value!(inner.core.network.application.http.listen_address, value);
         set_config_value!(inner.core.network.application.http.path, value);
         set_config_value!(inner.core.network.application.http.url, value);
+        set_config_value!(inner.core.network.application.http.directory, value);
         set_config_value!(inner.core.network.protocol.udp.enabled, value);
         set_config_value!(inner.core.network.protocol.udp.socket_pool_size, value);
         set_config_value!(inner.core.network.protocol.udp.listen_address, value);
         set_config_value!(inner.core.network.protocol.udp.public_address, value);
+        set_config_value!(inner.core.network.protocol.udp.preference, value);
         set_config_value!(inner.core.network.protocol.tcp.connect, value);
         set_config_value!(inner.core.network.protocol.tcp.listen, value);
         set_config_value!(inner.core.network.protocol.tcp.max_connections, value);
         set_config_value!(inner.core.network.protocol.tcp.listen_address, value);
         set_config_value!(inner.core.network.protocol.tcp.public_address, value);
+        set_config_value!(inner.core.network.protocol.tcp.preference, value);
         set_config_value!(inner.core.network.protocol.ws.connect, value);
         set_config_value!(inner.core.network.protocol.ws.listen, value);
         set_config_value!(inner.core.network.protocol.ws.max_connections, value);
         set_config_value!(inner.core.network.protocol.ws.listen_address, value);
         set_config_value!(inner.core.network.protocol.ws.path, value);
         set_config_value!(inner.core.network.protocol.ws.url, value);
+        set_config_value!(inner.core.network.protocol.ws.preference, value);
         set_config_value!(inner.core.network.protocol.wss.connect, value);
         set_config_value!(inner.core.network.protocol.wss.listen, value);
         set_config_value!(inner.core.network.protocol.wss.max_connections, value);
         set_config_value!(inner.core.network.protocol.wss.listen_address, value);
         set_config_value!(inner.core.network.protocol.wss.path, value);
         set_config_value!(inner.core.network.protocol.wss.url, value);
+        set_config_value!(inner.core.network.protocol.wss.preference, value);
+        set_config_value!(inner.core.network.protocol.ipc.connect, value);
+        set_config_value!(inner.core.network.protocol.ipc.listen, value);
+        set_config_value!(inner.core.network.protocol.ipc.listen_path, value);
+        set_config_value!(inner.core.network.role, value);
+        set_config_value!(inner.core.network.metered_policy, value);
+        set_config_value!(inner.core.network.roaming_policy, value);
+        set_config_value!(inner.core.network.low_power_wake_interval_ms, value);
         Err(eyre!("settings key not found"))
     }
 
@@ -1113,6 +1293,12 @@ impl Settings {
                 "protected_store.always_use_insecure_storage" => Ok(Box::new(
                     inner.core.protected_store.always_use_insecure_storage,
                 )),
+                "protected_store.hardware_node_id" => {
+                    Ok(Box::new(inner.core.protected_store.hardware_node_id))
+                }
+                "protected_store.hardware_node_id_migrate" => Ok(Box::new(
+                    inner.core.protected_store.hardware_node_id_migrate,
+                )),
                 "protected_store.directory" => {
                     Ok(Box::new(inner.core.protected_store.directory.clone()))
                 }
@@ -1134,6 +1320,9 @@ impl Settings {
 
                 "table_store.directory" => Ok(Box::new(inner.core.table_store.directory.clone())),
                 "table_store.delete" => Ok(Box::new(inner.core.table_store.delete)),
+                "table_store.backend" => Ok(Box::new(VeilidConfigTableStoreBackend::from_str(
+                    &inner.core.table_store.backend,
+                )?)),
 
                 "block_store.directory" => Ok(Box::new(inner.core.block_store.directory.clone())),
                 "block_store.delete" => Ok(Box::new(inner.core.block_store.delete)),
@@ -1165,6 +1354,15 @@ impl Settings {
                 "network.hole_punch_receipt_time_ms" => {
                     Ok(Box::new(inner.core.network.hole_punch_receipt_time_ms))
                 }
+                "network.prewarm_grace_period_ms" => {
+                    Ok(Box::new(inner.core.network.prewarm_grace_period_ms))
+                }
+                "network.max_relay_client_bytes_per_sec" => {
+                    Ok(Box::new(inner.core.network.max_relay_client_bytes_per_sec))
+                }
+                "network.max_relay_client_queued_bytes" => {
+                    Ok(Box::new(inner.core.network.max_relay_client_queued_bytes))
+                }
                 "network.network_key_password" => {
                     Ok(Box::new(inner.core.network.network_key_password.clone()))
                 }
@@ -1204,6 +1402,12 @@ impl Settings {
                 "network.routing_table.limit_attached_weak" => Ok(Box::new(
                     inner.core.network.routing_table.limit_attached_weak,
                 )),
+                "network.routing_table.node_info_max_age_ms" => Ok(Box::new(
+                    inner.core.network.routing_table.node_info_max_age_ms,
+                )),
+                "network.routing_table.verified_peers_only" => Ok(Box::new(
+                    inner.core.network.routing_table.verified_peers_only.clone(),
+                )),
                 "network.rpc.concurrency" => Ok(Box::new(inner.core.network.rpc.concurrency)),
                 "network.rpc.queue_size" => Ok(Box::new(inner.core.network.rpc.queue_size)),
                 "network.rpc.max_timestamp_behind_ms" => {
@@ -1212,6 +1416,12 @@ impl Settings {
                 "network.rpc.max_timestamp_ahead_ms" => {
                     Ok(Box::new(inner.core.network.rpc.max_timestamp_ahead_ms))
                 }
+                "network.rpc.max_clock_skew_ms" => {
+                    Ok(Box::new(inner.core.network.rpc.max_clock_skew_ms))
+                }
+                "network.rpc.clock_skew_warning_ms" => {
+                    Ok(Box::new(inner.core.network.rpc.clock_skew_warning_ms))
+                }
                 "network.rpc.timeout_ms" => Ok(Box::new(inner.core.network.rpc.timeout_ms)),
                 "network.rpc.max_route_hop_count" => {
                     Ok(Box::new(inner.core.network.rpc.max_route_hop_count))
@@ -1219,6 +1429,36 @@ impl Settings {
                 "network.rpc.default_route_hop_count" => {
                     Ok(Box::new(inner.core.network.rpc.default_route_hop_count))
                 }
+                "network.rpc.max_private_routes" => {
+                    Ok(Box::new(inner.core.network.rpc.max_private_routes))
+                }
+                "network.rpc.max_private_routes_per_api" => {
+                    Ok(Box::new(inner.core.network.rpc.max_private_routes_per_api))
+                }
+                "network.rpc.private_route_idle_release_ms" => Ok(Box::new(
+                    inner.core.network.rpc.private_route_idle_release_ms,
+                )),
+                "network.rpc.max_app_message_size" => {
+                    Ok(Box::new(inner.core.network.rpc.max_app_message_size))
+                }
+                "network.rpc.max_app_call_size" => {
+                    Ok(Box::new(inner.core.network.rpc.max_app_call_size))
+                }
+                "network.rpc.max_app_message_frequency_per_min" => Ok(Box::new(
+                    inner.core.network.rpc.max_app_message_frequency_per_min,
+                )),
+                "network.rpc.max_app_call_frequency_per_min" => Ok(Box::new(
+                    inner.core.network.rpc.max_app_call_frequency_per_min,
+                )),
+                "network.rpc.strict_udp_source" => {
+                    Ok(Box::new(inner.core.network.rpc.strict_udp_source))
+                }
+                "network.rpc.strict_udp_source_allow_rebind" => Ok(Box::new(
+                    inner.core.network.rpc.strict_udp_source_allow_rebind,
+                )),
+                "network.rpc.route_pow_difficulty" => {
+                    Ok(Box::new(inner.core.network.rpc.route_pow_difficulty))
+                }
                 "network.dht.max_find_node_count" => {
                     Ok(Box::new(inner.core.network.dht.max_find_node_count))
                 }
@@ -1249,6 +1489,9 @@ impl Settings {
                 "network.dht.set_value_fanout" => {
                     Ok(Box::new(inner.core.network.dht.set_value_fanout))
                 }
+                "network.dht.min_fanout_under_load" => {
+                    Ok(Box::new(inner.core.network.dht.min_fanout_under_load))
+                }
                 "network.dht.min_peer_count" => Ok(Box::new(inner.core.network.dht.min_peer_count)),
                 "network.dht.min_peer_refresh_time_ms" => {
                     Ok(Box::new(inner.core.network.dht.min_peer_refresh_time_ms))
@@ -1274,6 +1517,12 @@ impl Settings {
                 "network.dht.remote_max_storage_space_mb" => {
                     Ok(Box::new(inner.core.network.dht.remote_max_storage_space_mb))
                 }
+                "network.dht.remote_storage_high_watermark_percent" => Ok(Box::new(
+                    inner.core.network.dht.remote_storage_high_watermark_percent,
+                )),
+                "network.dht.remote_storage_low_watermark_percent" => Ok(Box::new(
+                    inner.core.network.dht.remote_storage_low_watermark_percent,
+                )),
                 "network.dht.public_watch_limit" => {
                     Ok(Box::new(inner.core.network.dht.public_watch_limit))
                 }
@@ -1283,13 +1532,26 @@ impl Settings {
                 "network.dht.max_watch_expiration_ms" => {
                     Ok(Box::new(inner.core.network.dht.max_watch_expiration_ms))
                 }
+                "network.dht.set_value_pow_difficulty" => {
+                    Ok(Box::new(inner.core.network.dht.set_value_pow_difficulty))
+                }
+                "network.dht.compress_values" => {
+                    Ok(Box::new(inner.core.network.dht.compress_values))
+                }
+                "network.dht.audit_log_max_entries" => {
+                    Ok(Box::new(inner.core.network.dht.audit_log_max_entries))
+                }
                 "network.upnp" => Ok(Box::new(inner.core.network.upnp)),
                 "network.detect_address_changes" => {
                     Ok(Box::new(inner.core.network.detect_address_changes))
                 }
+                "network.detect_address_changes_distinct_subnets" => Ok(Box::new(
+                    inner.core.network.detect_address_changes_distinct_subnets,
+                )),
                 "network.restricted_nat_retries" => {
                     Ok(Box::new(inner.core.network.restricted_nat_retries))
                 }
+                "network.max_envelope_size" => Ok(Box::new(inner.core.network.max_envelope_size)),
                 "network.tls.certificate_path" => {
                     Ok(Box::new(inner.core.network.tls.certificate_path.clone()))
                 }
@@ -1299,6 +1561,13 @@ impl Settings {
                 "network.tls.connection_initial_timeout_ms" => Ok(Box::new(
                     inner.core.network.tls.connection_initial_timeout_ms,
                 )),
+                "network.tor.enabled" => Ok(Box::new(inner.core.network.tor.enabled)),
+                "network.tor.socks_address" => {
+                    Ok(Box::new(inner.core.network.tor.socks_address.clone()))
+                }
+                "network.tor.onion_address" => {
+                    Ok(Box::new(inner.core.network.tor.onion_address.clone()))
+                }
                 "network.application.https.enabled" => {
                     Ok(Box::new(inner.core.network.application.https.enabled))
                 }
@@ -1332,6 +1601,16 @@ impl Settings {
                         .as_ref()
                         .map(|a| a.urlstring.clone()),
                 )),
+                "network.application.https.directory" => Ok(Box::new(
+                    inner
+                        .core
+                        .network
+                        .application
+                        .https
+                        .directory
+                        .as_ref()
+                        .map(|d| d.to_string_lossy().to_string()),
+                )),
                 "network.application.http.enabled" => {
                     Ok(Box::new(inner.core.network.application.http.enabled))
                 }
@@ -1365,6 +1644,16 @@ impl Settings {
                         .as_ref()
                         .map(|a| a.urlstring.clone()),
                 )),
+                "network.application.http.directory" => Ok(Box::new(
+                    inner
+                        .core
+                        .network
+                        .application
+                        .http
+                        .directory
+                        .as_ref()
+                        .map(|d| d.to_string_lossy().to_string()),
+                )),
                 "network.protocol.udp.enabled" => {
                     Ok(Box::new(inner.core.network.protocol.udp.enabled))
                 }
@@ -1384,6 +1673,9 @@ impl Settings {
                         .as_ref()
                         .map(|a| a.name.clone()),
                 )),
+                "network.protocol.udp.preference" => {
+                    Ok(Box::new(inner.core.network.protocol.udp.preference))
+                }
                 "network.protocol.tcp.connect" => {
                     Ok(Box::new(inner.core.network.protocol.tcp.connect))
                 }
@@ -1406,6 +1698,9 @@ impl Settings {
                         .as_ref()
                         .map(|a| a.name.clone()),
                 )),
+                "network.protocol.tcp.preference" => {
+                    Ok(Box::new(inner.core.network.protocol.tcp.preference))
+                }
                 "network.protocol.ws.connect" => {
                     Ok(Box::new(inner.core.network.protocol.ws.connect))
                 }
@@ -1436,6 +1731,9 @@ impl Settings {
                         .as_ref()
                         .map(|a| a.urlstring.clone()),
                 )),
+                "network.protocol.ws.preference" => {
+                    Ok(Box::new(inner.core.network.protocol.ws.preference))
+                }
                 "network.protocol.wss.connect" => {
                     Ok(Box::new(inner.core.network.protocol.wss.connect))
                 }
@@ -1468,6 +1766,30 @@ impl Settings {
                         .as_ref()
                         .map(|a| a.urlstring.clone()),
                 )),
+                "network.protocol.wss.preference" => {
+                    Ok(Box::new(inner.core.network.protocol.wss.preference))
+                }
+                "network.protocol.ipc.connect" => {
+                    Ok(Box::new(inner.core.network.protocol.ipc.connect))
+                }
+                "network.protocol.ipc.listen" => {
+                    Ok(Box::new(inner.core.network.protocol.ipc.listen))
+                }
+                "network.protocol.ipc.listen_path" => Ok(Box::new(
+                    inner.core.network.protocol.ipc.listen_path.clone(),
+                )),
+                "network.role" => Ok(Box::new(VeilidConfigRole::from_str(
+                    &inner.core.network.role,
+                )?)),
+                "network.metered_policy" => Ok(Box::new(VeilidConfigNetworkCostPolicy::from_str(
+                    &inner.core.network.metered_policy,
+                )?)),
+                "network.roaming_policy" => Ok(Box::new(VeilidConfigNetworkCostPolicy::from_str(
+                    &inner.core.network.roaming_policy,
+                )?)),
+                "network.low_power_wake_interval_ms" => {
+                    Ok(Box::new(inner.core.network.low_power_wake_interval_ms))
+                }
                 _ => Err(VeilidAPIError::generic(format!(
                     "config key '{}' doesn't exist",
                     key
@@ -1523,6 +1845,7 @@ mod tests {
         assert_eq!(s.logging.file.level, LogLevel::Info);
         assert!(s.logging.api.enabled);
         assert_eq!(s.logging.api.level, LogLevel::Info);
+        assert!(s.logging.api.component_levels.is_empty());
         assert!(!s.logging.otlp.enabled);
         assert_eq!(s.logging.otlp.level, LogLevel::Trace);
         assert_eq!(
@@ -1537,6 +1860,7 @@ mod tests {
             VeilidConfigTableStore::default().directory,
         );
         assert!(!s.core.table_store.delete);
+        assert_eq!(s.core.table_store.backend, "persistent");
 
         assert_eq!(
             s.core.block_store.directory,
@@ -1546,6 +1870,8 @@ mod tests {
 
         assert!(s.core.protected_store.allow_insecure_fallback);
         assert!(s.core.protected_store.always_use_insecure_storage);
+        assert!(!s.core.protected_store.hardware_node_id);
+        assert!(!s.core.protected_store.hardware_node_id_migrate);
         assert_eq!(
             s.core.protected_store.directory,
             VeilidConfigProtectedStore::default().directory
@@ -1566,6 +1892,9 @@ mod tests {
         assert_eq!(s.core.network.client_allowlist_timeout_ms, 300_000u32);
         assert_eq!(s.core.network.reverse_connection_receipt_time_ms, 5_000u32);
         assert_eq!(s.core.network.hole_punch_receipt_time_ms, 5_000u32);
+        assert_eq!(s.core.network.prewarm_grace_period_ms, 60_000u32);
+        assert_eq!(s.core.network.max_relay_client_bytes_per_sec, 1_048_576u32);
+        assert_eq!(s.core.network.max_relay_client_queued_bytes, 262_144u32);
         assert_eq!(s.core.network.network_key_password, None);
         assert_eq!(s.core.network.routing_table.node_id, None);
         assert_eq!(s.core.network.routing_table.node_id_secret, None);
@@ -1574,14 +1903,30 @@ mod tests {
             s.core.network.routing_table.bootstrap,
             vec!["bootstrap.veilid.net".to_owned()]
         );
+        assert!(s.core.network.routing_table.verified_peers_only.is_empty());
         //
         assert_eq!(s.core.network.rpc.concurrency, 0);
         assert_eq!(s.core.network.rpc.queue_size, 1024);
         assert_eq!(s.core.network.rpc.max_timestamp_behind_ms, Some(10_000u32));
         assert_eq!(s.core.network.rpc.max_timestamp_ahead_ms, Some(10_000u32));
+        assert_eq!(s.core.network.rpc.max_clock_skew_ms, 60_000u32);
+        assert_eq!(s.core.network.rpc.clock_skew_warning_ms, 5_000u32);
         assert_eq!(s.core.network.rpc.timeout_ms, 5_000u32);
         assert_eq!(s.core.network.rpc.max_route_hop_count, 4);
         assert_eq!(s.core.network.rpc.default_route_hop_count, 1);
+        assert_eq!(s.core.network.rpc.max_private_routes, 128u32);
+        assert_eq!(s.core.network.rpc.max_private_routes_per_api, 32u32);
+        assert_eq!(s.core.network.rpc.private_route_idle_release_ms, None);
+        assert_eq!(s.core.network.rpc.max_app_message_size, 32768u32);
+        assert_eq!(s.core.network.rpc.max_app_call_size, 32768u32);
+        assert_eq!(
+            s.core.network.rpc.max_app_message_frequency_per_min,
+            1024u32
+        );
+        assert_eq!(s.core.network.rpc.max_app_call_frequency_per_min, 256u32);
+        assert!(!s.core.network.rpc.strict_udp_source);
+        assert!(s.core.network.rpc.strict_udp_source_allow_rebind);
+        assert_eq!(s.core.network.rpc.route_pow_difficulty, 0u32);
         //
         assert_eq!(s.core.network.dht.max_find_node_count, 20u32);
         assert_eq!(s.core.network.dht.resolve_node_timeout_ms, 10_000u32);
@@ -1593,19 +1938,33 @@ mod tests {
         assert_eq!(s.core.network.dht.set_value_timeout_ms, 10_000u32);
         assert_eq!(s.core.network.dht.set_value_count, 5u32);
         assert_eq!(s.core.network.dht.set_value_fanout, 4u32);
+        assert_eq!(s.core.network.dht.min_fanout_under_load, 1u32);
         assert_eq!(s.core.network.dht.min_peer_count, 20u32);
         assert_eq!(s.core.network.dht.min_peer_refresh_time_ms, 60_000u32);
         assert_eq!(
             s.core.network.dht.validate_dial_info_receipt_time_ms,
             2_000u32
         );
+        assert_eq!(
+            s.core.network.dht.remote_storage_high_watermark_percent,
+            90u8
+        );
+        assert_eq!(
+            s.core.network.dht.remote_storage_low_watermark_percent,
+            75u8
+        );
         assert_eq!(s.core.network.dht.public_watch_limit, 32u32);
         assert_eq!(s.core.network.dht.member_watch_limit, 8u32);
         assert_eq!(s.core.network.dht.max_watch_expiration_ms, 600_000u32);
+        assert_eq!(s.core.network.dht.set_value_pow_difficulty, 0u32);
+        assert!(!s.core.network.dht.compress_values);
+        assert_eq!(s.core.network.dht.audit_log_max_entries, 0u32);
         //
         assert!(s.core.network.upnp);
         assert!(s.core.network.detect_address_changes);
+        assert_eq!(s.core.network.detect_address_changes_distinct_subnets, 3u32);
         assert_eq!(s.core.network.restricted_nat_retries, 0u32);
+        assert_eq!(s.core.network.max_envelope_size, 65507u32);
         //
         assert_eq!(
             s.core.network.tls.certificate_path,
@@ -1617,6 +1976,10 @@ mod tests {
         );
         assert_eq!(s.core.network.tls.connection_initial_timeout_ms, 2_000u32);
         //
+        assert!(!s.core.network.tor.enabled);
+        assert_eq!(s.core.network.tor.socks_address, "127.0.0.1:9050");
+        assert_eq!(s.core.network.tor.onion_address, None);
+        //
         assert!(!s.core.network.application.https.enabled);
         assert_eq!(s.core.network.application.https.listen_address.name, ":443");
         assert_eq!(
@@ -1628,6 +1991,7 @@ mod tests {
             std::path::PathBuf::from("app")
         );
         assert_eq!(s.core.network.application.https.url, None);
+        assert_eq!(s.core.network.application.https.directory, None);
         assert!(!s.core.network.application.http.enabled);
         assert_eq!(s.core.network.application.http.listen_address.name, ":80");
         assert_eq!(
@@ -1639,12 +2003,14 @@ mod tests {
             std::path::PathBuf::from("app")
         );
         assert_eq!(s.core.network.application.http.url, None);
+        assert_eq!(s.core.network.application.http.directory, None);
         //
         assert!(s.core.network.protocol.udp.enabled);
         assert_eq!(s.core.network.protocol.udp.socket_pool_size, 0);
         assert_eq!(s.core.network.protocol.udp.listen_address.name, "");
         assert_eq!(s.core.network.protocol.udp.listen_address.addrs, vec![]);
         assert_eq!(s.core.network.protocol.udp.public_address, None);
+        assert_eq!(s.core.network.protocol.udp.preference, 0);
 
         //
         assert!(s.core.network.protocol.tcp.connect);
@@ -1653,6 +2019,7 @@ mod tests {
         assert_eq!(s.core.network.protocol.tcp.listen_address.name, "");
         assert_eq!(s.core.network.protocol.tcp.listen_address.addrs, vec![]);
         assert_eq!(s.core.network.protocol.tcp.public_address, None);
+        assert_eq!(s.core.network.protocol.tcp.preference, 0);
 
         //
         assert!(s.core.network.protocol.ws.connect);
@@ -1665,6 +2032,7 @@ mod tests {
             std::path::PathBuf::from("ws")
         );
         assert_eq!(s.core.network.protocol.ws.url, None);
+        assert_eq!(s.core.network.protocol.ws.preference, 0);
         //
         assert!(s.core.network.protocol.wss.connect);
         assert!(!s.core.network.protocol.wss.listen);
@@ -1676,6 +2044,15 @@ mod tests {
             std::path::PathBuf::from("ws")
         );
         assert_eq!(s.core.network.protocol.wss.url, None);
+        assert_eq!(s.core.network.protocol.wss.preference, 0);
+        //
+        assert!(s.core.network.protocol.ipc.connect);
+        assert!(!s.core.network.protocol.ipc.listen);
+        assert_eq!(s.core.network.protocol.ipc.listen_path, "");
+        assert_eq!(s.core.network.role, "Full");
+        assert_eq!(s.core.network.metered_policy, "reduce_tick_rate");
+        assert_eq!(s.core.network.roaming_policy, "suspend");
+        assert_eq!(s.core.network.low_power_wake_interval_ms, 60_000u32);
         //
     }
 }
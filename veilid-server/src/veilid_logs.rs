@@ -63,6 +63,11 @@ impl VeilidLogs {
                 convert_loglevel(settingsr.logging.terminal.level),
                 &settingsr.logging.terminal.ignore_log_targets,
             );
+            filter
+                .set_component_levels(&convert_component_levels(
+                    &settingsr.logging.terminal.component_levels,
+                ))
+                .wrap_err("invalid terminal log component level")?;
             let layer = fmt::Layer::new()
                 .compact()
                 .with_writer(std::io::stdout)
@@ -113,6 +118,11 @@ impl VeilidLogs {
                 convert_loglevel(settingsr.logging.otlp.level),
                 &settingsr.logging.otlp.ignore_log_targets,
             );
+            filter
+                .set_component_levels(&convert_component_levels(
+                    &settingsr.logging.otlp.component_levels,
+                ))
+                .wrap_err("invalid otlp log component level")?;
             let layer = tracing_opentelemetry::layer()
                 .with_tracer(tracer)
                 .with_filter(filter.clone());
@@ -149,6 +159,11 @@ impl VeilidLogs {
                 convert_loglevel(settingsr.logging.file.level),
                 &settingsr.logging.file.ignore_log_targets,
             );
+            filter
+                .set_component_levels(&convert_component_levels(
+                    &settingsr.logging.file.component_levels,
+                ))
+                .wrap_err("invalid file log component level")?;
             let layer = fmt::Layer::new()
                 .compact()
                 .with_writer(non_blocking_appender)
@@ -164,7 +179,12 @@ impl VeilidLogs {
                 convert_loglevel(settingsr.logging.api.level),
                 &settingsr.logging.api.ignore_log_targets,
             );
-            let layer = veilid_core::ApiTracingLayer::get().with_filter(filter.clone());
+            filter
+                .set_component_levels(&convert_component_levels(
+                    &settingsr.logging.api.component_levels,
+                ))
+                .wrap_err("invalid api log component level")?;
+            let layer = veilid_core::ApiTracingLayer::new().with_filter(filter.clone());
             filters.insert("api", filter);
             layers.push(layer.boxed());
         }
@@ -177,6 +197,11 @@ impl VeilidLogs {
                         convert_loglevel(settingsr.logging.system.level),
                         &settingsr.logging.system.ignore_log_targets,
                     );
+                    filter
+                        .set_component_levels(&convert_component_levels(
+                            &settingsr.logging.system.component_levels,
+                        ))
+                        .wrap_err("invalid system log component level")?;
                     let layer = tracing_journald::layer().wrap_err("failed to set up journald logging")?
                         .with_filter(filter.clone());
                     filters.insert("system", filter);
@@ -230,6 +255,39 @@ impl VeilidLogs {
         Ok(())
     }
 
+    pub fn change_log_component_level(
+        &self,
+        layer: String,
+        component: String,
+        log_level: Option<veilid_core::VeilidConfigLogLevel>,
+    ) -> Result<(), veilid_core::VeilidAPIError> {
+        // get layer to change level on
+        let layer = if layer == "all" { "".to_owned() } else { layer };
+
+        // change component level on appropriate layer
+        let inner = self.inner.lock();
+        if layer.is_empty() {
+            // Change all layers
+            for f in inner.filters.values() {
+                f.set_component_level(&component, log_level)?;
+            }
+        } else {
+            // Change a specific layer
+            let f = match inner.filters.get(layer.as_str()) {
+                Some(f) => f,
+                None => {
+                    return Err(veilid_core::VeilidAPIError::InvalidArgument {
+                        context: "change_log_component_level".to_owned(),
+                        argument: "layer".to_owned(),
+                        value: layer,
+                    });
+                }
+            };
+            f.set_component_level(&component, log_level)?;
+        }
+        Ok(())
+    }
+
     pub fn change_log_ignore(
         &self,
         layer: String,
@@ -0,0 +1,69 @@
+//! Deterministic mode for reproducible debugging
+//!
+//! While deterministic mode is enabled, [get_timestamp] reads a virtual clock and [get_random_u32]
+//! and [get_random_u64] draw from a seeded generator, so the timing and non-security decisions of a
+//! process can be replayed exactly from a seed. Randomness used for keys, nonces and secrets,
+//! from [random_bytes] and [VeilidRng], is never affected.
+//!
+//! Deterministic mode makes operation ids and other identifiers predictable, and must never be
+//! used on a real network.
+use super::*;
+
+/// How far the virtual clock moves each time it is read, in microseconds
+///
+/// Keeps timestamps taken one after another strictly increasing, as they would be on a real clock.
+const VIRTUAL_CLOCK_READ_TICK_US: u64 = 1;
+
+struct DeterministicState {
+    rng_state: u64,
+    virtual_ts: u64,
+}
+
+static DETERMINISTIC_STATE: Mutex<Option<DeterministicState>> = Mutex::new(None);
+
+/// Enable deterministic mode for this process, replacing any previous seed and clock
+///
+/// `start_ts` is the virtual time in microseconds since the epoch that the clock starts at.
+pub fn enable_deterministic_mode(seed: u64, start_ts: u64) {
+    *DETERMINISTIC_STATE.lock() = Some(DeterministicState {
+        rng_state: seed,
+        virtual_ts: start_ts,
+    });
+}
+
+/// Go back to the real clock and random number generator
+pub fn disable_deterministic_mode() {
+    *DETERMINISTIC_STATE.lock() = None;
+}
+
+/// Check if deterministic mode is enabled
+pub fn is_deterministic_mode() -> bool {
+    DETERMINISTIC_STATE.lock().is_some()
+}
+
+/// Move the virtual clock forward, does nothing if deterministic mode is not enabled
+pub fn advance_virtual_time(us: u64) {
+    if let Some(state) = DETERMINISTIC_STATE.lock().as_mut() {
+        state.virtual_ts = state.virtual_ts.saturating_add(us);
+    }
+}
+
+pub(crate) fn deterministic_timestamp() -> Option<u64> {
+    let mut state = DETERMINISTIC_STATE.lock();
+    let state = state.as_mut()?;
+    let ts = state.virtual_ts;
+    state.virtual_ts = state.virtual_ts.saturating_add(VIRTUAL_CLOCK_READ_TICK_US);
+    Some(ts)
+}
+
+pub(crate) fn deterministic_random_u64() -> Option<u64> {
+    let mut state = DETERMINISTIC_STATE.lock();
+    let state = state.as_mut()?;
+
+    // splitmix64
+    state.rng_state = state.rng_state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = state.rng_state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    Some(z ^ (z >> 31))
+}
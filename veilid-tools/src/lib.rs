@@ -20,6 +20,10 @@
 //! * `rt-async-std` - Uses `async-std` as the async runtime
 //! * `rt-wasm-bindgen` - When building for the `wasm32` architecture, use this to enable `wasm-bindgen-futures` as the async runtime
 //!
+//! For debugging, these may be added:
+//!
+//! * `deterministic` - Allows replacing the clock and non-security randomness with a virtual clock and seeded generator
+//!
 #![deny(clippy::all)]
 #![allow(clippy::comparison_chain, clippy::upper_case_acronyms)]
 #![deny(unused_must_use)]
@@ -29,6 +33,8 @@ pub mod assembly_buffer;
 pub mod async_peek_stream;
 pub mod async_tag_lock;
 pub mod clone_stream;
+#[cfg(feature = "deterministic")]
+pub mod deterministic;
 pub mod eventual;
 pub mod eventual_base;
 pub mod eventual_value;
@@ -162,6 +168,9 @@ pub use async_tag_lock::*;
 #[doc(inline)]
 pub use clone_stream::*;
 #[doc(inline)]
+#[cfg(feature = "deterministic")]
+pub use deterministic::*;
+#[doc(inline)]
 pub use eventual::*;
 #[doc(inline)]
 pub use eventual_base::{EventualCommon, EventualResolvedFuture};
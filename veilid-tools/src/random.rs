@@ -7,11 +7,13 @@ impl CryptoRng for VeilidRng {}
 
 impl RngCore for VeilidRng {
     fn next_u32(&mut self) -> u32 {
-        get_random_u32()
+        let mut rng = rand::thread_rng();
+        rng.next_u32()
     }
 
     fn next_u64(&mut self) -> u64 {
-        get_random_u64()
+        let mut rng = rand::thread_rng();
+        rng.next_u64()
     }
 
     fn fill_bytes(&mut self, dest: &mut [u8]) {
@@ -29,12 +31,22 @@ pub fn random_bytes(dest: &mut [u8]) {
     rng.fill_bytes(dest);
 }
 
+/// Random number for non-security decisions, seeded in deterministic mode
 pub fn get_random_u32() -> u32 {
+    #[cfg(feature = "deterministic")]
+    if let Some(r) = crate::deterministic::deterministic_random_u64() {
+        return r as u32;
+    }
     let mut rng = rand::thread_rng();
     rng.next_u32()
 }
 
+/// Random number for non-security decisions, seeded in deterministic mode
 pub fn get_random_u64() -> u64 {
+    #[cfg(feature = "deterministic")]
+    if let Some(r) = crate::deterministic::deterministic_random_u64() {
+        return r;
+    }
     let mut rng = rand::thread_rng();
     rng.next_u64()
 }
@@ -1,5 +1,16 @@
 use super::*;
 
+/// Carry the span a task is spawned from into the task, so its logs keep that context
+#[cfg(feature = "tracing")]
+fn in_current_span<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    tracing::Instrument::in_current_span(future)
+}
+
+#[cfg(not(feature = "tracing"))]
+fn in_current_span<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    future
+}
+
 cfg_if! {
     if #[cfg(target_arch = "wasm32")] {
         use async_executors::{Bindgen, LocalSpawnHandleExt, SpawnHandleExt};
@@ -8,6 +19,7 @@ cfg_if! {
         where
             Out: Send + 'static,
         {
+            let future = in_current_span(future);
             MustJoinHandle::new(
                 Bindgen
                     .spawn_handle(future)
@@ -19,6 +31,7 @@ cfg_if! {
         where
             Out: 'static,
         {
+            let future = in_current_span(future);
             MustJoinHandle::new(
                 Bindgen
                     .spawn_handle_local(future)
@@ -30,6 +43,7 @@ cfg_if! {
         where
             Out: Send + 'static,
         {
+            let future = in_current_span(future);
             Bindgen
                 .spawn_handle_local(future)
                 .expect("wasm-bindgen-futures spawn_handle_local should never error out")
@@ -39,6 +53,7 @@ cfg_if! {
         where
             Out: 'static,
         {
+            let future = in_current_span(future);
             Bindgen
                 .spawn_handle_local(future)
                 .expect("wasm-bindgen-futures spawn_handle_local should never error out")
@@ -51,6 +66,7 @@ cfg_if! {
         where
             Out: Send + 'static,
         {
+            let future = in_current_span(future);
             cfg_if! {
                 if #[cfg(feature="rt-async-std")] {
                     MustJoinHandle::new(async_std::task::spawn(future))
@@ -64,6 +80,7 @@ cfg_if! {
         where
             Out: 'static,
         {
+            let future = in_current_span(future);
             cfg_if! {
                 if #[cfg(feature="rt-async-std")] {
                     MustJoinHandle::new(async_std::task::spawn_local(future))
@@ -77,6 +94,7 @@ cfg_if! {
         where
             Out: Send + 'static,
         {
+            let future = in_current_span(future);
             cfg_if! {
                 if #[cfg(feature="rt-async-std")] {
                     drop(async_std::task::spawn(future));
@@ -90,6 +108,7 @@ cfg_if! {
         where
             Out: 'static,
         {
+            let future = in_current_span(future);
             cfg_if! {
                 if #[cfg(feature="rt-async-std")] {
                     drop(async_std::task::spawn_local(future));
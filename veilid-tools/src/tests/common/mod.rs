@@ -1,4 +1,6 @@
 pub mod test_async_tag_lock;
+#[cfg(feature = "deterministic")]
+pub mod test_deterministic;
 pub mod test_host_interface;
 
 #[allow(dead_code)]
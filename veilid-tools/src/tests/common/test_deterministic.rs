@@ -0,0 +1,62 @@
+use crate::*;
+
+pub async fn test_seeded_randomness() {
+    info!("test_seeded_randomness");
+
+    enable_deterministic_mode(42, 1_000_000);
+    let first: Vec<u64> = (0..8).map(|_| get_random_u64()).collect();
+    let first_u32 = get_random_u32();
+
+    // The same seed gives the same sequence
+    enable_deterministic_mode(42, 1_000_000);
+    let second: Vec<u64> = (0..8).map(|_| get_random_u64()).collect();
+    assert_eq!(first, second);
+    assert_eq!(first_u32, get_random_u32());
+
+    // A different seed does not
+    enable_deterministic_mode(43, 1_000_000);
+    let third: Vec<u64> = (0..8).map(|_| get_random_u64()).collect();
+    assert_ne!(first, third);
+
+    // Security randomness is never seeded
+    let mut a = [0u8; 32];
+    let mut b = [0u8; 32];
+    enable_deterministic_mode(42, 1_000_000);
+    random_bytes(&mut a);
+    enable_deterministic_mode(42, 1_000_000);
+    random_bytes(&mut b);
+    assert_ne!(a, b);
+
+    disable_deterministic_mode();
+}
+
+pub async fn test_virtual_clock() {
+    info!("test_virtual_clock");
+
+    enable_deterministic_mode(1, 1_000_000);
+    assert!(is_deterministic_mode());
+
+    // Each read moves the clock forward a little
+    let t1 = get_timestamp();
+    let t2 = get_timestamp();
+    assert_eq!(t1, 1_000_000);
+    assert!(t2 > t1);
+
+    // Sleeping does not move the clock, only advancing it does
+    sleep(10).await;
+    let t3 = get_timestamp();
+    assert!(t3 - t2 < 1_000);
+    advance_virtual_time(5_000_000);
+    let t4 = get_timestamp();
+    assert!(t4 - t3 >= 5_000_000);
+
+    // The real clock is back when deterministic mode is off
+    disable_deterministic_mode();
+    assert!(!is_deterministic_mode());
+    assert!(get_timestamp() > 1_600_000_000_000_000);
+}
+
+pub async fn test_all() {
+    test_seeded_randomness().await;
+    test_virtual_clock().await;
+}
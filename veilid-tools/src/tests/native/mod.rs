@@ -22,6 +22,11 @@ pub async fn run_all_tests() {
     test_async_tag_lock::test_all().await;
     info!("TEST: exec_test_assembly_buffer");
     test_assembly_buffer::test_all().await;
+    #[cfg(feature = "deterministic")]
+    {
+        info!("TEST: exec_test_deterministic");
+        test_deterministic::test_all().await;
+    }
 
     info!("Finished unit tests");
 }
@@ -122,5 +127,15 @@ cfg_if! {
                 test_assembly_buffer::test_all().await;
             });
         }
+
+        #[cfg(feature = "deterministic")]
+        #[test]
+        #[serial]
+        fn run_test_deterministic() {
+            setup();
+            block_on(async {
+                test_deterministic::test_all().await;
+            });
+        }
     }
 }
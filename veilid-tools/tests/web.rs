@@ -44,3 +44,11 @@ async fn run_test_async_tag_lock() {
 
     test_async_tag_lock::test_all().await;
 }
+
+#[cfg(feature = "deterministic")]
+#[wasm_bindgen_test]
+async fn run_test_deterministic() {
+    setup();
+
+    test_deterministic::test_all().await;
+}
@@ -186,6 +186,20 @@ pub struct VeilidRouteBlob {
 }
 from_impl_to_jsvalue!(VeilidRouteBlob);
 
+#[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(from_wasm_abi, into_wasm_abi)
+)]
+pub struct VeilidRendezvousInviteBlob {
+    pub invite: veilid_core::RendezvousInvite,
+    #[serde(with = "veilid_core::as_human_base64")]
+    #[cfg_attr(target_arch = "wasm32", tsify(type = "string"))]
+    pub blob: Vec<u8>,
+}
+from_impl_to_jsvalue!(VeilidRendezvousInviteBlob);
+
 // WASM Bindings
 
 #[wasm_bindgen()]
@@ -234,7 +248,7 @@ pub fn initialize_veilid_core(platform_config: String) {
             platform_config.logging.api.level,
             &platform_config.logging.api.ignore_log_targets,
         );
-        let layer = veilid_core::ApiTracingLayer::get().with_filter(filter.clone());
+        let layer = veilid_core::ApiTracingLayer::new().with_filter(filter.clone());
         filters.insert("api", filter);
         layers.push(layer.boxed());
     }
@@ -263,6 +277,27 @@ pub fn change_log_level(layer: String, log_level: String) {
     }
 }
 
+#[wasm_bindgen()]
+pub fn change_log_component_level(layer: String, component: String, log_level: String) {
+    let layer = if layer == "all" { "".to_owned() } else { layer };
+    let log_level: Option<veilid_core::VeilidConfigLogLevel> = if log_level == "default" {
+        None
+    } else {
+        Some(deserialize_json(&log_level).unwrap())
+    };
+    let filters = (*FILTERS).borrow();
+    if layer.is_empty() {
+        // Change all layers
+        for f in filters.values() {
+            f.set_component_level(&component, log_level).unwrap();
+        }
+    } else {
+        // Change a specific layer
+        let f = filters.get(layer.as_str()).unwrap();
+        f.set_component_level(&component, log_level).unwrap();
+    }
+}
+
 #[wasm_bindgen()]
 pub fn change_log_ignore(layer: String, log_ignore: String) {
     let layer = if layer == "all" { "".to_owned() } else { layer };
@@ -320,6 +355,24 @@ pub fn get_veilid_state() -> Promise {
     })
 }
 
+#[wasm_bindgen()]
+pub fn get_storage_usage() -> Promise {
+    wrap_api_future_json(async move {
+        let veilid_api = get_veilid_api()?;
+        let storage_usage = veilid_api.get_storage_usage().await?;
+        APIResult::Ok(storage_usage)
+    })
+}
+
+#[wasm_bindgen()]
+pub fn get_storage_status() -> Promise {
+    wrap_api_future_json(async move {
+        let veilid_api = get_veilid_api()?;
+        let storage_status = veilid_api.get_storage_status().await?;
+        APIResult::Ok(storage_status)
+    })
+}
+
 #[wasm_bindgen()]
 pub fn attach() -> Promise {
     wrap_api_future_void(async move {
@@ -338,6 +391,53 @@ pub fn detach() -> Promise {
     })
 }
 
+#[wasm_bindgen()]
+pub fn set_keepalive_port(port: Option<web_sys::MessagePort>) -> Promise {
+    wrap_api_future_void(async move {
+        let veilid_api = get_veilid_api()?;
+        veilid_api.set_keepalive_port(port)?;
+        APIRESULT_UNDEFINED
+    })
+}
+
+#[wasm_bindgen()]
+pub fn keepalive_tick() -> Promise {
+    wrap_api_future_void(async move {
+        let veilid_api = get_veilid_api()?;
+        veilid_api.keepalive_tick()?;
+        APIRESULT_UNDEFINED
+    })
+}
+
+#[wasm_bindgen()]
+pub fn set_network_cost(network_cost: String) -> Promise {
+    let network_cost: veilid_core::NetworkCost =
+        veilid_core::deserialize_json(&network_cost).unwrap();
+    wrap_api_future_void(async move {
+        let veilid_api = get_veilid_api()?;
+        veilid_api.set_network_cost(network_cost).await?;
+        APIRESULT_UNDEFINED
+    })
+}
+
+#[wasm_bindgen()]
+pub fn set_low_power(low_power: bool) -> Promise {
+    wrap_api_future_void(async move {
+        let veilid_api = get_veilid_api()?;
+        veilid_api.set_low_power(low_power)?;
+        APIRESULT_UNDEFINED
+    })
+}
+
+#[wasm_bindgen()]
+pub fn trigger_public_dial_info_detection() -> Promise {
+    wrap_api_future_void(async move {
+        let veilid_api = get_veilid_api()?;
+        veilid_api.trigger_public_dial_info_detection()?;
+        APIRESULT_UNDEFINED
+    })
+}
+
 #[wasm_bindgen()]
 pub fn shutdown_veilid_core() -> Promise {
     wrap_api_future_void(async move {
@@ -409,6 +509,23 @@ pub fn routing_context_with_safety(id: u32, safety_selection: String) -> u32 {
     add_routing_context(routing_context)
 }
 
+#[wasm_bindgen()]
+pub fn routing_context_with_safety_profile(id: u32, profile: String) -> u32 {
+    let profile: veilid_core::SafetyProfile = veilid_core::deserialize_json(&profile).unwrap();
+
+    let routing_context = {
+        let rc = (*ROUTING_CONTEXTS).borrow();
+        let Some(routing_context) = rc.get(&id) else {
+            return 0;
+        };
+        routing_context.clone()
+    };
+    let Ok(routing_context) = routing_context.with_safety_profile(profile) else {
+        return 0;
+    };
+    add_routing_context(routing_context)
+}
+
 #[wasm_bindgen()]
 pub fn routing_context_with_sequencing(id: u32, sequencing: String) -> u32 {
     let sequencing: veilid_core::Sequencing = veilid_core::deserialize_json(&sequencing).unwrap();
@@ -424,6 +541,19 @@ pub fn routing_context_with_sequencing(id: u32, sequencing: String) -> u32 {
     add_routing_context(routing_context)
 }
 
+#[wasm_bindgen()]
+pub fn routing_context_with_timeout(id: u32, timeout_ms: Option<u32>) -> u32 {
+    let routing_context = {
+        let rc = (*ROUTING_CONTEXTS).borrow();
+        let Some(routing_context) = rc.get(&id) else {
+            return 0;
+        };
+        routing_context.clone()
+    };
+    let routing_context = routing_context.with_timeout(timeout_ms);
+    add_routing_context(routing_context)
+}
+
 fn get_routing_context(id: u32, func_name: &str) -> APIResult<veilid_core::RoutingContext> {
     let rc = (*ROUTING_CONTEXTS).borrow();
     let Some(routing_context) = rc.get(&id) else {
@@ -444,6 +574,17 @@ pub fn routing_context_safety(id: u32) -> Promise {
     })
 }
 
+#[wasm_bindgen()]
+pub fn routing_context_test_private_route(id: u32, route_id: String) -> Promise {
+    let route_id: veilid_core::RouteId = veilid_core::RouteId::try_decode(&route_id).unwrap();
+    wrap_api_future_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_test_private_route")?;
+
+        let res = routing_context.test_private_route(route_id).await?;
+        APIResult::Ok(res)
+    })
+}
+
 #[wasm_bindgen()]
 pub fn routing_context_app_call(id: u32, target_string: String, request: String) -> Promise {
     let request: Vec<u8> = data_encoding::BASE64URL_NOPAD
@@ -475,6 +616,39 @@ pub fn routing_context_app_message(id: u32, target_string: String, message: Stri
     })
 }
 
+#[wasm_bindgen()]
+pub fn routing_context_prewarm(id: u32, target_string: String) -> Promise {
+    wrap_api_future_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_prewarm")?;
+
+        let veilid_api = get_veilid_api()?;
+        let target = veilid_api.parse_as_target(target_string)?;
+        let contact_method = routing_context.prewarm(target).await?;
+        APIResult::Ok(contact_method)
+    })
+}
+
+#[wasm_bindgen()]
+pub fn routing_context_app_message_closest(
+    id: u32,
+    key: String,
+    count: u32,
+    message: String,
+) -> Promise {
+    let key: veilid_core::TypedKey = veilid_core::deserialize_json(&key).unwrap();
+    let message: Vec<u8> = data_encoding::BASE64URL_NOPAD
+        .decode(message.as_bytes())
+        .unwrap();
+    wrap_api_future_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_app_message_closest")?;
+
+        let delivered = routing_context
+            .app_message_closest(key, count, message)
+            .await?;
+        APIResult::Ok(delivered)
+    })
+}
+
 #[wasm_bindgen()]
 pub fn routing_context_create_dht_record(id: u32, schema: String, kind: u32) -> Promise {
     let crypto_kind = if kind == 0 {
@@ -494,6 +668,52 @@ pub fn routing_context_create_dht_record(id: u32, schema: String, kind: u32) ->
     })
 }
 
+#[wasm_bindgen()]
+pub fn routing_context_create_expiring_dht_record(
+    id: u32,
+    schema: String,
+    expiration: String,
+    kind: u32,
+) -> Promise {
+    let crypto_kind = if kind == 0 {
+        None
+    } else {
+        Some(veilid_core::FourCC::from(kind))
+    };
+    let schema: veilid_core::DHTSchema = veilid_core::deserialize_json(&schema).unwrap();
+    let expiration = veilid_core::Timestamp::from_str(&expiration).unwrap();
+
+    wrap_api_future_json(async move {
+        let routing_context =
+            get_routing_context(id, "routing_context_create_expiring_dht_record")?;
+
+        let dht_record_descriptor = routing_context
+            .create_expiring_dht_record(schema, expiration, crypto_kind)
+            .await?;
+        APIResult::Ok(dht_record_descriptor)
+    })
+}
+
+#[wasm_bindgen()]
+pub fn routing_context_extend_dht_record_expiration(
+    id: u32,
+    key: String,
+    expiration: Option<String>,
+) -> Promise {
+    let key: veilid_core::TypedKey = veilid_core::deserialize_json(&key).unwrap();
+    let expiration: Option<veilid_core::Timestamp> =
+        expiration.map(|s| veilid_core::Timestamp::from_str(&s).unwrap());
+    wrap_api_future_void(async move {
+        let routing_context =
+            get_routing_context(id, "routing_context_extend_dht_record_expiration")?;
+
+        routing_context
+            .extend_dht_record_expiration(key, expiration)
+            .await?;
+        APIRESULT_UNDEFINED
+    })
+}
+
 #[wasm_bindgen()]
 pub fn routing_context_open_dht_record(id: u32, key: String, writer: Option<String>) -> Promise {
     let key: veilid_core::TypedKey = veilid_core::deserialize_json(&key).unwrap();
@@ -632,6 +852,135 @@ pub fn routing_context_inspect_dht_record(
     })
 }
 
+#[wasm_bindgen()]
+pub fn routing_context_sync_dht_record(id: u32, key: String) -> Promise {
+    let key: veilid_core::TypedKey = veilid_core::deserialize_json(&key).unwrap();
+    wrap_api_future_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_sync_dht_record")?;
+
+        let res = routing_context.sync_dht_record(key).await?;
+        APIResult::Ok(res)
+    })
+}
+
+#[wasm_bindgen()]
+pub fn routing_context_publish_private_route(
+    id: u32,
+    key: String,
+    subkey: u32,
+    rotation: Option<String>,
+) -> Promise {
+    let key: veilid_core::TypedKey = veilid_core::deserialize_json(&key).unwrap();
+    let rotation: Option<veilid_core::PrivateRouteRotation> =
+        rotation.map(|s| veilid_core::deserialize_json(&s).unwrap());
+
+    wrap_api_future_plain(async move {
+        let routing_context = get_routing_context(id, "routing_context_publish_private_route")?;
+
+        let route_id = routing_context
+            .publish_private_route(key, subkey, rotation)
+            .await?;
+
+        APIResult::Ok(route_id.encode())
+    })
+}
+
+#[wasm_bindgen()]
+pub fn routing_context_resolve_private_route(
+    id: u32,
+    key: String,
+    subkey: u32,
+    force_refresh: bool,
+) -> Promise {
+    let key: veilid_core::TypedKey = veilid_core::deserialize_json(&key).unwrap();
+
+    wrap_api_future_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_resolve_private_route")?;
+
+        let res = routing_context
+            .resolve_private_route(key, subkey, force_refresh)
+            .await?;
+
+        APIResult::Ok(res)
+    })
+}
+
+#[wasm_bindgen()]
+pub fn routing_context_create_invite(id: u32) -> Promise {
+    wrap_api_future_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_create_invite")?;
+
+        let (invite, blob) = routing_context.create_invite().await?;
+        let invite_blob = VeilidRendezvousInviteBlob { invite, blob };
+        APIResult::Ok(invite_blob)
+    })
+}
+
+#[wasm_bindgen()]
+pub fn routing_context_accept_invite(id: u32, invite_blob: String) -> Promise {
+    let invite_blob: Vec<u8> = data_encoding::BASE64URL_NOPAD
+        .decode(invite_blob.as_bytes())
+        .unwrap();
+    wrap_api_future_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_accept_invite")?;
+
+        let session = routing_context.accept_invite(invite_blob).await?;
+        APIResult::Ok(session)
+    })
+}
+
+#[wasm_bindgen()]
+pub fn routing_context_answer_invite(id: u32, invite: String, app_call: String) -> Promise {
+    let invite: veilid_core::RendezvousInvite = veilid_core::deserialize_json(&invite).unwrap();
+    let app_call: veilid_core::VeilidAppCall = veilid_core::deserialize_json(&app_call).unwrap();
+    wrap_api_future_json(async move {
+        let routing_context = get_routing_context(id, "routing_context_answer_invite")?;
+
+        let opt_session = routing_context.answer_invite(&invite, &app_call).await?;
+        APIResult::Ok(opt_session)
+    })
+}
+
+#[wasm_bindgen()]
+pub fn routing_context_send_session_message(id: u32, session: String, message: String) -> Promise {
+    let session: veilid_core::RendezvousSession = veilid_core::deserialize_json(&session).unwrap();
+    let message: Vec<u8> = data_encoding::BASE64URL_NOPAD
+        .decode(message.as_bytes())
+        .unwrap();
+    wrap_api_future_void(async move {
+        let routing_context = get_routing_context(id, "routing_context_send_session_message")?;
+
+        session.send(&routing_context, &message).await?;
+        APIRESULT_UNDEFINED
+    })
+}
+
+#[wasm_bindgen()]
+pub fn routing_context_open_session_message(id: u32, session: String, message: String) -> Promise {
+    let session: veilid_core::RendezvousSession = veilid_core::deserialize_json(&session).unwrap();
+    let message: Vec<u8> = data_encoding::BASE64URL_NOPAD
+        .decode(message.as_bytes())
+        .unwrap();
+    wrap_api_future_plain(async move {
+        let routing_context = get_routing_context(id, "routing_context_open_session_message")?;
+
+        let opened = session.open(&routing_context.api(), &message)?;
+        let opened = data_encoding::BASE64URL_NOPAD.encode(&opened);
+        APIResult::Ok(opened)
+    })
+}
+
+#[wasm_bindgen()]
+pub fn routing_context_close_session(id: u32, session: String) -> Promise {
+    let session: veilid_core::RendezvousSession = veilid_core::deserialize_json(&session).unwrap();
+    wrap_api_future_void(async move {
+        let routing_context = get_routing_context(id, "routing_context_close_session")?;
+
+        session.close(&routing_context.api())?;
+        APIRESULT_UNDEFINED
+    })
+}
+
 #[wasm_bindgen()]
 pub fn new_private_route() -> Promise {
     wrap_api_future_json(async move {
@@ -707,6 +1056,31 @@ pub fn app_call_reply(call_id: String, message: String) -> Promise {
     })
 }
 
+#[wasm_bindgen()]
+pub fn make_receipt(expiration: String, extra_data: String) -> Promise {
+    let expiration = veilid_core::TimestampDuration::from_str(&expiration).unwrap();
+    let extra_data: Vec<u8> = data_encoding::BASE64URL_NOPAD
+        .decode(extra_data.as_bytes())
+        .unwrap();
+    wrap_api_future_plain(async move {
+        let veilid_api = get_veilid_api()?;
+        let receipt = veilid_api.make_receipt(expiration, extra_data)?;
+        APIResult::Ok(data_encoding::BASE64URL_NOPAD.encode(&receipt))
+    })
+}
+
+#[wasm_bindgen()]
+pub fn verify_receipt(receipt: String) -> Promise {
+    let receipt: Vec<u8> = data_encoding::BASE64URL_NOPAD
+        .decode(receipt.as_bytes())
+        .unwrap();
+    wrap_api_future_json(async move {
+        let veilid_api = get_veilid_api()?;
+        let out = veilid_api.verify_receipt(receipt).await?;
+        APIResult::Ok(out)
+    })
+}
+
 fn add_table_db(table_db: veilid_core::TableDB) -> u32 {
     let mut next_id: u32 = 1;
     let mut tdbs = (*TABLE_DBS).borrow_mut();
@@ -1520,6 +1894,15 @@ pub fn now() -> String {
     veilid_core::get_aligned_timestamp().as_u64().to_string()
 }
 
+#[wasm_bindgen()]
+pub fn get_network_time() -> Promise {
+    wrap_api_future_json(async move {
+        let veilid_api = get_veilid_api()?;
+        let network_time = veilid_api.get_network_time()?;
+        APIResult::Ok(network_time)
+    })
+}
+
 #[wasm_bindgen()]
 pub fn debug(command: String) -> Promise {
     wrap_api_future_plain(async move {
@@ -1558,3 +1941,8 @@ pub fn veilid_version() -> JsValue {
 pub fn default_veilid_config() -> String {
     veilid_core::default_veilid_config()
 }
+
+#[wasm_bindgen()]
+pub fn veilid_config_schema() -> String {
+    veilid_core::serialize_json(veilid_core::veilid_config_schema())
+}
@@ -10,7 +10,7 @@ export type CryptoKey = string;
 export type Nonce = string;
 export type Signature = string;
 export type KeyPair = `${PublicKey}:${SecretKey}`;
-export type FourCC = "NONE" | "VLD0" | string;
+export type FourCC = "NONE" | "VLD0" | "VLD1" | string;
 export type CryptoTyped<TCryptoKey extends string> = `${FourCC}:${TCryptoKey}`;
 export type CryptoTypedGroup<TCryptoKey extends string> = Array<CryptoTyped<TCryptoKey>>;
 "#;
@@ -67,7 +67,7 @@ impl VeilidClient {
                 platformConfig.logging.api.level,
                 &platformConfig.logging.api.ignore_log_targets,
             );
-            let layer = veilid_core::ApiTracingLayer::get().with_filter(filter.clone());
+            let layer = veilid_core::ApiTracingLayer::new().with_filter(filter.clone());
             filters.insert("api", filter);
             layers.push(layer.boxed());
         }
@@ -129,6 +129,29 @@ impl VeilidClient {
         }
     }
 
+    /// Override the log level of one component (rtab, net, rpc, stor) on a layer.
+    /// Pass no level to have the component follow the layer's level again.
+    // TODO: can we refine the TS type of `layer`?
+    pub fn changeLogComponentLevel(
+        layer: String,
+        component: String,
+        log_level: Option<VeilidConfigLogLevel>,
+    ) -> APIResult<()> {
+        let layer = if layer == "all" { "".to_owned() } else { layer };
+        let filters = (*FILTERS).borrow();
+        if layer.is_empty() {
+            // Change all layers
+            for f in filters.values() {
+                f.set_component_level(&component, log_level)?;
+            }
+        } else {
+            // Change a specific layer
+            let f = filters.get(layer.as_str()).unwrap();
+            f.set_component_level(&component, log_level)?;
+        }
+        APIRESULT_UNDEFINED
+    }
+
     // TODO: can we refine the TS type of `layer`?
     pub fn changeLogIgnore(layer: String, changes: Vec<String>) {
         let layer = if layer == "all" { "".to_owned() } else { layer };
@@ -162,6 +185,27 @@ impl VeilidClient {
         APIResult::Ok(core_state)
     }
 
+    /// Get how much storage space the DHT record stores are using.
+    pub async fn getStorageUsage() -> APIResult<DHTStorageUsage> {
+        let veilid_api = get_veilid_api()?;
+        let storage_usage = veilid_api.get_storage_usage().await?;
+        APIResult::Ok(storage_usage)
+    }
+
+    /// Get the pending offline writes, open records and storage space used by the DHT storage manager.
+    pub async fn getStorageStatus() -> APIResult<StorageManagerStatus> {
+        let veilid_api = get_veilid_api()?;
+        let storage_status = veilid_api.get_storage_status().await?;
+        APIResult::Ok(storage_status)
+    }
+
+    /// Get the current time as estimated from the clocks of our peers, for timestamping data consistently.
+    pub fn getNetworkTime() -> APIResult<NetworkTime> {
+        let veilid_api = get_veilid_api()?;
+        let network_time = veilid_api.get_network_time()?;
+        APIResult::Ok(network_time)
+    }
+
     /// Connect to the network.
     pub async fn attach() -> APIResult<()> {
         let veilid_api = get_veilid_api()?;
@@ -176,6 +220,64 @@ impl VeilidClient {
         APIRESULT_UNDEFINED
     }
 
+    /// Delegate keepalive ticks to a SharedWorker or Service Worker so connections survive tab backgrounding.
+    ///
+    /// The worker receives `{type: "start", interval_ms}` and `{type: "stop"}` messages on the port,
+    /// and should post `{type: "tick"}` every `interval_ms` while started. Pass `undefined` to stop using it.
+    pub fn setKeepalivePort(port: Option<web_sys::MessagePort>) -> APIResult<()> {
+        let veilid_api = get_veilid_api()?;
+        veilid_api.set_keepalive_port(port)?;
+        APIRESULT_UNDEFINED
+    }
+
+    /// Tick now instead of waiting for the next tick interval, running the relay keepalive right away.
+    pub fn keepaliveTick() -> APIResult<()> {
+        let veilid_api = get_veilid_api()?;
+        veilid_api.keepalive_tick()?;
+        APIRESULT_UNDEFINED
+    }
+
+    /// Report whether the network in use is metered or roaming, so the configured network cost policies apply.
+    pub async fn setNetworkCost(networkCost: NetworkCost) -> APIResult<()> {
+        let veilid_api = get_veilid_api()?;
+        veilid_api.set_network_cost(networkCost).await?;
+        APIRESULT_UNDEFINED
+    }
+
+    /// Enter low power mode when the app goes into the background, and leave it when the app comes back.
+    pub fn setLowPower(lowPower: bool) -> APIResult<()> {
+        let veilid_api = get_veilid_api()?;
+        veilid_api.set_low_power(lowPower)?;
+        APIRESULT_UNDEFINED
+    }
+
+    /// Detect our public dial info and network class again, such as after changing router or firewall settings.
+    pub fn triggerPublicDialInfoDetection() -> APIResult<()> {
+        let veilid_api = get_veilid_api()?;
+        veilid_api.trigger_public_dial_info_detection()?;
+        APIRESULT_UNDEFINED
+    }
+
+    /// Make a signed receipt that can be handed to someone else and returned to this node later.
+    ///
+    /// * `expiration` - how long the receipt can be returned for, in microseconds
+    /// * `extraData` - arbitrary data carried with the receipt, up to 1250 bytes. It is signed but not encrypted.
+    pub fn makeReceipt(expiration: String, extraData: Box<[u8]>) -> APIResult<Box<[u8]>> {
+        let expiration = veilid_core::TimestampDuration::from_str(&expiration)
+            .map_err(VeilidAPIError::generic)?;
+        let veilid_api = get_veilid_api()?;
+        let receipt = veilid_api.make_receipt(expiration, extraData.into_vec())?;
+        APIResult::Ok(receipt.into_boxed_slice())
+    }
+
+    /// Verify a receipt made by `makeReceipt` that was returned to this node.
+    /// Each receipt can only be verified once, and only before it expires.
+    pub async fn verifyReceipt(receipt: Box<[u8]>) -> APIResult<VeilidReceipt> {
+        let veilid_api = get_veilid_api()?;
+        let out = veilid_api.verify_receipt(receipt.into_vec()).await?;
+        APIResult::Ok(out)
+    }
+
     /// Get the current timestamp, in string format
     pub fn now() -> String {
         veilid_core::get_aligned_timestamp().as_u64().to_string()
@@ -207,4 +309,9 @@ impl VeilidClient {
     pub fn defaultConfig() -> String {
         veilid_core::default_veilid_config()
     }
+
+    /// Return a description of every configuration field, with its type, default and constraints, in string format
+    pub fn configSchema() -> String {
+        veilid_core::serialize_json(veilid_core::veilid_config_schema())
+    }
 }
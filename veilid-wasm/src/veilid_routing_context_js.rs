@@ -133,6 +133,15 @@ impl VeilidRoutingContext {
         })
     }
 
+    /// Use safety routes chosen by a [SafetyProfile], keeping the current sequencing preference.
+    /// Returns a new instance of VeilidRoutingContext - does not mutate.
+    pub fn withSafetyProfile(&self, profile: SafetyProfile) -> APIResult<VeilidRoutingContext> {
+        let routing_context = self.getRoutingContext()?;
+        APIResult::Ok(VeilidRoutingContext {
+            inner_routing_context: routing_context.with_safety_profile(profile)?,
+        })
+    }
+
     /// Use a specified `Sequencing` preference.
     /// Returns a new instance of VeilidRoutingContext - does not mutate.
     pub fn withSequencing(&self, sequencing: Sequencing) -> APIResult<VeilidRoutingContext> {
@@ -142,6 +151,15 @@ impl VeilidRoutingContext {
         })
     }
 
+    /// Use a specified operation timeout in milliseconds, or `undefined` for the node default.
+    /// Returns a new instance of VeilidRoutingContext - does not mutate.
+    pub fn withTimeout(&self, timeout_ms: Option<u32>) -> APIResult<VeilidRoutingContext> {
+        let routing_context = self.getRoutingContext()?;
+        APIResult::Ok(VeilidRoutingContext {
+            inner_routing_context: routing_context.with_timeout(timeout_ms),
+        })
+    }
+
     /// Get the safety selection in use on this routing context
     /// @returns the SafetySelection currently in use if successful.
     pub fn safety(&self) -> APIResult<SafetySelection> {
@@ -150,6 +168,17 @@ impl VeilidRoutingContext {
         let safety_selection = routing_context.safety();
         APIResult::Ok(safety_selection)
     }
+
+    /// Test that one of our own private routes is reachable before it is published.
+    ///
+    /// @param {string} route_id - the id of a private route allocated by this node.
+    /// @returns whether the test made it back through the route, and the round trip latency if it did.
+    pub async fn testPrivateRoute(&self, route_id: String) -> APIResult<PrivateRouteTestResult> {
+        let route_id: veilid_core::RouteId = RouteId::from_str(&route_id)?;
+        let routing_context = self.getRoutingContext()?;
+        let res = routing_context.test_private_route(route_id).await?;
+        APIResult::Ok(res)
+    }
     /// App-level unidirectional message that does not expect any value to be returned.
     ///
     /// Veilid apps may use this for arbitrary message passing.
@@ -166,6 +195,43 @@ impl VeilidRoutingContext {
         APIRESULT_UNDEFINED
     }
 
+    /// Set up a connection to a target ahead of time, so the first message to it does not wait on the handshake.
+    ///
+    /// @param {string} target - can be either a direct node id or a private route.
+    /// @returns how the target was reached.
+    pub async fn prewarm(&self, target_string: String) -> APIResult<ContactMethod> {
+        let routing_context = self.getRoutingContext()?;
+        let veilid_api = get_veilid_api()?;
+        let target = veilid_api.parse_as_target(target_string)?;
+        let contact_method = routing_context.prewarm(target).await?;
+        APIResult::Ok(contact_method)
+    }
+
+    /// Send an app message to the nodes closest to a key.
+    ///
+    /// @param {string} key - the key whose closest nodes should receive the message.
+    /// @param {number} count - how many of the closest nodes to send to.
+    /// @param {Uint8Array} message - an arbitrary message blob of up to `32768` bytes.
+    /// @returns the node ids the message was delivered to.
+    pub async fn appMessageClosest(
+        &self,
+        key: String,
+        count: u32,
+        message: Box<[u8]>,
+    ) -> APIResult<StringArray> {
+        let key = TypedKey::from_str(&key)?;
+        let routing_context = self.getRoutingContext()?;
+        let message = message.into_vec();
+        let delivered = routing_context
+            .app_message_closest(key, count, message)
+            .await?;
+        let delivered = delivered
+            .iter()
+            .map(|item| item.to_string())
+            .collect::<Vec<String>>();
+        APIResult::Ok(into_unchecked_string_array(delivered))
+    }
+
     /// App-level bidirectional call that expects a response to be returned.
     ///
     /// Veilid apps may use this for arbitrary message passing.
@@ -212,6 +278,60 @@ impl VeilidRoutingContext {
         APIResult::Ok(dht_record_descriptor)
     }
 
+    /// Creates a new DHT record a specified crypto kind and schema that expires at a specified time
+    ///
+    /// Once the expiration passes, storage nodes stop serving the record and eventually delete it.
+    /// The record is considered 'open' after the create operation succeeds.
+    ///
+    /// @param {string} expiration - when the record expires, in microseconds.
+    /// @returns the newly allocated DHT record's key if successful.
+    #[wasm_bindgen(skip_jsdoc)]
+    pub async fn createExpiringDhtRecord(
+        &self,
+        schema: DHTSchema,
+        expiration: String,
+        kind: String,
+    ) -> APIResult<DHTRecordDescriptor> {
+        let crypto_kind = if kind.is_empty() {
+            None
+        } else {
+            Some(veilid_core::FourCC::from_str(&kind)?)
+        };
+        let expiration =
+            veilid_core::Timestamp::from_str(&expiration).map_err(VeilidAPIError::generic)?;
+        let routing_context = self.getRoutingContext()?;
+
+        let dht_record_descriptor = routing_context
+            .create_expiring_dht_record(schema, expiration, crypto_kind)
+            .await?;
+        APIResult::Ok(dht_record_descriptor)
+    }
+
+    /// Extends the expiration of a DHT record, or makes it never expire if `expiration` is not given.
+    ///
+    /// The record must be open with the owner as its writer, and the expiration can only be moved later.
+    ///
+    /// @param {string} key - key of the DHT record.
+    /// @param {string} expiration - when the record expires, in microseconds.
+    #[wasm_bindgen(skip_jsdoc)]
+    pub async fn extendDhtRecordExpiration(
+        &self,
+        key: String,
+        expiration: Option<String>,
+    ) -> APIResult<()> {
+        let key = TypedKey::from_str(&key)?;
+        let expiration = expiration
+            .map(|expiration| {
+                veilid_core::Timestamp::from_str(&expiration).map_err(VeilidAPIError::generic)
+            })
+            .transpose()?;
+        let routing_context = self.getRoutingContext()?;
+        routing_context
+            .extend_dht_record_expiration(key, expiration)
+            .await?;
+        APIRESULT_UNDEFINED
+    }
+
     /// Opens a DHT record at a specific key.
     ///
     /// Associates a secret if one is provided to provide writer capability. Records may only be opened or created. To re-open with a different routing context, first close the value.
@@ -420,4 +540,124 @@ impl VeilidRoutingContext {
             .await?;
         APIResult::Ok(res)
     }
+
+    /// Brings an opened DHT record up to date, fetching only the subkeys that are stale locally.
+    ///
+    /// Subkey ranges are compared with a node holding the record by digests of their sequence numbers,
+    /// so syncing a large record that has barely changed takes few round trips.
+    ///
+    /// @param {string} key - key of the DHT record, which must first be opened for reading or writing.
+    /// @returns the subkeys that were refreshed from the network.
+    #[wasm_bindgen(skip_jsdoc)]
+    pub async fn syncDhtRecord(&self, key: String) -> APIResult<ValueSubkeyRangeSet> {
+        let key = TypedKey::from_str(&key)?;
+        let routing_context = self.getRoutingContext()?;
+        let res = routing_context.sync_dht_record(key).await?;
+        APIResult::Ok(res)
+    }
+
+    /// Published Private Routes
+    /// Allocates a new private route and writes its blob to a DHT record subkey.
+    ///
+    /// The record must be open with a writer. The route follows the hop count, stability and sequencing of this routing context.
+    /// With a `rotation` schedule the route is replaced periodically, and each replacement is written to the same subkey.
+    ///
+    /// @param {string} key - the DHT record to publish the route to.
+    /// @param {number} subkey - the subkey of the record to write the route blob to.
+    /// @returns the id of the published route.
+    pub async fn publishPrivateRoute(
+        &self,
+        key: String,
+        subkey: u32,
+        rotation: Option<PrivateRouteRotation>,
+    ) -> APIResult<RouteId> {
+        let key = TypedKey::from_str(&key)?;
+        let routing_context = self.getRoutingContext()?;
+        let route_id = routing_context
+            .publish_private_route(key, subkey, rotation)
+            .await?;
+        APIResult::Ok(route_id)
+    }
+
+    /// Reads a private route blob published to a DHT record subkey and imports it.
+    ///
+    /// The record must be open. Pass `forceRefresh` to pick up a replacement route after the old one stops working.
+    ///
+    /// Returns `undefined` if no route has been published to the subkey.
+    pub async fn resolvePrivateRoute(
+        &self,
+        key: String,
+        subkey: u32,
+        forceRefresh: bool,
+    ) -> APIResult<Option<RouteId>> {
+        let key = TypedKey::from_str(&key)?;
+        let routing_context = self.getRoutingContext()?;
+        let res = routing_context
+            .resolve_private_route(key, subkey, forceRefresh)
+            .await?;
+        APIResult::Ok(res)
+    }
+
+    /// Rendezvous
+    /// Create an invite that another node can accept to establish a session with this one.
+    ///
+    /// Returns the invite, which must be kept to answer the accepting node's handshake, and a 'blob' to hand to the other node.
+    pub async fn createInvite(&self) -> APIResult<VeilidRendezvousInviteBlob> {
+        let routing_context = self.getRoutingContext()?;
+        let (invite, blob) = routing_context.create_invite().await?;
+        let invite_blob = VeilidRendezvousInviteBlob { invite, blob };
+        APIResult::Ok(invite_blob)
+    }
+
+    /// Accept an invite blob created by another node and establish a session with it.
+    pub async fn acceptInvite(&self, invite_blob: Box<[u8]>) -> APIResult<RendezvousSession> {
+        let invite_blob: Vec<u8> = invite_blob.into_vec();
+        let routing_context = self.getRoutingContext()?;
+        let session = routing_context.accept_invite(invite_blob).await?;
+        APIResult::Ok(session)
+    }
+
+    /// Answer an accepting node's handshake for one of our invites.
+    ///
+    /// Returns undefined if the app call is not a handshake for this invite and should be handled normally.
+    pub async fn answerInvite(
+        &self,
+        invite: RendezvousInvite,
+        app_call: VeilidAppCall,
+    ) -> APIResult<Option<RendezvousSession>> {
+        let routing_context = self.getRoutingContext()?;
+        let opt_session = routing_context.answer_invite(&invite, &app_call).await?;
+        APIResult::Ok(opt_session)
+    }
+
+    /// Encrypt and send a message to the other end of a session.
+    pub async fn sendSessionMessage(
+        &self,
+        session: RendezvousSession,
+        message: Box<[u8]>,
+    ) -> APIResult<()> {
+        let message: Vec<u8> = message.into_vec();
+        let routing_context = self.getRoutingContext()?;
+        session.send(&routing_context, &message).await?;
+        APIRESULT_UNDEFINED
+    }
+
+    /// Decrypt a message received over the local route of a session.
+    pub fn openSessionMessage(
+        &self,
+        session: RendezvousSession,
+        message: Box<[u8]>,
+    ) -> APIResult<Uint8Array> {
+        let routing_context = self.getRoutingContext()?;
+        let opened = session.open(&routing_context.api(), &message)?;
+        let opened = Uint8Array::from(opened.as_slice());
+        APIResult::Ok(opened)
+    }
+
+    /// Release the private routes used by a session.
+    pub fn closeSession(&self, session: RendezvousSession) -> APIResult<()> {
+        let routing_context = self.getRoutingContext()?;
+        session.close(&routing_context.api())?;
+        APIRESULT_UNDEFINED
+    }
 }